@@ -0,0 +1,241 @@
+//! Rectangular (block) selections.
+//!
+//! A block is described by two corners, each a document line and a display
+//! column. It is realized as one selection range per line spanning the same
+//! display-column range, so insert, delete, and paste treat it like any other
+//! multi-selection. The block stays active while vertical motions resize it;
+//! any other selection change leaves the generated ranges in place as plain
+//! cursors.
+
+use xeno_base::range::{Direction as MoveDir, Range};
+use xeno_base::transaction::Change;
+use xeno_base::{Selection, Transaction, column};
+
+use super::Buffer;
+
+/// One corner of a block selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockCorner {
+	/// Document line index.
+	pub line: usize,
+	/// Display column within the line, with tabs and wide graphemes expanded.
+	pub col: usize,
+}
+
+/// Active block selection, spanning every line between its two corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSelection {
+	/// Corner fixed when the block was started.
+	pub anchor: BlockCorner,
+	/// Corner moved by vertical motions; holds the primary cursor.
+	pub head: BlockCorner,
+}
+
+impl BlockSelection {
+	/// Returns the inclusive line span covered by the block.
+	pub fn lines(&self) -> (usize, usize) {
+		(
+			self.anchor.line.min(self.head.line),
+			self.anchor.line.max(self.head.line),
+		)
+	}
+
+	/// Returns the display-column span `(left, right)` of the block.
+	pub fn cols(&self) -> (usize, usize) {
+		(
+			self.anchor.col.min(self.head.col),
+			self.anchor.col.max(self.head.col),
+		)
+	}
+}
+
+impl Buffer {
+	/// Returns the active block selection, if any.
+	pub fn block_selection(&self) -> Option<BlockSelection> {
+		self.block_selection
+	}
+
+	/// Starts a block selection with both corners at the primary cursor.
+	pub fn begin_block_selection(&mut self, tab_width: usize) {
+		self.ensure_valid_selection();
+		let corner = self.block_corner_at(self.selection.primary().head, tab_width);
+		self.block_selection = Some(BlockSelection {
+			anchor: corner,
+			head: corner,
+		});
+		self.realize_block_selection(tab_width);
+	}
+
+	/// Ends the block selection, keeping its ranges as a plain multi-selection.
+	pub fn end_block_selection(&mut self) {
+		self.block_selection = None;
+	}
+
+	/// Moves the block's head corner by `count` lines, keeping its column.
+	///
+	/// Returns `false` if no block selection is active.
+	pub fn move_block_head(&mut self, direction: MoveDir, count: usize) -> bool {
+		let last_line = self.doc().content.len_lines().saturating_sub(1);
+		let Some(block) = self.block_selection.as_mut() else {
+			return false;
+		};
+		block.head.line = match direction {
+			MoveDir::Forward => (block.head.line + count).min(last_line),
+			MoveDir::Backward => block.head.line.saturating_sub(count),
+		};
+		true
+	}
+
+	/// Builds a transaction padding lines within the block that end before
+	/// its right edge with spaces, or `None` if every line is long enough.
+	pub fn block_padding(&self, tab_width: usize) -> Option<Transaction> {
+		let block = self.block_selection?;
+		let (first, last) = block.lines();
+		let (_, right) = block.cols();
+		let doc = self.doc();
+
+		let changes: Vec<Change> = (first..=last)
+			.filter_map(|line| {
+				let text = line_text(&doc.content, line);
+				let width = column::display_width(&text, tab_width);
+				(width < right).then(|| {
+					let end = doc.content.line_to_char(line) + text.chars().count();
+					Change {
+						start: end,
+						end,
+						replacement: Some(" ".repeat(right - width)),
+					}
+				})
+			})
+			.collect();
+
+		(!changes.is_empty()).then(|| Transaction::change(doc.content.slice(..), changes))
+	}
+
+	/// Regenerates the selection from the active block: one range per line,
+	/// from the anchor column to the head column.
+	///
+	/// Lines ending before the block's left edge are skipped, except the head
+	/// line, which always carries the primary cursor. Columns past a line's
+	/// end clamp to the end of that line.
+	pub fn realize_block_selection(&mut self, tab_width: usize) {
+		let Some(block) = self.block_selection else {
+			return;
+		};
+		let (first, last) = block.lines();
+		let (left, _) = block.cols();
+
+		let (ranges, primary_index) = {
+			let doc = self.doc();
+			let mut ranges = Vec::with_capacity(last - first + 1);
+			let mut primary_index = 0;
+			for line in first..=last {
+				let text = line_text(&doc.content, line);
+				if line != block.head.line && column::display_width(&text, tab_width) < left {
+					continue;
+				}
+				let line_start = doc.content.line_to_char(line);
+				let at = |col| line_start + column::col_to_char(&text, col, tab_width);
+				if line == block.head.line {
+					primary_index = ranges.len();
+				}
+				ranges.push(Range::new(at(block.anchor.col), at(block.head.col)));
+			}
+			(ranges, primary_index)
+		};
+
+		self.selection = Selection::from_vec(ranges, primary_index);
+		self.cursor = self.selection.primary().head;
+	}
+
+	/// Returns the block corner for a char position.
+	fn block_corner_at(&self, pos: usize, tab_width: usize) -> BlockCorner {
+		let doc = self.doc();
+		let line = doc.content.char_to_line(pos);
+		let text = line_text(&doc.content, line);
+		let offset = pos - doc.content.line_to_char(line);
+		BlockCorner {
+			line,
+			col: column::char_to_col(&text, offset, tab_width),
+		}
+	}
+}
+
+/// Returns the text of `line` without its line ending.
+fn line_text(content: &xeno_base::Rope, line: usize) -> String {
+	let text: String = content.line(line).into();
+	text.trim_end_matches(['\n', '\r']).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::buffer::BufferId;
+
+	fn make_buffer(content: &str, cursor: usize) -> Buffer {
+		let mut buffer = Buffer::new(BufferId(1), content.to_string(), None);
+		buffer.set_cursor_and_selection(cursor, Selection::point(cursor));
+		buffer
+	}
+
+	fn spans(buffer: &Buffer) -> Vec<(usize, usize)> {
+		buffer
+			.selection
+			.ranges()
+			.iter()
+			.map(|r| (r.anchor, r.head))
+			.collect()
+	}
+
+	#[test]
+	fn vertical_motion_adds_range_per_line() {
+		// Lines: "abcdef" / "ghijkl" / "mnopqr"
+		let mut buffer = make_buffer("abcdef\nghijkl\nmnopqr", 2);
+		buffer.begin_block_selection(4);
+		buffer.move_block_head(MoveDir::Forward, 2);
+		buffer.realize_block_selection(4);
+
+		assert_eq!(spans(&buffer), vec![(2, 2), (9, 9), (16, 16)]);
+		assert_eq!(buffer.selection.primary().head, 16);
+	}
+
+	#[test]
+	fn block_spans_display_columns() {
+		// Lines: "\tab" / "漢字ab"; display col 4 is 'a' on both lines.
+		let mut buffer = make_buffer("\tab\n漢字ab", 1);
+		buffer.begin_block_selection(4);
+		buffer.move_block_head(MoveDir::Forward, 1);
+		if let Some(block) = buffer.block_selection.as_mut() {
+			block.head.col = 5;
+		}
+		buffer.realize_block_selection(4);
+
+		assert_eq!(spans(&buffer), vec![(1, 2), (6, 7)]);
+	}
+
+	#[test]
+	fn short_lines_skipped_or_padded() {
+		// Lines: "abcdef" / "a" / "abcdef"
+		let mut buffer = make_buffer("abcdef\na\nabcdef", 4);
+		buffer.begin_block_selection(4);
+		buffer.move_block_head(MoveDir::Forward, 2);
+		buffer.realize_block_selection(4);
+		assert_eq!(spans(&buffer), vec![(4, 4), (13, 13)]);
+
+		let tx = buffer.block_padding(4).expect("short line needs padding");
+		buffer.apply_transaction(&tx);
+		buffer.realize_block_selection(4);
+		assert_eq!(buffer.doc().content.line(1).to_string(), "a   \n");
+		assert_eq!(spans(&buffer), vec![(4, 4), (11, 11), (16, 16)]);
+	}
+
+	#[test]
+	fn selection_change_ends_block() {
+		let mut buffer = make_buffer("abc\ndef", 1);
+		buffer.begin_block_selection(4);
+		assert!(buffer.block_selection().is_some());
+
+		buffer.set_selection(Selection::point(0));
+		assert!(buffer.block_selection().is_none());
+	}
+}
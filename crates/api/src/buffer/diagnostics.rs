@@ -0,0 +1,172 @@
+//! Diagnostics prepared for display, following document edits.
+//!
+//! Servers publish diagnostics against the text they last saw, so the
+//! positions they send drift as soon as the user types. Prepared
+//! diagnostics hold character ranges on the [`Document`](super::Document)
+//! and are mapped through every edit, like marks, until the server
+//! publishes again and they are prepared afresh.
+
+use xeno_base::transaction::Bias;
+use xeno_base::{Rope, Transaction};
+
+/// A diagnostic converted to character offsets in its document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedDiagnostic {
+	/// First character of the range.
+	pub start: usize,
+	/// Character after the range; equal to `start` for an empty range.
+	pub end: usize,
+	/// Severity (4=Error, 3=Warning, 2=Info, 1=Hint).
+	pub severity: u8,
+	/// Message shown as inline virtual text.
+	pub message: String,
+	/// Server or tool that reported the diagnostic, if known.
+	pub source: Option<String>,
+}
+
+impl PreparedDiagnostic {
+	/// Converts an LSP diagnostic whose positions are in `encoding`.
+	///
+	/// Returns `None` for diagnostics without a known severity.
+	#[cfg(feature = "lsp")]
+	pub fn from_lsp(
+		diagnostic: &xeno_lsp::lsp_types::Diagnostic,
+		text: &Rope,
+		encoding: xeno_lsp::OffsetEncoding,
+	) -> Option<Self> {
+		use xeno_lsp::lsp_types::DiagnosticSeverity;
+
+		// LSP: 1=Error, 2=Warning, 3=Info, 4=Hint → Gutter: 4, 3, 2, 1
+		let severity = match diagnostic.severity {
+			Some(DiagnosticSeverity::ERROR) => 4,
+			Some(DiagnosticSeverity::WARNING) => 3,
+			Some(DiagnosticSeverity::INFORMATION) => 2,
+			Some(DiagnosticSeverity::HINT) => 1,
+			_ => return None,
+		};
+		let (start, end) = xeno_lsp::lsp_range_to_char_range(text, diagnostic.range, encoding);
+		Some(Self {
+			start,
+			end,
+			severity,
+			message: diagnostic.message.clone(),
+			source: diagnostic.source.clone(),
+		})
+	}
+}
+
+/// Diagnostics of a document, in the positions of its current content.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedDiagnostics {
+	/// Diagnostics in the order the servers sent them.
+	items: Vec<PreparedDiagnostic>,
+	/// Whether the diagnostics have been prepared from the server's at least
+	/// once.
+	synced: bool,
+}
+
+impl PreparedDiagnostics {
+	/// Iterates over the diagnostics in the order the server sent them.
+	pub fn iter(&self) -> impl Iterator<Item = &PreparedDiagnostic> {
+		self.items.iter()
+	}
+
+	/// Returns whether there are no diagnostics.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Returns whether the diagnostics were never prepared from the server's.
+	pub fn needs_sync(&self) -> bool {
+		!self.synced
+	}
+
+	/// Replaces the diagnostics with ones freshly prepared from the server's.
+	pub fn replace(&mut self, items: Vec<PreparedDiagnostic>) {
+		self.items = items;
+		self.synced = true;
+	}
+
+	/// Maps every range through an applied transaction.
+	///
+	/// Text inserted at either edge stays outside the range, so typing next
+	/// to an error does not stretch its underline.
+	pub fn map_through(&mut self, tx: &Transaction) {
+		let changes = tx.changes();
+		for item in &mut self.items {
+			let start = changes.map_pos(item.start, Bias::Right);
+			let end = changes.map_pos(item.end, Bias::Left);
+			item.start = start;
+			item.end = end.max(start);
+		}
+	}
+
+	/// Drops all diagnostics until the server publishes again.
+	pub fn clear(&mut self) {
+		self.items.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	fn diagnostic(start: usize, end: usize) -> PreparedDiagnostic {
+		PreparedDiagnostic {
+			start,
+			end,
+			severity: 4,
+			message: "mismatched types".into(),
+			source: None,
+		}
+	}
+
+	#[test]
+	fn ranges_follow_edits_without_growing() {
+		let doc = Rope::from("let x: u8 = \"a\";");
+		let mut diagnostics = PreparedDiagnostics::default();
+		diagnostics.replace(vec![diagnostic(12, 15), diagnostic(4, 5)]);
+
+		// Insert before the first range and at both edges of the second.
+		let tx = Transaction::change(
+			doc.slice(..),
+			[
+				Change {
+					start: 0,
+					end: 0,
+					replacement: Some("  ".into()),
+				},
+				Change {
+					start: 4,
+					end: 4,
+					replacement: Some("mut ".into()),
+				},
+				Change {
+					start: 5,
+					end: 5,
+					replacement: Some("y".into()),
+				},
+			],
+		);
+		diagnostics.map_through(&tx);
+		let ranges: Vec<_> = diagnostics.iter().map(|d| (d.start, d.end)).collect();
+		assert_eq!(ranges, vec![(19, 22), (10, 11)]);
+
+		// Deleting a whole range collapses it instead of dropping it.
+		let mut doc = doc;
+		tx.apply(&mut doc);
+		let tx = Transaction::change(
+			doc.slice(..),
+			[Change {
+				start: 18,
+				end: 23,
+				replacement: None,
+			}],
+		);
+		diagnostics.map_through(&tx);
+		let first = diagnostics.iter().next().unwrap();
+		assert_eq!((first.start, first.end), (18, 18));
+	}
+}
@@ -4,20 +4,46 @@
 //! Multiple buffers can reference the same document, enabling split views of
 //! the same file with shared undo history.
 
-use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
 
-use xeno_base::{Rope, Selection};
+use xeno_base::{Rope, Transaction};
 use xeno_language::LanguageLoader;
 use xeno_language::syntax::Syntax;
 
-use crate::buffer::BufferId;
-use crate::editor::types::HistoryEntry;
+use super::diagnostics::PreparedDiagnostics;
+use super::encoding::Encoding;
+use super::folds::Folds;
+use super::inlay_hints::PreparedInlayHints;
+use super::line_ending::{self, LineEnding};
+use super::marks::SelectionMarks;
+use super::semantic_tokens::SemanticTokens;
+use super::snippet::SnippetSession;
+use crate::editor::types::{HistorySelections, HistoryStep, UndoTree};
 
 /// Counter for generating unique document IDs.
 static NEXT_DOCUMENT_ID: AtomicU64 = AtomicU64::new(1);
 
+/// An open undo group on a [`Document`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct UndoGroup {
+	/// Number of transactions recorded since the group was opened.
+	pub(crate) edits: usize,
+}
+
+/// Marks a document opened by `:new` or `:scratch` as a scratch document.
+///
+/// Scratch documents are not backed by a file. Their unsaved changes do not
+/// block quitting unless they are marked persistent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scratch {
+	/// Name given by `:scratch <name>`, if any.
+	pub name: Option<String>,
+	/// Whether unsaved changes guard quitting like a file's would.
+	pub persistent: bool,
+}
+
 /// Unique identifier for a document.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DocumentId(pub u64);
@@ -56,14 +82,52 @@ pub struct Document {
 	/// Whether the document has unsaved changes.
 	pub modified: bool,
 
+	/// Scratch state, for documents opened by `:new` or `:scratch`.
+	///
+	/// Cleared once the document is written to a file.
+	pub scratch: Option<Scratch>,
+
 	/// Whether the document is read-only (prevents all text modifications).
 	pub readonly: bool,
 
-	/// Undo history stack.
-	pub undo_stack: Vec<HistoryEntry>,
+	/// Whether the file is still streaming into the document.
+	///
+	/// Edits are refused until it has, whatever `readonly` says, so the
+	/// read-only flag set by `--read-only` or `:set-buffer` survives the
+	/// load.
+	pub loading: bool,
+
+	/// Line ending detected on load, re-applied when saving.
+	pub line_ending: LineEnding,
+
+	/// Whether the loaded text mixed `\n` and `\r\n` endings.
+	pub mixed_line_endings: bool,
+
+	/// Encoding the file was decoded from, re-applied when saving.
+	pub encoding: Encoding,
+
+	/// Whether invalid UTF-8 in the file was replaced on load.
+	///
+	/// Such a document is locked against edits and saves unless
+	/// `allow-lossy-write` is set, since writing it would destroy the
+	/// original bytes.
+	pub lossy: bool,
+
+	/// Whether the file exceeded `large-file-size` when opened.
+	pub large_file: bool,
+
+	/// Modification time of the file when it was last loaded or saved.
+	///
+	/// A different time at save means something else wrote the file.
+	pub disk_mtime: Option<SystemTime>,
+
+	/// Content as last loaded from or saved to disk.
+	///
+	/// Serves as the merge base when the file changed underneath the buffer.
+	pub disk_base: Option<Rope>,
 
-	/// Redo history stack.
-	pub redo_stack: Vec<HistoryEntry>,
+	/// Branching undo history.
+	pub history: UndoTree,
 
 	/// Detected file type (e.g., "rust", "python").
 	pub file_type: Option<String>,
@@ -71,8 +135,34 @@ pub struct Document {
 	/// Syntax highlighting state.
 	pub syntax: Option<Syntax>,
 
-	/// Flag for grouping insert-mode edits into a single undo.
-	pub(crate) insert_undo_active: bool,
+	/// Saved selections, mapped through every edit to the content.
+	pub marks: SelectionMarks,
+
+	/// Diagnostics from the language server, mapped through every edit
+	/// until it publishes again.
+	pub diagnostics: PreparedDiagnostics,
+
+	/// Inlay hints from the language server, mapped through every edit
+	/// until they are requested again.
+	pub inlay_hints: PreparedInlayHints,
+
+	/// Semantic tokens from the language server, mapped through every edit
+	/// until they are requested again.
+	pub semantic_tokens: SemanticTokens,
+
+	/// Tabstops of the snippet being filled in, mapped through every edit
+	/// until the session ends.
+	pub snippet: Option<SnippetSession>,
+
+	/// Folds from the language server, mapped through every edit until
+	/// they are requested again.
+	pub folds: Folds,
+
+	/// Open undo group, if any.
+	///
+	/// While a group is open, edits compose into the group's history entry
+	/// instead of creating new ones, so the whole group undoes as one step.
+	pub(crate) undo_group: Option<UndoGroup>,
 
 	/// Document version, incremented on every transaction.
 	///
@@ -82,47 +172,93 @@ pub struct Document {
 	/// Pending LSP changes queued for sync.
 	#[cfg(feature = "lsp")]
 	pub pending_lsp_changes: Vec<xeno_base::LspDocumentChange>,
+
+	/// Whether the next LSP sync has to send the whole text, because an edit
+	/// since the last one was not turned into change events.
+	#[cfg(feature = "lsp")]
+	pub pending_lsp_full_sync: bool,
 }
 
 impl Document {
 	/// Creates a new document with the given content and optional file path.
+	///
+	/// `\r\n` endings are stripped from the content and remembered in
+	/// [`line_ending`](Self::line_ending).
 	pub fn new(content: String, path: Option<PathBuf>) -> Self {
+		let normalized = line_ending::normalize(content);
 		Self {
 			id: DocumentId::next(),
-			content: Rope::from(content.as_str()),
+			content: Rope::from(normalized.text.as_str()),
 			path,
 			modified: false,
+			scratch: None,
 			readonly: false,
-			undo_stack: Vec::new(),
-			redo_stack: Vec::new(),
+			loading: false,
+			line_ending: normalized.ending,
+			mixed_line_endings: normalized.mixed,
+			encoding: Encoding::Utf8,
+			lossy: false,
+			large_file: false,
+			disk_mtime: None,
+			disk_base: None,
+			history: UndoTree::new(),
 			file_type: None,
 			syntax: None,
-			insert_undo_active: false,
+			marks: SelectionMarks::default(),
+			diagnostics: PreparedDiagnostics::default(),
+			inlay_hints: PreparedInlayHints::default(),
+			semantic_tokens: SemanticTokens::default(),
+			snippet: None,
+			folds: Folds::default(),
+			undo_group: None,
 			version: 0,
 			#[cfg(feature = "lsp")]
 			pending_lsp_changes: Vec::new(),
+			#[cfg(feature = "lsp")]
+			pending_lsp_full_sync: false,
 		}
 	}
 
+	/// Drops pending LSP change events so the next sync sends the whole text.
+	#[cfg(feature = "lsp")]
+	pub fn require_lsp_full_sync(&mut self) {
+		self.pending_lsp_changes.clear();
+		self.pending_lsp_full_sync = true;
+	}
+
 	/// Creates a new scratch document (no file path).
 	pub fn scratch() -> Self {
 		Self::new(String::new(), None)
 	}
 
-	/// Initializes syntax highlighting for this document based on file path.
+	/// Detects the file type and initializes syntax highlighting for it.
+	///
+	/// Detection looks at the path, then the shebang, then any modeline; see
+	/// [`LanguageLoader::detect_language`]. A document with no detectable
+	/// type is left without a file type or syntax.
 	pub fn init_syntax(&mut self, language_loader: &LanguageLoader) {
-		if let Some(ref p) = self.path
-			&& let Some(lang_id) = language_loader.language_for_path(p)
-		{
-			let lang_data = language_loader.get(lang_id);
-			self.file_type = lang_data.map(|l| l.name.clone());
-			self.syntax = Syntax::new(self.content.slice(..), lang_id, language_loader).ok();
-		}
+		let detected =
+			language_loader.detect_language(self.path.as_deref(), self.content.slice(..));
+		self.file_type = detected
+			.and_then(|lang_id| language_loader.get(lang_id))
+			.map(|l| l.name.clone());
+		self.syntax = detected
+			.and_then(|lang_id| Syntax::new(self.content.slice(..), lang_id, language_loader).ok());
+	}
+
+	/// Sets the file type by name, overriding detection.
+	///
+	/// A name the loader does not know still drives per-filetype options,
+	/// but leaves the document without syntax highlighting.
+	pub fn set_file_type(&mut self, name: &str, language_loader: &LanguageLoader) {
+		self.file_type = Some(name.to_string());
+		self.syntax = None;
+		self.init_syntax_for_language(name, language_loader);
 	}
 
 	/// Initializes syntax highlighting for this document by language name.
 	pub fn init_syntax_for_language(&mut self, name: &str, language_loader: &LanguageLoader) {
-		if let Some(lang_id) = language_loader.language_for_name(name) {
+		if let Some(lang_id) = language_loader.language_for_file_type(name) {
 			let lang_data = language_loader.get(lang_id);
 			self.file_type = lang_data.map(|l| l.name.clone());
 			self.syntax = Syntax::new(self.content.slice(..), lang_id, language_loader).ok();
@@ -139,68 +275,264 @@ impl Document {
 		}
 	}
 
-	/// Pushes the current state onto the undo stack.
-	pub(crate) fn push_undo_snapshot(&mut self, selections: HashMap<BufferId, Selection>) {
-		self.undo_stack.push(HistoryEntry {
-			doc: self.content.clone(),
-			selections,
-		});
-		self.redo_stack.clear();
+	/// Saves the current state as a new node in the undo tree.
+	///
+	/// The node starts as an identity edit. Subsequent edits are composed
+	/// into it by [`apply_and_record`](Self::apply_and_record).
+	pub(crate) fn push_undo_snapshot(&mut self, selections: HistorySelections) {
+		self.history.push(self.content.slice(..), selections);
+	}
 
-		const MAX_UNDO: usize = 100;
-		if self.undo_stack.len() > MAX_UNDO {
-			self.undo_stack.remove(0);
+	/// Applies a transaction to the content, recording it in the undo tree.
+	///
+	/// The edit is composed into the current history node so that undoing the
+	/// node reverts every edit made since it was saved.
+	pub(crate) fn apply_and_record(&mut self, tx: &Transaction) {
+		let inverse = tx.invert(&self.content);
+		let before = self.content.clone();
+		tx.apply(&mut self.content);
+		self.marks.map_through(tx);
+		self.diagnostics.map_through(tx);
+		self.inlay_hints.map_through(tx);
+		self.semantic_tokens.map_through(tx);
+		self.folds.map_through(tx, &before, &self.content);
+		if let Some(snippet) = &mut self.snippet {
+			snippet.map_through(tx);
+		}
+		if let Some(group) = &mut self.undo_group {
+			group.edits += 1;
 		}
+		self.history.record(tx, inverse);
 	}
 
-	/// Saves current state to undo history. Resets any grouped insert session.
-	pub fn save_undo_state(&mut self, selections: HashMap<BufferId, Selection>) {
-		self.insert_undo_active = false;
-		self.push_undo_snapshot(selections);
+	/// Saves current state to undo history.
+	///
+	/// Does nothing while an undo group is open, since the group's entry
+	/// already covers any edit that follows.
+	pub fn save_undo_state(&mut self, selections: HistorySelections) {
+		if self.undo_group.is_none() {
+			self.push_undo_snapshot(selections);
+		}
 	}
 
-	/// Saves undo state for insert mode, grouping consecutive inserts.
-	/// Returns true if a new snapshot was created.
-	pub fn save_insert_undo_state(&mut self, selections: HashMap<BufferId, Selection>) -> bool {
-		if self.insert_undo_active {
+	/// Opens an undo group, saving the current state as its history entry.
+	///
+	/// Returns true if a new group was opened, false if one was already open.
+	pub fn begin_undo_group(&mut self, selections: HistorySelections) -> bool {
+		if self.undo_group.is_some() {
 			return false;
 		}
-		self.insert_undo_active = true;
 		self.push_undo_snapshot(selections);
+		self.undo_group = Some(UndoGroup::default());
 		true
 	}
 
+	/// Closes the open undo group, if any.
+	pub fn end_undo_group(&mut self) {
+		self.undo_group = None;
+	}
+
+	/// Returns the number of edits recorded in the open undo group, if any.
+	pub fn undo_group_edits(&self) -> Option<usize> {
+		self.undo_group.map(|group| group.edits)
+	}
+
+	/// Applies a path of history steps, returning the selections of the last one.
+	///
+	/// Stops and discards all history if a step no longer matches the content
+	/// (e.g. after the content was replaced wholesale).
+	fn apply_history_steps(
+		&mut self,
+		steps: Vec<HistoryStep>,
+		language_loader: &LanguageLoader,
+	) -> Option<HistorySelections> {
+		let mut selections = None;
+		self.snippet = None;
+		for step in steps {
+			if step.transaction.changes().len() != self.content.len_chars() {
+				self.history.clear();
+				self.marks.clear();
+				self.diagnostics.clear();
+				self.inlay_hints.clear();
+				self.semantic_tokens.clear();
+				self.folds.clear();
+				break;
+			}
+			let before = self.content.clone();
+			step.transaction.apply(&mut self.content);
+			self.marks.map_through(&step.transaction);
+			self.diagnostics.map_through(&step.transaction);
+			self.inlay_hints.map_through(&step.transaction);
+			self.semantic_tokens.map_through(&step.transaction);
+			self.folds
+				.map_through(&step.transaction, &before, &self.content);
+			selections = Some(step.selections);
+		}
+		if selections.is_some() {
+			self.version = self.version.wrapping_add(1);
+			self.reparse_syntax(language_loader);
+		}
+		selections
+	}
+
 	/// Undoes the last change. Returns restored selections if successful.
 	pub fn undo(
 		&mut self,
-		current_selections: HashMap<BufferId, Selection>,
+		current_selections: HistorySelections,
 		language_loader: &LanguageLoader,
-	) -> Option<HashMap<BufferId, Selection>> {
-		self.insert_undo_active = false;
-		let entry = self.undo_stack.pop()?;
-		self.redo_stack.push(HistoryEntry {
-			doc: self.content.clone(),
-			selections: current_selections,
-		});
-		self.content = entry.doc;
-		self.reparse_syntax(language_loader);
-		Some(entry.selections)
-	}
-
-	/// Redoes the last undone change. Returns restored selections if successful.
+	) -> Option<HistorySelections> {
+		self.undo_group = None;
+		let step = self.history.undo(current_selections)?;
+		self.apply_history_steps(vec![step], language_loader)
+	}
+
+	/// Redoes the most recently undone change. Returns restored selections if successful.
 	pub fn redo(
 		&mut self,
-		current_selections: HashMap<BufferId, Selection>,
+		current_selections: HistorySelections,
+		language_loader: &LanguageLoader,
+	) -> Option<HistorySelections> {
+		self.undo_group = None;
+		let step = self.history.redo(current_selections)?;
+		self.apply_history_steps(vec![step], language_loader)
+	}
+
+	/// Moves to an arbitrary node of the undo tree, crossing branches as needed.
+	///
+	/// Returns restored selections, or `None` if already at `target`.
+	pub fn goto_history(
+		&mut self,
+		target: usize,
+		current_selections: HistorySelections,
 		language_loader: &LanguageLoader,
-	) -> Option<HashMap<BufferId, Selection>> {
-		self.insert_undo_active = false;
-		let entry = self.redo_stack.pop()?;
-		self.undo_stack.push(HistoryEntry {
-			doc: self.content.clone(),
-			selections: current_selections,
-		});
-		self.content = entry.doc;
-		self.reparse_syntax(language_loader);
-		Some(entry.selections)
+	) -> Option<HistorySelections> {
+		self.undo_group = None;
+		let steps = self.history.goto(target, current_selections);
+		self.apply_history_steps(steps, language_loader)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use xeno_base::{Selection, Transaction};
+	use xeno_language::LanguageLoader;
+
+	use super::Document;
+	use crate::buffer::BufferId;
+
+	/// Deterministic linear congruential generator for reproducible edit sequences.
+	struct Lcg(u64);
+
+	impl Lcg {
+		fn below(&mut self, bound: usize) -> usize {
+			self.0 = self
+				.0
+				.wrapping_mul(6364136223846793005)
+				.wrapping_add(1442695040888963407);
+			((self.0 >> 33) as usize) % bound.max(1)
+		}
+	}
+
+	fn random_edit(doc: &Document, rng: &mut Lcg) -> (Transaction, Selection) {
+		let len = doc.content.len_chars();
+		let start = rng.below(len + 1);
+		let end = (start + rng.below(4)).min(len);
+		let sel = Selection::single(start, end);
+		let tx = match rng.below(3) {
+			0 => Transaction::delete(doc.content.slice(..), &sel),
+			1 => Transaction::insert(doc.content.slice(..), &sel, "xy\n".into()),
+			_ => Transaction::insert(doc.content.slice(..), &Selection::point(start), "é".into()),
+		};
+		(tx, sel)
+	}
+
+	#[test]
+	fn undo_redo_roundtrips_random_edits() {
+		let loader = LanguageLoader::new();
+		let mut doc = Document::new(
+			"the quick brown fox\njumps over\nthe lazy dog\n".into(),
+			None,
+		);
+		let mut rng = Lcg(42);
+		let mut states = vec![(doc.content.clone(), Selection::point(0))];
+
+		for _ in 0..50 {
+			let selection = states.last().unwrap().1.clone();
+			doc.save_undo_state(HashMap::from([(BufferId::SCRATCH, selection)]));
+			let (tx, sel) = random_edit(&doc, &mut rng);
+			doc.apply_and_record(&tx);
+			states.push((doc.content.clone(), sel));
+		}
+
+		for i in (1..states.len()).rev() {
+			let current = HashMap::from([(BufferId::SCRATCH, states[i].1.clone())]);
+			let restored = doc.undo(current, &loader).expect("undo available");
+			assert_eq!(doc.content, states[i - 1].0);
+			assert_eq!(restored.get(&BufferId::SCRATCH), Some(&states[i - 1].1));
+		}
+		assert!(doc.undo(HashMap::new(), &loader).is_none());
+
+		for i in 1..states.len() {
+			let current = HashMap::from([(BufferId::SCRATCH, states[i - 1].1.clone())]);
+			let restored = doc.redo(current, &loader).expect("redo available");
+			assert_eq!(doc.content, states[i].0);
+			assert_eq!(restored.get(&BufferId::SCRATCH), Some(&states[i].1));
+		}
+		assert!(doc.redo(HashMap::new(), &loader).is_none());
+	}
+
+	#[test]
+	fn edits_after_save_undo_as_one_step() {
+		let loader = LanguageLoader::new();
+		let mut doc = Document::new("abc".into(), None);
+		doc.save_undo_state(HashMap::new());
+		for text in ["1", "2", "3"] {
+			let tx = Transaction::insert(doc.content.slice(..), &Selection::point(0), text.into());
+			doc.apply_and_record(&tx);
+		}
+		assert_eq!(doc.content.to_string(), "321abc");
+		doc.undo(HashMap::new(), &loader);
+		assert_eq!(doc.content.to_string(), "abc");
+		doc.redo(HashMap::new(), &loader);
+		assert_eq!(doc.content.to_string(), "321abc");
+	}
+
+	#[test]
+	fn undo_group_absorbs_saves_until_closed() {
+		let loader = LanguageLoader::new();
+		let mut doc = Document::new("abc".into(), None);
+		assert!(doc.begin_undo_group(HashMap::new()));
+		assert!(!doc.begin_undo_group(HashMap::new()));
+		for text in ["1", "2"] {
+			doc.save_undo_state(HashMap::new());
+			let tx = Transaction::insert(doc.content.slice(..), &Selection::point(0), text.into());
+			doc.apply_and_record(&tx);
+		}
+		assert_eq!(doc.undo_group_edits(), Some(2));
+		assert_eq!(doc.history.len(), 2);
+
+		doc.end_undo_group();
+		doc.save_undo_state(HashMap::new());
+		let tx = Transaction::insert(doc.content.slice(..), &Selection::point(0), "3".into());
+		doc.apply_and_record(&tx);
+		assert_eq!(doc.history.len(), 3);
+
+		doc.undo(HashMap::new(), &loader);
+		assert_eq!(doc.content.to_string(), "21abc");
+		doc.undo(HashMap::new(), &loader);
+		assert_eq!(doc.content.to_string(), "abc");
+	}
+
+	#[test]
+	fn undo_closes_open_group() {
+		let loader = LanguageLoader::new();
+		let mut doc = Document::new("abc".into(), None);
+		doc.begin_undo_group(HashMap::new());
+		let tx = Transaction::insert(doc.content.slice(..), &Selection::point(0), "x".into());
+		doc.apply_and_record(&tx);
+		doc.undo(HashMap::new(), &loader);
+		assert_eq!(doc.undo_group_edits(), None);
 	}
 }
@@ -180,12 +180,14 @@ impl Buffer {
 			return false;
 		}
 		let mut doc = self.doc_mut();
-		if self.readonly_override.is_none() && doc.readonly {
+		if doc.loading || (self.readonly_override.is_none() && doc.readonly) {
 			return false;
 		}
-		tx.apply(&mut doc.content);
+		doc.apply_and_record(tx);
 		doc.modified = true;
 		doc.version = doc.version.wrapping_add(1);
+		#[cfg(feature = "lsp")]
+		doc.require_lsp_full_sync();
 		true
 	}
 
@@ -202,11 +204,11 @@ impl Buffer {
 			return false;
 		}
 		let mut doc = self.doc_mut();
-		if self.readonly_override.is_none() && doc.readonly {
+		if doc.loading || (self.readonly_override.is_none() && doc.readonly) {
 			return false;
 		}
 		let old_doc = doc.content.clone();
-		tx.apply(&mut doc.content);
+		doc.apply_and_record(tx);
 
 		if doc.syntax.is_some() {
 			let new_doc = doc.content.clone();
@@ -222,10 +224,15 @@ impl Buffer {
 
 		doc.modified = true;
 		doc.version = doc.version.wrapping_add(1);
+		#[cfg(feature = "lsp")]
+		doc.require_lsp_full_sync();
 		true
 	}
 
 	/// Applies a transaction, updates syntax, and queues LSP changes.
+	///
+	/// If the transaction cannot be turned into change events, the document
+	/// is marked for a full sync instead.
 	#[cfg(feature = "lsp")]
 	pub fn apply_edit_with_lsp(
 		&self,
@@ -237,13 +244,13 @@ impl Buffer {
 			return false;
 		}
 		let mut doc = self.doc_mut();
-		if self.readonly_override.is_none() && doc.readonly {
+		if doc.loading || (self.readonly_override.is_none() && doc.readonly) {
 			return false;
 		}
 
 		let old_doc = doc.content.clone();
 		let lsp_changes = compute_lsp_changes(&old_doc, tx, encoding);
-		tx.apply(&mut doc.content);
+		doc.apply_and_record(tx);
 
 		if doc.syntax.is_some() {
 			let new_doc = doc.content.clone();
@@ -259,21 +266,30 @@ impl Buffer {
 
 		doc.modified = true;
 		doc.version = doc.version.wrapping_add(1);
-		if !lsp_changes.is_empty() {
-			doc.pending_lsp_changes.extend(lsp_changes);
+		match lsp_changes {
+			Some(changes) if !doc.pending_lsp_full_sync => doc.pending_lsp_changes.extend(changes),
+			Some(_) => {}
+			None => doc.require_lsp_full_sync(),
 		}
 		true
 	}
 
 	/// Drains pending LSP changes for this document.
+	///
+	/// Returns `None` if the document has to be synced in full instead.
 	#[cfg(feature = "lsp")]
-	pub fn drain_lsp_changes(&self) -> Vec<LspDocumentChange> {
-		std::mem::take(&mut self.doc_mut().pending_lsp_changes)
+	pub fn drain_lsp_changes(&self) -> Option<Vec<LspDocumentChange>> {
+		let mut doc = self.doc_mut();
+		let changes = std::mem::take(&mut doc.pending_lsp_changes);
+		(!std::mem::take(&mut doc.pending_lsp_full_sync)).then_some(changes)
 	}
 
 	/// Finalizes selection/cursor after a transaction is applied.
+	///
+	/// Positions are snapped to grapheme boundaries of the edited text.
 	pub fn finalize_selection(&mut self, new_selection: xeno_base::Selection) {
-		self.set_selection(new_selection);
+		let aligned = new_selection.grapheme_aligned(self.doc().content.slice(..));
+		self.set_selection(aligned);
 		self.sync_cursor_to_selection();
 	}
 }
@@ -309,7 +325,7 @@ mod tests {
 			let loader = xeno_language::LanguageLoader::new();
 			buffer.apply_edit_with_lsp(&tx, &loader, OffsetEncoding::Utf16);
 
-			let changes = buffer.drain_lsp_changes();
+			let changes = buffer.drain_lsp_changes().unwrap();
 			assert_eq!(changes.len(), 1);
 			assert_eq!(changes[0].range, LspRange::point(LspPosition::new(0, 5)));
 			assert_eq!(changes[0].new_text, " world");
@@ -333,7 +349,7 @@ mod tests {
 			buffer.apply_edit_with_lsp(&tx2, &loader, OffsetEncoding::Utf16);
 			buffer.finalize_selection(sel2);
 
-			let changes = buffer.drain_lsp_changes();
+			let changes = buffer.drain_lsp_changes().unwrap();
 			assert_eq!(changes.len(), 2);
 
 			// First change: insert "A" at (0, 0) in original doc
@@ -364,7 +380,7 @@ mod tests {
 			let (tx, _sel) = buffer.prepare_insert("X");
 			buffer.apply_edit_with_lsp(&tx, &loader, OffsetEncoding::Utf16);
 
-			let changes = buffer.drain_lsp_changes();
+			let changes = buffer.drain_lsp_changes().unwrap();
 			assert_eq!(changes.len(), 3);
 
 			// Changes are ordered by position in pre-change document,
@@ -390,13 +406,30 @@ mod tests {
 			let (tx, _sel) = buffer.prepare_insert("!");
 			buffer.apply_edit_with_lsp(&tx, &loader, OffsetEncoding::Utf16);
 
-			let changes = buffer.drain_lsp_changes();
+			let changes = buffer.drain_lsp_changes().unwrap();
 			assert_eq!(changes.len(), 1);
 
 			// Second drain should be empty
-			let changes2 = buffer.drain_lsp_changes();
+			let changes2 = buffer.drain_lsp_changes().unwrap();
 			assert!(changes2.is_empty());
 		}
+
+		#[test]
+		fn untracked_edit_requires_full_sync() {
+			let mut buffer = make_buffer("test");
+			let loader = xeno_language::LanguageLoader::new();
+
+			buffer.set_selection(Selection::single(4, 4));
+			let (tx, _sel) = buffer.prepare_insert("!");
+			buffer.apply_edit_with_lsp(&tx, &loader, OffsetEncoding::Utf16);
+			let (tx, _sel) = buffer.prepare_insert("?");
+			buffer.apply_transaction(&tx);
+			let (tx, _sel) = buffer.prepare_insert(".");
+			buffer.apply_edit_with_lsp(&tx, &loader, OffsetEncoding::Utf16);
+
+			assert!(buffer.drain_lsp_changes().is_none());
+			assert_eq!(buffer.drain_lsp_changes(), Some(Vec::new()));
+		}
 	}
 
 	#[test]
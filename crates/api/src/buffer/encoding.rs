@@ -0,0 +1,140 @@
+//! Text encoding of loaded files.
+//!
+//! Files are decoded as UTF-8 when valid. Otherwise they either fall back to
+//! Latin-1, which maps every byte to a character and so round-trips exactly,
+//! or are decoded lossily with U+FFFD in place of invalid sequences.
+
+use anyhow::bail;
+
+/// Number of leading bytes searched for NUL when detecting binary files.
+pub const BINARY_SNIFF_LEN: usize = 8 * 1024;
+
+/// Error reported for files that look binary.
+pub const BINARY_FILE_ERROR: &str = "binary file; use a hex viewer such as `xxd` instead";
+
+/// Encoding a document was decoded from and is written back in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+	/// UTF-8.
+	#[default]
+	Utf8,
+	/// ISO-8859-1, one byte per character.
+	Latin1,
+}
+
+impl Encoding {
+	/// Returns the option name of this encoding.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Utf8 => "utf-8",
+			Self::Latin1 => "latin-1",
+		}
+	}
+
+	/// Parses an option name.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"utf-8" => Some(Self::Utf8),
+			"latin-1" => Some(Self::Latin1),
+			_ => None,
+		}
+	}
+
+	/// Encodes `text`, or returns the first character this encoding cannot
+	/// represent.
+	pub fn encode(self, text: String) -> Result<Vec<u8>, char> {
+		match self {
+			Self::Utf8 => Ok(text.into_bytes()),
+			Self::Latin1 => text
+				.chars()
+				.map(|c| u8::try_from(u32::from(c)).map_err(|_| c))
+				.collect(),
+		}
+	}
+}
+
+/// Text decoded from a file's bytes.
+#[derive(Debug)]
+pub struct DecodedText {
+	/// Decoded content.
+	pub text: String,
+	/// Encoding the content was decoded from.
+	pub encoding: Encoding,
+	/// Whether invalid UTF-8 was replaced with U+FFFD.
+	pub lossy: bool,
+}
+
+/// Returns whether file contents starting with `head` look binary.
+///
+/// Only the first few KiB are searched for NUL.
+pub fn is_binary(head: &[u8]) -> bool {
+	head[..head.len().min(BINARY_SNIFF_LEN)].contains(&0)
+}
+
+/// Decodes file contents, using `fallback` when they are not valid UTF-8.
+///
+/// Fails for binary files, recognized by a NUL byte near the start.
+pub fn decode(bytes: Vec<u8>, fallback: Encoding) -> anyhow::Result<DecodedText> {
+	if is_binary(&bytes) {
+		bail!(BINARY_FILE_ERROR);
+	}
+
+	let bytes = match String::from_utf8(bytes) {
+		Ok(text) => {
+			return Ok(DecodedText {
+				text,
+				encoding: Encoding::Utf8,
+				lossy: false,
+			});
+		}
+		Err(e) => e.into_bytes(),
+	};
+
+	Ok(match fallback {
+		Encoding::Utf8 => DecodedText {
+			text: String::from_utf8_lossy(&bytes).into_owned(),
+			encoding: Encoding::Utf8,
+			lossy: true,
+		},
+		Encoding::Latin1 => DecodedText {
+			text: bytes.iter().map(|&b| char::from(b)).collect(),
+			encoding: Encoding::Latin1,
+			lossy: false,
+		},
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn valid_utf8_ignores_fallback() {
+		let decoded = decode("héllo".as_bytes().to_vec(), Encoding::Latin1).unwrap();
+		assert_eq!(decoded.text, "héllo");
+		assert_eq!(decoded.encoding, Encoding::Utf8);
+		assert!(!decoded.lossy);
+	}
+
+	#[test]
+	fn invalid_utf8_decodes_lossily() {
+		let decoded = decode(b"caf\xe9\n".to_vec(), Encoding::Utf8).unwrap();
+		assert_eq!(decoded.text, "caf\u{FFFD}\n");
+		assert!(decoded.lossy);
+	}
+
+	#[test]
+	fn latin1_round_trips() {
+		let bytes = b"caf\xe9 \xff\n".to_vec();
+		let decoded = decode(bytes.clone(), Encoding::Latin1).unwrap();
+		assert_eq!(decoded.text, "café ÿ\n");
+		assert!(!decoded.lossy);
+		assert_eq!(Encoding::Latin1.encode(decoded.text), Ok(bytes));
+		assert_eq!(Encoding::Latin1.encode("€".into()), Err('€'));
+	}
+
+	#[test]
+	fn nul_bytes_are_binary() {
+		assert!(decode(b"\x7fELF\x02\x01\x00".to_vec(), Encoding::Latin1).is_err());
+	}
+}
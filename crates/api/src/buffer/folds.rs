@@ -0,0 +1,326 @@
+//! Folds: ranges of lines that can collapse into their first line.
+//!
+//! The language server reports the foldable ranges of a document; which of
+//! them are closed is the editor's. A closed fold shows only its first
+//! line, the header, and hides the rest. Folds are kept in lines and, like
+//! [diagnostics](super::PreparedDiagnostics), follow every edit until the
+//! ranges are requested again. An edit touching the hidden lines of a
+//! closed fold opens it, so no change ever happens out of sight.
+
+use xeno_base::transaction::{Bias, Operation};
+use xeno_base::{Rope, Transaction};
+
+/// A foldable range of lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fold {
+	/// Header line, which stays visible.
+	pub start: usize,
+	/// Last line of the fold.
+	pub end: usize,
+	/// Whether the lines after the header are hidden.
+	pub closed: bool,
+}
+
+impl Fold {
+	/// Returns whether the fold spans `line`, header included.
+	fn spans(&self, line: usize) -> bool {
+		self.start <= line && line <= self.end
+	}
+
+	/// Returns whether the fold is closed and hides `line`.
+	fn hides(&self, line: usize) -> bool {
+		self.closed && self.start < line && line <= self.end
+	}
+}
+
+/// Folds of a document, in the lines of its current content.
+#[derive(Debug, Clone, Default)]
+pub struct Folds {
+	/// Folds ordered by header line, outer folds before the ones they hold.
+	items: Vec<Fold>,
+}
+
+impl Folds {
+	/// Iterates over the folds in order of their header lines.
+	pub fn iter(&self) -> impl Iterator<Item = &Fold> {
+		self.items.iter()
+	}
+
+	/// Returns whether there are no folds.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Replaces the folds with freshly reported `(start, end)` line ranges.
+	///
+	/// Ranges of a single line are dropped. A new fold stays closed if a
+	/// closed fold had the same header line.
+	pub fn replace(&mut self, ranges: impl IntoIterator<Item = (usize, usize)>) {
+		let closed: Vec<usize> = self
+			.items
+			.iter()
+			.filter(|fold| fold.closed)
+			.map(|fold| fold.start)
+			.collect();
+		let mut items: Vec<Fold> = ranges
+			.into_iter()
+			.filter(|&(start, end)| start < end)
+			.map(|(start, end)| Fold {
+				start,
+				end,
+				closed: closed.contains(&start),
+			})
+			.collect();
+		items.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+		items.dedup_by_key(|fold| (fold.start, fold.end));
+		self.items = items;
+	}
+
+	/// Drops all folds until they are requested again.
+	pub fn clear(&mut self) {
+		self.items.clear();
+	}
+
+	/// Returns whether any fold is closed.
+	pub fn any_closed(&self) -> bool {
+		self.items.iter().any(|fold| fold.closed)
+	}
+
+	/// Returns the outermost closed fold hiding `line`, if any.
+	pub fn hiding(&self, line: usize) -> Option<&Fold> {
+		self.items.iter().find(|fold| fold.hides(line))
+	}
+
+	/// Returns whether `line` is hidden inside a closed fold.
+	pub fn is_hidden(&self, line: usize) -> bool {
+		self.hiding(line).is_some()
+	}
+
+	/// Returns the line shown for `line`: itself, or the header of the
+	/// closed fold hiding it.
+	pub fn visible_line(&self, line: usize) -> usize {
+		self.hiding(line).map_or(line, |fold| fold.start)
+	}
+
+	/// Returns the last line of the widest closed fold `line` heads, if it
+	/// heads one.
+	pub fn closed_end(&self, line: usize) -> Option<usize> {
+		self.items
+			.iter()
+			.filter(|fold| fold.closed && fold.start == line)
+			.map(|fold| fold.end)
+			.max()
+	}
+
+	/// Returns the number of lines hidden under `line` if it heads a closed
+	/// fold.
+	pub fn hidden_lines(&self, line: usize) -> Option<usize> {
+		self.closed_end(line).map(|end| end - line)
+	}
+
+	/// Returns the visible line after the one showing `line`.
+	pub fn next_visible_line(&self, line: usize) -> usize {
+		let line = self.visible_line(line);
+		self.closed_end(line).unwrap_or(line) + 1
+	}
+
+	/// Returns the visible line before the one showing `line`, or `None` at
+	/// the first line.
+	pub fn prev_visible_line(&self, line: usize) -> Option<usize> {
+		let line = self.visible_line(line).checked_sub(1)?;
+		Some(self.visible_line(line))
+	}
+
+	/// Opens the fold closed at `line`, or else closes the innermost fold
+	/// spanning it.
+	///
+	/// Returns whether there was a fold to open or close.
+	pub fn toggle(&mut self, line: usize) -> bool {
+		let line = self.visible_line(line);
+		if self.closed_end(line).is_some() {
+			for fold in self.items.iter_mut().filter(|fold| fold.start == line) {
+				fold.closed = false;
+			}
+			return true;
+		}
+		match self.items.iter_mut().rev().find(|fold| fold.spans(line)) {
+			Some(fold) => {
+				fold.closed = true;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Opens every closed fold hiding `line`. Returns whether any was.
+	pub fn reveal(&mut self, line: usize) -> bool {
+		let mut opened = false;
+		for fold in self.items.iter_mut().filter(|fold| fold.hides(line)) {
+			fold.closed = false;
+			opened = true;
+		}
+		opened
+	}
+
+	/// Opens or closes every fold.
+	pub fn set_all_closed(&mut self, closed: bool) {
+		for fold in &mut self.items {
+			fold.closed = closed;
+		}
+	}
+
+	/// Maps every fold through an applied transaction, from the content
+	/// `before` it to the content `after`.
+	///
+	/// Closed folds whose hidden lines the edit touches are opened; folds
+	/// reduced to a single line are dropped.
+	pub fn map_through(&mut self, tx: &Transaction, before: &Rope, after: &Rope) {
+		if self.items.is_empty() {
+			return;
+		}
+		let touched = touched_ranges(tx);
+		let changes = tx.changes();
+		let lines = before.len_lines();
+		for fold in &mut self.items {
+			let start = fold.start.min(lines - 1);
+			let end = fold.end.min(lines - 1);
+			let hidden_start = before.line_to_char((start + 1).min(end));
+			let content_end = before.line_to_char(end) + line_content_len(before, end);
+			if fold.closed
+				&& touched
+					.iter()
+					.any(|&(from, to)| from <= content_end && to >= hidden_start)
+			{
+				fold.closed = false;
+			}
+			let header = changes.map_pos(before.line_to_char(start), Bias::Right);
+			let last = changes.map_pos(content_end, Bias::Left);
+			fold.start = after.char_to_line(header);
+			fold.end = after.char_to_line(last);
+		}
+		self.items.retain(|fold| fold.start < fold.end);
+	}
+}
+
+/// Returns the character ranges of the content before `tx` that it
+/// deletes or inserts at, in order.
+fn touched_ranges(tx: &Transaction) -> Vec<(usize, usize)> {
+	let mut pos = 0;
+	let mut touched = Vec::new();
+	for op in tx.operations() {
+		match op {
+			Operation::Retain(len) => pos += len,
+			Operation::Delete(len) => {
+				touched.push((pos, pos + len));
+				pos += len;
+			}
+			Operation::Insert(insertion) if !insertion.is_empty() => touched.push((pos, pos)),
+			Operation::Insert(_) => {}
+		}
+	}
+	touched
+}
+
+/// Returns the number of characters on `line`, its line break excluded.
+fn line_content_len(text: &Rope, line: usize) -> usize {
+	let slice = text.line(line);
+	let len = slice.len_chars();
+	if len > 0 && slice.char(len - 1) == '\n' {
+		len - 1
+	} else {
+		len
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	fn text(lines: usize) -> Rope {
+		Rope::from(
+			(0..lines)
+				.map(|i| format!("line {i}\n"))
+				.collect::<String>(),
+		)
+	}
+
+	fn folds(ranges: &[(usize, usize)]) -> Folds {
+		let mut folds = Folds::default();
+		folds.replace(ranges.iter().copied());
+		folds
+	}
+
+	fn edit(doc: &Rope, start: usize, end: usize, text: &str) -> (Transaction, Rope) {
+		let tx = Transaction::change(
+			doc.slice(..),
+			[Change {
+				start,
+				end,
+				replacement: Some(text.into()),
+			}],
+		);
+		let mut after = doc.clone();
+		tx.apply(&mut after);
+		(tx, after)
+	}
+
+	#[test]
+	fn closed_folds_hide_lines_after_their_header() {
+		let mut folds = folds(&[(2, 8), (3, 5)]);
+		assert!(folds.toggle(4));
+		assert!(folds.is_hidden(5) && !folds.is_hidden(3) && !folds.is_hidden(6));
+		assert_eq!(folds.next_visible_line(3), 6);
+
+		assert!(folds.toggle(7));
+		assert_eq!(folds.visible_line(4), 2);
+		assert_eq!(folds.hidden_lines(2), Some(6));
+		assert_eq!(folds.next_visible_line(2), 9);
+		assert_eq!(folds.prev_visible_line(9), Some(2));
+		assert_eq!(folds.prev_visible_line(0), None);
+
+		assert!(folds.toggle(2));
+		assert!(!folds.is_hidden(7) && folds.is_hidden(4));
+		assert!(!folds.toggle(10));
+	}
+
+	#[test]
+	fn folds_follow_lines_inserted_above() {
+		let doc = text(10);
+		let mut folds = folds(&[(2, 5)]);
+		folds.set_all_closed(true);
+		let (tx, after) = edit(&doc, 0, 0, "new\n");
+		folds.map_through(&tx, &doc, &after);
+		let fold = folds.iter().next().unwrap();
+		assert_eq!((fold.start, fold.end, fold.closed), (3, 6, true));
+	}
+
+	#[test]
+	fn edits_inside_a_closed_fold_open_it() {
+		let doc = text(10);
+		let mut folds = folds(&[(2, 5)]);
+		folds.set_all_closed(true);
+
+		let header_end = doc.line_to_char(3) - 1;
+		let (tx, after) = edit(&doc, header_end - 1, header_end - 1, "x");
+		folds.map_through(&tx, &doc, &after);
+		assert!(folds.any_closed());
+
+		let doc = after;
+		let inside = doc.line_to_char(4) + 2;
+		let (tx, after) = edit(&doc, inside, inside + 1, "");
+		folds.map_through(&tx, &doc, &after);
+		assert!(!folds.any_closed());
+		assert_eq!(folds.iter().count(), 1);
+	}
+
+	#[test]
+	fn replacing_keeps_folds_closed_by_header() {
+		let mut folds = folds(&[(2, 5), (7, 9)]);
+		folds.toggle(2);
+		folds.replace([(2, 6), (7, 9), (4, 4)]);
+		let closed: Vec<_> = folds.iter().map(|fold| fold.closed).collect();
+		assert_eq!(closed, [true, false]);
+	}
+}
@@ -0,0 +1,93 @@
+//! Indentation helpers for new lines and soft-tab deletion.
+
+use xeno_base::RopeSlice;
+use xeno_base::range::CharIdx;
+
+/// Returns the leading spaces and tabs of `line`.
+pub fn line_indent(text: RopeSlice, line: usize) -> String {
+	text.line(line)
+		.chars()
+		.take_while(|c| matches!(c, ' ' | '\t'))
+		.collect()
+}
+
+/// Returns the indentation for a line break inserted at `pos`.
+///
+/// Copies the indentation of `pos`'s line, cut short if `pos` sits inside
+/// it, and appends `unit` when the text before `pos` ends with one of
+/// `open_tokens`.
+pub fn newline_indent(text: RopeSlice, pos: CharIdx, unit: &str, open_tokens: &[String]) -> String {
+	let line = text.char_to_line(pos);
+	let line_start = text.line_to_char(line);
+	let mut indent = line_indent(text, line);
+	indent.truncate(
+		indent
+			.char_indices()
+			.nth(pos - line_start)
+			.map_or(indent.len(), |(i, _)| i),
+	);
+
+	let before = text.slice(line_start..pos).to_string();
+	let before = before.trim_end();
+	if !before.is_empty() && open_tokens.iter().any(|t| before.ends_with(t.as_str())) {
+		indent.push_str(unit);
+	}
+	indent
+}
+
+/// Returns where a backspace at `pos` should start deleting to remove one
+/// soft-tab indent level, or `None` if `pos` is not inside space-only
+/// indentation.
+pub fn soft_tab_start(text: RopeSlice, pos: CharIdx, width: usize) -> Option<CharIdx> {
+	let line_start = text.line_to_char(text.char_to_line(pos));
+	let col = pos - line_start;
+	if col == 0 || width == 0 || text.slice(line_start..pos).chars().any(|c| c != ' ') {
+		return None;
+	}
+	let remove = match col % width {
+		0 => width,
+		partial => partial,
+	};
+	Some(pos - remove)
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Rope;
+
+	use super::*;
+
+	fn tokens() -> Vec<String> {
+		["{", "(", "[", ":"].map(String::from).to_vec()
+	}
+
+	#[test]
+	fn newline_copies_and_opens_indent() {
+		let text = Rope::from("\tfn main() {\n    let x = 1;\nif x:  \n");
+		let slice = text.slice(..);
+
+		assert_eq!(newline_indent(slice, 12, "    ", &tokens()), "\t    ");
+		assert_eq!(newline_indent(slice, 26, "    ", &tokens()), "    ");
+		assert_eq!(newline_indent(slice, 34, "  ", &tokens()), "  ");
+		assert_eq!(newline_indent(slice, 34, "  ", &[]), "");
+	}
+
+	#[test]
+	fn newline_inside_indentation_keeps_what_precedes() {
+		let text = Rope::from("        x\n");
+		assert_eq!(newline_indent(text.slice(..), 3, "    ", &tokens()), "   ");
+		assert_eq!(newline_indent(text.slice(..), 0, "    ", &tokens()), "");
+	}
+
+	#[test]
+	fn soft_tab_removes_to_previous_stop() {
+		let text = Rope::from("        x\n      y\n  \tz\n");
+		let slice = text.slice(..);
+
+		assert_eq!(soft_tab_start(slice, 8, 4), Some(4));
+		assert_eq!(soft_tab_start(slice, 16, 4), Some(14));
+		assert_eq!(soft_tab_start(slice, 9, 4), None);
+		assert_eq!(soft_tab_start(slice, 10, 4), None);
+		assert_eq!(soft_tab_start(slice, 21, 4), None);
+	}
+}
@@ -0,0 +1,261 @@
+//! Inlay hints prepared for display, following document edits.
+//!
+//! Hints are requested for the lines around the viewport rather than the
+//! whole document, so each set remembers the character range it covers.
+//! Like [diagnostics](super::PreparedDiagnostics), the hints and their range
+//! are mapped through every edit until they are requested again.
+
+use std::ops::Range;
+
+use xeno_base::transaction::Bias;
+use xeno_base::{Rope, Transaction};
+
+/// What an inlay hint annotates, which decides the side it sticks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+	/// A type annotation after the expression it describes.
+	Type,
+	/// A parameter name before the argument it labels.
+	Parameter,
+	/// A hint of unspecified kind.
+	Other,
+}
+
+/// One piece of an inlay hint's label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHintPart {
+	/// Text of the part.
+	pub text: String,
+	/// Text shown when asking about this part, if any.
+	pub tooltip: Option<String>,
+}
+
+/// An inlay hint converted to a character offset in its document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedInlayHint {
+	/// Character the hint is drawn before.
+	pub pos: usize,
+	/// What the hint annotates.
+	pub kind: InlayHintKind,
+	/// Label pieces, drawn one after another.
+	pub parts: Vec<InlayHintPart>,
+	/// Whether a space separates the hint from the text before it.
+	pub padding_left: bool,
+	/// Whether a space separates the hint from the text after it.
+	pub padding_right: bool,
+	/// Text shown when asking about the hint as a whole, if any.
+	pub tooltip: Option<String>,
+}
+
+impl PreparedInlayHint {
+	/// Converts an LSP inlay hint whose position is in `encoding`.
+	#[cfg(feature = "lsp")]
+	pub fn from_lsp(
+		hint: &xeno_lsp::lsp_types::InlayHint,
+		text: &Rope,
+		encoding: xeno_lsp::OffsetEncoding,
+	) -> Self {
+		use xeno_lsp::lsp_types::{InlayHintLabel, InlayHintLabelPartTooltip, InlayHintTooltip};
+
+		let parts = match &hint.label {
+			InlayHintLabel::String(text) => vec![InlayHintPart {
+				text: text.clone(),
+				tooltip: None,
+			}],
+			InlayHintLabel::LabelParts(parts) => parts
+				.iter()
+				.map(|part| InlayHintPart {
+					text: part.value.clone(),
+					tooltip: part.tooltip.as_ref().map(|tooltip| match tooltip {
+						InlayHintLabelPartTooltip::String(text) => text.clone(),
+						InlayHintLabelPartTooltip::MarkupContent(markup) => markup.value.clone(),
+					}),
+				})
+				.collect(),
+		};
+		let kind = match hint.kind {
+			Some(xeno_lsp::lsp_types::InlayHintKind::TYPE) => InlayHintKind::Type,
+			Some(xeno_lsp::lsp_types::InlayHintKind::PARAMETER) => InlayHintKind::Parameter,
+			_ => InlayHintKind::Other,
+		};
+		Self {
+			pos: xeno_lsp::lsp_position_to_char(text, hint.position, encoding),
+			kind,
+			parts,
+			padding_left: hint.padding_left.unwrap_or(false),
+			padding_right: hint.padding_right.unwrap_or(false),
+			tooltip: hint.tooltip.as_ref().map(|tooltip| match tooltip {
+				InlayHintTooltip::String(text) => text.clone(),
+				InlayHintTooltip::MarkupContent(markup) => markup.value.clone(),
+			}),
+		}
+	}
+
+	/// Returns the text drawn for the hint, padding included.
+	///
+	/// Labels longer than `max_len` characters are cut short with an
+	/// ellipsis; a `max_len` of zero leaves them whole.
+	pub fn label(&self, max_len: usize) -> String {
+		let mut label: String = self.parts.iter().map(|part| part.text.as_str()).collect();
+		if max_len > 0 && label.chars().count() > max_len {
+			label = label.chars().take(max_len.saturating_sub(1)).collect();
+			label.push('…');
+		}
+		let mut text = String::with_capacity(label.len() + 2);
+		if self.padding_left {
+			text.push(' ');
+		}
+		text.push_str(&label);
+		if self.padding_right {
+			text.push(' ');
+		}
+		text
+	}
+
+	/// Iterates over the tooltips of the hint and then of its parts.
+	pub fn tooltips(&self) -> impl Iterator<Item = &str> {
+		self.tooltip
+			.as_deref()
+			.into_iter()
+			.chain(self.parts.iter().filter_map(|part| part.tooltip.as_deref()))
+	}
+}
+
+/// Inlay hints of a document, in the positions of its current content.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedInlayHints {
+	/// Hints ordered by position.
+	items: Vec<PreparedInlayHint>,
+	/// Characters the hints were requested for, if they ever were.
+	covered: Option<Range<usize>>,
+}
+
+impl PreparedInlayHints {
+	/// Iterates over the hints in position order.
+	pub fn iter(&self) -> impl Iterator<Item = &PreparedInlayHint> {
+		self.items.iter()
+	}
+
+	/// Returns whether there are no hints.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Returns the characters the hints were requested for, or `None` if
+	/// they never were or have been dropped since.
+	pub fn covered(&self) -> Option<Range<usize>> {
+		self.covered.clone()
+	}
+
+	/// Iterates over the hints drawn before the character at `pos`.
+	pub fn at(&self, pos: usize) -> impl Iterator<Item = &PreparedInlayHint> {
+		let start = self.items.partition_point(|hint| hint.pos < pos);
+		self.items[start..]
+			.iter()
+			.take_while(move |hint| hint.pos == pos)
+	}
+
+	/// Replaces the hints with ones freshly requested for `covered`.
+	pub fn replace(&mut self, covered: Range<usize>, mut items: Vec<PreparedInlayHint>) {
+		items.sort_by_key(|hint| hint.pos);
+		self.items = items;
+		self.covered = Some(covered);
+	}
+
+	/// Maps every position through an applied transaction.
+	///
+	/// Text typed where a hint sits joins the token the hint annotates: a
+	/// type hint moves past it, since it follows its expression, while a
+	/// parameter hint stays before it, since it precedes its argument.
+	pub fn map_through(&mut self, tx: &Transaction) {
+		let changes = tx.changes();
+		for hint in &mut self.items {
+			let bias = match hint.kind {
+				InlayHintKind::Parameter => Bias::Left,
+				InlayHintKind::Type | InlayHintKind::Other => Bias::Right,
+			};
+			hint.pos = changes.map_pos(hint.pos, bias);
+		}
+		self.items.sort_by_key(|hint| hint.pos);
+		if let Some(covered) = &mut self.covered {
+			let start = changes.map_pos(covered.start, Bias::Left);
+			let end = changes.map_pos(covered.end, Bias::Right);
+			*covered = start..end.max(start);
+		}
+	}
+
+	/// Drops all hints until they are requested again.
+	pub fn clear(&mut self) {
+		self.items.clear();
+		self.covered = None;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	fn hint(pos: usize, kind: InlayHintKind, text: &str) -> PreparedInlayHint {
+		PreparedInlayHint {
+			pos,
+			kind,
+			parts: vec![InlayHintPart {
+				text: text.into(),
+				tooltip: None,
+			}],
+			padding_left: false,
+			padding_right: false,
+			tooltip: None,
+		}
+	}
+
+	#[test]
+	fn hints_stick_to_the_token_they_annotate() {
+		let doc = Rope::from("let x = f(1);");
+		let mut hints = PreparedInlayHints::default();
+		hints.replace(
+			0..13,
+			vec![
+				hint(10, InlayHintKind::Parameter, "n:"),
+				hint(5, InlayHintKind::Type, ": u8"),
+			],
+		);
+
+		// Extend the name and prepend a digit to the argument.
+		let tx = Transaction::change(
+			doc.slice(..),
+			[
+				Change {
+					start: 5,
+					end: 5,
+					replacement: Some("s".into()),
+				},
+				Change {
+					start: 10,
+					end: 10,
+					replacement: Some("2".into()),
+				},
+			],
+		);
+		hints.map_through(&tx);
+		let positions: Vec<_> = hints.iter().map(|h| h.pos).collect();
+		assert_eq!(positions, vec![6, 11]);
+		assert_eq!(hints.covered(), Some(0..15));
+		assert_eq!(hints.at(11).count(), 1);
+	}
+
+	#[test]
+	fn labels_are_padded_and_truncated() {
+		let mut long = hint(0, InlayHintKind::Type, "HashMap<");
+		long.parts.push(InlayHintPart {
+			text: "String, Vec<u8>>".into(),
+			tooltip: Some("std::vec::Vec".into()),
+		});
+		long.padding_left = true;
+		assert_eq!(long.label(0), " HashMap<String, Vec<u8>>");
+		assert_eq!(long.label(10), " HashMap<S…");
+		assert_eq!(long.tooltips().collect::<Vec<_>>(), ["std::vec::Vec"]);
+	}
+}
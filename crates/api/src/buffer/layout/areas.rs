@@ -20,12 +20,12 @@ impl Layout {
 			Layout::Single(view) => vec![(*view, area)],
 			Layout::Split {
 				direction,
-				position,
+				weights,
 				first,
 				second,
 			} => {
 				let (first_area, second_area, _) =
-					Self::compute_split_areas(area, *direction, *position);
+					Self::compute_split_areas(area, *direction, *weights);
 				let mut areas = first.compute_view_areas(first_area);
 				areas.extend(second.compute_view_areas(second_area));
 				areas
@@ -73,7 +73,7 @@ impl Layout {
 	) -> Option<(SplitDirection, Rect, SplitPath)> {
 		let Layout::Split {
 			direction,
-			position,
+			weights,
 			first,
 			second,
 		} = self
@@ -82,7 +82,7 @@ impl Layout {
 		};
 
 		let (first_area, second_area, sep_rect) =
-			Self::compute_split_areas(area, *direction, *position);
+			Self::compute_split_areas(area, *direction, *weights);
 
 		if x >= sep_rect.x
 			&& x < sep_rect.x + sep_rect.width
@@ -144,6 +144,9 @@ impl Layout {
 	}
 
 	/// Resizes the split at the given path based on mouse position.
+	///
+	/// The separator follows the mouse as far as both children's minimum
+	/// sizes allow, and the split's weights are rewritten to match.
 	pub fn resize_at_path(
 		&mut self,
 		area: Rect,
@@ -158,7 +161,7 @@ impl Layout {
 	fn do_resize_at_path(&mut self, area: Rect, path: &[bool], mouse_x: u16, mouse_y: u16) -> bool {
 		let Layout::Split {
 			direction,
-			position,
+			weights,
 			first,
 			second,
 		} = self
@@ -167,23 +170,19 @@ impl Layout {
 		};
 
 		if path.is_empty() {
-			let new_position = match direction {
-				SplitDirection::Horizontal => {
-					let min_pos = area.x + first.min_width();
-					let max_pos = (area.x + area.width).saturating_sub(second.min_width() + 1);
-					mouse_x.clamp(min_pos.min(max_pos), max_pos)
-				}
-				SplitDirection::Vertical => {
-					let min_pos = area.y + first.min_height();
-					let max_pos = (area.y + area.height).saturating_sub(second.min_height() + 1);
-					mouse_y.clamp(min_pos.min(max_pos), max_pos)
-				}
+			let (start, extent) = Self::split_span(area, *direction);
+			let (first_min, second_min, mouse) = match direction {
+				SplitDirection::Horizontal => (first.min_width(), second.min_width(), mouse_x),
+				SplitDirection::Vertical => (first.min_height(), second.min_height(), mouse_y),
 			};
-			*position = new_position;
+			let min_pos = start + first_min;
+			let max_pos = (start + extent).saturating_sub(second_min + 1);
+			let separator = mouse.clamp(min_pos.min(max_pos), max_pos);
+			*weights = Self::weights_for(extent, separator.saturating_sub(start));
 			return true;
 		}
 
-		let (first_area, second_area, _) = Self::compute_split_areas(area, *direction, *position);
+		let (first_area, second_area, _) = Self::compute_split_areas(area, *direction, *weights);
 		if path[0] {
 			second.do_resize_at_path(second_area, &path[1..], mouse_x, mouse_y)
 		} else {
@@ -208,7 +207,7 @@ impl Layout {
 	) -> Option<(SplitDirection, Rect)> {
 		let Layout::Split {
 			direction,
-			position,
+			weights,
 			first,
 			second,
 		} = self
@@ -217,7 +216,7 @@ impl Layout {
 		};
 
 		let (first_area, second_area, sep_rect) =
-			Self::compute_split_areas(area, *direction, *position);
+			Self::compute_split_areas(area, *direction, *weights);
 
 		if path.is_empty() {
 			return Some((*direction, sep_rect));
@@ -230,15 +229,44 @@ impl Layout {
 		}
 	}
 
-	/// Computes the areas for a split given absolute separator position.
+	/// Returns the start coordinate and extent of `area` along a split's axis.
+	pub(super) fn split_span(area: Rect, direction: SplitDirection) -> (u16, u16) {
+		match direction {
+			SplitDirection::Horizontal => (area.x, area.width),
+			SplitDirection::Vertical => (area.y, area.height),
+		}
+	}
+
+	/// Returns the size of the first child when `extent` cells (separator
+	/// included) are shared out by `weights`, rounded to the nearest cell.
+	pub(super) fn weighted_size(extent: u16, weights: (u16, u16)) -> u16 {
+		let available = u32::from(extent.saturating_sub(1));
+		let total = u32::from(weights.0) + u32::from(weights.1);
+		if total == 0 {
+			return (available / 2) as u16;
+		}
+		((available * u32::from(weights.0) + total / 2) / total) as u16
+	}
+
+	/// Returns weights that reproduce a first child of `first_size` cells
+	/// within `extent` cells (separator included).
+	pub(super) fn weights_for(extent: u16, first_size: u16) -> (u16, u16) {
+		let available = extent.saturating_sub(1);
+		let first_size = first_size.min(available);
+		(first_size, available - first_size)
+	}
+
+	/// Computes the areas for a split whose children share `area` by `weights`.
 	///
 	/// Returns (first_area, second_area, separator_rect).
 	/// The separator position is clamped to ensure both areas meet minimum size requirements.
 	pub(super) fn compute_split_areas(
 		area: Rect,
 		direction: SplitDirection,
-		position: u16,
+		weights: (u16, u16),
 	) -> (Rect, Rect, Rect) {
+		let (start, extent) = Self::split_span(area, direction);
+		let position = start + Self::weighted_size(extent, weights);
 		match direction {
 			SplitDirection::Horizontal => {
 				let min_pos = area.x + Self::MIN_WIDTH;
@@ -301,7 +329,7 @@ impl Layout {
 	pub fn separator_positions(&self, area: Rect) -> Vec<(SplitDirection, u8, Rect)> {
 		let Layout::Split {
 			direction,
-			position,
+			weights,
 			first,
 			second,
 		} = self
@@ -310,7 +338,7 @@ impl Layout {
 		};
 
 		let (first_area, second_area, sep_rect) =
-			Self::compute_split_areas(area, *direction, *position);
+			Self::compute_split_areas(area, *direction, *weights);
 
 		// Visual priority is always 0 for text buffers (no panels)
 		let priority = 0;
@@ -3,18 +3,20 @@
 //! The `Layout` enum represents how buffers are arranged in the editor window.
 //! It supports recursive splitting for complex layouts.
 //!
-//! Split positions are stored as absolute screen coordinates, not ratios.
-//! This ensures splits remain stable when other UI elements appear or disappear.
+//! Split sizes are stored as a pair of weights rather than screen
+//! coordinates, so when the available area changes the space is shared out
+//! proportionally. Resizing a split (by mouse or by command) rewrites the
+//! weights to the resulting cell sizes.
 
 mod areas;
 mod navigation;
+mod resize;
 #[cfg(test)]
 mod tests;
 mod types;
 
 pub use types::{BufferView, SplitDirection, SplitPath};
 pub use xeno_base::SpatialDirection;
-use xeno_tui::layout::Rect;
 
 use super::BufferId;
 
@@ -41,8 +43,8 @@ pub enum Layout {
 	Split {
 		/// Direction of the split (horizontal or vertical).
 		direction: SplitDirection,
-		/// Absolute position of the separator (x for horizontal, y for vertical).
-		position: u16,
+		/// Relative sizes of the first and second child along the split.
+		weights: (u16, u16),
 		/// First child (left for horizontal, top for vertical).
 		first: Box<Layout>,
 		/// Second child (right for horizontal, bottom for vertical).
@@ -69,12 +71,12 @@ impl Layout {
 
 	/// Creates a side-by-side split (first on left, second on right).
 	///
-	/// The separator is placed at the horizontal center of the given area.
+	/// Both sides start with equal weight.
 	/// This is a "vertical split" in Vim/Helix terminology (vertical divider line).
-	pub fn side_by_side(first: Layout, second: Layout, area: Rect) -> Self {
+	pub fn side_by_side(first: Layout, second: Layout) -> Self {
 		Layout::Split {
 			direction: SplitDirection::Horizontal,
-			position: area.x + area.width / 2,
+			weights: (1, 1),
 			first: Box::new(first),
 			second: Box::new(second),
 		}
@@ -82,12 +84,12 @@ impl Layout {
 
 	/// Creates a stacked split (first on top, second on bottom).
 	///
-	/// The separator is placed at the vertical center of the given area.
+	/// Both sides start with equal weight.
 	/// This is a "horizontal split" in Vim/Helix terminology (horizontal divider line).
-	pub fn stacked(first: Layout, second: Layout, area: Rect) -> Self {
+	pub fn stacked(first: Layout, second: Layout) -> Self {
 		Layout::Split {
 			direction: SplitDirection::Vertical,
-			position: area.y + area.height / 2,
+			weights: (1, 1),
 			first: Box::new(first),
 			second: Box::new(second),
 		}
@@ -174,7 +176,7 @@ impl Layout {
 			Layout::Single(_) => Some(self.clone()),
 			Layout::Split {
 				direction,
-				position,
+				weights,
 				first,
 				second,
 			} => match (first.remove_view(target), second.remove_view(target)) {
@@ -182,7 +184,7 @@ impl Layout {
 				(Some(layout), None) | (None, Some(layout)) => Some(layout),
 				(Some(f), Some(s)) => Some(Layout::Split {
 					direction: *direction,
-					position: *position,
+					weights: *weights,
 					first: Box::new(f),
 					second: Box::new(s),
 				}),
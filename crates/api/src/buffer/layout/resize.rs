@@ -0,0 +1,126 @@
+//! Keyboard-driven split resizing and equalizing.
+
+use xeno_tui::layout::Rect;
+
+use super::Layout;
+use super::types::SplitDirection;
+use crate::buffer::BufferId;
+
+impl Layout {
+	/// Grows `view` by `delta` cells along `direction`, or shrinks it when
+	/// `delta` is negative.
+	///
+	/// Only the nearest enclosing split of `direction` is adjusted. Returns
+	/// false if no such split contains the view.
+	pub fn resize_view(
+		&mut self,
+		area: Rect,
+		view: BufferId,
+		direction: SplitDirection,
+		delta: i32,
+	) -> bool {
+		self.adjust_view(area, view, direction, &|size| {
+			(i32::from(size) + delta).clamp(0, i32::from(u16::MAX)) as u16
+		})
+	}
+
+	/// Sets the size of `view` along `direction` to `size` cells, as far as
+	/// the minimum sizes of it and its sibling allow.
+	///
+	/// Returns false if no split of `direction` contains the view.
+	pub fn set_view_size(
+		&mut self,
+		area: Rect,
+		view: BufferId,
+		direction: SplitDirection,
+		size: u16,
+	) -> bool {
+		self.adjust_view(area, view, direction, &|_| size)
+	}
+
+	/// Resizes the side of the nearest `direction` split holding `view` to
+	/// the size returned by `size`, given its current size.
+	fn adjust_view(
+		&mut self,
+		area: Rect,
+		view: BufferId,
+		direction: SplitDirection,
+		size: &dyn Fn(u16) -> u16,
+	) -> bool {
+		let Layout::Split {
+			direction: split_direction,
+			weights,
+			first,
+			second,
+		} = self
+		else {
+			return false;
+		};
+
+		let (first_area, second_area, _) =
+			Self::compute_split_areas(area, *split_direction, *weights);
+		let in_first = first.contains_view(view);
+		let adjusted = if in_first {
+			first.adjust_view(first_area, view, direction, size)
+		} else if second.contains_view(view) {
+			second.adjust_view(second_area, view, direction, size)
+		} else {
+			return false;
+		};
+		if adjusted || *split_direction != direction {
+			return adjusted;
+		}
+
+		let (_, extent) = Self::split_span(area, direction);
+		let (_, first_size) = Self::split_span(first_area, direction);
+		let (_, second_size) = Self::split_span(second_area, direction);
+		let (first_min, second_min) = match direction {
+			SplitDirection::Horizontal => (first.min_width(), second.min_width()),
+			SplitDirection::Vertical => (first.min_height(), second.min_height()),
+		};
+
+		let available = extent.saturating_sub(1);
+		let wanted = if in_first {
+			size(first_size)
+		} else {
+			available.saturating_sub(size(second_size))
+		};
+		let max_first = available.saturating_sub(second_min);
+		*weights = Self::weights_for(extent, wanted.clamp(first_min.min(max_first), max_first));
+		true
+	}
+
+	/// Shares space equally between all views.
+	///
+	/// Each split is weighted by how many views each side lays out along
+	/// the split's direction, so `[A | [B | C]]` gets three equal columns.
+	pub fn equalize(&mut self) {
+		if let Layout::Split {
+			direction,
+			weights,
+			first,
+			second,
+		} = self
+		{
+			*weights = (first.span(*direction), second.span(*direction));
+			first.equalize();
+			second.equalize();
+		}
+	}
+
+	/// Returns the number of views laid out one after another along `direction`.
+	fn span(&self, direction: SplitDirection) -> u16 {
+		match self {
+			Layout::Single(_) => 1,
+			Layout::Split {
+				direction: split_direction,
+				first,
+				second,
+				..
+			} if *split_direction == direction => first.span(direction) + second.span(direction),
+			Layout::Split { first, second, .. } => {
+				first.span(direction).max(second.span(direction))
+			}
+		}
+	}
+}
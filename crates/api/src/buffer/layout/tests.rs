@@ -9,18 +9,31 @@ fn make_rect(x: u16, y: u16, width: u16, height: u16) -> xeno_tui::layout::Rect
 	}
 }
 
-fn get_position(layout: &Layout) -> Option<u16> {
-	match layout {
-		Layout::Split { position, .. } => Some(*position),
-		Layout::Single(_) => None,
-	}
+/// Returns the x or y coordinate of the separator at `path`.
+fn separator_at(layout: &Layout, area: xeno_tui::layout::Rect, path: Vec<bool>) -> Option<u16> {
+	let (direction, rect) = layout.separator_rect_at_path(area, &SplitPath(path))?;
+	Some(match direction {
+		SplitDirection::Horizontal => rect.x,
+		SplitDirection::Vertical => rect.y,
+	})
 }
 
-fn get_inner_position(layout: &Layout) -> Option<u16> {
-	match layout {
-		Layout::Split { second, .. } => get_position(second),
-		Layout::Single(_) => None,
-	}
+fn get_position(layout: &Layout, area: xeno_tui::layout::Rect) -> Option<u16> {
+	separator_at(layout, area, vec![])
+}
+
+fn get_inner_position(layout: &Layout, area: xeno_tui::layout::Rect) -> Option<u16> {
+	separator_at(layout, area, vec![true])
+}
+
+/// Returns the area of `id` in `layout`.
+fn view_area(layout: &Layout, area: xeno_tui::layout::Rect, id: u64) -> xeno_tui::layout::Rect {
+	layout
+		.compute_view_areas(area)
+		.into_iter()
+		.find(|(v, _)| *v == BufferId(id))
+		.unwrap()
+		.1
 }
 
 #[test]
@@ -35,28 +48,19 @@ fn single_layout() {
 #[test]
 fn side_by_side_split() {
 	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	assert_eq!(layout.first_buffer(), Some(BufferId(1)));
 	assert_eq!(layout.buffer_ids(), vec![BufferId(1), BufferId(2)]);
 	assert!(layout.contains(BufferId(1)));
 	assert!(layout.contains(BufferId(2)));
 	assert!(!layout.contains(BufferId(3)));
-	assert_eq!(get_position(&layout), Some(40));
+	assert_eq!(get_position(&layout, area), Some(40));
 }
 
 #[test]
 fn next_prev_buffer() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	assert_eq!(layout.next_buffer(BufferId(1)), BufferId(2));
 	assert_eq!(layout.next_buffer(BufferId(2)), BufferId(1));
@@ -66,12 +70,7 @@ fn next_prev_buffer() {
 
 #[test]
 fn remove_buffer() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	let after_remove = layout.remove(BufferId(1)).unwrap();
 	assert_eq!(after_remove.buffer_ids(), vec![BufferId(2)]);
@@ -83,13 +82,9 @@ fn remove_buffer() {
 #[test]
 fn resize_simple_stacked_split() {
 	let area = make_rect(0, 0, 80, 30);
-	let mut layout = Layout::stacked(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
-	assert_eq!(get_position(&layout), Some(15));
+	assert_eq!(get_position(&layout, area), Some(15));
 
 	let sep_info = layout.separator_with_path_at_position(area, 40, 15);
 	assert!(sep_info.is_some());
@@ -99,7 +94,7 @@ fn resize_simple_stacked_split() {
 
 	layout.resize_at_path(area, &path, 40, 20);
 
-	let new_position = get_position(&layout).unwrap();
+	let new_position = get_position(&layout, area).unwrap();
 	assert_eq!(new_position, 20, "Position should be at mouse y");
 }
 
@@ -107,16 +102,11 @@ fn resize_simple_stacked_split() {
 fn resize_nested_splits_only_affects_target() {
 	let area = make_rect(0, 0, 80, 30);
 	// Outer split at y=15, inner split within second half (y=16 to y=29)
-	// Inner area would be y=16, height=14, so inner separator at y=16+7=23
-	let inner_area = make_rect(0, 16, 80, 14);
-	let inner = Layout::stacked(
-		Layout::single(BufferId(2)),
-		Layout::single(BufferId(3)),
-		inner_area,
-	);
-	let mut layout = Layout::stacked(Layout::single(BufferId(1)), inner, area);
+	// Inner area is y=16, height=14, so inner separator at y=16+7=23
+	let inner = Layout::stacked(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), inner);
 
-	let outer_pos_before = get_position(&layout).unwrap();
+	let outer_pos_before = get_position(&layout, area).unwrap();
 	assert_eq!(outer_pos_before, 15);
 
 	// Inner separator is at y=23 (16 + 7)
@@ -133,25 +123,20 @@ fn resize_nested_splits_only_affects_target() {
 
 	assert_eq!(
 		outer_pos_before,
-		get_position(&layout).unwrap(),
+		get_position(&layout, area).unwrap(),
 		"Outer position should not change"
 	);
 
-	let inner_pos_after = get_inner_position(&layout).unwrap();
+	let inner_pos_after = get_inner_position(&layout, area).unwrap();
 	assert_eq!(inner_pos_after, 26, "Inner position should be at mouse y");
 }
 
 #[test]
 fn separator_rect_at_path() {
 	let area = make_rect(0, 0, 80, 30);
-	// Inner layout uses the area it will occupy (second half: y=16, height=14)
-	let inner_area = make_rect(0, 16, 80, 14);
-	let inner = Layout::stacked(
-		Layout::single(BufferId(2)),
-		Layout::single(BufferId(3)),
-		inner_area,
-	);
-	let layout = Layout::stacked(Layout::single(BufferId(1)), inner, area);
+	// Inner layout occupies the second half: y=16, height=14
+	let inner = Layout::stacked(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let layout = Layout::stacked(Layout::single(BufferId(1)), inner);
 
 	let outer_sep = layout.separator_rect_at_path(area, &SplitPath(vec![]));
 	assert!(outer_sep.is_some());
@@ -172,17 +157,9 @@ fn separator_rect_at_path() {
 #[test]
 fn separator_positions_2x2_grid() {
 	let area = make_rect(0, 0, 81, 25);
-	let top = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
-	let bottom = Layout::side_by_side(
-		Layout::single(BufferId(3)),
-		Layout::single(BufferId(4)),
-		area,
-	);
-	let layout = Layout::stacked(top, bottom, area);
+	let top = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
+	let bottom = Layout::side_by_side(Layout::single(BufferId(3)), Layout::single(BufferId(4)));
+	let layout = Layout::stacked(top, bottom);
 
 	let seps = layout.separator_positions(area);
 
@@ -224,23 +201,20 @@ fn separator_positions_2x2_grid() {
 }
 
 #[test]
-fn absolute_position_stable_across_area_changes() {
-	let area = make_rect(0, 0, 80, 40);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+fn weights_scale_with_area() {
+	let area = make_rect(0, 0, 80, 30);
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
+	assert_eq!(get_position(&layout, area), Some(40));
 
-	assert_eq!(get_position(&layout), Some(40));
+	// First view gets 20 of the 79 available columns
+	layout.resize_at_path(area, &SplitPath(vec![]), 20, 15);
+	assert_eq!(get_position(&layout, area), Some(20));
 
-	let smaller_area = make_rect(0, 0, 80, 30);
-	let seps = layout.separator_positions(smaller_area);
-	assert_eq!(seps.len(), 1);
-	let (_, _, sep_rect) = &seps[0];
+	let wider = make_rect(0, 0, 160, 30);
 	assert_eq!(
-		sep_rect.x, 40,
-		"Separator should stay at absolute position 40"
+		get_position(&layout, wider),
+		Some(40),
+		"Separator should keep its share of the width"
 	);
 }
 
@@ -252,12 +226,7 @@ fn min_width_single() {
 
 #[test]
 fn min_width_horizontal_split() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Horizontal split: first_min + 1 (separator) + second_min
 	assert_eq!(layout.min_width(), Layout::MIN_WIDTH * 2 + 1);
@@ -265,12 +234,7 @@ fn min_width_horizontal_split() {
 
 #[test]
 fn min_width_vertical_split() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::stacked(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::stacked(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Vertical split: max of children (both are MIN_WIDTH)
 	assert_eq!(layout.min_width(), Layout::MIN_WIDTH);
@@ -284,12 +248,7 @@ fn min_height_single() {
 
 #[test]
 fn min_height_vertical_split() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::stacked(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::stacked(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Vertical split: first_min + 1 (separator) + second_min
 	assert_eq!(layout.min_height(), Layout::MIN_HEIGHT * 2 + 1);
@@ -297,12 +256,7 @@ fn min_height_vertical_split() {
 
 #[test]
 fn min_height_horizontal_split() {
-	let area = make_rect(0, 0, 80, 30);
-	let layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Horizontal split: max of children (both are MIN_HEIGHT)
 	assert_eq!(layout.min_height(), Layout::MIN_HEIGHT);
@@ -310,14 +264,9 @@ fn min_height_horizontal_split() {
 
 #[test]
 fn min_width_nested_splits() {
-	let area = make_rect(0, 0, 120, 30);
 	// [A | [B | C]] - three columns
-	let inner = Layout::side_by_side(
-		Layout::single(BufferId(2)),
-		Layout::single(BufferId(3)),
-		area,
-	);
-	let layout = Layout::side_by_side(Layout::single(BufferId(1)), inner, area);
+	let inner = Layout::side_by_side(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let layout = Layout::side_by_side(Layout::single(BufferId(1)), inner);
 
 	// first (MIN_WIDTH) + 1 + second (MIN_WIDTH + 1 + MIN_WIDTH)
 	assert_eq!(layout.min_width(), Layout::MIN_WIDTH * 3 + 2);
@@ -326,16 +275,12 @@ fn min_width_nested_splits() {
 #[test]
 fn resize_respects_minimum_width() {
 	let area = make_rect(0, 0, 80, 30);
-	let mut layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Try to drag separator to far left (would make first too small)
 	layout.resize_at_path(area, &SplitPath(vec![]), 0, 15);
 
-	let pos = get_position(&layout).unwrap();
+	let pos = get_position(&layout, area).unwrap();
 	// Position should be clamped to area.x + MIN_WIDTH
 	assert_eq!(pos, Layout::MIN_WIDTH);
 
@@ -348,16 +293,12 @@ fn resize_respects_minimum_width() {
 #[test]
 fn resize_respects_minimum_height() {
 	let area = make_rect(0, 0, 80, 30);
-	let mut layout = Layout::stacked(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Try to drag separator to far top (would make first too small)
 	layout.resize_at_path(area, &SplitPath(vec![]), 40, 0);
 
-	let pos = get_position(&layout).unwrap();
+	let pos = get_position(&layout, area).unwrap();
 	// Position should be clamped to area.y + MIN_HEIGHT
 	assert_eq!(pos, Layout::MIN_HEIGHT);
 
@@ -370,16 +311,12 @@ fn resize_respects_minimum_height() {
 #[test]
 fn resize_respects_sibling_minimum_width() {
 	let area = make_rect(0, 0, 80, 30);
-	let mut layout = Layout::side_by_side(
-		Layout::single(BufferId(1)),
-		Layout::single(BufferId(2)),
-		area,
-	);
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
 
 	// Try to drag separator to far right (would make second too small)
 	layout.resize_at_path(area, &SplitPath(vec![]), 200, 15);
 
-	let pos = get_position(&layout).unwrap();
+	let pos = get_position(&layout, area).unwrap();
 	// Position should be clamped to area.x + area.width - MIN_WIDTH - 1
 	let expected_max = area.x + area.width - Layout::MIN_WIDTH - 1;
 	assert_eq!(pos, expected_max);
@@ -394,13 +331,8 @@ fn resize_respects_sibling_minimum_width() {
 fn resize_cannot_push_nested_split() {
 	let area = make_rect(0, 0, 80, 30);
 	// Create [A | [B | C]] - nested horizontal splits
-	let inner_area = make_rect(41, 0, 39, 30);
-	let inner = Layout::side_by_side(
-		Layout::single(BufferId(2)),
-		Layout::single(BufferId(3)),
-		inner_area,
-	);
-	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), inner, area);
+	let inner = Layout::side_by_side(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), inner);
 
 	// Inner split needs MIN_WIDTH + 1 + MIN_WIDTH
 	let inner_min = Layout::MIN_WIDTH * 2 + 1;
@@ -408,7 +340,7 @@ fn resize_cannot_push_nested_split() {
 	// Try to drag outer separator far to the right
 	layout.resize_at_path(area, &SplitPath(vec![]), 200, 15);
 
-	let pos = get_position(&layout).unwrap();
+	let pos = get_position(&layout, area).unwrap();
 	// Position should be clamped so second child (inner split) has room for its minimum
 	let expected_max = area.x + area.width - inner_min - 1;
 	assert_eq!(pos, expected_max);
@@ -431,25 +363,86 @@ fn resize_nested_cannot_push_sibling() {
 	let area = make_rect(0, 0, 80, 30);
 	// Create stacked: top is single, bottom is [B | C]
 	// [[A] / [B | C]]
-	let inner_area = make_rect(0, 16, 80, 14);
-	let inner = Layout::side_by_side(
-		Layout::single(BufferId(2)),
-		Layout::single(BufferId(3)),
-		inner_area,
-	);
-	let mut layout = Layout::stacked(Layout::single(BufferId(1)), inner, area);
+	let inner = Layout::side_by_side(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), inner);
 
 	// Record outer separator position
-	let outer_pos_before = get_position(&layout).unwrap();
+	let outer_pos_before = get_position(&layout, area).unwrap();
 
 	// Resize the inner split (B|C boundary) - should not affect outer split
 	// Inner split path is [true] (second child of outer)
 	layout.resize_at_path(area, &SplitPath(vec![true]), 20, 20);
 
 	// Outer position should be unchanged
-	let outer_pos_after = get_position(&layout).unwrap();
+	let outer_pos_after = get_position(&layout, area).unwrap();
 	assert_eq!(
 		outer_pos_before, outer_pos_after,
 		"Outer split position should not change"
 	);
 }
+
+#[test]
+fn resize_view_grows_focused_side() {
+	let area = make_rect(0, 0, 81, 30);
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
+
+	assert!(layout.resize_view(area, BufferId(1), SplitDirection::Horizontal, 5));
+	assert_eq!(view_area(&layout, area, 1).width, 45);
+
+	// Growing the right view moves the separator left
+	assert!(layout.resize_view(area, BufferId(2), SplitDirection::Horizontal, 10));
+	assert_eq!(view_area(&layout, area, 2).width, 45);
+	assert_eq!(view_area(&layout, area, 1).width, 35);
+}
+
+#[test]
+fn resize_view_without_matching_split() {
+	let area = make_rect(0, 0, 80, 30);
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
+	assert!(!layout.resize_view(area, BufferId(1), SplitDirection::Vertical, 3));
+
+	let mut single = Layout::single(BufferId(1));
+	assert!(!single.resize_view(area, BufferId(1), SplitDirection::Horizontal, 3));
+}
+
+#[test]
+fn resize_view_uses_nearest_split() {
+	let area = make_rect(0, 0, 80, 31);
+	// [A / [B | C]]: B's height belongs to the outer split
+	let inner = Layout::side_by_side(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), inner);
+
+	let width_before = view_area(&layout, area, 2).width;
+	assert!(layout.resize_view(area, BufferId(2), SplitDirection::Vertical, 4));
+	assert_eq!(view_area(&layout, area, 2).height, 19);
+	assert_eq!(view_area(&layout, area, 2).width, width_before);
+}
+
+#[test]
+fn set_view_size_respects_minimums() {
+	let area = make_rect(0, 0, 80, 30);
+	let mut layout = Layout::stacked(Layout::single(BufferId(1)), Layout::single(BufferId(2)));
+
+	assert!(layout.set_view_size(area, BufferId(2), SplitDirection::Vertical, 10));
+	assert_eq!(view_area(&layout, area, 2).height, 10);
+
+	layout.set_view_size(area, BufferId(1), SplitDirection::Vertical, 0);
+	assert_eq!(view_area(&layout, area, 1).height, Layout::MIN_HEIGHT);
+
+	layout.set_view_size(area, BufferId(1), SplitDirection::Vertical, 100);
+	assert_eq!(view_area(&layout, area, 2).height, Layout::MIN_HEIGHT);
+}
+
+#[test]
+fn equalize_shares_space_between_views() {
+	let area = make_rect(0, 0, 92, 30);
+	// [A | [B | C]] should become three equal columns
+	let inner = Layout::side_by_side(Layout::single(BufferId(2)), Layout::single(BufferId(3)));
+	let mut layout = Layout::side_by_side(Layout::single(BufferId(1)), inner);
+	layout.resize_view(area, BufferId(1), SplitDirection::Horizontal, 20);
+
+	layout.equalize();
+	for id in 1..=3 {
+		assert_eq!(view_area(&layout, area, id).width, 30);
+	}
+}
@@ -0,0 +1,156 @@
+//! Line ending detection and conversion.
+//!
+//! Documents always hold `\n`-terminated lines. The file's own ending is
+//! detected on load, stripped from the content, and re-applied on save.
+
+/// Line terminator written when a document is saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+	/// Unix `\n`.
+	#[default]
+	Lf,
+	/// Windows `\r\n`.
+	Crlf,
+}
+
+impl LineEnding {
+	/// Returns the option name of this ending (`lf` or `crlf`).
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Lf => "lf",
+			Self::Crlf => "crlf",
+		}
+	}
+
+	/// Parses an option name; `auto` and unknown names yield `None`.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"lf" => Some(Self::Lf),
+			"crlf" => Some(Self::Crlf),
+			_ => None,
+		}
+	}
+
+	/// Returns the terminator written to disk.
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Lf => "\n",
+			Self::Crlf => "\r\n",
+		}
+	}
+
+	/// Appends `text` to `out`, writing each `\n` with this ending.
+	pub fn encode_into(self, out: &mut Vec<u8>, text: &str) {
+		match self {
+			Self::Lf => out.extend_from_slice(text.as_bytes()),
+			Self::Crlf => {
+				for (i, line) in text.split('\n').enumerate() {
+					if i > 0 {
+						out.extend_from_slice(self.as_str().as_bytes());
+					}
+					out.extend_from_slice(line.as_bytes());
+				}
+			}
+		}
+	}
+}
+
+/// Result of normalizing loaded text to `\n` line endings.
+#[derive(Debug)]
+pub struct NormalizedText {
+	/// Text with every `\r\n` replaced by `\n`.
+	pub text: String,
+	/// Ending used by the majority of lines, `Lf` on a tie.
+	pub ending: LineEnding,
+	/// Whether both endings occur in the original text.
+	pub mixed: bool,
+}
+
+/// Detects the dominant line ending of `text` and strips `\r\n` to `\n`.
+pub fn normalize(text: String) -> NormalizedText {
+	let mut counts = EndingCounts::default();
+	let text = counts.strip(text);
+	NormalizedText {
+		text,
+		ending: counts.majority(),
+		mixed: counts.mixed(),
+	}
+}
+
+/// Running tally of line endings, for text normalized in pieces.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EndingCounts {
+	/// Lines ending in `\r\n`.
+	crlf: usize,
+	/// Lines ending in a bare `\n`.
+	lf: usize,
+}
+
+impl EndingCounts {
+	/// Counts the endings in `text` and returns it with `\r\n` stripped to
+	/// `\n`.
+	///
+	/// A piece must not end between the `\r` and `\n` of a pair.
+	pub fn strip(&mut self, text: String) -> String {
+		let crlf = text.matches("\r\n").count();
+		self.crlf += crlf;
+		self.lf += text.matches('\n').count() - crlf;
+		if crlf > 0 {
+			text.replace("\r\n", "\n")
+		} else {
+			text
+		}
+	}
+
+	/// Returns the ending used by most lines, `Lf` on a tie.
+	pub fn majority(&self) -> LineEnding {
+		if self.crlf > self.lf {
+			LineEnding::Crlf
+		} else {
+			LineEnding::Lf
+		}
+	}
+
+	/// Returns whether both endings were seen.
+	pub fn mixed(&self) -> bool {
+		self.crlf > 0 && self.lf > 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn crlf_round_trips() {
+		let original = "one\r\ntwo\r\n\r\nthree";
+		let normalized = normalize(original.to_string());
+		assert_eq!(normalized.text, "one\ntwo\n\nthree");
+		assert_eq!(normalized.ending, LineEnding::Crlf);
+		assert!(!normalized.mixed);
+
+		let mut out = Vec::new();
+		normalized.ending.encode_into(&mut out, &normalized.text);
+		assert_eq!(out, original.as_bytes());
+	}
+
+	#[test]
+	fn mixed_endings_keep_majority() {
+		let normalized = normalize("a\r\nb\nc\r\n".to_string());
+		assert_eq!(normalized.text, "a\nb\nc\n");
+		assert_eq!(normalized.ending, LineEnding::Crlf);
+		assert!(normalized.mixed);
+
+		let normalized = normalize("a\nb\r\nc\n".to_string());
+		assert_eq!(normalized.ending, LineEnding::Lf);
+		assert!(normalized.mixed);
+	}
+
+	#[test]
+	fn lone_carriage_return_is_kept() {
+		let normalized = normalize("a\rb\n".to_string());
+		assert_eq!(normalized.text, "a\rb\n");
+		assert_eq!(normalized.ending, LineEnding::Lf);
+		assert!(!normalized.mixed);
+	}
+}
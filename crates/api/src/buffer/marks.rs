@@ -0,0 +1,95 @@
+//! Saved selections (marks) that follow document edits.
+//!
+//! Marks live on the [`Document`](super::Document), so every edit to its
+//! content, including undo and redo, maps them through the same changeset
+//! that changed the text. Content replaced outside the history invalidates
+//! them all.
+
+use std::collections::BTreeMap;
+
+use xeno_base::{Selection, Transaction};
+
+/// Slot used by `save_selection` and `restore_selection` when no register
+/// is given.
+pub const DEFAULT_MARK: char = '^';
+
+/// Named selection slots plus the selection most recently replaced by a
+/// search or regex selection.
+#[derive(Debug, Clone, Default)]
+pub struct SelectionMarks {
+	/// Selections saved by name.
+	slots: BTreeMap<char, Selection>,
+	/// Selection in effect before the last selection-replacing operation.
+	previous: Option<Selection>,
+}
+
+impl SelectionMarks {
+	/// Returns the selection saved in `slot`.
+	pub fn get(&self, slot: char) -> Option<&Selection> {
+		self.slots.get(&slot)
+	}
+
+	/// Saves `selection` to `slot`, replacing any previous contents.
+	pub fn set(&mut self, slot: char, selection: Selection) {
+		self.slots.insert(slot, selection);
+	}
+
+	/// Iterates over the named slots in slot order.
+	pub fn iter(&self) -> impl Iterator<Item = (char, &Selection)> {
+		self.slots.iter().map(|(&slot, sel)| (slot, sel))
+	}
+
+	/// Returns the selection replaced by the last search or regex selection.
+	pub fn previous(&self) -> Option<&Selection> {
+		self.previous.as_ref()
+	}
+
+	/// Records the selection about to be replaced.
+	pub fn set_previous(&mut self, selection: Selection) {
+		self.previous = Some(selection);
+	}
+
+	/// Maps every saved selection through an applied transaction.
+	pub fn map_through(&mut self, tx: &Transaction) {
+		for selection in self.slots.values_mut().chain(self.previous.as_mut()) {
+			*selection = tx.map_selection(selection);
+		}
+	}
+
+	/// Drops all saved selections.
+	pub fn clear(&mut self) {
+		self.slots.clear();
+		self.previous = None;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Rope;
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	#[test]
+	fn marks_follow_edits() {
+		let doc = Rope::from("alpha beta gamma");
+		let mut marks = SelectionMarks::default();
+		marks.set(DEFAULT_MARK, Selection::single(6, 10));
+		marks.set_previous(Selection::single(11, 16));
+
+		let tx = Transaction::change(
+			doc.slice(..),
+			[Change {
+				start: 0,
+				end: 0,
+				replacement: Some(">> ".into()),
+			}],
+		);
+		marks.map_through(&tx);
+
+		let saved = marks.get(DEFAULT_MARK).unwrap().primary();
+		assert_eq!((saved.anchor, saved.head), (9, 13));
+		let previous = marks.previous().unwrap().primary();
+		assert_eq!((previous.anchor, previous.head), (14, 19));
+	}
+}
@@ -6,28 +6,54 @@
 //!
 //! Multiple buffers can share the same document, enabling proper split behavior.
 
+mod block;
+mod diagnostics;
 mod document;
 mod editing;
+mod encoding;
+mod folds;
 mod history;
+mod indent;
+mod inlay_hints;
 mod layout;
+mod line_ending;
+mod marks;
 mod navigation;
+mod semantic_tokens;
+mod snippet;
 
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
-pub use document::{Document, DocumentId};
+pub use block::{BlockCorner, BlockSelection};
+pub use diagnostics::{PreparedDiagnostic, PreparedDiagnostics};
+pub use document::{Document, DocumentId, Scratch};
+pub use encoding::{BINARY_FILE_ERROR, BINARY_SNIFF_LEN, DecodedText, Encoding, decode, is_binary};
+pub use folds::{Fold, Folds};
 pub use history::HistoryResult;
+pub use indent::{line_indent, newline_indent, soft_tab_start};
+pub use inlay_hints::{InlayHintKind, InlayHintPart, PreparedInlayHint, PreparedInlayHints};
 pub use layout::{BufferView, Layout, SpatialDirection, SplitDirection, SplitPath};
+pub use line_ending::{EndingCounts, LineEnding, NormalizedText, normalize};
+pub use marks::{DEFAULT_MARK, SelectionMarks};
+pub use semantic_tokens::{SemanticToken, SemanticTokens, semantic_token_scope};
+pub use snippet::SnippetSession;
+use xeno_base::graphemes::ensure_grapheme_boundary_prev;
 use xeno_base::range::CharIdx;
-use xeno_base::{Mode, Selection};
+use xeno_base::{Mode, Rope, Selection};
 use xeno_input::InputHandler;
 use xeno_language::LanguageLoader;
+use xeno_registry::gutter::{GutterWidthContext, LineNumbers, total_width};
 use xeno_registry::options::{
-	FromOptionValue, OptionKey, OptionStore, OptionValue, TypedOptionKey,
+	FromOptionValue, OptionKey, OptionStore, OptionValue, TypedOptionKey, keys,
 };
 
+use crate::render::WrapIndent;
+
 /// Unique identifier for a buffer.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// IDs increase in creation order, which is the order buffers cycle in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BufferId(pub u64);
 
 impl BufferId {
@@ -63,6 +89,9 @@ pub struct Buffer {
 	/// Scroll position: first visible segment within the line (for wrapped lines).
 	pub scroll_segment: usize,
 
+	/// Scroll position: first visible display column (for unwrapped lines).
+	pub left_col: usize,
+
 	/// Text width for wrapping calculations.
 	pub text_width: usize,
 
@@ -75,6 +104,25 @@ pub struct Buffer {
 	/// Whether to suppress auto-scroll down to keep the cursor visible.
 	pub suppress_scroll_down: bool,
 
+	/// Whether the viewport was scrolled away from the cursor (e.g., by the
+	/// mouse wheel) and should stay put until the cursor moves.
+	pub viewport_detached: bool,
+
+	/// Line number column shown in the gutter.
+	///
+	/// Resolved from the `line-numbers` option by the editor, which owns the
+	/// option stores, so that [`gutter_width`](Self::gutter_width) can be
+	/// computed from the buffer alone.
+	pub line_numbers: LineNumbers,
+
+	/// Whether long lines soft-wrap, resolved from the `wrap` option by the
+	/// editor like [`line_numbers`](Self::line_numbers).
+	pub wrap: bool,
+
+	/// Prefix of wrapped continuation rows, resolved from the `wrap-indent`,
+	/// `wrap-indent-extra` and `wrap-marker` options by the editor.
+	pub wrap_indent: WrapIndent,
+
 	/// Buffer-local option overrides (set via `:setlocal`).
 	///
 	/// These take precedence over language-specific and global options when
@@ -92,16 +140,26 @@ pub struct Buffer {
 	/// without affecting other buffers sharing the same document.
 	readonly_override: Option<bool>,
 
-	/// Remembered column position for vertical navigation.
+	/// Remembered display columns for vertical navigation, one per range.
 	///
-	/// When moving vertically (j/k, up/down, scroll), the cursor should return
-	/// to this column when reaching lines long enough to accommodate it. This
-	/// prevents the cursor from drifting left when crossing short or empty lines.
+	/// When moving vertically (j/k, up/down, scroll), each cursor returns to
+	/// its column when reaching lines long enough to accommodate it. This
+	/// prevents cursors from drifting left when crossing short or empty lines.
+	/// Columns are display cells within the visual line, so wide graphemes and
+	/// tabs keep cursors visually aligned.
+	///
+	/// Set when vertical motion begins from the current cursor columns. Reset
+	/// when any horizontal or explicit cursor movement occurs (h/l, word
+	/// motions, goto, mouse click, edits, etc.). Ignored if the range count no
+	/// longer matches the selection.
+	goal_columns: Option<Vec<usize>>,
+
+	/// Active rectangular selection, if any.
 	///
-	/// Set when vertical motion begins from current cursor column. Reset when
-	/// any horizontal or explicit cursor movement occurs (h/l, word motions,
-	/// goto, mouse click, etc.).
-	goal_column: Option<usize>,
+	/// While set, vertical motions resize the block instead of moving each
+	/// cursor. Cleared by any other selection change, leaving the generated
+	/// ranges behind as an ordinary multi-selection.
+	block_selection: Option<BlockSelection>,
 }
 
 impl Buffer {
@@ -116,13 +174,19 @@ impl Buffer {
 			input: InputHandler::new(),
 			scroll_line: 0,
 			scroll_segment: 0,
+			left_col: 0,
 			text_width: 80,
 			last_viewport_height: 0,
 			last_rendered_cursor: 0,
 			suppress_scroll_down: false,
+			viewport_detached: false,
+			line_numbers: LineNumbers::default(),
+			wrap: true,
+			wrap_indent: WrapIndent::default(),
 			local_options: OptionStore::new(),
 			readonly_override: None,
-			goal_column: None,
+			goal_columns: None,
+			block_selection: None,
 		}
 	}
 
@@ -147,13 +211,19 @@ impl Buffer {
 			input: InputHandler::new(),
 			scroll_line: self.scroll_line,
 			scroll_segment: self.scroll_segment,
+			left_col: self.left_col,
 			text_width: self.text_width,
 			last_viewport_height: 0,
 			last_rendered_cursor: self.cursor,
 			suppress_scroll_down: false,
+			viewport_detached: false,
+			line_numbers: self.line_numbers,
+			wrap: self.wrap,
+			wrap_indent: self.wrap_indent.clone(),
 			local_options: self.local_options.clone(),
 			readonly_override: None,
-			goal_column: None,
+			goal_columns: None,
+			block_selection: None,
 		}
 	}
 
@@ -201,6 +271,40 @@ impl Buffer {
 		self.document.read().unwrap().modified
 	}
 
+	/// Returns whether the buffer has unsaved changes that should block
+	/// quitting.
+	///
+	/// Changes to a scratch buffer are only guarded once it is marked
+	/// persistent.
+	pub fn needs_save(&self) -> bool {
+		let doc = self.document.read().unwrap();
+		doc.modified
+			&& doc
+				.scratch
+				.as_ref()
+				.is_none_or(|scratch| scratch.persistent)
+	}
+
+	/// Returns the scratch state of the buffer's document.
+	pub fn scratch(&self) -> Option<Scratch> {
+		self.document.read().unwrap().scratch.clone()
+	}
+
+	/// Returns the name shown for the buffer: its path, or `[scratch:name]`
+	/// for a named scratch buffer and `[scratch]` for any other buffer
+	/// without one.
+	pub fn display_name(&self) -> String {
+		let doc = self.document.read().unwrap();
+		match (
+			&doc.path,
+			doc.scratch.as_ref().and_then(|s| s.name.as_deref()),
+		) {
+			(Some(path), _) => path.display().to_string(),
+			(None, Some(name)) => format!("[scratch:{name}]"),
+			(None, None) => "[scratch]".to_string(),
+		}
+	}
+
 	/// Sets the modified flag.
 	pub fn set_modified(&self, modified: bool) {
 		self.document.write().unwrap().modified = modified;
@@ -208,11 +312,11 @@ impl Buffer {
 
 	/// Returns whether this buffer is read-only.
 	///
-	/// Checks the buffer-level override first, then falls back to the
-	/// document's readonly flag.
+	/// A document still loading always is. Otherwise checks the buffer-level
+	/// override first, then falls back to the document's readonly flag.
 	pub fn is_readonly(&self) -> bool {
-		self.readonly_override
-			.unwrap_or_else(|| self.document.read().unwrap().readonly)
+		let doc = self.document.read().unwrap();
+		doc.loading || self.readonly_override.unwrap_or(doc.readonly)
 	}
 
 	/// Sets the read-only flag on the underlying document.
@@ -285,16 +389,21 @@ impl Buffer {
 	/// Computes the gutter width using the registry system.
 	///
 	/// This delegates to [`xeno_registry::gutter::total_width`] which computes
-	/// the combined width of all enabled gutter columns.
+	/// the combined width of the gutter columns shown for this buffer.
 	pub fn gutter_width(&self) -> u16 {
-		use xeno_registry::gutter::{GutterWidthContext, total_width};
-
-		let doc = self.document.read().unwrap();
-		let ctx = GutterWidthContext {
-			total_lines: doc.content.len_lines(),
-			viewport_width: self.text_width as u16 + 100, // approximate
-		};
-		total_width(&ctx)
+		let viewport_width = self.text_width as u16 + 100; // approximate
+		total_width(&self.gutter_width_context(viewport_width))
+	}
+
+	/// Returns the gutter width context of this buffer in a viewport
+	/// `viewport_width` cells wide.
+	pub fn gutter_width_context(&self, viewport_width: u16) -> GutterWidthContext {
+		GutterWidthContext {
+			total_lines: self.document.read().unwrap().content.len_lines(),
+			viewport_width,
+			visible_lines: self.last_viewport_height,
+			line_numbers: self.line_numbers,
+		}
 	}
 
 	/// Reparses the entire syntax tree from scratch.
@@ -305,31 +414,65 @@ impl Buffer {
 			.reparse_syntax(language_loader);
 	}
 
-	/// Returns the undo stack length.
-	pub fn undo_stack_len(&self) -> usize {
-		self.document.read().unwrap().undo_stack.len()
+	/// Returns whether the document has a change to undo.
+	pub fn can_undo(&self) -> bool {
+		self.document.read().unwrap().history.can_undo()
 	}
 
-	/// Returns the redo stack length.
-	pub fn redo_stack_len(&self) -> usize {
-		self.document.read().unwrap().redo_stack.len()
+	/// Returns whether the document has an undone change to redo.
+	pub fn can_redo(&self) -> bool {
+		self.document.read().unwrap().history.can_redo()
 	}
 
-	/// Clears the insert undo grouping flag.
-	pub fn clear_insert_undo_active(&self) {
-		self.doc_mut().insert_undo_active = false;
+	/// Clamps selection and cursor to valid document bounds and snaps them to
+	/// grapheme cluster boundaries.
+	///
+	/// This runs after every applied transaction and content replacement, so
+	/// a selection left stale by an edit never reaches rendering. Any actual
+	/// correction is logged, since it means some edit path did not map the
+	/// selection itself.
+	pub fn ensure_valid_selection(&mut self) {
+		let text = self.doc().content.clone();
+		let before = self.selection.clone();
+		if self.selection.clamp_to(text.slice(..)) {
+			tracing::debug!(
+				buffer = ?self.id,
+				len = text.len_chars(),
+				?before,
+				after = ?self.selection,
+				"snapped selection into document"
+			);
+		}
+		let max_char = text.len_chars();
+		self.cursor = ensure_grapheme_boundary_prev(text.slice(..), self.cursor.min(max_char));
 	}
 
-	/// Clamps selection and cursor to valid document bounds.
-	pub fn ensure_valid_selection(&mut self) {
-		let max_char = self.doc().content.len_chars();
-		self.selection.clamp(max_char);
-		self.cursor = self.cursor.min(max_char);
+	/// Replaces the document content wholesale, outside the undo history, and
+	/// snaps the selection into the new text.
+	///
+	/// Diagnostics, inlay hints, semantic tokens and folds are dropped until
+	/// they are fetched
+	/// for the new text, and any snippet session ends.
+	pub fn replace_content(&mut self, content: Rope) {
+		let mut doc = self.doc_mut();
+		doc.content = content;
+		doc.diagnostics.clear();
+		doc.inlay_hints.clear();
+		doc.semantic_tokens.clear();
+		doc.folds.clear();
+		doc.snippet = None;
+		drop(doc);
+		self.ensure_valid_selection();
 	}
 
 	/// Maps selection and cursor through a [`Transaction`](xeno_base::Transaction).
+	///
+	/// Mapped positions are snapped to grapheme boundaries, since an edit can
+	/// join a cursor's neighbours into a single cluster.
 	pub fn map_selection_through(&mut self, tx: &xeno_base::Transaction) {
-		self.set_selection(tx.map_selection(&self.selection));
+		let mapped = tx.map_selection(&self.selection);
+		let aligned = mapped.grapheme_aligned(self.doc().content.slice(..));
+		self.set_selection(aligned);
 		self.sync_cursor_to_selection();
 	}
 
@@ -376,6 +519,15 @@ impl Buffer {
 			.expect("option type mismatch with registered default")
 	}
 
+	/// Returns the line ending the next save writes.
+	///
+	/// A `line-ending` option of `lf` or `crlf` overrides the ending detected
+	/// when the document was loaded.
+	pub fn line_ending(&self, editor: &crate::editor::Editor) -> LineEnding {
+		let name: String = self.option(keys::LINE_ENDING, editor);
+		LineEnding::from_name(&name).unwrap_or_else(|| self.doc().line_ending)
+	}
+
 	/// Sets cursor position and resets goal column.
 	///
 	/// Use this for horizontal motion, clicks, jumps, edits - any cursor
@@ -383,7 +535,8 @@ impl Buffer {
 	#[inline]
 	pub fn set_cursor(&mut self, pos: CharIdx) {
 		self.cursor = pos;
-		self.goal_column = None;
+		self.goal_columns = None;
+		self.block_selection = None;
 	}
 
 	/// Sets selection and resets goal column.
@@ -393,7 +546,8 @@ impl Buffer {
 	#[inline]
 	pub fn set_selection(&mut self, sel: Selection) {
 		self.selection = sel;
-		self.goal_column = None;
+		self.goal_columns = None;
+		self.block_selection = None;
 	}
 
 	/// Syncs cursor to selection head without resetting goal column.
@@ -413,23 +567,28 @@ impl Buffer {
 	pub fn set_cursor_and_selection(&mut self, pos: CharIdx, sel: Selection) {
 		self.cursor = pos;
 		self.selection = sel;
-		self.goal_column = None;
+		self.goal_columns = None;
+		self.block_selection = None;
+	}
+
+	/// Returns the primary range's remembered goal column, if any.
+	pub fn goal_column(&self) -> Option<usize> {
+		self.goal_columns
+			.as_ref()
+			.and_then(|goals| goals.get(self.selection.primary_index()).copied())
 	}
 
-	/// Establishes goal column from current cursor position.
+	/// Establishes goal columns from the current selection heads.
 	///
 	/// Use after explicit horizontal positioning (mouse click) to set the
-	/// goal column for subsequent vertical navigation.
-	#[inline]
-	pub fn establish_goal_column(&mut self) {
-		let col = {
-			let doc = self.doc();
-			let line = doc
-				.content
-				.char_to_line(self.cursor.min(doc.content.len_chars()));
-			let line_start = doc.content.line_to_char(line);
-			self.cursor.saturating_sub(line_start)
-		};
-		self.goal_column = Some(col);
+	/// goal columns for subsequent vertical navigation.
+	pub fn establish_goal_column(&mut self, tab_width: usize) {
+		let goals = self
+			.selection
+			.ranges()
+			.iter()
+			.map(|range| self.visual_column(range.head, tab_width))
+			.collect();
+		self.goal_columns = Some(goals);
 	}
 }
@@ -1,7 +1,7 @@
 //! Cursor navigation for buffers.
 
 use xeno_base::range::{Direction as MoveDir, Range};
-use xeno_base::{ScrollDirection, Selection};
+use xeno_base::{ScrollDirection, Selection, column};
 
 use super::Buffer;
 use crate::render::wrap::WrapSegment;
@@ -9,10 +9,10 @@ use crate::render::wrap::WrapSegment;
 impl Buffer {
 	/// Moves cursors vertically, accounting for line wrapping.
 	///
-	/// Uses the remembered goal column to restore horizontal position when
-	/// crossing short or empty lines. The goal column is set from the primary
-	/// cursor's position on the first vertical motion, then preserved until
-	/// a horizontal motion resets it.
+	/// Each range remembers its own goal column, measured in display cells
+	/// within its visual line, so crossing short or empty lines does not drift
+	/// the cursor left. Goals are captured on the first vertical motion and
+	/// preserved until a horizontal motion or edit resets them.
 	///
 	/// # Parameters
 	/// - `direction`: Forward (down) or Backward (up)
@@ -30,15 +30,17 @@ impl Buffer {
 		let ranges = self.selection.ranges().to_vec();
 		let primary_index = self.selection.primary_index();
 
-		let goal_col = self.goal_column.unwrap_or_else(|| {
-			let primary = &ranges[primary_index];
-			self.compute_column_in_line(primary.head)
-		});
-		self.goal_column = Some(goal_col);
+		let goals = match self.goal_columns.take() {
+			Some(goals) if goals.len() == ranges.len() => goals,
+			_ => ranges
+				.iter()
+				.map(|range| self.visual_column(range.head, tab_width))
+				.collect(),
+		};
 
 		let mut new_ranges = Vec::with_capacity(ranges.len());
 
-		for range in ranges.iter() {
+		for (range, &goal_col) in ranges.iter().zip(&goals) {
 			let mut pos = range.head;
 			for _ in 0..count {
 				pos = self.visual_move_from(pos, direction, tab_width, goal_col);
@@ -57,20 +59,52 @@ impl Buffer {
 
 		self.selection = Selection::from_vec(new_ranges, primary_index);
 		self.cursor = self.selection.primary().head;
+		self.goal_columns = Some(goals);
 	}
 
-	/// Computes the column position of a cursor within its line.
-	fn compute_column_in_line(&self, cursor: usize) -> usize {
-		let doc = self.doc();
-		let line = doc.content.char_to_line(cursor);
-		let line_start = doc.content.line_to_char(line);
-		cursor.saturating_sub(line_start)
+	/// Computes the display column of `pos` within its visual (wrapped) line.
+	///
+	/// Measured in cells from the start of the row, including a continuation
+	/// row's indent, with wide graphemes and tabs expanded.
+	pub(crate) fn visual_column(&self, pos: usize, tab_width: usize) -> usize {
+		let (line_start, line_text) = {
+			let doc = self.doc();
+			let pos = pos.min(doc.content.len_chars());
+			let line = doc.content.char_to_line(pos);
+			let line_start = doc.content.line_to_char(line);
+			let line_text: String = doc.content.line(line).into();
+			(line_start, line_text)
+		};
+		let line_text = line_text.trim_end_matches('\n');
+		let segments = self.wrap_line(line_text, self.text_width, tab_width);
+		if segments.is_empty() {
+			return 0;
+		}
+
+		let col_in_line = pos.saturating_sub(line_start);
+		let seg = &segments[self.find_segment_for_col(&segments, col_in_line)];
+		seg.indent
+			+ column::char_to_col(
+				&seg.text,
+				col_in_line.saturating_sub(seg.start_offset),
+				tab_width,
+			)
+	}
+
+	/// Returns the line-relative char offset closest to display column
+	/// `goal_col` of the row holding `seg`, clamped to the segment's text.
+	fn segment_offset_for_col(seg: &WrapSegment, goal_col: usize, tab_width: usize) -> usize {
+		let width = column::display_width(&seg.text, tab_width);
+		let col = goal_col
+			.saturating_sub(seg.indent)
+			.min(width.saturating_sub(1));
+		seg.start_offset + column::col_to_char(&seg.text, col, tab_width)
 	}
 
 	/// Computes a new cursor position from visual line movement.
 	///
-	/// Uses `goal_col` (column in original line) to restore horizontal position
-	/// when the target line is long enough.
+	/// Uses `goal_col` (display column within the visual line) to restore
+	/// horizontal position when the target line is long enough.
 	fn visual_move_from(
 		&self,
 		cursor: usize,
@@ -91,27 +125,19 @@ impl Buffer {
 			};
 			let line_text: String = doc.content.slice(line_start..line_end).into();
 
-			let next_line_data = if doc_line + 1 < total_lines {
-				let next_line_start = doc.content.line_to_char(doc_line + 1);
-				let next_line_end = if doc_line + 2 < total_lines {
-					doc.content.line_to_char(doc_line + 2)
-				} else {
-					doc.content.len_chars()
-				};
-				let text: String = doc.content.slice(next_line_start..next_line_end).into();
-				Some((next_line_start, text))
+			// Lines hidden by closed folds are stepped over.
+			let next_line = doc.folds.next_visible_line(doc_line);
+			let next_line_data = if next_line < total_lines {
+				let text: String = doc.content.line(next_line).into();
+				Some((doc.content.line_to_char(next_line), text))
 			} else {
 				None
 			};
 
-			let prev_line_data = if doc_line > 0 {
-				let prev_line = doc_line - 1;
-				let prev_line_start = doc.content.line_to_char(prev_line);
-				let text: String = doc.content.slice(prev_line_start..line_start).into();
-				Some((prev_line_start, text))
-			} else {
-				None
-			};
+			let prev_line_data = doc.folds.prev_visible_line(doc_line).map(|prev_line| {
+				let text: String = doc.content.line(prev_line).into();
+				(doc.content.line_to_char(prev_line), text)
+			});
 
 			(
 				doc_line,
@@ -133,10 +159,7 @@ impl Buffer {
 			MoveDir::Forward => {
 				if current_seg_idx + 1 < segments.len() {
 					let next_seg = &segments[current_seg_idx + 1];
-					let seg_len = next_seg.text.chars().count().saturating_sub(1);
-					let col_in_seg = goal_col.saturating_sub(next_seg.start_offset);
-					let new_col = next_seg.start_offset + col_in_seg.min(seg_len);
-					line_start + new_col
+					line_start + Self::segment_offset_for_col(next_seg, goal_col, tab_width)
 				} else if let Some((next_line_start, next_line_text)) = next_line_data {
 					let next_line_text = next_line_text.trim_end_matches('\n');
 					let next_segments = self.wrap_line(next_line_text, self.text_width, tab_width);
@@ -144,10 +167,8 @@ impl Buffer {
 					if next_segments.is_empty() {
 						next_line_start
 					} else {
-						let first_seg = &next_segments[0];
-						let seg_len = first_seg.text.chars().count().saturating_sub(1).max(0);
-						let new_col = goal_col.min(seg_len);
-						next_line_start + new_col
+						next_line_start
+							+ Self::segment_offset_for_col(&next_segments[0], goal_col, tab_width)
 					}
 				} else {
 					cursor
@@ -156,10 +177,7 @@ impl Buffer {
 			MoveDir::Backward => {
 				if current_seg_idx > 0 {
 					let prev_seg = &segments[current_seg_idx - 1];
-					let seg_len = prev_seg.text.chars().count().saturating_sub(1);
-					let col_in_seg = goal_col.saturating_sub(prev_seg.start_offset);
-					let new_col = prev_seg.start_offset + col_in_seg.min(seg_len);
-					line_start + new_col
+					line_start + Self::segment_offset_for_col(prev_seg, goal_col, tab_width)
 				} else if let Some((prev_line_start, prev_line_text)) = prev_line_data {
 					let prev_line_text = prev_line_text.trim_end_matches('\n');
 					let prev_segments = self.wrap_line(prev_line_text, self.text_width, tab_width);
@@ -168,9 +186,8 @@ impl Buffer {
 						prev_line_start
 					} else {
 						let last_seg = &prev_segments[prev_segments.len() - 1];
-						let seg_len = last_seg.text.chars().count().saturating_sub(1).max(0);
-						let new_col = last_seg.start_offset + goal_col.min(seg_len);
-						prev_line_start + new_col
+						prev_line_start
+							+ Self::segment_offset_for_col(last_seg, goal_col, tab_width)
 					}
 				} else {
 					cursor
@@ -192,6 +209,9 @@ impl Buffer {
 
 	/// Handles mouse scroll events.
 	///
+	/// Scrolls the viewport without moving the cursor; the viewport stays
+	/// detached from the cursor until the cursor next moves.
+	///
 	/// # Parameters
 	/// - `direction`: Scroll direction
 	/// - `count`: Number of lines to scroll
@@ -208,20 +228,34 @@ impl Buffer {
 				for _ in 0..count {
 					self.scroll_viewport_up(tab_width);
 				}
-				self.move_visual_vertical(MoveDir::Backward, count, false, tab_width);
+				self.viewport_detached = true;
 			}
 			ScrollDirection::Down => {
 				for _ in 0..count {
 					self.scroll_viewport_down(tab_width);
 				}
-				self.move_visual_vertical(MoveDir::Forward, count, false, tab_width);
-			}
-			ScrollDirection::Left | ScrollDirection::Right => {
-				// Horizontal scroll not implemented yet
+				self.viewport_detached = true;
 			}
+			ScrollDirection::Left => self.scroll_viewport_horizontal(MoveDir::Backward, count),
+			ScrollDirection::Right => self.scroll_viewport_horizontal(MoveDir::Forward, count),
 		}
 	}
 
+	/// Scrolls an unwrapped viewport `count` columns left or right, leaving
+	/// the cursor where it is until it next moves.
+	///
+	/// Does nothing while lines are wrapped.
+	pub fn scroll_viewport_horizontal(&mut self, direction: MoveDir, count: usize) {
+		if self.wrap {
+			return;
+		}
+		self.left_col = match direction {
+			MoveDir::Forward => self.left_col.saturating_add(count),
+			MoveDir::Backward => self.left_col.saturating_sub(count),
+		};
+		self.viewport_detached = true;
+	}
+
 	/// Scrolls viewport up by one visual line.
 	///
 	/// # Parameters
@@ -229,8 +263,8 @@ impl Buffer {
 	pub fn scroll_viewport_up(&mut self, tab_width: usize) {
 		if self.scroll_segment > 0 {
 			self.scroll_segment -= 1;
-		} else if self.scroll_line > 0 {
-			self.scroll_line -= 1;
+		} else if let Some(prev_line) = self.prev_visible_line(self.scroll_line) {
+			self.scroll_line = prev_line;
 			let (line_text, num_segments) = {
 				let doc = self.doc();
 				let line_start = doc.content.line_to_char(self.scroll_line);
@@ -274,15 +308,40 @@ impl Buffer {
 		};
 
 		if self.scroll_line < total_lines {
+			let next_line = self.next_visible_line(self.scroll_line);
 			if self.scroll_segment + 1 < num_segments {
 				self.scroll_segment += 1;
-			} else if self.scroll_line + 1 < total_lines {
-				self.scroll_line += 1;
+			} else if next_line < total_lines {
+				self.scroll_line = next_line;
 				self.scroll_segment = 0;
 			}
 		}
 	}
 
+	/// Scrolls the viewport so the primary cursor sits `row` visual rows
+	/// below its top, or as close as the start of the buffer allows.
+	///
+	/// Rows are counted in wrap segments, so a wrapped cursor line is placed
+	/// by the row holding the cursor.
+	pub fn scroll_cursor_to_row(&mut self, row: usize, tab_width: usize) {
+		self.ensure_valid_selection();
+		let (line, line_text, col) = {
+			let doc = self.doc();
+			let cursor = self.cursor.min(doc.content.len_chars());
+			let line = doc.content.char_to_line(cursor);
+			let line_text: String = doc.content.line(line).into();
+			(line, line_text, cursor - doc.content.line_to_char(line))
+		};
+		let segments = self.wrap_line(line_text.trim_end_matches('\n'), self.text_width, tab_width);
+
+		self.scroll_line = line;
+		self.scroll_segment = self.find_segment_for_col(&segments, col);
+		for _ in 0..row {
+			self.scroll_viewport_up(tab_width);
+		}
+		self.viewport_detached = false;
+	}
+
 	/// Converts screen coordinates to document position.
 	///
 	/// # Parameters
@@ -301,7 +360,7 @@ impl Buffer {
 			return None;
 		}
 
-		let text_col = (screen_col - gutter_width) as usize;
+		let text_col = (screen_col - gutter_width) as usize + self.left_col;
 		let mut visual_row = 0;
 		let mut line_idx = self.scroll_line;
 		let mut start_segment = self.scroll_segment;
@@ -333,53 +392,94 @@ impl Buffer {
 							return Some(line_start + segment.start_offset);
 						}
 
-						let mut col = 0usize;
-						let mut last_char_offset = 0usize;
-						for (i, ch) in segment.text.chars().enumerate() {
-							last_char_offset = i;
-							let mut w = if ch == '\t' {
-								tab_width.saturating_sub(col % tab_width)
-							} else {
-								1
-							};
-							if w == 0 {
-								w = 1;
-							}
-
-							let remaining = self.text_width.saturating_sub(col);
-							if remaining == 0 {
-								break;
-							}
-							if w > remaining {
-								w = remaining;
-							}
-
-							if text_col < col + w {
-								return Some(line_start + segment.start_offset + i);
-							}
-							col += w;
-						}
-						return Some(line_start + segment.start_offset + last_char_offset);
+						return Some(
+							line_start + Self::segment_offset_for_col(segment, text_col, tab_width),
+						);
 					}
 					visual_row += 1;
 				}
 			}
 
 			start_segment = 0;
-			line_idx += 1;
+			line_idx = doc.folds.next_visible_line(line_idx);
 		}
 
 		Some(doc.content.len_chars().saturating_sub(1).max(0))
 	}
 
+	/// Returns the visible line after the one showing `line`, stepping over
+	/// the lines closed folds hide.
+	pub fn next_visible_line(&self, line: usize) -> usize {
+		self.doc().folds.next_visible_line(line)
+	}
+
+	/// Returns the visible line before the one showing `line`, or `None` at
+	/// the top of the buffer.
+	pub fn prev_visible_line(&self, line: usize) -> Option<usize> {
+		self.doc().folds.prev_visible_line(line)
+	}
+
+	/// Moves every cursor out of the lines closed folds hide: with `reveal`
+	/// the folds open, otherwise the cursor goes to the fold's first line.
+	pub fn keep_cursors_out_of_folds(&mut self, reveal: bool) {
+		let hidden: Vec<usize> = {
+			let doc = self.doc();
+			if !doc.folds.any_closed() {
+				return;
+			}
+			let len = doc.content.len_chars();
+			self.selection
+				.iter()
+				.map(|range| doc.content.char_to_line(range.head.min(len)))
+				.filter(|&line| doc.folds.is_hidden(line))
+				.collect()
+		};
+		if hidden.is_empty() {
+			return;
+		}
+		if reveal {
+			let mut doc = self.doc_mut();
+			for line in hidden {
+				doc.folds.reveal(line);
+			}
+			return;
+		}
+
+		let ranges = {
+			let doc = self.doc();
+			let len = doc.content.len_chars();
+			self.selection
+				.iter()
+				.map(|range| {
+					let line = doc.content.char_to_line(range.head.min(len));
+					if doc.folds.is_hidden(line) {
+						Range::point(doc.content.line_to_char(doc.folds.visible_line(line)))
+					} else {
+						*range
+					}
+				})
+				.collect()
+		};
+		let primary_index = self.selection.primary_index();
+		self.set_selection(Selection::from_vec(ranges, primary_index));
+		self.cursor = self.selection.primary().head;
+	}
+
 	/// Wraps a line of text into segments.
 	///
+	/// While [`wrap`](Self::wrap) is off, a line is a single segment;
+	/// otherwise continuation rows are narrowed by
+	/// [`wrap_indent`](Self::wrap_indent).
+	///
 	/// # Parameters
 	/// - `text`: The text to wrap
 	/// - `width`: Maximum width in characters for each segment
 	/// - `tab_width`: Number of spaces a tab character occupies
 	pub fn wrap_line(&self, text: &str, width: usize, tab_width: usize) -> Vec<WrapSegment> {
-		crate::render::wrap_line(text, width, tab_width)
+		if !self.wrap {
+			return crate::render::wrap_line(text, usize::MAX, tab_width);
+		}
+		crate::render::wrap_line_indented(text, width, tab_width, &self.wrap_indent)
 	}
 }
 
@@ -403,17 +503,34 @@ mod tests {
 		// Move through empty line - snaps to col 0 but goal preserved
 		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
 		assert_eq!(buffer.cursor, 20);
-		assert_eq!(buffer.goal_column, Some(10));
+		assert_eq!(buffer.goal_column(), Some(10));
 
 		// Move to "short" - clamps to end but goal preserved
 		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
 		assert_eq!(buffer.cursor, 25);
-		assert_eq!(buffer.goal_column, Some(10));
+		assert_eq!(buffer.goal_column(), Some(10));
 
 		// Move to long line - restores to col 10
 		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
 		assert_eq!(buffer.cursor, 37);
-		assert_eq!(buffer.goal_column, Some(10));
+		assert_eq!(buffer.goal_column(), Some(10));
+	}
+
+	#[test]
+	fn mouse_scroll_leaves_cursor_in_place() {
+		let mut buffer = make_buffer("one\ntwo\nthree\nfour\nfive");
+		buffer.text_width = 80;
+		buffer.cursor = 1;
+		buffer.selection = xeno_base::Selection::point(1);
+
+		buffer.handle_mouse_scroll(ScrollDirection::Down, 2, 4);
+		assert_eq!(buffer.scroll_line, 2);
+		assert_eq!(buffer.cursor, 1);
+		assert!(buffer.viewport_detached);
+
+		buffer.handle_mouse_scroll(ScrollDirection::Up, 1, 4);
+		assert_eq!(buffer.scroll_line, 1);
+		assert_eq!(buffer.cursor, 1);
 	}
 
 	#[test]
@@ -424,10 +541,10 @@ mod tests {
 		buffer.selection = xeno_base::Selection::point(5);
 
 		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
-		assert_eq!(buffer.goal_column, Some(5));
+		assert_eq!(buffer.goal_column(), Some(5));
 
 		buffer.set_cursor(12);
-		assert_eq!(buffer.goal_column, None);
+		assert_eq!(buffer.goal_column(), None);
 	}
 
 	#[test]
@@ -437,11 +554,11 @@ mod tests {
 		buffer.text_width = 80;
 		buffer.cursor = 8;
 		buffer.selection = xeno_base::Selection::point(8);
-		assert_eq!(buffer.goal_column, None);
+		assert_eq!(buffer.goal_column(), None);
 
 		// First vertical move sets goal from current col
 		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
-		assert_eq!(buffer.goal_column, Some(8));
+		assert_eq!(buffer.goal_column(), Some(8));
 		assert_eq!(buffer.cursor, 13); // end of "hi"
 
 		// Restore to col 8 on longer line
@@ -459,14 +576,89 @@ mod tests {
 
 		buffer.move_visual_vertical(MoveDir::Backward, 1, false, 4);
 		assert_eq!(buffer.cursor, 29); // empty line
-		assert_eq!(buffer.goal_column, Some(15));
+		assert_eq!(buffer.goal_column(), Some(15));
 
 		buffer.move_visual_vertical(MoveDir::Backward, 1, false, 4);
 		assert_eq!(buffer.cursor, 27); // end of "short"
-		assert_eq!(buffer.goal_column, Some(15));
+		assert_eq!(buffer.goal_column(), Some(15));
 
 		buffer.move_visual_vertical(MoveDir::Backward, 1, false, 4);
 		assert_eq!(buffer.cursor, 15); // restored to col 15
-		assert_eq!(buffer.goal_column, Some(15));
+		assert_eq!(buffer.goal_column(), Some(15));
+	}
+
+	#[test]
+	fn goal_column_measured_in_display_cells() {
+		// Lines: "\tab" / "漢字ab" / "abcdefgh"
+		let mut buffer = make_buffer("\tab\n漢字ab\nabcdefgh");
+		buffer.text_width = 80;
+		buffer.cursor = 1; // 'a' after tab, display col 4
+		buffer.selection = xeno_base::Selection::point(1);
+
+		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
+		assert_eq!(buffer.goal_column(), Some(4));
+		assert_eq!(buffer.cursor, 6); // 'a' after two wide chars
+
+		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
+		assert_eq!(buffer.cursor, 13); // col 4 on "abcdefgh"
+	}
+
+	#[test]
+	fn goal_column_kept_per_range() {
+		// Lines: "abcdef" / "abc" / "abcdef"
+		let mut buffer = make_buffer("abcdef\nabc\nabcdef");
+		buffer.text_width = 80;
+		buffer.selection =
+			xeno_base::Selection::from_vec(vec![Range::point(0), Range::point(5)], 0);
+		buffer.cursor = 0;
+
+		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
+		let heads: Vec<_> = buffer.selection.ranges().iter().map(|r| r.head).collect();
+		assert_eq!(heads, vec![7, 9]);
+
+		buffer.move_visual_vertical(MoveDir::Forward, 1, false, 4);
+		let heads: Vec<_> = buffer.selection.ranges().iter().map(|r| r.head).collect();
+		assert_eq!(heads, vec![11, 16]);
+	}
+
+	#[test]
+	fn wrap_indent_offsets_columns() {
+		let mut buffer = make_buffer("  aaaa bbbb cccc");
+		buffer.text_width = 10;
+		buffer.wrap_indent = crate::render::WrapIndent {
+			preserve: true,
+			extra: 0,
+			marker: "↪ ".to_string(),
+		};
+		buffer.cursor = 9; // second 'b', drawn at column 6 of row 1
+		buffer.selection = xeno_base::Selection::point(9);
+		assert_eq!(buffer.visual_column(9, 4), 6);
+
+		buffer.move_visual_vertical(MoveDir::Backward, 1, false, 4);
+		assert_eq!(buffer.cursor, 6); // column 6 on the first row
+
+		let gutter = buffer.gutter_width();
+		assert_eq!(buffer.screen_to_doc_position(1, gutter + 5, 4), Some(8));
+		assert_eq!(buffer.screen_to_doc_position(1, gutter, 4), Some(7));
+	}
+
+	#[test]
+	fn scroll_cursor_to_row_counts_wrapped_rows() {
+		// Line 1 wraps into two rows at width 10.
+		let mut buffer = make_buffer("zero\naaaa bbbb cccc\ntwo\nthree");
+		buffer.text_width = 10;
+		buffer.set_cursor(22); // "two"
+
+		buffer.scroll_cursor_to_row(0, 4);
+		assert_eq!((buffer.scroll_line, buffer.scroll_segment), (2, 0));
+
+		buffer.scroll_cursor_to_row(2, 4);
+		assert_eq!((buffer.scroll_line, buffer.scroll_segment), (1, 0));
+
+		buffer.scroll_cursor_to_row(1, 4);
+		assert_eq!((buffer.scroll_line, buffer.scroll_segment), (1, 1));
+
+		buffer.scroll_cursor_to_row(10, 4);
+		assert_eq!((buffer.scroll_line, buffer.scroll_segment), (0, 0));
 	}
 }
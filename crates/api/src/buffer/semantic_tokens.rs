@@ -0,0 +1,260 @@
+//! Semantic tokens prepared for display, following document edits.
+//!
+//! A language server's semantic tokens classify the identifiers, keywords
+//! and literals of a whole document. Each token is decoded to a character
+//! range and the theme scope its type and modifiers map to, so the theme is
+//! only consulted when drawing. Like [inlay hints](super::PreparedInlayHints)
+//! the tokens are mapped through every edit until they are requested again:
+//! text typed inside a token widens it, text typed at its edges is left out
+//! of it, and a token whose text is deleted goes with it.
+
+use xeno_base::transaction::Bias;
+use xeno_base::{Rope, Transaction};
+
+/// A semantic token converted to characters of its document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+	/// First character of the token.
+	pub start: usize,
+	/// Character just past the token.
+	pub end: usize,
+	/// Theme scope the token is drawn with.
+	pub scope: &'static str,
+}
+
+/// Semantic tokens of a document, in the positions of its current content.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticTokens {
+	/// Non-overlapping tokens ordered by position.
+	items: Vec<SemanticToken>,
+}
+
+impl SemanticTokens {
+	/// Decodes the relative token stream of an LSP response for `text`,
+	/// with positions in `encoding`.
+	///
+	/// Tokens whose type the theme has no scope for are left out.
+	#[cfg(feature = "lsp")]
+	pub fn from_lsp(
+		data: &[xeno_lsp::lsp_types::SemanticToken],
+		legend: &xeno_lsp::lsp_types::SemanticTokensLegend,
+		text: &Rope,
+		encoding: xeno_lsp::OffsetEncoding,
+	) -> Self {
+		let mut items = Vec::with_capacity(data.len());
+		let (mut line, mut column) = (0usize, 0u32);
+		for token in data {
+			if token.delta_line > 0 {
+				line += token.delta_line as usize;
+				column = 0;
+			}
+			column += token.delta_start;
+			if line >= text.len_lines() {
+				break;
+			}
+			let Some(token_type) = legend.token_types.get(token.token_type as usize) else {
+				continue;
+			};
+			let modifiers: Vec<&str> = legend
+				.token_modifiers
+				.iter()
+				.enumerate()
+				.filter(|(bit, _)| *bit < 32 && token.token_modifiers_bitset & (1 << bit) != 0)
+				.map(|(_, modifier)| modifier.as_str())
+				.collect();
+			let Some(scope) = semantic_token_scope(token_type.as_str(), &modifiers) else {
+				continue;
+			};
+			let slice = text.line(line);
+			let line_start = text.line_to_char(line);
+			let start = xeno_lsp::lsp_column_to_char(slice, column, encoding);
+			let end = xeno_lsp::lsp_column_to_char(slice, column + token.length, encoding);
+			if start < end {
+				items.push(SemanticToken {
+					start: line_start + start,
+					end: line_start + end,
+					scope,
+				});
+			}
+		}
+		let mut tokens = Self::default();
+		tokens.replace(items);
+		tokens
+	}
+
+	/// Iterates over the tokens in position order.
+	pub fn iter(&self) -> impl Iterator<Item = &SemanticToken> {
+		self.items.iter()
+	}
+
+	/// Returns whether there are no tokens.
+	pub fn is_empty(&self) -> bool {
+		self.items.is_empty()
+	}
+
+	/// Returns the tokens overlapping the characters `from..to`.
+	pub fn overlapping(&self, from: usize, to: usize) -> &[SemanticToken] {
+		let start = self.items.partition_point(|token| token.end <= from);
+		let end = start + self.items[start..].partition_point(|token| token.start < to);
+		&self.items[start..end]
+	}
+
+	/// Replaces the tokens with freshly decoded ones, dropping any that
+	/// overlap the token before them.
+	pub fn replace(&mut self, mut items: Vec<SemanticToken>) {
+		items.sort_by_key(|token| token.start);
+		items.dedup_by(|next, prev| next.start < prev.end);
+		self.items = items;
+	}
+
+	/// Maps every token through an applied transaction.
+	pub fn map_through(&mut self, tx: &Transaction) {
+		if self.items.is_empty() {
+			return;
+		}
+		let changes = tx.changes();
+		for token in &mut self.items {
+			token.start = changes.map_pos(token.start, Bias::Right);
+			token.end = changes.map_pos(token.end, Bias::Left);
+		}
+		self.items.retain(|token| token.start < token.end);
+	}
+
+	/// Drops all tokens until they are requested again.
+	pub fn clear(&mut self) {
+		self.items.clear();
+	}
+}
+
+/// Returns the theme scope for a semantic token of `token_type` with
+/// `modifiers`, or `None` if there is none to draw it with.
+///
+/// Covers the standard LSP token types along with common extensions such
+/// as rust-analyzer's.
+pub fn semantic_token_scope(token_type: &str, modifiers: &[&str]) -> Option<&'static str> {
+	let builtin = modifiers.contains(&"defaultLibrary");
+	Some(match token_type {
+		"namespace" => "namespace",
+		"type" | "class" | "struct" | "interface" | "enum" | "union" | "typeAlias" if builtin => {
+			"type.builtin"
+		}
+		"type" | "class" | "struct" | "interface" | "enum" | "union" | "typeAlias" => "type",
+		"builtinType" => "type.builtin",
+		"typeParameter" => "type.parameter",
+		"enumMember" => "type.enum.variant",
+		"parameter" => "variable.parameter",
+		"variable" if builtin => "variable.builtin",
+		"variable" => "variable",
+		"selfKeyword" | "selfTypeKeyword" => "variable.builtin",
+		"property" | "event" => "variable.other.member",
+		"function" if builtin => "function.builtin",
+		"function" => "function",
+		"method" => "function.method",
+		"macro" => "function.macro",
+		"decorator" | "attribute" | "builtinAttribute" | "derive" => "attribute",
+		"keyword" => "keyword",
+		"modifier" => "keyword.storage.modifier",
+		"comment" if modifiers.contains(&"documentation") => "comment.block.documentation",
+		"comment" => "comment",
+		"string" => "string",
+		"regexp" => "string.regexp",
+		"number" => "constant.numeric",
+		"boolean" => "constant.builtin.boolean",
+		"character" => "constant.character",
+		"escapeSequence" => "constant.character.escape",
+		"operator" => "operator",
+		"label" | "lifetime" => "label",
+		"punctuation" => "punctuation",
+		"brace" | "bracket" | "parenthesis" => "punctuation.bracket",
+		"formatSpecifier" => "punctuation.special",
+		_ => return None,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	fn token(start: usize, end: usize) -> SemanticToken {
+		SemanticToken {
+			start,
+			end,
+			scope: "variable",
+		}
+	}
+
+	#[test]
+	fn tokens_widen_inside_and_not_at_their_edges() {
+		let doc = Rope::from("let ab = cd;");
+		let mut tokens = SemanticTokens::default();
+		tokens.replace(vec![token(9, 11), token(4, 6)]);
+
+		// Type inside the first token, before the second, and delete the
+		// `=` between them.
+		let tx = Transaction::change(
+			doc.slice(..),
+			[
+				Change {
+					start: 5,
+					end: 5,
+					replacement: Some("x".into()),
+				},
+				Change {
+					start: 7,
+					end: 8,
+					replacement: None,
+				},
+				Change {
+					start: 9,
+					end: 9,
+					replacement: Some("y".into()),
+				},
+			],
+		);
+		tokens.map_through(&tx);
+		let ranges: Vec<_> = tokens.iter().map(|t| (t.start, t.end)).collect();
+		assert_eq!(ranges, [(4, 7), (10, 12)]);
+		assert_eq!(tokens.overlapping(7, 11).len(), 1);
+		assert!(tokens.overlapping(7, 10).is_empty());
+	}
+
+	#[test]
+	fn deleted_tokens_are_dropped() {
+		let doc = Rope::from("a bc d");
+		let mut tokens = SemanticTokens::default();
+		tokens.replace(vec![token(0, 1), token(2, 4), token(5, 6)]);
+		let tx = Transaction::change(
+			doc.slice(..),
+			[Change {
+				start: 1,
+				end: 5,
+				replacement: None,
+			}],
+		);
+		tokens.map_through(&tx);
+		assert_eq!(tokens.iter().count(), 2);
+	}
+
+	#[test]
+	fn scopes_follow_type_and_modifiers() {
+		assert_eq!(semantic_token_scope("struct", &[]), Some("type"));
+		assert_eq!(
+			semantic_token_scope("function", &["defaultLibrary"]),
+			Some("function.builtin")
+		);
+		assert_eq!(
+			semantic_token_scope("comment", &["documentation"]),
+			Some("comment.block.documentation")
+		);
+		assert_eq!(semantic_token_scope("unknownThing", &[]), None);
+
+		// Every scope has to be one the renderer can look up.
+		let names = xeno_registry::themes::SyntaxStyles::scope_names();
+		for token_type in ["enumMember", "lifetime", "parenthesis", "escapeSequence"] {
+			let scope = semantic_token_scope(token_type, &[]).unwrap();
+			assert!(names.contains(&scope), "{scope}");
+		}
+	}
+}
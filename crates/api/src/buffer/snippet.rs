@@ -0,0 +1,158 @@
+//! Snippet sessions: the tabstops of an expanded snippet that Tab and
+//! Shift-Tab move between.
+//!
+//! A session lives on the [`Document`](super::Document) it was expanded
+//! into, so every edit maps its tabstops through the same changeset that
+//! changed the text; typing into a placeholder grows it. Undo and redo end
+//! the session, as does content replaced outside the history.
+
+use std::ops::Range;
+
+use xeno_base::direction::SeqDirection;
+use xeno_base::range::CharIdx;
+use xeno_base::transaction::Bias;
+use xeno_base::{Selection, Transaction};
+use xeno_core::SnippetTabstop;
+
+/// Tabstops of an expanded snippet, in document positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetSession {
+	/// Ranges of each tabstop's occurrences, in visiting order; the last is
+	/// the final cursor position.
+	tabstops: Vec<Vec<Range<CharIdx>>>,
+	/// Index of the tabstop the cursor is at.
+	current: usize,
+}
+
+impl SnippetSession {
+	/// Starts a session at the first tabstop of a snippet rendered at
+	/// `offset`, or returns `None` if it has none besides the final one.
+	pub fn new(tabstops: &[SnippetTabstop], offset: CharIdx) -> Option<Self> {
+		let tabstops: Vec<Vec<Range<CharIdx>>> = tabstops
+			.iter()
+			.map(|tabstop| {
+				tabstop
+					.ranges
+					.iter()
+					.map(|r| r.start + offset..r.end + offset)
+					.collect()
+			})
+			.collect();
+		(tabstops.len() > 1).then_some(Self {
+			tabstops,
+			current: 0,
+		})
+	}
+
+	/// Returns the ranges of the current tabstop's occurrences.
+	pub fn current(&self) -> &[Range<CharIdx>] {
+		&self.tabstops[self.current]
+	}
+
+	/// Returns whether the cursor is at the final tabstop, which ends the
+	/// session.
+	pub fn at_end(&self) -> bool {
+		self.current + 1 == self.tabstops.len()
+	}
+
+	/// Moves to the next or previous tabstop, returning whether there was
+	/// one to move to.
+	pub fn step(&mut self, direction: SeqDirection) -> bool {
+		let next = match direction {
+			SeqDirection::Next => self.current + 1,
+			SeqDirection::Prev => match self.current.checked_sub(1) {
+				Some(prev) => prev,
+				None => return false,
+			},
+		};
+		if next >= self.tabstops.len() {
+			return false;
+		}
+		self.current = next;
+		true
+	}
+
+	/// Returns a selection of the current tabstop: its default text
+	/// selected at each occurrence, or a cursor where it has none.
+	pub fn selection(&self) -> Selection {
+		let ranges = self
+			.current()
+			.iter()
+			.map(|r| xeno_base::Range::new(r.start, r.end))
+			.collect();
+		Selection::from_vec(ranges, 0)
+	}
+
+	/// Returns whether `selection` selects the default text of the current
+	/// tabstop, which typing replaces.
+	pub fn selects_placeholder(&self, selection: &Selection) -> bool {
+		let current = self.current();
+		current.iter().any(|r| !r.is_empty())
+			&& selection.len() == current.len()
+			&& selection
+				.iter()
+				.zip(current)
+				.all(|(range, tabstop)| range.from() == tabstop.start && range.to() == tabstop.end)
+	}
+
+	/// Maps every tabstop through an applied transaction.
+	pub fn map_through(&mut self, tx: &Transaction) {
+		let changes = tx.changes();
+		for range in self.tabstops.iter_mut().flatten() {
+			let start = changes.map_pos(range.start, Bias::Left);
+			let end = changes.map_pos(range.end, Bias::Right);
+			*range = start..end.max(start);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Rope;
+	use xeno_base::transaction::Change;
+
+	use super::*;
+
+	#[test]
+	fn typing_into_placeholder_grows_it() {
+		let tabstops = [
+			SnippetTabstop {
+				index: 1,
+				ranges: vec![3..7, 10..14],
+			},
+			SnippetTabstop {
+				index: 0,
+				ranges: vec![15..15],
+			},
+		];
+		let mut session = SnippetSession::new(&tabstops, 2).unwrap();
+		assert_eq!(session.current(), [5..9, 12..16]);
+		assert!(session.selects_placeholder(&session.selection()));
+
+		let doc = Rope::from("xxfn name = name;");
+		let tx = Transaction::change(
+			doc.slice(..),
+			[
+				Change {
+					start: 5,
+					end: 9,
+					replacement: Some("id".into()),
+				},
+				Change {
+					start: 12,
+					end: 16,
+					replacement: Some("id".into()),
+				},
+			],
+		);
+		session.map_through(&tx);
+		assert_eq!(session.current(), [5..7, 10..12]);
+
+		assert!(session.step(SeqDirection::Next));
+		assert!(session.at_end());
+		assert_eq!(session.current(), [13..13]);
+		assert!(!session.step(SeqDirection::Next));
+		assert!(session.step(SeqDirection::Prev));
+		assert!(!session.step(SeqDirection::Prev));
+	}
+}
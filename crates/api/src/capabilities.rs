@@ -8,16 +8,21 @@ use std::pin::Pin;
 
 use xeno_base::direction::{Axis, SeqDirection, SpatialDirection};
 use xeno_base::range::{CharIdx, Direction as MoveDir};
-use xeno_base::{Mode, Selection};
+use xeno_base::transaction::Operation;
+use xeno_base::{Mode, PendingKind, Selection};
 use xeno_core::editor_ctx::{
 	CommandQueueAccess, CursorAccess, EditAccess, EditorCapabilities, FileOpsAccess, FocusOps,
 	JumpAccess, MacroAccess, ModeAccess, NotificationAccess, OptionAccess, PaletteAccess,
-	SearchAccess, SelectionAccess, SplitOps, ThemeAccess, UndoAccess, ViewportAccess,
+	SearchAccess, SelectionAccess, SplitOps, ThemeAccess, UndoAccess, UndoNodeInfo, ViewportAccess,
 };
-use xeno_registry::commands::{CommandEditorOps, CommandError};
-use xeno_registry::options::{OptionKey, OptionScope, OptionValue, find_by_kdl, parse};
-use xeno_registry::{HookContext, HookEventData, emit_sync_with as emit_hook_sync_with};
-use xeno_registry_notifications::{Notification, keys};
+use xeno_registry::commands::{CommandEditorOps, CommandError, HistoryJump};
+use xeno_registry::options::{
+	OptionKey, OptionScope, OptionValue, find_by_kdl, keys as opts, parse,
+};
+use xeno_registry::{
+	HookContext, HookEventData, ScreenPosition, ScrollAmount, emit_sync_with as emit_hook_sync_with,
+};
+use xeno_registry_notifications::Notification;
 
 use crate::editor::Editor;
 
@@ -51,6 +56,17 @@ fn parse_option_value(kdl_key: &str, value: &str) -> Result<OptionValue, Command
 	})
 }
 
+/// Returns whether `kdl_key` names an option that changes how lines wrap.
+fn is_wrap_option(kdl_key: &str) -> bool {
+	[
+		opts::WRAP.def().kdl_key,
+		opts::WRAP_INDENT.def().kdl_key,
+		opts::WRAP_INDENT_EXTRA.def().kdl_key,
+		opts::WRAP_MARKER.def().kdl_key,
+	]
+	.contains(&kdl_key)
+}
+
 impl CursorAccess for Editor {
 	fn cursor(&self) -> CharIdx {
 		self.buffer().cursor
@@ -86,12 +102,21 @@ impl ModeAccess for Editor {
 	}
 
 	fn set_mode(&mut self, mode: Mode) {
-		if matches!(mode, Mode::Insert) && self.buffer().is_readonly() {
-			NotificationAccess::emit(self, keys::buffer_readonly.into());
+		if matches!(mode, Mode::Insert) && !self.guard_readonly() {
 			return;
 		}
+		// Pending actions return to the current mode, so an insert session
+		// keeps its undo group across e.g. `ctrl-r`.
+		if self.buffer().input.mode() != mode && !matches!(mode, Mode::PendingAction(_)) {
+			self.end_undo_group();
+		}
 		self.buffer_mut().input.set_mode(mode);
 	}
+
+	fn set_pending(&mut self, kind: PendingKind, prompt: &str) {
+		self.buffer_mut().input.set_pending(kind, prompt);
+		self.frame.needs_redraw = true;
+	}
 }
 
 impl NotificationAccess for Editor {
@@ -112,6 +137,10 @@ impl SearchAccess for Editor {
 		}
 	}
 
+	fn open_prompt(&mut self, reverse: bool) {
+		self.open_search_prompt(reverse);
+	}
+
 	fn use_selection_as_pattern(&mut self) -> bool {
 		self.do_use_selection_as_search()
 	}
@@ -125,6 +154,10 @@ impl SearchAccess for Editor {
 			.input
 			.set_last_search(pattern.to_string(), false);
 	}
+
+	fn clear_highlight(&mut self) {
+		self.clear_search_highlight();
+	}
 }
 
 impl UndoAccess for Editor {
@@ -140,12 +173,52 @@ impl UndoAccess for Editor {
 		self.redo();
 	}
 
+	fn begin_undo_group(&mut self) {
+		self.begin_undo_group();
+	}
+
+	fn end_undo_group(&mut self) {
+		self.end_undo_group();
+	}
+
 	fn can_undo(&self) -> bool {
-		self.buffer().undo_stack_len() > 0
+		self.buffer().can_undo()
 	}
 
 	fn can_redo(&self) -> bool {
-		self.buffer().redo_stack_len() > 0
+		self.buffer().can_redo()
+	}
+
+	fn undo_tree(&self) -> Vec<UndoNodeInfo> {
+		let doc = self.buffer().doc();
+		let current = doc.history.current();
+		doc.history
+			.nodes()
+			.iter()
+			.enumerate()
+			.map(|(id, node)| {
+				let (mut added, mut removed) = (0, 0);
+				for op in node.redo.operations() {
+					match op {
+						Operation::Insert(insertion) => added += insertion.char_len,
+						Operation::Delete(len) => removed += len,
+						Operation::Retain(_) => {}
+					}
+				}
+				UndoNodeInfo {
+					id,
+					parent: node.parent,
+					timestamp: node.timestamp,
+					current: id == current,
+					added,
+					removed,
+				}
+			})
+			.collect()
+	}
+
+	fn goto_undo_node(&mut self, id: usize) {
+		self.goto_undo_node(id);
 	}
 }
 
@@ -158,6 +231,24 @@ impl EditAccess for Editor {
 		self.move_visual_vertical(direction, count, extend);
 	}
 
+	fn scroll(&mut self, direction: MoveDir, amount: ScrollAmount, extend: bool) {
+		self.scroll_view(direction, amount, extend);
+	}
+
+	fn align_view(&mut self, position: ScreenPosition, count: usize) {
+		self.align_view(position, count);
+	}
+
+	fn scroll_horizontal(&mut self, direction: MoveDir, count: usize) {
+		self.buffer_mut()
+			.scroll_viewport_horizontal(direction, count);
+		self.frame.needs_redraw = true;
+	}
+
+	fn toggle_block_selection(&mut self) {
+		self.toggle_block_selection();
+	}
+
 	fn paste(&mut self, before: bool) {
 		if before {
 			self.paste_before();
@@ -183,7 +274,32 @@ impl CommandEditorOps for Editor {
 	}
 
 	fn is_modified(&self) -> bool {
-		FileOpsAccess::is_modified(self)
+		self.buffer().needs_save()
+	}
+
+	fn confirm_quit(&mut self) {
+		use crate::prompt::{ConfirmPopup, ConfirmResult};
+
+		let name = self
+			.buffer()
+			.path()
+			.map_or_else(|| "[scratch]".to_string(), |p| p.display().to_string());
+		let popup = ConfirmPopup::new(
+			"Unsaved changes",
+			format!("{name} has unsaved changes."),
+			|editor, result| match result {
+				ConfirmResult::Chosen('s') => editor.workspace.command_queue.push("wq", Vec::new()),
+				ConfirmResult::Chosen('d') => editor
+					.workspace
+					.command_queue
+					.push("quit_force", Vec::new()),
+				_ => {}
+			},
+		)
+		.choice('s', "save and quit")
+		.choice('d', "discard and quit")
+		.choice('c', "cancel");
+		self.open_confirm(popup);
 	}
 
 	fn is_readonly(&self) -> bool {
@@ -205,6 +321,14 @@ impl CommandEditorOps for Editor {
 		FileOpsAccess::save_as(self, path)
 	}
 
+	fn earlier(&mut self, jump: HistoryJump) {
+		self.travel_history(SeqDirection::Prev, jump);
+	}
+
+	fn later(&mut self, jump: HistoryJump) {
+		self.travel_history(SeqDirection::Next, jump);
+	}
+
 	fn set_theme(&mut self, name: &str) -> Result<(), CommandError> {
 		ThemeAccess::set_theme(self, name)
 	}
@@ -212,6 +336,15 @@ impl CommandEditorOps for Editor {
 	fn set_option(&mut self, kdl_key: &str, value: &str) -> Result<(), CommandError> {
 		let opt_value = parse_option_value(kdl_key, value)?;
 		let _ = self.config.global_options.set_by_kdl(kdl_key, opt_value);
+		if kdl_key == opts::LARGE_FILE.def().kdl_key {
+			self.refresh_large_file_modes();
+		}
+		if kdl_key == opts::LINE_NUMBERS.def().kdl_key {
+			self.refresh_line_numbers();
+		}
+		if is_wrap_option(kdl_key) {
+			self.refresh_wrap();
+		}
 
 		if let Some(def) = find_by_kdl(kdl_key) {
 			emit_hook_sync_with(
@@ -248,6 +381,15 @@ impl CommandEditorOps for Editor {
 			.buffer_mut()
 			.local_options
 			.set_by_kdl(kdl_key, opt_value);
+		if def.kdl_key == opts::LARGE_FILE.def().kdl_key {
+			self.refresh_large_file_modes();
+		}
+		if def.kdl_key == opts::LINE_NUMBERS.def().kdl_key {
+			self.refresh_line_numbers();
+		}
+		if is_wrap_option(def.kdl_key) {
+			self.refresh_wrap();
+		}
 
 		emit_hook_sync_with(
 			&HookContext::new(
@@ -262,6 +404,39 @@ impl CommandEditorOps for Editor {
 		Ok(())
 	}
 
+	fn toggle_option(&mut self, key: &str) -> Result<(&'static str, bool), CommandError> {
+		let def = find_by_kdl(key)
+			.or_else(|| find_by_kdl(&key.replace('_', "-")))
+			.ok_or_else(|| {
+				CommandError::InvalidArgument(match parse::suggest_option(key) {
+					Some(s) => format!("unknown option '{key}'. Did you mean '{s}'?"),
+					None => format!("unknown option '{key}'"),
+				})
+			})?;
+		let option = OptionKey::new(def);
+		let Some(current) = self.resolve_option(self.focused_view(), option).as_bool() else {
+			return Err(CommandError::InvalidArgument(format!(
+				"'{}' is not a boolean option",
+				def.kdl_key
+			)));
+		};
+
+		// A buffer-local or language value would hide a global change.
+		let buffer = self.buffer();
+		let shadowed = buffer.local_options.get(option).is_some()
+			|| buffer
+				.file_type()
+				.and_then(|ft| self.config.language_options.get(&ft))
+				.is_some_and(|store| store.get(option).is_some());
+		let value = (!current).to_string();
+		if shadowed && def.scope != OptionScope::Global {
+			self.set_local_option(def.kdl_key, &value)?;
+		} else {
+			self.set_option(def.kdl_key, &value)?;
+		}
+		Ok((def.kdl_key, !current))
+	}
+
 	fn open_info_popup(&mut self, content: &str, file_type: Option<&str>) {
 		use crate::info_popup::PopupAnchor;
 		Editor::open_info_popup(self, content.to_string(), file_type, PopupAnchor::Center);
@@ -271,6 +446,22 @@ impl CommandEditorOps for Editor {
 		Editor::close_all_info_popups(self);
 	}
 
+	fn selection_marks(&self) -> Vec<(String, Vec<(usize, usize)>)> {
+		let doc = self.buffer().doc();
+		let spans = |sel: &Selection| -> Vec<(usize, usize)> {
+			sel.iter().map(|r| (r.anchor, r.head)).collect()
+		};
+		let mut marks: Vec<_> = doc
+			.marks
+			.iter()
+			.map(|(slot, sel)| (slot.to_string(), spans(sel)))
+			.collect();
+		if let Some(previous) = doc.marks.previous() {
+			marks.push(("last".to_string(), spans(previous)));
+		}
+		marks
+	}
+
 	fn goto_file(
 		&mut self,
 		path: PathBuf,
@@ -297,6 +488,14 @@ impl SplitOps for Editor {
 		}
 	}
 
+	fn resize_split(&mut self, axis: Axis, delta: i32) {
+		self.resize_focused_view(axis, delta);
+	}
+
+	fn equalize_splits(&mut self) {
+		Editor::equalize_splits(self);
+	}
+
 	fn close_split(&mut self) {
 		self.close_current_buffer();
 	}
@@ -387,6 +586,18 @@ impl JumpAccess for Editor {
 			.jump_list
 			.push(crate::editor::JumpLocation { buffer_id, cursor });
 	}
+
+	fn save_selection(&mut self, slot: Option<char>) {
+		self.save_selection(slot);
+	}
+
+	fn restore_selection(&mut self, slot: Option<char>) -> bool {
+		self.restore_selection(slot)
+	}
+
+	fn reselect_last(&mut self) -> bool {
+		self.reselect_last()
+	}
 }
 
 impl MacroAccess for Editor {
@@ -0,0 +1,67 @@
+//! Per-buffer property commands.
+
+use futures::future::LocalBoxFuture;
+use xeno_registry::options::parse::parse_bool;
+use xeno_registry_notifications::keys;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	set_buffer,
+	{ aliases: &["set-buffer"], description: "Set a property of the current buffer" },
+	handler: cmd_set_buffer
+);
+
+/// Handler for `:set-buffer <property> [value]`.
+///
+/// `readonly` and `persistent` take a boolean, defaulting to true. A
+/// persistent scratch buffer guards its unsaved changes on quit. `filetype`
+/// takes a file type name, or re-runs detection when given none.
+fn cmd_set_buffer<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let Some((&property, value)) = ctx.args.split_first() else {
+			return Err(CommandError::MissingArgument("property"));
+		};
+		match property {
+			"readonly" => {
+				let readonly = match value.first() {
+					Some(value) => parse_bool(value).map_err(CommandError::InvalidArgument)?,
+					None => true,
+				};
+				ctx.editor.buffer().set_readonly(readonly);
+				ctx.editor.notify(if readonly {
+					keys::readonly_enabled
+				} else {
+					keys::readonly_disabled
+				});
+			}
+			"persistent" => {
+				let persistent = match value.first() {
+					Some(value) => parse_bool(value).map_err(CommandError::InvalidArgument)?,
+					None => true,
+				};
+				let buffer = ctx.editor.buffer();
+				let mut doc = buffer.doc_mut();
+				let Some(scratch) = doc.scratch.as_mut() else {
+					return Err(CommandError::Failed(
+						"only scratch buffers can be marked persistent".to_string(),
+					));
+				};
+				scratch.persistent = persistent;
+			}
+			"filetype" => {
+				let buffer_id = ctx.editor.focused_view();
+				ctx.editor.set_file_type(buffer_id, value.first().copied());
+			}
+			other => {
+				return Err(CommandError::InvalidArgument(format!(
+					"unknown buffer property: {other}"
+				)));
+			}
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
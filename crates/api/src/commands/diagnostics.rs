@@ -0,0 +1,65 @@
+//! Diagnostic navigation commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor::ERROR_SEVERITY;
+use crate::editor_command;
+
+editor_command!(
+	diagnostic_next,
+	{ description: "Jump to the next diagnostic in the buffer" },
+	handler: cmd_diagnostic_next
+);
+
+/// Handler for `:diagnostic_next [count] [--errors]`.
+fn cmd_diagnostic_next<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { step_diagnostic(ctx, true) })
+}
+
+editor_command!(
+	diagnostic_prev,
+	{ description: "Jump to the previous diagnostic in the buffer" },
+	handler: cmd_diagnostic_prev
+);
+
+/// Handler for `:diagnostic_prev [count] [--errors]`.
+fn cmd_diagnostic_prev<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { step_diagnostic(ctx, false) })
+}
+
+/// Jumps through the focused buffer's diagnostics, `count` at a time and
+/// only through errors with `--errors`.
+///
+/// A count argument overrides the command's count prefix, so actions can
+/// pass on theirs.
+fn step_diagnostic(
+	ctx: &mut EditorCommandContext<'_>,
+	forward: bool,
+) -> Result<CommandOutcome, CommandError> {
+	let mut count = ctx.count;
+	let mut min_severity = 1;
+	for arg in ctx.args {
+		match *arg {
+			"--errors" => min_severity = ERROR_SEVERITY,
+			arg => {
+				count = arg.parse().map_err(|_| {
+					CommandError::InvalidArgument(format!("expected a count, got '{arg}'"))
+				})?;
+			}
+		}
+	}
+	if !ctx.editor.goto_diagnostic(forward, count, min_severity) {
+		let what = if min_severity == ERROR_SEVERITY {
+			"No errors"
+		} else {
+			"No diagnostics"
+		};
+		return Err(CommandError::Failed(format!("{what} in buffer")));
+	}
+	Ok(CommandOutcome::Ok)
+}
@@ -0,0 +1,32 @@
+//! File opening commands.
+
+use std::path::PathBuf;
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	edit,
+	{ aliases: &["e"], description: "Edit a file" },
+	handler: cmd_edit
+);
+
+/// Handler for `:edit <path>`.
+///
+/// Opens the file in the focused split, reusing its buffer if it is already
+/// open. A path that does not exist opens an empty buffer that creates the
+/// file on write.
+fn cmd_edit<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if ctx.args.is_empty() {
+			return Err(CommandError::MissingArgument("filename"));
+		}
+		let path = PathBuf::from(ctx.args.join(" "));
+		ctx.editor.open_files([(path, None)]).await;
+		Ok(CommandOutcome::Ok)
+	})
+}
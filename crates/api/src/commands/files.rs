@@ -0,0 +1,27 @@
+//! File explorer commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::files::FILES_PANEL_ID;
+
+editor_command!(
+	files,
+	{ aliases: &["explorer"], description: "Toggle the file explorer panel" },
+	handler: cmd_files
+);
+
+/// Handler for `:files`.
+///
+/// Opens and focuses the file explorer, or closes it if it is open.
+fn cmd_files<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(FILES_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,61 @@
+//! Folding commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	fold_toggle,
+	{ aliases: &["fold-toggle"], description: "Open the fold under cursor, or close the innermost one around it" },
+	handler: cmd_fold_toggle
+);
+
+/// Handler for `:fold-toggle`.
+fn cmd_fold_toggle<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if !ctx.editor.toggle_fold() {
+			return Err(CommandError::Failed("No fold at cursor".into()));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	fold_close_all,
+	{ aliases: &["fold-close-all"], description: "Close every fold in the buffer" },
+	handler: cmd_fold_close_all
+);
+
+/// Handler for `:fold-close-all`.
+fn cmd_fold_close_all<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { set_all_folds(ctx, true) })
+}
+
+editor_command!(
+	fold_open_all,
+	{ aliases: &["fold-open-all"], description: "Open every fold in the buffer" },
+	handler: cmd_fold_open_all
+);
+
+/// Handler for `:fold-open-all`.
+fn cmd_fold_open_all<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { set_all_folds(ctx, false) })
+}
+
+/// Opens or closes every fold of the focused buffer.
+fn set_all_folds(
+	ctx: &mut EditorCommandContext<'_>,
+	closed: bool,
+) -> Result<CommandOutcome, CommandError> {
+	if !ctx.editor.set_all_folds(closed) {
+		return Err(CommandError::Failed("No folds".into()));
+	}
+	Ok(CommandOutcome::Ok)
+}
@@ -0,0 +1,140 @@
+//! Location jumping and location list commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor::{Editor, JumpLocation, Location};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::locations::LOCATIONS_PANEL_ID;
+
+editor_command!(
+	open_location,
+	{ description: "Open a file at a line and column" },
+	handler: cmd_open_location
+);
+
+/// Handler for `:open_location <path> <line> [column]`.
+///
+/// Line and column are 1-based, as in compiler and grep output. The position
+/// left is pushed onto the jump list so the jump can be undone.
+fn cmd_open_location<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let (path, line, column) = match ctx.args {
+			[path, line] => (path, line, &"1"),
+			[path, line, column] => (path, line, column),
+			[] => return Err(CommandError::MissingArgument("path")),
+			[_] => return Err(CommandError::MissingArgument("line")),
+			_ => return Err(CommandError::InvalidArgument("too many arguments".into())),
+		};
+		let number = |arg: &str| {
+			arg.parse::<usize>()
+				.ok()
+				.filter(|n| *n > 0)
+				.ok_or_else(|| CommandError::InvalidArgument(format!("not a position: {arg}")))
+		};
+		let location = Location::new(*path, number(line)? - 1, number(column)? - 1);
+
+		let origin = JumpLocation {
+			buffer_id: ctx.editor.focused_view(),
+			cursor: ctx.editor.buffer().cursor,
+		};
+		ctx.editor
+			.goto_location(&location)
+			.await
+			.map_err(|e| CommandError::Io(e.to_string()))?;
+		ctx.editor.workspace.jump_list.push(origin);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	location_next,
+	{ aliases: &["cnext", "cn"], description: "Jump to the next entry of the location list" },
+	handler: cmd_location_next
+);
+
+/// Handler for `:location_next`.
+fn cmd_location_next<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { step_location(ctx.editor, 1).await })
+}
+
+editor_command!(
+	location_prev,
+	{ aliases: &["cprev"], description: "Jump to the previous entry of the location list" },
+	handler: cmd_location_prev
+);
+
+/// Handler for `:location_prev`.
+fn cmd_location_prev<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { step_location(ctx.editor, -1).await })
+}
+
+/// Jumps `delta` entries through the location list from the current one.
+async fn step_location(editor: &mut Editor, delta: isize) -> Result<CommandOutcome, CommandError> {
+	let locations = &editor.workspace.locations;
+	if locations.items().is_empty() {
+		return Err(CommandError::Failed("location list is empty".into()));
+	}
+	let index = locations
+		.step(delta)
+		.ok_or_else(|| CommandError::Failed("no more locations".into()))?;
+	editor
+		.goto_location_entry(index)
+		.await
+		.map_err(|e| CommandError::Failed(e.to_string()))?;
+	Ok(CommandOutcome::Ok)
+}
+
+editor_command!(
+	location_goto,
+	{ aliases: &["cc"], description: "Jump to an entry of the location list" },
+	handler: cmd_location_goto
+);
+
+/// Handler for `:location_goto [n]`.
+///
+/// `n` is 1-based and defaults to the current entry, or the first.
+fn cmd_location_goto<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let index = match ctx.args {
+			[] => ctx.editor.workspace.locations.current().unwrap_or(0),
+			[n] => n
+				.parse::<usize>()
+				.ok()
+				.and_then(|n| n.checked_sub(1))
+				.ok_or_else(|| CommandError::InvalidArgument(format!("not an entry: {n}")))?,
+			_ => return Err(CommandError::InvalidArgument("too many arguments".into())),
+		};
+		ctx.editor
+			.goto_location_entry(index)
+			.await
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	locations,
+	{ aliases: &["copen"], description: "Toggle the location list panel" },
+	handler: cmd_locations
+);
+
+/// Handler for `:locations`.
+fn cmd_locations<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(LOCATIONS_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -1,12 +1,27 @@
 //! LSP commands with direct [`Editor`] access.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
 use futures::future::LocalBoxFuture;
-use xeno_lsp::lsp_types::{GotoDefinitionResponse, HoverContents, MarkedString, MarkupContent};
+use xeno_lsp::lsp_types::{
+	DiagnosticSeverity, GotoDefinitionResponse, HoverContents, MarkedString, MarkupContent,
+	Position, Range,
+};
+use xeno_lsp::{OffsetEncoding, ServerInfo, ServerState};
+use xeno_registry_notifications::keys;
 
 use super::{CommandError, CommandOutcome, EditorCommandContext};
-use crate::editor::Editor;
+use crate::buffer::Buffer;
+use crate::editor::{Editor, JumpLocation, LocationItem, LocationKind, WorkspaceEditSummary};
 use crate::editor_command;
 use crate::info_popup::PopupAnchor;
+use crate::lsp::GotoKind;
+use crate::prompt::{PromptPopup, PromptResult};
+use crate::ui::UiRequest;
+use crate::ui::lsp_log::LSP_LOG_PANEL_ID;
+use crate::ui::outline::OUTLINE_PANEL_ID;
 
 editor_command!(
 	hover,
@@ -23,11 +38,28 @@ fn cmd_hover<'a>(
 			.lsp
 			.hover(ctx.editor.buffer())
 			.await
-			.map_err(|e| CommandError::Failed(e.to_string()))?
-			.ok_or_else(|| CommandError::Failed("No hover information available".into()))?;
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
 
-		let content = format_hover_contents(&hover.contents);
-		Editor::open_info_popup(ctx.editor, content, Some("markdown"), PopupAnchor::Center);
+		// Tooltips of the inlay hints next to the cursor follow the hover.
+		let mut sections: Vec<_> = hover
+			.map(|hover| format_hover_contents(&hover.contents))
+			.into_iter()
+			.collect();
+		sections.extend(ctx.editor.inlay_hint_tooltips());
+		if sections.is_empty() {
+			return Err(CommandError::Failed(
+				"No hover information available".into(),
+			));
+		}
+		let content = sections.join("\n\n---\n\n");
+		let anchor = ctx
+			.editor
+			.cursor_screen_position()
+			.map_or(PopupAnchor::Center, |pos| PopupAnchor::Cursor {
+				x: pos.x,
+				y: pos.y,
+			});
+		Editor::open_info_popup(ctx.editor, content, Some("markdown"), anchor);
 		Ok(CommandOutcome::Ok)
 	})
 }
@@ -40,48 +72,584 @@ editor_command!(
 
 fn cmd_goto_definition<'a>(
 	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(goto(ctx, GotoKind::Definition))
+}
+
+editor_command!(
+	goto_type_definition,
+	{ aliases: &["gy", "lsp-type-definition"], description: "Go to type definition" },
+	handler: cmd_goto_type_definition
+);
+
+fn cmd_goto_type_definition<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(goto(ctx, GotoKind::TypeDefinition))
+}
+
+editor_command!(
+	goto_implementation,
+	{ aliases: &["gi", "lsp-implementation"], description: "Go to implementation" },
+	handler: cmd_goto_implementation
+);
+
+fn cmd_goto_implementation<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(goto(ctx, GotoKind::Implementation))
+}
+
+editor_command!(
+	goto_declaration,
+	{ aliases: &["lsp-declaration"], description: "Go to declaration" },
+	handler: cmd_goto_declaration
+);
+
+fn cmd_goto_declaration<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(goto(ctx, GotoKind::Declaration))
+}
+
+editor_command!(
+	select_highlights,
+	{ aliases: &["select-all-highlights"], description: "Select every highlighted occurrence of the symbol under cursor" },
+	handler: cmd_select_highlights
+);
+
+fn cmd_select_highlights<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
 ) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
 	Box::pin(async move {
-		use crate::editor::Location;
+		if !ctx.editor.select_symbol_highlights() {
+			return Err(CommandError::Failed("No highlights".into()));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+/// Goes to what `kind` finds for the symbol at the cursor.
+///
+/// A single location is jumped to, pushing the position left onto the jump
+/// list; several fill the location list to pick from.
+async fn goto(
+	ctx: &mut EditorCommandContext<'_>,
+	kind: GotoKind,
+) -> Result<CommandOutcome, CommandError> {
+	let not_found = || CommandError::Failed(format!("No {} found", kind.name()));
+	let (response, encoding) = ctx
+		.editor
+		.lsp
+		.goto(ctx.editor.buffer(), kind)
+		.await
+		.map_err(|e| CommandError::Failed(e.to_string()))?
+		.ok_or_else(not_found)?;
+
+	let locations: Vec<_> = match response {
+		GotoDefinitionResponse::Scalar(location) => vec![location],
+		GotoDefinitionResponse::Array(locations) => locations,
+		GotoDefinitionResponse::Link(links) => links
+			.into_iter()
+			.map(|link| xeno_lsp::lsp_types::Location {
+				uri: link.target_uri,
+				range: link.target_selection_range,
+			})
+			.collect(),
+	};
+
+	match locations.as_slice() {
+		[] => Err(not_found()),
+		[location] => {
+			let path = xeno_lsp::path_from_uri(&location.uri).ok_or_else(|| {
+				CommandError::Failed(format!("Invalid file path in {}", kind.name()))
+			})?;
+			let origin = JumpLocation {
+				buffer_id: ctx.editor.focused_view(),
+				cursor: ctx.editor.buffer().cursor,
+			};
+			ctx.editor
+				.goto_lsp_position(path, location.range.start, encoding)
+				.await
+				.map_err(|e| CommandError::Io(e.to_string()))?;
+			ctx.editor.workspace.jump_list.push(origin);
+			Ok(CommandOutcome::Ok)
+		}
+		locations => {
+			let title = format!("{}: {}", kind.name(), word_at_cursor(ctx.editor.buffer()));
+			let mut previews = LinePreviews::default();
+			let items = locations
+				.iter()
+				.filter_map(|location| {
+					let path = xeno_lsp::path_from_uri(&location.uri)?;
+					Some(location_item(
+						ctx.editor,
+						&mut previews,
+						path,
+						&location.range,
+						encoding,
+					))
+				})
+				.map(|item| item.with_kind(LocationKind::Symbol))
+				.collect();
+			ctx.editor.show_locations(title, items);
+			Ok(CommandOutcome::Ok)
+		}
+	}
+}
+
+editor_command!(
+	rename,
+	{ aliases: &["lsp-rename"], description: "Rename the symbol at cursor" },
+	handler: cmd_rename
+);
+
+/// Renames the symbol at the cursor to the given name, or asks for the name
+/// in a prompt when none is given.
+fn cmd_rename<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		match ctx.args {
+			[] => {
+				let (initial, error) =
+					match ctx.editor.lsp.prepare_rename(ctx.editor.buffer()).await {
+						Ok(name) => (
+							name.unwrap_or_else(|| word_at_cursor(ctx.editor.buffer())),
+							None,
+						),
+						Err(e) => (word_at_cursor(ctx.editor.buffer()), Some(e.to_string())),
+					};
+				open_rename_prompt(ctx.editor, initial, error);
+				Ok(CommandOutcome::Ok)
+			}
+			[new_name] => rename_symbol(ctx.editor, new_name.to_string()).await,
+			_ => Err(CommandError::InvalidArgument(
+				"rename takes a single name".into(),
+			)),
+		}
+	})
+}
+
+/// Opens the rename prompt with `initial` as its input.
+///
+/// An `error` from the server is shown on the prompt's border until the
+/// input is edited. Submitting queues the rename, which needs to await the
+/// server.
+fn open_rename_prompt(editor: &mut Editor, initial: String, error: Option<String>) {
+	let title = error.map_or_else(|| "Rename".to_string(), |e| format!("Rename: {e}"));
+	let prompt = PromptPopup::new(title, |editor, result| {
+		if let PromptResult::Submitted(new_name) = result {
+			editor
+				.workspace
+				.command_queue
+				.push("rename", vec![new_name.trim().to_string()]);
+		}
+	})
+	.initial(initial)
+	.validator(|input| {
+		if input.trim().is_empty() {
+			Err("Rename: name required".to_string())
+		} else {
+			Ok(())
+		}
+	});
+	editor.open_prompt(prompt);
+}
+
+/// Renames the symbol at the cursor to `new_name` across the workspace.
+///
+/// A server rejecting the rename reopens the prompt with its message.
+async fn rename_symbol(
+	editor: &mut Editor,
+	new_name: String,
+) -> Result<CommandOutcome, CommandError> {
+	let (edit, encoding) = match editor.lsp.rename(editor.buffer(), new_name.clone()).await {
+		Ok(Some(response)) => response,
+		Ok(None) => {
+			return Err(CommandError::Failed(
+				"No language server can rename here".into(),
+			));
+		}
+		Err(e) => {
+			open_rename_prompt(editor, new_name, Some(e.to_string()));
+			return Ok(CommandOutcome::Ok);
+		}
+	};
+	let summary = editor
+		.apply_workspace_edit(edit, encoding)
+		.await
+		.map_err(CommandError::Failed)?;
+	editor.notify(keys::symbol_renamed::call(summary.edits, summary.files));
+	Ok(CommandOutcome::Ok)
+}
+
+editor_command!(
+	format,
+	{ aliases: &["fmt", "lsp-format"], description: "Format the buffer, or the selection, with its language server" },
+	handler: cmd_format
+);
 
-		let response = ctx
+/// Formats the focused buffer with its language server, only the primary
+/// selection when it is not a bare cursor.
+fn cmd_format<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let buffer_id = ctx.editor.focused_view();
+		let edits = ctx
 			.editor
-			.lsp
-			.goto_definition(ctx.editor.buffer())
+			.format_buffer(buffer_id, true)
 			.await
-			.map_err(|e| CommandError::Failed(e.to_string()))?
-			.ok_or_else(|| CommandError::Failed("No definition found".into()))?;
-
-		let location = match response {
-			GotoDefinitionResponse::Scalar(loc) => loc,
-			GotoDefinitionResponse::Array(locs) => locs
-				.into_iter()
-				.next()
-				.ok_or_else(|| CommandError::Failed("No definition found".into()))?,
-			GotoDefinitionResponse::Link(links) => {
-				let link = links
-					.into_iter()
-					.next()
-					.ok_or_else(|| CommandError::Failed("No definition found".into()))?;
-				xeno_lsp::lsp_types::Location {
-					uri: link.target_uri,
-					range: link.target_selection_range,
-				}
+			.map_err(CommandError::Failed)?
+			.ok_or_else(|| {
+				CommandError::Failed("No language server can format this buffer".into())
+			})?;
+		ctx.editor.notify(keys::buffer_formatted::call(edits));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	code_action,
+	{ aliases: &["lsp-code-action"], description: "List code actions at the selection, or apply the Nth" },
+	handler: cmd_code_action
+);
+
+/// Handler for `:code_action [N]`.
+///
+/// Lists the code actions at the primary selection in a popup, or applies
+/// the Nth of them, counted from 1, and reports what it changed.
+fn cmd_code_action<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let number = match ctx.args {
+			[] => None,
+			[arg] => Some(
+				arg.parse::<usize>()
+					.ok()
+					.filter(|&n| n > 0)
+					.ok_or_else(|| {
+						CommandError::InvalidArgument(format!(
+							"expected an action number, got '{arg}'"
+						))
+					})?,
+			),
+			_ => {
+				return Err(CommandError::InvalidArgument(
+					"code_action takes a single action number".into(),
+				));
 			}
 		};
+		let (actions, encoding) = ctx
+			.editor
+			.request_code_actions()
+			.await
+			.map_err(CommandError::Failed)?;
+		if actions.is_empty() {
+			return Err(CommandError::Failed("No code actions available".into()));
+		}
+		let Some(number) = number else {
+			ctx.editor.open_code_actions(&actions);
+			return Ok(CommandOutcome::Ok);
+		};
+		let action = actions
+			.into_iter()
+			.nth(number - 1)
+			.ok_or_else(|| CommandError::Failed(format!("No code action {number}")))?;
+		let outcome = ctx
+			.editor
+			.apply_code_action(action, encoding)
+			.await
+			.map_err(CommandError::Failed)?;
+		let changes = if outcome.summary == WorkspaceEditSummary::default() && outcome.ran_command {
+			"run by the language server".to_string()
+		} else {
+			outcome.summary.to_string()
+		};
+		ctx.editor
+			.notify(keys::code_action_applied::call(&outcome.title, &changes));
+		Ok(CommandOutcome::Ok)
+	})
+}
 
-		let path = xeno_lsp::path_from_uri(&location.uri)
-			.ok_or_else(|| CommandError::Failed("Invalid file path in definition".into()))?;
+editor_command!(
+	references,
+	{ aliases: &["lsp-references"], description: "List references to the symbol at cursor" },
+	handler: cmd_references
+);
 
-		ctx.editor
-			.goto_location(&Location::from_lsp(path, &location.range.start))
+/// Fills the location list with the references to the symbol at the cursor,
+/// declaration included.
+fn cmd_references<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let (mut locations, encoding) = ctx
+			.editor
+			.lsp
+			.references(ctx.editor.buffer(), true)
 			.await
-			.map_err(|e| CommandError::Io(e.to_string()))?;
+			.map_err(|e| CommandError::Failed(e.to_string()))?
+			.filter(|(locations, _)| !locations.is_empty())
+			.ok_or_else(|| CommandError::Failed("No references found".into()))?;
+		locations.sort_by(|a, b| {
+			(a.uri.as_str(), a.range.start.line, a.range.start.character).cmp(&(
+				b.uri.as_str(),
+				b.range.start.line,
+				b.range.start.character,
+			))
+		});
+
+		let title = format!("references: {}", word_at_cursor(ctx.editor.buffer()));
+		let mut previews = LinePreviews::default();
+		let items = locations
+			.iter()
+			.filter_map(|location| {
+				let path = xeno_lsp::path_from_uri(&location.uri)?;
+				Some(location_item(
+					ctx.editor,
+					&mut previews,
+					path,
+					&location.range,
+					encoding,
+				))
+			})
+			.map(|item| item.with_kind(LocationKind::Reference))
+			.collect();
+		ctx.editor.show_locations(title, items);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	workspace_symbols,
+	{ aliases: &["lsp-workspace-symbols"], description: "Pick a workspace symbol to jump to" },
+	handler: cmd_workspace_symbols
+);
+
+/// Opens the workspace symbol picker, filtering with the arguments.
+fn cmd_workspace_symbols<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if !ctx.editor.open_symbol_picker(ctx.args.join(" ")) {
+			return Err(CommandError::Failed(
+				"No language server can search symbols here".into(),
+			));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	diagnostics,
+	{ aliases: &["lsp-diagnostics"], description: "List diagnostics of the buffer, or of all files" },
+	handler: cmd_diagnostics
+);
 
+/// Handler for `:diagnostics [--workspace]`.
+///
+/// Fills the location list with the diagnostics of the focused buffer, by
+/// position, or with `--workspace` those of every file, by file and
+/// position.
+fn cmd_diagnostics<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let workspace = match ctx.args {
+			[] => false,
+			["--workspace"] => true,
+			[arg, ..] => {
+				return Err(CommandError::InvalidArgument(format!(
+					"expected --workspace, got '{arg}'"
+				)));
+			}
+		};
+		let items = if workspace {
+			workspace_diagnostics(ctx.editor)
+		} else {
+			buffer_diagnostics(ctx.editor.buffer())?
+		};
+		if items.is_empty() {
+			return Err(CommandError::Failed("No diagnostics".into()));
+		}
+		let title = if workspace {
+			"diagnostics"
+		} else {
+			"buffer diagnostics"
+		};
+		ctx.editor.show_locations(title, items);
 		Ok(CommandOutcome::Ok)
 	})
 }
 
+/// Returns the location list kind of a diagnostic severity.
+fn diagnostic_kind(severity: u8) -> LocationKind {
+	match severity {
+		3 => LocationKind::Warning,
+		2 => LocationKind::Info,
+		1 => LocationKind::Hint,
+		_ => LocationKind::Error,
+	}
+}
+
+/// Lists the diagnostics of `buffer` where they are drawn, mapped through
+/// edits made since the server published them.
+fn buffer_diagnostics(buffer: &Buffer) -> Result<Vec<LocationItem>, CommandError> {
+	let path = buffer
+		.path()
+		.ok_or_else(|| CommandError::Failed("buffer has no file".into()))?;
+	let doc = buffer.doc();
+	let text = &doc.content;
+	let line_col = |pos: usize| {
+		let pos = pos.min(text.len_chars());
+		let line = text.char_to_line(pos);
+		(line, pos - text.line_to_char(line))
+	};
+	let mut diagnostics: Vec<_> = doc.diagnostics.iter().collect();
+	diagnostics.sort_by_key(|d| (d.start, d.end));
+	Ok(diagnostics
+		.into_iter()
+		.map(|diagnostic| {
+			let (line, column) = line_col(diagnostic.start);
+			let (end_line, end_column) = line_col(diagnostic.end);
+			let message = tagged_message(diagnostic.source.as_deref(), &diagnostic.message);
+			LocationItem::new(path.clone(), line, column, message)
+				.with_end(end_line, end_column)
+				.with_kind(diagnostic_kind(diagnostic.severity))
+		})
+		.collect())
+}
+
+/// Returns a diagnostic's message led by the server or tool that reported
+/// it, as diagnostics of several servers are listed together.
+fn tagged_message(source: Option<&str>, message: &str) -> String {
+	match source {
+		Some(source) if !source.is_empty() => format!("{source}: {message}"),
+		_ => message.to_string(),
+	}
+}
+
+/// Lists the diagnostics of every file, by file and position.
+fn workspace_diagnostics(editor: &Editor) -> Vec<LocationItem> {
+	let mut files = editor.lsp.all_diagnostics();
+	files.sort_by(|a, b| a.0.cmp(&b.0));
+	let mut previews = LinePreviews::default();
+	let mut items = Vec::new();
+	for (path, mut diagnostics) in files {
+		diagnostics.sort_by_key(|(d, _)| (d.range.start.line, d.range.start.character));
+		items.extend(diagnostics.into_iter().map(|(diagnostic, encoding)| {
+			let kind = match diagnostic.severity {
+				Some(DiagnosticSeverity::WARNING) => LocationKind::Warning,
+				Some(DiagnosticSeverity::INFORMATION) => LocationKind::Info,
+				Some(DiagnosticSeverity::HINT) => LocationKind::Hint,
+				_ => LocationKind::Error,
+			};
+			let range = diagnostic.range;
+			LocationItem::new(
+				path.clone(),
+				range.start.line as usize,
+				previews.column(editor, &path, range.start, encoding),
+				tagged_message(diagnostic.source.as_deref(), &diagnostic.message),
+			)
+			.with_end(
+				range.end.line as usize,
+				previews.column(editor, &path, range.end, encoding),
+			)
+			.with_kind(kind)
+		}));
+	}
+	items
+}
+
+/// Lines of files read for location previews, so each file is read once.
+#[derive(Default)]
+struct LinePreviews {
+	/// Lines of each file read so far, empty if it could not be read.
+	files: HashMap<PathBuf, Vec<String>>,
+}
+
+impl LinePreviews {
+	/// Returns line `line` of `path` without its line ending, from its buffer
+	/// if it is open.
+	fn line(&mut self, editor: &Editor, path: &Path, line: usize) -> String {
+		if let Some(buffer) = editor
+			.buffers
+			.find_by_path(path)
+			.and_then(|id| editor.buffers.get_buffer(id))
+		{
+			let doc = buffer.doc();
+			if line >= doc.content.len_lines() {
+				return String::new();
+			}
+			return doc
+				.content
+				.line(line)
+				.to_string()
+				.trim_end_matches('\n')
+				.to_string();
+		}
+		let lines = self.files.entry(path.to_path_buf()).or_insert_with(|| {
+			std::fs::read_to_string(path)
+				.map(|text| text.lines().map(str::to_string).collect())
+				.unwrap_or_default()
+		});
+		lines.get(line).cloned().unwrap_or_default()
+	}
+
+	/// Returns the character column of an LSP `position` in `path`, whose
+	/// column is counted in `encoding`.
+	fn column(
+		&mut self,
+		editor: &Editor,
+		path: &Path,
+		position: Position,
+		encoding: OffsetEncoding,
+	) -> usize {
+		let line = self.line(editor, path, position.line as usize);
+		xeno_lsp::lsp_column_to_char(line.as_str().into(), position.character, encoding)
+	}
+}
+
+/// Builds a location list entry for an LSP `range` in `path`, previewing
+/// its first line.
+fn location_item(
+	editor: &Editor,
+	previews: &mut LinePreviews,
+	path: PathBuf,
+	range: &Range,
+	encoding: OffsetEncoding,
+) -> LocationItem {
+	let line = range.start.line as usize;
+	let text = previews.line(editor, &path, line).trim_end().to_string();
+	let start = previews.column(editor, &path, range.start, encoding);
+	let end = previews.column(editor, &path, range.end, encoding);
+	LocationItem::new(path, line, start, text).with_end(range.end.line as usize, end)
+}
+
+/// Returns the identifier around the cursor, or an empty string.
+fn word_at_cursor(buffer: &Buffer) -> String {
+	let doc = buffer.doc();
+	let text = &doc.content;
+	let is_word = |i: usize| {
+		let c = text.char(i);
+		c.is_alphanumeric() || c == '_'
+	};
+	let cursor = buffer.cursor.min(text.len_chars());
+	let start = (0..cursor)
+		.rev()
+		.take_while(|&i| is_word(i))
+		.last()
+		.unwrap_or(cursor);
+	let end = (cursor..text.len_chars())
+		.take_while(|&i| is_word(i))
+		.last()
+		.map_or(cursor, |i| i + 1);
+	text.slice(start..end).to_string()
+}
+
 /// Formats LSP hover contents to markdown.
 fn format_hover_contents(contents: &HoverContents) -> String {
 	match contents {
@@ -102,3 +670,179 @@ fn format_hover_contents(contents: &HoverContents) -> String {
 		HoverContents::Markup(MarkupContent { value, .. }) => value.clone(),
 	}
 }
+
+editor_command!(
+	outline,
+	{ aliases: &["lsp-outline"], description: "Toggle the outline of the focused buffer" },
+	handler: cmd_outline
+);
+
+/// Handler for `:outline`.
+fn cmd_outline<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(OUTLINE_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	lsp_restart,
+	{ aliases: &["lsp-restart"], description: "Restart the language servers of the buffer, or the one named" },
+	handler: cmd_lsp_restart
+);
+
+/// Handler for `:lsp-restart [server]`.
+///
+/// Shuts the servers down, killing those that do not exit in time, then
+/// starts them again and reopens every buffer of the language with them.
+fn cmd_lsp_restart<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let name = server_name_arg(ctx.args)?;
+		let names = ctx
+			.editor
+			.lsp
+			.restart_servers(ctx.editor.buffer(), name)
+			.await
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		ctx.editor
+			.lsp
+			.on_buffer_open(ctx.editor.buffer())
+			.await
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		if let Some(language) = ctx.editor.buffer().file_type() {
+			ctx.editor.reopen_lsp_documents(&language, None);
+		}
+		ctx.editor
+			.notify(keys::lsp_servers_stopped::call(&names, true));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	lsp_stop,
+	{ aliases: &["lsp-stop"], description: "Stop the language servers of the buffer, or the one named" },
+	handler: cmd_lsp_stop
+);
+
+/// Handler for `:lsp-stop [server]`.
+///
+/// The servers stay stopped, crashed or not, until `:lsp-restart`.
+fn cmd_lsp_stop<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let name = server_name_arg(ctx.args)?;
+		let names = ctx
+			.editor
+			.lsp
+			.stop_servers(ctx.editor.buffer(), name)
+			.await
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		ctx.editor
+			.notify(keys::lsp_servers_stopped::call(&names, false));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+/// Returns the optional server name argument of `:lsp-restart` and
+/// `:lsp-stop`.
+fn server_name_arg<'a>(args: &[&'a str]) -> Result<Option<&'a str>, CommandError> {
+	match args {
+		[] => Ok(None),
+		[name] => Ok(Some(*name)),
+		[_, extra, ..] => Err(CommandError::InvalidArgument(format!(
+			"expected one server name, got '{extra}' too"
+		))),
+	}
+}
+
+editor_command!(
+	lsp_log,
+	{ aliases: &["lsp-log"], description: "Toggle the log of language server output and messages" },
+	handler: cmd_lsp_log
+);
+
+/// Handler for `:lsp-log`.
+fn cmd_lsp_log<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(LSP_LOG_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	lsp_status,
+	{ aliases: &["lsp-status"], description: "Show the state of every language server" },
+	handler: cmd_lsp_status
+);
+
+/// Handler for `:lsp-status`.
+fn cmd_lsp_status<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let servers = ctx.editor.lsp.server_infos();
+		if servers.is_empty() {
+			return Err(CommandError::Failed("No language servers started".into()));
+		}
+		let content = servers
+			.iter()
+			.map(format_server_info)
+			.collect::<Vec<_>>()
+			.join("\n");
+		Editor::open_info_popup(ctx.editor, content, None, PopupAnchor::Center);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+/// Formats a server for `:lsp-status`: its name, language and state, the
+/// figures of its process if one runs, and its root, with its last error
+/// on a line of its own.
+fn format_server_info(server: &ServerInfo) -> String {
+	let state = match server.state {
+		ServerState::Starting => "starting".to_string(),
+		ServerState::Running => "running".to_string(),
+		ServerState::Exited => "exited".to_string(),
+		ServerState::Restarting(delay) => {
+			format!("restarting in {:.1}s", delay.as_secs_f32())
+		}
+		ServerState::Stopped => "stopped".to_string(),
+		ServerState::Failed => "failed".to_string(),
+	};
+	let mut fields = vec![format!("{} ({})", server.name, server.language), state];
+	if let Some(pid) = server.pid {
+		fields.push(format!("pid {pid}"));
+	}
+	if let Some(uptime) = server.uptime {
+		fields.push(format!("up {}", format_uptime(uptime)));
+		fields.push(format!("{} pending", server.pending_requests));
+	}
+	if server.crashes > 0 {
+		fields.push(format!("{} crashes", server.crashes));
+	}
+	fields.push(server.root.display().to_string());
+
+	let mut line = fields.join("  ");
+	if let Some(error) = &server.last_error {
+		line.push_str(&format!("\n  last error: {error}"));
+	}
+	line
+}
+
+/// Formats an uptime to the second under an hour, or to the minute.
+fn format_uptime(uptime: Duration) -> String {
+	let secs = uptime.as_secs();
+	match secs {
+		0..60 => format!("{secs}s"),
+		60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+		_ => format!("{}h {}m", secs / 3600, secs / 60 % 60),
+	}
+}
@@ -6,8 +6,24 @@
 //!
 //! [`CommandEditorOps`]: xeno_registry::commands::CommandEditorOps
 
+mod buffer;
+mod diagnostics;
+mod edit;
+mod files;
+mod folds;
+mod location;
 #[cfg(feature = "lsp")]
 mod lsp;
+mod panel;
+mod popup;
+mod recovery;
+mod resize;
+mod scratch;
+mod search;
+mod session;
+mod terminal;
+mod undotree;
+mod write;
 
 use std::any::Any;
 
@@ -0,0 +1,153 @@
+//! Panel arrangement commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::dock::{DockSlot, MAX_PANEL_PERCENT, MIN_PANEL_PERCENT};
+
+editor_command!(
+	panel_next_tab,
+	{ description: "Show the next panel stacked in the dock" },
+	handler: cmd_panel_next_tab
+);
+
+/// Handler for `:panel_next_tab`.
+fn cmd_panel_next_tab<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor.apply_ui_requests(vec![UiRequest::NextTab]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	panel_float,
+	{ description: "Float a panel, or dock it back" },
+	handler: cmd_panel_float
+);
+
+/// Handler for `:panel_float [id]`.
+///
+/// Without an argument this toggles the focused panel, or docks the
+/// floating one back when the editor has focus.
+fn cmd_panel_float<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let ui = &ctx.editor.ui;
+		let id = ctx
+			.args
+			.first()
+			.map(|id| id.to_string())
+			.or_else(|| ui.focused_panel_id().map(String::from))
+			.or_else(|| ui.dock.active_in_slot(DockSlot::Overlay).map(String::from))
+			.ok_or_else(|| CommandError::Failed("no panel to float".into()))?;
+		if !ui.has_panel(&id) {
+			return Err(CommandError::InvalidArgument(format!(
+				"unknown panel '{id}'"
+			)));
+		}
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::ToggleFloating(Some(id))]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	panel_resize,
+	{ description: "Set the size of the focused panel, in percent" },
+	handler: cmd_panel_resize
+);
+
+/// Handler for `:panel_resize N|+N|-N`.
+///
+/// Resizes the focused panel, or the one focused last when the editor has
+/// focus.
+///
+/// The size belongs to the panel, so panels stacked in the same dock keep
+/// their own. It stays between [`MIN_PANEL_PERCENT`] and
+/// [`MAX_PANEL_PERCENT`].
+fn cmd_panel_resize<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let arg = *ctx
+			.args
+			.first()
+			.ok_or(CommandError::MissingArgument("size"))?;
+		let invalid = || CommandError::InvalidArgument(format!("expected a size, got '{arg}'"));
+		let ui = &mut ctx.editor.ui;
+		let Some(id) = ui.target_panel_id().map(String::from) else {
+			return Err(CommandError::Failed("no panel to resize".into()));
+		};
+		if arg.starts_with(['+', '-']) {
+			let delta = arg.parse::<i32>().map_err(|_| invalid())?;
+			ui.dock.resize_panel(&id, delta);
+		} else {
+			let percent = arg.parse::<u16>().map_err(|_| invalid())?;
+			ui.dock.set_panel_size(&id, percent);
+		}
+		ctx.editor.frame.needs_redraw = true;
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	cycle_focus,
+	{ description: "Focus the editor, then each docked panel in turn" },
+	handler: cmd_cycle_focus
+);
+
+/// Handler for `:cycle_focus`.
+///
+/// Focus goes from the editor to the left, bottom and right docks, then
+/// back to the editor.
+fn cmd_cycle_focus<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor.apply_ui_requests(vec![UiRequest::CycleFocus]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	close_panel,
+	{ description: "Close the focused panel" },
+	handler: cmd_close_panel
+);
+
+/// Handler for `:close_panel`.
+///
+/// Closes the focused panel, or the one focused last when the editor has
+/// focus.
+fn cmd_close_panel<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if ctx.editor.ui.target_panel_id().is_none() {
+			return Err(CommandError::Failed("no panel to close".into()));
+		}
+		ctx.editor.apply_ui_requests(vec![UiRequest::CloseFocused]);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	close_other_panels,
+	{ description: "Close every panel except the focused one" },
+	handler: cmd_close_other_panels
+);
+
+/// Handler for `:close_other_panels`.
+fn cmd_close_other_panels<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor.apply_ui_requests(vec![UiRequest::CloseOthers]);
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,60 @@
+//! Info popup commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	focus_popup,
+	{ aliases: &["focus-popup", "fp"], description: "Focus the open info popup to scroll it" },
+	handler: cmd_focus_popup
+);
+
+/// Handler for `:focus-popup`.
+fn cmd_focus_popup<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if !ctx.editor.focus_info_popup() {
+			return Err(CommandError::Failed("no popup to focus".into()));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	pin_popup,
+	{ aliases: &["pin-popup"], description: "Pin or unpin the focused or latest info popup" },
+	handler: cmd_pin_popup
+);
+
+/// Handler for `:pin-popup`.
+fn cmd_pin_popup<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if ctx.editor.toggle_pin_info_popup().is_none() {
+			return Err(CommandError::Failed("no popup to pin".into()));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	next_popup,
+	{ aliases: &["next-popup"], description: "Focus the next info popup, then the document" },
+	handler: cmd_next_popup
+);
+
+/// Handler for `:next-popup`.
+fn cmd_next_popup<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		if !ctx.editor.focus_next_info_popup() {
+			return Err(CommandError::Failed("no popup to focus".into()));
+		}
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,38 @@
+//! Crash recovery commands.
+
+use futures::future::LocalBoxFuture;
+use xeno_registry_notifications::keys;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	recover,
+	{ description: "Load unsaved changes from a crashed session into the buffer" },
+	handler: cmd_recover
+);
+
+fn cmd_recover<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let path = ctx.editor.recover_swap_file()?;
+		ctx.editor.notify(keys::swap_recovered::call(&path));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	discard_swap,
+	{ aliases: &["discard-swap"], description: "Delete unsaved changes from a crashed session" },
+	handler: cmd_discard_swap
+);
+
+fn cmd_discard_swap<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor.discard_swap_file()?;
+		Ok(CommandOutcome::Ok)
+	})
+}
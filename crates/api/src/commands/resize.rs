@@ -0,0 +1,62 @@
+//! Split resizing commands.
+
+use futures::future::LocalBoxFuture;
+use xeno_base::direction::Axis;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor::Editor;
+use crate::editor_command;
+
+editor_command!(
+	resize,
+	{ aliases: &["res"], description: "Set the height of the current split" },
+	handler: cmd_resize
+);
+
+/// Handler for `:resize [N|+N|-N]`.
+fn cmd_resize<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { resize(ctx.editor, Axis::Horizontal, ctx.args.first().copied()) })
+}
+
+editor_command!(
+	vertical_resize,
+	{ aliases: &["vertical-resize", "vres"], description: "Set the width of the current split" },
+	handler: cmd_vertical_resize
+);
+
+/// Handler for `:vertical-resize [N|+N|-N]`.
+fn cmd_vertical_resize<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move { resize(ctx.editor, Axis::Vertical, ctx.args.first().copied()) })
+}
+
+/// Resizes the focused split along `axis`.
+///
+/// `N` sets the size, `+N` and `-N` grow or shrink by `N`, and no argument
+/// makes the split as large as its siblings allow.
+fn resize(
+	editor: &mut Editor,
+	axis: Axis,
+	arg: Option<&str>,
+) -> Result<CommandOutcome, CommandError> {
+	let invalid =
+		|arg: &str| CommandError::InvalidArgument(format!("expected a size, got '{arg}'"));
+	let resized = match arg {
+		None => editor.set_focused_view_size(axis, u16::MAX),
+		Some(arg) if arg.starts_with(['+', '-']) => {
+			let delta = arg.parse::<i32>().map_err(|_| invalid(arg))?;
+			editor.resize_focused_view(axis, delta)
+		}
+		Some(arg) => {
+			let size = arg.parse::<u16>().map_err(|_| invalid(arg))?;
+			editor.set_focused_view_size(axis, size)
+		}
+	};
+	if !resized {
+		return Err(CommandError::Failed("no split to resize".into()));
+	}
+	Ok(CommandOutcome::Ok)
+}
@@ -0,0 +1,41 @@
+//! Scratch buffer commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	new,
+	{ description: "Open an empty scratch buffer" },
+	handler: cmd_new
+);
+
+/// Handler for `:new`.
+fn cmd_new<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor.open_scratch(None);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	scratch,
+	{ description: "Open the named scratch buffer, creating it if needed" },
+	handler: cmd_scratch
+);
+
+/// Handler for `:scratch <name>`.
+fn cmd_scratch<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let Some(&name) = ctx.args.first() else {
+			return Err(CommandError::MissingArgument("scratch buffer name"));
+		};
+		ctx.editor.open_scratch(Some(name));
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,27 @@
+//! Project search commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::search::SEARCH_PANEL_ID;
+
+editor_command!(
+	grep,
+	{ aliases: &["search_project"], description: "Toggle the project search panel" },
+	handler: cmd_grep
+);
+
+/// Handler for `:grep`.
+///
+/// Opens and focuses the project search panel, or closes it if it is open.
+fn cmd_grep<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(SEARCH_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
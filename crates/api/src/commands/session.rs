@@ -0,0 +1,44 @@
+//! Session save and restore commands.
+
+use futures::future::LocalBoxFuture;
+use xeno_registry_notifications::keys;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	mksession,
+	{ description: "Save open files, selections, and layout as a session" },
+	handler: cmd_mksession
+);
+
+fn cmd_mksession<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let path = ctx
+			.editor
+			.save_session(ctx.args.first().copied())
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		ctx.editor.notify(keys::session_saved::call(&path));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+editor_command!(
+	load_session,
+	{ aliases: &["load-session"], description: "Restore a saved session" },
+	handler: cmd_load_session
+);
+
+fn cmd_load_session<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.load_session(ctx.args.first().copied())
+			.await
+			.map_err(|e| CommandError::Failed(e.to_string()))?;
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,28 @@
+//! Terminal commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::terminal::TERMINAL_PANEL_ID;
+
+editor_command!(
+	terminal,
+	{ aliases: &["term"], description: "Toggle the terminal panel" },
+	handler: cmd_terminal
+);
+
+/// Handler for `:terminal`.
+///
+/// Opens and focuses the terminal panel, starting the shell, or closes it
+/// and ends the shell if it is open.
+fn cmd_terminal<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(TERMINAL_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
@@ -0,0 +1,28 @@
+//! Undo tree commands.
+
+use futures::future::LocalBoxFuture;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+use crate::ui::UiRequest;
+use crate::ui::undotree::UNDOTREE_PANEL_ID;
+
+editor_command!(
+	undotree,
+	{ aliases: &["undo_tree"], description: "Toggle the undo tree panel" },
+	handler: cmd_undotree
+);
+
+/// Handler for `:undotree`.
+///
+/// Opens and focuses the undo tree of the focused document, or closes it if
+/// it is open.
+fn cmd_undotree<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		ctx.editor
+			.apply_ui_requests(vec![UiRequest::TogglePanel(UNDOTREE_PANEL_ID.to_string())]);
+		Ok(CommandOutcome::Ok)
+	})
+}
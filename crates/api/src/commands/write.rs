@@ -0,0 +1,28 @@
+//! Forced write command.
+
+use futures::future::LocalBoxFuture;
+use xeno_registry_notifications::keys;
+
+use super::{CommandError, CommandOutcome, EditorCommandContext};
+use crate::editor_command;
+
+editor_command!(
+	write_force,
+	{ aliases: &["write!", "w!"], description: "Write buffer to file, even if read-only" },
+	handler: cmd_write_force
+);
+
+/// Handler for `:write!`.
+///
+/// Writes past the read-only flag and changes on disk, lifting file
+/// permissions that deny the write for its duration.
+fn cmd_write_force<'a>(
+	ctx: &'a mut EditorCommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let buffer_id = ctx.editor.focused_view();
+		let path = ctx.editor.force_save_buffer(buffer_id).await?;
+		ctx.editor.notify(keys::file_saved::call(&path));
+		Ok(CommandOutcome::Ok)
+	})
+}
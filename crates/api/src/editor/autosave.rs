@@ -0,0 +1,163 @@
+//! Automatic saving of modified buffers.
+//!
+//! The `autosave` option picks when modified buffers are written: when the
+//! terminal loses focus, or once no edit has been made for a while. A save
+//! that falls due is carried out by [`Editor::run_autosave`] from the main
+//! loop, through the same path as `:write`.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+use xeno_registry::options::keys as opts;
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+
+/// When the `autosave` option saves modified buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutosaveMode {
+	/// Never.
+	Off,
+	/// When the terminal loses focus.
+	FocusLost,
+	/// After this long without an edit.
+	Idle(Duration),
+}
+
+impl AutosaveMode {
+	/// Parses an `autosave` option value; invalid values turn autosave off.
+	fn parse(value: &str) -> Self {
+		match value {
+			"focus-lost" => Self::FocusLost,
+			_ => value
+				.strip_prefix("idle:")
+				.and_then(|ms| ms.parse().ok())
+				.map_or(Self::Off, |ms| Self::Idle(Duration::from_millis(ms))),
+		}
+	}
+}
+
+/// Pending autosave state.
+#[derive(Debug, Default)]
+pub struct AutosaveState {
+	/// Whether modified buffers should be saved on the next run.
+	due: bool,
+	/// Time of the latest edit not yet covered by an idle autosave.
+	last_edit: Option<Instant>,
+}
+
+impl Editor {
+	/// Returns the current `autosave` mode.
+	fn autosave_mode(&self) -> AutosaveMode {
+		self.config
+			.global_options
+			.get_string(opts::AUTOSAVE.untyped())
+			.map_or(AutosaveMode::Off, AutosaveMode::parse)
+	}
+
+	/// Records that buffers were edited, restarting the idle autosave timer.
+	pub(super) fn note_autosave_edit(&mut self) {
+		self.autosave.last_edit = Some(Instant::now());
+	}
+
+	/// Marks an autosave due once the `idle:<ms>` delay has passed since the
+	/// last edit.
+	pub(super) fn update_idle_autosave(&mut self) {
+		let AutosaveMode::Idle(delay) = self.autosave_mode() else {
+			return;
+		};
+		if self
+			.autosave
+			.last_edit
+			.is_some_and(|edit| edit.elapsed() >= delay)
+		{
+			self.autosave.last_edit = None;
+			self.autosave.due = true;
+		}
+	}
+
+	/// Marks an autosave due when the terminal loses focus in `focus-lost`
+	/// mode.
+	pub(super) fn autosave_on_focus_lost(&mut self) {
+		if self.autosave_mode() == AutosaveMode::FocusLost {
+			self.autosave.due = true;
+		}
+	}
+
+	/// Saves every modified file buffer if an autosave is due.
+	///
+	/// Buffers that are read-only, still loading, locked by lossy decoding,
+	/// or holding swap file text awaiting an explicit write are skipped.
+	/// Failures are reported and the buffer stays modified.
+	pub async fn run_autosave(&mut self) {
+		if !std::mem::take(&mut self.autosave.due) {
+			return;
+		}
+
+		let mut paths = HashSet::new();
+		let candidates: Vec<_> = self
+			.buffers
+			.buffers()
+			.filter(|buffer| buffer.modified() && !buffer.is_readonly())
+			.filter_map(|buffer| Some((buffer.id, buffer.path()?)))
+			.filter(|(_, path)| paths.insert(path.clone()))
+			.map(|(id, _)| id)
+			.collect();
+
+		for buffer_id in candidates {
+			if self.loading_progress(buffer_id).is_some()
+				|| self.lossy_locked(buffer_id)
+				|| self.swap_needs_explicit_write(buffer_id)
+			{
+				continue;
+			}
+			if let Err(e) = self.save_buffer(buffer_id).await {
+				warn!(error = %e, "autosave failed");
+				self.notify(keys::autosave_failed::call(&e.to_string()));
+			}
+		}
+		self.frame.needs_redraw = true;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::options::OptionValue;
+
+	use super::*;
+
+	#[test]
+	fn autosave_modes_parse() {
+		assert_eq!(AutosaveMode::parse("off"), AutosaveMode::Off);
+		assert_eq!(AutosaveMode::parse("focus-lost"), AutosaveMode::FocusLost);
+		assert_eq!(
+			AutosaveMode::parse("idle:250"),
+			AutosaveMode::Idle(Duration::from_millis(250))
+		);
+		assert_eq!(AutosaveMode::parse("idle:soon"), AutosaveMode::Off);
+	}
+
+	#[tokio::test]
+	async fn focus_lost_saves_modified_buffers() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("a.txt");
+		std::fs::write(&path, "a\n").unwrap();
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		editor.insert_text("b");
+		editor.handle_focus_out();
+		editor.run_autosave().await;
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\n");
+
+		editor.config.global_options.set(
+			opts::AUTOSAVE.untyped(),
+			OptionValue::String("focus-lost".into()),
+		);
+		editor.handle_focus_out();
+		editor.run_autosave().await;
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "ba\n");
+		assert!(!editor.buffer().modified());
+	}
+}
@@ -4,7 +4,7 @@
 //! Focus state is mirrored from the [`Editor`] for compatibility.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use xeno_language::LanguageLoader;
 
@@ -89,12 +89,19 @@ impl BufferManager {
 	/// The new buffer has independent cursor/selection/scroll state but
 	/// edits in either buffer affect both (they share the same Document).
 	pub fn clone_focused_buffer_for_split(&mut self) -> BufferId {
+		self.clone_buffer_for_split(self.focused_view)
+			.expect("focused buffer must exist")
+	}
+
+	/// Creates a new buffer that shares the document of buffer `id`.
+	///
+	/// Returns `None` if there is no such buffer.
+	pub fn clone_buffer_for_split(&mut self, id: BufferId) -> Option<BufferId> {
 		let new_id = BufferId(self.next_buffer_id);
+		let new_buffer = self.buffers.get(&id)?.clone_for_split(new_id);
 		self.next_buffer_id += 1;
-
-		let new_buffer = self.focused_buffer().clone_for_split(new_id);
 		self.buffers.insert(new_id, new_buffer);
-		new_id
+		Some(new_id)
 	}
 
 	/// Removes a buffer. Does not update focus.
@@ -190,10 +197,25 @@ impl BufferManager {
 	/// Returns the first buffer that has a matching path. Note that multiple
 	/// buffers may share the same document (via splits), so this returns
 	/// just one of them.
-	pub fn find_by_path(&self, path: &std::path::Path) -> Option<BufferId> {
-		self.buffers
+	///
+	/// Paths match if they name the same file, so `a.txt` and `./a.txt` find
+	/// the same buffer.
+	pub fn find_by_path(&self, path: &Path) -> Option<BufferId> {
+		let wanted = normalize_path(path);
+		let mut matches: Vec<_> = self
+			.buffers
 			.values()
-			.find(|b| b.path().as_deref() == Some(path))
+			.filter(|b| b.path().is_some_and(|p| normalize_path(&p) == wanted))
 			.map(|b| b.id)
+			.collect();
+		matches.sort_unstable();
+		matches.first().copied()
 	}
 }
+
+/// Resolves `path` for comparison, following symlinks when the file exists.
+fn normalize_path(path: &Path) -> PathBuf {
+	std::fs::canonicalize(path)
+		.or_else(|_| std::path::absolute(path))
+		.unwrap_or_else(|_| path.to_path_buf())
+}
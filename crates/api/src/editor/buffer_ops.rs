@@ -2,15 +2,20 @@
 //!
 //! Opening files, creating buffers, and cloning for splits.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tracing::warn;
+use xeno_base::{Rope, Selection, Transaction};
+use xeno_registry::options::keys as opts;
 use xeno_registry::{
 	HookContext, HookEventData, emit as emit_hook, emit_sync_with as emit_hook_sync_with,
 };
+use xeno_registry_notifications::keys;
 
-use super::{Editor, is_writable};
-use crate::buffer::BufferId;
+use super::file_load::{large_file_size, open_large_file};
+use super::save_conflict::file_mtime;
+use super::{Editor, is_writable, read_file};
+use crate::buffer::{BufferId, Encoding, Scratch};
 
 impl Editor {
 	/// Opens a new buffer from content, optionally with a path.
@@ -18,12 +23,26 @@ impl Editor {
 	/// This async version awaits all hooks including async ones (e.g., LSP).
 	/// For sync contexts like split operations, use [`open_buffer_sync`](Self::open_buffer_sync).
 	pub async fn open_buffer(&mut self, content: String, path: Option<PathBuf>) -> BufferId {
+		self.open_buffer_as(content, path, false).await
+	}
+
+	/// Opens a new buffer, flagged as a large file if `large_file` is set so
+	/// no language server is attached.
+	async fn open_buffer_as(
+		&mut self,
+		content: String,
+		path: Option<PathBuf>,
+		large_file: bool,
+	) -> BufferId {
 		let buffer_id = self.buffers.create_buffer(
 			content,
 			path.clone(),
 			&self.config.language_loader,
 			self.viewport.width,
 		);
+		if large_file && let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+			buffer.doc_mut().large_file = true;
+		}
 
 		let scratch_path = PathBuf::from("[scratch]");
 		let hook_path = path.as_ref().unwrap_or(&scratch_path);
@@ -42,7 +61,8 @@ impl Editor {
 		.await;
 
 		#[cfg(feature = "lsp")]
-		if let Some(buffer) = self.buffers.get_buffer(buffer_id)
+		if !self.large_file_mode(buffer_id)
+			&& let Some(buffer) = self.buffers.get_buffer(buffer_id)
 			&& let Err(e) = self.lsp.on_buffer_open(buffer).await
 		{
 			warn!(error = %e, "LSP buffer open failed");
@@ -84,23 +104,214 @@ impl Editor {
 
 	/// Opens a file as a new buffer.
 	///
-	/// Returns the new buffer's ID, or an error if the file couldn't be read.
-	/// If the file exists but is not writable, the buffer is opened in readonly mode.
+	/// Returns the new buffer's ID, or an error if the file couldn't be read
+	/// or is binary. Invalid UTF-8 is decoded with the `encoding` option's
+	/// fallback. Files over `large-file-size` stream in after this returns.
 	pub async fn open_file(&mut self, path: PathBuf) -> anyhow::Result<BufferId> {
-		let content = match tokio::fs::read_to_string(&path).await {
-			Ok(s) => s,
-			Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
-			Err(e) => return Err(e.into()),
+		if let Some(size) = large_file_size(&path, &self.config.global_options).await {
+			let (file, head) = open_large_file(&path).await?;
+			let buffer_id = self
+				.open_buffer_as(String::new(), Some(path.clone()), true)
+				.await;
+			self.begin_file_load(buffer_id, path, size, file, head);
+			return Ok(buffer_id);
+		}
+
+		let fallback = self
+			.config
+			.global_options
+			.get_string(opts::ENCODING.untyped())
+			.and_then(Encoding::from_name)
+			.unwrap_or_default();
+		let decoded = read_file(&path, fallback).await?;
+		let buffer_id = self.open_buffer(decoded.text, Some(path.clone())).await;
+		self.finish_file_load(buffer_id, &path, decoded.encoding, decoded.lossy);
+		Ok(buffer_id)
+	}
+
+	/// Records how a freshly loaded file was decoded and reports anything
+	/// unusual about it.
+	///
+	/// If the file exists but is not writable, the buffer is opened in
+	/// readonly mode. Lossy decoding and mixed LF and CRLF endings (saved with
+	/// the majority ending) are reported, as is a swap file left by a crash.
+	/// The loaded text and the file's modification time are kept so a save
+	/// can tell whether the file changed underneath the buffer.
+	pub(super) fn finish_file_load(
+		&mut self,
+		buffer_id: BufferId,
+		path: &Path,
+		encoding: Encoding,
+		lossy: bool,
+	) {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		if path.exists() && !is_writable(path) {
+			buffer.set_readonly(true);
+		}
+		let mixed_ending = {
+			let mut doc = buffer.doc_mut();
+			doc.encoding = encoding;
+			doc.lossy = lossy;
+			doc.disk_base = Some(doc.content.clone());
+			doc.disk_mtime = file_mtime(path);
+			doc.mixed_line_endings.then(|| doc.line_ending.name())
 		};
 
-		let readonly = path.exists() && !is_writable(&path);
-		let buffer_id = self.open_buffer(content, Some(path)).await;
+		if lossy {
+			self.notify(keys::lossy_decode::call(path));
+		}
+		if let Some(ending) = mixed_ending {
+			self.notify(keys::mixed_line_endings::call(path, ending));
+		}
+		self.check_swap_file(buffer_id, path);
+	}
 
-		if readonly && let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+	/// Sets a buffer's file type to `name`, or re-detects it when `None`.
+	///
+	/// The file type selects per-language options and syntax highlighting.
+	/// A change is announced through the `buffer:filetype` hook.
+	pub fn set_file_type(&mut self, buffer_id: BufferId, name: Option<&str>) {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		let before = buffer.file_type();
+		match name {
+			Some(name) => buffer
+				.doc_mut()
+				.set_file_type(name, &self.config.language_loader),
+			None => buffer.init_syntax(&self.config.language_loader),
+		}
+		let (path, after) = (buffer.path(), buffer.file_type());
+		if self.large_file_mode(buffer_id) {
+			self.apply_large_file_mode(buffer_id);
+		}
+		self.frame.needs_redraw = true;
+		if before == after {
+			return;
+		}
+
+		self.refresh_line_numbers();
+		self.refresh_wrap();
+		let path = path.unwrap_or_else(|| PathBuf::from("[scratch]"));
+		emit_hook_sync_with(
+			&HookContext::new(
+				HookEventData::BufferFileType {
+					path: &path,
+					file_type: after.as_deref(),
+				},
+				Some(&self.extensions),
+			),
+			&mut self.hook_runtime,
+		);
+	}
+
+	/// Marks the document of every open buffer read-only.
+	pub fn set_all_readonly(&mut self) {
+		for buffer in self.buffers.buffers() {
 			buffer.set_readonly(true);
 		}
+		self.frame.needs_redraw = true;
+	}
 
-		Ok(buffer_id)
+	/// Shows a scratch buffer in the focused view, as `:new` and `:scratch`
+	/// do.
+	///
+	/// Without a `name` a new unnamed scratch buffer is opened; with one, the
+	/// scratch buffer of that name is reused if it is already open.
+	pub fn open_scratch(&mut self, name: Option<&str>) -> BufferId {
+		let buffer_id = match name {
+			Some(name) => self.scratch_buffer(name),
+			None => self.create_scratch_buffer(None),
+		};
+		self.show_buffer(buffer_id);
+		buffer_id
+	}
+
+	/// Returns the scratch buffer named `name`, opening it if needed.
+	pub fn scratch_buffer(&mut self, name: &str) -> BufferId {
+		self.buffers
+			.buffers()
+			.filter(|buffer| {
+				buffer
+					.scratch()
+					.is_some_and(|s| s.name.as_deref() == Some(name))
+			})
+			.map(|buffer| buffer.id)
+			.min()
+			.unwrap_or_else(|| self.create_scratch_buffer(Some(name)))
+	}
+
+	/// Replaces the text of the scratch buffer named `name` and shows it.
+	///
+	/// Lets commands that produce a report write it to a buffer instead of
+	/// a notification. The new text is not treated as an unsaved change.
+	pub fn show_in_scratch(&mut self, name: &str, text: &str) -> BufferId {
+		let buffer_id = self.scratch_buffer(name);
+		self.show_buffer(buffer_id);
+		let tx = {
+			let buffer = self
+				.buffers
+				.get_buffer(buffer_id)
+				.expect("scratch buffer exists");
+			Transaction::diff(&buffer.doc().content, &Rope::from(text))
+		};
+		self.save_undo_state();
+		self.apply_transaction_with_selection(buffer_id, &tx, Some(Selection::point(0)));
+		if let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+			buffer.set_modified(false);
+		}
+		buffer_id
+	}
+
+	/// Opens an empty scratch buffer without showing it.
+	fn create_scratch_buffer(&mut self, name: Option<&str>) -> BufferId {
+		let buffer_id = self.open_buffer_sync(String::new(), None);
+		if let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+			buffer.doc_mut().scratch = Some(Scratch {
+				name: name.map(String::from),
+				persistent: false,
+			});
+		}
+		buffer_id
+	}
+
+	/// Opens each file into its own buffer and focuses the first.
+	///
+	/// A path that is already open reuses its buffer. A missing path opens an
+	/// empty buffer with a notification, and the file is created on write.
+	/// A 1-based line number moves that buffer's cursor to the line. Files
+	/// that fail to load are reported and skipped.
+	pub async fn open_files(&mut self, files: impl IntoIterator<Item = (PathBuf, Option<usize>)>) {
+		let mut first = None;
+		for (path, line) in files {
+			let id = match self.buffers.find_by_path(&path) {
+				Some(id) => id,
+				None => match self.open_file(path.clone()).await {
+					Ok(id) => id,
+					Err(e) => {
+						self.notify(keys::file_load_error::call(&format!(
+							"{}: {e}",
+							path.display()
+						)));
+						continue;
+					}
+				},
+			};
+			if !path.exists() {
+				self.notify(keys::new_file::call(&path));
+			}
+			if let Some(line) = line {
+				self.show_buffer(id);
+				self.goto_line_col(line.saturating_sub(1), 0);
+			}
+			first.get_or_insert(id);
+		}
+
+		if let Some(id) = first {
+			self.show_buffer(id);
+		}
 	}
 
 	/// Creates a new buffer that shares the same document as the current buffer.
@@ -118,7 +329,8 @@ impl Editor {
 	#[cfg(feature = "lsp")]
 	pub async fn init_lsp_for_open_buffers(&mut self) -> anyhow::Result<()> {
 		for buffer_id in self.buffers.buffer_ids().collect::<Vec<_>>() {
-			if let Some(buffer) = self.buffers.get_buffer(buffer_id)
+			if !self.large_file_mode(buffer_id)
+				&& let Some(buffer) = self.buffers.get_buffer(buffer_id)
 				&& buffer.path().is_some()
 				&& let Err(e) = self.lsp.on_buffer_open(buffer).await
 			{
@@ -134,3 +346,83 @@ impl Editor {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn open_files_reuses_buffers_and_cycles() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let (a, b) = (dir.join("a.txt"), dir.join("b.txt"));
+		let mut editor = Editor::new_scratch();
+
+		editor
+			.open_files([(a.clone(), None), (b.clone(), Some(3)), (a.clone(), None)])
+			.await;
+		assert_eq!(editor.buffer_count(), 3);
+		assert_eq!(editor.buffer().path(), Some(a.clone()));
+
+		editor.focus_next_buffer();
+		assert_eq!(editor.buffer().path(), Some(b.clone()));
+		editor.focus_next_buffer();
+		assert_eq!(editor.buffer().path(), None);
+		editor.focus_prev_buffer();
+		assert_eq!(editor.buffer().path(), Some(b));
+		assert_eq!(
+			editor.base_window().layout.views(),
+			vec![editor.focused_view()]
+		);
+	}
+
+	#[test]
+	fn file_type_detects_from_content_and_can_be_overridden() {
+		let mut editor = Editor::new_scratch();
+		let id = editor.focused_view();
+		editor.insert_text("#!/usr/bin/env python3\nprint(1)\n");
+		editor.set_file_type(id, None);
+		assert_eq!(editor.buffer().file_type().as_deref(), Some("python"));
+
+		editor.set_file_type(id, Some("notes"));
+		assert_eq!(editor.buffer().file_type().as_deref(), Some("notes"));
+		assert!(!editor.buffer().has_syntax());
+
+		editor.set_file_type(id, None);
+		assert_eq!(editor.buffer().file_type().as_deref(), Some("python"));
+	}
+
+	#[test]
+	fn scratch_buffers_are_named_and_do_not_block_quit() {
+		let mut editor = Editor::new_scratch();
+		let notes = editor.open_scratch(Some("notes"));
+		assert_eq!(editor.focused_view(), notes);
+		assert_eq!(editor.buffer().display_name(), "[scratch:notes]");
+
+		editor.insert_text("draft");
+		assert!(editor.buffer().modified());
+		assert!(!editor.buffer().needs_save());
+		editor
+			.buffer()
+			.doc_mut()
+			.scratch
+			.as_mut()
+			.unwrap()
+			.persistent = true;
+		assert!(editor.buffer().needs_save());
+
+		let unnamed = editor.open_scratch(None);
+		assert_ne!(unnamed, notes);
+		assert_eq!(editor.buffer().display_name(), "[scratch]");
+		assert_eq!(editor.open_scratch(Some("notes")), notes);
+	}
+
+	#[test]
+	fn show_in_scratch_replaces_text() {
+		let mut editor = Editor::new_scratch();
+		let id = editor.show_in_scratch("registers", "a: one\n");
+		assert_eq!(editor.show_in_scratch("registers", "b: two\n"), id);
+		assert_eq!(editor.buffer().doc().content.to_string(), "b: two\n");
+		assert!(!editor.buffer().modified());
+	}
+}
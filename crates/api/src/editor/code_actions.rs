@@ -0,0 +1,204 @@
+//! Code actions from language servers.
+//!
+//! `:code_action` lists the actions the buffer's server offers for the
+//! primary selection, or the cursor, with the diagnostics there as context.
+//! They are listed in a popup keyed `1`-`9` and then `a`-`z`; picking one
+//! queues `:code_action N`, which asks again and applies the Nth action.
+//!
+//! Applying an action makes its edit, first filled in with
+//! `codeAction/resolve` if the server listed the action without one, and
+//! then runs its command with `workspace/executeCommand`.
+
+use std::ops::Range;
+
+use xeno_lsp::OffsetEncoding;
+use xeno_lsp::lsp_types::{CodeActionOrCommand, CodeActionResponse};
+
+use super::{Editor, WorkspaceEditSummary};
+use crate::prompt::{ConfirmPopup, ConfirmResult};
+
+/// Keys picking the listed actions, in order.
+const ACTION_KEYS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+/// What applying a code action did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeActionOutcome {
+	/// Title of the action.
+	pub title: String,
+	/// What its edit changed.
+	pub summary: WorkspaceEditSummary,
+	/// Whether its command was run on the server.
+	pub ran_command: bool,
+}
+
+impl Editor {
+	/// Asks the focused buffer's server for the code actions at the primary
+	/// selection, or the cursor when the selection is a bare cursor.
+	///
+	/// Returns the actions along with the offset encoding their edits use;
+	/// the list is empty if no server handles the buffer.
+	pub async fn request_code_actions(
+		&self,
+	) -> Result<(CodeActionResponse, OffsetEncoding), String> {
+		let buffer = self.buffer();
+		let primary = buffer.selection.primary();
+		let range: Range<usize> = if primary.is_empty() {
+			buffer.cursor..buffer.cursor
+		} else {
+			primary.from()..primary.to()
+		};
+		let response = self
+			.lsp
+			.code_actions(buffer, range)
+			.await
+			.map_err(|e| e.to_string())?;
+		Ok(response.unwrap_or((Vec::new(), OffsetEncoding::Utf16)))
+	}
+
+	/// Lists `actions` in a popup; picking one queues `:code_action` with
+	/// its number.
+	///
+	/// Only as many actions as there are keys are listed. Disabled actions
+	/// show why they cannot be applied.
+	pub fn open_code_actions(&mut self, actions: &[CodeActionOrCommand]) {
+		let mut popup = ConfirmPopup::new("Code actions", "", |editor, result| {
+			if let ConfirmResult::Chosen(key) = result
+				&& let Some(index) = ACTION_KEYS.find(key)
+			{
+				editor
+					.workspace
+					.command_queue
+					.push("code_action", vec![(index + 1).to_string()]);
+			}
+		})
+		.stacked();
+		for (key, action) in ACTION_KEYS.chars().zip(actions) {
+			popup = popup.choice(key, action_label(action));
+		}
+		self.open_confirm(popup);
+	}
+
+	/// Applies a code action: its edit, resolved first if the server listed
+	/// the action without one, and then its command.
+	///
+	/// Nothing changes if the action is disabled or its edit cannot be
+	/// applied; see [`Editor::apply_workspace_edit`].
+	pub async fn apply_code_action(
+		&mut self,
+		action: CodeActionOrCommand,
+		encoding: OffsetEncoding,
+	) -> Result<CodeActionOutcome, String> {
+		let (title, edit, command) = match action {
+			CodeActionOrCommand::Command(command) => (command.title.clone(), None, Some(command)),
+			CodeActionOrCommand::CodeAction(action) => {
+				if let Some(disabled) = &action.disabled {
+					return Err(format!("{}: {}", action.title, disabled.reason));
+				}
+				let action = if action.edit.is_none() {
+					self.lsp
+						.resolve_code_action(self.buffer(), action)
+						.await
+						.map_err(|e| e.to_string())?
+				} else {
+					action
+				};
+				(action.title, action.edit, action.command)
+			}
+		};
+		if edit.is_none() && command.is_none() {
+			return Err(format!("{title}: nothing to apply"));
+		}
+
+		let summary = match edit {
+			Some(edit) => self.apply_workspace_edit(edit, encoding).await?,
+			None => WorkspaceEditSummary::default(),
+		};
+		let ran_command = match command {
+			Some(command) => {
+				let ran = self
+					.lsp
+					.execute_command(self.buffer(), command)
+					.await
+					.map_err(|e| format!("{title}: {e}"))?;
+				if !ran {
+					return Err(format!("{title}: language server cannot run commands"));
+				}
+				true
+			}
+			None => false,
+		};
+		Ok(CodeActionOutcome {
+			title,
+			summary,
+			ran_command,
+		})
+	}
+}
+
+/// Returns the label listing `action`, with the reason it is disabled.
+fn action_label(action: &CodeActionOrCommand) -> String {
+	match action {
+		CodeActionOrCommand::Command(command) => command.title.clone(),
+		CodeActionOrCommand::CodeAction(action) => match &action.disabled {
+			Some(disabled) => format!("{} (disabled: {})", action.title, disabled.reason),
+			None => action.title.clone(),
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_lsp::lsp_types::{CodeAction, CodeActionDisabled, Command};
+
+	use super::*;
+
+	#[test]
+	fn labels_show_why_actions_are_disabled() {
+		let command = CodeActionOrCommand::Command(Command {
+			title: "Run test".into(),
+			command: "run".into(),
+			arguments: None,
+		});
+		let disabled = CodeActionOrCommand::CodeAction(CodeAction {
+			title: "Extract function".into(),
+			disabled: Some(CodeActionDisabled {
+				reason: "no expression selected".into(),
+			}),
+			..Default::default()
+		});
+		assert_eq!(action_label(&command), "Run test");
+		assert_eq!(
+			action_label(&disabled),
+			"Extract function (disabled: no expression selected)"
+		);
+	}
+
+	#[tokio::test]
+	async fn disabled_and_empty_actions_change_nothing() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("let x = 1;\n");
+		let disabled = CodeActionOrCommand::CodeAction(CodeAction {
+			title: "Inline".into(),
+			disabled: Some(CodeActionDisabled {
+				reason: "not a variable".into(),
+			}),
+			..Default::default()
+		});
+		let error = editor
+			.apply_code_action(disabled, OffsetEncoding::Utf8)
+			.await
+			.unwrap_err();
+		assert_eq!(error, "Inline: not a variable");
+
+		let empty = CodeActionOrCommand::CodeAction(CodeAction {
+			title: "Nothing".into(),
+			..Default::default()
+		});
+		let error = editor
+			.apply_code_action(empty, OffsetEncoding::Utf8)
+			.await
+			.unwrap_err();
+		assert_eq!(error, "Nothing: nothing to apply");
+		assert_eq!(editor.buffer().doc().content.to_string(), "let x = 1;\n");
+	}
+}
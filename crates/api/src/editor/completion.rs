@@ -0,0 +1,97 @@
+//! Completion menu integration with editor.
+
+use termina::event::{MouseButton, MouseEvent, MouseEventKind};
+use xeno_base::{ScrollDirection, Selection};
+use xeno_core::CompletionItem;
+
+use super::Editor;
+use crate::editor::types::CompletionState;
+
+impl Editor {
+	/// Returns whether the completion menu is open.
+	pub(crate) fn completion_active(&self) -> bool {
+		self.overlays
+			.get::<CompletionState>()
+			.is_some_and(|state| state.active)
+	}
+
+	/// Closes the completion menu, dropping its items, along with any
+	/// language server completion behind it.
+	pub(crate) fn close_completion(&mut self) {
+		*self.overlays.get_or_default::<CompletionState>() = CompletionState::default();
+		#[cfg(feature = "lsp")]
+		self.overlays
+			.get_or_default::<crate::editor::lsp_completion::LspCompletionState>()
+			.close();
+		self.frame.needs_redraw = true;
+	}
+
+	/// Accepts the selected completion, replacing the text from the menu's
+	/// replace start to the cursor with the item's insert text, expanded
+	/// if it is a snippet. Language server items make their own edits.
+	///
+	/// Closes the menu and returns the accepted item, or `None` without
+	/// editing if the menu is closed or nothing is selected.
+	pub(crate) fn accept_completion(&mut self) -> Option<CompletionItem> {
+		#[cfg(feature = "lsp")]
+		if self.lsp_completion_open() {
+			return self.accept_lsp_completion();
+		}
+		let state = self.overlays.get::<CompletionState>()?;
+		let item = state
+			.active
+			.then(|| state.selected_idx.and_then(|i| state.items.get(i)))
+			.flatten()
+			.cloned()?;
+		let replace_start = state.replace_start;
+		self.close_completion();
+
+		if replace_start < self.buffer().cursor {
+			let cursor = self.buffer().cursor;
+			self.buffer_mut()
+				.set_selection(Selection::single(replace_start, cursor));
+			self.delete_selection();
+		}
+		if item.snippet {
+			self.insert_snippet(&item.insert_text);
+		} else {
+			self.insert_text(&item.insert_text);
+		}
+		Some(item)
+	}
+
+	/// Handles a mouse event over the completion menu.
+	///
+	/// Hovering an item selects it, a left click accepts it as Enter would
+	/// and the wheel scrolls the list. A click outside the menu closes it
+	/// but is not consumed, so it still reaches whatever is underneath.
+	/// Returns `true` if the event was consumed.
+	pub(crate) fn handle_completion_mouse(&mut self, mouse: MouseEvent) -> bool {
+		if !self.completion_active() {
+			return false;
+		}
+		let state = self.overlays.get_or_default::<CompletionState>();
+		let item = state.item_at(mouse.column, mouse.row);
+		let inside = state.area.contains((mouse.column, mouse.row).into());
+
+		match mouse.kind {
+			MouseEventKind::Down(MouseButton::Left) if item.is_some() => {
+				state.selected_idx = item;
+				self.accept_completion();
+				return true;
+			}
+			MouseEventKind::Down(_) if !inside => {
+				self.close_completion();
+				return false;
+			}
+			MouseEventKind::Moved if item.is_some() && item != state.selected_idx => {
+				state.selected_idx = item;
+			}
+			MouseEventKind::ScrollUp if inside => state.scroll(ScrollDirection::Up, 1),
+			MouseEventKind::ScrollDown if inside => state.scroll(ScrollDirection::Down, 1),
+			_ => return inside,
+		}
+		self.frame.needs_redraw = true;
+		true
+	}
+}
@@ -0,0 +1,153 @@
+//! Jumping between the diagnostics of a buffer.
+//!
+//! Jumps use the document's prepared diagnostics, so they land where the
+//! diagnostics are drawn even after edits the server has not caught up
+//! with. Each jump shows the messages at its target in a popup anchored at
+//! the cursor, which closes on the next key like any unpinned info popup.
+
+use xeno_base::Selection;
+use xeno_registry_notifications::keys;
+
+use super::{Editor, JumpLocation};
+use crate::info_popup::PopupAnchor;
+
+/// Severity of error diagnostics, the highest.
+pub const ERROR_SEVERITY: u8 = 4;
+
+impl Editor {
+	/// Moves the cursor `count` diagnostics forward, or backward, in the
+	/// focused buffer, wrapping around the ends with a notification.
+	///
+	/// Only diagnostics at least as severe as `min_severity` count; pass
+	/// [`ERROR_SEVERITY`] to visit errors only. Returns false if there is
+	/// no such diagnostic.
+	pub fn goto_diagnostic(&mut self, forward: bool, count: usize, min_severity: u8) -> bool {
+		let cursor = self.buffer().cursor;
+		let mut starts: Vec<usize> = self
+			.buffer()
+			.doc()
+			.diagnostics
+			.iter()
+			.filter(|d| d.severity >= min_severity)
+			.map(|d| d.start)
+			.collect();
+		starts.sort_unstable();
+		starts.dedup();
+		let Some((target, wrapped)) = step_through(&starts, cursor, forward, count.max(1)) else {
+			return false;
+		};
+
+		self.workspace.jump_list.push(JumpLocation {
+			buffer_id: self.focused_view(),
+			cursor,
+		});
+		let buffer = self.buffer_mut();
+		buffer.set_cursor(target);
+		buffer.set_selection(Selection::point(target));
+		if self.cursor_screen_position().is_none() {
+			self.center_cursor_line();
+		}
+		if wrapped {
+			self.notify(keys::diagnostics_wrapped::call(forward));
+		}
+		self.show_diagnostics_at(target, min_severity);
+		true
+	}
+
+	/// Shows the messages of the diagnostics starting at `pos` in a popup
+	/// anchored at the cursor.
+	fn show_diagnostics_at(&mut self, pos: usize, min_severity: u8) {
+		let messages: Vec<String> = self
+			.buffer()
+			.doc()
+			.diagnostics
+			.iter()
+			.filter(|d| d.start == pos && d.severity >= min_severity)
+			.map(|d| d.message.clone())
+			.collect();
+		if messages.is_empty() {
+			return;
+		}
+		let anchor = self
+			.cursor_screen_position()
+			.map_or(PopupAnchor::Center, |pos| PopupAnchor::Cursor {
+				x: pos.x,
+				y: pos.y,
+			});
+		self.open_info_popup(messages.join("\n"), None, anchor);
+	}
+}
+
+/// Returns the position `count` steps from `cursor` through the sorted,
+/// deduplicated `starts`, and whether the steps wrapped around an end.
+fn step_through(
+	starts: &[usize],
+	cursor: usize,
+	forward: bool,
+	count: usize,
+) -> Option<(usize, bool)> {
+	let len = starts.len();
+	if len == 0 {
+		return None;
+	}
+	let index = if forward {
+		starts.partition_point(|&s| s <= cursor) + count - 1
+	} else {
+		// Index counted from the end, so moving back is moving up.
+		len - starts.partition_point(|&s| s < cursor) + count - 1
+	};
+	let wrapped = index >= len;
+	let index = index % len;
+	let index = if forward { index } else { len - 1 - index };
+	Some((starts[index], wrapped))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::buffer::PreparedDiagnostic;
+
+	#[test]
+	fn steps_wrap_around_both_ends() {
+		let starts = [4, 10, 20];
+		assert_eq!(step_through(&starts, 0, true, 1), Some((4, false)));
+		assert_eq!(step_through(&starts, 4, true, 2), Some((20, false)));
+		assert_eq!(step_through(&starts, 20, true, 1), Some((4, true)));
+		assert_eq!(step_through(&starts, 10, false, 1), Some((4, false)));
+		assert_eq!(step_through(&starts, 12, false, 3), Some((20, true)));
+		assert_eq!(step_through(&[], 12, false, 3), None);
+	}
+
+	#[test]
+	fn errors_only_skips_warnings() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("let a = 1;\nlet b = 2;\nlet c = 3;\n");
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(0));
+		buffer.sync_cursor_to_selection();
+		let diagnostic = |start, severity| PreparedDiagnostic {
+			start,
+			end: start + 1,
+			severity,
+			message: format!("at {start}"),
+			source: None,
+		};
+		editor
+			.buffer()
+			.doc_mut()
+			.diagnostics
+			.replace(vec![diagnostic(15, 3), diagnostic(26, ERROR_SEVERITY)]);
+
+		assert!(editor.goto_diagnostic(true, 1, 1));
+		assert_eq!(editor.buffer().cursor, 15);
+		assert!(editor.goto_diagnostic(true, 1, ERROR_SEVERITY));
+		assert_eq!(editor.buffer().cursor, 26);
+		assert!(editor.goto_diagnostic(false, 1, ERROR_SEVERITY));
+		assert_eq!(editor.buffer().cursor, 26);
+		let record = editor.notification_history.records().last().unwrap();
+		assert_eq!(record.id, "diagnostics_wrapped");
+
+		editor.buffer().doc_mut().diagnostics.clear();
+		assert!(!editor.goto_diagnostic(true, 1, 1));
+	}
+}
@@ -0,0 +1,299 @@
+//! Highlighting the occurrences of the symbol under the cursor.
+//!
+//! While `document-highlight` is on, the focused buffer's language server
+//! is asked for the occurrences of the symbol under the cursor each time
+//! the `editor:idle` hook fires. The answer is shown until the text changes
+//! or the cursor leaves every occurrence; answers to an older version of
+//! the text, or that no longer hold the cursor, are dropped.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::oneshot;
+use tracing::debug;
+use xeno_base::range::CharIdx;
+use xeno_base::{Range, Selection};
+use xeno_lsp::lsp_types::{DocumentHighlight, DocumentHighlightKind};
+use xeno_lsp::{OffsetEncoding, lsp_range_to_char_range};
+use xeno_registry::hook;
+use xeno_registry::options::keys;
+
+use super::Editor;
+use super::extensions::ExtensionMap;
+use crate::buffer::BufferId;
+use crate::render::SymbolHighlights;
+
+/// Response to a document highlight request, with the offset encoding of
+/// its positions.
+type Response = xeno_lsp::Result<Option<(Vec<DocumentHighlight>, OffsetEncoding)>>;
+
+/// Editor extension recording that the `editor:idle` hook fired since the
+/// last tick asked.
+#[derive(Default)]
+pub struct DocumentHighlightIdle(AtomicBool);
+
+hook!(
+	document_highlight_idle,
+	EditorIdle,
+	100,
+	"Request the occurrences of the symbol under the cursor",
+	|ctx| {
+		if let Some(idle) = ctx
+			.extensions::<ExtensionMap>()
+			.and_then(|ext| ext.get::<DocumentHighlightIdle>())
+		{
+			idle.0.store(true, Ordering::Relaxed);
+		}
+	}
+);
+
+/// A request in flight for the occurrences under the cursor.
+struct InFlight {
+	/// Buffer the request was made through.
+	buffer: BufferId,
+	/// Document version the request was made at.
+	version: u64,
+	/// Receives the response.
+	rx: oneshot::Receiver<Response>,
+}
+
+/// Occurrences shown in one buffer.
+struct Shown {
+	/// Buffer they are shown in.
+	buffer: BufferId,
+	/// Document version they belong to.
+	version: u64,
+	/// Occurrences in document order, each with whether it writes to the
+	/// symbol.
+	ranges: Vec<(Range, bool)>,
+}
+
+impl Shown {
+	/// Returns whether the cursor at `pos` is on one of the occurrences,
+	/// counting the position just after one.
+	fn holds(&self, pos: CharIdx) -> bool {
+		self.ranges
+			.iter()
+			.any(|(range, _)| range.from() <= pos && pos <= range.to())
+	}
+}
+
+/// Overlay state for document highlights.
+#[derive(Default)]
+pub(crate) struct DocumentHighlightState {
+	/// Latest request, if its response has not arrived.
+	in_flight: Option<InFlight>,
+	/// Occurrences currently highlighted.
+	shown: Option<Shown>,
+}
+
+impl Editor {
+	/// Takes in a response, drops highlights the cursor or an edit left
+	/// behind, and sends a request once the editor has been idle.
+	pub(crate) fn poll_document_highlight(&mut self) {
+		let mut changed = self.receive_document_highlight();
+
+		let buffer_id = self.focused_view();
+		let (cursor, version) = {
+			let buffer = self.buffer();
+			(buffer.cursor, buffer.version())
+		};
+		let state = self.overlays.get_or_default::<DocumentHighlightState>();
+		if state.shown.as_ref().is_some_and(|shown| {
+			shown.buffer != buffer_id || shown.version != version || !shown.holds(cursor)
+		}) {
+			state.shown = None;
+			changed = true;
+		}
+
+		let idle = self
+			.extensions
+			.get::<DocumentHighlightIdle>()
+			.is_some_and(|idle| idle.0.swap(false, Ordering::Relaxed));
+		if idle
+			&& state.shown.is_none()
+			&& self.resolve_typed_option(buffer_id, keys::DOCUMENT_HIGHLIGHT)
+			&& !self.large_file_mode(buffer_id)
+		{
+			match self.lsp.document_highlight(self.buffer()) {
+				Ok(Some(request)) => {
+					let (tx, rx) = oneshot::channel();
+					tokio::spawn(async move {
+						let _ = tx.send(request.await);
+					});
+					let state = self.overlays.get_or_default::<DocumentHighlightState>();
+					state.in_flight = Some(InFlight {
+						buffer: buffer_id,
+						version,
+						rx,
+					});
+				}
+				Ok(None) => {}
+				Err(e) => debug!(error = %e, "document highlight request failed"),
+			}
+		}
+
+		if changed {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Takes in the response if it has arrived. Returns whether the
+	/// highlights changed.
+	fn receive_document_highlight(&mut self) -> bool {
+		let state = self.overlays.get_or_default::<DocumentHighlightState>();
+		let Some(mut in_flight) = state.in_flight.take() else {
+			return false;
+		};
+		let response = match in_flight.rx.try_recv() {
+			Ok(response) => response,
+			Err(oneshot::error::TryRecvError::Empty) => {
+				state.in_flight = Some(in_flight);
+				return false;
+			}
+			Err(oneshot::error::TryRecvError::Closed) => return false,
+		};
+		let (highlights, encoding) = match response {
+			Ok(Some(response)) => response,
+			Ok(None) => return false,
+			Err(e) => {
+				debug!(error = %e, "document highlight request failed");
+				return false;
+			}
+		};
+
+		let Some(buffer) = self.buffers.get_buffer(in_flight.buffer) else {
+			return false;
+		};
+		let doc = buffer.doc();
+		// Positions in a response to an older version would land on the
+		// wrong text.
+		if doc.version != in_flight.version {
+			return false;
+		}
+		let mut ranges: Vec<(Range, bool)> = highlights
+			.iter()
+			.map(|highlight| {
+				let (from, to) = lsp_range_to_char_range(&doc.content, highlight.range, encoding);
+				(
+					Range::new(from, to),
+					highlight.kind == Some(DocumentHighlightKind::WRITE),
+				)
+			})
+			.filter(|(range, _)| !range.is_empty())
+			.collect();
+		ranges.sort_by_key(|(range, _)| range.from());
+		ranges.dedup_by(|next, prev| next.0.from() < prev.0.to());
+
+		let shown = Shown {
+			buffer: in_flight.buffer,
+			version: in_flight.version,
+			ranges,
+		};
+		if shown.ranges.is_empty() || !shown.holds(buffer.cursor) {
+			return false;
+		}
+		drop(doc);
+		self.overlays
+			.get_or_default::<DocumentHighlightState>()
+			.shown = Some(shown);
+		true
+	}
+
+	/// Returns the occurrences highlighted in `buffer_id`, for rendering.
+	pub(crate) fn symbol_highlights_for(&self, buffer_id: BufferId) -> SymbolHighlights {
+		let Some(shown) = self
+			.overlays
+			.get::<DocumentHighlightState>()
+			.and_then(|state| state.shown.as_ref())
+			.filter(|shown| shown.buffer == buffer_id)
+		else {
+			return SymbolHighlights::default();
+		};
+		SymbolHighlights {
+			ranges: shown.ranges.clone(),
+		}
+	}
+
+	/// Selects every highlighted occurrence in the focused buffer, the one
+	/// under the cursor becoming the primary selection.
+	///
+	/// Returns whether there were occurrences to select.
+	pub(crate) fn select_symbol_highlights(&mut self) -> bool {
+		let buffer_id = self.focused_view();
+		let ranges = self.symbol_highlights_for(buffer_id).ranges;
+		if ranges.is_empty() {
+			return false;
+		}
+		let cursor = self.buffer().cursor;
+		let primary = ranges
+			.iter()
+			.position(|(range, _)| range.from() <= cursor && cursor <= range.to())
+			.unwrap_or(0);
+		let selection = Selection::from_vec(
+			ranges.into_iter().map(|(range, _)| range).collect(),
+			primary,
+		);
+		let buffer = self.buffer_mut();
+		buffer.set_selection(selection);
+		buffer.sync_cursor_to_selection();
+		self.frame.needs_redraw = true;
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn show(editor: &mut Editor, ranges: Vec<(Range, bool)>) {
+		let buffer = editor.focused_view();
+		let version = editor.buffer().version();
+		editor
+			.overlays
+			.get_or_default::<DocumentHighlightState>()
+			.shown = Some(Shown {
+			buffer,
+			version,
+			ranges,
+		});
+	}
+
+	#[test]
+	fn highlights_clear_when_the_cursor_leaves_them() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("let a = 1; a + b");
+		editor.buffer_mut().set_selection(Selection::point(4));
+		editor.buffer_mut().sync_cursor_to_selection();
+		show(
+			&mut editor,
+			vec![(Range::new(4, 5), true), (Range::new(11, 12), false)],
+		);
+
+		editor.poll_document_highlight();
+		let focused = editor.focused_view();
+		assert_eq!(editor.symbol_highlights_for(focused).ranges.len(), 2);
+
+		editor.buffer_mut().set_selection(Selection::point(15));
+		editor.buffer_mut().sync_cursor_to_selection();
+		editor.poll_document_highlight();
+		assert!(editor.symbol_highlights_for(focused).ranges.is_empty());
+	}
+
+	#[test]
+	fn selecting_highlights_keeps_the_cursor_primary() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("let a = 1; a + b");
+		assert!(!editor.select_symbol_highlights());
+
+		editor.buffer_mut().set_selection(Selection::point(11));
+		editor.buffer_mut().sync_cursor_to_selection();
+		show(
+			&mut editor,
+			vec![(Range::new(4, 5), true), (Range::new(11, 12), false)],
+		);
+		assert!(editor.select_symbol_highlights());
+		let selection = &editor.buffer().selection;
+		assert_eq!(selection.len(), 2);
+		assert_eq!(selection.primary(), Range::new(11, 12));
+	}
+}
@@ -10,15 +10,19 @@
 //! 3. Text transformation
 //! 4. Post-effects (mode change, cursor adjustment)
 
-use xeno_base::range::{Direction as MoveDir, Range};
-use xeno_base::{Selection, Transaction};
+use xeno_base::graphemes::{next_grapheme_boundary, prev_grapheme_boundary};
+use xeno_base::range::{CharIdx, Direction as MoveDir, Range};
+use xeno_base::{RopeSlice, Selection, Transaction};
 use xeno_core::editor_ctx::ModeAccess;
-use xeno_core::movement;
+use xeno_core::{WordType, movement};
 use xeno_registry::edit_op::{
 	CharMapKind, CursorAdjust, EditOp, PostEffect, PreEffect, SelectionOp, TextTransform,
 };
+use xeno_registry::options::keys;
+use xeno_registry_notifications::keys as notify;
 
 use super::Editor;
+use crate::buffer::soft_tab_start;
 
 impl Editor {
 	/// Executes a data-oriented edit operation.
@@ -164,8 +168,11 @@ impl Editor {
 			}
 
 			SelectionOp::SelectCharBefore => {
+				let soft_tab = (!self.option(keys::USE_TABS)).then(|| self.tab_width());
 				let (ranges, primary_index) = {
 					let buffer = self.buffer();
+					let doc = buffer.doc();
+					let text = doc.content.slice(..);
 					let mut ranges = Vec::new();
 					let mut primary_index = 0usize;
 					for (idx, range) in buffer.selection.ranges().iter().enumerate() {
@@ -175,7 +182,10 @@ impl Editor {
 						if idx == buffer.selection.primary_index() {
 							primary_index = ranges.len();
 						}
-						ranges.push(Range::new(range.head - 1, range.head));
+						let start = soft_tab
+							.and_then(|width| soft_tab_start(text, range.head, width))
+							.unwrap_or_else(|| prev_grapheme_boundary(text, range.head));
+						ranges.push(Range::new(start, range.head));
 					}
 					(ranges, primary_index)
 				};
@@ -186,6 +196,25 @@ impl Editor {
 				}
 			}
 
+			SelectionOp::SelectWordBefore => {
+				self.select_back_from_heads(|text, head| {
+					let range = movement::move_to_prev_word_start(
+						text,
+						Range::point(head),
+						1,
+						WordType::Word,
+						false,
+					);
+					range.head
+				});
+			}
+
+			SelectionOp::SelectToLineStart => {
+				self.select_back_from_heads(|text, head| {
+					text.line_to_char(text.char_to_line(head))
+				});
+			}
+
 			SelectionOp::SelectToNextLineStart => {
 				let (selection, valid) = {
 					let buffer = self.buffer();
@@ -209,15 +238,12 @@ impl Editor {
 				let new_ranges: Vec<_> = {
 					let buffer = self.buffer();
 					let doc = buffer.doc();
-					let len = doc.content.len_chars();
+					let text = doc.content.slice(..);
 					buffer
 						.selection
 						.ranges()
 						.iter()
-						.map(|r| {
-							let new_pos = (r.head + 1).min(len);
-							Range::point(new_pos)
-						})
+						.map(|r| Range::point(next_grapheme_boundary(text, r.head)))
 						.collect()
 				};
 				let primary_index = self.buffer().selection.primary_index();
@@ -227,19 +253,38 @@ impl Editor {
 		}
 	}
 
+	/// Replaces every range with one running from `start(head)` forward to
+	/// its head, leaving a point where there is nothing before the head.
+	fn select_back_from_heads(&mut self, start: impl Fn(RopeSlice, CharIdx) -> CharIdx) {
+		let (ranges, primary_index) = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			let text = doc.content.slice(..);
+			let ranges: Vec<_> = buffer
+				.selection
+				.ranges()
+				.iter()
+				.map(|r| Range::new(start(text, r.head).min(r.head), r.head))
+				.collect();
+			(ranges, buffer.selection.primary_index())
+		};
+		self.buffer_mut()
+			.set_selection(Selection::from_vec(ranges, primary_index));
+	}
+
 	/// Applies a text transformation to the current selection.
 	fn apply_text_transform(&mut self, transform: &TextTransform) {
 		match transform {
 			TextTransform::None => {}
 
 			TextTransform::Delete => {
-				if self.buffer().selection.primary().is_empty() {
+				if self.buffer().selection.ranges().iter().all(Range::is_empty) {
 					return;
 				}
 				if !self.guard_readonly() {
 					return;
 				}
-				self.save_undo_state();
+				self.save_edit_undo_state();
 				let (tx, new_sel) = {
 					let buffer = self.buffer();
 					let doc = buffer.doc();
@@ -272,6 +317,21 @@ impl Editor {
 				self.insert_text(text);
 			}
 
+			TextTransform::InsertNewline => {
+				self.insert_newline();
+			}
+
+			TextTransform::OpenLineAbove => {
+				self.open_line_above();
+			}
+
+			TextTransform::InsertRegister(name) => {
+				match self.workspace.registers.get(*name).map(str::to_owned) {
+					Some(text) => self.insert_text(&text),
+					None => self.notify(notify::register_empty::call(*name)),
+				}
+			}
+
 			TextTransform::MapChars(kind) => {
 				self.apply_char_mapping(*kind);
 			}
@@ -521,3 +581,118 @@ impl Editor {
 		self.buffer_mut().set_cursor(new_cursor);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::edit_op;
+
+	use super::*;
+
+	fn editor_with(text: &str, cursor: usize) -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text(text);
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(cursor));
+		buffer.sync_cursor_to_selection();
+		editor
+	}
+
+	fn content(editor: &Editor) -> String {
+		editor.buffer().doc().content.to_string()
+	}
+
+	#[test]
+	fn delete_back_removes_whole_cluster() {
+		for (text, cursor, expected) in [
+			("ae\u{301}b", 3, "ab"),
+			("a👍🏽b", 3, "ab"),
+			("a👩\u{200d}👩\u{200d}👧b", 6, "ab"),
+		] {
+			let mut editor = editor_with(text, cursor);
+			editor.execute_edit_op(edit_op::delete_back());
+			assert_eq!(content(&editor), expected, "backspace in {text:?}");
+			assert_eq!(editor.buffer().selection.primary().head, 1);
+		}
+	}
+
+	/// Builds an editor in insert mode with a cursor at each of `cursors`.
+	fn inserting_at(text: &str, cursors: &[usize]) -> Editor {
+		let mut editor = editor_with(text, 0);
+		let ranges = cursors.iter().map(|&c| Range::point(c)).collect();
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::from_vec(ranges, 0));
+		buffer.sync_cursor_to_selection();
+		buffer.input.set_mode(xeno_base::Mode::Insert);
+		editor
+	}
+
+	fn heads(editor: &Editor) -> Vec<usize> {
+		editor
+			.buffer()
+			.selection
+			.ranges()
+			.iter()
+			.map(|r| r.head)
+			.collect()
+	}
+
+	#[test]
+	fn delete_word_backward_at_each_cursor() {
+		let mut editor = inserting_at("foo bar  \nbaz qux", &[9, 17]);
+		editor.execute_edit_op(edit_op::delete_word_backward());
+		assert_eq!(content(&editor), "foo \nbaz ");
+		assert_eq!(heads(&editor), vec![4, 9]);
+	}
+
+	#[test]
+	fn delete_to_line_start_at_each_cursor() {
+		let mut editor = inserting_at("alpha\nbeta\n", &[3, 6]);
+		editor.execute_edit_op(edit_op::delete_to_line_start());
+		assert_eq!(content(&editor), "ha\nbeta\n");
+		assert_eq!(heads(&editor), vec![0, 3]);
+	}
+
+	#[test]
+	fn insert_register_at_each_cursor() {
+		let mut editor = inserting_at("a\nb", &[1, 3]);
+		editor.workspace.registers.yank = "xy".into();
+		editor.execute_edit_op(edit_op::insert_register('"'));
+		assert_eq!(content(&editor), "axy\nbxy");
+
+		editor.execute_edit_op(edit_op::insert_register('q'));
+		assert_eq!(content(&editor), "axy\nbxy");
+	}
+
+	#[test]
+	fn insert_literal_at_each_cursor() {
+		let mut editor = inserting_at("a\nb", &[1, 3]);
+		editor.execute_edit_op(edit_op::insert_literal('\t'));
+		assert_eq!(content(&editor), "a\t\nb\t");
+		assert_eq!(heads(&editor), vec![2, 5]);
+	}
+
+	#[test]
+	fn insert_chords_join_the_insert_undo_group() {
+		let mut editor = inserting_at("", &[0]);
+		editor.insert_text("one two");
+		editor.execute_edit_op(edit_op::delete_word_backward());
+		editor.workspace.registers.yank = "!".into();
+		editor.execute_edit_op(edit_op::insert_register('"'));
+		assert_eq!(content(&editor), "one !");
+
+		editor.buffer_mut().input.set_mode(xeno_base::Mode::Normal);
+		editor.end_undo_group();
+		editor.undo();
+		assert_eq!(content(&editor), "");
+	}
+
+	#[test]
+	fn delete_forward_removes_whole_cluster() {
+		for text in ["ae\u{301}b", "a👍🏽b", "a👩\u{200d}👩\u{200d}👧b"] {
+			let mut editor = editor_with(text, 1);
+			editor.execute_edit_op(edit_op::delete(false));
+			assert_eq!(content(&editor), "ab", "delete in {text:?}");
+			assert_eq!(editor.buffer().selection.primary().head, 1);
+		}
+	}
+}
@@ -2,10 +2,15 @@
 //!
 //! Insert, delete, yank, paste, and transaction application.
 
+use xeno_base::range::{CharIdx, Range};
+use xeno_base::transaction::Change;
 use xeno_base::{Selection, Transaction};
+use xeno_language::DEFAULT_INDENT_OPEN_TOKENS;
+use xeno_registry::options::keys as opts;
 use xeno_registry_notifications::keys;
 
 use super::Editor;
+use crate::buffer::{line_indent, newline_indent};
 
 impl Editor {
 	pub(crate) fn guard_readonly(&mut self) -> bool {
@@ -13,10 +18,26 @@ impl Editor {
 			self.notify(keys::buffer_readonly);
 			return false;
 		}
+		if self.lossy_locked(self.focused_view()) {
+			self.notify(keys::lossy_buffer);
+			return false;
+		}
 		true
 	}
 
-	fn apply_transaction_with_selection(
+	/// Returns whether `buffer_id` was decoded lossily and
+	/// `allow-lossy-write` has not unlocked it.
+	///
+	/// Editing or saving such a buffer would replace the file's invalid
+	/// UTF-8 with U+FFFD.
+	pub(crate) fn lossy_locked(&self, buffer_id: crate::buffer::BufferId) -> bool {
+		self.buffers
+			.get_buffer(buffer_id)
+			.is_some_and(|buffer| buffer.doc().lossy)
+			&& !self.resolve_typed_option(buffer_id, opts::ALLOW_LOSSY_WRITE)
+	}
+
+	pub(super) fn apply_transaction_with_selection(
 		&mut self,
 		buffer_id: crate::buffer::BufferId,
 		tx: &Transaction,
@@ -63,12 +84,30 @@ impl Editor {
 
 		if applied {
 			self.sync_sibling_selections(tx);
+			self.snap_document_selections(buffer_id);
 			self.frame.dirty_buffers.insert(buffer_id);
 		}
 
 		applied
 	}
 
+	/// Snaps the selections of every view of `buffer_id`'s document into the
+	/// current text.
+	///
+	/// Edits that arrive without a mapped selection, such as plugin or LSP
+	/// workspace edits, can leave ranges past the end of the document or
+	/// inside a grapheme cluster.
+	fn snap_document_selections(&mut self, buffer_id: crate::buffer::BufferId) {
+		let Some(doc_id) = self.buffers.get_buffer(buffer_id).map(|b| b.document_id()) else {
+			return;
+		};
+		for buffer in self.buffers.buffers_mut() {
+			if buffer.document_id() == doc_id {
+				buffer.ensure_valid_selection();
+			}
+		}
+	}
+
 	/// Inserts text at the current cursor position(s).
 	pub fn insert_text(&mut self, text: &str) {
 		let buffer_id = self.focused_view();
@@ -77,11 +116,7 @@ impl Editor {
 			return;
 		}
 
-		if self.buffer().mode() == xeno_base::Mode::Insert {
-			self.save_insert_undo_state();
-		} else {
-			self.save_undo_state();
-		}
+		self.save_edit_undo_state();
 
 		// Prepare the transaction and new selection (without applying)
 		let (tx, new_selection) = {
@@ -99,6 +134,132 @@ impl Editor {
 		}
 	}
 
+	/// Inserts a line break at each cursor, indenting the new line when
+	/// `autoindent` is on.
+	pub fn insert_newline(&mut self) {
+		if !self.option(opts::AUTOINDENT) {
+			self.insert_text("\n");
+			return;
+		}
+		let unit = self.indent_unit();
+		let open_tokens = self.indent_open_tokens();
+		let edits = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			let text = doc.content.slice(..);
+			buffer
+				.selection
+				.ranges()
+				.iter()
+				.map(|r| {
+					let insert = format!("\n{}", newline_indent(text, r.head, &unit, &open_tokens));
+					let len = insert.chars().count();
+					(r.head, insert, len)
+				})
+				.collect()
+		};
+		self.insert_at_cursors(edits);
+	}
+
+	/// Opens a line above each cursor with the cursor line's indentation.
+	pub fn open_line_above(&mut self) {
+		let edits = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			let text = doc.content.slice(..);
+			let autoindent = self.option(opts::AUTOINDENT);
+			buffer
+				.selection
+				.ranges()
+				.iter()
+				.map(|r| {
+					let line = text.char_to_line(r.head);
+					let indent = if autoindent {
+						line_indent(text, line)
+					} else {
+						String::new()
+					};
+					let len = indent.chars().count();
+					(text.line_to_char(line), format!("{indent}\n"), len)
+				})
+				.collect()
+		};
+		self.insert_at_cursors(edits);
+	}
+
+	/// Returns the text of one indent level for the focused buffer.
+	pub(crate) fn indent_unit(&self) -> String {
+		if self.option(opts::USE_TABS) {
+			"\t".to_string()
+		} else {
+			" ".repeat(self.tab_width())
+		}
+	}
+
+	/// Returns the line endings that open an indent level for the focused
+	/// buffer, or none when `smart-indent` is off.
+	fn indent_open_tokens(&self) -> Vec<String> {
+		if !self.option(opts::SMART_INDENT) {
+			return Vec::new();
+		}
+		let loader = &self.config.language_loader;
+		self.buffer()
+			.file_type()
+			.and_then(|ft| loader.language_for_name(&ft))
+			.and_then(|lang| loader.get(lang))
+			.map(|data| data.indent_open_tokens.clone())
+			.unwrap_or_else(|| {
+				DEFAULT_INDENT_OPEN_TOKENS
+					.iter()
+					.map(|t| t.to_string())
+					.collect()
+			})
+	}
+
+	/// Inserts text at positions derived from each cursor in one transaction.
+	///
+	/// Each edit is `(position, text, cursor offset)`, one per selection range
+	/// in order; that range becomes a cursor `offset` chars into its text.
+	fn insert_at_cursors(&mut self, edits: Vec<(CharIdx, String, usize)>) {
+		if !self.guard_readonly() {
+			return;
+		}
+		let buffer_id = self.focused_view();
+		self.save_edit_undo_state();
+
+		let (tx, new_selection) = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			let mut order: Vec<_> = (0..edits.len()).collect();
+			order.sort_by_key(|&i| edits[i].0);
+
+			let mut cursors = vec![0; edits.len()];
+			let mut shift = 0;
+			for &i in &order {
+				let (pos, text, offset) = &edits[i];
+				cursors[i] = pos + shift + offset;
+				shift += text.chars().count();
+			}
+			let tx = Transaction::change(
+				doc.content.slice(..),
+				order.iter().map(|&i| Change {
+					start: edits[i].0,
+					end: edits[i].0,
+					replacement: Some(edits[i].1.as_str().into()),
+				}),
+			);
+			let ranges = cursors.into_iter().map(Range::point).collect();
+			(
+				tx,
+				Selection::from_vec(ranges, buffer.selection.primary_index()),
+			)
+		};
+
+		if !self.apply_transaction_with_selection(buffer_id, &tx, Some(new_selection)) {
+			self.notify(keys::buffer_readonly);
+		}
+	}
+
 	/// Copies the current selection to the yank register.
 	pub fn yank_selection(&mut self) {
 		if let Some((text, count)) = self.buffer_mut().yank_selection() {
@@ -207,6 +368,10 @@ impl Editor {
 
 	/// Applies a transaction to the focused buffer.
 	pub fn apply_transaction(&mut self, tx: &Transaction) {
+		if self.lossy_locked(self.focused_view()) {
+			self.notify(keys::lossy_buffer);
+			return;
+		}
 		let buffer_id = self.focused_view();
 		let applied = self.apply_transaction_with_selection(buffer_id, tx, None);
 		if !applied {
@@ -226,3 +391,127 @@ impl Editor {
 		buffer.reparse_syntax(&self.config.language_loader);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn deleting_all_selected_text_snaps_selections() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("keep this\nthen drop all of this");
+		{
+			let buffer = editor.buffer_mut();
+			buffer.set_selection(Selection::new(
+				Range::new(15, 19),
+				vec![Range::new(24, 26), Range::new(28, 31)],
+			));
+			buffer.sync_cursor_to_selection();
+		}
+
+		let tx = {
+			let doc = editor.buffer().doc();
+			Transaction::change(
+				doc.content.slice(..),
+				[Change {
+					start: 9,
+					end: doc.content.len_chars(),
+					replacement: None,
+				}],
+			)
+		};
+		editor.apply_transaction(&tx);
+
+		let buffer = editor.buffer();
+		assert_eq!(buffer.doc().content.to_string(), "keep this");
+		assert_eq!(buffer.selection.ranges(), &[Range::point(9)]);
+		assert_eq!(buffer.cursor, 9);
+	}
+
+	#[test]
+	fn replaced_content_snaps_into_graphemes() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("plain text");
+		{
+			let buffer = editor.buffer_mut();
+			buffer.set_selection(Selection::single(2, 8));
+			buffer.sync_cursor_to_selection();
+		}
+
+		editor
+			.buffer_mut()
+			.replace_content(xeno_base::Rope::from("e\u{301}e\u{301}"));
+
+		let buffer = editor.buffer();
+		assert_eq!(buffer.selection.ranges(), &[Range::new(2, 4)]);
+		assert_eq!(buffer.cursor, 4);
+	}
+
+	fn inserting(text: &str, cursors: &[usize]) -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text(text);
+		let buffer = editor.buffer_mut();
+		let ranges = cursors.iter().map(|&c| Range::point(c)).collect();
+		buffer.set_selection(Selection::from_vec(ranges, 0));
+		buffer.sync_cursor_to_selection();
+		buffer.input.set_mode(xeno_base::Mode::Insert);
+		editor
+	}
+
+	fn state(editor: &Editor) -> (String, Vec<usize>) {
+		let buffer = editor.buffer();
+		let heads = buffer.selection.ranges().iter().map(|r| r.head).collect();
+		(buffer.doc().content.to_string(), heads)
+	}
+
+	fn set_option(editor: &mut Editor, key: xeno_registry::options::OptionKey, value: bool) {
+		editor
+			.config
+			.global_options
+			.set(key, xeno_registry::options::OptionValue::Bool(value));
+	}
+
+	#[test]
+	fn newline_copies_and_opens_indent_per_cursor() {
+		let mut editor = inserting("a {\n  b", &[3, 7]);
+		editor.insert_newline();
+		assert_eq!(state(&editor), ("a {\n    \n  b\n  ".into(), vec![8, 15]));
+	}
+
+	#[test]
+	fn newline_without_autoindent_is_bare() {
+		let mut editor = inserting("  x {", &[5]);
+		set_option(&mut editor, opts::AUTOINDENT.untyped(), false);
+		editor.insert_newline();
+		assert_eq!(state(&editor), ("  x {\n".into(), vec![6]));
+	}
+
+	#[test]
+	fn open_lines_use_autoindent() {
+		use xeno_registry::edit_op;
+
+		let mut editor = inserting("  x:", &[1]);
+		editor.execute_edit_op(edit_op::open_below());
+		assert_eq!(state(&editor), ("  x:\n      ".into(), vec![11]));
+
+		let mut editor = inserting("  x:", &[3]);
+		editor.execute_edit_op(edit_op::open_above());
+		assert_eq!(state(&editor), ("  \n  x:".into(), vec![2]));
+	}
+
+	#[test]
+	fn backspace_removes_soft_tab_level() {
+		use xeno_registry::edit_op;
+
+		let mut editor = inserting("      x", &[6]);
+		editor.execute_edit_op(edit_op::delete_back());
+		assert_eq!(state(&editor), ("    x".into(), vec![4]));
+		editor.execute_edit_op(edit_op::delete_back());
+		assert_eq!(state(&editor), ("x".into(), vec![0]));
+
+		let mut editor = inserting("    x", &[4]);
+		set_option(&mut editor, opts::USE_TABS.untyped(), true);
+		editor.execute_edit_op(edit_op::delete_back());
+		assert_eq!(state(&editor), ("   x".into(), vec![3]));
+	}
+}
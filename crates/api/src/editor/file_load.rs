@@ -0,0 +1,410 @@
+//! Large-file mode and background file loading.
+//!
+//! Files over `large-file-size` are read on a background task and appended
+//! to their buffer a few chunks per tick, so the first frame is drawn before
+//! the whole file is in memory. The buffer stays read-only until loading
+//! finishes. Such buffers open in large-file mode, which drops syntax
+//! highlighting, language servers, and search match counts and caps undo
+//! history; `:set large-file off` restores all but the language server.
+
+use std::path::{Path, PathBuf};
+
+use tokio::io::AsyncReadExt;
+use tokio::sync::mpsc;
+use xeno_registry::options::{OptionStore, keys as opts};
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+use super::types::MAX_UNDO_NODES;
+use crate::buffer::{
+	BINARY_FILE_ERROR, BINARY_SNIFF_LEN, BufferId, Encoding, EndingCounts, is_binary,
+};
+
+/// Bytes read from disk per chunk.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Chunks appended to a buffer per editor tick.
+const CHUNKS_PER_TICK: usize = 4;
+
+/// Undo nodes kept for a buffer in large-file mode.
+const LARGE_FILE_UNDO_NODES: usize = 100;
+
+/// A file being streamed into its buffer.
+pub struct FileLoad {
+	/// Buffer receiving the content.
+	buffer_id: BufferId,
+	/// File being read.
+	path: PathBuf,
+	/// File size when loading started.
+	size: u64,
+	/// Bytes received so far.
+	read: u64,
+	/// Chunks from the reader task, closed at end of file.
+	chunks: mpsc::Receiver<std::io::Result<Vec<u8>>>,
+	/// Decoder carrying state across chunk boundaries.
+	decoder: ChunkDecoder,
+}
+
+/// Incremental UTF-8 decoder that normalizes line endings.
+#[derive(Debug, Default)]
+struct ChunkDecoder {
+	/// Bytes held back from the previous chunk: a UTF-8 sequence cut by the
+	/// chunk boundary, or a `\r` that may start a `\r\n`.
+	carry: Vec<u8>,
+	/// Line endings seen so far.
+	endings: EndingCounts,
+	/// Whether invalid UTF-8 was replaced with U+FFFD.
+	lossy: bool,
+}
+
+impl ChunkDecoder {
+	/// Decodes as much of `chunk` as is complete.
+	fn push(&mut self, chunk: &[u8]) -> String {
+		let mut bytes = std::mem::take(&mut self.carry);
+		bytes.extend_from_slice(chunk);
+
+		let mut cut = bytes.len();
+		if bytes.last() == Some(&b'\r') {
+			cut -= 1;
+		}
+		if let Some(start) = (cut.saturating_sub(3)..cut)
+			.rev()
+			.find(|&i| bytes[i] & 0xC0 != 0x80)
+		{
+			let len = match bytes[start] {
+				b if b >= 0xF0 => 4,
+				b if b >= 0xE0 => 3,
+				b if b >= 0xC0 => 2,
+				_ => 1,
+			};
+			if start + len > cut {
+				cut = start;
+			}
+		}
+		self.carry = bytes.split_off(cut);
+		self.decode(bytes)
+	}
+
+	/// Decodes whatever was held back at end of file.
+	fn finish(&mut self) -> String {
+		let bytes = std::mem::take(&mut self.carry);
+		self.decode(bytes)
+	}
+
+	/// Decodes complete bytes, replacing invalid UTF-8.
+	fn decode(&mut self, bytes: Vec<u8>) -> String {
+		let text = String::from_utf8(bytes).unwrap_or_else(|e| {
+			self.lossy = true;
+			String::from_utf8_lossy(e.as_bytes()).into_owned()
+		});
+		self.endings.strip(text)
+	}
+}
+
+/// Returns the size of `path` if it is over the `large-file-size` in
+/// `global_options`.
+pub(super) async fn large_file_size(path: &Path, global_options: &OptionStore) -> Option<u64> {
+	let threshold = global_options
+		.get_int(opts::LARGE_FILE_SIZE.untyped())
+		.unwrap_or_else(|| {
+			(opts::LARGE_FILE_SIZE.def().default)()
+				.as_int()
+				.unwrap_or(0)
+		});
+	if threshold <= 0 {
+		return None;
+	}
+	let size = tokio::fs::metadata(path).await.ok()?.len();
+	(size > threshold as u64 * 1024 * 1024).then_some(size)
+}
+
+/// Opens a large file and reads its first bytes, failing if it is binary.
+pub(super) async fn open_large_file(path: &Path) -> anyhow::Result<(tokio::fs::File, Vec<u8>)> {
+	let mut file = tokio::fs::File::open(path).await?;
+	let mut head = vec![0; BINARY_SNIFF_LEN];
+	let mut filled = 0;
+	while filled < head.len() {
+		match file.read(&mut head[filled..]).await? {
+			0 => break,
+			n => filled += n,
+		}
+	}
+	head.truncate(filled);
+	if is_binary(&head) {
+		anyhow::bail!(BINARY_FILE_ERROR);
+	}
+	Ok((file, head))
+}
+
+impl Editor {
+	/// Returns whether `buffer_id` is in large-file mode.
+	pub fn large_file_mode(&self, buffer_id: BufferId) -> bool {
+		self.buffers
+			.get_buffer(buffer_id)
+			.is_some_and(|buffer| buffer.doc().large_file)
+			&& self.resolve_typed_option(buffer_id, opts::LARGE_FILE)
+	}
+
+	/// Returns how much of `buffer_id`'s file has loaded, in percent, while
+	/// it is still loading.
+	pub fn loading_progress(&self, buffer_id: BufferId) -> Option<u8> {
+		self.file_loads
+			.iter()
+			.find(|load| load.buffer_id == buffer_id)
+			.map(|load| (load.read * 100 / load.size.max(1)).min(100) as u8)
+	}
+
+	/// Marks a freshly created buffer as a large file and starts streaming
+	/// `file` into it, beginning with the already read `head`.
+	pub(super) fn begin_file_load(
+		&mut self,
+		buffer_id: BufferId,
+		path: PathBuf,
+		size: u64,
+		mut file: tokio::fs::File,
+		head: Vec<u8>,
+	) {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		{
+			let mut doc = buffer.doc_mut();
+			doc.large_file = true;
+			doc.loading = true;
+		}
+
+		let (tx, chunks) = mpsc::channel(CHUNKS_PER_TICK * 2);
+		tokio::spawn(async move {
+			if tx.send(Ok(head)).await.is_err() {
+				return;
+			}
+			loop {
+				let mut chunk = vec![0; CHUNK_SIZE];
+				let result = match file.read(&mut chunk).await {
+					Ok(0) => return,
+					Ok(n) => {
+						chunk.truncate(n);
+						Ok(chunk)
+					}
+					Err(e) => Err(e),
+				};
+				let failed = result.is_err();
+				if tx.send(result).await.is_err() || failed {
+					return;
+				}
+			}
+		});
+
+		self.notify(keys::large_file::call(&path, size));
+		self.file_loads.push(FileLoad {
+			buffer_id,
+			path,
+			size,
+			read: 0,
+			chunks,
+			decoder: ChunkDecoder::default(),
+		});
+		self.apply_large_file_mode(buffer_id);
+		self.frame.needs_redraw = true;
+	}
+
+	/// Appends chunks received since the last tick to their buffers and
+	/// finishes loads that reached end of file.
+	pub(super) fn poll_file_loads(&mut self) {
+		let mut loads = std::mem::take(&mut self.file_loads);
+		loads.retain_mut(|load| {
+			let Some(buffer) = self.buffers.get_buffer(load.buffer_id) else {
+				return false;
+			};
+			for _ in 0..CHUNKS_PER_TICK {
+				match load.chunks.try_recv() {
+					Ok(Ok(chunk)) => {
+						load.read += chunk.len() as u64;
+						let text = load.decoder.push(&chunk);
+						let mut doc = buffer.doc_mut();
+						let end = doc.content.len_chars();
+						doc.content.insert(end, &text);
+					}
+					Ok(Err(e)) => {
+						// Detach the partial content so `:write` cannot truncate the
+						// file, and keep it read-only.
+						{
+							let mut doc = buffer.doc_mut();
+							doc.path = None;
+							doc.loading = false;
+							doc.readonly = true;
+						}
+						self.notify(keys::file_load_error::call(&format!(
+							"{}: {e}",
+							load.path.display()
+						)));
+						return false;
+					}
+					Err(mpsc::error::TryRecvError::Empty) => return true,
+					Err(mpsc::error::TryRecvError::Disconnected) => {
+						self.finish_streamed_load(load);
+						return false;
+					}
+				}
+			}
+			true
+		});
+		loads.append(&mut self.file_loads);
+		self.file_loads = loads;
+		if !self.file_loads.is_empty() {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Completes a load that reached end of file.
+	fn finish_streamed_load(&mut self, load: &mut FileLoad) {
+		let Some(buffer) = self.buffers.get_buffer(load.buffer_id) else {
+			return;
+		};
+		let text = load.decoder.finish();
+		{
+			let mut doc = buffer.doc_mut();
+			let end = doc.content.len_chars();
+			doc.content.insert(end, &text);
+			doc.line_ending = load.decoder.endings.majority();
+			doc.mixed_line_endings = load.decoder.endings.mixed();
+			doc.loading = false;
+		}
+		self.finish_file_load(
+			load.buffer_id,
+			&load.path,
+			Encoding::Utf8,
+			load.decoder.lossy,
+		);
+		self.apply_large_file_mode(load.buffer_id);
+		self.frame.needs_redraw = true;
+	}
+
+	/// Re-applies large-file mode, including its wrapping override, to every
+	/// large-file buffer after the `large-file` option changes.
+	pub(crate) fn refresh_large_file_modes(&mut self) {
+		for buffer_id in self.buffer_ids() {
+			self.apply_large_file_mode(buffer_id);
+		}
+		self.refresh_wrap();
+	}
+
+	/// Drops or restores syntax highlighting and undo depth to match whether
+	/// `buffer_id` is in large-file mode.
+	pub(super) fn apply_large_file_mode(&mut self, buffer_id: BufferId) {
+		let large = self.large_file_mode(buffer_id);
+		let loading = self.loading_progress(buffer_id).is_some();
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		if !buffer.doc().large_file {
+			return;
+		}
+		if large {
+			let mut doc = buffer.doc_mut();
+			doc.syntax = None;
+			doc.history.set_limit(LARGE_FILE_UNDO_NODES);
+		} else {
+			buffer.doc_mut().history.set_limit(MAX_UNDO_NODES);
+			if !loading && !buffer.has_syntax() {
+				buffer.init_syntax(&self.config.language_loader);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::options::OptionValue;
+
+	use super::*;
+
+	#[test]
+	fn decoder_joins_split_sequences() {
+		let text = "é\r\n€";
+		let bytes = text.as_bytes();
+		for cut in 0..=bytes.len() {
+			let mut decoder = ChunkDecoder::default();
+			let mut out = decoder.push(&bytes[..cut]);
+			out += &decoder.push(&bytes[cut..]);
+			out += &decoder.finish();
+			assert_eq!(out, "é\n€", "split at {cut}");
+			assert!(!decoder.lossy);
+			assert_eq!(decoder.endings.majority(), crate::buffer::LineEnding::Crlf);
+		}
+	}
+
+	#[test]
+	fn decoder_replaces_invalid_bytes() {
+		let mut decoder = ChunkDecoder::default();
+		let mut out = decoder.push(b"a\xff");
+		out += &decoder.finish();
+		assert_eq!(out, "a\u{FFFD}");
+		assert!(decoder.lossy);
+	}
+
+	#[tokio::test]
+	async fn large_files_stream_in_and_degrade() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("big.log");
+		let line = "0123456789abcdef\r\n";
+		let content = line.repeat(1024 * 1024 / line.len() + 1);
+		std::fs::write(&path, &content).unwrap();
+
+		let mut editor = Editor::new_scratch();
+		editor
+			.config
+			.global_options
+			.set(opts::LARGE_FILE_SIZE.untyped(), OptionValue::Int(1));
+		let id = editor.open_file(path.clone()).await.unwrap();
+		assert!(editor.large_file_mode(id));
+		assert!(editor.buffers.get_buffer(id).unwrap().is_readonly());
+
+		while editor.loading_progress(id).is_some() {
+			editor.tick();
+			tokio::task::yield_now().await;
+		}
+		let buffer = editor.buffers.get_buffer(id).unwrap();
+		assert!(!buffer.is_readonly());
+		assert_eq!(
+			buffer.doc().content.len_bytes(),
+			content.len() - content.matches('\r').count()
+		);
+		assert_eq!(buffer.doc().line_ending, crate::buffer::LineEnding::Crlf);
+
+		editor
+			.buffers
+			.get_buffer_mut(id)
+			.unwrap()
+			.local_options
+			.set(opts::LARGE_FILE.untyped(), OptionValue::Bool(false));
+		editor.refresh_large_file_modes();
+		assert!(!editor.large_file_mode(id));
+	}
+
+	#[tokio::test]
+	async fn read_only_survives_streaming() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("big.log");
+		std::fs::write(&path, "0123456789abcdef\n".repeat(128 * 1024)).unwrap();
+
+		let mut editor = Editor::new_scratch();
+		editor
+			.config
+			.global_options
+			.set(opts::LARGE_FILE_SIZE.untyped(), OptionValue::Int(1));
+		let id = editor.open_file(path.clone()).await.unwrap();
+		// As `--read-only` does once the files are open.
+		editor.set_all_readonly();
+
+		while editor.loading_progress(id).is_some() {
+			editor.tick();
+			tokio::task::yield_now().await;
+		}
+		let buffer = editor.buffers.get_buffer(id).unwrap();
+		assert!(buffer.doc().readonly);
+		assert!(buffer.is_readonly());
+	}
+}
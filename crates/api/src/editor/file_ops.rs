@@ -2,13 +2,201 @@
 //!
 //! Implements [`FileOpsAccess`] for the [`Editor`].
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tracing::warn;
 use xeno_registry::commands::CommandError;
 use xeno_registry::{HookContext, HookEventData, emit as emit_hook};
 
 use super::Editor;
+use super::save_conflict::file_mtime;
+use crate::buffer::BufferId;
+
+impl Editor {
+	/// Writes a buffer to its file through the full save path.
+	///
+	/// Tells language servers about the save and applies the edits they ask
+	/// for, formats the buffer if `format-on-save` is on, runs the write
+	/// hooks, applies the buffer's line ending and encoding, and removes the
+	/// buffer's swap file. Returns the path
+	/// written. Refuses to write a read-only buffer, or to overwrite a file
+	/// that changed on disk since the buffer last loaded or saved it.
+	pub async fn save_buffer(&mut self, buffer_id: BufferId) -> Result<PathBuf, CommandError> {
+		self.ensure_writable(buffer_id)?;
+		if let Some(change) = self.disk_change(buffer_id).await? {
+			return Err(change.error());
+		}
+		self.write_buffer(buffer_id, false).await
+	}
+
+	/// Writes a buffer to its file even if it is read-only or the file
+	/// changed on disk, as `:write!` does.
+	///
+	/// A file whose permissions deny writing is made writable for the write
+	/// and has its permissions restored afterwards.
+	pub async fn force_save_buffer(
+		&mut self,
+		buffer_id: BufferId,
+	) -> Result<PathBuf, CommandError> {
+		self.write_buffer(buffer_id, true).await
+	}
+
+	/// Fails if a buffer is read-only, pointing at `:write!`.
+	fn ensure_writable(&self, buffer_id: BufferId) -> Result<(), CommandError> {
+		if self
+			.buffers
+			.get_buffer(buffer_id)
+			.is_some_and(|buffer| buffer.is_readonly())
+		{
+			return Err(CommandError::Failed(
+				"buffer is read-only; use :write! to write anyway".to_string(),
+			));
+		}
+		Ok(())
+	}
+
+	/// Writes a buffer to its file without checking it for changes on disk.
+	///
+	/// With `force`, permissions denying the write are lifted for it.
+	pub(crate) async fn write_buffer(
+		&mut self,
+		buffer_id: BufferId,
+		force: bool,
+	) -> Result<PathBuf, CommandError> {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return Err(CommandError::Failed(format!("no buffer {}", buffer_id.0)));
+		};
+		let Some(path_owned) = buffer.path() else {
+			return Err(CommandError::InvalidArgument(
+				"No filename. Use :write <filename>".to_string(),
+			));
+		};
+
+		if self.loading_progress(buffer_id).is_some() {
+			return Err(CommandError::Failed("file is still loading".to_string()));
+		}
+		if self.lossy_locked(buffer_id) {
+			return Err(CommandError::Failed(
+				"buffer has invalid UTF-8; :set allow-lossy-write true to overwrite".to_string(),
+			));
+		}
+
+		// Server edits first, so formatting has the last word.
+		#[cfg(feature = "lsp")]
+		{
+			self.will_save_before_write(buffer_id).await;
+			self.format_before_write(buffer_id).await;
+		}
+
+		let buffer = self
+			.buffers
+			.get_buffer(buffer_id)
+			.expect("buffer exists across formatting");
+		let text_slice = buffer.doc().content.clone();
+		emit_hook(&HookContext::new(
+			HookEventData::BufferWritePre {
+				path: &path_owned,
+				text: text_slice.slice(..),
+			},
+			Some(&self.extensions),
+		))
+		.await;
+
+		let buffer = self
+			.buffers
+			.get_buffer(buffer_id)
+			.expect("buffer exists across write hooks");
+
+		let ending = buffer.line_ending(self);
+		let mut content = Vec::new();
+		for chunk in buffer.doc().content.chunks() {
+			ending.encode_into(&mut content, chunk);
+		}
+		let encoding = buffer.doc().encoding;
+		let content =
+			String::from_utf8(content).expect("line ending conversion keeps UTF-8 intact");
+		let content = encoding.encode(content).map_err(|c| {
+			CommandError::Failed(format!(
+				"cannot encode U+{:04X} as {}",
+				u32::from(c),
+				encoding.name()
+			))
+		})?;
+
+		write_file(&path_owned, &content, force).await?;
+
+		if let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+			buffer.set_modified(false);
+			let mut doc = buffer.doc_mut();
+			doc.line_ending = ending;
+			doc.mixed_line_endings = false;
+			doc.lossy = false;
+			doc.disk_base = Some(doc.content.clone());
+			doc.disk_mtime = file_mtime(&path_owned);
+		}
+		self.remove_swap_file(buffer_id);
+
+		#[cfg(feature = "lsp")]
+		if let Some(buffer) = self.buffers.get_buffer(buffer_id)
+			&& let Err(e) = self.lsp.on_buffer_did_save(buffer)
+		{
+			warn!(error = %e, "LSP did_save notification failed");
+		}
+
+		emit_hook(&HookContext::new(
+			HookEventData::BufferWrite { path: &path_owned },
+			Some(&self.extensions),
+		))
+		.await;
+
+		Ok(path_owned)
+	}
+}
+
+/// Writes `content` to `path`.
+///
+/// With `force`, a write refused for lack of permission is retried with the
+/// owner's write bit set, restoring the original permissions afterwards.
+async fn write_file(path: &Path, content: &[u8], force: bool) -> Result<(), CommandError> {
+	let io_error = |e: std::io::Error| CommandError::Io(e.to_string());
+	match tokio::fs::write(path, content).await {
+		Err(e) if force && e.kind() == std::io::ErrorKind::PermissionDenied => {
+			let permissions = tokio::fs::metadata(path)
+				.await
+				.map_err(io_error)?
+				.permissions();
+			if tokio::fs::set_permissions(path, owner_writable(&permissions))
+				.await
+				.is_err()
+			{
+				return Err(io_error(e));
+			}
+			let written = tokio::fs::write(path, content).await;
+			let restored = tokio::fs::set_permissions(path, permissions).await;
+			written.and(restored).map_err(io_error)
+		}
+		result => result.map_err(io_error),
+	}
+}
+
+/// Returns `permissions` with writing allowed for the file's owner.
+#[cfg(unix)]
+fn owner_writable(permissions: &std::fs::Permissions) -> std::fs::Permissions {
+	use std::os::unix::fs::PermissionsExt;
+	std::fs::Permissions::from_mode(permissions.mode() | 0o200)
+}
+
+/// Returns `permissions` with writing allowed for the file's owner.
+#[cfg(not(unix))]
+fn owner_writable(permissions: &std::fs::Permissions) -> std::fs::Permissions {
+	let mut permissions = permissions.clone();
+	#[allow(
+		clippy::permissions_set_readonly_false,
+		reason = "non-Unix permissions only carry a read-only attribute"
+	)]
+	permissions.set_readonly(false);
+	permissions
+}
 
 impl xeno_core::editor_ctx::FileOpsAccess for Editor {
 	fn is_modified(&self) -> bool {
@@ -19,64 +207,129 @@ impl xeno_core::editor_ctx::FileOpsAccess for Editor {
 		&mut self,
 	) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), CommandError>> + '_>> {
 		Box::pin(async move {
-			let path_owned = match &self.buffer().path() {
-				Some(p) => p.clone(),
-				None => {
-					return Err(CommandError::InvalidArgument(
-						"No filename. Use :write <filename>".to_string(),
-					));
+			let buffer_id = self.focused_view();
+			self.ensure_writable(buffer_id)?;
+			if let Some(change) = self.disk_change(buffer_id).await? {
+				let error = change.error();
+				self.open_save_conflict(buffer_id, change);
+				return Err(error);
+			}
+			let path = self.write_buffer(buffer_id, false).await?;
+			self.show_notification(xeno_registry_notifications::keys::file_saved::call(&path));
+			Ok(())
+		})
+	}
+
+	fn save_as(
+		&mut self,
+		path: PathBuf,
+	) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), CommandError>> + '_>> {
+		Box::pin(async move {
+			let buffer_id = self.focused_view();
+			let old_path = self.buffer().path();
+			if old_path.as_ref() != Some(&path) {
+				let buffer = self.buffer_mut();
+				buffer.set_path(Some(path));
+				{
+					let mut doc = buffer.doc_mut();
+					doc.disk_mtime = None;
+					doc.scratch = None;
 				}
-			};
-
-			let text_slice = self.buffer().doc().content.clone();
-			emit_hook(&HookContext::new(
-				HookEventData::BufferWritePre {
-					path: &path_owned,
-					text: text_slice.slice(..),
-				},
-				Some(&self.extensions),
-			))
-			.await;
+				self.set_file_type(buffer_id, None);
+			}
+			self.save().await?;
 
+			// A buffer that had no file is only now known to language servers.
 			#[cfg(feature = "lsp")]
-			if let Err(e) = self.lsp.on_buffer_will_save(self.buffer()) {
-				warn!(error = %e, "LSP will_save notification failed");
+			if old_path.is_none()
+				&& !self.large_file_mode(buffer_id)
+				&& let Some(buffer) = self.buffers.get_buffer(buffer_id)
+				&& let Err(e) = self.lsp.on_buffer_open(buffer).await
+			{
+				warn!(error = %e, "LSP buffer open failed");
 			}
+			Ok(())
+		})
+	}
+}
 
-			let mut content = Vec::new();
-			for chunk in self.buffer().doc().content.chunks() {
-				content.extend_from_slice(chunk.as_bytes());
-			}
+#[cfg(test)]
+mod tests {
+	use xeno_core::editor_ctx::FileOpsAccess;
+	use xeno_registry::options::{OptionValue, keys};
 
-			tokio::fs::write(&path_owned, &content)
-				.await
-				.map_err(|e| CommandError::Io(e.to_string()))?;
+	use super::*;
 
-			self.buffer_mut().set_modified(false);
-			self.show_notification(xeno_registry_notifications::keys::file_saved::call(
-				&path_owned,
-			));
+	#[tokio::test]
+	async fn save_preserves_and_converts_line_endings() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("crlf.txt");
+		std::fs::write(&path, "one\r\ntwo\r\n").unwrap();
 
-			#[cfg(feature = "lsp")]
-			if let Err(e) = self.lsp.on_buffer_did_save(self.buffer(), true) {
-				warn!(error = %e, "LSP did_save notification failed");
-			}
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		assert_eq!(editor.buffer().doc().content.to_string(), "one\ntwo\n");
+		editor.save().await.unwrap();
+		assert_eq!(std::fs::read(&path).unwrap(), b"one\r\ntwo\r\n");
 
-			emit_hook(&HookContext::new(
-				HookEventData::BufferWrite { path: &path_owned },
-				Some(&self.extensions),
-			))
-			.await;
+		editor.buffer_mut().local_options.set(
+			keys::LINE_ENDING.untyped(),
+			OptionValue::String("lf".into()),
+		);
+		editor.save().await.unwrap();
+		assert_eq!(std::fs::read(&path).unwrap(), b"one\ntwo\n");
+	}
 
-			Ok(())
-		})
+	#[tokio::test]
+	async fn save_never_rewrites_undecodable_bytes() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("legacy.txt");
+		let original = b"caf\xe9\r\n".to_vec();
+		std::fs::write(&path, &original).unwrap();
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		assert!(editor.lossy_locked(editor.focused_view()));
+		assert!(editor.save().await.is_err());
+		assert_eq!(std::fs::read(&path).unwrap(), original);
+
+		let mut editor = Editor::new_scratch();
+		editor.config.global_options.set(
+			keys::ENCODING.untyped(),
+			OptionValue::String("latin-1".into()),
+		);
+		editor.open_files([(path.clone(), None)]).await;
+		assert_eq!(editor.buffer().doc().content.to_string(), "café\n");
+		editor.save().await.unwrap();
+		assert_eq!(std::fs::read(&path).unwrap(), original);
 	}
 
-	fn save_as(
-		&mut self,
-		path: PathBuf,
-	) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), CommandError>> + '_>> {
-		self.buffer_mut().set_path(Some(path));
-		self.save()
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn read_only_needs_forced_write_and_keeps_permissions() {
+		use std::os::unix::fs::PermissionsExt;
+
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("locked.txt");
+		std::fs::write(&path, "old\n").unwrap();
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o444)).unwrap();
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		editor.buffer().set_readonly(false);
+		editor.insert_text("new ");
+		editor.buffer().set_readonly(true);
+		assert!(editor.save().await.is_err());
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "old\n");
+
+		editor
+			.force_save_buffer(editor.focused_view())
+			.await
+			.unwrap();
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "new old\n");
+		let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+		assert_eq!(mode & 0o777, 0o444);
+
+		std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
 	}
 }
@@ -8,6 +8,7 @@ use xeno_registry::{HookContext, HookEventData, ViewId, emit_sync_with as emit_h
 use super::Editor;
 use crate::buffer::{BufferId, BufferView, SpatialDirection};
 use crate::palette::PaletteState;
+use crate::ui::UiRequest;
 use crate::window::{Window, WindowId};
 
 /// Panel identifier used by focus targets.
@@ -132,22 +133,45 @@ impl Editor {
 		self.focus_view(prev);
 	}
 
-	/// Focuses the next text buffer in the layout.
+	/// Shows the next open buffer in the focused view, in creation order.
 	pub fn focus_next_buffer(&mut self) {
-		let current_id = self.focused_view();
-		let next_id = self
-			.layout
-			.next_buffer(&self.base_window().layout, current_id);
-		self.focus_buffer(next_id);
+		self.cycle_buffer(true);
 	}
 
-	/// Focuses the previous text buffer in the layout.
+	/// Shows the previous open buffer in the focused view, in creation order.
 	pub fn focus_prev_buffer(&mut self) {
-		let current_id = self.focused_view();
-		let prev_id = self
-			.layout
-			.prev_buffer(&self.base_window().layout, current_id);
-		self.focus_buffer(prev_id);
+		self.cycle_buffer(false);
+	}
+
+	/// Steps through all open buffers, wrapping at either end.
+	fn cycle_buffer(&mut self, forward: bool) {
+		let ids = self.buffer_ids();
+		let current = self.focused_view();
+		let Some(idx) = ids.iter().position(|&id| id == current) else {
+			return;
+		};
+		let target = if forward {
+			ids[(idx + 1) % ids.len()]
+		} else {
+			ids[(idx + ids.len() - 1) % ids.len()]
+		};
+		self.show_buffer(target);
+	}
+
+	/// Focuses a buffer, swapping it into the focused view if no view
+	/// shows it yet.
+	///
+	/// Returns true if the buffer exists and was focused.
+	pub fn show_buffer(&mut self, id: BufferId) -> bool {
+		if self.buffers.get_buffer(id).is_none() {
+			return false;
+		}
+		let current = self.focused_view();
+		let base_layout = &mut self.windows.base_window_mut().layout;
+		if id != current && !self.layout.views(base_layout).contains(&id) {
+			self.layout.replace_view_buffer(base_layout, current, id);
+		}
+		self.focus_view(id)
 	}
 
 	/// Focuses the view in the given direction, using cursor position as tiebreaker.
@@ -167,6 +191,16 @@ impl Editor {
 		}
 	}
 
+	/// Applies UI requests raised outside of panel event handling, such as
+	/// from a command or a popup callback, and follows the resulting focus.
+	pub fn apply_ui_requests(&mut self, requests: Vec<UiRequest>) {
+		self.ui.apply_requests(requests);
+		if self.ui.take_wants_redraw() {
+			self.frame.needs_redraw = true;
+		}
+		self.sync_focus_from_ui();
+	}
+
 	pub(super) fn sync_focus_from_ui(&mut self) {
 		let old_focus = self.focus.clone();
 		if let Some(panel_id) = self.ui.focused_panel_id() {
@@ -216,8 +250,14 @@ impl Editor {
 	}
 
 	/// Returns the display name for the current mode.
+	///
+	/// Normal mode with an active block selection reports `BLOCK`.
 	pub fn mode_name(&self) -> &'static str {
-		self.buffer().input.mode_name()
+		let buffer = self.buffer();
+		if buffer.input.mode() == Mode::Normal && buffer.block_selection().is_some() {
+			return "BLOCK";
+		}
+		buffer.input.mode_name()
 	}
 
 	fn handle_window_focus_change(&mut self, old_focus: FocusTarget, new_focus: &FocusTarget) {
@@ -257,8 +297,13 @@ impl Editor {
 			}
 		}
 
+		// Cycling focus between info popups does not dismiss them.
+		let between_popups = old_window.zip(new_window).is_some_and(|(old, new)| {
+			self.info_popup_in_window(old).is_some() && self.info_popup_in_window(new).is_some()
+		});
 		if let Some(window) = old_window
 			&& old_window != new_window
+			&& !between_popups
 		{
 			let should_close = matches!(
 				self.windows.get(window),
@@ -271,6 +316,8 @@ impl Editor {
 					.and_then(|p| p.window_id());
 				if Some(window) == palette_window {
 					self.close_palette();
+				} else if let Some(popup_id) = self.info_popup_in_window(window) {
+					self.close_info_popup(popup_id);
 				} else {
 					self.close_floating_window(window);
 				}
@@ -0,0 +1,131 @@
+//! Folding range requests.
+//!
+//! While `folds` is on for a buffer shown in the base layout, the folding
+//! ranges of its document are requested when it is first shown, and again
+//! after edits once the `buffer:change` hook has been quiet for
+//! [`FOLD_DEBOUNCE`]. Until then the folds follow the edits through
+//! [`Folds::map_through`]. Turning the option off drops them.
+//!
+//! [`Folds::map_through`]: crate::buffer::Folds::map_through
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::debug;
+use xeno_lsp::lsp_types::FoldingRange;
+use xeno_registry::options::keys;
+
+use super::Editor;
+use super::lsp_requests::{DocumentRequests, LspRequestChanges};
+use crate::buffer::{BufferId, DocumentId};
+
+/// How long edits must pause before folding ranges are requested again.
+pub const FOLD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Overlay state for folding range requests.
+#[derive(Default)]
+pub(crate) struct FoldingRangeState {
+	/// Requests of each document with folds turned on.
+	requests: DocumentRequests<Vec<FoldingRange>>,
+}
+
+impl Editor {
+	/// Sends the folding range requests the shown buffers need, takes in
+	/// responses and drops the folds of buffers that turned them off.
+	pub(crate) fn poll_folding_ranges(&mut self) {
+		let mut changed = self.receive_folding_ranges();
+
+		let mut shown: HashMap<DocumentId, BufferId> = HashMap::new();
+		let mut disabled = Vec::new();
+		let views = self
+			.layout
+			.compute_view_areas(&self.base_window().layout, self.doc_area());
+		for (view, _) in views {
+			let Some(buffer) = self.buffers.get_buffer(view) else {
+				continue;
+			};
+			let document = buffer.document_id();
+			if !self.resolve_typed_option(view, keys::FOLDS) || self.large_file_mode(view) {
+				disabled.push((view, document));
+			} else {
+				shown.entry(document).or_insert(view);
+			}
+		}
+
+		let state = self.overlays.get_or_default::<FoldingRangeState>();
+		for (view, document) in disabled {
+			if shown.contains_key(&document) {
+				continue;
+			}
+			state.requests.forget(document);
+			if let Some(buffer) = self.buffers.get_buffer(view) {
+				let mut doc = buffer.doc_mut();
+				if !doc.folds.is_empty() {
+					doc.folds.clear();
+					changed = true;
+				}
+			}
+		}
+
+		let last_change = self
+			.extensions
+			.get::<LspRequestChanges>()
+			.and_then(LspRequestChanges::last);
+		for (document, view) in shown {
+			let requests = &mut self.overlays.get_or_default::<FoldingRangeState>().requests;
+			if requests.is_pending(document) {
+				continue;
+			}
+			let Some(buffer) = self.buffers.get_buffer(view) else {
+				continue;
+			};
+			let version = buffer.version();
+			if !requests.is_due(document, version, last_change, FOLD_DEBOUNCE) {
+				continue;
+			}
+			let request = self.lsp.folding_ranges(buffer);
+			if let Err(e) = requests.send(document, view, version, (), request) {
+				debug!(error = %e, "folding range request failed");
+			}
+		}
+
+		if changed {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Takes in the responses that have arrived. Returns whether any folds
+	/// changed.
+	fn receive_folding_ranges(&mut self) -> bool {
+		let state = self.overlays.get_or_default::<FoldingRangeState>();
+		let mut changed = false;
+		for arrived in state.requests.receive() {
+			let Some(buffer) = self.buffers.get_buffer(arrived.buffer) else {
+				continue;
+			};
+			let mut doc = buffer.doc_mut();
+			// Lines in a response to an older version may be off; the edit
+			// since then asks again.
+			if doc.version != arrived.version {
+				continue;
+			}
+			let ranges = match arrived.response {
+				Ok(Some(ranges)) => ranges,
+				Ok(None) => continue,
+				Err(e) => {
+					debug!(error = %e, "folding range request failed");
+					continue;
+				}
+			};
+			let last_line = doc.content.len_lines() - 1;
+			doc.folds.replace(ranges.iter().map(|range| {
+				(
+					range.start_line as usize,
+					(range.end_line as usize).min(last_line),
+				)
+			}));
+			changed = true;
+		}
+		changed
+	}
+}
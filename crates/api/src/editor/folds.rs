@@ -0,0 +1,73 @@
+//! Opening and closing folds.
+//!
+//! Folds belong to the document, so every view of it shows them alike.
+//! Closing a fold around a cursor moves the cursor to the fold's first
+//! line; elsewhere, a cursor that lands in a closed fold opens it or is
+//! put on its first line, following `fold-reveal`.
+
+use super::Editor;
+
+impl Editor {
+	/// Opens the fold closed at the cursor's line, or else closes the
+	/// innermost fold around it.
+	///
+	/// Returns whether there was a fold to toggle.
+	pub(crate) fn toggle_fold(&mut self) -> bool {
+		let line = self.buffer().cursor_line();
+		if !self.buffer().doc_mut().folds.toggle(line) {
+			return false;
+		}
+		self.buffer_mut().keep_cursors_out_of_folds(false);
+		self.frame.needs_redraw = true;
+		true
+	}
+
+	/// Opens or closes every fold of the focused buffer.
+	///
+	/// Returns whether the buffer has folds.
+	pub(crate) fn set_all_folds(&mut self, closed: bool) -> bool {
+		{
+			let buffer = self.buffer();
+			let mut doc = buffer.doc_mut();
+			if doc.folds.is_empty() {
+				return false;
+			}
+			doc.folds.set_all_closed(closed);
+		}
+		self.buffer_mut().keep_cursors_out_of_folds(false);
+		self.frame.needs_redraw = true;
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Selection;
+	use xeno_base::range::Direction as MoveDir;
+
+	use super::*;
+
+	#[test]
+	fn closing_a_fold_moves_the_cursor_to_its_header() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("fn a() {\n\tone;\n\ttwo;\n}\n");
+		editor.buffer().doc_mut().folds.replace([(0, 2)]);
+		let inside = editor.buffer().doc().content.line_to_char(2) + 2;
+		editor.buffer_mut().set_selection(Selection::point(inside));
+		editor.buffer_mut().sync_cursor_to_selection();
+
+		assert!(editor.toggle_fold());
+		assert_eq!(editor.buffer().cursor, 0);
+		assert!(editor.buffer().doc().folds.is_hidden(2));
+
+		editor.buffer_mut().set_selection(Selection::point(inside));
+		editor.buffer_mut().keep_cursors_out_of_folds(true);
+		assert!(!editor.buffer().doc().folds.any_closed());
+
+		assert!(editor.set_all_folds(true));
+		editor
+			.buffer_mut()
+			.move_visual_vertical(MoveDir::Forward, 1, false, 4);
+		assert_eq!(editor.buffer().cursor_line(), 3);
+	}
+}
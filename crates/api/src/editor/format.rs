@@ -0,0 +1,169 @@
+//! Formatting buffers with their language server.
+//!
+//! `:format` formats the whole buffer, or the primary selection when it is
+//! not a bare cursor. With `format-on-save` on, buffers are formatted before
+//! every write, after any edits the server asked for through
+//! `willSaveWaitUntil`; a server slower than `format-timeout` is skipped so
+//! it can never keep a buffer from being saved.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use xeno_lsp::OffsetEncoding;
+use xeno_lsp::lsp_types::{FormattingOptions, TextEdit};
+use xeno_registry::options::keys;
+use xeno_registry_notifications::keys as notif_keys;
+
+use super::Editor;
+use crate::buffer::BufferId;
+
+impl Editor {
+	/// Formats a buffer with its language server, only the primary selection
+	/// when `selection` is set and the selection is not a bare cursor.
+	///
+	/// Returns the number of edits applied, or `None` if no server handles
+	/// the buffer or can format it.
+	pub async fn format_buffer(
+		&mut self,
+		buffer_id: BufferId,
+		selection: bool,
+	) -> Result<Option<usize>, String> {
+		let range = selection
+			.then(|| self.buffers.get_buffer(buffer_id))
+			.flatten()
+			.map(|buffer| buffer.selection.primary())
+			.filter(|range| !range.is_empty())
+			.map(|range| range.from()..range.to());
+		match self.request_format(buffer_id, range).await? {
+			Some((edits, encoding)) => self
+				.apply_format_edits(buffer_id, &edits, encoding)
+				.map(Some),
+			None => Ok(None),
+		}
+	}
+
+	/// Formats a buffer about to be written if `format-on-save` is on.
+	///
+	/// Failures and servers slower than `format-timeout` leave the buffer
+	/// as it is, with a warning.
+	pub(super) async fn format_before_write(&mut self, buffer_id: BufferId) {
+		if !self.resolve_typed_option(buffer_id, keys::FORMAT_ON_SAVE) {
+			return;
+		}
+		let timeout = self.resolve_typed_option(buffer_id, keys::FORMAT_TIMEOUT);
+		let timeout = Duration::from_millis(timeout.max(1) as u64);
+		let response = tokio::time::timeout(timeout, self.request_format(buffer_id, None)).await;
+		let result = match response {
+			Ok(Ok(Some((edits, encoding)))) => self.apply_format_edits(buffer_id, &edits, encoding),
+			Ok(Ok(None)) => Ok(0),
+			Ok(Err(e)) => Err(e),
+			Err(_) => Err(format!(
+				"language server took over {} ms",
+				timeout.as_millis()
+			)),
+		};
+		if let Err(reason) = result {
+			self.notify(notif_keys::format_on_save_failed::call(&reason));
+		}
+	}
+
+	/// Asks the buffer's language server for the edits formatting it, or
+	/// the characters in `range`.
+	async fn request_format(
+		&self,
+		buffer_id: BufferId,
+		range: Option<Range<usize>>,
+	) -> Result<Option<(Vec<TextEdit>, OffsetEncoding)>, String> {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return Ok(None);
+		};
+		let options = FormattingOptions {
+			tab_size: self.resolve_typed_option(buffer_id, keys::TAB_WIDTH).max(1) as u32,
+			insert_spaces: !self.resolve_typed_option(buffer_id, keys::USE_TABS),
+			trim_trailing_whitespace: Some(true),
+			insert_final_newline: Some(true),
+			..Default::default()
+		};
+		self.lsp
+			.format(buffer, options, range)
+			.await
+			.map_err(|e| e.to_string())
+	}
+
+	/// Applies formatting `edits`, or the edits a server asked for before a
+	/// save, to a buffer as one undo step, returning how many there were.
+	///
+	/// The edits are sorted and checked for overlaps first, and selections
+	/// are mapped through them so cursors stay on the same text.
+	pub(super) fn apply_format_edits(
+		&mut self,
+		buffer_id: BufferId,
+		edits: &[TextEdit],
+		encoding: OffsetEncoding,
+	) -> Result<usize, String> {
+		if edits.is_empty() {
+			return Ok(0);
+		}
+		if self
+			.buffers
+			.get_buffer(buffer_id)
+			.is_some_and(|buffer| buffer.is_readonly())
+		{
+			return Err("buffer is read-only".to_string());
+		}
+		let tx = self.edit_transaction(buffer_id, edits, encoding)?;
+		let selection = {
+			let buffer = self.buffers.get_buffer(buffer_id).expect("buffer exists");
+			tx.map_selection(&buffer.selection)
+		};
+		self.save_undo_state_for(buffer_id);
+		self.apply_transaction_with_selection(buffer_id, &tx, Some(selection));
+		self.frame.needs_redraw = true;
+		Ok(edits.len())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Selection;
+	use xeno_lsp::lsp_types::{Position, Range as LspRange};
+
+	use super::*;
+
+	fn edit(start: u32, end: u32, text: &str) -> TextEdit {
+		TextEdit {
+			range: LspRange::new(Position::new(0, start), Position::new(0, end)),
+			new_text: text.to_string(),
+		}
+	}
+
+	#[test]
+	fn edits_apply_in_order_and_keep_the_cursor_on_its_text() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("let  x=1;\n");
+		let buffer_id = editor.focused_view();
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(5));
+		buffer.sync_cursor_to_selection();
+
+		// Servers may send edits in any order.
+		let edits = [edit(6, 7, " = "), edit(3, 5, " ")];
+		assert_eq!(
+			editor.apply_format_edits(buffer_id, &edits, OffsetEncoding::Utf8),
+			Ok(2)
+		);
+		assert_eq!(editor.buffer().doc().content.to_string(), "let x = 1;\n");
+		assert_eq!(editor.buffer().cursor, 4);
+
+		editor.undo();
+		assert_eq!(editor.buffer().doc().content.to_string(), "let  x=1;\n");
+
+		let overlapping = [edit(0, 5, "x"), edit(4, 7, "y")];
+		assert!(
+			editor
+				.apply_format_edits(buffer_id, &overlapping, OffsetEncoding::Utf8)
+				.is_err()
+		);
+		assert_eq!(editor.buffer().doc().content.to_string(), "let  x=1;\n");
+	}
+}
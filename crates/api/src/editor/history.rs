@@ -2,11 +2,12 @@
 
 use std::collections::HashMap;
 
-use xeno_base::Selection;
+use xeno_base::{Selection, SeqDirection};
+use xeno_registry::commands::HistoryJump;
 use xeno_registry_notifications::keys;
 
 use crate::buffer::{BufferId, DocumentId};
-use crate::editor::Editor;
+use crate::editor::{Editor, UndoTree};
 
 impl Editor {
 	/// Collects selections from all buffers sharing the same document.
@@ -37,22 +38,46 @@ impl Editor {
 
 	/// Saves current state to undo history for all views of the focused document.
 	pub fn save_undo_state(&mut self) {
-		let buffer_id = self.focused_view();
+		self.save_undo_state_for(self.focused_view());
+	}
+
+	/// Saves current state to undo history for all views of `buffer_id`'s
+	/// document.
+	pub(crate) fn save_undo_state_for(&mut self, buffer_id: BufferId) {
 		let doc_id = self
 			.buffers
 			.get_buffer(buffer_id)
-			.expect("focused buffer must exist")
+			.expect("buffer must exist")
 			.document_id();
 		let selections = self.collect_sibling_selections(doc_id);
 		self.buffers
 			.get_buffer_mut(buffer_id)
-			.expect("focused buffer must exist")
+			.expect("buffer must exist")
 			.doc_mut()
 			.save_undo_state(selections);
 	}
 
-	/// Saves undo state for insert mode, grouping consecutive inserts.
-	pub(crate) fn save_insert_undo_state(&mut self) {
+	/// Records undo state before an edit.
+	///
+	/// In insert mode the edit joins the insert session's undo group, so the
+	/// whole session undoes as one step; otherwise it gets its own entry.
+	pub(crate) fn save_edit_undo_state(&mut self) {
+		if self.buffer().mode() == xeno_base::Mode::Insert {
+			self.begin_undo_group();
+		} else {
+			self.save_undo_state();
+		}
+	}
+
+	/// Opens an undo group on the focused document.
+	///
+	/// Edits made while the group is open undo as a single step. If a group is
+	/// already open and has reached the `undo-group-max-edits` limit, it is
+	/// closed and a new group begins in its place.
+	pub fn begin_undo_group(&mut self) {
+		let max_edits = self
+			.option(xeno_registry::options::keys::UNDO_GROUP_MAX_EDITS)
+			.max(1) as usize;
 		let buffer_id = self.focused_view();
 		let doc_id = self
 			.buffers
@@ -60,11 +85,23 @@ impl Editor {
 			.expect("focused buffer must exist")
 			.document_id();
 		let selections = self.collect_sibling_selections(doc_id);
-		self.buffers
+		let buffer = self
+			.buffers
 			.get_buffer_mut(buffer_id)
-			.expect("focused buffer must exist")
-			.doc_mut()
-			.save_insert_undo_state(selections);
+			.expect("focused buffer must exist");
+		let mut doc = buffer.doc_mut();
+		if doc
+			.undo_group_edits()
+			.is_some_and(|edits| edits >= max_edits)
+		{
+			doc.end_undo_group();
+		}
+		doc.begin_undo_group(selections);
+	}
+
+	/// Closes the undo group on the focused document, if one is open.
+	pub fn end_undo_group(&mut self) {
+		self.buffer().doc_mut().end_undo_group();
 	}
 
 	/// Undoes the last change, restoring selections for all views of the document.
@@ -130,4 +167,68 @@ impl Editor {
 		self.restore_sibling_selections(doc_id, &selections);
 		self.notify(keys::redo);
 	}
+
+	/// Moves chronologically through the focused document's undo tree.
+	///
+	/// Unlike [`undo`](Self::undo) and [`redo`](Self::redo), this crosses
+	/// branches: states are visited in the order they were created, walking up
+	/// to the common ancestor and back down as needed.
+	pub fn travel_history(&mut self, direction: SeqDirection, jump: HistoryJump) {
+		self.seek_history(direction, |history| match jump {
+			HistoryJump::Steps(steps) => history.target_by_steps(direction, steps),
+			HistoryJump::Time(span) => history.target_by_time(direction, span),
+		});
+	}
+
+	/// Moves the focused document to node `target` of its undo tree.
+	pub fn goto_undo_node(&mut self, target: usize) {
+		let direction = if target < self.buffer().doc().history.current() {
+			SeqDirection::Prev
+		} else {
+			SeqDirection::Next
+		};
+		self.seek_history(direction, |_| target);
+	}
+
+	/// Moves the focused document to the undo tree node picked by `target`,
+	/// notifying as an undo or a redo depending on `direction`.
+	fn seek_history(&mut self, direction: SeqDirection, target: impl FnOnce(&UndoTree) -> usize) {
+		if !self.guard_readonly() {
+			return;
+		}
+		let buffer_id = self.focused_view();
+		let doc_id = self
+			.buffers
+			.get_buffer(buffer_id)
+			.expect("focused buffer must exist")
+			.document_id();
+		let current = self.collect_sibling_selections(doc_id);
+
+		let restored = {
+			let buffer = self
+				.buffers
+				.get_buffer_mut(buffer_id)
+				.expect("focused buffer must exist");
+			let mut doc = buffer.doc_mut();
+			let target = target(&doc.history);
+			doc.goto_history(target, current, &self.config.language_loader)
+		};
+
+		let Some(selections) = restored else {
+			self.notify(match direction {
+				SeqDirection::Prev => keys::nothing_to_undo,
+				SeqDirection::Next => keys::nothing_to_redo,
+			});
+			return;
+		};
+
+		// Mark buffer dirty for LSP sync (full sync, not incremental)
+		self.mark_buffer_dirty_for_full_sync(buffer_id);
+
+		self.restore_sibling_selections(doc_id, &selections);
+		self.notify(match direction {
+			SeqDirection::Prev => keys::undo,
+			SeqDirection::Next => keys::redo,
+		});
+	}
 }
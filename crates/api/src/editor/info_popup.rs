@@ -1,16 +1,25 @@
 //! Info popup integration with editor.
 
-use super::Editor;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use xeno_registry::options::keys;
+use xeno_tui::layout::Position;
+
+use super::{Editor, FocusTarget};
 use crate::info_popup::{
-	InfoPopup, InfoPopupId, InfoPopupStore, PopupAnchor, compute_popup_rect, info_popup_style,
+	ExitingPopup, InfoPopup, InfoPopupId, InfoPopupStore, PINNED_TITLE, PopupAnchor,
+	PopupAnimation, compute_popup_placement, info_popup_style,
 };
-use crate::window::{GutterSelector, Window};
+use crate::render::cursor_visual_row;
+use crate::window::{GutterSelector, Window, WindowId};
 
 impl Editor {
 	/// Opens an info popup with the given content.
 	///
 	/// The popup is positioned relative to the anchor point. Content is displayed
 	/// in a read-only buffer with syntax highlighting based on the optional file type.
+	/// It replaces any open popups that are not pinned.
 	pub fn open_info_popup(
 		&mut self,
 		content: String,
@@ -18,6 +27,7 @@ impl Editor {
 		anchor: PopupAnchor,
 	) -> Option<InfoPopupId> {
 		let bounds = self.viewport.doc_area?;
+		self.close_unpinned_info_popups();
 
 		let lines: Vec<&str> = content.lines().collect();
 		let content_height = lines.len().min(20) as u16;
@@ -28,7 +38,7 @@ impl Editor {
 			.unwrap_or(20)
 			.min(60) as u16;
 
-		let rect = compute_popup_rect(anchor, content_width, content_height, bounds);
+		let placement = compute_popup_placement(anchor, content_width, content_height, bounds);
 
 		let buffer_id = self.buffers.create_scratch();
 		{
@@ -36,7 +46,7 @@ impl Editor {
 				.buffers
 				.get_buffer_mut(buffer_id)
 				.expect("just created");
-			buffer.doc_mut().content = ropey::Rope::from_str(&content);
+			buffer.replace_content(ropey::Rope::from_str(&content));
 			if let Some(ft) = file_type {
 				buffer
 					.doc_mut()
@@ -45,7 +55,7 @@ impl Editor {
 			buffer.set_readonly_override(Some(true));
 		}
 
-		let window_id = self.create_floating_window(buffer_id, rect, info_popup_style());
+		let window_id = self.create_floating_window(buffer_id, placement.rect, info_popup_style());
 
 		let Window::Floating(float) = self.windows.get_mut(window_id).expect("just created") else {
 			unreachable!()
@@ -54,33 +64,107 @@ impl Editor {
 		float.dismiss_on_blur = true;
 		float.gutter = GutterSelector::Hidden;
 
+		let animate = self.popup_animations_enabled();
 		let store = self.overlays.get_or_default::<InfoPopupStore>();
 		let popup_id = store.next_id();
+		let animation = if animate {
+			PopupAnimation::new(store.entry_timing, store.exit_timing)
+		} else {
+			PopupAnimation::none()
+		};
 		store.insert(InfoPopup {
 			id: popup_id,
 			window_id,
 			buffer_id,
 			anchor,
+			side: placement.side,
+			pinned: false,
+			animation,
 		});
 
 		self.frame.needs_redraw = true;
 		Some(popup_id)
 	}
 
+	/// Returns the screen cell of the primary cursor in the focused view, or
+	/// `None` if it is scrolled out of view.
+	///
+	/// Cursor-anchored popups use this as their [`PopupAnchor::Cursor`].
+	pub(crate) fn cursor_screen_position(&self) -> Option<Position> {
+		let tab_width = self.tab_width();
+		let buffer = self.buffer();
+		let row = cursor_visual_row(buffer, tab_width)?;
+		let mut col = buffer.visual_column(buffer.cursor, tab_width);
+		if !buffer.wrap {
+			col = col.checked_sub(buffer.left_col)?;
+		}
+		let area = self.focused_view_area();
+		let x = usize::from(area.x) + usize::from(buffer.gutter_width()) + col;
+		let y = usize::from(area.y) + row;
+		Some(Position::new(
+			u16::try_from(x).ok()?,
+			u16::try_from(y).ok()?,
+		))
+	}
+
 	/// Closes an info popup by ID.
+	///
+	/// With popup animations enabled, the closed window keeps being drawn
+	/// until it has faded out, and its buffer is removed after that.
 	pub fn close_info_popup(&mut self, popup_id: InfoPopupId) {
-		let Some(popup) = self
+		let Some(mut popup) = self
 			.overlays
 			.get_or_default::<InfoPopupStore>()
 			.remove(popup_id)
 		else {
 			return;
 		};
+		popup.animation.exit();
+		let window = match self.windows.get(popup.window_id) {
+			Some(Window::Floating(float)) if popup.animation.is_animating() => Some(float.clone()),
+			_ => None,
+		};
 		self.close_floating_window(popup.window_id);
-		self.buffers.remove_buffer(popup.buffer_id);
+		match window {
+			Some(window) => {
+				self.overlays
+					.get_or_default::<InfoPopupStore>()
+					.push_exiting(ExitingPopup {
+						window,
+						animation: popup.animation,
+					});
+			}
+			None => self.buffers.remove_buffer(popup.buffer_id),
+		}
 		self.frame.needs_redraw = true;
 	}
 
+	/// Returns whether popups fade in and out.
+	///
+	/// Needs the `ui-animations` option and a true color terminal, since
+	/// fading blends colors.
+	pub(crate) fn popup_animations_enabled(&self) -> bool {
+		static TRUECOLOR: OnceLock<bool> = OnceLock::new();
+		self.option(keys::UI_ANIMATIONS)
+			&& *TRUECOLOR.get_or_init(|| {
+				std::env::var("COLORTERM")
+					.is_ok_and(|value| matches!(value.as_str(), "truecolor" | "24bit"))
+			})
+	}
+
+	/// Advances popup fade animations by `delta`, removing the buffers of
+	/// closed popups that finished fading out.
+	pub(crate) fn tick_popup_animations(&mut self, delta: Duration) {
+		let store = self.overlays.get_or_default::<InfoPopupStore>();
+		let finished = store.tick(delta);
+		if store.is_animating() {
+			self.frame.needs_redraw = true;
+		}
+		for buffer_id in finished {
+			self.buffers.remove_buffer(buffer_id);
+		}
+	}
+
 	/// Closes all open info popups.
 	pub fn close_all_info_popups(&mut self) {
 		let popup_ids: Vec<_> = self
@@ -93,6 +177,100 @@ impl Editor {
 		}
 	}
 
+	/// Closes the open info popups that are not pinned.
+	pub(crate) fn close_unpinned_info_popups(&mut self) {
+		let popup_ids: Vec<_> = self
+			.overlays
+			.get_or_default::<InfoPopupStore>()
+			.ordered()
+			.into_iter()
+			.filter(|popup| !popup.pinned)
+			.map(|popup| popup.id)
+			.collect();
+		for id in popup_ids {
+			self.close_info_popup(id);
+		}
+	}
+
+	/// Returns the info popup shown in `window_id`, if any.
+	pub(crate) fn info_popup_in_window(&self, window_id: WindowId) -> Option<InfoPopupId> {
+		self.overlays
+			.get::<InfoPopupStore>()?
+			.by_window(window_id)
+			.map(|popup| popup.id)
+	}
+
+	/// Returns the info popup that has focus, if any.
+	fn focused_info_popup(&self) -> Option<InfoPopupId> {
+		match self.focus {
+			FocusTarget::Buffer { window, .. } => self.info_popup_in_window(window),
+			FocusTarget::Panel(_) => None,
+		}
+	}
+
+	/// Pins or unpins the focused info popup, or the most recently opened one
+	/// if none has focus.
+	///
+	/// A pinned popup shows [`PINNED_TITLE`] on its border, stays open when
+	/// it loses focus and is not replaced by new popups. Escape while it has
+	/// focus, or closing all popups, still dismisses it. Returns the new pin
+	/// state, or `None` if no info popup is open.
+	pub fn toggle_pin_info_popup(&mut self) -> Option<bool> {
+		let id = match self.focused_info_popup() {
+			Some(id) => id,
+			None => self.overlays.get::<InfoPopupStore>()?.latest()?.id,
+		};
+		let popup = self
+			.overlays
+			.get_or_default::<InfoPopupStore>()
+			.get_mut(id)?;
+		popup.pinned = !popup.pinned;
+		let (pinned, window_id) = (popup.pinned, popup.window_id);
+
+		if let Some(Window::Floating(float)) = self.windows.get_mut(window_id) {
+			float.dismiss_on_blur = !pinned;
+			float.style.title = pinned.then(|| PINNED_TITLE.to_string());
+		}
+		self.frame.needs_redraw = true;
+		Some(pinned)
+	}
+
+	/// Moves focus to the next info popup, oldest first, and from the last
+	/// one back to the document.
+	///
+	/// Moving between popups does not dismiss the one left behind. Returns
+	/// false if no info popup is open.
+	pub fn focus_next_info_popup(&mut self) -> bool {
+		let popups: Vec<_> = self
+			.overlays
+			.get_or_default::<InfoPopupStore>()
+			.ordered()
+			.into_iter()
+			.map(|popup| (popup.id, popup.window_id, popup.buffer_id))
+			.collect();
+		if popups.is_empty() {
+			return false;
+		}
+		let next = match self.focused_info_popup() {
+			Some(current) => popups
+				.iter()
+				.position(|&(id, ..)| id == current)
+				.and_then(|i| popups.get(i + 1)),
+			None => popups.first(),
+		};
+		match next {
+			Some(&(_, window_id, buffer_id)) => {
+				self.focus_buffer_in_window(window_id, buffer_id, true);
+			}
+			None => {
+				let base_buffer = self.base_window().focused_buffer;
+				self.focus_view(base_buffer);
+			}
+		}
+		self.frame.needs_redraw = true;
+		true
+	}
+
 	/// Updates the content of an existing info popup.
 	pub fn update_info_popup(
 		&mut self,
@@ -114,7 +292,7 @@ impl Editor {
 		};
 
 		buffer.set_readonly_override(Some(false));
-		buffer.doc_mut().content = ropey::Rope::from_str(&content);
+		buffer.replace_content(ropey::Rope::from_str(&content));
 
 		if let Some(ft) = file_type {
 			let current_ft = buffer.doc().file_type.clone();
@@ -130,8 +308,88 @@ impl Editor {
 		true
 	}
 
+	/// Moves focus into the most recently opened info popup, so the usual
+	/// scroll keys page through its content.
+	///
+	/// Returns false if no info popup is open.
+	pub fn focus_info_popup(&mut self) -> bool {
+		let Some((window_id, buffer_id)) = self
+			.overlays
+			.get::<InfoPopupStore>()
+			.and_then(InfoPopupStore::latest)
+			.map(|popup| (popup.window_id, popup.buffer_id))
+		else {
+			return false;
+		};
+		self.focus_buffer_in_window(window_id, buffer_id, true);
+		self.frame.needs_redraw = true;
+		true
+	}
+
 	/// Returns the number of open info popups.
 	pub fn info_popup_count(&self) -> usize {
 		self.overlays.get::<InfoPopupStore>().map_or(0, |s| s.len())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use xeno_tui::layout::Rect;
+
+	use super::*;
+
+	fn editor() -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.viewport.doc_area = Some(Rect::new(0, 1, 80, 22));
+		editor
+	}
+
+	fn open(editor: &mut Editor, content: &str) -> InfoPopupId {
+		editor
+			.open_info_popup(content.to_string(), None, PopupAnchor::Center)
+			.unwrap()
+	}
+
+	#[test]
+	fn new_popups_replace_only_unpinned_ones() {
+		let mut editor = editor();
+		let pinned = open(&mut editor, "first");
+		assert_eq!(editor.toggle_pin_info_popup(), Some(true));
+		open(&mut editor, "second");
+		let third = open(&mut editor, "third");
+		assert_eq!(editor.info_popup_count(), 2);
+
+		let store = editor.overlays.get::<InfoPopupStore>().unwrap();
+		let ids: Vec<_> = store.ordered().iter().map(|popup| popup.id).collect();
+		assert_eq!(ids, [pinned, third]);
+		let window_id = store.get(pinned).unwrap().window_id;
+		let Some(Window::Floating(float)) = editor.windows.get(window_id) else {
+			panic!("popup window missing");
+		};
+		assert!(!float.dismiss_on_blur);
+		assert_eq!(float.style.title.as_deref(), Some(PINNED_TITLE));
+	}
+
+	#[test]
+	fn focus_cycles_through_popups_without_dismissing() {
+		let mut editor = editor();
+		let pinned = open(&mut editor, "first");
+		editor.toggle_pin_info_popup();
+		let unpinned = open(&mut editor, "second");
+
+		assert!(editor.focus_next_info_popup());
+		assert_eq!(editor.focused_info_popup(), Some(pinned));
+		assert!(editor.focus_next_info_popup());
+		assert_eq!(editor.focused_info_popup(), Some(unpinned));
+		assert_eq!(editor.info_popup_count(), 2);
+
+		// Leaving the last popup returns to the document, which dismisses
+		// the unpinned popup as it loses focus.
+		assert!(editor.focus_next_info_popup());
+		assert_eq!(editor.focused_info_popup(), None);
+		assert_eq!(editor.info_popup_count(), 1);
+
+		editor.close_all_info_popups();
+		assert!(!editor.focus_next_info_popup());
+	}
+}
@@ -0,0 +1,236 @@
+//! Inlay hint requests.
+//!
+//! While `inlay-hints` is on for a buffer shown in the base layout, its
+//! hints are requested for the visible lines plus a screenful on either
+//! side. They are requested again when the view scrolls past the lines
+//! last covered, when a server sends `workspace/inlayHint/refresh`, and
+//! after edits once the `buffer:change` hook has been quiet for
+//! [`INLAY_HINT_DEBOUNCE`]. Until then the hints already shown follow the
+//! edits through [`PreparedInlayHints::map_through`]. Turning the option
+//! off drops them.
+//!
+//! [`PreparedInlayHints::map_through`]: crate::buffer::PreparedInlayHints::map_through
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::ops::Range;
+use std::time::Duration;
+
+use tracing::debug;
+use xeno_base::Rope;
+use xeno_lsp::OffsetEncoding;
+use xeno_lsp::lsp_types::InlayHint;
+use xeno_registry::options::keys;
+
+use super::Editor;
+use super::lsp_requests::{DocumentRequests, LspRequestChanges};
+use crate::buffer::{BufferId, DocumentId, PreparedInlayHint};
+
+/// How long edits must pause before hints are requested again.
+pub const INLAY_HINT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Answer to an inlay hint request, with the offset encoding of its
+/// positions.
+type Answer = (Vec<InlayHint>, OffsetEncoding);
+
+/// What an inlay hint request was made with.
+#[derive(Clone)]
+struct Asked {
+	/// Characters the hints are requested for.
+	range: Range<usize>,
+	/// Refresh counter of the language servers when it was made.
+	refresh: u64,
+}
+
+/// A document shown in the base layout, with the lines to cover.
+struct Shown {
+	/// One of the buffers showing the document.
+	buffer: BufferId,
+	/// Characters visible across its views.
+	visible: Range<usize>,
+	/// Characters to request hints for.
+	wanted: Range<usize>,
+	/// Current document version.
+	version: u64,
+}
+
+/// Overlay state for inlay hint requests.
+#[derive(Default)]
+pub(crate) struct InlayHintState {
+	/// Requests of each document with hints turned on.
+	requests: DocumentRequests<Answer, Asked>,
+}
+
+impl Editor {
+	/// Sends the inlay hint requests the shown buffers need, takes in
+	/// responses and drops the hints of buffers that turned them off.
+	pub(crate) fn poll_inlay_hints(&mut self) {
+		let mut changed = self.receive_inlay_hints();
+
+		let mut shown: HashMap<DocumentId, Shown> = HashMap::new();
+		let mut disabled = Vec::new();
+		let views = self
+			.layout
+			.compute_view_areas(&self.base_window().layout, self.doc_area());
+		for (view, area) in views {
+			let Some(buffer) = self.buffers.get_buffer(view) else {
+				continue;
+			};
+			let document = buffer.document_id();
+			if !self.resolve_typed_option(view, keys::INLAY_HINTS) || self.large_file_mode(view) {
+				disabled.push((view, document));
+				continue;
+			}
+			let height = usize::from(area.height);
+			let doc = buffer.doc();
+			let visible = line_span(&doc.content, buffer.scroll_line, height);
+			let wanted = line_span(
+				&doc.content,
+				buffer.scroll_line.saturating_sub(height),
+				height * 3,
+			);
+			match shown.entry(document) {
+				Entry::Occupied(mut entry) => {
+					let entry = entry.get_mut();
+					entry.visible =
+						entry.visible.start.min(visible.start)..entry.visible.end.max(visible.end);
+					entry.wanted =
+						entry.wanted.start.min(wanted.start)..entry.wanted.end.max(wanted.end);
+				}
+				Entry::Vacant(entry) => {
+					entry.insert(Shown {
+						buffer: view,
+						visible,
+						wanted,
+						version: doc.version,
+					});
+				}
+			}
+		}
+
+		let state = self.overlays.get_or_default::<InlayHintState>();
+		for (view, document) in disabled {
+			if shown.contains_key(&document) {
+				continue;
+			}
+			state.requests.forget(document);
+			if let Some(buffer) = self.buffers.get_buffer(view) {
+				let mut doc = buffer.doc_mut();
+				if doc.inlay_hints.covered().is_some() {
+					doc.inlay_hints.clear();
+					changed = true;
+				}
+			}
+		}
+
+		let last_change = self
+			.extensions
+			.get::<LspRequestChanges>()
+			.and_then(LspRequestChanges::last);
+		let refresh = self.lsp.inlay_hints_version();
+		for (document, shown) in shown {
+			let requests = &mut self.overlays.get_or_default::<InlayHintState>().requests;
+			if requests.is_pending(document) {
+				continue;
+			}
+			let Some(buffer) = self.buffers.get_buffer(shown.buffer) else {
+				continue;
+			};
+			let covered = buffer.doc().inlay_hints.covered();
+			let scrolled = covered.is_none_or(|covered| {
+				covered.start > shown.visible.start || covered.end < shown.visible.end
+			});
+			let due = requests.is_due(document, shown.version, last_change, INLAY_HINT_DEBOUNCE);
+			let stale = requests
+				.context(document)
+				.is_some_and(|asked| scrolled || asked.refresh != refresh);
+			if !due && !stale {
+				continue;
+			}
+
+			let request = self.lsp.inlay_hints(buffer, shown.wanted.clone());
+			let asked = Asked {
+				range: shown.wanted.clone(),
+				refresh,
+			};
+			let sent = requests.send(document, shown.buffer, shown.version, asked, request);
+			let supported = sent.unwrap_or_else(|e| {
+				debug!(error = %e, "inlay hint request failed");
+				false
+			});
+			if !supported {
+				// Cover the lines anyway so only a retry asks again.
+				let mut doc = buffer.doc_mut();
+				changed |= !doc.inlay_hints.is_empty();
+				doc.inlay_hints.replace(shown.wanted, Vec::new());
+			}
+		}
+
+		if changed {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Takes in the responses that have arrived. Returns whether any hints
+	/// changed.
+	fn receive_inlay_hints(&mut self) -> bool {
+		let state = self.overlays.get_or_default::<InlayHintState>();
+		let mut changed = false;
+		for arrived in state.requests.receive() {
+			let Some(buffer) = self.buffers.get_buffer(arrived.buffer) else {
+				continue;
+			};
+			let mut doc = buffer.doc_mut();
+			// Positions in a response to an older version would land on
+			// the wrong text; the edit since then asks again.
+			if doc.version != arrived.version {
+				continue;
+			}
+			let hints = match arrived.response {
+				Ok(Some((hints, encoding))) => hints
+					.iter()
+					.map(|hint| PreparedInlayHint::from_lsp(hint, &doc.content, encoding))
+					.collect(),
+				Ok(None) => Vec::new(),
+				Err(e) => {
+					debug!(error = %e, "inlay hint request failed");
+					state.requests.set_unsupported(arrived.document);
+					Vec::new()
+				}
+			};
+			changed |= !(hints.is_empty() && doc.inlay_hints.is_empty());
+			doc.inlay_hints.replace(arrived.context.range, hints);
+		}
+		changed
+	}
+
+	/// Returns the tooltips of the inlay hints next to the cursor, each
+	/// under the label it belongs to.
+	pub(crate) fn inlay_hint_tooltips(&self) -> Vec<String> {
+		let buffer = self.buffer();
+		let doc = buffer.doc();
+		let max_len = buffer.option(keys::INLAY_HINT_MAX_LENGTH, self).max(0) as usize;
+		doc.inlay_hints
+			.at(buffer.cursor)
+			.chain(doc.inlay_hints.at(buffer.cursor + 1))
+			.filter_map(|hint| {
+				let tooltips: Vec<_> = hint.tooltips().collect();
+				if tooltips.is_empty() {
+					return None;
+				}
+				Some(format!(
+					"`{}`\n\n{}",
+					hint.label(max_len).trim(),
+					tooltips.join("\n\n")
+				))
+			})
+			.collect()
+	}
+}
+
+/// Returns the characters of the `count` lines starting at `first`,
+/// clamped to the text.
+fn line_span(text: &Rope, first: usize, count: usize) -> Range<usize> {
+	let lines = text.len_lines();
+	text.line_to_char(first.min(lines))..text.line_to_char((first + count).min(lines))
+}
@@ -3,7 +3,8 @@
 //! Processing keyboard input and dispatching actions.
 
 use termina::event::KeyCode;
-use xeno_base::{Key, Mode, Selection};
+use xeno_base::direction::SeqDirection;
+use xeno_base::{Key, Mode, Range, Selection};
 use xeno_input::KeyResult;
 
 use crate::editor::{Editor, FocusTarget};
@@ -69,6 +70,25 @@ impl Editor {
 
 	/// Processes a key event, routing to menus, UI, or input state machine.
 	pub async fn handle_key(&mut self, key: termina::event::KeyEvent) -> bool {
+		// A save conflict prompt is modal until answered
+		if self.save_conflict_open() {
+			self.handle_save_conflict_key(&key).await;
+			return false;
+		}
+
+		// So is a confirm popup
+		if self.confirm_open() {
+			self.handle_confirm_key(&key);
+			return false;
+		}
+
+		// And the workspace symbol picker
+		#[cfg(feature = "lsp")]
+		if self.symbol_picker().is_some() {
+			self.handle_symbol_picker_key(&key).await;
+			return false;
+		}
+
 		// Handle menu bar when active
 		if self.menu.is_active() {
 			self.handle_menu_key(&key);
@@ -111,12 +131,69 @@ impl Editor {
 
 		let old_mode = self.mode();
 
+		if self.search_prompt_buffer().is_some()
+			&& key.modifiers.contains(termina::event::Modifiers::CONTROL)
+		{
+			let direction = match key.code {
+				KeyCode::Char('n') => Some(SeqDirection::Next),
+				KeyCode::Char('p') => Some(SeqDirection::Prev),
+				_ => None,
+			};
+			if let Some(direction) = direction {
+				self.cycle_search_preview(direction);
+				return false;
+			}
+		}
+
+		#[cfg(feature = "lsp")]
+		if self.mode() == Mode::Insert && self.handle_lsp_completion_key(&key) {
+			return false;
+		}
+
+		#[cfg(feature = "lsp")]
+		if self.mode() == Mode::Insert
+			&& !self.completion_active()
+			&& key.modifiers.contains(termina::event::Modifiers::CONTROL)
+		{
+			let direction = match key.code {
+				KeyCode::Char('n') => Some(SeqDirection::Next),
+				KeyCode::Char('p') => Some(SeqDirection::Prev),
+				_ => None,
+			};
+			if let Some(direction) = direction
+				&& self.cycle_signature_help(direction)
+			{
+				return false;
+			}
+		}
+
+		if key.code == KeyCode::Tab && self.prompt_buffer().is_some() {
+			self.complete_prompt();
+			return false;
+		}
+
 		if self.palette_is_open() && key.code == KeyCode::Enter {
 			self.execute_palette();
 			self.frame.needs_redraw = true;
 			return false;
 		}
 
+		if self.mode() == Mode::Insert {
+			let direction = match key.code {
+				KeyCode::BackTab => Some(SeqDirection::Prev),
+				KeyCode::Tab if key.modifiers.contains(termina::event::Modifiers::SHIFT) => {
+					Some(SeqDirection::Prev)
+				}
+				KeyCode::Tab => Some(SeqDirection::Next),
+				_ => None,
+			};
+			if let Some(direction) = direction
+				&& self.snippet_jump(direction)
+			{
+				return false;
+			}
+		}
+
 		if self.handle_floating_escape(&key) {
 			return false;
 		}
@@ -134,8 +211,15 @@ impl Editor {
 				false
 			}
 			KeyResult::ModeChange(new_mode) => {
-				let leaving_insert = !matches!(new_mode, Mode::Insert);
 				if new_mode != old_mode {
+					// Cancelling a pending action returns to the mode it was
+					// entered from, so an insert session keeps its undo group.
+					if !matches!(old_mode, Mode::PendingAction(_)) {
+						self.end_undo_group();
+					}
+					if new_mode == Mode::Normal {
+						self.end_snippet_session();
+					}
 					emit_hook(&HookContext::new(
 						HookEventData::ModeChange {
 							old_mode,
@@ -145,16 +229,26 @@ impl Editor {
 					))
 					.await;
 				}
-				if leaving_insert {
-					self.buffer_mut().clear_insert_undo_active();
-				}
 				false
 			}
 			KeyResult::InsertChar(c) => {
 				if !self.guard_readonly() {
 					return false;
 				}
-				self.insert_text(&c.to_string());
+				self.replace_snippet_placeholder();
+				if c == '\n' {
+					self.insert_newline();
+				} else {
+					self.insert_text(&c.to_string());
+					#[cfg(feature = "lsp")]
+					self.signature_help_typed(c);
+				}
+				false
+			}
+			KeyResult::InvalidCodepoint(value) => {
+				self.show_notification(xeno_registry_notifications::keys::invalid_codepoint::call(
+					value,
+				));
 				false
 			}
 			KeyResult::Consumed | KeyResult::Unhandled => false,
@@ -203,10 +297,35 @@ impl Editor {
 				buffer.set_selection(Selection::point(doc_pos));
 			}
 			buffer.sync_cursor_to_selection();
-			buffer.establish_goal_column();
+			buffer.establish_goal_column(tab_width);
 		}
 	}
 
+	/// Handles a double-click with view-local coordinates by selecting the
+	/// word under the pointer.
+	pub(crate) fn handle_mouse_double_click_local(&mut self, local_row: u16, local_col: u16) {
+		let tab_width = self.tab_width();
+		let Some(doc_pos) = self
+			.buffer()
+			.screen_to_doc_position(local_row, local_col, tab_width)
+		else {
+			return;
+		};
+		let buffer = self.buffer_mut();
+		let word = {
+			let doc = buffer.doc();
+			xeno_core::movement::select_word_object(
+				doc.content.slice(..),
+				Range::point(doc_pos),
+				xeno_core::WordType::Word,
+				true,
+			)
+		};
+		buffer.set_selection(Selection::from(word));
+		buffer.sync_cursor_to_selection();
+		buffer.establish_goal_column(tab_width);
+	}
+
 	/// Handles mouse drag with view-local coordinates.
 	pub(crate) fn handle_mouse_drag_local(&mut self, local_row: u16, local_col: u16) {
 		let tab_width = self.tab_width();
@@ -233,6 +352,14 @@ impl Editor {
 			KeyCode::Right | KeyCode::Char('l') => self.menu.right(),
 			KeyCode::Up | KeyCode::Char('k') => self.menu.up(),
 			KeyCode::Down | KeyCode::Char('j') => self.menu.down(),
+			KeyCode::Char(c) => {
+				if self.menu.handle_char(c) {
+					crate::menu::process_menu_events(
+						&mut self.menu,
+						&mut self.workspace.command_queue,
+					);
+				}
+			}
 			_ => {}
 		}
 		self.frame.needs_redraw = true;
@@ -265,7 +392,9 @@ impl Editor {
 			return true;
 		}
 
-		if floating.dismiss_on_blur {
+		if let Some(popup_id) = self.info_popup_in_window(window) {
+			self.close_info_popup(popup_id);
+		} else if floating.dismiss_on_blur {
 			self.close_floating_window(window);
 		}
 
@@ -2,19 +2,38 @@
 //!
 //! Processing mouse input for text selection and separator dragging.
 
+use std::time::{Duration, Instant};
+
 use termina::event::MouseEventKind;
-use xeno_base::Selection;
+use xeno_base::{ScrollDirection, Selection};
 use xeno_input::KeyResult;
+use xeno_registry::options::keys;
 
 use crate::editor::{Editor, FocusTarget};
 use crate::window::Window;
 
+/// Longest gap between two clicks on the same cell that counts as a
+/// double-click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 impl Editor {
 	/// Processes a mouse event, returning true if the event triggered a quit.
+	///
+	/// Ignores all events while the `mouse` option is off; the terminal
+	/// layer releases capture in that case, but events may still be queued.
 	pub async fn handle_mouse(&mut self, mouse: termina::event::MouseEvent) -> bool {
+		if !self.option(keys::MOUSE) {
+			return false;
+		}
+
 		let width = self.viewport.width.unwrap_or(80);
 		let height = self.viewport.height.unwrap_or(24);
 
+		// The statusline has no click targets; keep its row out of the views.
+		if mouse.row + 1 == height && !self.menu.is_active() {
+			return false;
+		}
+
 		// Handle menu bar clicks (row 0) or when menu is active
 		if mouse.row == 0 || self.menu.is_active() {
 			let menu_x = mouse.column;
@@ -52,15 +71,26 @@ impl Editor {
 			}
 		}
 
-		// Main area excludes menu bar (1 row) and status line (1 row)
-		let main_height = height.saturating_sub(2);
-		let main_area = xeno_tui::layout::Rect {
-			x: 0,
-			y: 1,
-			width,
-			height: main_height,
-		};
+		// Buffer line clicks (row 1, when shown)
+		if mouse.row == 1 && self.bufferline_visible() {
+			if let MouseEventKind::Down(_) = mouse.kind {
+				let area = xeno_tui::layout::Rect {
+					x: 0,
+					y: 1,
+					width,
+					height: 1,
+				};
+				if let Some(index) = self.bufferline().tab_at(area, mouse.column, mouse.row)
+					&& let Some(&id) = self.buffer_ids().get(index)
+				{
+					self.show_buffer(id);
+					self.frame.needs_redraw = true;
+				}
+			}
+			return false;
+		}
 
+		let main_area = self.main_area();
 		let mut ui = std::mem::take(&mut self.ui);
 		let dock_layout = ui.compute_layout(main_area);
 
@@ -173,6 +203,21 @@ impl Editor {
 			}
 		}
 
+		if self.handle_completion_mouse(mouse) {
+			return false;
+		}
+
+		// Clicking outside every popup dismisses the ones that are not
+		// pinned; the click itself still lands on the view underneath.
+		if matches!(mouse.kind, MouseEventKind::Down(_))
+			&& !self
+				.windows
+				.floating_windows()
+				.any(|(_, window)| window.contains(mouse_x, mouse_y))
+		{
+			self.close_unpinned_info_popups();
+		}
+
 		let mut floating_hit = None;
 		for (window_id, window) in self.windows.floating_windows() {
 			if window.contains(mouse_x, mouse_y) {
@@ -180,6 +225,22 @@ impl Editor {
 			}
 		}
 
+		// The wheel scrolls the popup under the pointer in place, leaving
+		// focus (and any popup that closes on blur) where it was.
+		let wheel = match mouse.kind {
+			MouseEventKind::ScrollUp => Some(ScrollDirection::Up),
+			MouseEventKind::ScrollDown => Some(ScrollDirection::Down),
+			_ => None,
+		};
+		if let Some(direction) = wheel
+			&& let Some((_, window)) = floating_hit
+		{
+			let buffer_id = window.buffer;
+			self.scroll_buffer_viewport(buffer_id, direction, 1);
+			self.frame.needs_redraw = true;
+			return false;
+		}
+
 		let separator_hit = if floating_hit.is_some() {
 			None
 		} else {
@@ -311,7 +372,19 @@ impl Editor {
 		match result {
 			KeyResult::MouseClick { extend, .. } => {
 				self.layout.text_selection_origin = Some((target_view, view_area));
-				self.handle_mouse_click_local(local_row, local_col, extend);
+				let now = Instant::now();
+				let double = !extend
+					&& self.layout.last_click.is_some_and(|(view, row, col, at)| {
+						(view, row, col) == (target_view, mouse_y, mouse_x)
+							&& now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+					});
+				if double {
+					self.layout.last_click = None;
+					self.handle_mouse_double_click_local(local_row, local_col);
+				} else {
+					self.layout.last_click = Some((target_view, mouse_y, mouse_x, now));
+					self.handle_mouse_click_local(local_row, local_col, extend);
+				}
 				false
 			}
 			KeyResult::MouseDrag { .. } => {
@@ -350,18 +423,24 @@ impl Editor {
 		doc_area
 	}
 
-	/// Computes the document area based on current window dimensions.
-	pub fn doc_area(&self) -> xeno_tui::layout::Rect {
+	/// Computes the area between the top bars and the status line.
+	///
+	/// Excludes the menu bar, the buffer line when it is shown, and the
+	/// status line.
+	fn main_area(&self) -> xeno_tui::layout::Rect {
 		let width = self.viewport.width.unwrap_or(80);
 		let height = self.viewport.height.unwrap_or(24);
-		// Exclude menu bar (1 row) and status line (1 row)
-		let main_height = height.saturating_sub(2);
-		let main_area = xeno_tui::layout::Rect {
+		let top = if self.bufferline_visible() { 2 } else { 1 };
+		xeno_tui::layout::Rect {
 			x: 0,
-			y: 1,
+			y: top,
 			width,
-			height: main_height,
-		};
-		self.ui.compute_layout(main_area).doc_area
+			height: height.saturating_sub(top + 1),
+		}
+	}
+
+	/// Computes the document area based on current window dimensions.
+	pub fn doc_area(&self) -> xeno_tui::layout::Rect {
+		self.ui.compute_layout(self.main_area()).doc_area
 	}
 }
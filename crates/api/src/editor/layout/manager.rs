@@ -2,6 +2,8 @@
 //!
 //! The [`LayoutManager`] owns stacked layout layers and coordinates all layout operations.
 
+use std::time::Instant;
+
 use xeno_tui::layout::Rect;
 
 use crate::buffer::{BufferView, Layout, SplitDirection};
@@ -32,6 +34,10 @@ pub struct LayoutManager {
 
 	/// Tracks the view where a text selection drag started.
 	pub text_selection_origin: Option<(BufferView, Rect)>,
+
+	/// View, screen cell (row, column), and time of the last text click,
+	/// used to detect double-clicks.
+	pub last_click: Option<(BufferView, u16, u16, Instant)>,
 }
 
 impl Default for LayoutManager {
@@ -44,6 +50,7 @@ impl Default for LayoutManager {
 			mouse_velocity: MouseVelocityTracker::default(),
 			dragging_separator: None,
 			text_selection_origin: None,
+			last_click: None,
 		}
 	}
 }
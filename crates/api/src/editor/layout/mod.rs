@@ -17,7 +17,7 @@
 //! - [`types`] - Type definitions (`LayerIndex`, `SeparatorId`, `SeparatorHit`)
 //! - [`layers`] - Layer management and area computation
 //! - [`views`] - View navigation and lookup
-//! - [`splits`] - Split creation and removal
+//! - [`splits`] - Split creation, resizing, and removal
 //! - [`separators`] - Separator hit detection
 //! - [`drag`] - Drag state and hover animation
 
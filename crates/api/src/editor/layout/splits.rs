@@ -1,11 +1,12 @@
-//! Split creation and removal.
+//! Split creation, resizing, and removal.
 //!
-//! Creating horizontal/vertical splits and removing views from the layout.
+//! Creating horizontal/vertical splits, resizing them, and removing views
+//! from the layout.
 
 use xeno_tui::layout::Rect;
 
 use super::manager::LayoutManager;
-use crate::buffer::{BufferId, BufferView, Layout};
+use crate::buffer::{BufferId, BufferView, Layout, SplitDirection};
 
 impl LayoutManager {
 	/// Creates a horizontal split with a new buffer below the current view.
@@ -14,16 +15,8 @@ impl LayoutManager {
 		base_layout: &mut Layout,
 		current_view: BufferView,
 		new_buffer_id: BufferId,
-		doc_area: Rect,
 	) {
-		let Some(view_area) = self.view_area(base_layout, current_view, doc_area) else {
-			return;
-		};
-		let new_layout = Layout::stacked(
-			Layout::single(current_view),
-			Layout::text(new_buffer_id),
-			view_area,
-		);
+		let new_layout = Layout::stacked(Layout::single(current_view), Layout::text(new_buffer_id));
 		if let Some(layer_idx) = self.layer_of_view(base_layout, current_view) {
 			if layer_idx == 0 {
 				base_layout.replace_view(current_view, new_layout);
@@ -39,16 +32,9 @@ impl LayoutManager {
 		base_layout: &mut Layout,
 		current_view: BufferView,
 		new_buffer_id: BufferId,
-		doc_area: Rect,
 	) {
-		let Some(view_area) = self.view_area(base_layout, current_view, doc_area) else {
-			return;
-		};
-		let new_layout = Layout::side_by_side(
-			Layout::single(current_view),
-			Layout::text(new_buffer_id),
-			view_area,
-		);
+		let new_layout =
+			Layout::side_by_side(Layout::single(current_view), Layout::text(new_buffer_id));
 		if let Some(layer_idx) = self.layer_of_view(base_layout, current_view) {
 			if layer_idx == 0 {
 				base_layout.replace_view(current_view, new_layout);
@@ -58,20 +44,52 @@ impl LayoutManager {
 		}
 	}
 
-	/// Gets the area of a specific view.
-	pub(super) fn view_area(
-		&self,
-		base_layout: &Layout,
+	/// Grows `view` by `delta` cells along `direction`, or shrinks it when
+	/// `delta` is negative.
+	///
+	/// Returns false if no split of `direction` in the view's layer contains it.
+	pub fn resize_view(
+		&mut self,
+		base_layout: &mut Layout,
 		view: BufferView,
 		doc_area: Rect,
-	) -> Option<Rect> {
-		let layer_idx = self.layer_of_view(base_layout, view)?;
+		direction: SplitDirection,
+		delta: i32,
+	) -> bool {
+		let Some(layer_idx) = self.layer_of_view(base_layout, view) else {
+			return false;
+		};
 		let layer_area = self.layer_area(layer_idx, doc_area);
-		self.layer(base_layout, layer_idx)?
-			.compute_view_areas(layer_area)
-			.into_iter()
-			.find(|(v, _)| *v == view)
-			.map(|(_, area)| area)
+		self.layer_mut(base_layout, layer_idx)
+			.is_some_and(|layout| layout.resize_view(layer_area, view, direction, delta))
+	}
+
+	/// Sets the size of `view` along `direction` to `size` cells.
+	///
+	/// Returns false if no split of `direction` in the view's layer contains it.
+	pub fn set_view_size(
+		&mut self,
+		base_layout: &mut Layout,
+		view: BufferView,
+		doc_area: Rect,
+		direction: SplitDirection,
+		size: u16,
+	) -> bool {
+		let Some(layer_idx) = self.layer_of_view(base_layout, view) else {
+			return false;
+		};
+		let layer_area = self.layer_area(layer_idx, doc_area);
+		self.layer_mut(base_layout, layer_idx)
+			.is_some_and(|layout| layout.set_view_size(layer_area, view, direction, size))
+	}
+
+	/// Shares space equally between the views in `view`'s layer.
+	pub fn equalize(&mut self, base_layout: &mut Layout, view: BufferView) {
+		if let Some(layer_idx) = self.layer_of_view(base_layout, view)
+			&& let Some(layout) = self.layer_mut(base_layout, layer_idx)
+		{
+			layout.equalize();
+		}
 	}
 
 	/// Removes a view from its layer, collapsing splits as needed.
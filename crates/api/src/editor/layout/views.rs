@@ -8,6 +8,22 @@ use super::manager::LayoutManager;
 use crate::buffer::{BufferId, BufferView, Layout, SpatialDirection};
 
 impl LayoutManager {
+	/// Shows `buffer_id` in place of `current_view`, keeping the split shape.
+	///
+	/// Returns false if no layer holds `current_view`.
+	pub fn replace_view_buffer(
+		&mut self,
+		base_layout: &mut Layout,
+		current_view: BufferView,
+		buffer_id: BufferId,
+	) -> bool {
+		let Some(layer_idx) = self.layer_of_view(base_layout, current_view) else {
+			return false;
+		};
+		self.layer_mut(base_layout, layer_idx)
+			.is_some_and(|layout| layout.replace_view(current_view, Layout::text(buffer_id)))
+	}
+
 	/// Returns the first view in the layout (from topmost non-empty layer).
 	pub fn first_view(&self, base_layout: &Layout) -> BufferView {
 		for i in (1..self.layers.len()).rev() {
@@ -5,9 +5,11 @@
 #[cfg(feature = "lsp")]
 use std::collections::HashSet;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use tracing::{debug, warn};
 use xeno_registry::commands::{CommandContext, CommandOutcome, find_command};
+use xeno_registry::options::keys;
 use xeno_registry::{HookContext, HookEventData, emit_sync_with as emit_hook_sync_with};
 
 use super::Editor;
@@ -32,15 +34,38 @@ impl Editor {
 		self.ui = ui;
 	}
 
-	/// Runs the main editor tick: dirty buffer hooks, LSP sync, and animations.
+	/// Runs the main editor tick: file loading, dirty buffer hooks, idle
+	/// hooks, LSP sync, autosave timing, swap files, and animations.
 	pub fn tick(&mut self) {
 		// Check if separator animation needs continuous redraws
 		if self.layout.animation_needs_redraw() {
 			self.frame.needs_redraw = true;
 		}
 
+		self.expire_pending_action();
+		self.poll_file_loads();
+		self.poll_progress_notifications();
+		self.flush_notification_summaries();
+
+		#[cfg(feature = "lsp")]
+		{
+			let published: Vec<PathBuf> = self
+				.lsp
+				.poll_diagnostics()
+				.into_iter()
+				.map(|event| event.path)
+				.collect();
+			if self.prepare_buffer_diagnostics(&published) {
+				self.frame.needs_redraw = true;
+			}
+		}
+
+		#[cfg(feature = "lsp")]
+		self.poll_lsp_servers();
+
+		// Keep the statusline spinner turning while a server reports progress.
 		#[cfg(feature = "lsp")]
-		if !self.lsp.poll_diagnostics().is_empty() {
+		if self.lsp.documents().has_progress() {
 			self.frame.needs_redraw = true;
 		}
 
@@ -48,6 +73,20 @@ impl Editor {
 		let mut lsp_docs: HashSet<crate::buffer::DocumentId> = HashSet::new();
 
 		let dirty_ids: Vec<_> = self.frame.dirty_buffers.drain().collect();
+		if !dirty_ids.is_empty() {
+			self.note_autosave_edit();
+			self.refresh_search_matches();
+		}
+		if let Some(prompt_buffer) = self.search_prompt_buffer()
+			&& dirty_ids.contains(&prompt_buffer)
+		{
+			self.update_search_preview();
+		}
+		if let Some(prompt_buffer) = self.prompt_buffer()
+			&& dirty_ids.contains(&prompt_buffer)
+		{
+			self.update_prompt_validation();
+		}
 		for buffer_id in dirty_ids {
 			if let Some(buffer) = self.buffers.get_buffer(buffer_id) {
 				let scratch_path = PathBuf::from("[scratch]");
@@ -69,17 +108,75 @@ impl Editor {
 				);
 
 				#[cfg(feature = "lsp")]
-				if lsp_docs.insert(buffer.document_id()) {
+				if !self.large_file_mode(buffer_id) && lsp_docs.insert(buffer.document_id()) {
 					self.queue_lsp_change(buffer_id);
 				}
 			}
 		}
+		let (buffer_id, cursor, version) = {
+			let buffer = self.buffer();
+			(buffer.id, buffer.cursor, buffer.version())
+		};
+		if self
+			.frame
+			.idle
+			.observe(buffer_id, cursor, version, Instant::now())
+		{
+			emit_hook_sync_with(
+				&HookContext::new(HookEventData::EditorIdle, Some(&self.extensions)),
+				&mut self.hook_runtime,
+			);
+		}
+
+		#[cfg(feature = "lsp")]
+		self.poll_signature_help();
+		#[cfg(feature = "lsp")]
+		self.poll_lsp_completion();
+		#[cfg(feature = "lsp")]
+		self.poll_symbol_picker();
+		#[cfg(feature = "lsp")]
+		self.poll_inlay_hints();
+		#[cfg(feature = "lsp")]
+		self.poll_document_highlight();
+		#[cfg(feature = "lsp")]
+		self.poll_folding_ranges();
+		#[cfg(feature = "lsp")]
+		self.poll_semantic_tokens();
+		self.update_idle_autosave();
+		self.write_swap_files();
+		self.prompt_swap_recovery();
+
 		emit_hook_sync_with(
 			&HookContext::new(HookEventData::EditorTick, Some(&self.extensions)),
 			&mut self.hook_runtime,
 		);
 	}
 
+	/// Cancels a pending action that has waited past `pending-timeout`.
+	fn expire_pending_action(&mut self) {
+		let timeout = self.option(keys::PENDING_TIMEOUT);
+		if timeout <= 0
+			|| !self
+				.buffer()
+				.input
+				.pending_expired(Duration::from_millis(timeout as u64))
+		{
+			return;
+		}
+
+		let old_mode = self.mode();
+		if let Some(new_mode) = self.buffer_mut().input.cancel_pending() {
+			emit_hook_sync_with(
+				&HookContext::new(
+					HookEventData::ModeChange { old_mode, new_mode },
+					Some(&self.extensions),
+				),
+				&mut self.hook_runtime,
+			);
+			self.frame.needs_redraw = true;
+		}
+	}
+
 	/// Marks a buffer dirty for LSP full sync (clears incremental changes, bumps version).
 	///
 	/// Use this after operations that replace the entire document content (e.g., undo/redo)
@@ -89,9 +186,7 @@ impl Editor {
 			let mut doc = buffer.doc_mut();
 			doc.version = doc.version.wrapping_add(1);
 			#[cfg(feature = "lsp")]
-			{
-				doc.pending_lsp_changes.clear();
-			}
+			doc.require_lsp_full_sync();
 		}
 		self.frame.dirty_buffers.insert(buffer_id);
 	}
@@ -104,7 +199,11 @@ impl Editor {
 	#[cfg(feature = "lsp")]
 	const LSP_MAX_INCREMENTAL_BYTES: usize = 100 * 1024; // 100 KB
 
-	/// Queues an LSP buffer change notification to be processed asynchronously.
+	/// Sends an LSP buffer change notification.
+	///
+	/// The notification goes out right away so versions follow the order of
+	/// edits; only opening a document not yet known to its server is done
+	/// asynchronously, with the text as of then.
 	#[cfg(feature = "lsp")]
 	fn queue_lsp_change(&mut self, buffer_id: crate::buffer::BufferId) {
 		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
@@ -115,13 +214,14 @@ impl Editor {
 		};
 		let content = buffer.doc().content.clone();
 		let changes = buffer.drain_lsp_changes();
-		let supports_incremental = self.lsp.incremental_encoding_for_buffer(buffer).is_some();
+		let encoding = self.lsp.incremental_encoding_for_buffer(buffer);
+		let supports_incremental = encoding.is_some();
 
 		// Safety fallback: skip incremental if too many changes or too much data
-		let change_count = changes.len();
-		let total_bytes: usize = changes.iter().map(|c| c.new_text.len()).sum();
+		let change_count = changes.as_ref().map_or(0, Vec::len);
+		let total_bytes: usize = changes.iter().flatten().map(|c| c.new_text.len()).sum();
 		let use_incremental = supports_incremental
-			&& !changes.is_empty()
+			&& change_count > 0
 			&& change_count <= Self::LSP_MAX_INCREMENTAL_CHANGES
 			&& total_bytes <= Self::LSP_MAX_INCREMENTAL_BYTES;
 
@@ -135,17 +235,55 @@ impl Editor {
 		);
 
 		let sync = self.lsp.sync().clone();
-		tokio::spawn(async move {
-			let result = if use_incremental {
-				sync.notify_change_incremental(&path, &language, &content, changes)
-					.await
-			} else {
-				sync.notify_change_full(&path, &language, &content).await
+		let changes = changes.filter(|_| use_incremental).zip(encoding);
+		match sync.try_notify_change(&path, &language, &content, changes) {
+			Ok(true) => {}
+			Ok(false) => {
+				tokio::spawn(async move {
+					if let Err(e) = sync.open_document(&path, &language, &content).await {
+						warn!(error = %e, path = ?path, "LSP document open failed");
+					}
+				});
+			}
+			Err(e) => warn!(error = %e, path = ?path, "LSP change notification failed"),
+		}
+	}
+
+	/// Prepares the diagnostics of buffers whose server just published for
+	/// one of the `published` paths, or that were never prepared.
+	///
+	/// Other buffers keep the diagnostics mapped through their edits, so
+	/// underlines stay on the text they were reported for. Returns whether
+	/// any buffer's diagnostics were replaced.
+	#[cfg(feature = "lsp")]
+	fn prepare_buffer_diagnostics(&mut self, published: &[PathBuf]) -> bool {
+		let published: Vec<_> = published
+			.iter()
+			.filter_map(|path| xeno_lsp::uri_from_path(path))
+			.collect();
+		let mut prepared = false;
+		for buffer in self.buffers.buffers() {
+			let Some(path) = buffer.path() else {
+				continue;
 			};
-			if let Err(e) = result {
-				warn!(error = %e, path = ?path, "LSP change notification failed");
+			let stale = buffer.doc().diagnostics.needs_sync()
+				|| (!published.is_empty()
+					&& xeno_lsp::uri_from_path(&path).is_some_and(|uri| published.contains(&uri)));
+			if !stale {
+				continue;
 			}
-		});
+			let diagnostics = self.lsp.diagnostics_with_encoding(&path);
+			let mut doc = buffer.doc_mut();
+			let items = diagnostics
+				.iter()
+				.filter_map(|(diagnostic, encoding)| {
+					crate::buffer::PreparedDiagnostic::from_lsp(diagnostic, &doc.content, *encoding)
+				})
+				.collect();
+			doc.diagnostics.replace(items);
+			prepared = true;
+		}
+		prepared
 	}
 
 	/// Clears and updates style overlays (called before each render frame).
@@ -190,6 +328,7 @@ impl Editor {
 	/// Handles terminal focus gained events, emitting the FocusGained hook.
 	pub fn handle_focus_in(&mut self) {
 		self.frame.needs_redraw = true;
+		self.set_terminal_focused(true);
 		emit_hook_sync_with(
 			&HookContext::new(HookEventData::FocusGained, Some(&self.extensions)),
 			&mut self.hook_runtime,
@@ -197,30 +336,56 @@ impl Editor {
 	}
 
 	/// Handles terminal focus lost events, emitting the FocusLost hook.
+	///
+	/// With `autosave` set to `focus-lost`, modified buffers are saved on the
+	/// next [`run_autosave`](Self::run_autosave). Until focus returns, error
+	/// notifications are forwarded per the `notify-desktop` option.
 	pub fn handle_focus_out(&mut self) {
 		self.frame.needs_redraw = true;
+		self.set_terminal_focused(false);
+		self.autosave_on_focus_lost();
 		emit_hook_sync_with(
 			&HookContext::new(HookEventData::FocusLost, Some(&self.extensions)),
 			&mut self.hook_runtime,
 		);
 	}
 
-	/// Handles paste events, delegating to UI or inserting text directly.
+	/// Handles a bracketed paste, delivering the payload as a whole.
+	///
+	/// A focused panel gets the paste first. Otherwise insert mode inserts it
+	/// as a single transaction that undoes in one step, the palette takes it
+	/// as one line of literal text, and normal mode stores it in the yank
+	/// register. The paste never passes through key handling, so its contents
+	/// cannot trigger actions.
 	pub fn handle_paste(&mut self, content: String) {
-		let mut ui = std::mem::take(&mut self.ui);
-		let handled = ui.handle_paste(self, content.clone());
-		if ui.take_wants_redraw() {
-			self.frame.needs_redraw = true;
+		self.frame.needs_redraw = true;
+
+		if self.ui.focused_panel_id().is_some() {
+			let mut ui = std::mem::take(&mut self.ui);
+			let handled = ui.handle_paste(self, content.clone());
+			ui.take_wants_redraw();
+			self.ui = ui;
+			self.sync_focus_from_ui();
+			if handled {
+				return;
+			}
 		}
-		self.ui = ui;
-		self.sync_focus_from_ui();
 
-		if handled {
-			self.frame.needs_redraw = true;
+		if self.palette_is_open() {
+			let line = content.lines().collect::<Vec<_>>().join(" ");
+			self.insert_text(&line);
 			return;
 		}
 
-		self.insert_text(&content);
+		if self.mode() == xeno_base::Mode::Insert {
+			self.end_undo_group();
+			self.insert_text(&content);
+			self.end_undo_group();
+		} else {
+			let count = content.chars().count();
+			self.workspace.registers.yank = content;
+			self.notify(xeno_registry_notifications::keys::yanked_chars::call(count));
+		}
 	}
 
 	/// Drains and executes all queued commands.
@@ -305,3 +470,35 @@ impl Editor {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Mode;
+
+	use super::*;
+
+	#[test]
+	fn insert_mode_paste_is_one_literal_undo_step() {
+		let mut editor = Editor::new_scratch();
+		editor.buffer_mut().input.set_mode(Mode::Insert);
+		editor.insert_text("ab");
+
+		editor.handle_paste("dd\n\tx\n%\n".to_string());
+		assert_eq!(editor.buffer().doc().content.to_string(), "abdd\n\tx\n%\n");
+		assert_eq!(editor.mode(), Mode::Insert);
+
+		editor.undo();
+		assert_eq!(editor.buffer().doc().content.to_string(), "ab");
+	}
+
+	#[test]
+	fn normal_mode_paste_fills_yank_register() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("text");
+
+		editor.handle_paste("dd\nx".to_string());
+		assert_eq!(editor.buffer().doc().content.to_string(), "text");
+		assert_eq!(editor.workspace.registers.yank, "dd\nx");
+		assert_eq!(editor.mode(), Mode::Normal);
+	}
+}
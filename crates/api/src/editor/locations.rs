@@ -0,0 +1,73 @@
+//! Location list navigation.
+//!
+//! Project search, references and diagnostics all fill
+//! the same [`LocationList`](super::LocationList); these methods replace it
+//! and jump to its entries.
+
+use xeno_registry::ScreenPosition;
+use xeno_registry::notifications::keys;
+
+use super::{Editor, JumpLocation, Location, LocationItem};
+use crate::ui::UiRequest;
+use crate::ui::locations::LOCATIONS_PANEL_ID;
+
+impl Editor {
+	/// Replaces the location list with `items` collected by `title`.
+	///
+	/// Panels call this from their event handlers and ask for the location
+	/// panel with [`UiRequest::OpenPanel`]; everywhere else
+	/// [`Editor::show_locations`] does both.
+	pub fn set_locations(&mut self, title: impl Into<String>, items: Vec<LocationItem>) {
+		self.notify(keys::locations_set::call(items.len()));
+		self.workspace.locations.set(title, items);
+	}
+
+	/// Replaces the location list and opens the location panel, unless
+	/// there is nothing to show. Focus stays where it is.
+	pub fn show_locations(&mut self, title: impl Into<String>, items: Vec<LocationItem>) {
+		let empty = items.is_empty();
+		self.set_locations(title, items);
+		if !empty {
+			self.apply_ui_requests(vec![UiRequest::OpenPanel(LOCATIONS_PANEL_ID.to_string())]);
+		}
+	}
+
+	/// Jumps to entry `index` of the location list, opening its file if
+	/// needed, and centers the target line.
+	///
+	/// The position left is pushed onto the jump list.
+	pub async fn goto_location_entry(&mut self, index: usize) -> anyhow::Result<()> {
+		let Some(item) = self.workspace.locations.items().get(index).cloned() else {
+			anyhow::bail!("no location {}", index + 1);
+		};
+		let origin = JumpLocation {
+			buffer_id: self.focused_view(),
+			cursor: self.buffer().cursor,
+		};
+		self.goto_location(&Location::new(item.path, item.line, item.column))
+			.await?;
+		self.workspace.jump_list.push(origin);
+		self.workspace.locations.select(index);
+		self.center_cursor_line();
+		Ok(())
+	}
+
+	/// Scrolls the focused view so the cursor line is in its middle.
+	///
+	/// A buffer that was just opened has not been drawn yet, so its
+	/// viewport height is taken from the layout.
+	pub(super) fn center_cursor_line(&mut self) {
+		if self.buffer().last_viewport_height == 0 {
+			let area = self.doc_area();
+			let focused = self.focused_view();
+			let height = self
+				.layout
+				.compute_view_areas(&self.base_window().layout, area)
+				.into_iter()
+				.find(|(view, _)| *view == focused)
+				.map_or(area.height, |(_, rect)| rect.height);
+			self.buffer_mut().last_viewport_height = usize::from(height);
+		}
+		self.align_view(ScreenPosition::Middle, 1);
+	}
+}
@@ -0,0 +1,763 @@
+//! Language server completion in insert mode.
+//!
+//! Ctrl-Space asks the buffer's server for completions at the cursor and
+//! opens the completion menu on the word before it. Typing narrows the
+//! list to the items fuzzy matching the word, best first, Up/Down and
+//! Ctrl-n/Ctrl-p move through it, Enter or Tab accepts and Escape closes
+//! it, as does moving the cursor off the word.
+//!
+//! Servers usually list items without their documentation and fill it in
+//! on `completionItem/resolve`. The selected item is resolved once the
+//! selection has rested on it for [`RESOLVE_DELAY`], so scrolling through
+//! the list sends nothing, and its detail and documentation show in a
+//! popup beside the menu. Accepting makes an item's `additionalTextEdits`,
+//! such as an import, in the same transaction as its own edit. An item
+//! whose resolve fails is shown and accepted as the server first sent it.
+
+use std::time::{Duration, Instant};
+
+use termina::event::{KeyCode, KeyEvent, Modifiers};
+use tokio::sync::oneshot;
+use tracing::debug;
+use xeno_base::range::CharIdx;
+use xeno_base::transaction::Change;
+use xeno_base::{Mode, RopeSlice, Selection, SeqDirection, Transaction};
+use xeno_core::completion::rank;
+use xeno_core::fuzzy::FuzzyMatch;
+use xeno_core::{CompletionItem, CompletionKind, MatchMode};
+use xeno_lsp::lsp_types::{
+	self, CompletionItemKind, CompletionResponse, CompletionTextEdit, Documentation,
+	InsertTextFormat,
+};
+use xeno_lsp::{OffsetEncoding, lsp_range_to_char_range};
+
+use super::Editor;
+use crate::buffer::BufferId;
+use crate::editor::types::CompletionState;
+
+/// How long the selection must rest on an item before it is resolved.
+pub(crate) const RESOLVE_DELAY: Duration = Duration::from_millis(120);
+
+/// Response to a completion request.
+type ListResponse = xeno_lsp::Result<Option<(CompletionResponse, OffsetEncoding)>>;
+
+/// Response to a resolve request.
+type ResolveResponse = xeno_lsp::Result<lsp_types::CompletionItem>;
+
+/// An item the server listed.
+#[derive(Debug, Clone)]
+struct Entry {
+	/// The item, replaced by its resolved form once that arrives.
+	item: lsp_types::CompletionItem,
+	/// Whether resolving the item was tried and has finished, whether or
+	/// not it succeeded.
+	resolved: bool,
+}
+
+/// Overlay state for language server completion.
+///
+/// The completion menu shows [`CompletionState::items`]; this keeps the
+/// server's items behind them, with `shown` mapping menu rows to entries.
+#[derive(Debug, Default)]
+pub(crate) struct LspCompletionState {
+	/// Number of the latest completion request.
+	generation: u64,
+	/// Buffer completion is open in. `None` while closed.
+	buffer: Option<BufferId>,
+	/// Start of the word being completed.
+	start: CharIdx,
+	/// Cursor position the request was made at.
+	requested_at: CharIdx,
+	/// Word the menu was last narrowed to.
+	word: String,
+	/// Completion request waiting for its response, with its generation.
+	request: Option<(u64, oneshot::Receiver<ListResponse>)>,
+	/// Offset encoding of the items' edits.
+	encoding: OffsetEncoding,
+	/// Every item the server listed, in the server's sort order.
+	entries: Vec<Entry>,
+	/// Entries matching the word with how they matched, one per menu row.
+	shown: Vec<(usize, FuzzyMatch)>,
+	/// Entry the menu selection is on and when it moved there.
+	selected: Option<(usize, Instant)>,
+	/// Resolve request waiting for its response, with its entry.
+	resolving: Option<(usize, oneshot::Receiver<ResolveResponse>)>,
+}
+
+impl LspCompletionState {
+	/// Returns whether completion is open, including while the list is
+	/// still on the way.
+	fn is_open(&self) -> bool {
+		self.buffer.is_some()
+	}
+
+	/// Closes completion. Responses still on the way become stale.
+	pub fn close(&mut self) {
+		*self = Self {
+			generation: self.generation + 1,
+			..Self::default()
+		};
+	}
+
+	/// Takes in the items of `response`, sorted the way the server asks.
+	fn set_items(&mut self, response: CompletionResponse, encoding: OffsetEncoding) {
+		let mut items = match response {
+			CompletionResponse::Array(items) => items,
+			CompletionResponse::List(list) => list.items,
+		};
+		items.sort_by(|a, b| sort_key(a).cmp(sort_key(b)));
+		self.encoding = encoding;
+		self.entries = items
+			.into_iter()
+			.map(|item| Entry {
+				item,
+				resolved: false,
+			})
+			.collect();
+		self.selected = None;
+		self.resolving = None;
+	}
+
+	/// Narrows the shown entries to those whose filter text fuzzy matches
+	/// `word`, best first and in the server's order among equals.
+	fn narrow(&mut self, word: &str) {
+		let texts = self.entries.iter().map(|entry| {
+			entry
+				.item
+				.filter_text
+				.as_deref()
+				.unwrap_or(&entry.item.label)
+		});
+		self.shown = rank(word, texts, MatchMode::Fuzzy);
+		self.word = word.to_string();
+	}
+
+	/// Returns the entry shown on menu row `row`.
+	fn entry_at(&self, row: Option<usize>) -> Option<usize> {
+		self.shown.get(row?).map(|&(i, _)| i)
+	}
+
+	/// Returns the selected item as it stands, resolved or not.
+	pub fn selected_item(&self) -> Option<&lsp_types::CompletionItem> {
+		let (entry, _) = self.selected?;
+		self.entries.get(entry).map(|e| &e.item)
+	}
+
+	/// Returns the selected entry if it is due to be resolved at `now`:
+	/// unresolved, not being resolved, and selected for [`RESOLVE_DELAY`].
+	fn due_for_resolve(&self, now: Instant) -> Option<usize> {
+		let (entry, since) = self.selected?;
+		let due = !self.entries[entry].resolved
+			&& self.resolving.as_ref().is_none_or(|(e, _)| *e != entry)
+			&& now.duration_since(since) >= RESOLVE_DELAY;
+		due.then_some(entry)
+	}
+
+	/// Takes in the outcome of resolving `entry`, keeping the item as it
+	/// was if resolving failed.
+	fn resolved(&mut self, entry: usize, response: ResolveResponse) {
+		let Some(slot) = self.entries.get_mut(entry) else {
+			return;
+		};
+		match response {
+			Ok(item) => slot.item = item,
+			Err(e) => debug!(error = %e, "completion resolve failed"),
+		}
+		slot.resolved = true;
+	}
+}
+
+impl Editor {
+	/// Returns whether the completion menu shows language server items.
+	pub(crate) fn lsp_completion_open(&self) -> bool {
+		self.overlays
+			.get::<LspCompletionState>()
+			.is_some_and(LspCompletionState::is_open)
+			&& self.completion_active()
+	}
+
+	/// Returns the language server item selected in the completion menu.
+	pub(crate) fn selected_lsp_completion(&self) -> Option<&lsp_types::CompletionItem> {
+		if !self.lsp_completion_open() {
+			return None;
+		}
+		self.overlays.get::<LspCompletionState>()?.selected_item()
+	}
+
+	/// Asks the buffer's server for completions at the cursor.
+	pub(crate) fn trigger_lsp_completion(&mut self) {
+		if self.mode() != Mode::Insert {
+			return;
+		}
+		let request = match self.lsp.completion(self.buffer()) {
+			Ok(Some(request)) => request,
+			Ok(None) => return,
+			Err(e) => {
+				debug!(error = %e, "completion request failed");
+				return;
+			}
+		};
+		let buffer_id = self.focused_view();
+		let cursor = self.buffer().cursor;
+		let start = word_start(self.buffer().doc().content.slice(..), cursor);
+
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		state.close();
+		state.buffer = Some(buffer_id);
+		state.start = start;
+		state.requested_at = cursor;
+		let (tx, rx) = oneshot::channel();
+		tokio::spawn(async move {
+			let _ = tx.send(request.await);
+		});
+		state.request = Some((state.generation, rx));
+	}
+
+	/// Handles `key` in insert mode: Ctrl-Space asks for completions and,
+	/// while the menu shows server items, the arrows and Ctrl-n/Ctrl-p move
+	/// through it, Enter and Tab accept and Escape closes it.
+	///
+	/// Returns whether the key was consumed.
+	pub(crate) fn handle_lsp_completion_key(&mut self, key: &KeyEvent) -> bool {
+		let ctrl = key.modifiers.contains(Modifiers::CONTROL);
+		if ctrl && key.code == KeyCode::Char(' ') {
+			self.trigger_lsp_completion();
+			return true;
+		}
+		if !self.lsp_completion_open() {
+			return false;
+		}
+		match key.code {
+			KeyCode::Down => self.move_completion_selection(SeqDirection::Next),
+			KeyCode::Char('n') if ctrl => self.move_completion_selection(SeqDirection::Next),
+			KeyCode::Up => self.move_completion_selection(SeqDirection::Prev),
+			KeyCode::Char('p') if ctrl => self.move_completion_selection(SeqDirection::Prev),
+			KeyCode::Enter | KeyCode::Tab => {
+				self.accept_completion();
+			}
+			KeyCode::Escape => self.close_completion(),
+			_ => return false,
+		}
+		true
+	}
+
+	/// Moves the menu selection to the next or previous item, wrapping
+	/// around.
+	pub(crate) fn move_completion_selection(&mut self, direction: SeqDirection) {
+		let state = self.overlays.get_or_default::<CompletionState>();
+		let len = state.items.len();
+		if len == 0 {
+			return;
+		}
+		let selected = state.selected_idx.unwrap_or(0);
+		state.selected_idx = Some(match direction {
+			SeqDirection::Next => (selected + 1) % len,
+			SeqDirection::Prev => (selected + len - 1) % len,
+		});
+		state.ensure_selected_visible();
+		self.frame.needs_redraw = true;
+	}
+
+	/// Follows typing with the menu, takes in the completion list and
+	/// resolves the selected item once it is due.
+	pub(crate) fn poll_lsp_completion(&mut self) {
+		let Some(state) = self.overlays.get::<LspCompletionState>() else {
+			return;
+		};
+		if !state.is_open() {
+			return;
+		}
+		let start = state.start;
+		let cursor = self.buffer().cursor;
+		let word = {
+			let doc = self.buffer().doc();
+			let text = doc.content.slice(..);
+			(cursor >= start && word_start(text, cursor) == start)
+				.then(|| text.slice(start..cursor.min(text.len_chars())).to_string())
+		};
+		let Some(word) = word
+			.filter(|_| self.mode() == Mode::Insert && state.buffer == Some(self.focused_view()))
+		else {
+			self.close_completion();
+			return;
+		};
+
+		self.receive_lsp_completion(&word);
+		if !self.lsp_completion_open() {
+			return;
+		}
+		if self.overlays.get_or_default::<LspCompletionState>().word != word {
+			self.narrow_lsp_completion(&word);
+		}
+
+		let row = self
+			.overlays
+			.get::<CompletionState>()
+			.and_then(|c| c.selected_idx);
+		let now = Instant::now();
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		let entry = state.entry_at(row);
+		if entry != state.selected.map(|(e, _)| e) {
+			state.selected = entry.map(|e| (e, now));
+			self.frame.needs_redraw = true;
+		}
+
+		self.receive_completion_resolve();
+		self.send_completion_resolve(now);
+	}
+
+	/// Takes in the completion list if it has arrived, opening the menu on
+	/// the items matching `word`.
+	fn receive_lsp_completion(&mut self, word: &str) {
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		let Some((generation, mut rx)) = state.request.take() else {
+			return;
+		};
+		let response = match rx.try_recv() {
+			Ok(response) => response,
+			Err(oneshot::error::TryRecvError::Empty) => {
+				state.request = Some((generation, rx));
+				return;
+			}
+			Err(oneshot::error::TryRecvError::Closed) => return,
+		};
+		if generation != state.generation {
+			return;
+		}
+		match response {
+			Ok(Some((response, encoding))) => {
+				state.set_items(response, encoding);
+				self.narrow_lsp_completion(word);
+			}
+			Ok(None) => self.close_completion(),
+			Err(e) => {
+				debug!(error = %e, "completion request failed");
+				self.close_completion();
+			}
+		}
+	}
+
+	/// Shows the items matching `word` in the menu, keeping the selection
+	/// on the same item if it still matches. Closes the menu if none do.
+	fn narrow_lsp_completion(&mut self, word: &str) {
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		let previous = state.selected.map(|(e, _)| e);
+		state.narrow(word);
+		if state.shown.is_empty() {
+			self.close_completion();
+			return;
+		}
+		let row = previous
+			.and_then(|e| state.shown.iter().position(|&(i, _)| i == e))
+			.unwrap_or(0);
+		let items = state
+			.shown
+			.iter()
+			.map(|(i, found)| {
+				let mut item = menu_item(&state.entries[*i].item);
+				item.set_match(word, found.clone(), MatchMode::Fuzzy);
+				item
+			})
+			.collect();
+		let replace_start = state.start;
+
+		let menu = self.overlays.get_or_default::<CompletionState>();
+		menu.items = items;
+		menu.selected_idx = Some(row);
+		menu.replace_start = replace_start;
+		menu.active = true;
+		menu.scroll_offset = 0;
+		menu.ensure_selected_visible();
+		self.frame.needs_redraw = true;
+	}
+
+	/// Starts resolving the selected item if it is due, dropping the
+	/// resolve of an item the selection has left.
+	fn send_completion_resolve(&mut self, now: Instant) {
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		let Some(entry) = state.due_for_resolve(now) else {
+			return;
+		};
+		let item = state.entries[entry].item.clone();
+		let request = match self.lsp.resolve_completion(self.buffer(), item) {
+			Ok(Some(request)) => request,
+			result => {
+				if let Err(e) = result {
+					debug!(error = %e, "completion resolve failed");
+				}
+				let state = self.overlays.get_or_default::<LspCompletionState>();
+				state.entries[entry].resolved = true;
+				return;
+			}
+		};
+		let (tx, rx) = oneshot::channel();
+		tokio::spawn(async move {
+			let _ = tx.send(request.await);
+		});
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		state.resolving = Some((entry, rx));
+	}
+
+	/// Takes in the resolved item if it has arrived.
+	fn receive_completion_resolve(&mut self) {
+		let state = self.overlays.get_or_default::<LspCompletionState>();
+		let Some((entry, mut rx)) = state.resolving.take() else {
+			return;
+		};
+		match rx.try_recv() {
+			Ok(response) => {
+				state.resolved(entry, response);
+				self.frame.needs_redraw = true;
+			}
+			Err(oneshot::error::TryRecvError::Empty) => state.resolving = Some((entry, rx)),
+			Err(oneshot::error::TryRecvError::Closed) => state.entries[entry].resolved = true,
+		}
+	}
+
+	/// Accepts the selected language server item: its edit, or its insert
+	/// text over the word, and its additional edits, as one transaction.
+	/// Snippet items start a snippet session.
+	///
+	/// Closes the menu and returns the accepted item as shown in it.
+	pub(crate) fn accept_lsp_completion(&mut self) -> Option<CompletionItem> {
+		let row = self.overlays.get::<CompletionState>()?.selected_idx;
+		let state = self.overlays.get::<LspCompletionState>()?;
+		let entry = state.entry_at(row)?;
+		let item = state.entries[entry].item.clone();
+		let (start, requested_at, encoding) = (state.start, state.requested_at, state.encoding);
+		self.close_completion();
+		if !self.guard_readonly() {
+			return None;
+		}
+
+		let buffer_id = self.focused_view();
+		let cursor = self.buffer().cursor;
+		let (range, new_text) = {
+			let doc = self.buffer().doc();
+			match &item.text_edit {
+				Some(edit) => {
+					let (range, new_text) = match edit {
+						CompletionTextEdit::Edit(edit) => (edit.range, &edit.new_text),
+						CompletionTextEdit::InsertAndReplace(edit) => (edit.insert, &edit.new_text),
+					};
+					let (from, to) = lsp_range_to_char_range(&doc.content, range, encoding);
+					// Text typed since the request extends the edit.
+					let to = if to >= requested_at {
+						(to + cursor).saturating_sub(requested_at)
+					} else {
+						to
+					};
+					(from.min(cursor)..to.max(cursor), new_text.clone())
+				}
+				None => (
+					start..cursor,
+					item.insert_text
+						.clone()
+						.unwrap_or_else(|| item.label.clone()),
+				),
+			}
+		};
+		let snippet = item.insert_text_format == Some(InsertTextFormat::SNIPPET);
+		let rendered = snippet.then(|| self.render_snippet(&new_text, range.start));
+		let text = rendered.as_ref().map_or(new_text, |r| r.text.clone());
+
+		let main = Change {
+			start: range.start,
+			end: range.end,
+			replacement: (!text.is_empty()).then(|| text.clone()),
+		};
+		let (tx, at) = {
+			let doc = self.buffer().doc();
+			completion_transaction(
+				doc.content.slice(..),
+				main,
+				additional_changes(&doc.content, &item, encoding),
+			)
+		};
+		self.save_edit_undo_state();
+		let end = at + text.chars().count();
+		self.apply_transaction_with_selection(buffer_id, &tx, Some(Selection::point(end)));
+		match &rendered {
+			Some(rendered) => self.start_snippet_session(rendered, at),
+			None => self.buffer_mut().sync_cursor_to_selection(),
+		}
+		self.frame.needs_redraw = true;
+		Some(menu_item(&item))
+	}
+}
+
+/// Returns the key the server sorts `item` by.
+fn sort_key(item: &lsp_types::CompletionItem) -> &str {
+	item.sort_text.as_deref().unwrap_or(&item.label)
+}
+
+/// Returns whether `c` can be part of a completed word.
+fn is_word_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the start of the word ending at `cursor`, which is `cursor`
+/// itself after a non-word char.
+fn word_start(text: RopeSlice, cursor: CharIdx) -> CharIdx {
+	let cursor = cursor.min(text.len_chars());
+	let mut start = cursor;
+	while start > 0 && is_word_char(text.char(start - 1)) {
+		start -= 1;
+	}
+	start
+}
+
+/// Converts a server's item to a completion menu item.
+fn menu_item(item: &lsp_types::CompletionItem) -> CompletionItem {
+	let kind = match item.kind {
+		Some(CompletionItemKind::SNIPPET) => CompletionKind::Snippet,
+		Some(CompletionItemKind::FILE | CompletionItemKind::FOLDER) => CompletionKind::File,
+		_ => CompletionKind::Symbol,
+	};
+	CompletionItem {
+		label: item.label.clone(),
+		insert_text: item
+			.insert_text
+			.clone()
+			.unwrap_or_else(|| item.label.clone()),
+		detail: item.detail.clone(),
+		filter_text: item.filter_text.clone(),
+		kind,
+		snippet: item.insert_text_format == Some(InsertTextFormat::SNIPPET),
+		score: 0,
+		match_indices: Vec::new(),
+	}
+}
+
+/// Returns the Markdown shown beside the menu for `item`: its detail as a
+/// code block, then its documentation. `None` if it has neither.
+pub(crate) fn completion_docs(item: &lsp_types::CompletionItem) -> Option<String> {
+	let detail = item
+		.detail
+		.as_deref()
+		.filter(|d| !d.trim().is_empty())
+		.map(|d| format!("```\n{}\n```", d.trim()));
+	let documentation = item
+		.documentation
+		.as_ref()
+		.map(|docs| match docs {
+			Documentation::String(text) => text.as_str(),
+			Documentation::MarkupContent(content) => content.value.as_str(),
+		})
+		.filter(|d| !d.trim().is_empty())
+		.map(|d| d.trim().to_string());
+	match (detail, documentation) {
+		(Some(detail), Some(docs)) => Some(format!("{detail}\n\n{docs}")),
+		(detail, docs) => detail.or(docs),
+	}
+}
+
+/// Converts the additional edits of `item` to changes to `text`.
+fn additional_changes(
+	text: &xeno_base::Rope,
+	item: &lsp_types::CompletionItem,
+	encoding: OffsetEncoding,
+) -> Vec<Change> {
+	item.additional_text_edits
+		.iter()
+		.flatten()
+		.map(|edit| {
+			let (start, end) = lsp_range_to_char_range(text, edit.range, encoding);
+			Change {
+				start,
+				end,
+				replacement: (!edit.new_text.is_empty()).then(|| edit.new_text.clone()),
+			}
+		})
+		.collect()
+}
+
+/// Builds the transaction making the `main` change of a completion along
+/// with its `additional` changes, and returns where the main change's text
+/// starts once it is applied.
+///
+/// Additional changes overlapping the main change or each other are
+/// dropped, keeping the main change alone.
+fn completion_transaction(
+	text: RopeSlice,
+	main: Change,
+	mut additional: Vec<Change>,
+) -> (Transaction, CharIdx) {
+	additional.sort_by_key(|change| change.start);
+	let overlaps = |a: &Change, b: &Change| a.start < b.end && b.start < a.end;
+	let clash = additional
+		.windows(2)
+		.any(|pair| pair[0].end > pair[1].start)
+		|| additional.iter().any(|change| {
+			overlaps(change, &main)
+				|| (change.start == change.end
+					&& change.start > main.start
+					&& change.start < main.end)
+		});
+	if clash {
+		debug!("completion's additional edits overlap its edit, dropping them");
+		additional.clear();
+	}
+
+	let shift: isize = additional
+		.iter()
+		.filter(|change| change.end <= main.start)
+		.map(|change| {
+			let inserted = change.replacement.as_ref().map_or(0, |r| r.chars().count());
+			inserted as isize - (change.end - change.start) as isize
+		})
+		.sum();
+	let at = main.start.saturating_add_signed(shift);
+
+	let mut changes = additional;
+	let index = changes.partition_point(|change| change.end <= main.start);
+	changes.insert(index, main);
+	(Transaction::change(text, changes), at)
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Rope;
+	use xeno_lsp::lsp_types::{MarkupContent, MarkupKind, Position, Range as LspRange, TextEdit};
+
+	use super::*;
+
+	fn item(label: &str, sort: &str) -> lsp_types::CompletionItem {
+		lsp_types::CompletionItem {
+			label: label.to_string(),
+			sort_text: Some(sort.to_string()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn narrows_to_the_word_best_first() {
+		let mut state = LspCompletionState::default();
+		let response = CompletionResponse::Array(vec![
+			item("push_str", "2"),
+			item("Push", "1"),
+			item("pop", "0"),
+		]);
+		state.set_items(response, OffsetEncoding::Utf16);
+		state.narrow("PU");
+		let labels: Vec<_> = state
+			.shown
+			.iter()
+			.map(|&(i, _)| state.entries[i].item.label.as_str())
+			.collect();
+		assert_eq!(labels, ["Push", "push_str"]);
+		assert_eq!(state.entry_at(Some(1)), Some(2));
+		assert_eq!(state.entry_at(Some(2)), None);
+
+		// A match at a word start ranks above the server's order.
+		state.narrow("ps");
+		assert_eq!(state.entry_at(Some(0)), Some(2));
+		assert_eq!(state.shown[0].1.positions, [0, 5]);
+		assert_eq!(state.entry_at(Some(1)), Some(1));
+	}
+
+	#[test]
+	fn resolves_only_after_the_selection_rests() {
+		let mut state = LspCompletionState::default();
+		state.set_items(
+			CompletionResponse::Array(vec![item("a", "0")]),
+			OffsetEncoding::Utf16,
+		);
+		let now = Instant::now();
+		state.selected = Some((0, now));
+		assert_eq!(state.due_for_resolve(now), None);
+		assert_eq!(state.due_for_resolve(now + RESOLVE_DELAY), Some(0));
+
+		state.resolved(0, Err(xeno_lsp::Error::Protocol("gone".into())));
+		assert_eq!(state.due_for_resolve(now + RESOLVE_DELAY), None);
+		assert_eq!(state.selected_item().unwrap().label, "a");
+	}
+
+	#[test]
+	fn docs_join_detail_and_documentation() {
+		let mut item = item("len", "0");
+		assert_eq!(completion_docs(&item), None);
+		item.detail = Some("fn len(&self) -> usize".into());
+		item.documentation = Some(Documentation::MarkupContent(MarkupContent {
+			kind: MarkupKind::Markdown,
+			value: "Returns the **length**.".into(),
+		}));
+		assert_eq!(
+			completion_docs(&item).as_deref(),
+			Some("```\nfn len(&self) -> usize\n```\n\nReturns the **length**.")
+		);
+	}
+
+	#[test]
+	fn finds_the_word_before_the_cursor() {
+		let text = Rope::from("let x = foo_ba");
+		assert_eq!(word_start(text.slice(..), 14), 8);
+		assert_eq!(word_start(text.slice(..), 8), 8);
+	}
+
+	#[test]
+	fn additional_edits_apply_with_the_completion_as_one_step() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("fn main() {\n\tHashM\n}");
+		let cursor = editor
+			.buffer()
+			.doc()
+			.content
+			.to_string()
+			.find("M\n")
+			.unwrap() + 1;
+		editor.buffer_mut().set_selection(Selection::point(cursor));
+		editor.buffer_mut().sync_cursor_to_selection();
+
+		let mut hash_map = item("HashMap", "0");
+		hash_map.additional_text_edits = Some(vec![TextEdit {
+			range: LspRange::new(Position::new(0, 0), Position::new(0, 0)),
+			new_text: "use std::collections::HashMap;\n".into(),
+		}]);
+		let buffer_id = editor.focused_view();
+		let state = editor.overlays.get_or_default::<LspCompletionState>();
+		state.buffer = Some(buffer_id);
+		state.start = cursor - 5;
+		state.requested_at = cursor;
+		state.set_items(
+			CompletionResponse::Array(vec![hash_map]),
+			OffsetEncoding::Utf16,
+		);
+		editor.narrow_lsp_completion("HashM");
+		assert!(editor.lsp_completion_open());
+
+		assert_eq!(editor.accept_lsp_completion().unwrap().label, "HashMap");
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"use std::collections::HashMap;\nfn main() {\n\tHashMap\n}"
+		);
+		assert_eq!(editor.buffer().cursor, 51);
+		assert!(!editor.completion_active());
+
+		editor.undo();
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"fn main() {\n\tHashM\n}"
+		);
+	}
+
+	#[test]
+	fn overlapping_additional_edits_are_dropped() {
+		let text = Rope::from("abc");
+		let main = Change {
+			start: 1,
+			end: 3,
+			replacement: Some("xy".into()),
+		};
+		let clashing = Change {
+			start: 2,
+			end: 3,
+			replacement: None,
+		};
+		let (tx, at) = completion_transaction(text.slice(..), main, vec![clashing]);
+		let mut doc = text.clone();
+		tx.apply(&mut doc);
+		assert_eq!(doc.to_string(), "axy");
+		assert_eq!(at, 1);
+	}
+}
@@ -0,0 +1,239 @@
+//! Language server requests made per document in the background.
+//!
+//! Features that ask a language server about a whole document, such as the
+//! outline or inlay hints, keep a [`DocumentRequests`] of their own. A document's request
+//! is due when none was made yet, after edits once the `buffer:change` hook
+//! has been quiet for the feature's debounce, and every [`RETRY_INTERVAL`]
+//! while no server takes the request, as one may still be starting. At most
+//! one request per document is in flight, and its response is taken in on
+//! a later tick.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+use xeno_registry::hook;
+
+use super::extensions::ExtensionMap;
+use crate::buffer::{BufferId, DocumentId};
+
+/// How often a request is made again while the document's language server
+/// is missing or has not said it takes it.
+const RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Response to a request, `None` when no server took it.
+type Response<T> = xeno_lsp::Result<Option<T>>;
+
+/// Editor extension recording when the `buffer:change` hook last fired,
+/// for debouncing language server requests.
+#[derive(Default)]
+pub struct LspRequestChanges(Mutex<Option<Instant>>);
+
+impl LspRequestChanges {
+	/// Returns when a buffer last changed.
+	pub(crate) fn last(&self) -> Option<Instant> {
+		*self.0.lock()
+	}
+}
+
+hook!(
+	lsp_requests_buffer_change,
+	BufferChange,
+	100,
+	"Schedule language server requests after edits",
+	|ctx| {
+		if let Some(changes) = ctx
+			.extensions::<ExtensionMap>()
+			.and_then(|ext| ext.get::<LspRequestChanges>())
+		{
+			*changes.0.lock() = Some(Instant::now());
+		}
+	}
+);
+
+/// A request in flight for one document.
+struct InFlight<T> {
+	/// Buffer the request was made through.
+	buffer: BufferId,
+	/// Document version the request was made at.
+	version: u64,
+	/// Receives the response.
+	rx: oneshot::Receiver<Response<T>>,
+}
+
+/// The latest request for one document.
+struct Requested<C> {
+	/// When the request was made.
+	at: Instant,
+	/// Document version it was made at.
+	version: u64,
+	/// Whether a server takes the request for the document, as far as is
+	/// known.
+	supported: bool,
+	/// What the caller made the request with.
+	context: C,
+}
+
+/// A response taken in by [`DocumentRequests::receive`].
+pub(crate) struct Arrived<T, C> {
+	/// Document the request was for.
+	pub document: DocumentId,
+	/// Buffer the request was made through.
+	pub buffer: BufferId,
+	/// Document version the request was made at.
+	pub version: u64,
+	/// What the caller made the request with.
+	pub context: C,
+	/// The response.
+	pub response: Response<T>,
+}
+
+/// Requests of one kind, answered with a `T`, for each document that wants
+/// them, with the context `C` each was made with.
+pub(crate) struct DocumentRequests<T, C = ()> {
+	/// Requests in flight, one per document at most.
+	in_flight: HashMap<DocumentId, InFlight<T>>,
+	/// Latest request of each document.
+	requested: HashMap<DocumentId, Requested<C>>,
+}
+
+impl<T, C> Default for DocumentRequests<T, C> {
+	fn default() -> Self {
+		Self {
+			in_flight: HashMap::new(),
+			requested: HashMap::new(),
+		}
+	}
+}
+
+impl<T: Send + 'static, C: Clone> DocumentRequests<T, C> {
+	/// Forgets the requests of `document`, dropping the one in flight.
+	pub(crate) fn forget(&mut self, document: DocumentId) {
+		self.in_flight.remove(&document);
+		self.requested.remove(&document);
+	}
+
+	/// Forgets the requests of every document.
+	pub(crate) fn clear(&mut self) {
+		self.in_flight.clear();
+		self.requested.clear();
+	}
+
+	/// Returns whether a request for `document` is in flight.
+	pub(crate) fn is_pending(&self, document: DocumentId) -> bool {
+		self.in_flight.contains_key(&document)
+	}
+
+	/// Returns the context the latest request for `document` was made
+	/// with, if any was.
+	pub(crate) fn context(&self, document: DocumentId) -> Option<&C> {
+		self.requested
+			.get(&document)
+			.map(|requested| &requested.context)
+	}
+
+	/// Returns whether `document`, now at `version`, is due a request:
+	/// none was made yet, a buffer changed after the last one and has been
+	/// quiet for `debounce` since, or no server took the last one a while
+	/// ago.
+	pub(crate) fn is_due(
+		&self,
+		document: DocumentId,
+		version: u64,
+		last_change: Option<Instant>,
+		debounce: Duration,
+	) -> bool {
+		self.requested.get(&document).is_none_or(|requested| {
+			let edited = requested.version != version
+				&& last_change.is_some_and(|at| at > requested.at && at.elapsed() >= debounce);
+			let retry = !requested.supported && requested.at.elapsed() >= RETRY_INTERVAL;
+			edited || retry
+		})
+	}
+
+	/// Records a request for `document` at `version`, made through `buffer`
+	/// with `context`, and awaits it in the background if a server took it.
+	///
+	/// Returns whether one did; until it does the request is made again
+	/// every [`RETRY_INTERVAL`].
+	pub(crate) fn send<F>(
+		&mut self,
+		document: DocumentId,
+		buffer: BufferId,
+		version: u64,
+		context: C,
+		request: xeno_lsp::Result<Option<F>>,
+	) -> xeno_lsp::Result<bool>
+	where
+		F: Future<Output = Response<T>> + Send + 'static,
+	{
+		self.in_flight.remove(&document);
+		self.requested.insert(
+			document,
+			Requested {
+				at: Instant::now(),
+				version,
+				supported: matches!(request, Ok(Some(_))),
+				context,
+			},
+		);
+		let Some(request) = request? else {
+			return Ok(false);
+		};
+		let (tx, rx) = oneshot::channel();
+		tokio::spawn(async move {
+			let _ = tx.send(request.await);
+		});
+		self.in_flight.insert(
+			document,
+			InFlight {
+				buffer,
+				version,
+				rx,
+			},
+		);
+		Ok(true)
+	}
+
+	/// Marks the latest request for `document` as one no server takes, so
+	/// it is made again after a while.
+	pub(crate) fn set_unsupported(&mut self, document: DocumentId) {
+		if let Some(requested) = self.requested.get_mut(&document) {
+			requested.supported = false;
+		}
+	}
+
+	/// Takes the responses that have arrived.
+	///
+	/// A response no server answered marks its request unsupported.
+	pub(crate) fn receive(&mut self) -> Vec<Arrived<T, C>> {
+		let mut arrived = Vec::new();
+		self.in_flight.retain(|&document, in_flight| {
+			match in_flight.rx.try_recv() {
+				Ok(response) => {
+					arrived.push((document, in_flight.buffer, in_flight.version, response))
+				}
+				Err(oneshot::error::TryRecvError::Empty) => return true,
+				Err(oneshot::error::TryRecvError::Closed) => {}
+			}
+			false
+		});
+		arrived
+			.into_iter()
+			.filter_map(|(document, buffer, version, response)| {
+				let requested = self.requested.get_mut(&document)?;
+				if matches!(response, Ok(None)) {
+					requested.supported = false;
+				}
+				Some(Arrived {
+					document,
+					buffer,
+					version,
+					context: requested.context.clone(),
+					response,
+				})
+			})
+			.collect()
+	}
+}
@@ -0,0 +1,93 @@
+//! Language server lifecycle.
+//!
+//! Crashed servers are reported here and, once their restart delay has
+//! passed, started again by opening their documents anew. `:lsp-restart`
+//! goes through the same path after stopping the servers by hand.
+//!
+//! The `lsp-trace` option is applied to the server log here too, so a
+//! change through `:set` or the LSP log panel takes effect on the next
+//! tick.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use tracing::warn;
+use xeno_lsp::TraceLevel;
+use xeno_registry::options::keys as opt_keys;
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+
+impl Editor {
+	/// Applies the `lsp-trace` option, reports language servers that
+	/// crashed and restarts those whose restart delay has passed.
+	pub(super) fn poll_lsp_servers(&mut self) {
+		let trace: String = self.option(opt_keys::LSP_TRACE);
+		let log = self.lsp.server_log();
+		let level = TraceLevel::parse(&trace).unwrap_or_default();
+		if log.trace_level() != level {
+			log.set_trace_level(level);
+		}
+
+		for crash in self.lsp.poll_crashes() {
+			match crash.restart_in {
+				Some(delay) => {
+					self.notify(keys::lsp_server_crashed::call(
+						&crash.name,
+						crash.crashes,
+						delay,
+					));
+				}
+				None => {
+					self.notify(keys::lsp_server_failed::call(
+						&crash.name,
+						crash.crashes,
+						crash.error.as_deref(),
+					));
+				}
+			}
+			self.frame.needs_redraw = true;
+		}
+
+		for (language, root) in self.lsp.due_restarts() {
+			self.reopen_lsp_documents(&language, Some(&root));
+		}
+	}
+
+	/// Opens the buffers of `language`, under `root` if given, with their
+	/// language servers again, starting servers that are not running.
+	///
+	/// Documents are opened one after another in the background, so a
+	/// server is started once rather than for each of them.
+	pub(crate) fn reopen_lsp_documents(&self, language: &str, root: Option<&Path>) {
+		let mut seen = HashSet::new();
+		let documents: Vec<_> = self
+			.buffers
+			.buffers()
+			.filter(|buffer| buffer.file_type().as_deref() == Some(language))
+			.filter(|buffer| !self.large_file_mode(buffer.id))
+			.filter(|buffer| seen.insert(buffer.document_id()))
+			.filter_map(|buffer| {
+				let path = buffer.path()?;
+				let path = path
+					.canonicalize()
+					.unwrap_or_else(|_| std::env::current_dir().unwrap_or_default().join(&path));
+				root.is_none_or(|root| path.starts_with(root))
+					.then(|| (path, buffer.doc().content.clone()))
+			})
+			.collect();
+		if documents.is_empty() {
+			return;
+		}
+
+		let sync = self.lsp.sync().clone();
+		let language = language.to_string();
+		tokio::spawn(async move {
+			for (path, content) in documents {
+				if let Err(e) = sync.open_document(&path, &language, &content).await {
+					warn!(error = %e, path = ?path, "LSP document reopen failed");
+				}
+			}
+		});
+	}
+}
@@ -0,0 +1,151 @@
+//! Saved selection marks and reselect-last.
+
+use xeno_base::Selection;
+use xeno_registry_notifications::keys;
+
+use crate::buffer::DEFAULT_MARK;
+use crate::editor::Editor;
+
+impl Editor {
+	/// Saves the current selection to `slot`, or to the default `^` slot.
+	pub fn save_selection(&mut self, slot: Option<char>) {
+		let slot = slot.unwrap_or(DEFAULT_MARK);
+		let selection = self.buffer().selection.clone();
+		self.buffer().doc_mut().marks.set(slot, selection);
+		self.notify(keys::selection_saved::call(slot));
+	}
+
+	/// Restores the selection saved in `slot`, or in the default `^` slot.
+	///
+	/// Returns false and notifies if the slot is empty.
+	pub fn restore_selection(&mut self, slot: Option<char>) -> bool {
+		let slot = slot.unwrap_or(DEFAULT_MARK);
+		let saved = self.buffer().doc().marks.get(slot).cloned();
+		match saved {
+			Some(selection) => {
+				self.apply_saved_selection(selection);
+				true
+			}
+			None => {
+				self.notify(keys::mark_empty::call(slot));
+				false
+			}
+		}
+	}
+
+	/// Restores the selection replaced by the last search or regex selection,
+	/// remembering the current one in its place.
+	pub fn reselect_last(&mut self) -> bool {
+		let current = self.buffer().selection.clone();
+		let previous = self.buffer().doc().marks.previous().cloned();
+		let Some(previous) = previous else {
+			self.notify(keys::no_previous_selection);
+			return false;
+		};
+		self.buffer().doc_mut().marks.set_previous(current);
+		self.apply_saved_selection(previous);
+		true
+	}
+
+	/// Remembers the current selection before replacing it, for
+	/// [`reselect_last`](Self::reselect_last).
+	pub(crate) fn remember_selection(&mut self) {
+		let selection = self.buffer().selection.clone();
+		self.buffer().doc_mut().marks.set_previous(selection);
+	}
+
+	/// Makes a saved selection current, snapped to grapheme boundaries.
+	fn apply_saved_selection(&mut self, selection: Selection) {
+		let aligned = selection.grapheme_aligned(self.buffer().doc().content.slice(..));
+		let buffer = self.buffer_mut();
+		buffer.set_selection(aligned);
+		buffer.sync_cursor_to_selection();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::range::Range;
+
+	use super::*;
+
+	fn editor_with(text: &str, selection: Selection) -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text(text);
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(selection);
+		buffer.sync_cursor_to_selection();
+		editor
+	}
+
+	fn spans(editor: &Editor) -> Vec<(usize, usize)> {
+		editor
+			.buffer()
+			.selection
+			.ranges()
+			.iter()
+			.map(|r| (r.anchor, r.head))
+			.collect()
+	}
+
+	#[test]
+	fn restored_selection_follows_edits() {
+		let saved = Selection::from_vec(vec![Range::new(4, 6), Range::new(8, 10)], 1);
+		let mut editor = editor_with("one two three", saved);
+		editor.save_selection(None);
+
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(0));
+		buffer.sync_cursor_to_selection();
+		editor.insert_text(">> ");
+
+		assert!(editor.restore_selection(None));
+		assert_eq!(spans(&editor), vec![(7, 9), (11, 13)]);
+		assert_eq!(editor.buffer().selection.primary_index(), 1);
+	}
+
+	#[test]
+	fn named_slots_are_independent() {
+		let mut editor = editor_with("abcdef", Selection::single(0, 2));
+		editor.save_selection(Some('a'));
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::single(3, 5));
+		buffer.sync_cursor_to_selection();
+		editor.save_selection(Some('b'));
+
+		assert!(editor.restore_selection(Some('a')));
+		assert_eq!(spans(&editor), vec![(0, 2)]);
+		assert!(!editor.restore_selection(Some('c')));
+	}
+
+	#[test]
+	fn reselect_last_undoes_search_replacement() {
+		let mut editor = editor_with("foo bar foo", Selection::single(4, 6));
+		editor
+			.buffer_mut()
+			.input
+			.set_last_search("foo".to_string(), false);
+		editor.do_search_next(false, false);
+		assert_eq!(spans(&editor), vec![(8, 11)]);
+
+		assert!(editor.reselect_last());
+		assert_eq!(spans(&editor), vec![(4, 6)]);
+		assert!(editor.reselect_last());
+		assert_eq!(spans(&editor), vec![(8, 11)]);
+	}
+
+	#[test]
+	fn undo_maps_marks_back() {
+		let mut editor = editor_with("hello world", Selection::single(6, 10));
+		editor.save_selection(None);
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(0));
+		buffer.sync_cursor_to_selection();
+		editor.insert_text("say ");
+		assert_eq!(editor.buffer().doc().content.to_string(), "say hello world");
+		editor.undo();
+
+		assert!(editor.restore_selection(None));
+		assert_eq!(spans(&editor), vec![(6, 10)]);
+	}
+}
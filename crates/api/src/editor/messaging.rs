@@ -1,11 +1,207 @@
 //! Notification display for the editor.
+//!
+//! Every notification is also kept in a [`NotificationHistory`]. A
+//! notification repeated while its toast shows is counted on that toast, and
+//! its history record gets the final count when the toast goes.
+//!
+//! Notifications below the `notification-level` option, or past the
+//! `notification-rate-limit` of their origin, are kept in the history without
+//! a toast. Those dropped by the rate limit are summed up in one toast per
+//! origin once its window ends.
+//!
+//! While the terminal is unfocused, error notifications are also queued as
+//! [`DesktopAlert`]s for the frontend to forward, per the `notify-desktop`
+//! option.
 
-use xeno_registry_notifications::{AutoDismiss, Level, Notification};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use xeno_registry::options::keys as opt_keys;
+use xeno_registry_notifications::{AutoDismiss, Level, Notification, keys};
 use xeno_tui::style::Style;
 use xeno_tui::widgets::icon::presets as icon_presets;
-use xeno_tui::widgets::notifications::{self as notif, Anchor, Toast, ToastIcon};
+use xeno_tui::widgets::notifications::{
+	self as notif, Anchor, DismissedToast, SizeConstraint, Toast, ToastIcon,
+};
 
 use crate::editor::Editor;
+use crate::render::wrap_line;
+
+/// Tab width toast content is wrapped with.
+const TOAST_TAB_WIDTH: usize = 4;
+
+/// Most notifications the history keeps.
+const HISTORY_LIMIT: usize = 500;
+
+/// Title of forwarded desktop notifications.
+const DESKTOP_TITLE: &str = "Xeno";
+
+/// Window the `notification-rate-limit` option counts toasts over.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// A notification the editor emitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationRecord {
+	/// ID of the notification's definition.
+	pub id: &'static str,
+	/// Severity level.
+	pub level: Level,
+	/// The message shown.
+	pub message: String,
+	/// Times the notification was emitted while its toast showed.
+	pub count: u32,
+	/// Toast still showing the notification.
+	toast: Option<u64>,
+}
+
+/// Notifications the editor emitted, oldest first.
+#[derive(Debug, Default)]
+pub struct NotificationHistory {
+	/// Records, capped at [`HISTORY_LIMIT`].
+	records: VecDeque<NotificationRecord>,
+}
+
+impl NotificationHistory {
+	/// Returns the records, oldest first.
+	pub fn records(&self) -> impl DoubleEndedIterator<Item = &NotificationRecord> {
+		self.records.iter()
+	}
+
+	/// Records `notification`, shown on `toast` if any.
+	///
+	/// Nothing is added when `toast` already shows an earlier record, as the
+	/// notification was deduplicated onto it.
+	fn record(&mut self, notification: &Notification, toast: Option<u64>) {
+		if toast.is_some() && self.records.iter().any(|r| r.toast == toast) {
+			return;
+		}
+		if self.records.len() == HISTORY_LIMIT {
+			self.records.pop_front();
+		}
+		self.records.push_back(NotificationRecord {
+			id: notification.def.id,
+			level: notification.level(),
+			message: notification.message.clone(),
+			count: 1,
+			toast,
+		});
+	}
+
+	/// Stores the final count of the record shown on a dismissed toast.
+	fn dismissed(&mut self, toast: DismissedToast) {
+		if let Some(record) = self
+			.records
+			.iter_mut()
+			.rev()
+			.find(|r| r.toast == Some(toast.id))
+		{
+			record.count = toast.count;
+			record.toast = None;
+		}
+	}
+}
+
+/// Toasts an origin showed in its current rate window.
+#[derive(Debug)]
+struct RateWindow {
+	/// When the window began.
+	started: Instant,
+	/// Toasts shown in the window.
+	shown: usize,
+	/// Notifications dropped in the window.
+	suppressed: usize,
+}
+
+/// Per-origin rate limit on toasts.
+#[derive(Debug, Default)]
+pub(crate) struct NotificationLimiter {
+	/// Open windows, keyed by origin.
+	windows: HashMap<String, RateWindow>,
+}
+
+impl NotificationLimiter {
+	/// Counts a toast from `origin`, returning whether it may show.
+	///
+	/// A `limit` of 0 admits everything.
+	fn admit(&mut self, origin: &str, limit: usize, now: Instant) -> bool {
+		if limit == 0 {
+			return true;
+		}
+		let window = self
+			.windows
+			.entry(origin.to_string())
+			.or_insert(RateWindow {
+				started: now,
+				shown: 0,
+				suppressed: 0,
+			});
+		if window.shown < limit {
+			window.shown += 1;
+			true
+		} else {
+			window.suppressed += 1;
+			false
+		}
+	}
+
+	/// Closes windows older than [`RATE_WINDOW`], returning each origin that
+	/// dropped notifications with how many.
+	fn expire(&mut self, now: Instant) -> Vec<(String, usize)> {
+		let mut summaries = Vec::new();
+		self.windows.retain(|origin, window| {
+			if now.saturating_duration_since(window.started) < RATE_WINDOW {
+				return true;
+			}
+			if window.suppressed > 0 {
+				summaries.push((origin.clone(), window.suppressed));
+			}
+			false
+		});
+		summaries.sort();
+		summaries
+	}
+}
+
+/// A notification forwarded outside the editor while the terminal is
+/// unfocused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DesktopAlert {
+	/// Raise a desktop notification.
+	Notify {
+		/// Notification title.
+		title: String,
+		/// Notification text.
+		body: String,
+	},
+	/// Ring the terminal bell.
+	Bell,
+}
+
+/// Terminal focus and the alerts waiting for the frontend.
+#[derive(Debug)]
+pub(crate) struct DesktopForwarding {
+	/// Whether the terminal has focus.
+	focused: bool,
+	/// Alerts not yet taken by the frontend.
+	pending: Vec<DesktopAlert>,
+}
+
+impl Default for DesktopForwarding {
+	fn default() -> Self {
+		Self {
+			focused: true,
+			pending: Vec::new(),
+		}
+	}
+}
+
+/// Wraps a line of toast content the way buffer text is soft wrapped.
+pub(crate) fn wrap_toast_line(line: &str, width: usize) -> Vec<String> {
+	wrap_line(line, width, TOAST_TAB_WIDTH)
+		.into_iter()
+		.map(|segment| segment.text)
+		.collect()
+}
 
 impl Editor {
 	/// Emits a typed notification.
@@ -27,10 +223,88 @@ impl Editor {
 	}
 
 	/// Shows a typed notification (internal).
+	///
+	/// The notification goes to the history without a toast when it is below
+	/// the `notification-level` option or its origin is over the
+	/// `notification-rate-limit`.
 	pub fn show_notification(&mut self, notification: Notification) {
-		let level = notification.level();
-		let auto_dismiss = notification.auto_dismiss();
+		let now = Instant::now();
+		self.flush_notification_summaries_at(now);
 
+		let min_level: String = self.option(opt_keys::NOTIFICATION_LEVEL);
+		let min_level = Level::from_name(&min_level).unwrap_or_default();
+		let limit = self.option(opt_keys::NOTIFICATION_RATE_LIMIT).max(0) as usize;
+		if notification.level() < min_level
+			|| !self
+				.notification_limiter
+				.admit(notification.origin(), limit, now)
+		{
+			self.notification_history.record(&notification, None);
+			return;
+		}
+		self.forward_to_desktop(&notification);
+		self.push_notification(notification);
+	}
+
+	/// Queues a [`DesktopAlert`] for an error notification emitted while the
+	/// terminal is unfocused, as the `notify-desktop` option asks.
+	fn forward_to_desktop(&mut self, notification: &Notification) {
+		if self.desktop.focused || notification.level() != Level::Error {
+			return;
+		}
+		let mode: String = self.option(opt_keys::NOTIFY_DESKTOP);
+		let alert = match mode.as_str() {
+			"on" => DesktopAlert::Notify {
+				title: DESKTOP_TITLE.to_string(),
+				body: notification.message.clone(),
+			},
+			"bell" => DesktopAlert::Bell,
+			_ => return,
+		};
+		self.desktop.pending.push(alert);
+	}
+
+	/// Records whether the terminal has focus, for desktop forwarding.
+	pub(crate) fn set_terminal_focused(&mut self, focused: bool) {
+		self.desktop.focused = focused;
+	}
+
+	/// Takes the alerts the frontend should forward to the desktop.
+	pub fn take_desktop_alerts(&mut self) -> Vec<DesktopAlert> {
+		std::mem::take(&mut self.desktop.pending)
+	}
+
+	/// Shows `notification` as a toast and records it, skipping the filters.
+	fn push_notification(&mut self, notification: Notification) {
+		let toast = self
+			.level_toast(notification.level(), notification.message.clone())
+			.kind(notification.def.id)
+			.dedupe(notification.dedupe())
+			.auto_dismiss(match notification.auto_dismiss() {
+				AutoDismiss::Never => notif::AutoDismiss::Never,
+				AutoDismiss::After(d) => notif::AutoDismiss::After(d),
+			});
+
+		let id = self.notifications.push(toast);
+		self.notification_history.record(&notification, Some(id));
+	}
+
+	/// Shows a summary toast for each origin whose rate window ended with
+	/// notifications dropped.
+	pub(crate) fn flush_notification_summaries(&mut self) {
+		self.flush_notification_summaries_at(Instant::now());
+	}
+
+	/// Does [`flush_notification_summaries`](Self::flush_notification_summaries)
+	/// as of `now`.
+	fn flush_notification_summaries_at(&mut self, now: Instant) {
+		for (origin, count) in self.notification_limiter.expire(now) {
+			self.push_notification(keys::notifications_suppressed::call(count, &origin));
+		}
+	}
+
+	/// Builds a toast showing `content`, styled for `level`.
+	pub(crate) fn level_toast(&self, level: Level, content: String) -> Toast {
 		// Get style based on level
 		let (semantic, icon_glyph) = match level {
 			Level::Info => ("info", icon_presets::INFO),
@@ -43,22 +317,129 @@ impl Editor {
 		let notif_style: Style = self.config.theme.colors.notification_style(semantic);
 		let accent = notif_style.fg.unwrap_or_default();
 
-		let toast = Toast::new(notification.message)
-			.anchor(Anchor::TopRight)
+		Toast::new(content)
+			.anchor(self.notification_anchor())
+			.max_width(Some(SizeConstraint::Percent(0.4)))
+			.max_height(Some(SizeConstraint::Cells(10)))
 			.style(notif_style)
 			.border_style(Style::default().fg(accent))
 			.icon(ToastIcon::new(icon_glyph).style(Style::default().fg(accent)))
 			.animation(notif::Animation::Fade)
-			.auto_dismiss(match auto_dismiss {
-				AutoDismiss::Never => notif::AutoDismiss::Never,
-				AutoDismiss::After(d) => notif::AutoDismiss::After(d),
-			});
+	}
 
-		self.notifications.push(toast);
+	/// Returns the corner the `notification-anchor` option stacks toasts from.
+	fn notification_anchor(&self) -> Anchor {
+		let anchor: String = self.option(opt_keys::NOTIFICATION_ANCHOR);
+		match anchor.as_str() {
+			"top-left" => Anchor::TopLeft,
+			"bottom-left" => Anchor::BottomLeft,
+			"bottom-right" => Anchor::BottomRight,
+			_ => Anchor::TopRight,
+		}
+	}
+
+	/// Applies the `notification-max-visible` and `ui-animations` options to
+	/// the toast stack.
+	pub(crate) fn configure_notifications(&mut self) {
+		let max = self.option(opt_keys::NOTIFICATION_MAX_VISIBLE);
+		let slide = self.option(opt_keys::UI_ANIMATIONS);
+		self.notifications
+			.set_max_visible((max > 0).then_some(max as usize));
+		self.notifications.set_slide_stacking(slide);
 	}
 
 	/// Clears all visible notifications.
 	pub fn clear_all_notifications(&mut self) {
 		self.notifications.clear();
+		self.record_dismissed_notifications();
+	}
+
+	/// Stores the final counts of toasts that left the screen in the
+	/// history.
+	pub(crate) fn record_dismissed_notifications(&mut self) {
+		for toast in self.notifications.take_dismissed() {
+			self.notification_history.dismissed(toast);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::options::OptionValue;
+	use xeno_tui::widgets::notifications::ToastManager;
+
+	use super::*;
+
+	#[test]
+	fn repeats_are_recorded_once_with_their_final_count() {
+		let mut toasts = ToastManager::new();
+		let mut history = NotificationHistory::default();
+		for _ in 0..3 {
+			let notification = keys::buffer_readonly.emit();
+			let id =
+				toasts.push(Toast::new(notification.message.clone()).kind(notification.def.id));
+			history.record(&notification, Some(id));
+		}
+		assert_eq!(history.records().count(), 1);
+		assert_eq!(history.records().next().unwrap().count, 1);
+
+		toasts.clear();
+		for toast in toasts.take_dismissed() {
+			history.dismissed(toast);
+		}
+		let record = history.records().next().unwrap();
+		assert_eq!((record.id, record.count), ("buffer_readonly", 3));
+
+		let notification = keys::buffer_readonly.emit();
+		let id = toasts.push(Toast::new(notification.message.clone()));
+		history.record(&notification, Some(id));
+		assert_eq!(history.records().count(), 2);
+	}
+
+	#[test]
+	fn rate_limit_sums_up_dropped_notifications() {
+		let mut limiter = NotificationLimiter::default();
+		let start = Instant::now();
+		let admitted = (0..5).filter(|_| limiter.admit("lsp", 3, start)).count();
+		assert_eq!(admitted, 3);
+		assert!(limiter.admit("git", 3, start));
+		assert!(limiter.expire(start + RATE_WINDOW / 2).is_empty());
+
+		let summaries = limiter.expire(start + RATE_WINDOW);
+		assert_eq!(summaries, vec![("lsp".to_string(), 2)]);
+		assert!(limiter.windows.is_empty());
+		assert!(limiter.admit("lsp", 3, start + RATE_WINDOW));
+		assert!((0..10).all(|_| limiter.admit("lsp", 0, start)));
+	}
+
+	#[test]
+	fn filtered_notifications_are_only_recorded() {
+		let mut editor = Editor::new_scratch();
+		editor.notify(keys::buffer_readonly);
+		assert_eq!(editor.notifications.len(), 1);
+
+		editor.config.global_options.set(
+			opt_keys::NOTIFICATION_LEVEL.untyped(),
+			OptionValue::String("error".to_string()),
+		);
+		editor.notify(keys::buffer_readonly);
+		assert_eq!(editor.notifications.len(), 1);
+		assert_eq!(editor.notification_history.records().count(), 2);
+	}
+
+	#[test]
+	fn errors_are_forwarded_only_while_unfocused() {
+		let mut editor = Editor::new_scratch();
+		editor.config.global_options.set(
+			opt_keys::NOTIFY_DESKTOP.untyped(),
+			OptionValue::String("bell".to_string()),
+		);
+		editor.notify(keys::error::call("boom"));
+		assert!(editor.take_desktop_alerts().is_empty());
+
+		editor.set_terminal_focused(false);
+		editor.notify(keys::buffer_readonly);
+		editor.notify(keys::error::call("boom"));
+		assert_eq!(editor.take_desktop_alerts(), vec![DesktopAlert::Bell]);
 	}
 }
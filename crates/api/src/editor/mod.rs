@@ -15,34 +15,72 @@
 
 /// Action dispatch and context setup.
 mod actions_exec;
+/// Automatic saving of modified buffers.
+mod autosave;
 /// Buffer collection management.
 mod buffer_manager;
 /// Buffer creation operations.
 mod buffer_ops;
+/// Code actions from language servers.
+#[cfg(feature = "lsp")]
+mod code_actions;
 /// Command queue for deferred execution.
 mod command_queue;
+/// Completion menu acceptance and mouse handling.
+mod completion;
+/// Jumping between diagnostics.
+mod diagnostics;
+/// Highlights of the symbol under the cursor.
+#[cfg(feature = "lsp")]
+pub(crate) mod document_highlight;
 /// Data-oriented edit operation executor.
 mod edit_op_executor;
 /// Text editing operations.
 mod editing;
 /// Extension container and lifecycle.
 pub mod extensions;
+/// Large-file mode and background file loading.
+mod file_load;
 /// File save and load operations.
 mod file_ops;
 /// View focus management.
 mod focus;
+/// Folding ranges requested from language servers.
+#[cfg(feature = "lsp")]
+mod folding_ranges;
+/// Opening and closing folds.
+mod folds;
+/// Formatting buffers with their language server.
+#[cfg(feature = "lsp")]
+mod format;
 /// Undo/redo history.
 mod history;
 /// Async hook execution runtime.
 mod hook_runtime;
 /// Info popup operations.
 mod info_popup;
+/// Inlay hints requested around the viewport.
+#[cfg(feature = "lsp")]
+pub(crate) mod inlay_hints;
 /// Input handling.
 mod input;
 /// Split layout management.
 mod layout;
 /// Editor lifecycle (tick, render).
 mod lifecycle;
+/// Location list navigation.
+mod locations;
+/// Language server completion with lazily resolved documentation.
+#[cfg(feature = "lsp")]
+pub(crate) mod lsp_completion;
+/// Per-document language server requests debounced after edits.
+#[cfg(feature = "lsp")]
+pub(crate) mod lsp_requests;
+/// Restarting crashed language servers.
+#[cfg(feature = "lsp")]
+mod lsp_servers;
+/// Saved selection marks.
+mod marks;
 /// Message and notification display.
 mod messaging;
 /// Cursor navigation utilities.
@@ -51,44 +89,94 @@ mod navigation;
 mod options;
 /// Command palette operations.
 mod palette;
+/// Progress notifications updated in place.
+mod progress;
+/// Modal prompt and confirm popups.
+mod prompt;
+/// Resolving saves over files changed on disk.
+mod save_conflict;
 /// Search state and operations.
 mod search;
+/// Incremental search prompt.
+mod search_prompt;
+/// Semantic tokens requested from language servers.
+#[cfg(feature = "lsp")]
+mod semantic_tokens;
 /// Separator hit detection.
 mod separator;
+/// Saved editing sessions.
+mod session;
+/// Signature help popup while typing call arguments.
+#[cfg(feature = "lsp")]
+pub(crate) mod signature_help;
+/// Snippet expansion and tabstop sessions.
+mod snippets;
 /// Split view operations.
 mod splits;
+/// Crash recovery swap files.
+mod swap;
+/// Workspace symbol picker.
+#[cfg(feature = "lsp")]
+pub(crate) mod symbol_picker;
 /// Theme management.
 mod theming;
 /// Shared type definitions.
 pub mod types;
 /// Buffer access and viewport management.
 mod views;
+/// Edits language servers make to buffers about to be saved.
+#[cfg(feature = "lsp")]
+mod will_save;
+/// LSP workspace edits applied across buffers.
+#[cfg(feature = "lsp")]
+mod workspace_edit;
 
 use std::path::PathBuf;
 
 pub use buffer_manager::BufferManager;
+#[cfg(feature = "lsp")]
+pub use code_actions::CodeActionOutcome;
 pub use command_queue::CommandQueue;
+pub use diagnostics::ERROR_SEVERITY;
 pub use focus::{FocusReason, FocusTarget, PanelId};
 pub use hook_runtime::HookRuntime;
 pub use layout::{LayoutManager, SeparatorHit, SeparatorId};
+pub use messaging::{DesktopAlert, NotificationHistory, NotificationRecord};
 pub use navigation::Location;
+pub use progress::{PROGRESS_STALL_TIMEOUT, ProgressHandle, ProgressOutcome};
 pub use types::{
-	Config, FrameState, HistoryEntry, JumpList, JumpLocation, MacroState, Registers, Viewport,
-	Workspace,
+	Config, FrameState, JumpList, JumpLocation, LocationItem, LocationKind, LocationList,
+	MacroState, Registers, UndoNode, UndoTree, Viewport, Workspace,
 };
+#[cfg(feature = "lsp")]
+pub use workspace_edit::WorkspaceEditSummary;
 use xeno_language::LanguageLoader;
+use xeno_registry::options::OptionStore;
 use xeno_registry::{
 	HookContext, HookEventData, WindowKind, emit_sync_with as emit_hook_sync_with,
 };
 use xeno_tui::layout::Rect;
 use xeno_tui::widgets::menu::MenuState;
 
+#[cfg(feature = "lsp")]
+use self::document_highlight::DocumentHighlightIdle;
+#[cfg(feature = "lsp")]
+use self::lsp_requests::LspRequestChanges;
 pub use self::separator::{DragState, MouseVelocityTracker, SeparatorHoverAnimation};
-use crate::buffer::{BufferId, Layout};
+use crate::buffer::{BufferId, DecodedText, Encoding, Layout, decode};
 use crate::editor::extensions::{ExtensionMap, StyleOverlays};
 use crate::menu::{MenuAction, create_menu};
 use crate::overlay::OverlayManager;
 use crate::ui::UiManager;
+use crate::ui::files::FileExplorer;
+use crate::ui::locations::LocationPanel;
+#[cfg(feature = "lsp")]
+use crate::ui::lsp_log::LspLogPanel;
+#[cfg(feature = "lsp")]
+use crate::ui::outline::OutlinePanel;
+use crate::ui::search::SearchPanel;
+use crate::ui::terminal::TerminalPanel;
+use crate::ui::undotree::{UndoTreeChanges, UndoTreePanel};
 use crate::window::{BaseWindow, FloatingStyle, WindowId, WindowManager};
 
 /// The main editor/workspace structure.
@@ -161,6 +249,18 @@ pub struct Editor {
 	/// Notification system.
 	pub notifications: xeno_tui::widgets::notifications::ToastManager,
 
+	/// Notifications emitted so far, kept after their toasts go.
+	pub notification_history: NotificationHistory,
+
+	/// Progress notifications of operations still running.
+	progress_toasts: progress::ProgressNotifications,
+
+	/// Per-origin rate limit on notification toasts.
+	notification_limiter: messaging::NotificationLimiter,
+
+	/// Terminal focus and alerts to forward to the desktop.
+	desktop: messaging::DesktopForwarding,
+
 	/// Extension map (typemap for extension state like ACP).
 	/// Used for loosely-coupled features that can't be direct dependencies.
 	pub extensions: ExtensionMap,
@@ -180,6 +280,15 @@ pub struct Editor {
 
 	/// Type-erased storage for UI overlays (popups, palette, completions).
 	pub overlays: OverlayManager,
+
+	/// Large files still streaming into their buffers.
+	pub(crate) file_loads: Vec<file_load::FileLoad>,
+
+	/// Pending automatic save state.
+	pub(crate) autosave: autosave::AutosaveState,
+
+	/// Crash recovery swap file state.
+	pub(crate) swap: swap::SwapState,
 }
 
 impl xeno_core::EditorOps for Editor {}
@@ -187,20 +296,22 @@ impl xeno_core::EditorOps for Editor {}
 impl Editor {
 	/// Creates a new editor by loading content from the given file path.
 	///
-	/// If the file exists but is not writable, the buffer is opened in readonly mode.
+	/// Invalid UTF-8 is decoded lossily; binary files fail to open. A file
+	/// over the default `large-file-size` streams in after the editor starts.
+	/// See [`open_file`](Self::open_file) for how the buffer is then set up.
 	pub async fn new(path: PathBuf) -> anyhow::Result<Self> {
-		let content = match tokio::fs::read_to_string(&path).await {
-			Ok(s) => s,
-			Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
-			Err(e) => return Err(e.into()),
-		};
-
-		let editor = Self::from_content(content, Some(path.clone()));
-
-		if path.exists() && !is_writable(&path) {
-			editor.buffer().set_readonly(true);
+		if let Some(size) = file_load::large_file_size(&path, &OptionStore::new()).await {
+			let (file, head) = file_load::open_large_file(&path).await?;
+			let mut editor = Self::from_content(String::new(), Some(path.clone()));
+			let buffer_id = editor.focused_view();
+			editor.begin_file_load(buffer_id, path, size, file, head);
+			return Ok(editor);
 		}
 
+		let decoded = read_file(&path, Encoding::Utf8).await?;
+		let mut editor = Self::from_content(decoded.text, Some(path.clone()));
+		let buffer_id = editor.focused_view();
+		editor.finish_file_load(buffer_id, &path, decoded.encoding, decoded.lossy);
 		Ok(editor)
 	}
 
@@ -209,6 +320,16 @@ impl Editor {
 		Self::from_content(String::new(), None)
 	}
 
+	/// Creates an editor holding piped input in an unnamed buffer.
+	///
+	/// The buffer starts modified, so quitting warns until it is written
+	/// with `:write <path>`.
+	pub fn from_piped(content: String) -> Self {
+		let editor = Self::from_content(content, None);
+		editor.buffer().set_modified(true);
+		editor
+	}
+
 	/// Creates an editor from the given content and optional file path.
 	pub fn from_content(content: String, path: Option<PathBuf>) -> Self {
 		// Initialize language loader from embedded languages.kdl
@@ -252,26 +373,52 @@ impl Editor {
 			&mut hook_runtime,
 		);
 
+		let mut ui = UiManager::new();
+		ui.register_panel(Box::new(FileExplorer::new()));
+		ui.register_panel(Box::new(SearchPanel::new()));
+		ui.register_panel(Box::new(LocationPanel::new()));
+		ui.register_panel(Box::new(TerminalPanel::new()));
+		ui.register_panel(Box::new(UndoTreePanel::new()));
+		#[cfg(feature = "lsp")]
+		ui.register_panel(Box::new(OutlinePanel::new()));
+		#[cfg(feature = "lsp")]
+		ui.register_panel(Box::new(LspLogPanel::new()));
+
+		let mut extensions = ExtensionMap::new();
+		extensions.insert(UndoTreeChanges::default());
+		#[cfg(feature = "lsp")]
+		extensions.insert(LspRequestChanges::default());
+		#[cfg(feature = "lsp")]
+		extensions.insert(DocumentHighlightIdle::default());
+
 		Self {
 			buffers: buffer_manager,
 			windows: window_manager,
 			focus,
 			layout: LayoutManager::new(),
 			viewport: Viewport::default(),
-			ui: UiManager::new(),
+			ui,
 			frame: FrameState::default(),
 			workspace: Workspace::default(),
 			config: Config::new(language_loader),
 			notifications: xeno_tui::widgets::notifications::ToastManager::new()
 				.max_visible(Some(5))
-				.overflow(xeno_tui::widgets::notifications::Overflow::DropOldest),
-			extensions: ExtensionMap::new(),
+				.overflow(xeno_tui::widgets::notifications::Overflow::Summarize)
+				.wrap(messaging::wrap_toast_line),
+			notification_history: NotificationHistory::default(),
+			progress_toasts: progress::ProgressNotifications::default(),
+			notification_limiter: messaging::NotificationLimiter::default(),
+			desktop: messaging::DesktopForwarding::default(),
+			extensions,
 			#[cfg(feature = "lsp")]
 			lsp: crate::lsp::LspManager::new(),
 			style_overlays: StyleOverlays::new(),
 			hook_runtime,
 			menu: create_menu(),
 			overlays: OverlayManager::new(),
+			file_loads: Vec::new(),
+			autosave: autosave::AutosaveState::default(),
+			swap: swap::SwapState::default(),
 		}
 	}
 
@@ -328,6 +475,16 @@ impl Editor {
 	}
 }
 
+/// Reads and decodes a file, treating a missing file as empty.
+async fn read_file(path: &std::path::Path, fallback: Encoding) -> anyhow::Result<DecodedText> {
+	let bytes = match tokio::fs::read(path).await {
+		Ok(bytes) => bytes,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+		Err(e) => return Err(e.into()),
+	};
+	decode(bytes, fallback)
+}
+
 /// Checks if a file is writable by attempting to open it for writing.
 fn is_writable(path: &std::path::Path) -> bool {
 	std::fs::OpenOptions::new().write(true).open(path).is_ok()
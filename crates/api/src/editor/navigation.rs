@@ -9,9 +9,11 @@ use xeno_base::ScrollDirection;
 use xeno_base::range::Direction as MoveDir;
 use xeno_base::selection::Selection;
 use xeno_registry::options::keys;
+use xeno_registry::{ScreenPosition, ScrollAmount};
 
 use super::Editor;
 use crate::buffer::BufferId;
+use crate::render::cursor_visual_row;
 
 /// Target location for navigation.
 #[derive(Debug, Clone)]
@@ -33,16 +35,6 @@ impl Location {
 			column,
 		}
 	}
-
-	/// Creates a location from LSP Position (line and character are 0-indexed).
-	#[cfg(feature = "lsp")]
-	pub fn from_lsp(path: impl Into<PathBuf>, position: &xeno_lsp::lsp_types::Position) -> Self {
-		Self {
-			path: path.into(),
-			line: position.line as usize,
-			column: position.character as usize,
-		}
-	}
 }
 
 impl Editor {
@@ -63,21 +55,154 @@ impl Editor {
 
 	/// Moves cursors vertically, accounting for line wrapping.
 	///
-	/// Resolves the `tab-width` option and delegates to Buffer.
+	/// Resolves the `tab-width` option and delegates to Buffer. While a block
+	/// selection is active, this grows or shrinks the block by whole lines
+	/// instead.
 	pub fn move_visual_vertical(&mut self, direction: MoveDir, count: usize, extend: bool) {
 		let tab_width = self.tab_width();
+		if self.buffer_mut().move_block_head(direction, count) {
+			self.realize_block_selection(tab_width);
+			return;
+		}
 		self.buffer_mut()
 			.move_visual_vertical(direction, count, extend, tab_width);
 	}
 
+	/// Starts a block selection at the primary cursor, or ends the active one.
+	pub fn toggle_block_selection(&mut self) {
+		if self.buffer().block_selection().is_some() {
+			self.buffer_mut().end_block_selection();
+			return;
+		}
+		let tab_width = self.tab_width();
+		self.buffer_mut().begin_block_selection(tab_width);
+		self.realize_block_selection(tab_width);
+	}
+
+	/// Regenerates the focused buffer's block selection, first padding short
+	/// lines when `block-pad-short-lines` is enabled.
+	fn realize_block_selection(&mut self, tab_width: usize) {
+		if self.option(keys::BLOCK_PAD_SHORT_LINES)
+			&& !self.buffer().is_readonly()
+			&& let Some(tx) = self.buffer().block_padding(tab_width)
+		{
+			self.save_undo_state();
+			self.apply_transaction(&tx);
+		}
+		self.buffer_mut().realize_block_selection(tab_width);
+	}
+
+	/// Scrolls the view by `amount`, moving the cursors along with it.
+	///
+	/// Page scrolls move the viewport and the cursors by the same number of
+	/// visual rows, then keep the primary cursor inside the `scrolloff`
+	/// margins so the next render does not scroll again.
+	pub fn scroll_view(&mut self, direction: MoveDir, amount: ScrollAmount, extend: bool) {
+		let height = self.buffer().last_viewport_height;
+		let rows = match amount {
+			ScrollAmount::Line(count) => {
+				self.move_visual_vertical(direction, count, extend);
+				return;
+			}
+			ScrollAmount::HalfPage => height / 2,
+			ScrollAmount::FullPage => height.saturating_sub(2),
+		};
+		if height == 0 {
+			// Before the first render the viewport size is unknown.
+			let count = if amount == ScrollAmount::HalfPage {
+				10
+			} else {
+				20
+			};
+			self.move_visual_vertical(direction, count, extend);
+			return;
+		}
+		let rows = rows.max(1);
+
+		let tab_width = self.tab_width();
+		let buffer = self.buffer_mut();
+		for _ in 0..rows {
+			match direction {
+				MoveDir::Forward => buffer.scroll_viewport_down(tab_width),
+				MoveDir::Backward => buffer.scroll_viewport_up(tab_width),
+			}
+		}
+		self.move_visual_vertical(direction, rows, extend);
+		self.keep_cursor_in_scroll_margins(extend);
+	}
+
+	/// Scrolls the view so the cursor line sits at `position`, as `zt`,
+	/// `zz` and `zb` do, without moving the cursor.
+	///
+	/// `count` is the 1-based row from the top or bottom edge, like for
+	/// `H` and `L`. The row is kept inside the `scrolloff` margins.
+	pub fn align_view(&mut self, position: ScreenPosition, count: usize) {
+		let tab_width = self.tab_width();
+		let buffer = self.buffer();
+		let height = buffer.last_viewport_height;
+		if height == 0 {
+			return;
+		}
+		let last_row = height - 1;
+		let margin = self.scroll_margins_for(buffer.id).rows.min(last_row / 2);
+		let row = match position {
+			ScreenPosition::Top => count.saturating_sub(1),
+			ScreenPosition::Middle => last_row / 2,
+			ScreenPosition::Bottom => height.saturating_sub(count.max(1)),
+		};
+		let row = row.clamp(margin, last_row - margin);
+
+		self.buffer_mut().scroll_cursor_to_row(row, tab_width);
+		self.frame.needs_redraw = true;
+	}
+
+	/// Moves the cursors so the primary cursor is at least `scrolloff` rows
+	/// from the viewport edges, unless the buffer boundary is closer.
+	fn keep_cursor_in_scroll_margins(&mut self, extend: bool) {
+		let tab_width = self.tab_width();
+		let buffer = self.buffer();
+		let height = buffer.last_viewport_height;
+		let Some(row) = cursor_visual_row(buffer, tab_width) else {
+			return;
+		};
+		let margin = self
+			.scroll_margins_for(buffer.id)
+			.rows
+			.min(height.saturating_sub(1) / 2);
+		let at_top = buffer.scroll_line == 0 && buffer.scroll_segment == 0;
+		let at_last_line = buffer.cursor_line() + 1 >= buffer.doc().content.len_lines();
+
+		if row < margin && !at_top {
+			self.move_visual_vertical(MoveDir::Forward, margin - row, extend);
+		} else if row + margin >= height && !at_last_line {
+			self.move_visual_vertical(MoveDir::Backward, row + margin + 1 - height, extend);
+		}
+	}
+
 	/// Handles mouse scroll events.
 	///
 	/// Resolves `scroll-lines` and `tab-width` options and delegates to Buffer.
 	pub(crate) fn handle_mouse_scroll(&mut self, direction: ScrollDirection, count: usize) {
+		let buffer_id = self.focused_view();
+		self.scroll_buffer_viewport(buffer_id, direction, count);
+	}
+
+	/// Scrolls the viewport of `buffer_id` as a mouse wheel would, whether or
+	/// not it has focus.
+	///
+	/// Popups under the pointer scroll this way without taking focus, which
+	/// would close the popups that dismiss on blur once focus moved back.
+	pub(crate) fn scroll_buffer_viewport(
+		&mut self,
+		buffer_id: BufferId,
+		direction: ScrollDirection,
+		count: usize,
+	) {
 		let scroll_lines = (self.option(keys::SCROLL_LINES) as usize).max(1);
-		let tab_width = self.tab_width();
-		self.buffer_mut()
-			.handle_mouse_scroll(direction, count * scroll_lines, tab_width);
+		let tab_width = self.tab_width_for(buffer_id);
+		if let Some(buffer) = self.buffers.get_buffer_mut(buffer_id) {
+			buffer.handle_mouse_scroll(direction, count * scroll_lines, tab_width);
+		}
 	}
 
 	/// Navigates to a specific location (file, line, column).
@@ -98,12 +223,12 @@ impl Editor {
 		// Check if we already have this file open
 		let buffer_id = if let Some(id) = self.buffers.find_by_path(&location.path) {
 			// Switch to existing buffer
-			self.focus_buffer(id);
+			self.show_buffer(id);
 			id
 		} else {
 			// Open the file
 			let id = self.open_file(location.path.clone()).await?;
-			self.focus_buffer(id);
+			self.show_buffer(id);
 			id
 		};
 
@@ -113,6 +238,28 @@ impl Editor {
 		Ok(buffer_id)
 	}
 
+	/// Opens a file and moves the cursor to an LSP `position` in it, whose
+	/// column is counted in `encoding`.
+	#[cfg(feature = "lsp")]
+	pub async fn goto_lsp_position(
+		&mut self,
+		path: PathBuf,
+		position: xeno_lsp::lsp_types::Position,
+		encoding: xeno_lsp::OffsetEncoding,
+	) -> anyhow::Result<BufferId> {
+		let buffer_id = self
+			.goto_location(&Location::new(path, position.line as usize, 0))
+			.await?;
+		let (line, column) = {
+			let content = &self.buffer().doc().content;
+			let pos = xeno_lsp::lsp_position_to_char(content, position, encoding);
+			let line = content.char_to_line(pos);
+			(line, pos - content.line_to_char(line))
+		};
+		self.goto_line_col(line, column);
+		Ok(buffer_id)
+	}
+
 	/// Moves cursor to a specific line and column.
 	///
 	/// Line and column are 0-indexed. If the line doesn't exist, goes to the
@@ -4,7 +4,8 @@ use xeno_base::Mode;
 use xeno_registry::options::{OptionValue, keys};
 
 use super::Editor;
-use crate::palette::{Palette, PaletteState, palette_rect, palette_style};
+use crate::palette::{Palette, PaletteKind, PaletteState, palette_rect, palette_style};
+use crate::prompt::PromptResult;
 use crate::window::{GutterSelector, Window};
 
 impl Editor {
@@ -13,6 +14,11 @@ impl Editor {
 	/// Creates a scratch buffer in a floating window for command input.
 	/// Returns `false` if the palette is already open or window dimensions are unavailable.
 	pub fn open_palette(&mut self) -> bool {
+		self.open_palette_as(PaletteKind::Command, '>')
+	}
+
+	/// Opens the palette for `kind` input, with `prompt` drawn in its gutter.
+	pub(crate) fn open_palette_as(&mut self, kind: PaletteKind, prompt: char) -> bool {
 		if self
 			.overlays
 			.get::<PaletteState>()
@@ -40,7 +46,7 @@ impl Editor {
 		};
 		float.sticky = true;
 		float.dismiss_on_blur = true;
-		float.gutter = GutterSelector::Prompt('>');
+		float.gutter = GutterSelector::Prompt(prompt);
 
 		self.focus_floating_window(window_id);
 		self.buffers
@@ -52,32 +58,40 @@ impl Editor {
 		self.overlays.insert(PaletteState::Open(Palette {
 			window_id,
 			buffer_id,
+			kind,
 		}));
 		true
 	}
 
 	/// Closes the command palette without executing.
+	///
+	/// A search prompt is cancelled, restoring the searched view, and a
+	/// prompt popup is answered with [`PromptResult::Cancelled`].
 	pub fn close_palette(&mut self) {
-		let Some(palette) = self.overlays.get::<PaletteState>().and_then(|p| p.active()) else {
-			return;
+		match self.dismiss_palette() {
+			Some(PaletteKind::Search(prompt)) => self.cancel_search_prompt(prompt),
+			Some(PaletteKind::Prompt(prompt)) => (prompt.on_result)(self, PromptResult::Cancelled),
+			Some(PaletteKind::Command) | None => {}
+		}
+	}
+
+	/// Closes the palette window and returns what it was open for.
+	pub(crate) fn dismiss_palette(&mut self) -> Option<PaletteKind> {
+		let state = std::mem::take(self.overlays.get_or_default::<PaletteState>());
+		let PaletteState::Open(palette) = state else {
+			return None;
 		};
-		let window_id = palette.window_id;
-		let buffer_id = palette.buffer_id;
 
-		self.close_floating_window(window_id);
-		self.buffers.remove_buffer(buffer_id);
-		self.overlays.insert(PaletteState::Closed);
+		self.close_floating_window(palette.window_id);
+		self.buffers.remove_buffer(palette.buffer_id);
 
 		self.focus_base_window();
 		self.focused_buffer_mut().input.set_mode(Mode::Normal);
+		Some(palette.kind)
 	}
 
-	/// Executes the command in the palette and closes it.
-	///
-	/// Parses the input as `<command> [args...]` and queues it for execution.
-	/// Returns the raw input string on success, or `None` if the palette wasn't
-	/// open, the input was empty, or the command was not found.
-	pub fn execute_palette(&mut self) -> Option<String> {
+	/// Returns the text typed into the open palette.
+	pub(crate) fn palette_input(&self) -> Option<String> {
 		let buffer_id = self
 			.overlays
 			.get::<PaletteState>()
@@ -88,9 +102,27 @@ impl Editor {
 			.doc()
 			.content
 			.to_string();
-		let input = input.trim().to_string();
+		Some(input.trim().to_string())
+	}
+
+	/// Executes the command in the palette and closes it.
+	///
+	/// Parses the input as `<command> [args...]` and queues it for execution.
+	/// A search prompt instead commits its pattern and jumps to the match,
+	/// and a prompt popup submits its input if it is valid.
+	/// Returns the raw input string on success, or `None` if the palette wasn't
+	/// open, the input was empty, or the command was not found.
+	pub fn execute_palette(&mut self) -> Option<String> {
+		let input = self.palette_input()?;
 
-		self.close_palette();
+		if self.prompt_buffer().is_some() {
+			self.submit_prompt(input.clone());
+			return Some(input);
+		}
+
+		if let Some(PaletteKind::Search(prompt)) = self.dismiss_palette() {
+			return self.commit_search_prompt(prompt, input);
+		}
 
 		if input.is_empty() {
 			return None;
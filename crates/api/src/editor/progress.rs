@@ -0,0 +1,284 @@
+//! Progress notifications updated in place.
+//!
+//! [`Editor::progress`] shows a toast with a spinner, or a bar once the
+//! fraction done is known, and returns a [`ProgressHandle`] that updates it
+//! from any thread. The toast stays until the handle finishes it, then turns
+//! into a success or error notification. If every handle is dropped without
+//! finishing, the toast is reported as stalled after
+//! [`PROGRESS_STALL_TIMEOUT`].
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use xeno_registry_notifications::{Level, Notification, keys};
+use xeno_tui::widgets::notifications::{AutoDismiss, ToastProgress};
+
+use super::Editor;
+
+/// How long a progress toast whose handles were all dropped without
+/// finishing it keeps showing before it is reported as stalled.
+pub const PROGRESS_STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long each spinner frame stays on screen.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// How an operation reported by a progress notification ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressOutcome {
+	/// It succeeded, with a closing message that may be empty.
+	Success(String),
+	/// It failed, with the error.
+	Error(String),
+}
+
+/// Progress shared between the editor and the handles of one operation.
+#[derive(Debug)]
+struct ProgressState {
+	/// Name of the operation, drawn in the toast's border.
+	title: String,
+	/// What the operation is doing now.
+	message: String,
+	/// Fraction done from 0 to 1, if known.
+	fraction: Option<f32>,
+	/// How the operation ended, once it has.
+	outcome: Option<ProgressOutcome>,
+}
+
+/// Handle updating a progress notification, from any thread.
+///
+/// Dropping it without calling [`finish`](Self::finish) leaves the toast to
+/// be reported as stalled.
+#[derive(Debug)]
+pub struct ProgressHandle {
+	/// State the editor draws the toast from.
+	state: Arc<Mutex<ProgressState>>,
+}
+
+impl ProgressHandle {
+	/// Sets what the operation is doing now.
+	pub fn set_message(&self, message: impl Into<String>) {
+		self.state.lock().message = message.into();
+	}
+
+	/// Sets the fraction done, drawing a bar, or `None` to draw a spinner.
+	pub fn set_fraction(&self, fraction: Option<f32>) {
+		self.state.lock().fraction = fraction.map(|f| f.clamp(0.0, 1.0));
+	}
+
+	/// Ends the operation, turning its toast into a success or error
+	/// notification.
+	pub fn finish(self, outcome: ProgressOutcome) {
+		self.state.lock().outcome = Some(outcome);
+	}
+}
+
+/// A progress notification the editor is showing.
+#[derive(Debug)]
+struct ProgressEntry {
+	/// State shared with the handles.
+	state: Arc<Mutex<ProgressState>>,
+	/// Toast showing the progress, once pushed.
+	toast: Option<u64>,
+	/// When the operation started, for turning the spinner.
+	started: Instant,
+	/// When the editor first saw every handle dropped.
+	orphaned: Option<Instant>,
+}
+
+/// Progress notifications of running operations, keyed by caller-chosen ID.
+#[derive(Debug, Default)]
+pub(super) struct ProgressNotifications {
+	/// Running operations.
+	entries: HashMap<String, ProgressEntry>,
+}
+
+/// What to do with a progress toast on this tick.
+enum ProgressUpdate {
+	/// Draw the current progress.
+	Show {
+		/// Toast showing the progress, if pushed.
+		toast: Option<u64>,
+		/// Name of the operation.
+		title: String,
+		/// What the operation is doing now.
+		message: String,
+		/// What to draw below the message.
+		progress: ToastProgress,
+	},
+	/// Replace the toast with a closing notification.
+	End {
+		/// Toast showing the progress, if pushed.
+		toast: Option<u64>,
+		/// Notification saying how the operation ended.
+		notification: Notification,
+	},
+}
+
+impl Editor {
+	/// Shows a progress notification for the operation `id` and returns a
+	/// handle updating it.
+	///
+	/// Calling this again with the ID of a running operation renames it and
+	/// returns another handle to the same toast.
+	pub fn progress(&mut self, id: impl Into<String>, title: impl Into<String>) -> ProgressHandle {
+		let title = title.into();
+		let entry = self
+			.progress_toasts
+			.entries
+			.entry(id.into())
+			.or_insert_with(|| ProgressEntry {
+				state: Arc::new(Mutex::new(ProgressState {
+					title: String::new(),
+					message: String::new(),
+					fraction: None,
+					outcome: None,
+				})),
+				toast: None,
+				started: Instant::now(),
+				orphaned: None,
+			});
+		entry.state.lock().title = title;
+		let handle = ProgressHandle {
+			state: entry.state.clone(),
+		};
+		self.poll_progress_notifications();
+		handle
+	}
+
+	/// Redraws the toasts of running operations and replaces those of
+	/// finished or stalled ones with closing notifications.
+	pub(crate) fn poll_progress_notifications(&mut self) {
+		self.poll_progress_notifications_at(Instant::now());
+	}
+
+	/// Does [`poll_progress_notifications`](Self::poll_progress_notifications)
+	/// as of `now`.
+	fn poll_progress_notifications_at(&mut self, now: Instant) {
+		if self.progress_toasts.entries.is_empty() {
+			return;
+		}
+		let mut updates = Vec::new();
+		for (id, entry) in &mut self.progress_toasts.entries {
+			let state = entry.state.lock();
+			let notification = match &state.outcome {
+				Some(ProgressOutcome::Success(message)) => {
+					Some(keys::progress_done::call(&state.title, message))
+				}
+				Some(ProgressOutcome::Error(error)) => {
+					Some(keys::progress_failed::call(&state.title, error))
+				}
+				None if Arc::strong_count(&entry.state) == 1 => {
+					let since = *entry.orphaned.get_or_insert(now);
+					(now.saturating_duration_since(since) >= PROGRESS_STALL_TIMEOUT)
+						.then(|| keys::progress_stalled::call(&state.title))
+				}
+				None => {
+					entry.orphaned = None;
+					None
+				}
+			};
+			let update = match notification {
+				Some(notification) => ProgressUpdate::End {
+					toast: entry.toast,
+					notification,
+				},
+				None => ProgressUpdate::Show {
+					toast: entry.toast,
+					title: state.title.clone(),
+					message: state.message.clone(),
+					progress: ToastProgress {
+						fraction: state.fraction,
+						tick: (now.saturating_duration_since(entry.started).as_millis()
+							/ SPINNER_INTERVAL.as_millis()) as u64,
+					},
+				},
+			};
+			updates.push((id.clone(), update));
+		}
+
+		for (id, update) in updates {
+			match update {
+				ProgressUpdate::Show {
+					toast,
+					title,
+					message,
+					progress,
+				} => {
+					let content = self
+						.level_toast(Level::Info, message)
+						.title(title)
+						.dedupe(false)
+						.auto_dismiss(AutoDismiss::Never)
+						.progress(Some(progress));
+					// The toast may have been dropped to make room for others.
+					let toast = match toast {
+						Some(toast) if self.notifications.update(toast, content.clone()) => toast,
+						_ => self.notifications.push(content),
+					};
+					if let Some(entry) = self.progress_toasts.entries.get_mut(&id) {
+						entry.toast = Some(toast);
+					}
+				}
+				ProgressUpdate::End {
+					toast,
+					notification,
+				} => {
+					if let Some(toast) = toast {
+						self.notifications.remove(toast);
+					}
+					self.progress_toasts.entries.remove(&id);
+					self.show_notification(notification);
+				}
+			}
+		}
+		self.frame.needs_redraw = true;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn finishing_replaces_the_progress_toast() {
+		let mut editor = Editor::new_scratch();
+		let handle = editor.progress("index", "Indexing");
+		handle.set_message("3/10 crates");
+		handle.set_fraction(Some(0.3));
+		editor.poll_progress_notifications();
+		assert_eq!(editor.notifications.len(), 1);
+		assert_eq!(editor.notification_history.records().count(), 0);
+
+		handle.finish(ProgressOutcome::Success("10 crates".into()));
+		editor.poll_progress_notifications();
+		assert_eq!(editor.notifications.len(), 1);
+		let record = editor.notification_history.records().last().unwrap();
+		assert_eq!(record.id, "progress_done");
+		assert_eq!(record.message, "Indexing: 10 crates");
+		assert!(editor.progress_toasts.entries.is_empty());
+	}
+
+	#[test]
+	fn dropped_handles_stall_after_a_timeout() {
+		let mut editor = Editor::new_scratch();
+		drop(editor.progress("grep", "Searching"));
+		let start = Instant::now();
+		editor.poll_progress_notifications_at(start);
+		assert_eq!(editor.progress_toasts.entries.len(), 1);
+
+		let again = editor.progress("grep", "Searching again");
+		editor.poll_progress_notifications_at(start + PROGRESS_STALL_TIMEOUT);
+		assert_eq!(editor.progress_toasts.entries.len(), 1);
+		drop(again);
+
+		let later = start + PROGRESS_STALL_TIMEOUT * 2;
+		editor.poll_progress_notifications_at(later);
+		editor.poll_progress_notifications_at(later + PROGRESS_STALL_TIMEOUT);
+		let record = editor.notification_history.records().last().unwrap();
+		assert_eq!(record.id, "progress_stalled");
+		assert_eq!(record.message, "Searching again stalled");
+		assert!(editor.progress_toasts.entries.is_empty());
+	}
+}
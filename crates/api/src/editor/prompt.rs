@@ -0,0 +1,314 @@
+//! Prompt and confirm popup integration with editor.
+//!
+//! A [`PromptPopup`] opens the palette input with its own title and gutter
+//! character; a [`ConfirmPopup`] opens a read-only floating window centered
+//! in the document area. Either one takes every key until it is answered.
+
+use termina::event::{KeyCode, KeyEvent};
+use xeno_base::Rope;
+
+use super::Editor;
+use crate::buffer::BufferId;
+use crate::info_popup::{PopupAnchor, compute_popup_rect, info_popup_style};
+use crate::palette::{PaletteKind, PaletteState};
+use crate::prompt::{ConfirmPopup, ConfirmResult, PromptPopup, PromptResult};
+use crate::window::{GutterSelector, Window, WindowId};
+
+/// An open confirm popup.
+#[derive(Debug)]
+struct ActiveConfirm {
+	/// Floating window showing the message and choices.
+	window_id: WindowId,
+	/// Read-only buffer backing the window.
+	buffer_id: BufferId,
+	/// The popup being answered.
+	popup: ConfirmPopup,
+}
+
+/// Overlay state holding the open confirm popup, if any.
+#[derive(Debug, Default)]
+pub(crate) struct ConfirmState {
+	active: Option<ActiveConfirm>,
+}
+
+impl Editor {
+	/// Opens a prompt in the palette input.
+	///
+	/// Returns `false` without calling back if the palette is already open
+	/// or window dimensions are unavailable.
+	pub fn open_prompt(&mut self, prompt: PromptPopup) -> bool {
+		let (title, gutter) = (prompt.title.clone(), prompt.prompt);
+		let initial = prompt.initial.clone();
+		if !self.open_palette_as(PaletteKind::Prompt(prompt), gutter) {
+			return false;
+		}
+		if !initial.is_empty() {
+			self.insert_text(&initial);
+		}
+		self.set_palette_title(Some(title));
+		self.update_prompt_validation();
+		true
+	}
+
+	/// Returns the input buffer of the open prompt, if any.
+	pub(crate) fn prompt_buffer(&self) -> Option<BufferId> {
+		let state = self.overlays.get::<PaletteState>()?;
+		state.prompt().and(state.buffer_id())
+	}
+
+	/// Re-runs the open prompt's validator after its input changed.
+	pub(crate) fn update_prompt_validation(&mut self) {
+		let Some(input) = self.palette_input() else {
+			return;
+		};
+		let Some(prompt) = self.overlays.get_or_default::<PaletteState>().prompt_mut() else {
+			return;
+		};
+		prompt.error = prompt.validate(&input);
+		let title = prompt.error.clone().unwrap_or_else(|| prompt.title.clone());
+		self.set_palette_title(Some(title));
+	}
+
+	/// Completes the open prompt's input with its completion hook.
+	///
+	/// Returns `false` if no prompt is open or there was nothing to add.
+	pub(crate) fn complete_prompt(&mut self) -> bool {
+		let Some(input) = self.palette_input() else {
+			return false;
+		};
+		let Some(completed) = self
+			.overlays
+			.get::<PaletteState>()
+			.and_then(PaletteState::prompt)
+			.and_then(|prompt| prompt.complete(&input))
+		else {
+			return false;
+		};
+		self.insert_text(&completed[input.len()..]);
+		self.update_prompt_validation();
+		true
+	}
+
+	/// Submits the open prompt's input if it passes validation.
+	///
+	/// Invalid input leaves the prompt open with the validator's message as
+	/// its title.
+	pub(crate) fn submit_prompt(&mut self, input: String) {
+		let error = self
+			.overlays
+			.get::<PaletteState>()
+			.and_then(PaletteState::prompt)
+			.and_then(|prompt| prompt.validate(&input));
+		if let Some(error) = error {
+			self.set_palette_title(Some(error));
+			return;
+		}
+		if let Some(PaletteKind::Prompt(prompt)) = self.dismiss_palette() {
+			(prompt.on_result)(self, PromptResult::Submitted(input));
+		}
+	}
+
+	/// Shows `title` on the palette's top border.
+	fn set_palette_title(&mut self, title: Option<String>) {
+		let window_id = self
+			.overlays
+			.get::<PaletteState>()
+			.and_then(|p| p.window_id());
+		if let Some(id) = window_id
+			&& let Some(Window::Floating(float)) = self.windows.get_mut(id)
+		{
+			float.style.title = title;
+		}
+		self.frame.needs_redraw = true;
+	}
+
+	/// Opens a confirm popup centered in the document area, replacing any
+	/// confirm popup already open.
+	///
+	/// A replaced popup is answered with [`ConfirmResult::Cancelled`].
+	pub fn open_confirm(&mut self, popup: ConfirmPopup) {
+		self.answer_confirm(ConfirmResult::Cancelled);
+
+		let content = popup.content();
+		let width = content
+			.lines()
+			.map(|l| l.chars().count())
+			.max()
+			.unwrap_or(0)
+			.max(popup.title.chars().count());
+		let height = content.lines().count();
+		let rect = compute_popup_rect(
+			PopupAnchor::Center,
+			width.min(80) as u16,
+			height as u16,
+			self.doc_area(),
+		);
+
+		let buffer_id = self.buffers.create_scratch();
+		{
+			let buffer = self
+				.buffers
+				.get_buffer_mut(buffer_id)
+				.expect("just created");
+			buffer.replace_content(Rope::from_str(&content));
+			buffer.set_readonly_override(Some(true));
+		}
+
+		let mut style = info_popup_style();
+		style.title = Some(popup.title.clone());
+		let window_id = self.create_floating_window(buffer_id, rect, style);
+		let Window::Floating(float) = self.windows.get_mut(window_id).expect("just created") else {
+			unreachable!()
+		};
+		float.sticky = true;
+		float.gutter = GutterSelector::Hidden;
+
+		self.overlays.get_or_default::<ConfirmState>().active = Some(ActiveConfirm {
+			window_id,
+			buffer_id,
+			popup,
+		});
+		self.frame.needs_redraw = true;
+	}
+
+	/// Returns whether a confirm popup is open.
+	pub(crate) fn confirm_open(&self) -> bool {
+		self.overlays
+			.get::<ConfirmState>()
+			.is_some_and(|s| s.active.is_some())
+	}
+
+	/// Handles a key while a confirm popup is open.
+	///
+	/// The popup is modal: keys other than its choices and Escape are
+	/// ignored.
+	pub(crate) fn handle_confirm_key(&mut self, key: &KeyEvent) {
+		let result = match key.code {
+			KeyCode::Escape => ConfirmResult::Cancelled,
+			KeyCode::Char(c) => {
+				let chosen = self
+					.overlays
+					.get::<ConfirmState>()
+					.and_then(|s| s.active.as_ref())
+					.and_then(|active| active.popup.choice_for(c));
+				let Some(key) = chosen else {
+					return;
+				};
+				ConfirmResult::Chosen(key)
+			}
+			_ => return,
+		};
+		self.answer_confirm(result);
+	}
+
+	/// Closes the open confirm popup and passes `result` to its callback.
+	pub(crate) fn answer_confirm(&mut self, result: ConfirmResult) {
+		let Some(active) = self.overlays.get_or_default::<ConfirmState>().active.take() else {
+			return;
+		};
+		self.close_floating_window(active.window_id);
+		self.buffers.remove_buffer(active.buffer_id);
+		self.frame.needs_redraw = true;
+		(active.popup.on_result)(self, result);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{Arc, Mutex};
+
+	use termina::event::Modifiers;
+
+	use super::*;
+
+	fn editor() -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.viewport.width = Some(80);
+		editor.viewport.height = Some(24);
+		editor
+	}
+
+	fn key(code: KeyCode) -> KeyEvent {
+		KeyEvent::new(code, Modifiers::NONE)
+	}
+
+	#[test]
+	fn confirm_answers_with_choice_or_cancel() {
+		let answers = Arc::new(Mutex::new(Vec::new()));
+		let mut editor = editor();
+		for code in [KeyCode::Char('N'), KeyCode::Escape] {
+			let sink = Arc::clone(&answers);
+			editor.open_confirm(
+				ConfirmPopup::new("Replace", "Replace the file?", move |_, result| {
+					sink.lock().unwrap().push(result);
+				})
+				.choice('y', "yes")
+				.choice('n', "no"),
+			);
+			assert!(editor.confirm_open());
+			editor.handle_confirm_key(&key(KeyCode::Char('q')));
+			assert!(editor.confirm_open());
+			editor.handle_confirm_key(&key(code));
+			assert!(!editor.confirm_open());
+		}
+		assert_eq!(
+			*answers.lock().unwrap(),
+			[ConfirmResult::Chosen('n'), ConfirmResult::Cancelled]
+		);
+	}
+
+	#[test]
+	fn prompt_submits_only_valid_input() {
+		let answer = Arc::new(Mutex::new(None));
+		let sink = Arc::clone(&answer);
+		let mut editor = editor();
+		let prompt = PromptPopup::new("Rename", move |_, result| {
+			*sink.lock().unwrap() = Some(result);
+		})
+		.validator(|input| {
+			if input.is_empty() {
+				Err("name required".to_string())
+			} else {
+				Ok(())
+			}
+		});
+		assert!(editor.open_prompt(prompt));
+
+		editor.submit_prompt(String::new());
+		assert!(editor.palette_is_open());
+		assert_eq!(*answer.lock().unwrap(), None);
+
+		editor.insert_text("renamed");
+		editor.execute_palette();
+		assert!(!editor.palette_is_open());
+		assert_eq!(
+			*answer.lock().unwrap(),
+			Some(PromptResult::Submitted("renamed".to_string()))
+		);
+	}
+
+	#[test]
+	fn prompt_completes_and_cancels() {
+		let answer = Arc::new(Mutex::new(None));
+		let sink = Arc::clone(&answer);
+		let mut editor = editor();
+		let prompt = PromptPopup::new("Theme", move |_, result| {
+			*sink.lock().unwrap() = Some(result);
+		})
+		.initial("gr")
+		.completer(|input| {
+			["gruvbox", "gruber"]
+				.into_iter()
+				.filter(|name| name.starts_with(input))
+				.map(String::from)
+				.collect()
+		});
+		assert!(editor.open_prompt(prompt));
+		assert!(editor.complete_prompt());
+		assert_eq!(editor.palette_input().as_deref(), Some("gru"));
+		assert!(!editor.complete_prompt());
+
+		editor.close_palette();
+		assert_eq!(*answer.lock().unwrap(), Some(PromptResult::Cancelled));
+	}
+}
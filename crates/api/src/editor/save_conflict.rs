@@ -0,0 +1,377 @@
+//! Saving over a file that changed on disk.
+//!
+//! Each document remembers the text and modification time of its file as it
+//! was last loaded or saved. A save that finds a newer file with different
+//! text does not write; instead a modal popup offers to keep the buffer
+//! (overwriting the file), load the disk version, or merge the disk changes
+//! into the buffer against the remembered text. Dismissing the popup leaves
+//! both the buffer and the file untouched.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use termina::event::{KeyCode, KeyEvent};
+use xeno_base::transaction::merge3;
+use xeno_base::{Rope, Transaction};
+use xeno_registry::commands::CommandError;
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+use crate::buffer::{BufferId, decode, normalize};
+use crate::info_popup::{PopupAnchor, compute_popup_rect, info_popup_style};
+use crate::window::{GutterSelector, Window, WindowId};
+
+/// A file's text on disk, newer than the buffer's last load or save.
+#[derive(Debug, Clone)]
+pub(crate) struct DiskChange {
+	/// Path of the changed file.
+	pub(crate) path: PathBuf,
+	/// Decoded, `\n`-normalized text of the file.
+	pub(crate) theirs: Rope,
+	/// Modification time of the file when it was read.
+	pub(crate) mtime: Option<SystemTime>,
+}
+
+impl DiskChange {
+	/// Returns the error reported for a save refused over this change.
+	pub(crate) fn error(&self) -> CommandError {
+		CommandError::Failed(format!(
+			"{} changed on disk since it was read",
+			self.path.display()
+		))
+	}
+}
+
+/// An open save conflict prompt.
+#[derive(Debug)]
+struct SaveConflict {
+	/// Floating window showing the choices.
+	window_id: WindowId,
+	/// Read-only buffer backing the popup.
+	popup_buffer: BufferId,
+	/// Buffer whose save was refused.
+	buffer_id: BufferId,
+	/// The file's current text.
+	change: DiskChange,
+}
+
+/// Overlay state holding the open save conflict prompt, if any.
+#[derive(Debug, Default)]
+pub(crate) struct SaveConflictState {
+	active: Option<SaveConflict>,
+}
+
+/// How to resolve a save conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Resolution {
+	/// Overwrite the file with the buffer.
+	KeepMine,
+	/// Replace the buffer with the file.
+	LoadTheirs,
+	/// Apply the file's changes to the buffer, marking conflicts.
+	Merge,
+}
+
+/// Returns the modification time of the file at `path`, if it exists.
+pub(crate) fn file_mtime(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Editor {
+	/// Checks whether a buffer's file changed on disk since it was last
+	/// loaded or saved.
+	///
+	/// A newer file whose text still matches the buffer's base only has its
+	/// modification time recorded. A missing file, or a buffer never loaded
+	/// from disk, is not a change.
+	pub(crate) async fn disk_change(
+		&mut self,
+		buffer_id: BufferId,
+	) -> Result<Option<DiskChange>, CommandError> {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return Ok(None);
+		};
+		let Some(path) = buffer.path() else {
+			return Ok(None);
+		};
+		let (loaded, encoding) = {
+			let doc = buffer.doc();
+			(doc.disk_mtime, doc.encoding)
+		};
+		let Some(loaded) = loaded else {
+			return Ok(None);
+		};
+		let mtime = file_mtime(&path);
+		if mtime.is_none_or(|mtime| mtime == loaded) {
+			return Ok(None);
+		}
+
+		let bytes = tokio::fs::read(&path)
+			.await
+			.map_err(|e| CommandError::Io(e.to_string()))?;
+		let decoded = decode(bytes, encoding).map_err(|e| CommandError::Failed(e.to_string()))?;
+		let theirs = Rope::from(normalize(decoded.text).text.as_str());
+
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return Ok(None);
+		};
+		let mut doc = buffer.doc_mut();
+		if doc.disk_base.as_ref() == Some(&theirs) {
+			doc.disk_mtime = mtime;
+			return Ok(None);
+		}
+		Ok(Some(DiskChange {
+			path,
+			theirs,
+			mtime,
+		}))
+	}
+
+	/// Opens the modal prompt asking how to resolve a refused save.
+	pub(crate) fn open_save_conflict(&mut self, buffer_id: BufferId, change: DiskChange) {
+		self.close_save_conflict();
+
+		let content = format!(
+			"{} changed on disk since it was read.\n\n\
+			 k  keep mine: overwrite the file\n\
+			 l  load theirs: replace the buffer\n\
+			 m  merge disk changes into the buffer\n\
+			 esc  cancel",
+			change.path.display()
+		);
+		let width = content
+			.lines()
+			.map(|l| l.chars().count())
+			.max()
+			.unwrap_or(0);
+		let height = content.lines().count();
+		let rect = compute_popup_rect(
+			PopupAnchor::Center,
+			width.min(80) as u16,
+			height as u16,
+			self.doc_area(),
+		);
+
+		let popup_buffer = self.buffers.create_scratch();
+		{
+			let buffer = self
+				.buffers
+				.get_buffer_mut(popup_buffer)
+				.expect("just created");
+			buffer.replace_content(Rope::from_str(&content));
+			buffer.set_readonly_override(Some(true));
+		}
+
+		let mut style = info_popup_style();
+		style.title = Some("File changed on disk".to_string());
+		let window_id = self.create_floating_window(popup_buffer, rect, style);
+		let Window::Floating(float) = self.windows.get_mut(window_id).expect("just created") else {
+			unreachable!()
+		};
+		float.sticky = true;
+		float.gutter = GutterSelector::Hidden;
+
+		self.overlays.get_or_default::<SaveConflictState>().active = Some(SaveConflict {
+			window_id,
+			popup_buffer,
+			buffer_id,
+			change,
+		});
+		self.frame.needs_redraw = true;
+	}
+
+	/// Returns whether the save conflict prompt is open.
+	pub(crate) fn save_conflict_open(&self) -> bool {
+		self.overlays
+			.get::<SaveConflictState>()
+			.is_some_and(|s| s.active.is_some())
+	}
+
+	/// Handles a key while the save conflict prompt is open.
+	///
+	/// The prompt is modal: keys other than its choices and Escape are
+	/// ignored.
+	pub(crate) async fn handle_save_conflict_key(&mut self, key: &KeyEvent) {
+		let resolution = match key.code {
+			KeyCode::Char('k') => Resolution::KeepMine,
+			KeyCode::Char('l') => Resolution::LoadTheirs,
+			KeyCode::Char('m') => Resolution::Merge,
+			KeyCode::Escape => {
+				self.close_save_conflict();
+				return;
+			}
+			_ => return,
+		};
+		self.resolve_save_conflict(resolution).await;
+	}
+
+	/// Closes the prompt and applies `resolution` to the conflicting buffer.
+	pub(crate) async fn resolve_save_conflict(&mut self, resolution: Resolution) {
+		let Some(conflict) = self.close_save_conflict() else {
+			return;
+		};
+		let (buffer_id, change) = (conflict.buffer_id, conflict.change);
+		if self.buffers.get_buffer(buffer_id).is_none() {
+			return;
+		}
+
+		match resolution {
+			Resolution::KeepMine => match self.write_buffer(buffer_id, false).await {
+				Ok(path) => self.notify(keys::file_saved::call(&path)),
+				Err(e) => self.notify(keys::command_error::call(&e.to_string())),
+			},
+			Resolution::LoadTheirs => {
+				if self.replace_with_disk_text(buffer_id, &change.theirs) {
+					let buffer = self.buffers.get_buffer(buffer_id).expect("checked above");
+					buffer.set_modified(false);
+					self.record_disk_state(buffer_id, &change);
+					self.remove_swap_file(buffer_id);
+					self.notify(keys::disk_version_loaded::call(&change.path));
+				}
+			}
+			Resolution::Merge => {
+				let merged = {
+					let doc = self
+						.buffers
+						.get_buffer(buffer_id)
+						.expect("checked above")
+						.doc();
+					let base = doc.disk_base.clone().unwrap_or_default();
+					merge3(&base, &doc.content, &change.theirs)
+				};
+				if self.replace_with_disk_text(buffer_id, &Rope::from(merged.text.as_str())) {
+					self.record_disk_state(buffer_id, &change);
+					self.notify(keys::disk_changes_merged::call(
+						&change.path,
+						merged.conflicts,
+					));
+				}
+			}
+		}
+	}
+
+	/// Rewrites a buffer to `text` as one undoable edit touching only the
+	/// lines that differ.
+	fn replace_with_disk_text(&mut self, buffer_id: BufferId, text: &Rope) -> bool {
+		let (tx, selection) = {
+			let buffer = self.buffers.get_buffer(buffer_id).expect("caller checked");
+			let tx = Transaction::diff(&buffer.doc().content, text);
+			let selection = tx.map_selection(&buffer.selection);
+			(tx, selection)
+		};
+		self.save_undo_state();
+		let applied = self.apply_transaction_with_selection(buffer_id, &tx, Some(selection));
+		if !applied {
+			self.notify(keys::buffer_readonly);
+		}
+		applied
+	}
+
+	/// Makes the disk text of `change` the buffer's new merge base.
+	fn record_disk_state(&mut self, buffer_id: BufferId, change: &DiskChange) {
+		if let Some(buffer) = self.buffers.get_buffer(buffer_id) {
+			let mut doc = buffer.doc_mut();
+			doc.disk_base = Some(change.theirs.clone());
+			doc.disk_mtime = change.mtime;
+		}
+	}
+
+	/// Closes the save conflict prompt, returning it if one was open.
+	fn close_save_conflict(&mut self) -> Option<SaveConflict> {
+		let conflict = self
+			.overlays
+			.get_or_default::<SaveConflictState>()
+			.active
+			.take()?;
+		self.close_floating_window(conflict.window_id);
+		self.buffers.remove_buffer(conflict.popup_buffer);
+		self.frame.needs_redraw = true;
+		Some(conflict)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use xeno_core::editor_ctx::FileOpsAccess;
+
+	use super::*;
+
+	/// Writes `text` to `path` with a modification time `age` in the past.
+	fn write_aged(path: &Path, text: &str, age: u64) {
+		std::fs::write(path, text).unwrap();
+		std::fs::File::options()
+			.write(true)
+			.open(path)
+			.unwrap()
+			.set_modified(SystemTime::now() - Duration::from_secs(age))
+			.unwrap();
+	}
+
+	async fn conflicted_editor() -> (Editor, PathBuf, tempfile::TempDir) {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("file.txt");
+		write_aged(&path, "one\ntwo\nthree\n", 60);
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		editor.buffer_mut().set_cursor(0);
+		editor.insert_text("ONE ");
+		write_aged(&path, "one\ntwo\nTHREE\n", 0);
+		(editor, path, tmp)
+	}
+
+	#[tokio::test]
+	async fn save_over_changed_file_prompts_without_writing() {
+		let (mut editor, path, _dir) = conflicted_editor().await;
+		assert!(editor.save().await.is_err());
+		assert!(editor.save_conflict_open());
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\nTHREE\n");
+
+		assert!(editor.close_save_conflict().is_some());
+		assert!(!editor.save_conflict_open());
+		assert!(editor.buffer().modified());
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"ONE one\ntwo\nthree\n"
+		);
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\nTHREE\n");
+	}
+
+	#[tokio::test]
+	async fn resolutions_keep_load_or_merge() {
+		let (mut editor, path, _dir) = conflicted_editor().await;
+		assert!(editor.save().await.is_err());
+		editor.resolve_save_conflict(Resolution::Merge).await;
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"ONE one\ntwo\nTHREE\n"
+		);
+		assert!(editor.buffer().modified());
+		editor.save().await.unwrap();
+		assert_eq!(
+			std::fs::read_to_string(&path).unwrap(),
+			"ONE one\ntwo\nTHREE\n"
+		);
+
+		let (mut editor, path, _dir) = conflicted_editor().await;
+		assert!(editor.save().await.is_err());
+		editor.resolve_save_conflict(Resolution::LoadTheirs).await;
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"one\ntwo\nTHREE\n"
+		);
+		assert!(!editor.buffer().modified());
+
+		let (mut editor, path_keep, _dir) = conflicted_editor().await;
+		assert!(editor.save().await.is_err());
+		editor.resolve_save_conflict(Resolution::KeepMine).await;
+		assert_eq!(
+			std::fs::read_to_string(&path_keep).unwrap(),
+			"ONE one\ntwo\nthree\n"
+		);
+		assert!(!editor.buffer().modified());
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\ntwo\nTHREE\n");
+	}
+}
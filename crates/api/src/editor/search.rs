@@ -1,10 +1,162 @@
 use xeno_base::Selection;
-use xeno_core::movement;
+use xeno_base::range::Range;
+use xeno_core::movement::{self, Regex, SearchCase, SearchError};
+use xeno_registry::options::keys as opt_keys;
 use xeno_registry_notifications::keys;
 
 use super::Editor;
+use crate::buffer::BufferId;
+use crate::render::SearchHighlights;
+
+/// Lines searched above and below the viewport when highlighting matches,
+/// so short scrolls reuse the cached matches.
+const SEARCH_HIGHLIGHT_MARGIN: usize = 50;
 
 impl Editor {
+	/// Returns the configured case mode for searches.
+	pub(crate) fn search_case(&self) -> SearchCase {
+		let case: String = self.option(opt_keys::SEARCH_CASE);
+		SearchCase::parse(&case).unwrap_or_default()
+	}
+
+	/// Compiles `pattern` under the current case mode, reusing the cached
+	/// regex when neither the pattern nor the mode changed.
+	pub(crate) fn search_regex(&mut self, pattern: &str) -> Result<Regex, SearchError> {
+		let case = self.search_case();
+		self.workspace.search.regex(pattern, case)
+	}
+
+	/// Records where `found` falls among all matches, for the statusline.
+	///
+	/// Skipped in large-file mode, where counting would scan the whole file.
+	fn update_search_matches(&mut self, regex: &Regex, found: Range) {
+		if self.large_file_mode(self.focused_view()) {
+			self.workspace.search.matches = None;
+			return;
+		}
+		let matches = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			let mut current = 0;
+			let mut total = 0;
+			for m in movement::find_iter(doc.content.slice(..), regex, 0) {
+				total += 1;
+				if m.min() == found.min() {
+					current = total;
+				}
+			}
+			(current, total)
+		};
+		self.workspace.search.matches = Some(matches);
+	}
+
+	/// Makes `found` the current match, counting matches for the statusline
+	/// and turning match highlighting back on.
+	fn land_on_search_match(&mut self, regex: &Regex, found: Range) {
+		self.update_search_matches(regex, found);
+		self.workspace.search.current = Some(found);
+		self.workspace.search.highlight = true;
+	}
+
+	/// Hides match highlighting until the next search.
+	pub(crate) fn clear_search_highlight(&mut self) {
+		self.workspace.search.highlight = false;
+		self.frame.needs_redraw = true;
+	}
+
+	/// Recounts matches after the text changed, keeping the index of the
+	/// match under the primary selection, and drops cached highlights.
+	pub(crate) fn refresh_search_matches(&mut self) {
+		self.workspace.search.invalidate_visible();
+		if self.workspace.search.matches.is_none() {
+			return;
+		}
+		let Some(pattern) = self
+			.buffer()
+			.input
+			.last_search()
+			.map(|(p, _)| p.to_string())
+		else {
+			return;
+		};
+		let Ok(regex) = self.search_regex(&pattern) else {
+			return;
+		};
+		let found = self.buffer().selection.primary();
+		self.update_search_matches(&regex, found);
+		self.workspace.search.current = self
+			.workspace
+			.search
+			.matches
+			.filter(|&(current, _)| current > 0)
+			.map(|_| found);
+	}
+
+	/// Returns the search matches to highlight in `buffer_id`'s viewport of
+	/// `rows` rows.
+	///
+	/// Only lines near the viewport are searched, and the result is cached
+	/// until the viewport leaves them or the text changes, so a common
+	/// pattern in a huge file costs nothing per frame.
+	pub(crate) fn search_highlights_for(
+		&mut self,
+		buffer_id: BufferId,
+		rows: usize,
+	) -> SearchHighlights {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return SearchHighlights::default();
+		};
+		let (pattern, current) = match &self.workspace.search.preview {
+			Some(preview) if preview.buffer == buffer_id => {
+				(preview.pattern.clone(), preview.current)
+			}
+			_ if !self.workspace.search.highlight => return SearchHighlights::default(),
+			_ => {
+				let Some((pattern, _)) = buffer.input.last_search() else {
+					return SearchHighlights::default();
+				};
+				(pattern.to_string(), self.workspace.search.current)
+			}
+		};
+		let total_lines = buffer.doc().content.len_lines();
+		let first = buffer.scroll_line.min(total_lines);
+		let visible = first..(first + rows).min(total_lines);
+		let case = self.search_case();
+
+		if let Some(ranges) =
+			self.workspace
+				.search
+				.visible_matches(buffer_id, &pattern, case, visible.clone())
+		{
+			return SearchHighlights {
+				ranges: ranges.to_vec(),
+				current,
+			};
+		}
+
+		let Ok(regex) = self.search_regex(&pattern) else {
+			return SearchHighlights::default();
+		};
+		let lines = visible.start.saturating_sub(SEARCH_HIGHLIGHT_MARGIN)
+			..(visible.end + SEARCH_HIGHLIGHT_MARGIN).min(total_lines);
+		let ranges: Vec<Range> = {
+			let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+				return SearchHighlights::default();
+			};
+			let doc = buffer.doc();
+			let text = doc.content.slice(..);
+			let start = text.line_to_char(lines.start);
+			let end = text.line_to_char(lines.end);
+			movement::find_iter_between(text, &regex, start, end)
+				.filter(|m| m.from() < m.to())
+				.collect()
+		};
+		self.workspace
+			.search
+			.set_visible_matches(buffer_id, &pattern, case, lines, ranges.clone());
+		SearchHighlights { ranges, current }
+	}
+
 	/// Searches forward for the current pattern.
 	pub(crate) fn do_search_next(&mut self, add_selection: bool, extend: bool) -> bool {
 		let search_info = self
@@ -14,13 +166,21 @@ impl Editor {
 			.map(|(p, r)| (p.to_string(), r));
 		if let Some((pattern, _reverse)) = search_info {
 			let cursor_pos = self.buffer().cursor;
+			let regex = self.search_regex(&pattern);
 			let search_result = {
 				let buffer = self.buffer();
 				let doc = buffer.doc();
-				movement::find_next(doc.content.slice(..), &pattern, cursor_pos + 1)
+				regex.map(|re| {
+					(
+						movement::next_match(doc.content.slice(..), &re, cursor_pos + 1),
+						re,
+					)
+				})
 			};
 			match search_result {
-				Ok(Some(range)) => {
+				Ok((Some(range), re)) => {
+					self.land_on_search_match(&re, range);
+					self.remember_selection();
 					self.buffer_mut().set_cursor(range.head);
 					if add_selection {
 						self.buffer_mut().selection.push(range);
@@ -33,10 +193,12 @@ impl Editor {
 							.set_selection(Selection::single(range.min(), range.max()));
 					}
 				}
-				Ok(None) => {
+				Ok((None, _)) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::pattern_not_found);
 				}
 				Err(e) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::regex_error::call(&e.to_string()));
 				}
 			}
@@ -55,13 +217,21 @@ impl Editor {
 			.map(|(p, r)| (p.to_string(), r));
 		if let Some((pattern, _reverse)) = search_info {
 			let cursor_pos = self.buffer().cursor;
+			let regex = self.search_regex(&pattern);
 			let search_result = {
 				let buffer = self.buffer();
 				let doc = buffer.doc();
-				movement::find_prev(doc.content.slice(..), &pattern, cursor_pos)
+				regex.map(|re| {
+					(
+						movement::prev_match(doc.content.slice(..), &re, cursor_pos),
+						re,
+					)
+				})
 			};
 			match search_result {
-				Ok(Some(range)) => {
+				Ok((Some(range), re)) => {
+					self.land_on_search_match(&re, range);
+					self.remember_selection();
 					self.buffer_mut().set_cursor(range.head);
 					if add_selection {
 						self.buffer_mut().selection.push(range);
@@ -74,10 +244,12 @@ impl Editor {
 							.set_selection(Selection::single(range.min(), range.max()));
 					}
 				}
-				Ok(None) => {
+				Ok((None, _)) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::pattern_not_found);
 				}
 				Err(e) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::regex_error::call(&e.to_string()));
 				}
 			}
@@ -104,20 +276,25 @@ impl Editor {
 				.input
 				.set_last_search(pattern.clone(), false);
 			self.notify(keys::search_info::call(&text));
+			let regex = self.search_regex(&pattern);
 			let search_result = {
 				let buffer = self.buffer();
 				let doc = buffer.doc();
-				movement::find_next(doc.content.slice(..), &pattern, to)
+				regex.map(|re| (movement::next_match(doc.content.slice(..), &re, to), re))
 			};
 			match search_result {
-				Ok(Some(range)) => {
+				Ok((Some(range), re)) => {
+					self.land_on_search_match(&re, range);
+					self.remember_selection();
 					self.buffer_mut()
 						.set_selection(Selection::single(range.min(), range.max()));
 				}
-				Ok(None) => {
+				Ok((None, _)) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::no_more_matches);
 				}
 				Err(e) => {
+					self.workspace.search.matches = None;
 					self.notify(keys::regex_error::call(&e.to_string()));
 				}
 			}
@@ -150,6 +327,7 @@ impl Editor {
 					.map(|r| xeno_base::range::Range::new(from + r.min(), from + r.max()))
 					.collect();
 				let count = new_ranges.len();
+				self.remember_selection();
 				self.buffer_mut()
 					.set_selection(Selection::from_vec(new_ranges, 0));
 				self.notify(keys::matches_count::call(count));
@@ -196,6 +374,7 @@ impl Editor {
 				}
 				if !new_ranges.is_empty() {
 					let count = new_ranges.len();
+					self.remember_selection();
 					self.buffer_mut()
 						.set_selection(Selection::from_vec(new_ranges, 0));
 					self.notify(keys::splits_count::call(count));
@@ -267,3 +446,45 @@ impl Editor {
 		false
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::options::OptionValue;
+
+	use super::*;
+
+	fn editor_with(text: &str, cursor: usize) -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text(text);
+		let buffer = editor.buffer_mut();
+		buffer.set_selection(Selection::point(cursor));
+		buffer.sync_cursor_to_selection();
+		editor
+	}
+
+	fn set_search_case(editor: &mut Editor, case: &str) {
+		editor.config.global_options.set(
+			opt_keys::SEARCH_CASE.untyped(),
+			OptionValue::String(case.to_string()),
+		);
+	}
+
+	#[test]
+	fn case_option_change_applies_to_next_search() {
+		let mut editor = editor_with("Foo foo Foo", 0);
+		editor
+			.buffer_mut()
+			.input
+			.set_last_search("foo".to_string(), false);
+
+		editor.do_search_next(false, false);
+		assert_eq!(editor.buffer().selection.primary().min(), 4);
+		assert_eq!(editor.workspace.search.matches, Some((1, 1)));
+
+		set_search_case(&mut editor, "smart");
+		editor.do_search_next(false, false);
+		assert_eq!(editor.buffer().selection.primary().min(), 8);
+		assert_eq!(editor.workspace.search.matches, Some((3, 3)));
+		assert_eq!(editor.workspace.search.case, SearchCase::Smart);
+	}
+}
@@ -0,0 +1,294 @@
+//! Incremental search through the palette input.
+//!
+//! While the prompt is open, each edit re-runs the pattern from the cursor
+//! the prompt was opened at and scrolls the searched view to the match,
+//! leaving the selection alone. Enter commits the search; cancelling puts
+//! the view back exactly as it was.
+
+use xeno_base::direction::SeqDirection;
+use xeno_base::range::{CharIdx, Range};
+use xeno_core::movement;
+
+use super::Editor;
+use crate::buffer::BufferId;
+use crate::editor::JumpLocation;
+use crate::editor::types::SearchPreview;
+use crate::palette::{PaletteKind, PaletteState, PreviewStatus, SearchPrompt, ViewOrigin};
+use crate::render::horizontal_scroll_offset;
+use crate::window::Window;
+
+impl Editor {
+	/// Opens the palette as a search prompt over the focused buffer.
+	///
+	/// - `reverse`: search backward from the cursor instead of forward
+	pub fn open_search_prompt(&mut self, reverse: bool) -> bool {
+		let target = self.focused_view();
+		let buffer = self.buffer();
+		let origin = ViewOrigin {
+			cursor: buffer.cursor,
+			scroll_line: buffer.scroll_line,
+			scroll_segment: buffer.scroll_segment,
+			left_col: buffer.left_col,
+			viewport_detached: buffer.viewport_detached,
+		};
+		let prompt = SearchPrompt {
+			target,
+			reverse,
+			origin,
+			preview: None,
+			status: PreviewStatus::Empty,
+		};
+		self.open_palette_as(PaletteKind::Search(prompt), if reverse { '?' } else { '/' })
+	}
+
+	/// Returns the input buffer of the open search prompt, if any.
+	pub(crate) fn search_prompt_buffer(&self) -> Option<BufferId> {
+		let state = self.overlays.get::<PaletteState>()?;
+		state.search().and(state.buffer_id())
+	}
+
+	/// Re-runs the preview after the prompt's pattern changed.
+	pub(crate) fn update_search_preview(&mut self) {
+		self.preview_search(None);
+	}
+
+	/// Moves the preview to the next or previous match of the pattern.
+	pub(crate) fn cycle_search_preview(&mut self, direction: SeqDirection) {
+		self.preview_search(Some(direction));
+	}
+
+	/// Previews the prompt's pattern in its target view.
+	///
+	/// Without a `step`, searches from the cursor the prompt was opened at in
+	/// the prompt's direction; with one, moves from the current preview.
+	/// Invalid patterns only update the prompt's indicator, so typing a
+	/// half-finished regex does not raise notifications.
+	fn preview_search(&mut self, step: Option<SeqDirection>) {
+		let Some(input) = self.palette_input() else {
+			return;
+		};
+		let Some(prompt) = self.overlays.get::<PaletteState>().and_then(|p| p.search()) else {
+			return;
+		};
+		let (target, reverse, origin, preview) =
+			(prompt.target, prompt.reverse, prompt.origin, prompt.preview);
+
+		if input.is_empty() {
+			self.restore_view(target, origin);
+			self.set_search_preview(None, PreviewStatus::Empty);
+			return;
+		}
+
+		let Ok(regex) = self.search_regex(&input) else {
+			self.workspace.search.preview = None;
+			self.set_search_preview(preview, PreviewStatus::InvalidRegex);
+			return;
+		};
+
+		let found = {
+			let Some(buffer) = self.buffers.get_buffer(target) else {
+				return;
+			};
+			let doc = buffer.doc();
+			let text = doc.content.slice(..);
+			let backward = match step {
+				Some(direction) => direction == SeqDirection::Prev,
+				None => reverse,
+			};
+			let from = match (step, preview) {
+				(Some(_), Some(current)) => current.min(),
+				_ => origin.cursor,
+			};
+			if backward {
+				movement::prev_match(text, &regex, from)
+			} else {
+				movement::next_match(text, &regex, from + 1)
+			}
+		};
+
+		self.workspace.search.preview = Some(SearchPreview {
+			buffer: target,
+			pattern: input,
+			current: found,
+		});
+		match found {
+			Some(range) => {
+				self.reveal_in_view(target, range.min());
+				self.set_search_preview(found, PreviewStatus::Match);
+			}
+			None => {
+				self.restore_view(target, origin);
+				self.set_search_preview(None, PreviewStatus::NoMatch);
+			}
+		}
+	}
+
+	/// Records the previewed match and shows the status on the prompt.
+	fn set_search_preview(&mut self, preview: Option<Range>, status: PreviewStatus) {
+		let Some(state) = self.overlays.get_or_default::<PaletteState>().search_mut() else {
+			return;
+		};
+		state.preview = preview;
+		state.status = status;
+		let window_id = self
+			.overlays
+			.get::<PaletteState>()
+			.and_then(|p| p.window_id());
+		if let Some(id) = window_id
+			&& let Some(Window::Floating(float)) = self.windows.get_mut(id)
+		{
+			float.style.title = status.indicator().map(String::from);
+		}
+		self.frame.needs_redraw = true;
+	}
+
+	/// Commits a search prompt's pattern and jumps to the first match.
+	///
+	/// The jump pushes a jumplist entry and remembers the previous selection
+	/// for reselect-last. Returns the committed pattern.
+	pub(crate) fn commit_search_prompt(
+		&mut self,
+		prompt: SearchPrompt,
+		pattern: String,
+	) -> Option<String> {
+		self.workspace.search.preview = None;
+		self.restore_view(prompt.target, prompt.origin);
+		if pattern.is_empty() {
+			return None;
+		}
+
+		self.buffer_mut()
+			.input
+			.set_last_search(pattern.clone(), prompt.reverse);
+		if prompt.reverse {
+			self.do_search_prev(false, false);
+		} else {
+			self.do_search_next(false, false);
+		}
+		if self.buffer().cursor != prompt.origin.cursor {
+			self.workspace.jump_list.push(JumpLocation {
+				buffer_id: prompt.target,
+				cursor: prompt.origin.cursor,
+			});
+		}
+		Some(pattern)
+	}
+
+	/// Cancels a search prompt, restoring its target view exactly.
+	pub(crate) fn cancel_search_prompt(&mut self, prompt: SearchPrompt) {
+		self.workspace.search.preview = None;
+		self.restore_view(prompt.target, prompt.origin);
+	}
+
+	/// Puts a buffer's cursor and scroll position back to `origin`.
+	fn restore_view(&mut self, buffer_id: BufferId, origin: ViewOrigin) {
+		let Some(buffer) = self.get_buffer_mut(buffer_id) else {
+			return;
+		};
+		buffer.cursor = origin.cursor;
+		buffer.last_rendered_cursor = origin.cursor;
+		buffer.scroll_line = origin.scroll_line;
+		buffer.scroll_segment = origin.scroll_segment;
+		buffer.left_col = origin.left_col;
+		buffer.viewport_detached = origin.viewport_detached;
+		self.frame.needs_redraw = true;
+	}
+
+	/// Scrolls a view so `pos` is visible without moving its cursor.
+	fn reveal_in_view(&mut self, buffer_id: BufferId, pos: CharIdx) {
+		let tab_width = self.tab_width_for(buffer_id);
+		let Some(buffer) = self.get_buffer_mut(buffer_id) else {
+			return;
+		};
+		let line = buffer.doc().content.char_to_line(pos);
+		let height = buffer.last_viewport_height.max(1);
+		if line < buffer.scroll_line || line >= buffer.scroll_line + height {
+			buffer.scroll_line = line.saturating_sub(height / 2);
+			buffer.scroll_segment = 0;
+		}
+		if !buffer.wrap {
+			let col = buffer.visual_column(pos, tab_width);
+			buffer.left_col = horizontal_scroll_offset(buffer.left_col, col, buffer.text_width, 1);
+		}
+		buffer.last_rendered_cursor = buffer.cursor;
+		buffer.viewport_detached = true;
+		self.frame.needs_redraw = true;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn editor_with(text: &str) -> Editor {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text(text);
+		editor.buffer_mut().set_cursor(0);
+		editor.viewport.width = Some(80);
+		editor.viewport.height = Some(24);
+		editor
+	}
+
+	fn type_pattern(editor: &mut Editor, pattern: &str) {
+		editor.insert_text(pattern);
+		editor.update_search_preview();
+	}
+
+	#[test]
+	fn preview_moves_view_not_selection() {
+		let mut editor = editor_with("foo\nbar\nfoo bar\n");
+		let target = editor.focused_view();
+		assert!(editor.open_search_prompt(false));
+		type_pattern(&mut editor, "bar");
+
+		let prompt = editor
+			.overlays
+			.get::<PaletteState>()
+			.unwrap()
+			.search()
+			.unwrap();
+		assert_eq!(prompt.status, PreviewStatus::Match);
+		assert_eq!(prompt.preview.map(|r| r.min()), Some(4));
+		assert_eq!(editor.get_buffer(target).unwrap().cursor, 0);
+	}
+
+	#[test]
+	fn invalid_regex_sets_indicator_without_notifying() {
+		let mut editor = editor_with("foo\n");
+		assert!(editor.open_search_prompt(false));
+		type_pattern(&mut editor, "(");
+
+		let prompt = editor
+			.overlays
+			.get::<PaletteState>()
+			.unwrap()
+			.search()
+			.unwrap();
+		assert_eq!(prompt.status, PreviewStatus::InvalidRegex);
+		assert_eq!(prompt.status.indicator(), Some("invalid regex"));
+	}
+
+	#[test]
+	fn cancel_restores_view_and_commit_jumps() {
+		let mut editor = editor_with("foo\nbar\nfoo bar\n");
+		let target = editor.focused_view();
+		editor.get_buffer_mut(target).unwrap().scroll_line = 1;
+
+		assert!(editor.open_search_prompt(false));
+		type_pattern(&mut editor, "bar");
+		editor.cycle_search_preview(SeqDirection::Next);
+		editor.close_palette();
+		let buffer = editor.get_buffer(target).unwrap();
+		assert_eq!((buffer.cursor, buffer.scroll_line), (0, 1));
+		assert!(editor.workspace.search.preview.is_none());
+
+		assert!(editor.open_search_prompt(false));
+		type_pattern(&mut editor, "bar");
+		assert_eq!(editor.execute_palette().as_deref(), Some("bar"));
+		assert_eq!(editor.buffer().selection.primary().min(), 4);
+		assert_eq!(
+			editor.workspace.jump_list.jump_backward().map(|j| j.cursor),
+			Some(0)
+		);
+	}
+}
@@ -0,0 +1,254 @@
+//! Semantic token requests.
+//!
+//! While `semantic-highlighting` is on for a buffer shown in the base
+//! layout, the semantic tokens of its document are requested in full when
+//! it is first shown. After edits, once the `buffer:change` hook has been
+//! quiet for [`SEMANTIC_TOKEN_DEBOUNCE`], the server that sent them is
+//! asked only for the changes since its last result, if it can send them.
+//! Until the answer arrives the tokens follow the edits through
+//! [`SemanticTokens::map_through`]. Turning the option off drops them.
+//!
+//! [`SemanticTokens::map_through`]: crate::buffer::SemanticTokens::map_through
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tracing::debug;
+use xeno_lsp::LanguageServerId;
+use xeno_lsp::lsp_types::{SemanticToken, SemanticTokensEdit, SemanticTokensFullDeltaResult};
+use xeno_registry::options::keys;
+
+use super::Editor;
+use super::lsp_requests::{DocumentRequests, LspRequestChanges};
+use crate::buffer::{BufferId, DocumentId, SemanticTokens};
+use crate::lsp::SemanticTokensAnswer;
+
+/// How long edits must pause before semantic tokens are requested again.
+pub const SEMANTIC_TOKEN_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The last tokens a server sent for one document, which its deltas edit.
+struct Received {
+	/// Server that sent them.
+	server: LanguageServerId,
+	/// Result id to ask for the next delta against, if the server gave one.
+	result_id: Option<String>,
+	/// Tokens as the server encoded them.
+	data: Vec<SemanticToken>,
+}
+
+/// Overlay state for semantic token requests.
+#[derive(Default)]
+pub(crate) struct SemanticTokenState {
+	/// Requests of each document with semantic highlighting on.
+	requests: DocumentRequests<SemanticTokensAnswer>,
+	/// Last tokens received for each document.
+	received: HashMap<DocumentId, Received>,
+}
+
+impl Editor {
+	/// Sends the semantic token requests the shown buffers need, takes in
+	/// responses and drops the tokens of buffers that turned them off.
+	pub(crate) fn poll_semantic_tokens(&mut self) {
+		let mut changed = self.receive_semantic_tokens();
+
+		let mut shown: HashMap<DocumentId, BufferId> = HashMap::new();
+		let mut disabled = Vec::new();
+		let views = self
+			.layout
+			.compute_view_areas(&self.base_window().layout, self.doc_area());
+		for (view, _) in views {
+			let Some(buffer) = self.buffers.get_buffer(view) else {
+				continue;
+			};
+			let document = buffer.document_id();
+			if !self.resolve_typed_option(view, keys::SEMANTIC_HIGHLIGHTING)
+				|| self.large_file_mode(view)
+			{
+				disabled.push((view, document));
+			} else {
+				shown.entry(document).or_insert(view);
+			}
+		}
+
+		let state = self.overlays.get_or_default::<SemanticTokenState>();
+		for (view, document) in disabled {
+			if shown.contains_key(&document) {
+				continue;
+			}
+			state.requests.forget(document);
+			state.received.remove(&document);
+			if let Some(buffer) = self.buffers.get_buffer(view) {
+				let mut doc = buffer.doc_mut();
+				if !doc.semantic_tokens.is_empty() {
+					doc.semantic_tokens.clear();
+					changed = true;
+				}
+			}
+		}
+
+		let last_change = self
+			.extensions
+			.get::<LspRequestChanges>()
+			.and_then(LspRequestChanges::last);
+		for (document, view) in shown {
+			let state = self.overlays.get_or_default::<SemanticTokenState>();
+			if state.requests.is_pending(document) {
+				continue;
+			}
+			let Some(buffer) = self.buffers.get_buffer(view) else {
+				continue;
+			};
+			let version = buffer.version();
+			if !state
+				.requests
+				.is_due(document, version, last_change, SEMANTIC_TOKEN_DEBOUNCE)
+			{
+				continue;
+			}
+
+			let previous = state
+				.received
+				.get(&document)
+				.and_then(|received| Some((received.server, received.result_id.clone()?)));
+			let request = self.lsp.semantic_tokens(buffer, previous);
+			if let Err(e) = state.requests.send(document, view, version, (), request) {
+				debug!(error = %e, "semantic tokens request failed");
+			}
+		}
+
+		if changed {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Takes in the responses that have arrived. Returns whether any tokens
+	/// changed.
+	fn receive_semantic_tokens(&mut self) -> bool {
+		let state = self.overlays.get_or_default::<SemanticTokenState>();
+		let mut changed = false;
+		for arrived in state.requests.receive() {
+			let document = arrived.document;
+			let answer = match arrived.response {
+				Ok(Some(answer)) => answer,
+				Ok(None) => continue,
+				Err(e) => {
+					debug!(error = %e, "semantic tokens request failed");
+					continue;
+				}
+			};
+
+			let previous = state
+				.received
+				.remove(&document)
+				.filter(|received| received.server == answer.server);
+			let (result_id, data) = match answer.result {
+				SemanticTokensFullDeltaResult::Tokens(tokens) => (tokens.result_id, tokens.data),
+				SemanticTokensFullDeltaResult::TokensDelta(delta) => {
+					match previous.and_then(|previous| apply_edits(previous.data, delta.edits)) {
+						Some(data) => (delta.result_id, data),
+						None => {
+							// Without the tokens the delta edits, ask again
+							// for all of them.
+							state.requests.forget(document);
+							continue;
+						}
+					}
+				}
+				SemanticTokensFullDeltaResult::PartialTokensDelta { edits } => {
+					match previous.and_then(|previous| apply_edits(previous.data, edits)) {
+						Some(data) => (None, data),
+						None => {
+							state.requests.forget(document);
+							continue;
+						}
+					}
+				}
+			};
+
+			let Some(buffer) = self.buffers.get_buffer(arrived.buffer) else {
+				continue;
+			};
+			let mut doc = buffer.doc_mut();
+			// The server's result stands for later deltas either way, but
+			// columns in a response to an older version may be off; the
+			// edit since then asks again.
+			if doc.version == arrived.version {
+				doc.semantic_tokens =
+					SemanticTokens::from_lsp(&data, &answer.legend, &doc.content, answer.encoding);
+				changed = true;
+			}
+			state.received.insert(
+				document,
+				Received {
+					server: answer.server,
+					result_id,
+					data,
+				},
+			);
+		}
+		changed
+	}
+}
+
+/// Applies the edits of a semantic tokens delta to the tokens it was made
+/// against, or returns `None` if an edit falls outside them.
+///
+/// Edit offsets count the integers of the encoded stream, five per token.
+/// Each edit refers to the tokens before any of them applies.
+fn apply_edits(
+	mut data: Vec<SemanticToken>,
+	mut edits: Vec<SemanticTokensEdit>,
+) -> Option<Vec<SemanticToken>> {
+	edits.sort_by_key(|edit| edit.start);
+	for edit in edits.into_iter().rev() {
+		let start = edit.start as usize / 5;
+		let end = start + edit.delete_count as usize / 5;
+		if end > data.len() {
+			return None;
+		}
+		data.splice(start..end, edit.data.unwrap_or_default());
+	}
+	Some(data)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn token(delta_start: u32) -> SemanticToken {
+		SemanticToken {
+			delta_line: 0,
+			delta_start,
+			length: 1,
+			token_type: 0,
+			token_modifiers_bitset: 0,
+		}
+	}
+
+	#[test]
+	fn delta_edits_apply_against_the_previous_tokens() {
+		let data = vec![token(0), token(1), token(2), token(3)];
+		let edits = vec![
+			SemanticTokensEdit {
+				start: 15,
+				delete_count: 5,
+				data: None,
+			},
+			SemanticTokensEdit {
+				start: 5,
+				delete_count: 5,
+				data: Some(vec![token(7), token(8)]),
+			},
+		];
+		let data = apply_edits(data, edits).unwrap();
+		let starts: Vec<_> = data.iter().map(|t| t.delta_start).collect();
+		assert_eq!(starts, [0, 7, 8, 2]);
+
+		let past_the_end = SemanticTokensEdit {
+			start: 20,
+			delete_count: 5,
+			data: None,
+		};
+		assert!(apply_edits(data, vec![past_the_end]).is_none());
+	}
+}
@@ -0,0 +1,635 @@
+//! Saved editing sessions.
+//!
+//! A session records the open file buffers with their selections and scroll
+//! positions, the split layout, the dock layout of open panels, the theme,
+//! and the working directory. Sessions are JSON files under
+//! `$XDG_STATE_HOME/xeno/sessions`. A named session is `<name>.json`; the
+//! unnamed session of a directory is keyed by a hash of its path, so each
+//! project has its own.
+//!
+//! Files carry a format version. Fields added later default when missing,
+//! and unknown fields are ignored, so old files stay readable. Panels and
+//! dock slots are saved by name, and names this build does not know are
+//! skipped on restore.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+use xeno_base::{Range, Selection};
+use xeno_registry::options::keys as opts;
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+use crate::buffer::{BufferId, Layout, SplitDirection};
+use crate::paths::get_state_dir;
+use crate::ui::UiFocus;
+use crate::ui::dock::{DockSlot, SizeSpec};
+use crate::ui::panel::UiRequest;
+
+/// Current session file format version.
+const SESSION_VERSION: u32 = 1;
+
+/// A saved session.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct Session {
+	/// Format version the file was written with.
+	version: u32,
+	/// Working directory.
+	cwd: Option<PathBuf>,
+	/// Theme name.
+	theme: Option<String>,
+	/// File buffers, one per view of a file.
+	buffers: Vec<SessionBuffer>,
+	/// Split layout, with leaves indexing [`buffers`](Self::buffers).
+	layout: Option<SessionLayout>,
+	/// Index of the focused buffer.
+	focused: Option<usize>,
+	/// IDs of open panels, for builds that predate [`dock`](Self::dock).
+	panels: Vec<String>,
+	/// Open panels with their slots, the slot sizes and the focused panel.
+	dock: Option<SessionDock>,
+}
+
+impl Default for Session {
+	fn default() -> Self {
+		Self {
+			version: SESSION_VERSION,
+			cwd: None,
+			theme: None,
+			buffers: Vec::new(),
+			layout: None,
+			focused: None,
+			panels: Vec::new(),
+			dock: None,
+		}
+	}
+}
+
+/// The dock layout in a session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionDock {
+	/// Open panels, in the order they were opened within each slot.
+	panels: Vec<SessionPanel>,
+	/// Slot sizes as percentages, keyed by slot name.
+	sizes: BTreeMap<String, u16>,
+	/// ID of the focused panel; `None` when the editor had focus.
+	focused: Option<String>,
+}
+
+/// An open panel in a session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionPanel {
+	/// Panel ID.
+	id: String,
+	/// Name of the slot the panel was docked in.
+	slot: String,
+	/// Whether the panel was the one showing in its slot.
+	active: bool,
+	/// Size of the panel as a percentage, if set apart from its slot's.
+	size: Option<u16>,
+}
+
+/// A file buffer in a session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionBuffer {
+	/// Absolute file path.
+	path: PathBuf,
+	/// Selection ranges as `[anchor, head]` character offsets.
+	selections: Vec<[usize; 2]>,
+	/// Index of the primary selection.
+	primary: usize,
+	/// First visible line.
+	scroll_line: usize,
+}
+
+/// A node of the saved split layout.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SessionLayout {
+	/// A view of the buffer at this index.
+	View(usize),
+	/// A split of two layouts.
+	Split {
+		/// `horizontal` (side by side) or `vertical` (stacked).
+		direction: String,
+		/// Relative sizes of the two children. Older sessions stored a
+		/// separator position instead and restore as an even split.
+		#[serde(default)]
+		weights: (u16, u16),
+		/// Left or top child.
+		first: Box<SessionLayout>,
+		/// Right or bottom child.
+		second: Box<SessionLayout>,
+	},
+}
+
+impl SessionLayout {
+	/// Records `layout`, dropping views of buffers without an index.
+	fn capture(layout: &Layout, index: &HashMap<BufferId, usize>) -> Option<Self> {
+		match layout {
+			Layout::Single(id) => index.get(id).copied().map(Self::View),
+			Layout::Split {
+				direction,
+				weights,
+				first,
+				second,
+			} => match (Self::capture(first, index), Self::capture(second, index)) {
+				(Some(first), Some(second)) => Some(Self::Split {
+					direction: match direction {
+						SplitDirection::Horizontal => "horizontal",
+						SplitDirection::Vertical => "vertical",
+					}
+					.to_string(),
+					weights: *weights,
+					first: Box::new(first),
+					second: Box::new(second),
+				}),
+				(first, second) => first.or(second),
+			},
+		}
+	}
+
+	/// Rebuilds a layout, collapsing splits around views that were not
+	/// restored.
+	fn restore(&self, ids: &[Option<BufferId>]) -> Option<Layout> {
+		match self {
+			Self::View(i) => ids.get(*i).copied().flatten().map(Layout::Single),
+			Self::Split {
+				direction,
+				weights,
+				first,
+				second,
+			} => match (first.restore(ids), second.restore(ids)) {
+				(Some(first), Some(second)) => Some(Layout::Split {
+					direction: if direction == "vertical" {
+						SplitDirection::Vertical
+					} else {
+						SplitDirection::Horizontal
+					},
+					weights: *weights,
+					first: Box::new(first),
+					second: Box::new(second),
+				}),
+				(first, second) => first.or(second),
+			},
+		}
+	}
+}
+
+/// Returns the directory holding session files.
+fn sessions_dir() -> anyhow::Result<PathBuf> {
+	get_state_dir()
+		.map(|dir| dir.join("sessions"))
+		.context("no state directory for sessions")
+}
+
+/// Returns the session file for `name`, or the current directory's unnamed
+/// session.
+fn session_path(dir: &Path, name: Option<&str>) -> anyhow::Result<PathBuf> {
+	match name {
+		Some(name) => {
+			if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+				bail!("invalid session name '{name}'");
+			}
+			Ok(dir.join(format!("{name}.json")))
+		}
+		None => {
+			let cwd = std::env::current_dir().context("no current directory")?;
+			let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+			for &byte in cwd.as_os_str().as_encoded_bytes() {
+				hash ^= u64::from(byte);
+				hash = hash.wrapping_mul(0x0100_0000_01b3);
+			}
+			Ok(dir.join(format!("cwd-{hash:016x}.json")))
+		}
+	}
+}
+
+impl Editor {
+	/// Captures the current session.
+	fn capture_session(&self) -> Session {
+		let mut buffers = Vec::new();
+		let mut index = HashMap::new();
+		for id in self.buffer_ids() {
+			let buffer = self.buffers.get_buffer(id).expect("listed buffer exists");
+			let Some(path) = buffer.path() else {
+				continue;
+			};
+			index.insert(id, buffers.len());
+			buffers.push(SessionBuffer {
+				path: path.canonicalize().unwrap_or(path),
+				selections: buffer
+					.selection
+					.ranges()
+					.iter()
+					.map(|r| [r.anchor, r.head])
+					.collect(),
+				primary: buffer.selection.primary_index(),
+				scroll_line: buffer.scroll_line,
+			});
+		}
+
+		let dock = self.capture_dock();
+		let mut panels: Vec<_> = dock.panels.iter().map(|p| p.id.clone()).collect();
+		panels.sort();
+
+		Session {
+			version: SESSION_VERSION,
+			cwd: std::env::current_dir().ok(),
+			theme: Some(self.config.theme.name.to_string()),
+			layout: SessionLayout::capture(&self.base_window().layout, &index),
+			focused: index.get(&self.focused_view()).copied(),
+			buffers,
+			panels,
+			dock: Some(dock),
+		}
+	}
+
+	/// Captures the open panels and dock slot sizes.
+	fn capture_dock(&self) -> SessionDock {
+		let mut dock = SessionDock {
+			focused: self.ui.focused_panel_id().map(String::from),
+			..SessionDock::default()
+		};
+		for slot in DockSlot::ALL {
+			let Some(state) = self.ui.dock.slots.get(&slot) else {
+				continue;
+			};
+			let SizeSpec::Percent(size) = state.size;
+			dock.sizes.insert(slot.name().to_string(), size);
+			let sizes = &self.ui.dock.panel_sizes;
+			dock.panels.extend(state.open.iter().map(|id| SessionPanel {
+				id: id.clone(),
+				slot: slot.name().to_string(),
+				active: state.active.as_ref() == Some(id),
+				size: sizes.get(id).map(|&SizeSpec::Percent(p)| p),
+			}));
+		}
+		dock
+	}
+
+	/// Writes the current session to the session named `name`, or to the
+	/// current directory's unnamed session. Returns the file written.
+	pub fn save_session(&self, name: Option<&str>) -> anyhow::Result<PathBuf> {
+		let dir = sessions_dir()?;
+		let path = session_path(&dir, name)?;
+		std::fs::create_dir_all(&dir)?;
+		let json = serde_json::to_string_pretty(&self.capture_session())?;
+		std::fs::write(&path, json)?;
+		Ok(path)
+	}
+
+	/// Restores the session named `name`, or the current directory's unnamed
+	/// session.
+	///
+	/// Files that no longer exist are skipped and reported. Buffers already
+	/// open stay open behind the restored layout.
+	pub async fn load_session(&mut self, name: Option<&str>) -> anyhow::Result<()> {
+		let path = session_path(&sessions_dir()?, name)?;
+		let json = std::fs::read_to_string(&path)
+			.with_context(|| format!("cannot read session {}", path.display()))?;
+		let session: Session = serde_json::from_str(&json)
+			.with_context(|| format!("invalid session {}", path.display()))?;
+		if session.version > SESSION_VERSION {
+			bail!(
+				"session {} has format version {}; this build reads up to {SESSION_VERSION}",
+				path.display(),
+				session.version
+			);
+		}
+		self.restore_session(session).await;
+		Ok(())
+	}
+
+	/// Applies a loaded session.
+	async fn restore_session(&mut self, session: Session) {
+		if let Some(cwd) = &session.cwd
+			&& let Err(e) = std::env::set_current_dir(cwd)
+		{
+			tracing::warn!(cwd = %cwd.display(), error = %e, "cannot restore session directory");
+		}
+		if let Some(theme) = &session.theme {
+			let _ = self.set_theme(theme);
+		}
+
+		let mut ids = Vec::with_capacity(session.buffers.len());
+		let mut opened: HashMap<&Path, BufferId> = HashMap::new();
+		let mut missing = Vec::new();
+		for entry in &session.buffers {
+			if !entry.path.exists() {
+				missing.push(entry.path.display().to_string());
+				ids.push(None);
+				continue;
+			}
+			let id = match opened.get(entry.path.as_path()) {
+				Some(&first) => self.buffers.clone_buffer_for_split(first),
+				None => match self.buffers.find_by_path(&entry.path) {
+					Some(id) => Some(id),
+					None => match self.open_file(entry.path.clone()).await {
+						Ok(id) => Some(id),
+						Err(e) => {
+							self.notify(keys::file_load_error::call(&format!(
+								"{}: {e}",
+								entry.path.display()
+							)));
+							None
+						}
+					},
+				},
+			};
+			if let Some(id) = id {
+				opened.entry(entry.path.as_path()).or_insert(id);
+				self.restore_session_view(id, entry);
+			}
+			ids.push(id);
+		}
+
+		if let Some(layout) = session.layout.as_ref().and_then(|l| l.restore(&ids)) {
+			let focused = session
+				.focused
+				.and_then(|i| ids.get(i).copied().flatten())
+				.filter(|id| layout.views().contains(id))
+				.unwrap_or_else(|| layout.first_view());
+			self.base_window_mut().layout = layout;
+			self.focus_view(focused);
+		}
+
+		if self.option(opts::PANELS_RESTORE) {
+			self.restore_session_dock(&session);
+		}
+		if !missing.is_empty() {
+			self.notify(keys::session_files_missing::call(&missing.join(", ")));
+		}
+		self.frame.needs_redraw = true;
+	}
+
+	/// Reopens a session's panels in their slots and restores slot sizes and
+	/// panel focus.
+	///
+	/// Sessions from before the dock was saved reopen their panels in the
+	/// panels' default slots. Panels that are no longer registered, such as
+	/// those of a removed plugin, are skipped and reported.
+	fn restore_session_dock(&mut self, session: &Session) {
+		let legacy;
+		let dock = match &session.dock {
+			Some(dock) => dock,
+			None => {
+				legacy = SessionDock {
+					panels: session
+						.panels
+						.iter()
+						.map(|id| SessionPanel {
+							id: id.clone(),
+							..SessionPanel::default()
+						})
+						.collect(),
+					..SessionDock::default()
+				};
+				&legacy
+			}
+		};
+
+		for (name, &size) in &dock.sizes {
+			if let Some(state) =
+				DockSlot::from_name(name).and_then(|s| self.ui.dock.slots.get_mut(&s))
+			{
+				state.size = SizeSpec::Percent(size.clamp(1, 100));
+			}
+		}
+
+		let mut missing = Vec::new();
+		for panel in &dock.panels {
+			if !self.ui.has_panel(&panel.id) {
+				tracing::warn!(panel = %panel.id, "session panel is not registered");
+				missing.push(panel.id.as_str());
+				continue;
+			}
+			match DockSlot::from_name(&panel.slot) {
+				Some(slot) => {
+					self.ui.open_in_slot(&panel.id, slot);
+				}
+				None => self.ui.set_open(&panel.id, true),
+			}
+			if let Some(size) = panel.size {
+				self.ui
+					.dock
+					.panel_sizes
+					.insert(panel.id.clone(), SizeSpec::Percent(size.clamp(1, 100)));
+			}
+		}
+		// Opening a panel shows it, so bring back the ones that were showing.
+		for panel in dock.panels.iter().filter(|p| p.active) {
+			if let Some(slot) = self.ui.dock.slot_of(&panel.id)
+				&& let Some(state) = self.ui.dock.slots.get_mut(&slot)
+			{
+				state.active = Some(panel.id.clone());
+			}
+		}
+
+		if let Some(id) = &dock.focused
+			&& self.ui.dock.is_open(id)
+		{
+			self.ui
+				.apply_requests(vec![UiRequest::Focus(UiFocus::panel(id.clone()))]);
+			self.sync_focus_from_ui();
+		}
+		if !missing.is_empty() {
+			self.notify(keys::session_panels_missing::call(&missing.join(", ")));
+		}
+	}
+
+	/// Restores a buffer's selections and scroll position, clamped to its
+	/// current length.
+	fn restore_session_view(&mut self, id: BufferId, entry: &SessionBuffer) {
+		let Some(buffer) = self.buffers.get_buffer_mut(id) else {
+			return;
+		};
+		let (len, lines) = {
+			let doc = buffer.doc();
+			(doc.content.len_chars(), doc.content.len_lines())
+		};
+		let ranges: Vec<_> = entry
+			.selections
+			.iter()
+			.map(|&[anchor, head]| Range::new(anchor.min(len), head.min(len)))
+			.collect();
+		if !ranges.is_empty() {
+			let primary = entry.primary.min(ranges.len() - 1);
+			buffer.finalize_selection(Selection::from_vec(ranges, primary));
+		}
+		buffer.scroll_line = entry.scroll_line.min(lines.saturating_sub(1));
+	}
+
+	/// Suggests restoring the current directory's unnamed session, if one
+	/// was saved.
+	pub fn offer_session_restore(&mut self) {
+		let has_session = sessions_dir()
+			.and_then(|dir| session_path(&dir, None))
+			.is_ok_and(|path| path.exists());
+		if has_session {
+			self.notify(keys::session_available);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_registry::themes::Theme;
+	use xeno_tui::Frame;
+	use xeno_tui::layout::Rect;
+
+	use super::*;
+	use crate::ui::panel::{CursorRequest, EventResult, Panel, UiEvent};
+
+	struct TestPanel(&'static str);
+
+	impl Panel for TestPanel {
+		fn id(&self) -> &str {
+			self.0
+		}
+
+		fn default_slot(&self) -> DockSlot {
+			DockSlot::Bottom
+		}
+
+		fn handle_event(&mut self, _: UiEvent, _: &mut Editor, _: bool) -> EventResult {
+			EventResult::not_consumed()
+		}
+
+		fn render(
+			&mut self,
+			_: &mut Frame<'_>,
+			_: Rect,
+			_: &mut Editor,
+			_: bool,
+			_: &Theme,
+		) -> Option<CursorRequest> {
+			None
+		}
+	}
+
+	#[test]
+	fn dock_round_trips_slots_sizes_and_focus() {
+		let mut editor = Editor::new_scratch();
+		editor.ui.register_panel(Box::new(TestPanel("files")));
+		editor.ui.register_panel(Box::new(TestPanel("terminal")));
+		editor.ui.open_in_slot("files", DockSlot::Right);
+		editor.ui.set_open("terminal", true);
+		editor.ui.dock.slots.get_mut(&DockSlot::Right).unwrap().size = SizeSpec::Percent(40);
+		editor
+			.ui
+			.dock
+			.panel_sizes
+			.insert("files".into(), SizeSpec::Percent(30));
+		editor
+			.ui
+			.apply_requests(vec![UiRequest::Focus(UiFocus::panel("files"))]);
+		let mut session = editor.capture_session();
+		session.dock.as_mut().unwrap().panels.push(SessionPanel {
+			id: "removed".into(),
+			slot: "sidebar".into(),
+			active: true,
+			size: None,
+		});
+
+		let mut restored = Editor::new_scratch();
+		restored.ui.register_panel(Box::new(TestPanel("files")));
+		restored.restore_session_dock(&session);
+		assert_eq!(restored.ui.dock.slot_of("files"), Some(DockSlot::Right));
+		assert!(!restored.ui.dock.is_open("terminal"));
+		assert_eq!(
+			restored.ui.dock.slots[&DockSlot::Right].size,
+			SizeSpec::Percent(40)
+		);
+		assert_eq!(
+			restored.ui.dock.panel_size("files"),
+			Some(SizeSpec::Percent(30))
+		);
+		assert_eq!(restored.ui.focused_panel_id(), Some("files"));
+	}
+
+	#[test]
+	fn sessions_without_dock_open_panels_in_default_slots() {
+		let session: Session = serde_json::from_str(r#"{"version":1,"panels":["files"]}"#).unwrap();
+		let mut editor = Editor::new_scratch();
+		editor.ui.register_panel(Box::new(TestPanel("files")));
+		editor.restore_session_dock(&session);
+		assert_eq!(editor.ui.dock.slot_of("files"), Some(DockSlot::Bottom));
+		assert_eq!(editor.ui.focused_panel_id(), None);
+	}
+
+	#[test]
+	fn layout_drops_missing_views() {
+		let layout = SessionLayout::Split {
+			direction: "vertical".into(),
+			weights: (12, 17),
+			first: Box::new(SessionLayout::View(0)),
+			second: Box::new(SessionLayout::Split {
+				direction: "horizontal".into(),
+				weights: (40, 39),
+				first: Box::new(SessionLayout::View(1)),
+				second: Box::new(SessionLayout::View(2)),
+			}),
+		};
+		let ids = [Some(BufferId(4)), None, Some(BufferId(6))];
+		let Some(Layout::Split {
+			direction,
+			first,
+			second,
+			..
+		}) = layout.restore(&ids)
+		else {
+			panic!("expected a split");
+		};
+		assert_eq!(direction, SplitDirection::Vertical);
+		assert_eq!(first.views(), [BufferId(4)]);
+		assert_eq!(second.views(), [BufferId(6)]);
+	}
+
+	#[test]
+	fn old_session_files_load_with_defaults() {
+		let session: Session =
+			serde_json::from_str(r#"{"version":1,"buffers":[{"path":"/a"}],"extra":true}"#)
+				.unwrap();
+		assert_eq!(session.buffers[0].path, Path::new("/a"));
+		assert!(session.buffers[0].selections.is_empty());
+		assert!(session.layout.is_none());
+	}
+
+	#[tokio::test]
+	async fn session_round_trips_buffers_and_selections() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let a = dir.join("a.txt");
+		let b = dir.join("b.txt");
+		std::fs::write(&a, "alpha\nbeta\n").unwrap();
+		std::fs::write(&b, "bravo\n").unwrap();
+
+		let mut editor = Editor::new(a.clone()).await.unwrap();
+		editor.open_file(b.clone()).await.unwrap();
+		editor
+			.buffer_mut()
+			.finalize_selection(Selection::single(2, 8));
+		let mut session = editor.capture_session();
+		std::fs::remove_file(&b).unwrap();
+		session.buffers[0].selections.push([40, 40]);
+
+		let mut restored = Editor::new_scratch();
+		restored.restore_session(session).await;
+		let id = restored
+			.buffers
+			.find_by_path(&a.canonicalize().unwrap())
+			.unwrap();
+		assert_eq!(restored.focused_view(), id);
+		let selection = &restored.buffer().selection;
+		assert_eq!(selection.ranges()[0], Range::new(2, 8));
+		assert_eq!(selection.ranges()[1], Range::point(11));
+		assert_eq!(restored.buffer_ids().len(), 2);
+	}
+}
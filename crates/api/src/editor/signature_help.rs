@@ -0,0 +1,461 @@
+//! Signature help popup integration with editor.
+//!
+//! Typing one of the server's trigger characters in insert mode asks for
+//! the signatures of the call around the cursor. While the popup is open,
+//! retrigger characters and cursor movement inside the call ask again so the
+//! highlighted parameter follows the cursor, and leaving the argument list
+//! closes it. Requests go out on the next tick, after the edit that caused
+//! them has been synced to the server. Each one carries a generation number
+//! and responses to anything but the latest request are dropped.
+
+use std::ops::Range;
+
+use tokio::sync::oneshot;
+use tracing::debug;
+use xeno_base::range::CharIdx;
+use xeno_base::{Mode, RopeSlice, SeqDirection};
+use xeno_lsp::lsp_types::{
+	ParameterLabel, SignatureHelp, SignatureHelpContext, SignatureHelpTriggerKind,
+};
+use xeno_lsp::{OffsetEncoding, str_offset_to_char};
+
+use super::Editor;
+use crate::buffer::BufferId;
+use crate::lsp::SignatureHelpTrigger;
+
+/// Furthest back from the cursor the enclosing argument list is looked for.
+const MAX_CALL_SCAN: usize = 4096;
+
+/// Response to a signature help request.
+type Response = xeno_lsp::Result<Option<(SignatureHelp, OffsetEncoding)>>;
+
+/// What asked for signature help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+	/// A trigger or retrigger character was typed.
+	Character(char),
+	/// The cursor moved inside the call.
+	CursorMoved,
+}
+
+/// One signature of the called function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Signature {
+	/// The signature as the server prints it.
+	pub label: String,
+	/// Char range of the active parameter within `label`.
+	pub active_parameter: Option<Range<usize>>,
+}
+
+/// Overlay state for the signature help popup.
+#[derive(Debug, Default)]
+pub(crate) struct SignatureHelpState {
+	/// Number of the latest request.
+	generation: u64,
+	/// Buffer the help is for.
+	buffer: Option<BufferId>,
+	/// Position of the `(` opening the argument list the help is for.
+	/// `None` while the popup is closed.
+	call_start: Option<CharIdx>,
+	/// Cursor position the latest request was made at.
+	cursor: CharIdx,
+	/// Request to send on the next tick.
+	queued: Option<Trigger>,
+	/// Request waiting for its response, with its generation.
+	in_flight: Option<(u64, oneshot::Receiver<Response>)>,
+	/// Latest response, handed back to the server on retriggers.
+	help: Option<SignatureHelp>,
+	/// Signatures shown, one per overload.
+	pub signatures: Vec<Signature>,
+	/// Index of the signature shown.
+	pub active: usize,
+}
+
+impl SignatureHelpState {
+	/// Returns whether the popup is open, including while its first
+	/// response is still on the way.
+	fn is_open(&self) -> bool {
+		self.call_start.is_some()
+	}
+
+	/// Returns the signature to show, if a response has arrived.
+	pub fn active_signature(&self) -> Option<&Signature> {
+		self.signatures.get(self.active)
+	}
+
+	/// Returns the popup title counting overloads, as in `2/4`, or `None`
+	/// if there is only one.
+	pub fn title(&self) -> Option<String> {
+		(self.signatures.len() > 1)
+			.then(|| format!("{}/{}", self.active + 1, self.signatures.len()))
+	}
+
+	/// Shows the next or previous overload, wrapping around.
+	fn cycle(&mut self, direction: SeqDirection) {
+		let len = self.signatures.len();
+		if len == 0 {
+			return;
+		}
+		self.active = match direction {
+			SeqDirection::Next => (self.active + 1) % len,
+			SeqDirection::Prev => (self.active + len - 1) % len,
+		};
+	}
+
+	/// Takes in the response to request `generation`.
+	///
+	/// Returns `false` without changing anything if a later request has
+	/// been made since.
+	fn accept(&mut self, generation: u64, help: SignatureHelp, encoding: OffsetEncoding) -> bool {
+		if generation != self.generation {
+			return false;
+		}
+		self.signatures = signatures(&help, encoding);
+		self.active = help
+			.active_signature
+			.map_or(0, |i| i as usize)
+			.min(self.signatures.len().saturating_sub(1));
+		self.help = Some(help);
+		true
+	}
+
+	/// Closes the popup. Responses still on the way become stale.
+	fn close(&mut self) {
+		*self = Self {
+			generation: self.generation + 1,
+			..Self::default()
+		};
+	}
+
+	/// Returns the context of a request made because of `trigger`.
+	fn context(&self, trigger: Trigger) -> SignatureHelpContext {
+		let (trigger_kind, trigger_character) = match trigger {
+			Trigger::Character(c) => (
+				SignatureHelpTriggerKind::TRIGGER_CHARACTER,
+				Some(c.to_string()),
+			),
+			Trigger::CursorMoved => (SignatureHelpTriggerKind::CONTENT_CHANGE, None),
+		};
+		let active_signature_help = self.help.clone().map(|mut help| {
+			help.active_signature = Some(self.active as u32);
+			help
+		});
+		SignatureHelpContext {
+			trigger_kind,
+			trigger_character,
+			is_retrigger: active_signature_help.is_some(),
+			active_signature_help,
+		}
+	}
+}
+
+impl Editor {
+	/// Returns the signature help popup's state if it has something to show.
+	pub(crate) fn visible_signature_help(&self) -> Option<&SignatureHelpState> {
+		self.overlays
+			.get::<SignatureHelpState>()
+			.filter(|state| state.active_signature().is_some())
+	}
+
+	/// Opens or refreshes signature help after `c` was typed in insert mode.
+	pub(crate) fn signature_help_typed(&mut self, c: char) {
+		let Some(trigger) = self.lsp.signature_help_trigger(self.buffer(), c) else {
+			return;
+		};
+		let open = self
+			.overlays
+			.get::<SignatureHelpState>()
+			.is_some_and(SignatureHelpState::is_open);
+		if trigger == SignatureHelpTrigger::Retrigger && !open {
+			return;
+		}
+		let buffer_id = self.focused_view();
+		let cursor = self.buffer().cursor;
+		let Some(call_start) = argument_list_start(self.buffer().doc().content.slice(..), cursor)
+		else {
+			return;
+		};
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		state.buffer = Some(buffer_id);
+		state.call_start = Some(call_start);
+		state.cursor = cursor;
+		state.queued = Some(Trigger::Character(c));
+	}
+
+	/// Shows the next or previous overload in the signature help popup.
+	///
+	/// Returns `false` if the popup has only one signature to show.
+	pub(crate) fn cycle_signature_help(&mut self, direction: SeqDirection) -> bool {
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		if state.signatures.len() < 2 {
+			return false;
+		}
+		state.cycle(direction);
+		self.frame.needs_redraw = true;
+		true
+	}
+
+	/// Closes the signature help popup.
+	pub(crate) fn close_signature_help(&mut self) {
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		let was_visible = state.active_signature().is_some();
+		state.close();
+		if was_visible {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Follows the cursor with the signature help popup, sends queued
+	/// requests and takes in responses.
+	pub(crate) fn poll_signature_help(&mut self) {
+		let Some(state) = self.overlays.get::<SignatureHelpState>() else {
+			return;
+		};
+		if !state.is_open() {
+			return;
+		}
+		if self.mode() != Mode::Insert || state.buffer != Some(self.focused_view()) {
+			self.close_signature_help();
+			return;
+		}
+
+		let cursor = self.buffer().cursor;
+		let call_start = argument_list_start(self.buffer().doc().content.slice(..), cursor);
+		if call_start.is_none() {
+			self.close_signature_help();
+			return;
+		}
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		if call_start != state.call_start || cursor != state.cursor {
+			state.call_start = call_start;
+			state.cursor = cursor;
+			state.queued.get_or_insert(Trigger::CursorMoved);
+		}
+
+		if let Some(trigger) = state.queued.take() {
+			self.send_signature_help(trigger);
+		}
+		self.receive_signature_help();
+	}
+
+	/// Starts a signature help request in the background.
+	fn send_signature_help(&mut self, trigger: Trigger) {
+		let context = self
+			.overlays
+			.get_or_default::<SignatureHelpState>()
+			.context(trigger);
+		let request = match self.lsp.signature_help(self.buffer(), Some(context)) {
+			Ok(Some(request)) => request,
+			Ok(None) => {
+				self.close_signature_help();
+				return;
+			}
+			Err(e) => {
+				debug!(error = %e, "signature help request failed");
+				self.close_signature_help();
+				return;
+			}
+		};
+
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		state.generation += 1;
+		let (tx, rx) = oneshot::channel();
+		tokio::spawn(async move {
+			let _ = tx.send(request.await);
+		});
+		state.in_flight = Some((state.generation, rx));
+	}
+
+	/// Takes in the response to the request in flight, if it has arrived.
+	fn receive_signature_help(&mut self) {
+		let state = self.overlays.get_or_default::<SignatureHelpState>();
+		let Some((generation, mut rx)) = state.in_flight.take() else {
+			return;
+		};
+		let response = match rx.try_recv() {
+			Ok(response) => response,
+			Err(oneshot::error::TryRecvError::Empty) => {
+				state.in_flight = Some((generation, rx));
+				return;
+			}
+			Err(oneshot::error::TryRecvError::Closed) => return,
+		};
+		match response {
+			Ok(Some((help, encoding))) if !help.signatures.is_empty() => {
+				if state.accept(generation, help, encoding) {
+					self.frame.needs_redraw = true;
+				}
+			}
+			Ok(_) => self.close_signature_help(),
+			Err(e) => {
+				debug!(error = %e, "signature help request failed");
+				self.close_signature_help();
+			}
+		}
+	}
+}
+
+/// Returns the position of the `(` opening the argument list around
+/// `cursor`, or `None` if the cursor is not inside parentheses.
+///
+/// Parentheses in strings and comments are counted like any other.
+pub(crate) fn argument_list_start(text: RopeSlice, cursor: CharIdx) -> Option<CharIdx> {
+	let cursor = cursor.min(text.len_chars());
+	let mut depth = 0usize;
+	for idx in (cursor.saturating_sub(MAX_CALL_SCAN)..cursor).rev() {
+		match text.char(idx) {
+			')' => depth += 1,
+			'(' if depth == 0 => return Some(idx),
+			'(' => depth -= 1,
+			_ => {}
+		}
+	}
+	None
+}
+
+/// Converts the signatures of `help`, whose label offsets count `encoding`
+/// units.
+fn signatures(help: &SignatureHelp, encoding: OffsetEncoding) -> Vec<Signature> {
+	help.signatures
+		.iter()
+		.map(|signature| {
+			let active_parameter = signature
+				.active_parameter
+				.or(help.active_parameter)
+				.and_then(|i| signature.parameters.as_ref()?.get(i as usize))
+				.and_then(|param| parameter_range(&signature.label, &param.label, encoding));
+			Signature {
+				label: signature.label.clone(),
+				active_parameter,
+			}
+		})
+		.collect()
+}
+
+/// Returns the char range `parameter` covers in `label`.
+///
+/// A parameter given by name is looked for after the label's first `(`, so
+/// it does not match the function name.
+fn parameter_range(
+	label: &str,
+	parameter: &ParameterLabel,
+	encoding: OffsetEncoding,
+) -> Option<Range<usize>> {
+	match parameter {
+		ParameterLabel::Simple(name) => {
+			let from = label.find('(').map_or(0, |i| i + 1);
+			let start = from + label[from..].find(name.as_str())?;
+			let start = label[..start].chars().count();
+			Some(start..start + name.chars().count())
+		}
+		ParameterLabel::LabelOffsets([start, end]) => {
+			let start = str_offset_to_char(label, *start, encoding)?;
+			Some(start..str_offset_to_char(label, *end, encoding)?)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Rope;
+	use xeno_lsp::lsp_types::{ParameterInformation, SignatureInformation};
+
+	use super::*;
+
+	fn signature(label: &str, params: &[ParameterLabel]) -> SignatureInformation {
+		SignatureInformation {
+			label: label.to_string(),
+			documentation: None,
+			parameters: Some(
+				params
+					.iter()
+					.map(|label| ParameterInformation {
+						label: label.clone(),
+						documentation: None,
+					})
+					.collect(),
+			),
+			active_parameter: None,
+		}
+	}
+
+	#[test]
+	fn finds_the_enclosing_argument_list() {
+		let text = Rope::from_str("foo(a, bar(b), c) + baz(");
+		let text = text.slice(..);
+		assert_eq!(argument_list_start(text, 5), Some(3));
+		assert_eq!(argument_list_start(text, 12), Some(10));
+		assert_eq!(argument_list_start(text, 15), Some(3));
+		assert_eq!(argument_list_start(text, 18), None);
+		assert_eq!(argument_list_start(text, 24), Some(23));
+	}
+
+	#[test]
+	fn highlights_the_active_parameter() {
+		let label = "fn add(a: u32, é: u32) -> u32";
+		let by_name = ParameterLabel::Simple("a: u32".to_string());
+		assert_eq!(
+			parameter_range(label, &by_name, OffsetEncoding::Utf16),
+			Some(7..13)
+		);
+		let by_offsets = ParameterLabel::LabelOffsets([15, 21]);
+		assert_eq!(
+			parameter_range(label, &by_offsets, OffsetEncoding::Utf16),
+			Some(15..21)
+		);
+		let by_bytes = ParameterLabel::LabelOffsets([15, 22]);
+		assert_eq!(
+			parameter_range(label, &by_bytes, OffsetEncoding::Utf8),
+			Some(15..21)
+		);
+		let inside_char = ParameterLabel::LabelOffsets([16, 22]);
+		assert_eq!(
+			parameter_range(label, &inside_char, OffsetEncoding::Utf8),
+			None
+		);
+	}
+
+	#[test]
+	fn drops_stale_responses_and_cycles_overloads() {
+		let help = SignatureHelp {
+			signatures: vec![
+				signature("f(a)", &[ParameterLabel::Simple("a".into())]),
+				signature(
+					"f(a, b)",
+					&[
+						ParameterLabel::Simple("a".into()),
+						ParameterLabel::Simple("b".into()),
+					],
+				),
+			],
+			active_signature: Some(1),
+			active_parameter: Some(1),
+		};
+		let mut state = SignatureHelpState {
+			generation: 2,
+			..SignatureHelpState::default()
+		};
+		assert!(!state.accept(1, help.clone(), OffsetEncoding::Utf16));
+		assert!(state.signatures.is_empty());
+
+		assert!(state.accept(2, help, OffsetEncoding::Utf16));
+		assert_eq!(state.title().as_deref(), Some("2/2"));
+		assert_eq!(state.signatures[0].active_parameter, None);
+		assert_eq!(state.signatures[1].active_parameter, Some(5..6));
+
+		state.cycle(SeqDirection::Next);
+		assert_eq!(state.title().as_deref(), Some("1/2"));
+		state.cycle(SeqDirection::Prev);
+		assert_eq!(state.active, 1);
+		let context = state.context(Trigger::Character(','));
+		assert!(context.is_retrigger);
+		assert_eq!(
+			context.active_signature_help.unwrap().active_signature,
+			Some(1)
+		);
+
+		state.close();
+		assert_eq!(state.generation, 3);
+		assert!(state.active_signature().is_none());
+	}
+}
@@ -0,0 +1,179 @@
+//! Snippet expansion and sessions.
+//!
+//! Expanding a snippet inserts its text at the cursor, indenting its lines
+//! like the cursor's line, and starts a [`SnippetSession`] on the document
+//! with the first tabstop selected. While it lasts, Tab and Shift-Tab in
+//! insert mode move between tabstops, every occurrence of a tabstop
+//! getting a cursor, and typing replaces a selected placeholder. The
+//! session ends at the final tabstop or on leaving insert mode.
+
+use xeno_base::Selection;
+use xeno_base::direction::SeqDirection;
+use xeno_base::range::CharIdx;
+use xeno_core::{RenderedSnippet, Snippet};
+
+use super::Editor;
+use crate::buffer::{SnippetSession, line_indent};
+
+impl Editor {
+	/// Expands the snippet `source` at the primary cursor, dropping the
+	/// other cursors. `$TM_SELECTED_TEXT` is the text of the primary
+	/// selection, which is left in place.
+	pub fn insert_snippet(&mut self, source: &str) {
+		if !self.guard_readonly() {
+			return;
+		}
+		let at = self.buffer().selection.primary().head;
+		let rendered = self.render_snippet(source, at);
+		self.buffer_mut().set_selection(Selection::point(at));
+		self.insert_text(&rendered.text);
+		self.start_snippet_session(&rendered, at);
+	}
+
+	/// Renders the snippet `source` for insertion at `at`, resolving its
+	/// variables against the focused buffer and indenting its lines like
+	/// the line `at` is on.
+	pub(crate) fn render_snippet(&self, source: &str, at: CharIdx) -> RenderedSnippet {
+		let snippet = Snippet::parse(source);
+		let buffer = self.buffer();
+		let primary = buffer.selection.primary();
+		let doc = buffer.doc();
+		let text = doc.content.slice(..);
+		let line = text.char_to_line(at);
+		let selected = text.slice(primary.from()..primary.to()).to_string();
+		let line_text = text.line(line).to_string();
+		let path = buffer.path();
+		let resolve = |name: &str| match name {
+			"TM_SELECTED_TEXT" => (!primary.is_empty()).then(|| selected.clone()),
+			"TM_CURRENT_LINE" => Some(line_text.trim_end_matches(['\n', '\r']).to_string()),
+			"TM_LINE_INDEX" => Some(line.to_string()),
+			"TM_LINE_NUMBER" => Some((line + 1).to_string()),
+			"TM_FILENAME" => Some(path.as_ref()?.file_name()?.to_string_lossy().into()),
+			"TM_FILENAME_BASE" => Some(path.as_ref()?.file_stem()?.to_string_lossy().into()),
+			"TM_DIRECTORY" => Some(path.as_ref()?.parent()?.to_string_lossy().into()),
+			"TM_FILEPATH" => Some(path.as_ref()?.to_string_lossy().into()),
+			_ => None,
+		};
+		snippet.render(resolve, &line_indent(text, line))
+	}
+
+	/// Starts a session on the tabstops of `rendered`, inserted at `at`,
+	/// selecting the first; without tabstops the cursor goes to the end
+	/// of the snippet.
+	pub(crate) fn start_snippet_session(&mut self, rendered: &RenderedSnippet, at: CharIdx) {
+		let session = SnippetSession::new(&rendered.tabstops, at);
+		let selection = match &session {
+			Some(session) => session.selection(),
+			None => {
+				let end = rendered.tabstops.last().map_or(0, |t| t.ranges[0].start);
+				Selection::point(at + end)
+			}
+		};
+		let buffer = self.buffer_mut();
+		buffer.doc_mut().snippet = session;
+		buffer.set_selection(selection);
+		buffer.sync_cursor_to_selection();
+		self.frame.needs_redraw = true;
+	}
+
+	/// Moves to the next or previous tabstop of the snippet session,
+	/// ending it on reaching the final one.
+	///
+	/// Returns whether there was a session to move in.
+	pub(crate) fn snippet_jump(&mut self, direction: SeqDirection) -> bool {
+		let buffer = self.buffer_mut();
+		let selection = {
+			let mut doc = buffer.doc_mut();
+			let Some(session) = &mut doc.snippet else {
+				return false;
+			};
+			if !session.step(direction) {
+				return true;
+			}
+			let selection = session.selection();
+			if session.at_end() {
+				doc.snippet = None;
+			}
+			selection
+		};
+		buffer.set_selection(selection);
+		buffer.sync_cursor_to_selection();
+		self.frame.needs_redraw = true;
+		true
+	}
+
+	/// Ends the snippet session of the focused buffer's document, if any.
+	pub(crate) fn end_snippet_session(&mut self) {
+		self.buffer().doc_mut().snippet = None;
+	}
+
+	/// Deletes the placeholder of the current tabstop if it is selected, so
+	/// that typed text replaces it.
+	pub(crate) fn replace_snippet_placeholder(&mut self) {
+		let selected = {
+			let buffer = self.buffer();
+			let doc = buffer.doc();
+			doc.snippet
+				.as_ref()
+				.is_some_and(|session| session.selects_placeholder(&buffer.selection))
+		};
+		if selected {
+			self.delete_selection();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_base::Range;
+
+	use super::*;
+
+	fn heads(editor: &Editor) -> Vec<usize> {
+		editor.buffer().selection.iter().map(|r| r.head).collect()
+	}
+
+	#[test]
+	fn snippet_expands_and_visits_tabstops() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("  ");
+		editor.buffer_mut().set_selection(Selection::point(2));
+		editor.insert_snippet("fn ${1:name}($2) {\n\t$0\n}");
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"  fn name() {\n  \t\n  }"
+		);
+		assert_eq!(editor.buffer().selection.primary(), Range::new(5, 9));
+
+		editor.replace_snippet_placeholder();
+		editor.insert_text("id");
+		assert_eq!(
+			editor.buffer().doc().content.to_string(),
+			"  fn id() {\n  \t\n  }"
+		);
+
+		assert!(editor.snippet_jump(SeqDirection::Next));
+		assert_eq!(heads(&editor), [8]);
+		assert!(editor.snippet_jump(SeqDirection::Next));
+		assert_eq!(heads(&editor), [15]);
+		assert!(editor.buffer().doc().snippet.is_none());
+		assert!(!editor.snippet_jump(SeqDirection::Next));
+	}
+
+	#[test]
+	fn mirrored_tabstops_get_a_cursor_each() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_snippet("${1:a} = $1;");
+		let ranges: Vec<_> = editor
+			.buffer()
+			.selection
+			.iter()
+			.map(|r| (r.from(), r.to()))
+			.collect();
+		assert_eq!(ranges, [(0, 1), (4, 5)]);
+
+		editor.replace_snippet_placeholder();
+		editor.insert_text("xy");
+		assert_eq!(editor.buffer().doc().content.to_string(), "xy = xy;");
+	}
+}
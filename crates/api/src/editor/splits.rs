@@ -5,28 +5,36 @@
 use std::path::PathBuf;
 
 use tracing::warn;
+use xeno_base::direction::Axis;
 use xeno_registry::{
 	HookContext, HookEventData, SplitDirection, ViewId, emit_sync_with as emit_hook_sync_with,
 };
 
 use super::Editor;
-use crate::buffer::{BufferId, BufferView};
+use crate::buffer::{self, BufferId, BufferView};
 
 /// Converts a buffer view to a hook-compatible view ID.
 fn hook_view_id(view: BufferView) -> ViewId {
 	ViewId::text(view.0)
 }
 
+/// Returns the layout splits whose dividers run along `axis`.
+fn splits_along(axis: Axis) -> buffer::SplitDirection {
+	match axis {
+		Axis::Horizontal => buffer::SplitDirection::Vertical,
+		Axis::Vertical => buffer::SplitDirection::Horizontal,
+	}
+}
+
 impl Editor {
 	/// Creates a horizontal split with the current view and a new buffer below.
 	///
 	/// Matches Vim's `:split` / Helix's `hsplit` (Ctrl+w s).
 	pub fn split_horizontal(&mut self, new_buffer_id: BufferId) {
 		let current_view = self.focused_view();
-		let doc_area = self.doc_area();
 		let base_layout = &mut self.windows.base_window_mut().layout;
 		let layout = &mut self.layout;
-		layout.split_horizontal(base_layout, current_view, new_buffer_id, doc_area);
+		layout.split_horizontal(base_layout, current_view, new_buffer_id);
 		self.focus_buffer(new_buffer_id);
 		emit_hook_sync_with(
 			&HookContext::new(
@@ -45,10 +53,9 @@ impl Editor {
 	/// Matches Vim's `:vsplit` / Helix's `vsplit` (Ctrl+w v).
 	pub fn split_vertical(&mut self, new_buffer_id: BufferId) {
 		let current_view = self.focused_view();
-		let doc_area = self.doc_area();
 		let base_layout = &mut self.windows.base_window_mut().layout;
 		let layout = &mut self.layout;
-		layout.split_vertical(base_layout, current_view, new_buffer_id, doc_area);
+		layout.split_vertical(base_layout, current_view, new_buffer_id);
 		self.focus_buffer(new_buffer_id);
 		emit_hook_sync_with(
 			&HookContext::new(
@@ -62,6 +69,48 @@ impl Editor {
 		);
 	}
 
+	/// Grows the focused view by `delta` cells, or shrinks it when `delta`
+	/// is negative.
+	///
+	/// `Axis::Horizontal` moves horizontal dividers and so changes the
+	/// height (Vim's `:resize`); `Axis::Vertical` changes the width.
+	/// Returns false if no split along `axis` contains the view.
+	pub fn resize_focused_view(&mut self, axis: Axis, delta: i32) -> bool {
+		let view = self.focused_view();
+		let doc_area = self.doc_area();
+		let base_layout = &mut self.windows.base_window_mut().layout;
+		let resized =
+			self.layout
+				.resize_view(base_layout, view, doc_area, splits_along(axis), delta);
+		self.frame.needs_redraw |= resized;
+		resized
+	}
+
+	/// Sets the height (`Axis::Horizontal`) or width (`Axis::Vertical`) of
+	/// the focused view to `size` cells, as far as minimum sizes allow.
+	///
+	/// Returns false if no split along `axis` contains the view.
+	pub fn set_focused_view_size(&mut self, axis: Axis, size: u16) -> bool {
+		let view = self.focused_view();
+		let doc_area = self.doc_area();
+		let base_layout = &mut self.windows.base_window_mut().layout;
+		let resized =
+			self.layout
+				.set_view_size(base_layout, view, doc_area, splits_along(axis), size);
+		self.frame.needs_redraw |= resized;
+		resized
+	}
+
+	/// Shares space equally between the views in the focused view's layer.
+	///
+	/// Matches Vim's `Ctrl+w =`.
+	pub fn equalize_splits(&mut self) {
+		let view = self.focused_view();
+		let base_layout = &mut self.windows.base_window_mut().layout;
+		self.layout.equalize(base_layout, view);
+		self.frame.needs_redraw = true;
+	}
+
 	/// Requests the editor to quit after the current event loop iteration.
 	pub fn request_quit(&mut self) {
 		self.frame.pending_quit = true;
@@ -0,0 +1,401 @@
+//! Swap files for crash recovery.
+//!
+//! Every `swap-interval`, modified buffers are copied to
+//! `$XDG_STATE_HOME/xeno/swap/<hash>.swp`, named by a hash of the file's
+//! canonical path. The first line of a swap file holds that path, the rest
+//! the buffer text. Saving a buffer or exiting cleanly removes its swap file.
+//!
+//! A swap file newer than its file and differing from it is left over from a
+//! crash. Once the file is opened and the editor has a size, a prompt offers
+//! to recover it into the buffer, delete it, or keep it for `:recover` or
+//! `:discard-swap` later. Until then it is kept untouched.
+//! Recovery only changes the buffer; the file on disk is rewritten by the
+//! next explicit write.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use tracing::warn;
+use xeno_base::transaction::Change;
+use xeno_base::{Selection, Transaction};
+use xeno_registry::commands::CommandError;
+use xeno_registry::options::keys as opts;
+use xeno_registry_notifications::keys;
+
+use super::Editor;
+use crate::buffer::BufferId;
+use crate::paths::get_state_dir;
+use crate::prompt::{ConfirmPopup, ConfirmResult};
+
+/// Swap file bookkeeping for the session.
+#[derive(Debug)]
+pub struct SwapState {
+	/// Directory holding swap files; `None` disables them.
+	pub(crate) dir: Option<PathBuf>,
+	/// When swap files were last written.
+	last_write: Instant,
+	/// Document version last written to each swap file this session.
+	written: HashMap<PathBuf, u64>,
+	/// Swap files from an earlier session awaiting `:recover` or
+	/// `:discard-swap`. These are never overwritten or removed implicitly.
+	pending: HashSet<PathBuf>,
+	/// Buffers and files with a pending swap file not yet prompted about.
+	unprompted: VecDeque<(BufferId, PathBuf)>,
+	/// Swap files recovered into a buffer whose file has not been explicitly
+	/// written since. Autosave leaves such files alone.
+	recovered: HashSet<PathBuf>,
+}
+
+impl Default for SwapState {
+	fn default() -> Self {
+		Self {
+			dir: get_state_dir().map(|dir| dir.join("swap")),
+			last_write: Instant::now(),
+			written: HashMap::new(),
+			pending: HashSet::new(),
+			unprompted: VecDeque::new(),
+			recovered: HashSet::new(),
+		}
+	}
+}
+
+impl SwapState {
+	/// Returns the swap file for `path`.
+	fn swap_path(&self, path: &Path) -> Option<PathBuf> {
+		let key = swap_key(path);
+		let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+		for &byte in key.as_os_str().as_encoded_bytes() {
+			hash ^= u64::from(byte);
+			hash = hash.wrapping_mul(0x0100_0000_01b3);
+		}
+		Some(self.dir.as_ref()?.join(format!("{hash:016x}.swp")))
+	}
+}
+
+/// Returns the canonical form of `path` that swap files are keyed by.
+fn swap_key(path: &Path) -> PathBuf {
+	path.canonicalize()
+		.or_else(|_| std::path::absolute(path))
+		.unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Reads the text of `swap` if it was written for `path`.
+fn read_swap(swap: &Path, path: &Path) -> Option<String> {
+	let contents = std::fs::read_to_string(swap).ok()?;
+	let (header, text) = contents.split_once('\n')?;
+	(Path::new(header) == swap_key(path)).then(|| text.to_string())
+}
+
+/// Writes `text` for `path` to `swap`, replacing it atomically.
+fn write_swap(swap: &Path, path: &Path, text: &str) -> std::io::Result<()> {
+	if let Some(dir) = swap.parent() {
+		std::fs::create_dir_all(dir)?;
+	}
+	let tmp = swap.with_extension("swp.tmp");
+	let mut contents = swap_key(path).into_os_string().into_encoded_bytes();
+	contents.push(b'\n');
+	contents.extend_from_slice(text.as_bytes());
+	std::fs::write(&tmp, contents)?;
+	std::fs::rename(&tmp, swap)
+}
+
+impl Editor {
+	/// Writes swap files for buffers modified since the last write, once
+	/// `swap-interval` has passed.
+	///
+	/// Swap files of buffers that are no longer modified are removed. Large
+	/// files and files still loading are skipped.
+	pub(super) fn write_swap_files(&mut self) {
+		let interval = self
+			.config
+			.global_options
+			.get_int(opts::SWAP_INTERVAL.untyped())
+			.unwrap_or_else(|| (opts::SWAP_INTERVAL.def().default)().as_int().unwrap_or(0));
+		if interval <= 0
+			|| self.swap.dir.is_none()
+			|| self.swap.last_write.elapsed() < Duration::from_millis(interval as u64)
+		{
+			return;
+		}
+		self.swap.last_write = Instant::now();
+
+		for buffer_id in self.buffers.buffer_ids().collect::<Vec<_>>() {
+			if self.loading_progress(buffer_id).is_some() {
+				continue;
+			}
+			let buffer = self
+				.buffers
+				.get_buffer(buffer_id)
+				.expect("listed buffer exists");
+			let Some(path) = buffer.path() else {
+				continue;
+			};
+			let Some(swap) = self.swap.swap_path(&path) else {
+				continue;
+			};
+			if self.swap.pending.contains(&swap) || buffer.doc().large_file {
+				continue;
+			}
+
+			if !buffer.modified() {
+				if self.swap.written.remove(&swap).is_some() {
+					let _ = std::fs::remove_file(&swap);
+				}
+				continue;
+			}
+			let version = buffer.version();
+			if self.swap.written.get(&swap) == Some(&version) {
+				continue;
+			}
+			let text = buffer.doc().content.to_string();
+			match write_swap(&swap, &path, &text) {
+				Ok(()) => {
+					self.swap.written.insert(swap, version);
+				}
+				Err(e) => warn!(path = %swap.display(), error = %e, "failed to write swap file"),
+			}
+		}
+	}
+
+	/// Returns the swap file of `buffer_id`'s file.
+	fn buffer_swap_path(&self, buffer_id: BufferId) -> Option<PathBuf> {
+		let path = self.buffers.get_buffer(buffer_id)?.path()?;
+		self.swap.swap_path(&path)
+	}
+
+	/// Removes the swap file of `buffer_id`'s file after it is saved.
+	///
+	/// A swap file awaiting recovery is kept.
+	pub(super) fn remove_swap_file(&mut self, buffer_id: BufferId) {
+		let Some(swap) = self.buffer_swap_path(buffer_id) else {
+			return;
+		};
+		if !self.swap.pending.contains(&swap) {
+			self.swap.written.remove(&swap);
+			self.swap.recovered.remove(&swap);
+			let _ = std::fs::remove_file(&swap);
+		}
+	}
+
+	/// Returns whether `buffer_id` holds unresolved or recovered swap file
+	/// text, which only an explicit write may save.
+	pub(super) fn swap_needs_explicit_write(&self, buffer_id: BufferId) -> bool {
+		self.buffer_swap_path(buffer_id).is_some_and(|swap| {
+			self.swap.pending.contains(&swap) || self.swap.recovered.contains(&swap)
+		})
+	}
+
+	/// Removes every swap file written this session, on clean exit.
+	pub fn remove_session_swap_files(&mut self) {
+		for (swap, _) in self.swap.written.drain() {
+			let _ = std::fs::remove_file(&swap);
+		}
+	}
+
+	/// Looks for a swap file left over from a crash for a freshly loaded file.
+	///
+	/// A swap file newer than `path` whose text differs from the buffer is
+	/// held for [`prompt_swap_recovery`](Self::prompt_swap_recovery) to ask
+	/// about. One matching the buffer is stale and removed.
+	pub(super) fn check_swap_file(&mut self, buffer_id: BufferId, path: &Path) {
+		let Some(swap) = self.swap.swap_path(path) else {
+			return;
+		};
+		let Ok(swap_time) = std::fs::metadata(&swap).and_then(|m| m.modified()) else {
+			return;
+		};
+		let file_time = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+		if file_time.is_some_and(|time| time >= swap_time) {
+			return;
+		}
+		let Some(text) = read_swap(&swap, path) else {
+			return;
+		};
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		if buffer.doc().content == text.as_str() {
+			let _ = std::fs::remove_file(&swap);
+			return;
+		}
+		self.swap.pending.insert(swap);
+		self.swap
+			.unprompted
+			.push_back((buffer_id, path.to_path_buf()));
+	}
+
+	/// Asks whether to recover the next swap file found on open.
+	///
+	/// Waits for the editor to have a size and for any other confirm popup
+	/// to be answered, so files opened together are asked about in turn.
+	/// Keeping the swap file leaves it for `:recover` or `:discard-swap`.
+	pub(super) fn prompt_swap_recovery(&mut self) {
+		if self.viewport.width.is_none() || self.confirm_open() {
+			return;
+		}
+		while let Some((buffer_id, path)) = self.swap.unprompted.pop_front() {
+			let pending = self
+				.buffer_swap_path(buffer_id)
+				.is_some_and(|swap| self.swap.pending.contains(&swap));
+			if !pending {
+				continue;
+			}
+			let message = format!(
+				"{} has unsaved changes from a crashed session.",
+				path.display()
+			);
+			let popup = ConfirmPopup::new("Swap file found", message, move |editor, result| {
+				let ConfirmResult::Chosen(key @ ('r' | 'd')) = result else {
+					editor.notify(keys::swap_found::call(&path));
+					return;
+				};
+				if !editor.show_buffer(buffer_id) {
+					return;
+				}
+				let resolved = if key == 'r' {
+					editor
+						.recover_swap_file()
+						.map(|path| editor.notify(keys::swap_recovered::call(&path)))
+				} else {
+					editor.discard_swap_file().map(drop)
+				};
+				if let Err(e) = resolved {
+					editor.notify(keys::command_error::call(&e.to_string()));
+				}
+			})
+			.choice('r', "recover")
+			.choice('d', "discard")
+			.choice('k', "keep for later");
+			self.open_confirm(popup);
+			return;
+		}
+	}
+
+	/// Returns the pending swap file for the focused buffer's file.
+	fn pending_swap(&self) -> Result<(PathBuf, PathBuf), CommandError> {
+		let path = self
+			.buffer()
+			.path()
+			.ok_or_else(|| CommandError::Failed("buffer has no file to recover".to_string()))?;
+		match self.swap.swap_path(&path) {
+			Some(swap) if self.swap.pending.contains(&swap) => Ok((path, swap)),
+			_ => Err(CommandError::Failed(format!(
+				"no swap file for {}",
+				path.display()
+			))),
+		}
+	}
+
+	/// Replaces the focused buffer's text with its pending swap file.
+	///
+	/// The replacement is a single undoable edit and leaves the buffer
+	/// modified; the file on disk is untouched until the next write.
+	pub fn recover_swap_file(&mut self) -> Result<PathBuf, CommandError> {
+		let (path, swap) = self.pending_swap()?;
+		let text = read_swap(&swap, &path)
+			.ok_or_else(|| CommandError::Failed(format!("cannot read {}", swap.display())))?;
+
+		let buffer_id = self.focused_view();
+		self.save_edit_undo_state();
+		let tx = {
+			let doc = self.buffer().doc();
+			Transaction::change(
+				doc.content.slice(..),
+				[Change {
+					start: 0,
+					end: doc.content.len_chars(),
+					replacement: Some(text.as_str().into()),
+				}],
+			)
+		};
+		if !self.apply_transaction_with_selection(buffer_id, &tx, Some(Selection::point(0))) {
+			return Err(CommandError::Failed("buffer is read-only".to_string()));
+		}
+		self.swap.pending.remove(&swap);
+		self.swap.recovered.insert(swap);
+		Ok(path)
+	}
+
+	/// Deletes the focused buffer's pending swap file.
+	pub fn discard_swap_file(&mut self) -> Result<PathBuf, CommandError> {
+		let (path, swap) = self.pending_swap()?;
+		std::fs::remove_file(&swap).map_err(|e| CommandError::Io(e.to_string()))?;
+		self.swap.pending.remove(&swap);
+		Ok(path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use termina::event::{KeyCode, KeyEvent, Modifiers};
+	use xeno_registry::options::OptionValue;
+
+	use super::*;
+
+	#[tokio::test]
+	async fn crash_swap_is_recovered_without_touching_the_file() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("notes.txt");
+		std::fs::write(&path, "saved\n").unwrap();
+		std::fs::File::options()
+			.write(true)
+			.open(&path)
+			.unwrap()
+			.set_modified(std::time::SystemTime::now() - Duration::from_secs(60))
+			.unwrap();
+
+		let mut editor = Editor::new_scratch();
+		editor.swap.dir = Some(dir.join("swap"));
+		let swap = editor.swap.swap_path(&path).unwrap();
+		write_swap(&swap, &path, "saved\nunsaved edit\n").unwrap();
+
+		let buffer_id = editor.open_file(path.clone()).await.unwrap();
+		assert!(editor.swap.pending.contains(&swap));
+		editor.prompt_swap_recovery();
+		assert!(!editor.confirm_open());
+
+		editor.handle_window_resize(80, 24);
+		editor.prompt_swap_recovery();
+		assert!(editor.confirm_open());
+		editor.handle_confirm_key(&KeyEvent::new(KeyCode::Char('r'), Modifiers::NONE));
+		assert!(!editor.confirm_open());
+		assert_eq!(editor.focused_view(), buffer_id);
+		assert_eq!(editor.buffer().doc().content, "saved\nunsaved edit\n");
+		assert!(editor.buffer().modified());
+		assert_eq!(std::fs::read_to_string(&path).unwrap(), "saved\n");
+		assert!(swap.exists());
+
+		editor.save_buffer(buffer_id).await.unwrap();
+		assert_eq!(
+			std::fs::read_to_string(&path).unwrap(),
+			"saved\nunsaved edit\n"
+		);
+		assert!(!swap.exists());
+	}
+
+	#[tokio::test]
+	async fn swap_files_follow_modifications() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("a.txt");
+		std::fs::write(&path, "a\n").unwrap();
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		editor.swap.dir = Some(dir.join("swap"));
+		let swap = editor.swap.swap_path(&path).unwrap();
+		editor
+			.config
+			.global_options
+			.set(opts::SWAP_INTERVAL.untyped(), OptionValue::Int(1));
+
+		editor.insert_text("b");
+		std::thread::sleep(Duration::from_millis(2));
+		editor.write_swap_files();
+		assert_eq!(read_swap(&swap, &path).as_deref(), Some("ba\n"));
+
+		editor.remove_session_swap_files();
+		assert!(!swap.exists());
+	}
+}
@@ -0,0 +1,507 @@
+//! Workspace symbol picker.
+//!
+//! `:workspace_symbols` opens a fuzzy picker over the symbols the focused
+//! buffer's language server finds with `workspace/symbol`. The server is
+//! asked again with the filter text once typing has paused for
+//! [`SYMBOL_QUERY_DEBOUNCE`]; until the new results arrive, the previous
+//! ones stay listed and are filtered locally. Batches a server streams
+//! through `$/progress` are listed as they come in.
+//!
+//! Only the latest request is kept. Replacing it or closing the picker
+//! aborts its task, which drops the request and so cancels it on the
+//! server. Enter jumps to the selected symbol, pushing a jump list entry.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use termina::event::{KeyCode, KeyEvent, Modifiers};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use xeno_lsp::OffsetEncoding;
+use xeno_lsp::lsp_types::{OneOf, Position, SymbolKind, WorkspaceSymbolResponse};
+use xeno_registry_notifications::keys;
+use xeno_tui::widgets::picker::{PickerKey, PickerState};
+
+use super::{Editor, JumpLocation, Location};
+use crate::buffer::BufferId;
+use crate::ui::outline::kind_icon;
+
+/// How long typing must pause before the server is asked again.
+pub const SYMBOL_QUERY_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// What the request in flight sends back.
+enum Batch {
+	/// Symbols streamed ahead of the response.
+	Partial(WorkspaceSymbolResponse),
+	/// The response, holding the symbols that were not streamed.
+	Done(xeno_lsp::Result<Option<WorkspaceSymbolResponse>>),
+}
+
+/// A symbol listed in the picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SymbolItem {
+	/// Name of the symbol.
+	pub name: String,
+	/// Name of the symbol containing it, if the server sent one.
+	pub container: Option<String>,
+	/// What the symbol is.
+	pub kind: SymbolKind,
+	/// File the symbol is in.
+	pub path: PathBuf,
+	/// Start of the symbol, or `None` if the server only named its file.
+	pub position: Option<Position>,
+}
+
+impl SymbolItem {
+	/// Returns the text the symbol is listed and matched as: its kind icon,
+	/// name and container.
+	fn label(&self) -> String {
+		match &self.container {
+			Some(container) => format!("{} {}  {container}", kind_icon(self.kind), self.name),
+			None => format!("{} {}", kind_icon(self.kind), self.name),
+		}
+	}
+}
+
+/// What the picker has to say besides its matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SymbolStatus {
+	/// Results for the current query are on their way.
+	Loading,
+	/// The latest response arrived.
+	Ready,
+	/// The language server does not search workspace symbols.
+	Unsupported,
+	/// The latest request failed.
+	Failed(String),
+}
+
+/// A request waiting for its response.
+struct InFlight {
+	/// Task running the request.
+	task: JoinHandle<()>,
+	/// Batches the task passes on.
+	batches: mpsc::UnboundedReceiver<Batch>,
+	/// Whether the symbols of an earlier query are still listed.
+	stale: bool,
+}
+
+impl Drop for InFlight {
+	fn drop(&mut self) {
+		// Dropping the request future cancels the request on the server.
+		self.task.abort();
+	}
+}
+
+/// An open workspace symbol picker.
+pub(crate) struct SymbolPicker {
+	/// Symbols, filter text and selection.
+	pub picker: PickerState<SymbolItem>,
+	/// What to say besides the matches.
+	pub status: SymbolStatus,
+	/// Buffer whose language server is asked.
+	buffer: BufferId,
+	/// Offset encoding of the symbols' positions.
+	encoding: OffsetEncoding,
+	/// Query of the latest request.
+	sent: Option<String>,
+	/// When the filter text last changed.
+	typed: Option<Instant>,
+	/// The latest request, until its response arrives.
+	in_flight: Option<InFlight>,
+}
+
+impl SymbolPicker {
+	/// Returns whether the query changed and typing paused long enough to
+	/// ask the server again.
+	fn needs_request(&self) -> bool {
+		self.sent.as_deref() != Some(self.picker.query())
+			&& self
+				.typed
+				.is_none_or(|typed| typed.elapsed() >= SYMBOL_QUERY_DEBOUNCE)
+	}
+
+	/// Takes in a batch of the request in flight. Returns whether the
+	/// request is done.
+	fn accept(&mut self, batch: Batch) -> bool {
+		let stale = self
+			.in_flight
+			.as_mut()
+			.is_some_and(|f| std::mem::take(&mut f.stale));
+		if stale {
+			self.picker.clear();
+		}
+		match batch {
+			Batch::Partial(symbols) => {
+				self.extend(symbols);
+				false
+			}
+			Batch::Done(Ok(Some(symbols))) => {
+				self.extend(symbols);
+				self.status = SymbolStatus::Ready;
+				true
+			}
+			Batch::Done(Ok(None)) => {
+				self.status = SymbolStatus::Unsupported;
+				true
+			}
+			Batch::Done(Err(e)) => {
+				self.status = SymbolStatus::Failed(e.to_string());
+				true
+			}
+		}
+	}
+
+	/// Lists the symbols of a response or batch.
+	fn extend(&mut self, symbols: WorkspaceSymbolResponse) {
+		for item in symbol_items(symbols) {
+			let label = item.label();
+			self.picker.push(item, label);
+		}
+	}
+}
+
+/// Overlay state for the workspace symbol picker.
+#[derive(Default)]
+pub(crate) struct SymbolPickerState {
+	/// The open picker.
+	pub open: Option<SymbolPicker>,
+}
+
+impl Editor {
+	/// Opens the workspace symbol picker with `query` as its filter text,
+	/// and asks the focused buffer's language server for matching symbols.
+	///
+	/// Returns `false` if no language server handles the buffer.
+	pub fn open_symbol_picker(&mut self, query: impl Into<String>) -> bool {
+		let mut picker = PickerState::new();
+		picker.set_query(query);
+		self.overlays.get_or_default::<SymbolPickerState>().open = Some(SymbolPicker {
+			picker,
+			status: SymbolStatus::Loading,
+			buffer: self.focused_view(),
+			encoding: OffsetEncoding::Utf16,
+			sent: None,
+			typed: None,
+			in_flight: None,
+		});
+		if !self.send_symbol_query() {
+			self.overlays.get_or_default::<SymbolPickerState>().open = None;
+			return false;
+		}
+		self.frame.needs_redraw = true;
+		true
+	}
+
+	/// Returns the open workspace symbol picker.
+	pub(crate) fn symbol_picker(&self) -> Option<&SymbolPicker> {
+		self.overlays
+			.get::<SymbolPickerState>()
+			.and_then(|s| s.open.as_ref())
+	}
+
+	/// Returns the open workspace symbol picker, mutably.
+	fn symbol_picker_mut(&mut self) -> Option<&mut SymbolPicker> {
+		self.overlays
+			.get_or_default::<SymbolPickerState>()
+			.open
+			.as_mut()
+	}
+
+	/// Closes the workspace symbol picker, cancelling its request.
+	pub(crate) fn close_symbol_picker(&mut self) {
+		if self
+			.overlays
+			.get_or_default::<SymbolPickerState>()
+			.open
+			.take()
+			.is_some()
+		{
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Handles a key while the workspace symbol picker is open.
+	///
+	/// The picker is modal: Escape closes it, Enter jumps to the selected
+	/// symbol, and other keys edit the filter text or move the selection.
+	pub(crate) async fn handle_symbol_picker_key(&mut self, key: &KeyEvent) {
+		let ctrl = key.modifiers.contains(Modifiers::CONTROL);
+		let picker_key = match key.code {
+			KeyCode::Escape => {
+				self.close_symbol_picker();
+				return;
+			}
+			KeyCode::Enter => {
+				self.accept_symbol_picker().await;
+				return;
+			}
+			KeyCode::Char('n') if ctrl => PickerKey::Down,
+			KeyCode::Char('p') if ctrl => PickerKey::Up,
+			KeyCode::Char(c) if !ctrl && !key.modifiers.contains(Modifiers::ALT) => {
+				PickerKey::Char(c)
+			}
+			KeyCode::Backspace => PickerKey::Backspace,
+			KeyCode::Up => PickerKey::Up,
+			KeyCode::Down => PickerKey::Down,
+			KeyCode::PageUp => PickerKey::PageUp,
+			KeyCode::PageDown => PickerKey::PageDown,
+			KeyCode::Tab => PickerKey::Tab,
+			KeyCode::BackTab => PickerKey::BackTab,
+			KeyCode::Home => PickerKey::Home,
+			KeyCode::End => PickerKey::End,
+			_ => return,
+		};
+		let Some(picker) = self.symbol_picker_mut() else {
+			return;
+		};
+		let query = picker.picker.query().len();
+		if picker.picker.handle_key(picker_key) {
+			if picker.picker.query().len() != query {
+				picker.typed = Some(Instant::now());
+			}
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Closes the picker and jumps to the selected symbol, opening its file
+	/// if needed.
+	async fn accept_symbol_picker(&mut self) {
+		let Some(picker) = self
+			.overlays
+			.get_or_default::<SymbolPickerState>()
+			.open
+			.take()
+		else {
+			return;
+		};
+		self.frame.needs_redraw = true;
+		let Some(item) = picker.picker.selected().cloned() else {
+			return;
+		};
+		let origin = JumpLocation {
+			buffer_id: self.focused_view(),
+			cursor: self.buffer().cursor,
+		};
+		let jumped = match item.position {
+			Some(position) => self
+				.goto_lsp_position(item.path, position, picker.encoding)
+				.await
+				.map(drop),
+			None => self
+				.goto_location(&Location::new(item.path, 0, 0))
+				.await
+				.map(drop),
+		};
+		match jumped {
+			Ok(()) => {
+				self.workspace.jump_list.push(origin);
+				self.center_cursor_line();
+			}
+			Err(e) => self.notify(keys::file_load_error::call(&e.to_string())),
+		}
+	}
+
+	/// Asks the server again once typing paused, and takes in the batches
+	/// of the request in flight.
+	pub(crate) fn poll_symbol_picker(&mut self) {
+		let Some(picker) = self.symbol_picker() else {
+			return;
+		};
+		if self.buffers.get_buffer(picker.buffer).is_none() {
+			self.close_symbol_picker();
+			return;
+		}
+		if picker.needs_request() {
+			self.send_symbol_query();
+		}
+
+		let Some(picker) = self.symbol_picker_mut() else {
+			return;
+		};
+		let mut changed = false;
+		while let Some(in_flight) = picker.in_flight.as_mut() {
+			let batch = match in_flight.batches.try_recv() {
+				Ok(batch) => batch,
+				Err(mpsc::error::TryRecvError::Empty) => break,
+				Err(mpsc::error::TryRecvError::Disconnected) => {
+					picker.in_flight = None;
+					break;
+				}
+			};
+			changed = true;
+			if picker.accept(batch) {
+				picker.in_flight = None;
+			}
+		}
+		if changed {
+			self.frame.needs_redraw = true;
+		}
+	}
+
+	/// Sends the picker's filter text to the server in the background,
+	/// replacing the request in flight.
+	///
+	/// Returns `false` if no language server handles the picker's buffer.
+	fn send_symbol_query(&mut self) -> bool {
+		let Some(picker) = self.symbol_picker() else {
+			return false;
+		};
+		let query = picker.picker.query().to_string();
+		let Some(buffer) = self.buffers.get_buffer(picker.buffer) else {
+			return false;
+		};
+		let (tx, rx) = mpsc::unbounded_channel();
+		let partial = tx.clone();
+		let request = self
+			.lsp
+			.workspace_symbols(buffer, query.clone(), move |symbols| {
+				let _ = partial.send(Batch::Partial(symbols));
+			});
+
+		let Some(picker) = self.symbol_picker_mut() else {
+			return false;
+		};
+		picker.sent = Some(query);
+		let Some((encoding, request)) = request else {
+			picker.in_flight = None;
+			picker.status = SymbolStatus::Unsupported;
+			return false;
+		};
+		picker.encoding = encoding;
+		picker.status = SymbolStatus::Loading;
+		let task = tokio::spawn(async move {
+			let _ = tx.send(Batch::Done(request.await));
+		});
+		picker.in_flight = Some(InFlight {
+			task,
+			batches: rx,
+			stale: true,
+		});
+		true
+	}
+}
+
+/// Converts a workspace symbol response to picker items, dropping symbols
+/// outside local files.
+fn symbol_items(response: WorkspaceSymbolResponse) -> Vec<SymbolItem> {
+	let container = |c: Option<String>| c.filter(|c| !c.is_empty());
+	match response {
+		WorkspaceSymbolResponse::Flat(symbols) => symbols
+			.into_iter()
+			.filter_map(|s| {
+				Some(SymbolItem {
+					path: xeno_lsp::path_from_uri(&s.location.uri)?,
+					position: Some(s.location.range.start),
+					name: s.name,
+					container: container(s.container_name),
+					kind: s.kind,
+				})
+			})
+			.collect(),
+		WorkspaceSymbolResponse::Nested(symbols) => symbols
+			.into_iter()
+			.filter_map(|s| {
+				let (uri, position) = match s.location {
+					OneOf::Left(location) => (location.uri, Some(location.range.start)),
+					OneOf::Right(location) => (location.uri, None),
+				};
+				Some(SymbolItem {
+					path: xeno_lsp::path_from_uri(&uri)?,
+					position,
+					name: s.name,
+					container: container(s.container_name),
+					kind: s.kind,
+				})
+			})
+			.collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_lsp::lsp_types::{
+		Location as LspLocation, Range, SymbolInformation, Uri, WorkspaceLocation, WorkspaceSymbol,
+	};
+
+	use super::*;
+
+	#[allow(
+		deprecated,
+		reason = "`SymbolInformation` has no constructor that leaves out `deprecated`"
+	)]
+	fn flat(name: &str, container: Option<&str>) -> SymbolInformation {
+		SymbolInformation {
+			name: name.into(),
+			kind: SymbolKind::FUNCTION,
+			tags: None,
+			deprecated: None,
+			location: LspLocation::new(
+				"file:///src/lib.rs".parse::<Uri>().unwrap(),
+				Range::new(Position::new(3, 4), Position::new(3, 9)),
+			),
+			container_name: container.map(Into::into),
+		}
+	}
+
+	#[test]
+	fn items_carry_kind_container_and_position() {
+		let items = symbol_items(WorkspaceSymbolResponse::Flat(vec![
+			flat("parse", Some("config")),
+			flat("main", Some("")),
+		]));
+		assert_eq!(items[0].label(), "ƒ parse  config");
+		assert_eq!(items[1].label(), "ƒ main");
+		assert_eq!(items[0].path, PathBuf::from("/src/lib.rs"));
+		assert_eq!(items[0].position, Some(Position::new(3, 4)));
+
+		let items = symbol_items(WorkspaceSymbolResponse::Nested(vec![WorkspaceSymbol {
+			name: "Config".into(),
+			kind: SymbolKind::STRUCT,
+			tags: None,
+			container_name: None,
+			location: OneOf::Right(WorkspaceLocation {
+				uri: "file:///src/config.rs".parse().unwrap(),
+			}),
+			data: None,
+		}]));
+		assert_eq!(items[0].label(), "◆ Config");
+		assert_eq!(items[0].position, None);
+	}
+
+	#[tokio::test]
+	async fn streamed_batches_replace_stale_results() {
+		let mut picker = SymbolPicker {
+			picker: PickerState::new(),
+			status: SymbolStatus::Ready,
+			buffer: BufferId::SCRATCH,
+			encoding: OffsetEncoding::Utf8,
+			sent: Some("pa".into()),
+			typed: None,
+			in_flight: None,
+		};
+		picker.extend(WorkspaceSymbolResponse::Flat(vec![flat("path", None)]));
+		picker.picker.set_query("par");
+		assert!(picker.needs_request());
+		picker.typed = Some(Instant::now());
+		assert!(!picker.needs_request());
+
+		let (_tx, rx) = mpsc::unbounded_channel();
+		picker.in_flight = Some(InFlight {
+			task: tokio::spawn(std::future::pending()),
+			batches: rx,
+			stale: true,
+		});
+		let batch = WorkspaceSymbolResponse::Flat(vec![flat("parse", None)]);
+		assert!(!picker.accept(Batch::Partial(batch)));
+		let batch = WorkspaceSymbolResponse::Flat(vec![flat("parser", None)]);
+		assert!(!picker.accept(Batch::Partial(batch)));
+		assert!(picker.accept(Batch::Done(Ok(Some(WorkspaceSymbolResponse::Flat(vec![]))))));
+		assert_eq!(picker.status, SymbolStatus::Ready);
+		assert_eq!(picker.picker.item_count(), 2);
+
+		picker.picker.tick(usize::MAX);
+		assert_eq!(picker.picker.match_count(), 2);
+		assert_eq!(picker.picker.selected().unwrap().name, "parse");
+	}
+}
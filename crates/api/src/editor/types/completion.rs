@@ -1,6 +1,10 @@
 //! Completion menu state.
 
+use xeno_base::ScrollDirection;
 use xeno_core::CompletionItem;
+use xeno_tui::layout::{Position, Rect};
+
+use crate::info_popup::PopupSide;
 
 /// State for managing the completion menu.
 #[derive(Clone, Default)]
@@ -15,6 +19,12 @@ pub struct CompletionState {
 	pub replace_start: usize,
 	/// Scroll offset for the completion menu viewport.
 	pub scroll_offset: usize,
+	/// Side of the cursor the menu opened on. Above the cursor, items are
+	/// listed bottom-up so the best match stays next to it.
+	pub side: PopupSide,
+	/// Area the item list was last rendered into, for mouse hit-testing.
+	/// Empty until the menu is first rendered.
+	pub area: Rect,
 }
 
 impl CompletionState {
@@ -40,4 +50,95 @@ impl CompletionState {
 		let end = (self.scroll_offset + Self::MAX_VISIBLE).min(self.items.len());
 		self.scroll_offset..end
 	}
+
+	/// Returns the index of the item drawn at screen cell (`x`, `y`), if
+	/// any.
+	pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+		if !self.area.contains(Position::new(x, y)) {
+			return None;
+		}
+		let row = match self.side {
+			PopupSide::Below => y - self.area.y,
+			PopupSide::Above => self.area.bottom() - 1 - y,
+		};
+		let idx = self.scroll_offset + usize::from(row);
+		self.visible_range().contains(&idx).then_some(idx)
+	}
+
+	/// Scrolls the list by `count` items in the wheel's `direction`.
+	///
+	/// Above the cursor the list runs bottom-up, so scrolling up moves
+	/// further down the list.
+	pub fn scroll(&mut self, direction: ScrollDirection, count: usize) {
+		let forward = match direction {
+			ScrollDirection::Down => self.side == PopupSide::Below,
+			ScrollDirection::Up => self.side == PopupSide::Above,
+			_ => return,
+		};
+		let max = self.items.len().saturating_sub(Self::MAX_VISIBLE);
+		self.scroll_offset = if forward {
+			(self.scroll_offset + count).min(max)
+		} else {
+			self.scroll_offset.saturating_sub(count)
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_core::CompletionKind;
+
+	use super::*;
+
+	fn state(len: usize, side: PopupSide) -> CompletionState {
+		CompletionState {
+			items: (0..len)
+				.map(|i| CompletionItem {
+					label: i.to_string(),
+					insert_text: i.to_string(),
+					detail: None,
+					filter_text: None,
+					kind: CompletionKind::Command,
+					snippet: false,
+					score: 0,
+					match_indices: Vec::new(),
+				})
+				.collect(),
+			active: true,
+			side,
+			area: Rect::new(4, 10, 20, 5),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn item_at_follows_list_direction() {
+		let mut below = state(3, PopupSide::Below);
+		assert_eq!(below.item_at(5, 10), Some(0));
+		assert_eq!(below.item_at(5, 12), Some(2));
+		assert_eq!(below.item_at(5, 13), None);
+		assert_eq!(below.item_at(3, 10), None);
+		below.scroll_offset = 1;
+		assert_eq!(below.item_at(5, 10), Some(1));
+
+		let above = state(3, PopupSide::Above);
+		assert_eq!(above.item_at(5, 14), Some(0));
+		assert_eq!(above.item_at(5, 12), Some(2));
+		assert_eq!(above.item_at(5, 11), None);
+	}
+
+	#[test]
+	fn wheel_scrolls_within_items() {
+		let mut below = state(12, PopupSide::Below);
+		below.scroll(ScrollDirection::Down, 5);
+		assert_eq!(below.scroll_offset, 2);
+		below.scroll(ScrollDirection::Up, 1);
+		assert_eq!(below.scroll_offset, 1);
+
+		let mut above = state(12, PopupSide::Above);
+		above.scroll(ScrollDirection::Up, 1);
+		assert_eq!(above.scroll_offset, 1);
+		above.scroll(ScrollDirection::Down, 3);
+		assert_eq!(above.scroll_offset, 0);
+	}
 }
@@ -1,9 +1,15 @@
 //! Per-frame runtime state.
 
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use xeno_base::range::CharIdx;
 
 use crate::buffer::{BufferId, BufferView};
 
+/// How long the cursor and text must rest before the editor counts as idle.
+pub const IDLE_DELAY: Duration = Duration::from_millis(250);
+
 /// Per-frame runtime state.
 ///
 /// Groups hot fields that are accessed every frame for better cache locality.
@@ -19,6 +25,8 @@ pub struct FrameState {
 	pub dirty_buffers: HashSet<BufferId>,
 	/// Views with sticky focus (resist mouse hover focus changes).
 	pub sticky_views: HashSet<BufferView>,
+	/// Rest tracking for `EditorIdle` hooks.
+	pub idle: IdleTracker,
 }
 
 impl Default for FrameState {
@@ -29,6 +37,66 @@ impl Default for FrameState {
 			last_tick: std::time::SystemTime::now(),
 			dirty_buffers: HashSet::new(),
 			sticky_views: HashSet::new(),
+			idle: IdleTracker::default(),
+		}
+	}
+}
+
+/// Focused buffer, cursor and document version as of the last tick.
+type IdleKey = (BufferId, CharIdx, u64);
+
+/// Tracks how long the focused buffer's cursor and text have rested.
+#[derive(Debug, Default)]
+pub struct IdleTracker {
+	/// State seen last, with when it was first seen.
+	last: Option<(IdleKey, Instant)>,
+	/// Whether the current rest has already been reported.
+	fired: bool,
+}
+
+impl IdleTracker {
+	/// Records the state at `now`. Returns true once per rest, on the first
+	/// call after the state has stayed the same for [`IDLE_DELAY`].
+	pub fn observe(
+		&mut self,
+		buffer: BufferId,
+		cursor: CharIdx,
+		version: u64,
+		now: Instant,
+	) -> bool {
+		let key = (buffer, cursor, version);
+		match self.last {
+			Some((last, since)) if last == key => {
+				if self.fired || now.duration_since(since) < IDLE_DELAY {
+					return false;
+				}
+				self.fired = true;
+				true
+			}
+			_ => {
+				self.last = Some((key, now));
+				self.fired = false;
+				false
+			}
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn idle_fires_once_per_rest() {
+		let buffer = BufferId(1);
+		let start = Instant::now();
+		let mut idle = IdleTracker::default();
+		assert!(!idle.observe(buffer, 3, 1, start));
+		assert!(!idle.observe(buffer, 3, 1, start + IDLE_DELAY / 2));
+		assert!(idle.observe(buffer, 3, 1, start + IDLE_DELAY));
+		assert!(!idle.observe(buffer, 3, 1, start + IDLE_DELAY * 2));
+
+		assert!(!idle.observe(buffer, 4, 1, start + IDLE_DELAY * 2));
+		assert!(idle.observe(buffer, 4, 1, start + IDLE_DELAY * 3));
+	}
+}
@@ -1,16 +1,502 @@
 //! Undo/redo history types.
+//!
+//! History is stored as a tree rather than a pair of stacks, so making an edit
+//! after undoing starts a new branch instead of discarding the redo history.
+//! Nodes are stored in creation order, which doubles as the chronological
+//! order used by `:earlier` and `:later`.
 
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
-use xeno_base::{Rope, Selection};
+use xeno_base::{RopeSlice, Selection, SeqDirection, Transaction};
 
 use crate::buffer::BufferId;
 
-/// Undo/redo history entry storing document state and per-view selections.
+/// Default number of nodes kept in an undo tree before the oldest are pruned.
+pub const MAX_UNDO_NODES: usize = 1000;
+
+/// Per-buffer selections captured alongside a history state.
+pub type HistorySelections = HashMap<BufferId, Selection>;
+
+/// A single state in the undo tree.
+///
+/// Every node except the root represents the document after an edit. The
+/// node stores both directions of that edit relative to its parent, so the
+/// tree can be walked up (undo) and down (redo) without document snapshots.
 #[derive(Clone)]
-pub struct HistoryEntry {
-	/// Document content at this point in history.
-	pub doc: Rope,
-	/// Per-buffer selections at this point in history.
-	pub selections: HashMap<BufferId, Selection>,
+pub struct UndoNode {
+	/// Parent node index, or `None` for the root.
+	pub parent: Option<usize>,
+	/// Child node indices in creation order.
+	pub children: Vec<usize>,
+	/// Child followed by redo, updated whenever a child is created or entered.
+	pub redo_child: Option<usize>,
+	/// Transaction that reverts this state to its parent's state.
+	pub undo: Transaction,
+	/// Transaction that reapplies this state on top of its parent's state.
+	pub redo: Transaction,
+	/// Per-buffer selections restored when this node is undone.
+	pub selections_before: HistorySelections,
+	/// Per-buffer selections restored when this node is redone.
+	pub selections_after: HistorySelections,
+	/// When this state was last edited.
+	pub timestamp: SystemTime,
+}
+
+impl UndoNode {
+	/// Creates a childless node with the given transactions.
+	fn new(parent: Option<usize>, undo: Transaction, redo: Transaction) -> Self {
+		Self {
+			parent,
+			children: Vec::new(),
+			redo_child: None,
+			undo,
+			redo,
+			selections_before: HashMap::new(),
+			selections_after: HashMap::new(),
+			timestamp: SystemTime::now(),
+		}
+	}
+}
+
+/// A step along a path through the undo tree.
+pub struct HistoryStep {
+	/// Transaction to apply to the document.
+	pub transaction: Transaction,
+	/// Selections to restore after applying the transaction.
+	pub selections: HistorySelections,
+}
+
+/// Branching undo history for a document.
+#[derive(Clone)]
+pub struct UndoTree {
+	/// All nodes in creation order. Index 0 is always the root.
+	nodes: Vec<UndoNode>,
+	/// Index of the node matching the current document state.
+	current: usize,
+	/// Number of nodes kept before the oldest are pruned.
+	limit: usize,
+}
+
+impl Default for UndoTree {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl UndoTree {
+	/// Creates an undo tree containing only the root state.
+	pub fn new() -> Self {
+		Self {
+			nodes: vec![UndoNode::new(
+				None,
+				Transaction::default(),
+				Transaction::default(),
+			)],
+			current: 0,
+			limit: MAX_UNDO_NODES,
+		}
+	}
+
+	/// Sets how many nodes are kept, pruning the oldest if already over.
+	pub fn set_limit(&mut self, limit: usize) {
+		self.limit = limit.max(1);
+		self.prune();
+	}
+
+	/// Returns all nodes in chronological order.
+	pub fn nodes(&self) -> &[UndoNode] {
+		&self.nodes
+	}
+
+	/// Returns the index of the current node.
+	pub fn current(&self) -> usize {
+		self.current
+	}
+
+	/// Returns the number of nodes, including the root.
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Returns true if the tree holds only the root state.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.len() == 1
+	}
+
+	/// Returns true if the current state has a parent to undo to.
+	pub fn can_undo(&self) -> bool {
+		self.nodes[self.current].parent.is_some()
+	}
+
+	/// Returns true if the current state has a child to redo to.
+	pub fn can_redo(&self) -> bool {
+		!self.nodes[self.current].children.is_empty()
+	}
+
+	/// Discards all history, keeping only the root state.
+	pub fn clear(&mut self) {
+		*self = Self {
+			limit: self.limit,
+			..Self::new()
+		};
+	}
+
+	/// Adds a new child of the current node and makes it current.
+	///
+	/// The node starts with identity transactions; edits are composed into it
+	/// by [`record`](Self::record).
+	pub fn push(&mut self, doc: RopeSlice, selections: HistorySelections) {
+		let identity = Transaction::change(doc, Vec::new());
+		let mut node = UndoNode::new(Some(self.current), identity.clone(), identity);
+		node.selections_before = selections;
+		self.attach(node);
+	}
+
+	/// Records an applied transaction and its inverse in the current node.
+	///
+	/// If the current node is the root or already has children, the edit
+	/// starts a new branch instead of rewriting existing history.
+	pub fn record(&mut self, tx: &Transaction, inverse: Transaction) {
+		let node = &mut self.nodes[self.current];
+		if node.parent.is_none() || !node.children.is_empty() {
+			let node = UndoNode::new(Some(self.current), inverse, tx.clone());
+			self.attach(node);
+			return;
+		}
+
+		node.undo = inverse.compose(std::mem::take(&mut node.undo));
+		node.redo = std::mem::take(&mut node.redo).compose(tx.clone());
+		node.timestamp = SystemTime::now();
+	}
+
+	/// Moves to the parent state, returning the step that reverts the current node.
+	pub fn undo(&mut self, current_selections: HistorySelections) -> Option<HistoryStep> {
+		let index = self.current;
+		let parent = self.nodes[index].parent?;
+		let node = &mut self.nodes[index];
+		node.selections_after = current_selections;
+		let step = HistoryStep {
+			transaction: node.undo.clone(),
+			selections: node.selections_before.clone(),
+		};
+		self.nodes[parent].redo_child = Some(index);
+		self.current = parent;
+		Some(step)
+	}
+
+	/// Moves to the most recent child state, returning the step that reapplies it.
+	pub fn redo(&mut self, current_selections: HistorySelections) -> Option<HistoryStep> {
+		let node = &self.nodes[self.current];
+		let child = node.redo_child.or_else(|| node.children.last().copied())?;
+		Some(self.enter_child(child, current_selections))
+	}
+
+	/// Returns the path of steps from the current node to `target`.
+	///
+	/// Walks up to the common ancestor and then down to the target, updating
+	/// the current node and the redo preference of every node entered.
+	pub fn goto(
+		&mut self,
+		target: usize,
+		mut current_selections: HistorySelections,
+	) -> Vec<HistoryStep> {
+		let target = target.min(self.nodes.len() - 1);
+		let target_path = self.ancestors(target);
+		let mut steps = Vec::new();
+
+		while !target_path.contains(&self.current) {
+			let Some(step) = self.undo(std::mem::take(&mut current_selections)) else {
+				break;
+			};
+			current_selections = step.selections.clone();
+			steps.push(step);
+		}
+
+		let start = target_path
+			.iter()
+			.position(|&index| index == self.current)
+			.unwrap_or(0);
+		for &child in target_path[..start].iter().rev() {
+			let step = self.enter_child(child, current_selections);
+			current_selections = step.selections.clone();
+			steps.push(step);
+		}
+
+		steps
+	}
+
+	/// Returns the node `steps` states before or after the current one in
+	/// chronological order, clamped to the ends of the history.
+	pub fn target_by_steps(&self, direction: SeqDirection, steps: usize) -> usize {
+		match direction {
+			SeqDirection::Prev => self.current.saturating_sub(steps),
+			SeqDirection::Next => (self.current + steps).min(self.nodes.len() - 1),
+		}
+	}
+
+	/// Returns the latest node that is at least `span` older (or at most `span`
+	/// newer) than the current state.
+	pub fn target_by_time(&self, direction: SeqDirection, span: Duration) -> usize {
+		let now = self.nodes[self.current].timestamp;
+		match direction {
+			SeqDirection::Prev => {
+				let limit = now.checked_sub(span).unwrap_or(SystemTime::UNIX_EPOCH);
+				(0..self.current)
+					.rev()
+					.find(|&index| self.nodes[index].timestamp <= limit)
+					.unwrap_or(0)
+			}
+			SeqDirection::Next => {
+				let limit = now.checked_add(span).unwrap_or(now);
+				(self.current..self.nodes.len())
+					.rev()
+					.find(|&index| self.nodes[index].timestamp <= limit)
+					.unwrap_or(self.current)
+			}
+		}
+	}
+
+	/// Moves into `child` of the current node and returns the step that applies it.
+	fn enter_child(&mut self, child: usize, current_selections: HistorySelections) -> HistoryStep {
+		self.nodes[self.current].redo_child = Some(child);
+		self.current = child;
+		let node = &mut self.nodes[child];
+		node.selections_before = current_selections;
+		HistoryStep {
+			transaction: node.redo.clone(),
+			selections: node.selections_after.clone(),
+		}
+	}
+
+	/// Returns `index` followed by each of its ancestors up to the root.
+	fn ancestors(&self, index: usize) -> Vec<usize> {
+		std::iter::successors(Some(index), |&i| self.nodes[i].parent).collect()
+	}
+
+	/// Appends a node as the newest child of the current node and enters it.
+	fn attach(&mut self, node: UndoNode) {
+		let index = self.nodes.len();
+		let parent = &mut self.nodes[self.current];
+		if parent.selections_after.is_empty() {
+			parent.selections_after = node.selections_before.clone();
+		}
+		parent.children.push(index);
+		parent.redo_child = Some(index);
+		self.nodes.push(node);
+		self.current = index;
+		self.prune();
+	}
+
+	/// Drops the oldest history until the tree fits within its limit.
+	///
+	/// A root with a single child is replaced by that child, unless the root
+	/// is the current state. Otherwise the oldest branch of the root not
+	/// containing the current node is removed, so the current node is never
+	/// pruned.
+	fn prune(&mut self) {
+		while self.nodes.len() > self.limit {
+			let current_path = self.ancestors(self.current);
+			let mut keep = vec![true; self.nodes.len()];
+			let children = &self.nodes[0].children;
+			if children.len() == 1 && self.current != 0 {
+				keep[0] = false;
+			} else if let Some(&branch) = children.iter().find(|&&c| !current_path.contains(&c)) {
+				let mut stack = vec![branch];
+				while let Some(index) = stack.pop() {
+					keep[index] = false;
+					stack.extend(&self.nodes[index].children);
+				}
+			} else {
+				return;
+			}
+			self.retain(&keep);
+		}
+	}
+
+	/// Removes nodes not marked in `keep`, remapping all indices.
+	fn retain(&mut self, keep: &[bool]) {
+		let mut remap = vec![None; self.nodes.len()];
+		let mut next = 0;
+		for (index, &kept) in keep.iter().enumerate() {
+			if kept {
+				remap[index] = Some(next);
+				next += 1;
+			}
+		}
+
+		let nodes = std::mem::take(&mut self.nodes);
+		self.nodes = nodes
+			.into_iter()
+			.zip(keep)
+			.filter(|(_, kept)| **kept)
+			.map(|(mut node, _)| {
+				node.parent = node.parent.and_then(|p| remap[p]);
+				node.children.retain_mut(|c| match remap[*c] {
+					Some(new) => {
+						*c = new;
+						true
+					}
+					None => false,
+				});
+				node.redo_child = node.redo_child.and_then(|c| remap[c]);
+				node
+			})
+			.collect();
+		self.current = remap[self.current].expect("the current node is never pruned");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use xeno_base::{Rope, Selection, SeqDirection, Transaction};
+
+	use super::UndoTree;
+
+	/// Saves a new history state, then applies and records an insert at `pos`.
+	fn edit(tree: &mut UndoTree, doc: &mut Rope, pos: usize, text: &str) {
+		tree.push(doc.slice(..), HashMap::new());
+		let tx = Transaction::insert(doc.slice(..), &Selection::point(pos), text.into());
+		let inverse = tx.invert(doc);
+		tx.apply(doc);
+		tree.record(&tx, inverse);
+	}
+
+	fn walk(tree: &mut UndoTree, doc: &mut Rope, target: usize) {
+		for step in tree.goto(target, HashMap::new()) {
+			step.transaction.apply(doc);
+		}
+	}
+
+	#[test]
+	fn edit_after_undo_creates_branch() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("x");
+		edit(&mut tree, &mut doc, 1, "a");
+		edit(&mut tree, &mut doc, 2, "b");
+
+		let step = tree.undo(HashMap::new()).unwrap();
+		step.transaction.apply(&mut doc);
+		assert_eq!(doc.to_string(), "xa");
+
+		edit(&mut tree, &mut doc, 2, "c");
+		assert_eq!(doc.to_string(), "xac");
+		assert_eq!(tree.len(), 4);
+		assert_eq!(tree.nodes()[1].children, vec![2, 3]);
+
+		walk(&mut tree, &mut doc, 2);
+		assert_eq!(doc.to_string(), "xab");
+		walk(&mut tree, &mut doc, 0);
+		assert_eq!(doc.to_string(), "x");
+		walk(&mut tree, &mut doc, 3);
+		assert_eq!(doc.to_string(), "xac");
+	}
+
+	#[test]
+	fn redo_follows_most_recent_child() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("");
+		edit(&mut tree, &mut doc, 0, "a");
+		tree.undo(HashMap::new())
+			.unwrap()
+			.transaction
+			.apply(&mut doc);
+		edit(&mut tree, &mut doc, 0, "b");
+		tree.undo(HashMap::new())
+			.unwrap()
+			.transaction
+			.apply(&mut doc);
+
+		tree.redo(HashMap::new())
+			.unwrap()
+			.transaction
+			.apply(&mut doc);
+		assert_eq!(doc.to_string(), "b");
+	}
+
+	#[test]
+	fn chronological_targets_cross_branches() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("");
+		edit(&mut tree, &mut doc, 0, "a");
+		edit(&mut tree, &mut doc, 1, "b");
+		tree.undo(HashMap::new())
+			.unwrap()
+			.transaction
+			.apply(&mut doc);
+		edit(&mut tree, &mut doc, 1, "c");
+
+		assert_eq!(tree.target_by_steps(SeqDirection::Prev, 1), 2);
+		walk(&mut tree, &mut doc, 2);
+		assert_eq!(doc.to_string(), "ab");
+		assert_eq!(tree.target_by_steps(SeqDirection::Next, 5), 3);
+		assert_eq!(tree.target_by_steps(SeqDirection::Prev, 5), 0);
+	}
+
+	#[test]
+	fn prune_keeps_current_branch() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("");
+		for _ in 0..super::MAX_UNDO_NODES + 10 {
+			edit(&mut tree, &mut doc, 0, "a");
+		}
+		assert_eq!(tree.len(), super::MAX_UNDO_NODES);
+		assert_eq!(tree.current(), super::MAX_UNDO_NODES - 1);
+		assert!(tree.nodes()[0].parent.is_none());
+		while let Some(step) = tree.undo(HashMap::new()) {
+			step.transaction.apply(&mut doc);
+		}
+		assert_eq!(doc.len_chars(), 11);
+	}
+
+	#[test]
+	fn lowering_limit_prunes_oldest() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("");
+		for _ in 0..20 {
+			edit(&mut tree, &mut doc, 0, "a");
+		}
+		tree.set_limit(5);
+		assert_eq!(tree.len(), 5);
+		edit(&mut tree, &mut doc, 0, "a");
+		assert_eq!(tree.len(), 5);
+		tree.clear();
+		for _ in 0..10 {
+			edit(&mut tree, &mut doc, 0, "a");
+		}
+		assert_eq!(tree.len(), 5);
+	}
+
+	#[test]
+	fn lowering_limit_at_the_root_keeps_its_state() {
+		let mut tree = UndoTree::new();
+		let mut doc = Rope::from("");
+		for _ in 0..20 {
+			let end = doc.len_chars();
+			edit(&mut tree, &mut doc, end, "a");
+		}
+		walk(&mut tree, &mut doc, 0);
+		assert_eq!(doc.to_string(), "");
+
+		// As large-file mode does after an undo back to the start.
+		tree.set_limit(5);
+		assert!(tree.len() <= 5);
+		assert_eq!(tree.current(), 0);
+		while let Some(step) = tree.redo(HashMap::new()) {
+			step.transaction.apply(&mut doc);
+		}
+		while let Some(step) = tree.undo(HashMap::new()) {
+			step.transaction.apply(&mut doc);
+		}
+		assert_eq!(doc.to_string(), "");
+
+		edit(&mut tree, &mut doc, 0, "b");
+		tree.undo(HashMap::new())
+			.unwrap()
+			.transaction
+			.apply(&mut doc);
+		assert_eq!(doc.to_string(), "");
+	}
 }
@@ -3,7 +3,7 @@
 //! Grouped structs for editor state management:
 //! - [`FrameState`] - Per-frame runtime state (hot fields)
 //! - [`Viewport`] - Terminal dimensions
-//! - [`Workspace`] - Session state (registers, jumps, macros)
+//! - [`Workspace`] - Session state (registers, jumps, macros, search)
 //! - [`Config`] - Editor configuration (theme, languages, options)
 
 mod completion;
@@ -15,7 +15,10 @@ mod workspace;
 
 pub use completion::CompletionState;
 pub use config::Config;
-pub use frame::FrameState;
-pub use history::HistoryEntry;
+pub use frame::{FrameState, IDLE_DELAY, IdleTracker};
+pub use history::{HistorySelections, HistoryStep, MAX_UNDO_NODES, UndoNode, UndoTree};
 pub use viewport::Viewport;
-pub use workspace::{JumpList, JumpLocation, MacroState, Registers, Workspace};
+pub use workspace::{
+	JumpList, JumpLocation, LocationItem, LocationKind, LocationList, MacroState, Registers,
+	SearchPreview, SearchState, Workspace,
+};
@@ -1,9 +1,12 @@
 //! Editing session state.
 
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use termina::event::KeyEvent;
+use xeno_base::Range;
 use xeno_base::range::CharIdx;
+use xeno_core::movement::{self, Regex, SearchCase, SearchError};
 
 use crate::buffer::BufferId;
 use crate::editor::CommandQueue;
@@ -15,6 +18,19 @@ pub struct Registers {
 	pub yank: String,
 }
 
+impl Registers {
+	/// Name of the default register, which holds the last yank.
+	pub const DEFAULT: char = '"';
+
+	/// Returns the non-empty contents of the named register.
+	pub fn get(&self, name: char) -> Option<&str> {
+		match name {
+			Self::DEFAULT if !self.yank.is_empty() => Some(&self.yank),
+			_ => None,
+		}
+	}
+}
+
 /// A saved position in the jump list.
 #[derive(Clone, Debug)]
 pub struct JumpLocation {
@@ -71,6 +87,169 @@ impl JumpList {
 	}
 }
 
+/// What a location list entry points at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LocationKind {
+	/// A text search match.
+	#[default]
+	Match,
+	/// A reference to a symbol.
+	Reference,
+	/// A symbol definition.
+	Symbol,
+	/// An error diagnostic.
+	Error,
+	/// A warning diagnostic.
+	Warning,
+	/// An informational diagnostic.
+	Info,
+	/// A hint diagnostic.
+	Hint,
+}
+
+impl LocationKind {
+	/// Returns the short label shown in the location panel.
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::Match => "match",
+			Self::Reference => "ref",
+			Self::Symbol => "symbol",
+			Self::Error => "error",
+			Self::Warning => "warning",
+			Self::Info => "info",
+			Self::Hint => "hint",
+		}
+	}
+}
+
+/// An entry of the location list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationItem {
+	/// File the location is in.
+	pub path: PathBuf,
+	/// Line number (0-indexed).
+	pub line: usize,
+	/// Column within the line (0-indexed, in characters).
+	pub column: usize,
+	/// End of the located range as a line and column, exclusive. Equal to
+	/// the start for a point.
+	pub end: (usize, usize),
+	/// Preview shown for the location, such as the text of the line or a
+	/// diagnostic message.
+	pub text: String,
+	/// What the location points at.
+	pub kind: LocationKind,
+}
+
+impl LocationItem {
+	/// Creates a [`LocationKind::Match`] at a point.
+	pub fn new(
+		path: impl Into<PathBuf>,
+		line: usize,
+		column: usize,
+		text: impl Into<String>,
+	) -> Self {
+		Self {
+			path: path.into(),
+			line,
+			column,
+			end: (line, column),
+			text: text.into(),
+			kind: LocationKind::Match,
+		}
+	}
+
+	/// Sets the exclusive end of the located range.
+	pub fn with_end(mut self, line: usize, column: usize) -> Self {
+		self.end = (line, column);
+		self
+	}
+
+	/// Sets what the location points at.
+	pub fn with_kind(mut self, kind: LocationKind) -> Self {
+		self.kind = kind;
+		self
+	}
+
+	/// Returns the columns of the located range on its first line, for
+	/// highlighting the preview. Ranges spanning lines run to the end of
+	/// the line.
+	pub fn columns(&self) -> std::ops::Range<usize> {
+		let end = if self.end.0 == self.line {
+			self.end.1.max(self.column)
+		} else {
+			usize::MAX
+		};
+		self.column..end
+	}
+}
+
+/// Locations collected by a search or query, such as project search
+/// results, references or diagnostics, kept for stepping through after the
+/// source is closed.
+#[derive(Debug, Default)]
+pub struct LocationList {
+	/// Describes where the locations came from.
+	title: String,
+	/// Locations in the order they were collected.
+	items: Vec<LocationItem>,
+	/// Index of the location last jumped to.
+	current: Option<usize>,
+	/// Incremented every time the list is replaced.
+	version: u64,
+}
+
+impl LocationList {
+	/// Replaces the list with `items` collected by `title`.
+	pub fn set(&mut self, title: impl Into<String>, items: Vec<LocationItem>) {
+		self.title = title.into();
+		self.items = items;
+		self.current = None;
+		self.version += 1;
+	}
+
+	/// Returns what the locations came from.
+	pub fn title(&self) -> &str {
+		&self.title
+	}
+
+	/// Returns the locations.
+	pub fn items(&self) -> &[LocationItem] {
+		&self.items
+	}
+
+	/// Returns the index of the location last jumped to.
+	pub fn current(&self) -> Option<usize> {
+		self.current
+	}
+
+	/// Marks the location at `index` as the one last jumped to.
+	pub fn select(&mut self, index: usize) {
+		if index < self.items.len() {
+			self.current = Some(index);
+		}
+	}
+
+	/// Returns a number that changes whenever the list is replaced.
+	pub fn version(&self) -> u64 {
+		self.version
+	}
+
+	/// Returns the index `delta` entries away from the current location,
+	/// or `None` past either end.
+	///
+	/// With no current location, stepping forward starts at the first
+	/// entry and stepping back at the last.
+	pub fn step(&self, delta: isize) -> Option<usize> {
+		let index = match self.current {
+			Some(current) => current.checked_add_signed(delta)?,
+			None if delta > 0 => (delta - 1) as usize,
+			None => self.items.len().checked_sub(delta.unsigned_abs())?,
+		};
+		(index < self.items.len()).then_some(index)
+	}
+}
+
 /// State for macro recording and playback.
 #[derive(Default)]
 pub struct MacroState {
@@ -129,10 +308,128 @@ impl MacroState {
 	}
 }
 
+/// Search matches found in one buffer's visible region.
+struct VisibleMatches {
+	/// Pattern and case mode the matches were found with.
+	pattern: String,
+	case: SearchCase,
+	/// Lines that were searched, including margins around the viewport.
+	lines: std::ops::Range<usize>,
+	/// Matches in document order.
+	ranges: Vec<Range>,
+}
+
+/// Pattern being typed into the search prompt, highlighted in place of the
+/// committed search in the buffer it previews.
+pub struct SearchPreview {
+	/// Buffer the prompt searches.
+	pub buffer: BufferId,
+	/// Pattern typed so far.
+	pub pattern: String,
+	/// Match shown in the view.
+	pub current: Option<Range>,
+}
+
+/// Compiled form of the last search pattern.
+///
+/// The regex is cached against both the pattern and the case mode it was
+/// compiled with, so changing `search-case` recompiles on the next search.
+#[derive(Default)]
+pub struct SearchState {
+	compiled: Option<(String, SearchCase, Regex)>,
+	/// Case mode of the most recent search.
+	pub case: SearchCase,
+	/// 1-based index of the current match and the total match count, if
+	/// the last search found anything. The index is 0 once the selection
+	/// no longer sits on a match.
+	pub matches: Option<(usize, usize)>,
+	/// Match the last search landed on.
+	pub current: Option<Range>,
+	/// Whether matches are highlighted; cleared by `clear_search_highlight`
+	/// until the next search.
+	pub highlight: bool,
+	/// Live preview of an open search prompt.
+	pub preview: Option<SearchPreview>,
+	/// Matches around each buffer's viewport, reused while the viewport
+	/// stays inside the searched lines.
+	visible: HashMap<BufferId, VisibleMatches>,
+}
+
+impl SearchState {
+	/// Returns the regex for `pattern` under `case`, compiling it only if
+	/// the pattern or mode differs from the cached one.
+	pub fn regex(&mut self, pattern: &str, case: SearchCase) -> Result<Regex, SearchError> {
+		self.case = case;
+		if let Some((cached, cached_case, regex)) = &self.compiled
+			&& cached == pattern
+			&& *cached_case == case
+		{
+			return Ok(regex.clone());
+		}
+		let regex = movement::compile_with_case(pattern, case)?;
+		self.compiled = Some((pattern.to_string(), case, regex.clone()));
+		Ok(regex)
+	}
+
+	/// Drops the cached regex and match counts.
+	pub fn invalidate(&mut self) {
+		self.compiled = None;
+		self.matches = None;
+		self.current = None;
+		self.visible.clear();
+	}
+
+	/// Returns cached matches for `buffer_id` if they were found with
+	/// `pattern` and `case` over lines covering `lines`.
+	pub fn visible_matches(
+		&self,
+		buffer_id: BufferId,
+		pattern: &str,
+		case: SearchCase,
+		lines: std::ops::Range<usize>,
+	) -> Option<&[Range]> {
+		self.visible
+			.get(&buffer_id)
+			.filter(|cached| {
+				cached.pattern == pattern
+					&& cached.case == case
+					&& cached.lines.start <= lines.start
+					&& cached.lines.end >= lines.end
+			})
+			.map(|cached| cached.ranges.as_slice())
+	}
+
+	/// Caches the matches found with `pattern` and `case` over `lines` of
+	/// `buffer_id`.
+	pub fn set_visible_matches(
+		&mut self,
+		buffer_id: BufferId,
+		pattern: &str,
+		case: SearchCase,
+		lines: std::ops::Range<usize>,
+		ranges: Vec<Range>,
+	) {
+		self.visible.insert(
+			buffer_id,
+			VisibleMatches {
+				pattern: pattern.to_string(),
+				case,
+				lines,
+				ranges,
+			},
+		);
+	}
+
+	/// Drops cached visible matches after the text changed.
+	pub fn invalidate_visible(&mut self) {
+		self.visible.clear();
+	}
+}
+
 /// Editing session state.
 ///
 /// Groups workspace-level state that persists across buffer switches:
-/// registers, jump list, macros, and queued commands.
+/// registers, jump list, macros, search, and queued commands.
 #[derive(Default)]
 pub struct Workspace {
 	/// Named registers (yank buffer, etc.).
@@ -141,6 +438,43 @@ pub struct Workspace {
 	pub jump_list: JumpList,
 	/// Macro recording and playback state.
 	pub macro_state: MacroState,
+	/// Compiled search pattern and match counts.
+	pub search: SearchState,
 	/// Queue for deferred command execution.
 	pub command_queue: CommandQueue,
+	/// Locations collected by the last search or query that sent its
+	/// results there.
+	pub locations: LocationList,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn list(len: usize) -> LocationList {
+		let mut list = LocationList::default();
+		let items = (0..len)
+			.map(|i| LocationItem::new("a.rs", i, 0, ""))
+			.collect();
+		list.set("test", items);
+		list
+	}
+
+	#[test]
+	fn location_list_steps_from_either_end() {
+		let mut locations = list(3);
+		assert_eq!((locations.step(1), locations.step(-1)), (Some(0), Some(2)));
+		locations.select(2);
+		assert_eq!((locations.step(1), locations.step(-1)), (None, Some(1)));
+		locations.select(0);
+		assert_eq!(locations.step(-1), None);
+		assert_eq!(list(0).step(1), None);
+	}
+
+	#[test]
+	fn location_columns_run_to_line_end_across_lines() {
+		let item = LocationItem::new("a.rs", 1, 4, "");
+		assert_eq!(item.clone().with_end(1, 9).columns(), 4..9);
+		assert_eq!(item.with_end(2, 0).columns(), 4..usize::MAX);
+	}
 }
@@ -2,10 +2,15 @@
 //!
 //! Provides convenient methods for accessing buffers. Delegates to [`BufferManager`].
 
+use xeno_registry::gutter::LineNumbers;
 use xeno_registry::options::keys;
 
 use super::{Editor, FocusTarget};
 use crate::buffer::{Buffer, BufferId, BufferView};
+use crate::render::{
+	DiagnosticMaps, DiagnosticsDisplay, InlayHintMap, LineHighlights, ScrollMargins,
+	ShowWhitespace, WhitespaceMarkers, WrapIndent,
+};
 use crate::window::Window;
 
 impl Editor {
@@ -71,9 +76,11 @@ impl Editor {
 		Some(self.focused_view())
 	}
 
-	/// Returns all text buffer IDs.
+	/// Returns all text buffer IDs in creation order.
 	pub fn buffer_ids(&self) -> Vec<BufferId> {
-		self.buffers.buffer_ids().collect()
+		let mut ids: Vec<_> = self.buffers.buffer_ids().collect();
+		ids.sort_unstable();
+		ids
 	}
 
 	/// Returns a reference to a specific buffer by ID.
@@ -116,11 +123,145 @@ impl Editor {
 			.unwrap_or(true)
 	}
 
-	/// Returns the scroll margin for a specific buffer.
-	pub fn scroll_margin_for(&self, buffer_id: BufferId) -> usize {
+	/// Returns the cursorline, colorcolumn, whitespace and symbol highlights
+	/// for a specific buffer, with colorcolumns converted to 0-based columns.
+	pub fn line_highlights_for(&self, buffer_id: BufferId) -> LineHighlights {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return LineHighlights::default();
+		};
+		let colorcolumns = buffer
+			.option(keys::COLORCOLUMN, self)
+			.split(',')
+			.filter_map(|col| col.trim().parse::<usize>().ok())
+			.filter_map(|col| col.checked_sub(1))
+			.collect();
+		let marker = |key| {
+			let value: String = buffer.option(key, self);
+			value.chars().next().unwrap_or(' ')
+		};
+		let whitespace = WhitespaceMarkers {
+			show: ShowWhitespace::from_name(&buffer.option(keys::SHOW_WHITESPACE, self))
+				.unwrap_or_default(),
+			tab: marker(keys::WHITESPACE_TAB),
+			space: marker(keys::WHITESPACE_SPACE),
+			nbsp: marker(keys::WHITESPACE_NBSP),
+		};
+		#[allow(unused_mut, reason = "symbols are only filled in with lsp")]
+		let mut highlights = LineHighlights {
+			cursorline: buffer.option(keys::CURSORLINE, self),
+			colorcolumns,
+			whitespace,
+			search: Default::default(),
+			symbols: Default::default(),
+		};
+		#[cfg(feature = "lsp")]
+		{
+			highlights.symbols = self.symbol_highlights_for(buffer_id);
+		}
+		highlights
+	}
+
+	/// Lays out a buffer's diagnostics for rendering, as far as its
+	/// `diagnostics-display` draws them.
+	pub fn diagnostic_maps_for(&self, buffer_id: BufferId) -> DiagnosticMaps {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return DiagnosticMaps::default();
+		};
+		let display = DiagnosticsDisplay::parse(&buffer.option(keys::DIAGNOSTICS_DISPLAY, self));
+		let doc = buffer.doc();
+		DiagnosticMaps::build(&doc.diagnostics, &doc.content, display)
+	}
+
+	/// Lays out the inlay hints of the `height` lines a buffer shows from
+	/// its scroll position, or none if `inlay-hints` is off.
+	pub fn inlay_hint_map_for(&self, buffer_id: BufferId, height: usize) -> InlayHintMap {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return InlayHintMap::default();
+		};
+		if !buffer.option(keys::INLAY_HINTS, self) {
+			return InlayHintMap::default();
+		}
+		let max_len = buffer.option(keys::INLAY_HINT_MAX_LENGTH, self).max(0) as usize;
+		let doc = buffer.doc();
+		let lines = buffer.scroll_line..buffer.scroll_line + height;
+		InlayHintMap::build(&doc.inlay_hints, &doc.content, lines, max_len)
+	}
+
+	/// Returns the `scrolloff` and `sidescrolloff` margins for a specific
+	/// buffer.
+	pub fn scroll_margins_for(&self, buffer_id: BufferId) -> ScrollMargins {
+		self.buffers
+			.get_buffer(buffer_id)
+			.map(|b| ScrollMargins {
+				rows: b.option(keys::SCROLLOFF, self) as usize,
+				columns: b.option(keys::SIDESCROLLOFF, self) as usize,
+			})
+			.unwrap_or(ScrollMargins {
+				rows: 5,
+				columns: 5,
+			})
+	}
+
+	/// Returns the line number mode for a specific buffer.
+	pub fn line_numbers_for(&self, buffer_id: BufferId) -> LineNumbers {
 		self.buffers
 			.get_buffer(buffer_id)
-			.map(|b| b.option(keys::SCROLL_MARGIN, self) as usize)
-			.unwrap_or(5)
+			.and_then(|b| LineNumbers::from_name(&b.option(keys::LINE_NUMBERS, self)))
+			.unwrap_or_default()
+	}
+
+	/// Re-resolves every buffer's line number mode after the `line-numbers`
+	/// option changes, so gutter widths update on the next frame.
+	pub(crate) fn refresh_line_numbers(&mut self) {
+		for buffer_id in self.buffer_ids() {
+			let mode = self.line_numbers_for(buffer_id);
+			if let Some(buffer) = self.get_buffer_mut(buffer_id) {
+				buffer.line_numbers = mode;
+			}
+		}
+		self.frame.needs_redraw = true;
+	}
+
+	/// Returns whether a buffer soft-wraps long lines. Large-file mode
+	/// always turns wrapping off.
+	pub fn wrap_for(&self, buffer_id: BufferId) -> bool {
+		let wrap = self
+			.buffers
+			.get_buffer(buffer_id)
+			.is_none_or(|b| b.option(keys::WRAP, self));
+		wrap && !self.large_file_mode(buffer_id)
+	}
+
+	/// Returns the prefix of a buffer's wrapped continuation rows.
+	pub fn wrap_indent_for(&self, buffer_id: BufferId) -> WrapIndent {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return WrapIndent::default();
+		};
+		WrapIndent {
+			preserve: buffer.option(keys::WRAP_INDENT, self),
+			extra: buffer.option(keys::WRAP_INDENT_EXTRA, self).max(0) as usize,
+			marker: buffer.option(keys::WRAP_MARKER, self),
+		}
+	}
+
+	/// Re-resolves every buffer's wrap state after `wrap`, the wrap indent
+	/// options or `large-file` change, resetting scroll offsets that only
+	/// apply to the old layout.
+	pub(crate) fn refresh_wrap(&mut self) {
+		for buffer_id in self.buffer_ids() {
+			let wrap = self.wrap_for(buffer_id);
+			let wrap_indent = self.wrap_indent_for(buffer_id);
+			if let Some(buffer) = self.get_buffer_mut(buffer_id) {
+				if buffer.wrap != wrap || buffer.wrap_indent != wrap_indent {
+					buffer.scroll_segment = 0;
+				}
+				if buffer.wrap != wrap {
+					buffer.left_col = 0;
+				}
+				buffer.wrap = wrap;
+				buffer.wrap_indent = wrap_indent;
+			}
+		}
+		self.frame.needs_redraw = true;
 	}
 }
@@ -0,0 +1,52 @@
+//! Edits language servers make to buffers about to be saved.
+//!
+//! Before a buffer is written its servers are sent `textDocument/willSave`.
+//! With `will-save-wait-until` on, servers that support it are then asked
+//! through `textDocument/willSaveWaitUntil` for edits to make first, such as
+//! organizing imports. Their edits always land before `format-on-save`
+//! formats the buffer; a server slower than `will-save-timeout` is skipped
+//! so it can never keep a buffer from being saved.
+
+use std::time::Duration;
+
+use tracing::warn;
+use xeno_registry::options::keys;
+use xeno_registry_notifications::keys as notif_keys;
+
+use super::Editor;
+use crate::buffer::BufferId;
+
+impl Editor {
+	/// Tells a buffer's language servers it is about to be written and, if
+	/// `will-save-wait-until` is on, applies the edits they ask for.
+	///
+	/// Failures and servers slower than `will-save-timeout` leave the buffer
+	/// as it is, with a warning.
+	pub(super) async fn will_save_before_write(&mut self, buffer_id: BufferId) {
+		let Some(buffer) = self.buffers.get_buffer(buffer_id) else {
+			return;
+		};
+		if let Err(e) = self.lsp.on_buffer_will_save(buffer) {
+			warn!(error = %e, "LSP will_save notification failed");
+		}
+		if !self.resolve_typed_option(buffer_id, keys::WILL_SAVE_WAIT_UNTIL) {
+			return;
+		}
+
+		let timeout = self.resolve_typed_option(buffer_id, keys::WILL_SAVE_TIMEOUT);
+		let timeout = Duration::from_millis(timeout.max(1) as u64);
+		let response = tokio::time::timeout(timeout, self.lsp.will_save_wait_until(buffer)).await;
+		let result = match response {
+			Ok(Ok(Some((edits, encoding)))) => self.apply_format_edits(buffer_id, &edits, encoding),
+			Ok(Ok(None)) => Ok(0),
+			Ok(Err(e)) => Err(e.to_string()),
+			Err(_) => Err(format!(
+				"language server took over {} ms",
+				timeout.as_millis()
+			)),
+		};
+		if let Err(reason) = result {
+			self.notify(notif_keys::will_save_edits_skipped::call(&reason));
+		}
+	}
+}
@@ -0,0 +1,730 @@
+//! Applying LSP workspace edits across buffers and files.
+//!
+//! An edit is planned in full before anything changes: every document it
+//! touches is loaded and changed in memory, and every file operation is
+//! checked against the files the steps before it leave behind. Only a plan
+//! that holds together is applied, file operations first and then one
+//! transaction per buffer.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+use xeno_base::transaction::Change;
+use xeno_base::{Rope, Transaction};
+use xeno_lsp::lsp_types::{
+	DocumentChangeOperation, DocumentChanges, OneOf, ResourceOp, TextEdit, Uri, WorkspaceEdit,
+};
+use xeno_lsp::{OffsetEncoding, lsp_range_to_char_range, path_from_uri};
+
+use super::Editor;
+use crate::buffer::BufferId;
+
+/// What applying a workspace edit changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WorkspaceEditSummary {
+	/// Number of text edits applied.
+	pub edits: usize,
+	/// Number of files the edits touched.
+	pub files: usize,
+	/// Number of files created.
+	pub created: usize,
+	/// Number of files or directories renamed.
+	pub renamed: usize,
+	/// Number of files or directories deleted.
+	pub deleted: usize,
+}
+
+impl fmt::Display for WorkspaceEditSummary {
+	/// Lists the changes, as in `3 edits in 2 files, 1 created`.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let plural = |n: usize, one: &str, many: &str| {
+			if n == 1 {
+				format!("1 {one}")
+			} else {
+				format!("{n} {many}")
+			}
+		};
+		let mut parts = Vec::new();
+		if self.edits > 0 {
+			parts.push(format!(
+				"{} in {}",
+				plural(self.edits, "edit", "edits"),
+				plural(self.files, "file", "files")
+			));
+		}
+		for (count, what) in [
+			(self.created, "created"),
+			(self.renamed, "renamed"),
+			(self.deleted, "deleted"),
+		] {
+			if count > 0 {
+				parts.push(format!("{count} {what}"));
+			}
+		}
+		if parts.is_empty() {
+			f.write_str("no changes")
+		} else {
+			f.write_str(&parts.join(", "))
+		}
+	}
+}
+
+/// A step of a workspace edit, in the order the server listed them.
+enum EditStep {
+	/// Text edits to one document.
+	Text(Uri, Vec<TextEdit>),
+	/// A file create, rename or delete.
+	File(ResourceOp),
+}
+
+/// A file operation checked against the files it touches.
+#[derive(Debug, PartialEq, Eq)]
+enum FileOp {
+	/// Creates an empty file, truncating one that exists.
+	Create(PathBuf),
+	/// Moves a file or directory.
+	Rename { from: PathBuf, to: PathBuf },
+	/// Removes a file or directory.
+	Delete { path: PathBuf, recursive: bool },
+}
+
+/// A document changed by an edit, as it is once the steps so far ran.
+struct PlannedDoc {
+	/// Where the document is.
+	path: PathBuf,
+	/// Buffer showing the document before the edit, or `None` for a file
+	/// the edit creates.
+	buffer: Option<BufferId>,
+	/// Text with the planned changes made.
+	text: Rope,
+	/// Changes to the text the document had before the edit.
+	tx: Option<Transaction>,
+	/// Number of text edits in `tx`.
+	edits: usize,
+}
+
+impl PlannedDoc {
+	/// Plans `edits` to the document's text as it is so far.
+	fn change(&mut self, edits: &[TextEdit], encoding: OffsetEncoding) -> Result<(), String> {
+		let tx = text_transaction(&self.text, edits, encoding)
+			.map_err(|e| format!("{}: {e}", self.path.display()))?;
+		self.replace_text(tx);
+		self.edits += edits.len();
+		Ok(())
+	}
+
+	/// Plans deleting all of the document's text.
+	fn clear(&mut self) {
+		let tx = Transaction::change(
+			self.text.slice(..),
+			[Change {
+				start: 0,
+				end: self.text.len_chars(),
+				replacement: None,
+			}],
+		);
+		self.replace_text(tx);
+	}
+
+	fn replace_text(&mut self, tx: Transaction) {
+		tx.apply(&mut self.text);
+		self.tx = Some(match self.tx.take() {
+			Some(before) => before.compose(tx),
+			None => tx,
+		});
+	}
+}
+
+/// The checked steps of a workspace edit, not yet applied.
+#[derive(Default)]
+struct EditPlan {
+	/// File operations, in order.
+	ops: Vec<FileOp>,
+	/// Documents the steps change or create.
+	docs: Vec<PlannedDoc>,
+	/// Paths renamed away or deleted by the steps so far.
+	gone: HashSet<PathBuf>,
+}
+
+impl EditPlan {
+	/// Returns the index of the planned document at `path`.
+	fn doc_index(&self, path: &Path) -> Option<usize> {
+		self.docs.iter().position(|doc| doc.path == path)
+	}
+
+	/// Returns whether `path`, or a directory holding it, is renamed away or
+	/// deleted by the steps so far.
+	fn is_gone(&self, path: &Path) -> bool {
+		path.ancestors().any(|p| self.gone.contains(p))
+	}
+
+	/// Returns whether `path` exists once the steps so far ran.
+	fn exists(&self, path: &Path) -> bool {
+		self.doc_index(path).is_some() || (!self.is_gone(path) && path.exists())
+	}
+}
+
+impl Editor {
+	/// Applies `edit` to the buffer of every file it touches, opening files
+	/// that are not loaded, and runs its file operations.
+	///
+	/// Each buffer's edits apply as one transaction, so they undo as one
+	/// step. Positions are read with `encoding`, the encoding of the server
+	/// that sent the edit. Nothing is changed if any file cannot be opened,
+	/// is readonly, has an edit outside its text, or a file operation would
+	/// fail on the files the steps before it leave behind. Files are created,
+	/// renamed and deleted before any buffer changes; buffers showing a
+	/// renamed file follow it, while buffers of a deleted file stay open.
+	pub async fn apply_workspace_edit(
+		&mut self,
+		edit: WorkspaceEdit,
+		encoding: OffsetEncoding,
+	) -> Result<WorkspaceEditSummary, String> {
+		let mut plan = EditPlan::default();
+		for step in edit_steps(edit) {
+			match step {
+				EditStep::Text(uri, edits) => {
+					let index = self.planned_doc(&mut plan, edit_path(&uri)?).await?;
+					plan.docs[index].change(&edits, encoding)?;
+				}
+				EditStep::File(op) => self.plan_file_op(&mut plan, op).await?,
+			}
+		}
+
+		let mut summary = WorkspaceEditSummary::default();
+		for op in &plan.ops {
+			self.run_file_op(op).await?;
+			match op {
+				FileOp::Create(_) => summary.created += 1,
+				FileOp::Rename { .. } => summary.renamed += 1,
+				FileOp::Delete { .. } => summary.deleted += 1,
+			}
+		}
+		for doc in plan.docs {
+			let Some(tx) = doc.tx else {
+				continue;
+			};
+			let buffer_id = match doc.buffer {
+				Some(id) => id,
+				None => self.buffer_for_edit(doc.path).await?,
+			};
+			let selection = {
+				let buffer = self.buffers.get_buffer(buffer_id).expect("buffer exists");
+				tx.map_selection(&buffer.selection)
+			};
+			self.save_undo_state_for(buffer_id);
+			if self.apply_transaction_with_selection(buffer_id, &tx, Some(selection)) {
+				summary.edits += doc.edits;
+				summary.files += 1;
+			}
+		}
+		self.frame.needs_redraw = true;
+		Ok(summary)
+	}
+
+	/// Returns the index of the planned document at `path`, loading it from
+	/// its buffer, opened if needed.
+	async fn planned_doc(&mut self, plan: &mut EditPlan, path: PathBuf) -> Result<usize, String> {
+		if let Some(index) = plan.doc_index(&path) {
+			return Ok(index);
+		}
+		if plan.is_gone(&path) {
+			return Err(format!("{} is removed by the edit", path.display()));
+		}
+		let buffer_id = self.buffer_for_edit(path.clone()).await?;
+		let text = self
+			.buffers
+			.get_buffer(buffer_id)
+			.expect("buffer exists")
+			.doc()
+			.content
+			.clone();
+		plan.docs.push(PlannedDoc {
+			path,
+			buffer: Some(buffer_id),
+			text,
+			tx: None,
+			edits: 0,
+		});
+		Ok(plan.docs.len() - 1)
+	}
+
+	/// Checks a file operation against the files the planned steps leave
+	/// behind and adds it to the plan.
+	///
+	/// Operations skipped by their `ignoreIfExists` or `ignoreIfNotExists`
+	/// option are left out.
+	async fn plan_file_op(&mut self, plan: &mut EditPlan, op: ResourceOp) -> Result<(), String> {
+		match op {
+			ResourceOp::Create(create) => {
+				let path = edit_path(&create.uri)?;
+				let options = create.options.unwrap_or_default();
+				if plan.exists(&path) {
+					if options.overwrite != Some(true) {
+						return if options.ignore_if_exists == Some(true) {
+							Ok(())
+						} else {
+							Err(format!("{} already exists", path.display()))
+						};
+					}
+					let index = self.planned_doc(plan, path.clone()).await?;
+					plan.docs[index].clear();
+				} else {
+					plan.gone.remove(&path);
+					plan.docs.push(PlannedDoc {
+						path: path.clone(),
+						buffer: None,
+						text: Rope::new(),
+						tx: None,
+						edits: 0,
+					});
+				}
+				plan.ops.push(FileOp::Create(path));
+			}
+			ResourceOp::Rename(rename) => {
+				let from = edit_path(&rename.old_uri)?;
+				let to = edit_path(&rename.new_uri)?;
+				let options = rename.options.unwrap_or_default();
+				if !plan.exists(&from) {
+					return Err(format!("{} does not exist", from.display()));
+				}
+				if plan.exists(&to) {
+					if options.overwrite != Some(true) {
+						return if options.ignore_if_exists == Some(true) {
+							Ok(())
+						} else {
+							Err(format!("{} already exists", to.display()))
+						};
+					}
+					if self.buffers.find_by_path(&to).is_some() {
+						return Err(format!(
+							"{} is open and cannot be overwritten",
+							to.display()
+						));
+					}
+					plan.docs.retain(|doc| doc.path != to);
+				}
+				if plan.doc_index(&from).is_none() && from.is_dir() {
+					for doc in &mut plan.docs {
+						if let Ok(rest) = doc.path.strip_prefix(&from) {
+							doc.path = to.join(rest);
+						}
+					}
+				} else {
+					let index = self.planned_doc(plan, from.clone()).await?;
+					plan.docs[index].path = to.clone();
+				}
+				plan.gone.insert(from.clone());
+				plan.gone.remove(&to);
+				plan.ops.push(FileOp::Rename { from, to });
+			}
+			ResourceOp::Delete(delete) => {
+				let path = edit_path(&delete.uri)?;
+				let options = delete.options.unwrap_or_default();
+				if !plan.exists(&path) {
+					return if options.ignore_if_not_exists == Some(true) {
+						Ok(())
+					} else {
+						Err(format!("{} does not exist", path.display()))
+					};
+				}
+				let recursive = options.recursive == Some(true);
+				if !recursive && path.read_dir().is_ok_and(|mut dir| dir.next().is_some()) {
+					return Err(format!("{} is not empty", path.display()));
+				}
+				plan.docs.retain(|doc| !doc.path.starts_with(&path));
+				plan.gone.insert(path.clone());
+				plan.ops.push(FileOp::Delete { path, recursive });
+			}
+		}
+		Ok(())
+	}
+
+	/// Runs a planned file operation on disk.
+	async fn run_file_op(&mut self, op: &FileOp) -> Result<(), String> {
+		let failed = |path: &Path, e: std::io::Error| format!("{}: {e}", path.display());
+		match op {
+			FileOp::Create(path) => {
+				if let Some(parent) = path.parent() {
+					tokio::fs::create_dir_all(parent)
+						.await
+						.map_err(|e| failed(parent, e))?;
+				}
+				tokio::fs::write(path, "")
+					.await
+					.map_err(|e| failed(path, e))?;
+			}
+			FileOp::Rename { from, to } => {
+				if let Some(parent) = to.parent() {
+					tokio::fs::create_dir_all(parent)
+						.await
+						.map_err(|e| failed(parent, e))?;
+				}
+				tokio::fs::rename(from, to)
+					.await
+					.map_err(|e| failed(from, e))?;
+				self.move_buffers(from, to).await;
+			}
+			FileOp::Delete { path, recursive } => {
+				let metadata = tokio::fs::metadata(path)
+					.await
+					.map_err(|e| failed(path, e))?;
+				let removed = if !metadata.is_dir() {
+					tokio::fs::remove_file(path).await
+				} else if *recursive {
+					tokio::fs::remove_dir_all(path).await
+				} else {
+					tokio::fs::remove_dir(path).await
+				};
+				removed.map_err(|e| failed(path, e))?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Points the buffers of files at or below `from` to where they moved,
+	/// reopening them with their language servers.
+	async fn move_buffers(&mut self, from: &Path, to: &Path) {
+		let moved: Vec<(BufferId, PathBuf)> = self
+			.buffers
+			.buffers()
+			.filter_map(|buffer| {
+				let path = buffer.path()?;
+				let rest = path.strip_prefix(from).ok()?;
+				Some((buffer.id, to.join(rest)))
+			})
+			.collect();
+		for (buffer_id, path) in moved {
+			let buffer = self.buffers.get_buffer(buffer_id).expect("buffer exists");
+			if buffer.path().as_deref() == Some(path.as_path()) {
+				// A split sharing the document already moved it.
+				continue;
+			}
+			if let Err(e) = self.lsp.on_buffer_close(buffer) {
+				warn!(error = %e, "LSP buffer close failed");
+			}
+			buffer.set_path(Some(path));
+			self.set_file_type(buffer_id, None);
+			if let Some(buffer) = self.buffers.get_buffer(buffer_id)
+				&& let Err(e) = self.lsp.on_buffer_open(buffer).await
+			{
+				warn!(error = %e, "LSP buffer open failed");
+			}
+		}
+	}
+
+	/// Returns the buffer showing `path`, opening the file if needed.
+	async fn buffer_for_edit(&mut self, path: PathBuf) -> Result<BufferId, String> {
+		let buffer_id = match self.buffers.find_by_path(&path) {
+			Some(id) => id,
+			None => self
+				.open_file(path.clone())
+				.await
+				.map_err(|e| format!("{}: {e}", path.display()))?,
+		};
+		let buffer = self.buffers.get_buffer(buffer_id).expect("buffer exists");
+		if buffer.is_readonly() || self.lossy_locked(buffer_id) {
+			return Err(format!("{} is readonly", path.display()));
+		}
+		Ok(buffer_id)
+	}
+
+	/// Builds the transaction making `edits` to `buffer_id`.
+	///
+	/// The edits are sorted by position; overlapping ones are refused.
+	pub(super) fn edit_transaction(
+		&self,
+		buffer_id: BufferId,
+		edits: &[TextEdit],
+		encoding: OffsetEncoding,
+	) -> Result<Transaction, String> {
+		let buffer = self.buffers.get_buffer(buffer_id).expect("buffer exists");
+		text_transaction(&buffer.doc().content, edits, encoding)
+	}
+}
+
+/// Builds the transaction making `edits` to `text`.
+///
+/// The edits are sorted by position; overlapping ones are refused.
+fn text_transaction(
+	text: &Rope,
+	edits: &[TextEdit],
+	encoding: OffsetEncoding,
+) -> Result<Transaction, String> {
+	let mut changes: Vec<_> = edits
+		.iter()
+		.map(|edit| {
+			let (start, end) = lsp_range_to_char_range(text, edit.range, encoding);
+			Change {
+				start,
+				end,
+				replacement: (!edit.new_text.is_empty()).then(|| edit.new_text.clone()),
+			}
+		})
+		.collect();
+	changes.sort_by_key(|change| change.start);
+	if changes.windows(2).any(|pair| pair[0].end > pair[1].start) {
+		return Err("edits overlap".to_string());
+	}
+	Ok(Transaction::change(text.slice(..), changes))
+}
+
+/// Returns the file path of a document in an edit.
+fn edit_path(uri: &Uri) -> Result<PathBuf, String> {
+	path_from_uri(uri).ok_or_else(|| format!("invalid file path in edit: {}", uri.as_str()))
+}
+
+/// Lists the steps of `edit` in order.
+///
+/// Edits to a document listed more than once are merged, unless a file
+/// operation comes between them.
+fn edit_steps(edit: WorkspaceEdit) -> Vec<EditStep> {
+	let mut steps: Vec<EditStep> = Vec::new();
+	let add = |steps: &mut Vec<EditStep>, uri: Uri, edits: Vec<TextEdit>| {
+		let since_op = steps
+			.iter_mut()
+			.rev()
+			.take_while(|step| matches!(step, EditStep::Text(..)));
+		for step in since_op {
+			if let EditStep::Text(known, known_edits) = step
+				&& *known == uri
+			{
+				known_edits.extend(edits);
+				return;
+			}
+		}
+		steps.push(EditStep::Text(uri, edits));
+	};
+
+	match edit.document_changes {
+		Some(DocumentChanges::Edits(edits)) => {
+			for doc_edit in edits {
+				add(
+					&mut steps,
+					doc_edit.text_document.uri,
+					unannotated(doc_edit.edits),
+				);
+			}
+		}
+		Some(DocumentChanges::Operations(operations)) => {
+			for operation in operations {
+				match operation {
+					DocumentChangeOperation::Edit(doc_edit) => {
+						add(
+							&mut steps,
+							doc_edit.text_document.uri,
+							unannotated(doc_edit.edits),
+						);
+					}
+					DocumentChangeOperation::Op(op) => steps.push(EditStep::File(op)),
+				}
+			}
+		}
+		None => {
+			for (uri, edits) in edit.changes.into_iter().flatten() {
+				add(&mut steps, uri, edits);
+			}
+		}
+	}
+	steps
+}
+
+/// Drops the change annotations from a document's edits.
+fn unannotated(
+	edits: Vec<OneOf<TextEdit, xeno_lsp::lsp_types::AnnotatedTextEdit>>,
+) -> Vec<TextEdit> {
+	edits
+		.into_iter()
+		.map(|edit| match edit {
+			OneOf::Left(edit) => edit,
+			OneOf::Right(annotated) => annotated.text_edit,
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use std::str::FromStr;
+
+	use xeno_lsp::lsp_types::{
+		CreateFile, DeleteFile, OptionalVersionedTextDocumentIdentifier, Position, Range,
+		RenameFile, TextDocumentEdit,
+	};
+
+	use super::*;
+
+	fn edit(line: u32, start: u32, end: u32, text: &str) -> TextEdit {
+		TextEdit {
+			range: Range::new(Position::new(line, start), Position::new(line, end)),
+			new_text: text.to_string(),
+		}
+	}
+
+	fn doc_edit(uri: &Uri, edits: Vec<TextEdit>) -> TextDocumentEdit {
+		TextDocumentEdit {
+			text_document: OptionalVersionedTextDocumentIdentifier {
+				uri: uri.clone(),
+				version: None,
+			},
+			edits: edits.into_iter().map(OneOf::Left).collect(),
+		}
+	}
+
+	fn uri(path: &Path) -> Uri {
+		xeno_lsp::uri_from_path(path).unwrap()
+	}
+
+	#[test]
+	fn collects_edits_per_document() {
+		let a = Uri::from_str("file:///a.rs").unwrap();
+		let edit_a = WorkspaceEdit {
+			document_changes: Some(DocumentChanges::Edits(vec![
+				doc_edit(&a, vec![edit(0, 0, 3, "bar")]),
+				doc_edit(&a, vec![edit(2, 4, 7, "bar")]),
+			])),
+			..Default::default()
+		};
+		let steps = edit_steps(edit_a);
+		assert!(matches!(&steps[..], [EditStep::Text(_, edits)] if edits.len() == 2));
+
+		let changes = WorkspaceEdit {
+			changes: Some(HashMap::from([(a.clone(), vec![edit(0, 0, 3, "bar")])])),
+			..Default::default()
+		};
+		assert_eq!(edit_steps(changes).len(), 1);
+
+		// A file operation keeps the edits on either side apart.
+		let around_op = WorkspaceEdit {
+			document_changes: Some(DocumentChanges::Operations(vec![
+				DocumentChangeOperation::Edit(doc_edit(&a, vec![edit(0, 0, 3, "bar")])),
+				DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+					uri: Uri::from_str("file:///b.rs").unwrap(),
+					options: None,
+					annotation_id: None,
+				})),
+				DocumentChangeOperation::Edit(doc_edit(&a, vec![edit(2, 4, 7, "bar")])),
+			])),
+			..Default::default()
+		};
+		assert_eq!(edit_steps(around_op).len(), 3);
+	}
+
+	#[test]
+	fn applies_edits_as_one_undo_step() {
+		let mut editor = Editor::new_scratch();
+		editor.insert_text("foo(foo);\n");
+		let buffer_id = editor.focused_view();
+		let edits = [edit(0, 0, 3, "bar"), edit(0, 4, 7, "bar")];
+		let tx = editor
+			.edit_transaction(buffer_id, &edits, OffsetEncoding::Utf8)
+			.unwrap();
+		editor.save_undo_state_for(buffer_id);
+		assert!(editor.apply_transaction_with_selection(buffer_id, &tx, None));
+		assert_eq!(editor.buffer().doc().content.to_string(), "bar(bar);\n");
+
+		editor.undo();
+		assert_eq!(editor.buffer().doc().content.to_string(), "foo(foo);\n");
+
+		let overlapping = [edit(0, 0, 5, "x"), edit(0, 4, 7, "y")];
+		assert!(
+			editor
+				.edit_transaction(buffer_id, &overlapping, OffsetEncoding::Utf8)
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn summary_lists_what_changed() {
+		let summary = WorkspaceEditSummary {
+			edits: 3,
+			files: 1,
+			created: 1,
+			..Default::default()
+		};
+		assert_eq!(summary.to_string(), "3 edits in 1 file, 1 created");
+		assert_eq!(WorkspaceEditSummary::default().to_string(), "no changes");
+	}
+
+	#[tokio::test]
+	async fn file_operations_run_before_their_edits() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let old = dir.join("old.rs");
+		let new = dir.join("new.rs");
+		let created = dir.join("sub/created.rs");
+		std::fs::write(&old, "fn old() {}\n").unwrap();
+
+		let mut editor = Editor::new(old.clone()).await.unwrap();
+		let edit = WorkspaceEdit {
+			document_changes: Some(DocumentChanges::Operations(vec![
+				DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+					old_uri: uri(&old),
+					new_uri: uri(&new),
+					options: None,
+					annotation_id: None,
+				})),
+				DocumentChangeOperation::Edit(doc_edit(&uri(&new), vec![edit(0, 3, 6, "new")])),
+				DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+					uri: uri(&created),
+					options: None,
+					annotation_id: None,
+				})),
+				DocumentChangeOperation::Edit(doc_edit(
+					&uri(&created),
+					vec![edit(0, 0, 0, "mod new;\n")],
+				)),
+			])),
+			..Default::default()
+		};
+		let summary = editor
+			.apply_workspace_edit(edit, OffsetEncoding::Utf8)
+			.await
+			.unwrap();
+		assert_eq!(
+			summary.to_string(),
+			"2 edits in 2 files, 1 created, 1 renamed"
+		);
+		assert!(!old.exists() && new.exists() && created.exists());
+		assert_eq!(editor.buffer().path(), Some(new.clone()));
+		assert_eq!(editor.buffer().doc().content.to_string(), "fn new() {}\n");
+		let created_id = editor.buffers.find_by_path(&created).unwrap();
+		let created_buffer = editor.buffers.get_buffer(created_id).unwrap();
+		assert_eq!(created_buffer.doc().content.to_string(), "mod new;\n");
+	}
+
+	#[tokio::test]
+	async fn failed_plan_changes_nothing() {
+		let tmp = tempfile::tempdir().unwrap();
+		let dir = tmp.path();
+		let path = dir.join("lib.rs");
+		let created = dir.join("created.rs");
+		std::fs::write(&path, "fn a() {}\n").unwrap();
+
+		let mut editor = Editor::new(path.clone()).await.unwrap();
+		let edit = WorkspaceEdit {
+			document_changes: Some(DocumentChanges::Operations(vec![
+				DocumentChangeOperation::Edit(doc_edit(&uri(&path), vec![edit(0, 3, 4, "b")])),
+				DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+					uri: uri(&created),
+					options: None,
+					annotation_id: None,
+				})),
+				DocumentChangeOperation::Op(ResourceOp::Delete(DeleteFile {
+					uri: uri(&dir.join("missing.rs")),
+					options: None,
+				})),
+			])),
+			..Default::default()
+		};
+		let error = editor
+			.apply_workspace_edit(edit, OffsetEncoding::Utf8)
+			.await
+			.unwrap_err();
+		assert!(error.ends_with("missing.rs does not exist"));
+		assert_eq!(editor.buffer().doc().content.to_string(), "fn a() {}\n");
+		assert!(!created.exists());
+	}
+}
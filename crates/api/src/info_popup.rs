@@ -8,13 +8,20 @@
 //! They reuse the buffer renderer for syntax highlighting and text wrapping.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
-use xeno_tui::layout::Rect;
+use xeno_tui::animation::Easing;
+use xeno_tui::layout::{HorizontalAlignment, Rect};
 use xeno_tui::widgets::BorderType;
-use xeno_tui::widgets::block::Padding;
+use xeno_tui::widgets::notifications::{AnimationPhase, Timing};
 
 use crate::buffer::BufferId;
-use crate::window::{FloatingStyle, WindowId};
+use crate::window::{FloatingStyle, FloatingWindow, WindowId};
+
+/// Default duration of a popup fading in.
+const DEFAULT_ENTRY_DURATION: Duration = Duration::from_millis(120);
+/// Default duration of a popup fading out.
+const DEFAULT_EXIT_DURATION: Duration = Duration::from_millis(100);
 
 /// Unique identifier for an info popup.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -31,8 +38,126 @@ pub struct InfoPopup {
 	pub buffer_id: BufferId,
 	/// Anchor position for the popup (where it should appear relative to).
 	pub anchor: PopupAnchor,
+	/// Which side of a cursor anchor the popup was placed on.
+	pub side: PopupSide,
+	/// Whether the popup stays open until dismissed explicitly.
+	pub pinned: bool,
+	/// Fade-in state of the popup.
+	pub animation: PopupAnimation,
+}
+
+/// Fade state of a popup as it is shown and closed.
+///
+/// Follows the [`AnimationPhase`]s of notifications: a popup enters,
+/// dwells until it is closed, then exits and is finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopupAnimation {
+	/// Current phase.
+	phase: AnimationPhase,
+	/// Progress through the current phase, from 0.0 to 1.0.
+	progress: f32,
+	/// Duration of the fade in.
+	entry: Duration,
+	/// Duration of the fade out.
+	exit: Duration,
+}
+
+impl PopupAnimation {
+	/// Creates an animation that starts fading in.
+	pub fn new(entry: Timing, exit: Timing) -> Self {
+		let duration = |timing, default| match timing {
+			Timing::Auto => default,
+			Timing::Fixed(d) => d,
+		};
+		Self {
+			phase: AnimationPhase::Entering,
+			progress: 0.0,
+			entry: duration(entry, DEFAULT_ENTRY_DURATION),
+			exit: duration(exit, DEFAULT_EXIT_DURATION),
+		}
+	}
+
+	/// Creates an animation for a popup shown and closed at once.
+	pub fn none() -> Self {
+		Self {
+			phase: AnimationPhase::Dwelling,
+			progress: 0.0,
+			entry: Duration::ZERO,
+			exit: Duration::ZERO,
+		}
+	}
+
+	/// Returns the current phase.
+	pub fn phase(&self) -> AnimationPhase {
+		self.phase
+	}
+
+	/// Starts fading out, from the current opacity if still fading in.
+	pub fn exit(&mut self) {
+		self.progress = match self.phase {
+			AnimationPhase::Entering => 1.0 - self.progress,
+			_ => 0.0,
+		};
+		self.phase = if self.exit.is_zero() {
+			AnimationPhase::Finished
+		} else {
+			AnimationPhase::Exiting
+		};
+	}
+
+	/// Advances the animation by `delta`.
+	pub fn tick(&mut self, delta: Duration) {
+		let (duration, next) = match self.phase {
+			AnimationPhase::Entering => (self.entry, AnimationPhase::Dwelling),
+			AnimationPhase::Exiting => (self.exit, AnimationPhase::Finished),
+			_ => return,
+		};
+		self.progress = if duration.is_zero() {
+			1.0
+		} else {
+			(self.progress + delta.as_secs_f32() / duration.as_secs_f32()).min(1.0)
+		};
+		if self.progress >= 1.0 {
+			self.phase = next;
+			self.progress = 0.0;
+		}
+	}
+
+	/// Returns whether the popup is fading in or out.
+	pub fn is_animating(&self) -> bool {
+		matches!(
+			self.phase,
+			AnimationPhase::Entering | AnimationPhase::Exiting
+		)
+	}
+
+	/// Returns how opaque the popup is drawn, from 0.0 to 1.0.
+	pub fn opacity(&self) -> f32 {
+		match self.phase {
+			AnimationPhase::Entering => Easing::EaseOut.apply(self.progress),
+			AnimationPhase::Exiting => 1.0 - Easing::EaseIn.apply(self.progress),
+			AnimationPhase::Dwelling => 1.0,
+			AnimationPhase::Pending | AnimationPhase::Finished => 0.0,
+		}
+	}
+}
+
+/// A closed popup that is still fading out.
+///
+/// Its window is already closed, so it no longer takes focus or mouse
+/// events; the copy kept here is only drawn. The buffer is removed once the
+/// animation finishes.
+#[derive(Debug)]
+pub struct ExitingPopup {
+	/// Copy of the closed window.
+	pub window: FloatingWindow,
+	/// Fade-out state.
+	pub animation: PopupAnimation,
 }
 
+/// Title shown on the border of a pinned popup.
+pub const PINNED_TITLE: &str = "📌";
+
 /// Anchor point for positioning info popups.
 #[derive(Debug, Clone, Copy, Default)]
 pub enum PopupAnchor {
@@ -43,6 +168,29 @@ pub enum PopupAnchor {
 	Point { x: u16, y: u16 },
 	/// Position adjacent to another window (e.g., next to completion menu).
 	Window(WindowId),
+	/// Next to a cursor cell: below it when there is room, above it
+	/// otherwise, and never covering it.
+	Cursor { x: u16, y: u16 },
+}
+
+/// Which side of its cursor anchor a popup opened on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PopupSide {
+	/// Below the cursor, reading down from it.
+	#[default]
+	Below,
+	/// Above the cursor, so the end of the popup is nearest it.
+	Above,
+}
+
+/// Where a popup ends up: its rectangle and the side of the anchor it is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupPlacement {
+	/// Final popup rectangle, inside the bounds it was placed in.
+	pub rect: Rect,
+	/// Side of a cursor anchor the popup is on; [`PopupSide::Below`] for
+	/// other anchors.
+	pub side: PopupSide,
 }
 
 /// Default floating style for info popups.
@@ -53,9 +201,10 @@ pub fn info_popup_style() -> FloatingStyle {
 	FloatingStyle {
 		border: true,
 		border_type: BorderType::Stripe,
-		padding: Padding::horizontal(1),
+		padding: None,
 		shadow: false,
 		title: None,
+		title_alignment: HorizontalAlignment::Left,
 	}
 }
 
@@ -68,6 +217,27 @@ pub fn compute_popup_rect(
 	content_height: u16,
 	bounds: Rect,
 ) -> Rect {
+	compute_popup_placement(anchor, content_width, content_height, bounds).rect
+}
+
+/// Computes where a popup goes based on anchor and content size, including
+/// which side of a cursor anchor it opens on.
+pub fn compute_popup_placement(
+	anchor: PopupAnchor,
+	content_width: u16,
+	content_height: u16,
+	bounds: Rect,
+) -> PopupPlacement {
+	if let PopupAnchor::Cursor { x, y } = anchor {
+		return place_at_cursor(
+			(x, y),
+			content_width.saturating_add(2),
+			content_height.saturating_add(2),
+			bounds,
+			None,
+		);
+	}
+
 	let width = content_width
 		.saturating_add(2)
 		.min(bounds.width.saturating_sub(4));
@@ -76,7 +246,7 @@ pub fn compute_popup_rect(
 		.min(bounds.height.saturating_sub(2));
 
 	let (x, y) = match anchor {
-		PopupAnchor::Center => (
+		PopupAnchor::Center | PopupAnchor::Cursor { .. } => (
 			bounds.x + bounds.width.saturating_sub(width) / 2,
 			bounds.y + bounds.height.saturating_sub(height) / 2,
 		),
@@ -92,7 +262,74 @@ pub fn compute_popup_rect(
 		), // TODO: position adjacent to window
 	};
 
-	Rect::new(x, y, width, height)
+	PopupPlacement {
+		rect: Rect::new(x, y, width, height),
+		side: PopupSide::Below,
+	}
+}
+
+/// Places a cursor-anchored popup on `side` of the cursor at `(x, y)`,
+/// shortening it to the rows on that side instead of flipping.
+///
+/// Popups sharing the cursor with another one use this to take the side the
+/// other one left free.
+pub fn compute_popup_placement_on(
+	(x, y): (u16, u16),
+	content_width: u16,
+	content_height: u16,
+	bounds: Rect,
+	side: PopupSide,
+) -> PopupPlacement {
+	place_at_cursor(
+		(x, y),
+		content_width.saturating_add(2),
+		content_height.saturating_add(2),
+		bounds,
+		Some(side),
+	)
+}
+
+/// Places a `width` by `height` popup next to the cursor cell at `cursor`.
+///
+/// Opens below the cursor row when the popup fits there, flips above when
+/// it only fits there, and otherwise takes whichever side has more rows and
+/// shortens to it. A given `side` is kept whether it fits or not. The popup
+/// starts at the cursor column and shifts left to stay inside `bounds`. Its
+/// rows never include the cursor row.
+fn place_at_cursor(
+	cursor: (u16, u16),
+	width: u16,
+	height: u16,
+	bounds: Rect,
+	side: Option<PopupSide>,
+) -> PopupPlacement {
+	let cursor_y = cursor.1.clamp(bounds.y, bounds.bottom().saturating_sub(1));
+	let below = bounds.bottom().saturating_sub(cursor_y + 1);
+	let above = cursor_y - bounds.y;
+
+	let side = side.unwrap_or(if height <= below || (height > above && below >= above) {
+		PopupSide::Below
+	} else {
+		PopupSide::Above
+	});
+	let (y, height) = match side {
+		PopupSide::Below => (cursor_y + 1, height.min(below)),
+		PopupSide::Above => {
+			let height = height.min(above);
+			(cursor_y - height, height)
+		}
+	};
+
+	let width = width.min(bounds.width);
+	let x = cursor
+		.0
+		.min(bounds.right().saturating_sub(width))
+		.max(bounds.x);
+
+	PopupPlacement {
+		rect: Rect::new(x, y, width, height),
+		side,
+	}
 }
 
 #[cfg(test)]
@@ -117,6 +354,58 @@ mod tests {
 		assert!(rect.y + rect.height <= bounds.y + bounds.height);
 	}
 
+	#[test]
+	fn cursor_popup_opens_below_and_flips_above() {
+		let bounds = Rect::new(0, 1, 80, 22);
+		let below = compute_popup_placement(PopupAnchor::Cursor { x: 10, y: 5 }, 20, 5, bounds);
+		assert_eq!(below.side, PopupSide::Below);
+		assert_eq!(below.rect, Rect::new(10, 6, 22, 7));
+
+		let above = compute_popup_placement(PopupAnchor::Cursor { x: 70, y: 20 }, 20, 5, bounds);
+		assert_eq!(above.side, PopupSide::Above);
+		assert_eq!(above.rect, Rect::new(58, 13, 22, 7));
+	}
+
+	#[test]
+	fn cursor_popup_shrinks_to_the_roomier_side() {
+		let bounds = Rect::new(0, 0, 40, 10);
+		let placement = compute_popup_placement(PopupAnchor::Cursor { x: 0, y: 3 }, 10, 20, bounds);
+		assert_eq!(placement.side, PopupSide::Below);
+		assert_eq!(placement.rect, Rect::new(0, 4, 12, 6));
+	}
+
+	#[test]
+	fn cursor_popup_keeps_a_forced_side() {
+		let bounds = Rect::new(0, 0, 80, 24);
+		let placement = compute_popup_placement_on((10, 5), 20, 8, bounds, PopupSide::Above);
+		assert_eq!(placement.side, PopupSide::Above);
+		assert_eq!(placement.rect, Rect::new(10, 0, 22, 5));
+	}
+
+	/// Checks every cursor cell and a spread of popup sizes: the popup stays
+	/// inside the bounds and off the cursor cell whenever it can.
+	#[test]
+	fn cursor_popup_stays_in_bounds_and_off_the_cursor() {
+		let bounds = Rect::new(3, 2, 17, 11);
+		for width in [1, 5, 17, 30] {
+			for height in [1, 3, 6, 11, 20] {
+				for y in bounds.top()..bounds.bottom() {
+					for x in bounds.left()..bounds.right() {
+						let placement = place_at_cursor((x, y), width, height, bounds, None);
+						let rect = placement.rect;
+						assert_eq!(rect.intersection(bounds), rect, "{rect:?} leaves bounds");
+						if rect.height > 0 {
+							let cursor = Rect::new(x, y, 1, 1);
+							assert!(!rect.intersects(cursor), "{rect:?} covers {cursor:?}");
+						}
+						let room = (bounds.bottom() - y - 1).max(y - bounds.y);
+						assert_eq!(rect.height, height.min(room));
+					}
+				}
+			}
+		}
+	}
+
 	#[test]
 	fn popup_rect_respects_point_position() {
 		let bounds = Rect::new(0, 1, 80, 22);
@@ -124,6 +413,29 @@ mod tests {
 		assert_eq!(rect.x, 10);
 		assert_eq!(rect.y, 5);
 	}
+
+	#[test]
+	fn animation_fades_in_and_out() {
+		let ms = Duration::from_millis;
+		let mut animation = PopupAnimation::new(Timing::Fixed(ms(100)), Timing::Fixed(ms(100)));
+		assert_eq!(animation.opacity(), 0.0);
+		animation.tick(ms(50));
+		let half = animation.opacity();
+		assert!(half > 0.0 && half < 1.0);
+		animation.tick(ms(50));
+		assert_eq!(animation.phase(), AnimationPhase::Dwelling);
+		assert_eq!(animation.opacity(), 1.0);
+
+		animation.exit();
+		assert!(animation.is_animating());
+		animation.tick(ms(100));
+		assert_eq!(animation.phase(), AnimationPhase::Finished);
+
+		let mut instant = PopupAnimation::none();
+		assert!(!instant.is_animating());
+		instant.exit();
+		assert_eq!(instant.phase(), AnimationPhase::Finished);
+	}
 }
 
 /// Storage for active info popups, keyed by [`InfoPopupId`].
@@ -136,6 +448,12 @@ mod tests {
 pub struct InfoPopupStore {
 	popups: HashMap<InfoPopupId, InfoPopup>,
 	next_id: u64,
+	/// Closed popups still fading out, oldest first.
+	exiting: Vec<ExitingPopup>,
+	/// How long new popups take to fade in.
+	pub entry_timing: Timing,
+	/// How long closed popups take to fade out.
+	pub exit_timing: Timing,
 }
 
 impl InfoPopupStore {
@@ -161,6 +479,30 @@ impl InfoPopupStore {
 		self.popups.get(&id)
 	}
 
+	/// Returns a mutable reference to a popup by ID.
+	pub fn get_mut(&mut self, id: InfoPopupId) -> Option<&mut InfoPopup> {
+		self.popups.get_mut(&id)
+	}
+
+	/// Returns the popup shown in `window_id`, if any.
+	pub fn by_window(&self, window_id: WindowId) -> Option<&InfoPopup> {
+		self.popups
+			.values()
+			.find(|popup| popup.window_id == window_id)
+	}
+
+	/// Returns all popups, oldest first.
+	pub fn ordered(&self) -> Vec<&InfoPopup> {
+		let mut popups: Vec<_> = self.popups.values().collect();
+		popups.sort_by_key(|popup| popup.id.0);
+		popups
+	}
+
+	/// Returns the most recently opened popup.
+	pub fn latest(&self) -> Option<&InfoPopup> {
+		self.popups.values().max_by_key(|popup| popup.id.0)
+	}
+
 	/// Returns an iterator over all popup IDs.
 	pub fn ids(&self) -> impl Iterator<Item = InfoPopupId> + '_ {
 		self.popups.keys().copied()
@@ -175,4 +517,50 @@ impl InfoPopupStore {
 	pub fn is_empty(&self) -> bool {
 		self.popups.is_empty()
 	}
+
+	/// Keeps drawing a closed popup's window until it has faded out.
+	pub fn push_exiting(&mut self, popup: ExitingPopup) {
+		self.exiting.push(popup);
+	}
+
+	/// Returns the closed popups still fading out, oldest first.
+	pub fn exiting(&self) -> &[ExitingPopup] {
+		&self.exiting
+	}
+
+	/// Returns how opaque the popup shown in `window_id` is drawn.
+	pub fn opacity(&self, window_id: WindowId) -> f32 {
+		self.by_window(window_id)
+			.map(|popup| popup.animation)
+			.or_else(|| {
+				self.exiting
+					.iter()
+					.find(|popup| popup.window.id == window_id)
+					.map(|popup| popup.animation)
+			})
+			.map_or(1.0, |animation| animation.opacity())
+	}
+
+	/// Advances every popup animation by `delta`, returning the buffers of
+	/// closed popups that finished fading out.
+	pub fn tick(&mut self, delta: Duration) -> Vec<BufferId> {
+		for popup in self.popups.values_mut() {
+			popup.animation.tick(delta);
+		}
+		let mut finished = Vec::new();
+		self.exiting.retain_mut(|popup| {
+			popup.animation.tick(delta);
+			let done = popup.animation.phase() == AnimationPhase::Finished;
+			if done {
+				finished.push(popup.window.buffer);
+			}
+			!done
+		});
+		finished
+	}
+
+	/// Returns whether any popup is fading in or out.
+	pub fn is_animating(&self) -> bool {
+		!self.exiting.is_empty() || self.popups.values().any(|p| p.animation.is_animating())
+	}
 }
@@ -39,6 +39,10 @@ pub mod overlay;
 pub mod palette;
 /// Platform-specific configuration paths.
 pub mod paths;
+/// Modal prompt and confirm popups.
+pub mod prompt;
+/// Regex search across the files of a directory.
+pub mod project_search;
 /// Rendering utilities for buffers, status line, and completion.
 pub mod render;
 /// Style utilities and conversions.
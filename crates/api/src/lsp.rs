@@ -3,6 +3,11 @@
 //! This module bridges the editor's buffer system with LSP functionality,
 //! providing document synchronization, diagnostics, and language features.
 //!
+//! A buffer's language may have several servers. Notifications go to all of
+//! them and their diagnostics are merged, while each request goes to the
+//! first server advertising the capability it needs, falling back to the
+//! next one if that server answers with an error.
+//!
 //! # Feature Flag
 //!
 //! This module is only available when the `lsp` feature is enabled:
@@ -41,22 +46,85 @@
 //! lsp.on_buffer_change(&buffer).await?;
 //! ```
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use tracing::debug;
 use xeno_base::LspDocumentChange;
 // Re-export for consumers
 pub use xeno_lsp::DiagnosticsEvent as LspDiagnosticsEvent;
 // Re-export types needed by consumers
 pub use xeno_lsp::LanguageServerConfig;
-use xeno_lsp::lsp_types::{TextDocumentSyncCapability, TextDocumentSyncKind};
+use xeno_lsp::lsp_types::{Position, Uri};
 use xeno_lsp::{
 	ClientHandle, DiagnosticsEvent, DiagnosticsEventReceiver, DocumentStateManager, DocumentSync,
-	OffsetEncoding, Registry, Result,
+	LanguageServerId, OffsetEncoding, Registry, Result, ServerCrash, ServerInfo, ServerLog,
+	ServerStatus, StoppedServer,
 };
 
 use crate::buffer::Buffer;
 
+/// How a typed character relates to signature help.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureHelpTrigger {
+	/// Opens signature help.
+	Trigger,
+	/// Refreshes signature help that is already showing.
+	Retrigger,
+}
+
+/// Which goto request to make about the symbol at the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GotoKind {
+	/// `textDocument/definition`.
+	Definition,
+	/// `textDocument/typeDefinition`.
+	TypeDefinition,
+	/// `textDocument/implementation`.
+	Implementation,
+	/// `textDocument/declaration`.
+	Declaration,
+}
+
+impl GotoKind {
+	/// Returns what the request looks for, as in "No definition found".
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Definition => "definition",
+			Self::TypeDefinition => "type definition",
+			Self::Implementation => "implementation",
+			Self::Declaration => "declaration",
+		}
+	}
+
+	/// Returns whether `client` answers this request.
+	fn supported_by(self, client: &ClientHandle) -> bool {
+		match self {
+			Self::Definition => client.supports_definition(),
+			Self::TypeDefinition => client.supports_type_definition(),
+			Self::Implementation => client.supports_implementation(),
+			Self::Declaration => client.supports_declaration(),
+		}
+	}
+}
+
+/// A language server's answer to a semantic tokens request.
+#[derive(Debug, Clone)]
+pub struct SemanticTokensAnswer {
+	/// Server that answered, which delta requests have to go back to.
+	pub server: LanguageServerId,
+	/// Legend the token types and modifiers index into.
+	pub legend: xeno_lsp::lsp_types::SemanticTokensLegend,
+	/// Offset encoding of the token columns.
+	pub encoding: OffsetEncoding,
+	/// The tokens, whole or as edits to the previous result.
+	pub result: xeno_lsp::lsp_types::SemanticTokensFullDeltaResult,
+}
+
+/// A language server a request about a buffer goes to, with the buffer's
+/// URI and cursor position in the server's offset encoding.
+type RequestTarget = (ClientHandle, Uri, Position);
+
 /// Central manager for LSP functionality.
 ///
 /// Coordinates language server lifecycle, document synchronization,
@@ -118,12 +186,21 @@ impl LspManager {
 		self.sync.documents().diagnostics_version()
 	}
 
-	/// Configure a language server.
+	/// Get the inlay hints version counter.
+	///
+	/// This counter increments every time a server asks for its inlay hints
+	/// to be refreshed.
+	pub fn inlay_hints_version(&self) -> u64 {
+		self.sync.documents().inlay_hints_version()
+	}
+
+	/// Configure a language server, after any already configured for the
+	/// language.
 	pub fn configure_server(&self, language: impl Into<String>, config: LanguageServerConfig) {
 		self.sync.registry().register(language, config);
 	}
 
-	/// Remove a language server configuration.
+	/// Remove the language server configurations of a language.
 	pub fn remove_server(&self, language: &str) {
 		self.sync.registry().unregister(language);
 	}
@@ -145,18 +222,19 @@ impl LspManager {
 
 	/// Called when a buffer is opened.
 	///
-	/// Starts the appropriate language server and opens the document.
-	pub async fn on_buffer_open(&self, buffer: &Buffer) -> Result<Option<ClientHandle>> {
+	/// Starts the language servers of the buffer's language and opens the
+	/// document with each, returning their clients.
+	pub async fn on_buffer_open(&self, buffer: &Buffer) -> Result<Vec<ClientHandle>> {
 		let Some(path) = buffer.path() else {
-			return Ok(None);
+			return Ok(Vec::new());
 		};
 
 		let Some(language) = &buffer.file_type() else {
-			return Ok(None);
+			return Ok(Vec::new());
 		};
 
 		if self.sync.registry().get_config(language).is_none() {
-			return Ok(None);
+			return Ok(Vec::new());
 		}
 
 		// Canonicalize path to absolute (required for LSP URIs)
@@ -165,11 +243,7 @@ impl LspManager {
 			.unwrap_or_else(|_| std::env::current_dir().unwrap_or_default().join(&path));
 
 		let content = buffer.doc().content.clone();
-		let client = self
-			.sync
-			.open_document(&abs_path, language, &content)
-			.await?;
-		Ok(Some(client))
+		self.sync.open_document(&abs_path, language, &content).await
 	}
 
 	/// Called when a buffer's content changes.
@@ -190,7 +264,10 @@ impl LspManager {
 
 	/// Called when a buffer's content changes incrementally.
 	///
-	/// Sends incremental document sync to the language server using pre-computed ranges.
+	/// Sends incremental document sync to the language servers using
+	/// pre-computed ranges, which must be in the encoding given by
+	/// [`Self::incremental_encoding_for_buffer`]. Falls back to a full sync
+	/// if no server takes incremental changes.
 	pub async fn on_buffer_change_incremental(
 		&self,
 		buffer: &Buffer,
@@ -205,9 +282,14 @@ impl LspManager {
 		};
 
 		let content = buffer.doc().content.clone();
-		self.sync
-			.notify_change_incremental(path, language, &content, changes)
-			.await
+		match self.incremental_encoding(path, language) {
+			Some(encoding) => {
+				self.sync
+					.notify_change_incremental(path, language, &content, changes, encoding)
+					.await
+			}
+			None => self.sync.notify_change_full(path, language, &content).await,
+		}
 	}
 
 	/// Called before a buffer is saved.
@@ -224,7 +306,7 @@ impl LspManager {
 	}
 
 	/// Called after a buffer is saved.
-	pub fn on_buffer_did_save(&self, buffer: &Buffer, include_text: bool) -> Result<()> {
+	pub fn on_buffer_did_save(&self, buffer: &Buffer) -> Result<()> {
 		let Some(path) = &buffer.path() else {
 			return Ok(());
 		};
@@ -233,14 +315,8 @@ impl LspManager {
 			return Ok(());
 		};
 
-		let doc = buffer.doc();
-		let text = if include_text {
-			Some(&doc.content)
-		} else {
-			None
-		};
 		self.sync
-			.notify_did_save(path, language, include_text, text)
+			.notify_did_save(path, language, &buffer.doc().content)
 	}
 
 	/// Called when a buffer is closed.
@@ -256,7 +332,7 @@ impl LspManager {
 		self.sync.close_document(path, language)
 	}
 
-	/// Get diagnostics for a buffer.
+	/// Get the diagnostics of every server for a buffer, merged.
 	pub fn get_diagnostics(&self, buffer: &Buffer) -> Vec<xeno_lsp::lsp_types::Diagnostic> {
 		buffer
 			.path()
@@ -265,6 +341,39 @@ impl LspManager {
 			.unwrap_or_default()
 	}
 
+	/// Get the diagnostics of every server for `path`, each tagged with its
+	/// server and paired with the offset encoding of its positions.
+	pub fn diagnostics_with_encoding(
+		&self,
+		path: &Path,
+	) -> Vec<(xeno_lsp::lsp_types::Diagnostic, OffsetEncoding)> {
+		self.sync.diagnostics_with_encoding(path)
+	}
+
+	/// Get the status of every language server attached to `buffer`, in
+	/// the order requests are routed to them.
+	pub fn server_statuses(&self, buffer: &Buffer) -> Vec<ServerStatus> {
+		let (Some(path), Some(language)) = (buffer.path(), buffer.file_type()) else {
+			return Vec::new();
+		};
+		self.sync
+			.registry()
+			.server_statuses(&language, &absolute_path(&path))
+	}
+
+	/// Get the offset encoding negotiated with the first language server of
+	/// `buffer`, or UTF-16, the LSP default, if no server handles it.
+	pub fn offset_encoding_for_buffer(&self, buffer: &Buffer) -> OffsetEncoding {
+		let (Some(path), Some(language)) = (buffer.path(), buffer.file_type()) else {
+			return OffsetEncoding::default();
+		};
+		self.sync
+			.registry()
+			.get(&language, &absolute_path(&path))
+			.map(|client| client.offset_encoding())
+			.unwrap_or_default()
+	}
+
 	/// Get error count for a buffer.
 	pub fn error_count(&self, buffer: &Buffer) -> usize {
 		buffer
@@ -293,17 +402,18 @@ impl LspManager {
 		self.sync.total_warning_count()
 	}
 
-	/// Prepare a position-based LSP request. Returns None if no client available.
-	fn prepare_position_request(
+	/// Prepare a request about `buffer` for each of its running servers
+	/// that advertises a capability, in configuration order, with the
+	/// cursor position in each server's encoding.
+	///
+	/// Returns `Ok(None)` if no server handles the buffer. If none of them
+	/// advertises the capability, only the first is returned, so the request
+	/// answers as unsupported.
+	fn request_targets(
 		&self,
 		buffer: &Buffer,
-	) -> Result<
-		Option<(
-			ClientHandle,
-			xeno_lsp::lsp_types::Uri,
-			xeno_lsp::lsp_types::Position,
-		)>,
-	> {
+		supports: impl Fn(&ClientHandle) -> bool,
+	) -> Result<Option<Vec<RequestTarget>>> {
 		let Some(path) = buffer.path() else {
 			return Ok(None);
 		};
@@ -311,81 +421,689 @@ impl LspManager {
 			return Ok(None);
 		};
 
-		let abs_path = path
-			.canonicalize()
-			.unwrap_or_else(|_| std::env::current_dir().unwrap_or_default().join(&path));
-
-		let Some(client) = self.sync.registry().get(&language, &abs_path) else {
+		let abs_path = absolute_path(&path);
+		let clients = self.sync.registry().get_all_for(&language, &abs_path);
+		let Some(first) = clients.first().cloned() else {
 			return Ok(None);
 		};
+		let mut clients: Vec<_> = clients.into_iter().filter(|c| supports(c)).collect();
+		if clients.is_empty() {
+			clients.push(first);
+		}
 
 		let uri = xeno_lsp::uri_from_path(&abs_path)
 			.ok_or_else(|| xeno_lsp::Error::Protocol("Invalid path".into()))?;
 
-		let encoding = client.offset_encoding();
-		let position =
-			xeno_lsp::char_to_lsp_position(&buffer.doc().content, buffer.cursor, encoding)
+		let doc = buffer.doc();
+		clients
+			.into_iter()
+			.map(|client| {
+				let position = xeno_lsp::char_to_lsp_position(
+					&doc.content,
+					buffer.cursor,
+					client.offset_encoding(),
+				)
 				.ok_or_else(|| xeno_lsp::Error::Protocol("Invalid position".into()))?;
-
-		Ok(Some((client, uri, position)))
+				Ok((client, uri.clone(), position))
+			})
+			.collect::<Result<_>>()
+			.map(Some)
 	}
 
 	/// Request hover information at the cursor position.
 	pub async fn hover(&self, buffer: &Buffer) -> Result<Option<xeno_lsp::lsp_types::Hover>> {
-		let Some((client, uri, position)) = self.prepare_position_request(buffer)? else {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_hover)? else {
+			return Ok(None);
+		};
+		first_answer(targets, |client, uri, position| async move {
+			client.hover(uri, position).await
+		})
+		.await
+	}
+
+	/// Start a request for completions at the cursor position.
+	///
+	/// Returns `Ok(None)` if no language server handles the buffer. Like
+	/// [`Self::signature_help`], the future runs in the background; it
+	/// resolves to the completions along with the offset encoding their
+	/// edits use.
+	pub fn completion(
+		&self,
+		buffer: &Buffer,
+	) -> Result<
+		Option<
+			impl Future<
+				Output = Result<Option<(xeno_lsp::lsp_types::CompletionResponse, OffsetEncoding)>>,
+			> + Send
+			+ use<>,
+		>,
+	> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_completion)? else {
 			return Ok(None);
 		};
-		client.hover(uri, position).await
+		Ok(Some(first_answer(
+			targets,
+			|client, uri, position| async move {
+				let encoding = client.offset_encoding();
+				let context = xeno_lsp::lsp_types::CompletionContext {
+					trigger_kind: xeno_lsp::lsp_types::CompletionTriggerKind::INVOKED,
+					trigger_character: None,
+				};
+				Ok(client
+					.completion(uri, position, Some(context))
+					.await?
+					.map(|response| (response, encoding)))
+			},
+		)))
 	}
 
-	/// Request completions at the cursor position.
-	pub async fn completion(
+	/// Start filling in the documentation, detail and additional edits of
+	/// a completion item the buffer's server listed.
+	///
+	/// Returns `Ok(None)` if no server handles the buffer or none resolves
+	/// completions, in which case the item is all there is.
+	pub fn resolve_completion(
 		&self,
 		buffer: &Buffer,
-	) -> Result<Option<xeno_lsp::lsp_types::CompletionResponse>> {
-		let Some((client, uri, position)) = self.prepare_position_request(buffer)? else {
+		item: xeno_lsp::lsp_types::CompletionItem,
+	) -> Result<
+		Option<impl Future<Output = Result<xeno_lsp::lsp_types::CompletionItem>> + Send + use<>>,
+	> {
+		let Some(targets) =
+			self.request_targets(buffer, ClientHandle::supports_completion_resolve)?
+		else {
 			return Ok(None);
 		};
-		client.completion(uri, position, None).await
+		if !targets
+			.iter()
+			.any(|(client, _, _)| client.supports_completion_resolve())
+		{
+			return Ok(None);
+		}
+		Ok(Some(first_answer(targets, move |client, _, _| {
+			let item = item.clone();
+			async move { client.completion_resolve(item).await }
+		})))
 	}
 
-	/// Request go to definition at the cursor position.
-	pub async fn goto_definition(
+	/// Request the locations of the symbol at the cursor that `kind` goes
+	/// to.
+	///
+	/// Returns the response along with the offset encoding its positions use.
+	pub async fn goto(
 		&self,
 		buffer: &Buffer,
-	) -> Result<Option<xeno_lsp::lsp_types::GotoDefinitionResponse>> {
-		let Some((client, uri, position)) = self.prepare_position_request(buffer)? else {
+		kind: GotoKind,
+	) -> Result<Option<(xeno_lsp::lsp_types::GotoDefinitionResponse, OffsetEncoding)>> {
+		let Some(targets) = self.request_targets(buffer, |client| kind.supported_by(client))?
+		else {
 			return Ok(None);
 		};
-		client.goto_definition(uri, position).await
+		first_answer(targets, |client, uri, position| async move {
+			let encoding = client.offset_encoding();
+			let response = match kind {
+				GotoKind::Definition => client.goto_definition(uri, position).await?,
+				GotoKind::TypeDefinition => client.goto_type_definition(uri, position).await?,
+				GotoKind::Implementation => client.goto_implementation(uri, position).await?,
+				GotoKind::Declaration => client.goto_declaration(uri, position).await?,
+			};
+			Ok(response.map(|response| (response, encoding)))
+		})
+		.await
 	}
 
 	/// Request references at the cursor position.
+	///
+	/// Returns the locations along with the offset encoding their positions
+	/// use.
 	pub async fn references(
 		&self,
 		buffer: &Buffer,
 		include_declaration: bool,
-	) -> Result<Option<Vec<xeno_lsp::lsp_types::Location>>> {
-		let Some((client, uri, position)) = self.prepare_position_request(buffer)? else {
+	) -> Result<Option<(Vec<xeno_lsp::lsp_types::Location>, OffsetEncoding)>> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_references)? else {
 			return Ok(None);
 		};
-		client.references(uri, position, include_declaration).await
+		first_answer(targets, |client, uri, position| async move {
+			let encoding = client.offset_encoding();
+			Ok(client
+				.references(uri, position, include_declaration)
+				.await?
+				.map(|locations| (locations, encoding)))
+		})
+		.await
 	}
 
-	/// Request formatting for the entire document.
+	/// Start a request for the symbols matching `query` across the
+	/// workspace, from the first language server of `buffer` that searches
+	/// workspace symbols.
+	///
+	/// Returns `None` if no language server handles the buffer. Otherwise
+	/// returns the offset encoding of the symbols' positions and a future
+	/// that, like [`Self::signature_help`], runs in the background. Batches
+	/// the server streams ahead of its response go to `on_partial`, and
+	/// dropping the future cancels the request. It resolves to `None` if the
+	/// server does not search workspace symbols. As batches may already have
+	/// arrived, there is no fallback to another server on error.
+	pub fn workspace_symbols<F>(
+		&self,
+		buffer: &Buffer,
+		query: String,
+		on_partial: F,
+	) -> Option<(
+		OffsetEncoding,
+		impl Future<Output = Result<Option<xeno_lsp::lsp_types::WorkspaceSymbolResponse>>>
+		+ Send
+		+ use<F>,
+	)>
+	where
+		F: Fn(xeno_lsp::lsp_types::WorkspaceSymbolResponse) + Send + Sync + 'static,
+	{
+		let (client, _, _) = self
+			.request_targets(buffer, ClientHandle::supports_workspace_symbol)
+			.ok()??
+			.into_iter()
+			.next()?;
+		let encoding = client.offset_encoding();
+		Some((encoding, async move {
+			client.workspace_symbol_streaming(query, on_partial).await
+		}))
+	}
+
+	/// Get the diagnostics of every file that has any, by path, each tagged
+	/// with its server and paired with the offset encoding of its positions.
+	pub fn all_diagnostics(
+		&self,
+	) -> Vec<(
+		PathBuf,
+		Vec<(xeno_lsp::lsp_types::Diagnostic, OffsetEncoding)>,
+	)> {
+		self.sync
+			.all_diagnostics_with_encoding()
+			.into_iter()
+			.filter_map(|(uri, diagnostics)| Some((xeno_lsp::path_from_uri(&uri)?, diagnostics)))
+			.collect()
+	}
+
+	/// Start a signature help request at the cursor position.
+	///
+	/// The returned future owns everything it needs, so it can run in the
+	/// background while editing goes on. Its result comes with the offset
+	/// encoding of the parameter label offsets in it.
+	pub fn signature_help(
+		&self,
+		buffer: &Buffer,
+		context: Option<xeno_lsp::lsp_types::SignatureHelpContext>,
+	) -> Result<
+		Option<
+			impl Future<Output = Result<Option<(xeno_lsp::lsp_types::SignatureHelp, OffsetEncoding)>>>
+			+ Send
+			+ use<>,
+		>,
+	> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_signature_help)?
+		else {
+			return Ok(None);
+		};
+		Ok(Some(first_answer(targets, move |client, uri, position| {
+			let context = context.clone();
+			async move {
+				let encoding = client.offset_encoding();
+				Ok(client
+					.signature_help(uri, position, context)
+					.await?
+					.map(|help| (help, encoding)))
+			}
+		})))
+	}
+
+	/// Start a request for the occurrences of the symbol at the cursor.
+	///
+	/// Returns `Ok(None)` if no language server handles the buffer. Like
+	/// [`Self::signature_help`], the future runs in the background; it
+	/// resolves to `None` if the server does not highlight symbols.
+	pub fn document_highlight(
+		&self,
+		buffer: &Buffer,
+	) -> Result<
+		Option<
+			impl Future<
+				Output = Result<
+					Option<(Vec<xeno_lsp::lsp_types::DocumentHighlight>, OffsetEncoding)>,
+				>,
+			> + Send
+			+ use<>,
+		>,
+	> {
+		let Some(targets) =
+			self.request_targets(buffer, ClientHandle::supports_document_highlight)?
+		else {
+			return Ok(None);
+		};
+		Ok(Some(first_answer(
+			targets,
+			|client, uri, position| async move {
+				let encoding = client.offset_encoding();
+				Ok(client
+					.document_highlight(uri, position)
+					.await?
+					.map(|highlights| (highlights, encoding)))
+			},
+		)))
+	}
+
+	/// Start a request for the symbols of `buffer`.
+	///
+	/// Returns `Ok(None)` if no language server handles the buffer. Like
+	/// [`Self::signature_help`], the future runs in the background; it
+	/// resolves to `None` if the server does not list document symbols.
+	pub fn document_symbols(
+		&self,
+		buffer: &Buffer,
+	) -> Result<
+		Option<
+			impl Future<
+				Output = Result<
+					Option<(xeno_lsp::lsp_types::DocumentSymbolResponse, OffsetEncoding)>,
+				>,
+			> + Send
+			+ use<>,
+		>,
+	> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_document_symbol)?
+		else {
+			return Ok(None);
+		};
+		Ok(Some(first_answer(targets, |client, uri, _| async move {
+			let encoding = client.offset_encoding();
+			Ok(client
+				.document_symbol(uri)
+				.await?
+				.map(|symbols| (symbols, encoding)))
+		})))
+	}
+
+	/// Start a request for the folding ranges of `buffer`.
+	///
+	/// Returns `Ok(None)` if no language server handles the buffer. Like
+	/// [`Self::signature_help`], the future runs in the background; it
+	/// resolves to `None` if the server does not provide folding ranges.
+	pub fn folding_ranges(
+		&self,
+		buffer: &Buffer,
+	) -> Result<
+		Option<
+			impl Future<Output = Result<Option<Vec<xeno_lsp::lsp_types::FoldingRange>>>> + Send + use<>,
+		>,
+	> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_folding_range)?
+		else {
+			return Ok(None);
+		};
+		Ok(Some(first_answer(targets, |client, uri, _| async move {
+			client.folding_ranges(uri).await
+		})))
+	}
+
+	/// Start a request for the semantic tokens of `buffer`.
+	///
+	/// With `previous`, the server and result id of the tokens last
+	/// received, that server is asked only for the changes since, if it can
+	/// send them. Returns `Ok(None)` if no language server handles the
+	/// buffer. Like [`Self::signature_help`], the future runs in the
+	/// background; it resolves to `None` if the server does not provide
+	/// semantic tokens.
+	pub fn semantic_tokens(
+		&self,
+		buffer: &Buffer,
+		previous: Option<(LanguageServerId, String)>,
+	) -> Result<Option<impl Future<Output = Result<Option<SemanticTokensAnswer>>> + Send + use<>>>
+	{
+		use xeno_lsp::lsp_types::{
+			SemanticTokens, SemanticTokensFullDeltaResult, SemanticTokensResult,
+		};
+
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_semantic_tokens)?
+		else {
+			return Ok(None);
+		};
+		Ok(Some(first_answer(targets, move |client, uri, _| {
+			let previous = previous
+				.clone()
+				.filter(|(server, _)| {
+					*server == client.id() && client.supports_semantic_tokens_delta()
+				})
+				.map(|(_, result_id)| result_id);
+			async move {
+				let result = match previous {
+					Some(result_id) => client.semantic_tokens_full_delta(uri, result_id).await?,
+					None => client
+						.semantic_tokens_full(uri)
+						.await?
+						.map(|result| match result {
+							SemanticTokensResult::Tokens(tokens) => {
+								SemanticTokensFullDeltaResult::Tokens(tokens)
+							}
+							SemanticTokensResult::Partial(partial) => {
+								SemanticTokensFullDeltaResult::Tokens(SemanticTokens {
+									result_id: None,
+									data: partial.data,
+								})
+							}
+						}),
+				};
+				let Some(legend) = client.semantic_tokens_legend().cloned() else {
+					return Ok(None);
+				};
+				Ok(result.map(|result| SemanticTokensAnswer {
+					server: client.id(),
+					legend,
+					encoding: client.offset_encoding(),
+					result,
+				}))
+			}
+		})))
+	}
+
+	/// Start a request for the inlay hints over the characters in `range`.
+	///
+	/// Returns `Ok(None)` if no language server handles the buffer. Like
+	/// [`Self::signature_help`], the future runs in the background; it
+	/// resolves to `None` if the server does not provide inlay hints, and
+	/// otherwise comes with the offset encoding of the hint positions.
+	pub fn inlay_hints(
+		&self,
+		buffer: &Buffer,
+		range: std::ops::Range<usize>,
+	) -> Result<
+		Option<
+			impl Future<Output = Result<Option<(Vec<xeno_lsp::lsp_types::InlayHint>, OffsetEncoding)>>>
+			+ Send
+			+ use<>,
+		>,
+	> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_inlay_hint)? else {
+			return Ok(None);
+		};
+		let targets = targets
+			.into_iter()
+			.map(|(client, uri, _)| {
+				let range = xeno_lsp::char_range_to_lsp_range(
+					&buffer.doc().content,
+					range.start,
+					range.end,
+					client.offset_encoding(),
+				)
+				.ok_or_else(|| xeno_lsp::Error::Protocol("Invalid range".into()))?;
+				Ok((client, uri, range))
+			})
+			.collect::<Result<Vec<_>>>()?;
+		Ok(Some(first_answer(
+			targets,
+			|client, uri, range| async move {
+				let encoding = client.offset_encoding();
+				Ok(client
+					.inlay_hints(uri, range)
+					.await?
+					.map(|hints| (hints, encoding)))
+			},
+		)))
+	}
+
+	/// Returns how typing `c` in `buffer` relates to signature help, or
+	/// `None` if the server signature help goes to does not react to it.
+	pub fn signature_help_trigger(&self, buffer: &Buffer, c: char) -> Option<SignatureHelpTrigger> {
+		let (client, _, _) = self
+			.request_targets(buffer, ClientHandle::supports_signature_help)
+			.ok()??
+			.into_iter()
+			.next()?;
+		let is = |chars: &[String]| chars.iter().any(|s| s.chars().eq([c]));
+		if is(client.signature_help_trigger_characters()) {
+			Some(SignatureHelpTrigger::Trigger)
+		} else if is(client.signature_help_retrigger_characters()) {
+			Some(SignatureHelpTrigger::Retrigger)
+		} else {
+			None
+		}
+	}
+
+	/// Check that the symbol at the cursor position can be renamed, returning
+	/// the name a rename should start from.
+	///
+	/// Asks the server a rename goes to. Returns `Ok(None)` if no server
+	/// handles the buffer, it cannot check rename positions, or it leaves
+	/// picking the name to the editor.
+	pub async fn prepare_rename(&self, buffer: &Buffer) -> Result<Option<String>> {
+		use xeno_lsp::lsp_types::PrepareRenameResponse;
+
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_rename)? else {
+			return Ok(None);
+		};
+		let answer = first_answer(targets, |client, uri, position| async move {
+			let encoding = client.offset_encoding();
+			Ok(client
+				.prepare_rename(uri, position)
+				.await?
+				.map(|response| (response, encoding)))
+		})
+		.await?;
+		let Some((response, encoding)) = answer else {
+			return Ok(None);
+		};
+		Ok(match response {
+			PrepareRenameResponse::Range(range) => {
+				let doc = buffer.doc();
+				let (start, end) = xeno_lsp::lsp_range_to_char_range(&doc.content, range, encoding);
+				Some(doc.content.slice(start..end).to_string())
+			}
+			PrepareRenameResponse::RangeWithPlaceholder { placeholder, .. } => Some(placeholder),
+			PrepareRenameResponse::DefaultBehavior { .. } => None,
+		})
+	}
+
+	/// Request a rename of the symbol at the cursor position to `new_name`.
+	///
+	/// Returns the edit along with the offset encoding its positions use.
+	pub async fn rename(
+		&self,
+		buffer: &Buffer,
+		new_name: String,
+	) -> Result<Option<(xeno_lsp::lsp_types::WorkspaceEdit, OffsetEncoding)>> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_rename)? else {
+			return Ok(None);
+		};
+		first_answer(targets, |client, uri, position| {
+			let new_name = new_name.clone();
+			async move {
+				let encoding = client.offset_encoding();
+				Ok(client
+					.rename(uri, position, new_name)
+					.await?
+					.map(|edit| (edit, encoding)))
+			}
+		})
+		.await
+	}
+
+	/// Request formatting for the document, or for the characters in `range`.
+	///
+	/// Returns the edits along with the offset encoding their positions use,
+	/// or `Ok(None)` if no server handles the buffer or it cannot format.
 	pub async fn format(
 		&self,
 		buffer: &Buffer,
-	) -> Result<Option<Vec<xeno_lsp::lsp_types::TextEdit>>> {
-		let Some((client, uri, _)) = self.prepare_position_request(buffer)? else {
+		options: xeno_lsp::lsp_types::FormattingOptions,
+		range: Option<std::ops::Range<usize>>,
+	) -> Result<Option<(Vec<xeno_lsp::lsp_types::TextEdit>, OffsetEncoding)>> {
+		let supports = |client: &ClientHandle| match range {
+			Some(_) => client.supports_range_formatting(),
+			None => client.supports_formatting(),
+		};
+		let Some(targets) = self.request_targets(buffer, supports)? else {
 			return Ok(None);
 		};
-		let options = xeno_lsp::lsp_types::FormattingOptions {
-			tab_size: 4,
-			insert_spaces: false,
-			..Default::default()
+		let content = buffer.doc().content.clone();
+		first_answer(targets, |client, uri, _| {
+			let (options, range, content) = (options.clone(), range.clone(), &content);
+			async move {
+				let encoding = client.offset_encoding();
+				let edits = match range {
+					Some(range) => {
+						let range = xeno_lsp::char_range_to_lsp_range(
+							content,
+							range.start,
+							range.end,
+							encoding,
+						)
+						.ok_or_else(|| xeno_lsp::Error::Protocol("Invalid range".into()))?;
+						client.range_formatting(uri, range, options).await?
+					}
+					None => client.formatting(uri, options).await?,
+				};
+				Ok(edits.map(|edits| (edits, encoding)))
+			}
+		})
+		.await
+	}
+
+	/// Request the edits to make to the document before it is saved.
+	///
+	/// Returns the edits along with the offset encoding their positions use,
+	/// or `Ok(None)` if no server handles the buffer or supports
+	/// `willSaveWaitUntil`.
+	pub async fn will_save_wait_until(
+		&self,
+		buffer: &Buffer,
+	) -> Result<Option<(Vec<xeno_lsp::lsp_types::TextEdit>, OffsetEncoding)>> {
+		use xeno_lsp::lsp_types::TextDocumentSaveReason;
+
+		let Some(targets) =
+			self.request_targets(buffer, ClientHandle::supports_will_save_wait_until)?
+		else {
+			return Ok(None);
 		};
-		client.formatting(uri, options).await
+		first_answer(targets, |client, uri, _| async move {
+			let edits = client
+				.will_save_wait_until(uri, TextDocumentSaveReason::MANUAL)
+				.await?;
+			Ok(edits.map(|edits| (edits, client.offset_encoding())))
+		})
+		.await
+	}
+
+	/// Request the code actions for the characters in `range`, passing the
+	/// diagnostics the server published over it as context.
+	///
+	/// Returns the actions along with the offset encoding their edits use,
+	/// or `Ok(None)` if no server handles the buffer or it has no code
+	/// actions.
+	pub async fn code_actions(
+		&self,
+		buffer: &Buffer,
+		range: std::ops::Range<usize>,
+	) -> Result<Option<(xeno_lsp::lsp_types::CodeActionResponse, OffsetEncoding)>> {
+		use xeno_lsp::lsp_types::{CodeActionContext, CodeActionTriggerKind};
+
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_code_action)?
+		else {
+			return Ok(None);
+		};
+		let key = |p: Position| (p.line, p.character);
+		let targets = targets
+			.into_iter()
+			.map(|(client, uri, _)| {
+				let range = xeno_lsp::char_range_to_lsp_range(
+					&buffer.doc().content,
+					range.start,
+					range.end,
+					client.offset_encoding(),
+				)
+				.ok_or_else(|| xeno_lsp::Error::Protocol("Invalid range".into()))?;
+				// Each server only gets back the diagnostics it published.
+				let diagnostics = self
+					.documents()
+					.get_diagnostics_by_server(&uri)
+					.into_iter()
+					.filter(|(server_id, _)| *server_id == client.id())
+					.flat_map(|(_, diagnostics)| diagnostics)
+					.filter(|d| {
+						key(d.range.start) <= key(range.end) && key(d.range.end) >= key(range.start)
+					})
+					.collect();
+				let context = CodeActionContext {
+					diagnostics,
+					only: None,
+					trigger_kind: Some(CodeActionTriggerKind::INVOKED),
+				};
+				Ok((client, uri, (range, context)))
+			})
+			.collect::<Result<Vec<_>>>()?;
+		first_answer(targets, |client, uri, (range, context)| async move {
+			let encoding = client.offset_encoding();
+			Ok(client
+				.code_action(uri, range, context)
+				.await?
+				.map(|actions| (actions, encoding)))
+		})
+		.await
+	}
+
+	/// Fill in the edit of a code action the buffer's server listed without
+	/// one.
+	///
+	/// Returns the action unchanged if no server can resolve actions.
+	pub async fn resolve_code_action(
+		&self,
+		buffer: &Buffer,
+		action: xeno_lsp::lsp_types::CodeAction,
+	) -> Result<xeno_lsp::lsp_types::CodeAction> {
+		match self.request_targets(buffer, ClientHandle::supports_code_action_resolve)? {
+			Some(targets) => {
+				first_answer(targets, |client, _, _| {
+					let action = action.clone();
+					async move { client.code_action_resolve(action).await }
+				})
+				.await
+			}
+			None => Ok(action),
+		}
+	}
+
+	/// Run a command on the buffer's servers that run commands, trying
+	/// those that list it first.
+	///
+	/// Returns `Ok(false)` if no server handles the buffer or none can run
+	/// commands.
+	pub async fn execute_command(
+		&self,
+		buffer: &Buffer,
+		command: xeno_lsp::lsp_types::Command,
+	) -> Result<bool> {
+		let Some(targets) = self.request_targets(buffer, ClientHandle::supports_execute_command)?
+		else {
+			return Ok(false);
+		};
+		let lists = |client: &ClientHandle| {
+			client
+				.try_capabilities()
+				.and_then(|c| c.execute_command_provider.as_ref())
+				.is_some_and(|provider| provider.commands.contains(&command.command))
+		};
+		let (mut targets, others): (Vec<_>, Vec<_>) = targets
+			.into_iter()
+			.filter(|(client, _, _)| client.supports_execute_command())
+			.partition(|(client, _, _)| lists(client));
+		targets.extend(others);
+		if targets.is_empty() {
+			return Ok(false);
+		}
+		first_answer(targets, |client, _, _| {
+			let command = command.clone();
+			async move { client.execute_command(command).await }
+		})
+		.await?;
+		Ok(true)
 	}
 
 	/// Shutdown all language servers.
@@ -393,6 +1111,71 @@ impl LspManager {
 		self.sync.registry().shutdown_all().await;
 	}
 
+	/// Stop the language servers of `buffer`, or only the one named, until
+	/// restarted.
+	///
+	/// Returns the names of the servers stopped.
+	pub async fn stop_servers(&self, buffer: &Buffer, name: Option<&str>) -> Result<Vec<String>> {
+		let (path, language) = server_scope(buffer)?;
+		let stopped = self.sync.registry().stop(&language, &path, name).await?;
+		Ok(self.forget_stopped(stopped))
+	}
+
+	/// Stop the language servers of `buffer`, or only the one named, so
+	/// they start afresh when the buffer's language's documents are next
+	/// opened.
+	///
+	/// Returns the names of the servers stopped.
+	pub async fn restart_servers(
+		&self,
+		buffer: &Buffer,
+		name: Option<&str>,
+	) -> Result<Vec<String>> {
+		let (path, language) = server_scope(buffer)?;
+		let stopped = self.sync.registry().restart(&language, &path, name).await?;
+		Ok(self.forget_stopped(stopped))
+	}
+
+	/// Collect the language servers that crashed since the last call,
+	/// forgetting what each knew of the documents.
+	pub fn poll_crashes(&self) -> Vec<ServerCrash> {
+		let crashes = self.sync.registry().poll_crashes();
+		for id in crashes.iter().filter_map(|crash| crash.id) {
+			self.documents().forget_server(id);
+		}
+		crashes
+	}
+
+	/// Take the crashed language servers due to restart, as the language
+	/// and root whose documents should be opened again to start them.
+	pub fn due_restarts(&self) -> Vec<(String, PathBuf)> {
+		self.sync.registry().due_restarts()
+	}
+
+	/// Describe every language server started or held back.
+	pub fn server_infos(&self) -> Vec<ServerInfo> {
+		self.sync.registry().server_infos()
+	}
+
+	/// Get the log of every language server's stderr and protocol trace.
+	pub fn server_log(&self) -> &Arc<ServerLog> {
+		self.sync.registry().log()
+	}
+
+	/// Forgets what stopped servers knew of the documents, returning their
+	/// names.
+	fn forget_stopped(&self, stopped: Vec<StoppedServer>) -> Vec<String> {
+		stopped
+			.into_iter()
+			.map(|server| {
+				if let Some(id) = server.id {
+					self.documents().forget_server(id);
+				}
+				server.name
+			})
+			.collect()
+	}
+
 	/// Returns the server encoding if incremental sync is supported.
 	pub fn incremental_encoding_for_buffer(&self, buffer: &Buffer) -> Option<OffsetEncoding> {
 		let path = buffer.path()?;
@@ -400,25 +1183,60 @@ impl LspManager {
 		self.incremental_encoding(&path, &language)
 	}
 
+	/// Returns the encoding of the first server of `language` taking
+	/// incremental changes. Other servers get the whole text, unless they
+	/// share the encoding and take incremental changes too.
 	fn incremental_encoding(&self, path: &Path, language: &str) -> Option<OffsetEncoding> {
-		let client = self.sync.registry().get(language, path)?;
-		let caps = client.try_capabilities()?;
-		let supports_incremental = match &caps.text_document_sync {
-			Some(TextDocumentSyncCapability::Kind(kind)) => {
-				*kind == TextDocumentSyncKind::INCREMENTAL
-			}
-			Some(TextDocumentSyncCapability::Options(options)) => {
-				matches!(options.change, Some(TextDocumentSyncKind::INCREMENTAL))
-			}
-			None => false,
-		};
+		self.sync
+			.registry()
+			.get_all_for(language, path)
+			.into_iter()
+			.find(ClientHandle::supports_incremental_sync)
+			.map(|client| client.offset_encoding())
+	}
+}
 
-		if supports_incremental {
-			Some(client.offset_encoding())
-		} else {
-			None
+/// Returns the absolute path and language of `buffer`, which pick its
+/// language servers.
+fn server_scope(buffer: &Buffer) -> Result<(PathBuf, String)> {
+	match (buffer.path(), buffer.file_type()) {
+		(Some(path), Some(language)) => Ok((absolute_path(&path), language)),
+		_ => Err(xeno_lsp::Error::Protocol(
+			"No language server for this buffer".into(),
+		)),
+	}
+}
+
+/// Returns `path` made absolute, as LSP URIs require.
+fn absolute_path(path: &Path) -> PathBuf {
+	path.canonicalize()
+		.unwrap_or_else(|_| std::env::current_dir().unwrap_or_default().join(path))
+}
+
+/// Sends a request to each target in turn until one answers without an
+/// error.
+///
+/// Returns the first answer, or the last error if every target failed.
+async fn first_answer<A, B, T, F, Fut>(
+	targets: Vec<(ClientHandle, A, B)>,
+	mut request: F,
+) -> Result<T>
+where
+	F: FnMut(ClientHandle, A, B) -> Fut,
+	Fut: Future<Output = Result<T>>,
+{
+	let mut error = None;
+	for (client, a, b) in targets {
+		let server = client.name().to_string();
+		match request(client, a, b).await {
+			Ok(answer) => return Ok(answer),
+			Err(e) => {
+				debug!(server = %server, error = %e, "LSP request failed, trying the next server");
+				error = Some(e);
+			}
 		}
 	}
+	Err(error.unwrap_or_else(|| xeno_lsp::Error::Protocol("No language server".into())))
 }
 
 impl Default for LspManager {
@@ -1,5 +1,7 @@
 //! Application menu bar.
 
+use xeno_core::get_keymap_registry;
+use xeno_registry::BindingMode;
 use xeno_tui::widgets::menu::{MenuEvent, MenuItem, MenuState};
 
 /// Action triggered by menu item selection.
@@ -10,9 +12,14 @@ pub enum MenuAction {
 }
 
 /// Creates the default application menu bar.
+///
+/// Items show their `shortcut`, or else the normal-mode keys bound to the
+/// action of the same name as their command, as a keybinding hint.
 pub fn create_menu() -> MenuState<MenuAction> {
 	use xeno_registry::menus::{MENU_GROUPS, MENU_ITEMS};
 
+	let keymap = get_keymap_registry();
+
 	let mut groups: Vec<_> = MENU_GROUPS.iter().collect();
 	groups.sort_by_key(|group| group.priority);
 
@@ -28,9 +35,23 @@ pub fn create_menu() -> MenuState<MenuAction> {
 			let children = items
 				.into_iter()
 				.map(|item| {
-					let menu_item = MenuItem::item(item.label, MenuAction::Command(item.command));
-					match item.icon {
-						Some(codepoint) => menu_item.icon_codepoint(codepoint),
+					let mut menu_item =
+						MenuItem::item(item.label, MenuAction::Command(item.command));
+					if let Some(codepoint) = item.icon {
+						menu_item = menu_item.icon_codepoint(codepoint);
+					}
+					let hint = item.shortcut.map(String::from).or_else(|| {
+						keymap
+							.keys_for_action(BindingMode::Normal, item.command)
+							.map(|keys| {
+								keys.iter()
+									.map(ToString::to_string)
+									.collect::<Vec<_>>()
+									.join(" ")
+							})
+					});
+					match hint {
+						Some(hint) => menu_item.hint(hint),
 						None => menu_item,
 					}
 				})
@@ -2,7 +2,13 @@
 //!
 //! The palette uses a scratch buffer as its input field, providing familiar
 //! text editing controls. Commands are parsed and executed on Enter.
+//!
+//! The same input doubles as the search prompt, which previews matches in
+//! the searched buffer while the pattern is typed.
 
 mod state;
 
-pub use state::{Palette, PaletteState, palette_rect, palette_style};
+pub use state::{
+	Palette, PaletteKind, PaletteState, PreviewStatus, SearchPrompt, ViewOrigin, palette_rect,
+	palette_style,
+};
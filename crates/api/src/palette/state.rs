@@ -1,10 +1,12 @@
 //! Command palette state and lifecycle.
 
-use xeno_tui::layout::Rect;
+use xeno_base::Range;
+use xeno_base::range::CharIdx;
+use xeno_tui::layout::{HorizontalAlignment, Rect};
 use xeno_tui::widgets::BorderType;
-use xeno_tui::widgets::block::Padding;
 
 use crate::buffer::BufferId;
+use crate::prompt::PromptPopup;
 use crate::window::{FloatingStyle, WindowId};
 
 /// Active command palette instance.
@@ -14,6 +16,74 @@ pub struct Palette {
 	pub window_id: WindowId,
 	/// The scratch buffer used for input.
 	pub buffer_id: BufferId,
+	/// What the input is used for.
+	pub kind: PaletteKind,
+}
+
+/// What a palette's input is used for.
+#[derive(Debug)]
+pub enum PaletteKind {
+	/// A command line, executed on Enter.
+	Command,
+	/// A search pattern, previewed while typed.
+	Search(SearchPrompt),
+	/// An answer to a [`PromptPopup`], checked while typed.
+	Prompt(PromptPopup),
+}
+
+/// Incremental search state for a palette opened as a search prompt.
+#[derive(Debug)]
+pub struct SearchPrompt {
+	/// Buffer the pattern is searched in and previewed on.
+	pub target: BufferId,
+	/// Whether the search runs backward from the cursor.
+	pub reverse: bool,
+	/// The target's view when the prompt opened, restored on cancel.
+	pub origin: ViewOrigin,
+	/// Match currently shown in the target view.
+	pub preview: Option<Range>,
+	/// Outcome of previewing the current input.
+	pub status: PreviewStatus,
+}
+
+/// A buffer's cursor and scroll position, saved so it can be restored exactly.
+#[derive(Debug, Clone, Copy)]
+pub struct ViewOrigin {
+	/// Primary cursor position.
+	pub cursor: CharIdx,
+	/// First visible line.
+	pub scroll_line: usize,
+	/// First visible wrap segment of that line.
+	pub scroll_segment: usize,
+	/// First visible column of unwrapped lines.
+	pub left_col: usize,
+	/// Whether the viewport was detached from the cursor.
+	pub viewport_detached: bool,
+}
+
+/// Outcome of previewing a search prompt's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PreviewStatus {
+	/// The input is empty.
+	#[default]
+	Empty,
+	/// A match is previewed.
+	Match,
+	/// The pattern is valid but matches nothing.
+	NoMatch,
+	/// The pattern does not compile.
+	InvalidRegex,
+}
+
+impl PreviewStatus {
+	/// Returns the inline indicator shown on the prompt, if any.
+	pub fn indicator(self) -> Option<&'static str> {
+		match self {
+			Self::Empty | Self::Match => None,
+			Self::NoMatch => Some("no match"),
+			Self::InvalidRegex => Some("invalid regex"),
+		}
+	}
 }
 
 /// Palette lifecycle state.
@@ -49,6 +119,45 @@ impl PaletteState {
 	pub fn buffer_id(&self) -> Option<BufferId> {
 		self.active().map(|p| p.buffer_id)
 	}
+
+	/// Returns the search prompt state if the palette is open for search.
+	pub fn search(&self) -> Option<&SearchPrompt> {
+		match self.active()?.kind {
+			PaletteKind::Search(ref prompt) => Some(prompt),
+			PaletteKind::Command | PaletteKind::Prompt(_) => None,
+		}
+	}
+
+	/// Returns the prompt popup if the palette is open for one.
+	pub fn prompt(&self) -> Option<&PromptPopup> {
+		match self.active()?.kind {
+			PaletteKind::Prompt(ref prompt) => Some(prompt),
+			PaletteKind::Command | PaletteKind::Search(_) => None,
+		}
+	}
+
+	/// Returns the prompt popup mutably if the palette is open for one.
+	pub fn prompt_mut(&mut self) -> Option<&mut PromptPopup> {
+		match self {
+			Self::Open(Palette {
+				kind: PaletteKind::Prompt(prompt),
+				..
+			}) => Some(prompt),
+			_ => None,
+		}
+	}
+
+	/// Returns the search prompt state mutably if the palette is open for
+	/// search.
+	pub fn search_mut(&mut self) -> Option<&mut SearchPrompt> {
+		match self {
+			Self::Open(Palette {
+				kind: PaletteKind::Search(prompt),
+				..
+			}) => Some(prompt),
+			_ => None,
+		}
+	}
 }
 
 /// Default floating style for the command palette.
@@ -56,9 +165,10 @@ pub fn palette_style() -> FloatingStyle {
 	FloatingStyle {
 		border: true,
 		border_type: BorderType::Stripe,
-		padding: Padding::horizontal(1),
+		padding: None,
 		shadow: false,
 		title: None,
+		title_alignment: HorizontalAlignment::Left,
 	}
 }
 
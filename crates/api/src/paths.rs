@@ -23,3 +23,13 @@ pub fn get_data_dir() -> Option<PathBuf> {
 pub fn get_cache_dir() -> Option<PathBuf> {
 	dirs::cache_dir().map(|p| p.join(APP_DIR))
 }
+
+/// Returns the platform-specific state directory for xeno.
+///
+/// Uses XDG base directories: `$XDG_STATE_HOME/xeno` (~/.local/state/xeno on Linux),
+/// falling back to the local data directory on platforms without one.
+pub fn get_state_dir() -> Option<PathBuf> {
+	dirs::state_dir()
+		.or_else(dirs::data_local_dir)
+		.map(|p| p.join(APP_DIR))
+}
@@ -0,0 +1,430 @@
+//! Searching the files below a directory for a regex.
+//!
+//! A search runs on its own thread and streams one [`SearchEvent::File`]
+//! per file with matches, tagged with the generation it was started with so
+//! the receiver can drop results of a query that has since changed. The
+//! internal backend walks the tree itself and matches with the same rope
+//! searcher as in-buffer search; the `rg` backend runs ripgrep and reads its
+//! JSON output.
+
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::{fs, thread};
+
+use serde_json::Value;
+use xeno_base::Rope;
+use xeno_core::movement::{self, Regex, SearchCase};
+
+use crate::ui::files::{IgnoreRule, is_ignored, parse_gitignore};
+
+/// Most matching lines a search reports before stopping.
+pub const MAX_MATCHES: usize = 10_000;
+
+/// Files larger than this are skipped by the internal backend.
+const MAX_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// How a search finds matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchBackend {
+	/// Walk the directory and search each file's rope.
+	#[default]
+	Internal,
+	/// Run `rg --json`.
+	Ripgrep,
+}
+
+impl SearchBackend {
+	/// Parses the `search-backend` option value.
+	pub fn parse(name: &str) -> Option<Self> {
+		match name {
+			"internal" => Some(Self::Internal),
+			"rg" => Some(Self::Ripgrep),
+			_ => None,
+		}
+	}
+}
+
+/// A line containing one or more matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineMatch {
+	/// Line number (0-indexed).
+	pub line: usize,
+	/// Text of the line without its line ending.
+	pub text: String,
+	/// Char ranges of the matches within `text`.
+	pub ranges: Vec<Range<usize>>,
+}
+
+/// The matching lines of one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileMatches {
+	/// Path of the file.
+	pub path: PathBuf,
+	/// Matching lines in file order.
+	pub matches: Vec<LineMatch>,
+}
+
+/// Progress reported by a running search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchEvent {
+	/// Matches in one more file.
+	File(FileMatches),
+	/// The search failed, for example on an invalid pattern.
+	Error(String),
+	/// The search finished or was cancelled.
+	Done,
+}
+
+/// A search to run.
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+	/// Regex to look for.
+	pub pattern: String,
+	/// How letter case is matched.
+	pub case: SearchCase,
+	/// Directory searched recursively.
+	pub root: PathBuf,
+	/// Backend finding the matches.
+	pub backend: SearchBackend,
+}
+
+/// Starts `request` on a new thread, sending its events tagged with
+/// `generation` to `events`.
+///
+/// Setting `cancel` stops the search at the next file; [`SearchEvent::Done`]
+/// is sent either way.
+pub fn spawn(
+	request: SearchRequest,
+	generation: u64,
+	events: Sender<(u64, SearchEvent)>,
+	cancel: Arc<AtomicBool>,
+) {
+	thread::spawn(move || {
+		let mut send = |event: SearchEvent| events.send((generation, event)).is_ok();
+		let result = match request.backend {
+			SearchBackend::Internal => search_internal(&request, &cancel, &mut send),
+			SearchBackend::Ripgrep => search_ripgrep(&request, &cancel, &mut send),
+		};
+		if let Err(e) = result {
+			send(SearchEvent::Error(e));
+		}
+		send(SearchEvent::Done);
+	});
+}
+
+/// Walks `request.root`, skipping hidden and ignored paths, and searches
+/// each file.
+fn search_internal(
+	request: &SearchRequest,
+	cancel: &AtomicBool,
+	send: &mut dyn FnMut(SearchEvent) -> bool,
+) -> Result<(), String> {
+	let regex =
+		movement::compile_with_case(&request.pattern, request.case).map_err(|e| e.to_string())?;
+	let mut found = 0;
+	let mut rules = Vec::new();
+	walk(&request.root, &mut rules, cancel, &mut |path| {
+		let Some(matches) = search_file(path, &regex) else {
+			return true;
+		};
+		found += matches.len();
+		let path = path.to_path_buf();
+		send(SearchEvent::File(FileMatches { path, matches })) && found < MAX_MATCHES
+	});
+	Ok(())
+}
+
+/// Calls `visit` for every file below `dir` that is neither hidden nor
+/// ignored, stopping early once `visit` returns false or `cancel` is set.
+///
+/// `rules` holds the `.gitignore` rules of the directories above `dir`.
+fn walk(
+	dir: &Path,
+	rules: &mut Vec<(PathBuf, Vec<IgnoreRule>)>,
+	cancel: &AtomicBool,
+	visit: &mut dyn FnMut(&Path) -> bool,
+) -> bool {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return true;
+	};
+	let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+	entries.sort_by_key(|entry| entry.file_name());
+
+	let own_rules = fs::read_to_string(dir.join(".gitignore")).ok();
+	if let Some(text) = &own_rules {
+		rules.push((dir.to_path_buf(), parse_gitignore(text)));
+	}
+	let mut keep_going = true;
+	for entry in entries {
+		if cancel.load(Ordering::Relaxed) {
+			keep_going = false;
+			break;
+		}
+		if entry.file_name().to_string_lossy().starts_with('.') {
+			continue;
+		}
+		let path = entry.path();
+		let Ok(kind) = entry.file_type() else {
+			continue;
+		};
+		if is_ignored(rules, &path, kind.is_dir()) {
+			continue;
+		}
+		keep_going = if kind.is_dir() {
+			walk(&path, rules, cancel, visit)
+		} else if kind.is_file() {
+			visit(&path)
+		} else {
+			true
+		};
+		if !keep_going {
+			break;
+		}
+	}
+	if own_rules.is_some() {
+		rules.pop();
+	}
+	keep_going
+}
+
+/// Returns the lines of the file at `path` matching `regex`, or `None` if
+/// there are none or the file is too large, unreadable or binary.
+fn search_file(path: &Path, regex: &Regex) -> Option<Vec<LineMatch>> {
+	if fs::metadata(path).ok()?.len() > MAX_FILE_BYTES {
+		return None;
+	}
+	let bytes = fs::read(path).ok()?;
+	if bytes.contains(&0) {
+		return None;
+	}
+	let text = String::from_utf8(bytes).ok()?;
+	let matches = find_lines(&Rope::from_str(&text), regex);
+	(!matches.is_empty()).then_some(matches)
+}
+
+/// Groups the matches of `regex` in `rope` by line.
+///
+/// A match spanning lines is cut at the end of its first line.
+pub(crate) fn find_lines(rope: &Rope, regex: &Regex) -> Vec<LineMatch> {
+	let mut lines: Vec<LineMatch> = Vec::new();
+	for found in movement::find_iter(rope.slice(..), regex, 0) {
+		let (start, end) = (found.min(), found.max());
+		if start == end && start == rope.len_chars() {
+			continue;
+		}
+		let line = rope.char_to_line(start);
+		let line_start = rope.line_to_char(line);
+		if lines.last().is_none_or(|last| last.line != line) {
+			let text = rope.line(line).to_string();
+			let text = text.trim_end_matches(['\n', '\r']).to_string();
+			lines.push(LineMatch {
+				line,
+				text,
+				ranges: Vec::new(),
+			});
+		}
+		let last = lines.last_mut().expect("just pushed");
+		let len = last.text.chars().count();
+		let start = (start - line_start).min(len);
+		last.ranges
+			.push(start..(end - line_start).clamp(start, len));
+	}
+	lines
+}
+
+/// Runs ripgrep for `request`, killing it if the search is cancelled.
+fn search_ripgrep(
+	request: &SearchRequest,
+	cancel: &AtomicBool,
+	send: &mut dyn FnMut(SearchEvent) -> bool,
+) -> Result<(), String> {
+	let case = match request.case {
+		SearchCase::Sensitive => "--case-sensitive",
+		SearchCase::Insensitive => "--ignore-case",
+		SearchCase::Smart => "--smart-case",
+	};
+	let mut child = Command::new("rg")
+		.args(["--json", "--no-config", case, "--", &request.pattern, "."])
+		.current_dir(&request.root)
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.map_err(|e| format!("rg: {e}"))?;
+	let stdout = child.stdout.take().expect("stdout is piped");
+
+	let mut found = 0;
+	let mut file: Option<FileMatches> = None;
+	for line in BufReader::new(stdout).lines() {
+		if cancel.load(Ordering::Relaxed) || found >= MAX_MATCHES {
+			let _ = child.kill();
+			break;
+		}
+		let Ok(line) = line else {
+			break;
+		};
+		match parse_rg_message(&line) {
+			Some(RgMessage::Begin(path)) => {
+				file = Some(FileMatches {
+					path: request.root.join(path),
+					matches: Vec::new(),
+				});
+			}
+			Some(RgMessage::Match(line_match)) => {
+				if let Some(file) = &mut file {
+					found += 1;
+					file.matches.push(line_match);
+				}
+			}
+			Some(RgMessage::End) => {
+				if let Some(file) = file.take().filter(|f| !f.matches.is_empty())
+					&& !send(SearchEvent::File(file))
+				{
+					let _ = child.kill();
+					break;
+				}
+			}
+			None => {}
+		}
+	}
+
+	let output = child.wait_with_output().map_err(|e| format!("rg: {e}"))?;
+	// Exit code 1 means no matches; a kill after cancelling is not an error.
+	if output.status.code().is_some_and(|code| code > 1) {
+		let stderr = String::from_utf8_lossy(&output.stderr);
+		return Err(stderr.lines().next().unwrap_or("rg failed").to_string());
+	}
+	Ok(())
+}
+
+/// A line of `rg --json` output the search uses.
+#[derive(Debug, PartialEq, Eq)]
+enum RgMessage {
+	/// Results for the file at this path follow.
+	Begin(PathBuf),
+	/// A matching line of the current file.
+	Match(LineMatch),
+	/// The current file is done.
+	End,
+}
+
+/// Parses a line of `rg --json` output, ignoring context lines, summaries
+/// and paths that are not valid UTF-8.
+fn parse_rg_message(line: &str) -> Option<RgMessage> {
+	let value: Value = serde_json::from_str(line).ok()?;
+	let data = value.get("data")?;
+	match value.get("type")?.as_str()? {
+		"begin" => Some(RgMessage::Begin(PathBuf::from(
+			data.pointer("/path/text")?.as_str()?,
+		))),
+		"end" => Some(RgMessage::End),
+		"match" => {
+			let raw = data.pointer("/lines/text")?.as_str()?;
+			let text = raw.trim_end_matches(['\n', '\r']);
+			let line = data.get("line_number")?.as_u64()?.checked_sub(1)? as usize;
+			let char_at = |byte: u64| {
+				let byte = (byte as usize).min(text.len());
+				text.get(..byte).map_or(0, |prefix| prefix.chars().count())
+			};
+			let ranges = data
+				.get("submatches")?
+				.as_array()?
+				.iter()
+				.filter_map(|m| {
+					let start = char_at(m.get("start")?.as_u64()?);
+					Some(start..char_at(m.get("end")?.as_u64()?).max(start))
+				})
+				.collect();
+			Some(RgMessage::Match(LineMatch {
+				line,
+				text: text.to_string(),
+				ranges,
+			}))
+		}
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::mpsc;
+
+	use super::*;
+
+	#[test]
+	fn groups_matches_by_line() {
+		let rope = Rope::from_str("let foo = foo();\nbar\nfoo\n");
+		let regex = movement::compile("foo").unwrap();
+		let lines = find_lines(&rope, &regex);
+		assert_eq!(
+			lines,
+			[
+				LineMatch {
+					line: 0,
+					text: "let foo = foo();".to_string(),
+					ranges: vec![4..7, 10..13],
+				},
+				LineMatch {
+					line: 2,
+					text: "foo".to_string(),
+					ranges: vec![0..3],
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn parses_ripgrep_json() {
+		assert_eq!(
+			parse_rg_message(r#"{"type":"begin","data":{"path":{"text":"src/a.rs"}}}"#),
+			Some(RgMessage::Begin(PathBuf::from("src/a.rs")))
+		);
+		let line = r#"{"type":"match","data":{"path":{"text":"src/a.rs"},"lines":{"text":"é foo\n"},"line_number":3,"absolute_offset":0,"submatches":[{"match":{"text":"foo"},"start":3,"end":6}]}}"#;
+		assert_eq!(
+			parse_rg_message(line),
+			Some(RgMessage::Match(LineMatch {
+				line: 2,
+				text: "é foo".to_string(),
+				ranges: vec![2..5],
+			}))
+		);
+		assert_eq!(parse_rg_message(r#"{"type":"summary","data":{}}"#), None);
+	}
+
+	#[test]
+	fn internal_search_skips_ignored_files() {
+		let tmp = tempfile::tempdir().unwrap();
+		let root = tmp.path();
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::create_dir_all(root.join("target")).unwrap();
+		fs::write(root.join(".gitignore"), "target/\n").unwrap();
+		fs::write(root.join("src/a.rs"), "fn needle() {}\n").unwrap();
+		fs::write(root.join("target/b.rs"), "needle\n").unwrap();
+		fs::write(root.join(".hidden"), "needle\n").unwrap();
+
+		let (tx, rx) = mpsc::channel();
+		let request = SearchRequest {
+			pattern: "needle".to_string(),
+			case: SearchCase::Sensitive,
+			root: root.to_path_buf(),
+			backend: SearchBackend::Internal,
+		};
+		spawn(request, 7, tx, Arc::new(AtomicBool::new(false)));
+		let events: Vec<_> = rx.iter().collect();
+		assert!(events.iter().all(|(generation, _)| *generation == 7));
+		let files: Vec<_> = events
+			.iter()
+			.filter_map(|(_, event)| match event {
+				SearchEvent::File(file) => Some(file.path.clone()),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(files, [root.join("src/a.rs")]);
+		assert_eq!(events.last().map(|(_, e)| e), Some(&SearchEvent::Done));
+	}
+}
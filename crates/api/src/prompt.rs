@@ -0,0 +1,302 @@
+//! Modal prompt and confirm popups.
+//!
+//! Features that need an answer from the user before going on open one of
+//! these instead of building their own input UI:
+//!
+//! - [`PromptPopup`] asks for a line of text in the palette input, with an
+//!   optional validator that keeps invalid input from being submitted and
+//!   an optional completion hook run on Tab.
+//! - [`ConfirmPopup`] shows a message and a set of keyed choices, such as
+//!   `[y]es [n]o [c]ancel`, and waits for one of the keys. Longer labels,
+//!   such as a list of actions, can be stacked one per line.
+//!
+//! Both are modal and deliver their answer once, through the callback they
+//! were built with. Escape always cancels.
+
+use std::fmt;
+
+use crate::editor::Editor;
+
+/// Callback receiving the answer to a [`PromptPopup`].
+pub type PromptCallback = Box<dyn FnOnce(&mut Editor, PromptResult) + Send + Sync>;
+
+/// Checks prompt input, returning the message to show if it is invalid.
+pub type PromptValidator = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Returns the completions of prompt input.
+pub type PromptCompleter = Box<dyn Fn(&str) -> Vec<String> + Send + Sync>;
+
+/// Callback receiving the answer to a [`ConfirmPopup`].
+pub type ConfirmCallback = Box<dyn FnOnce(&mut Editor, ConfirmResult) + Send + Sync>;
+
+/// How a [`PromptPopup`] was answered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptResult {
+	/// Enter was pressed on valid input.
+	Submitted(String),
+	/// The prompt was dismissed.
+	Cancelled,
+}
+
+/// A single-line text prompt.
+pub struct PromptPopup {
+	/// Title drawn on the prompt's border.
+	pub(crate) title: String,
+	/// Character drawn in the prompt's gutter.
+	pub(crate) prompt: char,
+	/// Text the input starts with.
+	pub(crate) initial: String,
+	/// Receives the answer.
+	pub(crate) on_result: PromptCallback,
+	/// Rejects input that cannot be submitted.
+	pub(crate) validator: Option<PromptValidator>,
+	/// Completes input on Tab.
+	pub(crate) completer: Option<PromptCompleter>,
+	/// Message from the validator about the current input.
+	pub(crate) error: Option<String>,
+}
+
+impl PromptPopup {
+	/// Creates a prompt titled `title` that passes its answer to `on_result`.
+	pub fn new(
+		title: impl Into<String>,
+		on_result: impl FnOnce(&mut Editor, PromptResult) + Send + Sync + 'static,
+	) -> Self {
+		Self {
+			title: title.into(),
+			prompt: '>',
+			initial: String::new(),
+			on_result: Box::new(on_result),
+			validator: None,
+			completer: None,
+			error: None,
+		}
+	}
+
+	/// Sets the character drawn in the prompt's gutter.
+	#[must_use]
+	pub fn prompt(mut self, prompt: char) -> Self {
+		self.prompt = prompt;
+		self
+	}
+
+	/// Sets the text the input starts with.
+	#[must_use]
+	pub fn initial(mut self, text: impl Into<String>) -> Self {
+		self.initial = text.into();
+		self
+	}
+
+	/// Sets the validator run on every edit and before submitting.
+	#[must_use]
+	pub fn validator(
+		mut self,
+		validator: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+	) -> Self {
+		self.validator = Some(Box::new(validator));
+		self
+	}
+
+	/// Sets the hook that lists completions of the input on Tab.
+	#[must_use]
+	pub fn completer(
+		mut self,
+		completer: impl Fn(&str) -> Vec<String> + Send + Sync + 'static,
+	) -> Self {
+		self.completer = Some(Box::new(completer));
+		self
+	}
+
+	/// Runs the validator over `input`, returning its message if invalid.
+	pub(crate) fn validate(&self, input: &str) -> Option<String> {
+		self.validator.as_ref()?(input).err()
+	}
+
+	/// Completes `input` as far as all its completions agree.
+	///
+	/// Returns `None` if there are no completions or they add nothing.
+	pub(crate) fn complete(&self, input: &str) -> Option<String> {
+		let candidates = self.completer.as_ref()?(input);
+		let (first, rest) = candidates.split_first()?;
+		let mut common = first.as_str();
+		for candidate in rest {
+			let len = common
+				.char_indices()
+				.zip(candidate.chars())
+				.find(|((_, a), b)| a != b)
+				.map_or(common.len().min(candidate.len()), |((i, _), _)| i);
+			common = &common[..len];
+		}
+		(common.len() > input.len() && common.starts_with(input)).then(|| common.to_string())
+	}
+}
+
+impl fmt::Debug for PromptPopup {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PromptPopup")
+			.field("title", &self.title)
+			.field("prompt", &self.prompt)
+			.field("error", &self.error)
+			.finish_non_exhaustive()
+	}
+}
+
+/// A choice offered by a [`ConfirmPopup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmChoice {
+	/// Key that picks the choice.
+	pub key: char,
+	/// Label shown after the key.
+	pub label: String,
+}
+
+impl ConfirmChoice {
+	/// Creates a choice picked with `key`.
+	pub fn new(key: char, label: impl Into<String>) -> Self {
+		Self {
+			key,
+			label: label.into(),
+		}
+	}
+}
+
+/// How a [`ConfirmPopup`] was answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmResult {
+	/// The choice with this key was picked.
+	Chosen(char),
+	/// The popup was dismissed with Escape.
+	Cancelled,
+}
+
+/// A message with keyed choices.
+pub struct ConfirmPopup {
+	/// Title drawn on the popup's border.
+	pub(crate) title: String,
+	/// Message shown above the choices.
+	pub(crate) message: String,
+	/// Choices, in the order they are listed.
+	pub(crate) choices: Vec<ConfirmChoice>,
+	/// Whether choices are listed one per line.
+	pub(crate) stacked: bool,
+	/// Receives the answer.
+	pub(crate) on_result: ConfirmCallback,
+}
+
+impl ConfirmPopup {
+	/// Creates a popup showing `message` that passes its answer to
+	/// `on_result`.
+	pub fn new(
+		title: impl Into<String>,
+		message: impl Into<String>,
+		on_result: impl FnOnce(&mut Editor, ConfirmResult) + Send + Sync + 'static,
+	) -> Self {
+		Self {
+			title: title.into(),
+			message: message.into(),
+			choices: Vec::new(),
+			stacked: false,
+			on_result: Box::new(on_result),
+		}
+	}
+
+	/// Adds a choice picked with `key`.
+	#[must_use]
+	pub fn choice(mut self, key: char, label: impl Into<String>) -> Self {
+		self.choices.push(ConfirmChoice::new(key, label));
+		self
+	}
+
+	/// Lists the choices one per line instead of on one line.
+	#[must_use]
+	pub fn stacked(mut self) -> Self {
+		self.stacked = true;
+		self
+	}
+
+	/// Returns the key of the choice `key` picks, ignoring case.
+	pub(crate) fn choice_for(&self, key: char) -> Option<char> {
+		self.choices
+			.iter()
+			.find(|choice| choice.key.eq_ignore_ascii_case(&key))
+			.map(|choice| choice.key)
+	}
+
+	/// Returns the popup's text: the message, a blank line and the choices.
+	/// An empty message leaves out the blank line.
+	///
+	/// A label starting with its key shows the key in brackets, as in
+	/// `[y]es`; other labels follow their bracketed key.
+	pub(crate) fn content(&self) -> String {
+		let choices: Vec<String> = self
+			.choices
+			.iter()
+			.map(|choice| {
+				let mut label = choice.label.chars();
+				match label.next() {
+					Some(first) if first.eq_ignore_ascii_case(&choice.key) => {
+						format!("[{first}]{}", label.as_str())
+					}
+					_ => format!("[{}] {}", choice.key, choice.label),
+				}
+			})
+			.collect();
+		let choices = choices.join(if self.stacked { "\n" } else { "  " });
+		if self.message.is_empty() {
+			choices
+		} else {
+			format!("{}\n\n{choices}", self.message)
+		}
+	}
+}
+
+impl fmt::Debug for ConfirmPopup {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ConfirmPopup")
+			.field("title", &self.title)
+			.field("message", &self.message)
+			.field("choices", &self.choices)
+			.field("stacked", &self.stacked)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn confirm_content_brackets_choice_keys() {
+		let popup = ConfirmPopup::new("Quit", "Unsaved changes.", |_, _| {})
+			.choice('s', "save")
+			.choice('d', "Discard")
+			.choice('x', "cancel");
+		assert_eq!(
+			popup.content(),
+			"Unsaved changes.\n\n[s]ave  [D]iscard  [x] cancel"
+		);
+		assert_eq!(popup.choice_for('S'), Some('s'));
+		assert_eq!(popup.choice_for('q'), None);
+
+		let stacked = ConfirmPopup::new("Code actions", "", |_, _| {})
+			.choice('1', "Import `HashMap`")
+			.choice('2', "Qualify path")
+			.stacked();
+		assert_eq!(stacked.content(), "[1] Import `HashMap`\n[2] Qualify path");
+	}
+
+	#[test]
+	fn completion_extends_to_common_prefix() {
+		let prompt = PromptPopup::new("Open", |_, _| {}).completer(|input| {
+			["src/main.rs", "src/mod.rs", "README.md"]
+				.into_iter()
+				.filter(|path| path.starts_with(input))
+				.map(String::from)
+				.collect()
+		});
+		assert_eq!(prompt.complete("s").as_deref(), Some("src/m"));
+		assert_eq!(prompt.complete("src/ma").as_deref(), Some("src/main.rs"));
+		assert_eq!(prompt.complete("src/m"), None);
+		assert_eq!(prompt.complete("x"), None);
+	}
+}
@@ -3,22 +3,29 @@
 use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use xeno_base::Mode;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use xeno_base::range::CharIdx;
+use xeno_base::{Mode, Range, column};
 use xeno_language::LanguageLoader;
-use xeno_language::highlight::{HighlightSpan, HighlightStyles};
+use xeno_language::highlight::{Highlight, HighlightSpan, HighlightStyles};
 use xeno_registry::gutter::GutterAnnotations;
 use xeno_registry::themes::{SyntaxStyles, Theme};
 use xeno_tui::layout::Rect;
-use xeno_tui::style::{Modifier, Style};
+use xeno_tui::style::{Color, Modifier, Style};
 use xeno_tui::text::{Line, Span};
 use xeno_tui::widgets::Paragraph;
 
-use super::diagnostics::{DiagnosticLineMap, DiagnosticRangeMap};
+use super::diagnostics::{
+	DiagnosticInlineMap, DiagnosticLineMap, DiagnosticRangeMap, severity_color,
+};
 use super::gutter::GutterLayout;
+use super::inlay_hints::{InlayHintLabel, InlayHintMap};
+use super::viewport::cursor_segment;
+use super::whitespace::WhitespaceMarkers;
 use crate::buffer::Buffer;
 use crate::editor::extensions::StyleOverlays;
-use crate::render::wrap::wrap_line;
+use crate::render::wrap::clip_line;
 use crate::window::GutterSelector;
 
 /// Result of rendering a buffer's content.
@@ -43,6 +50,10 @@ pub struct BufferRenderContext<'a> {
 	pub diagnostics: Option<&'a DiagnosticLineMap>,
 	/// Optional diagnostic range map for underlines.
 	pub diagnostic_ranges: Option<&'a DiagnosticRangeMap>,
+	/// Optional map of messages drawn after the end of their line.
+	pub inline_diagnostics: Option<&'a DiagnosticInlineMap>,
+	/// Optional map of inlay hint labels drawn within their lines.
+	pub inlay_hints: Option<&'a InlayHintMap>,
 }
 
 /// Cursor styling configuration for rendering.
@@ -59,6 +70,59 @@ pub struct CursorStyles {
 	pub unfocused: Style,
 }
 
+/// Per-buffer line highlight options for a render pass.
+#[derive(Debug, Clone, Default)]
+pub struct LineHighlights {
+	/// Whether to highlight the primary cursor's visual line.
+	pub cursorline: bool,
+	/// 0-based text area columns tinted by `colorcolumn`.
+	pub colorcolumns: Vec<usize>,
+	/// Markers drawn for visible whitespace.
+	pub whitespace: WhitespaceMarkers,
+	/// Search matches around the viewport.
+	pub search: SearchHighlights,
+	/// Occurrences of the symbol under the cursor.
+	pub symbols: SymbolHighlights,
+}
+
+/// Search matches to highlight in a render pass.
+#[derive(Debug, Clone, Default)]
+pub struct SearchHighlights {
+	/// Non-overlapping matches in document order.
+	pub ranges: Vec<Range>,
+	/// Match the last search landed on, drawn distinctly.
+	pub current: Option<Range>,
+}
+
+impl SearchHighlights {
+	/// Returns whether `pos` lies in a match, and if so whether that match
+	/// is the current one.
+	pub fn match_at(&self, pos: CharIdx) -> Option<bool> {
+		let idx = self.ranges.partition_point(|r| r.to() <= pos);
+		let found = self.ranges.get(idx).filter(|r| r.from() <= pos)?;
+		Some(self.current.is_some_and(|c| c.from() == found.from()))
+	}
+}
+
+/// Occurrences of the symbol under the cursor to highlight in a render
+/// pass, as a language server reported them.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolHighlights {
+	/// Non-overlapping occurrences in document order, each with whether it
+	/// writes to the symbol.
+	pub ranges: Vec<(Range, bool)>,
+}
+
+impl SymbolHighlights {
+	/// Returns whether `pos` lies in an occurrence, and if so whether that
+	/// occurrence writes to the symbol.
+	pub fn write_at(&self, pos: CharIdx) -> Option<bool> {
+		let idx = self.ranges.partition_point(|(r, _)| r.to() <= pos);
+		let (_, write) = self.ranges.get(idx).filter(|(r, _)| r.from() <= pos)?;
+		Some(*write)
+	}
+}
+
 /// Cursor line highlight configuration.
 ///
 /// Separates cursor position (needed for relative line numbers) from
@@ -67,19 +131,188 @@ pub struct CursorStyles {
 pub struct CursorlineConfig {
 	/// Whether cursorline highlighting is enabled.
 	pub enabled: bool,
-	/// Background color for cursorline (mode-aware).
-	pub bg: xeno_tui::style::Color,
+	/// Background color for cursorline.
+	pub bg: Color,
 	/// Cursor line index (real position, used for relative line numbers).
 	pub line: usize,
+	/// Wrap segment of the cursor line holding the primary cursor.
+	pub segment: usize,
 }
 
 impl CursorlineConfig {
-	/// Returns whether a given line should have cursorline styling.
-	pub fn should_highlight(&self, line_idx: usize) -> bool {
-		self.enabled && line_idx == self.line
+	/// Returns whether a given visual row should have cursorline styling.
+	///
+	/// Only the wrap segment holding the cursor is highlighted.
+	pub fn should_highlight(&self, line_idx: usize, segment: usize) -> bool {
+		self.enabled && line_idx == self.line && segment == self.segment
+	}
+}
+
+/// Pads a row from column `from` to `to` with spaces in `style`, tinting
+/// any `colorcolumns` in between with `colorcolumn_bg`.
+fn push_fill(
+	spans: &mut Vec<Span<'static>>,
+	from: usize,
+	to: usize,
+	style: Style,
+	colorcolumns: &[usize],
+	colorcolumn_bg: Color,
+) {
+	let mut col = from;
+	while col < to {
+		let (end, style) = if colorcolumns.contains(&col) {
+			(col + 1, style.bg(colorcolumn_bg))
+		} else {
+			let next = colorcolumns
+				.iter()
+				.copied()
+				.filter(|&c| c > col && c < to)
+				.min()
+				.unwrap_or(to);
+			(next, style)
+		};
+		spans.push(Span::styled(" ".repeat(end - col), style));
+		col = end;
 	}
 }
 
+/// Pads the rest of a line's last row: across the full width on the
+/// cursorline, or up to the last colorcolumn otherwise.
+fn push_eol_fill(
+	spans: &mut Vec<Span<'static>>,
+	from: usize,
+	text_width: usize,
+	cursorline_bg: Option<Color>,
+	colorcolumns: &[usize],
+	colorcolumn_bg: Color,
+) {
+	if let Some(bg) = cursorline_bg {
+		push_fill(spans, from, text_width, Style::default().bg(bg), &[], bg);
+	} else {
+		let end = colorcolumns
+			.iter()
+			.map(|col| col + 1)
+			.max()
+			.unwrap_or(0)
+			.min(text_width);
+		push_fill(
+			spans,
+			from,
+			end,
+			Style::default(),
+			colorcolumns,
+			colorcolumn_bg,
+		);
+	}
+}
+
+/// Cells left blank between the end of a line and its inline diagnostic.
+const INLINE_DIAGNOSTIC_GAP: usize = 2;
+
+/// Draws an inline diagnostic `message` from column `from`, after a gap in
+/// `fill`, cutting it with an ellipsis to end by `text_width`.
+///
+/// Returns the column after the message, or `from` if there is no room.
+fn push_inline_diagnostic(
+	spans: &mut Vec<Span<'static>>,
+	from: usize,
+	text_width: usize,
+	message: &str,
+	style: Style,
+	fill: Style,
+) -> usize {
+	let start = from + INLINE_DIAGNOSTIC_GAP;
+	let available = text_width.saturating_sub(start);
+	let message = message.replace(char::is_control, " ");
+	if available == 0 || message.is_empty() {
+		return from;
+	}
+	let text = if message.width() <= available {
+		message
+	} else {
+		let mut text = String::new();
+		let mut used = 0;
+		for grapheme in message.graphemes(true) {
+			let width = grapheme.width();
+			if used + width >= available {
+				break;
+			}
+			text.push_str(grapheme);
+			used += width;
+		}
+		text.push('…');
+		text
+	};
+	let end = start + text.width();
+	spans.push(Span::styled(" ".repeat(INLINE_DIAGNOSTIC_GAP), fill));
+	spans.push(Span::styled(text, style));
+	end
+}
+
+/// Draws the marker of a closed fold hiding `hidden` lines after the end of
+/// its first line, from column `from`, if it fits by `text_width`.
+///
+/// Returns the column after the marker, or `from` if it did not fit.
+fn push_fold_marker(
+	spans: &mut Vec<Span<'static>>,
+	from: usize,
+	text_width: usize,
+	hidden: usize,
+	style: Style,
+) -> usize {
+	let noun = if hidden == 1 { "line" } else { "lines" };
+	let text = format!(" … {hidden} {noun}");
+	let end = from + text.width();
+	if end > text_width {
+		return from;
+	}
+	spans.push(Span::styled(text, style));
+	end
+}
+
+/// Returns the column after `text` drawn from column `col` of a row whose
+/// text starts after `indent` cells, laying tabs out from `col_offset` as
+/// the renderer does.
+fn text_end_col(
+	text: &str,
+	mut col: usize,
+	indent: usize,
+	col_offset: usize,
+	tab_width: usize,
+) -> usize {
+	for grapheme in text.graphemes(true) {
+		col += if grapheme == "\t" {
+			let text_col = col - indent + col_offset;
+			tab_width.saturating_sub(text_col % tab_width).max(1)
+		} else {
+			column::grapheme_width(grapheme, col, tab_width)
+		};
+	}
+	col
+}
+
+/// Draws the inlay hint `labels` after the end of a line from column
+/// `from`, stopping at the first that does not fit by `text_width`.
+///
+/// Returns the column after the last label drawn.
+fn push_eol_hints<'l>(
+	spans: &mut Vec<Span<'static>>,
+	labels: impl IntoIterator<Item = &'l InlayHintLabel>,
+	mut from: usize,
+	text_width: usize,
+	style: Style,
+) -> usize {
+	for label in labels {
+		let width = label.text.width();
+		if from + width > text_width {
+			break;
+		}
+		spans.push(Span::styled(label.text.clone(), style));
+		from += width;
+	}
+	from
+}
+
 impl<'a> BufferRenderContext<'a> {
 	/// Creates cursor styling configuration based on theme and mode.
 	pub fn make_cursor_styles(&self) -> CursorStyles {
@@ -138,15 +371,19 @@ impl<'a> BufferRenderContext<'a> {
 	}
 
 	/// Collects syntax highlight spans for a buffer's visible viewport.
+	///
+	/// The document's semantic tokens come after the syntax tree's spans,
+	/// so they win where both color a character; tokens whose scope the
+	/// theme leaves unstyled let the syntax colors show through.
 	pub fn collect_highlight_spans(
 		&self,
 		buffer: &Buffer,
 		area: Rect,
 	) -> Vec<(HighlightSpan, Style)> {
 		let doc = buffer.doc();
-		let Some(ref syntax) = doc.syntax else {
+		if doc.syntax.is_none() && doc.semantic_tokens.is_empty() {
 			return Vec::new();
-		};
+		}
 
 		let start_line = buffer.scroll_line;
 		let end_line = (start_line + area.height as usize).min(doc.content.len_lines());
@@ -158,23 +395,47 @@ impl<'a> BufferRenderContext<'a> {
 			doc.content.len_bytes() as u32
 		};
 
-		let highlight_styles = HighlightStyles::new(SyntaxStyles::scope_names(), |scope| {
-			self.theme.colors.syntax.resolve(scope)
-		});
-
-		let highlighter = syntax.highlighter(
-			doc.content.slice(..),
-			self.language_loader,
-			start_byte..end_byte,
-		);
+		let scopes = SyntaxStyles::scope_names();
+		let highlight_styles =
+			HighlightStyles::new(scopes, |scope| self.theme.colors.syntax.resolve(scope));
+
+		let mut spans: Vec<(HighlightSpan, Style)> = match &doc.syntax {
+			Some(syntax) => syntax
+				.highlighter(
+					doc.content.slice(..),
+					self.language_loader,
+					start_byte..end_byte,
+				)
+				.map(|span| {
+					let abstract_style = highlight_styles.style_for_highlight(span.highlight);
+					let xeno_tui_style: Style = abstract_style;
+					(span, xeno_tui_style)
+				})
+				.collect(),
+			None => Vec::new(),
+		};
 
-		highlighter
-			.map(|span| {
-				let abstract_style = highlight_styles.style_for_highlight(span.highlight);
-				let xeno_tui_style: Style = abstract_style;
-				(span, xeno_tui_style)
-			})
-			.collect()
+		let first_char = doc.content.line_to_char(start_line);
+		let last_char = doc.content.byte_to_char(end_byte as usize);
+		for token in doc.semantic_tokens.overlapping(first_char, last_char) {
+			let Some(index) = scopes.iter().position(|scope| *scope == token.scope) else {
+				continue;
+			};
+			let highlight = Highlight::new(index as u32);
+			let style = highlight_styles.style_for_highlight(highlight);
+			if style == Style::default() {
+				continue;
+			}
+			let span = HighlightSpan {
+				start: doc.content.char_to_byte(token.start) as u32,
+				end: doc
+					.content
+					.char_to_byte(token.end.min(doc.content.len_chars())) as u32,
+				highlight,
+			};
+			spans.push((span, style));
+		}
+		spans
 	}
 
 	/// Looks up the style for a byte position from pre-computed highlight spans.
@@ -242,11 +503,7 @@ impl<'a> BufferRenderContext<'a> {
 
 	/// Applies diagnostic underline styling to a style if the position has a diagnostic.
 	///
-	/// Uses colored curly underlines based on severity:
-	/// - Error (4): Red curly underline
-	/// - Warning (3): Yellow curly underline
-	/// - Info (2): Blue curly underline
-	/// - Hint (1): Cyan curly underline
+	/// Uses curly underlines in the theme's status color for the severity.
 	pub fn apply_diagnostic_underline(
 		&self,
 		line_idx: usize,
@@ -257,19 +514,32 @@ impl<'a> BufferRenderContext<'a> {
 			return style;
 		};
 
-		use xeno_tui::style::{Color, UnderlineStyle};
-
-		let underline_color = match severity {
-			4 => self.theme.colors.status.error_fg,
-			3 => self.theme.colors.status.warning_fg,
-			2 => Color::Blue,
-			1 => Color::Cyan,
-			_ => return style,
-		};
+		use xeno_tui::style::UnderlineStyle;
 
 		style
 			.underline_style(UnderlineStyle::Curl)
-			.underline_color(underline_color)
+			.underline_color(severity_color(self.theme, severity))
+	}
+
+	/// Draws the inline diagnostic of a line, if any, from column `from`.
+	///
+	/// Returns the column after it, or `from` if nothing was drawn.
+	fn push_line_diagnostic(
+		&self,
+		spans: &mut Vec<Span<'static>>,
+		line_idx: usize,
+		from: usize,
+		text_width: usize,
+		cursorline_bg: Option<Color>,
+	) -> usize {
+		let Some(diagnostic) = self.inline_diagnostics.and_then(|map| map.get(&line_idx)) else {
+			return from;
+		};
+		let fill = cursorline_bg.map_or_else(Style::default, |bg| Style::default().bg(bg));
+		let style = fill
+			.fg(severity_color(self.theme, diagnostic.severity))
+			.add_modifier(Modifier::ITALIC);
+		push_inline_diagnostic(spans, from, text_width, &diagnostic.message, style, fill)
 	}
 
 	/// Renders a buffer into a paragraph widget using registry gutters.
@@ -280,7 +550,7 @@ impl<'a> BufferRenderContext<'a> {
 		use_block_cursor: bool,
 		is_focused: bool,
 		tab_width: usize,
-		cursorline: bool,
+		highlights: &LineHighlights,
 	) -> RenderResult {
 		self.render_buffer_with_gutter(
 			buffer,
@@ -289,7 +559,7 @@ impl<'a> BufferRenderContext<'a> {
 			is_focused,
 			GutterSelector::Registry,
 			tab_width,
-			cursorline,
+			highlights,
 		)
 	}
 
@@ -297,6 +567,7 @@ impl<'a> BufferRenderContext<'a> {
 	///
 	/// This is the main buffer rendering function that handles:
 	/// - Line wrapping and viewport positioning
+	/// - Closed folds, drawn as their first line and a count of the rest
 	/// - Cursor rendering (primary and secondary)
 	/// - Selection highlighting
 	/// - Gutter rendering
@@ -309,7 +580,9 @@ impl<'a> BufferRenderContext<'a> {
 	/// - `is_focused`: Whether this buffer is the focused/active buffer
 	/// - `gutter`: Gutter selection for this render pass
 	/// - `tab_width`: Number of spaces a tab character occupies (from options)
-	/// - `cursorline`: Whether to highlight the cursor line
+	/// - `highlights`: Cursorline and colorcolumn options; the cursor cell
+	///   takes precedence over selections, then the cursorline, then
+	///   colorcolumns
 	pub fn render_buffer_with_gutter(
 		&self,
 		buffer: &Buffer,
@@ -318,10 +591,11 @@ impl<'a> BufferRenderContext<'a> {
 		is_focused: bool,
 		gutter: GutterSelector,
 		tab_width: usize,
-		cursorline: bool,
+		highlights: &LineHighlights,
 	) -> RenderResult {
 		let total_lines = buffer.doc().content.len_lines();
-		let gutter_layout = GutterLayout::from_selector(gutter, total_lines, area.width);
+		let gutter_layout =
+			GutterLayout::from_selector(gutter, buffer.gutter_width_context(area.width));
 		let gutter_width = gutter_layout.total_width;
 		let text_width = area.width.saturating_sub(gutter_width) as usize;
 
@@ -335,10 +609,33 @@ impl<'a> BufferRenderContext<'a> {
 
 		let highlight_spans = self.collect_highlight_spans(buffer, area);
 		let mode_color = self.mode_color(buffer.mode());
+		let ui = &self.theme.colors.ui;
 		let cursorline_config = CursorlineConfig {
-			enabled: cursorline,
-			bg: self.theme.colors.ui.bg.blend(mode_color, 0.92), // 92% bg, 8% mode
+			enabled: highlights.cursorline,
+			bg: ui.cursorline_bg,
 			line: buffer.cursor_line(),
+			segment: cursor_segment(buffer, text_width, tab_width),
+		};
+		// Unwrapped views scroll horizontally; colorcolumns move with the text.
+		let col_offset = if buffer.wrap { 0 } else { buffer.left_col };
+		let shifted_colorcolumns: Vec<usize> = highlights
+			.colorcolumns
+			.iter()
+			.filter_map(|col| col.checked_sub(col_offset))
+			.collect();
+		let colorcolumns = shifted_colorcolumns.as_slice();
+		let colorcolumn_bg = ui.cursorline_bg.blend(ui.bg, 0.6); // lighter than cursorline
+		let whitespace = &highlights.whitespace;
+		let whitespace_fg = self.theme.colors.status.dim_fg;
+		let hint_style = |is_cursor_line: bool| {
+			let style = Style::default()
+				.fg(whitespace_fg)
+				.add_modifier(Modifier::ITALIC);
+			if is_cursor_line {
+				style.bg(cursorline_config.bg)
+			} else {
+				style
+			}
 		};
 
 		let buffer_path_owned = buffer.path();
@@ -350,8 +647,6 @@ impl<'a> BufferRenderContext<'a> {
 		let viewport_height = area.height as usize;
 
 		while output_lines.len() < viewport_height && current_line_idx < total_lines {
-			let is_cursor_line = cursorline_config.should_highlight(current_line_idx);
-
 			let line_annotations = if let Some(diags) = self.diagnostics
 				&& let Some(&severity) = diags.get(&current_line_idx)
 			{
@@ -372,8 +667,28 @@ impl<'a> BufferRenderContext<'a> {
 			let line_text: String = buffer.doc().content.slice(line_start..line_end).into();
 			let line_text = line_text.trim_end_matches('\n');
 			let line_content_end: CharIdx = line_start + line_text.chars().count();
-
-			let wrapped_segments = wrap_line(line_text, text_width, tab_width);
+			let marked_from = whitespace.marked_from(line_text);
+			let line_hints = self
+				.inlay_hints
+				.map_or(&[][..], |map| map.line(current_line_idx));
+			let folded = buffer.doc().folds.hidden_lines(current_line_idx);
+
+			let mut lead = 0;
+			let mut clipped_left = false;
+			let mut clipped_right = false;
+			let mut eol_visible = true;
+			let wrapped_segments = if buffer.wrap {
+				buffer.wrap_line(line_text, text_width, tab_width)
+			} else {
+				let clipped = clip_line(line_text, buffer.left_col, text_width, tab_width);
+				lead = clipped.lead;
+				clipped_left = clipped.clipped_left;
+				clipped_right = clipped.clipped_right;
+				eol_visible = clipped.eol_visible;
+				vec![clipped.segment]
+			};
+			// The last cell of a right-clipped row holds the clip marker.
+			let content_width = text_width - usize::from(clipped_right);
 			let num_segments = wrapped_segments.len().max(1);
 
 			for (seg_idx, segment) in wrapped_segments.iter().enumerate().skip(start_segment) {
@@ -381,27 +696,122 @@ impl<'a> BufferRenderContext<'a> {
 					break;
 				}
 
-				let is_first_segment = seg_idx == 0;
 				let is_last_segment = seg_idx == num_segments - 1;
-				let is_continuation = !is_first_segment;
+				let is_cursor_line = cursorline_config.should_highlight(current_line_idx, seg_idx);
 
 				let mut spans = gutter_layout.render_line(
 					current_line_idx,
 					total_lines,
 					&cursorline_config,
-					is_continuation,
+					seg_idx,
 					buffer.doc().content.line(current_line_idx),
 					buffer_path,
 					&line_annotations,
 					self.theme,
 				);
 
+				let clip_style = if is_cursor_line {
+					Style::default().fg(whitespace_fg).bg(cursorline_config.bg)
+				} else {
+					Style::default().fg(whitespace_fg)
+				};
+				let fill_bg_style = if is_cursor_line {
+					Style::default().bg(cursorline_config.bg)
+				} else {
+					Style::default()
+				};
+				let fill_columns = if is_cursor_line {
+					&[][..]
+				} else {
+					colorcolumns
+				};
+
 				let seg_char_offset = segment.start_offset;
 				let mut seg_col = 0usize;
-				for (i, ch) in segment.text.chars().enumerate() {
-					if seg_col >= text_width {
+				if clipped_left {
+					spans.push(Span::styled("…", clip_style));
+					seg_col = 1;
+				}
+				// Continuation rows start after the wrap indent and marker.
+				if segment.indent > 0 {
+					let marker = &buffer.wrap_indent.marker;
+					let marker_width = buffer.wrap_indent.marker_width();
+					let marker_col = segment.indent.saturating_sub(marker_width);
+					push_fill(
+						&mut spans,
+						0,
+						marker_col,
+						fill_bg_style,
+						fill_columns,
+						colorcolumn_bg,
+					);
+					if marker_width <= segment.indent {
+						spans.push(Span::styled(marker.clone(), clip_style));
+					} else {
+						push_fill(
+							&mut spans,
+							marker_col,
+							segment.indent,
+							fill_bg_style,
+							fill_columns,
+							colorcolumn_bg,
+						);
+					}
+					seg_col = segment.indent;
+				}
+				// Placeholder cells for a wide grapheme cut by the left edge.
+				push_fill(
+					&mut spans,
+					seg_col,
+					seg_col + lead,
+					fill_bg_style,
+					fill_columns,
+					colorcolumn_bg,
+				);
+				seg_col += lead;
+
+				// Hints of the segment, and those after the line's end on its
+				// last row. They only take the cells the text leaves free, so
+				// they never push text out of view; the last row keeps one for
+				// a cursor at the end of the line.
+				let seg_end = seg_char_offset + segment.text.chars().count();
+				let mut hints = line_hints
+					.iter()
+					.filter(|hint| {
+						hint.column >= seg_char_offset && (is_last_segment || hint.column < seg_end)
+					})
+					.peekable();
+				let mut hint_room = if hints.peek().is_some() {
+					let text_end = text_end_col(
+						&segment.text,
+						seg_col,
+						segment.indent,
+						col_offset,
+						tab_width,
+					);
+					content_width.saturating_sub(text_end + usize::from(is_last_segment))
+				} else {
+					0
+				};
+				let mut hint_shift = 0usize;
+
+				let mut i = 0usize;
+				for grapheme in segment.text.graphemes(true) {
+					if seg_col >= content_width {
 						break;
 					}
+					let grapheme_chars = grapheme.chars().count();
+
+					while let Some(hint) = hints.next_if(|hint| hint.column <= seg_char_offset + i)
+					{
+						let width = hint.text.width();
+						if width <= hint_room {
+							spans.push(Span::styled(hint.text.clone(), hint_style(is_cursor_line)));
+							seg_col += width;
+							hint_room -= width;
+							hint_shift += width;
+						}
+					}
 
 					let doc_pos: CharIdx = line_start + seg_char_offset + i;
 					let is_cursor = cursor_heads.contains(&doc_pos);
@@ -425,7 +835,7 @@ impl<'a> BufferRenderContext<'a> {
 					// Apply style overlays (e.g., zen mode dimming)
 					let syntax_style = self.apply_style_overlay(byte_pos, syntax_style);
 
-					let non_cursor_style = if in_selection {
+					let text_style = if in_selection {
 						// Blend bg + mode color + syntax fg for selection highlight
 						let base = syntax_style.unwrap_or(styles.base);
 						let syntax_fg = base.fg.unwrap_or(self.theme.colors.ui.fg);
@@ -438,6 +848,21 @@ impl<'a> BufferRenderContext<'a> {
 							.bg(selection_bg)
 							.fg(syntax_fg)
 							.add_modifier(base.add_modifier)
+					} else if let Some(is_current) = highlights.search.match_at(doc_pos) {
+						let base = syntax_style.unwrap_or(styles.base);
+						let style = base.bg(ui.search_match_bg);
+						if is_current {
+							style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+						} else {
+							style
+						}
+					} else if let Some(write) = highlights.symbols.write_at(doc_pos) {
+						let base = syntax_style.unwrap_or(styles.base);
+						base.bg(if write {
+							ui.document_highlight_write_bg
+						} else {
+							ui.document_highlight_bg
+						})
 					} else {
 						let base = syntax_style.unwrap_or(styles.base);
 						if is_cursor_line && base.bg.is_none() {
@@ -449,51 +874,86 @@ impl<'a> BufferRenderContext<'a> {
 
 					// Apply diagnostic underlines based on character position
 					let char_in_line = seg_char_offset + i;
-					let non_cursor_style = self.apply_diagnostic_underline(
-						current_line_idx,
-						char_in_line,
-						non_cursor_style,
-					);
+					let text_style =
+						self.apply_diagnostic_underline(current_line_idx, char_in_line, text_style);
 
-					let style = if is_cursor && (use_block_cursor || !is_focused) {
-						if blink_on || !is_focused {
-							cursor_style
-						} else {
-							// Blink off: show syntax-highlighted style instead of plain base
-							non_cursor_style
-						}
+					// Colorcolumns only tint cells nothing else has colored.
+					let stripe_columns: &[usize] = if text_style.bg.is_none() {
+						colorcolumns
+					} else {
+						&[]
+					};
+					let non_cursor_style = if stripe_columns.contains(&seg_col) {
+						text_style.bg(colorcolumn_bg)
+					} else {
+						text_style
+					};
+
+					// Blink off: show syntax-highlighted style instead of plain base
+					let shows_cursor =
+						is_cursor && (use_block_cursor || !is_focused) && (blink_on || !is_focused);
+					let style = if shows_cursor {
+						cursor_style
 					} else {
 						non_cursor_style
 					};
 
-					if ch == '\t' {
-						let remaining = text_width.saturating_sub(seg_col);
+					// Whitespace markers replace only the drawn glyph, dimmed
+					// unless the cursor is drawn over them.
+					let marker = marked_from
+						.and_then(|from| whitespace.marker(grapheme, char_in_line, from));
+					let glyph_style = if marker.is_some() && !shows_cursor {
+						style.fg(whitespace_fg)
+					} else {
+						style
+					};
+
+					if grapheme == "\t" {
+						let remaining = content_width.saturating_sub(seg_col);
 						if remaining == 0 {
 							break;
 						}
-						let mut tab_cells = tab_width.saturating_sub(seg_col % tab_width);
+						let text_col = seg_col - hint_shift - segment.indent + col_offset;
+						let mut tab_cells = tab_width.saturating_sub(text_col % tab_width);
 						if tab_cells == 0 {
 							tab_cells = 1;
 						}
 						tab_cells = tab_cells.min(remaining);
 
-						if is_cursor && use_block_cursor && blink_on {
-							spans.push(Span::styled(" ", cursor_style));
-							if tab_cells > 1 {
-								spans.push(Span::styled(
-									" ".repeat(tab_cells - 1),
-									non_cursor_style,
-								));
-							}
+						// A block cursor covers only the first cell of a tab.
+						let glyph = marker.map_or_else(|| " ".to_string(), String::from);
+						spans.push(Span::styled(glyph, glyph_style));
+						if shows_cursor && !(use_block_cursor && blink_on) {
+							push_fill(
+								&mut spans,
+								seg_col + 1,
+								seg_col + tab_cells,
+								style,
+								&[],
+								colorcolumn_bg,
+							);
 						} else {
-							spans.push(Span::styled(" ".repeat(tab_cells), style));
+							push_fill(
+								&mut spans,
+								seg_col + 1,
+								seg_col + tab_cells,
+								text_style,
+								stripe_columns,
+								colorcolumn_bg,
+							);
 						}
 
 						seg_col += tab_cells;
 					} else {
-						spans.push(Span::styled(ch.to_string(), style));
-						seg_col += 1;
+						let width = column::grapheme_width(grapheme, seg_col, tab_width);
+						if seg_col + width > content_width {
+							break;
+						}
+						let glyph = marker.map_or_else(|| grapheme.to_string(), String::from);
+						spans.push(Span::styled(glyph, glyph_style));
+						seg_col += width;
 					}
+					i += grapheme_chars;
 				}
 
 				if !is_last_segment && seg_col < text_width {
@@ -504,11 +964,39 @@ impl<'a> BufferRenderContext<'a> {
 						.ui
 						.gutter_fg
 						.blend(self.theme.colors.ui.bg, 0.5);
-					let mut fill_style = Style::default().fg(dim_color);
+					let fill_style = Style::default().fg(dim_color);
 					if is_cursor_line {
-						fill_style = fill_style.bg(cursorline_config.bg);
+						push_fill(
+							&mut spans,
+							seg_col,
+							seg_col + fill_count,
+							fill_style.bg(cursorline_config.bg),
+							&[],
+							colorcolumn_bg,
+						);
+					} else {
+						push_fill(
+							&mut spans,
+							seg_col,
+							seg_col + fill_count,
+							fill_style,
+							colorcolumns,
+							colorcolumn_bg,
+						);
 					}
-					spans.push(Span::styled(" ".repeat(fill_count), fill_style));
+				}
+
+				if clipped_right {
+					push_fill(
+						&mut spans,
+						seg_col,
+						content_width,
+						fill_bg_style,
+						fill_columns,
+						colorcolumn_bg,
+					);
+					spans.push(Span::styled("…", clip_style));
+					seg_col = text_width;
 				}
 
 				if is_last_segment {
@@ -521,7 +1009,9 @@ impl<'a> BufferRenderContext<'a> {
 						}
 					});
 
-					if cursor_at_eol && ((use_block_cursor && blink_on) || !is_focused) {
+					if eol_visible
+						&& cursor_at_eol && ((use_block_cursor && blink_on) || !is_focused)
+					{
 						let primary_here = if is_last_doc_line {
 							primary_cursor >= line_content_end && primary_cursor <= line_end
 						} else {
@@ -538,12 +1028,36 @@ impl<'a> BufferRenderContext<'a> {
 						seg_col += 1;
 					}
 
-					if is_cursor_line && seg_col < text_width {
-						spans.push(Span::styled(
-							" ".repeat(text_width - seg_col),
-							Style::default().bg(cursorline_config.bg),
-						));
+					if let Some(hidden) = folded {
+						seg_col =
+							push_fold_marker(&mut spans, seg_col, text_width, hidden, clip_style);
 					}
+
+					if eol_visible {
+						seg_col = push_eol_hints(
+							&mut spans,
+							hints,
+							seg_col,
+							text_width,
+							hint_style(is_cursor_line),
+						);
+						seg_col = self.push_line_diagnostic(
+							&mut spans,
+							current_line_idx,
+							seg_col,
+							text_width,
+							is_cursor_line.then_some(cursorline_config.bg),
+						);
+					}
+
+					push_eol_fill(
+						&mut spans,
+						seg_col,
+						text_width,
+						is_cursor_line.then_some(cursorline_config.bg),
+						colorcolumns,
+						colorcolumn_bg,
+					);
 				}
 
 				output_lines.push(Line::from(spans));
@@ -553,11 +1067,12 @@ impl<'a> BufferRenderContext<'a> {
 				&& start_segment == 0
 				&& output_lines.len() < viewport_height
 			{
+				let is_cursor_line = cursorline_config.should_highlight(current_line_idx, 0);
 				let mut spans = gutter_layout.render_line(
 					current_line_idx,
 					total_lines,
 					&cursorline_config,
-					false, // not a continuation
+					0,
 					buffer.doc().content.line(current_line_idx),
 					buffer_path,
 					&line_annotations,
@@ -589,19 +1104,44 @@ impl<'a> BufferRenderContext<'a> {
 					spans.push(Span::styled(" ", cursor_style));
 					cols_used = 1;
 				}
-
-				if is_cursor_line && cols_used < text_width {
-					spans.push(Span::styled(
-						" ".repeat(text_width - cols_used),
-						Style::default().bg(cursorline_config.bg),
-					));
+				if let Some(hidden) = folded {
+					let style = Style::default().fg(whitespace_fg);
+					let style = if is_cursor_line {
+						style.bg(cursorline_config.bg)
+					} else {
+						style
+					};
+					cols_used = push_fold_marker(&mut spans, cols_used, text_width, hidden, style);
 				}
+				cols_used = push_eol_hints(
+					&mut spans,
+					line_hints,
+					cols_used,
+					text_width,
+					hint_style(is_cursor_line),
+				);
+				cols_used = self.push_line_diagnostic(
+					&mut spans,
+					current_line_idx,
+					cols_used,
+					text_width,
+					is_cursor_line.then_some(cursorline_config.bg),
+				);
+
+				push_eol_fill(
+					&mut spans,
+					cols_used,
+					text_width,
+					is_cursor_line.then_some(cursorline_config.bg),
+					colorcolumns,
+					colorcolumn_bg,
+				);
 
 				output_lines.push(Line::from(spans));
 			}
 
 			start_segment = 0;
-			current_line_idx += 1;
+			current_line_idx = buffer.next_visible_line(current_line_idx);
 		}
 
 		while output_lines.len() < viewport_height {
@@ -614,3 +1154,136 @@ impl<'a> BufferRenderContext<'a> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cells(spans: &[Span<'static>]) -> Vec<(usize, Option<Color>)> {
+		spans
+			.iter()
+			.map(|span| (span.content.chars().count(), span.style.bg))
+			.collect()
+	}
+
+	#[test]
+	fn fill_tints_only_colorcolumns() {
+		let mut spans = Vec::new();
+		push_fill(&mut spans, 2, 10, Style::default(), &[4, 9, 12], Color::Red);
+		assert_eq!(
+			cells(&spans),
+			vec![
+				(2, None),
+				(1, Some(Color::Red)),
+				(4, None),
+				(1, Some(Color::Red))
+			]
+		);
+	}
+
+	#[test]
+	fn cursorline_fill_covers_colorcolumns() {
+		let mut spans = Vec::new();
+		push_eol_fill(&mut spans, 3, 8, Some(Color::Blue), &[5], Color::Red);
+		assert_eq!(cells(&spans), vec![(5, Some(Color::Blue))]);
+
+		let mut spans = Vec::new();
+		push_eol_fill(&mut spans, 3, 8, None, &[5], Color::Red);
+		assert_eq!(cells(&spans), vec![(2, None), (1, Some(Color::Red))]);
+	}
+
+	#[test]
+	fn inline_diagnostics_are_cut_to_the_width() {
+		let text = |spans: &[Span<'static>]| -> String {
+			spans.iter().map(|span| span.content.as_ref()).collect()
+		};
+
+		let mut spans = Vec::new();
+		let end = push_inline_diagnostic(
+			&mut spans,
+			3,
+			20,
+			"unused",
+			Style::default(),
+			Style::default(),
+		);
+		assert_eq!((text(&spans).as_str(), end), ("  unused", 11));
+
+		let mut spans = Vec::new();
+		let end = push_inline_diagnostic(
+			&mut spans,
+			3,
+			12,
+			"mismatched types",
+			Style::default(),
+			Style::default(),
+		);
+		assert_eq!((text(&spans).as_str(), end), ("  mismat…", 12));
+
+		let mut spans = Vec::new();
+		assert_eq!(
+			push_inline_diagnostic(&mut spans, 9, 11, "x", Style::default(), Style::default()),
+			9
+		);
+		assert!(spans.is_empty());
+	}
+
+	#[test]
+	fn eol_hints_stop_at_the_first_that_does_not_fit() {
+		let label = |text: &str| InlayHintLabel {
+			column: 4,
+			text: text.into(),
+		};
+		let labels = [label(" -> u8"), label(" long label"), label("x")];
+		let mut spans = Vec::new();
+		let end = push_eol_hints(&mut spans, &labels, 5, 20, Style::default());
+		assert_eq!(end, 11);
+		assert_eq!(spans.len(), 1);
+	}
+
+	#[test]
+	fn text_end_col_lays_out_tabs_like_the_renderer() {
+		assert_eq!(text_end_col("ab\tc", 0, 0, 0, 4), 5);
+		// A continuation row indented by 2 starts its tab stops there.
+		assert_eq!(text_end_col("\tx", 2, 2, 0, 4), 7);
+		// Horizontal scrolling shifts the stops.
+		assert_eq!(text_end_col("\t", 0, 0, 1, 4), 3);
+	}
+
+	#[test]
+	fn search_highlights_mark_current_match() {
+		let search = SearchHighlights {
+			ranges: vec![Range::new(2, 5), Range::new(8, 10)],
+			current: Some(Range::new(8, 10)),
+		};
+		assert_eq!(search.match_at(1), None);
+		assert_eq!(search.match_at(2), Some(false));
+		assert_eq!(search.match_at(5), None);
+		assert_eq!(search.match_at(9), Some(true));
+		assert_eq!(search.match_at(10), None);
+	}
+
+	#[test]
+	fn symbol_highlights_tell_writes_from_reads() {
+		let symbols = SymbolHighlights {
+			ranges: vec![(Range::new(0, 3), true), (Range::new(7, 10), false)],
+		};
+		assert_eq!(symbols.write_at(0), Some(true));
+		assert_eq!(symbols.write_at(3), None);
+		assert_eq!(symbols.write_at(9), Some(false));
+		assert_eq!(symbols.write_at(10), None);
+	}
+
+	#[test]
+	fn cursorline_highlights_cursor_segment_only() {
+		let config = CursorlineConfig {
+			enabled: true,
+			bg: Color::Blue,
+			line: 3,
+			segment: 1,
+		};
+		assert!(config.should_highlight(3, 1));
+		assert!(!config.should_highlight(3, 0));
+		assert!(!config.should_highlight(4, 1));
+	}
+}
@@ -1,10 +1,16 @@
 //! Diagnostic types and builders for buffer rendering.
 //!
-//! This module provides types for mapping LSP diagnostics to line-based
-//! structures suitable for gutter signs and inline underlines.
+//! This module maps a document's prepared diagnostics to line-based
+//! structures for gutter signs, inline underlines and end-of-line messages,
+//! each drawn only if `diagnostics-display` asks for it.
 
 use std::collections::HashMap;
 
+use xeno_registry::themes::Theme;
+use xeno_tui::style::Color;
+
+use crate::buffer::PreparedDiagnostics;
+
 /// Map from line number (0-indexed) to diagnostic severity (gutter format).
 ///
 /// Severity values match `GutterAnnotations::diagnostic_severity`:
@@ -31,81 +37,173 @@ pub struct DiagnosticSpan {
 /// Used for rendering underlines under diagnostic ranges.
 pub type DiagnosticRangeMap = HashMap<usize, Vec<DiagnosticSpan>>;
 
-/// Builds a diagnostic line map from LSP diagnostics.
-///
-/// Converts LSP severity to gutter severity and keeps only the highest
-/// severity per line.
-#[cfg(feature = "lsp")]
-pub fn build_diagnostic_line_map(
-	diagnostics: &[xeno_lsp::lsp_types::Diagnostic],
-) -> DiagnosticLineMap {
-	use xeno_lsp::lsp_types::DiagnosticSeverity;
-
-	let mut map = DiagnosticLineMap::new();
-
-	for diag in diagnostics {
-		let line = diag.range.start.line as usize;
-		// LSP: 1=Error, 2=Warning, 3=Info, 4=Hint → Gutter: 4, 3, 2, 1
-		let severity = match diag.severity {
-			Some(DiagnosticSeverity::ERROR) => 4,
-			Some(DiagnosticSeverity::WARNING) => 3,
-			Some(DiagnosticSeverity::INFORMATION) => 2,
-			Some(DiagnosticSeverity::HINT) => 1,
-			_ => 0,
-		};
-		map.entry(line)
-			.and_modify(|e| *e = (*e).max(severity))
-			.or_insert(severity);
+/// Message drawn after the end of a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineDiagnostic {
+	/// Severity level (same as gutter format).
+	pub severity: u8,
+	/// First line of the message.
+	pub message: String,
+}
+
+/// Map from line number to the message drawn after that line.
+pub type DiagnosticInlineMap = HashMap<usize, InlineDiagnostic>;
+
+/// Which parts of the diagnostics display are drawn, parsed from the
+/// `diagnostics-display` option.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiagnosticsDisplay {
+	/// Gutter sign on each line with a diagnostic.
+	pub signs: bool,
+	/// Curly underline under each diagnostic range.
+	pub underline: bool,
+	/// First message of a line drawn after its end.
+	pub inline: bool,
+}
+
+impl DiagnosticsDisplay {
+	/// Parses a comma-separated combination of `signs`, `underline` and
+	/// `inline`; `off` and unknown names enable nothing.
+	pub fn parse(value: &str) -> Self {
+		let mut display = Self::default();
+		for part in value.split(',').map(str::trim) {
+			match part {
+				"signs" => display.signs = true,
+				"underline" => display.underline = true,
+				"inline" => display.inline = true,
+				_ => {}
+			}
+		}
+		display
 	}
+}
 
-	map
+/// Diagnostics of a buffer laid out for one render pass.
+#[derive(Debug, Default)]
+pub struct DiagnosticMaps {
+	/// Highest severity per line, for gutter signs.
+	pub lines: DiagnosticLineMap,
+	/// Ranges per line, for underlines.
+	pub ranges: DiagnosticRangeMap,
+	/// Message per line, for inline virtual text.
+	pub inline: DiagnosticInlineMap,
 }
 
-/// Builds a diagnostic range map from LSP diagnostics.
-///
-/// Creates per-line spans with character ranges for rendering underlines.
-#[cfg(feature = "lsp")]
-pub fn build_diagnostic_range_map(
-	diagnostics: &[xeno_lsp::lsp_types::Diagnostic],
-) -> DiagnosticRangeMap {
-	use xeno_lsp::lsp_types::DiagnosticSeverity;
-
-	let mut map = DiagnosticRangeMap::new();
-
-	for diag in diagnostics {
-		let severity = match diag.severity {
-			Some(DiagnosticSeverity::ERROR) => 4,
-			Some(DiagnosticSeverity::WARNING) => 3,
-			Some(DiagnosticSeverity::INFORMATION) => 2,
-			Some(DiagnosticSeverity::HINT) => 1,
-			_ => 0,
-		};
-
-		if severity == 0 {
-			continue;
+impl DiagnosticMaps {
+	/// Lays out the `diagnostics` of `text`, filling only the maps that
+	/// `display` draws.
+	pub fn build(
+		diagnostics: &PreparedDiagnostics,
+		text: &ropey::Rope,
+		display: DiagnosticsDisplay,
+	) -> Self {
+		let mut maps = Self::default();
+		let len = text.len_chars();
+		for diag in diagnostics.iter() {
+			let start = diag.start.min(len);
+			let end = diag.end.clamp(start, len);
+			let start_line = text.char_to_line(start);
+
+			if display.signs {
+				maps.lines
+					.entry(start_line)
+					.and_modify(|e| *e = (*e).max(diag.severity))
+					.or_insert(diag.severity);
+			}
+
+			if display.inline && !maps.inline.contains_key(&start_line) {
+				let message = diag.message.lines().next().unwrap_or_default().trim();
+				maps.inline.insert(
+					start_line,
+					InlineDiagnostic {
+						severity: diag.severity,
+						message: message.to_string(),
+					},
+				);
+			}
+
+			if display.underline {
+				let end_line = text.char_to_line(end);
+				for line in start_line..=end_line {
+					let start_char = if line == start_line {
+						start - text.line_to_char(line)
+					} else {
+						0
+					};
+					let end_char = if line == end_line {
+						end - text.line_to_char(line)
+					} else {
+						usize::MAX
+					};
+					maps.ranges.entry(line).or_default().push(DiagnosticSpan {
+						start_char,
+						end_char,
+						severity: diag.severity,
+					});
+				}
+			}
 		}
+		maps
+	}
+}
+
+/// Returns the status color of a diagnostic severity; hints are dimmed.
+pub fn severity_color(theme: &Theme, severity: u8) -> Color {
+	let status = &theme.colors.status;
+	match severity {
+		4 => status.error_fg,
+		3 => status.warning_fg,
+		2 => status.accent_fg,
+		_ => status.dim_fg,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::buffer::PreparedDiagnostic;
 
-		let start_line = diag.range.start.line as usize;
-		let end_line = diag.range.end.line as usize;
-
-		for line in start_line..=end_line {
-			let start_char = if line == start_line {
-				diag.range.start.character as usize
-			} else {
-				0
-			};
-			let end_char = if line == end_line {
-				diag.range.end.character as usize
-			} else {
-				usize::MAX
-			};
-			map.entry(line).or_default().push(DiagnosticSpan {
-				start_char,
-				end_char,
-				severity,
-			});
+	fn diagnostic(start: usize, end: usize, severity: u8, message: &str) -> PreparedDiagnostic {
+		PreparedDiagnostic {
+			start,
+			end,
+			severity,
+			message: message.into(),
+			source: None,
 		}
 	}
 
-	map
+	#[test]
+	fn maps_follow_the_display_option() {
+		let text = ropey::Rope::from("fn main() {\n\tlet x = y;\n}\n");
+		let mut diagnostics = PreparedDiagnostics::default();
+		diagnostics.replace(vec![
+			diagnostic(17, 18, 1, "unused variable\nhelp: prefix it"),
+			diagnostic(21, 22, 4, "cannot find value `y`"),
+			diagnostic(10, 23, 3, "unreachable block"),
+		]);
+
+		let maps = DiagnosticMaps::build(
+			&diagnostics,
+			&text,
+			DiagnosticsDisplay::parse("signs, inline"),
+		);
+		assert_eq!(maps.lines, HashMap::from([(1, 4), (0, 3)]));
+		assert!(maps.ranges.is_empty());
+		assert_eq!(maps.inline[&1].message, "unused variable");
+		assert_eq!(maps.inline[&0].severity, 3);
+
+		let maps =
+			DiagnosticMaps::build(&diagnostics, &text, DiagnosticsDisplay::parse("underline"));
+		assert!(maps.lines.is_empty() && maps.inline.is_empty());
+		let spans: Vec<_> = maps.ranges[&1]
+			.iter()
+			.map(|s| (s.start_char, s.end_char))
+			.collect();
+		assert_eq!(spans, vec![(5, 6), (9, 10), (0, 11)]);
+		assert_eq!(maps.ranges[&0][0].start_char, 10);
+
+		let maps = DiagnosticMaps::build(&diagnostics, &text, DiagnosticsDisplay::parse("off"));
+		assert!(maps.lines.is_empty() && maps.ranges.is_empty() && maps.inline.is_empty());
+	}
 }
@@ -12,6 +12,7 @@ use xeno_tui::style::{Color, Style};
 use xeno_tui::text::Span;
 
 use super::context::CursorlineConfig;
+use super::diagnostics::severity_color;
 use crate::window::GutterSelector;
 
 enum GutterLayoutKind {
@@ -35,19 +36,15 @@ pub struct GutterLayout {
 
 impl GutterLayout {
 	/// Creates a new gutter layout for a buffer.
-	pub fn new(total_lines: usize, viewport_width: u16) -> Self {
-		Self::from_registry(total_lines, viewport_width)
+	pub fn new(ctx: GutterWidthContext) -> Self {
+		Self::from_registry(ctx)
 	}
 
 	/// Builds a gutter layout from a selector.
-	pub fn from_selector(
-		selector: GutterSelector,
-		total_lines: usize,
-		viewport_width: u16,
-	) -> Self {
+	pub fn from_selector(selector: GutterSelector, ctx: GutterWidthContext) -> Self {
 		match selector {
-			GutterSelector::Registry => Self::new(total_lines, viewport_width),
-			GutterSelector::Named(names) => Self::from_names(names, total_lines, viewport_width),
+			GutterSelector::Registry => Self::new(ctx),
+			GutterSelector::Named(names) => Self::from_names(names, ctx),
 			GutterSelector::Hidden => Self::hidden(),
 			GutterSelector::Prompt(prompt) => Self::prompt(prompt),
 			GutterSelector::Custom { width, render } => Self::custom(width, render),
@@ -55,11 +52,7 @@ impl GutterLayout {
 	}
 
 	/// Creates a gutter layout using registered gutter names.
-	pub fn from_names(names: &[&str], total_lines: usize, viewport_width: u16) -> Self {
-		let ctx = GutterWidthContext {
-			total_lines,
-			viewport_width,
-		};
+	pub fn from_names(names: &[&str], ctx: GutterWidthContext) -> Self {
 		let mut columns: Vec<(u16, &'static xeno_registry::gutter::GutterDef)> = names
 			.iter()
 			.filter_map(|name| find_gutter(name))
@@ -102,11 +95,7 @@ impl GutterLayout {
 		}
 	}
 
-	fn from_registry(total_lines: usize, viewport_width: u16) -> Self {
-		let ctx = GutterWidthContext {
-			total_lines,
-			viewport_width,
-		};
+	fn from_registry(ctx: GutterWidthContext) -> Self {
 		let columns = column_widths(&ctx);
 		Self {
 			total_width: total_width(&ctx),
@@ -127,20 +116,23 @@ impl GutterLayout {
 		}
 	}
 
-	/// Renders gutter spans for a single line.
+	/// Renders gutter spans for wrap segment `segment` of a single line.
 	#[allow(clippy::too_many_arguments)]
 	pub fn render_line(
 		&self,
 		line_idx: usize,
 		total_lines: usize,
 		cursorline: &CursorlineConfig,
-		is_continuation: bool,
+		segment: usize,
 		line_text: RopeSlice<'_>,
 		path: Option<&Path>,
 		annotations: &GutterAnnotations,
 		theme: &Theme,
 	) -> Vec<Span<'static>> {
-		let is_cursor_line = cursorline.should_highlight(line_idx);
+		let is_cursor_line = cursorline.should_highlight(line_idx, segment);
+		let is_continuation = segment > 0;
+		// Line numbers follow the cursor even when the highlight is disabled.
+		let on_cursor_line = line_idx == cursorline.line;
 
 		match &self.kind {
 			GutterLayoutKind::Hidden => Vec::new(),
@@ -169,7 +161,7 @@ impl GutterLayout {
 					line_idx,
 					total_lines,
 					cursor_line: cursorline.line,
-					is_cursor_line,
+					is_cursor_line: on_cursor_line,
 					is_continuation,
 					line_text,
 					path,
@@ -190,7 +182,7 @@ impl GutterLayout {
 					line_idx,
 					total_lines,
 					cursor_line: cursorline.line,
-					is_cursor_line,
+					is_cursor_line: on_cursor_line,
 					is_continuation,
 					line_text,
 					path,
@@ -265,12 +257,12 @@ impl GutterLayout {
 			Some(cell) => {
 				let fg = match cell.style {
 					GutterStyle::Normal | GutterStyle::Cursor => theme.colors.ui.gutter_fg,
-					GutterStyle::Dim | GutterStyle::Hint => {
-						theme.colors.ui.gutter_fg.blend(theme.colors.ui.bg, 0.5)
-					}
-					GutterStyle::Error => theme.colors.status.error_fg,
-					GutterStyle::Warning => theme.colors.status.warning_fg,
-					GutterStyle::Info => Color::Blue,
+					GutterStyle::Dim => theme.colors.ui.gutter_fg.blend(theme.colors.ui.bg, 0.5),
+					// Diagnostic signs share the colors of their underlines.
+					GutterStyle::Error => severity_color(theme, 4),
+					GutterStyle::Warning => severity_color(theme, 3),
+					GutterStyle::Info => severity_color(theme, 2),
+					GutterStyle::Hint => severity_color(theme, 1),
 				};
 
 				let mut style = Style::default().fg(fg);
@@ -0,0 +1,112 @@
+//! Inlay hints laid out for buffer rendering.
+//!
+//! Hints are drawn as virtual text before the character they sit at, with
+//! their padding and labels cut to `inlay-hint-max-length`.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::buffer::PreparedInlayHints;
+
+/// A hint label drawn within a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHintLabel {
+	/// Character of the line the label is drawn before; the line's length
+	/// for labels after its end.
+	pub column: usize,
+	/// Text drawn, padding included.
+	pub text: String,
+}
+
+/// Hint labels of the lines of one render pass.
+#[derive(Debug, Default)]
+pub struct InlayHintMap {
+	/// Labels of each line, ordered by column.
+	lines: HashMap<usize, Vec<InlayHintLabel>>,
+}
+
+impl InlayHintMap {
+	/// Lays out the `hints` of `text` on `lines`, cutting labels longer
+	/// than `max_len` characters unless it is zero.
+	pub fn build(
+		hints: &PreparedInlayHints,
+		text: &ropey::Rope,
+		lines: Range<usize>,
+		max_len: usize,
+	) -> Self {
+		let mut map = Self::default();
+		let len = text.len_chars();
+		let first = text.line_to_char(lines.start.min(text.len_lines()));
+		let last = text.line_to_char(lines.end.min(text.len_lines()));
+		for hint in hints.iter() {
+			let pos = hint.pos.min(len);
+			if pos < first || pos > last {
+				continue;
+			}
+			let line = text.char_to_line(pos);
+			if !lines.contains(&line) {
+				continue;
+			}
+			let label = hint.label(max_len).replace(char::is_control, " ");
+			if label.is_empty() {
+				continue;
+			}
+			map.lines.entry(line).or_default().push(InlayHintLabel {
+				column: pos - text.line_to_char(line),
+				text: label,
+			});
+		}
+		map
+	}
+
+	/// Returns the labels of a line, ordered by column.
+	pub fn line(&self, line: usize) -> &[InlayHintLabel] {
+		self.lines.get(&line).map_or(&[], Vec::as_slice)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::buffer::{InlayHintKind, InlayHintPart, PreparedInlayHint};
+
+	fn hint(pos: usize, text: &str, padding_left: bool) -> PreparedInlayHint {
+		PreparedInlayHint {
+			pos,
+			kind: InlayHintKind::Type,
+			parts: vec![InlayHintPart {
+				text: text.into(),
+				tooltip: None,
+			}],
+			padding_left,
+			padding_right: false,
+			tooltip: None,
+		}
+	}
+
+	#[test]
+	fn labels_land_on_their_lines_in_column_order() {
+		let text = ropey::Rope::from("let a = 1;\nlet b = f(a);\nlet c = 3;\n");
+		let mut hints = PreparedInlayHints::default();
+		hints.replace(
+			0..text.len_chars(),
+			vec![
+				hint(5, ": i32", false),
+				hint(21, "x:", false),
+				hint(16, ": Vec<String>", false),
+				hint(29, "unseen", false),
+				hint(24, "ends here", true),
+			],
+		);
+
+		let map = InlayHintMap::build(&hints, &text, 1..2, 6);
+		assert!(map.line(0).is_empty());
+		assert!(map.line(2).is_empty());
+		let labels: Vec<_> = map
+			.line(1)
+			.iter()
+			.map(|label| (label.column, label.text.as_str()))
+			.collect();
+		assert_eq!(labels, [(5, ": Vec…"), (10, "x:"), (13, " ends …")]);
+	}
+}
@@ -7,11 +7,18 @@
 mod context;
 mod diagnostics;
 mod gutter;
+mod inlay_hints;
 mod viewport;
+mod whitespace;
 
-pub use context::{BufferRenderContext, RenderResult};
-pub use diagnostics::DiagnosticLineMap;
-#[cfg(feature = "lsp")]
-#[allow(unused_imports, reason = "re-exported for public API completeness")]
-pub use diagnostics::{DiagnosticRangeMap, build_diagnostic_line_map, build_diagnostic_range_map};
-pub use viewport::ensure_buffer_cursor_visible;
+pub use context::{
+	BufferRenderContext, LineHighlights, RenderResult, SearchHighlights, SymbolHighlights,
+};
+pub use diagnostics::{
+	DiagnosticInlineMap, DiagnosticLineMap, DiagnosticMaps, DiagnosticRangeMap, DiagnosticsDisplay,
+	InlineDiagnostic,
+};
+pub use inlay_hints::{InlayHintLabel, InlayHintMap};
+pub(crate) use viewport::cursor_visual_row;
+pub use viewport::{ScrollMargins, ensure_buffer_cursor_visible, horizontal_scroll_offset};
+pub use whitespace::{ShowWhitespace, WhitespaceMarkers};
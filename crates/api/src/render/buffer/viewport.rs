@@ -6,7 +6,7 @@ use xeno_base::range::CharIdx;
 use xeno_tui::layout::Rect;
 
 use crate::buffer::Buffer;
-use crate::render::wrap::{WrapSegment, wrap_line};
+use crate::render::wrap::WrapSegment;
 
 /// Test event emitted when viewport scrolling occurs.
 #[derive(Serialize)]
@@ -63,6 +63,15 @@ impl ViewportEnsureEvent {
 	}
 }
 
+/// Minimum distances kept between the primary cursor and the viewport edges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrollMargins {
+	/// Visual rows above and below the cursor (`scrolloff`).
+	pub rows: usize,
+	/// Columns left and right of the cursor while unwrapped (`sidescrolloff`).
+	pub columns: usize,
+}
+
 /// Ensures the cursor is visible in the buffer's viewport with scroll margins.
 ///
 /// This function adjusts `buffer.scroll_line` and `buffer.scroll_segment` to ensure
@@ -71,10 +80,12 @@ impl ViewportEnsureEvent {
 ///
 /// # Scroll Margin
 ///
-/// The `scroll_margin` parameter specifies the preferred minimum lines between
-/// the cursor and viewport edges. When the cursor moves within this zone, the
-/// viewport scrolls to restore the margin. At buffer boundaries (first/last line),
-/// the cursor is allowed to reach the edge since scrolling further is impossible.
+/// `margins.rows` specifies the preferred minimum visual rows between the
+/// cursor and viewport edges, counting each wrap segment of a long line as a
+/// row. When the cursor moves within this zone, the viewport scrolls to
+/// restore the margin. At buffer boundaries (first/last row), the cursor is
+/// allowed to reach the edge since scrolling further is impossible. A margin
+/// larger than half the viewport keeps the cursor on the middle row.
 ///
 /// # Viewport Shrink Behavior
 ///
@@ -91,12 +102,13 @@ impl ViewportEnsureEvent {
 /// - `buffer`: The buffer to ensure cursor visibility for
 /// - `area`: The rectangular area the buffer is rendered into
 /// - `tab_width`: Number of spaces a tab character occupies (from options)
-/// - `scroll_margin`: Preferred minimum lines above/below cursor
+/// - `margins`: Preferred minimum rows above/below cursor, and columns
+///   left/right of it while lines are not wrapped
 pub fn ensure_buffer_cursor_visible(
 	buffer: &mut Buffer,
 	area: Rect,
 	tab_width: usize,
-	scroll_margin: usize,
+	margins: ScrollMargins,
 ) {
 	let total_lines = buffer.doc().content.len_lines();
 	let gutter_width = buffer.gutter_width();
@@ -106,6 +118,7 @@ pub fn ensure_buffer_cursor_visible(
 	let cursor_pos: CharIdx = buffer.cursor;
 	if cursor_pos != buffer.last_rendered_cursor {
 		buffer.suppress_scroll_down = false;
+		buffer.viewport_detached = false;
 	}
 
 	let prev_viewport_height = buffer.last_viewport_height;
@@ -130,6 +143,12 @@ pub fn ensure_buffer_cursor_visible(
 		buffer.scroll_line = total_lines.saturating_sub(1);
 		buffer.scroll_segment = 0;
 	}
+	// A fold closed over the top line leaves its header at the top.
+	let scroll_line = buffer.doc().folds.visible_line(buffer.scroll_line);
+	if scroll_line != buffer.scroll_line {
+		buffer.scroll_line = scroll_line;
+		buffer.scroll_segment = 0;
+	}
 	buffer.scroll_segment = clamp_segment_for_line(
 		buffer,
 		buffer.scroll_line,
@@ -138,6 +157,26 @@ pub fn ensure_buffer_cursor_visible(
 		tab_width,
 	);
 
+	if buffer.wrap {
+		buffer.left_col = 0;
+	}
+
+	if buffer.viewport_detached {
+		buffer.last_rendered_cursor = cursor_pos;
+		return;
+	}
+
+	if !buffer.wrap {
+		// Keep at least one column so the cursor never sits under a clip marker.
+		let cursor_col = buffer.visual_column(cursor_pos, tab_width);
+		buffer.left_col = horizontal_scroll_offset(
+			buffer.left_col,
+			cursor_col,
+			text_width,
+			margins.columns.max(1),
+		);
+	}
+
 	let cursor_line = buffer.cursor_line();
 	let cursor_line_start: CharIdx = buffer.doc().content.line_to_char(cursor_line);
 	let cursor_col = cursor_pos.saturating_sub(cursor_line_start);
@@ -153,14 +192,28 @@ pub fn ensure_buffer_cursor_visible(
 		.slice(cursor_line_start..cursor_line_end)
 		.into();
 	let cursor_line_text = cursor_line_text.trim_end_matches('\n');
-	let cursor_segments = wrap_line(cursor_line_text, text_width, tab_width);
+	let cursor_segments = buffer.wrap_line(cursor_line_text, text_width, tab_width);
 	let cursor_segment = find_segment_for_col(&cursor_segments, cursor_col);
 
-	let effective_margin = scroll_margin.min(viewport_height.saturating_sub(1) / 2);
-	let min_row = effective_margin; // cursor should be at least this far from top
-	let max_row = viewport_height
-		.saturating_sub(1)
-		.saturating_sub(effective_margin);
+	let last_row = viewport_height.saturating_sub(1);
+	let (top_margin, bottom_margin) = if margins.rows > viewport_height / 2 {
+		let center = last_row / 2;
+		(center, last_row - center)
+	} else {
+		let margin = margins.rows.min(last_row / 2);
+		(margin, margin)
+	};
+	// Near the end of the buffer there may be fewer rows left than the margin.
+	let rows_below = visual_rows_below(
+		buffer,
+		cursor_line,
+		cursor_segment,
+		bottom_margin,
+		text_width,
+		tab_width,
+	);
+	let min_row = top_margin; // cursor should be at least this far from top
+	let max_row = last_row - bottom_margin.min(rows_below);
 
 	// Find cursor's current visual row in viewport (None if not visible)
 	let cursor_row = cursor_row_in_viewport(
@@ -180,12 +233,12 @@ pub fn ensure_buffer_cursor_visible(
 			cursor_line < buffer.scroll_line
 				|| (cursor_line == buffer.scroll_line && cursor_segment < buffer.scroll_segment)
 		}
-		Some(row) => row < min_row && buffer.scroll_line > 0,
+		Some(row) => row < min_row && (buffer.scroll_line > 0 || buffer.scroll_segment > 0),
 	};
 
 	let needs_scroll_down = match cursor_row {
 		None => !needs_scroll_up, // cursor below viewport
-		Some(row) => row > max_row && cursor_line + 1 < total_lines,
+		Some(row) => row > max_row,
 	};
 
 	// Handle viewport shrinking - don't chase cursor downward
@@ -282,12 +335,14 @@ fn scroll_position_for_cursor_at_row(
 	let mut segment = cursor_segment;
 	let mut rows_above = 0;
 
-	while rows_above < target_row && (line > 0 || segment > 0) {
+	while rows_above < target_row {
 		if segment > 0 {
 			segment -= 1;
-		} else {
-			line -= 1;
+		} else if let Some(prev_line) = buffer.prev_visible_line(line) {
+			line = prev_line;
 			segment = line_segment_count(buffer, line, text_width, tab_width).saturating_sub(1);
+		} else {
+			break;
 		}
 		rows_above += 1;
 	}
@@ -295,6 +350,85 @@ fn scroll_position_for_cursor_at_row(
 	(line, segment)
 }
 
+/// Returns the left column offset that keeps `cursor_col` visible in an
+/// unwrapped line `text_width` cells wide, at least `margin` columns from
+/// either edge where possible.
+///
+/// A margin larger than half the width keeps the cursor centered.
+pub fn horizontal_scroll_offset(
+	left_col: usize,
+	cursor_col: usize,
+	text_width: usize,
+	margin: usize,
+) -> usize {
+	if text_width == 0 {
+		return cursor_col;
+	}
+	let last_col = text_width - 1;
+	let (left_margin, right_margin) = if margin > text_width / 2 {
+		let center = last_col / 2;
+		(center, last_col - center)
+	} else {
+		let margin = margin.min(last_col / 2);
+		(margin, margin)
+	};
+
+	if cursor_col < left_col + left_margin {
+		cursor_col.saturating_sub(left_margin)
+	} else if cursor_col > left_col + last_col - right_margin {
+		cursor_col + right_margin - last_col
+	} else {
+		left_col
+	}
+}
+
+/// Counts the visual rows after the given position, stopping at `limit`.
+fn visual_rows_below(
+	buffer: &Buffer,
+	line: usize,
+	segment: usize,
+	limit: usize,
+	text_width: usize,
+	tab_width: usize,
+) -> usize {
+	let (mut line, mut segment) = (line, segment);
+	let mut rows = 0;
+	while rows < limit
+		&& advance_one_visual_row(buffer, &mut line, &mut segment, text_width, tab_width)
+	{
+		rows += 1;
+	}
+	rows
+}
+
+/// Returns the wrap segment of its line that holds the primary cursor.
+pub(super) fn cursor_segment(buffer: &Buffer, text_width: usize, tab_width: usize) -> usize {
+	let cursor_line = buffer.cursor_line();
+	let (line_start, line_text) = {
+		let doc = buffer.doc();
+		let line_text: String = doc.content.line(cursor_line).into();
+		(doc.content.line_to_char(cursor_line), line_text)
+	};
+	let segments = buffer.wrap_line(line_text.trim_end_matches('\n'), text_width, tab_width);
+	find_segment_for_col(&segments, buffer.cursor.saturating_sub(line_start))
+}
+
+/// Returns the primary cursor's visual row within the buffer's current
+/// viewport, or `None` if it is off screen.
+pub(crate) fn cursor_visual_row(buffer: &Buffer, tab_width: usize) -> Option<usize> {
+	let text_width = buffer.text_width;
+	cursor_row_in_viewport(
+		buffer,
+		buffer.scroll_line,
+		buffer.scroll_segment,
+		buffer.cursor_line(),
+		cursor_segment(buffer, text_width, tab_width),
+		buffer.last_viewport_height,
+		text_width,
+		tab_width,
+	)
+}
+
 /// Returns the number of wrap segments for a line.
 fn line_segment_count(buffer: &Buffer, line: usize, text_width: usize, tab_width: usize) -> usize {
 	let total_lines = buffer.doc().content.len_lines();
@@ -311,7 +445,10 @@ fn line_segment_count(buffer: &Buffer, line: usize, text_width: usize, tab_width
 
 	let line_text: String = buffer.doc().content.slice(line_start..line_end).into();
 	let line_text = line_text.trim_end_matches('\n');
-	wrap_line(line_text, text_width, tab_width).len().max(1)
+	buffer
+		.wrap_line(line_text, text_width, tab_width)
+		.len()
+		.max(1)
 }
 
 /// Clamps a segment index to valid range for a given line.
@@ -336,7 +473,7 @@ fn clamp_segment_for_line(
 
 	let line_text: String = buffer.doc().content.slice(line_start..line_end).into();
 	let line_text = line_text.trim_end_matches('\n');
-	let segments = wrap_line(line_text, text_width, tab_width);
+	let segments = buffer.wrap_line(line_text, text_width, tab_width);
 	let num_segments = segments.len().max(1);
 
 	segment.min(num_segments.saturating_sub(1))
@@ -389,7 +526,8 @@ fn cursor_row_in_viewport(
 	None
 }
 
-/// Advances the viewport position by one visual row.
+/// Advances the viewport position by one visual row, stepping over the
+/// lines closed folds hide.
 fn advance_one_visual_row(
 	buffer: &Buffer,
 	line: &mut usize,
@@ -411,7 +549,7 @@ fn advance_one_visual_row(
 
 	let line_text: String = buffer.doc().content.slice(line_start..line_end).into();
 	let line_text = line_text.trim_end_matches('\n');
-	let segments = wrap_line(line_text, text_width, tab_width);
+	let segments = buffer.wrap_line(line_text, text_width, tab_width);
 	let num_segments = segments.len().max(1);
 
 	if *segment + 1 < num_segments {
@@ -419,11 +557,103 @@ fn advance_one_visual_row(
 		return true;
 	}
 
-	if *line + 1 < total_lines {
-		*line += 1;
+	let next_line = buffer.next_visible_line(*line);
+	if next_line < total_lines {
+		*line = next_line;
 		*segment = 0;
 		return true;
 	}
 
 	false
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::buffer::BufferId;
+
+	fn buffer_at_line(content: String, line: usize) -> Buffer {
+		let mut buffer = Buffer::new(BufferId(1), content, None);
+		let pos = buffer.doc().content.line_to_char(line);
+		buffer.set_cursor(pos);
+		buffer
+	}
+
+	fn short_lines(count: usize) -> String {
+		(0..count).map(|i| format!("line {i}\n")).collect()
+	}
+
+	fn margins(rows: usize) -> ScrollMargins {
+		ScrollMargins { rows, columns: 0 }
+	}
+
+	#[test]
+	fn scrolloff_keeps_rows_below_cursor() {
+		let mut buffer = buffer_at_line(short_lines(50), 8);
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins(3));
+		assert_eq!(cursor_visual_row(&buffer, 4), Some(6));
+		assert_eq!(buffer.scroll_line, 2);
+	}
+
+	#[test]
+	fn scrolloff_counts_wrapped_rows() {
+		let mut content = short_lines(4);
+		content.push_str(&"word ".repeat(40));
+		content.push('\n');
+		content.push_str(&short_lines(10));
+		let mut buffer = buffer_at_line(content, 5);
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 8), 4, margins(2));
+		assert_eq!(cursor_visual_row(&buffer, 4), Some(5));
+		assert!(buffer.scroll_segment > 0 || buffer.scroll_line > 4);
+	}
+
+	#[test]
+	fn large_scrolloff_centers_cursor() {
+		let mut buffer = buffer_at_line(short_lines(50), 20);
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins(100));
+		assert_eq!(cursor_visual_row(&buffer, 4), Some(4));
+	}
+
+	#[test]
+	fn scrolloff_yields_at_buffer_end() {
+		let mut buffer = buffer_at_line(short_lines(50), 50);
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins(3));
+		assert_eq!(cursor_visual_row(&buffer, 4), Some(9));
+	}
+
+	#[test]
+	fn closed_folds_take_one_row() {
+		let mut buffer = buffer_at_line(short_lines(50), 30);
+		buffer.doc_mut().folds.replace([(5, 24)]);
+		buffer.doc_mut().folds.set_all_closed(true);
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins(0));
+		assert_eq!(buffer.scroll_line, 2);
+		assert_eq!(cursor_visual_row(&buffer, 4), Some(9));
+	}
+
+	#[test]
+	fn sidescrolloff_keeps_columns_around_cursor() {
+		assert_eq!(horizontal_scroll_offset(0, 5, 20, 3), 0);
+		assert_eq!(horizontal_scroll_offset(0, 18, 20, 3), 2);
+		assert_eq!(horizontal_scroll_offset(10, 11, 20, 3), 8);
+		assert_eq!(horizontal_scroll_offset(0, 30, 20, 100), 21);
+	}
+
+	#[test]
+	fn unwrapped_view_scrolls_to_cursor_column() {
+		let mut buffer = buffer_at_line(format!("{}\n", "x".repeat(100)), 0);
+		buffer.wrap = false;
+		buffer.set_cursor(60);
+		let margins = ScrollMargins {
+			rows: 0,
+			columns: 3,
+		};
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins);
+		assert!(buffer.left_col > 0);
+		assert!(60 + 3 < buffer.left_col + buffer.text_width);
+
+		buffer.wrap = true;
+		ensure_buffer_cursor_visible(&mut buffer, Rect::new(0, 0, 40, 10), 4, margins);
+		assert_eq!(buffer.left_col, 0);
+	}
+}
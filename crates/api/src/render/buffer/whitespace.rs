@@ -0,0 +1,109 @@
+//! Visible whitespace markers.
+//!
+//! Markers only replace the glyphs drawn for whitespace; cell widths, cursor
+//! columns and buffer text are unaffected.
+
+/// Which whitespace the `show-whitespace` option makes visible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShowWhitespace {
+	/// No markers.
+	Off,
+	/// Whitespace after the last non-blank character of a line.
+	#[default]
+	Trailing,
+	/// All whitespace.
+	All,
+}
+
+impl ShowWhitespace {
+	/// Parses a `show-whitespace` option value.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"off" => Some(Self::Off),
+			"trailing" => Some(Self::Trailing),
+			"all" => Some(Self::All),
+			_ => None,
+		}
+	}
+}
+
+/// Whitespace rendering options for a buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceMarkers {
+	/// Which whitespace is marked.
+	pub show: ShowWhitespace,
+	/// Marker drawn in the first cell of a tab.
+	pub tab: char,
+	/// Marker drawn for a space.
+	pub space: char,
+	/// Marker drawn for a non-breaking space.
+	pub nbsp: char,
+}
+
+impl Default for WhitespaceMarkers {
+	fn default() -> Self {
+		Self {
+			show: ShowWhitespace::Off,
+			tab: '→',
+			space: '·',
+			nbsp: '⍽',
+		}
+	}
+}
+
+impl WhitespaceMarkers {
+	/// Returns the char offset within `line` where trailing whitespace
+	/// starts, or `None` if no marker can be drawn on the line.
+	pub fn marked_from(&self, line: &str) -> Option<usize> {
+		match self.show {
+			ShowWhitespace::Off => None,
+			ShowWhitespace::All => Some(0),
+			ShowWhitespace::Trailing => Some(line.trim_end().chars().count()),
+		}
+	}
+
+	/// Returns the marker for `grapheme` at char offset `char_in_line`, given
+	/// the offset returned by [`marked_from`](Self::marked_from).
+	pub fn marker(&self, grapheme: &str, char_in_line: usize, marked_from: usize) -> Option<char> {
+		if char_in_line < marked_from {
+			return None;
+		}
+		match grapheme {
+			"\t" => Some(self.tab),
+			" " => Some(self.space),
+			"\u{a0}" => Some(self.nbsp),
+			_ => None,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn markers(show: ShowWhitespace) -> WhitespaceMarkers {
+		WhitespaceMarkers {
+			show,
+			..WhitespaceMarkers::default()
+		}
+	}
+
+	#[test]
+	fn trailing_marks_only_after_last_non_blank() {
+		let ws = markers(ShowWhitespace::Trailing);
+		let from = ws.marked_from("a b \t").unwrap();
+		assert_eq!(from, 3);
+		assert_eq!(ws.marker(" ", 1, from), None);
+		assert_eq!(ws.marker(" ", 3, from), Some('·'));
+		assert_eq!(ws.marker("\t", 4, from), Some('→'));
+	}
+
+	#[test]
+	fn all_marks_every_kind_of_whitespace() {
+		let ws = markers(ShowWhitespace::All);
+		let from = ws.marked_from("a\u{a0}b").unwrap();
+		assert_eq!(ws.marker("\u{a0}", 1, from), Some('⍽'));
+		assert_eq!(ws.marker("b", 2, from), None);
+		assert_eq!(markers(ShowWhitespace::Off).marked_from("a "), None);
+	}
+}
@@ -0,0 +1,107 @@
+use std::path::{Component, Path};
+
+use xeno_registry::options::keys;
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::{BufferLine, BufferTab};
+
+use crate::Editor;
+
+impl Editor {
+	/// Returns whether the buffer line is shown, per the `bufferline` option.
+	pub(crate) fn bufferline_visible(&self) -> bool {
+		match self.option(keys::BUFFERLINE).as_str() {
+			"on" => true,
+			"auto" => self.buffers.buffer_count() > 1,
+			_ => false,
+		}
+	}
+
+	/// Builds the buffer line, with one tab per open buffer in the order
+	/// buffer cycling uses.
+	pub(crate) fn bufferline(&self) -> BufferLine<'static> {
+		let cwd = std::env::current_dir().ok();
+		let ids = self.buffer_ids();
+		let focused = self.focused_view();
+
+		let tabs = ids
+			.iter()
+			.filter_map(|&id| self.get_buffer(id))
+			.map(|buffer| {
+				let label = match buffer.path() {
+					Some(path) => shorten_path(&path, cwd.as_deref()),
+					None => buffer.display_name(),
+				};
+				let tab = BufferTab::new(label).modified(buffer.modified());
+				#[cfg(feature = "lsp")]
+				let tab = tab.errors(self.lsp.error_count(buffer));
+				tab
+			})
+			.collect();
+
+		let colors = &self.config.theme.colors;
+		BufferLine::new(tabs)
+			.active(ids.iter().position(|&id| id == focused))
+			.style(Style::default().fg(colors.popup.fg).bg(colors.popup.bg))
+			.active_style(
+				Style::default()
+					.fg(colors.ui.selection_fg)
+					.bg(colors.ui.selection_bg)
+					.add_modifier(Modifier::BOLD),
+			)
+			.modified_style(Style::default().fg(colors.status.warning_fg))
+			.error_style(Style::default().fg(colors.status.error_fg))
+			.overflow_style(Style::default().fg(colors.status.dim_fg))
+	}
+}
+
+/// Shortens `path` for a tab label.
+///
+/// The path is made relative to `cwd` when it lies inside it, and every
+/// directory is cut to its first character (two for hidden directories),
+/// so `crates/api/src/lib.rs` becomes `c/a/s/lib.rs`.
+fn shorten_path(path: &Path, cwd: Option<&Path>) -> String {
+	let path = cwd
+		.and_then(|cwd| path.strip_prefix(cwd).ok())
+		.unwrap_or(path);
+	let Some(name) = path.file_name() else {
+		return path.display().to_string();
+	};
+
+	let mut label = String::new();
+	for component in path.parent().into_iter().flat_map(Path::components) {
+		match component {
+			Component::RootDir => {}
+			Component::Normal(dir) => {
+				let dir = dir.to_string_lossy();
+				let keep = if dir.starts_with('.') { 2 } else { 1 };
+				label.extend(dir.chars().take(keep));
+			}
+			other => label.push_str(&other.as_os_str().to_string_lossy()),
+		}
+		label.push('/');
+	}
+	label.push_str(&name.to_string_lossy());
+	label
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn shortens_directories() {
+		let cwd = Path::new("/home/me/project");
+		assert_eq!(
+			shorten_path(Path::new("/home/me/project/crates/api/lib.rs"), Some(cwd)),
+			"c/a/lib.rs"
+		);
+		assert_eq!(
+			shorten_path(Path::new("/home/me/project/main.rs"), Some(cwd)),
+			"main.rs"
+		);
+		assert_eq!(
+			shorten_path(Path::new("/etc/.config/x.kdl"), Some(cwd)),
+			"/e/.c/x.kdl"
+		);
+	}
+}
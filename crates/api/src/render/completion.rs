@@ -3,14 +3,138 @@ use xeno_tui::layout::Rect;
 use xeno_tui::style::{Modifier, Style};
 use xeno_tui::text::{Line, Span};
 use xeno_tui::widgets::list::ListItem;
-use xeno_tui::widgets::{Block, Borders, List, Widget};
+#[cfg(feature = "lsp")]
+use xeno_tui::widgets::markdown::Markdown;
+use xeno_tui::widgets::{
+	BorderType, Borders, List, ListDirection, Padding, Scrollbar, ScrollbarOrientation,
+	ScrollbarState,
+};
 
 use crate::Editor;
+#[cfg(feature = "lsp")]
+use crate::editor::lsp_completion::completion_docs;
 use crate::editor::types::CompletionState;
+use crate::info_popup::{PopupAnchor, PopupSide, compute_popup_placement};
+
+/// Narrowest the documentation popup beside the menu is drawn.
+#[cfg(feature = "lsp")]
+const DOCS_MIN_WIDTH: u16 = 20;
+
+/// Widest the documentation popup beside the menu grows.
+#[cfg(feature = "lsp")]
+const DOCS_MAX_WIDTH: u16 = 60;
 
 impl Editor {
-	/// Creates a widget for rendering the completion popup menu.
-	pub fn render_completion_menu(&self, _area: Rect) -> impl Widget + '_ {
+	/// Renders the open completion menu next to the cursor within `bounds`,
+	/// and beside it the documentation of the selected item.
+	pub(crate) fn render_completion_popup(&mut self, frame: &mut xeno_tui::Frame, bounds: Rect) {
+		if !self.completion_active() {
+			return;
+		}
+		let Some(cursor) = self.cursor_screen_position() else {
+			return;
+		};
+		let (width, height) = {
+			let Some(state) = self.overlays.get::<CompletionState>() else {
+				return;
+			};
+			let label = state
+				.items
+				.iter()
+				.map(|it| it.label.len())
+				.max()
+				.unwrap_or(0);
+			let scrollbar = usize::from(state.items.len() > CompletionState::MAX_VISIBLE);
+			let width = 1 + 3 + label + 6 + scrollbar;
+			let height = state.items.len().min(CompletionState::MAX_VISIBLE);
+			(u16::try_from(width).unwrap_or(u16::MAX), height as u16)
+		};
+
+		// Placement leaves room for a full border; the menu only has a left
+		// one, so it gives up the rows it does not use.
+		let placement = compute_popup_placement(
+			PopupAnchor::Cursor {
+				x: cursor.x,
+				y: cursor.y,
+			},
+			width.saturating_sub(2),
+			height.saturating_sub(2),
+			bounds,
+		);
+		let mut rect = placement.rect;
+		if rect.height > height {
+			if placement.side == PopupSide::Above {
+				rect.y += rect.height - height;
+			}
+			rect.height = height;
+		}
+		if rect.is_empty() {
+			return;
+		}
+		self.overlays.get_or_default::<CompletionState>().side = placement.side;
+		self.render_completion_menu(frame, rect);
+		#[cfg(feature = "lsp")]
+		self.render_completion_docs(frame, rect, placement.side, bounds);
+	}
+
+	/// Renders the detail and documentation of the selected language server
+	/// item in a popup beside the `menu`, on whichever side of it has room.
+	#[cfg(feature = "lsp")]
+	fn render_completion_docs(
+		&self,
+		frame: &mut xeno_tui::Frame,
+		menu: Rect,
+		side: PopupSide,
+		bounds: Rect,
+	) {
+		let Some(docs) = self.selected_lsp_completion().and_then(completion_docs) else {
+			return;
+		};
+		let right = bounds.right().saturating_sub(menu.right());
+		let left = menu.x.saturating_sub(bounds.x);
+		let (room, on_right) = if right >= DOCS_MIN_WIDTH || right >= left {
+			(right, true)
+		} else {
+			(left, false)
+		};
+		let width = room.min(DOCS_MAX_WIDTH);
+		if width < DOCS_MIN_WIDTH {
+			return;
+		}
+
+		let base = Style::default()
+			.fg(self.config.theme.colors.popup.fg)
+			.bg(self.config.theme.colors.popup.bg);
+		let markdown = Markdown::new(&docs).style(base);
+		let chrome = 2 + 2 * self.config.theme.colors.popup.padding;
+		let height = markdown
+			.height(width.saturating_sub(chrome))
+			.saturating_add(2)
+			.min(bounds.height);
+		let x = if on_right {
+			menu.right()
+		} else {
+			menu.x - width
+		};
+		let y = match side {
+			PopupSide::Below => menu.y,
+			PopupSide::Above => menu.bottom().saturating_sub(height),
+		}
+		.clamp(bounds.y, bounds.bottom().saturating_sub(height));
+		let rect = Rect::new(x, y, width, height);
+
+		let popup = self.popup_frame();
+		let inner = popup.inner(rect);
+		frame.render_widget(popup, rect);
+		frame.render_widget(markdown, inner);
+	}
+
+	/// Renders the completion popup menu into `area`, with a scrollbar in
+	/// the rightmost column when not every item fits.
+	///
+	/// Records where the items were drawn in [`CompletionState::area`] so
+	/// mouse events can find the item under the pointer.
+	pub fn render_completion_menu(&mut self, frame: &mut xeno_tui::Frame, area: Rect) {
 		let completions = self
 			.overlays
 			.get::<CompletionState>()
@@ -40,6 +164,7 @@ impl Editor {
 					CompletionKind::Buffer => "󰈙",
 					CompletionKind::Snippet => "󰘦",
 					CompletionKind::Theme => "󰏘",
+					CompletionKind::Symbol => "󰊕",
 				};
 
 				let kind_color = match item.kind {
@@ -48,6 +173,7 @@ impl Editor {
 					CompletionKind::Buffer => self.config.theme.colors.status.accent_bg,
 					CompletionKind::Snippet => self.config.theme.colors.status.prefix_mode_bg,
 					CompletionKind::Theme => self.config.theme.colors.status.accent_bg,
+					CompletionKind::Symbol => self.config.theme.colors.status.insert_bg,
 				};
 
 				let base_style = if is_selected {
@@ -68,11 +194,7 @@ impl Editor {
 						.bg(self.config.theme.colors.popup.bg)
 				};
 
-				let label_style = if is_selected {
-					base_style.add_modifier(Modifier::BOLD)
-				} else {
-					base_style
-				};
+				let match_style = base_style.add_modifier(Modifier::BOLD);
 
 				let kind_name = match item.kind {
 					CompletionKind::Command => "Cmd",
@@ -80,6 +202,7 @@ impl Editor {
 					CompletionKind::Buffer => "Buf",
 					CompletionKind::Snippet => "Snip",
 					CompletionKind::Theme => "Theme",
+					CompletionKind::Symbol => "Sym",
 				};
 
 				let dim_style = if is_selected {
@@ -90,33 +213,112 @@ impl Editor {
 						.bg(self.config.theme.colors.popup.bg)
 				};
 
-				let line = Line::from(vec![
-					Span::styled(format!(" {} ", kind_icon), icon_style),
-					Span::styled(
-						format!("{:<width$}", item.label, width = max_label_len),
-						label_style,
-					),
-					Span::styled(format!(" {:>4} ", kind_name), dim_style),
-				]);
+				let mut spans = vec![Span::styled(format!(" {} ", kind_icon), icon_style)];
+				spans.extend(label_spans(
+					&item.label,
+					&item.match_indices,
+					base_style,
+					match_style,
+				));
+				let padding = max_label_len.saturating_sub(item.label.chars().count());
+				spans.push(Span::styled(" ".repeat(padding), base_style));
+				spans.push(Span::styled(format!(" {:>4} ", kind_name), dim_style));
+				let line = Line::from(spans);
 
 				ListItem::new(line).style(base_style)
 			})
 			.collect();
 
 		let stripe_style = Style::default().fg(self.config.theme.colors.status.normal_bg);
-		let border_set = xeno_tui::symbols::border::Set {
-			top_left: "▏",
-			vertical_left: "▏",
-			bottom_left: "▏",
-			..xeno_tui::symbols::border::EMPTY
+		let popup = self
+			.popup_frame()
+			.borders(Borders::LEFT)
+			.border_type(BorderType::Stripe)
+			.border_style(stripe_style)
+			.padding(Padding::ZERO);
+
+		let inner = popup.inner(area);
+		frame.render_widget(popup, area);
+
+		let total = completions.items.len();
+		let viewport = CompletionState::MAX_VISIBLE.min(usize::from(inner.height));
+		let (list_area, bar_area) = if total > viewport {
+			inner.split_right_column()
+		} else {
+			(inner, Rect::ZERO)
+		};
+		let direction = match completions.side {
+			PopupSide::Below => ListDirection::TopToBottom,
+			PopupSide::Above => ListDirection::BottomToTop,
 		};
+		frame.render_widget(List::new(items).direction(direction), list_area);
+		self.overlays.get_or_default::<CompletionState>().area = list_area;
 
-		let block = Block::default()
-			.style(Style::default().bg(self.config.theme.colors.popup.bg))
-			.borders(Borders::LEFT)
-			.border_set(border_set)
-			.border_style(stripe_style);
+		let mut scrollbar =
+			ScrollbarState::for_viewport(total, viewport, completions.scroll_offset);
+		frame.render_stateful_widget(
+			Scrollbar::new(ScrollbarOrientation::VerticalRight)
+				.symbols(xeno_tui::symbols::scrollbar::VERTICAL)
+				.begin_symbol(None)
+				.end_symbol(None)
+				.style(
+					Style::default()
+						.fg(self.config.theme.colors.status.dim_fg)
+						.bg(self.config.theme.colors.popup.bg),
+				)
+				.thumb_style(
+					Style::default()
+						.fg(self.config.theme.colors.popup.fg)
+						.bg(self.config.theme.colors.popup.bg),
+				),
+			bar_area,
+			&mut scrollbar,
+		);
+	}
+}
+
+/// Splits `label` into spans drawn with `style`, except the characters at
+/// `matched`, which are drawn with `match_style`.
+fn label_spans(
+	label: &str,
+	matched: &[usize],
+	style: Style,
+	match_style: Style,
+) -> Vec<Span<'static>> {
+	let mut spans = Vec::new();
+	let mut run = String::new();
+	let mut run_matched = false;
+	for (i, c) in label.chars().enumerate() {
+		let is_matched = matched.contains(&i);
+		if is_matched != run_matched && !run.is_empty() {
+			let style = if run_matched { match_style } else { style };
+			spans.push(Span::styled(std::mem::take(&mut run), style));
+		}
+		run_matched = is_matched;
+		run.push(c);
+	}
+	if !run.is_empty() {
+		let style = if run_matched { match_style } else { style };
+		spans.push(Span::styled(run, style));
+	}
+	spans
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-		List::new(items).block(block)
+	#[test]
+	fn matched_characters_get_their_own_spans() {
+		let bold = Style::default().add_modifier(Modifier::BOLD);
+		let spans = label_spans("set_option", &[0, 1, 4], Style::default(), bold);
+		let runs: Vec<_> = spans
+			.iter()
+			.map(|span| (span.content.as_ref(), span.style == bold))
+			.collect();
+		assert_eq!(
+			runs,
+			[("se", true), ("t_", false), ("o", true), ("ption", false)]
+		);
 	}
 }
@@ -8,17 +8,24 @@ mod whichkey;
 
 use std::time::{Duration, SystemTime};
 
+use xeno_registry::options::keys;
+use xeno_registry::themes::blend_colors;
+use xeno_tui::buffer::Buffer;
 use xeno_tui::layout::{Constraint, Direction, Layout, Rect};
-use xeno_tui::style::Style;
+use xeno_tui::style::{Color, Style};
 use xeno_tui::text::{Line, Span};
 use xeno_tui::widgets::menu::Menu;
-use xeno_tui::widgets::{Block, Borders, Clear, Paragraph, StatefulWidget};
+use xeno_tui::widgets::{
+	Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+	StatefulWidget,
+};
 
 use self::separator::{SeparatorStyle, junction_glyph};
-use super::buffer::{BufferRenderContext, ensure_buffer_cursor_visible};
+use super::buffer::{BufferRenderContext, ScrollMargins, ensure_buffer_cursor_visible};
 use crate::Editor;
 use crate::buffer::{BufferView, SplitDirection};
 use crate::editor::FocusTarget;
+use crate::info_popup::InfoPopupStore;
 
 /// Per-layer rendering data: (layer_index, layer_area, view_areas, separators).
 type LayerRenderData = (
@@ -28,6 +35,24 @@ type LayerRenderData = (
 	Vec<(SplitDirection, u8, Rect)>,
 );
 
+/// Returns the background colors of the cells in `rect`, row by row.
+fn sample_background(buf: &Buffer, rect: Rect) -> Vec<Color> {
+	rect.positions()
+		.map(|position| buf.cell(position).map_or(Color::Reset, |cell| cell.bg))
+		.collect()
+}
+
+/// Fades the cells of `rect` toward `background`, the colors sampled there
+/// before the popup was drawn.
+fn fade_cells(buf: &mut Buffer, rect: Rect, background: &[Color], opacity: f32) {
+	for (position, &bg) in rect.positions().zip(background) {
+		if let Some(cell) = buf.cell_mut(position) {
+			cell.fg = blend_colors(cell.fg, bg, opacity);
+			cell.bg = blend_colors(cell.bg, bg, opacity);
+		}
+	}
+}
+
 /// Clamps a rectangle to a bounding area, returning the intersection.
 fn clamp_rect(rect: Rect, bounds: Rect) -> Option<Rect> {
 	let x1 = rect.x.max(bounds.x);
@@ -51,6 +76,7 @@ impl Editor {
 	/// Renders the complete editor frame.
 	///
 	/// This is the main rendering entry point that orchestrates all UI elements:
+	/// - Menu bar and buffer line
 	/// - Document content with cursor and selections (including splits)
 	/// - UI panels (if any)
 	/// - Command/message line
@@ -65,7 +91,10 @@ impl Editor {
 			.duration_since(self.frame.last_tick)
 			.unwrap_or(Duration::from_millis(16));
 		self.frame.last_tick = now;
+		self.configure_notifications();
 		self.notifications.tick(delta);
+		self.record_dismissed_notifications();
+		self.tick_popup_animations(delta);
 
 		// Update style overlays to reflect current cursor position.
 		// This must happen at render time (not tick time) to handle
@@ -83,18 +112,21 @@ impl Editor {
 		let bg_block = Block::default().style(Style::default().bg(self.config.theme.colors.ui.bg));
 		frame.render_widget(bg_block, area);
 
+		let show_bufferline = self.bufferline_visible();
 		let chunks = Layout::default()
 			.direction(Direction::Vertical)
 			.constraints([
 				Constraint::Length(1),
+				Constraint::Length(u16::from(show_bufferline)),
 				Constraint::Min(1),
 				Constraint::Length(1),
 			])
 			.split(area);
 
 		let menu_area = chunks[0];
-		let main_area = chunks[1];
-		let status_area = chunks[2];
+		let bufferline_area = chunks[1];
+		let main_area = chunks[2];
+		let status_area = chunks[3];
 
 		let mut ui = std::mem::take(&mut self.ui);
 		let dock_layout = ui.compute_layout(main_area);
@@ -106,6 +138,9 @@ impl Editor {
 		// Render all buffers in the layout
 		self.render_split_buffers(frame, doc_area, use_block_cursor && doc_focused);
 		self.render_floating_windows(frame, use_block_cursor && doc_focused);
+		self.render_completion_popup(frame, doc_area);
+		#[cfg(feature = "lsp")]
+		self.render_signature_help(frame, doc_area);
 
 		if let Some(cursor_pos) = ui.render_panels(self, frame, &dock_layout, self.config.theme) {
 			frame.set_cursor_position(cursor_pos);
@@ -114,6 +149,8 @@ impl Editor {
 			self.frame.needs_redraw = true;
 		}
 		self.ui = ui;
+		#[cfg(feature = "lsp")]
+		self.render_symbol_picker(frame, doc_area);
 
 		let menu_bg =
 			Block::default().style(Style::default().bg(self.config.theme.colors.popup.bg));
@@ -131,11 +168,16 @@ impl Editor {
 			)
 			.render(menu_area, frame.buffer_mut(), &mut self.menu);
 
+		if show_bufferline {
+			frame.render_widget(self.bufferline(), bufferline_area);
+		}
+
 		let status_bg =
 			Block::default().style(Style::default().bg(self.config.theme.colors.popup.bg));
 		frame.render_widget(status_bg, status_area);
 		frame.render_widget(self.render_status_line(), status_area);
 
+		// Toasts stay inside the document area, clear of the statusline.
 		let mut notifications_area = doc_area;
 		notifications_area.height = notifications_area.height.saturating_sub(1);
 		notifications_area.width = notifications_area.width.saturating_sub(1);
@@ -174,19 +216,30 @@ impl Editor {
 			}
 		}
 
-		// During mouse drag (text_selection_origin is Some), disable scroll margin
+		// During mouse drag (text_selection_origin is Some), disable scroll margins
 		// to allow cursor to reach screen edges without triggering scrolloff.
 		let mouse_drag_active = self.layout.text_selection_origin.is_some();
 		for (_, _, view_areas, _) in &layer_data {
 			for (buffer_id, area) in view_areas {
 				let tab_width = self.tab_width_for(*buffer_id);
-				let scroll_margin = if mouse_drag_active {
-					0
+				let margins = if mouse_drag_active {
+					ScrollMargins::default()
 				} else {
-					self.scroll_margin_for(*buffer_id)
+					self.scroll_margins_for(*buffer_id)
 				};
+				let line_numbers = self.line_numbers_for(*buffer_id);
+				let wrap = self.wrap_for(*buffer_id);
+				let wrap_indent = self.wrap_indent_for(*buffer_id);
+				let fold_reveal = self.resolve_typed_option(*buffer_id, keys::FOLD_REVEAL);
 				if let Some(buffer) = self.get_buffer_mut(*buffer_id) {
-					ensure_buffer_cursor_visible(buffer, *area, tab_width, scroll_margin);
+					buffer.line_numbers = line_numbers;
+					if buffer.wrap != wrap || buffer.wrap_indent != wrap_indent {
+						buffer.wrap = wrap;
+						buffer.wrap_indent = wrap_indent;
+						buffer.scroll_segment = 0;
+					}
+					buffer.keep_cursors_out_of_folds(fold_reveal);
+					ensure_buffer_cursor_visible(buffer, *area, tab_width, margins);
 				}
 			}
 		}
@@ -213,29 +266,19 @@ impl Editor {
 			for (buffer_id, area) in view_areas {
 				let is_focused = *buffer_id == focused_view;
 				let tab_width = self.tab_width_for(*buffer_id);
-				let cursorline = self.cursorline_for(*buffer_id);
+				let mut highlights = self.line_highlights_for(*buffer_id);
+				highlights.search = self.search_highlights_for(*buffer_id, area.height as usize);
 				if let Some(buffer) = self.get_buffer(*buffer_id) {
-					#[cfg(feature = "lsp")]
-					let (diag_map, diag_ranges) = {
-						let diagnostics = self.lsp.get_diagnostics(buffer);
-						(
-							super::buffer::build_diagnostic_line_map(&diagnostics),
-							super::buffer::build_diagnostic_range_map(&diagnostics),
-						)
-					};
-
+					let diagnostics = self.diagnostic_maps_for(*buffer_id);
+					let inlay_hints = self.inlay_hint_map_for(*buffer_id, area.height as usize);
 					let ctx = BufferRenderContext {
 						theme: self.config.theme,
 						language_loader: &self.config.language_loader,
 						style_overlays: &self.style_overlays,
-						#[cfg(feature = "lsp")]
-						diagnostics: Some(&diag_map),
-						#[cfg(not(feature = "lsp"))]
-						diagnostics: None,
-						#[cfg(feature = "lsp")]
-						diagnostic_ranges: Some(&diag_ranges),
-						#[cfg(not(feature = "lsp"))]
-						diagnostic_ranges: None,
+						diagnostics: Some(&diagnostics.lines),
+						diagnostic_ranges: Some(&diagnostics.ranges),
+						inline_diagnostics: Some(&diagnostics.inline),
+						inlay_hints: Some(&inlay_hints),
 					};
 					let result = ctx.render_buffer(
 						buffer,
@@ -243,7 +286,7 @@ impl Editor {
 						use_block_cursor,
 						is_focused,
 						tab_width,
-						cursorline,
+						&highlights,
 					);
 					frame.render_widget(result.widget, *area);
 				}
@@ -275,10 +318,14 @@ impl Editor {
 			_ => None,
 		};
 
+		// Closed popups still fading out are drawn over the open windows.
+		let popups = self.overlays.get::<InfoPopupStore>();
+		let exiting = popups.into_iter().flat_map(|store| store.exiting());
 		let floating_windows: Vec<_> = self
 			.windows
 			.floating_windows()
 			.map(|(id, window)| (id, window.clone()))
+			.chain(exiting.map(|popup| (popup.window.id, popup.window.clone())))
 			.collect();
 		for (_, window) in &floating_windows {
 			let Some(rect) = clamp_rect(window.rect, bounds) else {
@@ -300,9 +347,11 @@ impl Editor {
 			}
 
 			let tab_width = self.tab_width_for(window.buffer);
-			let scroll_margin = self.scroll_margin_for(window.buffer);
+			let margins = self.scroll_margins_for(window.buffer);
+			let fold_reveal = self.resolve_typed_option(window.buffer, keys::FOLD_REVEAL);
 			if let Some(buffer) = self.get_buffer_mut(window.buffer) {
-				ensure_buffer_cursor_visible(buffer, content_area, tab_width, scroll_margin);
+				buffer.keep_cursors_out_of_folds(fold_reveal);
+				ensure_buffer_cursor_visible(buffer, content_area, tab_width, margins);
 			}
 		}
 
@@ -310,72 +359,66 @@ impl Editor {
 			let Some(rect) = clamp_rect(window.rect, bounds) else {
 				continue;
 			};
-
-			if window.style.shadow {
-				let shadow_rect = Rect {
-					x: rect.x.saturating_add(1),
-					y: rect.y.saturating_add(1),
-					width: rect.width,
-					height: rect.height,
-				};
-				if let Some(shadow) = clamp_rect(shadow_rect, bounds) {
-					let shadow_block =
-						Block::default().style(Style::default().bg(self.config.theme.colors.ui.bg));
-					frame.render_widget(shadow_block, shadow);
-				}
+			let opacity = self
+				.overlays
+				.get::<InfoPopupStore>()
+				.map_or(1.0, |store| store.opacity(window_id));
+			let background = (opacity < 1.0).then(|| sample_background(frame.buffer_mut(), rect));
+
+			let mut popup = self
+				.popup_frame()
+				.border_type(window.style.border_type)
+				.shadow(window.style.shadow)
+				.title_alignment(window.style.title_alignment);
+			if let Some(padding) = window.style.padding {
+				popup = popup.padding(padding);
 			}
-
-			frame.render_widget(Clear, rect);
-
-			let mut block = Block::default()
-				.style(Style::default().bg(self.config.theme.colors.popup.bg))
-				.padding(window.style.padding);
-			if window.style.border {
-				block = block
-					.borders(Borders::ALL)
-					.border_type(window.style.border_type)
-					.border_style(Style::default().fg(self.config.theme.colors.popup.fg));
-				if let Some(title) = &window.style.title {
-					block = block.title(title.as_str());
-				}
+			if !window.style.border {
+				popup = popup.borders(Borders::NONE);
+			}
+			if let Some(title) = &window.style.title {
+				popup = popup.title(title.clone());
 			}
 
-			let content_area = block.inner(rect);
-
-			frame.render_widget(block, rect);
+			let content_area = popup.inner(rect);
+			frame.render_widget(popup, rect);
 
 			if content_area.width == 0 || content_area.height == 0 {
 				continue;
 			}
 
+			// Content taller than the popup gets a scrollbar down its right
+			// column, one row per document line.
+			let (total, scroll_line) = self.get_buffer(window.buffer).map_or((0, 0), |buffer| {
+				(buffer.doc().content.len_lines(), buffer.scroll_line)
+			});
+			let viewport = usize::from(content_area.height);
+			let (content_area, bar_area) = if total > viewport {
+				content_area.split_right_column()
+			} else {
+				(content_area, Rect::ZERO)
+			};
+
+			let mut highlights = self.line_highlights_for(window.buffer);
+			highlights.search =
+				self.search_highlights_for(window.buffer, content_area.height as usize);
 			if let Some(buffer) = self.get_buffer(window.buffer) {
 				let is_focused = focused
 					.map(|(win, buf)| win == window_id && buf == window.buffer)
 					.unwrap_or(false);
 				let tab_width = self.tab_width_for(window.buffer);
-				let cursorline = self.cursorline_for(window.buffer);
-
-				#[cfg(feature = "lsp")]
-				let (diag_map, diag_ranges) = {
-					let diagnostics = self.lsp.get_diagnostics(buffer);
-					(
-						super::buffer::build_diagnostic_line_map(&diagnostics),
-						super::buffer::build_diagnostic_range_map(&diagnostics),
-					)
-				};
 
+				let diagnostics = self.diagnostic_maps_for(window.buffer);
+				let inlay_hints =
+					self.inlay_hint_map_for(window.buffer, content_area.height as usize);
 				let ctx = BufferRenderContext {
 					theme: self.config.theme,
 					language_loader: &self.config.language_loader,
 					style_overlays: &self.style_overlays,
-					#[cfg(feature = "lsp")]
-					diagnostics: Some(&diag_map),
-					#[cfg(not(feature = "lsp"))]
-					diagnostics: None,
-					#[cfg(feature = "lsp")]
-					diagnostic_ranges: Some(&diag_ranges),
-					#[cfg(not(feature = "lsp"))]
-					diagnostic_ranges: None,
+					diagnostics: Some(&diagnostics.lines),
+					diagnostic_ranges: Some(&diagnostics.ranges),
+					inline_diagnostics: Some(&diagnostics.inline),
+					inlay_hints: Some(&inlay_hints),
 				};
 				let result = ctx.render_buffer_with_gutter(
 					buffer,
@@ -384,10 +427,31 @@ impl Editor {
 					is_focused,
 					window.gutter,
 					tab_width,
-					cursorline,
+					&highlights,
 				);
 				frame.render_widget(result.widget, content_area);
 			}
+
+			let colors = &self.config.theme.colors;
+			let mut scrollbar = ScrollbarState::for_viewport(total, viewport, scroll_line);
+			frame.render_stateful_widget(
+				Scrollbar::new(ScrollbarOrientation::VerticalRight)
+					.symbols(xeno_tui::symbols::scrollbar::VERTICAL)
+					.begin_symbol(None)
+					.end_symbol(None)
+					.style(
+						Style::default()
+							.fg(colors.status.dim_fg)
+							.bg(colors.popup.bg),
+					)
+					.thumb_style(Style::default().fg(colors.popup.fg).bg(colors.popup.bg)),
+				bar_area,
+				&mut scrollbar,
+			);
+
+			if let Some(background) = background {
+				fade_cells(frame.buffer_mut(), rect, &background, opacity);
+			}
 		}
 	}
 
@@ -8,11 +8,15 @@ use xeno_core::keymap_registry::ContinuationKind;
 use xeno_registry::{BindingMode, find_prefix};
 use xeno_tui::layout::Rect;
 use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::BorderType;
 use xeno_tui::widgets::keytree::{KeyTree, KeyTreeNode};
-use xeno_tui::widgets::{Block, BorderType, Borders, Clear, Padding};
 
 use crate::Editor;
 
+/// Most columns the HUD flows continuations into before listing the rest
+/// as "+N more".
+const MAX_COLUMNS: usize = 3;
+
 impl Editor {
 	/// Renders the which-key HUD when there are pending keys.
 	pub fn render_whichkey_hud(&self, frame: &mut xeno_tui::Frame, doc_area: Rect) {
@@ -87,7 +91,12 @@ impl Editor {
 
 		let ancestor_lines = ancestors.len() as u16;
 		let content_height = (children.len() as u16 + ancestor_lines + 2).clamp(3, 14);
-		let width = 32u16.min(doc_area.width.saturating_sub(4));
+		let child_rows = content_height.saturating_sub(ancestor_lines + 2).max(1);
+		let columns = children
+			.len()
+			.div_ceil(usize::from(child_rows))
+			.clamp(1, MAX_COLUMNS) as u16;
+		let width = (32 * columns).min(doc_area.width.saturating_sub(4));
 		let height = content_height + 2;
 		let hud_area = Rect {
 			x: doc_area.x + doc_area.width.saturating_sub(width + 2),
@@ -96,23 +105,17 @@ impl Editor {
 			height,
 		};
 
-		let block = Block::default()
-			.style(
-				Style::default()
-					.bg(self.config.theme.colors.popup.bg)
-					.fg(self.config.theme.colors.popup.fg),
-			)
-			.borders(Borders::ALL)
+		let popup = self
+			.popup_frame()
 			.border_type(BorderType::Stripe)
-			.border_style(Style::default().fg(self.config.theme.colors.status.warning_fg))
-			.padding(Padding::horizontal(1));
+			.border_style(Style::default().fg(self.config.theme.colors.status.warning_fg));
 
-		let inner = block.inner(hud_area);
-		frame.render_widget(Clear, hud_area);
-		frame.render_widget(block, hud_area);
+		let inner = popup.inner(hud_area);
+		frame.render_widget(popup, hud_area);
 
 		let mut tree = KeyTree::new(root, children)
 			.ancestors(ancestors)
+			.max_columns(MAX_COLUMNS)
 			.ancestor_style(Style::default().fg(self.config.theme.colors.ui.gutter_fg))
 			.key_style(
 				Style::default()
@@ -1,15 +1,28 @@
 mod buffer;
+/// Buffer line rendering.
+mod bufferline;
 /// Completion popup rendering.
 mod completion;
 mod document;
+/// Themed popup frames.
+mod popup;
+/// Signature help popup rendering.
+#[cfg(feature = "lsp")]
+mod signature_help;
 /// Status line rendering.
 mod status;
+/// Workspace symbol picker rendering.
+#[cfg(feature = "lsp")]
+mod symbol_picker;
 /// Line wrapping with sticky punctuation.
 pub mod wrap;
 
-#[cfg(feature = "lsp")]
-pub use buffer::build_diagnostic_line_map;
+pub(crate) use buffer::cursor_visual_row;
 pub use buffer::{
-	BufferRenderContext, DiagnosticLineMap, RenderResult, ensure_buffer_cursor_visible,
+	BufferRenderContext, DiagnosticInlineMap, DiagnosticLineMap, DiagnosticMaps,
+	DiagnosticRangeMap, DiagnosticsDisplay, InlayHintLabel, InlayHintMap, InlineDiagnostic,
+	LineHighlights, RenderResult, ScrollMargins, SearchHighlights, ShowWhitespace,
+	SymbolHighlights, WhitespaceMarkers, ensure_buffer_cursor_visible, horizontal_scroll_offset,
 };
-pub use wrap::{WrapSegment, wrap_line};
+pub use status::{ProgressSegment, StatusLine};
+pub use wrap::{ClippedLine, WrapIndent, WrapSegment, clip_line, wrap_line, wrap_line_indented};
@@ -0,0 +1,23 @@
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::{Padding, PopupFrame};
+
+use crate::Editor;
+
+impl Editor {
+	/// Builds a popup frame styled from the theme's popup colors, with its
+	/// default padding and shadow style. Every popup draws its frame through
+	/// this so borders, titles and shadows look the same everywhere.
+	pub(crate) fn popup_frame(&self) -> PopupFrame<'static> {
+		let popup = &self.config.theme.colors.popup;
+		PopupFrame::new()
+			.style(Style::default().bg(popup.bg).fg(popup.fg))
+			.border_style(Style::default().fg(popup.border))
+			.title_style(Style::default().fg(popup.title))
+			.shadow_style(
+				Style::default()
+					.fg(popup.shadow)
+					.add_modifier(Modifier::DIM),
+			)
+			.padding(Padding::horizontal(popup.padding))
+	}
+}
@@ -0,0 +1,129 @@
+use unicode_width::UnicodeWidthChar;
+use xeno_tui::layout::Rect;
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::text::{Line, Span};
+use xeno_tui::widgets::Paragraph;
+
+use crate::Editor;
+use crate::editor::types::CompletionState;
+use crate::info_popup::{
+	PopupAnchor, PopupSide, compute_popup_placement, compute_popup_placement_on,
+};
+
+impl Editor {
+	/// Renders the signature help popup next to the cursor, with the active
+	/// parameter in bold and underlined and the overload count as its title.
+	///
+	/// While the completion menu is open the popup takes the other side of
+	/// the cursor. Signatures wider than `bounds` wrap.
+	pub(crate) fn render_signature_help(&self, frame: &mut xeno_tui::Frame, bounds: Rect) {
+		let Some(state) = self.visible_signature_help() else {
+			return;
+		};
+		let Some(signature) = state.active_signature() else {
+			return;
+		};
+		let Some(cursor) = self.cursor_screen_position() else {
+			return;
+		};
+
+		let base = Style::default()
+			.fg(self.config.theme.colors.popup.fg)
+			.bg(self.config.theme.colors.popup.bg);
+		let label: Vec<char> = signature.label.chars().collect();
+		let active = signature.active_parameter.clone().unwrap_or_default();
+		let end = active.end.min(label.len());
+		let active = active.start.min(end)..end;
+		let segments = [
+			(&label[..active.start], base),
+			(
+				&label[active.clone()],
+				base.add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+			),
+			(&label[active.end..], base),
+		];
+
+		let padding = self.config.theme.colors.popup.padding;
+		let chrome = 2 + 2 * padding;
+		let max_width = usize::from(bounds.width.saturating_sub(chrome)).max(1);
+		let title = state.title();
+		let title_width = title.as_ref().map_or(0, |t| t.chars().count() + 2);
+		let rows = wrap_segments(&segments, max_width);
+		let width = rows
+			.iter()
+			.map(Line::width)
+			.max()
+			.unwrap_or(0)
+			.max(title_width)
+			.min(max_width) as u16;
+
+		let content_width = width.saturating_add(2 * padding);
+		let content_height = rows.len() as u16;
+		let completion_side = self
+			.completion_active()
+			.then(|| self.overlays.get::<CompletionState>().map(|c| c.side))
+			.flatten();
+		let placement = match completion_side {
+			Some(side) => compute_popup_placement_on(
+				(cursor.x, cursor.y),
+				content_width,
+				content_height,
+				bounds,
+				match side {
+					PopupSide::Below => PopupSide::Above,
+					PopupSide::Above => PopupSide::Below,
+				},
+			),
+			None => compute_popup_placement(
+				PopupAnchor::Cursor {
+					x: cursor.x,
+					y: cursor.y,
+				},
+				content_width,
+				content_height,
+				bounds,
+			),
+		};
+		if placement.rect.height < 3 {
+			return;
+		}
+
+		let mut popup = self.popup_frame();
+		if let Some(title) = title {
+			popup = popup.title(title);
+		}
+		let inner = popup.inner(placement.rect);
+		frame.render_widget(popup, placement.rect);
+		frame.render_widget(Paragraph::new(rows).style(base), inner);
+	}
+}
+
+/// Lays out styled `segments` as rows at most `width` cells wide, breaking
+/// between chars.
+fn wrap_segments(segments: &[(&[char], Style)], width: usize) -> Vec<Line<'static>> {
+	let mut rows = Vec::new();
+	let mut row: Vec<Span<'static>> = Vec::new();
+	let mut row_width = 0;
+	for (chars, style) in segments {
+		let mut text = String::new();
+		for &c in *chars {
+			let char_width = c.width().unwrap_or(0);
+			if row_width > 0 && row_width + char_width > width {
+				if !text.is_empty() {
+					row.push(Span::styled(std::mem::take(&mut text), *style));
+				}
+				rows.push(Line::from(std::mem::take(&mut row)));
+				row_width = 0;
+			}
+			text.push(c);
+			row_width += char_width;
+		}
+		if !text.is_empty() {
+			row.push(Span::styled(text, *style));
+		}
+	}
+	if !row.is_empty() {
+		rows.push(Line::from(row));
+	}
+	rows
+}
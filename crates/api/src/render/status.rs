@@ -1,15 +1,19 @@
+use unicode_width::UnicodeWidthStr;
 use xeno_registry::{
-	RenderedSegment, SegmentPosition, SegmentStyle, StatuslineContext, render_position,
+	LspServerStatus, RenderedSegment, SegmentPosition, SegmentStyle, StatuslineContext,
+	render_position,
 };
+use xeno_tui::buffer::Buffer;
+use xeno_tui::layout::Rect;
 use xeno_tui::style::{Modifier, Style};
 use xeno_tui::text::{Line, Span};
-use xeno_tui::widgets::{Paragraph, Widget};
+use xeno_tui::widgets::{Paragraph, ProgressBar, Spinner, Widget};
 
 use crate::Editor;
 
 impl Editor {
 	/// Creates a widget for rendering the status line.
-	pub fn render_status_line(&self) -> impl Widget + '_ {
+	pub fn render_status_line(&self) -> StatusLine<'_> {
 		let buffer_ids = self.buffer_ids();
 		let buffer_index = self
 			.focused_buffer_id()
@@ -20,31 +24,40 @@ impl Editor {
 
 		// Extract data before creating the context to avoid lifetime issues
 		let buffer = self.buffer();
-		let path_str: Option<String> = buffer
-			.path()
-			.as_ref()
-			.and_then(|p| p.to_str().map(|s| s.to_string()));
+		let path_str = buffer.display_name();
 		let file_type_str: Option<String> = buffer.file_type();
 		let modified = buffer.modified();
-		let readonly = buffer.is_readonly();
+		let readonly = buffer.is_readonly() || self.lossy_locked(buffer.id);
 		let count = buffer.input.count();
 		let total_lines = buffer.doc().content.len_lines();
-		let mode_name = self.mode_name();
+		// A focused panel stands in for the mode, as keys go to it.
+		let panel_mode = self.ui.focused_panel_title().map(str::to_uppercase);
+		let mode_name = panel_mode.as_deref().unwrap_or(self.mode_name());
 		let line = self.cursor_line() + 1;
 		let col = self.cursor_col() + 1;
+		#[cfg(feature = "lsp")]
+		let lsp_servers = self.lsp_server_statuses(buffer);
+		#[cfg(not(feature = "lsp"))]
+		let lsp_servers = Vec::<LspServerStatus>::new();
 
 		let ctx = StatuslineContext {
 			mode_name,
-			path: path_str.as_deref(),
+			path: Some(&path_str),
 			modified,
 			readonly,
 			line,
 			col,
 			count,
+			pending_prompt: buffer.input.pending_prompt(),
 			total_lines,
 			file_type: file_type_str.as_deref(),
+			line_ending: buffer.line_ending(self).name(),
+			loading: self.loading_progress(buffer.id),
 			buffer_index,
 			buffer_count,
+			search_matches: self.workspace.search.matches,
+			search_case: self.workspace.search.case.name(),
+			lsp_servers: &lsp_servers,
 		};
 
 		let mut spans = Vec::new();
@@ -59,7 +72,50 @@ impl Editor {
 			spans.push(self.segment_to_span(&seg));
 		}
 
-		Paragraph::new(Line::from(spans))
+		StatusLine {
+			segments: Paragraph::new(Line::from(spans)),
+			#[cfg(feature = "lsp")]
+			progress: self.progress_segment(),
+			#[cfg(not(feature = "lsp"))]
+			progress: None,
+		}
+	}
+
+	/// Returns the language servers attached to `buffer`, for the status
+	/// line.
+	#[cfg(feature = "lsp")]
+	fn lsp_server_statuses(&self, buffer: &crate::buffer::Buffer) -> Vec<LspServerStatus> {
+		use xeno_lsp::ServerHealth;
+		use xeno_registry::LspServerHealth;
+
+		self.lsp
+			.server_statuses(buffer)
+			.into_iter()
+			.map(|status| LspServerStatus {
+				name: status.name,
+				health: match status.health {
+					ServerHealth::Starting => LspServerHealth::Starting,
+					ServerHealth::Running => LspServerHealth::Running,
+					ServerHealth::Exited => LspServerHealth::Exited,
+				},
+			})
+			.collect()
+	}
+
+	/// Builds the indicator for the busiest active language server progress.
+	#[cfg(feature = "lsp")]
+	fn progress_segment(&self) -> Option<ProgressSegment> {
+		let item = self.lsp.documents().busiest_progress()?;
+		let status = &self.config.theme.colors.status;
+		let elapsed = item.started_at.elapsed().as_millis();
+		Some(ProgressSegment {
+			title: item.title,
+			tick: (elapsed / SPINNER_INTERVAL_MS) as u64,
+			percentage: item.percentage.map(|p| p.min(100) as u16),
+			spinner_style: Style::default().fg(status.accent_fg),
+			label_style: Style::default().fg(status.dim_fg),
+			filled_style: Style::default().fg(status.success_fg),
+		})
 	}
 
 	/// Converts a statusline segment to a styled span.
@@ -77,3 +133,154 @@ impl Editor {
 		Span::styled(segment.text.clone(), style)
 	}
 }
+
+/// Milliseconds each spinner frame stays on screen.
+#[cfg(feature = "lsp")]
+const SPINNER_INTERVAL_MS: u128 = 80;
+
+/// Widest the progress title may grow before it is clipped.
+const PROGRESS_TITLE_WIDTH: u16 = 20;
+
+/// Width of the progress bar drawn for determinate progress.
+const PROGRESS_BAR_WIDTH: u16 = 10;
+
+/// Status line segments, with an optional progress indicator at the right edge.
+pub struct StatusLine<'a> {
+	/// Registry segments, left to right.
+	segments: Paragraph<'a>,
+	/// Progress of a long-running operation, if one is active.
+	progress: Option<ProgressSegment>,
+}
+
+/// Snapshot of a long-running operation for the status line.
+#[derive(Debug, Clone)]
+pub struct ProgressSegment {
+	/// Operation title drawn after the spinner.
+	pub title: String,
+	/// Spinner tick, advanced by the caller.
+	pub tick: u64,
+	/// Completed percentage, if the operation reports one.
+	pub percentage: Option<u16>,
+	/// Style of the spinner frame.
+	pub spinner_style: Style,
+	/// Style of the title and the unfilled bar.
+	pub label_style: Style,
+	/// Style of the filled bar.
+	pub filled_style: Style,
+}
+
+impl ProgressSegment {
+	/// Returns the cells needed to draw the segment, including its margins.
+	fn width(&self) -> u16 {
+		let title = (self.title.width() as u16).min(PROGRESS_TITLE_WIDTH);
+		let bar = self.percentage.map_or(0, |_| PROGRESS_BAR_WIDTH + 1);
+		1 + 2 + title + bar + 1
+	}
+}
+
+impl Widget for &ProgressSegment {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let inner = Rect {
+			x: area.x + 1,
+			width: area.width.saturating_sub(2),
+			..area
+		};
+		let bar = self.percentage.map_or(0, |_| PROGRESS_BAR_WIDTH + 1);
+		let spinner_width = inner.width.saturating_sub(bar);
+		Spinner::new(self.tick)
+			.label(self.title.as_str())
+			.style(self.spinner_style)
+			.label_style(self.label_style)
+			.render(
+				Rect {
+					width: spinner_width,
+					..inner
+				},
+				buf,
+			);
+
+		if let Some(percent) = self.percentage {
+			ProgressBar::new()
+				.percent(percent)
+				.style(self.label_style)
+				.filled_style(self.filled_style)
+				.render(
+					Rect {
+						x: inner.x + spinner_width + 1,
+						width: bar - 1,
+						..inner
+					},
+					buf,
+				);
+		}
+	}
+}
+
+impl Widget for StatusLine<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let Some(progress) = self.progress.filter(|p| p.width() <= area.width / 2) else {
+			self.segments.render(area, buf);
+			return;
+		};
+		let width = progress.width();
+		let segments = Rect {
+			width: area.width - width,
+			..area
+		};
+		self.segments.render(segments, buf);
+		progress.render(
+			Rect {
+				x: segments.right(),
+				width,
+				..area
+			},
+			buf,
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn indexing(percentage: Option<u16>) -> ProgressSegment {
+		ProgressSegment {
+			title: "Indexing".into(),
+			tick: 1,
+			percentage,
+			spinner_style: Style::default(),
+			label_style: Style::default(),
+			filled_style: Style::default(),
+		}
+	}
+
+	fn render(progress: Option<ProgressSegment>, width: u16) -> Buffer {
+		let mut buf = Buffer::empty(Rect::new(0, 0, width, 1));
+		StatusLine {
+			segments: Paragraph::new(" NORMAL "),
+			progress,
+		}
+		.render(buf.area, &mut buf);
+		buf
+	}
+
+	#[test]
+	fn draws_progress_at_right_edge() {
+		assert_eq!(
+			render(Some(indexing(Some(50))), 50),
+			Buffer::with_lines([" NORMAL                     ⠙ Indexing █████      "])
+		);
+		assert_eq!(
+			render(Some(indexing(None)), 24),
+			Buffer::with_lines([" NORMAL      ⠙ Indexing "])
+		);
+	}
+
+	#[test]
+	fn drops_progress_when_too_narrow() {
+		assert_eq!(
+			render(Some(indexing(Some(50))), 30),
+			Buffer::with_lines([" NORMAL                       "])
+		);
+	}
+}
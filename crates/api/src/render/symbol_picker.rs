@@ -0,0 +1,89 @@
+use unicode_width::UnicodeWidthStr;
+use xeno_tui::layout::{Position, Rect};
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::Paragraph;
+use xeno_tui::widgets::picker::Picker;
+
+use crate::Editor;
+use crate::editor::symbol_picker::{SymbolPickerState, SymbolStatus};
+
+/// Text before the filter text.
+const PROMPT: &str = "> ";
+
+impl Editor {
+	/// Renders the workspace symbol picker centered in `bounds`, with the
+	/// terminal cursor after its filter text.
+	///
+	/// While nothing is listed, the line under the prompt says whether
+	/// symbols are loading or why there are none.
+	pub(crate) fn render_symbol_picker(&mut self, frame: &mut xeno_tui::Frame, bounds: Rect) {
+		let theme = self.config.theme;
+		let popup = self.popup_frame().title("Workspace symbols");
+		let Some(picker) = self
+			.overlays
+			.get_or_default::<SymbolPickerState>()
+			.open
+			.as_mut()
+		else {
+			return;
+		};
+
+		let width = bounds
+			.width
+			.saturating_sub(8)
+			.clamp(40, 100)
+			.min(bounds.width);
+		let height = (bounds.height * 3 / 5).max(6).min(bounds.height);
+		let area = Rect::new(
+			bounds.x + (bounds.width - width) / 2,
+			bounds.y + (bounds.height - height) / 4,
+			width,
+			height,
+		);
+		let inner = popup.inner(area);
+		frame.render_widget(popup, area);
+		if inner.is_empty() {
+			return;
+		}
+
+		let colors = &theme.colors;
+		let base = Style::default().fg(colors.popup.fg).bg(colors.popup.bg);
+		let dim = Style::default().fg(colors.ui.gutter_fg);
+		frame.render_stateful_widget(
+			Picker::new()
+				.prompt(PROMPT)
+				.style(base)
+				.prompt_style(Style::default().fg(colors.popup.title))
+				.count_style(dim)
+				.highlight_style(
+					Style::default()
+						.fg(colors.ui.selection_fg)
+						.bg(colors.ui.selection_bg),
+				)
+				.match_style(Style::default().add_modifier(Modifier::BOLD))
+				.scrollbar_style(Style::default().fg(colors.popup.border)),
+			inner,
+			&mut picker.picker,
+		);
+
+		let placeholder = match &picker.status {
+			_ if picker.picker.match_count() > 0 => None,
+			SymbolStatus::Loading => Some("Searching…".to_string()),
+			SymbolStatus::Ready => Some("No matching symbols.".to_string()),
+			SymbolStatus::Unsupported => {
+				Some("The language server does not search workspace symbols.".to_string())
+			}
+			SymbolStatus::Failed(error) => Some(format!("Could not search symbols: {error}")),
+		};
+		if let Some(text) = placeholder
+			&& inner.height > 1
+		{
+			let line = Rect::new(inner.x, inner.y + 1, inner.width, 1);
+			frame.render_widget(Paragraph::new(text).style(base.patch(dim)), line);
+		}
+
+		let text_width = PROMPT.width() + picker.picker.query().width();
+		let x = inner.x + (text_width as u16).min(inner.width.saturating_sub(1));
+		frame.set_cursor_position(Position::new(x, inner.y));
+	}
+}
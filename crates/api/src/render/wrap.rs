@@ -4,6 +4,17 @@
 //! - Trailing punctuation (`. , ; : ! ? ) ] }`) stays with preceding word
 //! - Leading punctuation (`( [ { @ # $`) stays with following word
 //! - Path separators (`- /`) remain breakable
+//!
+//! Continuation rows can be prefixed by [`WrapIndent`]: the line's own
+//! leading whitespace, a fixed extra indent and a marker glyph. The prefix is
+//! dropped for lines indented so deeply that it would leave less than half
+//! the row for text.
+//!
+//! With wrapping off, [`clip_line`] instead cuts a single row out of the line
+//! at a horizontal scroll offset.
+
+use unicode_segmentation::UnicodeSegmentation;
+use xeno_base::column;
 
 /// A segment of a wrapped line.
 pub struct WrapSegment {
@@ -11,6 +22,52 @@ pub struct WrapSegment {
 	pub text: String,
 	/// Character offset from the start of the original line.
 	pub start_offset: usize,
+	/// Cells drawn before `text`: the continuation prefix, including the
+	/// wrap marker. Always zero on a line's first row.
+	pub indent: usize,
+}
+
+/// How continuation rows of a wrapped line are prefixed.
+///
+/// The default adds no prefix, so continuation rows start at column zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WrapIndent {
+	/// Repeat the line's leading whitespace on its continuation rows.
+	pub preserve: bool,
+	/// Cells added after the preserved indent.
+	pub extra: usize,
+	/// Glyph drawn at the start of each continuation row, after the indent.
+	pub marker: String,
+}
+
+impl WrapIndent {
+	/// Returns the display width of the wrap marker.
+	pub fn marker_width(&self) -> usize {
+		column::display_width(&self.marker, 1)
+	}
+
+	/// Returns the cells before the text of `line`'s continuation rows when
+	/// wrapped at `max_width`.
+	///
+	/// The indent is dropped when it would leave less than half the row for
+	/// text, and the marker too when it alone would.
+	pub fn prefix_width(&self, line: &str, max_width: usize, tab_width: usize) -> usize {
+		let marker = self.marker_width();
+		let indent = if self.preserve {
+			let leading = &line[..line.len() - line.trim_start_matches([' ', '\t']).len()];
+			column::display_width(leading, tab_width)
+		} else {
+			0
+		} + self.extra;
+
+		if (indent + marker) * 2 <= max_width {
+			indent + marker
+		} else if marker * 2 <= max_width {
+			marker
+		} else {
+			0
+		}
+	}
 }
 
 /// Wraps a line of text into segments that fit within a maximum width.
@@ -18,6 +75,17 @@ pub struct WrapSegment {
 /// Breaks at word boundaries when possible, keeping punctuation attached
 /// to their associated words (sticky punctuation).
 pub fn wrap_line(line: &str, max_width: usize, tab_width: usize) -> Vec<WrapSegment> {
+	wrap_line_indented(line, max_width, tab_width, &WrapIndent::default())
+}
+
+/// Wraps a line like [`wrap_line`], narrowing continuation rows by the
+/// prefix `indent` adds to them.
+pub fn wrap_line_indented(
+	line: &str,
+	max_width: usize,
+	tab_width: usize,
+	indent: &WrapIndent,
+) -> Vec<WrapSegment> {
 	if max_width == 0 {
 		return vec![];
 	}
@@ -27,41 +95,53 @@ pub fn wrap_line(line: &str, max_width: usize, tab_width: usize) -> Vec<WrapSegm
 		return vec![];
 	}
 
+	let widths = cluster_widths(line);
+	let prefix = indent.prefix_width(line, max_width, tab_width);
 	let mut segments = Vec::new();
 	let mut pos = 0;
 
 	while pos < chars.len() {
+		let row_indent = if pos == 0 { 0 } else { prefix };
+		let row_width = max_width - row_indent;
 		let mut col = 0usize;
 		let mut end = pos;
 
 		while end < chars.len() {
 			let ch = chars[end];
-			let mut w = if ch == '\t' {
-				tab_width.saturating_sub(col % tab_width)
-			} else {
-				1
-			};
-			if w == 0 {
-				w = 1;
-			}
-
-			let remaining = max_width.saturating_sub(col);
+			let remaining = row_width.saturating_sub(col);
 			if remaining == 0 {
 				break;
 			}
+
+			let w = if ch == '\t' {
+				tab_width
+					.saturating_sub(col % tab_width)
+					.max(1)
+					.min(remaining)
+			} else {
+				widths[end]
+			};
+			// Wide graphemes move to the next row rather than being split.
 			if w > remaining {
-				w = remaining;
+				break;
 			}
 
 			col += w;
 			end += 1;
-			if col >= max_width {
+			if col >= row_width {
 				break;
 			}
 		}
 
+		while end < chars.len() && widths[end] == 0 {
+			end += 1;
+		}
+
 		if end == pos {
-			end = (pos + 1).min(chars.len());
+			end = pos + 1;
+			while end < chars.len() && widths[end] == 0 {
+				end += 1;
+			}
 		}
 
 		let break_pos = if end < chars.len() {
@@ -74,6 +154,7 @@ pub fn wrap_line(line: &str, max_width: usize, tab_width: usize) -> Vec<WrapSegm
 		segments.push(WrapSegment {
 			text: chars[pos..break_pos].iter().collect(),
 			start_offset: pos,
+			indent: row_indent,
 		});
 
 		pos = break_pos;
@@ -82,6 +163,84 @@ pub fn wrap_line(line: &str, max_width: usize, tab_width: usize) -> Vec<WrapSegm
 	segments
 }
 
+/// A line clipped to the visible columns of an unwrapped view.
+pub struct ClippedLine {
+	/// The graphemes that fit entirely inside the visible columns.
+	pub segment: WrapSegment,
+	/// Blank cells drawn before the segment, covering a grapheme that
+	/// straddles the left edge.
+	pub lead: usize,
+	/// Content is hidden to the left; the first cell is left for a marker.
+	pub clipped_left: bool,
+	/// Content is hidden to the right; the last cell is left for a marker.
+	pub clipped_right: bool,
+	/// Whether the end-of-line cell falls inside the visible columns.
+	pub eol_visible: bool,
+}
+
+/// Clips a line to the `width` columns starting at display column `left_col`.
+///
+/// Edge cells are reserved for clip markers when content is hidden on that
+/// side. Wide graphemes cut by the left edge become blank lead cells rather
+/// than half a glyph; tabs cut by the right edge are kept for the caller to
+/// clamp, other graphemes that do not fit are dropped.
+pub fn clip_line(line: &str, left_col: usize, width: usize, tab_width: usize) -> ClippedLine {
+	let display_width = column::display_width(line, tab_width);
+	let clipped_left = left_col > 0 && display_width > 0 && width > 0;
+	let clipped_right = display_width > left_col + width && width > 0;
+	let start = left_col + usize::from(clipped_left);
+	let end = (left_col + width).saturating_sub(usize::from(clipped_right));
+
+	let mut text = String::new();
+	let mut start_offset = None;
+	let mut lead = 0;
+	let mut col = 0;
+	let mut char_idx = 0;
+	for g in line.graphemes(true) {
+		if col >= end {
+			break;
+		}
+		let w = column::grapheme_width(g, col, tab_width);
+		if col < start {
+			if col + w > start {
+				lead = (col + w).min(end) - start;
+			}
+		} else {
+			if col + w > end && g != "\t" {
+				break;
+			}
+			start_offset.get_or_insert(char_idx);
+			text.push_str(g);
+		}
+		col += w;
+		char_idx += g.chars().count();
+	}
+
+	ClippedLine {
+		segment: WrapSegment {
+			text,
+			start_offset: start_offset.unwrap_or(char_idx),
+			indent: 0,
+		},
+		lead,
+		clipped_left,
+		clipped_right,
+		eol_visible: !clipped_right && display_width >= start && display_width < left_col + width,
+	}
+}
+
+/// Returns per-char display widths, with each grapheme cluster's width on its
+/// first char and zero on the chars that continue it. Tabs report one cell;
+/// their real width depends on the column and is computed by the caller.
+fn cluster_widths(line: &str) -> Vec<usize> {
+	let mut widths = Vec::with_capacity(line.len());
+	for g in line.graphemes(true) {
+		widths.push(column::grapheme_width(g, 0, 1));
+		widths.extend(std::iter::repeat_n(0, g.chars().count() - 1));
+	}
+	widths
+}
+
 fn is_trailing_punct(ch: char) -> bool {
 	matches!(
 		ch,
@@ -185,8 +344,80 @@ mod tests {
 		assert_eq!(wrap("end.) next", 6), vec!["end.) ", "next"]);
 	}
 
+	#[test]
+	fn wide_graphemes_wrap_whole() {
+		assert_eq!(wrap("ab漢字", 3), vec!["ab", "漢", "字"]);
+		assert_eq!(wrap("e\u{301}e\u{301}", 1), vec!["e\u{301}", "e\u{301}"]);
+	}
+
 	#[test]
 	fn quote_with_word() {
 		assert_eq!(wrap("say \"hi\" ok", 9), vec!["say \"hi\" ", "ok"]);
 	}
+
+	#[test]
+	fn continuation_rows_keep_indent() {
+		let indent = WrapIndent {
+			preserve: true,
+			extra: 0,
+			marker: "↪ ".to_string(),
+		};
+		let segments = wrap_line_indented("  aaaa bbbb cccc", 10, 4, &indent);
+		let rows: Vec<_> = segments
+			.iter()
+			.map(|s| (s.text.as_str(), s.indent))
+			.collect();
+		assert_eq!(rows, vec![("  aaaa ", 0), ("bbbb ", 4), ("cccc", 4)]);
+	}
+
+	#[test]
+	fn deep_indent_falls_back_to_marker() {
+		let indent = WrapIndent {
+			preserve: true,
+			extra: 2,
+			marker: "↪ ".to_string(),
+		};
+		assert_eq!(indent.prefix_width("\tx", 16, 4), 8);
+		assert_eq!(indent.prefix_width("\t\tx", 16, 4), 2);
+		assert_eq!(indent.prefix_width("\t\tx", 3, 4), 0);
+		let segments = wrap_line_indented("        abcdefgh", 8, 4, &indent);
+		assert!(
+			segments
+				.iter()
+				.skip(1)
+				.all(|s| s.indent == 2 && !s.text.is_empty())
+		);
+	}
+
+	#[test]
+	fn clip_reserves_marker_cells() {
+		let clipped = clip_line("abcdefghij", 3, 5, 4);
+		assert_eq!(clipped.segment.text, "efg");
+		assert_eq!(clipped.segment.start_offset, 4);
+		assert!(clipped.clipped_left && clipped.clipped_right);
+		assert!(!clipped.eol_visible);
+
+		let clipped = clip_line("abcdefghij", 0, 20, 4);
+		assert_eq!(clipped.segment.text, "abcdefghij");
+		assert!(!clipped.clipped_left && !clipped.clipped_right);
+		assert!(clipped.eol_visible);
+	}
+
+	#[test]
+	fn clip_wide_grapheme_at_left_edge_becomes_lead() {
+		// "漢" spans columns 1-2; the first visible content column is 2.
+		let clipped = clip_line("a漢bc", 1, 4, 4);
+		assert_eq!(clipped.lead, 1);
+		assert_eq!(clipped.segment.text, "bc");
+		assert_eq!(clipped.segment.start_offset, 2);
+	}
+
+	#[test]
+	fn clip_line_scrolled_past_end() {
+		let clipped = clip_line("ab", 5, 4, 4);
+		assert!(clipped.clipped_left);
+		assert!(clipped.segment.text.is_empty());
+		assert!(!clipped.eol_visible);
+		assert!(!clip_line("", 5, 4, 4).clipped_left);
+	}
 }
@@ -2,10 +2,13 @@
 //!
 //! The dock system organizes panels into slots (left, right, top, bottom, overlay)
 //! and computes their layout constraints relative to the main document area.
+//! Panels sharing a slot stack as tabs with only the active one showing; the
+//! overlay slot floats its active panel in a bordered box centered over the
+//! main area.
 
 use std::collections::HashMap;
 
-use xeno_tui::layout::{Constraint, Direction, Layout, Rect};
+use xeno_tui::layout::{Constraint, Direction, Layout, Margin, Rect};
 
 /// Position where a panel can be docked in the editor layout.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -18,10 +21,52 @@ pub enum DockSlot {
 	Bottom,
 	/// Top of the editor, typically for toolbars or status displays.
 	Top,
-	/// Floating overlay that covers the main content area.
+	/// Floating box centered over the main content area.
 	Overlay,
 }
 
+impl DockSlot {
+	/// Every slot, in the order sessions list them.
+	pub const ALL: [Self; 5] = [
+		Self::Left,
+		Self::Right,
+		Self::Bottom,
+		Self::Top,
+		Self::Overlay,
+	];
+
+	/// Returns the slot's name as saved in sessions.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Left => "left",
+			Self::Right => "right",
+			Self::Bottom => "bottom",
+			Self::Top => "top",
+			Self::Overlay => "overlay",
+		}
+	}
+
+	/// Slots in the order focus cycles through them after the editor.
+	pub const FOCUS_ORDER: [Self; 5] = [
+		Self::Left,
+		Self::Bottom,
+		Self::Right,
+		Self::Top,
+		Self::Overlay,
+	];
+
+	/// Parses a slot name written by [`name`](Self::name).
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|slot| slot.name() == name)
+	}
+}
+
+/// Smallest size a docked panel can be resized to, in percent.
+pub const MIN_PANEL_PERCENT: u16 = 5;
+
+/// Largest size a docked panel can be resized to, in percent.
+pub const MAX_PANEL_PERCENT: u16 = 95;
+
 /// Specification for the size of a docked panel.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SizeSpec {
@@ -65,6 +110,11 @@ impl DockSlotState {
 pub struct DockManager {
 	/// Map from dock positions to their current state.
 	pub slots: HashMap<DockSlot, DockSlotState>,
+	/// Sizes of panels that were resized, used instead of their slot's size
+	/// while they are the active tab.
+	pub panel_sizes: HashMap<String, SizeSpec>,
+	/// Slot each floating panel was docked in before it floated.
+	floated_from: HashMap<String, DockSlot>,
 }
 
 /// Computed layout result from the dock manager.
@@ -74,6 +124,10 @@ pub struct DockLayout {
 	pub doc_area: Rect,
 	/// Map from panel IDs to their computed screen rectangles.
 	pub panel_areas: HashMap<String, Rect>,
+	/// Tab bar rows of slots stacking more than one panel.
+	pub tab_bars: Vec<(DockSlot, Rect)>,
+	/// Box of the floating panel, border included.
+	pub floating: Option<Rect>,
 }
 
 impl DockManager {
@@ -84,11 +138,11 @@ impl DockManager {
 		slots.insert(DockSlot::Top, DockSlotState::new(SizeSpec::Percent(25)));
 		slots.insert(DockSlot::Left, DockSlotState::new(SizeSpec::Percent(25)));
 		slots.insert(DockSlot::Right, DockSlotState::new(SizeSpec::Percent(25)));
-		slots.insert(
-			DockSlot::Overlay,
-			DockSlotState::new(SizeSpec::Percent(100)),
-		);
-		Self { slots }
+		slots.insert(DockSlot::Overlay, DockSlotState::new(SizeSpec::Percent(60)));
+		Self {
+			slots,
+			..Self::default()
+		}
 	}
 
 	/// Opens a panel in the specified dock slot, making it active.
@@ -121,6 +175,14 @@ impl DockManager {
 		self.slots.values().any(|s| s.open.iter().any(|p| p == id))
 	}
 
+	/// Returns the slot the panel with the given ID is open in, if any.
+	pub fn slot_of(&self, id: &str) -> Option<DockSlot> {
+		self.slots
+			.iter()
+			.find(|(_, s)| s.open.iter().any(|p| p == id))
+			.map(|(slot, _)| *slot)
+	}
+
 	/// Returns whether any panel is currently open in any slot.
 	pub fn any_open(&self) -> bool {
 		self.slots.values().any(|s| !s.open.is_empty())
@@ -131,6 +193,123 @@ impl DockManager {
 		self.slots.get(&slot).and_then(|s| s.active.as_deref())
 	}
 
+	/// Returns whether the panel is open and the active tab of its slot.
+	pub fn is_showing(&self, id: &str) -> bool {
+		self.slot_of(id)
+			.is_some_and(|slot| self.active_in_slot(slot) == Some(id))
+	}
+
+	/// Makes the tab `delta` places from the active one in `slot` active,
+	/// wrapping at either end, and returns its ID.
+	pub fn cycle_tab(&mut self, slot: DockSlot, delta: isize) -> Option<&str> {
+		let state = self.slots.get_mut(&slot)?;
+		let len = state.open.len();
+		if len == 0 {
+			return None;
+		}
+		let current = state
+			.active
+			.as_ref()
+			.and_then(|id| state.open.iter().position(|p| p == id))
+			.unwrap_or(0);
+		let next = (current as isize + delta).rem_euclid(len as isize) as usize;
+		state.active = Some(state.open[next].clone());
+		state.active.as_deref()
+	}
+
+	/// Returns the size of the panel, or of its slot if it was never resized.
+	pub fn panel_size(&self, id: &str) -> Option<SizeSpec> {
+		let slot = self.slot_of(id)?;
+		self.panel_sizes
+			.get(id)
+			.or_else(|| self.slots.get(&slot).map(|s| &s.size))
+			.copied()
+	}
+
+	/// Sets the size of an open panel, kept between [`MIN_PANEL_PERCENT`]
+	/// and [`MAX_PANEL_PERCENT`], and returns it.
+	pub fn set_panel_size(&mut self, id: &str, percent: u16) -> Option<SizeSpec> {
+		self.slot_of(id)?;
+		let size = SizeSpec::Percent(percent.clamp(MIN_PANEL_PERCENT, MAX_PANEL_PERCENT));
+		self.panel_sizes.insert(id.to_string(), size);
+		Some(size)
+	}
+
+	/// Grows an open panel by `delta` percent, or shrinks it when negative,
+	/// and returns its new size.
+	pub fn resize_panel(&mut self, id: &str, delta: i32) -> Option<SizeSpec> {
+		let SizeSpec::Percent(current) = self.panel_size(id)?;
+		let percent = (i32::from(current) + delta).clamp(0, 100) as u16;
+		self.set_panel_size(id, percent)
+	}
+
+	/// Returns the panels showing in each slot, in [`DockSlot::FOCUS_ORDER`].
+	pub fn focus_cycle(&self) -> Vec<&str> {
+		DockSlot::FOCUS_ORDER
+			.into_iter()
+			.filter_map(|slot| self.active_in_slot(slot))
+			.collect()
+	}
+
+	/// Returns every open panel, showing or stacked behind another tab.
+	pub fn open_panels(&self) -> Vec<&str> {
+		DockSlot::ALL
+			.iter()
+			.filter_map(|slot| self.slots.get(slot))
+			.flat_map(|state| state.open.iter().map(String::as_str))
+			.collect()
+	}
+
+	/// Returns the size the slot takes up, which is that of its active tab.
+	fn slot_size(&self, slot: DockSlot) -> Option<SizeSpec> {
+		let state = self.slots.get(&slot)?;
+		let active = state.active.as_ref()?;
+		Some(self.panel_sizes.get(active).copied().unwrap_or(state.size))
+	}
+
+	/// Moves a docked panel into the overlay slot, or a floating one back to
+	/// the slot it floated from, `fallback` if unknown.
+	///
+	/// Returns whether the panel is now floating.
+	pub fn toggle_floating(&mut self, id: &str, fallback: DockSlot) -> bool {
+		match self.slot_of(id) {
+			Some(DockSlot::Overlay) => {
+				let slot = self
+					.floated_from
+					.remove(id)
+					.filter(|slot| *slot != DockSlot::Overlay)
+					.unwrap_or(fallback);
+				self.close_panel(id);
+				self.open_panel(slot, id.to_string());
+				false
+			}
+			slot => {
+				self.floated_from
+					.insert(id.to_string(), slot.unwrap_or(fallback));
+				self.close_panel(id);
+				self.open_panel(DockSlot::Overlay, id.to_string());
+				true
+			}
+		}
+	}
+
+	/// Assigns `area` to the active panel of `slot`, keeping its top row for
+	/// a tab bar when the slot stacks several panels.
+	fn place(&self, layout: &mut DockLayout, slot: DockSlot, mut area: Rect) {
+		let Some(state) = self.slots.get(&slot) else {
+			return;
+		};
+		let Some(id) = &state.active else {
+			return;
+		};
+		if state.open.len() > 1 && area.height > 1 {
+			layout.tab_bars.push((slot, Rect { height: 1, ..area }));
+			area.y += 1;
+			area.height -= 1;
+		}
+		layout.panel_areas.insert(id.clone(), area);
+	}
+
 	/// Computes the layout for all open panels within the given area.
 	///
 	/// Returns a `DockLayout` containing the remaining document area and
@@ -140,108 +319,163 @@ impl DockManager {
 			doc_area: area,
 			..Default::default()
 		};
+		let constraint = |slot: DockSlot| {
+			self.slot_size(slot)
+				.map_or(Constraint::Length(0), SizeSpec::to_constraint)
+		};
 
-		let has_top = self
-			.slots
-			.get(&DockSlot::Top)
-			.map(|s| !s.open.is_empty())
-			.unwrap_or(false);
-		let has_bottom = self
-			.slots
-			.get(&DockSlot::Bottom)
-			.map(|s| !s.open.is_empty())
-			.unwrap_or(false);
-
-		let mut vertical_parts = vec![area];
+		let has_top = self.slot_size(DockSlot::Top).is_some();
+		let has_bottom = self.slot_size(DockSlot::Bottom).is_some();
 		let mut top_area = None;
 		let mut bottom_area = None;
-
 		if has_top || has_bottom {
-			let top_c = if has_top {
-				self.slots
-					.get(&DockSlot::Top)
-					.map(|s| s.size.to_constraint())
-					.unwrap_or(Constraint::Percentage(25))
-			} else {
-				Constraint::Length(0)
-			};
-			let bottom_c = if has_bottom {
-				self.slots
-					.get(&DockSlot::Bottom)
-					.map(|s| s.size.to_constraint())
-					.unwrap_or(Constraint::Percentage(30))
-			} else {
-				Constraint::Length(0)
-			};
-
-			vertical_parts = Layout::default()
+			let parts = Layout::default()
 				.direction(Direction::Vertical)
-				.constraints([top_c, Constraint::Min(1), bottom_c])
-				.split(area)
-				.to_vec();
-
+				.constraints([
+					constraint(DockSlot::Top),
+					Constraint::Min(1),
+					constraint(DockSlot::Bottom),
+				])
+				.split(area);
 			if has_top {
-				top_area = Some(vertical_parts[0]);
+				top_area = Some(parts[0]);
 			}
 			if has_bottom {
-				bottom_area = Some(vertical_parts[2]);
+				bottom_area = Some(parts[2]);
 			}
-			layout.doc_area = vertical_parts[1];
+			layout.doc_area = parts[1];
 		}
 
-		let has_left = self
-			.slots
-			.get(&DockSlot::Left)
-			.map(|s| !s.open.is_empty())
-			.unwrap_or(false);
-		let has_right = self
-			.slots
-			.get(&DockSlot::Right)
-			.map(|s| !s.open.is_empty())
-			.unwrap_or(false);
-
+		let has_left = self.slot_size(DockSlot::Left).is_some();
+		let has_right = self.slot_size(DockSlot::Right).is_some();
 		if has_left || has_right {
-			let left_c = if has_left {
-				self.slots
-					.get(&DockSlot::Left)
-					.map(|s| s.size.to_constraint())
-					.unwrap_or(Constraint::Percentage(25))
-			} else {
-				Constraint::Length(0)
-			};
-			let right_c = if has_right {
-				self.slots
-					.get(&DockSlot::Right)
-					.map(|s| s.size.to_constraint())
-					.unwrap_or(Constraint::Percentage(25))
-			} else {
-				Constraint::Length(0)
-			};
-
 			let parts = Layout::default()
 				.direction(Direction::Horizontal)
-				.constraints([left_c, Constraint::Min(1), right_c])
+				.constraints([
+					constraint(DockSlot::Left),
+					Constraint::Min(1),
+					constraint(DockSlot::Right),
+				])
 				.split(layout.doc_area);
-			if has_left && let Some(id) = self.active_in_slot(DockSlot::Left) {
-				layout.panel_areas.insert(id.to_string(), parts[0]);
+			if has_left {
+				self.place(&mut layout, DockSlot::Left, parts[0]);
 			}
-			if has_right && let Some(id) = self.active_in_slot(DockSlot::Right) {
-				layout.panel_areas.insert(id.to_string(), parts[2]);
+			if has_right {
+				self.place(&mut layout, DockSlot::Right, parts[2]);
 			}
 			layout.doc_area = parts[1];
 		}
 
-		if let Some(area) = top_area
-			&& let Some(id) = self.active_in_slot(DockSlot::Top)
-		{
-			layout.panel_areas.insert(id.to_string(), area);
+		if let Some(area) = top_area {
+			self.place(&mut layout, DockSlot::Top, area);
 		}
-		if let Some(area) = bottom_area
-			&& let Some(id) = self.active_in_slot(DockSlot::Bottom)
-		{
-			layout.panel_areas.insert(id.to_string(), area);
+		if let Some(area) = bottom_area {
+			self.place(&mut layout, DockSlot::Bottom, area);
+		}
+
+		if let Some(SizeSpec::Percent(percent)) = self.slot_size(DockSlot::Overlay) {
+			let scale = |len: u16| (u32::from(len) * u32::from(percent.min(100)) / 100) as u16;
+			let width = scale(area.width).max(area.width.min(20));
+			let height = scale(area.height).max(area.height.min(5));
+			let outer = Rect {
+				x: area.x + (area.width - width) / 2,
+				y: area.y + (area.height - height) / 2,
+				width,
+				height,
+			};
+			layout.floating = Some(outer);
+			self.place(
+				&mut layout,
+				DockSlot::Overlay,
+				outer.inner(Margin::new(1, 1)),
+			);
 		}
 
 		layout
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const AREA: Rect = Rect {
+		x: 0,
+		y: 0,
+		width: 100,
+		height: 40,
+	};
+
+	#[test]
+	fn stacked_panels_show_one_tab_below_a_tab_bar() {
+		let mut dock = DockManager::new();
+		dock.open_panel(DockSlot::Bottom, "search".into());
+		dock.open_panel(DockSlot::Bottom, "terminal".into());
+		let layout = dock.compute_layout(AREA);
+		assert!(!layout.panel_areas.contains_key("search"));
+		let (slot, bar) = layout.tab_bars[0];
+		assert_eq!(slot, DockSlot::Bottom);
+		assert_eq!(layout.panel_areas["terminal"].y, bar.y + 1);
+
+		assert_eq!(dock.cycle_tab(DockSlot::Bottom, 1), Some("search"));
+		assert_eq!(dock.cycle_tab(DockSlot::Bottom, 1), Some("terminal"));
+		assert_eq!(dock.cycle_tab(DockSlot::Bottom, -1), Some("search"));
+	}
+
+	#[test]
+	fn tabs_keep_their_own_size() {
+		let mut dock = DockManager::new();
+		dock.open_panel(DockSlot::Bottom, "search".into());
+		dock.open_panel(DockSlot::Bottom, "terminal".into());
+		dock.panel_sizes
+			.insert("terminal".into(), SizeSpec::Percent(50));
+		assert_eq!(dock.compute_layout(AREA).doc_area.height, 20);
+		dock.cycle_tab(DockSlot::Bottom, 1);
+		assert_eq!(dock.compute_layout(AREA).doc_area.height, 28);
+	}
+
+	#[test]
+	fn floating_panel_returns_to_its_slot() {
+		let mut dock = DockManager::new();
+		dock.open_panel(DockSlot::Left, "files".into());
+		assert!(dock.toggle_floating("files", DockSlot::Bottom));
+		let layout = dock.compute_layout(AREA);
+		assert_eq!(layout.doc_area, AREA);
+		let outer = layout.floating.unwrap();
+		assert_eq!((outer.width, outer.height), (60, 24));
+		assert_eq!(layout.panel_areas["files"], outer.inner(Margin::new(1, 1)));
+
+		assert!(!dock.toggle_floating("files", DockSlot::Bottom));
+		assert_eq!(dock.slot_of("files"), Some(DockSlot::Left));
+	}
+
+	#[test]
+	fn resizing_keeps_panels_within_bounds() {
+		let mut dock = DockManager::new();
+		dock.open_panel(DockSlot::Bottom, "terminal".into());
+		assert_eq!(
+			dock.resize_panel("terminal", 10),
+			Some(SizeSpec::Percent(40))
+		);
+		assert_eq!(
+			dock.resize_panel("terminal", -100),
+			Some(SizeSpec::Percent(MIN_PANEL_PERCENT))
+		);
+		assert_eq!(
+			dock.set_panel_size("terminal", 100),
+			Some(SizeSpec::Percent(MAX_PANEL_PERCENT))
+		);
+		assert_eq!(dock.resize_panel("search", 10), None);
+	}
+
+	#[test]
+	fn focus_cycles_left_bottom_right() {
+		let mut dock = DockManager::new();
+		dock.open_panel(DockSlot::Right, "outline".into());
+		dock.open_panel(DockSlot::Bottom, "search".into());
+		dock.open_panel(DockSlot::Bottom, "terminal".into());
+		dock.open_panel(DockSlot::Left, "files".into());
+		assert_eq!(dock.focus_cycle(), ["files", "terminal", "outline"]);
+		assert_eq!(dock.open_panels().len(), 4);
+	}
+}
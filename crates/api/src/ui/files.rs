@@ -0,0 +1,524 @@
+//! File explorer panel.
+//!
+//! Shows the working directory as a lazily loaded tree docked on the left.
+//! Files open through the `edit` command, so they go through the same path
+//! as `:e`. Creating, renaming and deleting entries ask through prompt and
+//! confirm popups, and the tree reloads once an operation finishes or the
+//! panel regains focus.
+
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{fs, io};
+
+use globset::{GlobBuilder, GlobMatcher};
+use termina::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use xeno_registry::notifications::keys;
+use xeno_registry::options::keys as opts;
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::Rect;
+use xeno_tui::style::Style;
+use xeno_tui::widgets::tree_view::{TreeItem, TreeKey, TreeView, TreeViewState};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::editor::Editor;
+use crate::prompt::{ConfirmPopup, ConfirmResult, PromptPopup, PromptResult};
+
+/// Identifier of the file explorer panel.
+pub const FILES_PANEL_ID: &str = "files";
+
+/// Panel browsing the files below the working directory.
+pub struct FileExplorer {
+	/// Directory shown at the top of the tree.
+	root: PathBuf,
+	/// Loaded entries, expansion and selection.
+	tree: TreeViewState<PathBuf>,
+	/// Whether dotfiles and ignored paths are hidden, shared with the loader.
+	hide_ignored: Rc<Cell<bool>>,
+	/// Set when the tree must be reloaded from disk.
+	stale: Arc<AtomicBool>,
+	/// Whether the panel is docked.
+	open: bool,
+	/// Area the tree was last drawn in, for mouse hit-testing.
+	area: Rect,
+}
+
+impl Default for FileExplorer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl FileExplorer {
+	/// Creates an explorer rooted at the working directory.
+	pub fn new() -> Self {
+		Self::with_root(std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+	}
+
+	/// Creates an explorer rooted at `root`.
+	pub fn with_root(root: PathBuf) -> Self {
+		let hide_ignored = Rc::new(Cell::new(true));
+		let loader = {
+			let (root, hide_ignored) = (root.clone(), Rc::clone(&hide_ignored));
+			move |dir: &PathBuf| list_dir(&root, dir, hide_ignored.get())
+		};
+		Self {
+			root,
+			tree: TreeViewState::new().with_loader(loader),
+			hide_ignored,
+			stale: Arc::new(AtomicBool::new(true)),
+			open: false,
+			area: Rect::ZERO,
+		}
+	}
+
+	/// Reloads the tree from disk if it is stale, keeping expansion and the
+	/// selection.
+	fn refresh(&mut self, editor: &Editor) {
+		if !self.stale.swap(false, Ordering::Relaxed) {
+			return;
+		}
+		self.hide_ignored
+			.set(editor.option(opts::EXPLORER_HIDE_IGNORED));
+		let roots = list_dir(&self.root, &self.root, self.hide_ignored.get());
+		self.tree.set_roots(roots);
+	}
+
+	/// Returns the selected path and whether it is a directory.
+	fn selected(&self) -> Option<(PathBuf, bool)> {
+		let path = self.tree.selected_id()?.clone();
+		let is_dir = path.is_dir();
+		Some((path, is_dir))
+	}
+
+	/// Returns the directory new entries are created in: the selected
+	/// directory, or the parent of the selected file.
+	fn target_dir(&self) -> PathBuf {
+		match self.selected() {
+			Some((path, true)) => path,
+			Some((path, false)) => path
+				.parent()
+				.map_or_else(|| self.root.clone(), Path::to_path_buf),
+			None => self.root.clone(),
+		}
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let tree_key = match key.code {
+			KeyCode::Char('j') | KeyCode::Down => Some(TreeKey::Down),
+			KeyCode::Char('k') | KeyCode::Up => Some(TreeKey::Up),
+			KeyCode::Char('g') | KeyCode::Home => Some(TreeKey::Home),
+			KeyCode::Char('G') | KeyCode::End => Some(TreeKey::End),
+			KeyCode::PageUp => Some(TreeKey::PageUp),
+			KeyCode::PageDown => Some(TreeKey::PageDown),
+			KeyCode::Char('l') | KeyCode::Right => Some(TreeKey::Right),
+			KeyCode::Char('h') | KeyCode::Left => Some(TreeKey::Left),
+			_ => None,
+		};
+		if let Some(tree_key) = tree_key {
+			self.tree.handle_key(tree_key);
+			return EventResult::consumed().with_request(UiRequest::Redraw);
+		}
+
+		match key.code {
+			KeyCode::Enter => match self.selected() {
+				Some((path, true)) => {
+					self.tree.toggle(&path);
+					EventResult::consumed().with_request(UiRequest::Redraw)
+				}
+				Some((path, false)) => {
+					editor
+						.workspace
+						.command_queue
+						.push("edit", vec![path.to_string_lossy().into_owned()]);
+					EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+				}
+				None => EventResult::consumed(),
+			},
+			KeyCode::Char('a') => self.prompt_create(editor, false),
+			KeyCode::Char('A') => self.prompt_create(editor, true),
+			KeyCode::Char('r') => self.prompt_rename(editor),
+			KeyCode::Char('d') => self.confirm_delete(editor),
+			KeyCode::Char('q') | KeyCode::Escape => {
+				EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+			}
+			_ => EventResult::consumed(),
+		}
+	}
+
+	/// Prompts for the name of a file or directory to create in the target
+	/// directory. A name ending in `/` creates a directory.
+	fn prompt_create(&self, editor: &mut Editor, dir: bool) -> EventResult {
+		let parent = self.target_dir();
+		let title = if dir { "New directory" } else { "New file" };
+		let prompt = PromptPopup::new(
+			title,
+			file_operation(Arc::clone(&self.stale), move |name: String| {
+				let path = parent.join(&name);
+				if dir || name.ends_with('/') {
+					fs::create_dir_all(&path)
+				} else {
+					if let Some(parent) = path.parent() {
+						fs::create_dir_all(parent)?;
+					}
+					fs::OpenOptions::new()
+						.write(true)
+						.create_new(true)
+						.open(&path)
+						.map(drop)
+				}
+			}),
+		)
+		.validator(non_empty);
+		open_prompt(editor, prompt)
+	}
+
+	/// Prompts for a new name for the selected entry.
+	fn prompt_rename(&self, editor: &mut Editor) -> EventResult {
+		let Some((path, _)) = self.selected() else {
+			return EventResult::consumed();
+		};
+		let name = path
+			.file_name()
+			.map(|n| n.to_string_lossy().into_owned())
+			.unwrap_or_default();
+		let prompt = PromptPopup::new(
+			"Rename",
+			file_operation(Arc::clone(&self.stale), move |name: String| {
+				let target = path.with_file_name(&name);
+				if target.exists() {
+					return Err(io::Error::new(
+						io::ErrorKind::AlreadyExists,
+						format!("{} already exists", target.display()),
+					));
+				}
+				fs::rename(&path, target)
+			}),
+		)
+		.initial(name)
+		.validator(non_empty);
+		open_prompt(editor, prompt)
+	}
+
+	/// Asks before deleting the selected entry, recursively for directories.
+	fn confirm_delete(&self, editor: &mut Editor) -> EventResult {
+		let Some((path, is_dir)) = self.selected() else {
+			return EventResult::consumed();
+		};
+		let message = format!("Delete {}?", path.display());
+		let stale = Arc::clone(&self.stale);
+		editor.open_confirm(
+			ConfirmPopup::new("Delete", message, move |editor, result| {
+				if result != ConfirmResult::Chosen('y') {
+					return;
+				}
+				let removed = if is_dir {
+					fs::remove_dir_all(&path)
+				} else {
+					fs::remove_file(&path)
+				};
+				finish_operation(editor, &stale, removed);
+			})
+			.choice('y', "yes")
+			.choice('n', "no"),
+		);
+		EventResult::consumed().with_request(UiRequest::Redraw)
+	}
+}
+
+impl Panel for FileExplorer {
+	fn id(&self) -> &str {
+		FILES_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Left
+	}
+
+	fn title(&self) -> &str {
+		"Files"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		self.open = open;
+		if open {
+			self.stale.store(true, Ordering::Relaxed);
+		}
+	}
+
+	fn on_focus_changed(&mut self, focused: bool) {
+		if focused {
+			self.stale.store(true, Ordering::Relaxed);
+		}
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick if self.open && self.stale.load(Ordering::Relaxed) => {
+				self.refresh(editor);
+				EventResult::not_consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Mouse(mouse) => {
+				match mouse.kind {
+					MouseEventKind::Down(MouseButton::Left) => {
+						let y = mouse.row.saturating_sub(self.area.y);
+						if mouse.row >= self.area.y
+							&& let Some(row) = self.tree.row_at(y)
+						{
+							self.tree.select_row(row);
+						}
+					}
+					MouseEventKind::ScrollUp => {
+						self.tree.handle_key(TreeKey::Up);
+					}
+					MouseEventKind::ScrollDown => {
+						self.tree.handle_key(TreeKey::Down);
+					}
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		_focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		self.refresh(editor);
+		self.area = area;
+		let colors = &theme.colors.ui;
+		let tree = TreeView::new()
+			.style(Style::default().fg(colors.fg).bg(colors.bg))
+			.highlight_style(
+				Style::default()
+					.fg(colors.selection_fg)
+					.bg(colors.selection_bg),
+			)
+			.guide_style(Style::default().fg(colors.gutter_fg));
+		frame.render_stateful_widget(tree, area, &mut self.tree);
+		None
+	}
+}
+
+/// Opens `prompt` with focus moved off the panel, so keys reach the prompt.
+fn open_prompt(editor: &mut Editor, prompt: PromptPopup) -> EventResult {
+	if !editor.open_prompt(prompt) {
+		return EventResult::consumed();
+	}
+	EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+}
+
+/// Rejects an empty name.
+fn non_empty(input: &str) -> Result<(), String> {
+	if input.trim().is_empty() {
+		Err("name required".to_string())
+	} else {
+		Ok(())
+	}
+}
+
+/// Wraps a file operation as a prompt callback that runs it on the submitted
+/// name, then refocuses the explorer.
+fn file_operation(
+	stale: Arc<AtomicBool>,
+	operation: impl FnOnce(String) -> io::Result<()> + Send + Sync + 'static,
+) -> impl FnOnce(&mut Editor, PromptResult) + Send + Sync + 'static {
+	move |editor, result| match result {
+		PromptResult::Submitted(name) => finish_operation(editor, &stale, operation(name)),
+		PromptResult::Cancelled => {
+			editor.apply_ui_requests(vec![UiRequest::Focus(UiFocus::panel(FILES_PANEL_ID))]);
+		}
+	}
+}
+
+/// Reports a failed file operation, marks the tree for reloading and
+/// refocuses the explorer.
+fn finish_operation(editor: &mut Editor, stale: &AtomicBool, result: io::Result<()>) {
+	if let Err(e) = result {
+		editor.notify(keys::file_operation_error::call(&e.to_string()));
+	}
+	stale.store(true, Ordering::Relaxed);
+	editor.apply_ui_requests(vec![UiRequest::Focus(UiFocus::panel(FILES_PANEL_ID))]);
+}
+
+/// Lists the entries of `dir`, directories first, each group sorted by name
+/// ignoring case.
+///
+/// With `hide_ignored`, dotfiles and paths matched by `.gitignore` files
+/// between `root` and `dir` are left out.
+fn list_dir(root: &Path, dir: &Path, hide_ignored: bool) -> Vec<TreeItem<PathBuf>> {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return Vec::new();
+	};
+	let rules = if hide_ignored {
+		ignore_rules(root, dir)
+	} else {
+		Vec::new()
+	};
+	let mut entries: Vec<(PathBuf, String, bool)> = entries
+		.filter_map(Result::ok)
+		.filter_map(|entry| {
+			let name = entry.file_name().to_string_lossy().into_owned();
+			let path = entry.path();
+			let is_dir = path.is_dir();
+			let hidden =
+				hide_ignored && (name.starts_with('.') || is_ignored(&rules, &path, is_dir));
+			(!hidden).then_some((path, name, is_dir))
+		})
+		.collect();
+	entries.sort_by(|a, b| {
+		b.2.cmp(&a.2)
+			.then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+	});
+	entries
+		.into_iter()
+		.map(|(path, name, is_dir)| {
+			if is_dir {
+				TreeItem::branch(path, format!("{name}/"))
+			} else {
+				TreeItem::leaf(path, name)
+			}
+		})
+		.collect()
+}
+
+/// A pattern from a `.gitignore` file.
+pub(crate) struct IgnoreRule {
+	/// Matches paths relative to the directory holding the file.
+	glob: GlobMatcher,
+	/// Whether the pattern started with `!`, re-including matches.
+	negated: bool,
+	/// Whether the pattern ended with `/`, matching only directories.
+	dir_only: bool,
+}
+
+/// Parses the patterns of a `.gitignore` file, skipping blank lines,
+/// comments and invalid globs.
+///
+/// Patterns with a `/` before their end are anchored to the file's
+/// directory; others match at any depth.
+pub(crate) fn parse_gitignore(text: &str) -> Vec<IgnoreRule> {
+	text.lines()
+		.filter_map(|line| {
+			let line = line.trim_end();
+			if line.is_empty() || line.starts_with('#') {
+				return None;
+			}
+			let (negated, line) = match line.strip_prefix('!') {
+				Some(rest) => (true, rest),
+				None => (false, line),
+			};
+			let (dir_only, line) = match line.strip_suffix('/') {
+				Some(rest) => (true, rest),
+				None => (false, line),
+			};
+			let pattern = if line.contains('/') {
+				line.trim_start_matches('/').to_string()
+			} else {
+				format!("**/{line}")
+			};
+			let glob = GlobBuilder::new(&pattern)
+				.literal_separator(true)
+				.build()
+				.ok()?
+				.compile_matcher();
+			Some(IgnoreRule {
+				glob,
+				negated,
+				dir_only,
+			})
+		})
+		.collect()
+}
+
+/// Reads the `.gitignore` rules that apply inside `dir`, from `root` down.
+fn ignore_rules(root: &Path, dir: &Path) -> Vec<(PathBuf, Vec<IgnoreRule>)> {
+	let mut dirs: Vec<&Path> = dir
+		.ancestors()
+		.take_while(|ancestor| ancestor.starts_with(root))
+		.collect();
+	dirs.reverse();
+	dirs.into_iter()
+		.filter_map(|dir| {
+			let text = fs::read_to_string(dir.join(".gitignore")).ok()?;
+			Some((dir.to_path_buf(), parse_gitignore(&text)))
+		})
+		.collect()
+}
+
+/// Returns whether `path` is ignored by `rules`; the last matching pattern
+/// wins, with deeper `.gitignore` files applied last.
+pub(crate) fn is_ignored(rules: &[(PathBuf, Vec<IgnoreRule>)], path: &Path, is_dir: bool) -> bool {
+	let mut ignored = false;
+	for (base, rules) in rules {
+		let Ok(relative) = path.strip_prefix(base) else {
+			continue;
+		};
+		for rule in rules {
+			if (is_dir || !rule.dir_only) && rule.glob.is_match(relative) {
+				ignored = !rule.negated;
+			}
+		}
+	}
+	ignored
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ignored(gitignore: &str, path: &str, is_dir: bool) -> bool {
+		let rules = vec![(PathBuf::from("/repo"), parse_gitignore(gitignore))];
+		is_ignored(&rules, &Path::new("/repo").join(path), is_dir)
+	}
+
+	#[test]
+	fn gitignore_patterns_match_like_git() {
+		assert!(ignored("*.log", "a/b/debug.log", false));
+		assert!(ignored("target/", "target", true));
+		assert!(!ignored("target/", "target", false));
+		assert!(ignored("/build", "build", true));
+		assert!(!ignored("/build", "src/build", true));
+		assert!(ignored("docs/*.md", "docs/a.md", false));
+		assert!(!ignored("docs/*.md", "docs/sub/a.md", false));
+		assert!(!ignored("*.log\n!keep.log", "keep.log", false));
+		assert!(!ignored("# *.rs\n\n", "main.rs", false));
+	}
+
+	#[test]
+	fn lists_directories_first_and_hides_ignored() {
+		let tmp = tempfile::tempdir().unwrap();
+		let root = tmp.path();
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::create_dir_all(root.join("target")).unwrap();
+		for file in ["b.rs", "A.md", ".hidden"] {
+			fs::write(root.join(file), "").unwrap();
+		}
+		fs::write(root.join(".gitignore"), "target/\n").unwrap();
+
+		let labels = |hide| -> Vec<String> {
+			list_dir(&root, &root, hide)
+				.into_iter()
+				.map(|item| item.label)
+				.collect()
+		};
+		assert_eq!(labels(true), ["src/", "A.md", "b.rs"]);
+		assert_eq!(
+			labels(false),
+			["src/", "target/", ".gitignore", ".hidden", "A.md", "b.rs"]
+		);
+	}
+}
@@ -19,6 +19,14 @@ impl UiKeyChord {
 			modifiers: Modifiers::CTRL,
 		})
 	}
+
+	/// Creates a chord for Alt+char.
+	pub const fn alt_char(c: char) -> Self {
+		Self(Key {
+			code: KeyCode::Char(c),
+			modifiers: Modifiers::ALT,
+		})
+	}
 }
 
 impl From<&KeyEvent> for UiKeyChord {
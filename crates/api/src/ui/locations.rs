@@ -0,0 +1,282 @@
+//! Location list panel.
+//!
+//! Shows the editor's [`LocationList`] as a table of kind, position and
+//! preview, whichever source filled it: project search, references or
+//! diagnostics. The list itself lives in the workspace, so stepping
+//! through it with `location_next` and `location_prev` works with the
+//! panel closed; the panel follows along.
+//!
+//! [`LocationList`]: crate::editor::LocationList
+
+use std::path::Path;
+
+use termina::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::{Constraint, Rect};
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::text::{Line, Span};
+use xeno_tui::widgets::{Cell, Paragraph, Row, Table, TableState};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::editor::{Editor, LocationItem, LocationKind, LocationList};
+
+/// Identifier of the location list panel.
+pub const LOCATIONS_PANEL_ID: &str = "locations";
+
+/// Widest the position column gets, in cells.
+const MAX_POSITION_WIDTH: usize = 48;
+
+/// Panel listing the entries of the location list.
+#[derive(Default)]
+pub struct LocationPanel {
+	/// Selected row and scroll offset.
+	table: TableState,
+	/// Version of the list the selection belongs to.
+	version: u64,
+	/// Current entry of the list as of the last sync.
+	current: Option<usize>,
+	/// Area of the table, as of the last render.
+	table_area: Rect,
+}
+
+impl LocationPanel {
+	/// Creates the panel.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Follows replacements of the list and jumps made from outside the
+	/// panel.
+	fn sync(&mut self, list: &LocationList) {
+		if self.version != list.version() {
+			self.version = list.version();
+			self.table = TableState::default();
+			self.current = None;
+		}
+		if self.current != list.current() {
+			self.current = list.current();
+			self.table.select(self.current);
+		}
+		if self.table.selected().is_none() && !list.items().is_empty() {
+			self.table.select(Some(0));
+		}
+	}
+
+	/// Moves the selection by `delta` rows, clamped to the list.
+	fn move_selection(&mut self, delta: isize, len: usize) {
+		let selected = self.table.selected().unwrap_or(0);
+		let last = len.saturating_sub(1);
+		self.table
+			.select(Some(selected.saturating_add_signed(delta).min(last)));
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let len = editor.workspace.locations.items().len();
+		let page = usize::from(self.table_area.height.max(1)) as isize;
+		match key.code {
+			KeyCode::Char('j') | KeyCode::Down => self.move_selection(1, len),
+			KeyCode::Char('k') | KeyCode::Up => self.move_selection(-1, len),
+			KeyCode::PageDown => self.move_selection(page, len),
+			KeyCode::PageUp => self.move_selection(-page, len),
+			KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN, len),
+			KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX, len),
+			KeyCode::Enter => {
+				let Some(index) = self.table.selected().filter(|i| *i < len) else {
+					return EventResult::consumed();
+				};
+				editor
+					.workspace
+					.command_queue
+					.push("location_goto", vec![(index + 1).to_string()]);
+				return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+			}
+			KeyCode::Char('q') | KeyCode::Escape => {
+				return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+			}
+			_ => return EventResult::consumed(),
+		}
+		EventResult::consumed().with_request(UiRequest::Redraw)
+	}
+}
+
+impl Panel for LocationPanel {
+	fn id(&self) -> &str {
+		LOCATIONS_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Bottom
+	}
+
+	fn title(&self) -> &str {
+		"Locations"
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		self.sync(&editor.workspace.locations);
+		match event {
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Mouse(mouse) => {
+				let len = editor.workspace.locations.items().len();
+				match mouse.kind {
+					MouseEventKind::Down(MouseButton::Left) if mouse.row >= self.table_area.y => {
+						let row = self.table.offset() + usize::from(mouse.row - self.table_area.y);
+						if row < len {
+							self.table.select(Some(row));
+						}
+					}
+					MouseEventKind::ScrollUp => self.move_selection(-1, len),
+					MouseEventKind::ScrollDown => self.move_selection(1, len),
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		_focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		if area.is_empty() {
+			return None;
+		}
+		let list = &editor.workspace.locations;
+		self.sync(list);
+
+		let colors = &theme.colors;
+		let base = Style::default().fg(colors.ui.fg).bg(colors.ui.bg);
+		let title = if list.title().is_empty() {
+			"No locations".to_string()
+		} else {
+			let position = list
+				.current()
+				.map_or(String::new(), |i| format!("{}/", i + 1));
+			format!("{} ({position}{})", list.title(), list.items().len())
+		};
+		frame.render_widget(
+			Paragraph::new(title).style(base.add_modifier(Modifier::BOLD)),
+			Rect { height: 1, ..area },
+		);
+		self.table_area = Rect {
+			y: area.y + 1,
+			height: area.height - 1,
+			..area
+		};
+
+		let cwd = std::env::current_dir().unwrap_or_default();
+		let positions: Vec<String> = list
+			.items()
+			.iter()
+			.map(|item| position_label(item, &cwd))
+			.collect();
+		let position_width = positions
+			.iter()
+			.map(|p| p.chars().count())
+			.max()
+			.unwrap_or(0)
+			.min(MAX_POSITION_WIDTH) as u16;
+		let highlight = Style::default().bg(colors.ui.search_match_bg);
+		let rows = list.items().iter().zip(positions).map(|(item, position)| {
+			let kind_style = match item.kind {
+				LocationKind::Error => Style::default().fg(colors.status.error_fg),
+				LocationKind::Warning => Style::default().fg(colors.status.warning_fg),
+				LocationKind::Info | LocationKind::Hint => {
+					Style::default().fg(colors.status.dim_fg)
+				}
+				_ => Style::default().fg(colors.status.accent_fg),
+			};
+			Row::new([
+				Cell::from(Span::styled(item.kind.label(), kind_style)),
+				Cell::from(Span::styled(
+					position,
+					Style::default().fg(colors.ui.gutter_fg),
+				)),
+				Cell::from(preview_line(item, highlight)),
+			])
+		});
+		let table = Table::new(
+			rows,
+			[
+				Constraint::Length(7),
+				Constraint::Length(position_width),
+				Constraint::Fill(1),
+			],
+		)
+		.style(base)
+		.row_highlight_style(
+			Style::default()
+				.fg(colors.ui.selection_fg)
+				.bg(colors.ui.selection_bg),
+		);
+		frame.render_stateful_widget(table, self.table_area, &mut self.table);
+		None
+	}
+}
+
+/// Returns `path:line:column` for `item`, 1-based, with the path relative
+/// to `cwd` where possible.
+fn position_label(item: &LocationItem, cwd: &Path) -> String {
+	let path = item.path.strip_prefix(cwd).unwrap_or(&item.path);
+	format!("{}:{}:{}", path.display(), item.line + 1, item.column + 1)
+}
+
+/// Builds the preview of `item` with its located range highlighted.
+///
+/// Only the first line of the text is shown, without its indentation.
+fn preview_line(item: &LocationItem, highlight: Style) -> Line<'static> {
+	let text = item.text.lines().next().unwrap_or_default();
+	let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+	let chars: Vec<char> = text.chars().skip(indent).collect();
+	let columns = item.columns();
+	let start = columns.start.saturating_sub(indent).min(chars.len());
+	let end = columns.end.saturating_sub(indent).clamp(start, chars.len());
+	if item.kind != LocationKind::Match && item.kind != LocationKind::Reference {
+		return Line::from(chars.into_iter().collect::<String>());
+	}
+	Line::from(vec![
+		Span::raw(chars[..start].iter().collect::<String>()),
+		Span::styled(chars[start..end].iter().collect::<String>(), highlight),
+		Span::raw(chars[end..].iter().collect::<String>()),
+	])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn preview_highlights_range_without_indent() {
+		let item = LocationItem::new("a.rs", 0, 6, "    let x = 1;").with_end(0, 9);
+		let line = preview_line(&item, Style::default());
+		let parts: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+		assert_eq!(parts, ["le", "t x", " = 1;"]);
+	}
+
+	#[test]
+	fn selection_follows_list() {
+		let mut list = LocationList::default();
+		let items = (0..3)
+			.map(|i| LocationItem::new("a.rs", i, 0, ""))
+			.collect();
+		list.set("test", items);
+		let mut panel = LocationPanel::new();
+		panel.sync(&list);
+		assert_eq!(panel.table.selected(), Some(0));
+		list.select(2);
+		panel.sync(&list);
+		assert_eq!(panel.table.selected(), Some(2));
+		list.set("again", Vec::new());
+		panel.sync(&list);
+		assert_eq!(panel.table.selected(), None);
+	}
+}
@@ -0,0 +1,235 @@
+//! LSP log panel.
+//!
+//! Shows the language servers' [`ServerLog`]: what they wrote to stderr
+//! and, while the `lsp-trace` option is on, a summary of each message
+//! exchanged with them. The panel follows the newest entries until
+//! scrolled up, and can be narrowed to one server.
+//!
+//! [`ServerLog`]: xeno_lsp::ServerLog
+
+use std::sync::Arc;
+
+use termina::event::{KeyCode, KeyEvent, MouseEventKind};
+use xeno_lsp::{LogEntry, LogKind, TraceLevel};
+use xeno_registry::options::keys as opt_keys;
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::Rect;
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::text::{Line, Span};
+use xeno_tui::widgets::Paragraph;
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::editor::Editor;
+
+/// Identifier of the LSP log panel.
+pub const LSP_LOG_PANEL_ID: &str = "lsp-log";
+
+/// Panel showing the log of the language servers.
+#[derive(Default)]
+pub struct LspLogPanel {
+	/// Server whose entries are shown, or all of them.
+	server: Option<Arc<str>>,
+	/// Entries between the bottom of the view and the newest entry; zero
+	/// follows new entries.
+	scroll: usize,
+	/// Version of the log as of the last tick.
+	version: u64,
+	/// Number of entries shown as of the last tick.
+	len: usize,
+	/// Whether the panel is docked.
+	open: bool,
+	/// Rows of entries, as of the last render.
+	height: usize,
+}
+
+impl LspLogPanel {
+	/// Creates the panel.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Picks up new entries, keeping the view in place while scrolled up.
+	/// Returns whether there were any.
+	fn poll(&mut self, editor: &Editor) -> bool {
+		let log = editor.lsp.server_log();
+		if log.version() == self.version {
+			return false;
+		}
+		self.version = log.version();
+		let len = log.len(self.server.as_deref());
+		if self.scroll > 0 {
+			self.scroll = (self.scroll + len.saturating_sub(self.len)).min(self.max_scroll(len));
+		}
+		self.len = len;
+		true
+	}
+
+	/// Returns the furthest the view scrolls up with `len` entries shown.
+	fn max_scroll(&self, len: usize) -> usize {
+		len.saturating_sub(self.height.max(1))
+	}
+
+	/// Scrolls the view up by `delta` entries, or down if negative.
+	fn scroll_by(&mut self, delta: isize) {
+		self.scroll = self
+			.scroll
+			.saturating_add_signed(delta)
+			.min(self.max_scroll(self.len));
+	}
+
+	/// Shows the next server's entries, then all of them again.
+	fn cycle_server(&mut self, editor: &Editor) {
+		let log = editor.lsp.server_log();
+		let servers = log.servers();
+		let next = match &self.server {
+			None => servers.first(),
+			Some(current) => servers
+				.iter()
+				.position(|s| s == current)
+				.and_then(|i| servers.get(i + 1)),
+		};
+		self.server = next.cloned();
+		self.scroll = 0;
+		self.len = log.len(self.server.as_deref());
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let page = self.height.max(1) as isize;
+		match key.code {
+			KeyCode::Char('j') | KeyCode::Down => self.scroll_by(-1),
+			KeyCode::Char('k') | KeyCode::Up => self.scroll_by(1),
+			KeyCode::PageDown => self.scroll_by(-page),
+			KeyCode::PageUp => self.scroll_by(page),
+			KeyCode::Char('g') | KeyCode::Home => self.scroll_by(isize::MAX),
+			KeyCode::Char('G') | KeyCode::End => self.scroll = 0,
+			KeyCode::Char('s') => self.cycle_server(editor),
+			KeyCode::Char('t') => {
+				let trace: String = editor.option(opt_keys::LSP_TRACE);
+				let level = TraceLevel::parse(&trace).unwrap_or_default().next();
+				editor
+					.workspace
+					.command_queue
+					.push("set", vec!["lsp-trace".into(), level.name().into()]);
+			}
+			KeyCode::Char('q') | KeyCode::Escape => {
+				return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+			}
+			_ => return EventResult::consumed(),
+		}
+		EventResult::consumed().with_request(UiRequest::Redraw)
+	}
+}
+
+impl Panel for LspLogPanel {
+	fn id(&self) -> &str {
+		LSP_LOG_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Bottom
+	}
+
+	fn title(&self) -> &str {
+		"LSP Log"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		self.open = open;
+		self.scroll = 0;
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick if self.open && self.poll(editor) => {
+				EventResult::not_consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Mouse(mouse) => {
+				match mouse.kind {
+					MouseEventKind::ScrollUp => self.scroll_by(3),
+					MouseEventKind::ScrollDown => self.scroll_by(-3),
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		_focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		if area.is_empty() {
+			return None;
+		}
+		let colors = &theme.colors;
+		let base = Style::default().fg(colors.ui.fg).bg(colors.ui.bg);
+		let log = editor.lsp.server_log();
+
+		let header = format!(
+			"{} · trace: {}  (s: server, t: trace)",
+			self.server.as_deref().unwrap_or("all servers"),
+			log.trace_level().name()
+		);
+		frame.render_widget(
+			Paragraph::new(header).style(base.add_modifier(Modifier::BOLD)),
+			Rect { height: 1, ..area },
+		);
+		let body = Rect {
+			y: area.y + 1,
+			height: area.height - 1,
+			..area
+		};
+		self.height = usize::from(body.height);
+		self.len = log.len(self.server.as_deref());
+		self.scroll = self.scroll.min(self.max_scroll(self.len));
+
+		let entries = log.tail(self.server.as_deref(), self.scroll, self.height);
+		if entries.is_empty() {
+			let text = if log.trace_level() == TraceLevel::Off {
+				"No server output yet. Press t to trace messages."
+			} else {
+				"No server output yet."
+			};
+			frame.render_widget(
+				Paragraph::new(text).style(base.fg(colors.ui.gutter_fg)),
+				body,
+			);
+			return None;
+		}
+		let lines: Vec<Line> = entries
+			.iter()
+			.map(|entry| entry_line(entry, self.server.is_none(), theme))
+			.collect();
+		frame.render_widget(Paragraph::new(lines).style(base), body);
+		None
+	}
+}
+
+/// Builds the line of `entry`, prefixed with its server's name when the
+/// entries of several servers are shown.
+fn entry_line(entry: &LogEntry, show_server: bool, theme: &Theme) -> Line<'static> {
+	let colors = &theme.colors;
+	let text_style = match entry.kind {
+		LogKind::Stderr => Style::default().fg(colors.status.warning_fg),
+		LogKind::Sent | LogKind::Received => Style::default().fg(colors.ui.fg),
+	};
+	let mut spans = Vec::with_capacity(2);
+	if show_server {
+		spans.push(Span::styled(
+			format!("[{}] ", entry.server),
+			Style::default().fg(colors.status.accent_fg),
+		));
+	}
+	spans.push(Span::styled(entry.text.clone(), text_style));
+	Line::from(spans)
+}
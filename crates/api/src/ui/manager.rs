@@ -2,15 +2,21 @@
 
 use std::collections::HashMap;
 
-use termina::event::{KeyEvent, MouseEvent};
+use termina::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use xeno_registry::themes::Theme;
-use xeno_tui::layout::Rect;
+use xeno_tui::layout::{Position, Rect};
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::text::{Line, Span};
+use xeno_tui::widgets::{Block, Clear, Paragraph};
 
-use super::dock::{DockLayout, DockManager};
+use super::dock::{DockLayout, DockManager, DockSlot};
 use super::focus::{FocusManager, UiFocus};
-use super::keymap::{BindingScope, KeybindingRegistry};
+use super::keymap::{BindingScope, KeybindingRegistry, UiKeyChord};
 use super::panel::{Panel, PanelInitContext, UiEvent, UiRequest};
 
+/// Percent the resize bindings grow or shrink the focused panel by.
+pub const RESIZE_STEP: i32 = 5;
+
 /// Central coordinator for the editor UI subsystem.
 ///
 /// Manages panel registration, dock layout, focus tracking, and event routing.
@@ -26,17 +32,41 @@ pub struct UiManager {
 	panels: HashMap<String, Box<dyn Panel>>,
 	/// Flag indicating the UI needs to be redrawn.
 	wants_redraw: bool,
+	/// Panel that had focus most recently, for panel verbs issued from the
+	/// editor.
+	last_panel: Option<String>,
 }
 
 impl UiManager {
 	/// Creates a new UI manager with default dock configuration.
+	///
+	/// These bindings work wherever focus is:
+	/// - `alt-n` shows the next tab of a slot
+	/// - `alt-o` floats or docks the focused panel
+	/// - `alt-l` cycles focus through the editor and the panels
+	/// - `alt-=`/`alt--` grow and shrink the focused panel
+	/// - `alt-q` closes the focused panel and `alt-k` keeps only it
 	pub fn new() -> Self {
+		let mut keymap = KeybindingRegistry::new();
+		let globals = [
+			('n', UiRequest::NextTab),
+			('o', UiRequest::ToggleFloating(None)),
+			('l', UiRequest::CycleFocus),
+			('=', UiRequest::ResizeFocused(RESIZE_STEP)),
+			('-', UiRequest::ResizeFocused(-RESIZE_STEP)),
+			('q', UiRequest::CloseFocused),
+			('k', UiRequest::CloseOthers),
+		];
+		for (c, request) in globals {
+			keymap.register_global(UiKeyChord::alt_char(c), 0, vec![request]);
+		}
 		Self {
 			dock: DockManager::new(),
 			focus: FocusManager::new(),
-			keymap: KeybindingRegistry::new(),
+			keymap,
 			panels: HashMap::new(),
 			wants_redraw: false,
+			last_panel: None,
 		}
 	}
 
@@ -76,6 +106,21 @@ impl UiManager {
 		self.focus.focused().panel_id()
 	}
 
+	/// Returns the panel that window verbs act on: the focused panel, or
+	/// the one focused last when the editor has focus and it is still open.
+	pub fn target_panel_id(&self) -> Option<&str> {
+		self.focused_panel_id().or_else(|| {
+			self.last_panel
+				.as_deref()
+				.filter(|id| self.dock.is_open(id))
+		})
+	}
+
+	/// Returns the title of the focused panel, if any.
+	pub fn focused_panel_title(&self) -> Option<&str> {
+		self.panels.get(self.focused_panel_id()?).map(|p| p.title())
+	}
+
 	/// Returns whether the panel with the given ID is currently focused.
 	pub fn is_panel_focused(&self, id: &str) -> bool {
 		self.focused_panel_id() == Some(id)
@@ -138,30 +183,54 @@ impl UiManager {
 	}
 
 	/// Routes a mouse event to the appropriate panel based on hit testing.
+	///
+	/// The floating panel is on top of everything else. Clicking a tab shows
+	/// and focuses its panel.
 	pub fn handle_mouse(
 		&mut self,
 		editor: &mut crate::editor::Editor,
 		mouse: MouseEvent,
 		layout: &DockLayout,
 	) -> bool {
-		let row = mouse.row;
-		let col = mouse.column;
+		let pos = Position::new(mouse.column, mouse.row);
+		let floating = layout.floating.filter(|area| area.contains(pos));
 
-		let mut hit_panel: Option<String> = None;
-		for (id, area) in &layout.panel_areas {
-			if row >= area.y
-				&& row < area.y + area.height
-				&& col >= area.x
-				&& col < area.x + area.width
+		let tab_bar = layout.tab_bars.iter().find(|(slot, area)| {
+			area.contains(pos) && (floating.is_none() || *slot == DockSlot::Overlay)
+		});
+		if let Some(&(slot, area)) = tab_bar {
+			if matches!(mouse.kind, MouseEventKind::Down(MouseButton::Left))
+				&& let Some((id, _)) = self
+					.tab_areas(slot, area)
+					.into_iter()
+					.find(|(_, tab)| tab.contains(pos))
 			{
-				hit_panel = Some(id.clone());
-				break;
+				self.dock.open_panel(slot, id.clone());
+				self.apply_requests(vec![UiRequest::Focus(UiFocus::panel(id))]);
+				self.wants_redraw = true;
 			}
+			return true;
 		}
 
+		let hit_panel = match floating {
+			Some(_) => self
+				.dock
+				.active_in_slot(DockSlot::Overlay)
+				.map(String::from),
+			None => layout
+				.panel_areas
+				.iter()
+				.find(|(_, area)| area.contains(pos))
+				.map(|(id, _)| id.clone()),
+		};
+
 		if let Some(id) = hit_panel {
 			// Focus follows mouse for panels.
 			self.apply_requests(vec![UiRequest::Focus(UiFocus::panel(id.clone()))]);
+			// Clicks on the floating panel's border only focus it.
+			if !layout.panel_areas.get(&id).is_some_and(|a| a.contains(pos)) {
+				return true;
+			}
 			let focused = self.is_panel_focused(&id);
 			if let Some(panel) = self.panels.get_mut(&id) {
 				let res = panel.handle_event(UiEvent::Mouse(mouse), editor, focused);
@@ -206,6 +275,24 @@ impl UiManager {
 		self.apply_requests(requests);
 	}
 
+	/// Returns whether a panel with the given ID is registered.
+	pub fn has_panel(&self, id: &str) -> bool {
+		self.panels.contains_key(id)
+	}
+
+	/// Opens a panel in `slot` instead of its default slot.
+	///
+	/// Returns `false` if no panel with the given ID is registered.
+	pub fn open_in_slot(&mut self, id: &str, slot: DockSlot) -> bool {
+		let Some(panel) = self.panels.get_mut(id) else {
+			return false;
+		};
+		self.dock.open_panel(slot, id.to_string());
+		panel.on_open_changed(true);
+		self.wants_redraw = true;
+		true
+	}
+
 	/// Sets whether a panel is open, updating dock state and focus as needed.
 	pub fn set_open(&mut self, id: &str, open: bool) {
 		let Some(panel) = self.panels.get_mut(id) else {
@@ -233,6 +320,137 @@ impl UiManager {
 		}
 	}
 
+	/// Shows the next tab of the focused panel's slot, moving focus along,
+	/// or of the first slot stacking several panels when the editor has
+	/// focus.
+	fn next_tab(&mut self) {
+		let focused = self.focused_panel_id().map(String::from);
+		let slot = match &focused {
+			Some(id) => self.dock.slot_of(id),
+			None => DockSlot::ALL.into_iter().find(|slot| {
+				self.dock
+					.slots
+					.get(slot)
+					.is_some_and(|state| state.open.len() > 1)
+			}),
+		};
+		let Some(next) = slot.and_then(|slot| self.dock.cycle_tab(slot, 1).map(String::from))
+		else {
+			return;
+		};
+		if focused.is_some() {
+			self.apply_requests(vec![UiRequest::Focus(UiFocus::panel(next))]);
+		}
+		self.wants_redraw = true;
+	}
+
+	/// Floats a docked panel or docks a floating one back where it was,
+	/// opening it first if needed. A panel that starts floating is focused.
+	fn toggle_floating(&mut self, id: Option<String>) {
+		let Some(id) = id.or_else(|| self.focused_panel_id().map(String::from)) else {
+			return;
+		};
+		let Some(panel) = self.panels.get_mut(&id) else {
+			return;
+		};
+		let fallback = match panel.default_slot() {
+			DockSlot::Overlay => DockSlot::Bottom,
+			slot => slot,
+		};
+		if !self.dock.is_open(&id) {
+			panel.on_open_changed(true);
+		}
+		if self.dock.toggle_floating(&id, fallback) {
+			self.apply_requests(vec![UiRequest::Focus(UiFocus::panel(id))]);
+		}
+		self.wants_redraw = true;
+	}
+
+	/// Moves focus from the editor to the panel showing in each slot in
+	/// [`DockSlot::FOCUS_ORDER`], and from the last one back to the editor.
+	fn cycle_focus(&mut self) {
+		let stops: Vec<UiFocus> = std::iter::once(UiFocus::editor())
+			.chain(self.dock.focus_cycle().into_iter().map(UiFocus::panel))
+			.collect();
+		let current = stops
+			.iter()
+			.position(|stop| stop == self.focus.focused())
+			.unwrap_or(0);
+		let next = stops[(current + 1) % stops.len()].clone();
+		self.apply_requests(vec![UiRequest::Focus(next)]);
+	}
+
+	/// Closes every open panel except the targeted one.
+	fn close_others(&mut self) {
+		let focused = self.target_panel_id().map(String::from);
+		let others: Vec<String> = self
+			.dock
+			.open_panels()
+			.into_iter()
+			.filter(|id| focused.as_deref() != Some(*id))
+			.map(String::from)
+			.collect();
+		for id in others {
+			self.set_open(&id, false);
+		}
+	}
+
+	/// Returns the tab of each panel stacked in `slot`, laid out from the
+	/// left of `bar`.
+	fn tab_areas(&self, slot: DockSlot, bar: Rect) -> Vec<(String, Rect)> {
+		let Some(state) = self.dock.slots.get(&slot) else {
+			return Vec::new();
+		};
+		let mut x = bar.x;
+		let right = bar.x + bar.width;
+		let mut tabs = Vec::new();
+		for id in &state.open {
+			let width = (self.tab_label(id).chars().count() as u16).min(right - x);
+			if width == 0 {
+				break;
+			}
+			tabs.push((id.clone(), Rect { x, width, ..bar }));
+			x += width;
+		}
+		tabs
+	}
+
+	/// Returns the text of the tab of panel `id`.
+	fn tab_label(&self, id: &str) -> String {
+		format!(" {} ", self.panels.get(id).map_or(id, |p| p.title()))
+	}
+
+	/// Draws the tab bar of `slot`, with the active tab highlighted.
+	fn render_tab_bar(
+		&self,
+		frame: &mut xeno_tui::Frame,
+		slot: DockSlot,
+		bar: Rect,
+		theme: &Theme,
+	) {
+		let colors = &theme.colors.ui;
+		let active = self.dock.active_in_slot(slot);
+		let spans: Vec<Span> = self
+			.tab_areas(slot, bar)
+			.into_iter()
+			.map(|(id, _)| {
+				let style = if active == Some(id.as_str()) {
+					Style::default()
+						.fg(colors.selection_fg)
+						.bg(colors.selection_bg)
+						.add_modifier(Modifier::BOLD)
+				} else {
+					Style::default().fg(colors.gutter_fg)
+				};
+				Span::styled(self.tab_label(&id), style)
+			})
+			.collect();
+		frame.render_widget(
+			Paragraph::new(Line::from(spans)).style(Style::default().bg(colors.bg)),
+			bar,
+		);
+	}
+
 	/// Processes a batch of UI requests (focus changes, panel toggles, etc.).
 	pub fn apply_requests(&mut self, requests: Vec<UiRequest>) {
 		for req in requests {
@@ -241,6 +459,14 @@ impl UiManager {
 					self.wants_redraw = true;
 				}
 				UiRequest::Focus(target) => {
+					// A panel stacked behind another tab comes to the front.
+					if let Some(id) = target.panel_id()
+						&& let Some(slot) = self.dock.slot_of(id)
+						&& !self.dock.is_showing(id)
+					{
+						self.dock.open_panel(slot, id.to_string());
+						self.wants_redraw = true;
+					}
 					let old = self.focus.focused().clone();
 					if old != target {
 						if let Some(old_id) = old.panel_id()
@@ -253,17 +479,46 @@ impl UiManager {
 						{
 							panel.on_focus_changed(true);
 						}
+						if let Some(old_id) = old.panel_id() {
+							self.last_panel = Some(old_id.to_string());
+						}
 						self.focus.set_focused(target);
 						self.wants_redraw = true;
 					}
 				}
 				UiRequest::ClosePanel(id) => self.set_open(&id, false),
 				UiRequest::TogglePanel(id) => self.toggle_panel(&id),
+				UiRequest::OpenPanel(id) => match self.dock.slot_of(&id) {
+					Some(slot) => {
+						self.dock.open_panel(slot, id);
+						self.wants_redraw = true;
+					}
+					None => self.set_open(&id, true),
+				},
+				UiRequest::NextTab => self.next_tab(),
+				UiRequest::ToggleFloating(id) => self.toggle_floating(id),
+				UiRequest::CycleFocus => self.cycle_focus(),
+				UiRequest::CloseFocused => {
+					if let Some(id) = self.target_panel_id().map(String::from) {
+						self.set_open(&id, false);
+					}
+				}
+				UiRequest::CloseOthers => self.close_others(),
+				UiRequest::ResizeFocused(delta) => {
+					if let Some(id) = self.target_panel_id().map(String::from)
+						&& self.dock.resize_panel(&id, delta).is_some()
+					{
+						self.wants_redraw = true;
+					}
+				}
 			}
 		}
 	}
 
 	/// Renders all open panels and returns the cursor position if any panel requests one.
+	///
+	/// Docked panels and tab bars come first, then the floating panel in a
+	/// bordered box over them.
 	pub fn render_panels(
 		&mut self,
 		editor: &mut crate::editor::Editor,
@@ -271,16 +526,63 @@ impl UiManager {
 		layout: &DockLayout,
 		theme: &Theme,
 	) -> Option<xeno_tui::layout::Position> {
-		let mut cursor: Option<xeno_tui::layout::Position> = None;
+		let floating_id = self
+			.dock
+			.active_in_slot(DockSlot::Overlay)
+			.map(String::from);
+		let mut cursor = None;
 		for (id, area) in &layout.panel_areas {
-			let focused = self.is_panel_focused(id);
-			if let Some(panel) = self.panels.get_mut(id) {
-				let cursor_req = panel.render(frame, *area, editor, focused, theme);
-				if focused && let Some(req) = cursor_req {
-					cursor = Some(req.pos);
-				}
+			if floating_id.as_ref() != Some(id) {
+				cursor = self
+					.render_panel(id, *area, editor, frame, theme)
+					.or(cursor);
+			}
+		}
+		for &(slot, bar) in &layout.tab_bars {
+			if slot != DockSlot::Overlay {
+				self.render_tab_bar(frame, slot, bar, theme);
+			}
+		}
+
+		if let (Some(id), Some(outer)) = (floating_id, layout.floating) {
+			let popup = &theme.colors.popup;
+			let title = self.panels.get(&id).map_or(id.as_str(), |p| p.title());
+			frame.render_widget(Clear, outer);
+			frame.render_widget(
+				Block::bordered()
+					.title(format!(" {title} "))
+					.style(Style::default().fg(popup.fg).bg(popup.bg))
+					.border_style(Style::default().fg(popup.border)),
+				outer,
+			);
+			if let Some(&(_, bar)) = layout
+				.tab_bars
+				.iter()
+				.find(|(s, _)| *s == DockSlot::Overlay)
+			{
+				self.render_tab_bar(frame, DockSlot::Overlay, bar, theme);
+			}
+			if let Some(area) = layout.panel_areas.get(&id) {
+				cursor = self
+					.render_panel(&id, *area, editor, frame, theme)
+					.or(cursor);
 			}
 		}
 		cursor
 	}
+
+	/// Renders panel `id` into `area`, returning its cursor if it is focused.
+	fn render_panel(
+		&mut self,
+		id: &str,
+		area: Rect,
+		editor: &mut crate::editor::Editor,
+		frame: &mut xeno_tui::Frame,
+		theme: &Theme,
+	) -> Option<Position> {
+		let focused = self.is_panel_focused(id);
+		let panel = self.panels.get_mut(id)?;
+		let cursor = panel.render(frame, area, editor, focused, theme);
+		cursor.filter(|_| focused).map(|req| req.pos)
+	}
 }
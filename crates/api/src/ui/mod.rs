@@ -1,9 +1,25 @@
 pub mod dock;
+/// File explorer panel.
+pub mod files;
 mod focus;
 pub mod keymap;
+/// Location list panel.
+pub mod locations;
+/// LSP log panel.
+#[cfg(feature = "lsp")]
+pub mod lsp_log;
 mod manager;
+/// Outline panel.
+#[cfg(feature = "lsp")]
+pub mod outline;
 /// Panel traits and request types.
 pub mod panel;
+/// Project search panel.
+pub mod search;
+/// Terminal panel.
+pub mod terminal;
+/// Undo tree panel.
+pub mod undotree;
 
 pub use focus::UiFocus;
 pub use keymap::UiKeyChord;
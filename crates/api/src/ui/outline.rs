@@ -0,0 +1,596 @@
+//! Outline panel.
+//!
+//! Shows the symbols the language server reports for the focused buffer
+//! through `textDocument/documentSymbol`, nested when the server sends
+//! `DocumentSymbol`s and as a flat list when it sends
+//! `SymbolInformation`. The symbol containing the cursor is selected and
+//! revealed as the cursor moves, and Enter jumps to a symbol's name.
+//!
+//! Symbols are requested again when another buffer gets focus, and after
+//! edits once the `buffer:change` hook has been quiet for
+//! [`OUTLINE_DEBOUNCE`].
+
+use std::ops::Range;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use ropey::Rope;
+use termina::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use tracing::debug;
+use xeno_lsp::lsp_types::{DocumentSymbol, DocumentSymbolResponse, SymbolKind};
+use xeno_lsp::{OffsetEncoding, lsp_range_to_char_range};
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::Rect;
+use xeno_tui::style::Style;
+use xeno_tui::widgets::Paragraph;
+use xeno_tui::widgets::tree_view::{TreeItem, TreeKey, TreeView, TreeViewState};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::buffer::BufferId;
+use crate::editor::Editor;
+use crate::editor::lsp_requests::{DocumentRequests, LspRequestChanges};
+
+/// Identifier of the outline panel.
+pub const OUTLINE_PANEL_ID: &str = "outline";
+
+/// How long edits must pause before symbols are requested again.
+pub const OUTLINE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Answer to a document symbol request, with the offset encoding of its
+/// positions.
+type Answer = (DocumentSymbolResponse, OffsetEncoding);
+
+/// A symbol of the outline, with positions converted to the buffer's
+/// characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutlineSymbol {
+	/// Name of the symbol.
+	name: String,
+	/// Extra information, such as a signature.
+	detail: Option<String>,
+	/// What the symbol is.
+	kind: SymbolKind,
+	/// Characters the symbol spans, body included.
+	range: Range<usize>,
+	/// Line and column of the symbol's name, 0-based.
+	target: (usize, usize),
+	/// Index of the enclosing symbol.
+	parent: Option<usize>,
+	/// Indices of the symbols nested in this one.
+	children: Vec<usize>,
+}
+
+/// What the panel has to show for the focused buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+	/// No request was made yet.
+	Idle,
+	/// The first response for the buffer is on its way.
+	Loading,
+	/// No language server handles the buffer.
+	NoServer,
+	/// The language server does not list document symbols.
+	Unsupported,
+	/// The request failed.
+	Failed(String),
+	/// Symbols arrived; the list may be empty.
+	Ready,
+}
+
+/// Panel listing the symbols of the focused buffer.
+pub struct OutlinePanel {
+	/// Rows of the tree, expansion and selection, keyed by symbol index.
+	tree: TreeViewState<usize>,
+	/// Symbols of the last response, parents before their children.
+	symbols: Vec<OutlineSymbol>,
+	/// What to show.
+	status: Status,
+	/// Buffer the symbols belong to.
+	buffer: Option<BufferId>,
+	/// Symbol requests for the buffer's document.
+	requests: DocumentRequests<Answer>,
+	/// Cursor position the selection last followed.
+	cursor: Option<usize>,
+	/// Whether the panel is docked.
+	open: bool,
+	/// Area the tree was last drawn in, for mouse hit-testing.
+	area: Rect,
+}
+
+impl Default for OutlinePanel {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl OutlinePanel {
+	/// Creates the panel.
+	pub fn new() -> Self {
+		Self {
+			tree: TreeViewState::new(),
+			symbols: Vec::new(),
+			status: Status::Idle,
+			buffer: None,
+			requests: DocumentRequests::default(),
+			cursor: None,
+			open: false,
+			area: Rect::default(),
+		}
+	}
+
+	/// Requests symbols when the focused buffer changed, or when edits
+	/// stopped for long enough since the last request.
+	fn poll(&mut self, editor: &mut Editor) -> bool {
+		let mut changed = self.receive(editor);
+		let view = editor.focused_view();
+		if self.buffer != Some(view) {
+			self.buffer = Some(view);
+			self.requests.clear();
+			self.set_symbols(Vec::new());
+			self.status = Status::Loading;
+			changed = true;
+		}
+
+		let buffer = editor.buffer();
+		let document = buffer.document_id();
+		let version = buffer.version();
+		let last_change = editor
+			.extensions
+			.get::<LspRequestChanges>()
+			.and_then(LspRequestChanges::last);
+		if self.requests.is_pending(document)
+			|| !self
+				.requests
+				.is_due(document, version, last_change, OUTLINE_DEBOUNCE)
+		{
+			return changed;
+		}
+		let request = editor.lsp.document_symbols(buffer);
+		match self.requests.send(document, view, version, (), request) {
+			Ok(true) => {}
+			Ok(false) => {
+				self.set_symbols(Vec::new());
+				self.status = Status::NoServer;
+			}
+			Err(e) => self.status = Status::Failed(e.to_string()),
+		}
+		true
+	}
+
+	/// Takes in the response to the request in flight, if it has arrived.
+	/// Returns whether the panel changed.
+	fn receive(&mut self, editor: &Editor) -> bool {
+		let mut changed = false;
+		for arrived in self.requests.receive() {
+			let Some(buffer) = editor.buffers.get_buffer(arrived.buffer) else {
+				continue;
+			};
+			let doc = buffer.doc();
+			// Positions in a response to an older version may be off; the
+			// edit since then asks again.
+			if doc.version != arrived.version {
+				continue;
+			}
+			let text = &doc.content;
+			match arrived.response {
+				Ok(Some((response, encoding))) => {
+					self.set_symbols(symbols(response, text, encoding));
+					self.status = Status::Ready;
+				}
+				Ok(None) => {
+					self.set_symbols(Vec::new());
+					self.status = Status::Unsupported;
+				}
+				Err(e) => {
+					debug!(error = %e, "document symbol request failed");
+					self.status = Status::Failed(e.to_string());
+				}
+			}
+			changed = true;
+		}
+		changed
+	}
+
+	/// Replaces the symbols and lays the tree out again.
+	///
+	/// Expansion is kept for symbols at the same index, and the selection
+	/// follows the cursor again on the next render.
+	fn set_symbols(&mut self, symbols: Vec<OutlineSymbol>) {
+		let item = |(id, symbol): (usize, &OutlineSymbol)| {
+			let label = match &symbol.detail {
+				Some(detail) => format!("{} {}  {detail}", kind_icon(symbol.kind), symbol.name),
+				None => format!("{} {}", kind_icon(symbol.kind), symbol.name),
+			};
+			if symbol.children.is_empty() {
+				TreeItem::leaf(id, label)
+			} else {
+				TreeItem::branch(id, label)
+			}
+		};
+		let at = |ids: &[usize]| {
+			ids.iter()
+				.map(|&id| item((id, &symbols[id])))
+				.collect::<Vec<_>>()
+		};
+		let roots: Vec<usize> = (0..symbols.len())
+			.filter(|&id| symbols[id].parent.is_none())
+			.collect();
+		self.tree.set_roots(at(&roots));
+		for (id, symbol) in symbols.iter().enumerate() {
+			if !symbol.children.is_empty() {
+				self.tree.set_children(&id, at(&symbol.children));
+			}
+		}
+		self.symbols = symbols;
+		self.cursor = None;
+	}
+
+	/// Selects the innermost symbol containing `cursor`, expanding the
+	/// symbols around it.
+	fn reveal(&mut self, cursor: usize) {
+		let Some(id) = symbol_at(&self.symbols, cursor) else {
+			return;
+		};
+		let mut ancestors = Vec::new();
+		let mut parent = self.symbols[id].parent;
+		while let Some(p) = parent {
+			ancestors.push(p);
+			parent = self.symbols[p].parent;
+		}
+		for ancestor in ancestors.into_iter().rev() {
+			self.tree.expand(&ancestor);
+		}
+		self.tree.select_id(&id);
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let tree_key = match key.code {
+			KeyCode::Char('j') | KeyCode::Down => Some(TreeKey::Down),
+			KeyCode::Char('k') | KeyCode::Up => Some(TreeKey::Up),
+			KeyCode::Char('g') | KeyCode::Home => Some(TreeKey::Home),
+			KeyCode::Char('G') | KeyCode::End => Some(TreeKey::End),
+			KeyCode::PageUp => Some(TreeKey::PageUp),
+			KeyCode::PageDown => Some(TreeKey::PageDown),
+			KeyCode::Char('l') | KeyCode::Right => Some(TreeKey::Right),
+			KeyCode::Char('h') | KeyCode::Left => Some(TreeKey::Left),
+			KeyCode::Char(' ') => Some(TreeKey::Toggle),
+			_ => None,
+		};
+		if let Some(tree_key) = tree_key {
+			self.tree.handle_key(tree_key);
+			return EventResult::consumed().with_request(UiRequest::Redraw);
+		}
+		match key.code {
+			KeyCode::Enter => {
+				let Some(path) = self.target_path(editor) else {
+					return EventResult::consumed();
+				};
+				let Some(symbol) = self.tree.selected_id().and_then(|&id| self.symbols.get(id))
+				else {
+					return EventResult::consumed();
+				};
+				let (line, column) = symbol.target;
+				editor.workspace.command_queue.push(
+					"open_location",
+					vec![
+						path.to_string_lossy().into_owned(),
+						(line + 1).to_string(),
+						(column + 1).to_string(),
+					],
+				);
+				EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+			}
+			KeyCode::Char('q') | KeyCode::Escape => {
+				EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+			}
+			_ => EventResult::consumed(),
+		}
+	}
+
+	/// Returns the path of the buffer the symbols belong to.
+	fn target_path(&self, editor: &Editor) -> Option<PathBuf> {
+		editor.buffers.get_buffer(self.buffer?)?.path()
+	}
+}
+
+impl Panel for OutlinePanel {
+	fn id(&self) -> &str {
+		OUTLINE_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Right
+	}
+
+	fn title(&self) -> &str {
+		"Outline"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		self.open = open;
+		if !open {
+			self.requests.clear();
+			self.buffer = None;
+			self.status = Status::Idle;
+		}
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick if self.open && self.poll(editor) => {
+				EventResult::not_consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Mouse(mouse) => {
+				match mouse.kind {
+					MouseEventKind::Down(MouseButton::Left) => {
+						let y = mouse.row.saturating_sub(self.area.y);
+						if mouse.row >= self.area.y
+							&& let Some(row) = self.tree.row_at(y)
+						{
+							self.tree.select_row(row);
+						}
+					}
+					MouseEventKind::ScrollUp => {
+						self.tree.handle_key(TreeKey::Up);
+					}
+					MouseEventKind::ScrollDown => {
+						self.tree.handle_key(TreeKey::Down);
+					}
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		self.area = area;
+		let colors = &theme.colors.ui;
+		let base = Style::default().fg(colors.fg).bg(colors.bg);
+
+		let placeholder = match &self.status {
+			Status::Ready if !self.symbols.is_empty() => None,
+			Status::Ready => Some("No symbols in this buffer.".to_string()),
+			Status::Idle | Status::Loading => Some("Loading symbols…".to_string()),
+			Status::NoServer => Some(
+				"No language server for this buffer. The outline lists symbols reported by \
+				 the buffer's language server."
+					.to_string(),
+			),
+			Status::Unsupported => {
+				Some("The language server does not provide document symbols.".to_string())
+			}
+			Status::Failed(error) => Some(format!("Could not get symbols: {error}")),
+		};
+		if let Some(text) = placeholder {
+			frame.render_widget(
+				Paragraph::new(text)
+					.wrap(xeno_tui::widgets::Wrap { trim: true })
+					.style(base.fg(colors.gutter_fg)),
+				area,
+			);
+			return None;
+		}
+
+		let cursor = editor.buffer().cursor;
+		if !focused && self.buffer == Some(editor.focused_view()) && self.cursor != Some(cursor) {
+			self.cursor = Some(cursor);
+			self.reveal(cursor);
+		}
+		let tree = TreeView::new()
+			.style(base)
+			.highlight_style(
+				Style::default()
+					.fg(colors.selection_fg)
+					.bg(colors.selection_bg),
+			)
+			.guide_style(Style::default().fg(colors.gutter_fg));
+		frame.render_stateful_widget(tree, area, &mut self.tree);
+		None
+	}
+}
+
+/// Converts a document symbol response, flattening nested symbols so that
+/// parents come before their children.
+///
+/// `SymbolInformation`s have no nesting and come out as a flat list in
+/// document order.
+fn symbols(
+	response: DocumentSymbolResponse,
+	text: &Rope,
+	encoding: OffsetEncoding,
+) -> Vec<OutlineSymbol> {
+	let convert = |range| lsp_range_to_char_range(text, range, encoding);
+	let target = |pos: usize| {
+		let pos = pos.min(text.len_chars());
+		let line = text.char_to_line(pos);
+		(line, pos - text.line_to_char(line))
+	};
+	let mut out = Vec::new();
+	match response {
+		DocumentSymbolResponse::Flat(mut infos) => {
+			infos.sort_by_key(|info| {
+				(
+					info.location.range.start.line,
+					info.location.range.start.character,
+				)
+			});
+			for info in infos {
+				let (start, end) = convert(info.location.range);
+				out.push(OutlineSymbol {
+					name: info.name,
+					detail: info.container_name.filter(|c| !c.is_empty()),
+					kind: info.kind,
+					range: start..end,
+					target: target(start),
+					parent: None,
+					children: Vec::new(),
+				});
+			}
+		}
+		DocumentSymbolResponse::Nested(nested) => {
+			let mut pending: Vec<(Option<usize>, DocumentSymbol)> =
+				nested.into_iter().rev().map(|s| (None, s)).collect();
+			while let Some((parent, symbol)) = pending.pop() {
+				let (start, end) = convert(symbol.range);
+				let (name_start, _) = convert(symbol.selection_range);
+				let id = out.len();
+				out.push(OutlineSymbol {
+					name: symbol.name,
+					detail: symbol.detail.filter(|d| !d.is_empty()),
+					kind: symbol.kind,
+					range: start..end,
+					target: target(name_start),
+					parent,
+					children: Vec::new(),
+				});
+				if let Some(parent) = parent {
+					out[parent].children.push(id);
+				}
+				let children = symbol.children.unwrap_or_default();
+				pending.extend(children.into_iter().rev().map(|s| (Some(id), s)));
+			}
+		}
+	}
+	out
+}
+
+/// Returns the innermost symbol whose range contains `cursor`.
+fn symbol_at(symbols: &[OutlineSymbol], cursor: usize) -> Option<usize> {
+	let contains = |id: &usize| symbols[*id].range.contains(&cursor);
+	let mut found = (0..symbols.len())
+		.filter(|&id| symbols[id].parent.is_none())
+		.find(&contains)?;
+	while let Some(child) = symbols[found].children.iter().copied().find(&contains) {
+		found = child;
+	}
+	Some(found)
+}
+
+/// Returns the one-character icon shown before a symbol of `kind`.
+pub(crate) fn kind_icon(kind: SymbolKind) -> &'static str {
+	match kind {
+		SymbolKind::FILE => "▤",
+		SymbolKind::MODULE | SymbolKind::NAMESPACE | SymbolKind::PACKAGE => "◫",
+		SymbolKind::CLASS | SymbolKind::STRUCT | SymbolKind::OBJECT => "◆",
+		SymbolKind::METHOD | SymbolKind::FUNCTION | SymbolKind::CONSTRUCTOR => "ƒ",
+		SymbolKind::PROPERTY | SymbolKind::FIELD => "◦",
+		SymbolKind::ENUM => "∈",
+		SymbolKind::ENUM_MEMBER => "·",
+		SymbolKind::INTERFACE => "◇",
+		SymbolKind::VARIABLE => "ν",
+		SymbolKind::CONSTANT => "π",
+		SymbolKind::TYPE_PARAMETER => "τ",
+		SymbolKind::OPERATOR => "±",
+		SymbolKind::EVENT => "↯",
+		_ => "•",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use xeno_lsp::lsp_types::{Location, Position, SymbolInformation, Uri};
+
+	use super::*;
+
+	fn range(start: (u32, u32), end: (u32, u32)) -> xeno_lsp::lsp_types::Range {
+		xeno_lsp::lsp_types::Range::new(
+			Position::new(start.0, start.1),
+			Position::new(end.0, end.1),
+		)
+	}
+
+	#[allow(
+		deprecated,
+		reason = "`DocumentSymbol` has no constructor that leaves out `deprecated`"
+	)]
+	fn nested(
+		name: &str,
+		r: xeno_lsp::lsp_types::Range,
+		children: Vec<DocumentSymbol>,
+	) -> DocumentSymbol {
+		DocumentSymbol {
+			name: name.into(),
+			detail: None,
+			kind: SymbolKind::FUNCTION,
+			tags: None,
+			deprecated: None,
+			range: r,
+			selection_range: xeno_lsp::lsp_types::Range::new(r.start, r.start),
+			children: (!children.is_empty()).then_some(children),
+		}
+	}
+
+	#[test]
+	fn nested_symbols_keep_hierarchy_and_follow_cursor() {
+		let text = Rope::from("mod a {\n  fn b() {}\n  fn c() {}\n}\nfn d() {}\n");
+		let response = DocumentSymbolResponse::Nested(vec![
+			nested(
+				"a",
+				range((0, 0), (3, 1)),
+				vec![
+					nested("b", range((1, 2), (1, 11)), Vec::new()),
+					nested("c", range((2, 2), (2, 11)), Vec::new()),
+				],
+			),
+			nested("d", range((4, 0), (4, 9)), Vec::new()),
+		]);
+		let symbols = symbols(response, &text, OffsetEncoding::Utf8);
+		let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+		assert_eq!(names, ["a", "b", "c", "d"]);
+		assert_eq!(symbols[0].children, [1, 2]);
+		assert_eq!(symbols[2].target, (2, 2));
+
+		let inside_c = text.line_to_char(2) + 5;
+		assert_eq!(symbol_at(&symbols, inside_c), Some(2));
+		assert_eq!(symbol_at(&symbols, text.line_to_char(3)), Some(0));
+		assert_eq!(symbol_at(&symbols, text.len_chars()), None);
+
+		let mut panel = OutlinePanel::new();
+		panel.set_symbols(symbols);
+		panel.reveal(inside_c);
+		assert_eq!(panel.tree.selected_id(), Some(&2));
+		assert!(panel.tree.is_expanded(&0));
+	}
+
+	#[test]
+	#[allow(
+		deprecated,
+		reason = "`SymbolInformation` has no constructor that leaves out `deprecated`"
+	)]
+	fn symbol_information_is_flat_and_ordered() {
+		let text = Rope::from("fn a() {}\nfn b() {}\n");
+		let info = |name: &str, line| SymbolInformation {
+			name: name.into(),
+			kind: SymbolKind::FUNCTION,
+			tags: None,
+			deprecated: None,
+			location: Location::new(
+				"file:///x.rs".parse::<Uri>().unwrap(),
+				range((line, 0), (line, 9)),
+			),
+			container_name: None,
+		};
+		let symbols = symbols(
+			DocumentSymbolResponse::Flat(vec![info("b", 1), info("a", 0)]),
+			&text,
+			OffsetEncoding::Utf8,
+		);
+		let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+		assert_eq!(names, ["a", "b"]);
+		assert!(symbols.iter().all(|s| s.parent.is_none()));
+	}
+}
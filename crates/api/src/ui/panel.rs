@@ -33,6 +33,29 @@ pub enum UiRequest {
 	ClosePanel(String),
 	/// Request toggling a panel's open state by ID.
 	TogglePanel(String),
+	/// Request opening a panel by ID without moving focus to it.
+	OpenPanel(String),
+	/// Request showing the next tab of the focused panel's slot, or of the
+	/// first slot stacking several panels when the editor has focus.
+	NextTab,
+	/// Request floating a docked panel or docking a floating one by ID;
+	/// `None` targets the focused panel.
+	ToggleFloating(Option<String>),
+	/// Request moving focus to the next stop of the editor, then the panel
+	/// showing in each slot in [`DockSlot::FOCUS_ORDER`].
+	CycleFocus,
+	/// Request closing the focused panel, or the one focused last when the
+	/// editor has focus.
+	CloseFocused,
+	/// Request closing every panel except the one [`CloseFocused`] targets.
+	///
+	/// [`CloseFocused`]: Self::CloseFocused
+	CloseOthers,
+	/// Request growing the panel [`CloseFocused`] targets by this many
+	/// percent, or shrinking it when negative.
+	///
+	/// [`CloseFocused`]: Self::CloseFocused
+	ResizeFocused(i32),
 }
 
 /// Result returned from panel event handlers.
@@ -89,6 +112,10 @@ pub trait Panel {
 	fn id(&self) -> &str;
 	/// Returns the default dock slot for this panel.
 	fn default_slot(&self) -> DockSlot;
+	/// Returns the name shown on the panel's tab and floating border.
+	fn title(&self) -> &str {
+		self.id()
+	}
 
 	/// Called when the panel is registered with the UI manager.
 	fn on_register(&mut self, _ctx: PanelInitContext<'_>) {}
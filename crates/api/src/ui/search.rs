@@ -0,0 +1,494 @@
+//! Project search panel.
+//!
+//! A query line on top and the matches grouped by file below. Every edit
+//! of the query cancels the running search and starts a new one; results
+//! are tagged with the generation of the query that produced them, so
+//! stragglers from an older query are dropped.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+use termina::event::{KeyCode, KeyEvent, Modifiers, MouseButton, MouseEventKind};
+use unicode_width::UnicodeWidthStr;
+use xeno_registry::options::keys as opts;
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::{Position, Rect};
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::text::{Line, Span};
+use xeno_tui::widgets::{Paragraph, Spinner};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::locations::LOCATIONS_PANEL_ID;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::editor::{Editor, LocationItem};
+use crate::project_search::{self, FileMatches, SearchBackend, SearchEvent, SearchRequest};
+
+/// Identifier of the project search panel.
+pub const SEARCH_PANEL_ID: &str = "search";
+
+/// Milliseconds each spinner frame stays on screen.
+const SPINNER_INTERVAL_MS: u128 = 80;
+
+/// Text drawn before the query.
+const QUERY_PREFIX: &str = "Search: ";
+
+/// A row of the result list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultRow {
+	/// Header of the file at this index.
+	File(usize),
+	/// A matching line: file index and line index within the file.
+	Match(usize, usize),
+}
+
+/// A search that has not finished yet.
+struct RunningSearch {
+	/// Set to stop the search thread.
+	cancel: Arc<AtomicBool>,
+	/// When the search started, for the spinner.
+	started: Instant,
+}
+
+/// Panel searching the files below the working directory.
+pub struct SearchPanel {
+	/// Directory searched.
+	root: PathBuf,
+	/// Regex typed so far.
+	query: String,
+	/// Files with matches, in the order they arrived.
+	results: Vec<FileMatches>,
+	/// Flattened file headers and matching lines.
+	rows: Vec<ResultRow>,
+	/// Index into `rows` of the selected row.
+	selected: usize,
+	/// Index into `rows` of the first row shown.
+	offset: usize,
+	/// Generation of the current query; events from others are dropped.
+	generation: u64,
+	/// The search for the current query, while it runs.
+	running: Option<RunningSearch>,
+	/// Why the last search failed or stopped early.
+	status: Option<String>,
+	/// Sending half handed to each search thread.
+	events_tx: Sender<(u64, SearchEvent)>,
+	/// Events from search threads.
+	events_rx: Receiver<(u64, SearchEvent)>,
+	/// Area the panel was last drawn in, for mouse hit-testing.
+	area: Rect,
+}
+
+impl Default for SearchPanel {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl SearchPanel {
+	/// Creates a panel searching the working directory.
+	pub fn new() -> Self {
+		let (events_tx, events_rx) = mpsc::channel();
+		Self {
+			root: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+			query: String::new(),
+			results: Vec::new(),
+			rows: Vec::new(),
+			selected: 0,
+			offset: 0,
+			generation: 0,
+			running: None,
+			status: None,
+			events_tx,
+			events_rx,
+			area: Rect::ZERO,
+		}
+	}
+
+	/// Stops the running search, if any. Anything it still sends is
+	/// dropped.
+	fn cancel(&mut self) {
+		self.generation += 1;
+		if let Some(running) = self.running.take() {
+			running.cancel.store(true, Ordering::Relaxed);
+			self.status = Some("cancelled".to_string());
+		}
+	}
+
+	/// Clears the results and searches for the current query.
+	fn restart(&mut self, editor: &Editor) {
+		self.cancel();
+		self.results.clear();
+		self.rows.clear();
+		self.selected = 0;
+		self.offset = 0;
+		self.status = None;
+		if self.query.is_empty() {
+			return;
+		}
+
+		let backend: String = editor.option(opts::SEARCH_BACKEND);
+		let request = SearchRequest {
+			pattern: self.query.clone(),
+			case: editor.search_case(),
+			root: self.root.clone(),
+			backend: SearchBackend::parse(&backend).unwrap_or_default(),
+		};
+		let cancel = Arc::new(AtomicBool::new(false));
+		project_search::spawn(
+			request,
+			self.generation,
+			self.events_tx.clone(),
+			Arc::clone(&cancel),
+		);
+		self.running = Some(RunningSearch {
+			cancel,
+			started: Instant::now(),
+		});
+	}
+
+	/// Takes the events sent so far, dropping those of older queries.
+	/// Returns whether anything changed.
+	fn poll(&mut self) -> bool {
+		let mut changed = false;
+		while let Ok((generation, event)) = self.events_rx.try_recv() {
+			if generation != self.generation {
+				continue;
+			}
+			changed = true;
+			match event {
+				SearchEvent::File(file) => {
+					let index = self.results.len();
+					self.rows.push(ResultRow::File(index));
+					self.rows
+						.extend((0..file.matches.len()).map(|line| ResultRow::Match(index, line)));
+					self.results.push(file);
+				}
+				SearchEvent::Error(e) => self.status = Some(e),
+				SearchEvent::Done => self.running = None,
+			}
+		}
+		changed
+	}
+
+	/// Returns the file, 1-based line and 1-based column the selected row
+	/// points at; a file header points at its first match.
+	fn selected_location(&self) -> Option<(PathBuf, usize, usize)> {
+		let (file, line) = match *self.rows.get(self.selected)? {
+			ResultRow::File(file) => (file, 0),
+			ResultRow::Match(file, line) => (file, line),
+		};
+		let file = &self.results[file];
+		let line = file.matches.get(line)?;
+		let column = line.ranges.first().map_or(0, |r| r.start);
+		Some((file.path.clone(), line.line + 1, column + 1))
+	}
+
+	/// Moves the selection by `delta` rows, clamped to the list.
+	fn move_selection(&mut self, delta: isize) {
+		let last = self.rows.len().saturating_sub(1);
+		self.selected = self.selected.saturating_add_signed(delta).min(last);
+	}
+
+	/// Returns every match as a location list entry.
+	fn locations(&self) -> Vec<LocationItem> {
+		self.results
+			.iter()
+			.flat_map(|file| {
+				file.matches.iter().map(|line| {
+					let range = line.ranges.first().cloned().unwrap_or_default();
+					LocationItem::new(file.path.clone(), line.line, range.start, line.text.clone())
+						.with_end(line.line, range.end)
+				})
+			})
+			.collect()
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let ctrl = key.modifiers.contains(Modifiers::CONTROL);
+		let page = usize::from(self.area.height.saturating_sub(1)).max(1) as isize;
+		match key.code {
+			KeyCode::Escape if self.running.is_some() => self.cancel(),
+			KeyCode::Escape => {
+				return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+			}
+			KeyCode::Enter => {
+				let Some((path, line, column)) = self.selected_location() else {
+					return EventResult::consumed();
+				};
+				editor.workspace.command_queue.push(
+					"open_location",
+					vec![
+						path.to_string_lossy().into_owned(),
+						line.to_string(),
+						column.to_string(),
+					],
+				);
+				return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+			}
+			KeyCode::Char('q') if ctrl => {
+				editor.set_locations(format!("search: {}", self.query), self.locations());
+				return EventResult::consumed()
+					.with_request(UiRequest::OpenPanel(LOCATIONS_PANEL_ID.to_string()));
+			}
+			KeyCode::Char('u') if ctrl => {
+				self.query.clear();
+				self.restart(editor);
+			}
+			KeyCode::Char('n') if ctrl => self.move_selection(1),
+			KeyCode::Char('p') if ctrl => self.move_selection(-1),
+			KeyCode::Down => self.move_selection(1),
+			KeyCode::Up => self.move_selection(-1),
+			KeyCode::PageDown => self.move_selection(page),
+			KeyCode::PageUp => self.move_selection(-page),
+			KeyCode::Backspace => {
+				if self.query.pop().is_some() {
+					self.restart(editor);
+				}
+			}
+			KeyCode::Char(c) if !ctrl && !key.modifiers.contains(Modifiers::ALT) => {
+				self.query.push(c);
+				self.restart(editor);
+			}
+			_ => {}
+		}
+		EventResult::consumed().with_request(UiRequest::Redraw)
+	}
+
+	/// Builds the query line, with a spinner or a summary at its end.
+	fn query_line(&self, theme: &Theme, width: u16) -> Line<'static> {
+		let colors = &theme.colors;
+		let mut spans = vec![
+			Span::styled(QUERY_PREFIX, Style::default().fg(colors.ui.gutter_fg)),
+			Span::raw(self.query.clone()),
+		];
+		let summary = match (&self.running, &self.status) {
+			(Some(running), _) => {
+				let tick = (running.started.elapsed().as_millis() / SPINNER_INTERVAL_MS) as u64;
+				Spinner::new(tick).frame().to_string()
+			}
+			(None, Some(status)) => status.clone(),
+			(None, None) if self.query.is_empty() => String::new(),
+			(None, None) => {
+				let matches: usize = self.results.iter().map(|f| f.matches.len()).sum();
+				format!("{matches} matches in {} files", self.results.len())
+			}
+		};
+		let used = QUERY_PREFIX.width() + self.query.width();
+		let pad = usize::from(width).saturating_sub(used + summary.width());
+		if pad > 0 && !summary.is_empty() {
+			spans.push(Span::raw(" ".repeat(pad)));
+			spans.push(Span::styled(
+				summary,
+				Style::default().fg(colors.status.accent_fg),
+			));
+		}
+		Line::from(spans)
+	}
+
+	/// Builds the line for `row`, with matches highlighted.
+	fn row_line(&self, row: ResultRow, theme: &Theme) -> Line<'static> {
+		let colors = &theme.colors.ui;
+		match row {
+			ResultRow::File(file) => {
+				let file = &self.results[file];
+				let path = file.path.strip_prefix(&self.root).unwrap_or(&file.path);
+				Line::from(vec![
+					Span::styled(
+						path.display().to_string(),
+						Style::default().add_modifier(Modifier::BOLD),
+					),
+					Span::styled(
+						format!(" ({})", file.matches.len()),
+						Style::default().fg(colors.gutter_fg),
+					),
+				])
+			}
+			ResultRow::Match(file, line) => {
+				let line = &self.results[file].matches[line];
+				let chars: Vec<char> = line.text.chars().collect();
+				let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+				let mut spans = vec![Span::styled(
+					format!("{:>5}: ", line.line + 1),
+					Style::default().fg(colors.gutter_fg),
+				)];
+				let highlight = Style::default().bg(colors.search_match_bg);
+				let mut at = indent;
+				for range in &line.ranges {
+					let start = range.start.max(at);
+					let end = range.end.max(start);
+					spans.push(Span::raw(chars[at..start].iter().collect::<String>()));
+					spans.push(Span::styled(
+						chars[start..end].iter().collect::<String>(),
+						highlight,
+					));
+					at = end;
+				}
+				spans.push(Span::raw(chars[at..].iter().collect::<String>()));
+				Line::from(spans)
+			}
+		}
+	}
+}
+
+impl Panel for SearchPanel {
+	fn id(&self) -> &str {
+		SEARCH_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Bottom
+	}
+
+	fn title(&self) -> &str {
+		"Search"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		if !open {
+			self.cancel();
+		}
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick => {
+				if self.poll() || self.running.is_some() {
+					return EventResult::not_consumed().with_request(UiRequest::Redraw);
+				}
+				EventResult::not_consumed()
+			}
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Paste(text) if focused => {
+				self.query.push_str(text.lines().next().unwrap_or_default());
+				self.restart(editor);
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Mouse(mouse) => {
+				match mouse.kind {
+					MouseEventKind::Down(MouseButton::Left) if mouse.row > self.area.y => {
+						let row = self.offset + usize::from(mouse.row - self.area.y - 1);
+						if row < self.rows.len() {
+							self.selected = row;
+						}
+					}
+					MouseEventKind::ScrollUp => self.move_selection(-1),
+					MouseEventKind::ScrollDown => self.move_selection(1),
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		_editor: &mut Editor,
+		focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		self.poll();
+		self.area = area;
+		if area.is_empty() {
+			return None;
+		}
+
+		let list_height = usize::from(area.height - 1);
+		if self.selected < self.offset {
+			self.offset = self.selected;
+		} else if list_height > 0 && self.selected >= self.offset + list_height {
+			self.offset = self.selected + 1 - list_height;
+		}
+
+		let colors = &theme.colors.ui;
+		let selected = Style::default()
+			.fg(colors.selection_fg)
+			.bg(colors.selection_bg);
+		let mut lines = vec![self.query_line(theme, area.width)];
+		for (index, &row) in self
+			.rows
+			.iter()
+			.enumerate()
+			.skip(self.offset)
+			.take(list_height)
+		{
+			let line = self.row_line(row, theme);
+			lines.push(if index == self.selected {
+				line.style(selected)
+			} else {
+				line
+			});
+		}
+		let paragraph = Paragraph::new(lines).style(Style::default().fg(colors.fg).bg(colors.bg));
+		frame.render_widget(paragraph, area);
+
+		focused.then(|| {
+			let x = (QUERY_PREFIX.width() + self.query.width()) as u16;
+			CursorRequest {
+				pos: Position::new(area.x + x.min(area.width - 1), area.y),
+				style: None,
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::project_search::LineMatch;
+
+	fn file(name: &str, lines: &[usize]) -> FileMatches {
+		FileMatches {
+			path: PathBuf::from(name),
+			matches: lines
+				.iter()
+				.map(|&line| LineMatch {
+					line,
+					text: "needle".to_string(),
+					ranges: vec![0..6],
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn drops_results_of_older_queries() {
+		let mut panel = SearchPanel::new();
+		let stale = panel.generation;
+		panel.cancel();
+		let current = panel.generation;
+		let tx = panel.events_tx.clone();
+		tx.send((stale, SearchEvent::File(file("old.rs", &[0]))))
+			.unwrap();
+		tx.send((current, SearchEvent::File(file("new.rs", &[2, 4]))))
+			.unwrap();
+		assert!(panel.poll());
+
+		assert_eq!(panel.results.len(), 1);
+		assert_eq!(
+			panel.rows,
+			[
+				ResultRow::File(0),
+				ResultRow::Match(0, 0),
+				ResultRow::Match(0, 1)
+			]
+		);
+		assert_eq!(
+			panel.selected_location(),
+			Some((PathBuf::from("new.rs"), 3, 1))
+		);
+		panel.move_selection(5);
+		assert_eq!(
+			panel.selected_location(),
+			Some((PathBuf::from("new.rs"), 5, 1))
+		);
+		assert_eq!(panel.locations().len(), 2);
+	}
+}
@@ -0,0 +1,477 @@
+//! Terminal panel.
+//!
+//! Runs the user's shell in a pseudo-terminal sized to the panel and draws
+//! its screen through a `vt100` parser. While the panel is focused every key
+//! goes to the shell except the `terminal-escape` key, which returns focus
+//! to the editor. The shell is killed and reaped when the panel closes or is
+//! dropped with the editor, so no zombie is left behind.
+
+use std::io::{Read, Write};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use portable_pty::{Child, ChildKiller, CommandBuilder, MasterPty, PtySize, native_pty_system};
+use termina::event::{KeyCode, KeyEvent, Modifiers, MouseEventKind};
+use xeno_base::Key;
+use xeno_registry::options::keys as opts;
+use xeno_registry::themes::Theme;
+use xeno_tui::Frame;
+use xeno_tui::layout::{Position, Rect};
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::Paragraph;
+use xeno_tui::widgets::terminal::{Cursor, PseudoTerminal};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::editor::Editor;
+
+/// Identifier of the terminal panel.
+pub const TERMINAL_PANEL_ID: &str = "terminal";
+
+/// Lines scrolled per mouse wheel step.
+const WHEEL_LINES: usize = 3;
+
+/// Records the window title set by the shell through OSC 0 and 2.
+#[derive(Debug, Default)]
+struct TitleTracker {
+	/// Last title set, if any.
+	title: Option<String>,
+}
+
+impl vt100::Callbacks for TitleTracker {
+	fn set_window_title(&mut self, _: &mut vt100::Screen, title: &[u8]) {
+		self.title = Some(String::from_utf8_lossy(title).into_owned());
+	}
+}
+
+/// A shell running in a pseudo-terminal.
+struct Shell {
+	/// Screen state fed from the shell's output.
+	parser: vt100::Parser<TitleTracker>,
+	/// Controlling side of the pseudo-terminal, used for resizing.
+	master: Box<dyn MasterPty + Send>,
+	/// Input to the shell.
+	writer: Box<dyn Write + Send>,
+	/// The shell process.
+	child: Box<dyn Child + Send + Sync>,
+	/// Output chunks read by the reader thread.
+	output: Receiver<Vec<u8>>,
+	/// Name of the shell, shown until it sets a title.
+	name: String,
+	/// Whether the shell has exited and been reaped.
+	exited: bool,
+}
+
+impl Shell {
+	/// Starts `$SHELL` in the working directory on a `rows` by `cols`
+	/// pseudo-terminal keeping `scrollback` lines of history.
+	fn spawn(rows: u16, cols: u16, scrollback: usize) -> anyhow::Result<Self> {
+		let pair = native_pty_system().openpty(pty_size(rows, cols))?;
+		let program = std::env::var("SHELL").unwrap_or_else(|_| default_shell().to_string());
+		let mut command = CommandBuilder::new(&program);
+		if let Ok(cwd) = std::env::current_dir() {
+			command.cwd(cwd);
+		}
+		command.env("TERM", "xterm-256color");
+		let child = pair.slave.spawn_command(command)?;
+		drop(pair.slave);
+
+		let mut reader = pair.master.try_clone_reader()?;
+		let writer = pair.master.take_writer()?;
+		let (tx, output) = mpsc::channel();
+		thread::spawn(move || {
+			let mut buf = [0u8; 8192];
+			loop {
+				match reader.read(&mut buf) {
+					Ok(0) | Err(_) => break,
+					Ok(n) => {
+						if tx.send(buf[..n].to_vec()).is_err() {
+							break;
+						}
+					}
+				}
+			}
+		});
+
+		let name = program.rsplit('/').next().unwrap_or(&program).to_string();
+		Ok(Self {
+			parser: vt100::Parser::new_with_callbacks(
+				rows,
+				cols,
+				scrollback,
+				TitleTracker::default(),
+			),
+			master: pair.master,
+			writer,
+			child,
+			output,
+			name,
+			exited: false,
+		})
+	}
+
+	/// Feeds pending output to the screen and reaps the shell if it exited.
+	/// Returns whether anything changed.
+	fn poll(&mut self) -> bool {
+		let mut changed = false;
+		while let Ok(bytes) = self.output.try_recv() {
+			self.parser.process(&bytes);
+			changed = true;
+		}
+		if !self.exited && matches!(self.child.try_wait(), Ok(Some(_))) {
+			self.exited = true;
+			changed = true;
+		}
+		changed
+	}
+
+	/// Resizes the screen and the pseudo-terminal if the size changed.
+	fn resize(&mut self, rows: u16, cols: u16) {
+		if self.parser.screen().size() == (rows, cols) {
+			return;
+		}
+		self.parser.screen_mut().set_size(rows, cols);
+		let _ = self.master.resize(pty_size(rows, cols));
+	}
+
+	/// Sends `bytes` to the shell, scrolling back to the live screen.
+	fn write(&mut self, bytes: &[u8]) {
+		self.parser.screen_mut().set_scrollback(0);
+		let _ = self.writer.write_all(bytes);
+		let _ = self.writer.flush();
+	}
+
+	/// Scrolls the view `lines` into the history, negative towards the live
+	/// screen.
+	fn scroll(&mut self, lines: isize) {
+		let screen = self.parser.screen_mut();
+		let offset = screen.scrollback().saturating_add_signed(lines);
+		screen.set_scrollback(offset);
+	}
+
+	/// Returns the title shown above the screen.
+	fn title(&self) -> String {
+		let mut title = self
+			.parser
+			.callbacks()
+			.title
+			.clone()
+			.filter(|t| !t.is_empty())
+			.unwrap_or_else(|| self.name.clone());
+		let scrolled = self.parser.screen().scrollback();
+		if scrolled > 0 {
+			title.push_str(&format!(" [+{scrolled}]"));
+		}
+		if self.exited {
+			title.push_str(" [exited, any key restarts]");
+		}
+		title
+	}
+}
+
+impl Drop for Shell {
+	fn drop(&mut self) {
+		if !self.exited {
+			let _ = self.child.kill();
+			let _ = self.child.wait();
+		}
+	}
+}
+
+/// Returns the shell used when `$SHELL` is unset.
+fn default_shell() -> &'static str {
+	if cfg!(windows) { "cmd.exe" } else { "/bin/sh" }
+}
+
+/// Returns a pseudo-terminal size of `rows` by `cols` cells.
+fn pty_size(rows: u16, cols: u16) -> PtySize {
+	PtySize {
+		rows,
+		cols,
+		pixel_width: 0,
+		pixel_height: 0,
+	}
+}
+
+/// Panel running an interactive shell.
+#[derive(Default)]
+pub struct TerminalPanel {
+	/// The running shell, started when the panel is first drawn.
+	shell: Option<Shell>,
+	/// Why the shell could not be started.
+	error: Option<String>,
+	/// Whether the panel is docked.
+	open: bool,
+	/// Area of the screen below the title, as of the last render.
+	screen_area: Rect,
+}
+
+impl TerminalPanel {
+	/// Creates a panel that starts a shell when it is first shown.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &Editor) -> EventResult {
+		let escape: String = editor.option(opts::TERMINAL_ESCAPE);
+		let escape = Key::parse(&escape).unwrap_or(Key::ctrl('\\'));
+		if Key::from(key).normalize() == escape.normalize() {
+			return EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()));
+		}
+
+		let Some(shell) = &mut self.shell else {
+			self.error = None;
+			return EventResult::consumed().with_request(UiRequest::Redraw);
+		};
+		if shell.exited {
+			self.shell = None;
+			return EventResult::consumed().with_request(UiRequest::Redraw);
+		}
+
+		let page = usize::from(self.screen_area.height.max(1)) as isize;
+		let shift = key.modifiers.contains(Modifiers::SHIFT);
+		match key.code {
+			KeyCode::PageUp if shift => shell.scroll(page),
+			KeyCode::PageDown if shift => shell.scroll(-page),
+			_ => {
+				let application_cursor = shell.parser.screen().application_cursor();
+				if let Some(bytes) = key_bytes(&key, application_cursor) {
+					shell.write(&bytes);
+				}
+			}
+		}
+		EventResult::consumed().with_request(UiRequest::Redraw)
+	}
+}
+
+impl Panel for TerminalPanel {
+	fn id(&self) -> &str {
+		TERMINAL_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Bottom
+	}
+
+	fn title(&self) -> &str {
+		"Terminal"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		self.open = open;
+		if !open {
+			self.shell = None;
+			self.error = None;
+		}
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick => match &mut self.shell {
+				Some(shell) if shell.poll() => {
+					EventResult::not_consumed().with_request(UiRequest::Redraw)
+				}
+				_ => EventResult::not_consumed(),
+			},
+			UiEvent::Resize => EventResult::not_consumed().with_request(UiRequest::Redraw),
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Paste(text) if focused => {
+				if let Some(shell) = self.shell.as_mut().filter(|s| !s.exited) {
+					if shell.parser.screen().bracketed_paste() {
+						shell.write(format!("\x1b[200~{text}\x1b[201~").as_bytes());
+					} else {
+						shell.write(text.as_bytes());
+					}
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Mouse(mouse) => {
+				if let Some(shell) = &mut self.shell {
+					match mouse.kind {
+						MouseEventKind::ScrollUp => shell.scroll(WHEEL_LINES as isize),
+						MouseEventKind::ScrollDown => shell.scroll(-(WHEEL_LINES as isize)),
+						_ => return EventResult::consumed(),
+					}
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		if area.height < 2 || area.width == 0 {
+			return None;
+		}
+		let title_area = Rect { height: 1, ..area };
+		let screen_area = Rect {
+			y: area.y + 1,
+			height: area.height - 1,
+			..area
+		};
+		self.screen_area = screen_area;
+
+		if self.open && self.shell.is_none() && self.error.is_none() {
+			let scrollback = editor.option(opts::TERMINAL_SCROLLBACK).max(0) as usize;
+			match Shell::spawn(screen_area.height, screen_area.width, scrollback) {
+				Ok(shell) => self.shell = Some(shell),
+				Err(e) => self.error = Some(format!("failed to start shell: {e}")),
+			}
+		}
+
+		let colors = &theme.colors;
+		let base = Style::default().fg(colors.ui.fg).bg(colors.ui.bg);
+		let title_style = Style::default()
+			.fg(colors.status.normal_fg)
+			.bg(colors.status.normal_bg)
+			.add_modifier(Modifier::BOLD);
+		let title = match (&self.shell, &self.error) {
+			(Some(shell), _) => shell.title(),
+			(None, Some(error)) => error.clone(),
+			(None, None) => String::new(),
+		};
+		frame.render_widget(
+			Paragraph::new(format!(" {title}")).style(title_style),
+			title_area,
+		);
+
+		let shell = self.shell.as_mut()?;
+		shell.poll();
+		shell.resize(screen_area.height, screen_area.width);
+		let screen = shell.parser.screen();
+		frame.render_widget(
+			PseudoTerminal::new(screen)
+				.cursor(Cursor::default().visibility(false))
+				.style(base),
+			screen_area,
+		);
+
+		if !focused || shell.exited || screen.hide_cursor() || screen.scrollback() > 0 {
+			return None;
+		}
+		let (row, col) = screen.cursor_position();
+		Some(CursorRequest {
+			pos: Position::new(
+				screen_area.x + col.min(screen_area.width - 1),
+				screen_area.y + row.min(screen_area.height - 1),
+			),
+			style: None,
+		})
+	}
+}
+
+/// Encodes `key` as the bytes a terminal sends for it, or `None` for keys
+/// with no encoding.
+///
+/// `application_cursor` selects the `ESC O` form of the arrow, Home and End
+/// keys, as requested by full-screen programs.
+fn key_bytes(key: &KeyEvent, application_cursor: bool) -> Option<Vec<u8>> {
+	let ctrl = key.modifiers.contains(Modifiers::CONTROL);
+	let alt = key.modifiers.contains(Modifiers::ALT);
+	let shift = key.modifiers.contains(Modifiers::SHIFT);
+	// xterm modifier parameter: 1 plus a bit per modifier.
+	let modifier = 1 + u8::from(shift) + 2 * u8::from(alt) + 4 * u8::from(ctrl);
+	let cursor = |code: char| {
+		if modifier > 1 {
+			format!("\x1b[1;{modifier}{code}").into_bytes()
+		} else if application_cursor {
+			format!("\x1bO{code}").into_bytes()
+		} else {
+			format!("\x1b[{code}").into_bytes()
+		}
+	};
+	let tilde = |n: u8| {
+		if modifier > 1 {
+			format!("\x1b[{n};{modifier}~").into_bytes()
+		} else {
+			format!("\x1b[{n}~").into_bytes()
+		}
+	};
+
+	let mut bytes = match key.code {
+		KeyCode::Char(c) if ctrl => {
+			let byte = match c.to_ascii_lowercase() {
+				c @ 'a'..='z' => c as u8 - b'a' + 1,
+				'@' | ' ' | '2' => 0,
+				'[' | '3' => 0x1b,
+				'\\' | '4' => 0x1c,
+				']' | '5' => 0x1d,
+				'^' | '6' => 0x1e,
+				'_' | '7' | '/' => 0x1f,
+				'?' | '8' => 0x7f,
+				_ => return None,
+			};
+			vec![byte]
+		}
+		KeyCode::Char(c) => c.to_string().into_bytes(),
+		KeyCode::Enter => vec![b'\r'],
+		KeyCode::Tab => vec![b'\t'],
+		KeyCode::BackTab => b"\x1b[Z".to_vec(),
+		KeyCode::Backspace => vec![0x7f],
+		KeyCode::Escape => vec![0x1b],
+		KeyCode::Up => cursor('A'),
+		KeyCode::Down => cursor('B'),
+		KeyCode::Right => cursor('C'),
+		KeyCode::Left => cursor('D'),
+		KeyCode::Home => cursor('H'),
+		KeyCode::End => cursor('F'),
+		KeyCode::Insert => tilde(2),
+		KeyCode::Delete => tilde(3),
+		KeyCode::PageUp => tilde(5),
+		KeyCode::PageDown => tilde(6),
+		KeyCode::Function(n @ 1..=4) => format!("\x1bO{}", (b'P' + n - 1) as char).into_bytes(),
+		KeyCode::Function(n @ 5..=12) => {
+			tilde([15, 17, 18, 19, 20, 21, 23, 24][usize::from(n - 5)])
+		}
+		_ => return None,
+	};
+	if alt
+		&& matches!(
+			key.code,
+			KeyCode::Char(_) | KeyCode::Enter | KeyCode::Backspace
+		) {
+		bytes.insert(0, 0x1b);
+	}
+	Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bytes(code: KeyCode, modifiers: Modifiers) -> Vec<u8> {
+		key_bytes(&KeyEvent::new(code, modifiers), false).unwrap()
+	}
+
+	#[test]
+	fn encodes_keys_like_xterm() {
+		assert_eq!(bytes(KeyCode::Char('é'), Modifiers::NONE), "é".as_bytes());
+		assert_eq!(bytes(KeyCode::Char('c'), Modifiers::CONTROL), [3]);
+		assert_eq!(bytes(KeyCode::Char('b'), Modifiers::ALT), b"\x1bb");
+		assert_eq!(bytes(KeyCode::Up, Modifiers::NONE), b"\x1b[A");
+		assert_eq!(bytes(KeyCode::Right, Modifiers::CONTROL), b"\x1b[1;5C");
+		assert_eq!(bytes(KeyCode::Delete, Modifiers::NONE), b"\x1b[3~");
+		assert_eq!(bytes(KeyCode::Function(5), Modifiers::NONE), b"\x1b[15~");
+		assert_eq!(
+			key_bytes(&KeyEvent::new(KeyCode::Up, Modifiers::NONE), true).unwrap(),
+			b"\x1bOA"
+		);
+	}
+
+	#[test]
+	fn tracks_osc_titles() {
+		let mut parser = vt100::Parser::new_with_callbacks(4, 20, 0, TitleTracker::default());
+		parser.process(b"\x1b]2;build\x07hello");
+		assert_eq!(parser.callbacks().title.as_deref(), Some("build"));
+		assert_eq!(parser.screen().contents(), "hello");
+	}
+}
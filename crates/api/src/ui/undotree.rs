@@ -0,0 +1,481 @@
+//! Undo tree panel.
+//!
+//! Shows the focused document's undo tree with the tree-view widget. A run
+//! of edits without alternatives reads as a flat list; where an edit was
+//! made after undoing, the branch point expands into one subtree per
+//! branch. Enter seeks the document to the selected state through
+//! [`UndoAccess`].
+//!
+//! The panel re-reads the tree only after the `buffer:change` hook fires or
+//! another document gets focus, and rebuilds the rows only when the shape of
+//! the tree changed; typing into the current state just relabels its row.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use termina::event::{KeyCode, KeyEvent, MouseButton, MouseEventKind};
+use xeno_registry::themes::Theme;
+use xeno_registry::{UndoAccess, UndoNodeInfo, hook};
+use xeno_tui::Frame;
+use xeno_tui::layout::Rect;
+use xeno_tui::style::{Modifier, Style};
+use xeno_tui::widgets::tree_view::{TreeItem, TreeKey, TreeView, TreeViewState};
+
+use super::UiFocus;
+use super::dock::DockSlot;
+use super::panel::{CursorRequest, EventResult, Panel, UiEvent, UiRequest};
+use crate::buffer::DocumentId;
+use crate::editor::Editor;
+use crate::editor::extensions::ExtensionMap;
+
+/// Identifier of the undo tree panel.
+pub const UNDOTREE_PANEL_ID: &str = "undotree";
+
+/// How often relative timestamps are redrawn while the panel is open.
+const AGE_REFRESH: Duration = Duration::from_secs(1);
+
+/// Editor extension set by the `buffer:change` hook, telling the panel to
+/// re-read the undo tree.
+pub struct UndoTreeChanges(AtomicBool);
+
+impl Default for UndoTreeChanges {
+	fn default() -> Self {
+		Self(AtomicBool::new(true))
+	}
+}
+
+impl UndoTreeChanges {
+	/// Returns whether a buffer changed since the last [`take`](Self::take).
+	fn pending(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+
+	/// Returns whether a buffer changed since the last call.
+	fn take(&self) -> bool {
+		self.0.swap(false, Ordering::Relaxed)
+	}
+}
+
+hook!(
+	undotree_buffer_change,
+	BufferChange,
+	100,
+	"Refresh the undo tree panel after edits",
+	|ctx| {
+		if let Some(changes) = ctx
+			.extensions::<ExtensionMap>()
+			.and_then(|ext| ext.get::<UndoTreeChanges>())
+		{
+			changes.0.store(true, Ordering::Relaxed);
+		}
+	}
+);
+
+/// Panel showing the undo tree of the focused document.
+#[derive(Default)]
+pub struct UndoTreePanel {
+	/// Rows of the tree, expansion and selection, keyed by node index.
+	tree: TreeViewState<usize>,
+	/// Document the tree belongs to.
+	doc: Option<DocumentId>,
+	/// Nodes as of the last refresh, in chronological order.
+	nodes: Vec<UndoNodeInfo>,
+	/// Nodes that had rows below them in the last layout.
+	branches: HashSet<usize>,
+	/// Set when the tree must be re-read regardless of the hook.
+	stale: bool,
+	/// Whether the panel is docked.
+	open: bool,
+	/// When the panel was last drawn, for refreshing timestamps.
+	drawn: Option<Instant>,
+	/// Area the tree was last drawn in, for mouse hit-testing.
+	area: Rect,
+}
+
+impl UndoTreePanel {
+	/// Creates the panel.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Re-reads the undo tree of the focused document if it may have changed.
+	fn refresh(&mut self, editor: &Editor) {
+		let doc = editor.buffer().document_id();
+		let stale = std::mem::take(&mut self.stale);
+		let changed = editor
+			.extensions
+			.get::<UndoTreeChanges>()
+			.is_none_or(UndoTreeChanges::take);
+		if !changed && !stale && self.doc == Some(doc) {
+			return;
+		}
+		let nodes = UndoAccess::undo_tree(editor);
+		let current = nodes.iter().position(|node| node.current);
+		let moved = current != self.nodes.iter().position(|node| node.current);
+
+		let same_shape = self.doc == Some(doc)
+			&& nodes.len() == self.nodes.len()
+			&& nodes
+				.iter()
+				.zip(&self.nodes)
+				.all(|(a, b)| a.parent == b.parent);
+		if same_shape {
+			for (node, old) in nodes.iter().zip(&self.nodes) {
+				if (node.added, node.removed) != (old.added, old.removed) {
+					self.tree.set_label(&node.id, label(node));
+				}
+			}
+		} else {
+			self.rebuild(&nodes);
+		}
+		self.doc = Some(doc);
+		self.nodes = nodes;
+		if (moved || !same_shape)
+			&& let Some(current) = current
+		{
+			self.tree.select_id(&current);
+		}
+	}
+
+	/// Returns whether the tree may have changed or its ages are due for a
+	/// redraw.
+	fn needs_redraw(&self, editor: &Editor) -> bool {
+		let changed = editor
+			.extensions
+			.get::<UndoTreeChanges>()
+			.is_some_and(UndoTreeChanges::pending);
+		changed
+			|| self.doc != Some(editor.buffer().document_id())
+			|| self
+				.drawn
+				.is_none_or(|drawn| drawn.elapsed() >= AGE_REFRESH)
+	}
+
+	/// Lays the rows out again for a tree of a new shape, expanding branch
+	/// points that are new.
+	fn rebuild(&mut self, nodes: &[UndoNodeInfo]) {
+		let layout = UndoLayout::new(nodes);
+		self.tree.set_roots(layout.roots);
+		let branches: HashSet<usize> = layout.children.iter().map(|(id, _)| *id).collect();
+		for (id, children) in layout.children {
+			self.tree.set_children(&id, children);
+			if !self.branches.contains(&id) {
+				self.tree.expand(&id);
+			}
+		}
+		self.branches = branches;
+	}
+
+	/// Handles a key while the panel is focused.
+	fn handle_key(&mut self, key: KeyEvent, editor: &mut Editor) -> EventResult {
+		let tree_key = match key.code {
+			KeyCode::Char('j') | KeyCode::Down => Some(TreeKey::Down),
+			KeyCode::Char('k') | KeyCode::Up => Some(TreeKey::Up),
+			KeyCode::Char('g') | KeyCode::Home => Some(TreeKey::Home),
+			KeyCode::Char('G') | KeyCode::End => Some(TreeKey::End),
+			KeyCode::PageUp => Some(TreeKey::PageUp),
+			KeyCode::PageDown => Some(TreeKey::PageDown),
+			KeyCode::Char('l') | KeyCode::Right => Some(TreeKey::Right),
+			KeyCode::Char('h') | KeyCode::Left => Some(TreeKey::Left),
+			KeyCode::Char(' ') => Some(TreeKey::Toggle),
+			_ => None,
+		};
+		if let Some(tree_key) = tree_key {
+			self.tree.handle_key(tree_key);
+			return EventResult::consumed().with_request(UiRequest::Redraw);
+		}
+		match key.code {
+			KeyCode::Enter => {
+				if let Some(&id) = self.tree.selected_id() {
+					UndoAccess::goto_undo_node(editor, id);
+					self.stale = true;
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			KeyCode::Char('q') | KeyCode::Escape => {
+				EventResult::consumed().with_request(UiRequest::Focus(UiFocus::editor()))
+			}
+			_ => EventResult::consumed(),
+		}
+	}
+}
+
+impl Panel for UndoTreePanel {
+	fn id(&self) -> &str {
+		UNDOTREE_PANEL_ID
+	}
+
+	fn default_slot(&self) -> DockSlot {
+		DockSlot::Right
+	}
+
+	fn title(&self) -> &str {
+		"Undo tree"
+	}
+
+	fn on_open_changed(&mut self, open: bool) {
+		self.open = open;
+		self.stale = open;
+	}
+
+	fn handle_event(&mut self, event: UiEvent, editor: &mut Editor, focused: bool) -> EventResult {
+		match event {
+			UiEvent::Tick if self.open && self.needs_redraw(editor) => {
+				EventResult::not_consumed().with_request(UiRequest::Redraw)
+			}
+			UiEvent::Key(key) if focused => self.handle_key(key, editor),
+			UiEvent::Mouse(mouse) => {
+				match mouse.kind {
+					MouseEventKind::Down(MouseButton::Left) => {
+						let y = mouse.row.saturating_sub(self.area.y);
+						if mouse.row >= self.area.y
+							&& let Some(row) = self.tree.row_at(y)
+						{
+							self.tree.select_row(row);
+						}
+					}
+					MouseEventKind::ScrollUp => {
+						self.tree.handle_key(TreeKey::Up);
+					}
+					MouseEventKind::ScrollDown => {
+						self.tree.handle_key(TreeKey::Down);
+					}
+					_ => return EventResult::consumed(),
+				}
+				EventResult::consumed().with_request(UiRequest::Redraw)
+			}
+			_ => EventResult::not_consumed(),
+		}
+	}
+
+	fn render(
+		&mut self,
+		frame: &mut Frame<'_>,
+		area: Rect,
+		editor: &mut Editor,
+		_focused: bool,
+		theme: &Theme,
+	) -> Option<CursorRequest> {
+		self.refresh(editor);
+		self.area = area;
+		self.drawn = Some(Instant::now());
+		let colors = &theme.colors.ui;
+		let tree = TreeView::new()
+			.style(Style::default().fg(colors.fg).bg(colors.bg))
+			.highlight_style(
+				Style::default()
+					.fg(colors.selection_fg)
+					.bg(colors.selection_bg),
+			)
+			.guide_style(Style::default().fg(colors.gutter_fg));
+		frame.render_stateful_widget(tree, area, &mut self.tree);
+
+		// Ages go at the right edge of each row, and the state the document
+		// is at stands out.
+		let now = SystemTime::now();
+		let age_style = Style::default().fg(colors.gutter_fg);
+		let current_style = Style::default()
+			.fg(theme.colors.status.accent_fg)
+			.add_modifier(Modifier::BOLD);
+		let buf = frame.buffer_mut();
+		let rows = self
+			.tree
+			.visible_rows()
+			.skip(self.tree.offset())
+			.take(usize::from(area.height));
+		for (y, row) in (area.y..).zip(rows) {
+			let Some(node) = self.nodes.get(*row.id) else {
+				continue;
+			};
+			let line = Rect {
+				y,
+				height: 1,
+				..area
+			};
+			if node.current && !row.selected {
+				buf.set_style(line, current_style);
+			}
+			// Guides, marker and label take the left of the row; the last
+			// column may hold the scrollbar.
+			let age = age(now, node.timestamp);
+			let used = row.depth * 2 + 2 + row.label.chars().count();
+			let width = age.chars().count();
+			if used + width + 2 <= usize::from(area.width) {
+				buf.set_string(area.right() - width as u16 - 1, y, age, age_style);
+			}
+		}
+		None
+	}
+}
+
+/// The undo tree arranged for the tree view.
+///
+/// A node with a single child is followed by that child on the next row at
+/// the same depth. A node with several children has one row below it per
+/// child, each heading its branch; the rest of a branch sits below its head.
+struct UndoLayout {
+	/// Rows at the top level: the run of states from the original document.
+	roots: Vec<TreeItem<usize>>,
+	/// Rows below each node that has some, parents before their children.
+	children: Vec<(usize, Vec<TreeItem<usize>>)>,
+}
+
+impl UndoLayout {
+	/// Arranges `nodes`, given in chronological order with the root first.
+	fn new(nodes: &[UndoNodeInfo]) -> Self {
+		let mut kids = vec![Vec::new(); nodes.len()];
+		for node in nodes {
+			if let Some(parent) = node.parent {
+				kids[parent].push(node.id);
+			}
+		}
+
+		// A node's own rows: its branches if it has several children, or
+		// the rest of its branch if it heads one.
+		let below = |id: usize| -> Vec<usize> {
+			let is_head = nodes[id]
+				.parent
+				.is_some_and(|parent| kids[parent].len() > 1);
+			match kids[id].as_slice() {
+				[] => Vec::new(),
+				[child] if is_head => run(&kids, *child),
+				[_] => Vec::new(),
+				branches => branches.to_vec(),
+			}
+		};
+		let item = |id: usize| {
+			let node = &nodes[id];
+			if below(id).is_empty() {
+				TreeItem::leaf(id, label(node))
+			} else {
+				TreeItem::branch(id, label(node))
+			}
+		};
+
+		let top = if nodes.is_empty() {
+			Vec::new()
+		} else {
+			run(&kids, 0)
+		};
+		let mut children = Vec::new();
+		let mut pending = top.clone();
+		let mut next = 0;
+		while next < pending.len() {
+			let id = pending[next];
+			next += 1;
+			let rows = below(id);
+			if !rows.is_empty() {
+				pending.extend(&rows);
+				children.push((id, rows.into_iter().map(item).collect()));
+			}
+		}
+		Self {
+			roots: top.into_iter().map(item).collect(),
+			children,
+		}
+	}
+}
+
+/// Returns `start` and the chain of only children following it.
+fn run(kids: &[Vec<usize>], start: usize) -> Vec<usize> {
+	let mut ids = vec![start];
+	while let [child] = kids[*ids.last().unwrap()].as_slice() {
+		ids.push(*child);
+	}
+	ids
+}
+
+/// Returns the text of the row of `node`: its index and what the edit did.
+fn label(node: &UndoNodeInfo) -> String {
+	if node.parent.is_none() {
+		return format!("{} original", node.id);
+	}
+	let summary = match (node.added, node.removed) {
+		(0, 0) => "no change".to_string(),
+		(added, 0) => format!("+{added}"),
+		(0, removed) => format!("-{removed}"),
+		(added, removed) => format!("+{added} -{removed}"),
+	};
+	format!("{} {summary}", node.id)
+}
+
+/// Returns how long before `now` a state was edited, in the largest whole
+/// unit.
+fn age(now: SystemTime, timestamp: SystemTime) -> String {
+	let secs = now.duration_since(timestamp).unwrap_or_default().as_secs();
+	match secs {
+		0..60 => format!("{secs}s"),
+		60..3600 => format!("{}m", secs / 60),
+		3600..86400 => format!("{}h", secs / 3600),
+		_ => format!("{}d", secs / 86400),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Builds nodes from their parents; the last node is current.
+	fn nodes(parents: &[Option<usize>]) -> Vec<UndoNodeInfo> {
+		parents
+			.iter()
+			.enumerate()
+			.map(|(id, &parent)| UndoNodeInfo {
+				id,
+				parent,
+				timestamp: SystemTime::UNIX_EPOCH,
+				current: id + 1 == parents.len(),
+				added: id,
+				removed: 0,
+			})
+			.collect()
+	}
+
+	fn ids(items: &[TreeItem<usize>]) -> Vec<usize> {
+		items.iter().map(|item| item.id).collect()
+	}
+
+	#[test]
+	fn linear_history_is_flat() {
+		let layout = UndoLayout::new(&nodes(&[None, Some(0), Some(1), Some(2)]));
+		assert_eq!(ids(&layout.roots), [0, 1, 2, 3]);
+		assert!(layout.children.is_empty());
+	}
+
+	#[test]
+	fn branches_hang_below_their_branch_point() {
+		// 0 - 1 - 2 - 3
+		//          \- 4 - 5
+		let layout = UndoLayout::new(&nodes(&[None, Some(0), Some(1), Some(2), Some(2), Some(4)]));
+		assert_eq!(ids(&layout.roots), [0, 1, 2]);
+		assert!(layout.roots[2].has_children);
+		let children: Vec<(usize, Vec<usize>)> = layout
+			.children
+			.iter()
+			.map(|(id, items)| (*id, ids(items)))
+			.collect();
+		assert_eq!(children, [(2, vec![3, 4]), (4, vec![5])]);
+	}
+
+	#[test]
+	fn new_branch_points_start_expanded() {
+		let mut panel = UndoTreePanel::new();
+		let tree = nodes(&[None, Some(0), Some(1), Some(1)]);
+		panel.rebuild(&tree);
+		panel.tree.select_id(&3);
+		let rows: Vec<usize> = panel.tree.visible_rows().map(|row| *row.id).collect();
+		assert_eq!(rows, [0, 1, 2, 3]);
+		assert_eq!(panel.tree.selected_id(), Some(&3));
+	}
+
+	#[test]
+	fn labels_summarize_edits() {
+		let mut node = nodes(&[None, Some(0)]).pop().unwrap();
+		assert_eq!(label(&node), "1 +1");
+		node.removed = 4;
+		assert_eq!(label(&node), "1 +1 -4");
+		let now = SystemTime::now();
+		assert_eq!(age(now, now - Duration::from_secs(125)), "2m");
+	}
+}
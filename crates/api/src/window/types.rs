@@ -1,7 +1,7 @@
 //! Window and floating window types.
 
 use xeno_registry::gutter::{GutterCell, GutterLineContext};
-use xeno_tui::layout::Rect;
+use xeno_tui::layout::{HorizontalAlignment, Rect};
 use xeno_tui::widgets::BorderType;
 use xeno_tui::widgets::block::Padding;
 
@@ -76,9 +76,11 @@ pub struct FloatingWindow {
 pub struct FloatingStyle {
 	pub border: bool,
 	pub border_type: BorderType,
-	pub padding: Padding,
+	/// Padding inside the border, or `None` for the theme's popup padding.
+	pub padding: Option<Padding>,
 	pub shadow: bool,
 	pub title: Option<String>,
+	pub title_alignment: HorizontalAlignment,
 }
 
 impl Default for FloatingStyle {
@@ -86,9 +88,10 @@ impl Default for FloatingStyle {
 		Self {
 			border: true,
 			border_type: BorderType::Rounded,
-			padding: Padding::ZERO,
+			padding: Some(Padding::ZERO),
 			shadow: false,
 			title: None,
+			title_alignment: HorizontalAlignment::Left,
 		}
 	}
 }
@@ -0,0 +1,95 @@
+//! Display column math for grapheme clusters.
+//!
+//! Columns are measured in terminal cells: each grapheme cluster occupies its
+//! Unicode display width (minimum one cell), and tabs expand to the next tab
+//! stop. Char offsets returned by these helpers always land on grapheme
+//! boundaries.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Returns the number of cells `grapheme` occupies when drawn at column `col`.
+pub fn grapheme_width(grapheme: &str, col: usize, tab_width: usize) -> usize {
+	if grapheme == "\t" {
+		let tab_width = tab_width.max(1);
+		return tab_width - col % tab_width;
+	}
+	grapheme.width().max(1)
+}
+
+/// Returns the total display width of `text`, starting from column zero.
+pub fn display_width(text: &str, tab_width: usize) -> usize {
+	text.graphemes(true)
+		.fold(0, |col, g| col + grapheme_width(g, col, tab_width))
+}
+
+/// Returns the display column at which the char at `char_idx` starts.
+///
+/// Offsets inside a grapheme cluster resolve to the column of the cluster's
+/// first cell. Offsets past the end return the width of the whole text.
+pub fn char_to_col(text: &str, char_idx: usize, tab_width: usize) -> usize {
+	let mut col = 0;
+	let mut chars = 0;
+	for g in text.graphemes(true) {
+		let len = g.chars().count();
+		if char_idx < chars + len {
+			return col;
+		}
+		col += grapheme_width(g, col, tab_width);
+		chars += len;
+	}
+	col
+}
+
+/// Returns the char offset of the grapheme cluster covering display column `col`.
+///
+/// Columns past the end of `text` return the text's char length, so callers
+/// can clamp to whatever end position suits them.
+pub fn col_to_char(text: &str, col: usize, tab_width: usize) -> usize {
+	let mut cur = 0;
+	let mut chars = 0;
+	for g in text.graphemes(true) {
+		let width = grapheme_width(g, cur, tab_width);
+		if col < cur + width {
+			return chars;
+		}
+		cur += width;
+		chars += g.chars().count();
+	}
+	chars
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tabs_expand_to_next_stop() {
+		assert_eq!(display_width("\tx", 4), 5);
+		assert_eq!(display_width("ab\tx", 4), 5);
+		assert_eq!(char_to_col("ab\tx", 3, 4), 4);
+		assert_eq!(col_to_char("ab\tx", 3, 4), 2);
+	}
+
+	#[test]
+	fn wide_and_combining_graphemes() {
+		// "漢" is two cells; "e\u{301}" is one cell spanning two chars.
+		let text = "漢e\u{301}x";
+		assert_eq!(display_width(text, 4), 4);
+		assert_eq!(char_to_col(text, 2, 4), 2);
+		assert_eq!(char_to_col(text, 3, 4), 3);
+		assert_eq!(col_to_char(text, 1, 4), 0);
+		assert_eq!(col_to_char(text, 2, 4), 1);
+		assert_eq!(col_to_char(text, 3, 4), 3);
+		assert_eq!(col_to_char(text, 10, 4), 4);
+	}
+
+	#[test]
+	fn emoji_clusters_render_as_one_wide_cell() {
+		assert_eq!(grapheme_width("👍🏽", 0, 4), 2);
+		assert_eq!(grapheme_width("👩\u{200d}👩\u{200d}👧", 0, 4), 2);
+		assert_eq!(grapheme_width("e\u{301}", 0, 4), 1);
+		assert_eq!(col_to_char("a👩\u{200d}👩\u{200d}👧b", 2, 4), 1);
+		assert_eq!(char_to_col("a👩\u{200d}👩\u{200d}👧b", 6, 4), 3);
+	}
+}
@@ -1,22 +1,31 @@
 use ropey::RopeSlice;
-use unicode_segmentation::UnicodeSegmentation;
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
 
 use crate::range::CharIdx;
 
 /// Returns whether `char_idx` is at a grapheme cluster boundary.
 ///
-/// Boundaries occur at the start/end of text and between grapheme clusters.
+/// Boundaries occur at the start/end of text and between extended grapheme
+/// clusters. Preceding text is consulted as needed, so emoji ZWJ sequences,
+/// skin-tone modifiers, and combining marks are never split.
 pub fn is_grapheme_boundary(text: RopeSlice, char_idx: CharIdx) -> bool {
-	if char_idx == 0 || char_idx == text.len_chars() {
+	if char_idx == 0 || char_idx >= text.len_chars() {
 		return true;
 	}
 
-	let start: CharIdx = char_idx.saturating_sub(1);
-	let end: CharIdx = (char_idx + 1).min(text.len_chars());
-	let chunk: String = text.slice(start..end).into();
-
-	let graphemes: Vec<&str> = chunk.graphemes(true).collect();
-	graphemes.len() > 1
+	let byte_idx = text.char_to_byte(char_idx);
+	let (chunk, chunk_byte_idx, _, _) = text.chunk_at_byte(byte_idx);
+	let mut cursor = GraphemeCursor::new(byte_idx, text.len_bytes(), true);
+	loop {
+		match cursor.is_boundary(chunk, chunk_byte_idx) {
+			Ok(is_boundary) => return is_boundary,
+			Err(GraphemeIncomplete::PreContext(n)) => {
+				let (ctx_chunk, ctx_byte_idx, _, _) = text.chunk_at_byte(n - 1);
+				cursor.provide_context(ctx_chunk, ctx_byte_idx);
+			}
+			Err(_) => unreachable!("is_boundary only requests pre-context"),
+		}
+	}
 }
 
 /// Returns the char index of the next grapheme cluster boundary after `char_idx`.
@@ -28,11 +37,24 @@ pub fn next_grapheme_boundary(text: RopeSlice, char_idx: CharIdx) -> CharIdx {
 		return len;
 	}
 
-	let mut idx: CharIdx = char_idx + 1;
-	while idx < len && !is_grapheme_boundary(text, idx) {
-		idx += 1;
+	let byte_idx = text.char_to_byte(char_idx);
+	let (mut chunk, mut chunk_byte_idx, _, _) = text.chunk_at_byte(byte_idx);
+	let mut cursor = GraphemeCursor::new(byte_idx, text.len_bytes(), true);
+	loop {
+		match cursor.next_boundary(chunk, chunk_byte_idx) {
+			Ok(None) => return len,
+			Ok(Some(n)) => return text.byte_to_char(n),
+			Err(GraphemeIncomplete::NextChunk) => {
+				chunk_byte_idx += chunk.len();
+				(chunk, _, _, _) = text.chunk_at_byte(chunk_byte_idx);
+			}
+			Err(GraphemeIncomplete::PreContext(n)) => {
+				let (ctx_chunk, ctx_byte_idx, _, _) = text.chunk_at_byte(n - 1);
+				cursor.provide_context(ctx_chunk, ctx_byte_idx);
+			}
+			Err(_) => unreachable!("next_boundary only requests next chunk or pre-context"),
+		}
 	}
-	idx
 }
 
 /// Returns the char index of the previous grapheme cluster boundary before `char_idx`.
@@ -43,11 +65,23 @@ pub fn prev_grapheme_boundary(text: RopeSlice, char_idx: CharIdx) -> CharIdx {
 		return 0;
 	}
 
-	let mut idx: CharIdx = char_idx - 1;
-	while idx > 0 && !is_grapheme_boundary(text, idx) {
-		idx -= 1;
+	let byte_idx = text.char_to_byte(char_idx.min(text.len_chars()));
+	let (mut chunk, mut chunk_byte_idx, _, _) = text.chunk_at_byte(byte_idx);
+	let mut cursor = GraphemeCursor::new(byte_idx, text.len_bytes(), true);
+	loop {
+		match cursor.prev_boundary(chunk, chunk_byte_idx) {
+			Ok(None) => return 0,
+			Ok(Some(n)) => return text.byte_to_char(n),
+			Err(GraphemeIncomplete::PrevChunk) => {
+				(chunk, chunk_byte_idx, _, _) = text.chunk_at_byte(chunk_byte_idx - 1);
+			}
+			Err(GraphemeIncomplete::PreContext(n)) => {
+				let (ctx_chunk, ctx_byte_idx, _, _) = text.chunk_at_byte(n - 1);
+				cursor.provide_context(ctx_chunk, ctx_byte_idx);
+			}
+			Err(_) => unreachable!("prev_boundary only requests prev chunk or pre-context"),
+		}
 	}
-	idx
 }
 
 /// Snaps `char_idx` to the next grapheme boundary if not already on one.
@@ -95,4 +129,39 @@ mod tests {
 		assert_eq!(next_grapheme_boundary(slice, 1), 2);
 		assert_eq!(next_grapheme_boundary(slice, 2), 3);
 	}
+
+	#[test]
+	fn combining_accent_is_one_cluster() {
+		let text = Rope::from("e\u{301}x");
+		let slice = text.slice(..);
+
+		assert!(!is_grapheme_boundary(slice, 1));
+		assert_eq!(next_grapheme_boundary(slice, 0), 2);
+		assert_eq!(prev_grapheme_boundary(slice, 2), 0);
+	}
+
+	#[test]
+	fn skin_tone_modifier_is_one_cluster() {
+		let text = Rope::from("a👍🏽b");
+		let slice = text.slice(..);
+
+		assert!(!is_grapheme_boundary(slice, 2));
+		assert_eq!(next_grapheme_boundary(slice, 1), 3);
+		assert_eq!(prev_grapheme_boundary(slice, 3), 1);
+	}
+
+	#[test]
+	fn zwj_sequence_is_one_cluster() {
+		// 👩 ZWJ 👩 ZWJ 👧 spans five chars.
+		let text = Rope::from("a👩\u{200d}👩\u{200d}👧b");
+		let slice = text.slice(..);
+
+		for idx in 2..6 {
+			assert!(!is_grapheme_boundary(slice, idx), "split at {idx}");
+		}
+		assert_eq!(next_grapheme_boundary(slice, 1), 6);
+		assert_eq!(prev_grapheme_boundary(slice, 6), 1);
+		assert_eq!(ensure_grapheme_boundary_prev(slice, 3), 1);
+		assert_eq!(ensure_grapheme_boundary_next(slice, 3), 6);
+	}
 }
@@ -48,6 +48,27 @@ impl Key {
 		}
 	}
 
+	/// Parses a key written as in keybinding configuration, such as
+	/// `ctrl-\` or `alt-enter`. Returns `None` for malformed keys.
+	pub fn parse(s: &str) -> Option<Self> {
+		use xeno_keymap_parser::Modifier;
+
+		let node = xeno_keymap_parser::parse(s).ok()?;
+		let code = match node.key {
+			KeyCode::Space => KeyCode::Char(' '),
+			code => code,
+		};
+		let has = |modifier: Modifier| node.modifiers & modifier as u8 != 0;
+		Some(Self {
+			code,
+			modifiers: Modifiers {
+				ctrl: has(Modifier::Ctrl),
+				alt: has(Modifier::Alt),
+				shift: has(Modifier::Shift),
+			},
+		})
+	}
+
 	/// Add Ctrl modifier.
 	pub const fn with_ctrl(self) -> Self {
 		Self {
@@ -183,17 +204,23 @@ impl From<termina::event::KeyEvent> for Key {
 	fn from(event: termina::event::KeyEvent) -> Self {
 		use termina::event::{KeyCode as TmKeyCode, Modifiers as TmModifiers};
 
-		let modifiers = Modifiers {
+		let mut modifiers = Modifiers {
 			ctrl: event.modifiers.contains(TmModifiers::CONTROL),
 			alt: event.modifiers.contains(TmModifiers::ALT),
 			shift: event.modifiers.contains(TmModifiers::SHIFT),
 		};
 
+		// Legacy terminals send shift-tab as its own key; the kitty protocol
+		// reports it as tab with shift. Both map to `shift-tab`.
+		if matches!(event.code, TmKeyCode::BackTab) {
+			modifiers.shift = true;
+		}
+
 		let code = match event.code {
 			TmKeyCode::Char(c) => KeyCode::Char(c),
 			TmKeyCode::Escape => KeyCode::Esc,
 			TmKeyCode::Enter => KeyCode::Enter,
-			TmKeyCode::Tab => KeyCode::Tab,
+			TmKeyCode::Tab | TmKeyCode::BackTab => KeyCode::Tab,
 			TmKeyCode::Backspace => KeyCode::Backspace,
 			TmKeyCode::Delete => KeyCode::Delete,
 			TmKeyCode::Insert => KeyCode::Insert,
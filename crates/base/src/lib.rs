@@ -1,5 +1,7 @@
 //! Core types for text editing: ranges, selections, transactions, and modes.
 
+/// Display column math for grapheme clusters and tabs.
+pub mod column;
 /// Directional types for navigation and layout operations.
 pub mod direction;
 /// Grapheme cluster boundary detection.
@@ -31,4 +31,16 @@ pub enum PendingKind {
 	ReplaceChar,
 	/// Select text object (`i`/`a` after operator).
 	Object(ObjectSelectionKind),
+	/// Insert a register's contents (`ctrl-r` in insert mode).
+	InsertRegister,
+	/// Insert the next key literally, or `u` and a codepoint (`ctrl-v` in
+	/// insert mode).
+	InsertLiteral,
+	/// Hex digits of a codepoint typed after `ctrl-v u`.
+	InsertCodepoint {
+		/// Value of the digits typed so far.
+		value: u32,
+		/// Number of digits typed so far.
+		digits: u8,
+	},
 }
@@ -127,7 +127,14 @@ impl Range {
 	}
 
 	/// Returns a range with positions aligned to grapheme boundaries.
+	///
+	/// Non-empty ranges grow outward to cover any partially selected cluster.
+	/// Cursors (empty ranges) snap back to the start of their cluster.
 	pub fn grapheme_aligned(self, text: RopeSlice) -> Self {
+		if self.is_empty() {
+			return Self::point(ensure_grapheme_boundary_prev(text, self.head));
+		}
+
 		let anchor = if self.anchor == 0 || self.anchor == text.len_chars() {
 			self.anchor
 		} else if self.direction() == Direction::Forward {
@@ -293,4 +300,12 @@ mod tests {
 		assert_eq!(aligned.anchor, 1);
 		assert_eq!(aligned.head, 3);
 	}
+
+	#[test]
+	fn test_grapheme_aligned_snaps_cursor_to_cluster_start() {
+		let text = Rope::from("a👍🏽b");
+		let slice = text.slice(..);
+		assert_eq!(Range::point(2).grapheme_aligned(slice), Range::point(1));
+		assert_eq!(Range::new(0, 2).grapheme_aligned(slice), Range::new(0, 3));
+	}
 }
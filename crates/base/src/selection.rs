@@ -117,20 +117,21 @@ impl Selection {
 		self.normalize();
 	}
 
-	/// Transforms all ranges using the given function, returning a new selection.
+	/// Applies `f` to every range and renormalizes, returning a new selection.
+	///
+	/// The primary stays with whichever range the old primary became; if that
+	/// range merges with others, the merged range is primary and keeps the
+	/// primary's direction.
 	pub fn transform<F>(&self, mut f: F) -> Self
 	where
-		F: FnMut(&Range) -> Range,
+		F: FnMut(Range) -> Range,
 	{
-		let primary = f(&self.primary());
-		let others = self
-			.ranges
-			.iter()
-			.enumerate()
-			.filter(|&(i, _)| i != self.primary_index)
-			.map(|(_, r)| f(r));
-
-		Self::new(primary, others)
+		let mut sel = Self {
+			ranges: self.ranges.iter().map(|&r| f(r)).collect(),
+			primary_index: self.primary_index,
+		};
+		sel.normalize();
+		sel
 	}
 
 	/// Transforms all ranges in place using the given function.
@@ -144,6 +145,14 @@ impl Selection {
 		self.normalize();
 	}
 
+	/// Returns this selection with overlapping and touching ranges unified.
+	///
+	/// See [`merge_overlaps_and_adjacent`](Self::merge_overlaps_and_adjacent).
+	pub fn merge_overlapping(mut self) -> Self {
+		self.merge_overlaps_and_adjacent();
+		self
+	}
+
 	/// Merge overlapping AND adjacent ranges.
 	///
 	/// Unlike `normalize()` (which only merges overlapping ranges), this also
@@ -153,36 +162,7 @@ impl Selection {
 	/// Use this when you want to combine all contiguous selections into
 	/// single ranges (e.g., for visual selection operations).
 	pub fn merge_overlaps_and_adjacent(&mut self) {
-		if self.ranges.len() <= 1 {
-			return;
-		}
-
-		let primary = self.ranges[self.primary_index];
-		self.ranges.sort_by_key(|r: &Range| r.min());
-
-		let mut merged: SmallVec<[Range; 1]> = SmallVec::new();
-		let mut primary_index = 0;
-
-		for range in &self.ranges {
-			if let Some(last) = merged.last_mut()
-				&& (last.overlaps(range) || last.max() == range.min())
-			{
-				let old_last = *last;
-				*last = last.merge(range);
-				if *range == primary || old_last == primary || last.contains(primary.min()) {
-					primary_index = merged.len() - 1;
-				}
-				continue;
-			}
-
-			if *range == primary {
-				primary_index = merged.len();
-			}
-			merged.push(*range);
-		}
-
-		self.ranges = merged;
-		self.primary_index = primary_index.min(self.ranges.len().saturating_sub(1));
+		self.merge_where(|last, range| last.overlaps(range) || last.max() == range.min());
 	}
 
 	/// Normalize the selection by sorting ranges and merging overlaps.
@@ -193,37 +173,47 @@ impl Selection {
 	///
 	/// If you want to also merge adjacent ranges, use `merge_overlaps_and_adjacent()`.
 	fn normalize(&mut self) {
+		self.merge_where(Range::overlaps);
+	}
+
+	/// Sorts ranges by start and folds each into its predecessor when
+	/// `should_merge(predecessor, range)` holds, tracking the primary by
+	/// position rather than by value.
+	fn merge_where(&mut self, should_merge: impl Fn(&Range, &Range) -> bool) {
 		if self.ranges.len() <= 1 {
+			self.primary_index = 0;
 			return;
 		}
 
-		let primary = self.ranges[self.primary_index];
-
-		self.ranges.sort_by_key(|r: &Range| r.min());
+		let mut indexed: SmallVec<[(Range, bool); 1]> = self
+			.ranges
+			.iter()
+			.enumerate()
+			.map(|(i, &r)| (r, i == self.primary_index))
+			.collect();
+		indexed.sort_by_key(|(r, _)| (r.min(), r.max()));
 
-		let mut merged: SmallVec<[Range; 1]> = SmallVec::new();
+		let mut merged: SmallVec<[Range; 1]> = SmallVec::with_capacity(indexed.len());
 		let mut primary_index = 0;
 
-		for range in &self.ranges {
-			if let Some(last) = merged.last_mut()
-				&& last.overlaps(range)
-			{
-				let old_last = *last;
-				*last = last.merge(range);
-				if *range == primary || old_last == primary || last.contains(primary.min()) {
-					primary_index = merged.len() - 1;
+		for (range, is_primary) in indexed {
+			match merged.last_mut() {
+				Some(last) if should_merge(last, &range) => {
+					*last = if is_primary {
+						range.merge(last)
+					} else {
+						last.merge(&range)
+					};
 				}
-				continue;
+				_ => merged.push(range),
 			}
-
-			if *range == primary {
-				primary_index = merged.len();
+			if is_primary {
+				primary_index = merged.len() - 1;
 			}
-			merged.push(*range);
 		}
 
 		self.ranges = merged;
-		self.primary_index = primary_index.min(self.ranges.len().saturating_sub(1));
+		self.primary_index = primary_index;
 	}
 
 	/// Returns a new selection with all ranges aligned to grapheme boundaries.
@@ -241,7 +231,19 @@ impl Selection {
 
 	/// Returns true if any range contains the given position.
 	pub fn contains(&self, pos: CharIdx) -> bool {
-		self.ranges.iter().any(|r: &Range| r.contains(pos))
+		self.range_at(pos).is_some()
+	}
+
+	/// Returns the index of the range containing `pos`, if any.
+	///
+	/// Uses binary search: normalized ranges are sorted and non-overlapping,
+	/// so their ends are sorted too.
+	pub fn range_at(&self, pos: CharIdx) -> Option<usize> {
+		let idx = self.ranges.partition_point(|r| r.max() <= pos);
+		self.ranges
+			.get(idx)
+			.is_some_and(|r| r.contains(pos))
+			.then_some(idx)
 	}
 
 	/// Returns the direction of the primary range.
@@ -278,6 +280,19 @@ impl Selection {
 		}
 		self.normalize();
 	}
+
+	/// Clamps all ranges to the bounds of `text` and snaps their ends to
+	/// grapheme cluster boundaries.
+	///
+	/// Use this after edits that did not map the selection, such as content
+	/// replaced wholesale. Returns whether any range changed.
+	pub fn clamp_to(&mut self, text: RopeSlice) -> bool {
+		let max_char = text.len_chars();
+		let clamped = self.transform(|r| r.clamp(max_char).grapheme_aligned(text));
+		let changed = clamped != *self;
+		*self = clamped;
+		changed
+	}
 }
 
 impl Default for Selection {
@@ -387,4 +402,116 @@ mod tests {
 		assert!(sel.contains(7));
 		assert!(!sel.contains(10));
 	}
+
+	#[test]
+	fn test_range_at() {
+		let sel = Selection::new(Range::new(10, 15), vec![Range::new(0, 5), Range::point(20)]);
+		assert_eq!(sel.range_at(0), Some(0));
+		assert_eq!(sel.range_at(4), Some(0));
+		assert_eq!(sel.range_at(5), None);
+		assert_eq!(sel.range_at(12), Some(1));
+		assert_eq!(sel.range_at(20), None);
+	}
+
+	#[test]
+	fn test_transform_merge_keeps_primary_direction() {
+		let sel = Selection::new(Range::new(8, 4), vec![Range::new(0, 5)]);
+		let merged = sel.transform(|r| r);
+		assert_eq!(merged.len(), 1);
+		assert_eq!(merged.primary(), Range::new(8, 0));
+	}
+
+	#[test]
+	fn test_merge_overlapping_unifies_touching() {
+		let sel = Selection::new(
+			Range::new(5, 10),
+			vec![Range::new(0, 5), Range::new(12, 14)],
+		);
+		let merged = sel.merge_overlapping();
+		assert_eq!(merged.ranges(), &[Range::new(0, 10), Range::new(12, 14)]);
+		assert_eq!(merged.primary_index(), 0);
+	}
+
+	struct Lcg(u64);
+
+	impl Lcg {
+		fn below(&mut self, bound: usize) -> usize {
+			self.0 = self
+				.0
+				.wrapping_mul(6364136223846793005)
+				.wrapping_add(1442695040888963407);
+			((self.0 >> 33) as usize) % bound.max(1)
+		}
+
+		fn range(&mut self) -> Range {
+			let anchor = self.below(100);
+			let head = if self.below(3) == 0 {
+				anchor
+			} else {
+				self.below(100)
+			};
+			Range::new(anchor, head)
+		}
+	}
+
+	#[test]
+	fn test_clamp_to_bounds_and_graphemes() {
+		let text = ropey::Rope::from("ae\u{301}x");
+		let mut sel = Selection::new(Range::new(2, 1), vec![Range::new(3, 9), Range::point(40)]);
+		assert!(sel.clamp_to(text.slice(..)));
+		assert_eq!(
+			sel.ranges(),
+			&[Range::new(3, 1), Range::new(3, 4), Range::point(4)]
+		);
+		assert_eq!(sel.primary(), Range::new(3, 1));
+
+		assert!(!sel.clamp_to(text.slice(..)));
+	}
+
+	fn assert_normalized(sel: &Selection) {
+		let ranges = sel.ranges();
+		assert!(!ranges.is_empty());
+		assert!(
+			sel.primary_index() < ranges.len(),
+			"primary out of bounds: {sel:?}"
+		);
+		for pair in ranges.windows(2) {
+			assert!(pair[0].min() <= pair[1].min(), "unsorted: {sel:?}");
+			assert!(!pair[0].overlaps(&pair[1]), "overlapping: {sel:?}");
+		}
+	}
+
+	#[test]
+	fn normalization_invariants_hold_after_random_transforms() {
+		let mut rng = Lcg(0x5e1e_c7);
+		for _ in 0..500 {
+			let count = 1 + rng.below(8);
+			let ranges: Vec<Range> = (0..count).map(|_| rng.range()).collect();
+			let primary = ranges[rng.below(count)];
+			let sel = Selection::from_vec(ranges, rng.below(count));
+			assert_normalized(&sel);
+
+			let shift = rng.below(20);
+			let shifted = sel.transform(|r| Range::new(r.anchor + shift, r.head / 2 + shift));
+			assert_normalized(&shifted);
+
+			let collapsed = sel.transform(|r| Range::point(r.head / 3));
+			assert_normalized(&collapsed);
+
+			let merged = sel.clone().merge_overlapping();
+			assert_normalized(&merged);
+			for pair in merged.ranges().windows(2) {
+				assert!(pair[0].max() < pair[1].min(), "touching: {merged:?}");
+			}
+
+			let mut pushed = sel.clone();
+			pushed.push(primary);
+			assert_normalized(&pushed);
+
+			for pos in 0..120 {
+				let linear = sel.ranges().iter().position(|r| r.contains(pos));
+				assert_eq!(sel.range_at(pos), linear, "range_at({pos}) in {sel:?}");
+			}
+		}
+	}
 }
@@ -0,0 +1,225 @@
+//! Minimal rope diffs for whole-buffer replacements.
+//!
+//! Lines are diffed first with Myers' algorithm, then each changed hunk is
+//! refined char by char, so positions mapped through the result move only
+//! where text actually changed. Both passes give up past a fixed edit budget
+//! and replace the remaining span wholesale, keeping pathological inputs
+//! bounded in time and memory.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use super::types::Change;
+use crate::Rope;
+
+/// Maximum line edits explored before a hunk is replaced wholesale.
+pub(super) const MAX_LINE_EDITS: usize = 1024;
+
+/// Maximum char edits explored while refining a single line hunk.
+const MAX_CHAR_EDITS: usize = 256;
+
+/// A changed region: `old` in the source is replaced by `new` in the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Hunk {
+	pub(super) old: Range<usize>,
+	pub(super) new: Range<usize>,
+}
+
+/// Computes sorted, non-overlapping changes that turn `old` into `new`.
+pub(super) fn diff(old: &Rope, new: &Rope) -> Vec<Change> {
+	let old_lines: Vec<Cow<'_, str>> = old.lines().map(Cow::from).collect();
+	let new_lines: Vec<Cow<'_, str>> = new.lines().map(Cow::from).collect();
+
+	let mut changes = Vec::new();
+	for hunk in diff_slices(&old_lines, &new_lines, MAX_LINE_EDITS) {
+		let old_start = old.line_to_char(hunk.old.start);
+		let old_end = old.line_to_char(hunk.old.end);
+		let new_start = new.line_to_char(hunk.new.start);
+		let new_end = new.line_to_char(hunk.new.end);
+
+		let a: Vec<char> = old.slice(old_start..old_end).chars().collect();
+		let b: Vec<char> = new.slice(new_start..new_end).chars().collect();
+		for refined in diff_slices(&a, &b, MAX_CHAR_EDITS) {
+			changes.push(Change {
+				start: old_start + refined.old.start,
+				end: old_start + refined.old.end,
+				replacement: (!refined.new.is_empty()).then(|| b[refined.new].iter().collect()),
+			});
+		}
+	}
+	changes
+}
+
+/// Diffs two sequences, trimming their common prefix and suffix before
+/// running Myers on the remainder.
+pub(super) fn diff_slices<T: PartialEq>(a: &[T], b: &[T], max_edits: usize) -> Vec<Hunk> {
+	let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+	let suffix = a[prefix..]
+		.iter()
+		.rev()
+		.zip(b[prefix..].iter().rev())
+		.take_while(|(x, y)| x == y)
+		.count();
+	let a_mid = &a[prefix..a.len() - suffix];
+	let b_mid = &b[prefix..b.len() - suffix];
+
+	if a_mid.is_empty() && b_mid.is_empty() {
+		return Vec::new();
+	}
+
+	let hunks = if a_mid.is_empty() || b_mid.is_empty() {
+		None
+	} else {
+		myers(a_mid, b_mid, max_edits)
+	};
+	let hunks = hunks.unwrap_or_else(|| {
+		vec![Hunk {
+			old: 0..a_mid.len(),
+			new: 0..b_mid.len(),
+		}]
+	});
+
+	hunks
+		.into_iter()
+		.map(|h| Hunk {
+			old: h.old.start + prefix..h.old.end + prefix,
+			new: h.new.start + prefix..h.new.end + prefix,
+		})
+		.collect()
+}
+
+/// Runs Myers' O(ND) diff, returning the changed hunks in order, or `None`
+/// if the edit distance exceeds `max_edits`.
+fn myers<T: PartialEq>(a: &[T], b: &[T], max_edits: usize) -> Option<Vec<Hunk>> {
+	let n = a.len() as isize;
+	let m = b.len() as isize;
+	let limit = (a.len() + b.len()).min(max_edits) as isize;
+	let offset = limit + 1;
+	let mut v = vec![0isize; 2 * limit as usize + 3];
+	// trace[d] holds v[-d..=d] as it stood before round d.
+	let mut trace: Vec<Vec<isize>> = Vec::new();
+
+	let idx = |k: isize| (k + offset) as usize;
+
+	for d in 0..=limit {
+		trace.push(v[idx(-d)..=idx(d)].to_vec());
+		for k in (-d..=d).step_by(2) {
+			let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+				v[idx(k + 1)]
+			} else {
+				v[idx(k - 1)] + 1
+			};
+			let mut y = x - k;
+			while x < n && y < m && a[x as usize] == b[y as usize] {
+				x += 1;
+				y += 1;
+			}
+			v[idx(k)] = x;
+			if x >= n && y >= m {
+				return Some(backtrack(&trace, n, m));
+			}
+		}
+	}
+	None
+}
+
+/// Walks the Myers trace back from `(n, m)`, collecting changed hunks.
+fn backtrack(trace: &[Vec<isize>], n: isize, m: isize) -> Vec<Hunk> {
+	let mut hunks: Vec<Hunk> = Vec::new();
+	let (mut x, mut y) = (n, m);
+
+	for d in (1..trace.len() as isize).rev() {
+		let v = &trace[d as usize];
+		let at = |k: isize| v[(k + d) as usize];
+		let k = x - y;
+		let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+			k + 1
+		} else {
+			k - 1
+		};
+		let prev_x = at(prev_k);
+		let prev_y = prev_x - prev_k;
+
+		// Skip the snake of equal elements back to the edit.
+		let snake = (x - prev_x).min(y - prev_y);
+		x -= snake;
+		y -= snake;
+
+		let (old, new) = if x == prev_x {
+			(x as usize..x as usize, prev_y as usize..y as usize)
+		} else {
+			(prev_x as usize..x as usize, y as usize..y as usize)
+		};
+		match hunks.last_mut() {
+			Some(last) if last.old.start == old.end && last.new.start == new.end => {
+				last.old.start = old.start;
+				last.new.start = new.start;
+			}
+			_ => hunks.push(Hunk { old, new }),
+		}
+
+		x = prev_x;
+		y = prev_y;
+	}
+
+	hunks.reverse();
+	hunks
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hunks(a: &str, b: &str) -> Vec<Hunk> {
+		let a: Vec<char> = a.chars().collect();
+		let b: Vec<char> = b.chars().collect();
+		diff_slices(&a, &b, MAX_CHAR_EDITS)
+	}
+
+	#[test]
+	fn identical_inputs_have_no_hunks() {
+		assert!(hunks("same", "same").is_empty());
+	}
+
+	#[test]
+	fn adjacent_edits_coalesce() {
+		assert_eq!(
+			hunks("abcdef", "abXYef"),
+			vec![Hunk {
+				old: 2..4,
+				new: 2..4
+			}]
+		);
+	}
+
+	#[test]
+	fn separate_edits_stay_separate() {
+		assert_eq!(
+			hunks("axbxc", "aybyc"),
+			vec![
+				Hunk {
+					old: 1..2,
+					new: 1..2
+				},
+				Hunk {
+					old: 3..4,
+					new: 3..4
+				},
+			]
+		);
+	}
+
+	#[test]
+	fn edit_budget_falls_back_to_replace() {
+		let a: Vec<usize> = (0..100).collect();
+		let b: Vec<usize> = (100..200).collect();
+		assert_eq!(myers(&a, &b, 10), None);
+		assert_eq!(
+			diff_slices(&a, &b, 10),
+			vec![Hunk {
+				old: 0..100,
+				new: 0..100
+			}]
+		);
+	}
+}
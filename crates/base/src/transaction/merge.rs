@@ -0,0 +1,179 @@
+//! Three-way line merges against a common base.
+//!
+//! Both sides are diffed against the base by line. Hunks from either side
+//! that overlap or touch are grouped; a group changed on one side only takes
+//! that side, a group changed identically on both takes either, and anything
+//! else is written out between conflict markers.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use super::diff::{Hunk, MAX_LINE_EDITS, diff_slices};
+use crate::Rope;
+
+/// Marker opening the buffer's half of a conflict.
+const OURS_MARKER: &str = "<<<<<<< buffer\n";
+/// Marker separating the two halves of a conflict.
+const SEPARATOR_MARKER: &str = "=======\n";
+/// Marker closing the disk's half of a conflict.
+const THEIRS_MARKER: &str = ">>>>>>> disk\n";
+
+/// Result of a three-way merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge {
+	/// Merged text, with conflicting regions wrapped in markers.
+	pub text: String,
+	/// Number of conflicting regions in `text`.
+	pub conflicts: usize,
+}
+
+/// Merges `ours` and `theirs`, both derived from `base`.
+///
+/// Non-conflicting changes from both sides are applied; overlapping changes
+/// that differ are kept side by side between `<<<<<<< buffer`, `=======`
+/// and `>>>>>>> disk` markers.
+pub fn merge3(base: &Rope, ours: &Rope, theirs: &Rope) -> Merge {
+	let base_lines = lines(base);
+	let ours_lines = lines(ours);
+	let theirs_lines = lines(theirs);
+
+	let ours_hunks = diff_slices(&base_lines, &ours_lines, MAX_LINE_EDITS);
+	let theirs_hunks = diff_slices(&base_lines, &theirs_lines, MAX_LINE_EDITS);
+
+	let mut out = String::new();
+	let mut conflicts = 0;
+	let mut pos = 0;
+	let (mut i, mut j) = (0, 0);
+
+	while i < ours_hunks.len() || j < theirs_hunks.len() {
+		let take_ours = match (ours_hunks.get(i), theirs_hunks.get(j)) {
+			(Some(a), Some(b)) => a.old.start <= b.old.start,
+			(Some(_), None) => true,
+			_ => false,
+		};
+		let first = if take_ours {
+			&ours_hunks[i]
+		} else {
+			&theirs_hunks[j]
+		};
+		let mut span = first.old.clone();
+		let (ours_from, theirs_from) = (i, j);
+
+		loop {
+			if let Some(h) = ours_hunks.get(i)
+				&& h.old.start <= span.end
+			{
+				span.end = span.end.max(h.old.end);
+				i += 1;
+			} else if let Some(h) = theirs_hunks.get(j)
+				&& h.old.start <= span.end
+			{
+				span.end = span.end.max(h.old.end);
+				j += 1;
+			} else {
+				break;
+			}
+		}
+
+		push_lines(&mut out, &base_lines[pos..span.start]);
+		let ours_group = &ours_hunks[ours_from..i];
+		let theirs_group = &theirs_hunks[theirs_from..j];
+		let ours_text = &ours_lines[side_range(ours_group, &span)];
+		let theirs_text = &theirs_lines[side_range(theirs_group, &span)];
+
+		if theirs_group.is_empty() || ours_text == theirs_text {
+			push_lines(&mut out, ours_text);
+		} else if ours_group.is_empty() {
+			push_lines(&mut out, theirs_text);
+		} else {
+			conflicts += 1;
+			push_marker(&mut out, OURS_MARKER);
+			push_lines(&mut out, ours_text);
+			push_marker(&mut out, SEPARATOR_MARKER);
+			push_lines(&mut out, theirs_text);
+			push_marker(&mut out, THEIRS_MARKER);
+		}
+		pos = span.end;
+	}
+	push_lines(&mut out, &base_lines[pos..]);
+
+	Merge {
+		text: out,
+		conflicts,
+	}
+}
+
+/// Splits a rope into lines, each keeping its line ending.
+fn lines(text: &Rope) -> Vec<Cow<'_, str>> {
+	text.lines().map(Cow::from).collect()
+}
+
+/// Maps the base `span` of a group onto the side its `hunks` came from.
+///
+/// A side with no hunks in the group matches the base there.
+fn side_range(hunks: &[Hunk], span: &Range<usize>) -> Range<usize> {
+	match (hunks.first(), hunks.last()) {
+		(Some(first), Some(last)) => {
+			(first.new.start - (first.old.start - span.start))
+				..(last.new.end + (span.end - last.old.end))
+		}
+		_ => span.clone(),
+	}
+}
+
+/// Appends whole lines to `out`.
+fn push_lines(out: &mut String, lines: &[Cow<'_, str>]) {
+	for line in lines {
+		out.push_str(line);
+	}
+}
+
+/// Appends a conflict marker on a line of its own.
+fn push_marker(out: &mut String, marker: &str) {
+	if !out.is_empty() && !out.ends_with('\n') {
+		out.push('\n');
+	}
+	out.push_str(marker);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn merge(base: &str, ours: &str, theirs: &str) -> Merge {
+		merge3(&Rope::from(base), &Rope::from(ours), &Rope::from(theirs))
+	}
+
+	#[test]
+	fn separate_changes_merge_cleanly() {
+		let merged = merge("a\nb\nc\nd\n", "A\nb\nc\nd\n", "a\nb\nc\nD\n");
+		assert_eq!(merged.text, "A\nb\nc\nD\n");
+		assert_eq!(merged.conflicts, 0);
+	}
+
+	#[test]
+	fn identical_changes_merge_once() {
+		let merged = merge("a\nb\nc\n", "a\nX\nc\n", "a\nX\nc\n");
+		assert_eq!(merged.text, "a\nX\nc\n");
+		assert_eq!(merged.conflicts, 0);
+	}
+
+	#[test]
+	fn overlapping_changes_get_markers() {
+		let merged = merge("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+		assert_eq!(
+			merged.text,
+			"a\n<<<<<<< buffer\nours\n=======\ntheirs\n>>>>>>> disk\nc\n"
+		);
+		assert_eq!(merged.conflicts, 1);
+	}
+
+	#[test]
+	fn markers_start_on_their_own_line() {
+		let merged = merge("a\nb", "a\nours", "a\ntheirs");
+		assert_eq!(
+			merged.text,
+			"a\n<<<<<<< buffer\nours\n=======\ntheirs\n>>>>>>> disk\n"
+		);
+	}
+}
@@ -1,11 +1,16 @@
 /// Text change set implementation.
 mod changeset;
+/// Minimal diffs between ropes.
+mod diff;
+/// Three-way line merges.
+mod merge;
 #[cfg(test)]
 mod tests;
 /// Transaction primitive types.
 mod types;
 
 pub use changeset::ChangeSet;
+pub use merge::{Merge, merge3};
 pub use types::{Bias, Change, Insertion, Operation, Tendril};
 
 use crate::range::CharIdx;
@@ -16,7 +21,7 @@ use crate::{Range, Rope, RopeSlice, Selection};
 /// Transaction wraps a [`ChangeSet`] with an optional [`Selection`], providing
 /// a high-level API for common editing operations like insert, delete, and change.
 /// Transactions can be inverted for undo/redo and composed for efficient batching.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Transaction {
 	/// The underlying set of document changes.
 	changes: ChangeSet,
@@ -90,6 +95,23 @@ impl Transaction {
 		}
 	}
 
+	/// Creates a transaction turning `old` into `new` with minimal changes.
+	///
+	/// Lines are diffed first, then changed hunks are refined per char, so
+	/// positions mapped through the result stay put wherever the text did not
+	/// change. Inputs too dissimilar to diff cheaply fall back to replacing the
+	/// differing span wholesale.
+	///
+	/// # Parameters
+	/// - `old`: The current document content
+	/// - `new`: The desired document content
+	///
+	/// # Returns
+	/// A new [`Transaction`] that applied to `old` yields `new`.
+	pub fn diff(old: &Rope, new: &Rope) -> Self {
+		Self::change(old.slice(..), diff::diff(old, new))
+	}
+
 	/// Creates a transaction that inserts text at each selection range.
 	///
 	/// For each range in the selection, replaces the range `[min, max)` with the
@@ -190,6 +212,30 @@ impl Transaction {
 		}
 	}
 
+	/// Composes this transaction with one applied after it.
+	///
+	/// The resulting transaction is equivalent to applying `self` followed by
+	/// `other`. The selection of `other` is kept, if any.
+	///
+	/// # Parameters
+	/// - `other`: The transaction to apply after this one
+	///
+	/// # Returns
+	/// A new [`Transaction`] combining both sets of changes.
+	pub fn compose(self, other: Transaction) -> Self {
+		Self {
+			changes: self.changes.compose(other.changes),
+			selection: other.selection.or(self.selection),
+		}
+	}
+
+	/// Returns true if applying this transaction leaves the document unchanged.
+	pub fn is_identity(&self) -> bool {
+		self.operations()
+			.iter()
+			.all(|op| matches!(op, Operation::Retain(_)))
+	}
+
 	/// Maps a selection through this transaction's changes.
 	///
 	/// Transforms each range in the selection by mapping its anchor and head
@@ -1,6 +1,6 @@
 use super::Transaction;
 use super::changeset::ChangeSet;
-use super::types::Change;
+use super::types::{Bias, Change, Operation};
 use crate::{Rope, Selection};
 
 #[test]
@@ -90,3 +90,120 @@ fn test_map_selection() {
 	assert_eq!(mapped.primary().anchor, 9);
 	assert_eq!(mapped.primary().head, 14);
 }
+
+#[test]
+fn test_transaction_invert_roundtrip() {
+	let original = Rope::from("hello world");
+	let mut doc = original.clone();
+	let tx = Transaction::change(
+		doc.slice(..),
+		vec![
+			Change {
+				start: 0,
+				end: 5,
+				replacement: Some("goodbye".into()),
+			},
+			Change {
+				start: 6,
+				end: 11,
+				replacement: None,
+			},
+		],
+	);
+	let inverse = tx.invert(&doc);
+	tx.apply(&mut doc);
+	assert_eq!(doc.to_string(), "goodbye ");
+	inverse.apply(&mut doc);
+	assert_eq!(doc, original);
+}
+
+#[test]
+fn test_transaction_compose_inverses() {
+	let original = Rope::from("abc");
+	let mut doc = original.clone();
+
+	let tx1 = Transaction::insert(doc.slice(..), &Selection::single(3, 3), "d".into());
+	let inv1 = tx1.invert(&doc);
+	tx1.apply(&mut doc);
+
+	let tx2 = Transaction::delete(doc.slice(..), &Selection::single(0, 1));
+	let inv2 = tx2.invert(&doc);
+	tx2.apply(&mut doc);
+	assert_eq!(doc.to_string(), "bcd");
+
+	let undo = inv2.compose(inv1);
+	undo.apply(&mut doc);
+	assert_eq!(doc, original);
+}
+
+#[test]
+fn test_transaction_is_identity() {
+	let doc = Rope::from("abc");
+	assert!(Transaction::change(doc.slice(..), Vec::new()).is_identity());
+	assert!(!Transaction::delete(doc.slice(..), &Selection::single(0, 1)).is_identity());
+}
+
+#[test]
+fn test_diff_roundtrip() {
+	let cases = [
+		("", "hello\n"),
+		("hello\n", ""),
+		("a\nb\nc\n", "a\nB\nc\n"),
+		("one\ntwo\nthree\n", "zero\none\nthree\nfour\n"),
+		("fn main() {}\n", "fn main() {\n\tprintln!();\n}\n"),
+		("no newline", "no newline\n"),
+	];
+	for (old, new) in cases {
+		let old = Rope::from(old);
+		let new = Rope::from(new);
+		let mut doc = old.clone();
+		Transaction::diff(&old, &new).apply(&mut doc);
+		assert_eq!(doc, new);
+	}
+}
+
+#[test]
+fn test_diff_refines_within_line() {
+	let old = Rope::from("let value = 1;\nlet other = 2;\n");
+	let new = Rope::from("let value = 42;\nlet other = 2;\n");
+	let tx = Transaction::diff(&old, &new);
+
+	// Cursor on "value" and on the next line stay in place.
+	assert_eq!(tx.changes().map_pos(5, Bias::Left), 5);
+	assert_eq!(tx.changes().map_pos(19, Bias::Left), 20);
+	let changed = tx
+		.operations()
+		.iter()
+		.filter(|op| !matches!(op, Operation::Retain(_)))
+		.count();
+	assert_eq!(changed, 2);
+}
+
+#[test]
+fn test_diff_large_file_single_line_change() {
+	let lines: Vec<String> = (0..10_000).map(|i| format!("line {i}\n")).collect();
+	let old = Rope::from(lines.concat());
+	let mut edited = lines.clone();
+	edited[5_000] = "line five thousand\n".into();
+	let new = Rope::from(edited.concat());
+
+	let tx = Transaction::diff(&old, &new);
+	let mut doc = old.clone();
+	tx.apply(&mut doc);
+	assert_eq!(doc, new);
+
+	let end = old.line_to_char(9_999);
+	assert_eq!(
+		tx.changes().map_pos(end, Bias::Left),
+		new.line_to_char(9_999)
+	);
+}
+
+#[test]
+fn test_diff_unrelated_content_falls_back() {
+	let old = Rope::from((0..5_000).map(|i| format!("old {i}\n")).collect::<String>());
+	let new = Rope::from((0..5_000).map(|i| format!("{i} new\n")).collect::<String>());
+	let mut doc = old.clone();
+	Transaction::diff(&old, &new).apply(&mut doc);
+	assert_eq!(doc, new);
+}
@@ -125,6 +125,18 @@ pub fn get_color_field(doc: &KdlDocument, name: &str, ctx: &ParseContext) -> Res
 	ctx.resolve_color(value)
 }
 
+/// Get an optional color field from a KDL document.
+pub fn get_optional_color_field(
+	doc: &KdlDocument,
+	name: &str,
+	ctx: &ParseContext,
+) -> Result<Option<Color>> {
+	doc.get_arg(name)
+		.and_then(|v| v.as_string())
+		.map(|value| ctx.resolve_color(value))
+		.transpose()
+}
+
 /// Parse a palette block into the context.
 pub fn parse_palette(node: &KdlNode, ctx: &mut ParseContext) -> Result<()> {
 	let Some(children) = node.children() else {
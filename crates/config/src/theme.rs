@@ -5,13 +5,15 @@
 use std::path::PathBuf;
 
 use kdl::{KdlDocument, KdlNode};
+use xeno_registry::themes::{Color, SyntaxStyle, SyntaxStyles, blend_colors};
 pub use xeno_registry::themes::{
-	NotificationColors, PopupColors, StatusColors, ThemeColors, ThemeVariant, UiColors,
+	DiffColors, NotificationColors, PopupColors, StatusColors, ThemeColors, ThemeVariant, UiColors,
 };
-use xeno_registry::themes::{SyntaxStyle, SyntaxStyles};
 
 use crate::error::{ConfigError, Result};
-use crate::kdl_util::{ParseContext, get_color_field, parse_modifier, parse_palette};
+use crate::kdl_util::{
+	ParseContext, get_color_field, get_optional_color_field, parse_modifier, parse_palette,
+};
 
 /// A parsed theme with owned data suitable for runtime use.
 #[derive(Debug, Clone)]
@@ -78,7 +80,8 @@ pub fn parse_standalone_theme(input: &str) -> Result<ParsedTheme> {
 
 	let ui = parse_ui_colors(doc.get("ui"), &ctx)?;
 	let status = parse_status_colors(doc.get("status"), &ctx)?;
-	let popup = parse_popup_colors(doc.get("popup"), &ctx)?;
+	let popup = parse_popup_colors(doc.get("popup"), &ui, &ctx)?;
+	let diff = parse_diff_colors(doc.get("diff"), &ui, &status, &ctx)?;
 	let syntax = parse_syntax_styles(doc.get("syntax"), &ctx)?;
 
 	Ok(ParsedTheme {
@@ -90,6 +93,7 @@ pub fn parse_standalone_theme(input: &str) -> Result<ParsedTheme> {
 			status,
 			popup,
 			notification: NotificationColors::INHERITED,
+			diff,
 			syntax,
 		},
 		source_path: None,
@@ -133,7 +137,8 @@ pub fn parse_theme_node(node: &KdlNode) -> Result<ParsedTheme> {
 
 	let ui = parse_ui_colors(children.get("ui"), &ctx)?;
 	let status = parse_status_colors(children.get("status"), &ctx)?;
-	let popup = parse_popup_colors(children.get("popup"), &ctx)?;
+	let popup = parse_popup_colors(children.get("popup"), &ui, &ctx)?;
+	let diff = parse_diff_colors(children.get("diff"), &ui, &status, &ctx)?;
 	let syntax = parse_syntax_styles(children.get("syntax"), &ctx)?;
 
 	Ok(ParsedTheme {
@@ -145,6 +150,7 @@ pub fn parse_theme_node(node: &KdlNode) -> Result<ParsedTheme> {
 			status,
 			popup,
 			notification: NotificationColors::INHERITED,
+			diff,
 			syntax,
 		},
 		source_path: None,
@@ -161,23 +167,42 @@ fn parse_variant(s: &str) -> Result<ThemeVariant> {
 }
 
 /// Parses UI colors from a KDL node.
+///
+/// `cursorline-bg` is optional and defaults to `bg` tinted slightly toward `fg`.
+/// `search-match-bg` is optional and defaults to `bg` tinted toward `selection-bg`.
+/// `document-highlight-bg` and `document-highlight-write-bg` are optional and
+/// default to `bg` tinted toward `fg` and toward `selection-bg`.
 fn parse_ui_colors(node: Option<&KdlNode>, ctx: &ParseContext) -> Result<UiColors> {
 	let node = node.ok_or_else(|| ConfigError::MissingField("ui".into()))?;
 	let children = node
 		.children()
 		.ok_or_else(|| ConfigError::MissingField("ui".into()))?;
 
+	let bg = get_color_field(children, "bg", ctx)?;
+	let fg = get_color_field(children, "fg", ctx)?;
+	let selection_bg = get_color_field(children, "selection-bg", ctx)?;
 	Ok(UiColors {
-		bg: get_color_field(children, "bg", ctx)?,
-		fg: get_color_field(children, "fg", ctx)?,
+		bg,
+		fg,
 		gutter_fg: get_color_field(children, "gutter-fg", ctx)?,
 		cursor_bg: get_color_field(children, "cursor-bg", ctx)?,
 		cursor_fg: get_color_field(children, "cursor-fg", ctx)?,
-		cursorline_bg: get_color_field(children, "cursorline-bg", ctx)?,
-		selection_bg: get_color_field(children, "selection-bg", ctx)?,
+		cursorline_bg: get_optional_color_field(children, "cursorline-bg", ctx)?
+			.unwrap_or_else(|| blend_colors(fg, bg, 0.08)),
+		selection_bg,
 		selection_fg: get_color_field(children, "selection-fg", ctx)?,
 		message_fg: get_color_field(children, "message-fg", ctx)?,
 		command_input_fg: get_color_field(children, "command-input-fg", ctx)?,
+		search_match_bg: get_optional_color_field(children, "search-match-bg", ctx)?
+			.unwrap_or_else(|| blend_colors(selection_bg, bg, 0.4)),
+		document_highlight_bg: get_optional_color_field(children, "document-highlight-bg", ctx)?
+			.unwrap_or_else(|| blend_colors(fg, bg, 0.12)),
+		document_highlight_write_bg: get_optional_color_field(
+			children,
+			"document-highlight-write-bg",
+			ctx,
+		)?
+		.unwrap_or_else(|| blend_colors(selection_bg, bg, 0.25)),
 	})
 }
 
@@ -207,7 +232,14 @@ fn parse_status_colors(node: Option<&KdlNode>, ctx: &ParseContext) -> Result<Sta
 }
 
 /// Parses popup/menu colors from a KDL node.
-fn parse_popup_colors(node: Option<&KdlNode>, ctx: &ParseContext) -> Result<PopupColors> {
+///
+/// `shadow` is optional and defaults to the UI `gutter-fg`. `padding` is optional and defaults
+/// to one cell.
+fn parse_popup_colors(
+	node: Option<&KdlNode>,
+	ui: &UiColors,
+	ctx: &ParseContext,
+) -> Result<PopupColors> {
 	let node = node.ok_or_else(|| ConfigError::MissingField("popup".into()))?;
 	let children = node
 		.children()
@@ -218,6 +250,43 @@ fn parse_popup_colors(node: Option<&KdlNode>, ctx: &ParseContext) -> Result<Popu
 		fg: get_color_field(children, "fg", ctx)?,
 		border: get_color_field(children, "border", ctx)?,
 		title: get_color_field(children, "title", ctx)?,
+		shadow: get_optional_color_field(children, "shadow", ctx)?.unwrap_or(ui.gutter_fg),
+		padding: children
+			.get_arg("padding")
+			.and_then(|v| v.as_integer())
+			.map_or(1, |padding| padding.clamp(0, u16::MAX.into()) as u16),
+	})
+}
+
+/// Parses diff colors from an optional KDL node.
+///
+/// Every field is optional. Added and removed lines default to the status
+/// `success-fg` and `error-fg` over `bg` tinted toward them, context lines to
+/// `fg` and hunk headers to the status `accent-fg`.
+fn parse_diff_colors(
+	node: Option<&KdlNode>,
+	ui: &UiColors,
+	status: &StatusColors,
+	ctx: &ParseContext,
+) -> Result<DiffColors> {
+	let children = node.and_then(KdlNode::children);
+	let field = |name: &str, default: Color| -> Result<Color> {
+		Ok(match children {
+			Some(children) => get_optional_color_field(children, name, ctx)?,
+			None => None,
+		}
+		.unwrap_or(default))
+	};
+
+	let added_fg = field("added-fg", status.success_fg)?;
+	let removed_fg = field("removed-fg", status.error_fg)?;
+	Ok(DiffColors {
+		added_fg,
+		added_bg: field("added-bg", blend_colors(added_fg, ui.bg, 0.15))?,
+		removed_fg,
+		removed_bg: field("removed-bg", blend_colors(removed_fg, ui.bg, 0.15))?,
+		context_fg: field("context-fg", ui.fg)?,
+		header_fg: field("header-fg", status.accent_fg)?,
 	})
 }
 
@@ -395,4 +464,50 @@ mod tests {
 			"comment style should have fg color"
 		);
 	}
+
+	#[test]
+	fn cursorline_bg_defaults_to_tinted_bg() {
+		let kdl = xeno_runtime::themes::get_str("gruvbox.kdl").unwrap();
+		let without: String = kdl
+			.lines()
+			.filter(|line| !line.trim_start().starts_with("cursorline-bg"))
+			.map(|line| format!("{line}\n"))
+			.collect();
+		let theme = parse_standalone_theme(&without).unwrap();
+		let ui = theme.colors.ui;
+		assert_eq!(ui.cursorline_bg, blend_colors(ui.fg, ui.bg, 0.08));
+	}
+
+	#[test]
+	fn diff_colors_default_to_status_colors() {
+		let kdl = xeno_runtime::themes::get_str("gruvbox.kdl").unwrap();
+		let theme = parse_standalone_theme(kdl).unwrap();
+		let (ui, status, diff) = (theme.colors.ui, theme.colors.status, theme.colors.diff);
+		assert_eq!(diff.added_fg, status.success_fg);
+		assert_eq!(diff.removed_bg, blend_colors(status.error_fg, ui.bg, 0.15));
+		assert_eq!(diff.context_fg, ui.fg);
+	}
+
+	#[test]
+	fn document_highlight_bgs_default_to_tints() {
+		let kdl = xeno_runtime::themes::get_str("gruvbox.kdl").unwrap();
+		let theme = parse_standalone_theme(kdl).unwrap();
+		let ui = theme.colors.ui;
+		assert_eq!(ui.document_highlight_bg, blend_colors(ui.fg, ui.bg, 0.12));
+		assert_eq!(
+			ui.document_highlight_write_bg,
+			blend_colors(ui.selection_bg, ui.bg, 0.25)
+		);
+	}
+
+	#[test]
+	fn search_match_bg_defaults_to_selection_tint() {
+		let kdl = xeno_runtime::themes::get_str("gruvbox.kdl").unwrap();
+		let theme = parse_standalone_theme(kdl).unwrap();
+		let ui = theme.colors.ui;
+		assert_eq!(
+			ui.search_match_bg,
+			blend_colors(ui.selection_bg, ui.bg, 0.4)
+		);
+	}
 }
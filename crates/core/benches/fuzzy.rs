@@ -0,0 +1,57 @@
+//! Filtering a 10k item completion source one keystroke at a time.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use xeno_core::MatchMode;
+use xeno_core::completion::rank;
+
+/// Builds identifier-like candidates in the mix of styles a language
+/// server sends.
+fn candidates(count: usize) -> Vec<String> {
+	const WORDS: [&str; 12] = [
+		"buffer",
+		"set",
+		"option",
+		"write",
+		"get",
+		"cursor",
+		"line",
+		"range",
+		"text",
+		"view",
+		"Selection",
+		"Handler",
+	];
+	(0..count)
+		.map(|i| {
+			let a = WORDS[i % WORDS.len()];
+			let b = WORDS[(i / WORDS.len()) % WORDS.len()];
+			match i % 3 {
+				0 => format!("{a}_{b}_{i}"),
+				1 => format!("{a}{}{i}", b.to_uppercase()),
+				_ => format!("{a}{b}"),
+			}
+		})
+		.collect()
+}
+
+fn filter_per_keystroke(c: &mut Criterion) {
+	let items = candidates(10_000);
+	let mut group = c.benchmark_group("fuzzy_10k");
+	for pattern in ["s", "se", "set", "setop", "sto"] {
+		group.bench_function(pattern, |b| {
+			b.iter(|| {
+				rank(
+					black_box(pattern),
+					items.iter().map(String::as_str),
+					MatchMode::Fuzzy,
+				)
+			})
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, filter_per_keystroke);
+criterion_main!(benches);
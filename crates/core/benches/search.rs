@@ -0,0 +1,29 @@
+//! Searching a large rope for a match on its last line.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use ropey::Rope;
+use xeno_core::movement;
+
+/// Size of the document searched.
+const DOC_BYTES: usize = 50 * 1024 * 1024;
+
+fn find_at_end(c: &mut Criterion) {
+	let line = "the quick brown fox jumps over the lazy dog\n";
+	let mut body = line.repeat(DOC_BYTES / line.len());
+	body.push_str("needle\n");
+	let text = Rope::from(body.as_str());
+	drop(body);
+
+	let mut group = c.benchmark_group("search_50mb");
+	group.sample_size(10);
+	group.throughput(Throughput::Bytes(text.len_bytes() as u64));
+	group.bench_function("find_next", |b| {
+		b.iter(|| movement::find_next(text.slice(..), black_box("needle"), 0))
+	});
+	group.finish();
+}
+
+criterion_group!(benches, find_at_end);
+criterion_main!(benches);
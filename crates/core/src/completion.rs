@@ -3,9 +3,15 @@
 //! Follows the rustyline pattern where `complete()` returns both the start
 //! position in the input where replacement begins and the list of candidates.
 //! This cleanly separates "where to replace" from "what to replace with".
+//!
+//! Sources narrow their candidates to the typed text with [`rank`], which
+//! [fuzzy matches](crate::fuzzy) by default, orders the matches best first
+//! and records on each item which characters of its label matched.
 
 use xeno_registry::commands::COMMANDS;
 
+use crate::fuzzy::{CaseMatching, FuzzyMatch, FuzzyMatcher};
+
 /// Prompt character for ex-style commands (`:write`, `:theme`, etc.).
 pub const PROMPT_COMMAND: char = ':';
 
@@ -22,6 +28,8 @@ pub enum CompletionKind {
 	Snippet,
 	/// Theme name completion.
 	Theme,
+	/// Code symbol completion from a language server.
+	Symbol,
 }
 
 /// A single completion suggestion.
@@ -37,6 +45,123 @@ pub struct CompletionItem {
 	pub filter_text: Option<String>,
 	/// Kind of item.
 	pub kind: CompletionKind,
+	/// Whether `insert_text` is in snippet syntax, to be expanded with
+	/// [`Snippet`](crate::snippet::Snippet) rather than inserted as is.
+	pub snippet: bool,
+	/// How well the item matched the typed text, higher being better.
+	pub score: i32,
+	/// Character indices of `label` that matched the typed text, for the
+	/// menu to highlight.
+	pub match_indices: Vec<usize>,
+}
+
+impl CompletionItem {
+	/// Returns the text the item is matched by: its filter text, or else
+	/// its label.
+	pub fn match_text(&self) -> &str {
+		self.filter_text.as_deref().unwrap_or(&self.label)
+	}
+
+	/// Records how the item's [match text](Self::match_text) matched
+	/// `pattern`.
+	///
+	/// With a filter text of its own, the label is matched separately for
+	/// the characters to highlight, and none are if it does not match.
+	pub fn set_match(&mut self, pattern: &str, found: FuzzyMatch, mode: MatchMode) {
+		self.score = found.score;
+		self.match_indices = if self.filter_text.is_none() {
+			found.positions
+		} else {
+			mode.matcher(pattern)
+				.matches(&self.label)
+				.map(|label| label.positions)
+				.unwrap_or_default()
+		};
+	}
+}
+
+/// How candidates are matched against the typed text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+	/// The typed characters appear in order anywhere in the candidate,
+	/// ignoring case, scored by [`FuzzyMatcher`].
+	#[default]
+	Fuzzy,
+	/// The candidate starts with the typed text exactly. Every match
+	/// scores the same.
+	Prefix,
+}
+
+impl MatchMode {
+	/// Returns a matcher for `pattern` in this mode.
+	pub fn matcher(self, pattern: &str) -> Matcher {
+		match self {
+			Self::Fuzzy => Matcher::Fuzzy(FuzzyMatcher::new(pattern, CaseMatching::Ignore)),
+			Self::Prefix => Matcher::Prefix(pattern.to_string()),
+		}
+	}
+}
+
+/// Matches one pattern against many candidates in a [`MatchMode`].
+#[derive(Debug, Clone)]
+pub enum Matcher {
+	/// Matches in [`MatchMode::Fuzzy`].
+	Fuzzy(FuzzyMatcher),
+	/// Matches in [`MatchMode::Prefix`].
+	Prefix(String),
+}
+
+impl Matcher {
+	/// Matches the pattern against `text`, or returns `None` if it does
+	/// not match.
+	pub fn matches(&mut self, text: &str) -> Option<FuzzyMatch> {
+		match self {
+			Self::Fuzzy(matcher) => matcher.matches(text),
+			Self::Prefix(prefix) => text.starts_with(prefix.as_str()).then(|| FuzzyMatch {
+				score: 0,
+				positions: (0..prefix.chars().count()).collect(),
+			}),
+		}
+	}
+}
+
+/// Matches `pattern` against `texts` and returns the index and match of
+/// each that matched, best first.
+///
+/// Texts that match equally well stay in the order they came in.
+pub fn rank<'a>(
+	pattern: &str,
+	texts: impl IntoIterator<Item = &'a str>,
+	mode: MatchMode,
+) -> Vec<(usize, FuzzyMatch)> {
+	let mut matcher = mode.matcher(pattern);
+	let mut ranked: Vec<_> = texts
+		.into_iter()
+		.enumerate()
+		.filter_map(|(i, text)| Some((i, matcher.matches(text)?)))
+		.collect();
+	ranked.sort_by(|a, b| b.1.score.cmp(&a.1.score));
+	ranked
+}
+
+/// Narrows `items` to those whose [match text](CompletionItem::match_text)
+/// matches `pattern`, best first, with their score and matched characters
+/// filled in.
+pub fn filter_items(
+	items: Vec<CompletionItem>,
+	pattern: &str,
+	mode: MatchMode,
+) -> Vec<CompletionItem> {
+	let ranked = rank(pattern, items.iter().map(CompletionItem::match_text), mode);
+	let mut items: Vec<Option<CompletionItem>> = items.into_iter().map(Some).collect();
+	ranked
+		.into_iter()
+		.filter_map(|(i, found)| {
+			let mut item = items[i].take()?;
+			item.set_match(pattern, found, mode);
+			Some(item)
+		})
+		.collect()
 }
 
 /// Result of a completion query.
@@ -92,6 +217,14 @@ pub trait CompletionSource {
 	/// Example: for input "theme gr" completing themes, returns `(6, [gruvbox, ...])`
 	/// indicating replacement starts at position 6 (after "theme ").
 	fn complete(&self, ctx: &CompletionContext) -> CompletionResult;
+
+	/// How candidates are matched against the typed text.
+	///
+	/// Sources whose candidates only make sense completed from the start
+	/// return [`MatchMode::Prefix`].
+	fn match_mode(&self) -> MatchMode {
+		MatchMode::Fuzzy
+	}
 }
 
 /// Completion source for editor commands.
@@ -111,21 +244,126 @@ impl CompletionSource for CommandSource {
 			return CompletionResult::empty();
 		}
 
-		let items: Vec<_> = COMMANDS
-			.iter()
-			.filter(|cmd| {
-				cmd.name.starts_with(input) || cmd.aliases.iter().any(|a| a.starts_with(input))
-			})
-			.map(|cmd| CompletionItem {
+		let candidates = COMMANDS.iter().map(|cmd| {
+			let item = CompletionItem {
 				label: cmd.name.to_string(),
 				insert_text: cmd.name.to_string(),
 				detail: Some(cmd.description.to_string()),
 				filter_text: None,
 				kind: CompletionKind::Command,
-			})
-			.collect();
+				snippet: false,
+				score: 0,
+				match_indices: Vec::new(),
+			};
+			(item, cmd.aliases)
+		});
+		let items = filter_named(candidates, input, self.match_mode());
 
 		// Command completions replace from position 0 (entire input)
 		CompletionResult::new(0, items)
 	}
 }
+
+/// Narrows `candidates`, items with the aliases they also go by, to those
+/// whose label or one of its aliases matches `pattern`, best first.
+///
+/// An item scores as its best matching name; the characters highlighted
+/// are those of the label, if it matched.
+pub fn filter_named<'a>(
+	candidates: impl IntoIterator<Item = (CompletionItem, &'a [&'a str])>,
+	pattern: &str,
+	mode: MatchMode,
+) -> Vec<CompletionItem> {
+	let mut matcher = mode.matcher(pattern);
+	let mut matched: Vec<_> = candidates
+		.into_iter()
+		.filter_map(|(mut item, aliases)| {
+			let label = matcher.matches(&item.label);
+			let alias = aliases
+				.iter()
+				.filter_map(|alias| matcher.matches(alias))
+				.map(|found| found.score)
+				.max();
+			let score = match (&label, alias) {
+				(Some(label), Some(alias)) => label.score.max(alias),
+				(Some(label), None) => label.score,
+				(None, Some(alias)) => alias,
+				(None, None) => return None,
+			};
+			item.score = score;
+			item.match_indices = label.map(|label| label.positions).unwrap_or_default();
+			Some(item)
+		})
+		.collect();
+	matched.sort_by(|a, b| b.score.cmp(&a.score));
+	matched
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn item(label: &str, filter_text: Option<&str>) -> CompletionItem {
+		CompletionItem {
+			label: label.to_string(),
+			insert_text: label.to_string(),
+			detail: None,
+			filter_text: filter_text.map(str::to_string),
+			kind: CompletionKind::Symbol,
+			snippet: false,
+			score: 0,
+			match_indices: Vec::new(),
+		}
+	}
+
+	fn labels(items: &[CompletionItem]) -> Vec<&str> {
+		items.iter().map(|item| item.label.as_str()).collect()
+	}
+
+	#[test]
+	fn ranks_best_first_keeping_ties_in_order() {
+		let items = vec![
+			item("reset", None),
+			item("set_b", None),
+			item("set_a", None),
+			item("other", None),
+		];
+		let items = filter_items(items, "set", MatchMode::Fuzzy);
+		assert_eq!(labels(&items), ["set_b", "set_a", "reset"]);
+		assert_eq!(items[0].match_indices, [0, 1, 2]);
+		assert_eq!(items[2].match_indices, [2, 3, 4]);
+		assert!(items[0].score > items[2].score);
+	}
+
+	#[test]
+	fn prefix_mode_matches_from_the_start_only() {
+		let items = vec![item("reset", None), item("set", None), item("Set", None)];
+		let items = filter_items(items, "se", MatchMode::Prefix);
+		assert_eq!(labels(&items), ["set"]);
+		assert_eq!(items[0].match_indices, [0, 1]);
+	}
+
+	#[test]
+	fn filter_text_decides_and_label_is_highlighted() {
+		let items = vec![item("len()", Some("len")), item("fn clen", Some("x"))];
+		let items = filter_items(items, "len", MatchMode::Fuzzy);
+		assert_eq!(labels(&items), ["len()"]);
+		assert_eq!(items[0].match_indices, [0, 1, 2]);
+	}
+
+	#[test]
+	fn aliases_match_without_highlighting_the_label() {
+		let candidates = [
+			(item("write", None), &["w"][..]),
+			(item("edit", None), &["e"][..]),
+		];
+		let items = filter_named(candidates, "w", MatchMode::Fuzzy);
+		assert_eq!(labels(&items), ["write"]);
+		assert_eq!(items[0].match_indices, [0]);
+
+		let candidates = [(item("quit", None), &["exit"][..])];
+		let items = filter_named(candidates, "ex", MatchMode::Fuzzy);
+		assert_eq!(labels(&items), ["quit"]);
+		assert!(items[0].match_indices.is_empty());
+	}
+}
@@ -1,10 +1,10 @@
 //! Editor context and effect handling.
 
+use xeno_base::Selection;
 use xeno_base::range::Range;
-use xeno_base::{Mode, Selection};
 pub use xeno_registry::actions::editor_ctx::*;
 use xeno_registry::{
-	ActionEffects, ActionResult, Effect, HookContext, HookEventData, ScreenPosition, ScrollAmount,
+	ActionEffects, ActionResult, Effect, HookContext, HookEventData, ScreenPosition,
 	emit_sync as emit_hook_sync, notification_keys as keys, result_handler,
 };
 
@@ -35,6 +35,30 @@ pub fn apply_effects(
 				emit_selection_hook(ctx, sel);
 			}
 
+			Effect::ToggleBlockSelection => {
+				if let Some(edit) = ctx.edit() {
+					edit.toggle_block_selection();
+				}
+			}
+
+			Effect::SaveSelection(slot) => {
+				if let Some(jumps) = ctx.jump_ops() {
+					jumps.save_selection(*slot);
+				}
+			}
+
+			Effect::RestoreSelection(slot) => {
+				if let Some(jumps) = ctx.jump_ops() {
+					jumps.restore_selection(*slot);
+				}
+			}
+
+			Effect::ReselectLast => {
+				if let Some(jumps) = ctx.jump_ops() {
+					jumps.reselect_last();
+				}
+			}
+
 			Effect::ScreenMotion { position, count } => {
 				apply_screen_motion(ctx, *position, *count, extend);
 			}
@@ -44,8 +68,7 @@ pub fn apply_effects(
 			}
 
 			Effect::Pending(pending) => {
-				ctx.emit(keys::pending_prompt::call(&pending.prompt));
-				ctx.set_mode(Mode::PendingAction(pending.kind));
+				ctx.set_pending(pending.kind, &pending.prompt);
 			}
 
 			Effect::EditOp(op) => {
@@ -59,13 +82,20 @@ pub fn apply_effects(
 				amount,
 				extend: scroll_extend,
 			} => {
-				let count = match amount {
-					ScrollAmount::Line(n) => *n,
-					ScrollAmount::HalfPage => 10,
-					ScrollAmount::FullPage => 20,
-				};
 				if let Some(edit) = ctx.edit() {
-					edit.move_visual_vertical(*direction, count, *scroll_extend);
+					edit.scroll(*direction, *amount, *scroll_extend);
+				}
+			}
+
+			Effect::ScrollHorizontal { direction, count } => {
+				if let Some(edit) = ctx.edit() {
+					edit.scroll_horizontal(*direction, *count);
+				}
+			}
+
+			Effect::AlignView { position, count } => {
+				if let Some(edit) = ctx.edit() {
+					edit.align_view(*position, *count);
 				}
 			}
 
@@ -103,6 +133,18 @@ pub fn apply_effects(
 				}
 			}
 
+			Effect::ResizeSplit { axis, delta } => {
+				if let Some(ops) = ctx.split_ops() {
+					ops.resize_split(*axis, *delta);
+				}
+			}
+
+			Effect::EqualizeSplits => {
+				if let Some(ops) = ctx.split_ops() {
+					ops.equalize_splits();
+				}
+			}
+
 			Effect::CloseSplit => {
 				if let Some(ops) = ctx.split_ops() {
 					ops.close_split();
@@ -148,12 +190,24 @@ pub fn apply_effects(
 				}
 			}
 
+			Effect::OpenSearchPrompt { reverse } => {
+				if let Some(search) = ctx.search() {
+					search.open_prompt(*reverse);
+				}
+			}
+
 			Effect::UseSelectionAsSearch => {
 				if let Some(search) = ctx.search() {
 					search.use_selection_as_pattern();
 				}
 			}
 
+			Effect::ClearSearchHighlight => {
+				if let Some(search) = ctx.search() {
+					search.clear_highlight();
+				}
+			}
+
 			Effect::Quit { force: _ } => {
 				outcome = HandleOutcome::Quit;
 			}
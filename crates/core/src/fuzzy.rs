@@ -0,0 +1,6 @@
+//! Fuzzy matching for completion.
+//!
+//! Completion ranks candidates with the same matcher the picker filters
+//! with, ignoring case so a typed `PU` still finds `push`.
+
+pub use xeno_tui::widgets::picker::{CaseMatching, FuzzyMatch, FuzzyMatcher, fuzzy_match};
@@ -54,6 +54,8 @@ pub enum LookupResult<'a> {
 pub struct KeymapRegistry {
 	/// Per-mode trie matchers for key sequences.
 	matchers: HashMap<BindingMode, Matcher<BindingEntry>>,
+	/// Per-mode shortest key sequence bound to each action, for shortcut hints.
+	action_keys: HashMap<BindingMode, HashMap<&'static str, Vec<Node>>>,
 }
 
 impl Default for KeymapRegistry {
@@ -67,14 +69,31 @@ impl KeymapRegistry {
 	pub fn new() -> Self {
 		Self {
 			matchers: HashMap::new(),
+			action_keys: HashMap::new(),
 		}
 	}
 
 	/// Adds a binding to the registry.
 	pub fn add(&mut self, mode: BindingMode, keys: Vec<Node>, entry: BindingEntry) {
+		let shortest = self
+			.action_keys
+			.entry(mode)
+			.or_default()
+			.entry(entry.action_name)
+			.or_insert_with(|| keys.clone());
+		if keys.len() < shortest.len() {
+			*shortest = keys.clone();
+		}
 		self.matchers.entry(mode).or_default().add(keys, entry);
 	}
 
+	/// Returns the shortest key sequence bound to `action` in `mode`.
+	///
+	/// Used to show shortcut hints next to things that run the action.
+	pub fn keys_for_action(&self, mode: BindingMode, action: &str) -> Option<&[Node]> {
+		self.action_keys.get(&mode)?.get(action).map(Vec::as_slice)
+	}
+
 	/// Looks up a key sequence in the given mode.
 	pub fn lookup(&self, mode: BindingMode, keys: &[Node]) -> LookupResult<'_> {
 		let Some(matcher) = self.matchers.get(&mode) else {
@@ -266,4 +285,26 @@ mod tests {
 			other => panic!("Expected None, got {other:?}"),
 		}
 	}
+
+	#[test]
+	fn keys_for_action_prefers_shortest_sequence() {
+		let mut registry = KeymapRegistry::new();
+		registry.add(
+			BindingMode::Normal,
+			parse_seq("space w").unwrap(),
+			test_entry("write"),
+		);
+		registry.add(
+			BindingMode::Normal,
+			parse_seq("ctrl-s").unwrap(),
+			test_entry("write"),
+		);
+
+		assert_eq!(
+			registry.keys_for_action(BindingMode::Normal, "write"),
+			Some(parse_seq("ctrl-s").unwrap().as_slice())
+		);
+		assert_eq!(registry.keys_for_action(BindingMode::Insert, "write"), None);
+		assert_eq!(registry.keys_for_action(BindingMode::Normal, "quit"), None);
+	}
 }
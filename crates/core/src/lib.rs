@@ -17,20 +17,24 @@
 
 pub mod completion;
 pub mod editor_ctx;
+pub mod fuzzy;
 pub mod index;
 pub mod keymap_registry;
 #[cfg(feature = "host")]
 pub mod movement;
+pub mod snippet;
 /// Terminal capability configuration.
 pub mod terminal_config;
 
 /// Theme completion source.
 pub mod theme {
+	use std::collections::HashSet;
+
 	use xeno_registry::themes::{THEMES, ThemeVariant, runtime_themes};
 
 	use super::completion::{
 		CompletionContext, CompletionItem, CompletionKind, CompletionResult, CompletionSource,
-		PROMPT_COMMAND,
+		PROMPT_COMMAND, filter_named,
 	};
 
 	/// Completion source for theme names.
@@ -55,29 +59,33 @@ pub mod theme {
 			let cmd_name = parts.first().unwrap();
 			let arg_start = cmd_name.len() + 1;
 
-			let mut items: Vec<_> = runtime_themes()
+			let mut seen = HashSet::new();
+			let candidates = runtime_themes()
 				.iter()
 				.copied()
 				.chain(THEMES.iter())
-				.filter(|t| {
-					t.name.starts_with(prefix) || t.aliases.iter().any(|a| a.starts_with(prefix))
-				})
-				.map(|t| CompletionItem {
-					label: t.name.to_string(),
-					insert_text: t.name.to_string(),
-					detail: Some(format!(
-						"{} theme",
-						match t.variant {
-							ThemeVariant::Dark => "dark",
-							ThemeVariant::Light => "light",
-						}
-					)),
-					filter_text: None,
-					kind: CompletionKind::Theme,
-				})
-				.collect();
-
-			items.dedup_by(|a, b| a.label == b.label);
+				.filter(|t| seen.insert(t.name))
+				.map(|t| {
+					let item = CompletionItem {
+						label: t.name.to_string(),
+						insert_text: t.name.to_string(),
+						detail: Some(format!(
+							"{} theme",
+							match t.variant {
+								ThemeVariant::Dark => "dark",
+								ThemeVariant::Light => "light",
+							}
+						)),
+						filter_text: None,
+						kind: CompletionKind::Theme,
+						snippet: false,
+						score: 0,
+						match_indices: Vec::new(),
+					};
+					(item, t.aliases)
+				});
+
+			let items = filter_named(candidates, prefix, self.match_mode());
 			CompletionResult::new(arg_start, items)
 		}
 	}
@@ -115,7 +123,9 @@ impl std::fmt::Display for ActionId {
 }
 
 // Core's own types
-pub use completion::{CompletionContext, CompletionItem, CompletionKind, CompletionSource};
+pub use completion::{
+	CompletionContext, CompletionItem, CompletionKind, CompletionSource, MatchMode,
+};
 pub use editor_ctx::{EditorCapabilities, EditorContext, EditorOps, HandleOutcome};
 pub use index::{
 	all_actions, all_commands, all_motions, all_text_objects, find_action, find_action_by_id,
@@ -124,5 +134,6 @@ pub use index::{
 pub use keymap_registry::{BindingEntry, KeymapRegistry, LookupResult, get_keymap_registry};
 #[cfg(feature = "host")]
 pub use movement::WordType;
-pub use terminal_config::{TerminalConfig, TerminalSequence};
+pub use snippet::{RenderedSnippet, Snippet, SnippetElement, SnippetTabstop};
+pub use terminal_config::{DesktopNotifySequence, TerminalConfig, TerminalSequence};
 pub use theme::ThemeSource;
@@ -1,109 +1,181 @@
 //! Regex-based search in document.
+//!
+//! Patterns are matched directly against the rope's chunks through
+//! [`regex_cursor`], so searching never copies the document into a string.
+//! Reverse search scans forward up to the cursor and keeps the last match,
+//! which preserves leftmost-first semantics without a reverse automaton.
 
-use regex::Regex;
+use regex::Regex as StrRegex;
 pub use regex::escape as escape_pattern;
+pub use regex_automata::meta::BuildError as SearchError;
+pub use regex_cursor::engines::meta::Regex;
+use regex_cursor::{Input, RopeyCursor};
 use ropey::RopeSlice;
 use xeno_base::Range;
 use xeno_base::range::CharIdx;
 
 /// Check if text matches a regex pattern.
 pub fn matches_pattern(text: &str, pattern: &str) -> Result<bool, regex::Error> {
-	let re = Regex::new(pattern)?;
+	let re = StrRegex::new(pattern)?;
 	Ok(re.is_match(text))
 }
 
-/// Find all matches of a pattern in text.
-pub fn find_all_matches(text: RopeSlice, pattern: &str) -> Result<Vec<Range>, regex::Error> {
-	let re = Regex::new(pattern)?;
-	let text_str: String = text.chars().collect();
-
-	let mut matches = Vec::new();
-	for m in re.find_iter(&text_str) {
-		let start = byte_to_char_offset(&text_str, m.start());
-		let end = byte_to_char_offset(&text_str, m.end());
-		matches.push(Range::new(start, end));
-	}
-
-	Ok(matches)
+/// How letter case is matched when compiling a search pattern.
+///
+/// Inline flags in the pattern, such as `(?i)` and `(?-i)`, still apply on
+/// top of the mode, so a single search can override it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchCase {
+	/// Letters match only their own case.
+	#[default]
+	Sensitive,
+	/// Letters match regardless of case.
+	Insensitive,
+	/// Insensitive unless the pattern contains an uppercase letter.
+	Smart,
 }
 
-/// Find the next match after the given position.
-pub fn find_next(
-	text: RopeSlice,
-	pattern: &str,
-	pos: CharIdx,
-) -> Result<Option<Range>, regex::Error> {
-	let re = Regex::new(pattern)?;
-	let text_str: String = text.chars().collect();
+impl SearchCase {
+	/// Parses the `search-case` option value.
+	pub fn parse(name: &str) -> Option<Self> {
+		match name {
+			"sensitive" => Some(Self::Sensitive),
+			"insensitive" => Some(Self::Insensitive),
+			"smart" => Some(Self::Smart),
+			_ => None,
+		}
+	}
 
-	let byte_pos = char_to_byte_offset(&text_str, pos);
-	if byte_pos < text_str.len()
-		&& let Some(m) = re.find(&text_str[byte_pos..])
-	{
-		let start = pos + byte_to_char_offset(&text_str[byte_pos..], m.start());
-		let end = pos + byte_to_char_offset(&text_str[byte_pos..], m.end());
-		return Ok(Some(Range::new(start, end)));
+	/// Returns the option value naming this mode.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Sensitive => "sensitive",
+			Self::Insensitive => "insensitive",
+			Self::Smart => "smart",
+		}
 	}
 
-	// Wrap around: search from start to pos
-	if let Some(m) = re.find(&text_str) {
-		let start = byte_to_char_offset(&text_str, m.start());
-		let end = byte_to_char_offset(&text_str, m.end());
-		if start < pos {
-			return Ok(Some(Range::new(start, end)));
+	/// Returns whether `pattern` should match case-insensitively.
+	///
+	/// Smart mode looks for uppercase letters outside escape sequences, so
+	/// classes like `\S` or `\W` do not switch it to case-sensitive.
+	pub fn ignores_case(self, pattern: &str) -> bool {
+		match self {
+			Self::Sensitive => false,
+			Self::Insensitive => true,
+			Self::Smart => {
+				let mut escaped = false;
+				!pattern.chars().any(|c| {
+					let upper = !escaped && c.is_uppercase();
+					escaped = !escaped && c == '\\';
+					upper
+				})
+			}
 		}
 	}
+}
 
-	Ok(None)
+/// Compiles a pattern for searching over ropes.
+pub fn compile(pattern: &str) -> Result<Regex, SearchError> {
+	Regex::new(pattern)
 }
 
-/// Find the previous match before the given position.
-pub fn find_prev(
+/// Compiles a pattern, applying the given case mode.
+pub fn compile_with_case(pattern: &str, case: SearchCase) -> Result<Regex, SearchError> {
+	if case.ignores_case(pattern) {
+		Regex::new(&format!("(?i){pattern}"))
+	} else {
+		Regex::new(pattern)
+	}
+}
+
+/// Iterates over matches of `regex` in `text` that start at or after `start`.
+///
+/// Text before `start` is still visible to look-around assertions such as
+/// `\b` and `^`, so a match is reported exactly as a whole-document search
+/// would report it.
+pub fn find_iter<'a>(
+	text: RopeSlice<'a>,
+	regex: &'a Regex,
+	start: CharIdx,
+) -> impl Iterator<Item = Range> + 'a {
+	let start = text.char_to_byte(start.min(text.len_chars()));
+	let input = Input::new(RopeyCursor::new(text)).range(start..);
+	regex
+		.find_iter(input)
+		.map(move |m| Range::new(text.byte_to_char(m.start()), text.byte_to_char(m.end())))
+}
+
+/// Iterates over matches of `regex` that lie entirely within `start..end`.
+///
+/// Unlike [`find_iter`], the scan stops at `end`, so a pattern with no
+/// further matches never searches the rest of the document. Look-around
+/// assertions still see the text on both sides of the range.
+pub fn find_iter_between<'a>(
+	text: RopeSlice<'a>,
+	regex: &'a Regex,
+	start: CharIdx,
+	end: CharIdx,
+) -> impl Iterator<Item = Range> + 'a {
+	let end = text.char_to_byte(end.min(text.len_chars()));
+	let start = text.char_to_byte(start.min(text.len_chars())).min(end);
+	let input = Input::new(RopeyCursor::new(text)).range(start..end);
+	regex
+		.find_iter(input)
+		.map(move |m| Range::new(text.byte_to_char(m.start()), text.byte_to_char(m.end())))
+}
+
+/// Find all matches of a pattern in text.
+pub fn find_all_matches(text: RopeSlice, pattern: &str) -> Result<Vec<Range>, SearchError> {
+	let re = compile(pattern)?;
+	Ok(find_iter(text, &re, 0).collect())
+}
+
+/// Find the next match after the given position, wrapping around to the
+/// start of the text.
+pub fn find_next(
 	text: RopeSlice,
 	pattern: &str,
 	pos: CharIdx,
-) -> Result<Option<Range>, regex::Error> {
-	let re = Regex::new(pattern)?;
-	let text_str: String = text.chars().collect();
-
-	// Find all matches before pos
-	let mut last_before: Option<Range> = None;
-	for m in re.find_iter(&text_str) {
-		let start = byte_to_char_offset(&text_str, m.start());
-		if start < pos {
-			let end = byte_to_char_offset(&text_str, m.end());
-			last_before = Some(Range::new(start, end));
-		} else {
-			break;
-		}
-	}
+) -> Result<Option<Range>, SearchError> {
+	Ok(next_match(text, &compile(pattern)?, pos))
+}
 
-	if last_before.is_some() {
-		return Ok(last_before);
-	}
+/// Find the previous match before the given position, wrapping around to
+/// the end of the text.
+pub fn find_prev(
+	text: RopeSlice,
+	pattern: &str,
+	pos: CharIdx,
+) -> Result<Option<Range>, SearchError> {
+	Ok(prev_match(text, &compile(pattern)?, pos))
+}
 
-	// Wrap around: find last match in document
-	let mut last: Option<Range> = None;
-	for m in re.find_iter(&text_str) {
-		let start = byte_to_char_offset(&text_str, m.start());
-		let end = byte_to_char_offset(&text_str, m.end());
-		last = Some(Range::new(start, end));
+/// Returns the first match of a compiled regex at or after `pos`, wrapping
+/// around to the start of the text.
+pub fn next_match(text: RopeSlice, regex: &Regex, pos: CharIdx) -> Option<Range> {
+	if pos < text.len_chars()
+		&& let Some(m) = find_iter(text, regex, pos).next()
+	{
+		return Some(m);
 	}
 
-	Ok(last)
+	// Wrap around: search from start to pos
+	find_iter(text, regex, 0).next().filter(|m| m.min() < pos)
 }
 
-/// Converts a byte offset to a character offset in a string.
-fn byte_to_char_offset(s: &str, byte_offset: usize) -> CharIdx {
-	s[..byte_offset].chars().count()
-}
+/// Returns the last match of a compiled regex starting before `pos`,
+/// wrapping around to the end of the text.
+pub fn prev_match(text: RopeSlice, regex: &Regex, pos: CharIdx) -> Option<Range> {
+	let before = find_iter(text, regex, 0)
+		.take_while(|m| m.min() < pos)
+		.last();
+	if before.is_some() {
+		return before;
+	}
 
-/// Converts a character offset to a byte offset in a string.
-fn char_to_byte_offset(s: &str, char_offset: CharIdx) -> usize {
-	s.char_indices()
-		.nth(char_offset)
-		.map(|(i, _)| i)
-		.unwrap_or(s.len())
+	// Wrap around: nothing starts before pos, so take the last match overall
+	find_iter(text, regex, pos).last()
 }
 
 #[cfg(test)]
@@ -155,4 +227,69 @@ mod tests {
 		assert_eq!(matches[1].min(), 8);
 		assert_eq!(matches[2].min(), 16);
 	}
+
+	#[test]
+	fn test_find_iter_between_stops_at_end() {
+		let text = Rope::from("foo foobar foo foo");
+		let re = compile(r"\bfoo\b").unwrap();
+
+		let starts: Vec<_> = find_iter_between(text.slice(..), &re, 1, 14)
+			.map(|m| m.min())
+			.collect();
+		assert_eq!(starts, vec![11]);
+	}
+
+	#[test]
+	fn test_find_iter_from_start() {
+		let text = Rope::from("foo foobar foo");
+		let re = compile(r"\bfoo\b").unwrap();
+
+		let starts: Vec<_> = find_iter(text.slice(..), &re, 1).map(|m| m.min()).collect();
+		assert_eq!(starts, vec![11]);
+	}
+
+	#[test]
+	fn test_match_spans_chunk_boundaries() {
+		let mut body = "x".repeat(10_000);
+		body.push_str("needle");
+		body.push_str(&"y".repeat(10_000));
+		let text = Rope::from(body.as_str());
+		assert!(text.chunks().count() > 1);
+
+		let m = find_next(text.slice(..), "x+needley", 0).unwrap().unwrap();
+		assert_eq!(m.min(), 0);
+		assert_eq!(m.max(), 10_007);
+
+		let m = find_prev(text.slice(..), "needle", text.len_chars())
+			.unwrap()
+			.unwrap();
+		assert_eq!(m.min(), 10_000);
+	}
+
+	#[test]
+	fn test_multibyte_offsets_are_chars() {
+		let text = Rope::from("héllo wörld wörld");
+		let matches = find_all_matches(text.slice(..), "wörld").unwrap();
+		assert_eq!(matches[0].min(), 6);
+		assert_eq!(matches[1].min(), 12);
+		assert_eq!(matches[1].max(), 17);
+	}
+
+	#[test]
+	fn test_search_case_modes() {
+		let text = Rope::from("Hello hello HELLO");
+		let slice = text.slice(..);
+		let count = |pattern: &str, case: SearchCase| {
+			let re = compile_with_case(pattern, case).unwrap();
+			find_iter(slice, &re, 0).count()
+		};
+
+		assert_eq!(count("hello", SearchCase::Sensitive), 1);
+		assert_eq!(count("hello", SearchCase::Insensitive), 3);
+		assert_eq!(count("hello", SearchCase::Smart), 3);
+		assert_eq!(count("Hello", SearchCase::Smart), 1);
+		assert_eq!(count(r"hello\S*", SearchCase::Smart), 3);
+		assert_eq!(count("(?i)hello", SearchCase::Sensitive), 3);
+		assert_eq!(count("(?-i)hello", SearchCase::Insensitive), 1);
+	}
 }
@@ -0,0 +1,482 @@
+//! Snippet syntax.
+//!
+//! Parses the snippet syntax language servers use for completion insert
+//! text, which is the TextMate syntax also used for user snippets:
+//!
+//! - `$1`, `${1}`: a tabstop the cursor visits in order
+//! - `${1:default}`: a tabstop whose default text is selected when visited;
+//!   defaults may nest further tabstops
+//! - `${1|one,two|}`: a tabstop offering choices, of which the first is
+//!   inserted
+//! - `$0`: where the cursor ends up, at the end of the snippet if missing
+//! - `$NAME`, `${NAME}`, `${NAME:default}`: a variable, resolved by the
+//!   caller
+//!
+//! `\` escapes `$`, `}` and `\`. Syntax that does not parse, such as a `$`
+//! followed by neither a digit, a name nor `{`, or a variable transform, is
+//! kept as literal text, so parsing never fails.
+//!
+//! A tabstop that appears several times is rendered with the same text at
+//! each occurrence, taken from the first occurrence that has a default.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+/// A piece of a parsed snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnippetElement {
+	/// Literal text.
+	Text(String),
+	/// A tabstop, with its default text if any.
+	Tabstop {
+		/// Number of the tabstop; 0 is the final cursor position.
+		index: u32,
+		/// Default text, empty for a bare tabstop.
+		placeholder: Vec<SnippetElement>,
+	},
+	/// A tabstop offering a choice of texts.
+	Choice {
+		/// Number of the tabstop.
+		index: u32,
+		/// The texts offered, in order.
+		options: Vec<String>,
+	},
+	/// A variable, with the text used when it is unknown.
+	Variable {
+		/// Name of the variable, such as `TM_SELECTED_TEXT`.
+		name: String,
+		/// Text used when the variable has no value.
+		default: Vec<SnippetElement>,
+	},
+}
+
+/// A parsed snippet.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Snippet {
+	/// Elements of the snippet, in order.
+	pub elements: Vec<SnippetElement>,
+}
+
+/// A tabstop of a rendered snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetTabstop {
+	/// Number of the tabstop; 0 is the final cursor position.
+	pub index: u32,
+	/// Characters of the rendered text each occurrence spans, in order.
+	pub ranges: Vec<Range<usize>>,
+}
+
+/// Text of a snippet with its tabstops.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RenderedSnippet {
+	/// Text to insert.
+	pub text: String,
+	/// Tabstops in the order the cursor visits them, ending with tabstop 0.
+	pub tabstops: Vec<SnippetTabstop>,
+}
+
+impl Snippet {
+	/// Parses `source`, taking syntax that does not parse literally.
+	pub fn parse(source: &str) -> Self {
+		let mut parser = Parser {
+			chars: source.chars().collect(),
+			pos: 0,
+		};
+		Self {
+			elements: parser.elements(None),
+		}
+	}
+
+	/// Renders the snippet, looking variables up with `resolve` and
+	/// starting every line after the first with `line_prefix`, such as the
+	/// indentation of the line the snippet is inserted into.
+	pub fn render(
+		&self,
+		resolve: impl Fn(&str) -> Option<String>,
+		line_prefix: &str,
+	) -> RenderedSnippet {
+		let mut defaults = HashMap::new();
+		collect_defaults(&self.elements, &mut defaults);
+		let mut renderer = Renderer {
+			resolve: &resolve,
+			line_prefix,
+			defaults,
+			text: String::new(),
+			len: 0,
+			ranges: BTreeMap::new(),
+			stack: Vec::new(),
+		};
+		renderer.render(&self.elements);
+
+		let end = renderer.len;
+		let mut ranges = renderer.ranges;
+		let last = ranges.remove(&0).unwrap_or_else(|| vec![end..end]);
+		let mut tabstops: Vec<SnippetTabstop> = ranges
+			.into_iter()
+			.map(|(index, ranges)| SnippetTabstop { index, ranges })
+			.collect();
+		tabstops.push(SnippetTabstop {
+			index: 0,
+			ranges: last,
+		});
+		RenderedSnippet {
+			text: renderer.text,
+			tabstops,
+		}
+	}
+}
+
+/// Records the default of each tabstop: the placeholder or first choice of
+/// its first occurrence that has one.
+fn collect_defaults<'a>(
+	elements: &'a [SnippetElement],
+	defaults: &mut HashMap<u32, TabstopDefault<'a>>,
+) {
+	for element in elements {
+		match element {
+			SnippetElement::Tabstop { index, placeholder } if !placeholder.is_empty() => {
+				defaults
+					.entry(*index)
+					.or_insert(TabstopDefault::Elements(placeholder));
+				collect_defaults(placeholder, defaults);
+			}
+			SnippetElement::Choice { index, options } => {
+				if let Some(first) = options.first() {
+					defaults
+						.entry(*index)
+						.or_insert(TabstopDefault::Text(first));
+				}
+			}
+			SnippetElement::Variable { default, .. } => collect_defaults(default, defaults),
+			_ => {}
+		}
+	}
+}
+
+/// Default text of a tabstop.
+#[derive(Clone, Copy)]
+enum TabstopDefault<'a> {
+	/// A placeholder.
+	Elements(&'a [SnippetElement]),
+	/// A choice.
+	Text(&'a str),
+}
+
+/// Renders snippet elements into text, recording where tabstops land.
+struct Renderer<'a, F> {
+	/// Looks variables up.
+	resolve: &'a F,
+	/// Text starting every line after the first.
+	line_prefix: &'a str,
+	/// Default text of each tabstop.
+	defaults: HashMap<u32, TabstopDefault<'a>>,
+	/// Text rendered so far.
+	text: String,
+	/// Length of the text in characters.
+	len: usize,
+	/// Ranges of each tabstop's occurrences.
+	ranges: BTreeMap<u32, Vec<Range<usize>>>,
+	/// Tabstops being rendered, to stop a default from containing itself.
+	stack: Vec<u32>,
+}
+
+impl<F: Fn(&str) -> Option<String>> Renderer<'_, F> {
+	/// Renders `elements` onto the text.
+	fn render(&mut self, elements: &[SnippetElement]) {
+		for element in elements {
+			match element {
+				SnippetElement::Text(text) => self.push(text),
+				SnippetElement::Tabstop { index, .. } | SnippetElement::Choice { index, .. } => {
+					let start = self.len;
+					if !self.stack.contains(index) {
+						self.stack.push(*index);
+						match self.defaults.get(index).copied() {
+							Some(TabstopDefault::Elements(default)) => self.render(default),
+							Some(TabstopDefault::Text(default)) => self.push(default),
+							None => {}
+						}
+						self.stack.pop();
+					}
+					self.ranges.entry(*index).or_default().push(start..self.len);
+				}
+				SnippetElement::Variable { name, default } => match (self.resolve)(name) {
+					Some(value) => self.push(&value),
+					None => self.render(default),
+				},
+			}
+		}
+	}
+
+	/// Appends `text`, prefixing each line after a line break.
+	fn push(&mut self, text: &str) {
+		for c in text.chars() {
+			self.text.push(c);
+			self.len += 1;
+			if c == '\n' {
+				self.text.push_str(self.line_prefix);
+				self.len += self.line_prefix.chars().count();
+			}
+		}
+	}
+}
+
+/// Recursive descent parser over the characters of a snippet.
+struct Parser {
+	/// Characters of the snippet.
+	chars: Vec<char>,
+	/// Index of the next character.
+	pos: usize,
+}
+
+impl Parser {
+	/// Returns the next character without consuming it.
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.pos).copied()
+	}
+
+	/// Consumes the next character if it is `c`.
+	fn eat(&mut self, c: char) -> bool {
+		let found = self.peek() == Some(c);
+		if found {
+			self.pos += 1;
+		}
+		found
+	}
+
+	/// Parses elements up to the end, or up to an unescaped `stop` which is
+	/// left unconsumed.
+	fn elements(&mut self, stop: Option<char>) -> Vec<SnippetElement> {
+		let mut elements = Vec::new();
+		let mut text = String::new();
+		while let Some(c) = self.peek() {
+			if Some(c) == stop {
+				break;
+			}
+			self.pos += 1;
+			match c {
+				'\\' => match self.peek() {
+					Some(escaped @ ('$' | '}' | '\\')) => {
+						self.pos += 1;
+						text.push(escaped);
+					}
+					_ => text.push('\\'),
+				},
+				'$' => {
+					let start = self.pos;
+					match self.dollar() {
+						Some(element) => {
+							if !text.is_empty() {
+								elements.push(SnippetElement::Text(std::mem::take(&mut text)));
+							}
+							elements.push(element);
+						}
+						None => {
+							self.pos = start;
+							text.push('$');
+						}
+					}
+				}
+				c => text.push(c),
+			}
+		}
+		if !text.is_empty() {
+			elements.push(SnippetElement::Text(text));
+		}
+		elements
+	}
+
+	/// Parses what follows a `$`, or returns `None` if it is not snippet
+	/// syntax.
+	fn dollar(&mut self) -> Option<SnippetElement> {
+		if let Some(index) = self.number() {
+			return Some(SnippetElement::Tabstop {
+				index,
+				placeholder: Vec::new(),
+			});
+		}
+		if let Some(name) = self.name() {
+			return Some(SnippetElement::Variable {
+				name,
+				default: Vec::new(),
+			});
+		}
+		if !self.eat('{') {
+			return None;
+		}
+		if let Some(index) = self.number() {
+			if self.eat('}') {
+				return Some(SnippetElement::Tabstop {
+					index,
+					placeholder: Vec::new(),
+				});
+			}
+			if self.eat(':') {
+				let placeholder = self.elements(Some('}'));
+				return self
+					.eat('}')
+					.then_some(SnippetElement::Tabstop { index, placeholder });
+			}
+			if self.eat('|') {
+				let options = self.choices()?;
+				return Some(SnippetElement::Choice { index, options });
+			}
+			return None;
+		}
+		let name = self.name()?;
+		if self.eat('}') {
+			return Some(SnippetElement::Variable {
+				name,
+				default: Vec::new(),
+			});
+		}
+		if self.eat(':') {
+			let default = self.elements(Some('}'));
+			return self
+				.eat('}')
+				.then_some(SnippetElement::Variable { name, default });
+		}
+		None
+	}
+
+	/// Parses the options of a choice after its `|`, through the closing
+	/// `|}`.
+	fn choices(&mut self) -> Option<Vec<String>> {
+		let mut options = Vec::new();
+		let mut option = String::new();
+		loop {
+			match self.peek()? {
+				'\\' => {
+					self.pos += 1;
+					match self.peek() {
+						Some(escaped @ ('$' | '}' | '\\' | ',' | '|')) => {
+							self.pos += 1;
+							option.push(escaped);
+						}
+						_ => option.push('\\'),
+					}
+				}
+				',' => {
+					self.pos += 1;
+					options.push(std::mem::take(&mut option));
+				}
+				'|' => {
+					self.pos += 1;
+					options.push(option);
+					return self.eat('}').then_some(options);
+				}
+				c => {
+					self.pos += 1;
+					option.push(c);
+				}
+			}
+		}
+	}
+
+	/// Parses a tabstop number.
+	fn number(&mut self) -> Option<u32> {
+		let start = self.pos;
+		while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+			self.pos += 1;
+		}
+		let number = self.chars[start..self.pos]
+			.iter()
+			.collect::<String>()
+			.parse();
+		if number.is_err() {
+			self.pos = start;
+		}
+		number.ok()
+	}
+
+	/// Parses a variable name: a letter or `_`, then letters, digits and
+	/// `_`.
+	fn name(&mut self) -> Option<String> {
+		if !self
+			.peek()
+			.is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+		{
+			return None;
+		}
+		let start = self.pos;
+		while self
+			.peek()
+			.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+		{
+			self.pos += 1;
+		}
+		Some(self.chars[start..self.pos].iter().collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn render(source: &str) -> RenderedSnippet {
+		Snippet::parse(source).render(
+			|name| (name == "TM_SELECTED_TEXT").then(|| "sel".to_string()),
+			"  ",
+		)
+	}
+
+	fn ranges(rendered: &RenderedSnippet) -> Vec<(u32, Vec<Range<usize>>)> {
+		rendered
+			.tabstops
+			.iter()
+			.map(|t| (t.index, t.ranges.clone()))
+			.collect()
+	}
+
+	#[test]
+	fn tabstops_render_defaults_in_order() {
+		let rendered = render("fn ${1:name}(${2:arg}: ${3|u32,i64|}) {\n\t$0\n}");
+		assert_eq!(rendered.text, "fn name(arg: u32) {\n  \t\n  }");
+		assert_eq!(
+			ranges(&rendered),
+			[
+				(1, vec![3..7]),
+				(2, vec![8..11]),
+				(3, vec![13..16]),
+				(0, vec![23..23]),
+			]
+		);
+	}
+
+	#[test]
+	fn repeated_tabstops_mirror_the_first_default() {
+		let rendered = render("let $1 = ${1:x}; $1$2");
+		assert_eq!(rendered.text, "let x = x; x");
+		assert_eq!(
+			ranges(&rendered),
+			[
+				(1, vec![4..5, 8..9, 11..12]),
+				(2, vec![12..12]),
+				(0, vec![12..12]),
+			]
+		);
+	}
+
+	#[test]
+	fn nested_placeholders_render_their_text() {
+		let rendered = render("${1:foo(${2:bar})}");
+		assert_eq!(rendered.text, "foo(bar)");
+		assert_eq!(ranges(&rendered)[..2], [(1, vec![0..8]), (2, vec![4..7])]);
+	}
+
+	#[test]
+	fn variables_resolve_or_fall_back_to_defaults() {
+		assert_eq!(render("<$TM_SELECTED_TEXT>").text, "<sel>");
+		assert_eq!(render("${TM_FILENAME:untitled}").text, "untitled");
+		assert_eq!(render("$UNKNOWN.").text, ".");
+	}
+
+	#[test]
+	fn invalid_syntax_is_literal() {
+		assert_eq!(
+			render("cost: $ 5 \\$1 ${1:open").text,
+			"cost: $ 5 $1 ${1:open"
+		);
+		assert_eq!(render("\\}\\\\\\n").text, "}\\\\n");
+		assert_eq!(render("${VAR/a/b/}").text, "${VAR/a/b/}");
+	}
+}
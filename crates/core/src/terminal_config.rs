@@ -17,6 +17,10 @@ pub enum TerminalSequence {
 	EnableAnyEventMouse,
 	/// Disable any-event mouse reporting.
 	DisableAnyEventMouse,
+	/// Enable bracketed paste, so pasted text arrives as a single event.
+	EnableBracketedPaste,
+	/// Disable bracketed paste.
+	DisableBracketedPaste,
 	/// Push kitty keyboard protocol with disambiguate mode.
 	PushKittyKeyboardDisambiguate,
 	/// Pop kitty keyboard protocol flags.
@@ -25,6 +29,71 @@ pub enum TerminalSequence {
 	ResetCursorStyle,
 }
 
+/// Escape sequence used to raise a desktop notification.
+///
+/// Terminals that understand neither ignore it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DesktopNotifySequence {
+	/// `OSC 777 ; notify ; title ; body ST`, understood by rxvt-unicode,
+	/// foot, Ghostty, WezTerm, and VTE-based terminals.
+	#[default]
+	Osc777,
+	/// `OSC 9 ; body ST`, understood by iTerm2, kitty, and Windows Terminal.
+	Osc9,
+}
+
+/// Longest notification title, in characters, put in a sequence.
+const MAX_NOTIFY_TITLE_CHARS: usize = 64;
+
+/// Longest notification body, in characters, put in a sequence.
+const MAX_NOTIFY_BODY_CHARS: usize = 256;
+
+impl DesktopNotifySequence {
+	/// Picks the sequence the terminal in the environment understands.
+	pub fn detect() -> Self {
+		let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+		if term_program == "iTerm.app"
+			|| std::env::var_os("WT_SESSION").is_some()
+			|| supports_kitty_keyboard()
+		{
+			Self::Osc9
+		} else {
+			Self::Osc777
+		}
+	}
+
+	/// Encodes a notification showing `title` and `body`.
+	///
+	/// Both are stripped of control characters, which could end the sequence
+	/// early or smuggle in another, and cut to a bounded length.
+	pub fn encode(self, title: &str, body: &str) -> String {
+		let body = sanitize_notify_text(body, MAX_NOTIFY_BODY_CHARS);
+		match self {
+			Self::Osc777 => {
+				// `;` separates the title from the body.
+				let title = sanitize_notify_text(title, MAX_NOTIFY_TITLE_CHARS).replace(';', ",");
+				format!("\x1b]777;notify;{title};{body}\x1b\\")
+			}
+			Self::Osc9 => format!("\x1b]9;{body}\x1b\\"),
+		}
+	}
+}
+
+/// Replaces control characters in `text` with spaces and cuts it to
+/// `max_chars`, marking a cut with an ellipsis.
+fn sanitize_notify_text(text: &str, max_chars: usize) -> String {
+	let mut clean: String = text
+		.chars()
+		.map(|c| if c.is_control() { ' ' } else { c })
+		.collect();
+	if let Some((cut, _)) = clean.char_indices().nth(max_chars) {
+		clean.truncate(cut);
+		clean.pop();
+		clean.push('…');
+	}
+	clean.trim().to_string()
+}
+
 /// Configures terminal feature sequences used by the UI.
 #[derive(Debug, Clone, Copy)]
 pub struct TerminalConfig {
@@ -34,6 +103,8 @@ pub struct TerminalConfig {
 	pub exit_sequences: &'static [TerminalSequence],
 	/// Sequences emitted on panic cleanup.
 	pub panic_sequences: &'static [TerminalSequence],
+	/// Sequence used to raise desktop notifications.
+	pub desktop_notify: DesktopNotifySequence,
 }
 
 /// Environment variable for overriding terminal configuration.
@@ -46,6 +117,7 @@ const DEFAULT_ENTER: &[TerminalSequence] = &[
 	TerminalSequence::EnableMouseTracking,
 	TerminalSequence::EnableSgrMouse,
 	TerminalSequence::EnableAnyEventMouse,
+	TerminalSequence::EnableBracketedPaste,
 ];
 
 /// Default exit sequences with full kitty keyboard cleanup.
@@ -55,6 +127,7 @@ const DEFAULT_EXIT: &[TerminalSequence] = &[
 	TerminalSequence::DisableMouseTracking,
 	TerminalSequence::DisableSgrMouse,
 	TerminalSequence::DisableAnyEventMouse,
+	TerminalSequence::DisableBracketedPaste,
 	TerminalSequence::DisableAlternateScreen,
 ];
 
@@ -64,6 +137,7 @@ const NO_KITTY_ENTER: &[TerminalSequence] = &[
 	TerminalSequence::EnableMouseTracking,
 	TerminalSequence::EnableSgrMouse,
 	TerminalSequence::EnableAnyEventMouse,
+	TerminalSequence::EnableBracketedPaste,
 ];
 
 /// Exit sequences for terminals without kitty keyboard support.
@@ -72,6 +146,7 @@ const NO_KITTY_EXIT: &[TerminalSequence] = &[
 	TerminalSequence::DisableMouseTracking,
 	TerminalSequence::DisableSgrMouse,
 	TerminalSequence::DisableAnyEventMouse,
+	TerminalSequence::DisableBracketedPaste,
 	TerminalSequence::DisableAlternateScreen,
 ];
 
@@ -99,22 +174,46 @@ impl TerminalConfig {
 			enter_sequences,
 			exit_sequences,
 			panic_sequences,
+			desktop_notify: DesktopNotifySequence::Osc777,
 		}
 	}
 
+	/// Sets the sequence used to raise desktop notifications.
+	#[must_use]
+	pub const fn with_desktop_notify(mut self, desktop_notify: DesktopNotifySequence) -> Self {
+		self.desktop_notify = desktop_notify;
+		self
+	}
+
+	/// Returns whether this configuration enables the kitty keyboard protocol.
+	///
+	/// Under the protocol Escape and Alt chords arrive unambiguously encoded.
+	pub fn kitty_keyboard(&self) -> bool {
+		self.enter_sequences
+			.contains(&TerminalSequence::PushKittyKeyboardDisambiguate)
+	}
+
 	/// Detects the best terminal configuration for the current environment.
 	///
 	/// Respects `XENO_TERMINAL_CONFIG` overrides ("kitty" or "no-kitty").
 	pub fn detect() -> Self {
-		if let Some(config) = Self::from_env() {
-			return config;
-		}
-
-		if supports_kitty_keyboard() {
-			return Self::default();
-		}
+		Self::detect_with_probe(false)
+	}
 
-		Self::new(NO_KITTY_ENTER, NO_KITTY_EXIT, NO_KITTY_EXIT)
+	/// Detects the configuration, trusting a terminal that answered a kitty
+	/// keyboard query.
+	///
+	/// `XENO_TERMINAL_CONFIG` still wins, and environment detection covers
+	/// terminals that support the protocol but did not answer in time.
+	pub fn detect_with_probe(kitty_keyboard: bool) -> Self {
+		let config = if let Some(config) = Self::from_env() {
+			config
+		} else if kitty_keyboard || supports_kitty_keyboard() {
+			Self::default()
+		} else {
+			Self::new(NO_KITTY_ENTER, NO_KITTY_EXIT, NO_KITTY_EXIT)
+		};
+		config.with_desktop_notify(DesktopNotifySequence::detect())
 	}
 
 	/// Parses configuration from the `XENO_TERMINAL_CONFIG` env var.
@@ -134,3 +233,24 @@ impl Default for TerminalConfig {
 		Self::new(DEFAULT_ENTER, DEFAULT_EXIT, DEFAULT_EXIT)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn desktop_notifications_cannot_escape_their_sequence() {
+		let encoded = DesktopNotifySequence::Osc777.encode("a;b", "bad\x1b]0;pwned\x07\nline");
+		assert_eq!(encoded, "\x1b]777;notify;a,b;bad ]0;pwned  line\x1b\\");
+		assert_eq!(encoded.matches('\x1b').count(), 2);
+		assert!(!encoded.contains('\x07'));
+
+		let long = "x".repeat(1000);
+		let encoded = DesktopNotifySequence::Osc9.encode("", &long);
+		assert_eq!(
+			encoded.chars().count(),
+			"\x1b]9;".len() + MAX_NOTIFY_BODY_CHARS + 2
+		);
+		assert!(encoded.contains('…'));
+	}
+}
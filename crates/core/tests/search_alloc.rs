@@ -0,0 +1,67 @@
+//! Heap allocations of rope search.
+//!
+//! Checks that searching never materializes the document: what a search
+//! allocates must not grow with the size of the text searched. Lives in its
+//! own test binary because it installs a counting global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ropey::Rope;
+use xeno_core::movement;
+
+struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Builds a document of about `bytes` bytes with `needle` on its last line.
+fn document(bytes: usize) -> Rope {
+	let line = "the quick brown fox jumps over the lazy dog\n";
+	let mut body = line.repeat(bytes / line.len());
+	body.push_str("needle\n");
+	Rope::from(body.as_str())
+}
+
+/// Searches `text` for the needle, returning its start and the bytes
+/// allocated meanwhile.
+fn find_needle(text: &Rope) -> (usize, usize) {
+	let before = ALLOCATED.load(Ordering::Relaxed);
+	let found = movement::find_next(text.slice(..), "needle", 0)
+		.unwrap()
+		.expect("needle is at the end of the document");
+	(found.min(), ALLOCATED.load(Ordering::Relaxed) - before)
+}
+
+#[test]
+fn search_does_not_copy_document() {
+	const SMALL: usize = 64 * 1024;
+	const LARGE: usize = 1024 * 1024;
+
+	let small = document(SMALL);
+	let large = document(LARGE);
+	let (small_at, small_allocated) = find_needle(&small);
+	let (large_at, large_allocated) = find_needle(&large);
+
+	assert_eq!(small_at, small.len_chars() - 7);
+	assert_eq!(large_at, large.len_chars() - 7);
+	// Copying the larger document would cost the difference in size again.
+	assert!(
+		large_allocated < small_allocated + (LARGE - SMALL) / 8,
+		"search allocated {small_allocated} bytes for {SMALL} bytes of text \
+		 but {large_allocated} for {LARGE}"
+	);
+}
@@ -1,6 +1,9 @@
 //! Input handler managing key processing and mode state.
 
+use std::time::{Duration, Instant};
+
 use tracing::debug;
+use xeno_base::PendingKind;
 use xeno_base::key::{Key, KeyCode, MouseButton, MouseEvent};
 use xeno_core::get_keymap_registry;
 use xeno_core::keymap_registry::{KeymapRegistry, LookupResult};
@@ -25,6 +28,15 @@ pub struct InputHandler {
 	pub(crate) last_search: Option<(String, bool)>,
 	/// Accumulated key sequence for multi-key bindings (e.g., `g g`).
 	pub(crate) key_sequence: Vec<Node>,
+	/// Count, extend, and register of the action dispatched by the current
+	/// key, restored if that action enters a pending state (e.g., `3f x`).
+	pub(crate) dispatched: Option<(u32, bool, Option<char>)>,
+	/// Mode to return to once a pending action receives its character.
+	pub(crate) resume_mode: Mode,
+	/// Prompt shown while a pending action waits for its character.
+	pub(crate) pending_prompt: Option<String>,
+	/// When the current pending action started waiting.
+	pub(crate) pending_since: Option<Instant>,
 }
 
 impl Default for InputHandler {
@@ -43,6 +55,10 @@ impl InputHandler {
 			extend: false,
 			last_search: None,
 			key_sequence: Vec::new(),
+			dispatched: None,
+			resume_mode: Mode::Normal,
+			pending_prompt: None,
+			pending_since: None,
 		}
 	}
 
@@ -53,7 +69,6 @@ impl InputHandler {
 
 	/// Returns the display name for the current mode.
 	pub fn mode_name(&self) -> &'static str {
-		use xeno_base::PendingKind;
 		match &self.mode {
 			Mode::Normal => "NORMAL",
 			Mode::Insert => "INSERT",
@@ -61,6 +76,8 @@ impl InputHandler {
 				PendingKind::FindChar { .. } | PendingKind::FindCharReverse { .. } => "FIND",
 				PendingKind::ReplaceChar => "REPLACE",
 				PendingKind::Object(_) => "OBJECT",
+				PendingKind::InsertRegister => "REGISTER",
+				PendingKind::InsertLiteral | PendingKind::InsertCodepoint { .. } => "LITERAL",
 			},
 		}
 	}
@@ -81,11 +98,67 @@ impl InputHandler {
 	}
 
 	/// Sets the editor mode, resetting parameters when entering normal mode.
+	///
+	/// Entering a pending action right after a dispatch carries that
+	/// dispatch's count, extend, and register over to the pending key, and
+	/// remembers the current mode so the pending key returns to it.
 	pub fn set_mode(&mut self, mode: Mode) {
-		self.mode = mode.clone();
-		if matches!(mode, Mode::Normal) {
-			self.reset_params();
+		match mode {
+			Mode::Normal => self.reset_params(),
+			Mode::PendingAction(_) => {
+				if !matches!(self.mode, Mode::PendingAction(_)) {
+					self.resume_mode = self.mode.clone();
+					self.pending_since = Some(Instant::now());
+				}
+				if let Some((count, extend, register)) = self.dispatched.take() {
+					self.count = count;
+					self.extend = extend;
+					self.register = register;
+				}
+			}
+			Mode::Insert => {}
+		}
+		if !matches!(mode, Mode::PendingAction(_)) {
+			self.pending_prompt = None;
+			self.pending_since = None;
+		}
+		self.mode = mode;
+	}
+
+	/// Enters a pending action that shows `prompt` until it gets its key.
+	pub fn set_pending(&mut self, kind: PendingKind, prompt: impl Into<String>) {
+		self.set_mode(Mode::PendingAction(kind));
+		self.pending_prompt = Some(prompt.into());
+	}
+
+	/// Returns the prompt of the pending action awaiting a key, if any.
+	pub fn pending_prompt(&self) -> Option<&str> {
+		self.pending_prompt.as_deref()
+	}
+
+	/// Returns whether a pending action has waited longer than `timeout`.
+	pub fn pending_expired(&self, timeout: Duration) -> bool {
+		self.pending_since
+			.is_some_and(|since| since.elapsed() >= timeout)
+	}
+
+	/// Abandons a pending action, returning to the mode it was entered from.
+	///
+	/// Returns the restored mode, or `None` if nothing was pending.
+	pub fn cancel_pending(&mut self) -> Option<Mode> {
+		if !matches!(self.mode, Mode::PendingAction(_)) {
+			return None;
 		}
+		self.reset_params();
+		Some(self.leave_pending())
+	}
+
+	/// Returns from a pending action to the mode it was entered from.
+	pub(crate) fn leave_pending(&mut self) -> Mode {
+		self.pending_prompt = None;
+		self.pending_since = None;
+		self.mode = std::mem::replace(&mut self.resume_mode, Mode::Normal);
+		self.mode.clone()
 	}
 
 	/// Stores the last search pattern and direction for repeat commands.
@@ -124,6 +197,7 @@ impl InputHandler {
 	/// Process a key and return the result.
 	pub fn handle_key(&mut self, key: Key) -> KeyResult {
 		let registry = get_keymap_registry();
+		self.dispatched = None;
 
 		match &self.mode {
 			Mode::Normal => self.handle_mode_key(key, BindingMode::Normal, registry),
@@ -152,7 +226,7 @@ impl InputHandler {
 
 		if key.is_escape() {
 			if !self.key_sequence.is_empty() {
-				self.key_sequence.clear();
+				self.reset_params();
 				return KeyResult::Consumed;
 			}
 			if binding_mode != BindingMode::Normal {
@@ -164,7 +238,11 @@ impl InputHandler {
 			return KeyResult::Consumed;
 		}
 
-		let key = self.process_shift_extend(key);
+		let key = if self.has_exact_binding(&key, binding_mode, registry) {
+			key
+		} else {
+			self.process_shift_extend(key)
+		};
 
 		let Ok(node) = key.to_keymap() else {
 			self.reset_params();
@@ -194,11 +272,8 @@ impl InputHandler {
 
 		match lookup_result {
 			LookupResult::Match(entry) => {
-				let count = if self.count > 0 {
-					self.count as usize
-				} else {
-					1
-				};
+				let raw_count = self.count;
+				let count = if raw_count > 0 { raw_count as usize } else { 1 };
 				let extend = self.extend;
 				let register = self.register;
 				let action_id = entry.action_id;
@@ -207,6 +282,7 @@ impl InputHandler {
 					self.mode = Mode::Normal;
 				}
 				self.reset_params();
+				self.dispatched = Some((raw_count, extend, register));
 
 				KeyResult::ActionById {
 					id: action_id,
@@ -237,6 +313,25 @@ impl InputHandler {
 		}
 	}
 
+	/// Returns whether a shifted key is bound as-is, such as `shift-enter` or
+	/// `ctrl-shift-p`, so shift must not be reinterpreted as extend.
+	fn has_exact_binding(
+		&self,
+		key: &Key,
+		binding_mode: BindingMode,
+		registry: &KeymapRegistry,
+	) -> bool {
+		if !key.modifiers.shift {
+			return false;
+		}
+		let Ok(node) = key.to_keymap() else {
+			return false;
+		};
+		let mut sequence = self.key_sequence.clone();
+		sequence.push(node);
+		!matches!(registry.lookup(binding_mode, &sequence), LookupResult::None)
+	}
+
 	/// Processes shift modifier to set extend flag and normalize the key.
 	fn process_shift_extend(&mut self, key: Key) -> Key {
 		if let KeyCode::Char(c) = key.code
@@ -3,7 +3,7 @@ use xeno_base::{ObjectSelectionKind, PendingKind};
 use xeno_core::resolve_action_id;
 
 use crate::InputHandler;
-use crate::types::{KeyResult, Mode};
+use crate::types::KeyResult;
 
 impl InputHandler {
 	/// Handles key input for pending actions (character find, text objects, etc.).
@@ -25,6 +25,11 @@ impl InputHandler {
 			PendingKind::FindChar { .. } => "find_char",
 			PendingKind::FindCharReverse { .. } => "find_char_reverse",
 			PendingKind::ReplaceChar => "replace_char",
+			PendingKind::InsertRegister => "insert_register",
+			PendingKind::InsertLiteral => "insert_unicode",
+			PendingKind::InsertCodepoint { value, digits } => {
+				return self.handle_codepoint_key(key, value, digits);
+			}
 			PendingKind::Object(selection) => match selection {
 				ObjectSelectionKind::Inner => "select_object_inner",
 				ObjectSelectionKind::Around => "select_object_around",
@@ -33,28 +38,93 @@ impl InputHandler {
 			},
 		};
 
-		match key.code {
-			KeyCode::Char(ch) => {
-				let count = self.effective_count() as usize;
-				let extend = self.extend;
-				let register = self.register;
-				self.reset_params();
-				let id = resolve_action_id(action_name)
-					.unwrap_or_else(|| panic!("{action_name} action not registered"));
-				KeyResult::ActionByIdWithChar {
-					id,
-					count,
-					extend,
-					register,
-					char_arg: ch,
-				}
+		let text = key
+			.codepoint()
+			.filter(|_| !key.modifiers.ctrl && !key.modifiers.alt);
+		let text = match (pending, key.code) {
+			(PendingKind::InsertLiteral, KeyCode::Char('u')) if text.is_some() => {
+				self.set_pending(
+					PendingKind::InsertCodepoint {
+						value: 0,
+						digits: 0,
+					},
+					"U+",
+				);
+				return KeyResult::Consumed;
+			}
+			(PendingKind::InsertLiteral, KeyCode::Tab) => Some('\t'),
+			(PendingKind::InsertLiteral, KeyCode::Enter) => Some('\n'),
+			_ => text,
+		};
+		let Some(ch) = text else {
+			// Escape, ctrl-c, and any key that cannot be typed as text abandon
+			// the pending action instead of being swallowed by it.
+			return match self.cancel_pending() {
+				Some(mode) => KeyResult::ModeChange(mode),
+				None => KeyResult::Consumed,
+			};
+		};
+
+		self.dispatch_pending_char(action_name, ch)
+	}
+
+	/// Accumulates the hex digits of a codepoint typed after `ctrl-v u`.
+	///
+	/// Six digits complete the codepoint, as does Enter or Space after at
+	/// least four. Any other key cancels.
+	fn handle_codepoint_key(&mut self, key: Key, value: u32, digits: u8) -> KeyResult {
+		let digit = key
+			.codepoint()
+			.filter(|_| !key.modifiers.ctrl && !key.modifiers.alt)
+			.and_then(|c| c.to_digit(16));
+		let (value, digits) = match digit {
+			Some(digit) => (value * 16 + digit, digits + 1),
+			None if digits >= 4 && (key.is_enter() || key.code == KeyCode::Space) => {
+				(value, digits)
 			}
-			KeyCode::Esc => {
-				self.mode = Mode::Normal;
+			None => {
+				return match self.cancel_pending() {
+					Some(mode) => KeyResult::ModeChange(mode),
+					None => KeyResult::Consumed,
+				};
+			}
+		};
+
+		if digit.is_some() && digits < 6 {
+			let width = usize::from(digits);
+			self.set_pending(
+				PendingKind::InsertCodepoint { value, digits },
+				format!("U+{value:0width$X}"),
+			);
+			return KeyResult::Consumed;
+		}
+
+		match char::from_u32(value) {
+			Some(ch) => self.dispatch_pending_char("insert_unicode", ch),
+			None => {
 				self.reset_params();
-				KeyResult::ModeChange(Mode::Normal)
+				self.leave_pending();
+				KeyResult::InvalidCodepoint(value)
 			}
-			_ => KeyResult::Consumed,
+		}
+	}
+
+	/// Completes a pending action with its character, returning to the mode
+	/// it was entered from.
+	fn dispatch_pending_char(&mut self, action_name: &str, ch: char) -> KeyResult {
+		let count = self.effective_count() as usize;
+		let extend = self.extend;
+		let register = self.register;
+		self.reset_params();
+		self.leave_pending();
+		let id = resolve_action_id(action_name)
+			.unwrap_or_else(|| panic!("{action_name} action not registered"));
+		KeyResult::ActionByIdWithChar {
+			id,
+			count,
+			extend,
+			register,
+			char_arg: ch,
 		}
 	}
 }
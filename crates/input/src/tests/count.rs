@@ -0,0 +1,104 @@
+//! Count prefix tests.
+//!
+//! These dispatch through the keymap registry, so they check the action that
+//! receives the count as well as the handler state left behind.
+
+use xeno_base::PendingKind;
+use xeno_base::key::{Key, KeyCode};
+use xeno_core::find_action_by_id;
+
+use crate::types::Mode;
+use crate::{InputHandler, KeyResult};
+
+/// Feeds `keys` to the handler, returning the result of the last one.
+fn feed(h: &mut InputHandler, keys: &str) -> KeyResult {
+	let mut result = KeyResult::Consumed;
+	for c in keys.chars() {
+		result = h.handle_key(Key::char(c));
+	}
+	result
+}
+
+/// Returns the dispatched action name and count.
+fn action(result: KeyResult) -> (&'static str, usize) {
+	match result {
+		KeyResult::ActionById { id, count, .. }
+		| KeyResult::ActionByIdWithChar { id, count, .. } => (
+			find_action_by_id(id).expect("action registered").name,
+			count,
+		),
+		other => panic!("expected an action, got {other:?}"),
+	}
+}
+
+#[test]
+fn count_applies_to_motion_once() {
+	let mut h = InputHandler::new();
+	assert_eq!(action(feed(&mut h, "3j")), ("move_down_visual", 3));
+	assert_eq!(h.count(), 0);
+	assert_eq!(action(feed(&mut h, "j")), ("move_down_visual", 1));
+}
+
+#[test]
+fn count_applies_to_edit() {
+	let mut h = InputHandler::new();
+	assert_eq!(action(feed(&mut h, "12d")), ("delete", 12));
+	assert_eq!(h.count(), 0);
+}
+
+#[test]
+fn count_survives_prefix_sequence() {
+	let mut h = InputHandler::new();
+	assert!(matches!(feed(&mut h, "4g"), KeyResult::Pending { .. }));
+	assert_eq!(h.count(), 4);
+	assert_eq!(action(feed(&mut h, "g")), ("document_start", 4));
+}
+
+#[test]
+fn count_survives_pending_char() {
+	let mut h = InputHandler::new();
+	assert_eq!(action(feed(&mut h, "3f")), ("find_char", 3));
+
+	// The editor enters the pending state once `find_char` asks for a char.
+	h.set_mode(Mode::PendingAction(PendingKind::FindChar {
+		inclusive: true,
+	}));
+	assert_eq!(h.count(), 3);
+	assert_eq!(action(feed(&mut h, "x")), ("find_char", 3));
+	assert_eq!(h.count(), 0);
+}
+
+#[test]
+fn pending_state_without_dispatch_has_no_count() {
+	let mut h = InputHandler::new();
+	assert_eq!(action(feed(&mut h, "3j")), ("move_down_visual", 3));
+	feed(&mut h, "5");
+	h.handle_key(Key::new(KeyCode::Esc));
+
+	h.set_mode(Mode::PendingAction(PendingKind::ReplaceChar));
+	assert_eq!(h.count(), 0);
+}
+
+#[test]
+fn escape_cancels_count() {
+	let mut h = InputHandler::new();
+	feed(&mut h, "5");
+	assert!(matches!(
+		h.handle_key(Key::new(KeyCode::Esc)),
+		KeyResult::Consumed
+	));
+	assert_eq!(h.count(), 0);
+
+	feed(&mut h, "7g");
+	h.handle_key(Key::new(KeyCode::Esc));
+	assert_eq!(h.count(), 0);
+	assert_eq!(h.pending_key_count(), 0);
+	assert_eq!(action(feed(&mut h, "j")), ("move_down_visual", 1));
+}
+
+#[test]
+fn leading_zero_is_line_start() {
+	let mut h = InputHandler::new();
+	assert_eq!(action(feed(&mut h, "0")), ("move_line_start", 1));
+	assert_eq!(action(feed(&mut h, "10j")), ("move_down_visual", 10));
+}
@@ -8,6 +8,11 @@ use xeno_base::key::Key;
 
 use crate::InputHandler;
 
+/// Count prefix entry, cancellation, and consumption.
+mod count;
+/// Pending action prompts, cancellation, and timeout.
+mod pending;
+
 #[test]
 fn test_digit_count_accumulates() {
 	let mut h = InputHandler::new();
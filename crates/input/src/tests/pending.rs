@@ -0,0 +1,84 @@
+//! Pending action tests.
+//!
+//! Cancelling never resolves an action, so these only check the state the
+//! handler is left in.
+
+use std::time::Duration;
+
+use xeno_base::PendingKind;
+use xeno_base::key::{Key, KeyCode};
+
+use crate::types::Mode;
+use crate::{InputHandler, KeyResult};
+
+/// Returns a handler waiting on `r` after entering from `mode`.
+fn pending_from(mode: Mode) -> InputHandler {
+	let mut h = InputHandler::new();
+	h.set_mode(mode);
+	h.set_pending(PendingKind::ReplaceChar, "replace");
+	h
+}
+
+#[test]
+fn prompt_shows_while_pending() {
+	let mut h = pending_from(Mode::Normal);
+	assert_eq!(h.pending_prompt(), Some("replace"));
+
+	h.cancel_pending();
+	assert_eq!(h.pending_prompt(), None);
+}
+
+#[test]
+fn escape_restores_prior_mode() {
+	let mut h = pending_from(Mode::Insert);
+	assert!(matches!(
+		h.handle_key(Key::new(KeyCode::Esc)),
+		KeyResult::ModeChange(Mode::Insert)
+	));
+	assert_eq!(h.mode(), Mode::Insert);
+	assert_eq!(h.pending_prompt(), None);
+}
+
+#[test]
+fn ctrl_c_cancels() {
+	let mut h = pending_from(Mode::Normal);
+	assert!(matches!(
+		h.handle_key(Key::ctrl('c')),
+		KeyResult::ModeChange(Mode::Normal)
+	));
+	assert_eq!(h.mode(), Mode::Normal);
+}
+
+#[test]
+fn non_text_keys_cancel() {
+	for key in [
+		Key::new(KeyCode::F(5)),
+		Key::new(KeyCode::Left),
+		Key::alt('x'),
+	] {
+		let mut h = pending_from(Mode::Insert);
+		assert!(
+			matches!(h.handle_key(key), KeyResult::ModeChange(Mode::Insert)),
+			"{key:?} should cancel"
+		);
+		assert_eq!(h.mode(), Mode::Insert);
+	}
+}
+
+#[test]
+fn cancel_outside_pending_is_noop() {
+	let mut h = InputHandler::new();
+	h.set_mode(Mode::Insert);
+	assert_eq!(h.cancel_pending(), None);
+	assert_eq!(h.mode(), Mode::Insert);
+}
+
+#[test]
+fn timeout_expires_only_while_pending() {
+	let mut h = pending_from(Mode::Normal);
+	assert!(h.pending_expired(Duration::ZERO));
+	assert!(!h.pending_expired(Duration::from_secs(3600)));
+
+	h.cancel_pending();
+	assert!(!h.pending_expired(Duration::ZERO));
+}
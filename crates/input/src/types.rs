@@ -45,6 +45,8 @@ pub enum KeyResult {
 	Unhandled,
 	/// Insert a character (in insert mode).
 	InsertChar(char),
+	/// A codepoint typed after `ctrl-v u` that is not a valid `char`.
+	InvalidCodepoint(u32),
 	/// Request to quit.
 	Quit,
 	/// Mouse click at screen coordinates.
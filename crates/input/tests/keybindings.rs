@@ -222,3 +222,118 @@ fn test_insert_mode_shift_preserves_uppercase_typing() {
 		other => panic!("expected InsertChar('A'), got {:?}", other),
 	}
 }
+
+#[test]
+fn test_insert_mode_line_editing_chords() {
+	let mut h = InputHandler::new();
+	h.set_mode(Mode::Insert);
+	for (c, expected) in [
+		('w', "delete_word_backward"),
+		('u', "delete_to_line_start"),
+		('r', "insert_register"),
+	] {
+		let (name, _) = extract_action(h.handle_key(Key::ctrl(c)))
+			.unwrap_or_else(|| panic!("ctrl-{c} should dispatch in insert mode"));
+		assert_eq!(name, expected);
+	}
+}
+
+#[test]
+fn test_insert_register_returns_to_insert_mode() {
+	let mut h = InputHandler::new();
+	h.set_mode(Mode::Insert);
+	h.set_mode(Mode::PendingAction(xeno_base::PendingKind::InsertRegister));
+
+	match h.handle_key(Key::char('"')) {
+		KeyResult::ActionByIdWithChar { id, char_arg, .. } => {
+			assert_eq!(find_action_by_id(id).unwrap().name, "insert_register");
+			assert_eq!(char_arg, '"');
+		}
+		other => panic!("expected insert_register with a char, got {other:?}"),
+	}
+	assert_eq!(h.mode(), Mode::Insert);
+}
+
+#[test]
+fn test_pending_char_returns_to_normal_mode() {
+	let mut h = InputHandler::new();
+	h.set_mode(Mode::PendingAction(xeno_base::PendingKind::ReplaceChar));
+	h.handle_key(Key::char('x'));
+	assert_eq!(h.mode(), Mode::Normal);
+}
+
+/// Enters `ctrl-v` from insert mode and feeds `keys`, returning the last result.
+fn feed_literal(h: &mut InputHandler, keys: &[Key]) -> KeyResult {
+	h.set_mode(Mode::Insert);
+	let (name, _) = extract_action(h.handle_key(Key::ctrl('v'))).expect("ctrl-v dispatches");
+	assert_eq!(name, "insert_unicode");
+	h.set_pending(xeno_base::PendingKind::InsertLiteral, "literal");
+
+	let mut result = KeyResult::Consumed;
+	for &key in keys {
+		result = h.handle_key(key);
+	}
+	result
+}
+
+fn literal_char(result: KeyResult) -> char {
+	match result {
+		KeyResult::ActionByIdWithChar { id, char_arg, .. } => {
+			assert_eq!(find_action_by_id(id).unwrap().name, "insert_unicode");
+			char_arg
+		}
+		other => panic!("expected insert_unicode with a char, got {other:?}"),
+	}
+}
+
+#[test]
+fn test_ctrl_v_inserts_next_key_literally() {
+	let mut h = InputHandler::new();
+	let result = feed_literal(&mut h, &[Key::new(KeyCode::Tab)]);
+	assert_eq!(literal_char(result), '\t');
+	assert_eq!(h.mode(), Mode::Insert);
+
+	let result = feed_literal(&mut h, &[Key::char('x')]);
+	assert_eq!(literal_char(result), 'x');
+}
+
+#[test]
+fn test_ctrl_v_u_reads_hex_codepoint() {
+	let mut h = InputHandler::new();
+	let digits = [
+		Key::char('u'),
+		Key::char('0'),
+		Key::char('0'),
+		Key::char('e'),
+	];
+	assert!(matches!(feed_literal(&mut h, &digits), KeyResult::Consumed));
+	assert_eq!(h.pending_prompt(), Some("U+00E"));
+
+	h.handle_key(Key::char('9'));
+	assert_eq!(h.pending_prompt(), Some("U+00E9"));
+	assert_eq!(literal_char(h.handle_key(Key::new(KeyCode::Enter))), 'é');
+	assert_eq!(h.mode(), Mode::Insert);
+
+	let keys: Vec<_> = "u01f600".chars().map(Key::char).collect();
+	assert_eq!(literal_char(feed_literal(&mut h, &keys)), '😀');
+}
+
+#[test]
+fn test_ctrl_v_u_rejects_invalid_codepoints() {
+	let mut h = InputHandler::new();
+	for (input, value) in [("ud800\n", 0xD800), ("u110000", 0x110000)] {
+		let keys: Vec<_> = input
+			.chars()
+			.map(|c| match c {
+				'\n' => Key::new(KeyCode::Enter),
+				c => Key::char(c),
+			})
+			.collect();
+		let result = feed_literal(&mut h, &keys);
+		assert!(
+			matches!(result, KeyResult::InvalidCodepoint(v) if v == value),
+			"{input:?} should be rejected, got {result:?}"
+		);
+		assert_eq!(h.mode(), Mode::Insert);
+	}
+}
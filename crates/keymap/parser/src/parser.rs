@@ -364,6 +364,21 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_parse_disambiguated_modifiers() {
+		let ctrl_shift = Modifier::Ctrl as u8 | Modifier::Shift as u8;
+		for (input, expected) in [
+			("ctrl-i", Node::new(Modifier::Ctrl as u8, Key::Char('i'))),
+			("shift-enter", Node::new(Modifier::Shift as u8, Key::Enter)),
+			("ctrl-shift-p", Node::new(ctrl_shift, Key::Char('p'))),
+			("shift-ctrl-p", Node::new(ctrl_shift, Key::Char('p'))),
+			("shift-tab", Node::new(Modifier::Shift as u8, Key::Tab)),
+		] {
+			assert_eq!(parse(input), Ok(expected), "{input}");
+		}
+		assert_ne!(parse("ctrl-i"), parse("tab"));
+	}
+
 	#[test]
 	fn test_parse_seq() {
 		for (s, v) in [
@@ -68,8 +68,9 @@ fn parse_language_node(node: &KdlNode) -> Result<Option<LanguageData>> {
 	comment_tokens.extend(parse_string_args(children, "comment-tokens"));
 
 	let block_comment = parse_block_comment(node, children);
+	let indent_open_tokens = parse_string_args(children, "indent-open-tokens");
 
-	Ok(Some(LanguageData::new(
+	let lang = LanguageData::new(
 		name,
 		grammar,
 		extensions,
@@ -79,7 +80,12 @@ fn parse_language_node(node: &KdlNode) -> Result<Option<LanguageData>> {
 		comment_tokens,
 		block_comment,
 		injection_regex,
-	)))
+	);
+	Ok(Some(if indent_open_tokens.is_empty() {
+		lang
+	} else {
+		lang.with_indent_open_tokens(indent_open_tokens)
+	}))
 }
 
 /// Parses file-types from either simple args (`file-types rs toml`) or
@@ -235,6 +241,21 @@ language name=python scope=source.python comment-token="#" {
 		assert_eq!(langs[0].comment_tokens, vec!["#"]);
 	}
 
+	#[test]
+	fn parse_indent_open_tokens() {
+		let kdl = r#"
+language name=rust scope=source.rust {
+    indent-open-tokens "{" "(" "["
+}
+language name=python scope=source.python {
+    file-types py
+}
+"#;
+		let langs = parse_language_configs(kdl).unwrap();
+		assert_eq!(langs[0].indent_open_tokens, vec!["{", "(", "["]);
+		assert_eq!(langs[1].indent_open_tokens, vec!["{", "(", "[", ":"]);
+	}
+
 	#[test]
 	fn load_embedded_languages() {
 		let langs = load_language_configs().expect("embedded languages.kdl should parse");
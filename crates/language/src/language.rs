@@ -11,6 +11,10 @@ use xeno_registry_themes::SyntaxStyles;
 use crate::grammar::load_grammar_or_build;
 use crate::query::read_query;
 
+/// Line endings after which smart indentation opens a new indent level,
+/// used when a language does not configure its own.
+pub const DEFAULT_INDENT_OPEN_TOKENS: &[&str] = &["{", "(", "[", ":"];
+
 /// Language data with lazily-loaded syntax configuration.
 ///
 /// Each registered language has its grammar and queries loaded on first use.
@@ -34,6 +38,8 @@ pub struct LanguageData {
 	pub block_comment: Option<(String, String)>,
 	/// Injection regex for matching in code blocks.
 	pub injection_regex: Option<regex::Regex>,
+	/// Line endings after which smart indentation adds an indent level.
+	pub indent_open_tokens: Vec<String>,
 	/// Lazily-loaded syntax configuration.
 	config: OnceCell<Option<TreeHouseConfig>>,
 }
@@ -69,10 +75,20 @@ impl LanguageData {
 					.map_err(|e| warn!(regex = r, error = %e, "Invalid injection regex"))
 					.ok()
 			}),
+			indent_open_tokens: DEFAULT_INDENT_OPEN_TOKENS
+				.iter()
+				.map(|t| t.to_string())
+				.collect(),
 			config: OnceCell::new(),
 		}
 	}
 
+	/// Replaces the tokens that open an indent level for smart indentation.
+	pub fn with_indent_open_tokens(mut self, tokens: Vec<String>) -> Self {
+		self.indent_open_tokens = tokens;
+		self
+	}
+
 	/// Returns the syntax configuration, loading it if necessary.
 	///
 	/// This loads the grammar and compiles the queries on first access.
@@ -8,6 +8,7 @@
 //! - [`grammar`]: Dynamic grammar loading from shared libraries
 //! - [`language`]: Language metadata (extensions, filenames, shebangs)
 //! - [`loader`]: Language registry implementing `tree_house::LanguageLoader`
+//! - [`modeline`]: File type hints from Vim and Emacs modelines
 //! - [`query`]: Query types for indentation, text objects, tags
 //! - [`highlight`]: Syntax highlighting via tree-sitter queries
 //! - [`config`]: Language configuration parsing from KDL
@@ -27,6 +28,7 @@ pub mod highlight;
 pub mod language;
 pub mod loader;
 pub mod lsp_config;
+pub mod modeline;
 pub mod query;
 pub mod runtime;
 pub mod syntax;
@@ -37,7 +39,7 @@ pub use grammar::{
 	load_grammar_or_build, query_search_paths, runtime_dir,
 };
 pub use highlight::{Highlight, HighlightEvent, HighlightSpan, HighlightStyles, Highlighter};
-pub use language::LanguageData;
+pub use language::{DEFAULT_INDENT_OPEN_TOKENS, LanguageData};
 pub use loader::{LanguageId, LanguageLoader};
 pub use lsp_config::{
 	LanguageLspInfo, LanguageLspMapping, LspConfigError, LspServerDef, load_language_lsp_mapping,
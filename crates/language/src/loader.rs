@@ -7,12 +7,14 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::Path;
 
+use ropey::RopeSlice;
 use tracing::error;
 pub use tree_house::Language as LanguageId;
 use tree_house::{InjectionLanguageMarker, Language, LanguageConfig as TreeHouseConfig};
 
 use crate::config::load_language_configs;
 use crate::language::LanguageData;
+use crate::modeline::{MODELINE_LINES, modeline_file_type};
 
 /// Simple glob pattern matching for file detection.
 ///
@@ -192,6 +194,43 @@ impl LanguageLoader {
 		})
 	}
 
+	/// Detects the language of a document.
+	///
+	/// Checks, in order: exact filename, extension and glob matches on
+	/// `path`, the shebang on the first line, and a Vim or Emacs modeline in
+	/// the first or last [`MODELINE_LINES`] lines.
+	pub fn detect_language(&self, path: Option<&Path>, text: RopeSlice) -> Option<Language> {
+		if let Some(lang) = path.and_then(|p| self.language_for_path(p)) {
+			return Some(lang);
+		}
+
+		let line = |idx: usize| text.line(idx).to_string();
+		let len = text.len_lines();
+		if len > 0
+			&& let Some(lang) = self.language_for_shebang(line(0).trim_end())
+		{
+			return Some(lang);
+		}
+
+		let head = 0..len.min(MODELINE_LINES);
+		let tail = len.saturating_sub(MODELINE_LINES).max(head.end)..len;
+		head.chain(tail).find_map(|idx| {
+			modeline_file_type(&line(idx)).and_then(|ft| self.language_for_file_type(ft))
+		})
+	}
+
+	/// Finds a language by a file type name as written in a modeline.
+	///
+	/// Tries the name itself, then the name as a shebang interpreter (`sh`)
+	/// and as an extension (`py`).
+	pub fn language_for_file_type(&self, file_type: &str) -> Option<Language> {
+		let file_type = file_type.to_ascii_lowercase();
+		[&self.by_name, &self.by_shebang, &self.by_extension]
+			.iter()
+			.find_map(|map| map.get(&file_type))
+			.map(|&idx| Language::new(idx as u32))
+	}
+
 	/// Finds a language by matching text against injection regexes.
 	fn language_for_injection_match(&self, text: &str) -> Option<Language> {
 		self.languages.iter().enumerate().find_map(|(idx, lang)| {
@@ -295,4 +334,50 @@ mod tests {
 		);
 		assert_eq!(loader.language_for_shebang("not a shebang"), None);
 	}
+
+	#[test]
+	fn detection_order() {
+		let mut loader = LanguageLoader::new();
+		let make = LanguageData::new(
+			"make".to_string(),
+			None,
+			vec!["mk".to_string()],
+			vec!["Makefile".to_string()],
+			vec![],
+			vec![],
+			vec!["#".to_string()],
+			None,
+			None,
+		);
+		let python = LanguageData::new(
+			"python".to_string(),
+			None,
+			vec!["py".to_string()],
+			vec![],
+			vec![],
+			vec!["python".to_string()],
+			vec!["#".to_string()],
+			None,
+			None,
+		);
+		let make = loader.register(make);
+		let python = loader.register(python);
+
+		let detect = |path: Option<&str>, text: &str| {
+			let text = ropey::Rope::from(text);
+			loader.detect_language(path.map(Path::new), text.slice(..))
+		};
+		assert_eq!(
+			detect(Some("src/Makefile"), "#!/usr/bin/env python3\n"),
+			Some(make)
+		);
+		assert_eq!(detect(Some("build.py"), ""), Some(python));
+		assert_eq!(
+			detect(Some("script"), "#!/usr/bin/env python3\n"),
+			Some(python)
+		);
+		assert_eq!(detect(None, "all:\n\n# vim: set ft=make:\n"), Some(make));
+		assert_eq!(detect(None, "x = 1\n# -*- mode: py -*-"), Some(python));
+		assert_eq!(detect(Some("notes.txt"), "plain\n"), None);
+	}
 }
@@ -0,0 +1,82 @@
+//! Vim and Emacs modeline parsing.
+//!
+//! Only the file type is read from a modeline:
+//!
+//! - Vim: `vim: set ft=python:` or `vi: filetype=python` (also `ex:`)
+//! - Emacs: `-*- mode: python -*-` or the short form `-*- python -*-`
+
+/// Number of lines at each end of a document searched for a modeline.
+pub const MODELINE_LINES: usize = 5;
+
+/// Returns the file type named by a modeline on `line`, if any.
+pub fn modeline_file_type(line: &str) -> Option<&str> {
+	vim_file_type(line).or_else(|| emacs_file_type(line))
+}
+
+/// Parses the file type from a Vim modeline.
+fn vim_file_type(line: &str) -> Option<&str> {
+	let options = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+		line.match_indices(marker)
+			.find(|&(idx, _)| idx == 0 || line[..idx].ends_with(char::is_whitespace))
+			.map(|(idx, _)| &line[idx + marker.len()..])
+	})?;
+	let options = options.trim_start();
+	let options = options
+		.strip_prefix("set ")
+		.or_else(|| options.strip_prefix("se "))
+		.unwrap_or(options);
+
+	options
+		.split(|c: char| c == ':' || c.is_whitespace())
+		.find_map(|opt| {
+			opt.strip_prefix("ft=")
+				.or_else(|| opt.strip_prefix("filetype="))
+		})
+		.filter(|ft| !ft.is_empty())
+}
+
+/// Parses the major mode from an Emacs `-*- ... -*-` line.
+fn emacs_file_type(line: &str) -> Option<&str> {
+	let (_, rest) = line.split_once("-*-")?;
+	let (vars, _) = rest.split_once("-*-")?;
+	let vars = vars.trim();
+
+	if !vars.contains(':') {
+		return Some(vars).filter(|mode| !mode.is_empty());
+	}
+	vars.split(';').find_map(|var| {
+		let (key, value) = var.split_once(':')?;
+		key.trim()
+			.eq_ignore_ascii_case("mode")
+			.then(|| value.trim())
+			.filter(|mode| !mode.is_empty())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn vim_modelines() {
+		assert_eq!(modeline_file_type("# vim: set ft=python:"), Some("python"));
+		assert_eq!(
+			modeline_file_type("// vim: ts=4 filetype=rust"),
+			Some("rust")
+		);
+		assert_eq!(modeline_file_type("/* vi:ft=c */"), Some("c"));
+		assert_eq!(modeline_file_type("# vim: ts=4 sw=4"), None);
+		assert_eq!(modeline_file_type("let evim:ft=x"), None);
+	}
+
+	#[test]
+	fn emacs_modelines() {
+		assert_eq!(modeline_file_type(";; -*- mode: lisp -*-"), Some("lisp"));
+		assert_eq!(
+			modeline_file_type("# -*- coding: utf-8; Mode: python -*-"),
+			Some("python")
+		);
+		assert_eq!(modeline_file_type("-*- ruby -*-"), Some("ruby"));
+		assert_eq!(modeline_file_type("-*- coding: utf-8 -*-"), None);
+	}
+}
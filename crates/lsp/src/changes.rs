@@ -7,53 +7,66 @@ use crate::client::OffsetEncoding;
 use crate::position::{char_range_to_lsp_range, char_to_lsp_position};
 
 /// Computes LSP change events from a transaction against pre-change text.
+///
+/// Each event is positioned against the text left by the events before it,
+/// as `textDocument/didChange` applies them in order. An insertion next to a
+/// deletion at the same place becomes one replacement event.
+///
+/// Returns `None` if a position cannot be converted to `encoding`, in which
+/// case the document has to be synced in full.
 pub fn compute_lsp_changes(
 	rope: &Rope,
 	tx: &Transaction,
 	encoding: OffsetEncoding,
-) -> Vec<LspDocumentChange> {
+) -> Option<Vec<LspDocumentChange>> {
 	let mut changes = Vec::new();
 	if tx.changes().is_empty() {
-		return changes;
+		return Some(changes);
 	}
 
 	let mut scratch = rope.clone();
 	let mut pos = 0usize;
+	let mut ops = tx.operations().iter().peekable();
 
-	for op in tx.operations() {
-		match op {
+	while let Some(op) = ops.next() {
+		let (deleted, inserted) = match op {
 			Operation::Retain(n) => {
 				pos += n;
+				continue;
 			}
-			Operation::Delete(n) => {
-				let end = (pos + n).min(scratch.len_chars());
-				let Some(range) = char_range_to_lsp_range(&scratch, pos, end, encoding) else {
-					return Vec::new();
-				};
-				changes.push(LspDocumentChange {
-					range: LspRange::new(
-						LspPosition::new(range.start.line, range.start.character),
-						LspPosition::new(range.end.line, range.end.character),
-					),
-					new_text: String::new(),
-				});
-				scratch.remove(pos..end);
-			}
-			Operation::Insert(ins) => {
-				let Some(lsp_pos) = char_to_lsp_position(&scratch, pos, encoding) else {
-					return Vec::new();
-				};
-				changes.push(LspDocumentChange {
-					range: LspRange::point(LspPosition::new(lsp_pos.line, lsp_pos.character)),
-					new_text: ins.text.clone(),
-				});
-				scratch.insert(pos, &ins.text);
-				pos += ins.char_len;
-			}
+			Operation::Delete(n) => match ops.peek() {
+				Some(Operation::Insert(ins)) => {
+					ops.next();
+					(*n, Some(ins))
+				}
+				_ => (*n, None),
+			},
+			Operation::Insert(ins) => match ops.peek() {
+				Some(Operation::Delete(n)) => {
+					ops.next();
+					(*n, Some(ins))
+				}
+				_ => (0, Some(ins)),
+			},
+		};
+
+		let end = (pos + deleted).min(scratch.len_chars());
+		let range = char_range_to_lsp_range(&scratch, pos, end, encoding)?;
+		changes.push(LspDocumentChange {
+			range: LspRange::new(
+				LspPosition::new(range.start.line, range.start.character),
+				LspPosition::new(range.end.line, range.end.character),
+			),
+			new_text: inserted.map(|ins| ins.text.clone()).unwrap_or_default(),
+		});
+		scratch.remove(pos..end);
+		if let Some(ins) = inserted {
+			scratch.insert(pos, &ins.text);
+			pos += ins.char_len;
 		}
 	}
 
-	changes
+	Some(changes)
 }
 
 #[cfg(test)]
@@ -69,7 +82,7 @@ mod tests {
 		let sel = Selection::single(6, 6);
 		let tx = Transaction::insert(rope.slice(..), &sel, "beautiful ".to_string());
 
-		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16);
+		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16).unwrap();
 
 		assert_eq!(changes.len(), 1);
 		assert_eq!(changes[0].range, LspRange::point(LspPosition::new(1, 0)));
@@ -82,7 +95,7 @@ mod tests {
 		let sel = Selection::single(6, 12);
 		let tx = Transaction::delete(rope.slice(..), &sel);
 
-		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16);
+		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16).unwrap();
 
 		assert_eq!(changes.len(), 1);
 		assert_eq!(
@@ -109,7 +122,7 @@ mod tests {
 		];
 		let tx = Transaction::change(rope.slice(..), changes);
 
-		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16);
+		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16).unwrap();
 
 		assert_eq!(changes.len(), 2);
 		assert_eq!(changes[0].range, LspRange::point(LspPosition::new(0, 0)));
@@ -117,4 +130,78 @@ mod tests {
 		assert_eq!(changes[1].range, LspRange::point(LspPosition::new(2, 0)));
 		assert_eq!(changes[1].new_text, "X");
 	}
+
+	#[test]
+	fn test_replacement_is_one_change() {
+		let rope = Rope::from("let a = 1;\n");
+		let changes = vec![Change {
+			start: 4,
+			end: 5,
+			replacement: Some("value".to_string()),
+		}];
+		let tx = Transaction::change(rope.slice(..), changes);
+
+		let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16).unwrap();
+
+		assert_eq!(changes.len(), 1);
+		assert_eq!(
+			changes[0].range,
+			LspRange::new(LspPosition::new(0, 4), LspPosition::new(0, 5))
+		);
+		assert_eq!(changes[0].new_text, "value");
+	}
+
+	/// Byte offset of a UTF-16 position in `text`, the way a server finds it.
+	fn utf16_offset(text: &str, pos: LspPosition) -> usize {
+		let line_start = text
+			.split_inclusive('\n')
+			.take(pos.line as usize)
+			.map(str::len)
+			.sum::<usize>();
+		let mut units = 0;
+		for (offset, ch) in text[line_start..].char_indices() {
+			if units >= pos.character as usize || ch == '\n' {
+				return line_start + offset;
+			}
+			units += ch.len_utf16();
+		}
+		text.len()
+	}
+
+	#[test]
+	fn test_random_edits_replay_on_server_text() {
+		const PIECES: &[&str] = &["a", "é", "\n", "😀", "中", " ", "x\ny", "🦀🦀"];
+		let mut seed = 0x2545_f491_4f6c_dd1du64;
+		let mut next = |bound: usize| {
+			seed = seed
+				.wrapping_mul(6_364_136_223_846_793_005)
+				.wrapping_add(1_442_695_040_888_963_407);
+			((seed >> 33) as usize) % bound.max(1)
+		};
+
+		let mut rope = Rope::from("fn main() {\n\t😀 = \"é\";\n}\n");
+		let mut server = rope.to_string();
+		for _ in 0..500 {
+			let mut points: Vec<usize> = (0..next(3) * 2 + 2)
+				.map(|_| next(rope.len_chars() + 1))
+				.collect();
+			points.sort_unstable();
+			let edits = points.chunks(2).map(|pair| Change {
+				start: pair[0],
+				end: if next(2) == 0 { pair[0] } else { pair[1] },
+				replacement: (next(3) > 0).then(|| PIECES[next(PIECES.len())].to_string()),
+			});
+			let edits: Vec<_> = edits.collect();
+			let tx = Transaction::change(rope.slice(..), edits);
+
+			let changes = compute_lsp_changes(&rope, &tx, OffsetEncoding::Utf16).unwrap();
+			tx.apply(&mut rope);
+			for change in changes {
+				let start = utf16_offset(&server, change.range.start);
+				let end = utf16_offset(&server, change.range.end);
+				server.replace_range(start..end, &change.new_text);
+			}
+			assert_eq!(server, rope.to_string());
+		}
+	}
 }
@@ -6,7 +6,8 @@ use lsp_types::{
 	GeneralClientCapabilities, HoverClientCapabilities, MarkupKind, PositionEncodingKind,
 	PublishDiagnosticsClientCapabilities, RenameClientCapabilities,
 	SignatureHelpClientCapabilities, SignatureInformationSettings, TagSupport,
-	TextDocumentClientCapabilities, WindowClientCapabilities, WorkspaceClientCapabilities,
+	TextDocumentClientCapabilities, TextDocumentSyncClientCapabilities, WindowClientCapabilities,
+	WorkspaceClientCapabilities,
 };
 
 /// Build client capabilities for initialization.
@@ -27,7 +28,7 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 				dynamic_registration: Some(false),
 			}),
 			inlay_hint: Some(lsp_types::InlayHintWorkspaceClientCapabilities {
-				refresh_support: Some(false),
+				refresh_support: Some(true),
 			}),
 			workspace_edit: Some(lsp_types::WorkspaceEditClientCapabilities {
 				document_changes: Some(true),
@@ -55,6 +56,12 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 			..Default::default()
 		}),
 		text_document: Some(TextDocumentClientCapabilities {
+			synchronization: Some(TextDocumentSyncClientCapabilities {
+				dynamic_registration: Some(false),
+				will_save: Some(true),
+				will_save_wait_until: Some(true),
+				did_save: Some(true),
+			}),
 			completion: Some(CompletionClientCapabilities {
 				completion_item: Some(CompletionItemCapability {
 					snippet_support: Some(enable_snippets),
@@ -101,6 +108,9 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 			formatting: Some(lsp_types::DocumentFormattingClientCapabilities {
 				dynamic_registration: Some(false),
 			}),
+			range_formatting: Some(lsp_types::DocumentRangeFormattingClientCapabilities {
+				dynamic_registration: Some(false),
+			}),
 			code_action: Some(lsp_types::CodeActionClientCapabilities {
 				code_action_literal_support: Some(lsp_types::CodeActionLiteralSupport {
 					code_action_kind: lsp_types::CodeActionKindLiteralSupport {
@@ -146,6 +156,12 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 				dynamic_registration: Some(false),
 				resolve_support: None,
 			}),
+			semantic_tokens: Some(semantic_tokens_capabilities()),
+			folding_range: Some(lsp_types::FoldingRangeClientCapabilities {
+				dynamic_registration: Some(false),
+				line_folding_only: Some(true),
+				..Default::default()
+			}),
 			..Default::default()
 		}),
 		window: Some(WindowClientCapabilities {
@@ -154,9 +170,9 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 			..Default::default()
 		}),
 		general: Some(GeneralClientCapabilities {
+			// In order of preference; servers that pick none of them use UTF-16.
 			position_encodings: Some(vec![
 				PositionEncodingKind::UTF8,
-				PositionEncodingKind::UTF32,
 				PositionEncodingKind::UTF16,
 			]),
 			..Default::default()
@@ -164,3 +180,61 @@ pub fn client_capabilities(enable_snippets: bool) -> ClientCapabilities {
 		..Default::default()
 	}
 }
+
+/// Semantic token requests and the token types and modifiers understood.
+fn semantic_tokens_capabilities() -> lsp_types::SemanticTokensClientCapabilities {
+	use lsp_types::{
+		SemanticTokenModifier, SemanticTokenType, SemanticTokensClientCapabilitiesRequests,
+		SemanticTokensFullOptions, TokenFormat,
+	};
+
+	lsp_types::SemanticTokensClientCapabilities {
+		dynamic_registration: Some(false),
+		requests: SemanticTokensClientCapabilitiesRequests {
+			range: Some(false),
+			full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+		},
+		token_types: vec![
+			SemanticTokenType::NAMESPACE,
+			SemanticTokenType::TYPE,
+			SemanticTokenType::CLASS,
+			SemanticTokenType::ENUM,
+			SemanticTokenType::INTERFACE,
+			SemanticTokenType::STRUCT,
+			SemanticTokenType::TYPE_PARAMETER,
+			SemanticTokenType::PARAMETER,
+			SemanticTokenType::VARIABLE,
+			SemanticTokenType::PROPERTY,
+			SemanticTokenType::ENUM_MEMBER,
+			SemanticTokenType::EVENT,
+			SemanticTokenType::FUNCTION,
+			SemanticTokenType::METHOD,
+			SemanticTokenType::MACRO,
+			SemanticTokenType::KEYWORD,
+			SemanticTokenType::MODIFIER,
+			SemanticTokenType::COMMENT,
+			SemanticTokenType::STRING,
+			SemanticTokenType::NUMBER,
+			SemanticTokenType::REGEXP,
+			SemanticTokenType::OPERATOR,
+			SemanticTokenType::DECORATOR,
+		],
+		token_modifiers: vec![
+			SemanticTokenModifier::DECLARATION,
+			SemanticTokenModifier::DEFINITION,
+			SemanticTokenModifier::READONLY,
+			SemanticTokenModifier::STATIC,
+			SemanticTokenModifier::DEPRECATED,
+			SemanticTokenModifier::ABSTRACT,
+			SemanticTokenModifier::ASYNC,
+			SemanticTokenModifier::MODIFICATION,
+			SemanticTokenModifier::DOCUMENTATION,
+			SemanticTokenModifier::DEFAULT_LIBRARY,
+		],
+		formats: vec![TokenFormat::RELATIVE],
+		overlapping_token_support: Some(false),
+		multiline_token_support: Some(false),
+		server_cancel_support: Some(false),
+		augments_syntax_tokens: Some(true),
+	}
+}
@@ -32,6 +32,9 @@ pub trait LspEventHandler: Send + Sync {
 
 	/// Called when the server wants to show a message to the user.
 	fn on_show_message(&self, _server_id: LanguageServerId, _level: LogLevel, _message: &str) {}
+
+	/// Called when the server asks for all inlay hints to be re-requested.
+	fn on_inlay_hint_refresh(&self, _server_id: LanguageServerId) {}
 }
 
 /// Log level for server messages.
@@ -0,0 +1,352 @@
+//! In-memory log of language server output.
+//!
+//! Every line a server writes to stderr is kept, and while tracing is on
+//! so is a summary of each JSON-RPC message exchanged with it. The log
+//! holds at most [`LOG_CAPACITY`] entries across all servers, dropping the
+//! oldest, and is written to from the transport tasks, which only ever
+//! hold its lock to push one entry. With tracing off, tracing costs one
+//! atomic load per message.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use crate::RequestId;
+use crate::mainloop::{MessageDirection, MessageObserver};
+use crate::message::Message;
+
+/// Entries kept before the oldest are dropped.
+pub const LOG_CAPACITY: usize = 5000;
+
+/// Characters of the params or result shown in a verbose trace entry.
+const MAX_PAYLOAD_CHARS: usize = 200;
+
+/// Requests awaiting a response that a trace remembers, beyond which it
+/// forgets them all; responses to requests sent while tracing was off
+/// never arrive to clear them.
+const MAX_TRACED_REQUESTS: usize = 1024;
+
+/// How much of the JSON-RPC traffic is logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraceLevel {
+	/// Nothing is logged.
+	#[default]
+	Off,
+	/// The method and ID of each message, and how long requests took.
+	Messages,
+	/// Messages, with their params or result cut short.
+	Verbose,
+}
+
+impl TraceLevel {
+	/// Levels in the order [`Self::next`] cycles through them.
+	const ALL: [Self; 3] = [Self::Off, Self::Messages, Self::Verbose];
+
+	/// Parses a level name as used by the `lsp-trace` option.
+	pub fn parse(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|level| level.name() == name)
+	}
+
+	/// Returns the level's name, as used by the `lsp-trace` option.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Off => "off",
+			Self::Messages => "messages",
+			Self::Verbose => "verbose",
+		}
+	}
+
+	/// Returns the next more detailed level, wrapping to off.
+	pub fn next(self) -> Self {
+		Self::ALL[(self as usize + 1) % Self::ALL.len()]
+	}
+}
+
+/// What an entry of the log records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogKind {
+	/// A line the server wrote to stderr.
+	Stderr,
+	/// A message sent to the server.
+	Sent,
+	/// A message received from the server.
+	Received,
+}
+
+/// An entry of the server log.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+	/// Name of the server.
+	pub server: Arc<str>,
+	/// What the entry records.
+	pub kind: LogKind,
+	/// The stderr line or message summary.
+	pub text: String,
+}
+
+/// Log of the output of every language server, shared by a registry.
+#[derive(Debug)]
+pub struct ServerLog {
+	/// Entries, oldest first.
+	entries: Mutex<VecDeque<LogEntry>>,
+	/// Most entries kept.
+	capacity: usize,
+	/// Current [`TraceLevel`], as its index.
+	trace: AtomicU8,
+	/// Bumped on every new entry.
+	version: AtomicU64,
+}
+
+impl Default for ServerLog {
+	fn default() -> Self {
+		Self::new(LOG_CAPACITY)
+	}
+}
+
+impl ServerLog {
+	/// Creates an empty log keeping at most `capacity` entries.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: Mutex::new(VecDeque::with_capacity(capacity.min(LOG_CAPACITY))),
+			capacity: capacity.max(1),
+			trace: AtomicU8::new(TraceLevel::Off as u8),
+			version: AtomicU64::new(0),
+		}
+	}
+
+	/// Returns how much of the JSON-RPC traffic is logged.
+	pub fn trace_level(&self) -> TraceLevel {
+		TraceLevel::ALL[usize::from(self.trace.load(Ordering::Relaxed))]
+	}
+
+	/// Sets how much of the JSON-RPC traffic is logged, taking effect on
+	/// the next message.
+	pub fn set_trace_level(&self, level: TraceLevel) {
+		self.trace.store(level as u8, Ordering::Relaxed);
+	}
+
+	/// Returns a counter bumped on every new entry.
+	pub fn version(&self) -> u64 {
+		self.version.load(Ordering::Relaxed)
+	}
+
+	/// Appends an entry, dropping the oldest if the log is full.
+	pub fn push(&self, server: &Arc<str>, kind: LogKind, text: String) {
+		let entry = LogEntry {
+			server: server.clone(),
+			kind,
+			text,
+		};
+		{
+			let mut entries = self.entries.lock();
+			if entries.len() == self.capacity {
+				entries.pop_front();
+			}
+			entries.push_back(entry);
+		}
+		self.version.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Returns the number of entries of `server`, or of every server.
+	pub fn len(&self, server: Option<&str>) -> usize {
+		let entries = self.entries.lock();
+		match server {
+			Some(server) => entries.iter().filter(|e| &*e.server == server).count(),
+			None => entries.len(),
+		}
+	}
+
+	/// Returns whether the log has no entries.
+	pub fn is_empty(&self) -> bool {
+		self.entries.lock().is_empty()
+	}
+
+	/// Returns up to `count` entries of `server`, or of every server,
+	/// ending `skip` entries before the newest, oldest first.
+	pub fn tail(&self, server: Option<&str>, skip: usize, count: usize) -> Vec<LogEntry> {
+		let entries = self.entries.lock();
+		let mut tail: Vec<_> = entries
+			.iter()
+			.rev()
+			.filter(|e| server.is_none_or(|server| &*e.server == server))
+			.skip(skip)
+			.take(count)
+			.cloned()
+			.collect();
+		tail.reverse();
+		tail
+	}
+
+	/// Returns the names of the servers with entries, in order of their
+	/// first entry still kept.
+	pub fn servers(&self) -> Vec<Arc<str>> {
+		let mut servers: Vec<Arc<str>> = Vec::new();
+		for entry in self.entries.lock().iter() {
+			if !servers.contains(&entry.server) {
+				servers.push(entry.server.clone());
+			}
+		}
+		servers
+	}
+}
+
+/// Protocol trace of one server, logging the messages its main loop reads
+/// and writes.
+pub(crate) struct ProtocolTrace {
+	/// Name of the server.
+	server: Arc<str>,
+	/// Log the trace goes to.
+	log: Arc<ServerLog>,
+	/// Method and send time of traced requests awaiting a response, by the
+	/// direction they went and their ID.
+	requests: Mutex<HashMap<(MessageDirection, String), (String, Instant)>>,
+}
+
+impl ProtocolTrace {
+	/// Creates the trace of `server`.
+	pub(crate) fn new(server: Arc<str>, log: Arc<ServerLog>) -> Self {
+		Self {
+			server,
+			log,
+			requests: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl MessageObserver for ProtocolTrace {
+	fn observe(&self, direction: MessageDirection, message: &Message) {
+		let level = self.log.trace_level();
+		if level == TraceLevel::Off {
+			return;
+		}
+		let verbose = level == TraceLevel::Verbose;
+		let (arrow, kind, answering) = match direction {
+			MessageDirection::Outgoing => ("→", LogKind::Sent, MessageDirection::Incoming),
+			MessageDirection::Incoming => ("←", LogKind::Received, MessageDirection::Outgoing),
+		};
+
+		let mut text = match message {
+			Message::Request(req) => {
+				let id = id_label(&req.id);
+				let mut requests = self.requests.lock();
+				if requests.len() >= MAX_TRACED_REQUESTS {
+					requests.clear();
+				}
+				requests.insert(
+					(direction, id.clone()),
+					(req.method.clone(), Instant::now()),
+				);
+				format!("{arrow} request #{id} {}", req.method)
+			}
+			Message::Response(resp) => {
+				let id = id_label(&resp.id);
+				let request = self.requests.lock().remove(&(answering, id.clone()));
+				let mut text = format!("{arrow} response #{id}");
+				if let Some((method, sent)) = request {
+					text.push_str(&format!(" {method} ({}ms)", sent.elapsed().as_millis()));
+				}
+				if let Some(error) = &resp.error {
+					text.push_str(&format!(" error: {}", error.message));
+				}
+				text
+			}
+			Message::Notification(notif) => format!("{arrow} notification {}", notif.method),
+		};
+		if verbose {
+			let payload = match message {
+				Message::Request(req) => Some(&req.params),
+				Message::Response(resp) => resp.result.as_ref(),
+				Message::Notification(notif) => Some(&notif.params),
+			};
+			if let Some(payload) = payload.filter(|p| !p.is_null()) {
+				text.push(' ');
+				text.push_str(&truncated_json(payload));
+			}
+		}
+		self.log.push(&self.server, kind, text);
+	}
+}
+
+/// Returns the ID of a request as shown in traces.
+fn id_label(id: &RequestId) -> String {
+	match id {
+		RequestId::Number(n) => n.to_string(),
+		RequestId::String(s) => s.clone(),
+	}
+}
+
+/// Returns `value` as JSON, cut short after [`MAX_PAYLOAD_CHARS`]
+/// characters.
+fn truncated_json(value: &Value) -> String {
+	let json = value.to_string();
+	match json.char_indices().nth(MAX_PAYLOAD_CHARS) {
+		Some((end, _)) => format!("{}…", &json[..end]),
+		None => json,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::AnyRequest;
+	use crate::types::AnyResponse;
+
+	#[test]
+	fn log_drops_oldest_entries() {
+		let log = ServerLog::new(2);
+		let (a, b): (Arc<str>, Arc<str>) = ("a".into(), "b".into());
+		log.push(&a, LogKind::Stderr, "one".into());
+		log.push(&b, LogKind::Stderr, "two".into());
+		log.push(&a, LogKind::Stderr, "three".into());
+
+		let texts =
+			|entries: Vec<LogEntry>| entries.into_iter().map(|e| e.text).collect::<Vec<_>>();
+		assert_eq!(texts(log.tail(None, 0, 10)), ["two", "three"]);
+		assert_eq!(texts(log.tail(Some("a"), 0, 10)), ["three"]);
+		assert_eq!(texts(log.tail(None, 1, 1)), ["two"]);
+		assert_eq!(log.servers(), [b, a]);
+		assert_eq!(log.version(), 3);
+	}
+
+	#[test]
+	fn trace_times_requests_only_when_on() {
+		let log = Arc::new(ServerLog::default());
+		let trace = ProtocolTrace::new("ra".into(), log.clone());
+		let request = Message::Request(AnyRequest {
+			id: RequestId::Number(7),
+			method: "textDocument/hover".into(),
+			params: serde_json::json!({ "x": "y".repeat(300) }),
+		});
+		let response = Message::Response(AnyResponse {
+			id: RequestId::Number(7),
+			result: Some(Value::Null),
+			error: None,
+		});
+
+		trace.observe(MessageDirection::Outgoing, &request);
+		assert!(log.is_empty());
+
+		log.set_trace_level(TraceLevel::Verbose);
+		trace.observe(MessageDirection::Outgoing, &request);
+		trace.observe(MessageDirection::Incoming, &response);
+		let entries = log.tail(None, 0, 10);
+		assert_eq!(entries[0].kind, LogKind::Sent);
+		assert!(
+			entries[0]
+				.text
+				.starts_with("→ request #7 textDocument/hover {")
+		);
+		assert!(entries[0].text.ends_with('…'));
+		assert!(
+			entries[1]
+				.text
+				.starts_with("← response #7 textDocument/hover (")
+		);
+		assert_eq!(TraceLevel::Verbose.next(), TraceLevel::Off);
+		assert_eq!(TraceLevel::parse("messages"), Some(TraceLevel::Messages));
+	}
+}
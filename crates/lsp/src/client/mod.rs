@@ -46,12 +46,15 @@ use std::ops::ControlFlow;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use lsp_types::notification::Notification;
 use lsp_types::request::Request;
 use lsp_types::{
 	ClientInfo, InitializeParams, InitializeResult, ServerCapabilities, Uri, WorkspaceFolder,
 };
+use parking_lot::Mutex;
 use serde_json::Value;
 use tokio::process::Command;
 use tokio::sync::{Notify, OnceCell};
@@ -60,11 +63,17 @@ use tracing::{debug, error, info, warn};
 mod capabilities;
 mod config;
 mod event_handler;
+mod log;
+/// Partial results streamed ahead of a response.
+mod partial_results;
 
 pub use capabilities::client_capabilities;
 pub use config::{LanguageServerId, OffsetEncoding, ServerConfig};
 pub use event_handler::{LogLevel, LspEventHandler, NoOpEventHandler, SharedEventHandler};
+pub use log::{LOG_CAPACITY, LogEntry, LogKind, ServerLog, TraceLevel};
 
+use self::log::ProtocolTrace;
+use self::partial_results::{PartialResults, RawProgress};
 use crate::router::Router;
 use crate::{MainLoop, Result, ServerSocket};
 
@@ -89,6 +98,47 @@ pub struct ClientHandle {
 	root_uri: Option<Uri>,
 	/// Notification channel for initialization completion.
 	initialize_notify: Arc<Notify>,
+	/// Callbacks of requests in flight that take partial results.
+	partial_results: Arc<PartialResults>,
+	/// Figures about the server process, for status displays.
+	stats: Arc<ProcessStats>,
+}
+
+/// Figures about a language server process, for status displays.
+#[derive(Debug)]
+struct ProcessStats {
+	/// OS process ID, if the process reported one.
+	pid: Option<u32>,
+	/// When the process was spawned.
+	started_at: Instant,
+	/// Requests sent and not yet answered.
+	pending_requests: AtomicUsize,
+	/// Message of the last failed request or main loop error.
+	last_error: Mutex<Option<String>>,
+}
+
+impl ProcessStats {
+	/// Records `error` as the last error of the server.
+	fn record_error(&self, error: &crate::Error) {
+		*self.last_error.lock() = Some(error.to_string());
+	}
+}
+
+/// Counts a request as pending until dropped, so requests whose future is
+/// dropped before the response arrives are counted out too.
+struct PendingRequest<'a>(&'a AtomicUsize);
+
+impl<'a> PendingRequest<'a> {
+	fn new(count: &'a AtomicUsize) -> Self {
+		count.fetch_add(1, Ordering::Relaxed);
+		Self(count)
+	}
+}
+
+impl Drop for PendingRequest<'_> {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::Relaxed);
+	}
 }
 
 impl std::fmt::Debug for ClientHandle {
@@ -146,18 +196,51 @@ impl ClientHandle {
 			.is_some_and(|c| c.completion_provider.is_some())
 	}
 
+	/// Check if the server fills in completion items on resolve.
+	pub fn supports_completion_resolve(&self) -> bool {
+		self.try_capabilities().is_some_and(|c| {
+			c.completion_provider
+				.as_ref()
+				.is_some_and(|provider| provider.resolve_provider == Some(true))
+		})
+	}
+
 	/// Check if the server supports formatting.
 	pub fn supports_formatting(&self) -> bool {
 		self.try_capabilities()
 			.is_some_and(|c| c.document_formatting_provider.is_some())
 	}
 
+	/// Check if the server supports formatting a range.
+	pub fn supports_range_formatting(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.document_range_formatting_provider.is_some())
+	}
+
 	/// Check if the server supports go to definition.
 	pub fn supports_definition(&self) -> bool {
 		self.try_capabilities()
 			.is_some_and(|c| c.definition_provider.is_some())
 	}
 
+	/// Check if the server supports go to type definition.
+	pub fn supports_type_definition(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.type_definition_provider.is_some())
+	}
+
+	/// Check if the server supports go to implementation.
+	pub fn supports_implementation(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.implementation_provider.is_some())
+	}
+
+	/// Check if the server supports go to declaration.
+	pub fn supports_declaration(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.declaration_provider.is_some())
+	}
+
 	/// Check if the server supports find references.
 	pub fn supports_references(&self) -> bool {
 		self.try_capabilities()
@@ -170,17 +253,189 @@ impl ClientHandle {
 			.is_some_and(|c| c.document_symbol_provider.is_some())
 	}
 
+	/// Check if the server supports workspace symbol search.
+	pub fn supports_workspace_symbol(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.workspace_symbol_provider.is_some())
+	}
+
 	/// Check if the server supports code actions.
 	pub fn supports_code_action(&self) -> bool {
 		self.try_capabilities()
 			.is_some_and(|c| c.code_action_provider.is_some())
 	}
 
+	/// Check if the server fills in code actions lazily.
+	pub fn supports_code_action_resolve(&self) -> bool {
+		self.try_capabilities().is_some_and(|c| {
+			matches!(
+				&c.code_action_provider,
+				Some(lsp_types::CodeActionProviderCapability::Options(options))
+					if options.resolve_provider == Some(true)
+			)
+		})
+	}
+
+	/// Check if the server runs commands.
+	pub fn supports_execute_command(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.execute_command_provider.is_some())
+	}
+
+	/// Check if the server supports rename.
+	pub fn supports_rename(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.rename_provider.is_some())
+	}
+
+	/// Check if the server supports checking a rename position beforehand.
+	pub fn supports_prepare_rename(&self) -> bool {
+		self.try_capabilities().is_some_and(|c| {
+			matches!(
+				c.rename_provider,
+				Some(lsp_types::OneOf::Right(lsp_types::RenameOptions {
+					prepare_provider: Some(true),
+					..
+				}))
+			)
+		})
+	}
+
+	/// Check if the server supports signature help.
+	pub fn supports_signature_help(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.signature_help_provider.is_some())
+	}
+
+	/// Check if the server supports document highlights.
+	pub fn supports_document_highlight(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.document_highlight_provider.is_some())
+	}
+
+	/// Check if the server supports folding ranges.
+	pub fn supports_folding_range(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.folding_range_provider.is_some())
+	}
+
+	/// Check if the server provides semantic tokens for whole documents.
+	pub fn supports_semantic_tokens(&self) -> bool {
+		use lsp_types::SemanticTokensFullOptions;
+
+		self.semantic_tokens_options().is_some_and(|o| {
+			matches!(
+				o.full,
+				Some(
+					SemanticTokensFullOptions::Bool(true) | SemanticTokensFullOptions::Delta { .. }
+				)
+			)
+		})
+	}
+
+	/// Check if the server sends semantic tokens as edits to its previous
+	/// result.
+	pub fn supports_semantic_tokens_delta(&self) -> bool {
+		self.semantic_tokens_options().is_some_and(|o| {
+			matches!(
+				o.full,
+				Some(lsp_types::SemanticTokensFullOptions::Delta { delta: Some(true) })
+			)
+		})
+	}
+
+	/// The legend the server's semantic tokens index into.
+	pub fn semantic_tokens_legend(&self) -> Option<&lsp_types::SemanticTokensLegend> {
+		self.semantic_tokens_options().map(|o| &o.legend)
+	}
+
+	/// The server's semantic token options, however it registered them.
+	fn semantic_tokens_options(&self) -> Option<&lsp_types::SemanticTokensOptions> {
+		use lsp_types::SemanticTokensServerCapabilities;
+
+		match self.try_capabilities()?.semantic_tokens_provider.as_ref()? {
+			SemanticTokensServerCapabilities::SemanticTokensOptions(options) => Some(options),
+			SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+				Some(&options.semantic_tokens_options)
+			}
+		}
+	}
+
+	/// Check if the server supports inlay hints.
+	pub fn supports_inlay_hint(&self) -> bool {
+		self.try_capabilities()
+			.is_some_and(|c| c.inlay_hint_provider.is_some())
+	}
+
+	/// Check if the server takes incremental document changes.
+	pub fn supports_incremental_sync(&self) -> bool {
+		use lsp_types::{TextDocumentSyncCapability, TextDocumentSyncKind};
+
+		self.try_capabilities()
+			.is_some_and(|c| match &c.text_document_sync {
+				Some(TextDocumentSyncCapability::Kind(kind)) => {
+					*kind == TextDocumentSyncKind::INCREMENTAL
+				}
+				Some(TextDocumentSyncCapability::Options(options)) => {
+					options.change == Some(TextDocumentSyncKind::INCREMENTAL)
+				}
+				None => false,
+			})
+	}
+
+	/// Check if the server wants `textDocument/willSave` notifications.
+	pub fn supports_will_save(&self) -> bool {
+		self.sync_options()
+			.is_some_and(|o| o.will_save == Some(true))
+	}
+
+	/// Check if the server supports `textDocument/willSaveWaitUntil`.
+	pub fn supports_will_save_wait_until(&self) -> bool {
+		self.sync_options()
+			.is_some_and(|o| o.will_save_wait_until == Some(true))
+	}
+
+	/// Check if the server wants the document text in `textDocument/didSave`.
+	pub fn save_include_text(&self) -> bool {
+		use lsp_types::TextDocumentSyncSaveOptions;
+
+		self.sync_options().is_some_and(|o| match &o.save {
+			Some(TextDocumentSyncSaveOptions::SaveOptions(save)) => save.include_text == Some(true),
+			Some(TextDocumentSyncSaveOptions::Supported(_)) | None => false,
+		})
+	}
+
+	/// The server's text document sync options, if it gave them in full
+	/// rather than as a bare sync kind.
+	fn sync_options(&self) -> Option<&lsp_types::TextDocumentSyncOptions> {
+		match &self.try_capabilities()?.text_document_sync {
+			Some(lsp_types::TextDocumentSyncCapability::Options(options)) => Some(options),
+			_ => None,
+		}
+	}
+
+	/// Characters that open signature help when typed.
+	pub fn signature_help_trigger_characters(&self) -> &[String] {
+		self.try_capabilities()
+			.and_then(|c| c.signature_help_provider.as_ref())
+			.and_then(|p| p.trigger_characters.as_deref())
+			.unwrap_or_default()
+	}
+
+	/// Characters that refresh signature help already showing when typed.
+	pub fn signature_help_retrigger_characters(&self) -> &[String] {
+		self.try_capabilities()
+			.and_then(|c| c.signature_help_provider.as_ref())
+			.and_then(|p| p.retrigger_characters.as_deref())
+			.unwrap_or_default()
+	}
+
 	/// Get the offset encoding negotiated with the server.
+	///
+	/// This is UTF-16, the LSP default, until the server has initialized.
 	pub fn offset_encoding(&self) -> OffsetEncoding {
-		self.capabilities()
-			.position_encoding
-			.as_ref()
+		self.try_capabilities()
+			.and_then(|c| c.position_encoding.as_ref())
 			.and_then(OffsetEncoding::from_lsp)
 			.unwrap_or_default()
 	}
@@ -195,6 +450,27 @@ impl ClientHandle {
 		self.root_uri.as_ref()
 	}
 
+	/// Get the OS process ID of the server.
+	pub fn pid(&self) -> Option<u32> {
+		self.stats.pid
+	}
+
+	/// Get how long ago the server process was spawned.
+	pub fn uptime(&self) -> Duration {
+		self.stats.started_at.elapsed()
+	}
+
+	/// Get the number of requests sent to the server and not yet answered.
+	pub fn pending_requests(&self) -> usize {
+		self.stats.pending_requests.load(Ordering::Relaxed)
+	}
+
+	/// Get the message of the last request the server failed, or of the
+	/// error that stopped its main loop.
+	pub fn last_error(&self) -> Option<String> {
+		self.stats.last_error.lock().clone()
+	}
+
 	/// Wait for initialization to complete.
 	pub async fn wait_initialized(&self) {
 		if self.is_initialized() {
@@ -333,6 +609,26 @@ impl ClientHandle {
 		)
 	}
 
+	/// Ask the server for edits to make to a document before it is saved.
+	///
+	/// Returns `Ok(None)` if the server doesn't support `willSaveWaitUntil`.
+	pub async fn will_save_wait_until(
+		&self,
+		uri: Uri,
+		reason: lsp_types::TextDocumentSaveReason,
+	) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+		if !self.supports_will_save_wait_until() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::WillSaveWaitUntil>(
+			lsp_types::WillSaveTextDocumentParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				reason,
+			},
+		)
+		.await
+	}
+
 	/// Notify the server that a document was saved.
 	pub fn text_document_did_save(&self, uri: Uri, text: Option<String>) -> Result<()> {
 		self.notify::<lsp_types::notification::DidSaveTextDocument>(
@@ -397,6 +693,68 @@ impl ClientHandle {
 		.await
 	}
 
+	/// Fill in the documentation, detail and additional edits of a
+	/// completion item.
+	///
+	/// Returns the item unchanged if the server doesn't resolve completions.
+	pub async fn completion_resolve(
+		&self,
+		item: lsp_types::CompletionItem,
+	) -> Result<lsp_types::CompletionItem> {
+		if !self.supports_completion_resolve() {
+			return Ok(item);
+		}
+		self.request::<lsp_types::request::ResolveCompletionItem>(item)
+			.await
+	}
+
+	/// Request signature help.
+	///
+	/// Returns `Ok(None)` if the server doesn't support signature help.
+	pub async fn signature_help(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+		context: Option<lsp_types::SignatureHelpContext>,
+	) -> Result<Option<lsp_types::SignatureHelp>> {
+		if !self.supports_signature_help() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::SignatureHelpRequest>(lsp_types::SignatureHelpParams {
+			text_document_position_params: lsp_types::TextDocumentPositionParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				position,
+			},
+			work_done_progress_params: Default::default(),
+			context,
+		})
+		.await
+	}
+
+	/// Request the occurrences of the symbol at a position in its document.
+	///
+	/// Returns `Ok(None)` if the server doesn't support document highlights.
+	pub async fn document_highlight(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<Vec<lsp_types::DocumentHighlight>>> {
+		if !self.supports_document_highlight() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::DocumentHighlightRequest>(
+			lsp_types::DocumentHighlightParams {
+				text_document_position_params: lsp_types::TextDocumentPositionParams {
+					text_document: lsp_types::TextDocumentIdentifier { uri },
+					position,
+				},
+				work_done_progress_params: Default::default(),
+				partial_result_params: Default::default(),
+			},
+		)
+		.await
+	}
+
 	/// Request go to definition.
 	///
 	/// Returns `Ok(None)` if the server doesn't support definition.
@@ -408,7 +766,69 @@ impl ClientHandle {
 		if !self.supports_definition() {
 			return Ok(None);
 		}
-		self.request::<lsp_types::request::GotoDefinition>(lsp_types::GotoDefinitionParams {
+		self.goto::<lsp_types::request::GotoDefinition>(uri, position)
+			.await
+	}
+
+	/// Request go to type definition.
+	///
+	/// Returns `Ok(None)` if the server doesn't support type definitions.
+	pub async fn goto_type_definition(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
+		if !self.supports_type_definition() {
+			return Ok(None);
+		}
+		self.goto::<lsp_types::request::GotoTypeDefinition>(uri, position)
+			.await
+	}
+
+	/// Request go to implementation.
+	///
+	/// Returns `Ok(None)` if the server doesn't support implementations.
+	pub async fn goto_implementation(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
+		if !self.supports_implementation() {
+			return Ok(None);
+		}
+		self.goto::<lsp_types::request::GotoImplementation>(uri, position)
+			.await
+	}
+
+	/// Request go to declaration.
+	///
+	/// Returns `Ok(None)` if the server doesn't support declarations.
+	pub async fn goto_declaration(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<lsp_types::GotoDefinitionResponse>> {
+		if !self.supports_declaration() {
+			return Ok(None);
+		}
+		self.goto::<lsp_types::request::GotoDeclaration>(uri, position)
+			.await
+	}
+
+	/// Sends one of the goto requests, which all take a position and answer
+	/// with locations.
+	async fn goto<R>(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<lsp_types::GotoDefinitionResponse>>
+	where
+		R: Request<
+				Params = lsp_types::GotoDefinitionParams,
+				Result = Option<lsp_types::GotoDefinitionResponse>,
+			>,
+	{
+		self.request::<R>(lsp_types::GotoDefinitionParams {
 			text_document_position_params: lsp_types::TextDocumentPositionParams {
 				text_document: lsp_types::TextDocumentIdentifier { uri },
 				position,
@@ -463,6 +883,135 @@ impl ClientHandle {
 		.await
 	}
 
+	/// Request symbols matching `query` across the workspace.
+	///
+	/// Returns `Ok(None)` if the server doesn't support workspace symbols.
+	pub async fn workspace_symbol(
+		&self,
+		query: String,
+	) -> Result<Option<lsp_types::WorkspaceSymbolResponse>> {
+		if !self.supports_workspace_symbol() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::WorkspaceSymbolRequest>(
+			lsp_types::WorkspaceSymbolParams {
+				query,
+				work_done_progress_params: Default::default(),
+				partial_result_params: Default::default(),
+			},
+		)
+		.await
+	}
+
+	/// Request symbols matching `query` across the workspace, passing the
+	/// batches the server streams ahead of its response to `on_partial`.
+	///
+	/// The response holds the symbols that were not streamed. Dropping the
+	/// future cancels the request. Returns `Ok(None)` if the server doesn't
+	/// support workspace symbols.
+	pub async fn workspace_symbol_streaming(
+		&self,
+		query: String,
+		on_partial: impl Fn(lsp_types::WorkspaceSymbolResponse) + Send + Sync + 'static,
+	) -> Result<Option<lsp_types::WorkspaceSymbolResponse>> {
+		if !self.supports_workspace_symbol() {
+			return Ok(None);
+		}
+		let stream =
+			self.partial_results
+				.register(move |value| match serde_json::from_value(value) {
+					Ok(batch) => on_partial(batch),
+					Err(e) => debug!(target: "lsp", error = %e, "Undecodable workspace symbols"),
+				});
+		self.request::<lsp_types::request::WorkspaceSymbolRequest>(
+			lsp_types::WorkspaceSymbolParams {
+				query,
+				work_done_progress_params: Default::default(),
+				partial_result_params: lsp_types::PartialResultParams {
+					partial_result_token: Some(stream.token()),
+				},
+			},
+		)
+		.await
+	}
+
+	/// Request the inlay hints within `range`.
+	///
+	/// Returns `Ok(None)` if the server doesn't support inlay hints.
+	pub async fn inlay_hints(
+		&self,
+		uri: Uri,
+		range: lsp_types::Range,
+	) -> Result<Option<Vec<lsp_types::InlayHint>>> {
+		if !self.supports_inlay_hint() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::InlayHintRequest>(lsp_types::InlayHintParams {
+			text_document: lsp_types::TextDocumentIdentifier { uri },
+			range,
+			work_done_progress_params: Default::default(),
+		})
+		.await
+	}
+
+	/// Request the folding ranges of a document.
+	///
+	/// Returns `Ok(None)` if the server doesn't support folding ranges.
+	pub async fn folding_ranges(&self, uri: Uri) -> Result<Option<Vec<lsp_types::FoldingRange>>> {
+		if !self.supports_folding_range() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::FoldingRangeRequest>(lsp_types::FoldingRangeParams {
+			text_document: lsp_types::TextDocumentIdentifier { uri },
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+		})
+		.await
+	}
+
+	/// Request the semantic tokens of a whole document.
+	///
+	/// Returns `Ok(None)` if the server doesn't provide semantic tokens.
+	pub async fn semantic_tokens_full(
+		&self,
+		uri: Uri,
+	) -> Result<Option<lsp_types::SemanticTokensResult>> {
+		if !self.supports_semantic_tokens() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::SemanticTokensFullRequest>(
+			lsp_types::SemanticTokensParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				work_done_progress_params: Default::default(),
+				partial_result_params: Default::default(),
+			},
+		)
+		.await
+	}
+
+	/// Request the changes to a document's semantic tokens since the result
+	/// `previous_result_id` names.
+	///
+	/// Returns `Ok(None)` if the server doesn't send semantic token deltas.
+	pub async fn semantic_tokens_full_delta(
+		&self,
+		uri: Uri,
+		previous_result_id: String,
+	) -> Result<Option<lsp_types::SemanticTokensFullDeltaResult>> {
+		if !self.supports_semantic_tokens_delta() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::SemanticTokensFullDeltaRequest>(
+			lsp_types::SemanticTokensDeltaParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				previous_result_id,
+				work_done_progress_params: Default::default(),
+				partial_result_params: Default::default(),
+			},
+		)
+		.await
+	}
+
 	/// Request formatting.
 	///
 	/// Returns `Ok(None)` if the server doesn't support formatting.
@@ -482,6 +1031,29 @@ impl ClientHandle {
 		.await
 	}
 
+	/// Request formatting of a range.
+	///
+	/// Returns `Ok(None)` if the server doesn't support range formatting.
+	pub async fn range_formatting(
+		&self,
+		uri: Uri,
+		range: lsp_types::Range,
+		options: lsp_types::FormattingOptions,
+	) -> Result<Option<Vec<lsp_types::TextEdit>>> {
+		if !self.supports_range_formatting() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::RangeFormatting>(
+			lsp_types::DocumentRangeFormattingParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				range,
+				options,
+				work_done_progress_params: Default::default(),
+			},
+		)
+		.await
+	}
+
 	/// Request code actions.
 	///
 	/// Returns `Ok(None)` if the server doesn't support code actions.
@@ -504,13 +1076,70 @@ impl ClientHandle {
 		.await
 	}
 
+	/// Fill in the edit of a code action listed without one.
+	///
+	/// Returns the action unchanged if the server doesn't support resolving.
+	pub async fn code_action_resolve(
+		&self,
+		action: lsp_types::CodeAction,
+	) -> Result<lsp_types::CodeAction> {
+		if !self.supports_code_action_resolve() {
+			return Ok(action);
+		}
+		self.request::<lsp_types::request::CodeActionResolveRequest>(action)
+			.await
+	}
+
+	/// Run a command on the server.
+	///
+	/// Returns `Ok(None)` if the server doesn't support commands.
+	pub async fn execute_command(
+		&self,
+		command: lsp_types::Command,
+	) -> Result<Option<serde_json::Value>> {
+		if !self.supports_execute_command() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::ExecuteCommand>(lsp_types::ExecuteCommandParams {
+			command: command.command,
+			arguments: command.arguments.unwrap_or_default(),
+			work_done_progress_params: Default::default(),
+		})
+		.await
+	}
+
+	/// Check that the symbol at a position can be renamed.
+	///
+	/// Returns `Ok(None)` if the server doesn't support prepare rename.
+	pub async fn prepare_rename(
+		&self,
+		uri: Uri,
+		position: lsp_types::Position,
+	) -> Result<Option<lsp_types::PrepareRenameResponse>> {
+		if !self.supports_prepare_rename() {
+			return Ok(None);
+		}
+		self.request::<lsp_types::request::PrepareRenameRequest>(
+			lsp_types::TextDocumentPositionParams {
+				text_document: lsp_types::TextDocumentIdentifier { uri },
+				position,
+			},
+		)
+		.await
+	}
+
 	/// Request rename.
+	///
+	/// Returns `Ok(None)` if the server doesn't support rename.
 	pub async fn rename(
 		&self,
 		uri: Uri,
 		position: lsp_types::Position,
 		new_name: String,
 	) -> Result<Option<lsp_types::WorkspaceEdit>> {
+		if !self.supports_rename() {
+			return Ok(None);
+		}
 		self.request::<lsp_types::request::Rename>(lsp_types::RenameParams {
 			text_document_position: lsp_types::TextDocumentPositionParams {
 				text_document: lsp_types::TextDocumentIdentifier { uri },
@@ -524,7 +1153,12 @@ impl ClientHandle {
 
 	/// Send a request to the language server.
 	pub async fn request<R: Request>(&self, params: R::Params) -> Result<R::Result> {
-		self.socket.request::<R>(params).await
+		let _pending = PendingRequest::new(&self.stats.pending_requests);
+		let result = self.socket.request::<R>(params).await;
+		if let Err(e) = &result {
+			self.stats.record_error(e);
+		}
+		result
 	}
 
 	/// Send a notification to the language server.
@@ -541,14 +1175,21 @@ struct ClientState {
 	server_id: LanguageServerId,
 	/// Event handler for LSP events.
 	event_handler: SharedEventHandler,
+	/// Callbacks of requests in flight that take partial results.
+	partial_results: Arc<PartialResults>,
 }
 
 impl ClientState {
 	/// Creates a new client state with the given event handler.
-	fn new(server_id: LanguageServerId, event_handler: SharedEventHandler) -> Self {
+	fn new(
+		server_id: LanguageServerId,
+		event_handler: SharedEventHandler,
+		partial_results: Arc<PartialResults>,
+	) -> Self {
 		Self {
 			server_id,
 			event_handler,
+			partial_results,
 		}
 	}
 }
@@ -564,6 +1205,7 @@ impl ClientState {
 /// * `name` - Human-readable name for the server
 /// * `config` - Server configuration (command, args, root path, etc.)
 /// * `event_handler` - Optional handler for server-to-client events (diagnostics, etc.)
+/// * `log` - Optional log for the server's stderr and protocol trace
 ///
 /// # Returns
 ///
@@ -575,6 +1217,7 @@ pub fn start_server(
 	name: String,
 	config: ServerConfig,
 	event_handler: Option<SharedEventHandler>,
+	log: Option<Arc<ServerLog>>,
 ) -> Result<(ClientHandle, tokio::task::JoinHandle<Result<()>>)> {
 	let root_uri = crate::uri_from_path(&config.root_path);
 
@@ -592,6 +1235,12 @@ pub fn start_server(
 	cmd.process_group(0);
 
 	let mut process = cmd.spawn().map_err(crate::Error::Io)?;
+	let stats = Arc::new(ProcessStats {
+		pid: process.id(),
+		started_at: Instant::now(),
+		pending_requests: AtomicUsize::new(0),
+		last_error: Mutex::new(None),
+	});
 
 	let stdin = process.stdin.take().expect("Failed to open stdin");
 	let stdout = process.stdout.take().expect("Failed to open stdout");
@@ -599,12 +1248,18 @@ pub fn start_server(
 
 	// Log stderr from the LSP server
 	let stderr_id = id;
+	let stderr_log = log.clone();
+	let log_name: Arc<str> = name.as_str().into();
+	let stderr_name = log_name.clone();
 	tokio::spawn(async move {
 		use tokio::io::AsyncBufReadExt;
 		let reader = tokio::io::BufReader::new(stderr);
 		let mut lines = reader.lines();
 		while let Ok(Some(line)) = lines.next_line().await {
 			warn!(server_id = stderr_id.0, stderr = %line, "LSP server stderr");
+			if let Some(log) = &stderr_log {
+				log.push(&stderr_name, LogKind::Stderr, line);
+			}
 		}
 	});
 
@@ -613,7 +1268,8 @@ pub fn start_server(
 
 	// Use provided event handler or a no-op default
 	let handler: SharedEventHandler = event_handler.unwrap_or_else(|| Arc::new(NoOpEventHandler));
-	let state = Arc::new(ClientState::new(id, handler));
+	let partial_results = Arc::new(PartialResults::default());
+	let state = Arc::new(ClientState::new(id, handler, partial_results.clone()));
 
 	// Build the router for handling server->client messages
 	let (main_loop, socket) = MainLoop::new_client(|_socket| {
@@ -632,8 +1288,21 @@ pub fn start_server(
 					.on_diagnostics(state.server_id, params.uri, params.diagnostics);
 				ControlFlow::Continue(())
 			})
-			.notification::<lsp_types::notification::Progress>(|state, params| {
-				state.event_handler.on_progress(state.server_id, params);
+			.notification::<RawProgress>(|state, params| {
+				match serde_json::from_value::<lsp_types::WorkDoneProgress>(params.value.clone()) {
+					Ok(progress) => state.event_handler.on_progress(
+						state.server_id,
+						lsp_types::ProgressParams {
+							token: params.token,
+							value: lsp_types::ProgressParamsValue::WorkDone(progress),
+						},
+					),
+					Err(_) => {
+						if !state.partial_results.deliver(&params.token, params.value) {
+							debug!(target: "lsp", "Partial results for a finished request");
+						}
+					}
+				}
 				ControlFlow::Continue(())
 			})
 			.notification::<lsp_types::notification::LogMessage>(|state, params| {
@@ -689,6 +1358,10 @@ pub fn start_server(
 				// Acknowledge work done progress creation
 				async move { Ok(()) }
 			})
+			.request::<lsp_types::request::InlayHintRefreshRequest, _>(|state, _params| {
+				state.event_handler.on_inlay_hint_refresh(state.server_id);
+				async move { Ok(()) }
+			})
 			// Catch-all for unhandled notifications
 			.unhandled_notification(|_state, notif| {
 				debug!(target: "lsp", method = %notif.method, "Unhandled notification");
@@ -696,6 +1369,10 @@ pub fn start_server(
 			});
 		router
 	});
+	let main_loop = match log {
+		Some(log) => main_loop.with_observer(Arc::new(ProtocolTrace::new(log_name, log))),
+		None => main_loop,
+	};
 
 	let handle = ClientHandle {
 		id,
@@ -705,6 +1382,8 @@ pub fn start_server(
 		root_path: config.root_path,
 		root_uri,
 		initialize_notify,
+		partial_results,
+		stats: stats.clone(),
 	};
 
 	let server_id = id;
@@ -716,6 +1395,7 @@ pub fn start_server(
 		let result = main_loop.run_buffered(stdout, stdin).await;
 		if let Err(ref e) = result {
 			error!(server_id = server_id.0, error = %e, "LSP main loop error");
+			stats.record_error(e);
 		} else {
 			info!(server_id = server_id.0, "LSP main loop exited normally");
 		}
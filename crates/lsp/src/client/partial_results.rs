@@ -0,0 +1,118 @@
+//! Partial results streamed ahead of a response.
+//!
+//! A request carrying a `partialResultToken` may be answered in pieces: the
+//! server sends batches as `$/progress` notifications under that token and
+//! then a final response with the rest. [`PartialResults`] hands out tokens
+//! and routes the batches to the callback registered with each one.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lsp_types::notification::Notification;
+use lsp_types::{NumberOrString, ProgressToken};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Receives the undecoded batches streamed under one token.
+type Sink = Box<dyn Fn(Value) + Send + Sync>;
+
+/// The `$/progress` notification with its value left undecoded.
+///
+/// [`lsp_types::ProgressParams`] only decodes work done progress, while
+/// partial results can be any JSON value.
+pub(super) enum RawProgress {}
+
+impl Notification for RawProgress {
+	type Params = RawProgressParams;
+	const METHOD: &'static str = "$/progress";
+}
+
+/// Parameters of [`RawProgress`].
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct RawProgressParams {
+	/// Token of the work done progress or request the value belongs to.
+	pub token: ProgressToken,
+	/// Work done progress, or a batch of partial results.
+	pub value: Value,
+}
+
+/// Callbacks of the requests in flight that take partial results.
+#[derive(Default)]
+pub(super) struct PartialResults {
+	/// Next token to hand out.
+	next_token: AtomicI32,
+	/// Callback of each registered token.
+	sinks: Mutex<HashMap<ProgressToken, Sink>>,
+}
+
+impl PartialResults {
+	/// Registers `sink` under a fresh token, until the returned guard drops.
+	pub fn register(self: &Arc<Self>, sink: impl Fn(Value) + Send + Sync + 'static) -> Stream {
+		let number = self.next_token.fetch_add(1, Ordering::Relaxed);
+		let token = NumberOrString::String(format!("xeno-partial-{number}"));
+		self.sinks
+			.lock()
+			.unwrap()
+			.insert(token.clone(), Box::new(sink));
+		Stream {
+			token,
+			results: Arc::clone(self),
+		}
+	}
+
+	/// Passes a batch to the callback registered under `token`.
+	///
+	/// Returns `false` if no request in flight uses the token.
+	pub fn deliver(&self, token: &ProgressToken, value: Value) -> bool {
+		let sinks = self.sinks.lock().unwrap();
+		let Some(sink) = sinks.get(token) else {
+			return false;
+		};
+		sink(value);
+		true
+	}
+}
+
+/// A registered partial result token, unregistered on drop.
+pub(super) struct Stream {
+	/// The token to send with the request.
+	token: ProgressToken,
+	/// Registry the token is in.
+	results: Arc<PartialResults>,
+}
+
+impl Stream {
+	/// Returns the token to send with the request.
+	pub fn token(&self) -> ProgressToken {
+		self.token.clone()
+	}
+}
+
+impl Drop for Stream {
+	fn drop(&mut self) {
+		self.results.sinks.lock().unwrap().remove(&self.token);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn batches_reach_their_stream_until_it_drops() {
+		let results = Arc::new(PartialResults::default());
+		let received = Arc::new(Mutex::new(Vec::new()));
+		let sink = Arc::clone(&received);
+		let stream = results.register(move |value| sink.lock().unwrap().push(value));
+		let other = results.register(|_| panic!("wrong stream"));
+		assert_ne!(stream.token(), other.token());
+
+		assert!(results.deliver(&stream.token(), Value::from(1)));
+		assert!(results.deliver(&stream.token(), Value::from(2)));
+		let token = stream.token();
+		drop(stream);
+		assert!(!results.deliver(&token, Value::from(3)));
+		assert_eq!(*received.lock().unwrap(), [Value::from(1), Value::from(2)]);
+	}
+}
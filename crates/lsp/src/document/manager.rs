@@ -8,7 +8,7 @@ use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
-use lsp_types::{Diagnostic, DiagnosticSeverity, ProgressParams, Uri};
+use lsp_types::{Diagnostic, ProgressParams, Uri};
 use parking_lot::RwLock;
 use tokio::sync::mpsc;
 use tracing::debug;
@@ -28,6 +28,8 @@ pub struct DocumentStateManager {
 	event_sender: Option<DiagnosticsEventSender>,
 	/// Global version counter for tracking any diagnostic change.
 	diagnostics_version: AtomicU64,
+	/// Counter bumped whenever a server asks to refresh its inlay hints.
+	inlay_hints_version: AtomicU64,
 	/// Active progress operations keyed by (server_id, token).
 	progress: RwLock<HashMap<(u64, String), ProgressItem>>,
 }
@@ -38,6 +40,7 @@ impl std::fmt::Debug for DocumentStateManager {
 			.field("documents", &self.documents)
 			.field("has_event_sender", &self.event_sender.is_some())
 			.field("diagnostics_version", &self.diagnostics_version)
+			.field("inlay_hints_version", &self.inlay_hints_version)
 			.field("progress_count", &self.progress.read().len())
 			.finish()
 	}
@@ -69,6 +72,7 @@ impl DocumentStateManager {
 			documents: RwLock::new(HashMap::new()),
 			event_sender: None,
 			diagnostics_version: AtomicU64::new(0),
+			inlay_hints_version: AtomicU64::new(0),
 			progress: RwLock::new(HashMap::new()),
 		}
 	}
@@ -82,6 +86,7 @@ impl DocumentStateManager {
 			documents: RwLock::new(HashMap::new()),
 			event_sender: Some(sender),
 			diagnostics_version: AtomicU64::new(0),
+			inlay_hints_version: AtomicU64::new(0),
 			progress: RwLock::new(HashMap::new()),
 		};
 		(manager, receiver)
@@ -95,6 +100,20 @@ impl DocumentStateManager {
 		self.diagnostics_version.load(Ordering::Relaxed)
 	}
 
+	/// Get the current inlay hints version.
+	///
+	/// This counter increments every time a server sends
+	/// `workspace/inlayHint/refresh`, meaning hints shown for any document
+	/// may be out of date.
+	pub fn inlay_hints_version(&self) -> u64 {
+		self.inlay_hints_version.load(Ordering::Relaxed)
+	}
+
+	/// Records that a server asked for its inlay hints to be re-requested.
+	pub fn refresh_inlay_hints(&self) {
+		self.inlay_hints_version.fetch_add(1, Ordering::Relaxed);
+	}
+
 	/// Get document state by file path.
 	pub fn get_by_path(&self, path: &Path) -> Option<Uri> {
 		let uri = crate::uri_from_path(path)?;
@@ -136,27 +155,26 @@ impl DocumentStateManager {
 		self.documents.write().remove(&key);
 	}
 
-	/// Updates diagnostics for a document.
+	/// Updates diagnostics for a document, as published by `server_id`.
 	///
-	/// Creates document state on-demand if the document isn't registered,
-	/// enabling project-wide diagnostics from LSP servers.
-	pub fn update_diagnostics(&self, uri: &Uri, diagnostics: Vec<Diagnostic>) {
-		let error_count = diagnostics
-			.iter()
-			.filter(|d| d.severity == Some(DiagnosticSeverity::ERROR))
-			.count();
-		let warning_count = diagnostics
-			.iter()
-			.filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
-			.count();
-
+	/// The diagnostics of other servers for the document are kept. Creates
+	/// document state on-demand if the document isn't registered, enabling
+	/// project-wide diagnostics from LSP servers.
+	pub fn update_diagnostics(
+		&self,
+		server_id: LanguageServerId,
+		uri: &Uri,
+		diagnostics: Vec<Diagnostic>,
+	) {
 		let uri_key = self.uri_key(uri);
 
 		// Try read lock first for the common case
 		{
 			let docs = self.documents.read();
 			if let Some(state) = docs.get(&uri_key) {
-				state.set_diagnostics(diagnostics);
+				state.set_diagnostics(server_id, diagnostics);
+				let (error_count, warning_count) = (state.error_count(), state.warning_count());
+				drop(docs);
 				self.diagnostics_version.fetch_add(1, Ordering::Relaxed);
 				self.send_diagnostics_event(uri, error_count, warning_count);
 				return;
@@ -164,16 +182,14 @@ impl DocumentStateManager {
 		}
 
 		// Document not registered - create on demand
-		{
+		let (error_count, warning_count) = {
 			let mut docs = self.documents.write();
-			if let Some(state) = docs.get(&uri_key) {
-				state.set_diagnostics(diagnostics);
-			} else {
-				let state = DocumentState::from_uri(self.normalize_uri(uri));
-				state.set_diagnostics(diagnostics);
-				docs.insert(uri_key, state);
-			}
-		}
+			let state = docs
+				.entry(uri_key)
+				.or_insert_with(|| DocumentState::from_uri(self.normalize_uri(uri)));
+			state.set_diagnostics(server_id, diagnostics);
+			(state.error_count(), state.warning_count())
+		};
 
 		self.diagnostics_version.fetch_add(1, Ordering::Relaxed);
 		self.send_diagnostics_event(uri, error_count, warning_count);
@@ -191,43 +207,61 @@ impl DocumentStateManager {
 		}
 	}
 
-	/// Get diagnostics for a document.
+	/// Get the diagnostics of every server for a document, merged.
 	pub fn get_diagnostics(&self, uri: &Uri) -> Vec<Diagnostic> {
 		let key = self.uri_key(uri);
 		let docs = self.documents.read();
 		docs.get(&key).map(|s| s.diagnostics()).unwrap_or_default()
 	}
 
-	/// Increment version for a document and return the new version.
-	pub fn increment_version(&self, uri: &Uri) -> Option<i32> {
+	/// Get the diagnostics for a document along with the server that
+	/// published them.
+	pub fn get_diagnostics_by_server(&self, uri: &Uri) -> Vec<(LanguageServerId, Vec<Diagnostic>)> {
 		let key = self.uri_key(uri);
 		let docs = self.documents.read();
-		docs.get(&key).map(|s| s.increment_version())
+		docs.get(&key)
+			.map(|s| s.diagnostics_by_server())
+			.unwrap_or_default()
 	}
 
-	/// Get version for a document.
-	pub fn get_version(&self, uri: &Uri) -> Option<i32> {
+	/// Increment the version of a document sent to a server and return the
+	/// new version.
+	pub fn increment_version(&self, uri: &Uri, server_id: LanguageServerId) -> Option<i32> {
 		let key = self.uri_key(uri);
 		let docs = self.documents.read();
-		docs.get(&key).map(|s| s.version())
+		docs.get(&key).and_then(|s| s.increment_version(server_id))
 	}
 
-	/// Mark a document as opened with a language server.
-	pub fn set_opened(&self, uri: &Uri, opened: bool) {
+	/// Get the version of a document last sent to a server.
+	pub fn get_version(&self, uri: &Uri, server_id: LanguageServerId) -> Option<i32> {
+		let key = self.uri_key(uri);
+		let docs = self.documents.read();
+		docs.get(&key).and_then(|s| s.version(server_id))
+	}
+
+	/// Mark a document as opened or closed with a language server.
+	pub fn set_opened(&self, uri: &Uri, server_id: LanguageServerId, opened: bool) {
 		let key = self.uri_key(uri);
 		let docs = self.documents.read();
 		if let Some(state) = docs.get(&key) {
-			state.set_opened(opened);
+			state.set_opened(server_id, opened);
 		}
 	}
 
-	/// Check if a document is opened with a language server.
+	/// Check if a document is opened with any language server.
 	pub fn is_opened(&self, uri: &Uri) -> bool {
 		let key = self.uri_key(uri);
 		let docs = self.documents.read();
 		docs.get(&key).map(|s| s.is_opened()).unwrap_or(false)
 	}
 
+	/// Check if a document is opened with a language server.
+	pub fn is_opened_with(&self, uri: &Uri, server_id: LanguageServerId) -> bool {
+		let key = self.uri_key(uri);
+		let docs = self.documents.read();
+		docs.get(&key).is_some_and(|s| s.is_opened_with(server_id))
+	}
+
 	/// Get all documents with errors.
 	pub fn documents_with_errors(&self) -> Vec<Uri> {
 		self.documents
@@ -238,6 +272,18 @@ impl DocumentStateManager {
 			.collect()
 	}
 
+	/// Get the diagnostics of every document that has any, including
+	/// documents not opened in the editor, along with the server that
+	/// published them.
+	pub fn all_diagnostics(&self) -> Vec<(Uri, Vec<(LanguageServerId, Vec<Diagnostic>)>)> {
+		self.documents
+			.read()
+			.values()
+			.map(|s| (s.uri().clone(), s.diagnostics_by_server()))
+			.filter(|(_, diagnostics)| !diagnostics.is_empty())
+			.collect()
+	}
+
 	/// Get total error count across all documents.
 	pub fn total_error_count(&self) -> usize {
 		self.documents
@@ -333,11 +379,52 @@ impl DocumentStateManager {
 		})
 	}
 
+	/// Get the active progress item with the most work left, if any.
+	///
+	/// Items without a percentage count as just started; ties go to the
+	/// longest running item.
+	pub fn busiest_progress(&self) -> Option<ProgressItem> {
+		self.progress
+			.read()
+			.values()
+			.min_by_key(|p| (p.percentage.unwrap_or(0), p.started_at))
+			.cloned()
+	}
+
 	/// Check if there are any active progress operations.
 	pub fn has_progress(&self) -> bool {
 		!self.progress.read().is_empty()
 	}
 
+	/// Forget everything about a server that is gone, such as one that
+	/// crashed or was stopped: which documents it had open, its
+	/// diagnostics and its progress.
+	///
+	/// Documents that lose diagnostics get a diagnostics event, so their
+	/// buffers are redrawn without them.
+	pub fn forget_server(&self, server_id: LanguageServerId) {
+		let cleared: Vec<_> = self
+			.documents
+			.read()
+			.values()
+			.filter(|state| state.remove_server(server_id))
+			.map(|state| {
+				(
+					state.uri().clone(),
+					state.error_count(),
+					state.warning_count(),
+				)
+			})
+			.collect();
+		if !cleared.is_empty() {
+			self.diagnostics_version.fetch_add(1, Ordering::Relaxed);
+		}
+		for (uri, error_count, warning_count) in cleared {
+			self.send_diagnostics_event(&uri, error_count, warning_count);
+		}
+		self.clear_server_progress(server_id);
+	}
+
 	/// Clear all progress for a specific server (e.g., when server crashes).
 	pub fn clear_server_progress(&self, server_id: LanguageServerId) {
 		self.progress
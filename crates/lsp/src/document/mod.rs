@@ -36,6 +36,7 @@ mod tests {
 	use lsp_types::{Diagnostic, DiagnosticSeverity, Range};
 
 	use super::*;
+	use crate::client::LanguageServerId;
 
 	fn make_diagnostic(severity: DiagnosticSeverity, message: &str) -> Diagnostic {
 		Diagnostic {
@@ -55,11 +56,23 @@ mod tests {
 	fn test_document_state_version() {
 		let uri = "file:///test.rs".parse().unwrap();
 		let state = DocumentState::from_uri(uri);
-
-		assert_eq!(state.version(), 0);
-		assert_eq!(state.increment_version(), 1);
-		assert_eq!(state.increment_version(), 2);
-		assert_eq!(state.version(), 2);
+		let (first, second) = (LanguageServerId(1), LanguageServerId(2));
+
+		assert_eq!(state.version(first), None);
+		assert_eq!(state.increment_version(first), None);
+		state.set_opened(first, true);
+		assert_eq!(state.version(first), Some(0));
+		assert_eq!(state.increment_version(first), Some(1));
+		assert_eq!(state.increment_version(first), Some(2));
+
+		// A server opened later starts over from its own version.
+		state.set_opened(second, true);
+		assert_eq!(state.increment_version(second), Some(1));
+		assert_eq!(state.version(first), Some(2));
+
+		state.set_opened(first, false);
+		assert!(!state.is_opened_with(first));
+		assert!(state.is_opened());
 	}
 
 	#[test]
@@ -75,7 +88,7 @@ mod tests {
 			make_diagnostic(DiagnosticSeverity::ERROR, "error 2"),
 			make_diagnostic(DiagnosticSeverity::WARNING, "warning 1"),
 		];
-		state.set_diagnostics(diagnostics);
+		state.set_diagnostics(LanguageServerId(1), diagnostics);
 
 		assert!(state.has_errors());
 		assert!(state.has_warnings());
@@ -93,10 +106,27 @@ mod tests {
 		assert!(manager.contains(&uri));
 
 		let diagnostics = vec![make_diagnostic(DiagnosticSeverity::ERROR, "test error")];
-		manager.update_diagnostics(&uri, diagnostics);
+		manager.update_diagnostics(LanguageServerId(1), &uri, diagnostics);
 		assert_eq!(manager.get_diagnostics(&uri).len(), 1);
 		assert_eq!(manager.total_error_count(), 1);
 
+		// Diagnostics of another server merge with the first's, and each
+		// server replaces only its own.
+		let lints = vec![make_diagnostic(DiagnosticSeverity::WARNING, "lint")];
+		manager.update_diagnostics(LanguageServerId(2), &uri, lints);
+		manager.update_diagnostics(LanguageServerId(1), &uri, Vec::new());
+		let by_server = manager.get_diagnostics_by_server(&uri);
+		assert_eq!(by_server.len(), 1);
+		assert_eq!(by_server[0].0, LanguageServerId(2));
+		assert_eq!(manager.total_error_count(), 0);
+		assert_eq!(manager.total_warning_count(), 1);
+
+		// A server that is gone leaves neither diagnostics nor open state.
+		manager.set_opened(&uri, LanguageServerId(2), true);
+		manager.forget_server(LanguageServerId(2));
+		assert!(manager.get_diagnostics(&uri).is_empty());
+		assert!(!manager.is_opened(&uri));
+
 		manager.unregister(&uri);
 		assert!(!manager.contains(&uri));
 	}
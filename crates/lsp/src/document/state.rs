@@ -1,28 +1,31 @@
 //! Document state for LSP tracking.
 //!
 //! Tracks version numbers, diagnostics, and other LSP-related metadata
-//! for individual documents.
+//! for individual documents. A document may be open with several language
+//! servers at once, so versions and diagnostics are kept per server.
 
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::atomic::{AtomicI32, Ordering};
 
 use lsp_types::{Diagnostic, DiagnosticSeverity, Uri};
 use parking_lot::RwLock;
 
+use crate::client::LanguageServerId;
+
 /// LSP state for a single document.
 ///
-/// Tracks version number for incremental sync, diagnostics, and other
+/// Tracks version numbers for incremental sync, diagnostics, and other
 /// LSP-related metadata.
 #[derive(Debug)]
 pub struct DocumentState {
 	/// Document URI (derived from file path).
 	uri: Uri,
-	/// Document version for LSP sync. Incremented on each change.
-	version: AtomicI32,
-	/// Whether the document has been opened with the language server.
-	opened: RwLock<bool>,
-	/// Current diagnostics from the language server.
-	diagnostics: RwLock<Vec<Diagnostic>>,
+	/// Servers the document has been opened with, and the version each was
+	/// last sent. Incremented on each change.
+	versions: RwLock<HashMap<LanguageServerId, i32>>,
+	/// Current diagnostics of each server that has any, in the order the
+	/// servers published them.
+	diagnostics: RwLock<Vec<(LanguageServerId, Vec<Diagnostic>)>>,
 	/// Language ID for the document (e.g., "rust", "python").
 	language_id: RwLock<Option<String>>,
 }
@@ -33,21 +36,14 @@ impl DocumentState {
 	/// Returns `None` if the path cannot be converted to a URL.
 	pub fn new(path: &Path) -> Option<Self> {
 		let uri = crate::uri_from_path(path)?;
-		Some(Self {
-			uri,
-			version: AtomicI32::new(0),
-			opened: RwLock::new(false),
-			diagnostics: RwLock::new(Vec::new()),
-			language_id: RwLock::new(None),
-		})
+		Some(Self::from_uri(uri))
 	}
 
 	/// Create a document state from a URI directly.
 	pub fn from_uri(uri: Uri) -> Self {
 		Self {
 			uri,
-			version: AtomicI32::new(0),
-			opened: RwLock::new(false),
+			versions: RwLock::new(HashMap::new()),
 			diagnostics: RwLock::new(Vec::new()),
 			language_id: RwLock::new(None),
 		}
@@ -58,26 +54,57 @@ impl DocumentState {
 		&self.uri
 	}
 
-	/// Get the current document version.
-	pub fn version(&self) -> i32 {
-		self.version.load(Ordering::Relaxed)
+	/// Get the version last sent to a server, or `None` if the document is
+	/// not open with it.
+	pub fn version(&self, server_id: LanguageServerId) -> Option<i32> {
+		self.versions.read().get(&server_id).copied()
 	}
 
-	/// Increment the version and return the new value.
+	/// Increment the version sent to a server and return the new value, or
+	/// `None` if the document is not open with it.
 	///
 	/// Should be called whenever the document content changes.
-	pub fn increment_version(&self) -> i32 {
-		self.version.fetch_add(1, Ordering::Relaxed) + 1
+	pub fn increment_version(&self, server_id: LanguageServerId) -> Option<i32> {
+		self.versions.write().get_mut(&server_id).map(|version| {
+			*version += 1;
+			*version
+		})
 	}
 
-	/// Check if the document has been opened with a language server.
+	/// Check if the document has been opened with any language server.
 	pub fn is_opened(&self) -> bool {
-		*self.opened.read()
+		!self.versions.read().is_empty()
+	}
+
+	/// Check if the document has been opened with a language server.
+	pub fn is_opened_with(&self, server_id: LanguageServerId) -> bool {
+		self.versions.read().contains_key(&server_id)
+	}
+
+	/// Mark the document as opened with a language server, at version 0,
+	/// or as closed with it.
+	pub fn set_opened(&self, server_id: LanguageServerId, opened: bool) {
+		let mut versions = self.versions.write();
+		if opened {
+			versions.entry(server_id).or_insert(0);
+		} else {
+			versions.remove(&server_id);
+		}
+	}
+
+	/// Forget a server that is gone: mark the document closed with it and
+	/// drop its diagnostics. Returns whether it had any diagnostics.
+	pub fn remove_server(&self, server_id: LanguageServerId) -> bool {
+		self.versions.write().remove(&server_id);
+		let mut diagnostics = self.diagnostics.write();
+		let before = diagnostics.len();
+		diagnostics.retain(|(id, _)| *id != server_id);
+		diagnostics.len() != before
 	}
 
-	/// Mark the document as opened with a language server.
-	pub fn set_opened(&self, opened: bool) {
-		*self.opened.write() = opened;
+	/// Get the servers the document has been opened with.
+	pub fn opened_servers(&self) -> Vec<LanguageServerId> {
+		self.versions.read().keys().copied().collect()
 	}
 
 	/// Get the language ID.
@@ -90,14 +117,33 @@ impl DocumentState {
 		*self.language_id.write() = Some(lang.into());
 	}
 
-	/// Get all diagnostics for this document.
+	/// Get the diagnostics of every server for this document, merged in
+	/// the order the servers published them.
 	pub fn diagnostics(&self) -> Vec<Diagnostic> {
+		self.diagnostics
+			.read()
+			.iter()
+			.flat_map(|(_, diagnostics)| diagnostics.iter().cloned())
+			.collect()
+	}
+
+	/// Get the diagnostics for this document along with the server that
+	/// published them.
+	pub fn diagnostics_by_server(&self) -> Vec<(LanguageServerId, Vec<Diagnostic>)> {
 		self.diagnostics.read().clone()
 	}
 
-	/// Set diagnostics for this document.
-	pub fn set_diagnostics(&self, diagnostics: Vec<Diagnostic>) {
-		*self.diagnostics.write() = diagnostics;
+	/// Set the diagnostics a server published for this document.
+	pub fn set_diagnostics(&self, server_id: LanguageServerId, diagnostics: Vec<Diagnostic>) {
+		let mut all = self.diagnostics.write();
+		match all.iter().position(|(id, _)| *id == server_id) {
+			Some(index) if diagnostics.is_empty() => {
+				all.remove(index);
+			}
+			Some(index) => all[index].1 = diagnostics,
+			None if diagnostics.is_empty() => {}
+			None => all.push((server_id, diagnostics)),
+		}
 	}
 
 	/// Clear all diagnostics.
@@ -110,6 +156,7 @@ impl DocumentState {
 		self.diagnostics
 			.read()
 			.iter()
+			.flat_map(|(_, diagnostics)| diagnostics)
 			.filter(|d| d.severity == Some(severity))
 			.cloned()
 			.collect()
@@ -120,6 +167,7 @@ impl DocumentState {
 		self.diagnostics
 			.read()
 			.iter()
+			.flat_map(|(_, diagnostics)| diagnostics)
 			.filter(|d| d.severity == Some(DiagnosticSeverity::ERROR))
 			.count()
 	}
@@ -129,6 +177,7 @@ impl DocumentState {
 		self.diagnostics
 			.read()
 			.iter()
+			.flat_map(|(_, diagnostics)| diagnostics)
 			.filter(|d| d.severity == Some(DiagnosticSeverity::WARNING))
 			.count()
 	}
@@ -95,8 +95,9 @@ pub use omni_trait::{LanguageClient, LanguageServer};
 pub mod client;
 #[cfg(feature = "client")]
 pub use client::{
-	ClientHandle, LanguageServerId, LogLevel, LspEventHandler, NoOpEventHandler, OffsetEncoding,
-	ServerConfig, SharedEventHandler, start_server,
+	ClientHandle, LOG_CAPACITY, LanguageServerId, LogEntry, LogKind, LogLevel, LspEventHandler,
+	NoOpEventHandler, OffsetEncoding, ServerConfig, ServerLog, SharedEventHandler, TraceLevel,
+	start_server,
 };
 
 #[cfg(feature = "position")]
@@ -104,7 +105,8 @@ pub use client::{
 pub mod position;
 #[cfg(feature = "position")]
 pub use position::{
-	char_range_to_lsp_range, char_to_lsp_position, lsp_position_to_char, lsp_range_to_char_range,
+	char_range_to_lsp_range, char_to_lsp_column, char_to_lsp_position, lsp_column_to_char,
+	lsp_position_to_char, lsp_range_to_char_range, str_offset_to_char,
 };
 
 #[cfg(feature = "position")]
@@ -118,7 +120,10 @@ pub use changes::compute_lsp_changes;
 #[cfg_attr(docsrs, doc(cfg(feature = "client")))]
 pub mod registry;
 #[cfg(feature = "client")]
-pub use registry::{LanguageServerConfig, Registry};
+pub use registry::{
+	LanguageServerConfig, Registry, ServerCrash, ServerHealth, ServerInfo, ServerState,
+	ServerStatus, StoppedServer,
+};
 
 #[cfg(feature = "client")]
 #[cfg_attr(docsrs, doc(cfg(feature = "client")))]
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::future::{Future, poll_fn};
 use std::ops::ControlFlow;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll, ready};
 
 use futures::channel::{mpsc, oneshot};
@@ -12,12 +13,13 @@ use futures::stream::FuturesUnordered;
 use futures::{
 	AsyncBufRead, AsyncRead, AsyncWrite, FutureExt, SinkExt, StreamExt, pin_mut, select_biased,
 };
+use lsp_types::notification::Notification;
 use pin_project_lite::pin_project;
 use serde_json::Value as JsonValue;
 
 use crate::message::Message;
 use crate::socket::{ClientSocket, MainLoopEvent, PeerSocket, ServerSocket};
-use crate::types::{AnyResponse, RequestId, ResponseError};
+use crate::types::{AnyNotification, AnyResponse, RequestId, ResponseError};
 use crate::{LspService, Result};
 
 /// Macro to define getter methods for accessing inner service fields.
@@ -46,6 +48,25 @@ macro_rules! define_getters {
     };
 }
 
+/// Which way a message crossed the transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum MessageDirection {
+	/// Read from the peer.
+	Incoming,
+	/// Written to the peer.
+	Outgoing,
+}
+
+/// Observer of every message a main loop reads or writes, for protocol
+/// traces.
+///
+/// Called on the main loop's task, so it must return quickly and never
+/// wait on anything.
+pub(crate) trait MessageObserver: Send + Sync {
+	/// Called with each message as it is read, or before it is written.
+	fn observe(&self, direction: MessageDirection, message: &Message);
+}
+
 /// Service main loop driver for either Language Servers or Language Clients.
 pub struct MainLoop<S: LspService> {
 	/// The wrapped LSP service.
@@ -58,6 +79,8 @@ pub struct MainLoop<S: LspService> {
 	outgoing: HashMap<RequestId, oneshot::Sender<AnyResponse>>,
 	/// Concurrent request handlers in flight.
 	tasks: FuturesUnordered<RequestFuture<S::Future>>,
+	/// Observer of the messages read and written, if any.
+	observer: Option<Arc<dyn MessageObserver>>,
 }
 
 define_getters!(impl[S: LspService] MainLoop<S>, service: S);
@@ -91,10 +114,22 @@ where
 			outgoing_id: 0,
 			outgoing: HashMap::new(),
 			tasks: FuturesUnordered::new(),
+			observer: None,
 		};
 		(this, socket)
 	}
 
+	/// Show every message read or written to `observer`.
+	#[must_use]
+	#[cfg_attr(
+		not(feature = "client"),
+		allow(dead_code, reason = "only language clients trace messages")
+	)]
+	pub(crate) fn with_observer(mut self, observer: Arc<dyn MessageObserver>) -> Self {
+		self.observer = Some(observer);
+		self
+	}
+
 	/// Drive the service main loop to provide the service.
 	///
 	/// Shortcut to [`MainLoop::run`] that accept an `impl AsyncRead` and implicit wrap it in a
@@ -134,7 +169,11 @@ where
 				resp = self.tasks.select_next_some() => ControlFlow::Continue(Some(Message::Response(resp))),
 				event = self.rx.next() => self.dispatch_event(event.expect("Sender is alive")),
 				msg = incoming.next() => {
-					let dispatch_fut = self.dispatch_message(msg.expect("Never ends")?).fuse();
+					let msg = msg.expect("Never ends")?;
+					if let Some(observer) = &self.observer {
+						observer.observe(MessageDirection::Incoming, &msg);
+					}
+					let dispatch_fut = self.dispatch_message(msg).fuse();
 					pin_mut!(dispatch_fut);
 					// NB. Concurrently wait for `poll_ready`, and write out the last message.
 					// If the service is waiting for client's response of the last request, while
@@ -154,6 +193,9 @@ where
 				ControlFlow::Continue(None) => continue,
 				ControlFlow::Break(ret) => break ret,
 			};
+			if let Some(observer) = &self.observer {
+				observer.observe(MessageDirection::Outgoing, &msg);
+			}
 			// Flush the previous one and load a new message to send.
 			outgoing.feed(msg).await?;
 			flush_fut = outgoing.flush().fuse();
@@ -206,6 +248,25 @@ where
 				self.service.emit(event)?;
 				ControlFlow::Continue(None)
 			}
+			MainLoopEvent::RequestDropped => {
+				// Each drop reports once, so cancelling one request per event
+				// keeps up even if several were dropped at the same time.
+				let dropped = self
+					.outgoing
+					.iter()
+					.find(|(_, resp_tx)| resp_tx.is_canceled())
+					.map(|(id, _)| id.clone());
+				let Some(id) = dropped else {
+					return ControlFlow::Continue(None);
+				};
+				self.outgoing.remove(&id);
+				let notif = AnyNotification {
+					method: <lsp_types::notification::Cancel as Notification>::METHOD.into(),
+					params: serde_json::to_value(lsp_types::CancelParams { id })
+						.expect("Failed to serialize"),
+				};
+				ControlFlow::Continue(Some(Message::Notification(notif)))
+			}
 		}
 	}
 }
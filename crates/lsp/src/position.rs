@@ -13,6 +13,11 @@
 //! Since Rope uses Unicode codepoints internally, UTF-32 is a 1:1 mapping.
 //! UTF-16 requires special handling for characters outside the BMP (emoji, etc.)
 //! which are represented as surrogate pairs (2 code units).
+//!
+//! Every conversion between rope indices and LSP positions goes through this
+//! module, with the encoding negotiated with the server involved. Positions
+//! from servers are clamped into the text instead of rejected, so a server
+//! that is behind on edits cannot make the editor fail or panic.
 
 use lsp_types::{Position, Range};
 use ropey::{Rope, RopeSlice};
@@ -21,18 +26,17 @@ use crate::client::OffsetEncoding;
 
 /// Convert an LSP Position to a character index in the rope.
 ///
-/// Returns `None` if the position is out of bounds.
-pub fn lsp_position_to_char(text: &Rope, pos: Position, encoding: OffsetEncoding) -> Option<usize> {
+/// Positions from servers are clamped rather than rejected: a line past the
+/// end of the text maps to the end of the text, a column past the end of its
+/// line to the end of the line, and a column inside a character to the end of
+/// that character.
+pub fn lsp_position_to_char(text: &Rope, pos: Position, encoding: OffsetEncoding) -> usize {
 	let line = pos.line as usize;
 	if line >= text.len_lines() {
-		return None;
+		return text.len_chars();
 	}
 
-	let line_start = text.line_to_char(line);
-	let line_text = text.line(line);
-	let char_offset = lsp_col_to_char_offset(line_text, pos.character, encoding)?;
-
-	Some(line_start + char_offset)
+	text.line_to_char(line) + lsp_column_to_char(text.line(line), pos.character, encoding)
 }
 
 /// Convert a character index in the rope to an LSP Position.
@@ -49,9 +53,7 @@ pub fn char_to_lsp_position(
 
 	let line = text.char_to_line(char_idx);
 	let line_start = text.line_to_char(line);
-	let char_offset = char_idx - line_start;
-	let line_text = text.line(line);
-	let lsp_col = char_offset_to_lsp_col(line_text, char_offset, encoding);
+	let lsp_col = char_to_lsp_column(text.line(line), char_idx - line_start, encoding);
 
 	Some(Position {
 		line: line as u32,
@@ -60,14 +62,17 @@ pub fn char_to_lsp_position(
 }
 
 /// Convert an LSP Range to a character range (start, end).
+///
+/// Both ends are clamped like [`lsp_position_to_char`], and a range ending
+/// before it starts is collapsed to its start.
 pub fn lsp_range_to_char_range(
 	text: &Rope,
 	range: Range,
 	encoding: OffsetEncoding,
-) -> Option<(usize, usize)> {
-	let start = lsp_position_to_char(text, range.start, encoding)?;
-	let end = lsp_position_to_char(text, range.end, encoding)?;
-	Some((start, end))
+) -> (usize, usize) {
+	let start = lsp_position_to_char(text, range.start, encoding);
+	let end = lsp_position_to_char(text, range.end, encoding);
+	(start, end.max(start))
 }
 
 /// Convert a character range to an LSP Range.
@@ -85,79 +90,64 @@ pub fn char_range_to_lsp_range(
 	})
 }
 
-/// Convert an LSP character column to a rope character offset within a line.
-fn lsp_col_to_char_offset(
-	line: RopeSlice,
-	lsp_col: u32,
-	encoding: OffsetEncoding,
-) -> Option<usize> {
-	match encoding {
-		OffsetEncoding::Utf32 => {
-			// UTF-32: LSP col == char offset
-			let col = lsp_col as usize;
-			// Clamp to line length (excluding newline if present)
-			let line_len = line_char_len_without_newline(line);
-			Some(col.min(line_len))
-		}
-		OffsetEncoding::Utf8 => {
-			// UTF-8: LSP col is byte offset
-			let target_bytes = lsp_col as usize;
-			let mut byte_count = 0;
-			for (char_idx, ch) in line.chars().enumerate() {
-				if byte_count >= target_bytes {
-					return Some(char_idx);
-				}
-				byte_count += ch.len_utf8();
-			}
-			// Past end of line, clamp to line length
-			Some(line_char_len_without_newline(line))
-		}
-		OffsetEncoding::Utf16 => {
-			// UTF-16: LSP col is number of UTF-16 code units
-			let target_units = lsp_col as usize;
-			let mut unit_count = 0;
-			for (char_idx, ch) in line.chars().enumerate() {
-				if unit_count >= target_units {
-					return Some(char_idx);
-				}
-				unit_count += ch.len_utf16();
-			}
-			// Past end of line, clamp to line length
-			Some(line_char_len_without_newline(line))
+/// Convert an LSP character column to a character offset within `line`.
+///
+/// For text that is not in a rope, such as a line read from a file that is
+/// not open. The column is clamped like in [`lsp_position_to_char`].
+pub fn lsp_column_to_char(line: RopeSlice, lsp_col: u32, encoding: OffsetEncoding) -> usize {
+	let target = lsp_col as usize;
+	let line_len = line_char_len_without_newline(line);
+	let width = match encoding {
+		// UTF-32: LSP col == char offset
+		OffsetEncoding::Utf32 => return target.min(line_len),
+		// UTF-8: LSP col is byte offset
+		OffsetEncoding::Utf8 => char::len_utf8,
+		// UTF-16: LSP col is number of UTF-16 code units
+		OffsetEncoding::Utf16 => char::len_utf16,
+	};
+	let mut units = 0;
+	for (char_idx, ch) in line.chars().take(line_len).enumerate() {
+		if units >= target {
+			return char_idx;
 		}
+		units += width(ch);
 	}
+	// Past end of line, clamp to line length
+	line_len
 }
 
-/// Convert a rope character offset within a line to an LSP character column.
-fn char_offset_to_lsp_col(line: RopeSlice, char_offset: usize, encoding: OffsetEncoding) -> u32 {
-	match encoding {
-		OffsetEncoding::Utf32 => {
-			// UTF-32: char offset == LSP col
-			char_offset as u32
-		}
-		OffsetEncoding::Utf8 => {
-			// UTF-8: count bytes up to char_offset
-			let mut byte_count = 0;
-			for (idx, ch) in line.chars().enumerate() {
-				if idx >= char_offset {
-					break;
-				}
-				byte_count += ch.len_utf8();
-			}
-			byte_count as u32
-		}
-		OffsetEncoding::Utf16 => {
-			// UTF-16: count code units up to char_offset
-			let mut unit_count = 0;
-			for (idx, ch) in line.chars().enumerate() {
-				if idx >= char_offset {
-					break;
-				}
-				unit_count += ch.len_utf16();
-			}
-			unit_count as u32
+/// Convert a character offset within `line` to an LSP character column.
+pub fn char_to_lsp_column(line: RopeSlice, char_offset: usize, encoding: OffsetEncoding) -> u32 {
+	let width = match encoding {
+		// UTF-32: char offset == LSP col
+		OffsetEncoding::Utf32 => return char_offset as u32,
+		OffsetEncoding::Utf8 => char::len_utf8,
+		OffsetEncoding::Utf16 => char::len_utf16,
+	};
+	line.chars().take(char_offset).map(width).sum::<usize>() as u32
+}
+
+/// Convert an offset counted in `encoding` units into `text` to a character
+/// offset.
+///
+/// For strings a server sends outside any document, such as a signature
+/// label. Unlike a column the offset is not clamped: one past the end of
+/// `text` or inside a character gives `None`.
+pub fn str_offset_to_char(text: &str, offset: u32, encoding: OffsetEncoding) -> Option<usize> {
+	let width: fn(char) -> usize = match encoding {
+		OffsetEncoding::Utf8 => char::len_utf8,
+		OffsetEncoding::Utf16 => char::len_utf16,
+		OffsetEncoding::Utf32 => |_| 1,
+	};
+	let target = offset as usize;
+	let mut units = 0;
+	for (char_idx, ch) in text.chars().enumerate() {
+		if units >= target {
+			return (units == target).then_some(char_idx);
 		}
+		units += width(ch);
 	}
+	(units == target).then(|| text.chars().count())
 }
 
 /// Get the character length of a line, excluding the trailing newline if present.
@@ -184,7 +174,7 @@ mod tests {
 			line: 0,
 			character: 3,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 3);
 		assert_eq!(
 			char_to_lsp_position(&text, char_idx, encoding).unwrap(),
@@ -196,7 +186,7 @@ mod tests {
 			line: 1,
 			character: 2,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 8); // "hello\n" = 6 chars, + 2 = 8
 		assert_eq!(
 			char_to_lsp_position(&text, char_idx, encoding).unwrap(),
@@ -215,7 +205,7 @@ mod tests {
 			line: 0,
 			character: 1,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 1);
 
 		// Position after emoji (in UTF-16, this is character 3 because emoji takes 2 units)
@@ -223,7 +213,7 @@ mod tests {
 			line: 0,
 			character: 3,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 2); // In rope chars: a=0, 😀=1, b=2
 
 		// Convert back: char index 2 should give UTF-16 column 3
@@ -242,7 +232,7 @@ mod tests {
 			line: 0,
 			character: 3,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 3); // c=1byte, a=1byte, f=1byte -> 3 bytes = 3 chars here
 
 		// Position at 'é' (char index 3, but é is at byte offset 4 and takes 2 bytes)
@@ -257,12 +247,12 @@ mod tests {
 		let text = Rope::from("hello\n");
 		let encoding = OffsetEncoding::Utf32;
 
-		// Line out of bounds
+		// Line out of bounds clamps to the end of the text
 		let pos = Position {
 			line: 5,
 			character: 0,
 		};
-		assert!(lsp_position_to_char(&text, pos, encoding).is_none());
+		assert_eq!(lsp_position_to_char(&text, pos, encoding), 6);
 
 		// Char index out of bounds
 		assert!(char_to_lsp_position(&text, 100, encoding).is_none());
@@ -278,7 +268,7 @@ mod tests {
 			line: 0,
 			character: 100,
 		};
-		let char_idx = lsp_position_to_char(&text, pos, encoding).unwrap();
+		let char_idx = lsp_position_to_char(&text, pos, encoding);
 		assert_eq!(char_idx, 2); // "hi" has 2 chars, clamped to end
 	}
 
@@ -298,11 +288,101 @@ mod tests {
 			},
 		};
 
-		let (start, end) = lsp_range_to_char_range(&text, lsp_range, encoding).unwrap();
+		let (start, end) = lsp_range_to_char_range(&text, lsp_range, encoding);
 		assert_eq!(start, 1); // 'e' in "hello"
 		assert_eq!(end, 9); // 'l' in "world" (6 + 3)
 
 		let back = char_range_to_lsp_range(&text, start, end, encoding).unwrap();
 		assert_eq!(back, lsp_range);
 	}
+
+	#[test]
+	fn str_offsets_count_encoding_units() {
+		// 'é' is 2 UTF-8 bytes, '😀' is 2 UTF-16 units and 4 UTF-8 bytes.
+		let text = "é😀x";
+		assert_eq!(str_offset_to_char(text, 6, OffsetEncoding::Utf8), Some(2));
+		assert_eq!(str_offset_to_char(text, 3, OffsetEncoding::Utf16), Some(2));
+		assert_eq!(str_offset_to_char(text, 2, OffsetEncoding::Utf32), Some(2));
+		assert_eq!(str_offset_to_char(text, 4, OffsetEncoding::Utf16), Some(3));
+		// Inside a character or past the end.
+		assert_eq!(str_offset_to_char(text, 1, OffsetEncoding::Utf8), None);
+		assert_eq!(str_offset_to_char(text, 2, OffsetEncoding::Utf16), None);
+		assert_eq!(str_offset_to_char(text, 4, OffsetEncoding::Utf32), None);
+	}
+
+	mod properties {
+		use proptest::prelude::*;
+
+		use super::*;
+
+		const ENCODINGS: [OffsetEncoding; 3] = [
+			OffsetEncoding::Utf8,
+			OffsetEncoding::Utf16,
+			OffsetEncoding::Utf32,
+		];
+
+		/// Text mixing ASCII, multi-byte characters, characters outside the
+		/// BMP (surrogate pairs in UTF-16) and newlines.
+		fn text() -> impl Strategy<Value = String> {
+			let pieces = prop::sample::select(&["a", " ", "é", "中", "😀", "𝄞", "\n"][..]);
+			prop::collection::vec(pieces, 0..48).prop_map(|pieces| pieces.concat())
+		}
+
+		/// Column of `char_offset` on `line`, counted the way a server does.
+		fn server_column(line: &str, char_offset: usize, encoding: OffsetEncoding) -> u32 {
+			let prefix: String = line.chars().take(char_offset).collect();
+			let units = match encoding {
+				OffsetEncoding::Utf8 => prefix.len(),
+				OffsetEncoding::Utf16 => prefix.encode_utf16().count(),
+				OffsetEncoding::Utf32 => prefix.chars().count(),
+			};
+			units as u32
+		}
+
+		proptest! {
+			#[test]
+			fn positions_round_trip(text in text()) {
+				let rope = Rope::from(text.as_str());
+				for encoding in ENCODINGS {
+					for idx in 0..=rope.len_chars() {
+						let pos = char_to_lsp_position(&rope, idx, encoding).unwrap();
+						let line = pos.line as usize;
+						let column = idx - rope.line_to_char(line);
+						let expected = server_column(&rope.line(line).to_string(), column, encoding);
+						prop_assert_eq!(pos.character, expected);
+						prop_assert_eq!(lsp_position_to_char(&rope, pos, encoding), idx);
+					}
+				}
+			}
+
+			#[test]
+			fn server_positions_are_clamped(
+				text in text(),
+				line in 0u32..16,
+				character in prop_oneof![0u32..16, any::<u32>()],
+			) {
+				let rope = Rope::from(text.as_str());
+				for encoding in ENCODINGS {
+					let idx = lsp_position_to_char(&rope, Position { line, character }, encoding);
+					prop_assert!(idx <= rope.len_chars());
+					let line = line as usize;
+					if line >= rope.len_lines() {
+						prop_assert_eq!(idx, rope.len_chars());
+						continue;
+					}
+					prop_assert_eq!(rope.char_to_line(idx), line);
+
+					// A column inside a character lands after it.
+					let text = rope.line(line).to_string();
+					let text = text.trim_end_matches('\n');
+					let column = idx - rope.line_to_char(line);
+					let width = server_column(text, text.chars().count(), encoding);
+					prop_assert!(server_column(text, column, encoding) >= character.min(width));
+					if column > 0 {
+						prop_assert!(server_column(text, column - 1, encoding) < character);
+					}
+				}
+			}
+		}
+	}
 }
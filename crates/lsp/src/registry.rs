@@ -6,9 +6,22 @@
 //! # Overview
 //!
 //! The registry maintains:
-//! - A map of server configurations by language/file type
-//! - Active server instances
-//! - Pending initialization state
+//! - An ordered list of server configurations by language/file type
+//! - Active server instances, one per server and project root
+//! - Servers still starting up
+//! - Servers stopped by the user, and crashed servers waiting to restart
+//!
+//! A language may have several servers, such as a language server plus a
+//! linter speaking LSP. Every one of them is started for a document; the
+//! order they were registered in decides which is asked first for requests.
+//!
+//! A server that crashes is restarted after a delay that doubles with each
+//! consecutive crash, until it has crashed more than its configured
+//! `max_restarts` times in a row; it is then left stopped until restarted
+//! with [`Registry::restart`]. Crashes are picked up by
+//! [`Registry::poll_crashes`], and a restart happens once
+//! [`Registry::due_restarts`] has handed the server back to be started when
+//! its documents are next opened.
 //!
 //! # Example
 //!
@@ -25,28 +38,34 @@
 //!     ..Default::default()
 //! });
 //!
-//! // Get or start a server for a Rust file
-//! let client = registry.get_or_start("rust", "/path/to/project").await?;
+//! // Get or start the servers for a Rust file
+//! let clients = registry.get_or_start_all("rust", "/path/to/project").await?;
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
+use futures::future::join_all;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::task::JoinHandle;
-use tracing::{info, trace, warn};
+use tracing::{debug, info, trace, warn};
 
 use crate::Result;
 use crate::client::{
-	ClientHandle, LanguageServerId, ServerConfig, SharedEventHandler, start_server,
+	ClientHandle, LanguageServerId, ServerConfig, ServerLog, SharedEventHandler, start_server,
 };
 
 /// Configuration for a language server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LanguageServerConfig {
+	/// Name the server is known by; the command if empty.
+	#[serde(default)]
+	pub name: String,
 	/// Command to run the language server.
 	pub command: String,
 	/// Arguments to pass to the command.
@@ -68,6 +87,10 @@ pub struct LanguageServerConfig {
 	/// Enable snippet support in completions.
 	#[serde(default)]
 	pub enable_snippets: bool,
+	/// Consecutive crashes the server is restarted after before it is left
+	/// stopped.
+	#[serde(default = "default_max_restarts")]
+	pub max_restarts: u32,
 }
 
 /// Returns the default LSP request timeout in seconds.
@@ -75,9 +98,29 @@ fn default_timeout() -> u64 {
 	30
 }
 
+/// Returns the default number of consecutive crashes a server is
+/// restarted after.
+fn default_max_restarts() -> u32 {
+	5
+}
+
+/// How long a server gets to shut down and exit before it is killed.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Delay before restarting a server after its first crash in a row.
+const RESTART_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest delay before restarting a crashed server.
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Uptime after which a crash no longer counts as following the previous
+/// one.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
 impl Default for LanguageServerConfig {
 	fn default() -> Self {
 		Self {
+			name: String::new(),
 			command: String::new(),
 			args: Vec::new(),
 			env: HashMap::new(),
@@ -85,10 +128,137 @@ impl Default for LanguageServerConfig {
 			timeout_secs: default_timeout(),
 			config: None,
 			enable_snippets: true,
+			max_restarts: default_max_restarts(),
+		}
+	}
+}
+
+impl LanguageServerConfig {
+	/// Returns the name the server is known by.
+	pub fn server_name(&self) -> &str {
+		if self.name.is_empty() {
+			&self.command
+		} else {
+			&self.name
 		}
 	}
 }
 
+/// How a language server attached to a document is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerHealth {
+	/// The server is starting and has not finished initializing.
+	Starting,
+	/// The server is initialized and running.
+	Running,
+	/// The server process has exited.
+	Exited,
+}
+
+/// A language server attached to a document, for status displays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerStatus {
+	/// Name the server is known by.
+	pub name: String,
+	/// How the server is doing.
+	pub health: ServerHealth,
+}
+
+/// What a server known to the registry is doing, for `:lsp-status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerState {
+	/// The server is starting and has not finished initializing.
+	Starting,
+	/// The server is initialized and running.
+	Running,
+	/// The server process has exited and is not restarting yet.
+	Exited,
+	/// The server crashed and restarts after the given delay.
+	Restarting(Duration),
+	/// The server was stopped by the user.
+	Stopped,
+	/// The server crashed too often in a row and was left stopped.
+	Failed,
+}
+
+/// A server known to the registry, with figures about its process.
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+	/// Language the server was started for.
+	pub language: String,
+	/// Name the server is known by.
+	pub name: String,
+	/// Project root the server was started in.
+	pub root: PathBuf,
+	/// What the server is doing.
+	pub state: ServerState,
+	/// OS process ID, if a process is running.
+	pub pid: Option<u32>,
+	/// Time since the running process was spawned.
+	pub uptime: Option<Duration>,
+	/// Requests sent to the running process and not yet answered.
+	pub pending_requests: usize,
+	/// Message of the last failed request or crash.
+	pub last_error: Option<String>,
+	/// Crashes in a row so far.
+	pub crashes: u32,
+}
+
+/// A crash of a language server, as reported by [`Registry::poll_crashes`].
+#[derive(Debug, Clone)]
+pub struct ServerCrash {
+	/// Language the server was started for.
+	pub language: String,
+	/// Name the server is known by.
+	pub name: String,
+	/// Project root the server was started in.
+	pub root: PathBuf,
+	/// ID of the instance that crashed, or `None` if it failed to start.
+	pub id: Option<LanguageServerId>,
+	/// Message of the last error of the server, if any.
+	pub error: Option<String>,
+	/// Crashes in a row so far, this one included.
+	pub crashes: u32,
+	/// Delay before the server restarts, or `None` if it crashed too often
+	/// and was left stopped.
+	pub restart_in: Option<Duration>,
+}
+
+/// A server stopped by [`Registry::stop`] or [`Registry::restart`].
+#[derive(Debug, Clone)]
+pub struct StoppedServer {
+	/// Name the server is known by.
+	pub name: String,
+	/// Project root the server was started in.
+	pub root: PathBuf,
+	/// ID of the instance that was running, if any.
+	pub id: Option<LanguageServerId>,
+}
+
+/// Key of a server instance: language, server name and project root.
+type ServerKey = (String, String, PathBuf);
+
+/// Crash history of a server, kept until it is stopped or restarted by
+/// hand.
+#[derive(Debug, Default)]
+struct RestartState {
+	/// Crashes in a row so far.
+	crashes: u32,
+	/// When the server may be started again, while waiting to restart.
+	retry_at: Option<Instant>,
+	/// Whether the server crashed too often and is left stopped.
+	gave_up: bool,
+	/// Message of the last error of the server before it crashed.
+	last_error: Option<String>,
+}
+
+impl RestartState {
+	/// Whether the server must not be started for now.
+	fn holds(&self) -> bool {
+		self.gave_up || self.retry_at.is_some()
+	}
+}
+
 /// A running language server instance.
 struct ServerInstance {
 	/// Handle for communicating with the server.
@@ -108,14 +278,24 @@ impl ServerInstance {
 ///
 /// Thread-safe: can be shared across async tasks using `Arc<Registry>`.
 pub struct Registry {
-	/// Configurations by language name.
-	configs: RwLock<HashMap<String, LanguageServerConfig>>,
-	/// Active server instances by (language, root_path).
-	servers: RwLock<HashMap<(String, PathBuf), ServerInstance>>,
+	/// Configurations by language name, in the order servers are asked.
+	configs: RwLock<HashMap<String, Vec<LanguageServerConfig>>>,
+	/// Active server instances by (language, server name, root_path).
+	servers: RwLock<HashMap<ServerKey, ServerInstance>>,
+	/// Servers started but not yet initialized.
+	starting: RwLock<HashMap<ServerKey, ClientHandle>>,
+	/// Servers stopped by the user, not started again until restarted.
+	stopped: RwLock<HashSet<ServerKey>>,
+	/// Crash history of servers that crashed.
+	restarts: RwLock<HashMap<ServerKey, RestartState>>,
+	/// Crashed restarts not yet reported by [`Self::poll_crashes`].
+	failed_starts: RwLock<Vec<ServerCrash>>,
 	/// Counter for generating unique server IDs.
 	next_id: AtomicU64,
 	/// Event handler for LSP events (diagnostics, progress, etc.).
 	event_handler: Option<SharedEventHandler>,
+	/// Stderr and protocol trace of every server.
+	log: Arc<ServerLog>,
 }
 
 impl Default for Registry {
@@ -130,8 +310,13 @@ impl Registry {
 		Self {
 			configs: RwLock::new(HashMap::new()),
 			servers: RwLock::new(HashMap::new()),
+			starting: RwLock::new(HashMap::new()),
+			stopped: RwLock::new(HashSet::new()),
+			restarts: RwLock::new(HashMap::new()),
+			failed_starts: RwLock::new(Vec::new()),
 			next_id: AtomicU64::new(1),
 			event_handler: None,
+			log: Arc::new(ServerLog::default()),
 		}
 	}
 
@@ -143,8 +328,13 @@ impl Registry {
 		Self {
 			configs: RwLock::new(HashMap::new()),
 			servers: RwLock::new(HashMap::new()),
+			starting: RwLock::new(HashMap::new()),
+			stopped: RwLock::new(HashSet::new()),
+			restarts: RwLock::new(HashMap::new()),
+			failed_starts: RwLock::new(Vec::new()),
 			next_id: AtomicU64::new(1),
 			event_handler: Some(event_handler),
+			log: Arc::new(ServerLog::default()),
 		}
 	}
 
@@ -155,26 +345,57 @@ impl Registry {
 		self.event_handler = Some(handler);
 	}
 
+	/// Returns the log of every server's stderr and protocol trace.
+	pub fn log(&self) -> &Arc<ServerLog> {
+		&self.log
+	}
+
 	/// Register a language server configuration for a language.
+	///
+	/// Servers registered for the same language are asked for requests in
+	/// registration order. Registering a server under a name already
+	/// configured for the language replaces that configuration in place.
 	pub fn register(&self, language: impl Into<String>, config: LanguageServerConfig) {
 		let language = language.into();
 		trace!(
 			language = %language,
+			server = %config.server_name(),
 			command = %config.command,
 			root_markers = ?config.root_markers,
 			"configured language server"
 		);
-		self.configs.write().insert(language, config);
+		let mut configs = self.configs.write();
+		let servers = configs.entry(language).or_default();
+		match servers
+			.iter_mut()
+			.find(|existing| existing.server_name() == config.server_name())
+		{
+			Some(existing) => *existing = config,
+			None => servers.push(config),
+		}
 	}
 
-	/// Remove a language server configuration.
+	/// Remove the language server configurations of a language.
 	pub fn unregister(&self, language: &str) {
 		self.configs.write().remove(language);
 	}
 
-	/// Get the configuration for a language.
+	/// Get the configuration of the first server for a language.
 	pub fn get_config(&self, language: &str) -> Option<LanguageServerConfig> {
-		self.configs.read().get(language).cloned()
+		self.configs
+			.read()
+			.get(language)
+			.and_then(|servers| servers.first())
+			.cloned()
+	}
+
+	/// Get the configurations of every server for a language, in order.
+	pub fn get_configs(&self, language: &str) -> Vec<LanguageServerConfig> {
+		self.configs
+			.read()
+			.get(language)
+			.cloned()
+			.unwrap_or_default()
 	}
 
 	/// List all registered languages.
@@ -182,36 +403,94 @@ impl Registry {
 		self.configs.read().keys().cloned().collect()
 	}
 
-	/// Get an active client for a language and file path, starting one if needed.
+	/// Get active clients for every server of a language and file path,
+	/// starting those that are not running, in configuration order.
+	///
+	/// A server that fails to start is logged and left out; an error is
+	/// returned only if none of the servers could be started. Servers
+	/// stopped by the user or waiting to restart after a crash are left out
+	/// too.
+	pub async fn get_or_start_all(
+		&self,
+		language: &str,
+		file_path: &Path,
+	) -> Result<Vec<ClientHandle>> {
+		let configs = self.get_configs(language);
+		if configs.is_empty() {
+			return Err(crate::Error::Protocol(format!(
+				"No server configured for {language}"
+			)));
+		}
+
+		let mut clients = Vec::with_capacity(configs.len());
+		let mut error = None;
+		for config in &configs {
+			let root_path = find_root_path(file_path, &config.root_markers);
+			if self.is_held(&server_key(language, config, root_path)) {
+				continue;
+			}
+			match self.get_or_start_server(language, config, file_path).await {
+				Ok(client) => clients.push(client),
+				Err(e) => {
+					warn!(
+						language = %language,
+						server = %config.server_name(),
+						error = %e,
+						"Failed to start language server"
+					);
+					error.get_or_insert(e);
+				}
+			}
+		}
+		match error {
+			Some(e) if clients.is_empty() => Err(e),
+			_ => Ok(clients),
+		}
+	}
+
+	/// Get an active client for one server of a language and file path,
+	/// starting it if needed.
 	///
 	/// This finds the project root based on the configured root markers,
 	/// then returns an existing server for that root or starts a new one.
-	/// If an existing server has crashed, it will be cleaned up and restarted.
-	pub async fn get_or_start(&self, language: &str, file_path: &Path) -> Result<ClientHandle> {
-		let config = self.get_config(language).ok_or_else(|| {
-			crate::Error::Protocol(format!("No server configured for {language}"))
-		})?;
-
+	/// If an existing server has crashed, the crash is recorded and the
+	/// server is left to restart after its delay.
+	async fn get_or_start_server(
+		&self,
+		language: &str,
+		config: &LanguageServerConfig,
+		file_path: &Path,
+	) -> Result<ClientHandle> {
 		let root_path = find_root_path(file_path, &config.root_markers);
-		let key = (language.to_string(), root_path.clone());
+		let key = server_key(language, config, root_path.clone());
 
-		// Check for existing server, clean up if dead
-		{
-			let servers = self.servers.read();
-			if let Some(instance) = servers.get(&key) {
-				if instance.is_alive() {
-					return Ok(instance.handle.clone());
-				}
-				warn!(language = %language, root = ?root_path, "Language server crashed, restarting");
+		// Check for existing server, record the crash if dead
+		let dead = {
+			let mut servers = self.servers.write();
+			match servers.get(&key) {
+				Some(instance) if instance.is_alive() => return Ok(instance.handle.clone()),
+				Some(_) => servers.remove(&key),
+				None => None,
 			}
+		};
+		if let Some(instance) = dead {
+			let crash = self.record_crash(
+				key,
+				Some(instance.handle.id()),
+				instance.handle.last_error(),
+				instance.handle.uptime(),
+			);
+			self.failed_starts.write().push(crash);
+			return Err(crate::Error::Protocol(format!(
+				"{} exited",
+				config.server_name()
+			)));
 		}
 
-		// Remove dead server if present
-		self.servers.write().remove(&key);
-
 		let id = LanguageServerId(self.next_id.fetch_add(1, Ordering::Relaxed));
 		info!(
 			language = %language,
+			server = %config.server_name(),
 			command = %config.command,
 			root = ?root_path,
 			"Starting language server"
@@ -224,41 +503,124 @@ impl Registry {
 
 		let (handle, task) = start_server(
 			id,
-			config.command.clone(),
+			config.server_name().to_string(),
 			server_config,
 			self.event_handler.clone(),
+			Some(self.log.clone()),
 		)?;
 
-		handle
+		self.starting.write().insert(key.clone(), handle.clone());
+		let initialized = handle
 			.initialize(config.enable_snippets, config.config.clone())
-			.await?;
+			.await;
+		self.starting.write().remove(&key);
+		let instance = ServerInstance {
+			handle: handle.clone(),
+			task,
+		};
 
-		self.servers.write().insert(
-			key,
-			ServerInstance {
-				handle: handle.clone(),
-				task,
-			},
-		);
+		if let Err(e) = initialized {
+			// A server restarting after a crash that fails to come up counts
+			// as crashing again.
+			if self.restarts.read().contains_key(&key) {
+				let crash =
+					self.record_crash(key, Some(handle.id()), Some(e.to_string()), Duration::ZERO);
+				self.failed_starts.write().push(crash);
+			}
+			stop_instance(instance).await;
+			return Err(e);
+		}
+		if self.stopped.read().contains(&key) {
+			stop_instance(instance).await;
+			return Err(crate::Error::Protocol(format!(
+				"{} was stopped",
+				config.server_name()
+			)));
+		}
+
+		self.servers.write().insert(key, instance);
 
 		Ok(handle)
 	}
 
-	/// Get an active client for a language and file path, if one exists and is alive.
+	/// Get the active client of the first running server for a language and
+	/// file path.
 	///
-	/// Finds the project root from the file path using configured root markers,
-	/// then looks up the server for that root.
+	/// Finds the project root from the file path using each server's root
+	/// markers, then looks up the server for that root.
 	///
-	/// Returns `None` if no server exists, no config exists, or if the server has crashed.
-	/// Dead servers are cleaned up lazily on next `get_or_start` call.
+	/// Returns `None` if no server exists, no config exists, or if every
+	/// server has crashed. Dead servers are cleaned up lazily on next
+	/// `get_or_start_all` call.
 	pub fn get(&self, language: &str, file_path: &Path) -> Option<ClientHandle> {
-		let config = self.get_config(language)?;
-		let root_path = find_root_path(file_path, &config.root_markers);
-		let key = (language.to_string(), root_path);
+		self.get_all_for(language, file_path).into_iter().next()
+	}
 
+	/// Get the active clients of every running server for a language and
+	/// file path, in configuration order.
+	pub fn get_all_for(&self, language: &str, file_path: &Path) -> Vec<ClientHandle> {
+		let configs = self.get_configs(language);
 		let servers = self.servers.read();
-		let instance = servers.get(&key)?;
-		instance.is_alive().then(|| instance.handle.clone())
+		configs
+			.iter()
+			.filter_map(|config| {
+				let root_path = find_root_path(file_path, &config.root_markers);
+				let instance = servers.get(&server_key(language, config, root_path))?;
+				instance.is_alive().then(|| instance.handle.clone())
+			})
+			.collect()
+	}
+
+	/// Get the status of every server attached to a language and file path,
+	/// in configuration order.
+	///
+	/// Servers that were never started for the file, failed to start, or
+	/// were stopped by the user are left out; crashed ones are exited. Rather than searched for, roots are matched against
+	/// the file's path, the deepest one winning, so this is cheap enough to
+	/// call on every frame. `file_path` must be absolute.
+	pub fn server_statuses(&self, language: &str, file_path: &Path) -> Vec<ServerStatus> {
+		let configs = self.get_configs(language);
+		let servers = self.servers.read();
+		let starting = self.starting.read();
+		let restarts = self.restarts.read();
+		configs
+			.iter()
+			.filter_map(|config| {
+				let serves = |(lang, name, root): &ServerKey| {
+					lang == language && name == config.server_name() && file_path.starts_with(root)
+				};
+				let health = if starting.keys().any(serves) {
+					ServerHealth::Starting
+				} else if let Some((_, instance)) = servers
+					.iter()
+					.filter(|(key, _)| serves(*key))
+					.max_by_key(|(key, _)| key.2.components().count())
+				{
+					if instance.is_alive() {
+						ServerHealth::Running
+					} else {
+						ServerHealth::Exited
+					}
+				} else if restarts.keys().any(serves) {
+					ServerHealth::Exited
+				} else {
+					return None;
+				};
+				Some(ServerStatus {
+					name: config.server_name().to_string(),
+					health,
+				})
+			})
+			.collect()
+	}
+
+	/// Get the running client with the given server ID.
+	pub fn get_by_id(&self, id: LanguageServerId) -> Option<ClientHandle> {
+		self.servers
+			.read()
+			.values()
+			.find(|instance| instance.handle.id() == id && instance.is_alive())
+			.map(|instance| instance.handle.clone())
 	}
 
 	/// Clean up all dead servers and return the number of servers removed.
@@ -277,7 +639,8 @@ impl Registry {
 			for key in dead_keys {
 				info!(
 					language = %key.0,
-					root = ?key.1,
+					server = %key.1,
+					root = ?key.2,
 					"Cleaning up dead language server"
 				);
 				servers.remove(&key);
@@ -296,21 +659,277 @@ impl Registry {
 			.collect()
 	}
 
-	/// Shutdown a specific server.
-	pub async fn shutdown(&self, language: &str, root_path: &Path) -> Result<()> {
-		let key = (language.to_string(), root_path.to_path_buf());
-		let instance = self.servers.write().remove(&key);
-		if let Some(instance) = instance {
-			instance.handle.shutdown_and_exit().await?;
-		}
-		Ok(())
+	/// Shutdown the servers of a language for a project root.
+	pub async fn shutdown(&self, language: &str, root_path: &Path) {
+		let instances: Vec<_> = {
+			let mut servers = self.servers.write();
+			let keys: Vec<_> = servers
+				.keys()
+				.filter(|(lang, _, root)| lang == language && root == root_path)
+				.cloned()
+				.collect();
+			keys.iter().filter_map(|key| servers.remove(key)).collect()
+		};
+		join_all(instances.into_iter().map(stop_instance)).await;
 	}
 
 	/// Shutdown all servers.
 	pub async fn shutdown_all(&self) {
-		let instances: Vec<_> = self.servers.write().drain().collect();
-		for (_, instance) in instances {
-			let _ = instance.handle.shutdown_and_exit().await;
+		let instances: Vec<_> = self.servers.write().drain().map(|(_, i)| i).collect();
+		join_all(instances.into_iter().map(stop_instance)).await;
+	}
+
+	/// Stop the servers of a language for a file path, or only the one
+	/// named, and keep them from being started until restarted.
+	///
+	/// Running servers are asked to shut down and exit, and killed if they
+	/// have not exited in time. Returns the servers stopped, or an error if
+	/// none is configured for the language under that name.
+	pub async fn stop(
+		&self,
+		language: &str,
+		file_path: &Path,
+		name: Option<&str>,
+	) -> Result<Vec<StoppedServer>> {
+		let keys = self.keys_for(language, file_path, name)?;
+		let instances: Vec<_> = {
+			let mut servers = self.servers.write();
+			let mut stopped = self.stopped.write();
+			let mut restarts = self.restarts.write();
+			keys.into_iter()
+				.map(|key| {
+					restarts.remove(&key);
+					stopped.insert(key.clone());
+					let instance = servers.remove(&key);
+					(key, instance)
+				})
+				.collect()
+		};
+
+		let stopped = instances
+			.iter()
+			.map(|((_, name, root), instance)| StoppedServer {
+				name: name.clone(),
+				root: root.clone(),
+				id: instance.as_ref().map(|i| i.handle.id()),
+			})
+			.collect();
+		for ((_, name, root), _) in instances.iter().filter(|(_, i)| i.is_some()) {
+			info!(language = %language, server = %name, root = ?root, "Stopping language server");
+		}
+		join_all(
+			instances
+				.into_iter()
+				.filter_map(|(_, instance)| instance)
+				.map(stop_instance),
+		)
+		.await;
+		Ok(stopped)
+	}
+
+	/// Stop the servers of a language for a file path, or only the one
+	/// named, and let them start again, with a clean crash history, when
+	/// their documents are next opened.
+	///
+	/// Returns the servers stopped, as [`Self::stop`] does.
+	pub async fn restart(
+		&self,
+		language: &str,
+		file_path: &Path,
+		name: Option<&str>,
+	) -> Result<Vec<StoppedServer>> {
+		let stopped = self.stop(language, file_path, name).await?;
+		let mut held = self.stopped.write();
+		for server in &stopped {
+			held.remove(&(
+				language.to_string(),
+				server.name.clone(),
+				server.root.clone(),
+			));
+		}
+		Ok(stopped)
+	}
+
+	/// Collect the servers that crashed since the last call.
+	///
+	/// Each crashed server is scheduled to restart after a delay, doubling
+	/// with each crash in a row, or left stopped once it has crashed more
+	/// than `max_restarts` times in a row. A crash after a minute of uptime
+	/// starts a new row.
+	pub fn poll_crashes(&self) -> Vec<ServerCrash> {
+		let dead: Vec<_> = {
+			let mut servers = self.servers.write();
+			let keys: Vec<_> = servers
+				.iter()
+				.filter(|(_, instance)| !instance.is_alive())
+				.map(|(key, _)| key.clone())
+				.collect();
+			keys.into_iter()
+				.filter_map(|key| servers.remove(&key).map(|instance| (key, instance)))
+				.collect()
+		};
+
+		let mut crashes = std::mem::take(&mut *self.failed_starts.write());
+		for (key, instance) in dead {
+			crashes.push(self.record_crash(
+				key,
+				Some(instance.handle.id()),
+				instance.handle.last_error(),
+				instance.handle.uptime(),
+			));
+		}
+		crashes
+	}
+
+	/// Take the crashed servers whose restart delay has passed, as the
+	/// language and root of each.
+	///
+	/// From then on the servers are started again when a document of the
+	/// language under the root is opened.
+	pub fn due_restarts(&self) -> Vec<(String, PathBuf)> {
+		let now = Instant::now();
+		self.restarts
+			.write()
+			.iter_mut()
+			.filter(|(_, state)| state.retry_at.is_some_and(|at| at <= now))
+			.map(|((language, _, root), state)| {
+				state.retry_at = None;
+				(language.clone(), root.clone())
+			})
+			.collect()
+	}
+
+	/// Describe every server the registry has started or holds back, by
+	/// language, name and root.
+	pub fn server_infos(&self) -> Vec<ServerInfo> {
+		let now = Instant::now();
+		let mut infos = BTreeMap::new();
+		let info = |key: &ServerKey, state, handle: Option<&ClientHandle>| ServerInfo {
+			language: key.0.clone(),
+			name: key.1.clone(),
+			root: key.2.clone(),
+			state,
+			pid: handle.and_then(ClientHandle::pid),
+			uptime: handle.map(ClientHandle::uptime),
+			pending_requests: handle.map_or(0, ClientHandle::pending_requests),
+			last_error: handle.and_then(ClientHandle::last_error),
+			crashes: 0,
+		};
+
+		for key in self.stopped.read().iter() {
+			infos.insert(key.clone(), info(key, ServerState::Stopped, None));
+		}
+		for (key, state) in self.restarts.read().iter() {
+			let server_state = match state.retry_at {
+				_ if state.gave_up => ServerState::Failed,
+				Some(at) => ServerState::Restarting(at.saturating_duration_since(now)),
+				None => ServerState::Exited,
+			};
+			let mut server = info(key, server_state, None);
+			server.crashes = state.crashes;
+			server.last_error = state.last_error.clone();
+			infos.insert(key.clone(), server);
+		}
+		for (key, instance) in self.servers.read().iter() {
+			let state = if instance.is_alive() {
+				ServerState::Running
+			} else {
+				ServerState::Exited
+			};
+			let crashes = infos.get(key).map_or(0, |server| server.crashes);
+			let mut server = info(key, state, Some(&instance.handle));
+			server.crashes = crashes;
+			infos.insert(key.clone(), server);
+		}
+		for (key, handle) in self.starting.read().iter() {
+			let crashes = infos.get(key).map_or(0, |server| server.crashes);
+			let mut server = info(key, ServerState::Starting, Some(handle));
+			server.crashes = crashes;
+			infos.insert(key.clone(), server);
+		}
+		infos.into_values().collect()
+	}
+
+	/// Whether a server must not be started, being stopped by the user or
+	/// waiting to restart after a crash.
+	fn is_held(&self, key: &ServerKey) -> bool {
+		self.stopped.read().contains(key)
+			|| self
+				.restarts
+				.read()
+				.get(key)
+				.is_some_and(RestartState::holds)
+	}
+
+	/// Get the keys of the servers of a language for a file path, or only
+	/// of the one named.
+	fn keys_for(
+		&self,
+		language: &str,
+		file_path: &Path,
+		name: Option<&str>,
+	) -> Result<Vec<ServerKey>> {
+		let keys: Vec<_> = self
+			.get_configs(language)
+			.iter()
+			.filter(|config| name.is_none_or(|name| config.server_name() == name))
+			.map(|config| {
+				let root_path = find_root_path(file_path, &config.root_markers);
+				server_key(language, config, root_path)
+			})
+			.collect();
+		if keys.is_empty() {
+			return Err(crate::Error::Protocol(match name {
+				Some(name) => format!("No language server named {name} for {language}"),
+				None => format!("No server configured for {language}"),
+			}));
+		}
+		Ok(keys)
+	}
+
+	/// Record a crash of a server that had been up for `uptime`, scheduling
+	/// its restart or giving up on it.
+	fn record_crash(
+		&self,
+		key: ServerKey,
+		id: Option<LanguageServerId>,
+		error: Option<String>,
+		uptime: Duration,
+	) -> ServerCrash {
+		let max_restarts = self
+			.get_configs(&key.0)
+			.iter()
+			.find(|config| config.server_name() == key.1)
+			.map_or(0, |config| config.max_restarts);
+
+		let mut restarts = self.restarts.write();
+		let state = restarts.entry(key.clone()).or_default();
+		if uptime >= STABLE_UPTIME {
+			state.crashes = 0;
+		}
+		state.crashes += 1;
+		state.last_error = error.clone();
+		let restart_in = (state.crashes <= max_restarts).then(|| restart_delay(state.crashes));
+		state.retry_at = restart_in.map(|delay| Instant::now() + delay);
+		state.gave_up = restart_in.is_none();
+
+		let (language, name, root) = key;
+		warn!(
+			language = %language,
+			server = %name,
+			root = ?root,
+			crashes = state.crashes,
+			restart_in = ?restart_in,
+			"Language server crashed"
+		);
+		ServerCrash {
+			language,
+			name,
+			root,
+			id,
+			error,
+			crashes: state.crashes,
+			restart_in,
 		}
 	}
 
@@ -320,6 +939,41 @@ impl Registry {
 	}
 }
 
+/// Ask a server to shut down and exit, killing it if it has not exited
+/// within [`SHUTDOWN_TIMEOUT`].
+async fn stop_instance(instance: ServerInstance) {
+	let ServerInstance { handle, mut task } = instance;
+	let exited = tokio::time::timeout(SHUTDOWN_TIMEOUT, async {
+		if let Err(e) = handle.shutdown_and_exit().await {
+			debug!(server = %handle.name(), error = %e, "Language server shutdown failed");
+		}
+		let _ = (&mut task).await;
+	})
+	.await;
+	if exited.is_err() {
+		warn!(server = %handle.name(), "Language server did not exit in time, killing it");
+		// Dropping the main loop drops the process, which kills it.
+		task.abort();
+	}
+}
+
+/// Returns the delay before restarting a server after `crashes` crashes in
+/// a row.
+fn restart_delay(crashes: u32) -> Duration {
+	RESTART_BASE_DELAY
+		.saturating_mul(1 << crashes.saturating_sub(1).min(16))
+		.min(RESTART_MAX_DELAY)
+}
+
+/// Returns the key of the instance of the server configured by `config`.
+fn server_key(language: &str, config: &LanguageServerConfig, root_path: PathBuf) -> ServerKey {
+	(
+		language.to_string(),
+		config.server_name().to_string(),
+		root_path,
+	)
+}
+
 /// Find the project root by walking up from the file path.
 ///
 /// Looks for any of the root markers. If none found, returns the file's directory.
@@ -403,4 +1057,75 @@ mod tests {
 		registry.unregister("rust");
 		assert!(registry.get_config("rust").is_none());
 	}
+
+	#[test]
+	fn test_registry_keeps_server_order() {
+		let registry = Registry::new();
+		let server = |name: &str, command: &str| LanguageServerConfig {
+			name: name.into(),
+			command: command.into(),
+			..Default::default()
+		};
+
+		registry.register("python", server("pyright", "pyright-langserver"));
+		registry.register("python", server("ruff", "ruff"));
+		registry.register("python", server("pyright", "basedpyright-langserver"));
+
+		let configs = registry.get_configs("python");
+		let names: Vec<_> = configs.iter().map(|c| c.server_name()).collect();
+		assert_eq!(names, ["pyright", "ruff"]);
+		assert_eq!(configs[0].command, "basedpyright-langserver");
+		assert_eq!(
+			registry.get_config("python").unwrap().server_name(),
+			"pyright"
+		);
+		assert!(
+			registry
+				.server_statuses("python", Path::new("/tmp"))
+				.is_empty()
+		);
+	}
+
+	#[test]
+	fn test_restart_delay_doubles_up_to_cap() {
+		assert_eq!(restart_delay(1), RESTART_BASE_DELAY);
+		assert_eq!(restart_delay(2), RESTART_BASE_DELAY * 2);
+		assert_eq!(restart_delay(3), RESTART_BASE_DELAY * 4);
+		assert_eq!(restart_delay(100), RESTART_MAX_DELAY);
+	}
+
+	#[test]
+	fn test_crashes_give_up_after_max_restarts() {
+		let registry = Registry::new();
+		registry.register(
+			"rust",
+			LanguageServerConfig {
+				command: "rust-analyzer".into(),
+				max_restarts: 2,
+				..Default::default()
+			},
+		);
+		let key: ServerKey = ("rust".into(), "rust-analyzer".into(), "/project".into());
+
+		let crash = |uptime| registry.record_crash(key.clone(), None, None, uptime);
+		assert_eq!(crash(Duration::ZERO).restart_in, Some(restart_delay(1)));
+		assert!(registry.is_held(&key));
+		assert_eq!(crash(Duration::ZERO).restart_in, Some(restart_delay(2)));
+
+		// A crash after running for a while starts a new row.
+		let stable = crash(STABLE_UPTIME);
+		assert_eq!(stable.crashes, 1);
+
+		crash(Duration::ZERO);
+		let gave_up = crash(Duration::ZERO);
+		assert_eq!(gave_up.crashes, 3);
+		assert_eq!(gave_up.restart_in, None);
+		assert!(registry.is_held(&key));
+		assert!(registry.due_restarts().is_empty());
+
+		let infos = registry.server_infos();
+		assert_eq!(infos.len(), 1);
+		assert_eq!(infos[0].state, ServerState::Failed);
+		assert_eq!(infos[0].crashes, 3);
+	}
 }
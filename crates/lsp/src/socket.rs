@@ -23,6 +23,8 @@ pub(crate) enum MainLoopEvent {
 	OutgoingRequest(AnyRequest, oneshot::Sender<AnyResponse>),
 	/// A user-defined loopback event.
 	Any(AnyEvent),
+	/// A request future was dropped before its response arrived.
+	RequestDropped,
 }
 
 /// Macro to implement common socket wrapper methods for Client/Server sockets.
@@ -119,6 +121,7 @@ impl PeerSocket {
 		let _: Result<_, _> = self.send(MainLoopEvent::OutgoingRequest(req, tx));
 		PeerSocketRequestFuture {
 			rx,
+			socket: Some(self.tx.clone()),
 			_marker: PhantomData,
 		}
 	}
@@ -139,9 +142,13 @@ impl PeerSocket {
 }
 
 /// Future for awaiting a response to a peer request.
+///
+/// Dropping it before the response arrives cancels the request.
 pub(crate) struct PeerSocketRequestFuture<T> {
 	/// Channel receiver for the response.
 	rx: oneshot::Receiver<AnyResponse>,
+	/// Sender to the main loop, until the request is answered.
+	socket: Option<mpsc::UnboundedSender<MainLoopEvent>>,
 	/// Marker for the expected result type.
 	_marker: PhantomData<fn() -> T>,
 }
@@ -150,11 +157,10 @@ impl<T: DeserializeOwned> Future for PeerSocketRequestFuture<T> {
 	type Output = Result<T>;
 
 	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-		let resp = ready!(
-			Pin::new(&mut self.rx)
-				.poll(cx)
-				.map_err(|_| Error::ServiceStopped)
-		)?;
+		let resp = ready!(Pin::new(&mut self.rx).poll(cx));
+		// Answered, or dropped by a stopped main loop: nothing to cancel.
+		self.socket = None;
+		let resp = resp.map_err(|_| Error::ServiceStopped)?;
 		Poll::Ready(match resp.error {
 			None => Ok(serde_json::from_value(resp.result.unwrap_or_default())?),
 			Some(err) => Err(Error::Response(err)),
@@ -162,8 +168,19 @@ impl<T: DeserializeOwned> Future for PeerSocketRequestFuture<T> {
 	}
 }
 
+impl<T> Drop for PeerSocketRequestFuture<T> {
+	fn drop(&mut self) {
+		if let Some(socket) = self.socket.take() {
+			// The main loop finds the request by its closed response channel.
+			let _: Result<_, _> = socket.unbounded_send(MainLoopEvent::RequestDropped);
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
+	use futures::StreamExt;
+
 	use super::*;
 
 	#[tokio::test]
@@ -193,4 +210,35 @@ mod tests {
 		));
 		assert!(matches!(socket.emit(42i32), Err(Error::ServiceStopped)));
 	}
+
+	#[tokio::test]
+	async fn dropping_unanswered_request_reports_it() {
+		let (tx, mut rx) = mpsc::unbounded();
+		let socket = PeerSocket { tx };
+
+		drop(socket.request::<lsp_types::request::Shutdown>(()));
+		assert!(matches!(
+			rx.next().await,
+			Some(MainLoopEvent::OutgoingRequest(..))
+		));
+		assert!(matches!(
+			rx.next().await,
+			Some(MainLoopEvent::RequestDropped)
+		));
+
+		let request = socket.request::<lsp_types::request::Shutdown>(());
+		let Some(MainLoopEvent::OutgoingRequest(req, resp_tx)) = rx.next().await else {
+			panic!("expected a request");
+		};
+		resp_tx
+			.send(AnyResponse {
+				id: req.id,
+				result: None,
+				error: None,
+			})
+			.unwrap();
+		request.await.unwrap();
+		drop(socket);
+		assert!(rx.next().await.is_none());
+	}
 }
@@ -35,7 +35,7 @@ use ropey::Rope;
 use xeno_base::lsp::LspDocumentChange;
 
 use crate::Result;
-use crate::client::{ClientHandle, LanguageServerId, LspEventHandler};
+use crate::client::{ClientHandle, LanguageServerId, LspEventHandler, OffsetEncoding};
 use crate::document::{DiagnosticsEventReceiver, DocumentStateManager};
 use crate::registry::Registry;
 
@@ -68,13 +68,18 @@ impl DocumentSyncEventHandler {
 }
 
 impl LspEventHandler for DocumentSyncEventHandler {
-	fn on_diagnostics(&self, _server_id: LanguageServerId, uri: Uri, diagnostics: Vec<Diagnostic>) {
-		self.documents.update_diagnostics(&uri, diagnostics);
+	fn on_diagnostics(&self, server_id: LanguageServerId, uri: Uri, diagnostics: Vec<Diagnostic>) {
+		self.documents
+			.update_diagnostics(server_id, &uri, diagnostics);
 	}
 
 	fn on_progress(&self, server_id: LanguageServerId, params: lsp_types::ProgressParams) {
 		self.documents.update_progress(server_id, params);
 	}
+
+	fn on_inlay_hint_refresh(&self, _server_id: LanguageServerId) {
+		self.documents.refresh_inlay_hints();
+	}
 }
 
 /// Document synchronization coordinator.
@@ -133,10 +138,11 @@ impl DocumentSync {
 		(sync, registry, documents, event_receiver)
 	}
 
-	/// Open a document with the appropriate language server.
+	/// Open a document with the appropriate language servers.
 	///
-	/// This finds or starts a language server for the document's language,
-	/// registers the document, and sends `textDocument/didOpen`.
+	/// This finds or starts every language server configured for the
+	/// document's language, registers the document, and sends
+	/// `textDocument/didOpen` to each server it is not open with yet.
 	///
 	/// # Arguments
 	///
@@ -148,26 +154,28 @@ impl DocumentSync {
 		path: &Path,
 		language: &str,
 		text: &Rope,
-	) -> Result<ClientHandle> {
-		let client = self.registry.get_or_start(language, path).await?;
+	) -> Result<Vec<ClientHandle>> {
+		let clients = self.registry.get_or_start_all(language, path).await?;
 
 		let uri = self
 			.documents
 			.register(path, Some(language))
 			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
 
-		let version = self.documents.get_version(&uri).unwrap_or(0);
-
-		client.text_document_did_open(
-			uri.clone(),
-			language.to_string(),
-			version,
-			text.to_string(),
-		)?;
-
-		self.documents.set_opened(&uri, true);
+		for client in &clients {
+			if self.documents.is_opened_with(&uri, client.id()) {
+				continue;
+			}
+			client.text_document_did_open(
+				uri.clone(),
+				language.to_string(),
+				0,
+				text.to_string(),
+			)?;
+			self.documents.set_opened(&uri, client.id(), true);
+		}
 
-		Ok(client)
+		Ok(clients)
 	}
 
 	/// Notify language servers of a document change.
@@ -181,76 +189,121 @@ impl DocumentSync {
 	/// * `language` - Language ID
 	/// * `text` - New document content
 	pub async fn notify_change_full(&self, path: &Path, language: &str, text: &Rope) -> Result<()> {
-		let uri = crate::uri_from_path(path)
-			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
-
-		if !self.documents.is_opened(&uri) {
+		if !self.try_notify_change(path, language, text, None)? {
 			self.open_document(path, language, text).await?;
-			return Ok(());
-		}
-
-		let version = self
-			.documents
-			.increment_version(&uri)
-			.ok_or_else(|| crate::Error::Protocol("Document not registered".into()))?;
-
-		if let Some(client) = self.registry.get(language, path) {
-			client.text_document_did_change_full(uri, version, text.to_string())?;
 		}
-
 		Ok(())
 	}
 
 	/// Notify language servers of an incremental document change.
 	///
-	/// Uses pre-computed LSP ranges for accurate position tracking.
+	/// Uses pre-computed LSP ranges, whose positions are in `encoding`, for
+	/// accurate position tracking.
 	pub async fn notify_change_incremental(
 		&self,
 		path: &Path,
 		language: &str,
 		text: &Rope,
 		changes: Vec<LspDocumentChange>,
+		encoding: OffsetEncoding,
 	) -> Result<()> {
 		if changes.is_empty() {
 			return Ok(());
 		}
 
+		if !self.try_notify_change(path, language, text, Some((changes, encoding)))? {
+			self.open_document(path, language, text).await?;
+		}
+		Ok(())
+	}
+
+	/// Sends `textDocument/didChange` to every server the document is open
+	/// with.
+	///
+	/// `changes` are incremental change events with the offset encoding of
+	/// their positions, or `None` to send the whole `text`. Servers that do
+	/// not take incremental changes, or use another encoding, get the whole
+	/// text either way. Each server's version is incremented as the
+	/// notification is sent, so the versions it sees follow the order of
+	/// calls.
+	///
+	/// Returns `false` if the document has not been opened with any server
+	/// yet, or a running server of the language does not have it open, such
+	/// as one restarted after a crash; [`open_document`](Self::open_document)
+	/// sends those servers its text then.
+	pub fn try_notify_change(
+		&self,
+		path: &Path,
+		language: &str,
+		text: &Rope,
+		changes: Option<(Vec<LspDocumentChange>, OffsetEncoding)>,
+	) -> Result<bool> {
 		let uri = crate::uri_from_path(path)
 			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
 
 		if !self.documents.is_opened(&uri) {
-			self.open_document(path, language, text).await?;
-			return Ok(());
+			return Ok(false);
 		}
 
-		let version = self
-			.documents
-			.increment_version(&uri)
-			.ok_or_else(|| crate::Error::Protocol("Document not registered".into()))?;
-
-		let content_changes: Vec<TextDocumentContentChangeEvent> = changes
-			.into_iter()
-			.map(|change| TextDocumentContentChangeEvent {
-				range: Some(base_range_to_lsp(change.range)),
-				range_length: None,
-				text: change.new_text,
-			})
-			.collect();
-
-		if let Some(client) = self.registry.get(language, path) {
-			client.text_document_did_change(uri, version, content_changes)?;
+		let content_changes = changes.map(|(changes, encoding)| {
+			let events: Vec<TextDocumentContentChangeEvent> = changes
+				.into_iter()
+				.map(|change| TextDocumentContentChangeEvent {
+					range: Some(base_range_to_lsp(change.range)),
+					range_length: None,
+					text: change.new_text,
+				})
+				.collect();
+			(events, encoding)
+		});
+
+		let mut all_opened = true;
+		for client in self.registry.get_all_for(language, path) {
+			if !self.documents.is_opened_with(&uri, client.id()) {
+				all_opened = false;
+				continue;
+			}
+			let version = self
+				.documents
+				.increment_version(&uri, client.id())
+				.ok_or_else(|| crate::Error::Protocol("Document not registered".into()))?;
+			match &content_changes {
+				Some((events, encoding))
+					if client.supports_incremental_sync()
+						&& client.offset_encoding() == *encoding =>
+				{
+					client.text_document_did_change(uri.clone(), version, events.clone())?;
+				}
+				_ => {
+					client.text_document_did_change_full(uri.clone(), version, text.to_string())?
+				}
+			}
 		}
 
-		Ok(())
+		Ok(all_opened)
 	}
 
-	/// Notify language servers that a document will be saved.
+	/// Get the running clients of `language` a document is open with, in
+	/// configuration order.
+	fn opened_clients(&self, uri: &Uri, language: &str, path: &Path) -> Vec<ClientHandle> {
+		self.registry
+			.get_all_for(language, path)
+			.into_iter()
+			.filter(|client| self.documents.is_opened_with(uri, client.id()))
+			.collect()
+	}
+
+	/// Notify the language servers that asked for it that a document will
+	/// be saved.
 	pub fn notify_will_save(&self, path: &Path, language: &str) -> Result<()> {
 		let uri = crate::uri_from_path(path)
 			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
 
-		if let Some(client) = self.registry.get(language, path) {
-			client.text_document_will_save(uri, TextDocumentSaveReason::MANUAL)?;
+		for client in self.opened_clients(&uri, language, path) {
+			if !client.supports_will_save() {
+				continue;
+			}
+			client.text_document_will_save(uri.clone(), TextDocumentSaveReason::MANUAL)?;
 		}
 
 		Ok(())
@@ -258,30 +311,17 @@ impl DocumentSync {
 
 	/// Notify language servers that a document was saved.
 	///
-	/// # Arguments
-	///
-	/// * `path` - Path to the file
-	/// * `language` - Language ID
-	/// * `include_text` - Whether to include the document text (some servers need it)
-	/// * `text` - Document content (only sent if `include_text` is true)
-	pub fn notify_did_save(
-		&self,
-		path: &Path,
-		language: &str,
-		include_text: bool,
-		text: Option<&Rope>,
-	) -> Result<()> {
+	/// The text goes only to the servers that declared they want it.
+	pub fn notify_did_save(&self, path: &Path, language: &str, text: &Rope) -> Result<()> {
 		let uri = crate::uri_from_path(path)
 			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
 
-		let text_content = if include_text {
-			text.map(|t| t.to_string())
-		} else {
-			None
-		};
-
-		if let Some(client) = self.registry.get(language, path) {
-			client.text_document_did_save(uri, text_content)?;
+		let mut text_content = None;
+		for client in self.opened_clients(&uri, language, path) {
+			let text = client
+				.save_include_text()
+				.then(|| text_content.get_or_insert_with(|| text.to_string()).clone());
+			client.text_document_did_save(uri.clone(), text)?;
 		}
 
 		Ok(())
@@ -289,23 +329,23 @@ impl DocumentSync {
 
 	/// Close a document with language servers.
 	///
-	/// This sends `textDocument/didClose` and removes the document from tracking.
+	/// This sends `textDocument/didClose` to every server the document is
+	/// open with and removes the document from tracking.
 	pub fn close_document(&self, path: &Path, language: &str) -> Result<()> {
 		let uri = crate::uri_from_path(path)
 			.ok_or_else(|| crate::Error::Protocol("Invalid path".into()))?;
 
-		if self.documents.is_opened(&uri)
-			&& let Some(client) = self.registry.get(language, path)
-		{
-			client.text_document_did_close(uri.clone())?;
-		}
+		let result = self
+			.opened_clients(&uri, language, path)
+			.into_iter()
+			.try_for_each(|client| client.text_document_did_close(uri.clone()));
 
 		self.documents.unregister(&uri);
 
-		Ok(())
+		result
 	}
 
-	/// Get diagnostics for a document.
+	/// Get the diagnostics of every server for a document, merged.
 	pub fn get_diagnostics(&self, path: &Path) -> Vec<lsp_types::Diagnostic> {
 		if let Some(uri) = crate::uri_from_path(path) {
 			self.documents.get_diagnostics(&uri)
@@ -314,6 +354,61 @@ impl DocumentSync {
 		}
 	}
 
+	/// Get the diagnostics of every server for a document, each with the
+	/// offset encoding of its positions.
+	///
+	/// See [`tag_diagnostics`](Self::tag_diagnostics).
+	pub fn diagnostics_with_encoding(
+		&self,
+		path: &Path,
+	) -> Vec<(lsp_types::Diagnostic, OffsetEncoding)> {
+		crate::uri_from_path(path)
+			.map(|uri| self.tag_diagnostics(self.documents.get_diagnostics_by_server(&uri)))
+			.unwrap_or_default()
+	}
+
+	/// Get the diagnostics of every document that has any, each with the
+	/// offset encoding of its positions.
+	///
+	/// See [`tag_diagnostics`](Self::tag_diagnostics).
+	pub fn all_diagnostics_with_encoding(
+		&self,
+	) -> Vec<(Uri, Vec<(lsp_types::Diagnostic, OffsetEncoding)>)> {
+		self.documents
+			.all_diagnostics()
+			.into_iter()
+			.map(|(uri, by_server)| (uri, self.tag_diagnostics(by_server)))
+			.collect()
+	}
+
+	/// Merges the diagnostics of several servers, pairing each with the
+	/// offset encoding of the server that published it.
+	///
+	/// Diagnostics published without a `source` are tagged with the name of
+	/// their server. Those of a server that is gone keep no tag and are
+	/// taken to be in UTF-16, the LSP default.
+	pub fn tag_diagnostics(
+		&self,
+		by_server: Vec<(LanguageServerId, Vec<lsp_types::Diagnostic>)>,
+	) -> Vec<(lsp_types::Diagnostic, OffsetEncoding)> {
+		let mut tagged = Vec::new();
+		for (server_id, diagnostics) in by_server {
+			let client = self.registry.get_by_id(server_id);
+			let encoding = client
+				.as_ref()
+				.map(|c| c.offset_encoding())
+				.unwrap_or_default();
+			let name = client.map(|c| c.name().to_string());
+			tagged.extend(diagnostics.into_iter().map(|mut diagnostic| {
+				if diagnostic.source.is_none() {
+					diagnostic.source = name.clone();
+				}
+				(diagnostic, encoding)
+			}));
+		}
+		tagged
+	}
+
 	/// Get error count for a document.
 	pub fn error_count(&self, path: &Path) -> usize {
 		if let Some(uri) = crate::uri_from_path(path) {
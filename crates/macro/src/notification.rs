@@ -56,6 +56,7 @@ pub fn register_notification(input: TokenStream) -> TokenStream {
 	let mut dismiss = quote! { xeno_registry::notifications::AutoDismiss::default() };
 	let mut icon = quote! { None };
 	let mut animation = quote! { xeno_registry::notifications::Animation::Fade };
+	let mut dedupe = quote! { true };
 	let mut timing = quote! {
 		(
 			xeno_registry::notifications::Timing::Fixed(::std::time::Duration::from_millis(200)),
@@ -73,6 +74,7 @@ pub fn register_notification(input: TokenStream) -> TokenStream {
 			"icon" => icon = quote! { Some(#val) },
 			"animation" => animation = quote! { #val },
 			"timing" => timing = quote! { #val },
+			"dedupe" => dedupe = quote! { #val },
 			_ => {
 				return syn::Error::new(name.span(), "Unknown notification field")
 					.to_compile_error()
@@ -96,6 +98,7 @@ pub fn register_notification(input: TokenStream) -> TokenStream {
 				auto_dismiss: #dismiss,
 				animation: #animation,
 				timing: #timing,
+				dedupe: #dedupe,
 				priority: 0,
 				source: xeno_registry::RegistrySource::Crate(env!("CARGO_PKG_NAME")),
 			};
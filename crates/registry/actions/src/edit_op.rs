@@ -121,6 +121,10 @@ pub enum SelectionOp {
 	ExpandToFullLines,
 	/// Select the character before cursor (for backspace).
 	SelectCharBefore,
+	/// Select from each cursor back to the previous word start (for ctrl-w).
+	SelectWordBefore,
+	/// Select from each cursor back to the start of its line (for ctrl-u).
+	SelectToLineStart,
 	/// Select from current position to next line start (for join lines).
 	SelectToNextLineStart,
 	/// Position cursor after current position (for paste after).
@@ -142,6 +146,12 @@ pub enum TextTransform {
 	Replace(String),
 	/// Insert text at cursor (selection becomes empty, text inserted).
 	Insert(String),
+	/// Insert the contents of the named register at each cursor.
+	InsertRegister(char),
+	/// Insert a line break at each cursor, applying autoindent.
+	InsertNewline,
+	/// Open a line above each cursor's line, applying autoindent.
+	OpenLineAbove,
 	/// Apply character mapping (case conversion).
 	MapChars(CharMapKind),
 	/// Replace each character in selection with the given char (vim's r).
@@ -309,20 +319,42 @@ pub fn delete_back() -> EditOp {
 		.with_transform(TextTransform::Delete)
 }
 
+/// Creates a delete-word-backward operation (insert mode ctrl-w).
+pub fn delete_word_backward() -> EditOp {
+	EditOp::new()
+		.with_selection(SelectionOp::SelectWordBefore)
+		.with_transform(TextTransform::Delete)
+}
+
+/// Creates a delete-to-line-start operation (insert mode ctrl-u).
+pub fn delete_to_line_start() -> EditOp {
+	EditOp::new()
+		.with_selection(SelectionOp::SelectToLineStart)
+		.with_transform(TextTransform::Delete)
+}
+
+/// Creates an insert-register operation (insert mode ctrl-r).
+pub fn insert_register(register: char) -> EditOp {
+	EditOp::new().with_transform(TextTransform::InsertRegister(register))
+}
+
+/// Creates an insert-literal operation (insert mode ctrl-v).
+pub fn insert_literal(ch: char) -> EditOp {
+	EditOp::new().with_transform(TextTransform::Insert(ch.to_string()))
+}
+
 /// Creates an open-below operation (new line below, enter insert).
 pub fn open_below() -> EditOp {
 	EditOp::new()
 		.with_selection(SelectionOp::ToLineEnd)
-		.with_transform(TextTransform::Insert("\n".to_string()))
+		.with_transform(TextTransform::InsertNewline)
 		.with_post(PostEffect::SetMode(Mode::Insert))
 }
 
 /// Creates an open-above operation (new line above, enter insert).
 pub fn open_above() -> EditOp {
 	EditOp::new()
-		.with_selection(SelectionOp::ToLineStart)
-		.with_transform(TextTransform::Insert("\n".to_string()))
-		.with_post(PostEffect::MoveCursor(CursorAdjust::Up(1)))
+		.with_transform(TextTransform::OpenLineAbove)
 		.with_post(PostEffect::SetMode(Mode::Insert))
 }
 
@@ -370,7 +402,7 @@ mod tests {
 	fn test_open_below_composition() {
 		let op = open_below();
 		assert!(matches!(op.selection, SelectionOp::ToLineEnd));
-		assert!(matches!(op.transform, TextTransform::Insert(_)));
+		assert!(matches!(op.transform, TextTransform::InsertNewline));
 		assert!(op.post.contains(&PostEffect::SetMode(Mode::Insert)));
 	}
 
@@ -31,6 +31,8 @@
 //!
 //! [`EditorCapabilities`]: super::EditorCapabilities
 
+use std::time::SystemTime;
+
 use ropey::RopeSlice;
 use xeno_base::direction::{Axis, SeqDirection, SpatialDirection};
 use xeno_base::range::{CharIdx, Direction};
@@ -38,7 +40,7 @@ use xeno_base::selection::Selection;
 use xeno_registry_notifications::Notification;
 use xeno_registry_options::{FromOptionValue, OptionKey, OptionValue, TypedOptionKey};
 
-use crate::Mode;
+use crate::{Mode, PendingKind};
 
 /// Cursor position access (required).
 ///
@@ -86,6 +88,12 @@ pub trait ModeAccess {
 	fn mode(&self) -> Mode;
 	/// Changes the editor mode.
 	fn set_mode(&mut self, mode: Mode);
+
+	/// Waits for the character a pending action needs, showing `prompt`.
+	fn set_pending(&mut self, kind: PendingKind, prompt: &str) {
+		let _ = prompt;
+		self.set_mode(Mode::PendingAction(kind));
+	}
 }
 
 /// Type-safe notification display (required).
@@ -129,17 +137,39 @@ pub trait SearchAccess {
 	/// - `add_selection`: if true, adds match to selections instead of replacing
 	/// - `extend`: if true, extends the current selection to include the match
 	fn search(&mut self, direction: SeqDirection, add_selection: bool, extend: bool) -> bool;
+	/// Opens the incremental search prompt, searching backward if `reverse`.
+	fn open_prompt(&mut self, reverse: bool);
 	/// Uses the current selection text as the search pattern.
 	fn use_selection_as_pattern(&mut self) -> bool;
 	/// Returns the current search pattern, if any.
 	fn pattern(&self) -> Option<&str>;
 	/// Sets the search pattern.
 	fn set_pattern(&mut self, pattern: &str);
+	/// Hides match highlighting until the next search.
+	fn clear_highlight(&mut self);
+}
+
+/// Summary of a single undo tree node, for visualizing history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoNodeInfo {
+	/// Chronological index of the node. Node 0 is the original document state.
+	pub id: usize,
+	/// Parent node, or `None` for the root.
+	pub parent: Option<usize>,
+	/// When this state was last edited.
+	pub timestamp: SystemTime,
+	/// Whether the document is currently at this state.
+	pub current: bool,
+	/// Characters inserted by the edit leading to this state.
+	pub added: usize,
+	/// Characters deleted by the edit leading to this state.
+	pub removed: usize,
 }
 
 /// Undo/redo operations (optional).
 ///
-/// Provides access to the buffer's history stack for undoing and redoing changes.
+/// Provides access to the buffer's undo tree for undoing and redoing changes.
+/// Undo walks toward the root of the tree; redo follows the most recent child.
 pub trait UndoAccess {
 	/// Saves the current state to the undo stack.
 	fn save_state(&mut self);
@@ -147,16 +177,26 @@ pub trait UndoAccess {
 	fn undo(&mut self);
 	/// Redoes the last undone change.
 	fn redo(&mut self);
+	/// Opens an undo group so subsequent edits undo as a single step.
+	fn begin_undo_group(&mut self);
+	/// Closes the open undo group, if any.
+	fn end_undo_group(&mut self);
 	/// Returns true if undo is available.
 	fn can_undo(&self) -> bool;
 	/// Returns true if redo is available.
 	fn can_redo(&self) -> bool;
+	/// Returns every node of the undo tree in chronological order.
+	fn undo_tree(&self) -> Vec<UndoNodeInfo>;
+	/// Moves the document to node `id` of the undo tree, crossing branches
+	/// as needed.
+	fn goto_undo_node(&mut self, id: usize);
 }
 
-/// Jump list operations.
+/// Jump list and selection mark operations.
 ///
 /// Provides navigation through the jump history. Jumps are saved automatically
-/// when making large cursor movements (e.g., searches, goto line).
+/// when making large cursor movements (e.g., searches, goto line). Marks save
+/// whole multi-range selections and follow later edits to the document.
 pub trait JumpAccess {
 	/// Jumps forward in the jump list.
 	fn jump_forward(&mut self) -> bool;
@@ -164,6 +204,14 @@ pub trait JumpAccess {
 	fn jump_backward(&mut self) -> bool;
 	/// Saves the current position to the jump list.
 	fn save_jump(&mut self);
+	/// Saves the current selection to a mark slot (`None` for the default slot).
+	fn save_selection(&mut self, slot: Option<char>);
+	/// Restores the selection saved in a mark slot. Returns false if it is empty.
+	fn restore_selection(&mut self, slot: Option<char>) -> bool;
+	/// Restores the selection replaced by the last search or regex selection.
+	///
+	/// The current selection takes its place, so repeating toggles between them.
+	fn reselect_last(&mut self) -> bool;
 }
 
 /// Macro recording/playback.
@@ -201,6 +249,31 @@ pub trait EditAccess {
 	/// - `extend`: If true, extends selection rather than moving
 	fn move_visual_vertical(&mut self, direction: Direction, count: usize, extend: bool);
 
+	/// Scrolls the view, moving the cursor along with it.
+	///
+	/// Page scrolls keep the cursor inside the `scrolloff` margins.
+	///
+	/// - `direction`: Forward for down, Backward for up
+	/// - `amount`: Number of visual lines or page fraction to scroll
+	/// - `extend`: If true, extends selection rather than moving
+	fn scroll(&mut self, direction: Direction, amount: crate::ScrollAmount, extend: bool);
+
+	/// Scrolls the view so the cursor line sits at `position` without
+	/// moving the cursor.
+	///
+	/// - `count`: 1-based row from the top or bottom edge
+	fn align_view(&mut self, position: crate::ScreenPosition, count: usize);
+
+	/// Scrolls the view sideways by `count` columns while `wrap` is off.
+	///
+	/// - `direction`: Forward for right, Backward for left
+	fn scroll_horizontal(&mut self, direction: Direction, count: usize);
+
+	/// Starts or ends a rectangular (block) selection at the primary cursor.
+	///
+	/// While active, vertical motions grow the block by one range per line.
+	fn toggle_block_selection(&mut self);
+
 	/// Pastes from the yank register.
 	///
 	/// - `before`: If true, pastes before cursor; otherwise after
@@ -265,6 +338,13 @@ pub trait SplitOps {
 	/// Split along the given axis. See trait docs for axis semantics.
 	fn split(&mut self, axis: Axis);
 
+	/// Grow the current split by `delta` cells, or shrink it when negative,
+	/// by moving the nearest dividers along `axis`.
+	fn resize_split(&mut self, axis: Axis, delta: i32);
+
+	/// Share space equally between all splits.
+	fn equalize_splits(&mut self);
+
 	/// Close the current split.
 	fn close_split(&mut self);
 
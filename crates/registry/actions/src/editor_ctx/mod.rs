@@ -31,7 +31,7 @@ pub use handlers::*;
 use xeno_base::range::CharIdx;
 use xeno_base::selection::Selection;
 
-use crate::{Capability, CommandError, Mode};
+use crate::{Capability, CommandError, Mode, PendingKind};
 
 /// Context for applying action results to editor state.
 ///
@@ -110,6 +110,11 @@ impl<'a> EditorContext<'a> {
 		self.inner.set_mode(mode);
 	}
 
+	/// Enters a pending action that shows `prompt` until it gets its key.
+	pub fn set_pending(&mut self, kind: PendingKind, prompt: &str) {
+		self.inner.set_pending(kind, prompt);
+	}
+
 	/// Returns search access if the capability is available.
 	pub fn search(&mut self) -> Option<&mut dyn SearchAccess> {
 		self.inner.search()
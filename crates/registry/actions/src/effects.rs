@@ -152,6 +152,18 @@ impl ActionEffects {
 		})
 	}
 
+	/// Scrolls an unwrapped viewport horizontally.
+	#[inline]
+	pub fn scroll_horizontal(direction: Direction, count: usize) -> Self {
+		Self::from_effect(Effect::ScrollHorizontal { direction, count })
+	}
+
+	/// Scrolls the viewport to put the cursor line at a screen position.
+	#[inline]
+	pub fn align_view(position: ScreenPosition, count: usize) -> Self {
+		Self::from_effect(Effect::AlignView { position, count })
+	}
+
 	/// Moves cursor visually (wrapped lines).
 	#[inline]
 	pub fn visual_move(direction: Direction, count: usize, extend: bool) -> Self {
@@ -218,14 +230,15 @@ impl<'a> IntoIterator for &'a ActionEffects {
 ///
 /// # Categories
 ///
-/// - **Cursor/Selection**: `SetCursor`, `SetSelection`, `ScreenMotion`
+/// - **Cursor/Selection**: `SetCursor`, `SetSelection`, `ToggleBlockSelection`, `ScreenMotion`
+/// - **Marks**: `SaveSelection`, `RestoreSelection`, `ReselectLast`
 /// - **Mode**: `SetMode`, `Pending`
 /// - **Text**: `EditOp`, `Paste`
-/// - **Viewport**: `Scroll`, `VisualMove`
-/// - **Navigation**: `FocusBuffer`, `FocusSplit`, `Split`, `CloseSplit`
+/// - **Viewport**: `Scroll`, `ScrollHorizontal`, `AlignView`, `VisualMove`
+/// - **Navigation**: `FocusBuffer`, `FocusSplit`, `Split`, `ResizeSplit`, `EqualizeSplits`, `CloseSplit`
 /// - **UI**: `Notify`, `OpenPalette`, `ClosePalette`, `ExecutePalette`
 /// - **Lifecycle**: `Quit`, `ForceRedraw`
-/// - **Search**: `Search`, `UseSelectionAsSearch`
+/// - **Search**: `Search`, `OpenSearchPrompt`, `UseSelectionAsSearch`, `ClearSearchHighlight`
 /// - **Deferred**: `QueueCommand`
 #[derive(Debug, Clone)]
 pub enum Effect {
@@ -235,6 +248,18 @@ pub enum Effect {
 	/// Set selection (includes cursor at primary head).
 	SetSelection(Selection),
 
+	/// Toggle rectangular (block) selection mode.
+	ToggleBlockSelection,
+
+	/// Save the selection to a mark slot (`None` for the default `^` slot).
+	SaveSelection(Option<char>),
+
+	/// Restore the selection saved in a mark slot (`None` for the default `^` slot).
+	RestoreSelection(Option<char>),
+
+	/// Restore the selection replaced by the last search or regex selection.
+	ReselectLast,
+
 	/// Move cursor to screen-relative position (H/M/L).
 	ScreenMotion {
 		/// Screen-relative position.
@@ -265,6 +290,23 @@ pub enum Effect {
 		extend: bool,
 	},
 
+	/// Scroll an unwrapped viewport sideways without moving the cursor.
+	ScrollHorizontal {
+		/// Direction to scroll (Forward = right, Backward = left).
+		direction: Direction,
+		/// Number of columns to scroll.
+		count: usize,
+	},
+
+	/// Scroll the viewport so the cursor line sits at a screen position
+	/// (zt/zz/zb), leaving the cursor in place.
+	AlignView {
+		/// Screen position for the cursor line.
+		position: ScreenPosition,
+		/// 1-based offset from the target edge.
+		count: usize,
+	},
+
 	/// Move cursor visually (wrapped lines).
 	VisualMove {
 		/// Direction to move (Forward = down, Backward = up).
@@ -290,6 +332,20 @@ pub enum Effect {
 	/// Create a new split.
 	Split(Axis),
 
+	/// Grow the focused split by `delta` cells, or shrink it when negative.
+	///
+	/// `Axis::Horizontal` moves horizontal dividers (height);
+	/// `Axis::Vertical` moves vertical dividers (width).
+	ResizeSplit {
+		/// Dividers to move.
+		axis: Axis,
+		/// Number of cells to grow by.
+		delta: i32,
+	},
+
+	/// Share space equally between all splits.
+	EqualizeSplits,
+
 	/// Close current split.
 	CloseSplit,
 
@@ -322,9 +378,18 @@ pub enum Effect {
 		add_selection: bool,
 	},
 
+	/// Open the incremental search prompt.
+	OpenSearchPrompt {
+		/// Whether to search backward from the cursor.
+		reverse: bool,
+	},
+
 	/// Use current selection as search pattern.
 	UseSelectionAsSearch,
 
+	/// Hide search match highlighting until the next search.
+	ClearSearchHighlight,
+
 	/// Quit the editor.
 	Quit {
 		/// Whether to force quit without save prompts.
@@ -0,0 +1,36 @@
+//! Diagnostic navigation actions.
+
+use crate::{ActionContext, ActionResult, Effect, action};
+
+/// Queues a diagnostic jump command, passing on the action's count.
+fn jump(name: &'static str, ctx: &ActionContext, errors: bool) -> ActionResult {
+	let mut args = vec![ctx.count.to_string()];
+	if errors {
+		args.push("--errors".to_string());
+	}
+	ActionResult::Effects(Effect::QueueCommand { name, args }.into())
+}
+
+action!(goto_next_diagnostic, {
+	description: "Jump to the next diagnostic in the buffer",
+	short_desc: "Next diagnostic",
+	bindings: r#"normal "g ]""#,
+}, |ctx| jump("diagnostic_next", ctx, false));
+
+action!(goto_prev_diagnostic, {
+	description: "Jump to the previous diagnostic in the buffer",
+	short_desc: "Previous diagnostic",
+	bindings: r#"normal "g [""#,
+}, |ctx| jump("diagnostic_prev", ctx, false));
+
+action!(goto_next_error, {
+	description: "Jump to the next error in the buffer",
+	short_desc: "Next error",
+	bindings: r#"normal "g }""#,
+}, |ctx| jump("diagnostic_next", ctx, true));
+
+action!(goto_prev_error, {
+	description: "Jump to the previous error in the buffer",
+	short_desc: "Previous error",
+	bindings: r#"normal "g {""#,
+}, |ctx| jump("diagnostic_prev", ctx, true));
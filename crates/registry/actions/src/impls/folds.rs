@@ -0,0 +1,32 @@
+//! Folding actions.
+
+use crate::{ActionResult, Effect, action};
+
+/// Queues a fold command.
+fn fold(name: &'static str) -> ActionResult {
+	ActionResult::Effects(
+		Effect::QueueCommand {
+			name,
+			args: Vec::new(),
+		}
+		.into(),
+	)
+}
+
+action!(fold_toggle, {
+	description: "Open the fold under cursor, or close the innermost one around it",
+	short_desc: "Toggle fold",
+	bindings: r#"normal "z a""#,
+}, |_ctx| fold("fold_toggle"));
+
+action!(fold_close_all, {
+	description: "Close every fold in the buffer",
+	short_desc: "Close all folds",
+	bindings: r#"normal "z M""#,
+}, |_ctx| fold("fold_close_all"));
+
+action!(fold_open_all, {
+	description: "Open every fold in the buffer",
+	short_desc: "Open all folds",
+	bindings: r#"normal "z R""#,
+}, |_ctx| fold("fold_open_all"));
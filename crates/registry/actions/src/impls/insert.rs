@@ -2,7 +2,10 @@ use xeno_base::range::Range;
 use xeno_base::{Mode, Selection};
 use xeno_registry_motions::keys as motions;
 
-use crate::{ActionEffects, ActionResult, Effect, action, insert_with_motion};
+use crate::{
+	ActionEffects, ActionResult, Effect, PendingAction, PendingKind, action, edit_op,
+	insert_with_motion,
+};
 
 action!(insert_mode, { description: "Switch to insert mode", bindings: r#"normal "i""# }, |ctx| {
 	let ranges: Vec<_> = ctx.selection.ranges().iter()
@@ -26,3 +29,37 @@ action!(insert_after, { description: "Insert after cursor", bindings: r#"normal
 	let sel = Selection::from_vec(ranges, ctx.selection.primary_index());
 	ActionResult::Effects(ActionEffects::motion(sel).with(Effect::SetMode(Mode::Insert)))
 });
+
+action!(delete_word_backward, {
+	description: "Delete to previous word start",
+	bindings: r#"insert "ctrl-w""#,
+}, |_ctx| ActionResult::Effects(ActionEffects::edit_op(edit_op::delete_word_backward())));
+
+action!(delete_to_line_start, {
+	description: "Delete to start of line",
+	bindings: r#"insert "ctrl-u""#,
+}, |_ctx| ActionResult::Effects(ActionEffects::edit_op(edit_op::delete_to_line_start())));
+
+action!(insert_register, {
+	description: "Insert register contents",
+	bindings: r#"insert "ctrl-r""#,
+}, |ctx| match ctx.args.char {
+	Some(register) => {
+		ActionResult::Effects(ActionEffects::edit_op(edit_op::insert_register(register)))
+	}
+	None => ActionResult::Effects(ActionEffects::pending(PendingAction {
+		kind: PendingKind::InsertRegister,
+		prompt: "register".into(),
+	})),
+});
+
+action!(insert_unicode, {
+	description: "Insert next key literally or a unicode codepoint",
+	bindings: r#"insert "ctrl-v""#,
+}, |ctx| match ctx.args.char {
+	Some(ch) => ActionResult::Effects(ActionEffects::edit_op(edit_op::insert_literal(ch))),
+	None => ActionResult::Effects(ActionEffects::pending(PendingAction {
+		kind: PendingKind::InsertLiteral,
+		prompt: "literal".into(),
+	})),
+});
@@ -0,0 +1,27 @@
+//! Location list actions.
+
+use crate::{ActionResult, Effect, action};
+
+action!(next_location, {
+	description: "Jump to the next entry of the location list",
+	short_desc: "Next location",
+	bindings: r#"normal "g n""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "location_next",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(prev_location, {
+	description: "Jump to the previous entry of the location list",
+	short_desc: "Previous location",
+	bindings: r#"normal "g p""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "location_prev",
+		args: Vec::new(),
+	}
+	.into(),
+));
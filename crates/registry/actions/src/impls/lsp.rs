@@ -0,0 +1,110 @@
+//! Language server actions.
+
+use crate::{ActionResult, Effect, action};
+
+action!(rename_symbol, {
+	description: "Rename symbol under cursor",
+	short_desc: "Rename",
+	bindings: r#"normal "alt-r""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "rename",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(format_buffer, {
+	description: "Format the buffer, or the selection, with its language server",
+	short_desc: "Format",
+	bindings: r#"normal "=""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "format",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(goto_definition, {
+	description: "Go to the definition of the symbol under cursor",
+	short_desc: "Definition",
+	bindings: r#"normal "g d""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "gd",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(goto_type_definition, {
+	description: "Go to the type definition of the symbol under cursor",
+	short_desc: "Type definition",
+	bindings: r#"normal "g y""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "goto_type_definition",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(goto_implementation, {
+	description: "Go to the implementations of the symbol under cursor",
+	short_desc: "Implementation",
+	bindings: r#"normal "g i""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "goto_implementation",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(goto_declaration, {
+	description: "Go to the declaration of the symbol under cursor",
+	short_desc: "Declaration",
+	bindings: r#"normal "g D""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "goto_declaration",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(code_action, {
+	description: "List code actions at the selection, with its diagnostics",
+	short_desc: "Code actions",
+	bindings: r#"normal "g a""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "code_action",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(workspace_symbols, {
+	description: "Pick a workspace symbol to jump to",
+	short_desc: "Workspace symbols",
+	bindings: r#"normal "g S""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "workspace_symbols",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(select_all_highlights, {
+	description: "Select every highlighted occurrence of the symbol under cursor",
+	short_desc: "Select highlights",
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "select_highlights",
+		args: Vec::new(),
+	}
+	.into(),
+));
@@ -10,3 +10,8 @@ action!(add_line_above, { description: "Add empty line above cursor" },
 
 action!(use_selection_as_search, { description: "Use current selection as search pattern" },
 	|_ctx| ActionResult::Effects(Effect::UseSelectionAsSearch.into()));
+
+action!(clear_search_highlight, {
+	description: "Clear search match highlighting",
+	bindings: r#"normal "ctrl-l""#,
+}, |_ctx| ActionResult::Effects(Effect::ClearSearchHighlight.into()));
@@ -1,9 +1,17 @@
+/// Diagnostic navigation actions.
+pub(crate) mod diagnostics;
 /// Text editing actions (delete, change, yank).
 pub(crate) mod editing;
 /// Search and find actions.
 pub(crate) mod find;
+/// Folding actions.
+pub(crate) mod folds;
 /// Insert mode text entry actions.
 pub(crate) mod insert;
+/// Location list actions.
+pub(crate) mod locations;
+/// Language server actions.
+pub(crate) mod lsp;
 /// Miscellaneous utility actions.
 pub(crate) mod misc;
 /// Mode switching actions.
@@ -12,6 +20,8 @@ pub(crate) mod modes;
 pub(crate) mod motions;
 /// Command palette actions.
 pub mod palette;
+/// Info popup actions.
+pub(crate) mod popup;
 /// Key sequence prefix descriptions for which-key HUD.
 pub(crate) mod prefixes;
 /// Viewport scrolling actions.
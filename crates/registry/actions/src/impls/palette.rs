@@ -6,3 +6,13 @@ action!(open_palette, {
 	description: "Open command palette",
 	bindings: r#"normal ":""#,
 }, |_ctx| ActionResult::Effects(Effect::OpenPalette.into()));
+
+action!(search_prompt, {
+	description: "Search forward, previewing matches while typing",
+	bindings: r#"normal "/""#,
+}, |_ctx| ActionResult::Effects(Effect::OpenSearchPrompt { reverse: false }.into()));
+
+action!(search_prompt_reverse, {
+	description: "Search backward, previewing matches while typing",
+	bindings: r#"normal "?""#,
+}, |_ctx| ActionResult::Effects(Effect::OpenSearchPrompt { reverse: true }.into()));
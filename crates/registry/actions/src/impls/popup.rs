@@ -0,0 +1,37 @@
+//! Info popup actions.
+//!
+//! Bindings live under `ctrl-w p`:
+//! - `p` - Pin or unpin the focused or latest popup
+//! - `n` - Focus the next popup, then the document
+//! - `d` - Dismiss all popups, pinned or not
+
+use crate::{ActionResult, Effect, action};
+
+action!(toggle_pin_popup, {
+	description: "Pin or unpin popup",
+	short_desc: "Pin",
+	bindings: r#"normal "ctrl-w p p""#,
+}, |_ctx| queue("pin_popup"));
+
+action!(focus_next_popup, {
+	description: "Focus next popup",
+	short_desc: "Next",
+	bindings: r#"normal "ctrl-w p n""#,
+}, |_ctx| queue("next_popup"));
+
+action!(dismiss_all_popups, {
+	description: "Dismiss all popups",
+	short_desc: "Dismiss all",
+	bindings: r#"normal "ctrl-w p d""#,
+}, |_ctx| queue("close_popups"));
+
+/// Queues the popup command `name`, which needs full editor access.
+fn queue(name: &'static str) -> ActionResult {
+	ActionResult::Effects(
+		Effect::QueueCommand {
+			name,
+			args: Vec::new(),
+		}
+		.into(),
+	)
+}
@@ -8,6 +8,7 @@ use crate::key_prefix;
 key_prefix!(normal "g" => "Goto");
 key_prefix!(normal "z" => "View");
 key_prefix!(normal "ctrl-w" => "Window");
-key_prefix!(normal "ctrl-w s" as ctrl_w_s => "Split");
 key_prefix!(normal "ctrl-w f" as ctrl_w_f => "Focus");
 key_prefix!(normal "ctrl-w c" as ctrl_w_c => "Close");
+key_prefix!(normal "ctrl-w p" as ctrl_w_p => "Popup");
+key_prefix!(normal "ctrl-w d" as ctrl_w_d => "Dock");
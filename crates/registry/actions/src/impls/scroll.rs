@@ -2,7 +2,7 @@
 
 use xeno_base::range::Direction;
 
-use crate::{ActionEffects, ActionResult, ScrollAmount, action};
+use crate::{ActionEffects, ActionResult, Effect, ScreenPosition, ScrollAmount, action};
 
 action!(scroll_up, {
 	description: "View scroll up",
@@ -28,24 +28,65 @@ action!(scroll_down, {
 	))
 });
 
-action!(scroll_half_page_up, {
-	description: "Scroll half page up",
-	bindings: r#"normal "ctrl-u""#,
-}, |ctx| ActionResult::Effects(ActionEffects::scroll(
+action!(cursor_line_to_top, {
+	description: "Scroll the cursor line to the top of the view",
+	short_desc: "Line to top",
+	bindings: r#"normal "z t""#,
+}, |ctx| ActionResult::Effects(ActionEffects::align_view(ScreenPosition::Top, ctx.count)));
+
+action!(center_cursor_line, {
+	description: "Scroll the cursor line to the middle of the view",
+	short_desc: "Center line",
+	bindings: r#"normal "z z""#,
+}, |ctx| ActionResult::Effects(ActionEffects::align_view(ScreenPosition::Middle, ctx.count)));
+
+action!(cursor_line_to_bottom, {
+	description: "Scroll the cursor line to the bottom of the view",
+	short_desc: "Line to bottom",
+	bindings: r#"normal "z b""#,
+}, |ctx| ActionResult::Effects(ActionEffects::align_view(ScreenPosition::Bottom, ctx.count)));
+
+action!(scroll_left, {
+	description: "View scroll left (wrap off)",
+	short_desc: "Scroll left",
+	bindings: r#"normal "z h""#,
+}, |ctx| ActionResult::Effects(ActionEffects::scroll_horizontal(
 	Direction::Backward,
-	ScrollAmount::HalfPage,
-	ctx.extend,
+	ctx.count,
 )));
 
-action!(scroll_half_page_down, {
-	description: "Scroll half page down",
-	bindings: r#"normal "ctrl-d""#,
-}, |ctx| ActionResult::Effects(ActionEffects::scroll(
+action!(scroll_right, {
+	description: "View scroll right (wrap off)",
+	short_desc: "Scroll right",
+	bindings: r#"normal "z l""#,
+}, |ctx| ActionResult::Effects(ActionEffects::scroll_horizontal(
 	Direction::Forward,
-	ScrollAmount::HalfPage,
-	ctx.extend,
+	ctx.count,
 )));
 
+action!(scroll_up_half, {
+	description: "Scroll half page up",
+	bindings: r#"normal "ctrl-u""#,
+}, |ctx| half_page(Direction::Backward, ctx.count, ctx.extend));
+
+action!(scroll_down_half, {
+	description: "Scroll half page down",
+	bindings: r#"normal "ctrl-d""#,
+}, |ctx| half_page(Direction::Forward, ctx.count, ctx.extend));
+
+/// Scrolls `count` half pages, moving the cursors by as many visual rows.
+fn half_page(direction: Direction, count: usize, extend: bool) -> ActionResult {
+	let mut effects = ActionEffects::new();
+	for _ in 0..count.max(1) {
+		effects.push(Effect::Scroll {
+			direction,
+			amount: ScrollAmount::HalfPage,
+			extend,
+		});
+	}
+	ActionResult::Effects(effects)
+}
+
 action!(scroll_page_up, {
 	description: "Scroll page up",
 	bindings: r#"normal "pageup" "ctrl-b"
@@ -1,38 +1,85 @@
+use xeno_base::range::{Direction, Range};
 use xeno_base::selection::Selection;
 
-use crate::{ActionContext, ActionEffects, ActionResult, action};
+use crate::{ActionContext, ActionEffects, ActionResult, Effect, action};
 
-action!(collapse_selection, {
-	description: "Collapse selection to cursor",
+action!(collapse_selections, {
+	description: "Collapse each selection to its cursor",
 	bindings: r#"normal ";" "esc""#,
 }, |ctx| {
-	let mut new_sel = ctx.selection.clone();
-	new_sel.transform_mut(|r| r.anchor = r.head);
+	let new_sel = ctx.selection.transform(|r| Range::point(r.head));
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 });
 
-action!(flip_selection, {
-	description: "Flip selection direction",
+action!(flip_selections, {
+	description: "Swap anchor and cursor of each selection",
 	bindings: r#"normal "alt-;""#,
 }, |ctx| {
-	let mut new_sel = ctx.selection.clone();
-	new_sel.transform_mut(|r| std::mem::swap(&mut r.anchor, &mut r.head));
+	let new_sel = ctx.selection.transform(|r| r.flip());
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 });
 
-action!(ensure_forward, {
-	description: "Ensure selection is forward",
+action!(ensure_selections_forward, {
+	description: "Make each selection face forward",
 	bindings: r#"normal "alt-:""#,
 }, |ctx| {
-	let mut new_sel = ctx.selection.clone();
-	new_sel.transform_mut(|r| {
-		if r.head < r.anchor {
-			std::mem::swap(&mut r.anchor, &mut r.head);
-		}
-	});
+	let new_sel = ctx
+		.selection
+		.transform(|r| r.with_direction(Direction::Forward));
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 });
 
+action!(trim_selections, {
+	description: "Trim whitespace from the ends of each selection",
+	bindings: r#"normal "_""#,
+}, handler: trim_selections_impl);
+
+/// Shrinks each selection past leading and trailing whitespace, keeping its
+/// direction. Selections made only of whitespace are dropped, unless that
+/// would leave none, in which case the primary is kept as is.
+fn trim_selections_impl(ctx: &ActionContext) -> ActionResult {
+	let text = &ctx.text;
+	let primary_index = ctx.selection.primary_index();
+	let mut new_ranges = Vec::new();
+	let mut new_primary = None;
+
+	for (idx, range) in ctx.selection.ranges().iter().enumerate() {
+		let (mut from, mut to) = (range.min(), range.max());
+		if from < to {
+			while from < to && text.char(from).is_whitespace() {
+				from += 1;
+			}
+			while to > from && text.char(to - 1).is_whitespace() {
+				to -= 1;
+			}
+			if from == to {
+				continue;
+			}
+		}
+
+		// A dropped primary passes to the next surviving selection.
+		if idx >= primary_index && new_primary.is_none() {
+			new_primary = Some(new_ranges.len());
+		}
+		new_ranges.push(match range.direction() {
+			Direction::Forward => Range::new(from, to),
+			Direction::Backward => Range::new(to, from),
+		});
+	}
+
+	if new_ranges.is_empty() {
+		return ActionResult::Effects(ActionEffects::motion(Selection::single(
+			ctx.selection.primary().anchor,
+			ctx.selection.primary().head,
+		)));
+	}
+
+	let primary = new_primary.unwrap_or(new_ranges.len() - 1);
+	ActionResult::Effects(ActionEffects::motion(Selection::from_vec(
+		new_ranges, primary,
+	)))
+}
+
 action!(select_line, {
 	description: "Select current line",
 	bindings: r#"normal "x""#,
@@ -61,6 +108,26 @@ fn select_line_impl(ctx: &ActionContext) -> ActionResult {
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 }
 
+action!(select_block, {
+	description: "Toggle rectangular block selection",
+	bindings: r#"normal "ctrl-v""#,
+}, |_ctx| ActionResult::Effects(Effect::ToggleBlockSelection.into()));
+
+action!(save_selection, {
+	description: "Save selection to a mark register",
+	bindings: r#"normal "Z""#,
+}, |ctx| ActionResult::Effects(Effect::SaveSelection(ctx.register).into()));
+
+action!(restore_selection, {
+	description: "Restore selection from a mark register",
+	bindings: r#"normal "alt-z""#,
+}, |ctx| ActionResult::Effects(Effect::RestoreSelection(ctx.register).into()));
+
+action!(reselect_last, {
+	description: "Reselect the selection replaced by the last search",
+	bindings: r#"normal "g v""#,
+}, |_ctx| ActionResult::Effects(Effect::ReselectLast.into()));
+
 action!(select_all, {
 	description: "Select all text",
 	bindings: r#"normal "%""#,
@@ -76,16 +143,15 @@ action!(expand_to_line, {
 
 /// Expands each selection to cover complete lines.
 fn expand_to_line_impl(ctx: &ActionContext) -> ActionResult {
-	let mut new_sel = ctx.selection.clone();
-	new_sel.transform_mut(|r| {
+	let new_sel = ctx.selection.transform(|r| {
 		let start_line = ctx.text.char_to_line(r.min());
 		let end_line = ctx.text.char_to_line(r.max());
-		r.anchor = ctx.text.line_to_char(start_line);
-		r.head = if end_line + 1 < ctx.text.len_lines() {
+		let end = if end_line + 1 < ctx.text.len_lines() {
 			ctx.text.line_to_char(end_line + 1)
 		} else {
 			ctx.text.len_chars()
 		};
+		Range::new(ctx.text.line_to_char(start_line), end)
 	});
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 }
@@ -161,16 +227,21 @@ fn split_lines_impl(ctx: &ActionContext) -> ActionResult {
 			};
 
 			if line_start < line_end {
-				new_ranges.push(xeno_base::range::Range::new(line_start, line_end));
+				new_ranges.push(Range::new(line_start, line_end));
 			}
 		}
 	}
 
 	if new_ranges.is_empty() {
-		ActionResult::Effects(ActionEffects::ok())
-	} else {
-		ActionResult::Effects(ActionEffects::motion(Selection::from_vec(new_ranges, 0)))
+		return ActionResult::Effects(ActionEffects::ok());
+	}
+
+	// The first line of the old primary stays primary.
+	let mut new_sel = Selection::from_vec(new_ranges, 0);
+	if let Some(idx) = new_sel.range_at(ctx.selection.primary().min()) {
+		new_sel.set_primary(idx);
 	}
+	ActionResult::Effects(ActionEffects::motion(new_sel))
 }
 
 action!(duplicate_selections_down, {
@@ -199,7 +270,7 @@ fn duplicate_selections_down_impl(ctx: &ActionContext) -> ActionResult {
 
 		let new_anchor = line_col_to_char(text, target_anchor_line, anchor_col);
 		let new_head = line_col_to_char(text, target_head_line, head_col);
-		let new_range = xeno_base::range::Range::new(new_anchor, new_head);
+		let new_range = Range::new(new_anchor, new_head);
 
 		if !new_ranges.contains(&new_range) {
 			new_ranges.push(new_range);
@@ -242,7 +313,7 @@ fn duplicate_selections_up_impl(ctx: &ActionContext) -> ActionResult {
 
 		let new_anchor = line_col_to_char(text, target_anchor_line, anchor_col);
 		let new_head = line_col_to_char(text, target_head_line, head_col);
-		let new_range = xeno_base::range::Range::new(new_anchor, new_head);
+		let new_range = Range::new(new_anchor, new_head);
 
 		if !new_ranges.contains(&new_range) {
 			new_ranges.push(new_range);
@@ -274,8 +345,7 @@ action!(merge_selections, {
 	description: "Merge overlapping selections",
 	bindings: r#"normal "alt-+""#,
 }, |ctx| {
-	let mut new_sel = ctx.selection.clone();
-	new_sel.merge_overlaps_and_adjacent();
+	let new_sel = ctx.selection.clone().merge_overlapping();
 	ActionResult::Effects(ActionEffects::motion(new_sel))
 });
 
@@ -379,4 +449,121 @@ mod tests {
 			panic!("Expected Effects result");
 		}
 	}
+
+	#[test]
+	fn test_split_lines_keeps_primary() {
+		let text = Rope::from("aa\nbb\ncc\ndd\n");
+		let sel = Selection::new(Range::new(6, 12), vec![Range::new(0, 3)]);
+
+		let ctx = ActionContext {
+			text: text.slice(..),
+			cursor: 12,
+			selection: &sel,
+			count: 1,
+			extend: false,
+			register: None,
+			args: ActionArgs::default(),
+		};
+
+		let ActionResult::Effects(effects) = split_lines_impl(&ctx) else {
+			panic!("Expected Effects result");
+		};
+		let Some(crate::Effect::SetSelection(new_sel)) = effects.as_slice().first() else {
+			panic!("Expected SetSelection effect");
+		};
+		assert_eq!(
+			new_sel.ranges(),
+			&[Range::new(0, 3), Range::new(6, 9), Range::new(9, 12)]
+		);
+		assert_eq!(new_sel.primary(), Range::new(6, 9));
+	}
+
+	fn apply(
+		handler: fn(&ActionContext) -> ActionResult,
+		text: &Rope,
+		sel: &Selection,
+	) -> Selection {
+		let ctx = ActionContext {
+			text: text.slice(..),
+			cursor: sel.primary().head,
+			selection: sel,
+			count: 1,
+			extend: false,
+			register: None,
+			args: ActionArgs::default(),
+		};
+		let ActionResult::Effects(effects) = handler(&ctx) else {
+			panic!("Expected Effects result");
+		};
+		let Some(crate::Effect::SetSelection(new_sel)) = effects.as_slice().first() else {
+			panic!("Expected SetSelection effect");
+		};
+		new_sel.clone()
+	}
+
+	#[test]
+	fn test_collapse_selections_keeps_cursor_side() {
+		let text = Rope::from("one two three");
+		let sel = Selection::new(Range::new(7, 4), vec![Range::new(0, 3), Range::new(8, 13)]);
+
+		let new_sel = apply(handler_collapse_selections, &text, &sel);
+		assert_eq!(
+			new_sel.ranges(),
+			&[Range::point(3), Range::point(4), Range::point(13)]
+		);
+		assert_eq!(new_sel.primary(), Range::point(4));
+	}
+
+	#[test]
+	fn test_flip_selections_swaps_each_direction() {
+		let text = Rope::from("one two three");
+		let sel = Selection::new(Range::new(7, 4), vec![Range::new(0, 3), Range::new(8, 13)]);
+
+		let new_sel = apply(handler_flip_selections, &text, &sel);
+		assert_eq!(
+			new_sel.ranges(),
+			&[Range::new(3, 0), Range::new(4, 7), Range::new(13, 8)]
+		);
+		assert_eq!(new_sel.primary(), Range::new(4, 7));
+	}
+
+	#[test]
+	fn test_ensure_selections_forward() {
+		let text = Rope::from("one two three");
+		let sel = Selection::new(Range::new(7, 4), vec![Range::new(0, 3), Range::new(13, 8)]);
+
+		let new_sel = apply(handler_ensure_selections_forward, &text, &sel);
+		assert_eq!(
+			new_sel.ranges(),
+			&[Range::new(0, 3), Range::new(4, 7), Range::new(8, 13)]
+		);
+		assert_eq!(new_sel.primary(), Range::new(4, 7));
+	}
+
+	#[test]
+	fn test_trim_selections_keeps_direction() {
+		let text = Rope::from("  one  \n two\t\n");
+		let sel = Selection::new(Range::new(13, 7), vec![Range::new(0, 6)]);
+
+		let new_sel = apply(trim_selections_impl, &text, &sel);
+		assert_eq!(new_sel.ranges(), &[Range::new(2, 5), Range::new(12, 9)]);
+		assert_eq!(new_sel.primary(), Range::new(12, 9));
+	}
+
+	#[test]
+	fn test_trim_selections_drops_blank_ranges() {
+		let text = Rope::from("aa   bb \n\n cc");
+		let sel = Selection::new(
+			Range::new(10, 7),
+			vec![Range::new(0, 2), Range::new(10, 13)],
+		);
+
+		let new_sel = apply(trim_selections_impl, &text, &sel);
+		assert_eq!(new_sel.ranges(), &[Range::new(0, 2), Range::new(11, 13)]);
+		assert_eq!(new_sel.primary(), Range::new(11, 13));
+
+		let blank = Selection::single(7, 2);
+		let new_sel = apply(trim_selections_impl, &Rope::from("aa   \n\n\n"), &blank);
+		assert_eq!(new_sel.ranges(), &[Range::new(7, 2)]);
+	}
 }
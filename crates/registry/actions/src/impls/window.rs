@@ -4,11 +4,22 @@
 //! - `split_horizontal`: horizontal divider → windows stacked top/bottom
 //! - `split_vertical`: vertical divider → windows side-by-side left/right
 //!
-//! Bindings use hierarchical key sequences under `ctrl-w`:
-//! - `s h/v` - Split horizontal/vertical
-//! - `f h/j/k/l` - Focus directions
+//! Bindings follow Vim under `ctrl-w`, with hierarchical groups for the rest:
+//! - `s`/`v` - Split horizontal/vertical
+//! - `h/j/k/l` - Focus directions
+//! - `>`/`<` and `+`/`-` - Grow/shrink width and height by count
+//! - `=` - Equalize split sizes
+//! - `q` - Close current split
 //! - `f n/p` - Buffer next/previous
 //! - `c c/o` - Close current/others
+//! - `t` - Next panel tab of the dock
+//! - `o` - Float or dock the focused panel
+//! - `w` - Cycle focus through the editor and the panels
+//! - `d +/-` - Grow/shrink the focused panel by count times 5 percent
+//! - `d c/o` - Close focused/other panels
+//!
+//! Panels take keys while focused, so the panel verbs are also bound to
+//! `alt` chords that work from anywhere.
 
 use xeno_base::direction::{Axis, SeqDirection, SpatialDirection};
 
@@ -17,39 +28,75 @@ use crate::{ActionResult, Effect, action};
 action!(split_horizontal, {
 	description: "Split horizontal",
 	short_desc: "Horizontal",
-	bindings: r#"normal "ctrl-w s h""#,
+	bindings: r#"normal "ctrl-w s""#,
 }, |_ctx| ActionResult::Effects(Effect::Split(Axis::Horizontal).into()));
 
 action!(split_vertical, {
 	description: "Split vertical",
 	short_desc: "Vertical",
-	bindings: r#"normal "ctrl-w s v""#,
+	bindings: r#"normal "ctrl-w v""#,
 }, |_ctx| ActionResult::Effects(Effect::Split(Axis::Vertical).into()));
 
 action!(focus_left, {
 	description: "Focus left",
 	short_desc: "Left",
-	bindings: r#"normal "ctrl-w f h""#,
+	bindings: r#"normal "ctrl-w h""#,
 }, |_ctx| ActionResult::Effects(Effect::FocusSplit(SpatialDirection::Left).into()));
 
 action!(focus_down, {
 	description: "Focus down",
 	short_desc: "Down",
-	bindings: r#"normal "ctrl-w f j""#,
+	bindings: r#"normal "ctrl-w j""#,
 }, |_ctx| ActionResult::Effects(Effect::FocusSplit(SpatialDirection::Down).into()));
 
 action!(focus_up, {
 	description: "Focus up",
 	short_desc: "Up",
-	bindings: r#"normal "ctrl-w f k""#,
+	bindings: r#"normal "ctrl-w k""#,
 }, |_ctx| ActionResult::Effects(Effect::FocusSplit(SpatialDirection::Up).into()));
 
 action!(focus_right, {
 	description: "Focus right",
 	short_desc: "Right",
-	bindings: r#"normal "ctrl-w f l""#,
+	bindings: r#"normal "ctrl-w l""#,
 }, |_ctx| ActionResult::Effects(Effect::FocusSplit(SpatialDirection::Right).into()));
 
+action!(grow_split_width, {
+	description: "Increase split width",
+	short_desc: "Wider",
+	bindings: r#"normal "ctrl-w >""#,
+}, |ctx| resize(Axis::Vertical, ctx.count, 1));
+
+action!(shrink_split_width, {
+	description: "Decrease split width",
+	short_desc: "Narrower",
+	bindings: r#"normal "ctrl-w <""#,
+}, |ctx| resize(Axis::Vertical, ctx.count, -1));
+
+action!(grow_split_height, {
+	description: "Increase split height",
+	short_desc: "Taller",
+	bindings: r#"normal "ctrl-w +""#,
+}, |ctx| resize(Axis::Horizontal, ctx.count, 1));
+
+action!(shrink_split_height, {
+	description: "Decrease split height",
+	short_desc: "Shorter",
+	bindings: r#"normal "ctrl-w -""#,
+}, |ctx| resize(Axis::Horizontal, ctx.count, -1));
+
+action!(equalize_splits, {
+	description: "Make all splits the same size",
+	short_desc: "Equalize",
+	bindings: r#"normal "ctrl-w =""#,
+}, |_ctx| ActionResult::Effects(Effect::EqualizeSplits.into()));
+
+/// Resizes the focused split by `count` cells in the direction of `sign`.
+fn resize(axis: Axis, count: usize, sign: i32) -> ActionResult {
+	let delta = i32::try_from(count).unwrap_or(i32::MAX) * sign;
+	ActionResult::Effects(Effect::ResizeSplit { axis, delta }.into())
+}
+
 action!(buffer_next, {
 	description: "Next buffer",
 	short_desc: "Next",
@@ -65,7 +112,7 @@ action!(buffer_prev, {
 action!(close_split, {
 	description: "Close current split",
 	short_desc: "Current",
-	bindings: r#"normal "ctrl-w c c""#,
+	bindings: r#"normal "ctrl-w q" "ctrl-w c c""#,
 }, |_ctx| ActionResult::Effects(Effect::CloseSplit.into()));
 
 action!(close_other_buffers, {
@@ -73,3 +120,71 @@ action!(close_other_buffers, {
 	short_desc: "Others",
 	bindings: r#"normal "ctrl-w c o""#,
 }, |_ctx| ActionResult::Effects(Effect::CloseOtherBuffers.into()));
+
+action!(panel_next_tab, {
+	description: "Show the next panel stacked in the dock",
+	short_desc: "Next panel tab",
+	bindings: r#"normal "ctrl-w t""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "panel_next_tab",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(panel_float, {
+	description: "Float the focused panel, or dock it back",
+	short_desc: "Float panel",
+	bindings: r#"normal "ctrl-w o""#,
+}, |_ctx| ActionResult::Effects(
+	Effect::QueueCommand {
+		name: "panel_float",
+		args: Vec::new(),
+	}
+	.into(),
+));
+
+action!(cycle_focus, {
+	description: "Focus the editor, then each docked panel in turn",
+	short_desc: "Cycle focus",
+	bindings: r#"normal "ctrl-w w""#,
+}, |_ctx| queue("cycle_focus", Vec::new()));
+
+action!(grow_panel, {
+	description: "Grow the focused panel",
+	short_desc: "Grow panel",
+	bindings: r#"normal "ctrl-w d +""#,
+}, |ctx| resize_panel(ctx.count, 1));
+
+action!(shrink_panel, {
+	description: "Shrink the focused panel",
+	short_desc: "Shrink panel",
+	bindings: r#"normal "ctrl-w d -""#,
+}, |ctx| resize_panel(ctx.count, -1));
+
+action!(close_panel, {
+	description: "Close the focused panel",
+	short_desc: "Close panel",
+	bindings: r#"normal "ctrl-w d c""#,
+}, |_ctx| queue("close_panel", Vec::new()));
+
+action!(close_other_panels, {
+	description: "Close every panel except the focused one",
+	short_desc: "Close other panels",
+	bindings: r#"normal "ctrl-w d o""#,
+}, |_ctx| queue("close_other_panels", Vec::new()));
+
+/// Percent each count of a panel resize grows or shrinks the panel by.
+const PANEL_RESIZE_STEP: i32 = 5;
+
+/// Resizes the focused panel by `count` steps in the direction of `sign`.
+fn resize_panel(count: usize, sign: i32) -> ActionResult {
+	let delta = count.min(100) as i32 * PANEL_RESIZE_STEP * sign;
+	queue("panel_resize", vec![format!("{delta:+}")])
+}
+
+/// Queues the editor command `name`.
+fn queue(name: &'static str, args: Vec<String>) -> ActionResult {
+	ActionResult::Effects(Effect::QueueCommand { name, args }.into())
+}
@@ -0,0 +1,92 @@
+//! Undo history navigation commands.
+
+use std::time::Duration;
+
+use futures::future::LocalBoxFuture;
+
+use crate::{CommandContext, CommandError, CommandOutcome, HistoryJump, command};
+
+command!(
+	earlier,
+	{ aliases: &["ea"], description: "Go to an older text state (count or 10s/5m/1h/1d)" },
+	handler: cmd_earlier
+);
+
+command!(
+	later,
+	{ aliases: &["lat"], description: "Go to a newer text state (count or 10s/5m/1h/1d)" },
+	handler: cmd_later
+);
+
+/// Handler for the `:earlier` command.
+fn cmd_earlier<'a>(
+	ctx: &'a mut CommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let jump = parse_history_jump(ctx.args.first().copied(), ctx.count)?;
+		ctx.editor.earlier(jump);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+/// Handler for the `:later` command.
+fn cmd_later<'a>(
+	ctx: &'a mut CommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let jump = parse_history_jump(ctx.args.first().copied(), ctx.count)?;
+		ctx.editor.later(jump);
+		Ok(CommandOutcome::Ok)
+	})
+}
+
+/// Parses a `:earlier`/`:later` argument.
+///
+/// A bare number moves that many states; a number followed by `s`, `m`, `h`,
+/// or `d` moves by that span of time. Without an argument, moves by `count`.
+fn parse_history_jump(arg: Option<&str>, count: usize) -> Result<HistoryJump, CommandError> {
+	let Some(arg) = arg else {
+		return Ok(HistoryJump::Steps(count.max(1)));
+	};
+
+	let invalid = || CommandError::InvalidArgument(format!("expected count or time span: {arg}"));
+	let (digits, unit) = match arg.find(|c: char| !c.is_ascii_digit()) {
+		Some(idx) => arg.split_at(idx),
+		None => (arg, ""),
+	};
+	let value: u64 = digits.parse().map_err(|_| invalid())?;
+
+	let secs = match unit {
+		"" => return Ok(HistoryJump::Steps(value as usize)),
+		"s" => value,
+		"m" => value.saturating_mul(60),
+		"h" => value.saturating_mul(60 * 60),
+		"d" => value.saturating_mul(24 * 60 * 60),
+		_ => return Err(invalid()),
+	};
+	Ok(HistoryJump::Time(Duration::from_secs(secs)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_steps_and_spans() {
+		assert_eq!(parse_history_jump(None, 3).unwrap(), HistoryJump::Steps(3));
+		assert_eq!(
+			parse_history_jump(Some("4"), 1).unwrap(),
+			HistoryJump::Steps(4)
+		);
+		assert_eq!(
+			parse_history_jump(Some("10s"), 1).unwrap(),
+			HistoryJump::Time(Duration::from_secs(10))
+		);
+		assert_eq!(
+			parse_history_jump(Some("2h"), 1).unwrap(),
+			HistoryJump::Time(Duration::from_secs(7200))
+		);
+		assert!(parse_history_jump(Some("5x"), 1).is_err());
+		assert!(parse_history_jump(Some("m"), 1).is_err());
+	}
+}
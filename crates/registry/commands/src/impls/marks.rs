@@ -0,0 +1,38 @@
+//! Selection mark inspection command.
+
+use futures::future::LocalBoxFuture;
+use xeno_registry_notifications::keys;
+
+use crate::{CommandContext, CommandError, CommandOutcome, command};
+
+command!(
+	marks,
+	{ description: "List saved selection marks" },
+	handler: cmd_marks
+);
+
+/// Handler for the `:marks` command.
+fn cmd_marks<'a>(
+	ctx: &'a mut CommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let marks = ctx.editor.selection_marks();
+		if marks.is_empty() {
+			ctx.emit(keys::no_marks);
+			return Ok(CommandOutcome::Ok);
+		}
+
+		let lines: Vec<String> = marks
+			.iter()
+			.map(|(label, spans)| {
+				let spans: Vec<String> = spans
+					.iter()
+					.map(|(anchor, head)| format!("{anchor}..{head}"))
+					.collect();
+				format!("{label:<6} {}", spans.join(" "))
+			})
+			.collect();
+		ctx.editor.open_info_popup(&lines.join("\n"), None);
+		Ok(CommandOutcome::Ok)
+	})
+}
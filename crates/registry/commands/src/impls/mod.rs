@@ -1,14 +1,17 @@
 //! Built-in command implementations.
 //!
 //! LSP commands (hover, goto-definition) are in `xeno-api::commands::lsp`
-//! since they need direct [`Editor`] access.
+//! and `:edit` in `xeno-api::commands::edit`, since they need direct
+//! [`Editor`] access.
 
 /// Buffer navigation and management commands.
 mod buffer;
-/// File editing commands (edit, open).
-mod edit;
 /// Help and documentation commands.
 mod help;
+/// Undo history navigation commands.
+mod history;
+/// Selection mark inspection commands.
+mod marks;
 /// Quit and exit commands.
 mod quit;
 /// Registry diagnostic commands.
@@ -1,5 +1,4 @@
 use futures::future::LocalBoxFuture;
-use xeno_registry_notifications::keys;
 
 use crate::{CommandContext, CommandError, CommandOutcome, command};
 
@@ -11,7 +10,7 @@ fn cmd_quit<'a>(
 ) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
 	Box::pin(async move {
 		if ctx.editor.is_modified() {
-			ctx.emit(keys::unsaved_changes_force_quit);
+			ctx.editor.confirm_quit();
 			return Ok(CommandOutcome::Ok);
 		}
 		Ok(CommandOutcome::Quit)
@@ -25,6 +25,16 @@ command!(
 	handler: cmd_setlocal
 );
 
+command!(
+	toggle,
+	{
+		aliases: &["tog"],
+		description: "Flip a boolean option",
+		source: RegistrySource::Builtin,
+	},
+	handler: cmd_toggle
+);
+
 /// Handler for the `:set` command.
 ///
 /// Accepts either `option=value` or `option value` syntax.
@@ -64,6 +74,24 @@ fn cmd_setlocal<'a>(
 	})
 }
 
+/// Handler for the `:toggle` command.
+///
+/// Flips a boolean option such as `inlay-hints` for the current buffer,
+/// globally unless the buffer has its own value for it.
+fn cmd_toggle<'a>(
+	ctx: &'a mut CommandContext<'a>,
+) -> LocalBoxFuture<'a, Result<CommandOutcome, CommandError>> {
+	Box::pin(async move {
+		let key = ctx
+			.args
+			.first()
+			.ok_or(CommandError::MissingArgument("option name"))?;
+		let (key, value) = ctx.editor.toggle_option(key)?;
+		ctx.emit(keys::option_set::call(key, &value.to_string()));
+		Ok(CommandOutcome::Ok)
+	})
+}
+
 /// Parses `:set` arguments into (key, value).
 ///
 /// Supports multiple formats:
@@ -6,6 +6,7 @@ use std::any::Any;
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::time::Duration;
 
 use futures::future::LocalBoxFuture;
 use linkme::distributed_slice;
@@ -67,14 +68,29 @@ pub enum CommandOutcome {
 	ForceQuit,
 }
 
+/// Amount to move through undo history with `:earlier` and `:later`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryJump {
+	/// A number of history states, in the order they were created.
+	Steps(usize),
+	/// A span of wall-clock time.
+	Time(Duration),
+}
+
 /// Editor operations available to commands.
 pub trait CommandEditorOps {
 	/// Emits a type-safe notification.
 	fn emit(&mut self, notification: Notification);
 	/// Clears all visible notifications.
 	fn clear_notifications(&mut self);
-	/// Returns whether the current buffer has unsaved changes.
+	/// Returns whether the current buffer has unsaved changes that should
+	/// block quitting.
+	///
+	/// Changes to scratch buffers only count once they are marked persistent.
 	fn is_modified(&self) -> bool;
+	/// Asks whether to save or discard unsaved changes before quitting, and
+	/// quits once the answer allows it.
+	fn confirm_quit(&mut self);
 	/// Returns whether the current buffer is read-only.
 	fn is_readonly(&self) -> bool;
 	/// Sets the read-only flag for the current buffer.
@@ -86,16 +102,31 @@ pub trait CommandEditorOps {
 		&mut self,
 		path: PathBuf,
 	) -> Pin<Box<dyn Future<Output = Result<(), CommandError>> + '_>>;
+	/// Moves back through the current buffer's undo history chronologically.
+	fn earlier(&mut self, jump: HistoryJump);
+	/// Moves forward through the current buffer's undo history chronologically.
+	fn later(&mut self, jump: HistoryJump);
 	/// Changes the active color theme.
 	fn set_theme(&mut self, name: &str) -> Result<(), CommandError>;
 	/// Sets a global option value by KDL key.
 	fn set_option(&mut self, kdl_key: &str, value: &str) -> Result<(), CommandError>;
 	/// Sets a buffer-local option value by KDL key.
 	fn set_local_option(&mut self, kdl_key: &str, value: &str) -> Result<(), CommandError>;
+	/// Flips a boolean option as the current buffer sees it, returning the
+	/// option's KDL key and new value.
+	///
+	/// `key` is a KDL key, or an option name with underscores for dashes.
+	fn toggle_option(&mut self, key: &str) -> Result<(&'static str, bool), CommandError>;
 	/// Opens an info popup with the given content and optional file type for syntax highlighting.
 	fn open_info_popup(&mut self, content: &str, file_type: Option<&str>);
 	/// Closes all open info popups.
 	fn close_all_info_popups(&mut self);
+	/// Returns the current document's saved selections as `(label, spans)`
+	/// pairs, where each span is an `(anchor, head)` char range.
+	///
+	/// Named slots are labelled by their register; the selection kept for
+	/// reselect-last is labelled `last`.
+	fn selection_marks(&self) -> Vec<(String, Vec<(usize, usize)>)>;
 
 	/// Opens a file and navigates to a specific line and column.
 	///
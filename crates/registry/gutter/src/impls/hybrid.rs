@@ -2,7 +2,8 @@
 
 use crate::{GutterCell, GutterStyle, gutter};
 
-/// Computes dynamic width based on total line count.
+/// Computes dynamic width based on total line count, since the cursor line
+/// shows its absolute number.
 fn line_number_width(ctx: &crate::GutterWidthContext) -> u16 {
 	(ctx.total_lines.max(1).ilog10() as u16 + 1).max(3)
 }
@@ -2,9 +2,10 @@
 
 use crate::{GutterCell, GutterStyle, gutter};
 
-/// Computes dynamic width based on total line count.
+/// Computes dynamic width from the largest distance the viewport can show,
+/// with a minimum of 3 characters.
 fn line_number_width(ctx: &crate::GutterWidthContext) -> u16 {
-	(ctx.total_lines.max(1).ilog10() as u16 + 1).max(3)
+	(ctx.max_relative_number().max(1).ilog10() as u16 + 1).max(3)
 }
 
 gutter!(relative_line_numbers, {
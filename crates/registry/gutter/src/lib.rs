@@ -7,10 +7,13 @@
 //!
 //! # Built-in Columns
 //!
-//! - `line_numbers` - Absolute line numbers (enabled by default)
-//! - `relative_line_numbers` - Distance from cursor line (disabled by default)
-//! - `hybrid_line_numbers` - Absolute on cursor, relative elsewhere (disabled)
+//! - `line_numbers` - Absolute line numbers
+//! - `relative_line_numbers` - Distance from cursor line
+//! - `hybrid_line_numbers` - Absolute on cursor, relative elsewhere
 //! - `signs` - Sign column for diagnostics/breakpoints (enabled by default)
+//!
+//! The three line number columns are mutually exclusive; [`LineNumbers`]
+//! picks which one is shown.
 
 use std::path::Path;
 
@@ -30,7 +33,7 @@ pub struct GutterLineContext<'a> {
 	pub total_lines: usize,
 	/// Current cursor line (0-indexed) - enables relative line numbers.
 	pub cursor_line: usize,
-	/// Whether this line is the cursor line.
+	/// Whether this line is the primary cursor's line.
 	pub is_cursor_line: bool,
 	/// Whether this is a wrapped continuation (not first segment of line).
 	pub is_continuation: bool,
@@ -49,6 +52,74 @@ pub struct GutterWidthContext {
 	pub total_lines: usize,
 	/// Maximum viewport width (for constraints).
 	pub viewport_width: u16,
+	/// Viewport height in rows, bounding relative line numbers; 0 if unknown.
+	pub visible_lines: usize,
+	/// Line number column to show.
+	pub line_numbers: LineNumbers,
+}
+
+impl GutterWidthContext {
+	/// Returns the largest distance a relative line number can show.
+	pub fn max_relative_number(&self) -> usize {
+		let span = match self.visible_lines {
+			0 => self.total_lines,
+			rows => self.total_lines.min(rows),
+		};
+		span.saturating_sub(1)
+	}
+}
+
+/// Which line number column the gutter shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineNumbers {
+	/// Absolute line numbers.
+	#[default]
+	Absolute,
+	/// Distance from the cursor line.
+	Relative,
+	/// Absolute on the cursor line, relative elsewhere.
+	Hybrid,
+	/// No line numbers.
+	None,
+}
+
+impl LineNumbers {
+	/// Names of the line number columns, one per mode that shows numbers.
+	const COLUMNS: [&'static str; 3] = [
+		"line_numbers",
+		"relative_line_numbers",
+		"hybrid_line_numbers",
+	];
+
+	/// Parses a `line-numbers` option value.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"absolute" => Some(Self::Absolute),
+			"relative" => Some(Self::Relative),
+			"hybrid" => Some(Self::Hybrid),
+			"none" => Some(Self::None),
+			_ => None,
+		}
+	}
+
+	/// Returns the name of the gutter column this mode shows.
+	pub fn column(self) -> Option<&'static str> {
+		match self {
+			Self::Absolute => Some(Self::COLUMNS[0]),
+			Self::Relative => Some(Self::COLUMNS[1]),
+			Self::Hybrid => Some(Self::COLUMNS[2]),
+			Self::None => None,
+		}
+	}
+
+	/// Returns whether `gutter` is shown under this mode.
+	fn shows(self, gutter: &GutterDef) -> bool {
+		if Self::COLUMNS.contains(&gutter.name) {
+			self.column() == Some(gutter.name)
+		} else {
+			gutter.default_enabled
+		}
+	}
 }
 
 /// What a gutter column renders for a single line.
@@ -159,9 +230,21 @@ pub fn column_width(gutter: &GutterDef, ctx: &GutterWidthContext) -> u16 {
 	}
 }
 
-/// Computes total gutter width from enabled columns.
+/// Returns the gutters shown with `line_numbers`, sorted by priority.
+///
+/// These are the enabled columns, with the line number column replaced by
+/// the one `line_numbers` selects.
+pub fn active_gutters(line_numbers: LineNumbers) -> impl Iterator<Item = &'static GutterDef> {
+	let mut gutters: Vec<_> = GUTTERS.iter().filter(|g| line_numbers.shows(g)).collect();
+	gutters.sort_by_key(|g| g.priority);
+	gutters.into_iter()
+}
+
+/// Computes total gutter width from the active columns.
 pub fn total_width(ctx: &GutterWidthContext) -> u16 {
-	let columns_width: u16 = enabled_gutters().map(|g| column_width(g, ctx)).sum();
+	let columns_width: u16 = active_gutters(ctx.line_numbers)
+		.map(|g| column_width(g, ctx))
+		.sum();
 	if columns_width > 0 {
 		columns_width + 1 // trailing separator space
 	} else {
@@ -169,11 +252,45 @@ pub fn total_width(ctx: &GutterWidthContext) -> u16 {
 	}
 }
 
-/// Computes widths for all enabled columns, returning (width, def) pairs sorted by priority.
+/// Computes widths for all active columns, returning (width, def) pairs sorted by priority.
 pub fn column_widths(ctx: &GutterWidthContext) -> Vec<(u16, &'static GutterDef)> {
-	enabled_gutters()
+	active_gutters(ctx.line_numbers)
 		.map(|g| (column_width(g, ctx), g))
 		.collect()
 }
 
 impl_registry_metadata!(GutterDef);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ctx(line_numbers: LineNumbers) -> GutterWidthContext {
+		GutterWidthContext {
+			total_lines: 12_000,
+			viewport_width: 80,
+			visible_lines: 40,
+			line_numbers,
+		}
+	}
+
+	#[test]
+	fn line_number_mode_picks_one_column() {
+		let names = |mode| active_gutters(mode).map(|g| g.name).collect::<Vec<_>>();
+		assert_eq!(names(LineNumbers::Absolute), ["signs", "line_numbers"]);
+		assert_eq!(
+			names(LineNumbers::Relative),
+			["signs", "relative_line_numbers"]
+		);
+		assert_eq!(names(LineNumbers::Hybrid), ["signs", "hybrid_line_numbers"]);
+		assert_eq!(names(LineNumbers::None), ["signs"]);
+	}
+
+	#[test]
+	fn width_fits_widest_displayed_number() {
+		assert_eq!(total_width(&ctx(LineNumbers::Absolute)), 2 + 5 + 1);
+		assert_eq!(total_width(&ctx(LineNumbers::Hybrid)), 2 + 5 + 1);
+		assert_eq!(total_width(&ctx(LineNumbers::Relative)), 2 + 3 + 1);
+		assert_eq!(total_width(&ctx(LineNumbers::None)), 2 + 1);
+	}
+}
@@ -55,6 +55,8 @@ xeno_macro::define_events! {
 	EditorQuit => "editor:quit",
 	/// Periodic tick.
 	EditorTick => "editor:tick",
+	/// The cursor and text have rested since they last changed.
+	EditorIdle => "editor:idle",
 	/// A buffer was opened/created.
 	BufferOpen => "buffer:open" {
 		/// Filesystem path of the opened buffer.
@@ -64,6 +66,13 @@ xeno_macro::define_events! {
 		/// Detected file type (e.g., "rust", "python"), if any.
 		file_type: OptionStr,
 	},
+	/// A buffer's file type changed after it was opened.
+	BufferFileType => "buffer:filetype" {
+		/// Filesystem path of the buffer.
+		path: Path,
+		/// New file type, or `None` if none was detected.
+		file_type: OptionStr,
+	},
 	/// A buffer is about to be written to disk.
 	BufferWritePre => "buffer:write-pre" {
 		/// Filesystem path where the buffer will be written.
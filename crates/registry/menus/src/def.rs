@@ -31,7 +31,7 @@ pub struct MenuItemDef {
 	pub name: &'static str,
 	/// ID of the parent menu group.
 	pub group: &'static str,
-	/// Label shown in the dropdown menu.
+	/// Label shown in the dropdown menu (may include accelerator key).
 	pub label: &'static str,
 	/// Command to execute when the item is selected.
 	pub command: &'static str,
@@ -8,7 +8,10 @@ mod word;
 pub use find::{find_char_backward, find_char_forward};
 pub use objects::{select_surround_object, select_word_object};
 use ropey::RopeSlice;
-pub use search::{escape_pattern, find_all_matches, find_next, find_prev, matches_pattern};
+pub use search::{
+	Regex, SearchCase, SearchError, compile, compile_with_case, escape_pattern, find_all_matches,
+	find_iter, find_next, find_prev, matches_pattern, next_match, prev_match,
+};
 pub use word::{move_to_next_word_end, move_to_next_word_start, move_to_prev_word_start};
 use xeno_base::graphemes::{next_grapheme_boundary, prev_grapheme_boundary};
 use xeno_base::range::{CharIdx, Direction, Range};
@@ -198,6 +201,28 @@ mod tests {
 		assert_eq!(moved.head, 5);
 	}
 
+	#[test]
+	fn test_move_crosses_whole_clusters() {
+		// "e" + combining acute, thumbs-up + skin tone, woman-woman-girl ZWJ family.
+		let text = Rope::from("e\u{301}👍🏽👩\u{200d}👩\u{200d}👧x");
+		let slice = text.slice(..);
+
+		let mut range = Range::point(0);
+		let mut heads = Vec::new();
+		for _ in 0..4 {
+			range = move_horizontally(slice, range, Direction::Forward, 1, false);
+			heads.push(range.head);
+		}
+		assert_eq!(heads, vec![2, 4, 9, 10]);
+
+		let mut heads = Vec::new();
+		for _ in 0..4 {
+			range = move_horizontally(slice, range, Direction::Backward, 1, false);
+			heads.push(range.head);
+		}
+		assert_eq!(heads, vec![9, 4, 2, 0]);
+	}
+
 	#[test]
 	fn test_move_down() {
 		let text = Rope::from("hello\nworld\n");
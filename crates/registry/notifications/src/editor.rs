@@ -18,6 +18,14 @@ static NOTIF_BUFFER_READONLY: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LOSSY_BUFFER: NotificationDef = NotificationDef::new(
+	"lossy_buffer",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
 #[distributed_slice(NOTIFICATIONS)]
 static NOTIF_NOTHING_TO_UNDO: NotificationDef = NotificationDef::new(
 	"nothing_to_undo",
@@ -210,6 +218,110 @@ static NOTIF_FILE_NOT_FOUND: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_NEW_FILE: NotificationDef = NotificationDef::new(
+	"new_file",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_MIXED_LINE_ENDINGS: NotificationDef = NotificationDef::new(
+	"mixed_line_endings",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LOSSY_DECODE: NotificationDef = NotificationDef::new(
+	"lossy_decode",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_DISK_VERSION_LOADED: NotificationDef = NotificationDef::new(
+	"disk_version_loaded",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_DISK_CHANGES_MERGED: NotificationDef = NotificationDef::new(
+	"disk_changes_merged",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LARGE_FILE: NotificationDef = NotificationDef::new(
+	"large_file",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SWAP_FOUND: NotificationDef = NotificationDef::new(
+	"swap_found",
+	Level::Warn,
+	AutoDismiss::Never,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SWAP_RECOVERED: NotificationDef = NotificationDef::new(
+	"swap_recovered",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_AUTOSAVE_FAILED: NotificationDef = NotificationDef::new(
+	"autosave_failed",
+	Level::Error,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SESSION_SAVED: NotificationDef = NotificationDef::new(
+	"session_saved",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SESSION_FILES_MISSING: NotificationDef = NotificationDef::new(
+	"session_files_missing",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SESSION_PANELS_MISSING: NotificationDef = NotificationDef::new(
+	"session_panels_missing",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SESSION_AVAILABLE: NotificationDef = NotificationDef::new(
+	"session_available",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
 #[distributed_slice(NOTIFICATIONS)]
 static NOTIF_FILE_LOAD_ERROR: NotificationDef = NotificationDef::new(
 	"file_load_error",
@@ -226,6 +338,22 @@ static NOTIF_FILE_SAVE_ERROR: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_FILE_OPERATION_ERROR: NotificationDef = NotificationDef::new(
+	"file_operation_error",
+	Level::Error,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LOCATIONS_SET: NotificationDef = NotificationDef::new(
+	"locations_set",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
 #[distributed_slice(NOTIFICATIONS)]
 static NOTIF_BUFFER_CLOSED: NotificationDef = NotificationDef::new(
 	"buffer_closed",
@@ -274,16 +402,128 @@ static NOTIF_OPTION_SET: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SELECTION_SAVED: NotificationDef = NotificationDef::new(
+	"selection_saved",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_MARK_EMPTY: NotificationDef = NotificationDef::new(
+	"mark_empty",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_REGISTER_EMPTY: NotificationDef = NotificationDef::new(
+	"register_empty",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_INVALID_CODEPOINT: NotificationDef = NotificationDef::new(
+	"invalid_codepoint",
+	Level::Error,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_SYMBOL_RENAMED: NotificationDef = NotificationDef::new(
+	"symbol_renamed",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_BUFFER_FORMATTED: NotificationDef = NotificationDef::new(
+	"buffer_formatted",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_FORMAT_ON_SAVE_FAILED: NotificationDef = NotificationDef::new(
+	"format_on_save_failed",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_WILL_SAVE_EDITS_SKIPPED: NotificationDef = NotificationDef::new(
+	"will_save_edits_skipped",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_DIAGNOSTICS_WRAPPED: NotificationDef = NotificationDef::new(
+	"diagnostics_wrapped",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_CODE_ACTION_APPLIED: NotificationDef = NotificationDef::new(
+	"code_action_applied",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LSP_SERVERS_STOPPED: NotificationDef = NotificationDef::new(
+	"lsp_servers_stopped",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LSP_SERVER_CRASHED: NotificationDef = NotificationDef::new(
+	"lsp_server_crashed",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_LSP_SERVER_FAILED: NotificationDef = NotificationDef::new(
+	"lsp_server_failed",
+	Level::Error,
+	AutoDismiss::Never,
+	RegistrySource::Builtin,
+);
+
 #[allow(non_upper_case_globals, non_camel_case_types)]
 pub mod keys {
 	use super::*;
 
 	pub const buffer_readonly: NotificationKey =
 		NotificationKey::new(&NOTIF_BUFFER_READONLY, "Buffer is read-only");
+	pub const lossy_buffer: NotificationKey = NotificationKey::new(
+		&NOTIF_LOSSY_BUFFER,
+		"Buffer has invalid UTF-8; :set allow-lossy-write true to edit",
+	);
 	pub const nothing_to_undo: NotificationKey =
 		NotificationKey::new(&NOTIF_NOTHING_TO_UNDO, "Nothing to undo");
 	pub const nothing_to_redo: NotificationKey =
 		NotificationKey::new(&NOTIF_NOTHING_TO_REDO, "Nothing to redo");
+	pub const session_available: NotificationKey = NotificationKey::new(
+		&NOTIF_SESSION_AVAILABLE,
+		"A session was saved in this directory; :load-session to restore it",
+	);
 	pub const undo: NotificationKey = NotificationKey::new(&NOTIF_UNDO, "Undo");
 	pub const redo: NotificationKey = NotificationKey::new(&NOTIF_REDO, "Redo");
 	pub const search_wrapped: NotificationKey =
@@ -316,6 +556,51 @@ pub mod keys {
 		NotificationKey::new(&NOTIF_READONLY_ENABLED, "Read-only enabled");
 	pub const readonly_disabled: NotificationKey =
 		NotificationKey::new(&NOTIF_READONLY_DISABLED, "Read-only disabled");
+	pub const no_previous_selection: NotificationKey =
+		NotificationKey::new(&NOTIF_MARK_EMPTY, "No previous selection");
+	pub const no_marks: NotificationKey =
+		NotificationKey::new(&NOTIF_MARK_EMPTY, "No saved selections");
+
+	/// "Selection saved to 'X'".
+	pub struct selection_saved;
+	impl selection_saved {
+		pub fn call(slot: char) -> Notification {
+			Notification::new(
+				&NOTIF_SELECTION_SAVED,
+				format!("Selection saved to '{slot}'"),
+			)
+		}
+	}
+
+	/// "No selection saved in 'X'".
+	pub struct mark_empty;
+	impl mark_empty {
+		pub fn call(slot: char) -> Notification {
+			Notification::new(&NOTIF_MARK_EMPTY, format!("No selection saved in '{slot}'"))
+		}
+	}
+
+	/// "Register 'X' is empty".
+	pub struct register_empty;
+	impl register_empty {
+		pub fn call(register: char) -> Notification {
+			Notification::new(
+				&NOTIF_REGISTER_EMPTY,
+				format!("Register '{register}' is empty"),
+			)
+		}
+	}
+
+	/// "U+XXXX is not a valid codepoint".
+	pub struct invalid_codepoint;
+	impl invalid_codepoint {
+		pub fn call(value: u32) -> Notification {
+			Notification::new(
+				&NOTIF_INVALID_CODEPOINT,
+				format!("U+{value:04X} is not a valid codepoint"),
+			)
+		}
+	}
 
 	/// "Yanked N chars".
 	pub struct yanked_chars;
@@ -419,6 +704,148 @@ pub mod keys {
 		}
 	}
 
+	/// "New file: /path".
+	pub struct new_file;
+	impl new_file {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(&NOTIF_NEW_FILE, format!("New file: {}", path.display()))
+		}
+	}
+
+	/// "Mixed line endings in /path; saving as crlf".
+	pub struct mixed_line_endings;
+	impl mixed_line_endings {
+		pub fn call(path: &Path, ending: &str) -> Notification {
+			Notification::new(
+				&NOTIF_MIXED_LINE_ENDINGS,
+				format!(
+					"Mixed line endings in {}; saving as {ending}",
+					path.display()
+				),
+			)
+		}
+	}
+
+	/// "/path is not valid UTF-8; opened read-only".
+	pub struct lossy_decode;
+	impl lossy_decode {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(
+				&NOTIF_LOSSY_DECODE,
+				format!(
+					"{} is not valid UTF-8; opened read-only (:set allow-lossy-write true to edit)",
+					path.display()
+				),
+			)
+		}
+	}
+
+	/// "Loaded /path from disk".
+	pub struct disk_version_loaded;
+	impl disk_version_loaded {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(
+				&NOTIF_DISK_VERSION_LOADED,
+				format!("Loaded {} from disk", path.display()),
+			)
+		}
+	}
+
+	/// "Merged disk changes into /path; N conflicts".
+	pub struct disk_changes_merged;
+	impl disk_changes_merged {
+		pub fn call(path: &Path, conflicts: usize) -> Notification {
+			Notification::new(
+				&NOTIF_DISK_CHANGES_MERGED,
+				format!(
+					"Merged disk changes into {}; {conflicts} conflicts",
+					path.display()
+				),
+			)
+		}
+	}
+
+	/// A session was written.
+	pub struct session_saved;
+	impl session_saved {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(
+				&NOTIF_SESSION_SAVED,
+				format!("Session saved to {}", path.display()),
+			)
+		}
+	}
+
+	/// Files of a restored session no longer exist.
+	pub struct session_files_missing;
+	impl session_files_missing {
+		pub fn call(paths: &str) -> Notification {
+			Notification::new(
+				&NOTIF_SESSION_FILES_MISSING,
+				format!("Session files no longer exist: {paths}"),
+			)
+		}
+	}
+
+	/// Panels of a restored session are no longer available.
+	pub struct session_panels_missing;
+	impl session_panels_missing {
+		pub fn call(panels: &str) -> Notification {
+			Notification::new(
+				&NOTIF_SESSION_PANELS_MISSING,
+				format!("Session panels no longer available: {panels}"),
+			)
+		}
+	}
+
+	/// Unsaved changes from a crashed session were found for a file.
+	pub struct swap_found;
+	impl swap_found {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(
+				&NOTIF_SWAP_FOUND,
+				format!(
+					"{} has unsaved changes from a crashed session: :recover to load them, :discard-swap to drop them",
+					path.display()
+				),
+			)
+		}
+	}
+
+	/// Autosave could not write a buffer.
+	pub struct autosave_failed;
+	impl autosave_failed {
+		pub fn call(error: &str) -> Notification {
+			Notification::new(&NOTIF_AUTOSAVE_FAILED, format!("Autosave failed: {error}"))
+		}
+	}
+
+	/// Swap file text was loaded into a buffer.
+	pub struct swap_recovered;
+	impl swap_recovered {
+		pub fn call(path: &Path) -> Notification {
+			Notification::new(
+				&NOTIF_SWAP_RECOVERED,
+				format!("Recovered {}; :write to keep it", path.display()),
+			)
+		}
+	}
+
+	/// Large-file mode explanation shown when a large file is opened.
+	pub struct large_file;
+	impl large_file {
+		pub fn call(path: &Path, size: u64) -> Notification {
+			Notification::new(
+				&NOTIF_LARGE_FILE,
+				format!(
+					"{} is {} MiB: syntax highlighting, soft wrap, language servers and search match counts are off and undo history is short (:set large-file off to restore)",
+					path.display(),
+					size / (1024 * 1024)
+				),
+			)
+		}
+	}
+
 	/// File load error.
 	pub struct file_load_error;
 	impl file_load_error {
@@ -438,6 +865,25 @@ pub mod keys {
 		}
 	}
 
+	/// File create, rename or delete error.
+	pub struct file_operation_error;
+	impl file_operation_error {
+		pub fn call(err: &str) -> Notification {
+			Notification::new(&NOTIF_FILE_OPERATION_ERROR, err.to_string())
+		}
+	}
+
+	/// "N locations in the location list".
+	pub struct locations_set;
+	impl locations_set {
+		pub fn call(count: usize) -> Notification {
+			Notification::new(
+				&NOTIF_LOCATIONS_SET,
+				format!("{} locations in the location list", count),
+			)
+		}
+	}
+
 	/// "Closed name".
 	pub struct buffer_closed;
 	impl buffer_closed {
@@ -446,6 +892,117 @@ pub mod keys {
 		}
 	}
 
+	/// "Renamed N occurrences in M files".
+	pub struct symbol_renamed;
+	impl symbol_renamed {
+		pub fn call(occurrences: usize, files: usize) -> Notification {
+			let files = if files == 1 {
+				"1 file".to_string()
+			} else {
+				format!("{files} files")
+			};
+			Notification::new(
+				&NOTIF_SYMBOL_RENAMED,
+				format!("Renamed {occurrences} occurrences in {files}"),
+			)
+		}
+	}
+
+	/// "Formatted: N edits", or "Already formatted".
+	pub struct buffer_formatted;
+	impl buffer_formatted {
+		pub fn call(edits: usize) -> Notification {
+			let message = match edits {
+				0 => "Already formatted".to_string(),
+				1 => "Formatted: 1 edit".to_string(),
+				n => format!("Formatted: {n} edits"),
+			};
+			Notification::new(&NOTIF_BUFFER_FORMATTED, message)
+		}
+	}
+
+	/// "Saved without formatting: reason".
+	pub struct format_on_save_failed;
+	impl format_on_save_failed {
+		pub fn call(reason: &str) -> Notification {
+			Notification::new(
+				&NOTIF_FORMAT_ON_SAVE_FAILED,
+				format!("Saved without formatting: {reason}"),
+			)
+		}
+	}
+
+	/// "Saved without the language server's save edits: reason".
+	pub struct will_save_edits_skipped;
+	impl will_save_edits_skipped {
+		pub fn call(reason: &str) -> Notification {
+			Notification::new(
+				&NOTIF_WILL_SAVE_EDITS_SKIPPED,
+				format!("Saved without the language server's save edits: {reason}"),
+			)
+		}
+	}
+
+	/// "Diagnostics wrapped to beginning" or "... to end".
+	pub struct diagnostics_wrapped;
+	impl diagnostics_wrapped {
+		pub fn call(forward: bool) -> Notification {
+			let to = if forward { "beginning" } else { "end" };
+			Notification::new(
+				&NOTIF_DIAGNOSTICS_WRAPPED,
+				format!("Diagnostics wrapped to {to}"),
+			)
+		}
+	}
+
+	/// "Title: what changed".
+	pub struct code_action_applied;
+	impl code_action_applied {
+		pub fn call(title: &str, changes: &str) -> Notification {
+			Notification::new(&NOTIF_CODE_ACTION_APPLIED, format!("{title}: {changes}"))
+		}
+	}
+
+	/// "Stopped X, Y" or "Restarted X, Y".
+	pub struct lsp_servers_stopped;
+	impl lsp_servers_stopped {
+		pub fn call(names: &[String], restarted: bool) -> Notification {
+			let verb = if restarted { "Restarted" } else { "Stopped" };
+			Notification::new(
+				&NOTIF_LSP_SERVERS_STOPPED,
+				format!("{verb} {}", names.join(", ")),
+			)
+		}
+	}
+
+	/// A language server crashed and restarts after a delay.
+	pub struct lsp_server_crashed;
+	impl lsp_server_crashed {
+		pub fn call(name: &str, crashes: u32, delay: Duration) -> Notification {
+			Notification::new(
+				&NOTIF_LSP_SERVER_CRASHED,
+				format!(
+					"{name} exited; restarting in {:.1}s (crash {crashes} in a row)",
+					delay.as_secs_f32()
+				),
+			)
+		}
+	}
+
+	/// A language server crashed too often and was left stopped.
+	pub struct lsp_server_failed;
+	impl lsp_server_failed {
+		pub fn call(name: &str, crashes: u32, error: Option<&str>) -> Notification {
+			let reason = error.map(|e| format!(" ({e})")).unwrap_or_default();
+			Notification::new(
+				&NOTIF_LSP_SERVER_FAILED,
+				format!(
+					"{name} crashed {crashes} times in a row and was stopped{reason}; :lsp-restart to try again"
+				),
+			)
+		}
+	}
+
 	/// "Set option = value".
 	pub struct option_set;
 	impl option_set {
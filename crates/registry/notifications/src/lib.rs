@@ -39,6 +39,8 @@ pub mod keys {
 }
 
 /// Severity level for notifications.
+///
+/// Levels order by severity: `Debug < Info < Success < Warn < Error`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Level {
 	/// Informational message (default).
@@ -54,6 +56,56 @@ pub enum Level {
 	Success,
 }
 
+impl Level {
+	/// Every level, least severe first.
+	pub const ALL: [Self; 5] = [
+		Self::Debug,
+		Self::Info,
+		Self::Success,
+		Self::Warn,
+		Self::Error,
+	];
+
+	/// Returns the level's name as written in options.
+	pub const fn name(self) -> &'static str {
+		match self {
+			Self::Debug => "debug",
+			Self::Info => "info",
+			Self::Success => "success",
+			Self::Warn => "warn",
+			Self::Error => "error",
+		}
+	}
+
+	/// Parses a level name written by [`name`](Self::name).
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|level| level.name() == name)
+	}
+
+	/// Returns the position of the level in [`ALL`](Self::ALL).
+	const fn severity(self) -> u8 {
+		match self {
+			Self::Debug => 0,
+			Self::Info => 1,
+			Self::Success => 2,
+			Self::Warn => 3,
+			Self::Error => 4,
+		}
+	}
+}
+
+impl PartialOrd for Level {
+	fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Level {
+	fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+		self.severity().cmp(&other.severity())
+	}
+}
+
 /// Controls automatic dismissal of notifications.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AutoDismiss {
@@ -86,12 +138,15 @@ pub struct NotificationDef {
 	pub level: Level,
 	/// Auto-dismiss behavior.
 	pub auto_dismiss: AutoDismiss,
+	/// Whether emitting the same message while its toast shows bumps a
+	/// repeat counter on it instead of stacking another toast.
+	pub dedupe: bool,
 	/// Where this notification was defined.
 	pub source: RegistrySource,
 }
 
 impl NotificationDef {
-	/// Creates a new notification definition.
+	/// Creates a new notification definition, deduplicated by default.
 	pub const fn new(
 		id: &'static str,
 		level: Level,
@@ -102,9 +157,17 @@ impl NotificationDef {
 			id,
 			level,
 			auto_dismiss,
+			dedupe: true,
 			source,
 		}
 	}
+
+	/// Sets whether repeats of a showing notification are counted on its
+	/// toast.
+	pub const fn dedupe(mut self, enabled: bool) -> Self {
+		self.dedupe = enabled;
+		self
+	}
 }
 
 /// Registry of all notification definitions.
@@ -121,6 +184,8 @@ pub struct Notification {
 	pub def: &'static NotificationDef,
 	/// The formatted message content.
 	pub message: String,
+	/// What emitted the notification, such as `lsp`, if not its type.
+	pub origin: Option<String>,
 }
 
 impl Notification {
@@ -129,9 +194,22 @@ impl Notification {
 		Self {
 			def,
 			message: message.into(),
+			origin: None,
 		}
 	}
 
+	/// Sets what emitted the notification, for rate limiting.
+	#[must_use]
+	pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+		self.origin = Some(origin.into());
+		self
+	}
+
+	/// Returns what emitted the notification, defaulting to its type's ID.
+	pub fn origin(&self) -> &str {
+		self.origin.as_deref().unwrap_or(self.def.id)
+	}
+
 	/// Returns the notification level.
 	pub fn level(&self) -> Level {
 		self.def.level
@@ -141,6 +219,11 @@ impl Notification {
 	pub fn auto_dismiss(&self) -> AutoDismiss {
 		self.def.auto_dismiss
 	}
+
+	/// Returns whether repeats are counted on one toast.
+	pub fn dedupe(&self) -> bool {
+		self.def.dedupe
+	}
 }
 
 /// Typed key referencing a notification definition with a static message.
@@ -209,3 +292,22 @@ impl From<NotificationKey> for Notification {
 		key.emit()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn levels_order_by_severity() {
+		assert!(Level::Debug < Level::Info);
+		assert!(Level::Success < Level::Warn);
+		assert!(Level::Warn < Level::Error);
+		let mut levels = Level::ALL;
+		levels.reverse();
+		levels.sort();
+		assert_eq!(levels, Level::ALL);
+		for level in Level::ALL {
+			assert_eq!(Level::from_name(level.name()), Some(level));
+		}
+	}
+}
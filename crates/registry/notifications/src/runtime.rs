@@ -1,4 +1,4 @@
-//! Runtime notification keys (result handlers, input state).
+//! Runtime notification keys (result handlers, input state, progress).
 
 use linkme::distributed_slice;
 
@@ -23,14 +23,6 @@ static NOTIF_SCREEN_MOTION_UNAVAILABLE: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
-#[distributed_slice(NOTIFICATIONS)]
-static NOTIF_PENDING_PROMPT: NotificationDef = NotificationDef::new(
-	"pending_prompt",
-	Level::Info,
-	AutoDismiss::DEFAULT,
-	RegistrySource::Builtin,
-);
-
 #[distributed_slice(NOTIFICATIONS)]
 static NOTIF_COUNT_DISPLAY: NotificationDef = NotificationDef::new(
 	"count_display",
@@ -47,6 +39,38 @@ static NOTIF_UNHANDLED_RESULT: NotificationDef = NotificationDef::new(
 	RegistrySource::Builtin,
 );
 
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_PROGRESS_DONE: NotificationDef = NotificationDef::new(
+	"progress_done",
+	Level::Success,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_PROGRESS_FAILED: NotificationDef = NotificationDef::new(
+	"progress_failed",
+	Level::Error,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_PROGRESS_STALLED: NotificationDef = NotificationDef::new(
+	"progress_stalled",
+	Level::Warn,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
+#[distributed_slice(NOTIFICATIONS)]
+static NOTIF_NOTIFICATIONS_SUPPRESSED: NotificationDef = NotificationDef::new(
+	"notifications_suppressed",
+	Level::Info,
+	AutoDismiss::DEFAULT,
+	RegistrySource::Builtin,
+);
+
 #[allow(non_upper_case_globals, non_camel_case_types)]
 pub mod keys {
 	use super::*;
@@ -64,14 +88,6 @@ pub mod keys {
 		"Screen motion target is unavailable",
 	);
 
-	/// Pending input prompt.
-	pub struct pending_prompt;
-	impl pending_prompt {
-		pub fn call(prompt: &str) -> Notification {
-			Notification::new(&NOTIF_PENDING_PROMPT, prompt.to_string())
-		}
-	}
-
 	/// Numeric count display.
 	pub struct count_display;
 	impl count_display {
@@ -90,4 +106,48 @@ pub mod keys {
 			)
 		}
 	}
+
+	/// "…and N more from source" after rate limiting.
+	pub struct notifications_suppressed;
+	impl notifications_suppressed {
+		pub fn call(count: usize, origin: &str) -> Notification {
+			Notification::new(
+				&NOTIF_NOTIFICATIONS_SUPPRESSED,
+				format!("…and {count} more from {origin}"),
+			)
+		}
+	}
+
+	/// "Title: message" when a long operation succeeds.
+	pub struct progress_done;
+	impl progress_done {
+		pub fn call(title: &str, message: &str) -> Notification {
+			Notification::new(&NOTIF_PROGRESS_DONE, progress_message(title, message))
+		}
+	}
+
+	/// "Title: error" when a long operation fails.
+	pub struct progress_failed;
+	impl progress_failed {
+		pub fn call(title: &str, error: &str) -> Notification {
+			Notification::new(&NOTIF_PROGRESS_FAILED, progress_message(title, error))
+		}
+	}
+
+	/// "Title stalled" when a long operation stops reporting.
+	pub struct progress_stalled;
+	impl progress_stalled {
+		pub fn call(title: &str) -> Notification {
+			Notification::new(&NOTIF_PROGRESS_STALLED, format!("{title} stalled"))
+		}
+	}
+
+	/// Joins the title of a long operation with its closing message.
+	fn progress_message(title: &str, message: &str) -> String {
+		if message.is_empty() {
+			title.to_string()
+		} else {
+			format!("{title}: {message}")
+		}
+	}
 }
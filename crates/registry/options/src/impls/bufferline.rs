@@ -0,0 +1,11 @@
+//! Buffer line options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "bufferline", scope = global, validate = bufferline)]
+/// When to show the line of open buffers below the menu bar.
+///
+/// `on` always shows it, `auto` shows it only while more than one buffer
+/// is open, and `off` hides it.
+pub static BUFFERLINE: &'static str = "off";
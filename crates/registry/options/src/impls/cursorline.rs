@@ -1,4 +1,4 @@
-//! Cursorline display option.
+//! Cursorline and colorcolumn display options.
 
 use xeno_macro::derive_option;
 
@@ -6,3 +6,10 @@ use xeno_macro::derive_option;
 #[option(kdl = "cursorline", scope = buffer)]
 /// Whether to highlight the line containing the cursor.
 pub static CURSORLINE: bool = true;
+
+#[derive_option]
+#[option(kdl = "colorcolumn", scope = buffer, validate = colorcolumn)]
+/// Comma-separated screen columns to tint, e.g. `80,100`; empty for none.
+///
+/// Columns are 1-based and counted from the start of the text area.
+pub static COLORCOLUMN: &'static str = "";
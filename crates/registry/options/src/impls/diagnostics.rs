@@ -0,0 +1,12 @@
+//! Diagnostics display options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "diagnostics-display", scope = buffer, validate = diagnostics_display)]
+/// How language server diagnostics are drawn.
+///
+/// A comma-separated combination of `signs` (a gutter sign per line),
+/// `underline` (a curly underline under each range) and `inline` (the first
+/// message of a line after its end), or `off` to draw none of them.
+pub static DIAGNOSTICS_DISPLAY: &'static str = "signs,underline";
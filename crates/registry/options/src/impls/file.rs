@@ -0,0 +1,45 @@
+//! File format options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "line-ending", scope = buffer, validate = line_ending)]
+/// Line ending written when the buffer is saved.
+///
+/// One of `auto`, `lf`, or `crlf`. `auto` keeps the ending detected when the
+/// file was loaded; the others convert every line on the next save.
+pub static LINE_ENDING: &'static str = "auto";
+
+#[derive_option]
+#[option(kdl = "encoding", scope = buffer, validate = encoding)]
+/// Encoding used to open files that are not valid UTF-8.
+///
+/// `utf-8` replaces invalid sequences with U+FFFD and locks the buffer
+/// against edits (see `allow-lossy-write`); `latin-1` decodes every byte
+/// losslessly and re-encodes on save.
+pub static ENCODING: &'static str = "utf-8";
+
+#[derive_option]
+#[option(kdl = "allow-lossy-write", scope = buffer)]
+/// Allow editing and saving a buffer whose file had invalid UTF-8.
+///
+/// Saving writes the U+FFFD replacement characters, destroying the original
+/// bytes of every invalid sequence.
+pub static ALLOW_LOSSY_WRITE: bool = false;
+
+#[derive_option]
+#[option(kdl = "large-file-size", scope = global, validate = non_negative_int)]
+/// Size in MiB above which files open in large-file mode; 0 disables it.
+///
+/// Large files load in the background, so the editor is usable before the
+/// whole file is read.
+pub static LARGE_FILE_SIZE: i64 = 50;
+
+#[derive_option]
+#[option(kdl = "large-file", scope = buffer)]
+/// Degrade features for files over `large-file-size`.
+///
+/// While on, such files skip syntax highlighting, language servers, and
+/// search match counts, and keep a short undo history. Turning it off
+/// restores all but the language server connection.
+pub static LARGE_FILE: bool = true;
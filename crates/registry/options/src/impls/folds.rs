@@ -0,0 +1,18 @@
+//! Folding options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "folds", scope = buffer)]
+/// Whether to ask the language server where the buffer can fold.
+///
+/// Turning it off drops the folds, opening them all.
+pub static FOLDS: bool = true;
+
+#[derive_option]
+#[option(kdl = "fold-reveal", scope = buffer)]
+/// Whether moving the cursor into a closed fold opens it.
+///
+/// Otherwise the cursor is put on the fold's first line. Moving up and
+/// down steps over closed folds either way.
+pub static FOLD_REVEAL: bool = true;
@@ -0,0 +1,12 @@
+//! Gutter display options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "line-numbers", scope = buffer, validate = line_numbers)]
+/// Line numbers shown in the gutter.
+///
+/// `absolute` numbers every line, `relative` shows each line's distance from
+/// the cursor line, `hybrid` is relative except for the cursor line's
+/// absolute number, and `none` hides the column.
+pub static LINE_NUMBERS: &'static str = "absolute";
@@ -6,3 +6,21 @@ use xeno_macro::derive_option;
 #[option(kdl = "tab-width", scope = buffer, validate = positive_int)]
 /// Number of spaces a tab character occupies for display.
 pub static TAB_WIDTH: i64 = 4;
+
+#[derive_option]
+#[option(kdl = "use-tabs", scope = buffer)]
+/// Indent with tab characters instead of `tab-width` spaces.
+pub static USE_TABS: bool = false;
+
+#[derive_option]
+#[option(kdl = "autoindent", scope = buffer)]
+/// Copy the current line's indentation onto new lines.
+pub static AUTOINDENT: bool = true;
+
+#[derive_option]
+#[option(kdl = "smart-indent", scope = buffer)]
+/// Add an indent level after lines ending in an opening token.
+///
+/// Tokens come from the language's `indent-open-tokens`; only applies while
+/// `autoindent` is on.
+pub static SMART_INDENT: bool = true;
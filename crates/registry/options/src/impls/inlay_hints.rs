@@ -0,0 +1,15 @@
+//! Inlay hint options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "inlay-hints", scope = buffer)]
+/// Whether to show the inlay hints of the language server.
+///
+/// Toggle it with `:toggle inlay-hints`.
+pub static INLAY_HINTS: bool = true;
+
+#[derive_option]
+#[option(kdl = "inlay-hint-max-length", scope = buffer, validate = non_negative_int)]
+/// Characters after which an inlay hint label is cut short; 0 for no limit.
+pub static INLAY_HINT_MAX_LENGTH: i64 = 30;
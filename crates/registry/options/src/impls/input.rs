@@ -0,0 +1,21 @@
+//! Keyboard input options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "escape-timeout", scope = global, validate = non_negative_int)]
+/// Milliseconds to wait after a bare Escape for the rest of an Alt chord.
+///
+/// Terminals without the kitty keyboard protocol send Alt+key as Escape
+/// followed by the key. A key arriving within this window is read as the
+/// chord; otherwise Escape is delivered alone. Zero disables the wait, and
+/// it is skipped entirely when the kitty protocol is active.
+pub static ESCAPE_TIMEOUT: i64 = 5;
+
+#[derive_option]
+#[option(kdl = "pending-timeout", scope = global, validate = non_negative_int)]
+/// Milliseconds before a pending action (`f`, `r`, ...) gives up waiting
+/// for its character.
+///
+/// Zero waits indefinitely.
+pub static PENDING_TIMEOUT: i64 = 0;
@@ -0,0 +1,25 @@
+//! Language server options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "lsp-trace", scope = global, validate = lsp_trace)]
+/// How much of the traffic with language servers is logged to the LSP log.
+///
+/// `messages` logs the method and ID of every request, response and
+/// notification, with how long each request took; `verbose` also logs
+/// their params or result, cut short. `off` logs only the servers' stderr.
+pub static LSP_TRACE: &'static str = "off";
+
+#[derive_option]
+#[option(kdl = "document-highlight", scope = buffer)]
+/// Whether to highlight the other occurrences of the symbol under the
+/// cursor, as the language server finds them, once the cursor rests.
+pub static DOCUMENT_HIGHLIGHT: bool = true;
+
+#[derive_option]
+#[option(kdl = "semantic-highlighting", scope = buffer)]
+/// Whether to color the buffer with the language server's semantic tokens.
+///
+/// They are drawn over the syntax highlighting; turning it off drops them.
+pub static SEMANTIC_HIGHLIGHTING: bool = true;
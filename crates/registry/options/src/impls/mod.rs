@@ -1,6 +1,24 @@
 //! Standard library option implementations.
 
+pub(crate) mod bufferline;
 pub(crate) mod cursorline;
+pub(crate) mod diagnostics;
+pub(crate) mod file;
+pub(crate) mod folds;
+pub(crate) mod gutter;
 pub(crate) mod indent;
+pub(crate) mod inlay_hints;
+pub(crate) mod input;
+pub(crate) mod lsp;
+pub(crate) mod mouse;
+pub(crate) mod notifications;
+pub(crate) mod save;
 pub(crate) mod scroll;
+pub(crate) mod search;
+pub(crate) mod selection;
+pub(crate) mod terminal;
 pub(crate) mod theme;
+pub(crate) mod ui;
+pub(crate) mod undo;
+pub(crate) mod whitespace;
+pub(crate) mod wrap;
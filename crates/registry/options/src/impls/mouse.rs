@@ -0,0 +1,11 @@
+//! Mouse input option.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "mouse", scope = global)]
+/// Whether the editor captures the mouse.
+///
+/// When disabled, mouse capture is released so the terminal handles
+/// selection and scrolling itself.
+pub static MOUSE: bool = true;
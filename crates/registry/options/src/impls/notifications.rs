@@ -0,0 +1,41 @@
+//! Notification options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "notification-level", scope = global, validate = notification_level)]
+/// Least severe notification level that shows a toast.
+///
+/// One of `debug`, `info`, `success`, `warn`, or `error`. Notifications
+/// below it are still kept in the notification history.
+pub static NOTIFICATION_LEVEL: &'static str = "info";
+
+#[derive_option]
+#[option(kdl = "notification-rate-limit", scope = global, validate = non_negative_int)]
+/// Most toasts one source may show per second; 0 disables the limit.
+///
+/// Notifications past the limit are kept in the history and summed up in a
+/// single toast once the second is over.
+pub static NOTIFICATION_RATE_LIMIT: i64 = 5;
+
+#[derive_option]
+#[option(kdl = "notification-anchor", scope = global, validate = notification_anchor)]
+/// Corner new toasts stack from: `top-right`, `top-left`, `bottom-right`, or
+/// `bottom-left`.
+pub static NOTIFICATION_ANCHOR: &'static str = "top-right";
+
+#[derive_option]
+#[option(kdl = "notification-max-visible", scope = global, validate = non_negative_int)]
+/// Most toasts shown at once; the rest wait behind a `+N more` line.
+///
+/// 0 shows as many as fit.
+pub static NOTIFICATION_MAX_VISIBLE: i64 = 5;
+
+#[derive_option]
+#[option(kdl = "notify-desktop", scope = global, validate = notify_desktop)]
+/// Forwards error notifications while the terminal is unfocused.
+///
+/// - `off`: never
+/// - `on`: as a desktop notification, via OSC 777 or OSC 9
+/// - `bell`: as a terminal bell
+pub static NOTIFY_DESKTOP: &'static str = "off";
@@ -0,0 +1,50 @@
+//! Saving options: autosave, crash recovery, and the language server's
+//! edits and formatting on save.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "autosave", scope = global, validate = autosave)]
+/// When modified buffers are written without an explicit `:write`.
+///
+/// `off` never autosaves, `focus-lost` saves when the terminal loses focus,
+/// and `idle:<ms>` saves once no edit has been made for that many
+/// milliseconds. Autosaves run the same write hooks as `:write`.
+pub static AUTOSAVE: &'static str = "off";
+
+#[derive_option]
+#[option(kdl = "swap-interval", scope = global, validate = non_negative_int)]
+/// Milliseconds between swap file writes for modified buffers; 0 disables them.
+///
+/// Swap files live in the state directory and offer recovery of unsaved
+/// changes after a crash.
+pub static SWAP_INTERVAL: i64 = 4000;
+
+#[derive_option]
+#[option(kdl = "format-on-save", scope = buffer)]
+/// Format buffers with their language server before writing them.
+pub static FORMAT_ON_SAVE: bool = false;
+
+#[derive_option]
+#[option(kdl = "format-timeout", scope = global, validate = positive_int)]
+/// Milliseconds `format-on-save` waits for the language server.
+///
+/// A server that takes longer is skipped with a warning and the buffer is
+/// written unformatted.
+pub static FORMAT_TIMEOUT: i64 = 1000;
+
+#[derive_option]
+#[option(kdl = "will-save-wait-until", scope = buffer)]
+/// Apply the edits language servers ask for before buffers are written.
+///
+/// Servers that support `willSaveWaitUntil`, to organize imports for
+/// instance, get to edit the buffer first; `format-on-save` formats after.
+pub static WILL_SAVE_WAIT_UNTIL: bool = true;
+
+#[derive_option]
+#[option(kdl = "will-save-timeout", scope = global, validate = positive_int)]
+/// Milliseconds `will-save-wait-until` waits for the language server.
+///
+/// A server that takes longer is skipped with a warning and the buffer is
+/// written without its edits.
+pub static WILL_SAVE_TIMEOUT: i64 = 1000;
@@ -8,10 +8,19 @@ use xeno_macro::derive_option;
 pub static SCROLL_LINES: i64 = 2;
 
 #[derive_option]
-#[option(kdl = "scroll-margin", scope = buffer, validate = positive_int)]
-/// Minimum lines to keep above/below cursor when scrolling.
+#[option(kdl = "scrolloff", scope = buffer, validate = non_negative_int)]
+/// Minimum visual rows to keep above/below cursor when scrolling.
 ///
-/// When the cursor moves within this many lines of the viewport edge,
-/// the view scrolls to maintain the margin. At buffer boundaries, the
-/// cursor is allowed to reach the edge.
-pub static SCROLL_MARGIN: i64 = 5;
+/// When the cursor moves within this many rows of the viewport edge,
+/// the view scrolls to maintain the margin. Wrapped lines count once per
+/// row. At buffer boundaries, the cursor is allowed to reach the edge, and
+/// a value larger than half the viewport keeps the cursor centered.
+pub static SCROLLOFF: i64 = 5;
+
+#[derive_option]
+#[option(kdl = "sidescrolloff", scope = buffer, validate = non_negative_int)]
+/// Minimum columns to keep left/right of the cursor when scrolling
+/// horizontally.
+///
+/// Only applies while lines are not soft-wrapped.
+pub static SIDESCROLLOFF: i64 = 5;
@@ -0,0 +1,20 @@
+//! Search options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "search-case", scope = global, validate = search_case)]
+/// How letter case is matched when searching.
+///
+/// One of `sensitive`, `insensitive`, or `smart` (insensitive unless the
+/// pattern contains an uppercase letter). Inline `(?i)` and `(?-i)` flags in
+/// a pattern override the mode.
+pub static SEARCH_CASE: &'static str = "sensitive";
+
+#[derive_option]
+#[option(kdl = "search-backend", scope = global, validate = search_backend)]
+/// How the project search panel finds matches.
+///
+/// `internal` walks the working directory itself, skipping hidden and
+/// `.gitignore`d paths; `rg` runs ripgrep and reads its JSON output.
+pub static SEARCH_BACKEND: &'static str = "internal";
@@ -0,0 +1,10 @@
+//! Selection behavior options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "block-pad-short-lines", scope = buffer)]
+/// Whether block selections pad short lines with spaces.
+///
+/// When disabled, lines ending before the block's left edge are skipped.
+pub static BLOCK_PAD_SHORT_LINES: bool = false;
@@ -0,0 +1,16 @@
+//! Terminal panel options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "terminal-scrollback", scope = global, validate = non_negative_int)]
+/// Lines of output the terminal panel keeps above the screen.
+pub static TERMINAL_SCROLLBACK: i64 = 1000;
+
+#[derive_option]
+#[option(kdl = "terminal-escape", scope = global)]
+/// Key that returns focus from the terminal panel to the editor, written as
+/// in keybindings, such as `ctrl-\`.
+///
+/// Every other key is sent to the shell.
+pub static TERMINAL_ESCAPE: &'static str = "ctrl-\\";
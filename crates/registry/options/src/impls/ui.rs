@@ -0,0 +1,22 @@
+//! User interface options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "ui-animations", scope = global)]
+/// Whether popups fade in and out.
+///
+/// Fading blends colors, so it is skipped on terminals without true color
+/// support even when enabled.
+pub static UI_ANIMATIONS: bool = true;
+
+#[derive_option]
+#[option(kdl = "panels-restore", scope = global)]
+/// Whether restoring a session reopens its panels where they were docked.
+pub static PANELS_RESTORE: bool = true;
+
+#[derive_option]
+#[option(kdl = "explorer-hide-ignored", scope = global)]
+/// Whether the file explorer hides dotfiles and paths matched by
+/// `.gitignore` files.
+pub static EXPLORER_HIDE_IGNORED: bool = true;
@@ -0,0 +1,11 @@
+//! Undo history options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "undo-group-max-edits", scope = buffer, validate = positive_int)]
+/// Maximum number of edits collected into a single undo group.
+///
+/// Long insert sessions are split into separate undo steps once this many
+/// edits have been grouped together.
+pub static UNDO_GROUP_MAX_EDITS: i64 = 100;
@@ -0,0 +1,27 @@
+//! Whitespace display options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "show-whitespace", scope = buffer, validate = show_whitespace)]
+/// Which whitespace to draw with visible markers.
+///
+/// `trailing` marks only whitespace after the last non-blank character of
+/// a line, `all` marks every space, tab and non-breaking space, and `off`
+/// draws none.
+pub static SHOW_WHITESPACE: &'static str = "trailing";
+
+#[derive_option]
+#[option(kdl = "whitespace-tab", scope = buffer, validate = single_char)]
+/// Marker drawn in the first cell of a visible tab.
+pub static WHITESPACE_TAB: &'static str = "→";
+
+#[derive_option]
+#[option(kdl = "whitespace-space", scope = buffer, validate = single_char)]
+/// Marker drawn for a visible space.
+pub static WHITESPACE_SPACE: &'static str = "·";
+
+#[derive_option]
+#[option(kdl = "whitespace-nbsp", scope = buffer, validate = single_char)]
+/// Marker drawn for a visible non-breaking space.
+pub static WHITESPACE_NBSP: &'static str = "⍽";
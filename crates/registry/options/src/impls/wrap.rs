@@ -0,0 +1,31 @@
+//! Soft wrap options.
+
+use xeno_macro::derive_option;
+
+#[derive_option]
+#[option(kdl = "wrap", scope = buffer)]
+/// Whether long lines soft-wrap onto continuation rows.
+///
+/// When off, long lines are clipped at the view edge and the view scrolls
+/// horizontally to follow the cursor, keeping `sidescrolloff` columns
+/// around it. Large-file mode always turns wrapping off.
+pub static WRAP: bool = true;
+
+#[derive_option]
+#[option(kdl = "wrap-indent", scope = buffer)]
+/// Whether continuation rows of a wrapped line repeat its leading whitespace.
+///
+/// Lines indented so deeply that the indent would leave less than half the
+/// row for text fall back to no indent.
+pub static WRAP_INDENT: bool = true;
+
+#[derive_option]
+#[option(kdl = "wrap-indent-extra", scope = buffer, validate = non_negative_int)]
+/// Extra cells of indent added to continuation rows of a wrapped line.
+pub static WRAP_INDENT_EXTRA: i64 = 0;
+
+#[derive_option]
+#[option(kdl = "wrap-marker", scope = buffer)]
+/// Marker drawn at the start of continuation rows of a wrapped line, after
+/// the indent. Empty draws no marker.
+pub static WRAP_MARKER: &'static str = "↪ ";
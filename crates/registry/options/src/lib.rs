@@ -107,10 +107,27 @@ pub use store::OptionStore;
 /// println!("Default tab width: {:?}", (def.default)());
 /// ```
 pub mod keys {
+	pub use crate::impls::bufferline::*;
 	pub use crate::impls::cursorline::*;
+	pub use crate::impls::diagnostics::*;
+	pub use crate::impls::file::*;
+	pub use crate::impls::folds::*;
+	pub use crate::impls::gutter::*;
 	pub use crate::impls::indent::*;
+	pub use crate::impls::inlay_hints::*;
+	pub use crate::impls::input::*;
+	pub use crate::impls::lsp::*;
+	pub use crate::impls::mouse::*;
+	pub use crate::impls::save::*;
 	pub use crate::impls::scroll::*;
+	pub use crate::impls::search::*;
+	pub use crate::impls::selection::*;
+	pub use crate::impls::terminal::*;
 	pub use crate::impls::theme::*;
+	pub use crate::impls::ui::*;
+	pub use crate::impls::undo::*;
+	pub use crate::impls::whitespace::*;
+	pub use crate::impls::wrap::*;
 }
 
 pub use xeno_registry_core::{Key, RegistryMetadata, RegistrySource, impl_registry_metadata};
@@ -95,13 +95,8 @@ pub fn suggest_option(key: &str) -> Option<String> {
 const REMOVED_OPTIONS: &[&str] = &[
 	"indent-width",
 	"use-tabs",
-	"line-numbers",
-	"wrap-lines",
-	"cursorline",
 	"cursorcolumn",
-	"colorcolumn",
 	"whitespace-visible",
-	"scroll-margin",
 	"scroll-smooth",
 	"backup",
 	"undo-file",
@@ -113,7 +108,6 @@ const REMOVED_OPTIONS: &[&str] = &[
 	"search-wrap",
 	"incremental-search",
 	"mouse",
-	"line-ending",
 	"idle-timeout",
 ];
 
@@ -13,6 +13,203 @@ pub fn positive_int(value: &OptionValue) -> Result<(), String> {
 	}
 }
 
+/// Validates that an integer is not negative (>= 0).
+pub fn non_negative_int(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::Int(n) if *n >= 0 => Ok(()),
+		OptionValue::Int(n) => Err(format!("must not be negative, got {n}")),
+		_ => Err("expected integer".to_string()),
+	}
+}
+
+/// Validates a `search-case` mode name.
+pub fn search_case(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "sensitive" | "insensitive" | "smart") => {
+			Ok(())
+		}
+		OptionValue::String(s) => Err(format!(
+			"expected one of sensitive, insensitive, smart; got '{s}'"
+		)),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `search-backend` name.
+pub fn search_backend(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "internal" | "rg") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of internal, rg; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `notification-level` name.
+pub fn notification_level(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s)
+			if matches!(s.as_str(), "debug" | "info" | "success" | "warn" | "error") =>
+		{
+			Ok(())
+		}
+		OptionValue::String(s) => Err(format!(
+			"expected one of debug, info, success, warn, error; got '{s}'"
+		)),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `notification-anchor` corner.
+pub fn notification_anchor(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s)
+			if matches!(
+				s.as_str(),
+				"top-right" | "top-left" | "bottom-right" | "bottom-left"
+			) =>
+		{
+			Ok(())
+		}
+		OptionValue::String(s) => Err(format!(
+			"expected one of top-right, top-left, bottom-right, bottom-left; got '{s}'"
+		)),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `notify-desktop` mode.
+pub fn notify_desktop(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "off" | "on" | "bell") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of off, on, bell; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `line-ending` name.
+pub fn line_ending(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "auto" | "lf" | "crlf") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of auto, lf, crlf; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates an `encoding` name.
+pub fn encoding(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "utf-8" | "latin-1") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of utf-8, latin-1; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `line-numbers` mode.
+pub fn line_numbers(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s)
+			if matches!(s.as_str(), "absolute" | "relative" | "hybrid" | "none") =>
+		{
+			Ok(())
+		}
+		OptionValue::String(s) => Err(format!(
+			"expected one of absolute, relative, hybrid, none; got '{s}'"
+		)),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `show-whitespace` mode.
+pub fn show_whitespace(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "off" | "trailing" | "all") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of off, trailing, all; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates that a string is a single printable character.
+pub fn single_char(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) => {
+			let mut chars = s.chars();
+			match (chars.next(), chars.next()) {
+				(Some(c), None) if !c.is_control() => Ok(()),
+				_ => Err(format!("expected a single character, got '{s}'")),
+			}
+		}
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `colorcolumn` list: comma-separated positive column numbers.
+pub fn colorcolumn(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) => s
+			.split(',')
+			.map(str::trim)
+			.filter(|col| !col.is_empty())
+			.try_for_each(|col| match col.parse::<u32>() {
+				Ok(n) if n >= 1 => Ok(()),
+				_ => Err(format!(
+					"expected comma-separated columns like 80,100; got '{s}'"
+				)),
+			}),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates an `autosave` mode: `off`, `focus-lost`, or `idle:<ms>`.
+pub fn autosave(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "off" | "focus-lost") => Ok(()),
+		OptionValue::String(s) => match s.strip_prefix("idle:").map(str::parse::<u64>) {
+			Some(Ok(ms)) if ms > 0 => Ok(()),
+			_ => Err(format!(
+				"expected one of off, focus-lost, idle:<ms>; got '{s}'"
+			)),
+		},
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `bufferline` mode.
+pub fn bufferline(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "off" | "on" | "auto") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of off, on, auto; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates a `diagnostics-display` list: `off`, or a comma-separated
+/// combination of `signs`, `underline` and `inline`.
+pub fn diagnostics_display(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if s == "off" => Ok(()),
+		OptionValue::String(s) => {
+			let mut parts = s.split(',').map(str::trim);
+			if parts.all(|part| matches!(part, "signs" | "underline" | "inline")) {
+				Ok(())
+			} else {
+				Err(format!(
+					"expected off or a combination of signs, underline, inline; got '{s}'"
+				))
+			}
+		}
+		_ => Err("expected string".to_string()),
+	}
+}
+
+/// Validates an `lsp-trace` level.
+pub fn lsp_trace(value: &OptionValue) -> Result<(), String> {
+	match value {
+		OptionValue::String(s) if matches!(s.as_str(), "off" | "messages" | "verbose") => Ok(()),
+		OptionValue::String(s) => Err(format!("expected one of off, messages, verbose; got '{s}'")),
+		_ => Err("expected string".to_string()),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -25,4 +222,120 @@ mod tests {
 		assert!(positive_int(&OptionValue::Int(-1)).is_err());
 		assert!(positive_int(&OptionValue::String("foo".into())).is_err());
 	}
+
+	#[test]
+	fn test_non_negative_int() {
+		assert!(non_negative_int(&OptionValue::Int(0)).is_ok());
+		assert!(non_negative_int(&OptionValue::Int(25)).is_ok());
+		assert!(non_negative_int(&OptionValue::Int(-1)).is_err());
+		assert!(non_negative_int(&OptionValue::Bool(false)).is_err());
+	}
+
+	#[test]
+	fn test_search_case() {
+		assert!(search_case(&OptionValue::String("smart".into())).is_ok());
+		assert!(search_case(&OptionValue::String("insensitive".into())).is_ok());
+		assert!(search_case(&OptionValue::String("Smart".into())).is_err());
+		assert!(search_case(&OptionValue::Bool(true)).is_err());
+	}
+
+	#[test]
+	fn test_search_backend() {
+		assert!(search_backend(&OptionValue::String("rg".into())).is_ok());
+		assert!(search_backend(&OptionValue::String("grep".into())).is_err());
+	}
+
+	#[test]
+	fn test_notification_level() {
+		assert!(notification_level(&OptionValue::String("warn".into())).is_ok());
+		assert!(notification_level(&OptionValue::String("warning".into())).is_err());
+	}
+
+	#[test]
+	fn test_notification_anchor() {
+		assert!(notification_anchor(&OptionValue::String("bottom-left".into())).is_ok());
+		assert!(notification_anchor(&OptionValue::String("center".into())).is_err());
+	}
+
+	#[test]
+	fn test_notify_desktop() {
+		assert!(notify_desktop(&OptionValue::String("bell".into())).is_ok());
+		assert!(notify_desktop(&OptionValue::String("yes".into())).is_err());
+	}
+
+	#[test]
+	fn test_line_ending() {
+		assert!(line_ending(&OptionValue::String("crlf".into())).is_ok());
+		assert!(line_ending(&OptionValue::String("auto".into())).is_ok());
+		assert!(line_ending(&OptionValue::String("CRLF".into())).is_err());
+		assert!(line_ending(&OptionValue::Int(1)).is_err());
+	}
+
+	#[test]
+	fn test_encoding() {
+		assert!(encoding(&OptionValue::String("latin-1".into())).is_ok());
+		assert!(encoding(&OptionValue::String("utf-16".into())).is_err());
+	}
+
+	#[test]
+	fn test_autosave() {
+		assert!(autosave(&OptionValue::String("off".into())).is_ok());
+		assert!(autosave(&OptionValue::String("focus-lost".into())).is_ok());
+		assert!(autosave(&OptionValue::String("idle:1500".into())).is_ok());
+		assert!(autosave(&OptionValue::String("idle:0".into())).is_err());
+		assert!(autosave(&OptionValue::String("idle:".into())).is_err());
+		assert!(autosave(&OptionValue::String("focus_lost".into())).is_err());
+	}
+
+	#[test]
+	fn test_line_numbers() {
+		assert!(line_numbers(&OptionValue::String("hybrid".into())).is_ok());
+		assert!(line_numbers(&OptionValue::String("none".into())).is_ok());
+		assert!(line_numbers(&OptionValue::String("on".into())).is_err());
+	}
+
+	#[test]
+	fn test_show_whitespace() {
+		assert!(show_whitespace(&OptionValue::String("trailing".into())).is_ok());
+		assert!(show_whitespace(&OptionValue::String("all".into())).is_ok());
+		assert!(show_whitespace(&OptionValue::String("some".into())).is_err());
+	}
+
+	#[test]
+	fn test_bufferline() {
+		assert!(bufferline(&OptionValue::String("auto".into())).is_ok());
+		assert!(bufferline(&OptionValue::String("always".into())).is_err());
+	}
+
+	#[test]
+	fn test_single_char() {
+		assert!(single_char(&OptionValue::String("→".into())).is_ok());
+		assert!(single_char(&OptionValue::String(".".into())).is_ok());
+		assert!(single_char(&OptionValue::String("".into())).is_err());
+		assert!(single_char(&OptionValue::String("->".into())).is_err());
+		assert!(single_char(&OptionValue::String("	".into())).is_err());
+	}
+
+	#[test]
+	fn test_colorcolumn() {
+		assert!(colorcolumn(&OptionValue::String("".into())).is_ok());
+		assert!(colorcolumn(&OptionValue::String("80, 100".into())).is_ok());
+		assert!(colorcolumn(&OptionValue::String("0".into())).is_err());
+		assert!(colorcolumn(&OptionValue::String("80,wide".into())).is_err());
+		assert!(colorcolumn(&OptionValue::Int(80)).is_err());
+	}
+
+	#[test]
+	fn test_diagnostics_display() {
+		assert!(diagnostics_display(&OptionValue::String("off".into())).is_ok());
+		assert!(diagnostics_display(&OptionValue::String("signs, inline".into())).is_ok());
+		assert!(diagnostics_display(&OptionValue::String("".into())).is_err());
+		assert!(diagnostics_display(&OptionValue::String("signs,off".into())).is_err());
+	}
+
+	#[test]
+	fn test_lsp_trace() {
+		assert!(lsp_trace(&OptionValue::String("verbose".into())).is_ok());
+		assert!(lsp_trace(&OptionValue::String("on".into())).is_err());
+	}
 }
@@ -28,6 +28,7 @@ pub use actions::editor_ctx::{
 	CommandQueueAccess, CursorAccess, EditAccess, EditorCapabilities, EditorContext, EditorOps,
 	FileOpsAccess, FocusOps, HandleOutcome, JumpAccess, MacroAccess, NotificationAccess,
 	ResultHandler, SearchAccess, SelectionAccess, SplitOps, TextAccess, ThemeAccess, UndoAccess,
+	UndoNodeInfo,
 };
 pub use actions::{
 	ACTIONS, ActionArgs, ActionContext, ActionDef, ActionEffects, ActionHandler, ActionResult,
@@ -44,8 +45,8 @@ pub use commands::{
 };
 pub use gutter::{
 	GUTTERS, GutterAnnotations, GutterCell, GutterDef, GutterLineContext, GutterStyle, GutterWidth,
-	GutterWidthContext, all as all_gutters, column_width, column_widths, enabled_gutters,
-	find as find_gutter, gutter, total_width as gutter_total_width,
+	GutterWidthContext, LineNumbers, active_gutters, all as all_gutters, column_width,
+	column_widths, enabled_gutters, find as find_gutter, gutter, total_width as gutter_total_width,
 };
 pub use hooks::{
 	Bool, BoxFuture, HOOKS, HookAction, HookContext, HookDef, HookEvent, HookEventData,
@@ -60,20 +61,24 @@ pub use notifications::{
 	NotificationKey, keys as notification_keys,
 };
 pub use statusline::{
-	RenderedSegment, STATUSLINE_SEGMENTS, SegmentPosition, SegmentStyle, StatuslineContext,
-	StatuslineSegmentDef, all_segments, find_segment, render_position, segments_for_position,
-	statusline_segment,
+	LspServerHealth, LspServerStatus, RenderedSegment, STATUSLINE_SEGMENTS, SegmentPosition,
+	SegmentStyle, StatuslineContext, StatuslineSegmentDef, all_segments, find_segment,
+	render_position, segments_for_position, statusline_segment,
 };
 pub use text_objects::{
 	TEXT_OBJECTS, TextObjectDef, TextObjectHandler, bracket_pair_object, symmetric_text_object,
 	text_object,
 };
+pub use xeno_registry_actions as actions;
+pub use xeno_registry_commands as commands;
 // Re-export shared types from registry core (canonical source)
 pub use xeno_registry_core::{RegistryMetadata, RegistrySource, impl_registry_metadata};
-pub use {
-	xeno_registry_actions as actions, xeno_registry_commands as commands,
-	xeno_registry_gutter as gutter, xeno_registry_hooks as hooks, xeno_registry_menus as menus,
-	xeno_registry_motions as motions, xeno_registry_notifications as notifications,
-	xeno_registry_options as options, xeno_registry_statusline as statusline,
-	xeno_registry_text_objects as text_objects, xeno_registry_themes as themes,
-};
+pub use xeno_registry_gutter as gutter;
+pub use xeno_registry_hooks as hooks;
+pub use xeno_registry_menus as menus;
+pub use xeno_registry_motions as motions;
+pub use xeno_registry_notifications as notifications;
+pub use xeno_registry_options as options;
+pub use xeno_registry_statusline as statusline;
+pub use xeno_registry_text_objects as text_objects;
+pub use xeno_registry_themes as themes;
@@ -0,0 +1,19 @@
+//! Line ending indicator segment.
+//!
+//! Only shown for CRLF, since LF is the default.
+
+use crate::{RenderedSegment, SegmentPosition, SegmentStyle, statusline_segment};
+
+statusline_segment!(
+	SEG_LINE_ENDING,
+	"line-ending",
+	SegmentPosition::Right,
+	15,
+	true,
+	|ctx| {
+		(ctx.line_ending == "crlf").then(|| RenderedSegment {
+			text: " CRLF ".to_string(),
+			style: SegmentStyle::Dim,
+		})
+	}
+);
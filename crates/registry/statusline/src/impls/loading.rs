@@ -0,0 +1,17 @@
+//! File loading progress segment.
+
+use crate::{RenderedSegment, SegmentPosition, SegmentStyle, statusline_segment};
+
+statusline_segment!(
+	SEG_LOADING,
+	"loading",
+	SegmentPosition::Right,
+	25,
+	true,
+	|ctx| {
+		ctx.loading.map(|percent| RenderedSegment {
+			text: format!(" loading {percent}% "),
+			style: SegmentStyle::Warning,
+		})
+	}
+);
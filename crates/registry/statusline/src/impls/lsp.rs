@@ -0,0 +1,34 @@
+//! Language server segment.
+//!
+//! Lists the servers attached to the buffer in the order requests go to
+//! them, marking those not running. The segment takes the style of the
+//! worst off server.
+
+use crate::{LspServerHealth, RenderedSegment, SegmentPosition, SegmentStyle, statusline_segment};
+
+statusline_segment!(SEG_LSP, "lsp", SegmentPosition::Right, 12, true, |ctx| {
+	if ctx.lsp_servers.is_empty() {
+		return None;
+	}
+	let names: Vec<_> = ctx
+		.lsp_servers
+		.iter()
+		.map(|server| match server.health {
+			LspServerHealth::Running => server.name.clone(),
+			LspServerHealth::Starting => format!("{} (starting)", server.name),
+			LspServerHealth::Exited => format!("{} (exited)", server.name),
+		})
+		.collect();
+	let health = |h| ctx.lsp_servers.iter().any(|s| s.health == h);
+	let style = if health(LspServerHealth::Exited) {
+		SegmentStyle::Error
+	} else if health(LspServerHealth::Starting) {
+		SegmentStyle::Warning
+	} else {
+		SegmentStyle::Dim
+	};
+	Some(RenderedSegment {
+		text: format!(" {} ", names.join(", ")),
+		style,
+	})
+});
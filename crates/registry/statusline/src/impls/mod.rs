@@ -3,7 +3,12 @@
 mod count;
 mod file;
 mod filetype;
+mod line_ending;
+mod loading;
+mod lsp;
 mod mode;
+mod pending;
 mod position;
 mod progress;
 mod readonly;
+mod search;
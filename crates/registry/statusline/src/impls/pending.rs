@@ -0,0 +1,17 @@
+//! Pending action prompt segment.
+
+use crate::{RenderedSegment, SegmentPosition, SegmentStyle, statusline_segment};
+
+statusline_segment!(
+	SEG_PENDING,
+	"pending",
+	SegmentPosition::Left,
+	5,
+	true,
+	|ctx| {
+		ctx.pending_prompt.map(|prompt| RenderedSegment {
+			text: format!(" {prompt} "),
+			style: SegmentStyle::Warning,
+		})
+	}
+);
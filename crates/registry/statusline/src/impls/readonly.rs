@@ -11,7 +11,7 @@ statusline_segment!(
 	|ctx| {
 		if ctx.readonly {
 			Some(RenderedSegment {
-				text: " READ-ONLY ".to_string(),
+				text: " \u{1F512} RO ".to_string(),
 				style: SegmentStyle::Warning,
 			})
 		} else {
@@ -0,0 +1,25 @@
+//! Search match count segment.
+
+use crate::{RenderedSegment, SegmentPosition, SegmentStyle, statusline_segment};
+
+statusline_segment!(
+	SEG_SEARCH,
+	"search",
+	SegmentPosition::Right,
+	30,
+	true,
+	|ctx| {
+		ctx.search_matches.map(|(current, total)| {
+			// The selection has moved off every match since the last search.
+			let current = if current == 0 {
+				"-".to_string()
+			} else {
+				current.to_string()
+			};
+			RenderedSegment {
+				text: format!(" {current}/{total} {} ", ctx.search_case),
+				style: SegmentStyle::Dim,
+			}
+		})
+	}
+);
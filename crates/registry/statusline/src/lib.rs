@@ -24,7 +24,7 @@ pub enum SegmentPosition {
 
 /// Context provided to statusline segment renderers.
 pub struct StatuslineContext<'a> {
-	/// Current mode name.
+	/// Current mode name, or the title of the focused panel.
 	pub mode_name: &'a str,
 	/// File path being edited.
 	pub path: Option<&'a str>,
@@ -38,14 +38,47 @@ pub struct StatuslineContext<'a> {
 	pub col: usize,
 	/// Numeric count prefix (0 if not specified).
 	pub count: u32,
+	/// Prompt of the pending action waiting for a key, if any.
+	pub pending_prompt: Option<&'a str>,
 	/// Total lines in document.
 	pub total_lines: usize,
 	/// File type name if detected.
 	pub file_type: Option<&'a str>,
+	/// Line ending the next save writes (`lf` or `crlf`).
+	pub line_ending: &'a str,
+	/// Percentage of the file loaded, while it is still loading.
+	pub loading: Option<u8>,
 	/// Current buffer index (1-indexed).
 	pub buffer_index: usize,
 	/// Total number of open buffers.
 	pub buffer_count: usize,
+	/// Current match (1-based) and total matches for the last search, if any.
+	pub search_matches: Option<(usize, usize)>,
+	/// Case mode of the last search (`sensitive`, `insensitive`, or `smart`).
+	pub search_case: &'a str,
+	/// Language servers attached to the buffer, in the order requests go to
+	/// them.
+	pub lsp_servers: &'a [LspServerStatus],
+}
+
+/// How a language server attached to the buffer is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LspServerHealth {
+	/// Started but not yet initialized.
+	Starting,
+	/// Initialized and running.
+	Running,
+	/// The server process has exited.
+	Exited,
+}
+
+/// A language server attached to the buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspServerStatus {
+	/// Name the server is known by.
+	pub name: String,
+	/// How the server is doing.
+	pub health: LspServerHealth,
 }
 
 /// A rendered segment with styling information.
@@ -52,6 +52,14 @@ pub struct UiColors {
 	pub message_fg: Color,
 	/// Command input line foreground color.
 	pub command_input_fg: Color,
+	/// Background of search matches visible in the viewport.
+	pub search_match_bg: Color,
+	/// Background of the occurrences of the symbol under the cursor that
+	/// read it.
+	pub document_highlight_bg: Color,
+	/// Background of the occurrences of the symbol under the cursor that
+	/// write to it.
+	pub document_highlight_write_bg: Color,
 }
 
 /// Status line color definitions per mode.
@@ -99,6 +107,27 @@ pub struct PopupColors {
 	pub border: Color,
 	/// Popup title color.
 	pub title: Color,
+	/// Foreground patched over cells under a popup's drop shadow.
+	pub shadow: Color,
+	/// Default horizontal padding between a popup's border and its content, in cells.
+	pub padding: u16,
+}
+
+/// Diff and patch colors.
+#[derive(Clone, Copy, Debug)]
+pub struct DiffColors {
+	/// Foreground of added lines.
+	pub added_fg: Color,
+	/// Background of added lines.
+	pub added_bg: Color,
+	/// Foreground of removed lines.
+	pub removed_fg: Color,
+	/// Background of removed lines.
+	pub removed_bg: Color,
+	/// Foreground of unchanged context lines.
+	pub context_fg: Color,
+	/// Foreground of hunk headers.
+	pub header_fg: Color,
 }
 
 /// Per-semantic-style color pair for notifications.
@@ -156,6 +185,8 @@ pub struct ThemeColors {
 	pub popup: PopupColors,
 	/// Notification color overrides.
 	pub notification: NotificationColors,
+	/// Diff and patch colors.
+	pub diff: DiffColors,
 	/// Syntax highlighting styles.
 	pub syntax: SyntaxStyles,
 }
@@ -299,6 +330,9 @@ pub static DEFAULT_THEME: Theme = Theme {
 			selection_fg: Color::White,
 			message_fg: Color::Yellow,
 			command_input_fg: Color::White,
+			search_match_bg: Color::Magenta,
+			document_highlight_bg: Color::DarkGray,
+			document_highlight_write_bg: Color::Blue,
 		},
 		status: StatusColors {
 			normal_bg: Color::Blue,
@@ -321,8 +355,18 @@ pub static DEFAULT_THEME: Theme = Theme {
 			fg: Color::White,
 			border: Color::White,
 			title: Color::Yellow,
+			shadow: Color::DarkGray,
+			padding: 1,
 		},
 		notification: NotificationColors::INHERITED,
+		diff: DiffColors {
+			added_fg: Color::Green,
+			added_bg: Color::Reset,
+			removed_fg: Color::Red,
+			removed_bg: Color::Reset,
+			context_fg: Color::Reset,
+			header_fg: Color::Cyan,
+		},
 		syntax: SyntaxStyles::minimal(),
 	},
 	priority: 0,
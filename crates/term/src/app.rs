@@ -5,6 +5,7 @@ use termina::escape::csi::{Csi, Cursor};
 use termina::event::{Event, KeyEventKind};
 use termina::{PlatformTerminal, Terminal as _};
 use xeno_api::Editor;
+use xeno_registry::options::keys;
 use xeno_registry::{
 	HookContext, HookEventData, emit as emit_hook, emit_sync_with as emit_hook_sync_with,
 };
@@ -37,15 +38,18 @@ impl Default for RenderTiming {
 	}
 }
 use crate::terminal::{
-	coalesce_resize_events, cursor_style_for_mode, disable_terminal_features,
-	enable_terminal_features, install_panic_hook,
+	EscapeFollowup, coalesce_resize_events, cursor_style_for_mode, detect_terminal_config,
+	disable_terminal_features_with_config, enable_terminal_features_with_config,
+	install_panic_hook_with_config, is_bare_escape, resolve_escape, set_mouse_capture,
+	write_desktop_alert,
 };
 
 /// Runs the editor main loop.
 pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 	let mut terminal = PlatformTerminal::new()?;
-	install_panic_hook(&mut terminal);
-	enable_terminal_features(&mut terminal)?;
+	let config = detect_terminal_config(&mut terminal)?;
+	install_panic_hook_with_config(&mut terminal, config);
+	enable_terminal_features_with_config(&mut terminal, config)?;
 	let events = terminal.event_reader();
 	let timing = RenderTiming::detect();
 
@@ -60,6 +64,8 @@ pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 	);
 
 	let result: io::Result<()> = async {
+		let mut mouse_capture = true;
+		let mut pending_event = None;
 		loop {
 			editor.ui_tick();
 			editor.tick();
@@ -68,11 +74,22 @@ pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 			if editor.drain_command_queue().await {
 				break;
 			}
+			editor.run_autosave().await;
 
 			if editor.take_quit_request() {
 				break;
 			}
 
+			let mouse_enabled = editor.option(keys::MOUSE);
+			if mouse_enabled != mouse_capture {
+				set_mouse_capture(terminal.backend_mut().terminal_mut(), mouse_enabled)?;
+				mouse_capture = mouse_enabled;
+			}
+
+			for alert in editor.take_desktop_alerts() {
+				write_desktop_alert(terminal.backend_mut().terminal_mut(), config, &alert)?;
+			}
+
 			terminal.draw(|frame| editor.render(frame))?;
 
 			// Priority: UI panel > editor mode
@@ -101,16 +118,34 @@ pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 				Some(timing.slow)
 			};
 
-			let has_event = match timeout {
-				Some(t) => events.poll(Some(t), &mut filter)?,
-				None => true,
-			};
+			let event = match pending_event.take() {
+				Some(event) => event,
+				None => {
+					let has_event = match timeout {
+						Some(t) => events.poll(Some(t), &mut filter)?,
+						None => true,
+					};
 
-			if !has_event {
-				continue;
-			}
+					if !has_event {
+						continue;
+					}
+
+					events.read(&mut filter)?
+				}
+			};
 
-			let event = events.read(&mut filter)?;
+			let event = if !config.kitty_keyboard() && is_bare_escape(&event) {
+				let wait = editor.option(keys::ESCAPE_TIMEOUT).max(0) as u64;
+				match resolve_escape(&events, Duration::from_millis(wait), &mut filter)? {
+					EscapeFollowup::Chord(chord) => Event::Key(chord),
+					EscapeFollowup::Alone(next) => {
+						pending_event = next;
+						event
+					}
+				}
+			} else {
+				event
+			};
 
 			match event {
 				Event::Key(key)
@@ -145,6 +180,10 @@ pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 	}
 	.await;
 
+	if result.is_ok() {
+		editor.remove_session_swap_files();
+	}
+
 	emit_hook(&HookContext::new(
 		HookEventData::EditorQuit,
 		Some(&editor.extensions),
@@ -152,7 +191,7 @@ pub async fn run_editor(mut editor: Editor) -> io::Result<()> {
 	.await;
 
 	let terminal_inner = terminal.backend_mut().terminal_mut();
-	let cleanup_result = disable_terminal_features(terminal_inner);
+	let cleanup_result = disable_terminal_features_with_config(terminal_inner, config);
 
 	result.and(cleanup_result)
 }
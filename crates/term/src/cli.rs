@@ -1,3 +1,4 @@
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
@@ -10,13 +11,26 @@ use xeno_api::styles::cli_styles;
 #[command(styles = cli_styles())]
 /// Command-line arguments.
 pub struct Cli {
-	/// File to edit (opens scratch buffer if omitted)
-	pub file: Option<PathBuf>,
+	/// Files to edit, each optionally preceded by `+N` to start at line N
+	/// (opens scratch buffer if omitted)
+	pub files: Vec<String>,
+
+	/// Files listed after `--`, taken literally even if they look like `+N`
+	#[arg(last = true)]
+	pub literal_files: Vec<PathBuf>,
 
 	/// Color theme to use (e.g., gruvbox, monokai, debug)
 	#[arg(long, short = 't')]
 	pub theme: Option<String>,
 
+	/// Restore a session saved with `:mksession <name>`
+	#[arg(long)]
+	pub session: Option<String>,
+
+	/// Open the named files read-only
+	#[arg(long, short = 'R')]
+	pub read_only: bool,
+
 	/// Launch xeno in a new terminal and show logs in this terminal
 	#[arg(long)]
 	pub log_launch: bool,
@@ -26,6 +40,42 @@ pub struct Cli {
 	pub command: Option<Command>,
 }
 
+impl Cli {
+	/// Returns the files to open, each with the 1-based line to start at.
+	///
+	/// A `+N` argument applies to the file that follows it, and `-` (which
+	/// names stdin) is left out.
+	pub fn file_args(&self) -> Vec<(PathBuf, Option<usize>)> {
+		let mut files = Vec::new();
+		let mut line = None;
+		for arg in &self.files {
+			match arg.strip_prefix('+').map(str::parse::<usize>) {
+				_ if arg == STDIN_ARG => {}
+				Some(Ok(n)) => line = Some(n),
+				_ => files.push((PathBuf::from(arg), line.take())),
+			}
+		}
+		for path in &self.literal_files {
+			files.push((path.clone(), line.take()));
+		}
+		files
+	}
+
+	/// Returns whether to read piped input into a buffer.
+	///
+	/// That is when `-` is given, or when stdin is not a terminal and no
+	/// files are named (so `xargs xeno` does not pick up its empty stdin).
+	pub fn reads_stdin(&self) -> bool {
+		self.files.iter().any(|arg| arg == STDIN_ARG)
+			|| (self.files.is_empty()
+				&& self.literal_files.is_empty()
+				&& !std::io::stdin().is_terminal())
+	}
+}
+
+/// File argument that stands for standard input.
+const STDIN_ARG: &str = "-";
+
 /// Available subcommands.
 #[derive(Subcommand, Debug)]
 pub enum Command {
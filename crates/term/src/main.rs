@@ -4,12 +4,12 @@ mod app;
 mod backend;
 mod cli;
 mod log_launcher;
+mod stdin;
 mod terminal;
 #[cfg(test)]
 mod tests;
 
 use std::ffi::OsStr;
-use std::path::PathBuf;
 
 use app::run_editor;
 use clap::Parser;
@@ -80,10 +80,7 @@ async fn main() -> anyhow::Result<()> {
 		None
 	};
 
-	let mut editor = match cli.file {
-		Some(path) => Editor::new(path).await?,
-		None => Editor::new_scratch(),
-	};
+	let mut editor = open_editor(&cli).await?;
 
 	editor.extensions.insert(AcpManager::new());
 	configure_lsp_servers(&mut editor);
@@ -119,7 +116,15 @@ async fn main() -> anyhow::Result<()> {
 		}
 	}
 
-	// CLI theme flag overrides config
+	if let Some(name) = &cli.session {
+		if let Err(e) = editor.load_session(Some(name)).await {
+			eprintln!("Warning: failed to load session '{name}': {e:#}");
+		}
+	} else if cli.file_args().is_empty() && !cli.reads_stdin() {
+		editor.offer_session_restore();
+	}
+
+	// CLI theme flag overrides config and session
 	if let Some(theme_name) = cli.theme
 		&& let Err(e) = editor.set_theme(&theme_name)
 	{
@@ -130,6 +135,28 @@ async fn main() -> anyhow::Result<()> {
 	Ok(())
 }
 
+/// Creates the editor with the buffers named on the command line.
+///
+/// Piped input is read before the terminal enters raw mode and opens in an
+/// unnamed buffer ahead of any files. With `--read-only`, every buffer opened
+/// here is marked read-only.
+async fn open_editor(cli: &Cli) -> anyhow::Result<Editor> {
+	let files = cli.file_args();
+	let mut editor = if cli.reads_stdin() {
+		Editor::from_piped(stdin::read_piped_stdin()?)
+	} else {
+		match files.first() {
+			Some((path, _)) => Editor::new(path.clone()).await?,
+			None => Editor::new_scratch(),
+		}
+	};
+	editor.open_files(files).await;
+	if cli.read_only {
+		editor.set_all_readonly();
+	}
+	Ok(editor)
+}
+
 /// Handles auth login/logout/status subcommands.
 async fn handle_auth_command(action: AuthAction) -> anyhow::Result<()> {
 	use xeno_auth::default_data_dir;
@@ -355,13 +382,18 @@ fn run_log_launcher_mode(cli: &Cli) -> anyhow::Result<()> {
 	let xeno_path = std::env::current_exe()?;
 
 	let mut args: Vec<&OsStr> = Vec::new();
-	if let Some(ref file) = cli.file {
-		args.push(file.as_os_str());
-	}
 	if let Some(ref theme) = cli.theme {
 		args.push(OsStr::new("--theme"));
 		args.push(OsStr::new(theme));
 	}
+	if cli.read_only {
+		args.push(OsStr::new("--read-only"));
+	}
+	args.extend(cli.files.iter().map(OsStr::new));
+	if !cli.literal_files.is_empty() {
+		args.push(OsStr::new("--"));
+		args.extend(cli.literal_files.iter().map(|p| p.as_os_str()));
+	}
 
 	let _child = log_launcher::spawn_in_terminal(
 		&xeno_path.to_string_lossy(),
@@ -432,13 +464,7 @@ async fn run_editor_normal() -> anyhow::Result<()> {
 			},
 		);
 
-	let file: Option<PathBuf> = std::env::args().nth(1).map(PathBuf::from);
-	let mut editor = match file {
-		Some(path) if path.exists() || !path.to_string_lossy().starts_with('-') => {
-			Editor::new(path).await?
-		}
-		_ => Editor::new_scratch(),
-	};
+	let mut editor = open_editor(&Cli::parse()).await?;
 
 	editor.extensions.insert(AcpManager::new());
 	configure_lsp_servers(&mut editor);
@@ -525,23 +551,25 @@ fn configure_lsp_servers(editor: &mut Editor) {
 		server_defs.iter().map(|s| (s.name.as_str(), s)).collect();
 
 	for (language, info) in &lang_mapping {
-		// Try each configured server in order until one with an available binary is found
-		let Some(server_def) = info.servers.iter().find_map(|name| {
+		// Every listed server with an available binary is attached, in order;
+		// requests go to the first one advertising the capability they need.
+		let available = info.servers.iter().filter_map(|name| {
 			let def = server_map.get(name.as_str())?;
 			which::which(&def.command).ok().map(|_| def)
-		}) else {
-			continue;
-		};
-		editor.lsp.configure_server(
-			language.clone(),
-			xeno_api::lsp::LanguageServerConfig {
-				command: server_def.command.clone(),
-				args: server_def.args.clone(),
-				env: server_def.environment.clone(),
-				root_markers: info.roots.clone(),
-				config: server_def.config.clone(),
-				..Default::default()
-			},
-		);
+		});
+		for server_def in available {
+			editor.lsp.configure_server(
+				language.clone(),
+				xeno_api::lsp::LanguageServerConfig {
+					name: server_def.name.clone(),
+					command: server_def.command.clone(),
+					args: server_def.args.clone(),
+					env: server_def.environment.clone(),
+					root_markers: info.roots.clone(),
+					config: server_def.config.clone(),
+					..Default::default()
+				},
+			);
+		}
 	}
 }
@@ -0,0 +1,35 @@
+//! Piped input, read before the terminal takes over.
+
+use std::io::{self, Read};
+
+/// Reads all of stdin, then reattaches stdin to the controlling terminal so
+/// keyboard input keeps working.
+///
+/// Invalid UTF-8 is replaced rather than rejected.
+#[cfg(unix)]
+pub fn read_piped_stdin() -> io::Result<String> {
+	use std::fs::OpenOptions;
+	use std::os::fd::AsRawFd;
+
+	let mut bytes = Vec::new();
+	io::stdin().lock().read_to_end(&mut bytes)?;
+
+	let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+	// SAFETY: both descriptors are open for the duration of the call, and
+	// dup2 atomically replaces stdin without touching `tty`.
+	if unsafe { libc::dup2(tty.as_raw_fd(), libc::STDIN_FILENO) } < 0 {
+		return Err(io::Error::last_os_error());
+	}
+
+	Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Reading piped input needs a controlling terminal to reattach to, which
+/// is only supported on Unix.
+#[cfg(not(unix))]
+pub fn read_piped_stdin() -> io::Result<String> {
+	Err(io::Error::new(
+		io::ErrorKind::Unsupported,
+		"reading from stdin is only supported on Unix",
+	))
+}
@@ -4,14 +4,15 @@
 //! alternate screen, mouse tracking, and keyboard enhancement protocols.
 
 use std::io::{self, Write};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use termina::escape::csi::{
-	Csi, Cursor, DecPrivateMode, DecPrivateModeCode, Keyboard, KittyKeyboardFlags, Mode,
+	Csi, Cursor, DecPrivateMode, DecPrivateModeCode, Device, Keyboard, KittyKeyboardFlags, Mode,
 };
-use termina::event::Event;
+use termina::event::{Event, KeyCode, KeyEvent, KeyEventKind, Modifiers};
 use termina::style::CursorStyle;
 use termina::{EventReader, PlatformTerminal, Terminal as _, WindowSize};
+use xeno_api::editor::DesktopAlert;
 use xeno_core::{TerminalConfig, TerminalSequence};
 
 /// Writes terminal escape sequences to a writer.
@@ -49,6 +50,12 @@ fn sequence_to_csi(sequence: TerminalSequence) -> Csi {
 		TerminalSequence::DisableAnyEventMouse => Csi::Mode(Mode::ResetDecPrivateMode(
 			DecPrivateMode::Code(DecPrivateModeCode::AnyEventMouse),
 		)),
+		TerminalSequence::EnableBracketedPaste => Csi::Mode(Mode::SetDecPrivateMode(
+			DecPrivateMode::Code(DecPrivateModeCode::BracketedPaste),
+		)),
+		TerminalSequence::DisableBracketedPaste => Csi::Mode(Mode::ResetDecPrivateMode(
+			DecPrivateMode::Code(DecPrivateModeCode::BracketedPaste),
+		)),
 		TerminalSequence::PushKittyKeyboardDisambiguate => Csi::Keyboard(Keyboard::PushFlags(
 			KittyKeyboardFlags::DISAMBIGUATE_ESCAPE_CODES,
 		)),
@@ -59,6 +66,60 @@ fn sequence_to_csi(sequence: TerminalSequence) -> Csi {
 	}
 }
 
+/// How long to wait for the terminal to answer the keyboard protocol query.
+const KITTY_QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Asks the terminal whether it supports the kitty keyboard protocol.
+///
+/// Sends a keyboard flags query followed by a primary device attributes
+/// request, which every terminal answers. A flags report arriving before the
+/// attributes means the protocol is supported. Any error or timeout counts
+/// as unsupported. The terminal must already be in raw mode.
+pub fn probe_kitty_keyboard(terminal: &mut PlatformTerminal) -> bool {
+	let query = write!(
+		terminal,
+		"{}{}",
+		Csi::Keyboard(Keyboard::QueryFlags),
+		Csi::Device(Device::RequestPrimaryDeviceAttributes)
+	)
+	.and_then(|()| terminal.flush());
+	if query.is_err() {
+		return false;
+	}
+
+	let events = terminal.event_reader();
+	let mut filter = |event: &Event| {
+		matches!(
+			event,
+			Event::Csi(Csi::Keyboard(Keyboard::ReportFlags(_)))
+				| Event::Csi(Csi::Device(Device::DeviceAttributes(_)))
+		)
+	};
+	let deadline = Instant::now() + KITTY_QUERY_TIMEOUT;
+	let mut supported = false;
+	loop {
+		let remaining = deadline.saturating_duration_since(Instant::now());
+		if !matches!(events.poll(Some(remaining), &mut filter), Ok(true)) {
+			break;
+		}
+		match events.read(&mut filter) {
+			Ok(Event::Csi(Csi::Keyboard(Keyboard::ReportFlags(_)))) => supported = true,
+			_ => break,
+		}
+	}
+	supported
+}
+
+/// Detects the terminal configuration, probing for kitty keyboard support.
+///
+/// Enters raw mode first so the query's answer is not echoed.
+pub fn detect_terminal_config(terminal: &mut PlatformTerminal) -> io::Result<TerminalConfig> {
+	terminal.enter_raw_mode()?;
+	Ok(TerminalConfig::detect_with_probe(probe_kitty_keyboard(
+		terminal,
+	)))
+}
+
 /// Enables terminal features using auto-detected configuration.
 pub fn enable_terminal_features(terminal: &mut PlatformTerminal) -> io::Result<()> {
 	enable_terminal_features_with_config(terminal, TerminalConfig::detect())
@@ -89,6 +150,43 @@ pub fn disable_terminal_features_with_config(
 	terminal.flush()
 }
 
+/// Turns mouse reporting on or off, leaving the other features untouched.
+///
+/// With capture released the terminal handles the mouse itself, so native
+/// text selection and scrollback work again.
+pub fn set_mouse_capture(terminal: &mut PlatformTerminal, enabled: bool) -> io::Result<()> {
+	let sequences: &[TerminalSequence] = if enabled {
+		&[
+			TerminalSequence::EnableMouseTracking,
+			TerminalSequence::EnableSgrMouse,
+			TerminalSequence::EnableAnyEventMouse,
+		]
+	} else {
+		&[
+			TerminalSequence::DisableAnyEventMouse,
+			TerminalSequence::DisableSgrMouse,
+			TerminalSequence::DisableMouseTracking,
+		]
+	};
+	write_sequences(terminal, sequences)?;
+	terminal.flush()
+}
+
+/// Forwards an alert to the desktop with the configured notification
+/// sequence, or rings the bell.
+pub fn write_desktop_alert(
+	terminal: &mut PlatformTerminal,
+	config: TerminalConfig,
+	alert: &DesktopAlert,
+) -> io::Result<()> {
+	match alert {
+		DesktopAlert::Notify { title, body } => {
+			write!(terminal, "{}", config.desktop_notify.encode(title, body))
+		}
+		DesktopAlert::Bell => write!(terminal, "\x07"),
+	}
+}
+
 /// Installs a panic hook to restore terminal state on panic.
 pub fn install_panic_hook(terminal: &mut PlatformTerminal) {
 	install_panic_hook_with_config(terminal, TerminalConfig::detect());
@@ -116,6 +214,62 @@ pub fn coalesce_resize_events(events: &EventReader, first: WindowSize) -> io::Re
 	Ok(latest)
 }
 
+/// What followed a bare Escape read from a legacy terminal.
+#[derive(Debug)]
+pub enum EscapeFollowup {
+	/// The Escape was the prefix of an Alt chord.
+	Chord(KeyEvent),
+	/// The Escape stood alone; carries the event that ended the wait, if any.
+	Alone(Option<Event>),
+}
+
+/// Returns whether `event` is an unmodified Escape press.
+pub fn is_bare_escape(event: &Event) -> bool {
+	matches!(
+		event,
+		Event::Key(key) if key.code == KeyCode::Escape
+			&& key.modifiers.is_empty()
+			&& key.kind == KeyEventKind::Press
+	)
+}
+
+/// Folds the key that followed a bare Escape into an Alt chord.
+///
+/// Only a character press that is not already an Alt chord qualifies.
+pub fn alt_chord(next: &Event) -> Option<KeyEvent> {
+	match next {
+		Event::Key(key)
+			if matches!(key.code, KeyCode::Char(_))
+				&& key.kind == KeyEventKind::Press
+				&& !key.modifiers.contains(Modifiers::ALT) =>
+		{
+			Some(KeyEvent::new(key.code, key.modifiers | Modifiers::ALT))
+		}
+		_ => None,
+	}
+}
+
+/// Waits up to `timeout` after a bare Escape for the rest of an Alt chord.
+///
+/// Legacy terminals encode Alt+key as Escape followed by the key, and the
+/// bytes can arrive in separate reads. Keystrokes typed by hand are far
+/// slower than the timeout, so Escape followed by a key never fuses.
+pub fn resolve_escape(
+	events: &EventReader,
+	timeout: Duration,
+	filter: &mut impl FnMut(&Event) -> bool,
+) -> io::Result<EscapeFollowup> {
+	if timeout.is_zero() || !events.poll(Some(timeout), &mut *filter)? {
+		return Ok(EscapeFollowup::Alone(None));
+	}
+
+	let next = events.read(&mut *filter)?;
+	Ok(match alt_chord(&next) {
+		Some(chord) => EscapeFollowup::Chord(chord),
+		None => EscapeFollowup::Alone(Some(next)),
+	})
+}
+
 /// Returns the appropriate cursor style for the given editor mode.
 pub fn cursor_style_for_mode(mode: xeno_base::Mode) -> CursorStyle {
 	match mode {
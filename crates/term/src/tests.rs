@@ -26,4 +26,76 @@ mod suite {
 		terminal.draw(|frame| editor.render(frame)).unwrap();
 		assert_snapshot!(terminal.backend());
 	}
+
+	#[test]
+	fn escape_followed_by_char_folds_into_alt_chord() {
+		use termina::event::{Event, KeyCode, KeyEvent, Modifiers};
+
+		use crate::terminal::{alt_chord, is_bare_escape};
+
+		let esc = Event::Key(KeyEvent::new(KeyCode::Escape, Modifiers::NONE));
+		assert!(is_bare_escape(&esc));
+		assert!(!is_bare_escape(&Event::Key(KeyEvent::new(
+			KeyCode::Escape,
+			Modifiers::ALT
+		))));
+
+		let chord = alt_chord(&Event::Key(KeyEvent::new(
+			KeyCode::Char('j'),
+			Modifiers::NONE,
+		)));
+		assert_eq!(
+			chord.map(|key| (key.code, key.modifiers)),
+			Some((KeyCode::Char('j'), Modifiers::ALT))
+		);
+		assert!(alt_chord(&esc).is_none());
+		assert!(
+			alt_chord(&Event::Key(KeyEvent::new(
+				KeyCode::Char('j'),
+				Modifiers::ALT
+			)))
+			.is_none()
+		);
+	}
+
+	#[test]
+	fn cli_collects_files_with_line_prefixes() {
+		use clap::Parser;
+
+		use crate::cli::Cli;
+
+		let cli =
+			Cli::try_parse_from(["xeno", "a.rs", "-", "+12", "b.rs", "--", "+3", "-c"]).unwrap();
+		assert!(cli.reads_stdin());
+		assert_eq!(
+			cli.file_args(),
+			vec![
+				(PathBuf::from("a.rs"), None),
+				(PathBuf::from("b.rs"), Some(12)),
+				(PathBuf::from("+3"), None),
+				(PathBuf::from("-c"), None),
+			]
+		);
+	}
+
+	#[test]
+	fn cli_read_only_flag() {
+		use clap::Parser;
+
+		use crate::cli::Cli;
+
+		assert!(
+			Cli::try_parse_from(["xeno", "-R", "a.rs"])
+				.unwrap()
+				.read_only
+		);
+		assert!(!Cli::try_parse_from(["xeno", "a.rs"]).unwrap().read_only);
+	}
+
+	#[test]
+	fn piped_buffer_starts_modified() {
+		let editor = Editor::from_piped("diff --git a/x b/x\n".to_string());
+		assert!(editor.buffer().modified());
+		assert_eq!(editor.buffer().path(), None);
+	}
 }
@@ -275,6 +275,33 @@ impl Rect {
 		}
 	}
 
+	/// Splits off the rightmost column, e.g. to reserve room for a scrollbar.
+	///
+	/// Returns the remaining area and the column. An empty `Rect` yields two
+	/// empty halves.
+	#[must_use = "method returns the split areas"]
+	pub const fn split_right_column(self) -> (Self, Self) {
+		if self.is_empty() {
+			return (
+				Self { width: 0, ..self },
+				Self {
+					x: self.right(),
+					width: 0,
+					..self
+				},
+			);
+		}
+		let width = self.width - 1;
+		(
+			Self { width, ..self },
+			Self {
+				x: self.x + width,
+				width: 1,
+				..self
+			},
+		)
+	}
+
 	/// Moves the `Rect` without modifying its size.
 	///
 	/// Moves the `Rect` according to the given offset without modifying its [`width`](Rect::width)
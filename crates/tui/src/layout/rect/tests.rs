@@ -87,6 +87,22 @@ fn outer() {
 	);
 }
 
+#[test]
+fn split_right_column() {
+	assert_eq!(
+		Rect::new(1, 2, 3, 4).split_right_column(),
+		(Rect::new(1, 2, 2, 4), Rect::new(3, 2, 1, 4)),
+	);
+	assert_eq!(
+		Rect::new(1, 2, 1, 4).split_right_column(),
+		(Rect::new(1, 2, 0, 4), Rect::new(1, 2, 1, 4)),
+	);
+	assert_eq!(
+		Rect::new(1, 2, 3, 0).split_right_column(),
+		(Rect::new(1, 2, 0, 0), Rect::new(4, 2, 0, 0)),
+	);
+}
+
 #[test]
 fn offset() {
 	assert_eq!(
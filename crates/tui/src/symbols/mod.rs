@@ -12,3 +12,5 @@ pub mod line;
 pub mod merge;
 /// Scrollbar symbols.
 pub mod scrollbar;
+/// Spinner frames.
+pub mod spinner;
@@ -0,0 +1,8 @@
+/// Braille dots spinner frames.
+pub const DOTS: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// ASCII line spinner frames, for terminals without braille glyphs.
+pub const LINE: &[&str] = &["-", "\\", "|", "/"];
+
+/// Quarter circle spinner frames.
+pub const ARC: &[&str] = &["◜", "◝", "◞", "◟"];
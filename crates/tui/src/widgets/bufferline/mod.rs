@@ -0,0 +1,300 @@
+//! The [`BufferLine`] widget lists open buffers as a strip of tabs.
+//!
+//! Each tab shows a label, a dot when the buffer is modified, and a count
+//! of errors when there are any. When the tabs do not fit, the strip
+//! scrolls to keep the active tab visible and draws `‹`/`›` at the edges
+//! that hide more tabs.
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::bufferline::{BufferLine, BufferTab};
+//!
+//! let line = BufferLine::new(vec![
+//!     BufferTab::new("main.rs").modified(true),
+//!     BufferTab::new("lib.rs").errors(2),
+//! ])
+//! .active(1);
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::vec::Vec;
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::widgets::Widget;
+
+#[cfg(test)]
+mod tests;
+
+/// Marker appended to the label of a modified buffer.
+const MODIFIED_MARKER: &str = " ●";
+/// Prefix of the error count.
+const ERROR_MARKER: &str = " ✗";
+/// Drawn at the left edge when tabs are scrolled out of view there.
+const LEFT_OVERFLOW: &str = "‹";
+/// Drawn at the right edge when tabs are scrolled out of view there.
+const RIGHT_OVERFLOW: &str = "›";
+
+/// One buffer in a [`BufferLine`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BufferTab<'a> {
+	/// Text shown for the buffer, usually a shortened path.
+	label: Cow<'a, str>,
+	/// Whether the buffer has unsaved changes.
+	modified: bool,
+	/// Number of error diagnostics in the buffer.
+	errors: usize,
+}
+
+impl<'a> BufferTab<'a> {
+	/// Creates a tab with the given label.
+	pub fn new(label: impl Into<Cow<'a, str>>) -> Self {
+		Self {
+			label: label.into(),
+			..Default::default()
+		}
+	}
+
+	/// Sets whether the buffer has unsaved changes.
+	#[must_use]
+	pub const fn modified(mut self, modified: bool) -> Self {
+		self.modified = modified;
+		self
+	}
+
+	/// Sets the number of errors shown on the tab.
+	#[must_use]
+	pub const fn errors(mut self, errors: usize) -> Self {
+		self.errors = errors;
+		self
+	}
+
+	/// Returns the text of the error count, if there are errors.
+	fn error_text(&self) -> Option<alloc::string::String> {
+		(self.errors > 0).then(|| format!("{ERROR_MARKER}{}", self.errors))
+	}
+
+	/// Returns the width of the tab in cells, including its padding.
+	fn width(&self) -> usize {
+		let modified = if self.modified {
+			MODIFIED_MARKER.width()
+		} else {
+			0
+		};
+		let errors = self.error_text().map_or(0, |text| text.width());
+		self.label.width() + modified + errors + 2
+	}
+}
+
+/// A strip of tabs, one per open buffer, with one tab marked active.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BufferLine<'a> {
+	/// Tabs in display order.
+	tabs: Vec<BufferTab<'a>>,
+	/// Index of the active tab.
+	active: Option<usize>,
+	/// Style of the strip and of inactive tabs.
+	style: Style,
+	/// Style patched over the active tab.
+	active_style: Style,
+	/// Style patched over the modified marker.
+	modified_style: Style,
+	/// Style patched over the error count.
+	error_style: Style,
+	/// Style of the overflow indicators.
+	overflow_style: Style,
+}
+
+/// Range of tabs that fits the strip, and where each edge hides more.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Strip {
+	/// First visible tab.
+	start: usize,
+	/// One past the last visible tab.
+	end: usize,
+	/// Whether the strip overflows, reserving a cell at each edge.
+	overflow: bool,
+}
+
+impl<'a> BufferLine<'a> {
+	/// Creates a buffer line from its tabs.
+	pub fn new(tabs: Vec<BufferTab<'a>>) -> Self {
+		Self {
+			tabs,
+			..Default::default()
+		}
+	}
+
+	/// Sets the active tab.
+	#[must_use]
+	pub fn active<T: Into<Option<usize>>>(mut self, active: T) -> Self {
+		self.active = active.into();
+		self
+	}
+
+	/// Sets the style of the strip and of inactive tabs.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over the active tab.
+	#[must_use]
+	pub const fn active_style(mut self, style: Style) -> Self {
+		self.active_style = style;
+		self
+	}
+
+	/// Sets the style patched over the modified marker.
+	#[must_use]
+	pub const fn modified_style(mut self, style: Style) -> Self {
+		self.modified_style = style;
+		self
+	}
+
+	/// Sets the style patched over the error count.
+	#[must_use]
+	pub const fn error_style(mut self, style: Style) -> Self {
+		self.error_style = style;
+		self
+	}
+
+	/// Sets the style of the `‹`/`›` overflow indicators.
+	#[must_use]
+	pub const fn overflow_style(mut self, style: Style) -> Self {
+		self.overflow_style = style;
+		self
+	}
+
+	/// Returns the index of the tab drawn at `(x, y)` when rendered in `area`.
+	pub fn tab_at(&self, area: Rect, x: u16, y: u16) -> Option<usize> {
+		if y < area.top() || y >= area.bottom() {
+			return None;
+		}
+		self.tab_spans(area)
+			.find(|&(_, start, end)| x >= start && x < end)
+			.map(|(index, ..)| index)
+	}
+
+	/// Picks the tabs that fit `width` cells, scrolling to keep the active
+	/// tab in view.
+	fn strip(&self, width: usize) -> Strip {
+		let widths: Vec<usize> = self.tabs.iter().map(BufferTab::width).collect();
+		if widths.iter().sum::<usize>() <= width {
+			return Strip {
+				start: 0,
+				end: self.tabs.len(),
+				overflow: false,
+			};
+		}
+
+		let available = width.saturating_sub(LEFT_OVERFLOW.width() + RIGHT_OVERFLOW.width());
+		let active = self
+			.active
+			.filter(|&i| i < self.tabs.len())
+			.unwrap_or_default();
+
+		let mut start = 0;
+		let mut used: usize = widths[..=active].iter().sum();
+		while start < active && used > available {
+			used -= widths[start];
+			start += 1;
+		}
+
+		let mut end = start;
+		let mut used = 0;
+		while end < widths.len() && used + widths[end] <= available {
+			used += widths[end];
+			end += 1;
+		}
+
+		Strip {
+			start,
+			end: end.max(start + 1),
+			overflow: true,
+		}
+	}
+
+	/// Yields `(index, start_x, end_x)` for each visible tab, clipped to the
+	/// space between the overflow indicators.
+	fn tab_spans(&self, area: Rect) -> impl Iterator<Item = (usize, u16, u16)> + '_ {
+		let strip = self.strip(usize::from(area.width));
+		let (left, right) = if strip.overflow {
+			(
+				area.left() + LEFT_OVERFLOW.width() as u16,
+				area.right().saturating_sub(RIGHT_OVERFLOW.width() as u16),
+			)
+		} else {
+			(area.left(), area.right())
+		};
+
+		let mut x = left;
+		(strip.start..strip.end).map_while(move |index| {
+			if x >= right {
+				return None;
+			}
+			let start = x;
+			x = x.saturating_add(self.tabs[index].width() as u16).min(right);
+			Some((index, start, x))
+		})
+	}
+}
+
+impl Widget for BufferLine<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+		buf.set_style(area, self.style);
+
+		let strip = self.strip(usize::from(area.width));
+		if strip.overflow {
+			if strip.start > 0 {
+				buf.set_string(area.left(), area.top(), LEFT_OVERFLOW, self.overflow_style);
+			}
+			if strip.end < self.tabs.len() {
+				let x = area.right().saturating_sub(RIGHT_OVERFLOW.width() as u16);
+				buf.set_string(x, area.top(), RIGHT_OVERFLOW, self.overflow_style);
+			}
+		}
+
+		for (index, start, end) in self.tab_spans(area) {
+			let tab = &self.tabs[index];
+			let base = if Some(index) == self.active {
+				self.style.patch(self.active_style)
+			} else {
+				self.style
+			};
+			buf.set_style(Rect::new(start, area.top(), end - start, 1), base);
+
+			let error_text = tab.error_text();
+			let pieces = [
+				(" ", base),
+				(tab.label.as_ref(), base),
+				(
+					if tab.modified { MODIFIED_MARKER } else { "" },
+					base.patch(self.modified_style),
+				),
+				(
+					error_text.as_deref().unwrap_or_default(),
+					base.patch(self.error_style),
+				),
+			];
+
+			let mut x = start;
+			for (text, style) in pieces {
+				if x >= end {
+					break;
+				}
+				(x, _) = buf.set_stringn(x, area.top(), text, usize::from(end - x), style);
+			}
+		}
+	}
+}
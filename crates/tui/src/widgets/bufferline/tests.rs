@@ -0,0 +1,87 @@
+use alloc::vec;
+
+use super::*;
+use crate::Terminal;
+use crate::backend::TestBackend;
+
+/// Draws `line` into a one-row terminal of `width` cells.
+fn draw(line: BufferLine<'_>, width: u16) -> Terminal<TestBackend> {
+	let mut terminal = Terminal::new(TestBackend::new(width, 1)).unwrap();
+	terminal
+		.draw(|frame| frame.render_widget(line, frame.area()))
+		.unwrap();
+	terminal
+}
+
+fn four_tabs() -> BufferLine<'static> {
+	BufferLine::new(vec![
+		BufferTab::new("one"),
+		BufferTab::new("two"),
+		BufferTab::new("three"),
+		BufferTab::new("four"),
+	])
+}
+
+#[test]
+fn renders_labels_and_markers() {
+	let line = BufferLine::new(vec![
+		BufferTab::new("a.rs").modified(true),
+		BufferTab::new("b.rs"),
+		BufferTab::new("c.rs").errors(3),
+	]);
+	draw(line, 26)
+		.backend()
+		.assert_buffer_lines([" a.rs ●  b.rs  c.rs ✗3    "]);
+}
+
+#[test]
+fn styles_active_tab_and_errors() {
+	let line = BufferLine::new(vec![BufferTab::new("a"), BufferTab::new("b").errors(1)])
+		.active(1)
+		.active_style(Style::new().reversed())
+		.error_style(Style::new().red());
+
+	let mut expected = Buffer::with_lines([" a  b ✗1 "]);
+	expected.set_style(Rect::new(3, 0, 6, 1), Style::new().reversed());
+	expected.set_style(Rect::new(5, 0, 3, 1), Style::new().reversed().red());
+	draw(line, 9).backend().assert_buffer(&expected);
+}
+
+#[test]
+fn overflow_keeps_active_tab_visible() {
+	draw(four_tabs().active(0), 16)
+		.backend()
+		.assert_buffer_lines(["  one  two     ›"]);
+	draw(four_tabs().active(3), 16)
+		.backend()
+		.assert_buffer_lines(["‹ three  four   "]);
+}
+
+#[test]
+fn overflow_shows_both_indicators_in_the_middle() {
+	draw(four_tabs().active(2), 12)
+		.backend()
+		.assert_buffer_lines(["‹ three    ›"]);
+}
+
+#[test]
+fn tab_at_matches_rendered_tabs() {
+	let area = Rect::new(0, 0, 16, 1);
+	let line = four_tabs().active(0);
+	assert_eq!(line.tab_at(area, 0, 0), None);
+	assert_eq!(line.tab_at(area, 1, 0), Some(0));
+	assert_eq!(line.tab_at(area, 6, 0), Some(1));
+	assert_eq!(line.tab_at(area, 11, 0), None);
+	assert_eq!(line.tab_at(area, 1, 1), None);
+}
+
+#[test]
+fn render_in_tiny_area() {
+	// The active tab is clipped rather than skipped.
+	draw(four_tabs().active(2), 5)
+		.backend()
+		.assert_buffer_lines(["‹ th›"]);
+
+	let mut buffer = Buffer::empty(Rect::ZERO);
+	four_tabs().render(buffer.area, &mut buffer);
+}
@@ -0,0 +1,321 @@
+//! Line diffs between two texts, unified diff parsing and intra-line changes.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+
+/// Kind of a line in a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffLineKind {
+	/// Unchanged line shown for context.
+	Context,
+	/// Line only in the new text.
+	Added,
+	/// Line only in the old text.
+	Removed,
+}
+
+impl DiffLineKind {
+	/// Returns the sign drawn before lines of this kind.
+	pub const fn sign(self) -> char {
+		match self {
+			Self::Context => ' ',
+			Self::Added => '+',
+			Self::Removed => '-',
+		}
+	}
+}
+
+/// A line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffLine {
+	/// Whether the line was kept, added or removed.
+	pub kind: DiffLineKind,
+	/// Text of the line, without its line ending.
+	pub text: String,
+	/// Byte ranges of `text` that differ from the paired line on the other
+	/// side, for modified lines.
+	pub changes: Vec<Range<usize>>,
+}
+
+impl DiffLine {
+	/// Creates a line with no intra-line changes.
+	pub fn new(kind: DiffLineKind, text: impl Into<String>) -> Self {
+		Self {
+			kind,
+			text: text.into(),
+			changes: Vec::new(),
+		}
+	}
+}
+
+/// A run of changes with the context around them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+	/// Header line, starting with `@@`.
+	pub header: String,
+	/// Lines of the hunk, in order.
+	pub lines: Vec<DiffLine>,
+}
+
+/// A diff as a list of hunks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Diff {
+	/// Hunks in file order.
+	pub hunks: Vec<Hunk>,
+}
+
+/// An edit turning the old lines into the new ones, with the old and new
+/// line indices it applies at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+	/// Old line `.0` equals new line `.1`.
+	Keep(usize, usize),
+	/// Old line `.0` is removed before new line `.1`.
+	Remove(usize, usize),
+	/// New line `.1` is added before old line `.0`.
+	Add(usize, usize),
+}
+
+impl Edit {
+	/// Returns the old and new line indices.
+	const fn position(self) -> (usize, usize) {
+		match self {
+			Self::Keep(o, n) | Self::Remove(o, n) | Self::Add(o, n) => (o, n),
+		}
+	}
+
+	/// Shifts both indices by `by` lines.
+	const fn shift(self, by: usize) -> Self {
+		match self {
+			Self::Keep(o, n) => Self::Keep(o + by, n + by),
+			Self::Remove(o, n) => Self::Remove(o + by, n + by),
+			Self::Add(o, n) => Self::Add(o + by, n + by),
+		}
+	}
+}
+
+impl Diff {
+	/// Diffs `old` against `new` line by line, keeping `context` unchanged
+	/// lines around each change.
+	pub fn between(old: &str, new: &str, context: usize) -> Self {
+		let old: Vec<&str> = old.lines().collect();
+		let new: Vec<&str> = new.lines().collect();
+		let edits = edit_script(&old, &new);
+
+		let changed: Vec<usize> = edits
+			.iter()
+			.enumerate()
+			.filter(|(_, edit)| !matches!(edit, Edit::Keep(..)))
+			.map(|(i, _)| i)
+			.collect();
+		let mut hunks = Vec::new();
+		let mut i = 0;
+		while i < changed.len() {
+			// Changes closer than twice the context share a hunk.
+			let mut j = i;
+			while j + 1 < changed.len() && changed[j + 1] - changed[j] <= 2 * context + 1 {
+				j += 1;
+			}
+			let start = changed[i].saturating_sub(context);
+			let end = (changed[j] + context + 1).min(edits.len());
+			hunks.push(hunk(&edits[start..end], &old, &new));
+			i = j + 1;
+		}
+		Self { hunks }
+	}
+
+	/// Parses a unified diff, as printed by `diff -u` or `git diff`.
+	///
+	/// File headers and other lines outside hunks are skipped.
+	pub fn parse_unified(patch: &str) -> Self {
+		let mut hunks: Vec<Hunk> = Vec::new();
+		for line in patch.lines() {
+			if line.starts_with("@@") {
+				hunks.push(Hunk {
+					header: line.to_string(),
+					lines: Vec::new(),
+				});
+				continue;
+			}
+			let Some(hunk) = hunks.last_mut() else {
+				continue;
+			};
+			let kind = match line.chars().next() {
+				Some('+') if !line.starts_with("+++ ") => DiffLineKind::Added,
+				Some('-') if !line.starts_with("--- ") => DiffLineKind::Removed,
+				Some(' ') | None => DiffLineKind::Context,
+				_ => continue,
+			};
+			let text = line.get(1..).unwrap_or_default();
+			hunk.lines.push(DiffLine::new(kind, text));
+		}
+		for hunk in &mut hunks {
+			mark_changes(&mut hunk.lines);
+		}
+		Self { hunks }
+	}
+
+	/// Returns true if there are no hunks.
+	pub fn is_empty(&self) -> bool {
+		self.hunks.is_empty()
+	}
+}
+
+/// Builds a hunk from a slice of the edit script.
+fn hunk(edits: &[Edit], old: &[&str], new: &[&str]) -> Hunk {
+	let mut lines = Vec::with_capacity(edits.len());
+	let (mut old_len, mut new_len) = (0, 0);
+	for &edit in edits {
+		let line = match edit {
+			Edit::Keep(o, _) => DiffLine::new(DiffLineKind::Context, old[o]),
+			Edit::Remove(o, _) => DiffLine::new(DiffLineKind::Removed, old[o]),
+			Edit::Add(_, n) => DiffLine::new(DiffLineKind::Added, new[n]),
+		};
+		old_len += usize::from(line.kind != DiffLineKind::Added);
+		new_len += usize::from(line.kind != DiffLineKind::Removed);
+		lines.push(line);
+	}
+	mark_changes(&mut lines);
+
+	// Starts are 1-based, except that an empty side is numbered from the
+	// line before it, as `diff -u` does.
+	let (old_start, new_start) = edits[0].position();
+	let old_start = old_start + usize::from(old_len > 0);
+	let new_start = new_start + usize::from(new_len > 0);
+	Hunk {
+		header: format!("@@ -{old_start},{old_len} +{new_start},{new_len} @@"),
+		lines,
+	}
+}
+
+/// Computes the shortest edit script from `old` to `new` (Myers' algorithm),
+/// after trimming the common prefix and suffix.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Edit> {
+	let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+	let suffix = old[prefix..]
+		.iter()
+		.rev()
+		.zip(new[prefix..].iter().rev())
+		.take_while(|(a, b)| a == b)
+		.count();
+	let a = &old[prefix..old.len() - suffix];
+	let b = &new[prefix..new.len() - suffix];
+
+	let mut edits: Vec<Edit> = (0..prefix).map(|i| Edit::Keep(i, i)).collect();
+	edits.extend(myers(a, b).into_iter().map(|edit| edit.shift(prefix)));
+	edits.extend((0..suffix).map(|i| Edit::Keep(old.len() - suffix + i, new.len() - suffix + i)));
+	edits
+}
+
+/// Myers' O(ND) diff, removals before additions within a change.
+fn myers(a: &[&str], b: &[&str]) -> Vec<Edit> {
+	let (n, m) = (a.len() as isize, b.len() as isize);
+	let max = n + m;
+	let index = |k: isize| (k + max + 1) as usize;
+	let mut v = vec![0isize; 2 * max as usize + 3];
+	let mut trace = Vec::new();
+
+	'search: for d in 0..=max {
+		trace.push(v.clone());
+		for k in (-d..=d).step_by(2) {
+			let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+				v[index(k + 1)]
+			} else {
+				v[index(k - 1)] + 1
+			};
+			let mut y = x - k;
+			while x < n && y < m && a[x as usize] == b[y as usize] {
+				x += 1;
+				y += 1;
+			}
+			v[index(k)] = x;
+			if x >= n && y >= m {
+				break 'search;
+			}
+		}
+	}
+
+	let mut edits = Vec::new();
+	let (mut x, mut y) = (n, m);
+	for (d, v) in trace.iter().enumerate().rev() {
+		let d = d as isize;
+		let k = x - y;
+		let prev_k = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+			k + 1
+		} else {
+			k - 1
+		};
+		let prev_x = v[index(prev_k)];
+		let prev_y = prev_x - prev_k;
+		while x > prev_x && y > prev_y {
+			x -= 1;
+			y -= 1;
+			edits.push(Edit::Keep(x as usize, y as usize));
+		}
+		if d > 0 {
+			let (o, n) = (prev_x as usize, prev_y as usize);
+			edits.push(if x == prev_x {
+				Edit::Add(o, n)
+			} else {
+				Edit::Remove(o, n)
+			});
+		}
+		(x, y) = (prev_x, prev_y);
+	}
+	edits.reverse();
+
+	// Within each change, list removals first so modified lines pair up.
+	let mut start = 0;
+	while start < edits.len() {
+		let end = edits[start..]
+			.iter()
+			.position(|edit| matches!(edit, Edit::Keep(..)))
+			.map_or(edits.len(), |len| start + len);
+		edits[start..end].sort_by_key(|edit| matches!(edit, Edit::Add(..)));
+		start = end + 1;
+	}
+	edits
+}
+
+/// Pairs each run of removed lines with the added lines after it and marks
+/// the differing middle of each pair as changed.
+fn mark_changes(lines: &mut [DiffLine]) {
+	let mut i = 0;
+	while i < lines.len() {
+		let removed = lines[i..]
+			.iter()
+			.take_while(|line| line.kind == DiffLineKind::Removed)
+			.count();
+		let added = lines[i + removed..]
+			.iter()
+			.take_while(|line| line.kind == DiffLineKind::Added)
+			.count();
+		for pair in 0..removed.min(added) {
+			let (old, new) = changed_ranges(&lines[i + pair].text, &lines[i + removed + pair].text);
+			lines[i + pair].changes = vec![old];
+			lines[i + removed + pair].changes = vec![new];
+		}
+		i += (removed + added).max(1);
+	}
+}
+
+/// Returns the byte ranges of `old` and `new` left after trimming their
+/// common prefix and suffix.
+fn changed_ranges(old: &str, new: &str) -> (Range<usize>, Range<usize>) {
+	let prefix: usize = old
+		.chars()
+		.zip(new.chars())
+		.take_while(|(a, b)| a == b)
+		.map(|(c, _)| c.len_utf8())
+		.sum();
+	let suffix: usize = old[prefix..]
+		.chars()
+		.rev()
+		.zip(new[prefix..].chars().rev())
+		.take_while(|(a, b)| a == b)
+		.map(|(c, _)| c.len_utf8())
+		.sum();
+	(prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
@@ -0,0 +1,318 @@
+//! The [`DiffView`] widget shows the hunks of a [`Diff`].
+//!
+//! A diff is computed from two texts with [`Diff::between`] or read from a
+//! unified diff with [`Diff::parse_unified`]. Each hunk is drawn as its
+//! `@@` header followed by its lines, either inline with `+`/`-` signs or
+//! side by side with the old text on the left. Modified lines have the
+//! changed part of the line highlighted. Side by side falls back to inline
+//! when the area is too narrow for two columns.
+//!
+//! Scrolling and hunk navigation live in [`DiffViewState`].
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::diff_view::{Diff, DiffView, DiffViewState};
+//!
+//! let diff = Diff::between("a\nb\nc\n", "a\nB\nc\n", 1);
+//! assert_eq!(diff.hunks[0].header, "@@ -1,3 +1,3 @@");
+//!
+//! let view = DiffView::new(&diff).side_by_side(true);
+//! let mut state = DiffViewState::new();
+//! ```
+
+use alloc::vec::Vec;
+
+pub use self::diff::{Diff, DiffLine, DiffLineKind, Hunk};
+pub use self::state::{DiffViewKey, DiffViewState};
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::symbols::{line, scrollbar};
+use crate::widgets::block::Block;
+use crate::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget};
+
+/// Diffing, parsing and intra-line changes.
+mod diff;
+/// Scrolling and hunk navigation.
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+/// Narrowest each side may be before side by side falls back to inline,
+/// unless set with [`DiffView::min_side_width`].
+pub const DEFAULT_MIN_SIDE_WIDTH: u16 = 40;
+
+/// A row of the laid out diff.
+enum DiffRow<'d> {
+	/// A hunk header.
+	Header(&'d Hunk),
+	/// A line drawn across the whole width.
+	Inline(&'d DiffLine),
+	/// The old and new line, either of which may be missing.
+	Split(Option<&'d DiffLine>, Option<&'d DiffLine>),
+}
+
+/// Hunks of a diff, inline or side by side.
+pub struct DiffView<'a> {
+	/// The diff shown.
+	diff: &'a Diff,
+	/// Block drawn around the view.
+	block: Option<Block<'a>>,
+	/// Whether to show old and new text side by side.
+	side_by_side: bool,
+	/// Narrowest each side may be when shown side by side.
+	min_side_width: u16,
+	/// Base style of the view.
+	style: Style,
+	/// Style patched over hunk headers.
+	header_style: Style,
+	/// Style patched over unchanged lines.
+	context_style: Style,
+	/// Style patched over added lines.
+	added_style: Style,
+	/// Style patched over removed lines.
+	removed_style: Style,
+	/// Style patched over the changed part of added lines.
+	added_change_style: Style,
+	/// Style patched over the changed part of removed lines.
+	removed_change_style: Style,
+	/// Style patched over the scrollbar thumb.
+	scrollbar_style: Style,
+}
+
+impl<'a> DiffView<'a> {
+	/// Creates an inline view of `diff` with no styling.
+	pub const fn new(diff: &'a Diff) -> Self {
+		Self {
+			diff,
+			block: None,
+			side_by_side: false,
+			min_side_width: DEFAULT_MIN_SIDE_WIDTH,
+			style: Style::new(),
+			header_style: Style::new(),
+			context_style: Style::new(),
+			added_style: Style::new(),
+			removed_style: Style::new(),
+			added_change_style: Style::new(),
+			removed_change_style: Style::new(),
+			scrollbar_style: Style::new(),
+		}
+	}
+
+	/// Wraps the view in a block.
+	#[must_use]
+	pub fn block(mut self, block: Block<'a>) -> Self {
+		self.block = Some(block);
+		self
+	}
+
+	/// Sets whether to show old and new text side by side.
+	#[must_use]
+	pub const fn side_by_side(mut self, side_by_side: bool) -> Self {
+		self.side_by_side = side_by_side;
+		self
+	}
+
+	/// Sets the narrowest each side may be before side by side falls back
+	/// to inline.
+	#[must_use]
+	pub const fn min_side_width(mut self, width: u16) -> Self {
+		self.min_side_width = width;
+		self
+	}
+
+	/// Sets the base style of the view.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over hunk headers.
+	#[must_use]
+	pub const fn header_style(mut self, style: Style) -> Self {
+		self.header_style = style;
+		self
+	}
+
+	/// Sets the style patched over unchanged lines.
+	#[must_use]
+	pub const fn context_style(mut self, style: Style) -> Self {
+		self.context_style = style;
+		self
+	}
+
+	/// Sets the style patched over added lines.
+	#[must_use]
+	pub const fn added_style(mut self, style: Style) -> Self {
+		self.added_style = style;
+		self
+	}
+
+	/// Sets the style patched over removed lines.
+	#[must_use]
+	pub const fn removed_style(mut self, style: Style) -> Self {
+		self.removed_style = style;
+		self
+	}
+
+	/// Sets the style patched over the changed part of added lines.
+	#[must_use]
+	pub const fn added_change_style(mut self, style: Style) -> Self {
+		self.added_change_style = style;
+		self
+	}
+
+	/// Sets the style patched over the changed part of removed lines.
+	#[must_use]
+	pub const fn removed_change_style(mut self, style: Style) -> Self {
+		self.removed_change_style = style;
+		self
+	}
+
+	/// Sets the style patched over the scrollbar thumb.
+	#[must_use]
+	pub const fn scrollbar_style(mut self, style: Style) -> Self {
+		self.scrollbar_style = style;
+		self
+	}
+
+	/// Lays out the diff as rows, returning them with the row of each hunk
+	/// header.
+	fn rows(&self, split: bool) -> (Vec<DiffRow<'a>>, Vec<usize>) {
+		let mut rows = Vec::new();
+		let mut hunk_rows = Vec::with_capacity(self.diff.hunks.len());
+		for hunk in &self.diff.hunks {
+			hunk_rows.push(rows.len());
+			rows.push(DiffRow::Header(hunk));
+			if !split {
+				rows.extend(hunk.lines.iter().map(DiffRow::Inline));
+				continue;
+			}
+
+			let mut lines = hunk.lines.as_slice();
+			while let Some(first) = lines.first() {
+				if first.kind == DiffLineKind::Context {
+					rows.push(DiffRow::Split(Some(first), Some(first)));
+					lines = &lines[1..];
+					continue;
+				}
+				// Pair a run of removed lines with the added lines after it.
+				let removed = run(lines, DiffLineKind::Removed);
+				let added = run(&lines[removed..], DiffLineKind::Added);
+				let (old, new) = lines[..removed + added].split_at(removed);
+				for i in 0..removed.max(added) {
+					rows.push(DiffRow::Split(old.get(i), new.get(i)));
+				}
+				lines = &lines[removed + added..];
+			}
+		}
+		(rows, hunk_rows)
+	}
+
+	/// Draws `line` with its sign on row `y`, or clears the row if there is
+	/// no line on this side.
+	fn render_line(&self, area: Rect, y: u16, line: Option<&DiffLine>, buf: &mut Buffer) {
+		let row = Rect::new(area.x, y, area.width, 1);
+		let Some(line) = line else {
+			buf.set_style(row, self.style);
+			return;
+		};
+		let (style, change_style) = match line.kind {
+			DiffLineKind::Context => (self.context_style, self.context_style),
+			DiffLineKind::Added => (self.added_style, self.added_change_style),
+			DiffLineKind::Removed => (self.removed_style, self.removed_change_style),
+		};
+		let base = self.style.patch(style);
+		let changed = base.patch(change_style);
+		buf.set_style(row, base);
+
+		let mut utf8 = [0; 4];
+		let sign = line.kind.sign().encode_utf8(&mut utf8);
+		let (mut x, _) = buf.set_stringn(area.x, y, sign, usize::from(area.width), base);
+		for (i, c) in line.text.char_indices() {
+			let remaining = usize::from(area.right().saturating_sub(x));
+			if remaining == 0 {
+				break;
+			}
+			let style = if line.changes.iter().any(|range| range.contains(&i)) {
+				changed
+			} else {
+				base
+			};
+			(x, _) = buf.set_stringn(x, y, c.encode_utf8(&mut utf8), remaining, style);
+		}
+	}
+}
+
+/// Returns how many lines at the start of `lines` are of `kind`.
+fn run(lines: &[DiffLine], kind: DiffLineKind) -> usize {
+	lines.iter().take_while(|line| line.kind == kind).count()
+}
+
+impl StatefulWidget for DiffView<'_> {
+	type State = DiffViewState;
+
+	fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		let area = area.intersection(buf.area);
+		buf.set_style(area, self.style);
+		let area = match self.block.take() {
+			Some(block) => {
+				let inner = block.inner(area);
+				block.render(area, buf);
+				inner
+			}
+			None => area,
+		};
+		if area.is_empty() {
+			return;
+		}
+
+		let split = self.side_by_side
+			&& area.width >= self.min_side_width.saturating_mul(2).saturating_add(1);
+		let (rows, hunk_rows) = self.rows(split);
+		let height = usize::from(area.height);
+		let (area, bar) = if rows.len() > height {
+			area.split_right_column()
+		} else {
+			(area, Rect::ZERO)
+		};
+		state.update(rows.len(), height, hunk_rows);
+
+		let left_width = area.width.saturating_sub(1) / 2;
+		let left = Rect::new(area.x, area.y, left_width, area.height);
+		let right = Rect::new(
+			left.right() + 1,
+			area.y,
+			area.width.saturating_sub(left_width + 1),
+			area.height,
+		);
+		for (i, row) in rows.iter().skip(state.offset).take(height).enumerate() {
+			let y = area.y + i as u16;
+			match *row {
+				DiffRow::Header(hunk) => {
+					let style = self.style.patch(self.header_style);
+					buf.set_style(Rect::new(area.x, y, area.width, 1), style);
+					buf.set_stringn(area.x, y, &hunk.header, usize::from(area.width), style);
+				}
+				DiffRow::Inline(line) => self.render_line(area, y, Some(line), buf),
+				DiffRow::Split(old, new) => {
+					self.render_line(left, y, old, buf);
+					buf.set_string(left.right(), y, line::VERTICAL, self.style);
+					self.render_line(right, y, new, buf);
+				}
+			}
+		}
+
+		let mut bar_state = ScrollbarState::for_viewport(rows.len(), height, state.offset);
+		Scrollbar::new(ScrollbarOrientation::VerticalRight)
+			.symbols(scrollbar::VERTICAL)
+			.begin_symbol(None)
+			.end_symbol(None)
+			.style(self.style)
+			.thumb_style(self.style.patch(self.scrollbar_style))
+			.render(bar, buf, &mut bar_state);
+	}
+}
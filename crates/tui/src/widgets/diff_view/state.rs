@@ -0,0 +1,108 @@
+//! Scroll position and hunk navigation.
+
+use alloc::vec::Vec;
+
+/// A key the diff view knows how to handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffViewKey {
+	/// Scroll up a row.
+	Up,
+	/// Scroll down a row.
+	Down,
+	/// Scroll up a page.
+	PageUp,
+	/// Scroll down a page.
+	PageDown,
+	/// Scroll to the top.
+	Home,
+	/// Scroll to the bottom.
+	End,
+	/// Scroll to the next hunk.
+	NextHunk,
+	/// Scroll to the previous hunk.
+	PrevHunk,
+}
+
+/// State of a [`DiffView`](super::DiffView): which row is at the top.
+///
+/// Row positions depend on the layout picked at render time, so the state
+/// records where each hunk starts as of the last render and navigates by
+/// those.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DiffViewState {
+	/// Index of the first visible row.
+	pub(crate) offset: usize,
+	/// Number of rows, as of the last render.
+	pub(crate) rows: usize,
+	/// Number of rows visible at once, as of the last render.
+	pub(crate) page: usize,
+	/// Row of each hunk header, as of the last render.
+	pub(crate) hunk_rows: Vec<usize>,
+}
+
+impl DiffViewState {
+	/// Creates a state scrolled to the top.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the index of the first visible row.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	/// Returns the index of the hunk at the top of the view, if any.
+	///
+	/// A hunk counts from its header until the next hunk's header.
+	pub fn current_hunk(&self) -> Option<usize> {
+		self.hunk_rows.iter().rposition(|&row| row <= self.offset)
+	}
+
+	/// Scrolls so the next hunk's header is at the top. Returns false if
+	/// the view could not move down to one.
+	pub fn next_hunk(&mut self) -> bool {
+		let offset = self.offset;
+		if let Some(&row) = self.hunk_rows.iter().find(|&&row| row > self.offset) {
+			self.scroll_to(row);
+		}
+		self.offset != offset
+	}
+
+	/// Scrolls so the previous hunk's header is at the top. Returns false if
+	/// there is none above.
+	pub fn prev_hunk(&mut self) -> bool {
+		let offset = self.offset;
+		if let Some(&row) = self.hunk_rows.iter().rev().find(|&&row| row < self.offset) {
+			self.scroll_to(row);
+		}
+		self.offset != offset
+	}
+
+	/// Handles a key. Returns true if it was used.
+	pub fn handle_key(&mut self, key: DiffViewKey) -> bool {
+		match key {
+			DiffViewKey::Up => self.scroll_to(self.offset.saturating_sub(1)),
+			DiffViewKey::Down => self.scroll_to(self.offset + 1),
+			DiffViewKey::PageUp => self.scroll_to(self.offset.saturating_sub(self.page)),
+			DiffViewKey::PageDown => self.scroll_to(self.offset + self.page),
+			DiffViewKey::Home => self.scroll_to(0),
+			DiffViewKey::End => self.scroll_to(usize::MAX),
+			DiffViewKey::NextHunk => return self.next_hunk(),
+			DiffViewKey::PrevHunk => return self.prev_hunk(),
+		}
+		true
+	}
+
+	/// Scrolls to `row`, keeping a full page visible where possible.
+	fn scroll_to(&mut self, row: usize) {
+		self.offset = row.min(self.rows.saturating_sub(self.page));
+	}
+
+	/// Records the layout of the last render and clamps the offset to it.
+	pub(crate) fn update(&mut self, rows: usize, page: usize, hunk_rows: Vec<usize>) {
+		self.rows = rows;
+		self.page = page.max(1);
+		self.hunk_rows = hunk_rows;
+		self.scroll_to(self.offset);
+	}
+}
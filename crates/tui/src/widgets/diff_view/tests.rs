@@ -0,0 +1,166 @@
+use super::*;
+use crate::Terminal;
+use crate::backend::TestBackend;
+
+const OLD: &str = "fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n\nfn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+const NEW: &str = "fn main() {\n    let x = 2;\n    println!(\"{x}\");\n}\n\nfn a() {}\nfn b() {}\nfn c() {}\nfn e() {}\nfn f() {}\n";
+
+/// The fixture as `git diff` prints it.
+const PATCH: &str = "\
+diff --git a/main.rs b/main.rs
+--- a/main.rs
++++ b/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+     println!(\"{x}\");
+@@ -8,2 +8,3 @@
+ fn c() {}
+-fn d() {}
++fn e() {}
++fn f() {}
+";
+
+/// Renders `view` into a `width` by `height` terminal.
+fn draw(
+	view: DiffView<'_>,
+	state: &mut DiffViewState,
+	width: u16,
+	height: u16,
+) -> Terminal<TestBackend> {
+	let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+	terminal
+		.draw(|frame| frame.render_stateful_widget(view, frame.area(), state))
+		.unwrap();
+	terminal
+}
+
+#[test]
+fn diff_between_matches_unified_patch() {
+	let diff = Diff::between(OLD, NEW, 1);
+	assert_eq!(diff, Diff::parse_unified(PATCH));
+	assert_eq!(diff.hunks.len(), 2);
+	assert_eq!(diff.hunks[1].header, "@@ -8,2 +8,3 @@");
+	assert!(Diff::between(OLD, OLD, 3).is_empty());
+}
+
+#[test]
+fn hunk_headers_number_empty_sides_from_the_line_before() {
+	let added = Diff::between("a\nb\n", "a\nx\nb\n", 0);
+	assert_eq!(added.hunks[0].header, "@@ -1,0 +2,1 @@");
+	let removed = Diff::between("a\nx\nb\n", "a\nb\n", 0);
+	assert_eq!(removed.hunks[0].header, "@@ -2,1 +1,0 @@");
+}
+
+#[test]
+fn modified_lines_mark_the_changed_middle() {
+	let diff = Diff::parse_unified(PATCH);
+	let lines = &diff.hunks[0].lines;
+	for line in &lines[1..3] {
+		assert_eq!(line.changes.len(), 1);
+		assert_eq!(line.changes[0], 12..13);
+	}
+	// Unpaired additions have no intra-line changes.
+	assert!(diff.hunks[1].lines[3].changes.is_empty());
+}
+
+#[test]
+fn renders_inline_hunks_with_styles() {
+	let diff = Diff::parse_unified(PATCH);
+	let view = DiffView::new(&diff)
+		.header_style(Style::new().cyan())
+		.added_style(Style::new().green())
+		.removed_style(Style::new().red())
+		.added_change_style(Style::new().bold());
+	let mut expected = Buffer::with_lines([
+		"@@ -1,3 +1,3 @@     ",
+		" fn main() {        ",
+		"-    let x = 1;     ",
+		"+    let x = 2;     ",
+		"     println!(\"{x}\")",
+		"@@ -8,2 +8,3 @@     ",
+		" fn c() {}          ",
+		"-fn d() {}          ",
+		"+fn e() {}          ",
+		"+fn f() {}          ",
+	]);
+	for y in [0, 5] {
+		expected.set_style(Rect::new(0, y, 20, 1), Style::new().cyan());
+	}
+	for y in [2, 7] {
+		expected.set_style(Rect::new(0, y, 20, 1), Style::new().red());
+	}
+	for y in [3, 8, 9] {
+		expected.set_style(Rect::new(0, y, 20, 1), Style::new().green());
+	}
+	expected.set_style(Rect::new(13, 3, 1, 1), Style::new().bold());
+	expected.set_style(Rect::new(4, 8, 1, 1), Style::new().bold());
+
+	let mut state = DiffViewState::new();
+	draw(view, &mut state, 20, 10)
+		.backend()
+		.assert_buffer(&expected);
+}
+
+#[test]
+fn renders_side_by_side_and_falls_back_when_narrow() {
+	let diff = Diff::parse_unified(PATCH);
+	let mut state = DiffViewState::new();
+	draw(
+		DiffView::new(&diff).side_by_side(true).min_side_width(10),
+		&mut state,
+		23,
+		10,
+	)
+	.backend()
+	.assert_buffer_lines([
+		"@@ -1,3 +1,3 @@        ",
+		" fn main() │ fn main() ",
+		"-    let x │+    let x ",
+		"     printl│     printl",
+		"@@ -8,2 +8,3 @@        ",
+		" fn c() {} │ fn c() {} ",
+		"-fn d() {} │+fn e() {} ",
+		"           │+fn f() {} ",
+		"                       ",
+		"                       ",
+	]);
+
+	draw(
+		DiffView::new(&diff).side_by_side(true).min_side_width(12),
+		&mut state,
+		23,
+		4,
+	)
+	.backend()
+	.assert_buffer_lines([
+		"@@ -1,3 +1,3 @@       █",
+		" fn main() {          █",
+		"-    let x = 1;       │",
+		"+    let x = 2;       │",
+	]);
+}
+
+#[test]
+fn hunk_navigation_scrolls_to_headers() {
+	let diff = Diff::parse_unified(PATCH);
+	let mut state = DiffViewState::new();
+	assert_eq!(state.current_hunk(), None);
+	draw(DiffView::new(&diff), &mut state, 20, 3);
+	assert_eq!(state.current_hunk(), Some(0));
+
+	assert!(state.next_hunk());
+	assert_eq!(state.offset(), 5);
+	assert_eq!(state.current_hunk(), Some(1));
+	// The last hunk is already at the top, as far down as the view goes.
+	assert!(!state.handle_key(DiffViewKey::NextHunk));
+
+	state.handle_key(DiffViewKey::End);
+	assert_eq!(state.offset(), 7);
+	assert!(state.handle_key(DiffViewKey::PrevHunk));
+	assert_eq!(state.offset(), 5);
+	assert!(state.prev_hunk());
+	assert_eq!(state.offset(), 0);
+	assert!(!state.prev_hunk());
+}
@@ -1,7 +1,13 @@
 //! A widget for displaying key bindings with tree-style connectors.
 //!
 //! [`KeyTree`] renders a root key with child continuations using
-//! box-drawing characters.
+//! box-drawing characters. Widths are measured in terminal cells, child
+//! descriptions are aligned after the widest key, and descriptions that do
+//! not fit end in an ellipsis.
+//!
+//! Children that do not fit the area's height flow top to bottom into
+//! further columns, each with its own connectors. When even that is not
+//! enough, the last row reads "+N more".
 //!
 //! # Example
 //!
@@ -16,8 +22,11 @@
 //! ```
 
 use alloc::borrow::Cow;
+use alloc::format;
 use alloc::vec::Vec;
 
+use unicode_width::UnicodeWidthStr;
+
 use crate::buffer::Buffer;
 use crate::layout::Rect;
 use crate::style::Style;
@@ -109,6 +118,8 @@ pub struct KeyTree<'a> {
 	suffix_style: Style,
 	/// Style for tree connector lines.
 	line_style: Style,
+	/// Most columns children may flow into, or `None` for as many as fit.
+	max_columns: Option<usize>,
 }
 
 impl<'a> KeyTree<'a> {
@@ -178,6 +189,47 @@ impl<'a> KeyTree<'a> {
 		self.line_style = style;
 		self
 	}
+
+	/// Limits how many columns children flow into when they do not fit
+	/// the area's height. Zero is treated as one.
+	#[must_use]
+	pub const fn max_columns(mut self, max: usize) -> Self {
+		self.max_columns = Some(if max == 0 { 1 } else { max });
+		self
+	}
+}
+
+/// Blank cells between columns of children.
+const COLUMN_GAP: u16 = 2;
+
+/// Marker drawn in the last visible cell of a cut description.
+const ELLIPSIS: &str = "…";
+
+/// Draws `text` at `(x, y)` within `max_width` cells, returning the number
+/// of cells used.
+///
+/// Text is clipped at grapheme cluster boundaries, so a wide character that
+/// does not fit is dropped whole. With `ellipsis` set, text that does not
+/// fit ends in [`ELLIPSIS`].
+fn draw_clipped(
+	buf: &mut Buffer,
+	x: u16,
+	y: u16,
+	text: &str,
+	max_width: u16,
+	style: Style,
+	ellipsis: bool,
+) -> u16 {
+	if max_width == 0 {
+		return 0;
+	}
+	if !ellipsis || text.width() <= usize::from(max_width) {
+		let (end, _) = buf.set_stringn(x, y, text, usize::from(max_width), style);
+		return end - x;
+	}
+	let (end, _) = buf.set_stringn(x, y, text, usize::from(max_width - 1), style);
+	let (end, _) = buf.set_stringn(end, y, ELLIPSIS, 1, style);
+	end - x
 }
 
 impl Widget for KeyTree<'_> {
@@ -186,20 +238,26 @@ impl Widget for KeyTree<'_> {
 			return;
 		}
 
+		let right = area.right();
 		let mut y = area.y;
 		let ancestor_indent = self.ancestors.len() as u16 * 2;
 
-		let root_width = self.root.len().min(area.width as usize);
-		buf.set_stringn(area.x, y, &self.root, root_width, self.ancestor_style);
-		let mut x = area.x + root_width as u16 + 1;
-		if let Some(ref desc) = self.root_desc {
-			if x < area.right() {
-				let desc_width = desc.len().min((area.right() - x) as usize);
-				buf.set_stringn(x, y, desc, desc_width, self.desc_style);
-				x += desc_width as u16;
-			}
+		let root_width = draw_clipped(
+			buf,
+			area.x,
+			y,
+			&self.root,
+			area.width,
+			self.ancestor_style,
+			false,
+		);
+		let mut x = area.x + root_width + 1;
+		if let Some(ref desc) = self.root_desc
+			&& x < right
+		{
+			x += draw_clipped(buf, x, y, desc, right - x, self.desc_style, true);
 		}
-		if !self.ancestors.is_empty() && x < area.right() {
+		if !self.ancestors.is_empty() && x < right {
 			buf.set_string(x, y, "…", self.suffix_style);
 		}
 		y += 1;
@@ -214,31 +272,35 @@ impl Widget for KeyTree<'_> {
 			let indent = i as u16 * 2;
 			let x = area.x + indent;
 
-			if x < area.right() {
+			if x < right {
 				buf.set_string(x, y, self.symbols.corner, self.line_style);
 			}
-			if x + 1 < area.right() {
+			if x + 1 < right {
 				buf.set_string(x + 1, y, self.symbols.horizontal, self.line_style);
 			}
-			if x + 2 < area.right() {
-				let key_width = ancestor.key.len().min((area.right() - x - 2) as usize);
-				buf.set_stringn(x + 2, y, &ancestor.key, key_width, self.ancestor_style);
-				let mut desc_x = x + 2 + key_width as u16 + 1;
-				if desc_x < area.right() && !ancestor.description.is_empty() {
-					let desc_width = ancestor
-						.description
-						.len()
-						.min((area.right() - desc_x) as usize);
-					buf.set_stringn(
+			if x + 2 < right {
+				let key_width = draw_clipped(
+					buf,
+					x + 2,
+					y,
+					&ancestor.key,
+					right - x - 2,
+					self.ancestor_style,
+					false,
+				);
+				let mut desc_x = x + 2 + key_width + 1;
+				if desc_x < right && !ancestor.description.is_empty() {
+					desc_x += draw_clipped(
+						buf,
 						desc_x,
 						y,
 						&ancestor.description,
-						desc_width,
+						right - desc_x,
 						self.desc_style,
+						true,
 					);
-					desc_x += desc_width as u16;
 				}
-				if has_children_below && desc_x < area.right() {
+				if has_children_below && desc_x < right {
 					buf.set_string(desc_x, y, "…", self.suffix_style);
 				}
 			}
@@ -247,60 +309,187 @@ impl Widget for KeyTree<'_> {
 
 		if y < area.bottom() {
 			let x = area.x + ancestor_indent;
-			if x < area.right() {
+			if x < right {
 				buf.set_string(x, y, self.symbols.vertical, self.line_style);
 			}
 			y += 1;
 		}
 
-		for (i, node) in self.children.iter().enumerate() {
-			if y >= area.bottom() {
-				break;
-			}
+		let x = area.x + ancestor_indent;
+		let rows = usize::from(area.bottom().saturating_sub(y));
+		if rows == 0 || x >= right {
+			return;
+		}
+		if self.children.len() <= rows {
+			self.render_column(buf, &self.children, x, y, right, true);
+			return;
+		}
+		self.render_columns(buf, Rect::new(x, y, right - x, rows as u16));
+	}
+}
+
+impl KeyTree<'_> {
+	/// Returns the number of cells a child row needs when its key column
+	/// is `key_column` cells wide.
+	fn child_width(node: &KeyTreeNode<'_>, key_column: u16) -> u16 {
+		let suffix = node.suffix.as_deref().map_or(0, UnicodeWidthStr::width);
+		(2 + usize::from(key_column) + 1 + node.description.width() + suffix)
+			.min(usize::from(u16::MAX)) as u16
+	}
+
+	/// Returns the width of the widest key in `nodes`.
+	fn key_column(nodes: &[KeyTreeNode<'_>]) -> u16 {
+		nodes
+			.iter()
+			.map(|node| node.key.width())
+			.max()
+			.unwrap_or_default()
+			.min(usize::from(u16::MAX)) as u16
+	}
 
-			let is_last = i == self.children.len() - 1;
+	/// Draws `nodes` one per row from `(x, y)`, clipped at `right`.
+	///
+	/// The last node gets a corner connector when `closed` is set, and a
+	/// branch otherwise, for a column that continues below.
+	fn render_column(
+		&self,
+		buf: &mut Buffer,
+		nodes: &[KeyTreeNode<'_>],
+		x: u16,
+		y: u16,
+		right: u16,
+		closed: bool,
+	) {
+		// Descriptions line up in a column after the widest key.
+		let key_column = Self::key_column(nodes);
+
+		for (i, node) in nodes.iter().enumerate() {
+			let y = y + i as u16;
+			let is_last = closed && i == nodes.len() - 1;
 			let connector = if is_last {
 				self.symbols.corner
 			} else {
 				self.symbols.branch
 			};
 
-			let x = area.x + ancestor_indent;
-			if x < area.right() {
+			if x < right {
 				buf.set_string(x, y, connector, self.line_style);
 			}
 
 			let mut x = x + 1;
-			if x < area.right() {
+			if x < right {
 				buf.set_string(x, y, self.symbols.horizontal, self.line_style);
 				x += 1;
 			}
 
-			if x < area.right() {
-				let key_width = node.key.len().min((area.right() - x) as usize);
-				buf.set_stringn(x, y, &node.key, key_width, self.key_style);
-				x += key_width as u16;
+			if x < right {
+				draw_clipped(buf, x, y, &node.key, right - x, self.key_style, false);
+				x = x.saturating_add(key_column).min(right);
 			}
 
-			if x < area.right() {
+			if x < right {
 				buf.set_string(x, y, " ", self.desc_style);
 				x += 1;
 			}
 
-			if x < area.right() {
-				let desc_width = node.description.len().min((area.right() - x) as usize);
-				buf.set_stringn(x, y, &node.description, desc_width, self.desc_style);
-				x += desc_width as u16;
+			if x < right {
+				x += draw_clipped(
+					buf,
+					x,
+					y,
+					&node.description,
+					right - x,
+					self.desc_style,
+					true,
+				);
 			}
 
-			if let Some(ref suffix) = node.suffix {
-				if x < area.right() {
-					let suffix_width = suffix.len().min((area.right() - x) as usize);
-					buf.set_stringn(x, y, suffix, suffix_width, self.suffix_style);
-				}
+			if let Some(ref suffix) = node.suffix
+				&& x < right
+			{
+				draw_clipped(buf, x, y, suffix, right - x, self.suffix_style, false);
 			}
+		}
+	}
 
-			y += 1;
+	/// Flows the children top to bottom into as many columns as `area`
+	/// and [`Self::max_columns`] allow, ending in a "+N more" row when some
+	/// still do not fit.
+	fn render_columns(&self, buf: &mut Buffer, area: Rect) {
+		let rows = usize::from(area.height);
+		let count = self.children.len();
+
+		// Never split into columns too narrow to show a key and one cell of
+		// description.
+		let min_width = 2 + Self::key_column(&self.children) + 2;
+		let fit = (usize::from(area.width) + usize::from(COLUMN_GAP))
+			/ usize::from(min_width + COLUMN_GAP);
+		let columns = count
+			.div_ceil(rows)
+			.min(self.max_columns.unwrap_or(usize::MAX))
+			.min(fit)
+			.max(1);
+
+		let shown = if columns * rows >= count {
+			count
+		} else {
+			columns * rows - 1
+		};
+		let more = (shown < count).then(|| format!("+{} more", count - shown));
+
+		let chunks: Vec<&[KeyTreeNode<'_>]> = (0..columns)
+			.map(|column| {
+				let start = (column * rows).min(shown);
+				&self.children[start..(start + rows).min(shown)]
+			})
+			.collect();
+		let natural: Vec<u16> = chunks
+			.iter()
+			.enumerate()
+			.map(|(column, nodes)| {
+				let key_column = Self::key_column(nodes);
+				let nodes = nodes
+					.iter()
+					.map(|node| Self::child_width(node, key_column))
+					.max()
+					.unwrap_or_default();
+				let more = more
+					.as_deref()
+					.filter(|_| column + 1 == columns)
+					.map_or(0, |more| (2 + more.width()) as u16);
+				nodes.max(more)
+			})
+			.collect();
+
+		// Columns keep their natural widths when they all fit, and share the
+		// area evenly otherwise.
+		let gaps = COLUMN_GAP * (columns as u16 - 1);
+		let fits = natural
+			.iter()
+			.try_fold(gaps, |total, &width| total.checked_add(width))
+			.is_some_and(|total| total <= area.width);
+		let even = area.width.saturating_sub(gaps) / columns as u16;
+
+		let mut x = area.x;
+		for (column, nodes) in chunks.iter().enumerate() {
+			let width = if fits { natural[column] } else { even };
+			let right = x.saturating_add(width).min(area.right());
+			let is_last = column + 1 == columns;
+			self.render_column(buf, nodes, x, area.y, right, !is_last || more.is_none());
+
+			if is_last && let Some(ref more) = more {
+				let y = area.bottom() - 1;
+				if x < right {
+					buf.set_string(x, y, self.symbols.corner, self.line_style);
+				}
+				if x + 1 < right {
+					buf.set_string(x + 1, y, self.symbols.horizontal, self.line_style);
+				}
+				if x + 2 < right {
+					draw_clipped(buf, x + 2, y, more, right - x - 2, self.suffix_style, false);
+				}
+			}
+			x = right.saturating_add(COLUMN_GAP);
 		}
 	}
 }
@@ -388,4 +577,121 @@ mod tests {
 		assert!(lines[3].contains("├─n Next"));
 		assert!(lines[4].contains("╰─p Previous"));
 	}
+
+	/// Renders `tree` and compares it against `expected` rows, padded to
+	/// `width` cells.
+	fn assert_renders(tree: KeyTree<'_>, width: u16, expected: &[&str]) {
+		use unicode_width::UnicodeWidthStr;
+		let area = Rect::new(0, 0, width, expected.len() as u16);
+		let mut buf = Buffer::empty(area);
+		tree.render(area, &mut buf);
+
+		let rows: Vec<String> = expected
+			.iter()
+			.map(|row| {
+				let mut row = row.to_string();
+				row.extend(core::iter::repeat_n(' ', usize::from(width) - row.width()));
+				row
+			})
+			.collect();
+		assert_eq!(buf, Buffer::with_lines(rows));
+	}
+
+	#[test]
+	fn aligns_descriptions_after_widest_key() {
+		let children = vec![
+			KeyTreeNode::new("a", "one"),
+			KeyTreeNode::new("→x", "two"),
+			KeyTreeNode::new("你", "three"),
+		];
+		assert_renders(
+			KeyTree::new("g", children),
+			12,
+			&["g", "│", "├─a  one", "├─→x two", "╰─你 three"],
+		);
+	}
+
+	#[test]
+	fn wide_description_is_cut_with_ellipsis() {
+		let children = vec![KeyTreeNode::new("a", "你好世界")];
+		assert_renders(
+			KeyTree::new("g", children.clone()),
+			9,
+			&["g", "│", "╰─a 你好…"],
+		);
+
+		// A wide character that straddles the edge is dropped whole.
+		assert_renders(KeyTree::new("g", children), 8, &["g", "│", "╰─a 你…"]);
+	}
+
+	#[test]
+	fn combining_characters_take_one_cell() {
+		let children = vec![KeyTreeNode::new("e", "cafe\u{301}")];
+		assert_renders(
+			KeyTree::new("g", children.clone()),
+			8,
+			&["g", "│", "╰─e cafe\u{301}"],
+		);
+		assert_renders(KeyTree::new("g", children), 7, &["g", "│", "╰─e ca…"]);
+	}
+
+	#[test]
+	fn wide_root_description_is_measured_by_width() {
+		let children = vec![KeyTreeNode::new("w", "Window")];
+		let tree = KeyTree::new("空格", children).root_desc("窗口管理");
+		assert_renders(tree, 10, &["空格 窗口…", "│", "╰─w Window"]);
+	}
+
+	fn numbered(count: usize) -> Vec<KeyTreeNode<'static>> {
+		const DESCS: [&str; 8] = [
+			"one", "two", "three", "four", "five", "six", "seven", "eight",
+		];
+		(0..count)
+			.map(|i| KeyTreeNode::new(((b'a' + i as u8) as char).to_string(), DESCS[i]))
+			.collect()
+	}
+
+	#[test]
+	fn flows_children_into_columns() {
+		assert_renders(
+			KeyTree::new("g", numbered(6)),
+			25,
+			&[
+				"g",
+				"│",
+				"├─a one    ├─d four",
+				"├─b two    ├─e five",
+				"╰─c three  ╰─f six",
+			],
+		);
+	}
+
+	#[test]
+	fn shows_count_of_children_that_do_not_fit() {
+		assert_renders(
+			KeyTree::new("g", numbered(8)).max_columns(2),
+			25,
+			&[
+				"g",
+				"│",
+				"├─a one    ├─d four",
+				"├─b two    ├─e five",
+				"╰─c three  ╰─+3 more",
+			],
+		);
+		assert_renders(
+			KeyTree::new("g", numbered(4)).max_columns(1),
+			20,
+			&["g", "│", "├─a one", "╰─+3 more"],
+		);
+	}
+
+	#[test]
+	fn narrow_columns_share_width_evenly() {
+		assert_renders(
+			KeyTree::new("g", numbered(4)),
+			14,
+			&["g", "│", "├─a o…  ├─c t…", "╰─b t…  ╰─d f…"],
+		);
+	}
 }
@@ -0,0 +1,172 @@
+//! The [`Markdown`] widget renders a subset of Markdown, such as hover and
+//! documentation text from a language server.
+//!
+//! Headings, `**strong**` and `*emphasis*`, `` `inline code` ``, fenced
+//! code blocks, bullet and numbered lists and links are recognised; links
+//! draw their text followed by the dimmed URL. The widget wraps words to
+//! the width it is given, and [`Markdown::height`] measures the result so a
+//! popup can be sized before rendering. Anything else is drawn as plain
+//! text.
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::markdown::Markdown;
+//!
+//! let docs = Markdown::new("Returns the **length** of `self`.");
+//! assert_eq!(docs.height(16), 2);
+//! ```
+
+use self::parse::Marks;
+use self::wrap::RowKind;
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::{Color, Modifier, Style};
+use crate::widgets::Widget;
+
+/// Block and inline parsing.
+mod parse;
+/// Word wrapping into rows.
+mod wrap;
+
+#[cfg(test)]
+mod tests;
+
+/// Markdown text wrapped to the area it is rendered in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markdown<'a> {
+	/// The Markdown source.
+	text: &'a str,
+	/// Base style of the text.
+	style: Style,
+	/// Style patched over headings.
+	heading_style: Style,
+	/// Style patched over inline code.
+	code_style: Style,
+	/// Style patched over code block rows, across the full width.
+	code_block_style: Style,
+	/// Style patched over link text.
+	link_style: Style,
+	/// Style patched over link URLs.
+	url_style: Style,
+}
+
+impl<'a> Markdown<'a> {
+	/// Creates a widget for the Markdown `text`, with bold headings, code on
+	/// a dark gray background, underlined links and dimmed URLs.
+	pub const fn new(text: &'a str) -> Self {
+		Self {
+			text,
+			style: Style::new(),
+			heading_style: Style::new().add_modifier(Modifier::BOLD),
+			code_style: Style::new().bg(Color::DarkGray),
+			code_block_style: Style::new().bg(Color::DarkGray),
+			link_style: Style::new().add_modifier(Modifier::UNDERLINED),
+			url_style: Style::new().add_modifier(Modifier::DIM),
+		}
+	}
+
+	/// Sets the base style of the text.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over headings.
+	#[must_use]
+	pub const fn heading_style(mut self, style: Style) -> Self {
+		self.heading_style = style;
+		self
+	}
+
+	/// Sets the style patched over inline code.
+	#[must_use]
+	pub const fn code_style(mut self, style: Style) -> Self {
+		self.code_style = style;
+		self
+	}
+
+	/// Sets the style patched over code blocks, across the full width.
+	#[must_use]
+	pub const fn code_block_style(mut self, style: Style) -> Self {
+		self.code_block_style = style;
+		self
+	}
+
+	/// Sets the style patched over link text.
+	#[must_use]
+	pub const fn link_style(mut self, style: Style) -> Self {
+		self.link_style = style;
+		self
+	}
+
+	/// Sets the style patched over link URLs.
+	#[must_use]
+	pub const fn url_style(mut self, style: Style) -> Self {
+		self.url_style = style;
+		self
+	}
+
+	/// Returns the number of rows the text takes when wrapped to `width`.
+	pub fn height(&self, width: u16) -> u16 {
+		let rows = wrap::layout(self.text, width).len();
+		u16::try_from(rows).unwrap_or(u16::MAX)
+	}
+
+	/// Returns the style of text with `marks` in a row with `base` style.
+	fn marks_style(&self, base: Style, marks: Marks) -> Style {
+		let mut style = base;
+		if marks.strong {
+			style = style.add_modifier(Modifier::BOLD);
+		}
+		if marks.emphasis {
+			style = style.add_modifier(Modifier::ITALIC);
+		}
+		if marks.code {
+			style = style.patch(self.code_style);
+		}
+		if marks.link {
+			style = style.patch(self.link_style);
+		}
+		if marks.url {
+			style = style.patch(self.url_style);
+		}
+		style
+	}
+}
+
+impl Widget for Markdown<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		Widget::render(&self, area, buf);
+	}
+}
+
+impl Widget for &Markdown<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		buf.set_style(area, self.style);
+		let rows = wrap::layout(self.text, area.width);
+		for (row, y) in rows.iter().zip(area.top()..area.bottom()) {
+			let base = match row.kind {
+				RowKind::Text => self.style,
+				RowKind::Heading => self.style.patch(self.heading_style),
+				RowKind::Code => {
+					let style = self.style.patch(self.code_block_style);
+					buf.set_style(Rect::new(area.x, y, area.width, 1), style);
+					style
+				}
+			};
+			let mut x = area.x + row.indent;
+			for piece in &row.pieces {
+				let style = if row.kind == RowKind::Code {
+					base
+				} else {
+					self.marks_style(base, piece.marks)
+				};
+				let width = usize::from(area.right().saturating_sub(x));
+				(x, _) = buf.set_stringn(x, y, &piece.text, width, style);
+			}
+		}
+	}
+}
@@ -0,0 +1,510 @@
+//! Block and inline Markdown parsing.
+//!
+//! Only the subset the [`Markdown`](super::Markdown) widget draws is
+//! recognised. Anything else, including unterminated emphasis and malformed
+//! links, is kept as literal text.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Columns a tab advances to in code blocks.
+const TAB_WIDTH: usize = 4;
+
+/// A block-level element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Block<'a> {
+	/// An ATX heading, `# Title`.
+	Heading(&'a str),
+	/// Consecutive text lines, joined with spaces.
+	Paragraph(String),
+	/// A bullet or numbered list item.
+	Item {
+		/// Nesting level, from the item's indentation.
+		depth: usize,
+		/// Marker drawn before the text, `•` or `1.`.
+		marker: String,
+		/// The item's text, joined with its continuation lines.
+		text: String,
+	},
+	/// A fenced code block, with tabs expanded.
+	Code(Vec<String>),
+}
+
+/// Inline formatting applied to a run of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Marks {
+	/// Inside `**strong**`.
+	pub(crate) strong: bool,
+	/// Inside `*emphasis*`.
+	pub(crate) emphasis: bool,
+	/// Inside `` `code` ``.
+	pub(crate) code: bool,
+	/// The text of a link.
+	pub(crate) link: bool,
+	/// The target of a link.
+	pub(crate) url: bool,
+}
+
+/// A run of text with the same marks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Piece {
+	/// The text.
+	pub(crate) text: String,
+	/// Its formatting.
+	pub(crate) marks: Marks,
+}
+
+/// Splits `text` into blocks.
+pub(crate) fn blocks(text: &str) -> Vec<Block<'_>> {
+	let lines: Vec<&str> = text.lines().collect();
+	let mut blocks = Vec::new();
+	let mut i = 0;
+	while i < lines.len() {
+		let line = lines[i];
+		let trimmed = line.trim_start();
+		i += 1;
+		if trimmed.is_empty() {
+			continue;
+		}
+		let indent = line.len() - trimmed.len();
+
+		if let Some((fence, len)) = fence(trimmed) {
+			let mut code = Vec::new();
+			while let Some(&line) = lines.get(i) {
+				i += 1;
+				if closes_fence(line, fence, len) {
+					break;
+				}
+				code.push(expand_tabs(strip_indent(line, indent)));
+			}
+			blocks.push(Block::Code(code));
+		} else if let Some(title) = heading(trimmed) {
+			blocks.push(Block::Heading(title));
+		} else {
+			let (marker, first) = match list_marker(trimmed) {
+				Some((marker, rest)) => (Some(marker), rest),
+				None => (None, trimmed),
+			};
+			let mut text = String::from(first.trim());
+			while let Some(&line) = lines.get(i)
+				&& continues_paragraph(line)
+			{
+				text.push(' ');
+				text.push_str(line.trim());
+				i += 1;
+			}
+			blocks.push(match marker {
+				Some(marker) => Block::Item {
+					depth: indent / 2,
+					marker,
+					text,
+				},
+				None => Block::Paragraph(text),
+			});
+		}
+	}
+	blocks
+}
+
+/// Returns the fence character and length if `line` opens a code block.
+fn fence(line: &str) -> Option<(char, usize)> {
+	let c = line.chars().next().filter(|&c| c == '`' || c == '~')?;
+	let len = line.chars().take_while(|&ch| ch == c).count();
+	(len >= 3).then_some((c, len))
+}
+
+/// Returns true if `line` closes a block opened by `len` of `fence`.
+fn closes_fence(line: &str, fence: char, len: usize) -> bool {
+	let trimmed = line.trim();
+	trimmed.chars().take_while(|&c| c == fence).count() >= len
+		&& trimmed.trim_start_matches(fence).is_empty()
+}
+
+/// Returns the title if `line` is an ATX heading.
+fn heading(line: &str) -> Option<&str> {
+	let level = line.chars().take_while(|&c| c == '#').count();
+	let rest = &line[level..];
+	if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+		return None;
+	}
+	let rest = rest.trim();
+	// A closing run of `#` is only decoration when set off by a space.
+	let closed = rest.trim_end_matches('#');
+	Some(if closed.is_empty() || closed.ends_with([' ', '\t']) {
+		closed.trim_end()
+	} else {
+		rest
+	})
+}
+
+/// Returns the marker to draw and the remaining text if `line` starts a
+/// list item.
+fn list_marker(line: &str) -> Option<(String, &str)> {
+	if let Some(rest) = line
+		.strip_prefix(['-', '*', '+'])
+		.filter(|rest| rest.starts_with([' ', '\t']))
+	{
+		return Some((String::from("•"), rest));
+	}
+	let digits = line.chars().take_while(char::is_ascii_digit).count();
+	let rest = &line[digits..];
+	if (1..=9).contains(&digits)
+		&& let Some(rest) = rest.strip_prefix(['.', ')'])
+		&& rest.starts_with([' ', '\t'])
+	{
+		return Some((format!("{}.", &line[..digits]), rest));
+	}
+	None
+}
+
+/// Returns true if `line` continues the paragraph or item above it.
+fn continues_paragraph(line: &str) -> bool {
+	let trimmed = line.trim_start();
+	!trimmed.is_empty()
+		&& fence(trimmed).is_none()
+		&& heading(trimmed).is_none()
+		&& list_marker(trimmed).is_none()
+}
+
+/// Removes up to `indent` leading spaces, the indentation of the fence.
+fn strip_indent(line: &str, indent: usize) -> &str {
+	let spaces = line.bytes().take(indent).take_while(|&b| b == b' ').count();
+	&line[spaces..]
+}
+
+/// Replaces tabs with spaces up to the next tab stop.
+fn expand_tabs(line: &str) -> String {
+	let mut out = String::with_capacity(line.len());
+	let mut column = 0;
+	for c in line.chars() {
+		if c == '\t' {
+			let spaces = TAB_WIDTH - column % TAB_WIDTH;
+			out.extend(core::iter::repeat_n(' ', spaces));
+			column += spaces;
+		} else {
+			out.push(c);
+			column += 1;
+		}
+	}
+	out
+}
+
+/// Parses emphasis, inline code and links in `text`.
+pub(crate) fn inline(text: &str) -> Vec<Piece> {
+	let chars: Vec<char> = text.chars().collect();
+	let mut pieces = Vec::new();
+	parse_inline(&chars, Marks::default(), &mut pieces);
+	pieces
+}
+
+/// Parses `chars` with `base` marks, appending to `out`.
+fn parse_inline(chars: &[char], base: Marks, out: &mut Vec<Piece>) {
+	let mut strong: Option<char> = None;
+	let mut emphasis: Option<char> = None;
+	let mut i = 0;
+	while i < chars.len() {
+		let marks = Marks {
+			strong: base.strong || strong.is_some(),
+			emphasis: base.emphasis || emphasis.is_some(),
+			..base
+		};
+		let c = chars[i];
+		match c {
+			'\\' if chars.get(i + 1).is_some_and(char::is_ascii_punctuation) => {
+				push(out, chars[i + 1], marks);
+				i += 2;
+			}
+			'`' => {
+				let run = run_length(chars, i);
+				match find_code_end(chars, i + run, run) {
+					Some(end) => {
+						let code = Marks {
+							code: true,
+							..marks
+						};
+						let mut content = &chars[i + run..end];
+						if content.len() >= 2
+							&& content[0] == ' ' && content[content.len() - 1] == ' '
+						{
+							content = &content[1..content.len() - 1];
+						}
+						for &c in content {
+							push(out, c, code);
+						}
+						i = end + run;
+					}
+					None => {
+						for &c in &chars[i..i + run] {
+							push(out, c, marks);
+						}
+						i += run;
+					}
+				}
+			}
+			'*' | '_' => {
+				let len = run_length(chars, i).min(2);
+				let state = if len == 2 { &mut strong } else { &mut emphasis };
+				let before = i.checked_sub(1).map(|j| chars[j]);
+				let after = chars.get(i + len).copied();
+				let intraword = c == '_'
+					&& before.is_some_and(char::is_alphanumeric)
+					&& after.is_some_and(char::is_alphanumeric);
+
+				if !intraword && *state == Some(c) && before.is_some_and(|b| !b.is_whitespace()) {
+					*state = None;
+				} else if !intraword
+					&& state.is_none()
+					&& after.is_some_and(|a| !a.is_whitespace())
+					&& has_closer(chars, i + len, c, len)
+				{
+					*state = Some(c);
+				} else {
+					for _ in 0..len {
+						push(out, c, marks);
+					}
+				}
+				i += len;
+			}
+			'[' => match link(chars, i) {
+				Some((text, url, end)) => {
+					parse_inline(
+						text,
+						Marks {
+							link: true,
+							..marks
+						},
+						out,
+					);
+					if !url.is_empty() && !url.iter().eq(text.iter()) {
+						let url_marks = Marks { url: true, ..marks };
+						push(out, ' ', marks);
+						for &c in ['('].iter().chain(url).chain(&[')']) {
+							push(out, c, url_marks);
+						}
+					}
+					i = end;
+				}
+				None => {
+					push(out, c, marks);
+					i += 1;
+				}
+			},
+			'<' => match autolink(chars, i) {
+				Some(end) => {
+					for &c in &chars[i + 1..end - 1] {
+						push(
+							out,
+							c,
+							Marks {
+								link: true,
+								..marks
+							},
+						);
+					}
+					i = end;
+				}
+				None => {
+					push(out, c, marks);
+					i += 1;
+				}
+			},
+			_ => {
+				push(out, c, marks);
+				i += 1;
+			}
+		}
+	}
+}
+
+/// Appends `c` to the last piece if it has the same marks.
+fn push(out: &mut Vec<Piece>, c: char, marks: Marks) {
+	match out.last_mut() {
+		Some(piece) if piece.marks == marks => piece.text.push(c),
+		_ => out.push(Piece {
+			text: String::from(c),
+			marks,
+		}),
+	}
+}
+
+/// Returns how many times `chars[start]` repeats from `start`.
+fn run_length(chars: &[char], start: usize) -> usize {
+	chars[start..]
+		.iter()
+		.take_while(|&&c| c == chars[start])
+		.count()
+}
+
+/// Returns the index of a backtick run of exactly `len` at or after `from`.
+fn find_code_end(chars: &[char], from: usize, len: usize) -> Option<usize> {
+	let mut i = from;
+	while i < chars.len() {
+		if chars[i] == '`' {
+			let run = run_length(chars, i);
+			if run == len {
+				return Some(i);
+			}
+			i += run;
+		} else {
+			i += 1;
+		}
+	}
+	None
+}
+
+/// Returns true if `len` of `delim` after a non-space occurs at or after
+/// `from`, so an opening delimiter will be closed.
+fn has_closer(chars: &[char], from: usize, delim: char, len: usize) -> bool {
+	(from + 1..chars.len()).any(|i| {
+		chars[i..].iter().take(len).filter(|&&c| c == delim).count() == len
+			&& !chars[i - 1].is_whitespace()
+			&& (delim != '_' || !chars.get(i + len).is_some_and(|c| c.is_alphanumeric()))
+	})
+}
+
+/// Parses `[text](url)` at `start`, returning the text, the url and the
+/// index after the closing parenthesis.
+fn link(chars: &[char], start: usize) -> Option<(&[char], &[char], usize)> {
+	let mut depth = 0;
+	let mut close = None;
+	for (i, &c) in chars.iter().enumerate().skip(start) {
+		match c {
+			'[' => depth += 1,
+			']' => {
+				depth -= 1;
+				if depth == 0 {
+					close = Some(i);
+					break;
+				}
+			}
+			_ => {}
+		}
+	}
+	let close = close?;
+	if chars.get(close + 1) != Some(&'(') {
+		return None;
+	}
+	let url_start = close + 2;
+	let url_end = url_start + chars[url_start..].iter().position(|&c| c == ')')?;
+	let url = &chars[url_start..url_end];
+	// Drop an optional title, `[text](url "title")`.
+	let url = match url.iter().position(|c| c.is_whitespace()) {
+		Some(space) => &url[..space],
+		None => url,
+	};
+	Some((&chars[start + 1..close], url, url_end + 1))
+}
+
+/// Parses `<scheme://...>` at `start`, returning the index after `>`.
+fn autolink(chars: &[char], start: usize) -> Option<usize> {
+	let len = chars[start + 1..]
+		.iter()
+		.position(|&c| c == '>' || c == '<' || c.is_whitespace())?;
+	let end = start + 1 + len;
+	let target: String = chars[start + 1..end].iter().collect();
+	(chars[end] == '>' && target.contains("://")).then_some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+
+	use super::*;
+
+	fn texts(text: &str) -> Vec<(String, Marks)> {
+		inline(text)
+			.into_iter()
+			.map(|piece| (piece.text, piece.marks))
+			.collect()
+	}
+
+	#[test]
+	fn splits_blocks() {
+		let text = "# Title #\n\npara\ngraph\n- one\n  more\n10) ten\n\n```rs\n\tlet x;\n```\n";
+		assert_eq!(
+			blocks(text),
+			vec![
+				Block::Heading("Title"),
+				Block::Paragraph("para graph".into()),
+				Block::Item {
+					depth: 0,
+					marker: "•".into(),
+					text: "one more".into(),
+				},
+				Block::Item {
+					depth: 0,
+					marker: "10.".into(),
+					text: "ten".into(),
+				},
+				Block::Code(vec!["    let x;".into()]),
+			]
+		);
+	}
+
+	#[test]
+	fn unclosed_fence_runs_to_end() {
+		assert_eq!(
+			blocks("~~~\ncode\n\n"),
+			vec![Block::Code(vec!["code".into(), "".into()])]
+		);
+	}
+
+	#[test]
+	fn parses_inline_marks() {
+		let strong = Marks {
+			strong: true,
+			..Marks::default()
+		};
+		let code = Marks {
+			code: true,
+			..Marks::default()
+		};
+		assert_eq!(
+			texts("a **b** `c*`"),
+			vec![
+				("a ".into(), Marks::default()),
+				("b".into(), strong),
+				(" ".into(), Marks::default()),
+				("c*".into(), code),
+			]
+		);
+	}
+
+	#[test]
+	fn unmatched_delimiters_stay_literal() {
+		for text in [
+			"2 * 3 = 6",
+			"**open",
+			"snake_case_name",
+			"`tick",
+			"[a](b",
+			"\\*x*",
+		] {
+			let plain: String = inline(text).iter().map(|p| p.text.as_str()).collect();
+			let expected = text.replace("\\*", "*");
+			assert_eq!(plain, expected, "{text}");
+		}
+	}
+
+	#[test]
+	fn links_show_dimmed_url() {
+		let link = Marks {
+			link: true,
+			..Marks::default()
+		};
+		let url = Marks {
+			url: true,
+			..Marks::default()
+		};
+		assert_eq!(
+			texts("[docs](https://x.y \"t\")"),
+			vec![
+				("docs".into(), link),
+				(" ".into(), Marks::default()),
+				("(https://x.y)".into(), url),
+			]
+		);
+		assert_eq!(texts("<https://x.y>"), vec![("https://x.y".into(), link)]);
+	}
+}
@@ -0,0 +1,138 @@
+use alloc::string::String;
+
+use unicode_width::UnicodeWidthStr;
+
+use super::*;
+
+const FIXTURE: &str = "\
+# Hover
+
+Returns the **length** of `self`, in *bytes*.
+
+- first item
+- second item that wraps around
+  1. nested
+
+```rust
+fn len(&self)
+\t-> usize
+```
+
+See [docs](https://d.rs).
+";
+
+/// Renders `markdown` into a `width` wide buffer as tall as its height.
+fn render(markdown: &Markdown<'_>, width: u16) -> Buffer {
+	let area = Rect::new(0, 0, width, markdown.height(width));
+	let mut buf = Buffer::empty(area);
+	markdown.render(area, &mut buf);
+	buf
+}
+
+/// Returns a buffer of `rows`, each padded to `width` cells.
+fn lines(width: u16, rows: &[&str]) -> Buffer {
+	Buffer::with_lines(rows.iter().map(|row| {
+		let mut row = String::from(*row);
+		row.extend(core::iter::repeat_n(' ', usize::from(width) - row.width()));
+		row
+	}))
+}
+
+#[test]
+fn renders_fixture() {
+	let markdown = Markdown::new(FIXTURE);
+	assert_eq!(markdown.height(24), 14);
+
+	let mut expected = lines(
+		24,
+		&[
+			"Hover",
+			"",
+			"Returns the length of",
+			"self, in bytes.",
+			"",
+			"• first item",
+			"• second item that wraps",
+			"  around",
+			"  1. nested",
+			"",
+			"fn len(&self)",
+			"    -> usize",
+			"",
+			"See docs (https://d.rs).",
+		],
+	);
+	expected.set_style(Rect::new(0, 0, 5, 1), Style::new().bold());
+	expected.set_style(Rect::new(12, 2, 6, 1), Style::new().bold());
+	expected.set_style(Rect::new(0, 3, 4, 1), Style::new().on_dark_gray());
+	expected.set_style(Rect::new(9, 3, 5, 1), Style::new().italic());
+	expected.set_style(Rect::new(0, 10, 24, 2), Style::new().on_dark_gray());
+	expected.set_style(Rect::new(4, 13, 4, 1), Style::new().underlined());
+	expected.set_style(Rect::new(9, 13, 14, 1), Style::new().dim());
+	assert_eq!(render(&markdown, 24), expected);
+}
+
+#[test]
+fn wraps_to_narrow_widths() {
+	let markdown = Markdown::new(FIXTURE)
+		.heading_style(Style::new())
+		.code_style(Style::new())
+		.code_block_style(Style::new())
+		.link_style(Style::new())
+		.url_style(Style::new());
+	let mut expected = lines(
+		12,
+		&[
+			"Hover",
+			"",
+			"Returns the",
+			"length of",
+			"self, in",
+			"bytes.",
+			"",
+			"• first item",
+			"• second",
+			"  item that",
+			"  wraps",
+			"  around",
+			"  1. nested",
+			"",
+			"fn len(&self",
+			")",
+			"    -> usize",
+			"",
+			"See docs",
+			"(https://d.r",
+			"s).",
+		],
+	);
+	expected.set_style(Rect::new(0, 3, 6, 1), Style::new().bold());
+	expected.set_style(Rect::new(0, 5, 5, 1), Style::new().italic());
+	assert_eq!(render(&markdown, 12), expected);
+}
+
+#[test]
+fn unsupported_constructs_render_as_text() {
+	let markdown = Markdown::new("> quote\n\n| a | b |\n\n**open `tick [x](y\n####### deep");
+	let expected = lines(
+		40,
+		&[
+			"> quote",
+			"",
+			"| a | b |",
+			"",
+			"**open `tick [x](y ####### deep",
+		],
+	);
+	assert_eq!(render(&markdown, 40), expected);
+}
+
+#[test]
+fn never_panics_at_any_width() {
+	let text = "# *\n\n- \u{4e2d}\u{6587}\u{6587}\n      9. x\n```\n\u{4e2d}\t`\n\n[](<>)**__**";
+	for width in 0..8 {
+		let markdown = Markdown::new(text);
+		let buf = render(&markdown, width);
+		assert_eq!(buf.area.height, markdown.height(width));
+	}
+}
@@ -0,0 +1,238 @@
+//! Laying out parsed Markdown into rows of a given width.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use super::parse::{self, Block, Marks, Piece};
+
+/// How a row is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowKind {
+	/// Body text, or a blank line between blocks.
+	Text,
+	/// A heading.
+	Heading,
+	/// A line of a code block, with its background filling the width.
+	Code,
+}
+
+/// One line of output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Row {
+	/// Columns left blank before the pieces.
+	pub(crate) indent: u16,
+	/// Styled text, fitting the width after `indent`.
+	pub(crate) pieces: Vec<Piece>,
+	/// How the row is drawn.
+	pub(crate) kind: RowKind,
+}
+
+impl Row {
+	/// Creates a row of `kind` with no indent.
+	fn new(pieces: Vec<Piece>, kind: RowKind) -> Self {
+		Self {
+			indent: 0,
+			pieces,
+			kind,
+		}
+	}
+}
+
+/// A word and the marks of the whitespace before it.
+struct Word {
+	/// Marks of the space drawn before the word.
+	space: Marks,
+	/// The word's text.
+	pieces: Vec<Piece>,
+	/// Display width of the word.
+	width: usize,
+}
+
+/// Lays out `text` in rows at most `width` cells wide.
+pub(crate) fn layout(text: &str, width: u16) -> Vec<Row> {
+	let width = usize::from(width);
+	if width == 0 {
+		return Vec::new();
+	}
+	let mut rows = Vec::new();
+	let mut prev_item = false;
+	for block in parse::blocks(text) {
+		let item = matches!(block, Block::Item { .. });
+		// Blocks are separated by a blank row, but list items stay together.
+		if !rows.is_empty() && !(item && prev_item) {
+			rows.push(Row::new(Vec::new(), RowKind::Text));
+		}
+		prev_item = item;
+
+		match block {
+			Block::Heading(title) => rows.extend(
+				wrap(&parse::inline(title), width)
+					.into_iter()
+					.map(|pieces| Row::new(pieces, RowKind::Heading)),
+			),
+			Block::Paragraph(text) => rows.extend(
+				wrap(&parse::inline(&text), width)
+					.into_iter()
+					.map(|pieces| Row::new(pieces, RowKind::Text)),
+			),
+			Block::Item {
+				depth,
+				marker,
+				text,
+			} => {
+				let indent = (depth * 2).min(width.saturating_sub(1));
+				let marker_width = marker.width() + 1;
+				// Too narrow to hang the text after the marker: wrap under it.
+				let hang = if indent + marker_width < width {
+					indent + marker_width
+				} else {
+					indent
+				};
+				let mut lines = wrap(&parse::inline(&text), width - hang).into_iter();
+				let first = lines.next().unwrap_or_default();
+				let mut pieces = Vec::with_capacity(first.len() + 1);
+				pieces.push(Piece {
+					text: marker + " ",
+					marks: Marks::default(),
+				});
+				pieces.extend(first);
+				rows.push(Row {
+					indent: indent as u16,
+					pieces,
+					kind: RowKind::Text,
+				});
+				rows.extend(lines.map(|pieces| Row {
+					indent: hang as u16,
+					pieces,
+					kind: RowKind::Text,
+				}));
+			}
+			Block::Code(lines) => {
+				let code = Marks {
+					code: true,
+					..Marks::default()
+				};
+				for line in lines {
+					rows.extend(
+						split_chars(
+							&[Piece {
+								text: line,
+								marks: code,
+							}],
+							width,
+						)
+						.into_iter()
+						.map(|pieces| Row::new(pieces, RowKind::Code)),
+					);
+				}
+			}
+		}
+	}
+	rows
+}
+
+/// Wraps `pieces` at word boundaries into lines at most `width` cells wide,
+/// collapsing runs of whitespace. Words wider than a line are broken.
+fn wrap(pieces: &[Piece], width: usize) -> Vec<Vec<Piece>> {
+	let mut lines = Vec::new();
+	let mut line: Vec<Piece> = Vec::new();
+	let mut used = 0;
+	for word in words(pieces) {
+		if used > 0 {
+			if used + 1 + word.width <= width {
+				push_str(&mut line, " ", word.space);
+				used += 1;
+			} else {
+				lines.push(core::mem::take(&mut line));
+				used = 0;
+			}
+		}
+		if word.width <= width - used {
+			for piece in word.pieces {
+				push_str(&mut line, &piece.text, piece.marks);
+			}
+			used += word.width;
+			continue;
+		}
+		for piece in &word.pieces {
+			for c in piece.text.chars() {
+				let w = c.width().unwrap_or(0);
+				if used > 0 && used + w > width {
+					lines.push(core::mem::take(&mut line));
+					used = 0;
+				}
+				push_char(&mut line, c, piece.marks);
+				used += w;
+			}
+		}
+	}
+	if !line.is_empty() || lines.is_empty() {
+		lines.push(line);
+	}
+	lines
+}
+
+/// Breaks `pieces` into lines at most `width` cells wide, keeping every
+/// character including spaces.
+fn split_chars(pieces: &[Piece], width: usize) -> Vec<Vec<Piece>> {
+	let mut lines = Vec::new();
+	let mut line = Vec::new();
+	let mut used = 0;
+	for piece in pieces {
+		for c in piece.text.chars() {
+			let w = c.width().unwrap_or(0);
+			if used > 0 && used + w > width {
+				lines.push(core::mem::take(&mut line));
+				used = 0;
+			}
+			push_char(&mut line, c, piece.marks);
+			used += w;
+		}
+	}
+	lines.push(line);
+	lines
+}
+
+/// Splits `pieces` into whitespace-separated words.
+fn words(pieces: &[Piece]) -> Vec<Word> {
+	let mut words = Vec::new();
+	let mut current: Option<Word> = None;
+	let mut space = Marks::default();
+	for piece in pieces {
+		for c in piece.text.chars() {
+			if c.is_whitespace() {
+				words.extend(current.take());
+				space = piece.marks;
+				continue;
+			}
+			let word = current.get_or_insert_with(|| Word {
+				space,
+				pieces: Vec::new(),
+				width: 0,
+			});
+			push_char(&mut word.pieces, c, piece.marks);
+			word.width += c.width().unwrap_or(0);
+		}
+	}
+	words.extend(current);
+	words
+}
+
+/// Appends `c` to the last piece if it has the same marks.
+fn push_char(line: &mut Vec<Piece>, c: char, marks: Marks) {
+	let mut utf8 = [0; 4];
+	push_str(line, c.encode_utf8(&mut utf8), marks);
+}
+
+/// Appends `text` to the last piece if it has the same marks.
+fn push_str(line: &mut Vec<Piece>, text: &str, marks: Marks) {
+	match line.last_mut() {
+		Some(piece) if piece.marks == marks => piece.text.push_str(text),
+		_ => line.push(Piece {
+			text: String::from(text),
+			marks,
+		}),
+	}
+}
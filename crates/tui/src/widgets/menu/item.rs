@@ -1,7 +1,7 @@
 //! Menu item tree node.
 
 use alloc::borrow::Cow;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
 /// Width of a nerd font icon in terminal cells (typically 2 for proper display).
@@ -15,7 +15,9 @@ pub const ICON_TOTAL_WIDTH: u16 = ICON_CELL_WIDTH + ICON_PADDING;
 
 /// A node in the menu tree.
 ///
-/// Items can be leaf nodes (selectable, containing data) or groups (submenu containers).
+/// Items can be leaf nodes (selectable, containing data), groups (submenu containers)
+/// or separators. Leaves and groups can be disabled, which keeps them visible but
+/// out of reach of navigation and selection.
 pub struct MenuItem<T> {
 	/// Display name for the menu item.
 	pub(crate) name: Cow<'static, str>,
@@ -25,29 +27,100 @@ pub struct MenuItem<T> {
 	pub(crate) data: Option<T>,
 	/// Child items for submenu groups.
 	pub(crate) children: Vec<MenuItem<T>>,
+	/// Whether this item is a horizontal rule between other items.
+	pub(crate) separator: bool,
+	/// Whether this item is shown dimmed and cannot be highlighted or selected.
+	pub(crate) disabled: bool,
+	/// Lowercase key that jumps to this item while its menu is open.
+	pub(crate) accelerator: Option<char>,
+	/// Char index in `name` of the underlined mnemonic.
+	pub(crate) mnemonic: Option<usize>,
+	/// Right-aligned keybinding hint (e.g., "ctrl-s").
+	pub(crate) hint: Option<Cow<'static, str>>,
 }
 
 impl<T> MenuItem<T> {
 	/// Creates a selectable leaf item.
+	///
+	/// A `&` in `name` marks the next letter as the item's accelerator, which
+	/// is underlined when rendered; `&&` is a literal `&`.
 	pub fn item(name: impl Into<Cow<'static, str>>, data: T) -> Self {
+		Self::labeled(name.into(), Some(data), Vec::new())
+	}
+
+	/// Creates a group (submenu container).
+	///
+	/// The name may mark an accelerator with `&`, as for [`Self::item`].
+	pub fn group(name: impl Into<Cow<'static, str>>, children: Vec<Self>) -> Self {
+		Self::labeled(name.into(), None, children)
+	}
+
+	/// Creates an item from a label that may mark an accelerator with `&`.
+	fn labeled(label: Cow<'static, str>, data: Option<T>, children: Vec<Self>) -> Self {
+		let (name, mnemonic) = parse_mnemonic(label);
+		let accelerator = mnemonic
+			.and_then(|idx| name.chars().nth(idx))
+			.map(|c| c.to_ascii_lowercase());
 		Self {
-			name: name.into(),
+			name,
 			icon: None,
-			data: Some(data),
-			children: Vec::new(),
+			data,
+			children,
+			separator: false,
+			disabled: false,
+			accelerator,
+			mnemonic,
+			hint: None,
 		}
 	}
 
-	/// Creates a group (submenu container).
-	pub fn group(name: impl Into<Cow<'static, str>>, children: Vec<Self>) -> Self {
+	/// Creates a separator, drawn as a horizontal rule that navigation skips.
+	pub fn separator() -> Self {
 		Self {
-			name: name.into(),
+			name: Cow::Borrowed(""),
 			icon: None,
 			data: None,
-			children,
+			children: Vec::new(),
+			separator: true,
+			disabled: false,
+			accelerator: None,
+			mnemonic: None,
+			hint: None,
 		}
 	}
 
+	/// Sets whether this item is disabled.
+	///
+	/// Disabled items are rendered dimmed, skipped by keyboard navigation and
+	/// never emit [`MenuEvent::Selected`](super::MenuEvent::Selected).
+	#[must_use]
+	pub fn disabled(mut self, disabled: bool) -> Self {
+		self.disabled = disabled;
+		self
+	}
+
+	/// Sets the accelerator key explicitly, replacing any marked with `&`.
+	///
+	/// The first occurrence of the key in the name, ignoring case, is
+	/// underlined.
+	#[must_use]
+	pub fn accelerator(mut self, key: char) -> Self {
+		let key = key.to_ascii_lowercase();
+		self.accelerator = Some(key);
+		self.mnemonic = self
+			.name
+			.chars()
+			.position(|c| c.to_ascii_lowercase() == key);
+		self
+	}
+
+	/// Sets a keybinding hint shown right-aligned in the dropdown.
+	#[must_use]
+	pub fn hint(mut self, hint: impl Into<Cow<'static, str>>) -> Self {
+		self.hint = Some(hint.into());
+		self
+	}
+
 	/// Sets an icon for this menu item.
 	///
 	/// Icons are typically nerd font glyphs that display before the item name.
@@ -91,6 +164,26 @@ impl<T> MenuItem<T> {
 		!self.children.is_empty()
 	}
 
+	/// Returns true if this item is a separator.
+	pub fn is_separator(&self) -> bool {
+		self.separator
+	}
+
+	/// Returns true if this item is disabled.
+	pub fn is_disabled(&self) -> bool {
+		self.disabled
+	}
+
+	/// Returns true if this item can be highlighted and selected.
+	pub fn is_selectable(&self) -> bool {
+		!self.separator && !self.disabled
+	}
+
+	/// Returns true if this item is an enabled group with something to highlight.
+	pub(crate) fn is_enterable(&self) -> bool {
+		self.is_selectable() && first_selectable(&self.children).is_some()
+	}
+
 	/// Returns the item's display name.
 	pub fn name(&self) -> &str {
 		&self.name
@@ -100,4 +193,75 @@ impl<T> MenuItem<T> {
 	pub fn get_icon(&self) -> Option<&str> {
 		self.icon.as_deref()
 	}
+
+	/// Returns the item's accelerator key, if any.
+	pub fn get_accelerator(&self) -> Option<char> {
+		self.accelerator
+	}
+
+	/// Returns the item's keybinding hint, if any.
+	pub fn get_hint(&self) -> Option<&str> {
+		self.hint.as_deref()
+	}
+}
+
+/// Returns the index of the first selectable item in `items`.
+pub(crate) fn first_selectable<T>(items: &[MenuItem<T>]) -> Option<usize> {
+	items.iter().position(MenuItem::is_selectable)
+}
+
+/// Strips `&` mnemonic markers from `label`, returning the display name and
+/// the char index of the first marked character.
+fn parse_mnemonic(label: Cow<'static, str>) -> (Cow<'static, str>, Option<usize>) {
+	if !label.contains('&') {
+		return (label, None);
+	}
+	let mut name = String::with_capacity(label.len());
+	let mut mnemonic = None;
+	let mut chars = label.chars();
+	while let Some(c) = chars.next() {
+		if c != '&' {
+			name.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('&') => name.push('&'),
+			Some(next) => {
+				mnemonic.get_or_insert(name.chars().count());
+				name.push(next);
+			}
+			None => {}
+		}
+	}
+	(Cow::Owned(name), mnemonic)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_mnemonic_markers() {
+		let item = MenuItem::item("Save &As…", ());
+		assert_eq!(item.name(), "Save As…");
+		assert_eq!(item.get_accelerator(), Some('a'));
+		assert_eq!(item.mnemonic, Some(5));
+
+		let item = MenuItem::item("Find && &Replace", ());
+		assert_eq!(item.name(), "Find & Replace");
+		assert_eq!(item.get_accelerator(), Some('r'));
+
+		let item = MenuItem::item("Plain", ());
+		assert_eq!(item.get_accelerator(), None);
+	}
+
+	#[test]
+	fn explicit_accelerator_underlines_first_match() {
+		let item = MenuItem::item("&Quit", ()).accelerator('X');
+		assert_eq!(item.get_accelerator(), Some('x'));
+		assert_eq!(item.mnemonic, None);
+
+		let item = MenuItem::item("Exit", ()).accelerator('x');
+		assert_eq!(item.mnemonic, Some(1));
+	}
 }
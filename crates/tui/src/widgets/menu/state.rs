@@ -3,6 +3,7 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
+use super::item::first_selectable;
 use super::{MenuItem, MenuLayout};
 use crate::layout::Position;
 
@@ -85,6 +86,8 @@ impl<T: Clone> MenuState<T> {
 	}
 
 	/// Moves highlight up in current dropdown, or collapses if at top.
+	///
+	/// Separators and disabled items are skipped.
 	pub fn up(&mut self) {
 		match self.path.len() {
 			0 => {}
@@ -92,11 +95,15 @@ impl<T: Clone> MenuState<T> {
 				self.expanded = false;
 			}
 			n if n >= 2 => {
-				let last = self.path.last_mut().expect("path length checked");
-				if *last == 0 {
-					self.path.pop();
-				} else {
-					*last -= 1;
+				let current = self.path[n - 1];
+				let prev = self.siblings()[..current]
+					.iter()
+					.rposition(MenuItem::is_selectable);
+				match prev {
+					Some(idx) => self.path[n - 1] = idx,
+					None => {
+						self.path.pop();
+					}
 				}
 			}
 			_ => {}
@@ -104,6 +111,8 @@ impl<T: Clone> MenuState<T> {
 	}
 
 	/// Moves highlight down, or enters dropdown if on top bar.
+	///
+	/// Separators and disabled items are skipped.
 	pub fn down(&mut self) {
 		match self.path.len() {
 			0 => {}
@@ -113,17 +122,24 @@ impl<T: Clone> MenuState<T> {
 				}
 			}
 			1 if self.expanded => {
-				if self.bar_item().map(|item| item.is_group()).unwrap_or(false) {
-					self.path.push(0);
+				if let Some(idx) = self
+					.bar_item()
+					.filter(|item| item.is_enterable())
+					.and_then(|item| first_selectable(&item.children))
+				{
+					self.path.push(idx);
 				}
 			}
 			n if n >= 2 => {
-				let len = self.sibling_len();
-				if len == 0 {
-					return;
+				let current = self.path[n - 1];
+				let next = self
+					.siblings()
+					.iter()
+					.skip(current + 1)
+					.position(MenuItem::is_selectable);
+				if let Some(offset) = next {
+					self.path[n - 1] = current + 1 + offset;
 				}
-				let last = self.path.last_mut().expect("path length checked");
-				*last = (*last + 1).min(len.saturating_sub(1));
 			}
 			_ => {}
 		}
@@ -150,42 +166,32 @@ impl<T: Clone> MenuState<T> {
 			0 => {}
 			1 => self.move_bar_next(),
 			2 => {
-				let enter_submenu = self
-					.selected_item()
-					.map(|item| item.is_group())
-					.unwrap_or(false);
-				if enter_submenu {
-					self.path.push(0);
-				} else {
+				if !self.enter_submenu() {
 					self.path.truncate(1);
 					self.move_bar_next();
 				}
 			}
 			_ => {
-				if self
-					.selected_item()
-					.map(|item| item.is_group())
-					.unwrap_or(false)
-				{
-					self.path.push(0);
-				}
+				self.enter_submenu();
 			}
 		}
 	}
 
 	/// Selects the currently highlighted item.
 	///
-	/// Groups are expanded with first child highlighted.
-	/// Leaf items emit [`MenuEvent::Selected`].
+	/// Groups are expanded with first selectable child highlighted.
+	/// Leaf items emit [`MenuEvent::Selected`]. Separators and disabled
+	/// items do nothing.
 	pub fn select(&mut self) {
 		let (is_group, data) = match self.selected_item() {
-			Some(item) => (item.is_group(), item.data.as_ref().cloned()),
-			None => return,
+			Some(item) if item.is_selectable() => (item.is_group(), item.data.as_ref().cloned()),
+			_ => return,
 		};
 
 		if is_group {
-			self.expanded = true;
-			self.path.push(0);
+			if self.enter_submenu() {
+				self.expanded = true;
+			}
 			return;
 		}
 
@@ -194,6 +200,55 @@ impl<T: Clone> MenuState<T> {
 		}
 	}
 
+	/// Handles an accelerator key. Returns true if an item matched.
+	///
+	/// With a dropdown open, the matching item in it is selected: groups
+	/// open and leaves emit [`MenuEvent::Selected`]. With only the bar
+	/// active, or when nothing in a freshly opened dropdown matches, the
+	/// matching top-level group is opened. When several items share the
+	/// key, each press highlights the next of them instead.
+	pub fn handle_char(&mut self, c: char) -> bool {
+		let c = c.to_ascii_lowercase();
+		match self.path.len() {
+			0 => false,
+			1 if self.expanded => {
+				let children = self.bar_item().map_or(&[][..], |item| &item.children);
+				match accelerator_target(children, None, c) {
+					Some((target, unique)) => {
+						self.path.push(target);
+						if unique {
+							self.select();
+						}
+						true
+					}
+					None => self.handle_bar_char(c),
+				}
+			}
+			1 => self.handle_bar_char(c),
+			n => match accelerator_target(self.siblings(), Some(self.path[n - 1]), c) {
+				Some((target, unique)) => {
+					self.path[n - 1] = target;
+					if unique {
+						self.select();
+					}
+					true
+				}
+				None => false,
+			},
+		}
+	}
+
+	/// Handles an accelerator key against the top-level bar items.
+	fn handle_bar_char(&mut self, c: char) -> bool {
+		let Some((target, unique)) = accelerator_target(&self.items, self.path.first().copied(), c)
+		else {
+			return false;
+		};
+		self.select_bar_item(target);
+		self.expanded = unique && self.items[target].is_group();
+		true
+	}
+
 	/// Drains pending events.
 	pub fn drain_events(&mut self) -> impl Iterator<Item = MenuEvent<T>> + '_ {
 		self.events.drain(..)
@@ -233,9 +288,11 @@ impl<T: Clone> MenuState<T> {
 				true
 			}
 			HitResult::Dropdown(path) => {
-				self.set_dropdown_path(path);
-				self.expanded = true;
-				self.select();
+				if self.dropdown_item_selectable(&path) {
+					self.set_dropdown_path(path);
+					self.expanded = true;
+					self.select();
+				}
 				true
 			}
 			HitResult::Miss => false,
@@ -251,8 +308,10 @@ impl<T: Clone> MenuState<T> {
 				true
 			}
 			HitResult::Dropdown(path) => {
-				self.set_dropdown_path(path);
-				self.expanded = true;
+				if self.dropdown_item_selectable(&path) {
+					self.set_dropdown_path(path);
+					self.expanded = true;
+				}
 				true
 			}
 			HitResult::Miss => false,
@@ -313,16 +372,37 @@ impl<T: Clone> MenuState<T> {
 		self.path.first().and_then(|&idx| self.items.get(idx))
 	}
 
-	/// Returns the number of siblings at the current path level.
-	fn sibling_len(&self) -> usize {
+	/// Returns the siblings of the highlighted dropdown item.
+	fn siblings(&self) -> &[MenuItem<T>] {
 		if self.path.len() < 2 {
-			return 0;
+			return &[];
 		}
-		let parent_path = &self.path[..self.path.len().saturating_sub(1)];
-		let Some(parent) = self.item_at_path(parent_path) else {
-			return 0;
-		};
-		parent.children.len()
+		let parent_path = &self.path[..self.path.len() - 1];
+		self.item_at_path(parent_path)
+			.map_or(&[], |parent| parent.children.as_slice())
+	}
+
+	/// Highlights the first selectable child of the highlighted group.
+	/// Returns false if it has none.
+	fn enter_submenu(&mut self) -> bool {
+		let child = self
+			.selected_item()
+			.filter(|item| item.is_enterable())
+			.and_then(|item| first_selectable(&item.children));
+		if let Some(idx) = child {
+			self.path.push(idx);
+		}
+		child.is_some()
+	}
+
+	/// Returns true if the dropdown item at `dropdown_path`, under the
+	/// highlighted bar item, can be highlighted.
+	fn dropdown_item_selectable(&self, dropdown_path: &[usize]) -> bool {
+		let mut path = Vec::with_capacity(dropdown_path.len() + 1);
+		path.push(self.path.first().copied().unwrap_or(0));
+		path.extend_from_slice(dropdown_path);
+		self.item_at_path(&path)
+			.is_some_and(MenuItem::is_selectable)
 	}
 
 	/// Moves selection to the previous bar item.
@@ -355,3 +435,31 @@ impl<T: Clone> MenuState<T> {
 		self.path.extend(dropdown_path);
 	}
 }
+
+/// Finds the selectable item in `items` with accelerator `c`, returning its
+/// index and whether it is the only match.
+///
+/// When several items match, the first one after `current` is picked,
+/// wrapping around, so that repeated presses cycle through them.
+fn accelerator_target<T>(
+	items: &[MenuItem<T>],
+	current: Option<usize>,
+	c: char,
+) -> Option<(usize, bool)> {
+	let mut matches = items
+		.iter()
+		.enumerate()
+		.filter(|(_, item)| item.is_selectable() && item.accelerator == Some(c))
+		.map(|(idx, _)| idx);
+	let first = matches.next()?;
+	let mut rest = matches.peekable();
+	if rest.peek().is_none() {
+		return Some((first, true));
+	}
+	let next = current.and_then(|current| {
+		core::iter::once(first)
+			.chain(rest)
+			.find(|&idx| idx > current)
+	});
+	Some((next.unwrap_or(first), false))
+}
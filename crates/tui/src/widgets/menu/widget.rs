@@ -5,15 +5,18 @@ use alloc::format;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
 use super::item::ICON_TOTAL_WIDTH;
 use super::{DropdownLayout, MenuItem, MenuLayout, MenuState};
 use crate::buffer::Buffer;
 use crate::layout::Rect;
-use crate::style::{Color, Style};
+use crate::style::{Color, Modifier, Style};
+use crate::symbols::line;
 use crate::text::{Line, Span};
 use crate::widgets::block::Block;
 use crate::widgets::clear::Clear;
-use crate::widgets::{StatefulWidget, Widget};
+use crate::widgets::{BorderType, StatefulWidget, Widget};
 
 /// A horizontal menu bar with dropdown submenus.
 ///
@@ -24,8 +27,12 @@ pub struct Menu<T> {
 	default_style: Style,
 	/// Style for the currently highlighted item.
 	highlight_style: Style,
+	/// Style for disabled items.
+	disabled_style: Style,
 	/// Width of dropdown submenus in characters.
 	dropdown_width: u16,
+	/// Border drawn around dropdowns.
+	border_type: BorderType,
 	/// Phantom data to hold the generic type.
 	_marker: PhantomData<T>,
 }
@@ -42,7 +49,9 @@ impl<T> Menu<T> {
 		Self {
 			default_style: Style::default().fg(Color::White),
 			highlight_style: Style::default().fg(Color::White).bg(Color::LightBlue),
+			disabled_style: Style::default().fg(Color::DarkGray),
 			dropdown_width: 20,
+			border_type: BorderType::default(),
 			_marker: PhantomData,
 		}
 	}
@@ -59,12 +68,24 @@ impl<T> Menu<T> {
 		self
 	}
 
+	/// Sets the style for disabled items.
+	pub fn disabled_style(mut self, style: Style) -> Self {
+		self.disabled_style = style;
+		self
+	}
+
 	/// Sets the minimum dropdown width.
 	pub fn dropdown_width(mut self, width: u16) -> Self {
 		self.dropdown_width = width;
 		self
 	}
 
+	/// Sets the border drawn around dropdowns.
+	pub fn border_type(mut self, border_type: BorderType) -> Self {
+		self.border_type = border_type;
+		self
+	}
+
 	/// Renders a dropdown menu at the given position.
 	fn render_dropdown(
 		&self,
@@ -77,14 +98,22 @@ impl<T> Menu<T> {
 	) -> DropdownLayout {
 		let max_name_width = items
 			.iter()
-			.map(|item| item.name().len())
+			.map(|item| item.name().width())
 			.max()
 			.unwrap_or(0) as u16;
 
 		let has_icons = items.iter().any(|item| item.get_icon().is_some());
 		let icon_column_width = if has_icons { ICON_TOTAL_WIDTH } else { 0 };
-		let content_width = 1 + icon_column_width + max_name_width;
-		let block = Block::bordered().style(self.default_style);
+		let hint_column_width = items
+			.iter()
+			.filter_map(MenuItem::get_hint)
+			.map(|hint| hint.width() as u16 + 2)
+			.max()
+			.unwrap_or(0);
+		let content_width = 1 + icon_column_width + max_name_width + hint_column_width;
+		let block = Block::bordered()
+			.border_type(self.border_type)
+			.style(self.default_style);
 		let width = content_width + 2;
 		let height = items.len() as u16 + 2;
 
@@ -110,42 +139,69 @@ impl<T> Menu<T> {
 				break;
 			}
 
-			let mut label = if has_icons {
+			item_regions.push(Rect::new(item_x, item_y, content_width, 1));
+			if item.is_separator() {
+				let lines = separator_lines(self.border_type);
+				let rule = lines
+					.map_or(line::HORIZONTAL, |lines| lines.horizontal)
+					.repeat(usize::from(inner.width));
+				buf.set_stringn(
+					item_x,
+					item_y,
+					rule,
+					usize::from(inner.width),
+					self.default_style,
+				);
+				// Join the rule to the border only where the border draws lines.
+				if let Some(lines) = lines
+					&& area.width > 1
+				{
+					buf.set_string(area.x, item_y, lines.vertical_right, self.default_style);
+					buf.set_string(
+						area.right() - 1,
+						item_y,
+						lines.vertical_left,
+						self.default_style,
+					);
+				}
+				continue;
+			}
+
+			let prefix = if has_icons {
 				let icon_pad = " ".repeat(super::item::ICON_PADDING as usize);
 				match item.get_icon() {
-					Some(icon) => format!(
-						" {}{}{:<width$} ",
-						icon,
-						icon_pad,
-						item.name(),
-						width = max_name_width as usize
-					),
-					None => format!(
-						" {}{:<width$} ",
-						" ".repeat(ICON_TOTAL_WIDTH as usize),
-						item.name(),
-						width = max_name_width as usize
-					),
+					Some(icon) => format!(" {icon}{icon_pad}"),
+					None => format!(" {}", " ".repeat(ICON_TOTAL_WIDTH as usize)),
 				}
 			} else {
-				format!(" {:<width$} ", item.name(), width = max_name_width as usize)
+				" ".into()
 			};
+			let name_pad = usize::from(max_name_width).saturating_sub(item.name().width());
+			let mut label = format!("{prefix}{}{} ", item.name(), " ".repeat(name_pad));
 			if item.is_group() {
 				label.pop();
 				label.push('>');
 			}
+			let label = format!("{label:<width$}", width = content_width as usize);
 
 			let is_highlighted = highlighted == Some(idx);
-			let style = if is_highlighted {
+			let style = if item.is_disabled() {
+				self.default_style.patch(self.disabled_style)
+			} else if is_highlighted {
 				self.highlight_style
 			} else {
 				self.default_style
 			};
 
 			buf.set_span(item_x, item_y, &Span::styled(label, style), content_width);
-			item_regions.push(Rect::new(item_x, item_y, content_width, 1));
+			let name_x = item_x + prefix.width() as u16;
+			underline_mnemonic(buf, name_x, item_y, item, item_x + content_width);
+			if let Some(hint) = item.get_hint() {
+				let hint_x = (item_x + content_width).saturating_sub(hint.width() as u16);
+				buf.set_stringn(hint_x, item_y, hint, hint.width(), style);
+			}
 
-			if is_highlighted && item.is_group() {
+			if is_highlighted && item.is_enterable() {
 				let sub_path = highlight_path.get(1..).unwrap_or(&[]);
 				let sub_layout = self.render_dropdown(
 					inner.right(),
@@ -167,6 +223,52 @@ impl<T> Menu<T> {
 	}
 }
 
+/// Returns the lines a separator joins to a dropdown border of
+/// `border_type` with, or `None` if that border draws no lines.
+fn separator_lines(border_type: BorderType) -> Option<line::Set<'static>> {
+	match border_type {
+		BorderType::Plain
+		| BorderType::Rounded
+		| BorderType::LightDoubleDashed
+		| BorderType::LightTripleDashed
+		| BorderType::LightQuadrupleDashed => Some(line::NORMAL),
+		BorderType::Double => Some(line::DOUBLE),
+		BorderType::Thick
+		| BorderType::HeavyDoubleDashed
+		| BorderType::HeavyTripleDashed
+		| BorderType::HeavyQuadrupleDashed => Some(line::THICK),
+		BorderType::QuadrantInside
+		| BorderType::QuadrantOutside
+		| BorderType::Padded
+		| BorderType::Stripe => None,
+	}
+}
+
+/// Underlines the mnemonic of `item`, whose name is drawn from `name_x`,
+/// if it lies left of `right`.
+fn underline_mnemonic<T>(buf: &mut Buffer, name_x: u16, y: u16, item: &MenuItem<T>, right: u16) {
+	let Some(idx) = item.mnemonic else {
+		return;
+	};
+	let Some(c) = item.name().chars().nth(idx) else {
+		return;
+	};
+	let offset: usize = item
+		.name()
+		.chars()
+		.take(idx)
+		.filter_map(UnicodeWidthChar::width)
+		.sum();
+	let x = name_x.saturating_add(offset as u16);
+	if x < right && x < buf.area().right() {
+		let width = c.width().unwrap_or(1).max(1) as u16;
+		buf.set_style(
+			Rect::new(x, y, width.min(right - x), 1),
+			Style::default().add_modifier(Modifier::UNDERLINED),
+		);
+	}
+}
+
 impl<T: Clone> StatefulWidget for Menu<T> {
 	type State = MenuState<T>;
 
@@ -176,6 +278,7 @@ impl<T: Clone> StatefulWidget for Menu<T> {
 		let active_bar = state.path.first().copied();
 
 		let mut spans = Vec::new();
+		let mut mnemonics = Vec::new();
 		let mut x_pos = area.x;
 		let mut layout = MenuLayout {
 			bar_regions: Vec::new(),
@@ -193,15 +296,17 @@ impl<T: Clone> StatefulWidget for Menu<T> {
 				self.default_style
 			};
 
-			let label = match item.get_icon() {
+			let prefix = match item.get_icon() {
 				Some(icon) => {
 					let icon_pad = " ".repeat(super::item::ICON_PADDING as usize);
-					format!(" {}{}{} ", icon, icon_pad, item.name())
+					format!(" {icon}{icon_pad}")
 				}
-				None => format!(" {} ", item.name()),
+				None => " ".into(),
 			};
+			let label = format!("{prefix}{} ", item.name());
 			let span = Span::styled(label, style);
 			let span_width = span.width() as u16;
+			mnemonics.push((x_pos + prefix.width() as u16, item));
 			layout
 				.bar_regions
 				.push(Rect::new(x_pos, area.y, span_width, 1));
@@ -223,15 +328,20 @@ impl<T: Clone> StatefulWidget for Menu<T> {
 		}
 
 		buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+		for (name_x, item) in mnemonics {
+			underline_mnemonic(buf, name_x, area.y, item, area.right());
+		}
 		state.set_layout(layout);
 	}
 }
 
 #[cfg(test)]
 mod tests {
+	use alloc::string::{String, ToString};
 	use alloc::vec;
 
 	use super::*;
+	use crate::widgets::MenuEvent;
 
 	#[test]
 	fn menu_state_navigation() {
@@ -306,4 +416,229 @@ mod tests {
 		state.left();
 		assert_eq!(state.path, vec![0]);
 	}
+
+	fn file_menu() -> MenuState<&'static str> {
+		MenuState::new(vec![MenuItem::group(
+			"File",
+			vec![
+				MenuItem::item("New", "file:new"),
+				MenuItem::separator(),
+				MenuItem::item("Open", "file:open").disabled(true),
+				MenuItem::item("Quit", "file:quit"),
+			],
+		)])
+	}
+
+	#[test]
+	fn navigation_skips_separators_and_disabled_items() {
+		let mut state = file_menu();
+		state.activate();
+		state.down();
+		assert_eq!(state.path, vec![0, 0]);
+
+		state.down();
+		assert_eq!(state.highlight().unwrap().name(), "Quit");
+
+		// Nothing selectable below: stay put.
+		state.down();
+		assert_eq!(state.path, vec![0, 3]);
+
+		state.up();
+		assert_eq!(state.highlight().unwrap().name(), "New");
+
+		state.up();
+		assert_eq!(state.path, vec![0]);
+	}
+
+	#[test]
+	fn disabled_items_cannot_be_selected() {
+		let mut state = file_menu();
+		state.activate();
+		state.path = vec![0, 2];
+		state.select();
+		assert_eq!(state.drain_events().count(), 0);
+
+		state.path = vec![0, 1];
+		state.select();
+		assert_eq!(state.drain_events().count(), 0);
+	}
+
+	#[test]
+	fn submenu_entry_skips_unselectable_children() {
+		let mut state: MenuState<&str> = MenuState::new(vec![MenuItem::group(
+			"File",
+			vec![
+				MenuItem::group(
+					"Recent",
+					vec![
+						MenuItem::item("gone.txt", "recent:0").disabled(true),
+						MenuItem::item("doc.txt", "recent:1"),
+					],
+				),
+				MenuItem::group("Empty", vec![MenuItem::separator()]),
+			],
+		)]);
+		state.activate();
+		state.down();
+		state.right();
+		assert_eq!(state.path, vec![0, 0, 1]);
+
+		state.left();
+		state.down();
+		assert_eq!(state.highlight().unwrap().name(), "Empty");
+		state.select();
+		assert_eq!(state.path, vec![0, 1]);
+	}
+
+	/// Renders `menu` over `state` into a `width` by `height` buffer and
+	/// returns its rows without trailing blanks.
+	fn render_rows(
+		menu: Menu<&'static str>,
+		state: &mut MenuState<&'static str>,
+		width: u16,
+		height: u16,
+	) -> (Buffer, Vec<String>) {
+		let area = Rect::new(0, 0, width, height);
+		let mut buf = Buffer::empty(area);
+		menu.render(area, &mut buf, state);
+		let rows = (0..area.height)
+			.map(|y| {
+				let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+				row.trim_end().to_string()
+			})
+			.collect();
+		(buf, rows)
+	}
+
+	#[test]
+	fn renders_separator_and_disabled_item() {
+		let mut state = file_menu();
+		state.activate();
+		state.down();
+		let (buf, rows) = render_rows(
+			Menu::new().border_type(BorderType::Plain),
+			&mut state,
+			30,
+			7,
+		);
+		assert_eq!(
+			rows,
+			[
+				"  File",
+				" ┌─────┐",
+				" │ New │",
+				" ├─────┤",
+				" │ Open│",
+				" │ Quit│",
+				" └─────┘",
+			]
+		);
+		assert_eq!(buf[(3, 4)].fg, Color::DarkGray);
+		assert_eq!(buf[(3, 5)].fg, Color::White);
+	}
+
+	#[test]
+	fn separator_stays_inside_a_border_without_lines() {
+		let mut state = file_menu();
+		state.activate();
+		state.down();
+		let (_, rows) = render_rows(Menu::new(), &mut state, 30, 7);
+		assert_eq!(
+			rows,
+			["  File", "", "   New", "  ─────", "   Open", "   Quit", ""]
+		);
+
+		let (_, rows) = render_rows(
+			Menu::new().border_type(BorderType::Double),
+			&mut state,
+			30,
+			7,
+		);
+		assert_eq!(rows[3], " ╠═════╣");
+	}
+
+	#[test]
+	fn dropdowns_fit_names_by_display_width() {
+		let mut state = MenuState::new(vec![MenuItem::group(
+			"File",
+			vec![
+				MenuItem::item("日本語", "file:ja"),
+				MenuItem::item("Open", "file:open"),
+			],
+		)]);
+		state.activate();
+		let (_, rows) = render_rows(
+			Menu::new().border_type(BorderType::Plain),
+			&mut state,
+			30,
+			5,
+		);
+		// Six columns of name, one of leading space.
+		assert_eq!(rows[1], " ┌───────┐");
+		assert_eq!(rows[3], " │ Open  │");
+	}
+
+	fn accelerated_menu() -> MenuState<&'static str> {
+		MenuState::new(vec![
+			MenuItem::group(
+				"&File",
+				vec![
+					MenuItem::item("&Save", "file:save").hint("ctrl-s"),
+					MenuItem::item("&Split", "file:split"),
+					MenuItem::item("&Quit", "file:quit"),
+				],
+			),
+			MenuItem::group("&Edit", vec![MenuItem::item("&Undo", "edit:undo")]),
+		])
+	}
+
+	#[test]
+	fn accelerator_opens_bar_group() {
+		let mut state = accelerated_menu();
+		state.activate();
+		assert!(state.handle_char('E'));
+		assert_eq!(state.path, vec![1]);
+		assert!(state.expanded);
+		assert!(!state.handle_char('z'));
+	}
+
+	#[test]
+	fn accelerator_selects_dropdown_item() {
+		let mut state = accelerated_menu();
+		state.activate();
+		assert!(state.handle_char('q'));
+		let events: Vec<_> = state.drain_events().collect();
+		assert!(matches!(events[..], [MenuEvent::Selected("file:quit")]));
+	}
+
+	#[test]
+	fn ambiguous_accelerator_cycles() {
+		let mut state = accelerated_menu();
+		state.activate();
+		assert!(state.handle_char('s'));
+		assert_eq!(state.path, vec![0, 0]);
+		assert!(state.handle_char('s'));
+		assert_eq!(state.path, vec![0, 1]);
+		assert!(state.handle_char('s'));
+		assert_eq!(state.path, vec![0, 0]);
+		assert_eq!(state.drain_events().count(), 0);
+	}
+
+	#[test]
+	fn renders_mnemonics_and_hints() {
+		let mut state = accelerated_menu();
+		state.activate();
+		let (buf, rows) = render_rows(
+			Menu::new().border_type(BorderType::Plain),
+			&mut state,
+			40,
+			6,
+		);
+		assert_eq!(rows[2], " │ Save   ctrl-s│");
+		// Bar: " " then " File"; dropdown row: border then " Save".
+		assert!(buf[(2, 0)].modifier.contains(Modifier::UNDERLINED));
+		assert!(!buf[(3, 0)].modifier.contains(Modifier::UNDERLINED));
+		assert!(buf[(3, 2)].modifier.contains(Modifier::UNDERLINED));
+		assert!(!buf[(4, 2)].modifier.contains(Modifier::UNDERLINED));
+	}
 }
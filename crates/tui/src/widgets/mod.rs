@@ -4,16 +4,25 @@
 
 pub use self::block::{Block, BlockExt, Padding, TitlePosition};
 pub use self::borders::{BorderType, Borders};
+pub use self::bufferline::{BufferLine, BufferTab};
 pub use self::clear::Clear;
+pub use self::diff_view::{Diff, DiffView, DiffViewKey, DiffViewState};
 pub use self::icon::Icon;
 pub use self::keytree::{KeyTree, KeyTreeNode};
 pub use self::list::{List, ListDirection, ListItem, ListState};
+pub use self::markdown::Markdown;
 pub use self::menu::{Menu, MenuEvent, MenuItem, MenuState};
 pub use self::paragraph::{Paragraph, Wrap};
+pub use self::picker::{Picker, PickerKey, PickerState};
+pub use self::popup_frame::PopupFrame;
+pub use self::progress_bar::ProgressBar;
 pub use self::scrollbar::{Scrollbar, ScrollbarOrientation, ScrollbarState};
+pub use self::spinner::Spinner;
 pub use self::stateful_widget::StatefulWidget;
-pub use self::table::{Cell, HighlightSpacing, Row, Table, TableState};
+pub use self::table::{Cell, Elision, HighlightSpacing, Row, Table, TableState};
 pub use self::tabs::Tabs;
+pub use self::text_input::{TextInput, TextInputKey, TextInputState};
+pub use self::tree_view::{TreeItem, TreeKey, TreeView, TreeViewState};
 pub use self::widget::Widget;
 
 /// Stateful widget trait for widgets with state.
@@ -23,16 +32,25 @@ mod widget;
 
 pub mod block;
 pub mod borders;
+pub mod bufferline;
 pub mod clear;
+pub mod diff_view;
 pub mod icon;
 pub mod keytree;
 pub mod list;
+pub mod markdown;
 pub mod menu;
 pub mod paragraph;
+pub mod picker;
+pub mod popup_frame;
+pub mod progress_bar;
 pub mod scrollbar;
+pub mod spinner;
 pub mod table;
 pub mod tabs;
 pub mod terminal;
+pub mod text_input;
+pub mod tree_view;
 
 #[cfg(feature = "std")]
 pub mod notifications;
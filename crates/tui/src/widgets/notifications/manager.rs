@@ -5,15 +5,23 @@ use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use std::vec::Vec;
 
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 use super::toast::Toast;
-use super::types::{Anchor, Animation, AnimationPhase, AutoDismiss, Overflow, Timing};
+use super::types::{
+	Anchor, Animation, AnimationPhase, AutoDismiss, Overflow, SizeConstraint, TextOverflow, Timing,
+};
+use super::wrap::{self, WrapFn, wrap_words};
 use crate::animation::{Animatable, Easing};
 use crate::buffer::Buffer;
-use crate::layout::{Position, Rect};
-use crate::style::Color;
-use crate::text::Text;
-use crate::widgets::paragraph::Wrap;
-use crate::widgets::{Clear, Paragraph, Widget};
+use crate::layout::{Position, Rect, Size};
+use crate::style::{Color, Style};
+use crate::symbols::scrollbar;
+use crate::widgets::{
+	Clear, ProgressBar, Scrollbar, ScrollbarOrientation, ScrollbarState, Spinner, StatefulWidget,
+	Widget,
+};
 
 /// Default duration for toast entry animation.
 const DEFAULT_ENTRY_DURATION: Duration = Duration::from_millis(300);
@@ -23,6 +31,8 @@ const DEFAULT_EXIT_DURATION: Duration = Duration::from_millis(200);
 const DEFAULT_DWELL_DURATION: Duration = Duration::from_secs(4);
 /// Vertical spacing between stacked toasts.
 const STACK_SPACING: u16 = 1;
+/// Time a toast takes to slide most of the way into a freed slot.
+const STACK_SLIDE_DURATION: Duration = Duration::from_millis(120);
 
 /// Internal state for a single toast notification.
 #[derive(Debug)]
@@ -43,6 +53,11 @@ struct ToastState {
 	exit_duration: Duration,
 	/// Computed rectangle at full visibility.
 	full_rect: Rect,
+	/// Row the toast is drawn at while sliding towards `full_rect`, once
+	/// laid out.
+	stack_y: Option<f32>,
+	/// Whether the last render left the toast out of its stack.
+	hidden: bool,
 	/// Number of stacked duplicate notifications (1 = no duplicates).
 	stack_count: u32,
 	/// Original dwell duration for resetting on stack increment.
@@ -52,6 +67,7 @@ struct ToastState {
 impl ToastState {
 	/// Creates a new toast state with default timings.
 	fn new(toast: Toast) -> Self {
+		let remaining_dwell = dwell(&toast);
 		let entry_duration = match toast.entry_timing {
 			Timing::Auto => DEFAULT_ENTRY_DURATION,
 			Timing::Fixed(d) => d,
@@ -60,11 +76,6 @@ impl ToastState {
 			Timing::Auto => DEFAULT_EXIT_DURATION,
 			Timing::Fixed(d) => d,
 		};
-		let remaining_dwell = match toast.auto_dismiss {
-			AutoDismiss::Never => None,
-			AutoDismiss::After(d) if d.is_zero() => Some(DEFAULT_DWELL_DURATION),
-			AutoDismiss::After(d) => Some(d),
-		};
 		let original_dwell = remaining_dwell;
 
 		Self {
@@ -76,6 +87,8 @@ impl ToastState {
 			entry_duration,
 			exit_duration,
 			full_rect: Rect::default(),
+			stack_y: None,
+			hidden: false,
 			stack_count: 1,
 			original_dwell,
 		}
@@ -127,6 +140,20 @@ impl ToastState {
 		}
 	}
 
+	/// Moves the toast's drawn row towards its slot, or straight to it when
+	/// `slide` is off.
+	fn slide(&mut self, delta: Duration, slide: bool) {
+		let Some(y) = self.stack_y.as_mut() else {
+			return;
+		};
+		let target = f32::from(self.full_rect.y);
+		let step = delta.as_secs_f32() / STACK_SLIDE_DURATION.as_secs_f32();
+		*y += (target - *y) * step.min(1.0);
+		if !slide || (target - *y).abs() < 0.5 {
+			*y = target;
+		}
+	}
+
 	/// Returns true if the toast has completed its exit animation.
 	fn is_finished(&self) -> bool {
 		self.phase == AnimationPhase::Finished
@@ -147,6 +174,24 @@ impl ToastState {
 	}
 }
 
+/// Returns how long `toast` dwells before dismissing itself, if it does.
+fn dwell(toast: &Toast) -> Option<Duration> {
+	match toast.auto_dismiss {
+		AutoDismiss::Never => None,
+		AutoDismiss::After(d) if d.is_zero() => Some(DEFAULT_DWELL_DURATION),
+		AutoDismiss::After(d) => Some(d),
+	}
+}
+
+/// A toast that left the screen, with how many times it was shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DismissedToast {
+	/// ID returned when the toast was pushed.
+	pub id: u64,
+	/// Times the toast was pushed while showing, 1 if never repeated.
+	pub count: u32,
+}
+
 /// Converts an anchor to its screen position within the given area.
 fn anchor_position(anchor: Anchor, area: Rect) -> Position {
 	match anchor {
@@ -206,70 +251,60 @@ fn stack_counter_width(stack_count: u32) -> u16 {
 	1 + digits as u16
 }
 
+/// Resolves a size constraint against a terminal dimension of `total` cells.
+fn resolve_constraint(constraint: Option<SizeConstraint>, total: u16) -> u16 {
+	match constraint {
+		None => total,
+		Some(SizeConstraint::Cells(cells)) => cells,
+		Some(SizeConstraint::Percent(p)) => {
+			((total as f32 * p.clamp(0.0, 1.0)).ceil() as u16).max(1)
+		}
+	}
+}
+
 /// Computes the toast dimensions based on content and constraints.
-fn calculate_toast_size(toast: &Toast, area: Rect, stack_count: u32) -> (u16, u16) {
-	use super::types::SizeConstraint;
-
-	let max_width = toast
-		.max_width
-		.map(|c| match c {
-			SizeConstraint::Cells(w) => w.min(area.width),
-			SizeConstraint::Percent(p) => {
-				((area.width as f32 * p.clamp(0.0, 1.0)).ceil() as u16).max(1)
-			}
-		})
-		.unwrap_or(area.width);
-
-	let max_height = toast
-		.max_height
-		.map(|c| match c {
-			SizeConstraint::Cells(h) => h.min(area.height),
-			SizeConstraint::Percent(p) => {
-				((area.height as f32 * p.clamp(0.0, 1.0)).ceil() as u16).max(1)
-			}
-		})
-		.unwrap_or(area.height);
+///
+/// Percentage constraints are of `terminal`; the result also fits in `area`. The height counts
+/// the rows `wrap` lays the content out in at the resulting width, inside the border and
+/// padding. Size constraints never cut below one content column, or below the first content
+/// row and the counter and progress rows, so a stacked toast always shows its content; only
+/// `area` can.
+fn calculate_toast_size(
+	toast: &Toast,
+	area: Rect,
+	terminal: Size,
+	stack_count: u32,
+	wrap: WrapFn,
+) -> (u16, u16) {
+	let max_width = resolve_constraint(toast.max_width, terminal.width).min(area.width);
+	let max_height = resolve_constraint(toast.max_height, terminal.height).min(area.height);
 
 	let padding_h = toast.padding.left + toast.padding.right;
 	let padding_v = toast.padding.top + toast.padding.bottom;
 	let icon_width = toast.icon_column_width();
 	let counter_width = stack_counter_width(stack_count);
 
-	let content_width = toast
-		.content
-		.lines()
-		.map(|l| l.chars().count())
-		.max()
-		.unwrap_or(0) as u16;
+	let content_width = toast.content.lines().map(|l| l.width()).max().unwrap_or(0) as u16;
 
-	let width = (content_width.max(counter_width) + icon_width + 2 + padding_h)
-		.max(3)
-		.min(max_width);
+	let chrome_width = icon_width + 2 + padding_h;
+	let width = (content_width.max(counter_width) + chrome_width)
+		.min(max_width)
+		.max(1 + chrome_width)
+		.min(area.width);
 
-	// Account for text wrapping when calculating height
 	let inner_width = width.saturating_sub(2 + padding_h + icon_width);
-	let wrapped_lines: u16 = if inner_width > 0 {
-		toast
-			.content
-			.lines()
-			.map(|line| {
-				let len = line.chars().count() as u16;
-				if len == 0 {
-					1
-				} else {
-					len.div_ceil(inner_width)
-				}
-			})
-			.sum::<u16>()
-			.max(1)
+	let rows = if inner_width > 0 {
+		(wrap::layout(&toast.content, usize::from(inner_width), wrap).len() as u16).max(1)
 	} else {
 		1
 	};
 
-	let extra_lines = if stack_count > 1 { 1 } else { 0 };
-	let height = (wrapped_lines + extra_lines + 2 + padding_v)
-		.max(3)
-		.min(max_height);
+	let extra_lines = u16::from(stack_count > 1) + u16::from(toast.progress.is_some());
+	let chrome = 2 + padding_v;
+	let height = (rows + extra_lines + chrome)
+		.min(max_height)
+		.max(1 + extra_lines + chrome)
+		.min(area.height);
 
 	(width, height)
 }
@@ -356,7 +391,7 @@ fn default_slide_direction(anchor: Anchor) -> super::types::SlideDirection {
 }
 
 /// Renders a single toast to the buffer.
-fn render_toast(state: &ToastState, rect: Rect, buf: &mut Buffer) {
+fn render_toast(state: &ToastState, rect: Rect, wrap: WrapFn, buf: &mut Buffer) {
 	let opacity = calculate_opacity(state);
 	let bg_colors = if opacity < 1.0 {
 		Some(sample_background(rect, buf))
@@ -387,9 +422,43 @@ fn render_toast(state: &ToastState, rect: Rect, buf: &mut Buffer) {
 		inner
 	};
 
-	Paragraph::new(Text::raw(&state.toast.content))
-		.wrap(Wrap { trim: true })
-		.render(content_area, buf);
+	let counter_rows = u16::from(state.stack_count > 1);
+	let progress_rows = u16::from(state.toast.progress.is_some());
+	let content_rows = content_area
+		.height
+		.saturating_sub(counter_rows + progress_rows);
+	render_content(
+		&state.toast,
+		Rect {
+			height: content_rows,
+			..content_area
+		},
+		wrap,
+		buf,
+	);
+
+	if let Some(progress) = state.toast.progress
+		&& content_area.height > content_rows
+	{
+		let row = Rect {
+			y: content_area.y + content_rows,
+			height: 1,
+			..content_area
+		};
+		match progress.fraction {
+			Some(fraction) => ProgressBar::new()
+				.ratio(f64::from(fraction))
+				.label(format!(
+					"{:>3}%",
+					(fraction.clamp(0.0, 1.0) * 100.0).round()
+				))
+				.filled_style(state.toast.border_style)
+				.render(row, buf),
+			None => Spinner::new(progress.tick)
+				.style(state.toast.border_style)
+				.render(row, buf),
+		}
+	}
 
 	if state.stack_count > 1 && inner.height > 0 && inner.width > 0 {
 		let count_str = format!("\u{2a2f}{}", state.stack_count);
@@ -407,6 +476,58 @@ fn render_toast(state: &ToastState, rect: Rect, buf: &mut Buffer) {
 	}
 }
 
+/// Renders the content of `toast` as rows laid out by `wrap`, marking hidden rows as the toast's
+/// [`TextOverflow`] says when there are more than fit.
+fn render_content(toast: &Toast, area: Rect, wrap: WrapFn, buf: &mut Buffer) {
+	if area.is_empty() {
+		return;
+	}
+	let height = usize::from(area.height);
+	let mut rows = wrap::layout(&toast.content, usize::from(area.width), wrap);
+	let mut text_area = area;
+	if rows.len() > height {
+		match toast.text_overflow {
+			TextOverflow::Ellipsis => {
+				let last = &mut rows[height - 1];
+				let budget = usize::from(area.width) - 1;
+				let mut width = 0;
+				let end = last
+					.grapheme_indices(true)
+					.find(|(_, grapheme)| {
+						width += grapheme.width();
+						width > budget
+					})
+					.map_or(last.len(), |(i, _)| i);
+				last.truncate(end);
+				last.push('…');
+			}
+			TextOverflow::ScrollIndicator if area.width > 1 => {
+				let bar;
+				(text_area, bar) = area.split_right_column();
+				rows = wrap::layout(&toast.content, usize::from(text_area.width), wrap);
+				let mut bar_state = ScrollbarState::for_viewport(rows.len(), height, 0);
+				Scrollbar::new(ScrollbarOrientation::VerticalRight)
+					.symbols(scrollbar::VERTICAL)
+					.begin_symbol(None)
+					.end_symbol(None)
+					.thumb_style(toast.border_style)
+					.render(bar, buf, &mut bar_state);
+			}
+			TextOverflow::ScrollIndicator => {}
+		}
+	}
+
+	for (y, row) in (text_area.y..text_area.bottom()).zip(&rows) {
+		buf.set_stringn(
+			text_area.x,
+			y,
+			row,
+			usize::from(text_area.width),
+			Style::default(),
+		);
+	}
+}
+
 /// Computes the current opacity based on animation phase and progress.
 fn calculate_opacity(state: &ToastState) -> f32 {
 	if !state.toast.fade_effect && !matches!(state.toast.animation, Animation::Fade) {
@@ -462,6 +583,13 @@ pub struct ToastManager {
 	max_visible: Option<usize>,
 	/// Behavior when max_visible is exceeded.
 	overflow: Overflow,
+	/// Whether toasts slide into slots freed by dismissed ones, instead of
+	/// snapping.
+	slide_stacking: bool,
+	/// Lays out toast content as rows.
+	wrap: WrapFn,
+	/// Toasts that left since the last [`take_dismissed`](Self::take_dismissed).
+	dismissed: Vec<DismissedToast>,
 }
 
 impl Default for ToastManager {
@@ -478,16 +606,28 @@ impl ToastManager {
 			next_id: 0,
 			max_visible: None,
 			overflow: Overflow::default(),
+			slide_stacking: true,
+			wrap: wrap_words,
+			dismissed: Vec::new(),
 		}
 	}
 
 	/// Sets the maximum number of visible toasts.
 	#[must_use]
 	pub fn max_visible(mut self, max: Option<usize>) -> Self {
-		self.max_visible = max;
+		self.set_max_visible(max);
 		self
 	}
 
+	/// Changes the maximum number of visible toasts of a running manager.
+	///
+	/// Under [`Overflow::Summarize`] the limit is per anchor and toasts over
+	/// it are kept; otherwise it counts every toast and applies to the next
+	/// [`push`](Self::push).
+	pub fn set_max_visible(&mut self, max: Option<usize>) {
+		self.max_visible = max;
+	}
+
 	/// Sets the overflow behavior when the limit is reached.
 	#[must_use]
 	pub fn overflow(mut self, overflow: Overflow) -> Self {
@@ -495,14 +635,30 @@ impl ToastManager {
 		self
 	}
 
+	/// Sets whether toasts slide into slots freed by dismissed ones (the
+	/// default), or snap into them.
+	pub fn set_slide_stacking(&mut self, slide: bool) {
+		self.slide_stacking = slide;
+	}
+
+	/// Sets how toast content is wrapped into rows. Defaults to [`wrap_words`].
+	#[must_use]
+	pub fn wrap(mut self, wrap: WrapFn) -> Self {
+		self.wrap = wrap;
+		self
+	}
+
 	/// Adds a toast and returns its ID.
 	///
-	/// If a toast with identical content and anchor already exists (and is not
-	/// exiting), increments its stack count and resets the dismiss timer.
+	/// If `toast` [repeats](Toast::repeats) a showing toast that is not
+	/// exiting, increments that toast's stack count, resets its dismiss timer
+	/// and returns its ID instead.
 	pub fn push(&mut self, toast: Toast) -> u64 {
-		if let Some((&id, state)) = self.states.iter_mut().find(|(_, s)| {
-			s.can_stack() && s.toast.anchor == toast.anchor && s.toast.content == toast.content
-		}) {
+		if let Some((&id, state)) = self
+			.states
+			.iter_mut()
+			.find(|(_, s)| s.can_stack() && s.toast.repeats(&toast))
+		{
 			state.increment_stack();
 			return id;
 		}
@@ -510,14 +666,16 @@ impl ToastManager {
 		let id = self.next_id;
 		self.next_id = self.next_id.wrapping_add(1);
 
-		if let Some(max) = self.max_visible {
+		if let Some(max) = self.max_visible
+			&& self.overflow != Overflow::Summarize
+		{
 			while self.states.len() >= max {
 				let to_remove = match self.overflow {
 					Overflow::DropOldest => self.oldest_id(),
-					Overflow::DropNewest => self.newest_id(),
+					Overflow::DropNewest | Overflow::Summarize => self.newest_id(),
 				};
 				if let Some(remove_id) = to_remove {
-					self.states.remove(&remove_id);
+					self.dismiss(remove_id);
 				} else {
 					break;
 				}
@@ -528,14 +686,55 @@ impl ToastManager {
 		id
 	}
 
+	/// Replaces the content of the showing toast `id` in place, keeping its
+	/// position and animation. The dismiss timer restarts from the new
+	/// toast's [`AutoDismiss`].
+	///
+	/// Returns false if no such toast is showing.
+	pub fn update(&mut self, id: u64, toast: Toast) -> bool {
+		let Some(state) = self.states.get_mut(&id) else {
+			return false;
+		};
+		state.original_dwell = dwell(&toast);
+		state.remaining_dwell = state.original_dwell;
+		state.toast = toast;
+		true
+	}
+
 	/// Removes a toast by ID. Returns true if it existed.
 	pub fn remove(&mut self, id: u64) -> bool {
-		self.states.remove(&id).is_some()
+		self.dismiss(id)
 	}
 
 	/// Clears all toasts.
 	pub fn clear(&mut self) {
-		self.states.clear();
+		let ids: Vec<u64> = self.states.keys().copied().collect();
+		for id in ids {
+			self.dismiss(id);
+		}
+	}
+
+	/// Returns how many times the showing toast `id` was pushed.
+	pub fn count(&self, id: u64) -> Option<u32> {
+		self.states.get(&id).map(|s| s.stack_count)
+	}
+
+	/// Returns the toasts that expired or were removed since the last call,
+	/// with their final repeat counts.
+	pub fn take_dismissed(&mut self) -> Vec<DismissedToast> {
+		core::mem::take(&mut self.dismissed)
+	}
+
+	/// Removes a toast, noting it as dismissed. Returns true if it existed.
+	fn dismiss(&mut self, id: u64) -> bool {
+		let Some(state) = self.states.remove(&id) else {
+			return false;
+		};
+		self.dismissed.push(DismissedToast {
+			id,
+			count: state.stack_count,
+		});
+		true
 	}
 
 	/// Returns true if there are no toasts.
@@ -549,14 +748,30 @@ impl ToastManager {
 	}
 
 	/// Advances all toast animations and removes finished toasts.
+	///
+	/// Toasts the last render left out of their stack stay as they are.
 	pub fn tick(&mut self, delta: Duration) {
 		for state in self.states.values_mut() {
-			state.update(delta);
+			if !state.hidden {
+				state.update(delta);
+				state.slide(delta, self.slide_stacking);
+			}
+		}
+		let finished: Vec<u64> = self
+			.states
+			.iter()
+			.filter(|(_, state)| state.is_finished())
+			.map(|(&id, _)| id)
+			.collect();
+		for id in finished {
+			self.dismiss(id);
 		}
-		self.states.retain(|_, state| !state.is_finished());
 	}
 
 	/// Renders all toasts to the buffer.
+	///
+	/// Toasts are laid out afresh on every render: percentage size constraints are taken of the
+	/// whole buffer, so a resized terminal re-lays out the visible toasts on the next frame.
 	pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
 		if self.states.is_empty() {
 			return;
@@ -573,6 +788,10 @@ impl ToastManager {
 	}
 
 	/// Renders all toasts for a specific anchor point.
+	///
+	/// Toasts stack away from the anchor, the oldest first. Those past the
+	/// [`Overflow::Summarize`] limit or that would leave `area` are hidden and
+	/// counted on a `+N more` line after the last shown toast.
 	fn render_anchor_group(&mut self, anchor: Anchor, ids: &[u64], area: Rect, buf: &mut Buffer) {
 		let mut sorted_ids: Vec<u64> = ids.to_vec();
 		sorted_ids.sort_by_key(|id| {
@@ -581,6 +800,10 @@ impl ToastManager {
 				.map(|s| s.created_at)
 				.unwrap_or(Instant::now())
 		});
+		let limit = match (self.overflow, self.max_visible) {
+			(Overflow::Summarize, Some(max)) => max,
+			_ => usize::MAX,
+		};
 
 		let stacks_up = matches!(
 			anchor,
@@ -588,47 +811,74 @@ impl ToastManager {
 		);
 		let anchor_pos = anchor_position(anchor, area);
 
-		let ordered: Vec<u64> = if stacks_up {
-			sorted_ids.into_iter().rev().collect()
-		} else {
-			sorted_ids
-		};
-
 		let mut offset: u16 = 0;
-		let mut render_data: Vec<(u64, Rect, Rect)> = Vec::new();
-
-		for &id in &ordered {
-			if let Some(state) = self.states.get(&id) {
-				let (width, height) = calculate_toast_size(&state.toast, area, state.stack_count);
-				if height == 0 {
-					continue;
-				}
-
-				let x = calculate_x(anchor, anchor_pos.x, width, state.toast.margin, area);
-				let base_y = calculate_y(anchor, anchor_pos.y, height, state.toast.margin, area);
-				let y = if stacks_up {
-					base_y.saturating_sub(offset)
-				} else {
-					base_y.saturating_add(offset)
-				};
-
-				let full_rect = Rect::new(x, y, width, height).intersection(area);
-				let display_rect = apply_animation(state, full_rect, area);
-
-				if display_rect.width > 0 && display_rect.height > 0 {
-					render_data.push((id, full_rect, display_rect));
-				}
-
-				offset = offset.saturating_add(height).saturating_add(STACK_SPACING);
+		let mut render_data: Vec<(u64, Rect)> = Vec::new();
+		let mut hidden = 0;
+		let mut last_shown: Option<(Rect, u16, Style)> = None;
+
+		for id in sorted_ids {
+			let Some(state) = self.states.get_mut(&id) else {
+				continue;
+			};
+			let (width, height) = calculate_toast_size(
+				&state.toast,
+				area,
+				buf.area.as_size(),
+				state.stack_count,
+				self.wrap,
+			);
+			let x = calculate_x(anchor, anchor_pos.x, width, state.toast.margin, area);
+			let base_y = calculate_y(anchor, anchor_pos.y, height, state.toast.margin, area);
+			let y = if stacks_up {
+				base_y.checked_sub(offset).filter(|&y| y >= area.y)
+			} else {
+				base_y
+					.checked_add(offset)
+					.filter(|&y| y.saturating_add(height) <= area.bottom())
+			};
+			let Some(y) = y.filter(|_| height > 0 && render_data.len() < limit) else {
+				state.hidden = true;
+				hidden += 1;
+				continue;
+			};
+			state.hidden = false;
+
+			let full_rect = Rect::new(x, y, width, height);
+			state.full_rect = full_rect;
+			if !self.slide_stacking {
+				state.stack_y = Some(f32::from(y));
 			}
+			let stack_y = *state.stack_y.get_or_insert(f32::from(y));
+			let slot_rect = Rect {
+				y: (stack_y.round() as u16).clamp(area.y, area.bottom().saturating_sub(height)),
+				..full_rect
+			};
+			render_data.push((id, apply_animation(state, slot_rect, area)));
+			last_shown = Some((full_rect, state.toast.margin, state.toast.border_style));
+			offset = offset.saturating_add(height).saturating_add(STACK_SPACING);
 		}
 
-		for (id, full_rect, display_rect) in render_data {
-			if let Some(state) = self.states.get_mut(&id) {
-				state.full_rect = full_rect;
+		for (id, display_rect) in render_data {
+			if display_rect.width > 0
+				&& display_rect.height > 0
+				&& let Some(state) = self.states.get(&id)
+			{
+				render_toast(state, display_rect, self.wrap, buf);
 			}
-			if let Some(state) = self.states.get(&id) {
-				render_toast(state, display_rect, buf);
+		}
+
+		if hidden > 0 {
+			let label = format!("+{hidden} more");
+			let (style, margin) = last_shown.map_or((Style::default(), 0), |(_, m, s)| (s, m));
+			let width = (label.width() as u16).min(area.width);
+			let x = calculate_x(anchor, anchor_pos.x, width, margin, area);
+			let y = match last_shown {
+				Some((rect, ..)) if stacks_up => rect.y.checked_sub(1),
+				Some((rect, ..)) => Some(rect.bottom()),
+				None => Some(calculate_y(anchor, anchor_pos.y, 1, margin, area)),
+			};
+			if let Some(y) = y.filter(|&y| y >= area.y && y < area.bottom()) {
+				buf.set_stringn(x, y, &label, usize::from(width), style);
 			}
 		}
 	}
@@ -649,3 +899,237 @@ impl ToastManager {
 			.map(|(&id, _)| id)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::widgets::BorderType;
+	use crate::widgets::block::Padding;
+	use crate::widgets::notifications::ToastProgress;
+
+	/// A toast in the top-left corner with a plain border and no padding.
+	fn toast(content: &str) -> Toast {
+		Toast::new(content)
+			.anchor(Anchor::TopLeft)
+			.border_type(BorderType::Plain)
+			.padding(Padding::ZERO)
+			.margin(0)
+	}
+
+	/// Renders `toast` fully shown into a `width` by `height` buffer.
+	fn render(toast: Toast, width: u16, height: u16) -> Buffer {
+		let mut manager = ToastManager::new();
+		manager.push(toast);
+		manager.tick(Duration::from_secs(1));
+		let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+		manager.render(buf.area, &mut buf);
+		buf
+	}
+
+	#[test]
+	fn percentages_are_of_the_terminal() {
+		let toast = toast(&"x".repeat(100))
+			.max_width(Some(SizeConstraint::Percent(0.4)))
+			.max_height(Some(SizeConstraint::Cells(4)));
+		let terminal = Size::new(50, 20);
+		let size = |area| calculate_toast_size(&toast, area, terminal, 1, wrap_words);
+		assert_eq!(size(Rect::new(0, 0, 50, 20)), (20, 4));
+		// The area toasts go in narrows them further, but percentages
+		// stay relative to the whole terminal.
+		assert_eq!(size(Rect::new(0, 0, 30, 20)), (20, 4));
+		assert_eq!(size(Rect::new(0, 0, 12, 20)), (12, 4));
+		// Resizing the terminal changes the layout.
+		let size = calculate_toast_size(
+			&toast,
+			Rect::new(0, 0, 30, 10),
+			Size::new(30, 10),
+			1,
+			wrap_words,
+		);
+		assert_eq!(size, (12, 4));
+	}
+
+	#[test]
+	fn height_counts_wrapped_rows() {
+		let toast = toast("one two three four").max_width(Some(SizeConstraint::Cells(12)));
+		let size = calculate_toast_size(
+			&toast,
+			Rect::new(0, 0, 40, 20),
+			Size::new(40, 20),
+			1,
+			wrap_words,
+		);
+		assert_eq!(size, (12, 4));
+	}
+
+	#[test]
+	fn ellipsis_marks_hidden_rows() {
+		let toast = toast("one two three four five six")
+			.max_width(Some(SizeConstraint::Cells(10)))
+			.max_height(Some(SizeConstraint::Cells(4)));
+		assert_eq!(
+			render(toast, 14, 5),
+			Buffer::with_lines([
+				"┌────────┐    ",
+				"│one two │    ",
+				"│three…  │    ",
+				"└────────┘    ",
+				"              ",
+			])
+		);
+	}
+
+	#[test]
+	fn scroll_indicator_marks_hidden_rows() {
+		let toast = toast("one two three four five six")
+			.max_width(Some(SizeConstraint::Cells(10)))
+			.max_height(Some(SizeConstraint::Cells(4)))
+			.text_overflow(TextOverflow::ScrollIndicator);
+		assert_eq!(
+			render(toast, 14, 5),
+			Buffer::with_lines([
+				"┌────────┐    ",
+				"│one two█│    ",
+				"│three  ││    ",
+				"└────────┘    ",
+				"              ",
+			])
+		);
+	}
+
+	#[test]
+	fn repeats_bump_the_counter_until_dismissed() {
+		let mut manager = ToastManager::new();
+		let first = manager.push(toast("server crashed").kind("lsp"));
+		assert_eq!(manager.push(toast("server crashed").kind("lsp")), first);
+		assert_eq!(manager.push(toast("server crashed").kind("lsp")), first);
+		assert_eq!(manager.count(first), Some(3));
+		assert_ne!(manager.push(toast("server crashed").kind("git")), first);
+		assert_ne!(
+			manager.push(toast("server crashed").kind("lsp").dedupe(false)),
+			first
+		);
+		assert_eq!(manager.len(), 3);
+
+		manager.remove(first);
+		assert_eq!(
+			manager.take_dismissed(),
+			[DismissedToast {
+				id: first,
+				count: 3
+			}]
+		);
+		assert!(manager.take_dismissed().is_empty());
+	}
+
+	#[test]
+	fn progress_takes_the_last_content_row() {
+		let progress = |fraction| {
+			toast("indexing")
+				.max_width(Some(SizeConstraint::Cells(12)))
+				.progress(Some(ToastProgress { fraction, tick: 0 }))
+		};
+		assert_eq!(
+			render(progress(Some(0.5)), 12, 4),
+			Buffer::with_lines([
+				"┌────────┐  ",
+				"│indexing│  ",
+				"│ 50% █▌ │  ",
+				"└────────┘  ",
+			])
+		);
+		assert_eq!(
+			render(progress(None), 12, 4),
+			Buffer::with_lines([
+				"┌────────┐  ",
+				"│indexing│  ",
+				"│⠋       │  ",
+				"└────────┘  ",
+			])
+		);
+	}
+
+	#[test]
+	fn updates_replace_a_toast_in_place() {
+		let mut manager = ToastManager::new();
+		let id = manager.push(toast("indexing").auto_dismiss(AutoDismiss::Never));
+		manager.tick(Duration::from_secs(60));
+		assert!(manager.update(
+			id,
+			toast("done").auto_dismiss(AutoDismiss::After(Duration::from_secs(1)))
+		));
+		manager.tick(Duration::from_secs(2));
+		manager.tick(Duration::from_secs(1));
+		assert!(manager.is_empty());
+		assert!(!manager.update(id, toast("late")));
+	}
+
+	#[test]
+	fn toasts_past_the_limit_are_summarized() {
+		let mut manager = ToastManager::new()
+			.max_visible(Some(2))
+			.overflow(Overflow::Summarize);
+		let first = manager.push(toast("a"));
+		for content in ["b", "c", "d"] {
+			manager.push(toast(content));
+		}
+		manager.tick(Duration::from_secs(1));
+		let mut buf = Buffer::empty(Rect::new(0, 0, 8, 8));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(
+			buf,
+			Buffer::with_lines([
+				"┌─┐     ",
+				"│a│     ",
+				"└─┘     ",
+				"        ",
+				"┌─┐     ",
+				"│b│     ",
+				"└─┘     ",
+				"+2 more ",
+			])
+		);
+		assert_eq!(manager.len(), 4);
+
+		// A toast that would run off the area is summarized too.
+		manager.set_max_visible(None);
+		let mut buf = Buffer::empty(Rect::new(0, 0, 8, 8));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(buf[(0, 7)].symbol(), "+");
+
+		manager.remove(first);
+		manager.set_max_visible(Some(1));
+		manager.set_slide_stacking(false);
+		let mut buf = Buffer::empty(Rect::new(0, 0, 8, 4));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(
+			buf,
+			Buffer::with_lines(["┌─┐     ", "│b│     ", "└─┘     ", "+2 more "])
+		);
+	}
+
+	#[test]
+	fn toasts_slide_into_freed_slots() {
+		let mut manager = ToastManager::new();
+		let first = manager.push(toast("a"));
+		manager.push(toast("b"));
+		manager.tick(Duration::from_secs(1));
+		let mut buf = Buffer::empty(Rect::new(0, 0, 4, 8));
+		manager.render(buf.area, &mut buf);
+
+		manager.remove(first);
+		let mut buf = Buffer::empty(Rect::new(0, 0, 4, 8));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(buf[(1, 5)].symbol(), "b");
+
+		manager.tick(STACK_SLIDE_DURATION / 2);
+		let mut buf = Buffer::empty(Rect::new(0, 0, 4, 8));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(buf[(1, 3)].symbol(), "b");
+
+		manager.tick(Duration::from_secs(1));
+		let mut buf = Buffer::empty(Rect::new(0, 0, 4, 8));
+		manager.render(buf.area, &mut buf);
+		assert_eq!(buf[(1, 1)].symbol(), "b");
+	}
+}
@@ -3,8 +3,14 @@
 //! This module provides a self-contained notification system with:
 //! - Configurable anchor positions (corners and edges)
 //! - Multiple animation styles (slide, fade, expand/collapse)
-//! - Automatic stacking of multiple notifications
+//! - Automatic stacking of multiple notifications, with repeats of a
+//!   showing toast counted on it instead, sliding into slots freed by
+//!   dismissed toasts
+//! - A cap on visible toasts, with the rest summarized on a `+N more` line
 //! - Auto-dismiss with configurable timing
+//! - Progress bars and spinners on toasts updated in place
+//! - Sizes relative to the terminal, with content wrapped by a pluggable
+//!   [`WrapFn`]
 //!
 //! # Example
 //!
@@ -27,10 +33,12 @@
 mod manager;
 mod toast;
 mod types;
+mod wrap;
 
-pub use manager::ToastManager;
-pub use toast::{ICON_COLUMN_WIDTH, Toast, ToastIcon};
+pub use manager::{DismissedToast, ToastManager};
+pub use toast::{ICON_COLUMN_WIDTH, Toast, ToastIcon, ToastProgress};
 pub use types::{
 	Anchor, Animation, AnimationPhase, AutoDismiss, Level, Overflow, SizeConstraint,
-	SlideDirection, Timing,
+	SlideDirection, TextOverflow, Timing,
 };
+pub use wrap::{WrapFn, wrap_words};
@@ -2,7 +2,9 @@
 
 use alloc::string::String;
 
-use super::types::{Anchor, Animation, AutoDismiss, Level, SizeConstraint, SlideDirection, Timing};
+use super::types::{
+	Anchor, Animation, AutoDismiss, Level, SizeConstraint, SlideDirection, TextOverflow, Timing,
+};
 use crate::layout::HorizontalAlignment;
 use crate::style::Style;
 use crate::widgets::block::Padding;
@@ -34,6 +36,15 @@ impl ToastIcon {
 	}
 }
 
+/// Progress of an operation, drawn on the last content row of a toast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ToastProgress {
+	/// Completed fraction from 0 to 1, or `None` to draw a spinner.
+	pub fraction: Option<f32>,
+	/// Spinner tick, advanced by the caller.
+	pub tick: u64,
+}
+
 /// A toast notification to display to the user.
 ///
 /// Toasts are transient messages that appear briefly and can auto-dismiss.
@@ -62,10 +73,12 @@ pub struct Toast {
 	pub exit_timing: Timing,
 	/// Auto-dismiss behavior.
 	pub auto_dismiss: AutoDismiss,
-	/// Maximum width constraint.
+	/// Maximum width, with percentages of the terminal width.
 	pub max_width: Option<SizeConstraint>,
-	/// Maximum height constraint.
+	/// Maximum height, with percentages of the terminal height.
 	pub max_height: Option<SizeConstraint>,
+	/// What to show when the content has more rows than fit.
+	pub text_overflow: TextOverflow,
 	/// Internal padding.
 	pub padding: Padding,
 	/// External margin from anchor.
@@ -80,6 +93,13 @@ pub struct Toast {
 	pub border_type: BorderType,
 	/// Whether to apply fade effect during slide/expand animations.
 	pub fade_effect: bool,
+	/// Type of notification the toast shows, told apart when deduplicating.
+	pub kind: Option<String>,
+	/// Whether pushing an identical toast while this one shows bumps its
+	/// repeat counter instead of stacking another.
+	pub dedupe: bool,
+	/// Progress of the operation the toast reports on, if any.
+	pub progress: Option<ToastProgress>,
 }
 
 impl Default for Toast {
@@ -98,6 +118,7 @@ impl Default for Toast {
 			auto_dismiss: AutoDismiss::default(),
 			max_width: Some(SizeConstraint::Percent(0.4)),
 			max_height: Some(SizeConstraint::Percent(0.4)),
+			text_overflow: TextOverflow::Ellipsis,
 			padding: Padding::horizontal(1),
 			margin: 1,
 			style: Style::default(),
@@ -105,6 +126,9 @@ impl Default for Toast {
 			title_style: Style::default(),
 			border_type: BorderType::Stripe,
 			fade_effect: false,
+			kind: None,
+			dedupe: true,
+			progress: None,
 		}
 	}
 }
@@ -178,6 +202,27 @@ impl Toast {
 		self
 	}
 
+	/// Sets the maximum width. Percentages are of the terminal width.
+	#[must_use]
+	pub fn max_width(mut self, max_width: Option<SizeConstraint>) -> Self {
+		self.max_width = max_width;
+		self
+	}
+
+	/// Sets the maximum height. Percentages are of the terminal height.
+	#[must_use]
+	pub fn max_height(mut self, max_height: Option<SizeConstraint>) -> Self {
+		self.max_height = max_height;
+		self
+	}
+
+	/// Sets what to show when the content has more rows than fit.
+	#[must_use]
+	pub fn text_overflow(mut self, text_overflow: TextOverflow) -> Self {
+		self.text_overflow = text_overflow;
+		self
+	}
+
 	/// Sets the background style.
 	#[must_use]
 	pub fn style(mut self, style: Style) -> Self {
@@ -220,6 +265,39 @@ impl Toast {
 		self
 	}
 
+	/// Sets the type of notification the toast shows.
+	#[must_use]
+	pub fn kind(mut self, kind: impl Into<String>) -> Self {
+		self.kind = Some(kind.into());
+		self
+	}
+
+	/// Enables/disables deduplication of identical toasts.
+	#[must_use]
+	pub fn dedupe(mut self, enabled: bool) -> Self {
+		self.dedupe = enabled;
+		self
+	}
+
+	/// Sets the progress drawn below the content.
+	#[must_use]
+	pub fn progress(mut self, progress: Option<ToastProgress>) -> Self {
+		self.progress = progress;
+		self
+	}
+
+	/// Returns whether `other` repeats this toast: both deduplicate and have
+	/// the same type, level, anchor, title and content.
+	pub fn repeats(&self, other: &Self) -> bool {
+		self.dedupe
+			&& other.dedupe
+			&& self.kind == other.kind
+			&& self.level == other.level
+			&& self.anchor == other.anchor
+			&& self.title == other.title
+			&& self.content == other.content
+	}
+
 	/// Returns the width needed for the icon column, if an icon is present.
 	pub fn icon_column_width(&self) -> u16 {
 		if self.icon.is_some() {
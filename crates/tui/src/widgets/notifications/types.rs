@@ -128,6 +128,16 @@ pub enum SizeConstraint {
 	Percent(f32),
 }
 
+/// What a toast shows when its content has more rows than fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextOverflow {
+	/// End the last visible row with `…` (default).
+	#[default]
+	Ellipsis,
+	/// Draw a scrollbar beside the content showing how much is hidden.
+	ScrollIndicator,
+}
+
 /// Behavior when notification limit is reached.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Overflow {
@@ -136,4 +146,8 @@ pub enum Overflow {
 	DropOldest,
 	/// Reject new notifications when at capacity.
 	DropNewest,
+	/// Keep every notification, showing the oldest ones up to the limit per
+	/// anchor and counting the rest on a `+N more` line. Hidden toasts wait,
+	/// with their dismiss timers paused, until a slot frees up.
+	Summarize,
 }
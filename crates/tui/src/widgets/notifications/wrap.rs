@@ -0,0 +1,80 @@
+//! Laying out toast content as rows.
+
+use std::string::String;
+use std::vec::Vec;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Splits one line of toast content into rows at most `width` cells wide.
+///
+/// An empty result counts as a single empty row.
+pub type WrapFn = fn(&str, usize) -> Vec<String>;
+
+/// Wraps `line` at spaces, breaking words wider than `width` between
+/// graphemes. This is the default [`WrapFn`].
+pub fn wrap_words(line: &str, width: usize) -> Vec<String> {
+	let mut rows = Vec::new();
+	let mut row = String::new();
+	let mut row_width = 0;
+	for word in line.split(' ').filter(|word| !word.is_empty()) {
+		let word_width = word.width();
+		if row_width > 0 && row_width + 1 + word_width <= width {
+			row.push(' ');
+			row.push_str(word);
+			row_width += 1 + word_width;
+			continue;
+		}
+		if row_width > 0 {
+			rows.push(core::mem::take(&mut row));
+			row_width = 0;
+		}
+		for grapheme in word.graphemes(true) {
+			let grapheme_width = grapheme.width();
+			if row_width > 0 && row_width + grapheme_width > width {
+				rows.push(core::mem::take(&mut row));
+				row_width = 0;
+			}
+			row.push_str(grapheme);
+			row_width += grapheme_width;
+		}
+	}
+	if row_width > 0 {
+		rows.push(row);
+	}
+	rows
+}
+
+/// Lays out every line of `content` with `wrap`, trimming trailing spaces
+/// off each row.
+pub(super) fn layout(content: &str, width: usize, wrap: WrapFn) -> Vec<String> {
+	let mut rows = Vec::new();
+	for line in content.lines() {
+		let wrapped = wrap(line, width);
+		if wrapped.is_empty() {
+			rows.push(String::new());
+		}
+		rows.extend(wrapped.into_iter().map(|row| String::from(row.trim_end())));
+	}
+	rows
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wraps_at_spaces_and_breaks_long_words() {
+		assert_eq!(wrap_words("save the file now", 8), ["save the", "file now"]);
+		assert_eq!(wrap_words("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+		assert_eq!(wrap_words("ab 全角文字", 4), ["ab", "全角", "文字"]);
+	}
+
+	#[test]
+	fn layout_keeps_empty_lines() {
+		assert_eq!(
+			layout("one two\n\nthree", 4, wrap_words),
+			["one", "two", "", "thre", "e"]
+		);
+	}
+}
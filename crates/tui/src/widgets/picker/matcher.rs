@@ -0,0 +1,288 @@
+//! Fuzzy subsequence matching with match positions.
+//!
+//! A pattern matches a text when its characters appear in the text in
+//! order. Of all the ways they can, the best scoring one is kept: every
+//! matched character scores, more so at the start of a word or right after
+//! the previous match, and each character skipped between two matches costs
+//! a little. The matched positions come along so callers can highlight
+//! them.
+
+use alloc::vec::Vec;
+
+/// Score for each matched character.
+const SCORE_MATCH: i32 = 16;
+/// Bonus for a match directly after the previous one.
+const BONUS_CONSECUTIVE: i32 = 12;
+/// Bonus for a match at the start of a word.
+const BONUS_BOUNDARY: i32 = 10;
+/// Bonus for a match on the first character of the text.
+const BONUS_FIRST: i32 = 6;
+/// Penalty for each unmatched character inside the matched window.
+const PENALTY_GAP: i32 = 3;
+/// Score of a cell no match ends in.
+const NONE: i32 = i32::MIN / 2;
+
+/// A successful fuzzy match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FuzzyMatch {
+	/// Higher is better.
+	pub score: i32,
+	/// Char indices of the matched characters in the text, ascending.
+	pub positions: Vec<usize>,
+}
+
+/// How letter case is compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseMatching {
+	/// Case-insensitive unless the pattern contains an uppercase letter.
+	#[default]
+	Smart,
+	/// Always case-insensitive.
+	Ignore,
+}
+
+/// Matches `pattern` as a subsequence of `text` with [`CaseMatching::Smart`].
+///
+/// An empty pattern matches everything with a score of zero.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+	FuzzyMatcher::new(pattern, CaseMatching::Smart).matches(text)
+}
+
+/// Matches one pattern against many texts, reusing its buffers between
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct FuzzyMatcher {
+	/// Pattern characters, case folded unless matching case.
+	pattern: Vec<char>,
+	/// Whether case is compared exactly.
+	case_sensitive: bool,
+	/// Whether every pattern character is ASCII, so texts can be checked
+	/// byte by byte before scoring.
+	ascii: bool,
+	/// Characters of the text being matched.
+	text: Vec<char>,
+	/// Best score of a match of the first `i + 1` pattern characters ending
+	/// at text character `j`, at `i * text.len() + j`.
+	scores: Vec<i32>,
+	/// Text character the previous pattern character matched for each
+	/// cell of `scores`.
+	from: Vec<usize>,
+}
+
+impl FuzzyMatcher {
+	/// Creates a matcher for `pattern`.
+	pub fn new(pattern: &str, case: CaseMatching) -> Self {
+		let case_sensitive = case == CaseMatching::Smart && pattern.chars().any(char::is_uppercase);
+		Self {
+			pattern: pattern
+				.chars()
+				.map(|c| if case_sensitive { c } else { fold(c) })
+				.collect(),
+			case_sensitive,
+			ascii: pattern.is_ascii(),
+			..Self::default()
+		}
+	}
+
+	/// Matches the pattern against `text`, or returns `None` if its
+	/// characters do not all appear in `text` in order.
+	pub fn matches(&mut self, text: &str) -> Option<FuzzyMatch> {
+		if self.pattern.is_empty() {
+			return Some(FuzzyMatch::default());
+		}
+		if !self.is_subsequence(text) {
+			return None;
+		}
+
+		let Self {
+			pattern,
+			case_sensitive,
+			text: chars,
+			scores,
+			from,
+			..
+		} = self;
+		let fold_case = |c: char| if *case_sensitive { c } else { fold(c) };
+		chars.clear();
+		chars.extend(text.chars());
+		let (m, n) = (pattern.len(), chars.len());
+		scores.clear();
+		scores.resize(m * n, NONE);
+		from.clear();
+		from.resize(m * n, 0);
+
+		for (i, &p) in pattern.iter().enumerate() {
+			// Best cell of the previous row at least two characters back,
+			// which the current one reaches over a gap. Scores are kept
+			// raised by the gap penalty up to their column, so cells further
+			// left compare fairly.
+			let mut best = (NONE, 0);
+			for (j, &c) in chars.iter().enumerate() {
+				if i > 0 && j >= 2 {
+					let k = j - 2;
+					let score = scores[(i - 1) * n + k];
+					if score > NONE {
+						let raised = score.saturating_add(PENALTY_GAP.saturating_mul(col(k)));
+						if raised > best.0 {
+							best = (raised, k);
+						}
+					}
+				}
+				if fold_case(c) != p {
+					continue;
+				}
+				let mut gained = SCORE_MATCH;
+				if j == 0 {
+					gained += BONUS_FIRST;
+				}
+				if is_boundary(chars, j) {
+					gained += BONUS_BOUNDARY;
+				}
+				let cell = i * n + j;
+				if i == 0 {
+					scores[cell] = gained;
+					continue;
+				}
+				let consecutive = if j > 0 { scores[cell - n - 1] } else { NONE };
+				let gap = if best.0 > NONE {
+					best.0
+						.saturating_sub(PENALTY_GAP.saturating_mul(col(j - 1)))
+				} else {
+					NONE
+				};
+				if consecutive > NONE && consecutive + BONUS_CONSECUTIVE >= gap {
+					scores[cell] = consecutive + BONUS_CONSECUTIVE + gained;
+					from[cell] = j - 1;
+				} else if gap > NONE {
+					scores[cell] = gap + gained;
+					from[cell] = best.1;
+				}
+			}
+		}
+
+		let last = (m - 1) * n;
+		let (mut j, score) = (0..n)
+			.map(|j| (j, scores[last + j]))
+			.filter(|&(_, score)| score > NONE)
+			.max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+		let mut positions = Vec::with_capacity(m);
+		for i in (0..m).rev() {
+			positions.push(j);
+			j = from[i * n + j];
+		}
+		positions.reverse();
+		Some(FuzzyMatch { score, positions })
+	}
+
+	/// Returns whether the pattern characters appear in `text` in order,
+	/// a cheap check that rules out most texts before scoring.
+	fn is_subsequence(&self, text: &str) -> bool {
+		if self.ascii {
+			// Bytes of other characters never equal an ASCII one.
+			let mut rest = text.as_bytes();
+			for &p in &self.pattern {
+				let p = p as u8;
+				let found = rest.iter().position(|&b| {
+					if self.case_sensitive {
+						b == p
+					} else {
+						b.to_ascii_lowercase() == p
+					}
+				});
+				match found {
+					Some(at) => rest = &rest[at + 1..],
+					None => return false,
+				}
+			}
+			return true;
+		}
+		let mut pattern = self.pattern.iter().peekable();
+		for c in text.chars() {
+			let c = if self.case_sensitive { c } else { fold(c) };
+			if pattern.next_if(|&&p| p == c).is_some() && pattern.peek().is_none() {
+				return true;
+			}
+		}
+		pattern.peek().is_none()
+	}
+}
+
+/// Returns column `j` as a score multiplier.
+fn col(j: usize) -> i32 {
+	i32::try_from(j).unwrap_or(i32::MAX)
+}
+
+/// Returns true if `chars[pos]` starts a word: it follows a separator, or
+/// is an uppercase letter after a lowercase one.
+fn is_boundary(chars: &[char], pos: usize) -> bool {
+	let Some(&before) = pos.checked_sub(1).and_then(|i| chars.get(i)) else {
+		return true;
+	};
+	let c = chars[pos];
+	matches!(before, '/' | '\\' | '_' | '-' | '.' | ' ' | ':')
+		|| (before.is_lowercase() && c.is_uppercase())
+}
+
+/// Folds `c` for comparing without case.
+fn fold(c: char) -> char {
+	if c.is_ascii() {
+		c.to_ascii_lowercase()
+	} else {
+		c.to_lowercase().next().unwrap_or(c)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::vec;
+
+	use super::*;
+
+	fn score(pattern: &str, text: &str) -> i32 {
+		fuzzy_match(pattern, text).unwrap().score
+	}
+
+	#[test]
+	fn matches_subsequence() {
+		let m = fuzzy_match("mrs", "src/main.rs").unwrap();
+		assert_eq!(m.positions, vec![4, 9, 10]);
+		assert!(fuzzy_match("xyz", "src/main.rs").is_none());
+		assert!(fuzzy_match("bw", "write_buffer").is_none());
+		assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+	}
+
+	#[test]
+	fn narrows_to_shortest_window() {
+		let m = fuzzy_match("ab", "a__xab").unwrap();
+		assert_eq!(m.positions, vec![4, 5]);
+	}
+
+	#[test]
+	fn smart_case() {
+		assert!(fuzzy_match("readme", "README.md").is_some());
+		assert!(fuzzy_match("README", "readme.md").is_none());
+		assert!(fuzzy_match("rÉ", "ré").is_none());
+	}
+
+	#[test]
+	fn ignore_case() {
+		let mut matcher = FuzzyMatcher::new("WB", CaseMatching::Ignore);
+		assert_eq!(matcher.matches("writeBuffer").unwrap().positions, [0, 5]);
+		assert_eq!(matcher.matches("write_buffer").unwrap().positions, [0, 6]);
+		assert!(matcher.matches("write").is_none());
+		let mut matcher = FuzzyMatcher::new("É", CaseMatching::Ignore);
+		assert_eq!(matcher.matches("café").unwrap().positions, [3]);
+	}
+
+	#[test]
+	fn prefers_boundaries_and_runs() {
+		assert!(score("fb", "foo_bar") > score("fb", "fxxxxb"));
+		assert!(score("main", "main.rs") > score("main", "m_a_i_n"));
+		assert!(score("set", "set_option") > score("set", "reset"));
+		assert!(score("gd", "goto_definition") > score("gd", "grid"));
+		// The `b` after the separator beats the earlier one inside a word.
+		assert_eq!(fuzzy_match("b", "abc_b").unwrap().positions, [4]);
+		// A run beats the same characters spread apart.
+		assert_eq!(fuzzy_match("buf", "b_u_buf").unwrap().positions, [4, 5, 6]);
+	}
+}
@@ -0,0 +1,318 @@
+//! The [`Picker`] widget filters a list of items with a fuzzy query.
+//!
+//! A picker draws a prompt line holding the query and the number of
+//! matches, followed by the matching items, best first, with the matched
+//! characters highlighted, and a scrollbar when they do not all fit. An
+//! optional [`Preview`] callback draws the selected item beside the list.
+//!
+//! Items live in a [`PickerState`], which can be fed incrementally and
+//! filters on a budget per render, see [`Picker::filter_budget`].
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::picker::{Picker, PickerKey, PickerState};
+//!
+//! let mut state = PickerState::new();
+//! state.push(1, "src/main.rs");
+//! state.push(2, "src/lib.rs");
+//! state.handle_key(PickerKey::Char('l'));
+//!
+//! let picker = Picker::new().prompt("Files: ");
+//! ```
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use core::marker::PhantomData;
+
+use unicode_width::UnicodeWidthStr;
+
+pub use self::matcher::{CaseMatching, FuzzyMatch, FuzzyMatcher, fuzzy_match};
+pub use self::state::{PickerKey, PickerState};
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::symbols::{line, scrollbar};
+use crate::widgets::block::Block;
+use crate::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget};
+
+/// Fuzzy subsequence matching.
+mod matcher;
+/// Picker items, query and filtering.
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+/// Items matched per render unless set with [`Picker::filter_budget`].
+pub const DEFAULT_FILTER_BUDGET: usize = 10_000;
+
+/// Draws a preview of the selected item into the given area.
+pub type Preview<'a, T> = Box<dyn Fn(&T, Rect, &mut Buffer) + 'a>;
+
+/// A filter prompt above a scrollable list of fuzzy matches.
+pub struct Picker<'a, T> {
+	/// Block drawn around the picker.
+	block: Option<Block<'a>>,
+	/// Text before the query.
+	prompt: Cow<'a, str>,
+	/// Base style of the picker.
+	style: Style,
+	/// Style of the prompt text.
+	prompt_style: Style,
+	/// Style of the match count.
+	count_style: Style,
+	/// Style patched over the selected row.
+	highlight_style: Style,
+	/// Style patched over matched characters.
+	match_style: Style,
+	/// Style patched over the scrollbar thumb.
+	scrollbar_style: Style,
+	/// Most items matched against the query per render.
+	filter_budget: usize,
+	/// Draws the selected item beside the list.
+	preview: Option<Preview<'a, T>>,
+	/// The picked item type.
+	_marker: PhantomData<fn(&T)>,
+}
+
+impl<T> Default for Picker<'_, T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a, T> Picker<'a, T> {
+	/// Creates a picker with a `> ` prompt and no styling.
+	pub fn new() -> Self {
+		Self {
+			block: None,
+			prompt: Cow::Borrowed("> "),
+			style: Style::default(),
+			prompt_style: Style::default(),
+			count_style: Style::default(),
+			highlight_style: Style::default(),
+			match_style: Style::default(),
+			scrollbar_style: Style::default(),
+			filter_budget: DEFAULT_FILTER_BUDGET,
+			preview: None,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Wraps the picker in a block.
+	#[must_use]
+	pub fn block(mut self, block: Block<'a>) -> Self {
+		self.block = Some(block);
+		self
+	}
+
+	/// Sets the text shown before the query.
+	#[must_use]
+	pub fn prompt(mut self, prompt: impl Into<Cow<'a, str>>) -> Self {
+		self.prompt = prompt.into();
+		self
+	}
+
+	/// Sets the base style of the picker.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style of the prompt text.
+	#[must_use]
+	pub const fn prompt_style(mut self, style: Style) -> Self {
+		self.prompt_style = style;
+		self
+	}
+
+	/// Sets the style of the match count.
+	#[must_use]
+	pub const fn count_style(mut self, style: Style) -> Self {
+		self.count_style = style;
+		self
+	}
+
+	/// Sets the style patched over the selected row.
+	#[must_use]
+	pub const fn highlight_style(mut self, style: Style) -> Self {
+		self.highlight_style = style;
+		self
+	}
+
+	/// Sets the style patched over matched characters.
+	#[must_use]
+	pub const fn match_style(mut self, style: Style) -> Self {
+		self.match_style = style;
+		self
+	}
+
+	/// Sets the style patched over the scrollbar thumb.
+	#[must_use]
+	pub const fn scrollbar_style(mut self, style: Style) -> Self {
+		self.scrollbar_style = style;
+		self
+	}
+
+	/// Sets how many items are matched against the query per render.
+	///
+	/// Matching continues on the next render, so a large budget finishes
+	/// sooner and a small one keeps each frame cheap.
+	#[must_use]
+	pub const fn filter_budget(mut self, budget: usize) -> Self {
+		self.filter_budget = budget;
+		self
+	}
+
+	/// Sets the callback drawing the selected item beside the list.
+	#[must_use]
+	pub fn preview(mut self, preview: impl Fn(&T, Rect, &mut Buffer) + 'a) -> Self {
+		self.preview = Some(Box::new(preview));
+		self
+	}
+
+	/// Draws the query line, with the match count right-aligned.
+	fn render_prompt(&self, area: Rect, buf: &mut Buffer, state: &PickerState<T>) {
+		let count = if state.is_filtering() {
+			format!("{}/{}…", state.match_count(), state.item_count())
+		} else {
+			format!("{}/{}", state.match_count(), state.item_count())
+		};
+		let count_width = count.width() as u16;
+		let text_right = if count_width < area.width {
+			let x = area.right() - count_width;
+			buf.set_string(x, area.y, &count, self.style.patch(self.count_style));
+			x.saturating_sub(1)
+		} else {
+			area.right()
+		};
+
+		let width = usize::from(text_right - area.x);
+		let (x, _) = buf.set_stringn(
+			area.x,
+			area.y,
+			&self.prompt,
+			width,
+			self.style.patch(self.prompt_style),
+		);
+		let width = usize::from(text_right.saturating_sub(x));
+		buf.set_stringn(x, area.y, state.query(), width, self.style);
+	}
+
+	/// Draws the visible matches, one per row, with a scrollbar in the
+	/// rightmost column when they do not all fit.
+	fn render_matches(&self, area: Rect, buf: &mut Buffer, state: &mut PickerState<T>) {
+		let rows = usize::from(area.height);
+		let (area, bar) = if state.matches.len() > rows {
+			area.split_right_column()
+		} else {
+			(area, Rect::ZERO)
+		};
+		state.scroll_into_view(rows);
+		let visible = state
+			.matches
+			.iter()
+			.enumerate()
+			.skip(state.offset)
+			.take(usize::from(area.height));
+
+		for (row, (index, matched)) in visible.enumerate() {
+			let y = area.y + row as u16;
+			let base = if index == state.selected {
+				self.style.patch(self.highlight_style)
+			} else {
+				self.style
+			};
+			buf.set_style(Rect::new(area.x, y, area.width, 1), base);
+
+			let mut x = area.x;
+			let mut positions = matched.positions.iter().peekable();
+			let mut utf8 = [0; 4];
+			for (i, c) in state.text(matched.index).chars().enumerate() {
+				let style = if positions.next_if_eq(&&i).is_some() {
+					base.patch(self.match_style)
+				} else {
+					base
+				};
+				let remaining = usize::from(area.right().saturating_sub(x));
+				if remaining == 0 {
+					break;
+				}
+				(x, _) = buf.set_stringn(x, y, c.encode_utf8(&mut utf8), remaining, style);
+			}
+		}
+
+		let mut bar_state = ScrollbarState::for_viewport(state.matches.len(), rows, state.offset);
+		Scrollbar::new(ScrollbarOrientation::VerticalRight)
+			.symbols(scrollbar::VERTICAL)
+			.begin_symbol(None)
+			.end_symbol(None)
+			.style(self.style)
+			.thumb_style(self.style.patch(self.scrollbar_style))
+			.render(bar, buf, &mut bar_state);
+	}
+}
+
+impl<T> StatefulWidget for Picker<'_, T> {
+	type State = PickerState<T>;
+
+	fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		let area = area.intersection(buf.area);
+		buf.set_style(area, self.style);
+		let inner = match self.block.take() {
+			Some(block) => {
+				let inner = block.inner(area);
+				block.render(area, buf);
+				inner
+			}
+			None => area,
+		};
+		state.tick(self.filter_budget);
+		if inner.is_empty() {
+			return;
+		}
+
+		let (list_area, preview_area) = match self.preview {
+			Some(_) if inner.width >= 3 => {
+				let list_width = (inner.width - 1) / 2;
+				let divider = inner.x + list_width;
+				for y in inner.top()..inner.bottom() {
+					buf.set_string(divider, y, line::VERTICAL, self.style);
+				}
+				(
+					Rect::new(inner.x, inner.y, list_width, inner.height),
+					Some(Rect::new(
+						divider + 1,
+						inner.y,
+						inner.right() - divider - 1,
+						inner.height,
+					)),
+				)
+			}
+			_ => (inner, None),
+		};
+
+		self.render_prompt(
+			Rect::new(list_area.x, list_area.y, list_area.width, 1),
+			buf,
+			state,
+		);
+		let results = Rect::new(
+			list_area.x,
+			list_area.y + 1,
+			list_area.width,
+			list_area.height - 1,
+		);
+		self.render_matches(results, buf, state);
+
+		if let Some(preview) = &self.preview
+			&& let Some(area) = preview_area
+			&& let Some(matched) = state.matches.get(state.selected)
+		{
+			preview(state.item(matched.index), area, buf);
+		}
+	}
+}
@@ -0,0 +1,264 @@
+//! Picker items, query and incremental filtering.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use super::matcher::fuzzy_match;
+
+/// A key the picker knows how to handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickerKey {
+	/// Select the previous match.
+	Up,
+	/// Select the next match.
+	Down,
+	/// Select the match one page up.
+	PageUp,
+	/// Select the match one page down.
+	PageDown,
+	/// Select the next match, wrapping around.
+	Tab,
+	/// Select the previous match, wrapping around.
+	BackTab,
+	/// Select the first match.
+	Home,
+	/// Select the last match.
+	End,
+	/// Append a character to the query.
+	Char(char),
+	/// Delete the last character of the query.
+	Backspace,
+}
+
+/// An item with the text it is matched against.
+struct Entry<T> {
+	/// The picked value.
+	item: T,
+	/// Text shown and matched for the item.
+	text: String,
+}
+
+/// An item that matches the current query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Matched {
+	/// Index of the item in insertion order.
+	pub(crate) index: usize,
+	/// Match score, higher is better.
+	pub(crate) score: i32,
+	/// Char indices of the matched characters in the item's text.
+	pub(crate) positions: Vec<usize>,
+}
+
+/// State of a [`Picker`](super::Picker): its items, query, matches and
+/// selection.
+///
+/// Items can be pushed at any time, so a producer such as a directory walker
+/// can stream them in. Filtering is incremental: every render scans at most
+/// the picker's filter budget of items not yet matched against the query,
+/// so the UI stays responsive however many items there are. Call
+/// [`Self::tick`] to make progress without rendering.
+pub struct PickerState<T> {
+	/// All items in insertion order.
+	entries: Vec<Entry<T>>,
+	/// Current filter query.
+	query: String,
+	/// Items matching `query`, best first.
+	pub(crate) matches: Vec<Matched>,
+	/// Number of entries already matched against `query`.
+	scanned: usize,
+	/// Index into `matches` of the selected match.
+	pub(crate) selected: usize,
+	/// Index into `matches` of the first visible match.
+	pub(crate) offset: usize,
+	/// Number of matches visible at once, as of the last render.
+	pub(crate) page: usize,
+}
+
+impl<T> Default for PickerState<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> PickerState<T> {
+	/// Creates an empty picker state.
+	pub fn new() -> Self {
+		Self {
+			entries: Vec::new(),
+			query: String::new(),
+			matches: Vec::new(),
+			scanned: 0,
+			selected: 0,
+			offset: 0,
+			page: 1,
+		}
+	}
+
+	/// Adds an item, shown and matched as `text`.
+	pub fn push(&mut self, item: T, text: impl Into<String>) {
+		self.entries.push(Entry {
+			item,
+			text: text.into(),
+		});
+	}
+
+	/// Adds several `(item, text)` pairs.
+	pub fn extend<S: Into<String>>(&mut self, items: impl IntoIterator<Item = (T, S)>) {
+		for (item, text) in items {
+			self.push(item, text);
+		}
+	}
+
+	/// Removes all items.
+	pub fn clear(&mut self) {
+		self.entries.clear();
+		self.restart();
+	}
+
+	/// Returns the current query.
+	pub fn query(&self) -> &str {
+		&self.query
+	}
+
+	/// Replaces the query and restarts filtering.
+	pub fn set_query(&mut self, query: impl Into<String>) {
+		self.query = query.into();
+		self.restart();
+	}
+
+	/// Returns the number of items.
+	pub fn item_count(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns the number of items found to match the query so far.
+	pub fn match_count(&self) -> usize {
+		self.matches.len()
+	}
+
+	/// Returns true while some items are yet to be matched against the query.
+	pub fn is_filtering(&self) -> bool {
+		self.scanned < self.entries.len()
+	}
+
+	/// Returns the selected item.
+	pub fn selected(&self) -> Option<&T> {
+		self.matches
+			.get(self.selected)
+			.map(|matched| &self.entries[matched.index].item)
+	}
+
+	/// Returns the text of the selected item.
+	pub fn selected_text(&self) -> Option<&str> {
+		self.matches
+			.get(self.selected)
+			.map(|matched| self.entries[matched.index].text.as_str())
+	}
+
+	/// Matches up to `budget` more items against the query.
+	///
+	/// Returns true if items remain to be matched.
+	pub fn tick(&mut self, budget: usize) -> bool {
+		let end = self.entries.len().min(self.scanned.saturating_add(budget));
+		if end == self.scanned {
+			return false;
+		}
+		let selected = self.matches.get(self.selected).map(|matched| matched.index);
+
+		let found = self.entries[self.scanned..end]
+			.iter()
+			.enumerate()
+			.filter_map(|(offset, entry)| {
+				fuzzy_match(&self.query, &entry.text).map(|m| Matched {
+					index: self.scanned + offset,
+					score: m.score,
+					positions: m.positions,
+				})
+			});
+		self.matches.extend(found);
+		// Stable and adaptive to the two sorted runs, so merging a batch is cheap.
+		self.matches
+			.sort_by_key(|matched| (Reverse(matched.score), matched.index));
+		self.scanned = end;
+
+		// Keep the same item selected as better matches arrive.
+		if let Some(index) = selected
+			&& let Some(pos) = self.matches.iter().position(|m| m.index == index)
+		{
+			self.selected = pos;
+		}
+		self.is_filtering()
+	}
+
+	/// Handles a key. Returns true if it was used.
+	pub fn handle_key(&mut self, key: PickerKey) -> bool {
+		let last = self.matches.len().saturating_sub(1);
+		match key {
+			PickerKey::Up => self.select(self.selected.saturating_sub(1)),
+			PickerKey::Down => self.select((self.selected + 1).min(last)),
+			PickerKey::PageUp => self.select(self.selected.saturating_sub(self.page)),
+			PickerKey::PageDown => self.select((self.selected + self.page).min(last)),
+			PickerKey::Tab => self.select(if self.selected >= last {
+				0
+			} else {
+				self.selected + 1
+			}),
+			PickerKey::BackTab => self.select(if self.selected == 0 {
+				last
+			} else {
+				self.selected - 1
+			}),
+			PickerKey::Home => self.select(0),
+			PickerKey::End => self.select(last),
+			PickerKey::Char(c) => {
+				self.query.push(c);
+				self.restart();
+			}
+			PickerKey::Backspace => {
+				if self.query.pop().is_none() {
+					return false;
+				}
+				self.restart();
+			}
+		}
+		true
+	}
+
+	/// Selects the match at `index`.
+	fn select(&mut self, index: usize) {
+		self.selected = index;
+	}
+
+	/// Drops all matches so filtering starts over with the current query.
+	fn restart(&mut self) {
+		self.matches.clear();
+		self.scanned = 0;
+		self.selected = 0;
+		self.offset = 0;
+	}
+
+	/// Returns the text of the item at `index`.
+	pub(crate) fn text(&self, index: usize) -> &str {
+		&self.entries[index].text
+	}
+
+	/// Returns the item at `index`.
+	pub(crate) fn item(&self, index: usize) -> &T {
+		&self.entries[index].item
+	}
+
+	/// Scrolls so that the selection is among the `height` visible rows.
+	pub(crate) fn scroll_into_view(&mut self, height: usize) {
+		self.page = height.max(1);
+		self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+		if self.selected < self.offset {
+			self.offset = self.selected;
+		} else if self.selected >= self.offset + self.page {
+			self.offset = self.selected + 1 - self.page;
+		}
+		self.offset = self
+			.offset
+			.min(self.matches.len().saturating_sub(self.page));
+	}
+}
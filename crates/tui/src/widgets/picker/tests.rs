@@ -0,0 +1,169 @@
+use alloc::format;
+
+use super::*;
+use crate::Terminal;
+use crate::backend::TestBackend;
+
+fn files() -> PickerState<usize> {
+	let mut state = PickerState::new();
+	state.extend([(0, "src/main.rs"), (1, "src/lib.rs"), (2, "README.md")]);
+	state
+}
+
+fn query(state: &mut PickerState<usize>, query: &str) {
+	for c in query.chars() {
+		state.handle_key(PickerKey::Char(c));
+	}
+}
+
+/// Renders `picker` into a `width` by `height` terminal.
+fn draw(
+	picker: Picker<'_, usize>,
+	state: &mut PickerState<usize>,
+	width: u16,
+	height: u16,
+) -> Terminal<TestBackend> {
+	let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+	terminal
+		.draw(|frame| frame.render_stateful_widget(picker, frame.area(), state))
+		.unwrap();
+	terminal
+}
+
+#[test]
+fn renders_prompt_count_and_highlighted_matches() {
+	let mut state = files();
+	query(&mut state, "rs");
+	let picker = Picker::new()
+		.highlight_style(Style::new().reversed())
+		.match_style(Style::new().bold());
+
+	let mut expected = Buffer::with_lines([
+		"> rs             2/3",
+		"src/main.rs         ",
+		"src/lib.rs          ",
+		"                    ",
+	]);
+	expected.set_style(Rect::new(0, 1, 20, 1), Style::new().reversed());
+	expected.set_style(Rect::new(9, 1, 2, 1), Style::new().bold());
+	expected.set_style(Rect::new(8, 2, 2, 1), Style::new().bold());
+	draw(picker, &mut state, 20, 4)
+		.backend()
+		.assert_buffer(&expected);
+	assert_eq!(state.selected(), Some(&0));
+}
+
+#[test]
+fn navigation_keys_move_selection() {
+	let mut state = PickerState::new();
+	state.extend((0..10).map(|i| (i, format!("item{i}"))));
+	draw(Picker::new(), &mut state, 20, 4);
+
+	state.handle_key(PickerKey::Down);
+	assert_eq!(state.selected(), Some(&1));
+	state.handle_key(PickerKey::PageDown);
+	assert_eq!(state.selected(), Some(&4));
+	state.handle_key(PickerKey::End);
+	state.handle_key(PickerKey::Down);
+	assert_eq!(state.selected(), Some(&9));
+	state.handle_key(PickerKey::Tab);
+	assert_eq!(state.selected(), Some(&0));
+	state.handle_key(PickerKey::BackTab);
+	assert_eq!(state.selected(), Some(&9));
+	state.handle_key(PickerKey::PageUp);
+	assert_eq!(state.selected(), Some(&6));
+	state.handle_key(PickerKey::Home);
+	state.handle_key(PickerKey::Up);
+	assert_eq!(state.selected(), Some(&0));
+}
+
+#[test]
+fn scrolls_to_keep_selection_visible() {
+	let mut state = PickerState::new();
+	state.extend((0..10).map(|i| (i, format!("item{i}"))));
+	draw(Picker::new(), &mut state, 12, 4)
+		.backend()
+		.assert_buffer_lines([
+			">      10/10",
+			"item0      █",
+			"item1      │",
+			"item2      │",
+		]);
+
+	state.handle_key(PickerKey::End);
+	draw(Picker::new(), &mut state, 12, 4)
+		.backend()
+		.assert_buffer_lines([
+			">      10/10",
+			"item7      │",
+			"item8      │",
+			"item9      █",
+		]);
+}
+
+#[test]
+fn filters_large_lists_within_budget() {
+	let mut state = PickerState::new();
+	state.extend((0..100_000).map(|i| (i, format!("item{i}"))));
+
+	draw(Picker::new().filter_budget(1_000), &mut state, 24, 2)
+		.backend()
+		.assert_buffer_lines([">           1000/100000…", "item0                  █"]);
+	assert!(state.is_filtering());
+	assert_eq!(state.match_count(), 1_000);
+
+	while state.tick(DEFAULT_FILTER_BUDGET) {}
+	assert_eq!(state.match_count(), 100_000);
+
+	// Streamed items are picked up on the next tick.
+	state.push(100_000, "item100000");
+	assert!(state.is_filtering());
+	state.tick(1);
+	assert_eq!(state.match_count(), 100_001);
+}
+
+#[test]
+fn selection_follows_item_as_better_matches_arrive() {
+	let mut state = PickerState::new();
+	state.extend([(0, "a__b"), (1, "a_b"), (2, "ab")]);
+	query(&mut state, "ab");
+
+	state.tick(2);
+	assert_eq!(state.selected_text(), Some("a_b"));
+	state.handle_key(PickerKey::Down);
+	assert_eq!(state.selected_text(), Some("a__b"));
+
+	state.tick(1);
+	assert_eq!(state.matches[0].index, 2);
+	assert_eq!(state.selected_text(), Some("a__b"));
+}
+
+#[test]
+fn query_edits_restart_filtering() {
+	let mut state = files();
+	query(&mut state, "main");
+	state.tick(DEFAULT_FILTER_BUDGET);
+	assert_eq!(state.match_count(), 1);
+
+	assert!(state.handle_key(PickerKey::Backspace));
+	assert_eq!(state.query(), "mai");
+	state.set_query("");
+	assert!(!state.handle_key(PickerKey::Backspace));
+	state.tick(DEFAULT_FILTER_BUDGET);
+	assert_eq!(state.match_count(), 3);
+}
+
+#[test]
+fn preview_draws_selected_item() {
+	let mut state = files();
+	let picker = Picker::new().preview(|item: &usize, area, buf: &mut Buffer| {
+		buf.set_string(area.x, area.y, format!("#{item}"), Style::new());
+	});
+	draw(picker, &mut state, 21, 3)
+		.backend()
+		.assert_buffer_lines([
+			">      3/3│#0        ",
+			"src/main.█│          ",
+			"src/lib.r││          ",
+		]);
+}
@@ -0,0 +1,281 @@
+//! The [`PopupFrame`] widget draws the frame around a popup.
+
+use crate::buffer::Buffer;
+use crate::layout::{HorizontalAlignment, Rect};
+use crate::style::{Modifier, Style};
+use crate::text::Line;
+use crate::widgets::table::{Elision, elide};
+use crate::widgets::{Block, BorderType, Borders, Clear, Padding, Widget};
+
+/// The frame of a popup: border, padding, title and drop shadow.
+///
+/// Rendering clears the popup area, so whatever was drawn underneath does not show through, and
+/// then draws the frame. Content goes in [`PopupFrame::inner`]. A title too wide for the top
+/// border is cut short with `…`.
+///
+/// The drop shadow falls one cell below and to the right of the popup. It does not paint over the
+/// cells it covers; it patches [`PopupFrame::shadow_style`] over them, which dims them by default.
+///
+/// # Example
+///
+/// ```
+/// use xeno_tui::layout::{HorizontalAlignment, Rect};
+/// use xeno_tui::widgets::{BorderType, Padding, PopupFrame};
+///
+/// let frame = PopupFrame::new()
+///     .border_type(BorderType::Double)
+///     .padding(Padding::horizontal(1))
+///     .title("Rename")
+///     .title_alignment(HorizontalAlignment::Center)
+///     .shadow(true);
+/// assert_eq!(frame.inner(Rect::new(0, 0, 20, 5)), Rect::new(2, 1, 16, 3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PopupFrame<'a> {
+	/// Which sides have a border.
+	borders: Borders,
+	/// Symbols the border is drawn with.
+	border_type: BorderType,
+	/// Space between the border and the content.
+	padding: Padding,
+	/// Title drawn on the top border.
+	title: Option<Line<'a>>,
+	/// Where the title sits on the top border.
+	title_alignment: HorizontalAlignment,
+	/// Whether to draw a drop shadow.
+	shadow: bool,
+	/// Base style of the popup.
+	style: Style,
+	/// Style patched over the border.
+	border_style: Style,
+	/// Style patched over the title.
+	title_style: Style,
+	/// Style patched over the cells under the shadow.
+	shadow_style: Style,
+}
+
+impl Default for PopupFrame<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a> PopupFrame<'a> {
+	/// Creates a frame with a rounded border on all sides, no padding, no title and no shadow.
+	pub const fn new() -> Self {
+		Self {
+			borders: Borders::ALL,
+			border_type: BorderType::Rounded,
+			padding: Padding::ZERO,
+			title: None,
+			title_alignment: HorizontalAlignment::Left,
+			shadow: false,
+			style: Style::new(),
+			border_style: Style::new(),
+			title_style: Style::new(),
+			shadow_style: Style::new().add_modifier(Modifier::DIM),
+		}
+	}
+
+	/// Sets which sides have a border. [`Borders::NONE`] leaves the popup borderless.
+	#[must_use]
+	pub const fn borders(mut self, borders: Borders) -> Self {
+		self.borders = borders;
+		self
+	}
+
+	/// Sets the symbols the border is drawn with.
+	#[must_use]
+	pub const fn border_type(mut self, border_type: BorderType) -> Self {
+		self.border_type = border_type;
+		self
+	}
+
+	/// Sets the space between the border and the content.
+	#[must_use]
+	pub const fn padding(mut self, padding: Padding) -> Self {
+		self.padding = padding;
+		self
+	}
+
+	/// Sets the title drawn on the top border.
+	#[must_use]
+	pub fn title<T: Into<Line<'a>>>(mut self, title: T) -> Self {
+		self.title = Some(title.into());
+		self
+	}
+
+	/// Sets where the title sits on the top border.
+	#[must_use]
+	pub const fn title_alignment(mut self, alignment: HorizontalAlignment) -> Self {
+		self.title_alignment = alignment;
+		self
+	}
+
+	/// Sets whether to draw a drop shadow.
+	#[must_use]
+	pub const fn shadow(mut self, shadow: bool) -> Self {
+		self.shadow = shadow;
+		self
+	}
+
+	/// Sets the base style of the popup.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over the border.
+	#[must_use]
+	pub const fn border_style(mut self, style: Style) -> Self {
+		self.border_style = style;
+		self
+	}
+
+	/// Sets the style patched over the title.
+	#[must_use]
+	pub const fn title_style(mut self, style: Style) -> Self {
+		self.title_style = style;
+		self
+	}
+
+	/// Sets the style patched over the cells under the shadow.
+	#[must_use]
+	pub const fn shadow_style(mut self, style: Style) -> Self {
+		self.shadow_style = style;
+		self
+	}
+
+	/// Returns the area left for content inside the border and padding.
+	pub fn inner(&self, area: Rect) -> Rect {
+		self.to_block(area).inner(area)
+	}
+
+	/// Builds the block drawn over `area`, with the title cut to fit.
+	fn to_block(&self, area: Rect) -> Block<'a> {
+		let mut block = Block::new()
+			.borders(self.borders)
+			.border_type(self.border_type)
+			.border_style(self.border_style)
+			.padding(self.padding)
+			.style(self.style)
+			.title_style(self.title_style)
+			.title_alignment(self.title_alignment);
+		if let Some(title) = &self.title {
+			let sides = [Borders::LEFT, Borders::RIGHT]
+				.into_iter()
+				.filter(|&side| self.borders.contains(side))
+				.count();
+			let width = usize::from(area.width).saturating_sub(sides);
+			block = block.title(elide(title, width, Elision::End).unwrap_or_else(|| title.clone()));
+		}
+		block
+	}
+
+	/// Patches the shadow style over the column right of `area` and the row below it, both
+	/// offset by a cell.
+	fn render_shadow(&self, area: Rect, buf: &mut Buffer) {
+		let right = (area.y + 1..=area.bottom()).map(|y| (area.right(), y));
+		let below = (area.x + 1..area.right()).map(|x| (x, area.bottom()));
+		for position in right.chain(below) {
+			if buf.area.contains(position.into()) {
+				buf[position].set_style(self.shadow_style);
+			}
+		}
+	}
+}
+
+impl Widget for PopupFrame<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		Widget::render(&self, area, buf);
+	}
+}
+
+impl Widget for &PopupFrame<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+		if self.shadow {
+			self.render_shadow(area, buf);
+		}
+		Clear.render(area, buf);
+		self.to_block(area).render(area, buf);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	/// Renders `frame` over a 10x4 buffer filled with dots.
+	fn render(frame: &PopupFrame<'_>, area: Rect) -> Buffer {
+		let mut buf = Buffer::with_lines([".........."; 4]);
+		frame.render(area, &mut buf);
+		buf
+	}
+
+	#[rstest]
+	#[case::rounded(BorderType::Rounded, ["╭Title───╮", "│        │", "╰────────╯"])]
+	#[case::plain(BorderType::Plain, ["┌Title───┐", "│        │", "└────────┘"])]
+	#[case::double(BorderType::Double, ["╔Title═══╗", "║        ║", "╚════════╝"])]
+	fn renders_border_sets(#[case] border_type: BorderType, #[case] lines: [&str; 3]) {
+		let frame = PopupFrame::new().border_type(border_type).title("Title");
+		let buf = render(&frame, Rect::new(0, 0, 10, 3));
+		let [top, middle, bottom] = lines;
+		assert_eq!(buf, Buffer::with_lines([top, middle, bottom, ".........."]));
+	}
+
+	#[test]
+	fn renders_borderless_with_padding() {
+		let frame = PopupFrame::new()
+			.borders(Borders::NONE)
+			.padding(Padding::horizontal(1))
+			.title("Title");
+		let area = Rect::new(0, 0, 10, 3);
+		assert_eq!(frame.inner(area), Rect::new(1, 1, 8, 2));
+		assert_eq!(
+			render(&frame, area),
+			Buffer::with_lines(["Title     ", "          ", "          ", ".........."])
+		);
+	}
+
+	#[rstest]
+	#[case::left(HorizontalAlignment::Left, "╭Ab──────╮")]
+	#[case::center(HorizontalAlignment::Center, "╭───Ab───╮")]
+	#[case::right(HorizontalAlignment::Right, "╭──────Ab╮")]
+	fn aligns_title(#[case] alignment: HorizontalAlignment, #[case] top: &str) {
+		let frame = PopupFrame::new().title("Ab").title_alignment(alignment);
+		let buf = render(&frame, Rect::new(0, 0, 10, 2));
+		assert_eq!(
+			buf,
+			Buffer::with_lines([top, "╰────────╯", "..........", ".........."])
+		);
+	}
+
+	#[test]
+	fn truncates_long_title() {
+		let frame = PopupFrame::new().title("A very long title");
+		let buf = render(&frame, Rect::new(0, 0, 10, 2));
+		assert_eq!(
+			buf,
+			Buffer::with_lines(["╭A very …╮", "╰────────╯", "..........", ".........."])
+		);
+	}
+
+	#[test]
+	fn shadow_dims_cells_underneath() {
+		let frame = PopupFrame::new().shadow(true);
+		let buf = render(&frame, Rect::new(0, 0, 4, 2));
+		let mut expected =
+			Buffer::with_lines(["╭──╮......", "╰──╯......", "..........", ".........."]);
+		let dim = Style::new().add_modifier(Modifier::DIM);
+		expected.set_style(Rect::new(4, 1, 1, 2), dim);
+		expected.set_style(Rect::new(1, 2, 3, 1), dim);
+		assert_eq!(buf, expected);
+	}
+}
@@ -0,0 +1,219 @@
+//! The [`ProgressBar`] widget shows how far a determinate operation has got.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::symbols::block;
+use crate::widgets::Widget;
+
+/// A one-line bar filled in proportion to a ratio, optionally after a label.
+///
+/// The bar is drawn with block characters in eighths of a cell, so it moves smoothly even when
+/// narrow. It draws on the first row of its area: the label, a space, then the bar in the
+/// remaining width.
+///
+/// # Example
+///
+/// ```
+/// use xeno_tui::widgets::ProgressBar;
+///
+/// let bar = ProgressBar::new().percent(45).label("Indexing");
+/// assert_eq!(bar.bar(10), "████▌     ");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressBar<'a> {
+	/// Completed fraction, from 0 to 1.
+	ratio: f64,
+	/// Text drawn before the bar.
+	label: Option<Cow<'a, str>>,
+	/// Base style, including the unfilled part of the bar.
+	style: Style,
+	/// Style patched over the filled part of the bar.
+	filled_style: Style,
+	/// Style patched over the label.
+	label_style: Style,
+	/// Block characters the bar is drawn with.
+	symbols: block::Set<'a>,
+}
+
+impl Default for ProgressBar<'_> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a> ProgressBar<'a> {
+	/// Creates an empty bar with no label and no styling.
+	pub const fn new() -> Self {
+		Self {
+			ratio: 0.0,
+			label: None,
+			style: Style::new(),
+			filled_style: Style::new(),
+			label_style: Style::new(),
+			symbols: block::NINE_LEVELS,
+		}
+	}
+
+	/// Sets the completed fraction, clamped to 0..=1.
+	#[must_use]
+	pub const fn ratio(mut self, ratio: f64) -> Self {
+		// NaN compares false both ways and ends up empty.
+		self.ratio = if ratio >= 1.0 {
+			1.0
+		} else if ratio > 0.0 {
+			ratio
+		} else {
+			0.0
+		};
+		self
+	}
+
+	/// Sets the completed percentage, clamped to 100.
+	#[must_use]
+	pub const fn percent(self, percent: u16) -> Self {
+		let percent = if percent > 100 { 100 } else { percent };
+		self.ratio(percent as f64 / 100.0)
+	}
+
+	/// Sets the text drawn before the bar.
+	#[must_use]
+	pub fn label(mut self, label: impl Into<Cow<'a, str>>) -> Self {
+		self.label = Some(label.into());
+		self
+	}
+
+	/// Sets the base style, including the unfilled part of the bar.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over the filled part of the bar.
+	#[must_use]
+	pub const fn filled_style(mut self, style: Style) -> Self {
+		self.filled_style = style;
+		self
+	}
+
+	/// Sets the style patched over the label.
+	#[must_use]
+	pub const fn label_style(mut self, style: Style) -> Self {
+		self.label_style = style;
+		self
+	}
+
+	/// Sets the block characters the bar is drawn with.
+	#[must_use]
+	pub const fn symbols(mut self, symbols: block::Set<'a>) -> Self {
+		self.symbols = symbols;
+		self
+	}
+
+	/// Returns the bar as `width` cells of text, without the label.
+	pub fn bar(&self, width: u16) -> String {
+		(0..width).map(|cell| self.cell(cell, width).0).collect()
+	}
+
+	/// Returns the symbol for `cell` of a `width` wide bar, and whether it
+	/// is at least partly filled.
+	fn cell(&self, cell: u16, width: u16) -> (&'a str, bool) {
+		// Rounded to the nearest eighth of a cell.
+		let eighths = (self.ratio * f64::from(width) * 8.0 + 0.5) as u32;
+		let full = eighths / 8;
+		let cell = u32::from(cell);
+		if cell < full {
+			return (self.symbols.full, true);
+		}
+		if cell > full {
+			return (self.symbols.empty, false);
+		}
+		match eighths % 8 {
+			0 => (self.symbols.empty, false),
+			1 => (self.symbols.one_eighth, true),
+			2 => (self.symbols.one_quarter, true),
+			3 => (self.symbols.three_eighths, true),
+			4 => (self.symbols.half, true),
+			5 => (self.symbols.five_eighths, true),
+			6 => (self.symbols.three_quarters, true),
+			_ => (self.symbols.seven_eighths, true),
+		}
+	}
+}
+
+impl Widget for ProgressBar<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		Widget::render(&self, area, buf);
+	}
+}
+
+impl Widget for &ProgressBar<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+		buf.set_style(area, self.style);
+		let mut x = area.x;
+		if let Some(label) = &self.label {
+			let style = self.style.patch(self.label_style);
+			(x, _) = buf.set_stringn(x, area.y, label, usize::from(area.width), style);
+			x = (x + 1).min(area.right());
+		}
+
+		let width = area.right() - x;
+		let filled = self.style.patch(self.filled_style);
+		for cell in 0..width {
+			let (symbol, is_filled) = self.cell(cell, width);
+			let style = if is_filled { filled } else { self.style };
+			buf[(x + cell, area.y)].set_symbol(symbol).set_style(style);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::*;
+
+	#[rstest]
+	#[case::empty(0, "          ")]
+	#[case::one_eighth(1, "▏         ")]
+	#[case::partial(45, "████▌     ")]
+	#[case::almost(99, "█████████▉")]
+	#[case::full(100, "██████████")]
+	#[case::clamped(250, "██████████")]
+	fn bar_has_sub_cell_precision(#[case] percent: u16, #[case] expected: &str) {
+		assert_eq!(ProgressBar::new().percent(percent).bar(10), expected);
+	}
+
+	#[test]
+	fn ratio_is_clamped() {
+		assert_eq!(ProgressBar::new().ratio(f64::NAN).bar(4), "    ");
+		assert_eq!(ProgressBar::new().ratio(-1.0).bar(4), "    ");
+		assert_eq!(ProgressBar::new().ratio(2.0).bar(4), "████");
+	}
+
+	#[test]
+	fn renders_label_and_styled_bar() {
+		let mut buf = Buffer::empty(Rect::new(0, 0, 14, 1));
+		ProgressBar::new()
+			.percent(50)
+			.label("Index")
+			.style(Style::new().on_black())
+			.filled_style(Style::new().green())
+			.label_style(Style::new().bold())
+			.render(buf.area, &mut buf);
+
+		let mut expected = Buffer::with_lines(["Index ████    "]);
+		expected.set_style(buf.area, Style::new().on_black());
+		expected.set_style(Rect::new(0, 0, 5, 1), Style::new().bold());
+		expected.set_style(Rect::new(6, 0, 4, 1), Style::new().green());
+		assert_eq!(buf, expected);
+	}
+}
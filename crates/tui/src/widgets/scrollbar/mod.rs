@@ -440,6 +440,30 @@ impl ScrollbarState {
 		}
 	}
 
+	/// Constructs the state for a `viewport` showing items `offset..offset + viewport` of
+	/// `total` items, such as a scrolled list.
+	///
+	/// The thumb spans the visible share of the track and reaches the end of the track once the
+	/// last item is visible. When everything fits (`viewport >= total`) or the viewport is empty,
+	/// the content length is zero and the scrollbar renders nothing.
+	#[must_use = "creates the ScrollbarState"]
+	pub const fn for_viewport(total: usize, viewport: usize, offset: usize) -> Self {
+		if viewport == 0 || viewport >= total {
+			return Self::new(0);
+		}
+		let positions = total - viewport + 1;
+		let position = if offset < positions {
+			offset
+		} else {
+			positions - 1
+		};
+		Self {
+			content_length: positions,
+			position,
+			viewport_content_length: viewport,
+		}
+	}
+
 	/// Sets the scroll position of the scrollbar.
 	///
 	/// This represents the number of scrolled items.
@@ -46,3 +46,15 @@ fn render_in_zero_size_buffer(#[case] orientation: ScrollbarOrientation) {
 	// This should not panic, even if the buffer has zero size.
 	scrollbar.render(buffer.area, &mut buffer, &mut state);
 }
+
+#[rstest]
+#[case::vertical_left(ScrollbarOrientation::VerticalLeft)]
+#[case::vertical_right(ScrollbarOrientation::VerticalRight)]
+fn for_viewport_in_zero_height_area(#[case] orientation: ScrollbarOrientation) {
+	let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 4));
+	let scrollbar = Scrollbar::new(orientation);
+	let mut state = ScrollbarState::for_viewport(100, 0, 5);
+	// A zero-height viewport must not divide by zero.
+	scrollbar.render(Rect::new(0, 0, 4, 0), &mut buffer, &mut state);
+	assert_eq!(buffer, Buffer::empty(Rect::new(0, 0, 4, 4)));
+}
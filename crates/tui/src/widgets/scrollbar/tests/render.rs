@@ -383,3 +383,26 @@ fn thumb_visible_on_very_small_track(
 	scrollbar_no_arrows.render(buffer.area, &mut buffer, &mut state);
 	assert_eq!(buffer, Buffer::with_lines([expected]));
 }
+
+#[rstest]
+#[case::top("###-----", 10, 4, 0)]
+#[case::middle("--####--", 10, 4, 3)]
+#[case::last_item_visible("-----###", 10, 4, 6)]
+#[case::offset_out_of_bounds("-----###", 10, 4, 50)]
+#[case::everything_fits("        ", 4, 4, 0)]
+#[case::viewport_larger_than_content("        ", 3, 8, 0)]
+#[case::empty_viewport("        ", 10, 0, 0)]
+#[case::empty_content("        ", 0, 4, 0)]
+fn render_scrollbar_for_viewport(
+	#[case] expected: &str,
+	#[case] total: usize,
+	#[case] viewport: usize,
+	#[case] offset: usize,
+	scrollbar_no_arrows: Scrollbar,
+) {
+	let size = expected.width() as u16;
+	let mut buffer = Buffer::empty(Rect::new(0, 0, size, 1));
+	let mut state = ScrollbarState::for_viewport(total, viewport, offset);
+	scrollbar_no_arrows.render(buffer.area, &mut buffer, &mut state);
+	assert_eq!(buffer, Buffer::with_lines([expected]));
+}
@@ -0,0 +1,159 @@
+//! The [`Spinner`] widget shows that an operation of unknown length is running.
+
+use alloc::borrow::Cow;
+
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::symbols::spinner;
+use crate::widgets::Widget;
+
+/// A spinner frame, optionally followed by a label.
+///
+/// The spinner never reads the clock: its frame is picked from the tick passed to
+/// [`Spinner::new`], so the host decides how fast it turns and renders are reproducible.
+///
+/// # Example
+///
+/// ```
+/// use xeno_tui::symbols::spinner;
+/// use xeno_tui::widgets::Spinner;
+///
+/// let spinner = Spinner::new(3).frames(spinner::LINE).label("Indexing");
+/// assert_eq!(spinner.frame(), "/");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Spinner<'a> {
+	/// Ticks elapsed, selecting the frame.
+	tick: u64,
+	/// Frames cycled through, one per tick.
+	frames: &'a [&'a str],
+	/// Text drawn after the frame.
+	label: Option<Cow<'a, str>>,
+	/// Style of the frame.
+	style: Style,
+	/// Style patched over the label.
+	label_style: Style,
+}
+
+impl<'a> Spinner<'a> {
+	/// Creates a spinner showing the frame for `tick`, cycling through
+	/// [`spinner::DOTS`].
+	pub const fn new(tick: u64) -> Self {
+		Self {
+			tick,
+			frames: spinner::DOTS,
+			label: None,
+			style: Style::new(),
+			label_style: Style::new(),
+		}
+	}
+
+	/// Sets the frames cycled through, one per tick.
+	#[must_use]
+	pub const fn frames(mut self, frames: &'a [&'a str]) -> Self {
+		self.frames = frames;
+		self
+	}
+
+	/// Sets the text drawn after the frame.
+	#[must_use]
+	pub fn label(mut self, label: impl Into<Cow<'a, str>>) -> Self {
+		self.label = Some(label.into());
+		self
+	}
+
+	/// Sets the style of the frame.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over the label.
+	#[must_use]
+	pub const fn label_style(mut self, style: Style) -> Self {
+		self.label_style = style;
+		self
+	}
+
+	/// Returns the frame for the current tick, or `""` if there are no frames.
+	pub fn frame(&self) -> &'a str {
+		match self.frames.len() as u64 {
+			0 => "",
+			len => self.frames[(self.tick % len) as usize],
+		}
+	}
+}
+
+impl Widget for Spinner<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		Widget::render(&self, area, buf);
+	}
+}
+
+impl Widget for &Spinner<'_> {
+	fn render(self, area: Rect, buf: &mut Buffer) {
+		let area = area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+		buf.set_style(area, self.style);
+		let (x, _) = buf.set_stringn(
+			area.x,
+			area.y,
+			self.frame(),
+			usize::from(area.width),
+			self.style,
+		);
+		if let Some(label) = &self.label
+			&& x < area.right()
+		{
+			let x = x + 1;
+			buf.set_stringn(
+				x,
+				area.y,
+				label,
+				usize::from(area.right().saturating_sub(x)),
+				self.style.patch(self.label_style),
+			);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frame_follows_tick() {
+		let frames: [&str; 12] = core::array::from_fn(|tick| Spinner::new(tick as u64).frame());
+		assert_eq!(frames[..10], *spinner::DOTS);
+		assert_eq!(frames[10..], spinner::DOTS[..2]);
+		assert_eq!(Spinner::new(5).frames(&[]).frame(), "");
+	}
+
+	#[test]
+	fn renders_frame_and_label() {
+		let mut buf = Buffer::empty(Rect::new(0, 0, 12, 1));
+		Spinner::new(1)
+			.label("Indexing")
+			.style(Style::new().cyan())
+			.label_style(Style::new().bold())
+			.render(buf.area, &mut buf);
+		let mut expected = Buffer::with_lines(["⠙ Indexing  "]);
+		expected.set_style(Rect::new(0, 0, 12, 1), Style::new().cyan());
+		expected.set_style(Rect::new(2, 0, 8, 1), Style::new().bold());
+		assert_eq!(buf, expected);
+	}
+
+	#[test]
+	fn clips_label_to_area() {
+		let mut buf = Buffer::empty(Rect::new(0, 0, 5, 1));
+		Spinner::new(0)
+			.frames(spinner::LINE)
+			.label("Indexing")
+			.render(buf.area, &mut buf);
+		assert_eq!(buf, Buffer::with_lines(["- Ind"]));
+	}
+}
@@ -1,3 +1,6 @@
+use alloc::vec::Vec;
+
+use super::elision::{Elision, elide};
 use crate::buffer::Buffer;
 use crate::layout::Rect;
 use crate::style::{Style, Styled};
@@ -11,7 +14,8 @@ use crate::widgets::Widget;
 /// [`Style`] of the [`Cell`] by adding the [`Style`] of the [`Text`] content to the [`Style`] of
 /// the [`Cell`]. Styles set on the text content will only affect the content.
 ///
-/// You can use [`Text::alignment`] when creating a cell to align its content.
+/// You can use [`Text::alignment`] when creating a cell to align its content, and
+/// [`Cell::elision`] to mark where lines wider than the column were cut.
 ///
 /// # Examples
 ///
@@ -53,6 +57,8 @@ pub struct Cell<'a> {
 	content: Text<'a>,
 	/// Style applied to the cell.
 	style: Style,
+	/// How lines wider than the column are shortened.
+	elision: Elision,
 }
 
 impl<'a> Cell<'a> {
@@ -82,6 +88,7 @@ impl<'a> Cell<'a> {
 		Self {
 			content: content.into(),
 			style: Style::default(),
+			elision: Elision::Clip,
 		}
 	}
 
@@ -152,13 +159,50 @@ impl<'a> Cell<'a> {
 		self.style = style.into();
 		self
 	}
+
+	/// Set how lines wider than the column are shortened
+	///
+	/// Lines are clipped at the column edge by default. [`Elision::End`] and
+	/// [`Elision::Middle`] instead mark the cut with `…`, and the latter keeps the file name of
+	/// a path whole.
+	///
+	/// This is a fluent setter method which must be chained or used as it consumes self
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use xeno_tui::widgets::{Cell, Elision};
+	///
+	/// Cell::new("src/widgets/table/mod.rs").elision(Elision::Middle);
+	/// ```
+	#[must_use = "method moves the value of self and returns the modified value"]
+	pub const fn elision(mut self, elision: Elision) -> Self {
+		self.elision = elision;
+		self
+	}
 }
 
 impl Cell<'_> {
 	/// Renders the cell content into the given area.
 	pub(crate) fn render(&self, area: Rect, buf: &mut Buffer) {
 		buf.set_style(area, self.style);
-		Widget::render(&self.content, area, buf);
+		let width = usize::from(area.width);
+		let elided: Vec<_> = self
+			.content
+			.lines
+			.iter()
+			.enumerate()
+			.filter_map(|(i, line)| Some((i, elide(line, width, self.elision)?)))
+			.collect();
+		if elided.is_empty() {
+			Widget::render(&self.content, area, buf);
+			return;
+		}
+		let mut content = self.content.clone();
+		for (i, line) in elided {
+			content.lines[i] = line;
+		}
+		Widget::render(&content, area, buf);
 	}
 }
 
@@ -170,6 +214,7 @@ where
 		Self {
 			content: content.into(),
 			style: Style::default(),
+			elision: Elision::Clip,
 		}
 	}
 }
@@ -210,6 +255,22 @@ mod tests {
 		assert_eq!(cell.style, style);
 	}
 
+	#[test]
+	fn elision() {
+		let cell = Cell::new("").elision(Elision::Middle);
+		assert_eq!(cell.elision, Elision::Middle);
+		assert_eq!(Cell::new("").elision, Elision::Clip);
+	}
+
+	#[test]
+	fn render_elided() {
+		let mut buf = Buffer::empty(Rect::new(0, 0, 10, 2));
+		Cell::new("crates/tui/src/lib.rs\nshort")
+			.elision(Elision::Middle)
+			.render(buf.area, &mut buf);
+		assert_eq!(buf, Buffer::with_lines(["cr…/lib.rs", "short     "]));
+	}
+
 	#[test]
 	fn stylize() {
 		assert_eq!(
@@ -0,0 +1,192 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::text::{Line, Span};
+
+/// Marks where text was cut.
+const ELLIPSIS: &str = "…";
+
+/// How a [`Cell`](super::Cell) shortens a line wider than its column.
+///
+/// # Example
+///
+/// ```rust
+/// use xeno_tui::widgets::{Cell, Elision};
+///
+/// Cell::new("crates/tui/src/widgets/table/mod.rs").elision(Elision::Middle);
+/// ```
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Elision {
+	/// Cut the line off at the edge of the column.
+	#[default]
+	Clip,
+	/// Keep the start of the line and end it with `…`.
+	End,
+	/// Keep both ends of the line with `…` in between.
+	///
+	/// Paths keep their file name whole and lose whole directories from the
+	/// middle, so `crates/tui/src/widgets/table/mod.rs` becomes
+	/// `crates/tui/…/mod.rs`.
+	Middle,
+}
+
+/// Returns `line` shortened to `width` cells, or `None` if it fits or
+/// `elision` is [`Elision::Clip`].
+pub(crate) fn elide<'a>(line: &Line<'a>, width: usize, elision: Elision) -> Option<Line<'a>> {
+	let text: String = line.spans.iter().map(|span| &*span.content).collect();
+	if elision == Elision::Clip || width == 0 || text.width() <= width {
+		return None;
+	}
+	let budget = width - 1;
+	let (head_end, tail_start) = match elision {
+		Elision::Clip => unreachable!(),
+		Elision::End => (prefix_end(&text, budget), text.len()),
+		Elision::Middle => middle_cut(&text, budget),
+	};
+
+	// The ellipsis takes the style of the span it replaces text in.
+	let mut spans = Vec::with_capacity(line.spans.len() + 1);
+	let mut elided = false;
+	let mut start = 0;
+	for span in &line.spans {
+		let end = start + span.content.len();
+		if start < head_end {
+			spans.push(slice(span, 0, head_end.min(end) - start));
+		}
+		if !elided && end > head_end {
+			elided = true;
+			spans.push(Span::styled(ELLIPSIS, span.style));
+		}
+		if end > tail_start {
+			spans.push(slice(span, tail_start.max(start) - start, end - start));
+		}
+		start = end;
+	}
+	Some(Line {
+		spans,
+		..line.clone()
+	})
+}
+
+/// Returns bytes `from..to` of `span`.
+fn slice<'a>(span: &Span<'a>, from: usize, to: usize) -> Span<'a> {
+	Span::styled(String::from(&span.content[from..to]), span.style)
+}
+
+/// Returns the end of the longest prefix of `text` at most `budget` cells
+/// wide.
+fn prefix_end(text: &str, budget: usize) -> usize {
+	let mut used = 0;
+	for (i, grapheme) in text.grapheme_indices(true) {
+		used += grapheme.width();
+		if used > budget {
+			return i;
+		}
+	}
+	text.len()
+}
+
+/// Returns the start of the longest suffix of `text` at most `budget`
+/// cells wide.
+fn suffix_start(text: &str, budget: usize) -> usize {
+	let mut used = 0;
+	for (i, grapheme) in text.grapheme_indices(true).rev() {
+		used += grapheme.width();
+		if used > budget {
+			return i + grapheme.len();
+		}
+	}
+	0
+}
+
+/// Returns the end of the kept head and the start of the kept tail of
+/// `text`, together at most `budget` cells wide.
+fn middle_cut(text: &str, budget: usize) -> (usize, usize) {
+	if let Some(slash) = text.rfind('/') {
+		let tail_width = text[slash..].width();
+		if tail_width <= budget {
+			// Keep whole leading directories, then give what is left to whole
+			// directories before the file name.
+			let head = &text[..slash];
+			let mut head_end = prefix_end(head, budget - tail_width);
+			if let Some(dir) = head[..head_end].rfind('/') {
+				head_end = dir + 1;
+			}
+			let mut spare = budget - tail_width - head[..head_end].width();
+			let mut tail_start = slash;
+			while let Some(dir) = text[head_end..tail_start].rfind('/') {
+				let dir = head_end + dir;
+				let width = text[dir..tail_start].width();
+				if width > spare {
+					break;
+				}
+				spare -= width;
+				tail_start = dir;
+			}
+			return (head_end, tail_start);
+		}
+	}
+	let head_end = prefix_end(text, budget.div_ceil(2));
+	let tail_start = suffix_start(text, budget - text[..head_end].width());
+	(head_end, tail_start.max(head_end))
+}
+
+#[cfg(test)]
+mod tests {
+	use alloc::string::ToString;
+	use alloc::vec;
+
+	use rstest::rstest;
+
+	use super::*;
+	use crate::style::{Style, Stylize};
+
+	fn elided(text: &str, width: usize, elision: Elision) -> String {
+		let line = Line::from(text);
+		elide(&line, width, elision).map_or_else(|| String::from(text), |line| line.to_string())
+	}
+
+	#[rstest]
+	#[case::fits("src/lib.rs", 10, "src/lib.rs")]
+	#[case::end("hello world", 8, "hello w…")]
+	#[case::wide("中文字符", 5, "中文…")]
+	fn elides_end(#[case] text: &str, #[case] width: usize, #[case] expected: &str) {
+		assert_eq!(elided(text, width, Elision::End), expected);
+	}
+
+	#[rstest]
+	#[case::drops_directories("crates/tui/src/widgets/table/mod.rs", 20, "crates/tui/…/mod.rs")]
+	#[case::keeps_directories_before_name(
+		"crates/tui/src/widgets/table/mod.rs",
+		30,
+		"crates/tui/src/…/table/mod.rs"
+	)]
+	#[case::long_name("a/very_long_file_name.rs", 12, "a/very…me.rs")]
+	#[case::plain_text("hello world", 8, "hell…rld")]
+	#[case::one_cell("hello", 1, "…")]
+	fn elides_middle(#[case] text: &str, #[case] width: usize, #[case] expected: &str) {
+		assert_eq!(elided(text, width, Elision::Middle), expected);
+	}
+
+	#[test]
+	fn clip_leaves_text_alone() {
+		assert_eq!(elided("hello world", 5, Elision::Clip), "hello world");
+	}
+
+	#[test]
+	fn keeps_span_styles() {
+		let line = Line::from(vec![Span::from("src/").dim(), Span::from("widgets/mod.rs")]);
+		let elided = elide(&line, 12, Elision::Middle).unwrap();
+		assert_eq!(
+			elided.spans,
+			vec![
+				Span::styled("src/", Style::new().dim()),
+				Span::styled("…", Style::new()),
+				Span::styled("/mod.rs", Style::new()),
+			]
+		);
+	}
+}
@@ -7,6 +7,8 @@ use alloc::vec::Vec;
 use itertools::Itertools;
 
 pub use self::cell::Cell;
+pub use self::elision::Elision;
+pub(crate) use self::elision::elide;
 pub use self::highlight_spacing::HighlightSpacing;
 pub use self::row::Row;
 pub use self::state::TableState;
@@ -19,6 +21,8 @@ use crate::widgets::{StatefulWidget, Widget};
 
 /// Table cell widget for individual data items.
 mod cell;
+/// Shortening cell text that overflows its column.
+mod elision;
 /// Controls when highlight symbol spacing is allocated.
 mod highlight_spacing;
 /// Table row widget containing cells.
@@ -363,3 +363,38 @@ fn render_with_selection_and_offset<T: Into<Option<usize>>>(
 	assert_eq!(buf, Buffer::with_lines(expected_items));
 	assert_eq!(state.offset, expected_offset);
 }
+
+#[test]
+fn render_elided_cells_in_narrow_area() {
+	let header = Row::new(["path", "ln", "message"]);
+	let rows = [
+		("crates/tui/src/lib.rs", "12", "unused import `alloc`"),
+		("src/main.rs", "3", "missing semicolon"),
+	]
+	.map(|(path, line, message)| {
+		Row::new([
+			Cell::new(path).elision(Elision::Middle),
+			Cell::new(line),
+			Cell::new(message).elision(Elision::End),
+		])
+	});
+	let widths = [
+		Constraint::Length(10),
+		Constraint::Length(3),
+		Constraint::Fill(1),
+	];
+	let table = Table::new(rows, widths)
+		.header(header)
+		.row_highlight_style(Style::new().reversed());
+	let mut buf = Buffer::empty(Rect::new(0, 0, 24, 3));
+	let mut state = TableState::new().with_selected(1);
+	StatefulWidget::render(table, buf.area, &mut buf, &mut state);
+
+	let mut expected = Buffer::with_lines([
+		"path       ln  message  ",
+		"cr…/lib.rs 12  unused i…",
+		"s…/main.rs 3   missing …",
+	]);
+	expected.set_style(Rect::new(0, 2, 24, 1), Style::new().reversed());
+	assert_eq!(buf, expected);
+}
@@ -0,0 +1,177 @@
+//! The [`TextInput`] widget edits a single line of text, for prompts.
+//!
+//! Editing lives in [`TextInputState`], driven by [`TextInputKey`]s: the
+//! cursor moves over whole graphemes, words are killed into a kill ring
+//! that can be yanked back, and ctrl-a/ctrl-e style home and end are
+//! provided. When the text is wider than the input it scrolls horizontally
+//! to keep the cursor in view. The input can mask its text, shows a
+//! placeholder while empty, and records where the terminal cursor belongs
+//! in [`TextInputState::screen_cursor`].
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::text_input::{TextInput, TextInputKey, TextInputState};
+//!
+//! let mut state = TextInputState::new();
+//! state.handle_key(TextInputKey::Char('w'));
+//! state.handle_key(TextInputKey::Char('q'));
+//! assert_eq!(state.value(), "wq");
+//!
+//! let input = TextInput::new().placeholder("command");
+//! ```
+
+use alloc::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub use self::state::{TextInputKey, TextInputState};
+use crate::buffer::Buffer;
+use crate::layout::{Position, Rect};
+use crate::style::Style;
+use crate::widgets::block::Block;
+use crate::widgets::{StatefulWidget, Widget};
+
+/// Value, cursor and editing.
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+/// A single-line text input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput<'a> {
+	/// Block drawn around the input.
+	block: Option<Block<'a>>,
+	/// Base style of the input.
+	style: Style,
+	/// Text shown while the input is empty.
+	placeholder: Cow<'a, str>,
+	/// Style patched over the placeholder.
+	placeholder_style: Style,
+	/// Character drawn in place of each grapheme, hiding the text.
+	mask: Option<char>,
+}
+
+impl<'a> TextInput<'a> {
+	/// Creates an input with no placeholder and no styling.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Wraps the input in a block.
+	#[must_use]
+	pub fn block(mut self, block: Block<'a>) -> Self {
+		self.block = Some(block);
+		self
+	}
+
+	/// Sets the base style of the input.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the text shown while the input is empty.
+	#[must_use]
+	pub fn placeholder(mut self, placeholder: impl Into<Cow<'a, str>>) -> Self {
+		self.placeholder = placeholder.into();
+		self
+	}
+
+	/// Sets the style patched over the placeholder.
+	#[must_use]
+	pub const fn placeholder_style(mut self, style: Style) -> Self {
+		self.placeholder_style = style;
+		self
+	}
+
+	/// Draws `mask` in place of every grapheme, as for a password.
+	#[must_use]
+	pub const fn mask(mut self, mask: char) -> Self {
+		self.mask = Some(mask);
+		self
+	}
+
+	/// Returns the number of cells `grapheme` takes.
+	fn grapheme_width(&self, grapheme: &str) -> usize {
+		match self.mask {
+			Some(mask) => mask.width().unwrap_or(0),
+			None => grapheme.width(),
+		}
+	}
+}
+
+impl StatefulWidget for TextInput<'_> {
+	type State = TextInputState;
+
+	fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		let area = area.intersection(buf.area);
+		buf.set_style(area, self.style);
+		let inner = match self.block.take() {
+			Some(block) => {
+				let inner = block.inner(area);
+				block.render(area, buf);
+				inner
+			}
+			None => area,
+		};
+		state.screen_cursor = None;
+		if inner.is_empty() {
+			return;
+		}
+		let width = usize::from(inner.width);
+
+		if state.value().is_empty() {
+			buf.set_stringn(
+				inner.x,
+				inner.y,
+				&self.placeholder,
+				width,
+				self.style.patch(self.placeholder_style),
+			);
+			state.scroll = 0;
+			state.screen_cursor = Some(inner.as_position());
+			return;
+		}
+
+		let value = state.value();
+		let cursor: usize = value[..state.cursor()]
+			.graphemes(true)
+			.map(|g| self.grapheme_width(g))
+			.sum();
+		let total: usize = value.graphemes(true).map(|g| self.grapheme_width(g)).sum();
+		// Keep the cursor in view, and a cell free after the text for it.
+		let mut scroll = state.scroll;
+		if cursor < scroll {
+			scroll = cursor;
+		} else if cursor >= scroll + width {
+			scroll = cursor + 1 - width;
+		}
+		scroll = scroll.min((total + 1).saturating_sub(width));
+
+		let mut utf8 = [0; 4];
+		let mut column = 0;
+		for grapheme in value.graphemes(true) {
+			let cells = self.grapheme_width(grapheme);
+			// A wide grapheme cut by the left edge is left blank.
+			if column >= scroll {
+				let x = column - scroll;
+				if x + cells > width {
+					break;
+				}
+				let text = match self.mask {
+					Some(mask) => &*mask.encode_utf8(&mut utf8),
+					None => grapheme,
+				};
+				buf.set_stringn(inner.x + x as u16, inner.y, text, cells, self.style);
+			}
+			column += cells;
+		}
+
+		state.scroll = scroll;
+		state.screen_cursor = Some(Position::new(inner.x + (cursor - scroll) as u16, inner.y));
+	}
+}
@@ -0,0 +1,328 @@
+//! Text input value, cursor and kill ring editing.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::Position;
+
+/// Most kills kept for yanking.
+const KILL_RING_SIZE: usize = 16;
+
+/// An edit or movement the input knows how to apply.
+///
+/// The conventional bindings are noted on each variant; mapping terminal
+/// keys to them is left to the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextInputKey {
+	/// Insert a character at the cursor.
+	Char(char),
+	/// Delete the grapheme before the cursor (backspace).
+	Backspace,
+	/// Delete the grapheme after the cursor (delete).
+	Delete,
+	/// Move one grapheme left (left).
+	Left,
+	/// Move one grapheme right (right).
+	Right,
+	/// Move to the start of the previous word (alt-b, ctrl-left).
+	WordLeft,
+	/// Move to the end of the next word (alt-f, ctrl-right).
+	WordRight,
+	/// Move to the start of the input (ctrl-a, home).
+	Home,
+	/// Move to the end of the input (ctrl-e, end).
+	End,
+	/// Kill back to the start of the previous word (ctrl-w, alt-backspace).
+	KillWordBackward,
+	/// Kill forward to the end of the next word (alt-d).
+	KillWordForward,
+	/// Kill back to the start of the input (ctrl-u).
+	KillToStart,
+	/// Kill forward to the end of the input (ctrl-k).
+	KillToEnd,
+	/// Insert the most recent kill (ctrl-y).
+	Yank,
+	/// Replace the text just yanked with the kill before it (alt-y).
+	YankPop,
+}
+
+/// What the previous key did, for merging kills and cycling yanks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum LastEdit {
+	/// Anything that is not a kill or yank.
+	#[default]
+	Other,
+	/// Killed text, so the next kill extends the same ring entry.
+	Kill,
+	/// Yanked text from `start` to the cursor.
+	Yank {
+		/// Byte index where the yanked text starts.
+		start: usize,
+		/// Ring entry yanked, counting back from the most recent.
+		index: usize,
+	},
+}
+
+/// State of a [`TextInput`](super::TextInput): its value, cursor, scroll
+/// position and kill ring.
+///
+/// The cursor always sits on a grapheme boundary, so wide characters and
+/// combining sequences are moved over and deleted as a whole.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInputState {
+	/// The entered text, without control characters.
+	value: String,
+	/// Byte index of the cursor in `value`.
+	cursor: usize,
+	/// Display column of `value` drawn at the left edge, as of the last render.
+	pub(crate) scroll: usize,
+	/// Killed text, most recent last.
+	kill_ring: Vec<String>,
+	/// What the previous key did.
+	last: LastEdit,
+	/// Where the terminal cursor belongs, as of the last render.
+	pub(crate) screen_cursor: Option<Position>,
+}
+
+impl TextInputState {
+	/// Creates an empty input.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Creates an input holding `value`, with the cursor at the end.
+	pub fn with_value(value: &str) -> Self {
+		let mut state = Self::new();
+		state.set_value(value);
+		state
+	}
+
+	/// Returns the entered text.
+	pub fn value(&self) -> &str {
+		&self.value
+	}
+
+	/// Replaces the text and moves the cursor to the end.
+	pub fn set_value(&mut self, value: &str) {
+		self.value = sanitize(value);
+		self.cursor = self.value.len();
+		self.scroll = 0;
+		self.last = LastEdit::Other;
+	}
+
+	/// Clears the text, keeping the kill ring.
+	pub fn clear(&mut self) {
+		self.set_value("");
+	}
+
+	/// Returns the byte index of the cursor in the value.
+	pub fn cursor(&self) -> usize {
+		self.cursor
+	}
+
+	/// Moves the cursor to the grapheme boundary at or before byte `index`.
+	pub fn set_cursor(&mut self, index: usize) {
+		let len = self.value.len();
+		self.cursor = self
+			.value
+			.grapheme_indices(true)
+			.map(|(i, _)| i)
+			.chain([len])
+			.take_while(|&i| i <= index)
+			.last()
+			.unwrap_or(0);
+		self.last = LastEdit::Other;
+	}
+
+	/// Returns where the terminal cursor belongs, as of the last render, or
+	/// `None` if the input was not visible.
+	pub fn screen_cursor(&self) -> Option<Position> {
+		self.screen_cursor
+	}
+
+	/// Inserts `text` at the cursor, dropping control characters such as
+	/// newlines.
+	pub fn insert_str(&mut self, text: &str) {
+		let text = sanitize(text);
+		self.value.insert_str(self.cursor, &text);
+		self.cursor += text.len();
+		self.last = LastEdit::Other;
+	}
+
+	/// Handles a key. Returns true if it was used.
+	pub fn handle_key(&mut self, key: TextInputKey) -> bool {
+		let last = core::mem::take(&mut self.last);
+		let kill = last == LastEdit::Kill;
+		match key {
+			TextInputKey::Char(c) => {
+				if c.is_control() {
+					return false;
+				}
+				self.value.insert(self.cursor, c);
+				self.cursor += c.len_utf8();
+			}
+			TextInputKey::Backspace => {
+				let Some(start) = self.prev_boundary(self.cursor) else {
+					return false;
+				};
+				self.value.replace_range(start..self.cursor, "");
+				self.cursor = start;
+			}
+			TextInputKey::Delete => {
+				let Some(end) = self.next_boundary(self.cursor) else {
+					return false;
+				};
+				self.value.replace_range(self.cursor..end, "");
+			}
+			TextInputKey::Left => {
+				self.cursor = self.prev_boundary(self.cursor).unwrap_or(0);
+			}
+			TextInputKey::Right => {
+				self.cursor = self.next_boundary(self.cursor).unwrap_or(self.cursor);
+			}
+			TextInputKey::WordLeft => self.cursor = self.word_start(self.cursor),
+			TextInputKey::WordRight => self.cursor = self.word_end(self.cursor),
+			TextInputKey::Home => self.cursor = 0,
+			TextInputKey::End => self.cursor = self.value.len(),
+			TextInputKey::KillWordBackward => {
+				return self.kill(self.word_start(self.cursor)..self.cursor, true, kill);
+			}
+			TextInputKey::KillWordForward => {
+				return self.kill(self.cursor..self.word_end(self.cursor), false, kill);
+			}
+			TextInputKey::KillToStart => return self.kill(0..self.cursor, true, kill),
+			TextInputKey::KillToEnd => {
+				return self.kill(self.cursor..self.value.len(), false, kill);
+			}
+			TextInputKey::Yank => {
+				let Some(text) = self.kill_ring.last().cloned() else {
+					return false;
+				};
+				let start = self.cursor;
+				self.insert_str(&text);
+				self.last = LastEdit::Yank { start, index: 0 };
+			}
+			TextInputKey::YankPop => {
+				let LastEdit::Yank { start, index } = last else {
+					return false;
+				};
+				let index = (index + 1) % self.kill_ring.len();
+				let text = self.kill_ring[self.kill_ring.len() - 1 - index].clone();
+				self.value.replace_range(start..self.cursor, &text);
+				self.cursor = start + text.len();
+				self.last = LastEdit::Yank { start, index };
+			}
+		}
+		true
+	}
+
+	/// Removes `range` into the kill ring, extending the most recent entry
+	/// if the previous key was also a kill. Returns false if `range` is
+	/// empty.
+	fn kill(&mut self, range: Range<usize>, backward: bool, extend: bool) -> bool {
+		if range.is_empty() {
+			// An empty kill still continues a run of kills.
+			if extend {
+				self.last = LastEdit::Kill;
+			}
+			return false;
+		}
+		let text: String = self.value.drain(range.clone()).collect();
+		self.cursor = range.start;
+		match self.kill_ring.last_mut() {
+			Some(top) if extend && backward => top.insert_str(0, &text),
+			Some(top) if extend => top.push_str(&text),
+			_ => {
+				if self.kill_ring.len() == KILL_RING_SIZE {
+					self.kill_ring.remove(0);
+				}
+				self.kill_ring.push(text);
+			}
+		}
+		self.last = LastEdit::Kill;
+		true
+	}
+
+	/// Returns the start of the grapheme before byte `index`.
+	fn prev_boundary(&self, index: usize) -> Option<usize> {
+		self.value[..index]
+			.grapheme_indices(true)
+			.next_back()
+			.map(|(i, _)| i)
+	}
+
+	/// Returns the end of the grapheme after byte `index`.
+	fn next_boundary(&self, index: usize) -> Option<usize> {
+		self.value[index..]
+			.graphemes(true)
+			.next()
+			.map(|g| index + g.len())
+	}
+
+	/// Returns the start of the word before byte `index`, skipping
+	/// whitespace first.
+	fn word_start(&self, index: usize) -> usize {
+		let mut graphemes = self.value[..index].grapheme_indices(true).rev().peekable();
+		while graphemes
+			.next_if(|(_, g)| class(g) == Class::Space)
+			.is_some()
+		{}
+		let Some(&(mut start, first)) = graphemes.peek() else {
+			return 0;
+		};
+		for (i, g) in graphemes {
+			if class(g) != class(first) {
+				break;
+			}
+			start = i;
+		}
+		start
+	}
+
+	/// Returns the end of the word after byte `index`, skipping whitespace
+	/// first.
+	fn word_end(&self, index: usize) -> usize {
+		let mut graphemes = self.value[index..].grapheme_indices(true).peekable();
+		while graphemes
+			.next_if(|(_, g)| class(g) == Class::Space)
+			.is_some()
+		{}
+		let Some(&(_, first)) = graphemes.peek() else {
+			return self.value.len();
+		};
+		for (i, g) in graphemes {
+			if class(g) != class(first) {
+				return index + i;
+			}
+		}
+		self.value.len()
+	}
+}
+
+/// Kinds of grapheme a word is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+	/// Whitespace between words.
+	Space,
+	/// Letters, digits and underscores.
+	Word,
+	/// Anything else, such as punctuation.
+	Punct,
+}
+
+/// Classifies a grapheme by its first character.
+fn class(grapheme: &str) -> Class {
+	match grapheme.chars().next() {
+		Some(c) if c.is_whitespace() => Class::Space,
+		Some(c) if c.is_alphanumeric() || c == '_' => Class::Word,
+		_ => Class::Punct,
+	}
+}
+
+/// Drops control characters, which have no place in a single line.
+fn sanitize(text: &str) -> String {
+	text.chars().filter(|c| !c.is_control()).collect()
+}
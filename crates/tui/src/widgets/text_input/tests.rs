@@ -0,0 +1,174 @@
+use super::*;
+
+/// Renders `input` into a `width` by one buffer.
+fn render(input: TextInput<'_>, state: &mut TextInputState, width: u16) -> Buffer {
+	let area = Rect::new(0, 0, width, 1);
+	let mut buf = Buffer::empty(area);
+	input.render(area, &mut buf, state);
+	buf
+}
+
+fn keys(state: &mut TextInputState, keys: &[TextInputKey]) {
+	for &key in keys {
+		state.handle_key(key);
+	}
+}
+
+#[test]
+fn edits_around_wide_characters() {
+	let mut state = TextInputState::with_value("a中b");
+	assert_eq!(state.cursor(), 5);
+	keys(&mut state, &[TextInputKey::Left, TextInputKey::Left]);
+	assert_eq!(state.cursor(), 1);
+	state.handle_key(TextInputKey::Right);
+	assert_eq!(state.cursor(), 4);
+
+	state.handle_key(TextInputKey::Backspace);
+	assert_eq!(state.value(), "ab");
+	state.handle_key(TextInputKey::Char('文'));
+	assert_eq!((state.value(), state.cursor()), ("a文b", 4));
+	keys(&mut state, &[TextInputKey::Home, TextInputKey::Delete]);
+	assert_eq!((state.value(), state.cursor()), ("文b", 0));
+
+	state.set_cursor(2);
+	assert_eq!(state.cursor(), 0);
+}
+
+#[test]
+fn deletes_combining_sequences_whole() {
+	let mut state = TextInputState::with_value("e\u{301}x");
+	state.handle_key(TextInputKey::Left);
+	assert_eq!(state.cursor(), 3);
+	state.handle_key(TextInputKey::Backspace);
+	assert_eq!(state.value(), "x");
+	assert!(!state.handle_key(TextInputKey::Backspace));
+}
+
+#[test]
+fn moves_by_words() {
+	let mut state = TextInputState::with_value("foo.bar baz");
+	let mut stops = [0; 4];
+	for stop in &mut stops {
+		state.handle_key(TextInputKey::WordLeft);
+		*stop = state.cursor();
+	}
+	assert_eq!(stops, [8, 4, 3, 0]);
+	for stop in &mut stops {
+		state.handle_key(TextInputKey::WordRight);
+		*stop = state.cursor();
+	}
+	assert_eq!(stops, [3, 4, 7, 11]);
+}
+
+#[test]
+fn consecutive_kills_extend_one_entry() {
+	let mut state = TextInputState::with_value("alpha beta");
+	keys(
+		&mut state,
+		&[
+			TextInputKey::KillWordBackward,
+			TextInputKey::KillWordBackward,
+		],
+	);
+	assert_eq!(state.value(), "");
+	state.handle_key(TextInputKey::Yank);
+	assert_eq!(state.value(), "alpha beta");
+
+	keys(
+		&mut state,
+		&[
+			TextInputKey::Home,
+			TextInputKey::KillWordForward,
+			TextInputKey::KillToEnd,
+		],
+	);
+	assert_eq!(state.value(), "");
+	state.handle_key(TextInputKey::Yank);
+	assert_eq!(state.value(), "alpha beta");
+}
+
+#[test]
+fn yank_pop_cycles_kills() {
+	let mut state = TextInputState::with_value("one two three");
+	keys(
+		&mut state,
+		&[
+			TextInputKey::KillWordBackward,
+			TextInputKey::Left,
+			TextInputKey::KillWordBackward,
+			TextInputKey::End,
+			TextInputKey::Yank,
+		],
+	);
+	assert_eq!(state.value(), "one  two");
+	state.handle_key(TextInputKey::YankPop);
+	assert_eq!(state.value(), "one  three");
+	state.handle_key(TextInputKey::YankPop);
+	assert_eq!(state.value(), "one  two");
+
+	state.handle_key(TextInputKey::Char('!'));
+	assert!(!state.handle_key(TextInputKey::YankPop));
+}
+
+#[test]
+fn scrolls_to_keep_cursor_visible() {
+	let mut state = TextInputState::with_value("hello world");
+	let buf = render(TextInput::new(), &mut state, 5);
+	assert_eq!(buf, Buffer::with_lines(["orld "]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(4, 0)));
+
+	keys(&mut state, &[TextInputKey::WordLeft]);
+	let buf = render(TextInput::new(), &mut state, 5);
+	assert_eq!(buf, Buffer::with_lines(["world"]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(0, 0)));
+
+	keys(&mut state, &[TextInputKey::Left, TextInputKey::Left]);
+	let buf = render(TextInput::new(), &mut state, 5);
+	assert_eq!(buf, Buffer::with_lines(["o wor"]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(0, 0)));
+
+	keys(&mut state, &[TextInputKey::Home]);
+	let buf = render(TextInput::new(), &mut state, 5);
+	assert_eq!(buf, Buffer::with_lines(["hello"]));
+}
+
+#[test]
+fn blanks_wide_characters_cut_by_edges() {
+	let mut state = TextInputState::with_value("中文字");
+	let buf = render(TextInput::new(), &mut state, 4);
+	assert_eq!(buf, Buffer::with_lines([" 字 "]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(3, 0)));
+
+	state.handle_key(TextInputKey::Home);
+	let buf = render(TextInput::new(), &mut state, 3);
+	assert_eq!(buf, Buffer::with_lines(["中 "]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(0, 0)));
+}
+
+#[test]
+fn masks_text() {
+	let mut state = TextInputState::with_value("hunter2");
+	let buf = render(TextInput::new().mask('*'), &mut state, 10);
+	assert_eq!(buf, Buffer::with_lines(["*******   "]));
+	assert_eq!(state.screen_cursor(), Some(Position::new(7, 0)));
+}
+
+#[test]
+fn shows_placeholder_while_empty() {
+	let mut state = TextInputState::new();
+	let input = TextInput::new()
+		.placeholder("search")
+		.placeholder_style(Style::new().dim());
+	let mut expected = Buffer::with_lines(["search  "]);
+	expected.set_style(Rect::new(0, 0, 6, 1), Style::new().dim());
+	assert_eq!(render(input, &mut state, 8), expected);
+	assert_eq!(state.screen_cursor(), Some(Position::new(0, 0)));
+}
+
+#[test]
+fn drops_control_characters() {
+	let mut state = TextInputState::new();
+	state.insert_str("a\nb\tc");
+	assert!(!state.handle_key(TextInputKey::Char('\r')));
+	assert_eq!(state.value(), "abc");
+}
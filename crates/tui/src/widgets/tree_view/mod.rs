@@ -0,0 +1,231 @@
+//! The [`TreeView`] widget shows a tree of expandable nodes.
+//!
+//! Each visible node takes a row, indented under its parent with guides
+//! drawn from [`line`] symbols, followed by an expand marker and its label.
+//! A scrollbar is drawn when the rows do not all fit.
+//!
+//! Nodes live in a [`TreeViewState`], which loads children lazily, tracks
+//! expansion by node id and handles keyboard navigation.
+//!
+//! # Example
+//!
+//! ```
+//! use xeno_tui::widgets::tree_view::{TreeItem, TreeKey, TreeView, TreeViewState};
+//!
+//! let mut state = TreeViewState::new().with_loader(|id: &u32| {
+//!     (1..=2).map(|i| TreeItem::leaf(id * 10 + i, "child")).collect()
+//! });
+//! state.set_roots([TreeItem::branch(1, "root")]);
+//! state.handle_key(TreeKey::Right);
+//! assert_eq!(state.row_count(), 3);
+//!
+//! let tree = TreeView::<u32>::new();
+//! ```
+
+use core::hash::Hash;
+use core::marker::PhantomData;
+
+pub use self::state::{ChildLoader, TreeItem, TreeKey, TreeViewState, VisibleRow};
+use crate::buffer::Buffer;
+use crate::layout::Rect;
+use crate::style::Style;
+use crate::symbols::{line, scrollbar};
+use crate::widgets::block::Block;
+use crate::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget};
+
+/// Tree nodes, expansion and navigation.
+mod state;
+
+#[cfg(test)]
+mod tests;
+
+/// Symbols drawn before a node's label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TreeSymbols<'a> {
+	/// Marker of an expanded node.
+	pub expanded: &'a str,
+	/// Marker of a collapsed node.
+	pub collapsed: &'a str,
+	/// Marker of a node without children.
+	pub leaf: &'a str,
+}
+
+/// Triangle markers.
+pub const TRIANGLES: TreeSymbols<'static> = TreeSymbols {
+	expanded: "▾",
+	collapsed: "▸",
+	leaf: " ",
+};
+
+/// ASCII markers, for terminals without the triangle glyphs.
+pub const ASCII: TreeSymbols<'static> = TreeSymbols {
+	expanded: "-",
+	collapsed: "+",
+	leaf: " ",
+};
+
+/// A scrollable tree of nodes with indentation guides.
+pub struct TreeView<'a, Id> {
+	/// Block drawn around the tree.
+	block: Option<Block<'a>>,
+	/// Base style of the tree.
+	style: Style,
+	/// Style patched over the selected row.
+	highlight_style: Style,
+	/// Style patched over the indentation guides.
+	guide_style: Style,
+	/// Style patched over the scrollbar thumb.
+	scrollbar_style: Style,
+	/// Expand markers.
+	symbols: TreeSymbols<'a>,
+	/// The node id type.
+	_marker: PhantomData<fn(&Id)>,
+}
+
+impl<Id> Default for TreeView<'_, Id> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'a, Id> TreeView<'a, Id> {
+	/// Creates a tree with [`TRIANGLES`] markers and no styling.
+	pub const fn new() -> Self {
+		Self {
+			block: None,
+			style: Style::new(),
+			highlight_style: Style::new(),
+			guide_style: Style::new(),
+			scrollbar_style: Style::new(),
+			symbols: TRIANGLES,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Wraps the tree in a block.
+	#[must_use]
+	pub fn block(mut self, block: Block<'a>) -> Self {
+		self.block = Some(block);
+		self
+	}
+
+	/// Sets the base style of the tree.
+	#[must_use]
+	pub const fn style(mut self, style: Style) -> Self {
+		self.style = style;
+		self
+	}
+
+	/// Sets the style patched over the selected row.
+	#[must_use]
+	pub const fn highlight_style(mut self, style: Style) -> Self {
+		self.highlight_style = style;
+		self
+	}
+
+	/// Sets the style patched over the indentation guides.
+	#[must_use]
+	pub const fn guide_style(mut self, style: Style) -> Self {
+		self.guide_style = style;
+		self
+	}
+
+	/// Sets the style patched over the scrollbar thumb.
+	#[must_use]
+	pub const fn scrollbar_style(mut self, style: Style) -> Self {
+		self.scrollbar_style = style;
+		self
+	}
+
+	/// Sets the expand markers.
+	#[must_use]
+	pub const fn symbols(mut self, symbols: TreeSymbols<'a>) -> Self {
+		self.symbols = symbols;
+		self
+	}
+}
+
+impl<Id: Clone + Eq + Hash> StatefulWidget for TreeView<'_, Id> {
+	type State = TreeViewState<Id>;
+
+	fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		let area = area.intersection(buf.area);
+		buf.set_style(area, self.style);
+		let area = match self.block.take() {
+			Some(block) => {
+				let inner = block.inner(area);
+				block.render(area, buf);
+				inner
+			}
+			None => area,
+		};
+		if area.is_empty() {
+			return;
+		}
+
+		let rows = usize::from(area.height);
+		let (area, bar) = if state.rows.len() > rows {
+			area.split_right_column()
+		} else {
+			(area, Rect::ZERO)
+		};
+		state.scroll_into_view(rows);
+
+		let visible = state
+			.visible_rows()
+			.zip(&state.rows)
+			.skip(state.offset)
+			.take(rows);
+		for (i, (row, flat)) in visible.enumerate() {
+			let y = area.y + i as u16;
+			let base = if row.selected {
+				self.style.patch(self.highlight_style)
+			} else {
+				self.style
+			};
+			buf.set_style(Rect::new(area.x, y, area.width, 1), base);
+
+			let guide_style = base.patch(self.guide_style);
+			let mut x = area.x;
+			for (depth, &last) in flat.last.iter().enumerate() {
+				let guide = match (depth + 1 == flat.last.len(), last) {
+					(false, false) => line::VERTICAL,
+					(false, true) => " ",
+					(true, false) => line::VERTICAL_RIGHT,
+					(true, true) => line::BOTTOM_LEFT,
+				};
+				let fill = if depth + 1 == flat.last.len() {
+					line::HORIZONTAL
+				} else {
+					" "
+				};
+				for symbol in [guide, fill] {
+					if x < area.right() {
+						buf[(x, y)].set_symbol(symbol).set_style(guide_style);
+						x += 1;
+					}
+				}
+			}
+
+			let marker = match (row.has_children, row.expanded) {
+				(false, _) => self.symbols.leaf,
+				(true, true) => self.symbols.expanded,
+				(true, false) => self.symbols.collapsed,
+			};
+			let remaining = usize::from(area.right().saturating_sub(x));
+			(x, _) = buf.set_stringn(x, y, marker, remaining, base);
+			let x = (x + 1).min(area.right());
+			let remaining = usize::from(area.right() - x);
+			buf.set_stringn(x, y, row.label, remaining, base);
+		}
+
+		let mut bar_state = ScrollbarState::for_viewport(state.rows.len(), rows, state.offset);
+		Scrollbar::new(ScrollbarOrientation::VerticalRight)
+			.symbols(scrollbar::VERTICAL)
+			.begin_symbol(None)
+			.end_symbol(None)
+			.style(self.style)
+			.thumb_style(self.style.patch(self.scrollbar_style))
+			.render(bar, buf, &mut bar_state);
+	}
+}
@@ -0,0 +1,464 @@
+//! Tree nodes, expansion and the flattened visible rows.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use hashbrown::HashSet;
+
+/// A key the tree view knows how to handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TreeKey {
+	/// Select the previous row.
+	Up,
+	/// Select the next row.
+	Down,
+	/// Select the row one page up.
+	PageUp,
+	/// Select the row one page down.
+	PageDown,
+	/// Select the first row.
+	Home,
+	/// Select the last row.
+	End,
+	/// Collapse the selected node, or select its parent if it is collapsed.
+	Left,
+	/// Expand the selected node, or select its first child if it is expanded.
+	Right,
+	/// Expand or collapse the selected node.
+	Toggle,
+}
+
+/// A node as supplied by the host: its id, label and whether it may have
+/// children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TreeItem<Id> {
+	/// Identifies the node across reloads; expansion is tracked by id.
+	pub id: Id,
+	/// Text shown for the node.
+	pub label: String,
+	/// Whether the node can be expanded.
+	pub has_children: bool,
+}
+
+impl<Id> TreeItem<Id> {
+	/// Creates a node that cannot be expanded.
+	pub fn leaf(id: Id, label: impl Into<String>) -> Self {
+		Self {
+			id,
+			label: label.into(),
+			has_children: false,
+		}
+	}
+
+	/// Creates a node whose children are loaded when it is first expanded.
+	pub fn branch(id: Id, label: impl Into<String>) -> Self {
+		Self {
+			id,
+			label: label.into(),
+			has_children: true,
+		}
+	}
+}
+
+/// Loads the children of a node the first time it is expanded.
+pub type ChildLoader<Id> = Box<dyn FnMut(&Id) -> Vec<TreeItem<Id>>>;
+
+/// A node stored in the state's arena.
+struct Node<Id> {
+	/// The host's item.
+	item: TreeItem<Id>,
+	/// Arena index of the parent, `None` for roots.
+	parent: Option<usize>,
+	/// Arena indices of the children, `None` until loaded.
+	children: Option<Vec<usize>>,
+}
+
+/// A row of the flattened tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FlatRow {
+	/// Arena index of the node.
+	pub(crate) node: usize,
+	/// For each ancestor below the roots, then the node itself, whether it
+	/// is the last of its siblings. Empty for roots.
+	pub(crate) last: Vec<bool>,
+}
+
+/// A visible row of the tree, as yielded by [`TreeViewState::visible_rows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VisibleRow<'a, Id> {
+	/// The node's id.
+	pub id: &'a Id,
+	/// Text shown for the node.
+	pub label: &'a str,
+	/// Nesting depth, 0 for roots.
+	pub depth: usize,
+	/// Whether the node can be expanded.
+	pub has_children: bool,
+	/// Whether the node is expanded.
+	pub expanded: bool,
+	/// Whether the row is selected.
+	pub selected: bool,
+}
+
+/// State of a [`TreeView`](super::TreeView): its nodes, which of them are
+/// expanded, and the selection.
+///
+/// Children are loaded lazily by the [`ChildLoader`] the first time a node
+/// is expanded, or supplied up front with [`Self::set_children`].
+/// Expansion is remembered by id, so reloading a subtree keeps the nodes
+/// that were open. The visible rows are kept flattened so rendering and
+/// hit-testing are a lookup.
+pub struct TreeViewState<Id> {
+	/// All nodes loaded so far.
+	nodes: Vec<Node<Id>>,
+	/// Arena indices of the top-level nodes.
+	roots: Vec<usize>,
+	/// Ids of expanded nodes.
+	expanded: HashSet<Id>,
+	/// Loads children on first expansion.
+	loader: Option<ChildLoader<Id>>,
+	/// Visible rows, depth first.
+	pub(crate) rows: Vec<FlatRow>,
+	/// Index into `rows` of the selected row.
+	pub(crate) selected: usize,
+	/// Index into `rows` of the first visible row.
+	pub(crate) offset: usize,
+	/// Number of rows visible at once, as of the last render.
+	pub(crate) page: usize,
+}
+
+impl<Id: Clone + Eq + Hash> Default for TreeViewState<Id> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Id: Clone + Eq + Hash> TreeViewState<Id> {
+	/// Creates an empty tree without a child loader.
+	pub fn new() -> Self {
+		Self {
+			nodes: Vec::new(),
+			roots: Vec::new(),
+			expanded: HashSet::new(),
+			loader: None,
+			rows: Vec::new(),
+			selected: 0,
+			offset: 0,
+			page: 1,
+		}
+	}
+
+	/// Sets the callback loading a node's children on first expansion.
+	#[must_use]
+	pub fn with_loader(mut self, loader: impl FnMut(&Id) -> Vec<TreeItem<Id>> + 'static) -> Self {
+		self.loader = Some(Box::new(loader));
+		self
+	}
+
+	/// Replaces the top-level nodes, dropping everything loaded below them.
+	///
+	/// Expansion is kept by id, so nodes that were open load again as they
+	/// become visible.
+	pub fn set_roots(&mut self, roots: impl IntoIterator<Item = TreeItem<Id>>) {
+		let selected = self.selected_id().cloned();
+		self.nodes.clear();
+		self.roots = roots
+			.into_iter()
+			.map(|item| self.insert(item, None))
+			.collect();
+		self.load_expanded(self.roots.clone());
+		self.reflow(None);
+		if let Some(id) = selected {
+			self.select_id(&id);
+		}
+	}
+
+	/// Replaces the children of the node `id`, dropping their subtrees.
+	///
+	/// Returns false if no node with that id is loaded.
+	pub fn set_children(
+		&mut self,
+		id: &Id,
+		children: impl IntoIterator<Item = TreeItem<Id>>,
+	) -> bool {
+		let Some(node) = self.find(id) else {
+			return false;
+		};
+		let selected = self.selected_node();
+		let children = children
+			.into_iter()
+			.map(|item| self.insert(item, Some(node)))
+			.collect::<Vec<_>>();
+		self.nodes[node].item.has_children = !children.is_empty();
+		self.nodes[node].children = Some(children.clone());
+		self.load_expanded(children);
+		self.reflow(selected);
+		true
+	}
+
+	/// Replaces the label of the loaded node `id`, leaving the rows as they
+	/// are. Returns false if no node with that id is loaded.
+	pub fn set_label(&mut self, id: &Id, label: impl Into<String>) -> bool {
+		let Some(node) = self.find(id) else {
+			return false;
+		};
+		self.nodes[node].item.label = label.into();
+		true
+	}
+
+	/// Returns the number of visible rows.
+	pub fn row_count(&self) -> usize {
+		self.rows.len()
+	}
+
+	/// Returns the index of the first row shown, as of the last render.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	/// Returns the index of the selected row, or `None` if the tree is empty.
+	pub fn selected_row(&self) -> Option<usize> {
+		(!self.rows.is_empty()).then_some(self.selected)
+	}
+
+	/// Returns the id of the selected node.
+	pub fn selected_id(&self) -> Option<&Id> {
+		self.selected_node().map(|node| &self.nodes[node].item.id)
+	}
+
+	/// Selects row `row`, clamped to the last row.
+	pub fn select_row(&mut self, row: usize) {
+		self.selected = row.min(self.rows.len().saturating_sub(1));
+	}
+
+	/// Selects the node `id` if it is visible. Returns whether it was.
+	pub fn select_id(&mut self, id: &Id) -> bool {
+		match self
+			.rows
+			.iter()
+			.position(|row| self.nodes[row.node].item.id == *id)
+		{
+			Some(row) => {
+				self.selected = row;
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Returns whether the node `id` is expanded.
+	pub fn is_expanded(&self, id: &Id) -> bool {
+		self.expanded.contains(id)
+	}
+
+	/// Expands the node `id`, loading its children if needed.
+	///
+	/// Returns false if the node is not loaded or cannot be expanded.
+	pub fn expand(&mut self, id: &Id) -> bool {
+		let Some(node) = self.find(id) else {
+			return false;
+		};
+		if !self.nodes[node].item.has_children {
+			return false;
+		}
+		self.expanded.insert(id.clone());
+		let selected = self.selected_node();
+		self.load_expanded(alloc::vec![node]);
+		self.reflow(selected);
+		true
+	}
+
+	/// Collapses the node `id`.
+	///
+	/// A selection inside the collapsed subtree moves to the node itself.
+	/// Returns false if the node was not expanded.
+	pub fn collapse(&mut self, id: &Id) -> bool {
+		if !self.expanded.remove(id) {
+			return false;
+		}
+		let selected = self.selected_node();
+		self.reflow(selected);
+		true
+	}
+
+	/// Expands the node `id` if it is collapsed, and collapses it otherwise.
+	pub fn toggle(&mut self, id: &Id) -> bool {
+		if self.is_expanded(id) {
+			self.collapse(id)
+		} else {
+			self.expand(id)
+		}
+	}
+
+	/// Returns the row drawn `y` rows below the top of the tree, as of the
+	/// last render, for hit-testing.
+	pub fn row_at(&self, y: u16) -> Option<usize> {
+		let row = self.offset + usize::from(y);
+		(row < self.rows.len()).then_some(row)
+	}
+
+	/// Iterates over the visible rows, depth first.
+	pub fn visible_rows(&self) -> impl Iterator<Item = VisibleRow<'_, Id>> {
+		self.rows.iter().enumerate().map(|(index, row)| {
+			let item = &self.nodes[row.node].item;
+			VisibleRow {
+				id: &item.id,
+				label: &item.label,
+				depth: row.last.len(),
+				has_children: item.has_children,
+				expanded: self.expanded.contains(&item.id),
+				selected: index == self.selected,
+			}
+		})
+	}
+
+	/// Handles a key. Returns true if it was used.
+	pub fn handle_key(&mut self, key: TreeKey) -> bool {
+		let last = self.rows.len().saturating_sub(1);
+		match key {
+			TreeKey::Up => self.select_row(self.selected.saturating_sub(1)),
+			TreeKey::Down => self.select_row((self.selected + 1).min(last)),
+			TreeKey::PageUp => self.select_row(self.selected.saturating_sub(self.page)),
+			TreeKey::PageDown => self.select_row((self.selected + self.page).min(last)),
+			TreeKey::Home => self.select_row(0),
+			TreeKey::End => self.select_row(last),
+			TreeKey::Left => {
+				let Some(node) = self.selected_node() else {
+					return false;
+				};
+				let id = self.nodes[node].item.id.clone();
+				if !self.collapse(&id) {
+					let Some(parent) = self.nodes[node].parent else {
+						return false;
+					};
+					self.select_node(parent);
+				}
+			}
+			TreeKey::Right => {
+				let Some(id) = self.selected_id().cloned() else {
+					return false;
+				};
+				if self.is_expanded(&id) {
+					if self
+						.rows
+						.get(self.selected + 1)
+						.is_none_or(|row| self.nodes[row.node].parent != self.selected_node())
+					{
+						return false;
+					}
+					self.selected += 1;
+				} else if !self.expand(&id) {
+					return false;
+				}
+			}
+			TreeKey::Toggle => {
+				let Some(id) = self.selected_id().cloned() else {
+					return false;
+				};
+				return self.toggle(&id);
+			}
+		}
+		true
+	}
+
+	/// Adds `item` to the arena under `parent`, returning its index.
+	fn insert(&mut self, item: TreeItem<Id>, parent: Option<usize>) -> usize {
+		self.nodes.push(Node {
+			item,
+			parent,
+			children: None,
+		});
+		self.nodes.len() - 1
+	}
+
+	/// Returns the arena index of the loaded node `id`.
+	///
+	/// Children replaced by [`Self::set_children`] stay in the arena until
+	/// the next [`Self::set_roots`], so the newest node with the id wins.
+	fn find(&self, id: &Id) -> Option<usize> {
+		self.nodes.iter().rposition(|node| node.item.id == *id)
+	}
+
+	/// Returns the arena index of the selected node.
+	fn selected_node(&self) -> Option<usize> {
+		self.rows.get(self.selected).map(|row| row.node)
+	}
+
+	/// Selects the row showing arena node `node`, if it is visible.
+	fn select_node(&mut self, node: usize) {
+		if let Some(row) = self.rows.iter().position(|row| row.node == node) {
+			self.selected = row;
+		}
+	}
+
+	/// Loads the children of every expanded node in the given subtrees.
+	fn load_expanded(&mut self, mut pending: Vec<usize>) {
+		while let Some(node) = pending.pop() {
+			if !self.nodes[node].item.has_children
+				|| !self.expanded.contains(&self.nodes[node].item.id)
+			{
+				continue;
+			}
+			if self.nodes[node].children.is_none() {
+				let items = match &mut self.loader {
+					Some(loader) => loader(&self.nodes[node].item.id),
+					None => Vec::new(),
+				};
+				let children = items
+					.into_iter()
+					.map(|item| self.insert(item, Some(node)))
+					.collect();
+				self.nodes[node].children = Some(children);
+			}
+			pending.extend(self.nodes[node].children.iter().flatten().copied());
+		}
+	}
+
+	/// Rebuilds the visible rows, keeping arena node `selected` selected, or
+	/// its nearest visible ancestor if it was hidden.
+	fn reflow(&mut self, selected: Option<usize>) {
+		self.rows.clear();
+		let mut stack: Vec<(usize, Vec<bool>)> = self
+			.roots
+			.iter()
+			.rev()
+			.map(|&root| (root, Vec::new()))
+			.collect();
+		while let Some((node, last)) = stack.pop() {
+			if self.expanded.contains(&self.nodes[node].item.id)
+				&& let Some(children) = &self.nodes[node].children
+			{
+				for (i, &child) in children.iter().enumerate().rev() {
+					let mut child_last = last.clone();
+					child_last.push(i + 1 == children.len());
+					stack.push((child, child_last));
+				}
+			}
+			self.rows.push(FlatRow { node, last });
+		}
+
+		let mut target = selected;
+		while let Some(node) = target {
+			if self.rows.iter().any(|row| row.node == node) {
+				self.select_node(node);
+				return;
+			}
+			target = self.nodes.get(node).and_then(|node| node.parent);
+		}
+		self.select_row(self.selected);
+	}
+
+	/// Scrolls so that the selection is among the `height` visible rows.
+	pub(crate) fn scroll_into_view(&mut self, height: usize) {
+		self.page = height.max(1);
+		self.select_row(self.selected);
+		if self.selected < self.offset {
+			self.offset = self.selected;
+		} else if self.selected >= self.offset + self.page {
+			self.offset = self.selected + 1 - self.page;
+		}
+		self.offset = self.offset.min(self.rows.len().saturating_sub(self.page));
+	}
+}
@@ -0,0 +1,166 @@
+use alloc::format;
+use alloc::vec::Vec;
+
+use super::*;
+use crate::Terminal;
+use crate::backend::TestBackend;
+
+/// A tree of paths where `a`, `a/b`, ... are directories and every
+/// directory also holds a file `f`.
+fn paths() -> TreeViewState<&'static str> {
+	let mut state = TreeViewState::new().with_loader(|id: &&'static str| match *id {
+		"a" => Vec::from([TreeItem::branch("a/b", "b"), TreeItem::leaf("a/f", "f")]),
+		"a/b" => Vec::from([TreeItem::branch("a/b/c", "c"), TreeItem::leaf("a/b/f", "f")]),
+		"a/b/c" => Vec::from([TreeItem::leaf("a/b/c/f", "f")]),
+		_ => Vec::new(),
+	});
+	state.set_roots([TreeItem::branch("a", "a"), TreeItem::leaf("z", "z")]);
+	state
+}
+
+/// Renders `tree` into a `width` by `height` terminal.
+fn draw(
+	tree: TreeView<'_, &'static str>,
+	state: &mut TreeViewState<&'static str>,
+	width: u16,
+	height: u16,
+) -> Terminal<TestBackend> {
+	let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+	terminal
+		.draw(|frame| frame.render_stateful_widget(tree, frame.area(), state))
+		.unwrap();
+	terminal
+}
+
+#[test]
+fn renders_deep_nesting_with_guides() {
+	let mut state = paths();
+	for id in ["a", "a/b", "a/b/c"] {
+		assert!(state.expand(&id));
+	}
+	draw(TreeView::new(), &mut state, 12, 7)
+		.backend()
+		.assert_buffer_lines([
+			"▾ a         ",
+			"├─▾ b       ",
+			"│ ├─▾ c     ",
+			"│ │ └─  f   ",
+			"│ └─  f     ",
+			"└─  f       ",
+			"  z         ",
+		]);
+}
+
+#[test]
+fn set_label_keeps_rows_and_selection() {
+	let mut state = paths();
+	assert!(state.expand(&"a"));
+	state.select_row(2);
+	assert!(state.set_label(&"a/f", "g"));
+	assert!(!state.set_label(&"a/b/f", "g"));
+	let labels: Vec<&str> = state.visible_rows().map(|row| row.label).collect();
+	assert_eq!(labels, ["a", "b", "g", "z"]);
+	assert_eq!(state.selected_id(), Some(&"a/f"));
+}
+
+#[test]
+fn keys_expand_collapse_and_walk_the_tree() {
+	let mut state = paths();
+	assert_eq!(state.selected_id(), Some(&"a"));
+
+	assert!(state.handle_key(TreeKey::Right));
+	assert!(state.is_expanded(&"a"));
+	assert_eq!(state.row_count(), 4);
+	assert!(state.handle_key(TreeKey::Right));
+	assert_eq!(state.selected_id(), Some(&"a/b"));
+	state.handle_key(TreeKey::Down);
+	assert_eq!(state.selected_id(), Some(&"a/f"));
+	assert!(!state.handle_key(TreeKey::Right));
+
+	assert!(state.handle_key(TreeKey::Left));
+	assert_eq!(state.selected_id(), Some(&"a"));
+	assert!(state.handle_key(TreeKey::Left));
+	assert!(!state.is_expanded(&"a"));
+	assert!(!state.handle_key(TreeKey::Left));
+
+	state.handle_key(TreeKey::End);
+	assert_eq!(state.selected_id(), Some(&"z"));
+	assert!(!state.handle_key(TreeKey::Toggle));
+	state.handle_key(TreeKey::Home);
+	assert!(state.handle_key(TreeKey::Toggle));
+	assert_eq!(state.row_count(), 4);
+}
+
+#[test]
+fn collapsing_ancestor_moves_selection_to_it() {
+	let mut state = paths();
+	for id in ["a", "a/b", "a/b/c"] {
+		state.expand(&id);
+	}
+	assert!(state.select_id(&"a/b/c/f"));
+
+	assert!(state.collapse(&"a/b"));
+	assert_eq!(state.selected_id(), Some(&"a/b"));
+	assert_eq!(state.row_count(), 4);
+
+	// Expansion below the collapsed node is remembered.
+	state.expand(&"a/b");
+	assert_eq!(state.row_count(), 7);
+	assert_eq!(state.selected_id(), Some(&"a/b"));
+}
+
+#[test]
+fn empty_tree_renders_nothing_and_ignores_keys() {
+	let mut state = TreeViewState::<&'static str>::new();
+	for key in [
+		TreeKey::Down,
+		TreeKey::Left,
+		TreeKey::Right,
+		TreeKey::Toggle,
+	] {
+		state.handle_key(key);
+	}
+	assert_eq!(state.selected_row(), None);
+	assert_eq!(state.selected_id(), None);
+	assert_eq!(state.visible_rows().count(), 0);
+	draw(TreeView::new(), &mut state, 4, 2)
+		.backend()
+		.assert_buffer_lines(["    ", "    "]);
+}
+
+#[test]
+fn scrolls_and_hit_tests_rows() {
+	let mut state = TreeViewState::new();
+	state.set_roots((0..10).map(|i| TreeItem::leaf(i, format!("n{i}"))));
+	state.handle_key(TreeKey::End);
+	let mut terminal = Terminal::new(TestBackend::new(6, 3)).unwrap();
+	terminal
+		.draw(|frame| frame.render_stateful_widget(TreeView::new(), frame.area(), &mut state))
+		.unwrap();
+	terminal
+		.backend()
+		.assert_buffer_lines(["  n7 │", "  n8 │", "  n9 █"]);
+
+	assert_eq!(state.offset(), 7);
+	assert_eq!(state.row_at(1), Some(8));
+	assert_eq!(state.row_at(3), None);
+	state.select_row(8);
+	assert_eq!(state.selected_id(), Some(&8));
+}
+
+#[test]
+fn set_children_replaces_a_subtree() {
+	let mut state = TreeViewState::new();
+	state.set_roots([TreeItem::branch(0, "root")]);
+	state.expand(&0);
+	assert_eq!(state.row_count(), 1);
+
+	assert!(state.set_children(&0, [TreeItem::leaf(1, "one"), TreeItem::leaf(2, "two")]));
+	assert_eq!(state.row_count(), 3);
+	let labels: Vec<_> = state
+		.visible_rows()
+		.map(|row| (row.label, row.depth))
+		.collect();
+	assert_eq!(labels, [("root", 0), ("one", 1), ("two", 1)]);
+	assert!(!state.set_children(&9, []));
+}